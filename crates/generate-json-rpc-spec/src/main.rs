@@ -28,8 +28,11 @@ use sui_gateway::bcs_api::BcsApiImpl;
 use sui_gateway::json_rpc::sui_rpc_doc;
 use sui_gateway::read_api::{FullNodeApi, ReadApi};
 use sui_gateway::rpc_gateway::{GatewayReadApiImpl, RpcGatewayImpl, TransactionBuilderImpl};
+use sui_gateway::transaction_execution_api::FullNodeTransactionExecutionApi;
 use sui_json::SuiJsonValue;
 use sui_types::base_types::{ObjectID, SuiAddress};
+use sui_types::crypto::Signature;
+use sui_types::intent::{Intent, IntentScope};
 use sui_types::sui_serde::{Base64, Encoding};
 use sui_types::SUI_FRAMEWORK_ADDRESS;
 use test_utils::network::{start_rpc_test_network, TestNetwork};
@@ -82,6 +85,7 @@ async fn main() {
     open_rpc.add_module(ReadApi::rpc_doc_module());
     open_rpc.add_module(FullNodeApi::rpc_doc_module());
     open_rpc.add_module(BcsApiImpl::rpc_doc_module());
+    open_rpc.add_module(FullNodeTransactionExecutionApi::rpc_doc_module());
 
     match options.action {
         Action::Print => {
@@ -155,7 +159,7 @@ async fn create_response_sample() -> Result<
         network.http_client.sync_account_state(account).await?;
         let objects: Vec<SuiObjectInfo> = network
             .http_client
-            .get_objects_owned_by_address(account)
+            .get_objects_owned_by_address(account, None)
             .await?;
         owned_objects.insert(account, objects);
     }
@@ -185,6 +189,9 @@ async fn create_package_object_response(
         path: "sui_programmability/examples/move_tutorial".to_string(),
         gas: None,
         gas_budget: 10000,
+        dry_run: false,
+        with_unpublished_dependencies: false,
+        skip_dependency_verification: false,
     }
     .execute(context)
     .await?;
@@ -207,7 +214,7 @@ async fn create_transfer_response(
     coins: &[SuiObjectInfo],
 ) -> Result<TransactionResponse, anyhow::Error> {
     let response = WalletCommands::Transfer {
-        to: address,
+        to: address.into(),
         coin_object_id: coins.first().unwrap().object_id,
         gas: None,
         gas_budget: 1000,
@@ -217,8 +224,11 @@ async fn create_transfer_response(
     if let WalletCommandResult::Transfer(_, certificate, effects) = response {
         Ok(TransactionResponse::EffectResponse(
             TransactionEffectsResponse {
-                certificate,
-                effects,
+                certificate: Some(certificate),
+                effects: Some(effects),
+                balance_changes: vec![],
+                object_changes: vec![],
+                raw_transaction: None,
             },
         ))
     } else {
@@ -235,6 +245,9 @@ async fn create_hero_response(
         path: "sui_programmability/examples/games".to_string(),
         gas: None,
         gas_budget: 10000,
+        dry_run: false,
+        with_unpublished_dependencies: false,
+        skip_dependency_verification: false,
     }
     .execute(context)
     .await?;
@@ -256,6 +269,7 @@ async fn create_hero_response(
             args: vec![game_info, coin],
             gas: None,
             gas_budget: 10000,
+            dry_run: false,
         }
         .execute(context)
         .await?;
@@ -296,9 +310,13 @@ async fn create_error_response(
         )
         .await?;
 
-    let signature = context
+    let signature: Signature = context
         .keystore
-        .sign(&address, &response.tx_bytes.to_vec()?)?;
+        .sign(
+            &address,
+            &Intent::new(IntentScope::TransactionData).message_bytes(&response.tx_bytes.to_vec()?),
+        )?
+        .try_into()?;
     let signature_byte = Base64::encode(signature.signature_bytes());
     let pub_key = Base64::encode(signature.public_key_bytes());
     let tx_data = response.tx_bytes.encoded();
@@ -363,6 +381,7 @@ async fn get_nft_response(
         args,
         gas: None,
         gas_budget: 10000,
+        dry_run: false,
     }
     .execute(context)
     .await?;
@@ -373,8 +392,11 @@ async fn get_nft_response(
             .get_object(effects.created.first().unwrap().reference.object_id)
             .await?;
         let tx = TransactionResponse::EffectResponse(TransactionEffectsResponse {
-            certificate,
-            effects,
+            certificate: Some(certificate),
+            effects: Some(effects),
+            balance_changes: vec![],
+            object_changes: vec![],
+            raw_transaction: None,
         });
         Ok((tx, object))
     } else {
@@ -0,0 +1,57 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+// tests that the module of a shared object can restrict which of its children are used through it
+
+//# init --addresses test=0x0
+
+//# publish
+
+module test::M {
+    use Std::Vector;
+    use Sui::ID::{Self, ID, VersionedID};
+    use Sui::Transfer::{Self, ChildRef};
+    use Sui::TxContext::{Self, TxContext};
+
+    struct Child has key, store {
+        id: VersionedID,
+        value: u64,
+    }
+
+    struct Parent has key {
+        id: VersionedID,
+        // The only child which can be used through the parent.
+        allowed: ID,
+        children: vector<ChildRef<Child>>,
+    }
+
+    public(script) fun create_shared(ctx: &mut TxContext) {
+        let child = Child { id: TxContext::new_id(ctx), value: 0 };
+        let allowed = *ID::id(&child);
+        let (id, child) = Transfer::transfer_to_object_id(child, TxContext::new_id(ctx));
+        Transfer::share_object(Parent { id, allowed, children: Vector::singleton(child) })
+    }
+
+    public(script) fun add_child(parent: &mut Parent, ctx: &mut TxContext) {
+        let child = Child { id: TxContext::new_id(ctx), value: 0 };
+        let child = Transfer::transfer_to_object(child, parent);
+        Vector::push_back(&mut parent.children, child)
+    }
+
+    public(script) fun set_value(_parent: &mut Parent, child: &mut Child, value: u64) {
+        child.value = value
+    }
+
+    fun authorize_child(parent: &Parent, child: ID, _sender: address): bool {
+        parent.allowed == child
+    }
+}
+
+//# run test::M::create_shared
+
+//# run test::M::add_child --args object(106)
+
+//# run test::M::set_value --args object(106) object(105) 42
+
+// This run should error as the parent does not authorize the use of its second child
+//# run test::M::set_value --args object(106) object(108) 42
@@ -0,0 +1,20 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+// test that the calls of a batch transaction fail or succeed together
+
+//# init --accounts A
+
+//# run-batch --sender A
+Sui::ObjectBasics::create --args 10 @A
+Sui::ObjectBasics::create --args 20 @A
+
+//# run-batch --sender A
+Sui::ObjectBasics::set_value --args object(104) 1
+Sui::ObjectBasics::freeze_object --args object(105)
+
+//# run-batch --sender A
+Sui::ObjectBasics::set_value --args object(104) 2
+Sui::ObjectBasics::transfer --args object(105) @A
+
+//# view-object 104
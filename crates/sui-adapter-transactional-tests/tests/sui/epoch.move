@@ -0,0 +1,35 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+// test that transactions execute in the epoch advanced to
+
+//# init --addresses Test=0x0
+
+//# publish
+module Test::M {
+    use Sui::ID::VersionedID;
+    use Sui::TxContext::{Self, TxContext};
+    use Sui::Transfer;
+
+    struct Epoch has key {
+        id: VersionedID,
+        epoch: u64,
+    }
+
+    public(script) fun record(ctx: &mut TxContext) {
+        Transfer::transfer(
+            Epoch { id: TxContext::new_id(ctx), epoch: TxContext::epoch(ctx) },
+            TxContext::sender(ctx)
+        )
+    }
+}
+
+//# run Test::M::record
+
+//# view-object 105
+
+//# advance-epoch --count 2
+
+//# run Test::M::record
+
+//# view-object 107
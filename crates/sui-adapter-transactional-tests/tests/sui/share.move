@@ -0,0 +1,62 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+// test that objects can only be shared when they are created, and that objects
+// owned by other objects cannot be frozen or shared
+
+//# init --addresses test=0x0
+
+//# publish
+
+module test::M {
+    use Sui::ID::VersionedID;
+    use Sui::Transfer::{Self, ChildRef};
+    use Sui::TxContext::{Self, TxContext};
+
+    struct S has key {
+        id: VersionedID,
+    }
+
+    struct Parent has key {
+        id: VersionedID,
+        child: ChildRef<S>,
+    }
+
+    public(script) fun create(ctx: &mut TxContext) {
+        Transfer::transfer(S { id: TxContext::new_id(ctx) }, TxContext::sender(ctx))
+    }
+
+    public(script) fun create_shared(ctx: &mut TxContext) {
+        Transfer::share_object(S { id: TxContext::new_id(ctx) })
+    }
+
+    public(script) fun share(s: S, _ctx: &mut TxContext) {
+        Transfer::share_object(s)
+    }
+
+    public(script) fun create_parent(ctx: &mut TxContext) {
+        let child = S { id: TxContext::new_id(ctx) };
+        let (id, child) = Transfer::transfer_to_object_id(child, TxContext::new_id(ctx));
+        Transfer::transfer(Parent { id, child }, TxContext::sender(ctx))
+    }
+
+    public(script) fun freeze_child(_parent: &mut Parent, child: S, _ctx: &mut TxContext) {
+        Transfer::freeze_object(child)
+    }
+
+    public(script) fun share_child(_parent: &mut Parent, child: S, _ctx: &mut TxContext) {
+        Transfer::share_object(child)
+    }
+}
+
+//# run test::M::create
+
+//# run test::M::share --args object(105)
+
+//# run test::M::create_shared
+
+//# run test::M::create_parent
+
+//# run test::M::freeze_child --args object(110) object(111)
+
+//# run test::M::share_child --args object(110) object(111)
@@ -7,23 +7,33 @@ use crate::bytecode_rewriter::ModuleHandleRewriter;
 use move_binary_format::{
     access::ModuleAccess,
     binary_views::BinaryIndexedView,
-    errors::PartialVMResult,
+    compatibility::Compatibility,
+    errors::{Location, PartialVMResult, VMError},
     file_format::{CompiledModule, LocalIndex, SignatureToken, StructHandleIndex, Visibility},
+    normalized,
+};
+use sui_framework::{
+    natives::{costs, object_runtime::ObjectRuntime, profile},
+    EventType,
 };
-use sui_framework::EventType;
 use sui_types::{
     base_types::*,
-    error::{SuiError, SuiResult},
+    error::{MoveLocation, SuiError, SuiResult},
     event::{Event, TransferType},
     fp_ensure,
     gas::SuiGasStatus,
     id::VersionedID,
-    messages::{CallArg, InputObjectKind},
+    messages::{CallArg, InputObjectKind, MoveModuleUpgrade},
+    move_package::MovePackage,
     object::{self, Data, MoveObject, Object, Owner},
-    storage::{DeleteKind, Storage},
+    protocol_config::ProtocolConfig,
+    storage::{ChildObjectResolver, DeleteKind, Storage, WriteTotals},
+    upgrade_cap::UpgradeCap,
 };
 use sui_verifier::{
-    entry_points_verifier::{is_tx_context, INIT_FN_NAME, RESOLVED_STD_OPTION, RESOLVED_SUI_ID},
+    entry_points_verifier::{
+        is_tx_context, AUTHORIZE_CHILD_FN_NAME, INIT_FN_NAME, RESOLVED_STD_OPTION, RESOLVED_SUI_ID,
+    },
     verifier,
 };
 
@@ -31,9 +41,14 @@ use move_core_types::{
     account_address::AccountAddress,
     identifier::Identifier,
     language_storage::{ModuleId, StructTag, TypeTag},
-    resolver::{ModuleResolver, ResourceResolver},
+    resolver::{ModuleResolver, MoveResolver, ResourceResolver},
+    vm_status::StatusCode,
+};
+use move_vm_runtime::{
+    native_extensions::NativeContextExtensions,
+    native_functions::NativeFunctionTable,
+    session::{SerializedReturnValues, Session},
 };
-use move_vm_runtime::{native_functions::NativeFunctionTable, session::SerializedReturnValues};
 use std::{
     borrow::Borrow,
     collections::{BTreeMap, BTreeSet, HashSet},
@@ -44,6 +59,10 @@ use std::{
 use crate::object_root_ancestor_map::ObjectRootAncestorMap;
 pub use move_vm_runtime::move_vm::MoveVM;
 
+#[cfg(test)]
+#[path = "unit_tests/adapter_tests.rs"]
+mod adapter_tests;
+
 pub fn new_move_vm(natives: NativeFunctionTable) -> Result<MoveVM, SuiError> {
     MoveVM::new(natives).map_err(|_| SuiError::ExecutionInvariantViolation)
 }
@@ -58,7 +77,10 @@ pub fn new_move_vm(natives: NativeFunctionTable) -> Result<MoveVM, SuiError> {
 /// otherwise we return Ok(Ok).
 /// TODO: Do we really need the two layers?
 #[allow(clippy::too_many_arguments)]
-pub fn execute<E: Debug, S: ResourceResolver<Error = E> + ModuleResolver<Error = E> + Storage>(
+pub fn execute<
+    E: Debug,
+    S: ResourceResolver<Error = E> + ModuleResolver<Error = E> + Storage + ChildObjectResolver,
+>(
     vm: &MoveVM,
     state_view: &mut S,
     module_id: ModuleId,
@@ -72,9 +94,9 @@ pub fn execute<E: Debug, S: ResourceResolver<Error = E> + ModuleResolver<Error =
         .iter()
         .filter_map(|arg| match arg {
             CallArg::Pure(_) => None,
-            CallArg::ImmOrOwnedObject((id, _, _)) | CallArg::SharedObject(id) => {
-                Some((*id, state_view.read_object(id)?))
-            }
+            CallArg::ImmOrOwnedObject((id, _, _))
+            | CallArg::SharedObject(id)
+            | CallArg::ReadOnlySharedObject(id) => Some((*id, state_view.read_object(id)?)),
         })
         .collect();
     let module = vm.load_module(&module_id, state_view)?;
@@ -87,6 +109,7 @@ pub fn execute<E: Debug, S: ResourceResolver<Error = E> + ModuleResolver<Error =
         mutable_ref_objects,
         has_ctx_arg,
     } = resolve_and_type_check(&objects, &module, function, &type_args, args, is_genesis)?;
+    authorize_children_of_shared_objects(vm, state_view, &objects, ctx.sender(), gas_status)?;
 
     if has_ctx_arg {
         args.push(ctx.to_vec());
@@ -112,7 +135,7 @@ pub fn execute<E: Debug, S: ResourceResolver<Error = E> + ModuleResolver<Error =
 #[allow(clippy::too_many_arguments)]
 fn execute_internal<
     E: Debug,
-    S: ResourceResolver<Error = E> + ModuleResolver<Error = E> + Storage,
+    S: ResourceResolver<Error = E> + ModuleResolver<Error = E> + Storage + ChildObjectResolver,
 >(
     vm: &MoveVM,
     state_view: &mut S,
@@ -139,17 +162,39 @@ fn execute_internal<
         })
         .collect();
 
-    let mut session = vm.new_session(state_view);
+    let profiling = gas_status.is_profiling();
+    let remaining_gas = gas_status.remaining_gas();
+    if profiling {
+        profile::start_profiling();
+    }
+    let mut session = new_session(vm, &*state_view, object_data.keys().copied().collect());
+    // The natives are charged with the costs of the protocol version the transaction runs at.
+    let protocol_config = gas_status.protocol_config();
     // script visibility checked manually for entry points
-    let result = session
-        .execute_function_bypass_visibility(
-            module_id,
-            function,
-            type_args,
-            args,
-            gas_status.get_move_gas_status(),
-        )
-        .and_then(|ret| Ok((ret, session.finish()?)));
+    let result = gas_status
+        .meter_move_call(|move_gas_status| {
+            costs::with_protocol_config(protocol_config, || {
+                session.execute_function_bypass_visibility(
+                    module_id,
+                    function,
+                    type_args,
+                    args,
+                    move_gas_status,
+                )
+            })
+        })
+        .and_then(|ret| {
+            let (change_set, events, mut extensions) = session.finish_with_extensions()?;
+            let taken_children = extensions.remove::<ObjectRuntime>().into_taken();
+            Ok((ret, (change_set, events), taken_children))
+        });
+    if profiling {
+        gas_status.record_call(
+            format!("{}::{}", module_id, function),
+            remaining_gas,
+            profile::finish_profiling(),
+        );
+    }
 
     match result {
         Ok((
@@ -158,6 +203,7 @@ fn execute_internal<
                 return_values,
             },
             (change_set, events),
+            taken_children,
         )) => {
             // Sui Move programs should never touch global state, so ChangeSet should be empty
             debug_assert!(change_set.accounts().is_empty());
@@ -192,10 +238,20 @@ fn execute_internal<
                 .collect();
             // All mutable references should have been marked as updated
             debug_assert!(mutable_ref_objects.is_empty());
-            let by_value_object_map = object_data
+            let mut by_value_object_map: BTreeMap<_, _> = object_data
                 .into_iter()
                 .filter(|(id, _obj)| by_value_objects.contains(id))
                 .collect();
+            // The children taken during the call are handled as if they had been passed by value.
+            let mut object_owner_map = object_owner_map;
+            for (id, child) in taken_children {
+                gas_status.charge_storage_read(child.object_size_for_gas_metering())?;
+                if let Owner::ObjectOwner(parent) = child.owner {
+                    object_owner_map.insert(id.into(), parent);
+                }
+                by_value_object_map.insert(id, (child.owner, child.version()));
+                state_view.record_child_object(child);
+            }
             process_successful_execution(
                 state_view,
                 by_value_object_map,
@@ -203,27 +259,201 @@ fn execute_internal<
                 events,
                 ctx,
                 object_owner_map,
+                gas_status.protocol_config(),
             )?;
+            check_execution_limits(&state_view.write_totals(), gas_status.protocol_config())?;
 
             debug_assert!(return_values.is_empty());
             Ok(())
         }
         // charge for all computations so far
-        Err(error) => Err(SuiError::AbortedExecution {
-            error: error.to_string(),
-        }),
+        Err(error) => Err(convert_execution_error(&*state_view, gas_status, error)),
     }
 }
 
-pub fn publish<E: Debug, S: ResourceResolver<Error = E> + ModuleResolver<Error = E> + Storage>(
+/// Fail the transaction if it wrote more than `config` allows, whatever its gas budget, to bound
+/// the cost of executing and storing a single certificate. The totals are those of the whole
+/// transaction, so the calls of a batch share the limits.
+pub fn check_execution_limits(totals: &WriteTotals, config: &ProtocolConfig) -> SuiResult {
+    for (limit, max, actual) in [
+        (
+            "written objects",
+            config.max_written_objects,
+            totals.objects,
+        ),
+        ("events", config.max_events, totals.events),
+        ("written bytes", config.max_written_bytes, totals.bytes),
+    ] {
+        fp_ensure!(
+            actual <= max,
+            SuiError::ExceededExecutionLimit {
+                limit: limit.to_owned(),
+                max,
+                actual,
+            }
+        );
+    }
+    Ok(())
+}
+
+/// A session of `vm` over `state_view`, where the Move call can take the children of the objects
+/// it is given, other than the objects in `arguments`.
+fn new_session<'r, 'l, S: MoveResolver + ChildObjectResolver>(
+    vm: &'l MoveVM,
+    state_view: &'r S,
+    arguments: BTreeSet<ObjectID>,
+) -> Session<'r, 'l, S> {
+    let mut extensions = NativeContextExtensions::default();
+    extensions.add(ObjectRuntime::new(state_view, arguments));
+    vm.new_session_with_extensions(state_view, extensions)
+}
+
+/// Keep the location and the code of a Move abort, for clients to tell the user which
+/// constant the code is, and tell running out of the budget from hitting the computation cap.
+/// Other errors are only kept as text.
+fn convert_execution_error<E: Debug, S: ModuleResolver<Error = E>>(
+    state_view: &S,
+    gas_status: &SuiGasStatus,
+    error: VMError,
+) -> SuiError {
+    if error.major_status() == StatusCode::OUT_OF_GAS {
+        return gas_status.out_of_gas_error();
+    }
+    if let (StatusCode::ABORTED, Some(code), Location::Module(module), Some((function, offset))) = (
+        error.major_status(),
+        error.sub_status(),
+        error.location(),
+        error.offsets().first(),
+    ) {
+        let function_name = state_view
+            .get_module(module)
+            .ok()
+            .flatten()
+            .and_then(|bytes| CompiledModule::deserialize(&bytes).ok())
+            .and_then(|compiled| {
+                let definition = compiled.function_defs().get(function.0 as usize)?;
+                let handle = compiled.function_handle_at(definition.function);
+                Some(compiled.identifier_at(handle.name).to_string())
+            });
+        return SuiError::MoveAbort {
+            location: MoveLocation {
+                module: module.clone(),
+                function: function.0,
+                function_name,
+                instruction: *offset,
+            },
+            code,
+        };
+    }
+    SuiError::AbortedExecution {
+        error: error.to_string(),
+    }
+}
+
+pub fn publish<
+    E: Debug,
+    S: ResourceResolver<Error = E> + ModuleResolver<Error = E> + Storage + ChildObjectResolver,
+>(
     state_view: &mut S,
     natives: NativeFunctionTable,
     module_bytes: Vec<Vec<u8>>,
     ctx: &mut TxContext,
     gas_status: &mut SuiGasStatus,
 ) -> SuiResult {
-    gas_status.charge_publish_package(module_bytes.iter().map(|v| v.len()).sum())?;
-    let mut modules = module_bytes
+    let mut modules = deserialize_package(&module_bytes, gas_status)?;
+    let package_id = generate_package_id(&mut modules, ctx)?;
+    let vm = verify_and_link(state_view, &modules, package_id, natives, gas_status)?;
+    state_view.log_event(Event::Publish { package_id });
+    store_package_and_init_modules(state_view, &vm, modules, ctx, gas_status)
+}
+
+/// Publish the modules of `upgrade` as a new version of its package, at a new ID, with the
+/// `UpgradeCap` of the package: the sender must own the capability, which must be for the latest
+/// version of the package, and the new version must keep the modules of the version it replaces
+/// compatible. The capability then points at the new version. Unlike publishing, no `init`
+/// function is called, as the package was already initialized.
+pub fn upgrade<
+    E: Debug,
+    S: ResourceResolver<Error = E> + ModuleResolver<Error = E> + Storage + ChildObjectResolver,
+>(
+    state_view: &mut S,
+    natives: NativeFunctionTable,
+    upgrade: MoveModuleUpgrade,
+    ctx: &mut TxContext,
+    gas_status: &mut SuiGasStatus,
+) -> SuiResult {
+    let MoveModuleUpgrade {
+        package: package_id,
+        upgrade_cap,
+        modules: module_bytes,
+    } = upgrade;
+    let failure = |error: String| SuiError::PackageUpgradeFailure { package_id, error };
+
+    let mut cap_object =
+        state_view
+            .read_object(&upgrade_cap.0)
+            .cloned()
+            .ok_or(SuiError::ObjectNotFound {
+                object_id: upgrade_cap.0,
+            })?;
+    fp_ensure!(
+        cap_object.owner == Owner::AddressOwner(ctx.sender()),
+        failure(format!(
+            "the upgrade cap {} is not owned by the sender",
+            upgrade_cap.0
+        ))
+    );
+    let cap = UpgradeCap::try_from(&cap_object)?;
+    fp_ensure!(
+        cap.package == package_id,
+        failure(format!(
+            "the upgrade cap {} is for the package {}",
+            upgrade_cap.0, cap.package
+        ))
+    );
+    let package = state_view
+        .read_object(&package_id)
+        .and_then(|object| object.data.try_as_package())
+        .cloned()
+        .ok_or(SuiError::DependentPackageNotFound { package_id })?;
+
+    let mut modules = deserialize_package(&module_bytes, gas_status)?;
+    let new_package_id = generate_package_id(&mut modules, ctx)?;
+    check_upgrade_compatibility(&package, &modules).map_err(failure)?;
+    verify_and_link(state_view, &modules, new_package_id, natives, gas_status)?;
+    state_view.log_event(Event::Publish {
+        package_id: new_package_id,
+    });
+
+    cap_object
+        .data
+        .try_as_move_mut()
+        .expect("The upgrade cap is a Move object")
+        .update_contents_and_increment_version(cap.upgraded(new_package_id).to_bcs_bytes());
+    let package_object = Object::new_package(modules, ctx.digest());
+    state_view.set_create_object_ids(HashSet::from([new_package_id]));
+    state_view.write_object(package_object);
+    state_view.write_object(cap_object);
+    check_execution_limits(&state_view.write_totals(), gas_status.protocol_config())
+}
+
+/// The modules of a package to publish, deserialized, once the package is charged for its size.
+fn deserialize_package(
+    module_bytes: &[Vec<u8>],
+    gas_status: &mut SuiGasStatus,
+) -> SuiResult<Vec<CompiledModule>> {
+    let package_size: usize = module_bytes.iter().map(|v| v.len()).sum();
+    let max_package_size = gas_status.protocol_config().max_package_size;
+    fp_ensure!(
+        package_size as u64 <= max_package_size,
+        SuiError::ExceededExecutionLimit {
+            limit: "package bytes".to_string(),
+            max: max_package_size,
+            actual: package_size as u64,
+        }
+    );
+    gas_status.charge_publish_package(package_size)?;
+    let modules = module_bytes
         .iter()
         .map(|b| CompiledModule::deserialize(b))
         .collect::<PartialVMResult<Vec<CompiledModule>>>()
@@ -237,17 +467,13 @@ pub fn publish<E: Debug, S: ResourceResolver<Error = E> + ModuleResolver<Error =
             error: "Publishing empty list of modules".to_string(),
         }
     );
-
-    let package_id = generate_package_id(&mut modules, ctx)?;
-    let vm = verify_and_link(state_view, &modules, package_id, natives, gas_status)?;
-    state_view.log_event(Event::Publish { package_id });
-    store_package_and_init_modules(state_view, &vm, modules, ctx, gas_status)
+    Ok(modules)
 }
 
 /// Store package in state_view and call module initializers
 pub fn store_package_and_init_modules<
     E: Debug,
-    S: ResourceResolver<Error = E> + ModuleResolver<Error = E> + Storage,
+    S: ResourceResolver<Error = E> + ModuleResolver<Error = E> + Storage + ChildObjectResolver,
 >(
     state_view: &mut S,
     vm: &MoveVM,
@@ -272,12 +498,16 @@ pub fn store_package_and_init_modules<
     let package_object = Object::new_package(modules, ctx.digest());
     state_view.set_create_object_ids(HashSet::from([package_object.id()]));
     state_view.write_object(package_object);
+    check_execution_limits(&state_view.write_totals(), gas_status.protocol_config())?;
 
     init_modules(state_view, vm, modules_to_init, ctx, gas_status)
 }
 
 /// Modules in module_ids_to_init must have the init method defined
-fn init_modules<E: Debug, S: ResourceResolver<Error = E> + ModuleResolver<Error = E> + Storage>(
+fn init_modules<
+    E: Debug,
+    S: ResourceResolver<Error = E> + ModuleResolver<Error = E> + Storage + ChildObjectResolver,
+>(
     state_view: &mut S,
     vm: &MoveVM,
     module_ids_to_init: Vec<ModuleId>,
@@ -355,6 +585,97 @@ pub fn verify_and_link<
     Ok(vm)
 }
 
+/// Check that the modules `module_bytes` can replace the modules of the framework package
+/// `package` as an epoch change upgrades it: that they pass the Move and the Sui verifiers, have
+/// the address of the package, and keep every module of the package with the same struct
+/// layouts and public functions, for the objects of the framework types and the packages calling
+/// the framework to keep working. Returns the modules, deserialized.
+pub fn verify_framework_upgrade(
+    package: &MovePackage,
+    module_bytes: &[Vec<u8>],
+) -> SuiResult<Vec<CompiledModule>> {
+    let package_id = package.id();
+    let incompatible = |error: String| SuiError::IncompatibleFrameworkUpgrade { package_id, error };
+    let modules = module_bytes
+        .iter()
+        .map(|bytes| CompiledModule::deserialize(bytes))
+        .collect::<PartialVMResult<Vec<CompiledModule>>>()
+        .map_err(|err| incompatible(err.to_string()))?;
+    fp_ensure!(
+        !modules.is_empty(),
+        incompatible("the package has no modules".to_string())
+    );
+
+    for module in &modules {
+        fp_ensure!(
+            ObjectID::from(*module.address()) == package_id,
+            incompatible(format!(
+                "module {} is not at the address of the package",
+                module.self_id()
+            ))
+        );
+        move_bytecode_verifier::verify_module(module)
+            .map_err(|err| incompatible(err.to_string()))?;
+        verifier::verify_module(module)?;
+    }
+    check_upgrade_compatibility(package, &modules).map_err(incompatible)?;
+    Ok(modules)
+}
+
+/// Check that `modules` keep every module of `package` with the same struct layouts and public
+/// function signatures. The modules may be at another address than the package, as a new version
+/// of a user package is, in which case the modules of the package are compared as if they were
+/// at that address.
+pub fn check_upgrade_compatibility(
+    package: &MovePackage,
+    modules: &[CompiledModule],
+) -> Result<(), String> {
+    let address = match modules.first() {
+        Some(module) => *module.address(),
+        None => return Err("the package has no modules".to_string()),
+    };
+    let package_address = AccountAddress::from(package.id());
+    // Moves the modules of the package to the address of `modules`.
+    let rewriter = (address != package_address).then(|| {
+        let sub_map = package
+            .serialized_module_map()
+            .keys()
+            .map(|name| {
+                let name = Identifier::new(name.as_str()).unwrap();
+                (
+                    ModuleId::new(package_address, name.clone()),
+                    ModuleId::new(address, name),
+                )
+            })
+            .collect();
+        ModuleHandleRewriter::new(sub_map).expect("The addresses of the modules differ")
+    });
+
+    let new_modules: BTreeMap<_, _> = modules
+        .iter()
+        .map(|module| (module.self_id().name().to_string(), module))
+        .collect();
+    for (name, bytes) in package.serialized_module_map() {
+        let new_module = new_modules
+            .get(name)
+            .ok_or_else(|| format!("module {name} is removed"))?;
+        let mut old_module = CompiledModule::deserialize(bytes).map_err(|err| err.to_string())?;
+        if let Some(rewriter) = &rewriter {
+            rewriter.sub_module_ids(&mut old_module);
+        }
+        let compatibility = Compatibility::check(
+            &normalized::Module::new(&old_module),
+            &normalized::Module::new(new_module),
+        );
+        if !compatibility.is_fully_compatible() {
+            return Err(format!(
+                "module {name} changes the layout of its structs or its public functions"
+            ));
+        }
+    }
+    Ok(())
+}
+
 /// Given a list of `modules`, use `ctx` to generate a fresh ID for the new packages.
 /// If `is_framework` is true, then the modules can have arbitrary user-defined address,
 /// otherwise their addresses must be 0.
@@ -414,6 +735,7 @@ fn process_successful_execution<
     events: Vec<MoveEvent>,
     ctx: &TxContext,
     mut object_owner_map: BTreeMap<SuiAddress, SuiAddress>,
+    protocol_config: &ProtocolConfig,
 ) -> SuiResult {
     for (obj_id, new_contents) in mutable_refs {
         // update contents and increment sequence number
@@ -440,7 +762,8 @@ fn process_successful_execution<
             EventType::TransferToAddress
             | EventType::FreezeObject
             | EventType::TransferToObject
-            | EventType::ShareObject => {
+            | EventType::ShareObject
+            | EventType::TransferToParty => {
                 let new_owner = match event_type {
                     EventType::TransferToAddress => {
                         Owner::AddressOwner(SuiAddress::try_from(recipient.as_slice()).unwrap())
@@ -450,6 +773,9 @@ fn process_successful_execution<
                         Owner::ObjectOwner(ObjectID::try_from(recipient.borrow()).unwrap().into())
                     }
                     EventType::ShareObject => Owner::Shared,
+                    EventType::TransferToParty => {
+                        Owner::Party(SuiAddress::try_from(recipient.as_slice()).unwrap())
+                    }
                     _ => unreachable!(),
                 };
                 handle_transfer(
@@ -461,6 +787,7 @@ fn process_successful_execution<
                     state_view,
                     &mut object_owner_map,
                     &newly_generated_ids,
+                    protocol_config,
                 )
             }
             EventType::DeleteObjectID => {
@@ -528,7 +855,13 @@ fn process_successful_execution<
     // any object left in `by_value_objects` is an input passed by value that was not transferred or frozen.
     // this means that either the object was (1) deleted from the Sui system altogether, or
     // (2) wrapped inside another object that is in the Sui object pool
-    for (id, (_owner, version)) in by_value_objects {
+    for (id, (owner, version)) in by_value_objects {
+        // A shared or party object can't be wrapped: the transactions sequenced after this one
+        // would expect to find it at the top level.
+        fp_ensure!(
+            !owner.is_shared(),
+            SuiError::SharedObjectNotDeleted { object_id: id }
+        );
         state_view.delete_object(&id, version, DeleteKind::Wrap);
     }
 
@@ -548,6 +881,7 @@ fn handle_transfer<
     state_view: &mut S,
     object_owner_map: &mut BTreeMap<SuiAddress, SuiAddress>,
     newly_generated_ids: &HashSet<ObjectID>,
+    protocol_config: &ProtocolConfig,
 ) -> SuiResult {
     match type_ {
         TypeTag::Struct(s_type) => {
@@ -558,6 +892,13 @@ fn handle_transfer<
             {
                 check_transferred_object_invariants(&move_obj, &old_object)
             }
+            check_ownership_transition(
+                &move_obj,
+                &recipient,
+                &old_object,
+                newly_generated_ids,
+                protocol_config,
+            )?;
 
             // increment the object version. note that if the transferred object was
             // freshly created, this means that its version will now be 1.
@@ -632,6 +973,44 @@ fn handle_transfer<
     Ok(())
 }
 
+/// Check that giving `new_object` the owner `recipient` is a valid ownership transition:
+/// - A shared or party object passed by value can only be deleted, so that the transactions
+///   sequenced after the one deleting it find it deleted rather than owned by someone else.
+/// - An object owned by another object cannot be frozen, shared or given to a party, since the
+///   `ChildRef` held by its parent could then never be consumed.
+/// - An object can only be shared or given to a party in the transaction that creates it, unless
+///   `allow_sharing_existing_objects` is set in `protocol_config`. Objects that were passed in by
+///   value or unwrapped in this transaction are not new.
+fn check_ownership_transition(
+    new_object: &MoveObject,
+    recipient: &Owner,
+    old_object: &Option<(object::Owner, SequenceNumber)>,
+    newly_generated_ids: &HashSet<ObjectID>,
+    protocol_config: &ProtocolConfig,
+) -> SuiResult {
+    let object_id = new_object.id();
+    if let Some((owner, _)) = old_object {
+        fp_ensure!(
+            !owner.is_shared(),
+            SuiError::SharedObjectNotDeleted { object_id }
+        );
+    }
+    match recipient {
+        Owner::Immutable | Owner::Shared | Owner::Party(_) => {
+            if let Some((Owner::ObjectOwner(_), _)) = old_object {
+                return Err(SuiError::FreezeOrShareObjectOwnedObject { object_id });
+            }
+        }
+        Owner::AddressOwner(_) | Owner::ObjectOwner(_) => return Ok(()),
+    }
+    let is_new = old_object.is_none() && newly_generated_ids.contains(&object_id);
+    fp_ensure!(
+        !recipient.is_shared() || is_new || protocol_config.allow_sharing_existing_objects,
+        SuiError::SharedObjectNotNew { object_id }
+    );
+    Ok(())
+}
+
 #[cfg(debug_assertions)]
 fn check_transferred_object_invariants(
     new_object: &MoveObject,
@@ -758,6 +1137,7 @@ pub fn resolve_and_type_check(
                 }
                 CallArg::ImmOrOwnedObject(ref_) => InputObjectKind::ImmOrOwnedMoveObject(ref_),
                 CallArg::SharedObject(id) => InputObjectKind::SharedMoveObject(id),
+                CallArg::ReadOnlySharedObject(id) => InputObjectKind::ReadOnlySharedMoveObject(id),
             };
 
             let id = object_kind.object_id();
@@ -781,7 +1161,10 @@ pub fn resolve_and_type_check(
                     );
                     return Err(SuiError::TypeError { error });
                 }
-                InputObjectKind::SharedMoveObject(_) if !object.is_shared() => {
+                InputObjectKind::SharedMoveObject(_)
+                | InputObjectKind::ReadOnlySharedMoveObject(_)
+                    if !object.is_shared() =>
+                {
                     let error = format!(
                         "Argument at index {} populated with an immutable or owned object id {} \
                         but an shared object was expected",
@@ -804,6 +1187,17 @@ pub fn resolve_and_type_check(
                 }
             };
             let object_arg = move_object.contents().to_vec();
+            // A shared object read by the transaction keeps its version, so it can't be changed.
+            if matches!(object_kind, InputObjectKind::ReadOnlySharedMoveObject(_))
+                && !matches!(param_type, SignatureToken::Reference(_))
+            {
+                let error = format!(
+                    "Argument {} is a read-only shared object, which can only be passed by \
+                    immutable reference",
+                    idx
+                );
+                return Err(SuiError::TypeError { error });
+            }
             // check that m.type_ matches the parameter types of the function
             let inner_param_type = match &param_type {
                 SignatureToken::Reference(inner_t) => &**inner_t,
@@ -821,13 +1215,14 @@ pub fn resolve_and_type_check(
                 t @ SignatureToken::Struct(_)
                 | t @ SignatureToken::StructInstantiation(_, _)
                 | t @ SignatureToken::TypeParameter(_) => {
-                    if !object.is_owned() {
-                        // Forbid passing shared (both mutable and immutable) object by value.
-                        // This ensures that shared object cannot be transferred, deleted or wrapped.
+                    if object.is_immutable() {
+                        // Forbid passing immutable objects by value. Shared and party objects
+                        // can be passed by value to be deleted, which `check_ownership_transition`
+                        // and `process_successful_execution` ensure is all they can do.
                         return Err(SuiError::TypeError {
                             error: format!(
-                                "Only owned object can be passed by-value, violation found \
-                                        in argument {}",
+                                "Only owned or shared object can be passed by-value, violation \
+                                        found in argument {}",
                                 idx
                             ),
                         });
@@ -900,6 +1295,103 @@ fn check_child_object_of_shared_object(
     Ok(())
 }
 
+/// For each (either direct or indirect) child object of a shared object passed as a Move argument,
+/// call the `authorize_child` function of the module defining the type of the shared object, if
+/// it declares one for that type, with the shared object, the ID of the child and the sender.
+/// The use of the child is rejected unless the function returns true, which lets the shared
+/// object restrict the children that can be used through it. The function is charged to the
+/// transaction, and any events it emits are discarded.
+fn authorize_children_of_shared_objects<
+    E: Debug,
+    S: ResourceResolver<Error = E> + ModuleResolver<Error = E> + Storage + ChildObjectResolver,
+>(
+    vm: &MoveVM,
+    state_view: &S,
+    objects: &BTreeMap<ObjectID, impl Borrow<Object>>,
+    sender: SuiAddress,
+    gas_status: &mut SuiGasStatus,
+) -> SuiResult {
+    let object_owner_map = objects
+        .iter()
+        .map(|(id, obj)| (*id, obj.borrow().owner))
+        .collect();
+    let ancestor_map = ObjectRootAncestorMap::new(&object_owner_map)?;
+    for (object_id, owner) in object_owner_map {
+        // We are only interested in objects owned by objects.
+        if !matches!(owner, Owner::ObjectOwner(..)) {
+            continue;
+        }
+        let (ancestor_id, ancestor_owner) = ancestor_map.get_root_ancestor(&object_id)?;
+        if !ancestor_owner.is_shared() {
+            continue;
+        }
+        // unwrap safe because the object ID exists in object_owner_map.
+        let ancestor = match &objects.get(&ancestor_id).unwrap().borrow().data {
+            Data::Move(m) => m,
+            Data::Package(_) => continue,
+        };
+        let module_id = ancestor.type_.module_id();
+        let module = vm.load_module(&module_id, state_view)?;
+        if !declares_authorize_child(&module, &ancestor.type_) {
+            continue;
+        }
+        let args = vec![
+            ancestor.contents().to_vec(),
+            bcs::to_bytes(&object_id).unwrap(),
+            bcs::to_bytes(&sender).unwrap(),
+        ];
+        // Children taken by the function are discarded along with its events.
+        let mut session = new_session(vm, state_view, objects.keys().copied().collect());
+        let protocol_config = gas_status.protocol_config();
+        let authorized = gas_status
+            .meter_move_call(|move_gas_status| {
+                costs::with_protocol_config(protocol_config, || {
+                    session.execute_function_bypass_visibility(
+                        &module_id,
+                        AUTHORIZE_CHILD_FN_NAME,
+                        ancestor.type_.type_params.clone(),
+                        args,
+                        move_gas_status,
+                    )
+                })
+            })
+            .map_err(|error| convert_execution_error(state_view, gas_status, error))?
+            .return_values
+            .first()
+            .and_then(|(bytes, _layout)| bcs::from_bytes::<bool>(bytes).ok())
+            .unwrap_or(false);
+        fp_ensure!(
+            authorized,
+            SuiError::UnauthorizedSharedChildUse {
+                child: object_id,
+                ancestor: ancestor_id,
+                ancestor_module: module_id.to_string(),
+            }
+        );
+    }
+    Ok(())
+}
+
+/// Whether `module` declares an `authorize_child` function for the objects of type `type_`. The
+/// verifier ensures that its first parameter is a reference to a struct of the module.
+fn declares_authorize_child(module: &CompiledModule, type_: &StructTag) -> bool {
+    module.function_defs.iter().any(|fdef| {
+        let fhandle = module.function_handle_at(fdef.function);
+        if module.identifier_at(fhandle.name) != AUTHORIZE_CHILD_FN_NAME {
+            return false;
+        }
+        let struct_handle_idx = match module.signature_at(fhandle.parameters).0.first() {
+            Some(SignatureToken::Reference(parent)) => match &**parent {
+                SignatureToken::Struct(idx) | SignatureToken::StructInstantiation(idx, _) => *idx,
+                _ => return false,
+            },
+            _ => return false,
+        };
+        module.identifier_at(module.struct_handle_at(struct_handle_idx).name)
+            == type_.name.as_ident_str()
+    })
+}
+
 fn is_primitive(
     view: &BinaryIndexedView,
     function_type_arguments: &[TypeTag],
@@ -44,7 +44,7 @@ impl ObjectRootAncestorMap {
                         cur_id = parent_id.into();
                         fp_ensure!(cur_id != stack[0], SuiError::CircularObjectOwnership);
                     }
-                    Owner::AddressOwner(_) | Owner::Immutable | Owner::Shared => {
+                    Owner::AddressOwner(_) | Owner::Immutable | Owner::Shared | Owner::Party(_) => {
                         break (cur_id, cur_owner);
                     }
                 };
@@ -0,0 +1,168 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use super::*;
+use sui_types::gas_coin::GasCoin;
+
+#[test]
+fn test_writes_within_the_limits_pass() {
+    let config = ProtocolConfig::get_for_max_version();
+    let totals = WriteTotals {
+        objects: config.max_written_objects,
+        events: config.max_events,
+        bytes: config.max_written_bytes,
+    };
+    assert!(check_execution_limits(&totals, config).is_ok());
+    assert!(check_execution_limits(&WriteTotals::default(), config).is_ok());
+}
+
+#[test]
+fn test_each_limit_is_enforced() {
+    let config = ProtocolConfig::get_for_max_version();
+    let within = WriteTotals {
+        objects: 1,
+        events: 1,
+        bytes: 1,
+    };
+    let over = [
+        (
+            "written objects",
+            WriteTotals {
+                objects: config.max_written_objects + 1,
+                ..within
+            },
+        ),
+        (
+            "events",
+            WriteTotals {
+                events: config.max_events + 1,
+                ..within
+            },
+        ),
+        (
+            "written bytes",
+            WriteTotals {
+                bytes: config.max_written_bytes + 1,
+                ..within
+            },
+        ),
+    ];
+    for (expected_limit, totals) in over {
+        match check_execution_limits(&totals, config) {
+            Err(SuiError::ExceededExecutionLimit { limit, max, actual }) => {
+                assert_eq!(limit, expected_limit);
+                assert_eq!(actual, max + 1);
+            }
+            result => panic!("Expected {expected_limit} to exceed its limit, got {result:?}"),
+        }
+    }
+}
+
+#[test]
+fn test_sharing_existing_objects_follows_the_protocol_config() {
+    let object = GasCoin::new(ObjectID::random(), SequenceNumber::new(), 10).to_object();
+    let passed_by_value = Some((Owner::AddressOwner(SuiAddress::default()), object.version()));
+    let created = HashSet::from([object.id()]);
+
+    let disallowed = ProtocolConfig::get_for_max_version();
+    assert!(!disallowed.allow_sharing_existing_objects);
+    assert!(matches!(
+        check_ownership_transition(
+            &object,
+            &Owner::Shared,
+            &passed_by_value,
+            &HashSet::new(),
+            disallowed,
+        ),
+        Err(SuiError::SharedObjectNotNew { object_id }) if object_id == object.id()
+    ));
+
+    let allowed = ProtocolConfig {
+        allow_sharing_existing_objects: true,
+        ..disallowed.clone()
+    };
+    assert!(check_ownership_transition(
+        &object,
+        &Owner::Shared,
+        &passed_by_value,
+        &HashSet::new(),
+        &allowed,
+    )
+    .is_ok());
+
+    // An object created by the transaction can be shared either way.
+    for config in [disallowed, &allowed] {
+        assert!(
+            check_ownership_transition(&object, &Owner::Shared, &None, &created, config).is_ok()
+        );
+    }
+}
+
+fn serialized(modules: &[CompiledModule]) -> Vec<Vec<u8>> {
+    modules
+        .iter()
+        .map(|module| {
+            let mut bytes = Vec::new();
+            module.serialize(&mut bytes).unwrap();
+            bytes
+        })
+        .collect()
+}
+
+#[test]
+fn test_framework_upgrade_to_the_same_bytecode_is_compatible() {
+    let modules = sui_framework::get_sui_framework();
+    let package = MovePackage::from_iter(modules.clone());
+    let upgraded = verify_framework_upgrade(&package, &serialized(&modules)).unwrap();
+    assert_eq!(upgraded, modules);
+}
+
+#[test]
+fn test_framework_upgrade_cannot_remove_modules_or_move_them() {
+    let modules = sui_framework::get_sui_framework();
+    let package = MovePackage::from_iter(modules.clone());
+
+    let removed = serialized(&modules[1..]);
+    assert!(matches!(
+        verify_framework_upgrade(&package, &removed),
+        Err(SuiError::IncompatibleFrameworkUpgrade { package_id, .. }) if package_id == package.id()
+    ));
+
+    // The modules of another framework package are not at the address of this one.
+    let moved = serialized(&sui_framework::get_move_stdlib());
+    assert!(matches!(
+        verify_framework_upgrade(&package, &moved),
+        Err(SuiError::IncompatibleFrameworkUpgrade { .. })
+    ));
+}
+
+#[test]
+fn test_upgrade_at_another_address_is_compatible() {
+    let modules = sui_framework::get_sui_framework();
+    let package = MovePackage::from_iter(modules.clone());
+
+    // The new version of a package is published at a new address.
+    let address = AccountAddress::from(ObjectID::random());
+    let rewriter = ModuleHandleRewriter::new(
+        modules
+            .iter()
+            .map(|module| {
+                let id = module.self_id();
+                (id.clone(), ModuleId::new(address, id.name().to_owned()))
+            })
+            .collect(),
+    )
+    .unwrap();
+    let mut upgraded = modules;
+    for module in &mut upgraded {
+        rewriter.sub_module_ids(module);
+    }
+    assert!(check_upgrade_compatibility(&package, &upgraded).is_ok());
+
+    let removed = &upgraded[1..];
+    assert!(matches!(
+        check_upgrade_compatibility(&package, removed),
+        Err(error) if error.contains("is removed")
+    ));
+    assert!(check_upgrade_compatibility(&package, &[]).is_err());
+}
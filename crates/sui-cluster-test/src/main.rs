@@ -6,7 +6,7 @@ use serde_json::json;
 use std::collections::HashMap;
 use sui::config::{Config, GatewayType, WalletConfig};
 use sui::{
-    keystore::KeystoreType,
+    keystore::{Keystore, KeystoreType, SuiKeystore},
     wallet_commands::{
         call_move, WalletContext, EXAMPLE_NFT_DESCRIPTION, EXAMPLE_NFT_NAME, EXAMPLE_NFT_URL,
     },
@@ -82,7 +82,7 @@ impl ClusterTest {
             .await
             .to_effect_response()
             .unwrap();
-        let effects = response.effects;
+        let effects = response.effects.unwrap();
         if !matches!(effects.status, SuiExecutionStatus::Success { .. }) {
             panic!(
                 "Failed to execute transfer tranasction: {:?}",
@@ -196,7 +196,7 @@ impl ClusterTest {
         let signature = self
             .wallet_context()
             .keystore
-            .sign(&self.context.address, &txn_data.to_bytes())
+            .sign(&self.context.address, &txn_data.to_intent_bytes())
             .unwrap_or_else(|e| panic!("Failed to sign transaction for {}. {}", desc, e));
         self.wallet_context()
             .gateway
@@ -369,13 +369,20 @@ impl ClusterTest {
 
         info!("Use gateway: {}", &gateway_addr);
         info!("Use facuet: {}", &faucet_addr);
+        // The keystore of a test run is thrown away with it, so it is left in plaintext rather
+        // than asking for a passphrase.
         let keystore_path = temp_dir.path().join("wallet.key");
+        let mut test_keystore = SuiKeystore::default();
+        test_keystore.set_path(&keystore_path);
+        let new_address = test_keystore.add_random_key().unwrap();
         let keystore = KeystoreType::File(keystore_path);
-        let new_address = keystore.init().unwrap().add_random_key().unwrap();
         WalletConfig {
             accounts: vec![new_address],
+            aliases: Default::default(),
             keystore,
             gateway: GatewayType::RPC(gateway_addr),
+            envs: vec![],
+            active_env: None,
             active_address: Some(new_address),
         }
         .persisted(&wallet_config_path)
@@ -4,8 +4,8 @@
 use crate::{
     genesis,
     genesis_config::{GenesisConfig, ValidatorGenesisInfo},
-    utils, ConsensusConfig, NetworkConfig, NodeConfig, ValidatorInfo, AUTHORITIES_DB_NAME,
-    CONSENSUS_DB_NAME, DEFAULT_STAKE,
+    utils, ConsensusConfig, ForkDetection, NetworkConfig, NodeConfig, ValidatorInfo,
+    AUTHORITIES_DB_NAME, CONSENSUS_DB_NAME, DEFAULT_STAKE,
 };
 use debug_ignore::DebugIgnore;
 use narwhal_config::{
@@ -17,7 +17,10 @@ use std::{
     path::{Path, PathBuf},
     sync::Arc,
 };
-use sui_types::{base_types::encode_bytes_hex, crypto::get_key_pair_from_rng};
+use sui_types::{
+    base_types::{encode_bytes_hex, SuiAddress},
+    crypto::get_key_pair_from_rng,
+};
 
 pub struct ConfigBuilder<R = OsRng> {
     rng: R,
@@ -86,25 +89,38 @@ impl<R: ::rand::RngCore + ::rand::CryptoRng> ConfigBuilder<R> {
         self.build_with_validators(validators)
     }
 
-    pub fn build_with_validators(mut self, validators: Vec<ValidatorGenesisInfo>) -> NetworkConfig {
+    pub fn build_with_validators(
+        mut self,
+        mut validators: Vec<ValidatorGenesisInfo>,
+    ) -> NetworkConfig {
+        let initial_accounts_config = self
+            .initial_accounts_config
+            .unwrap_or_else(GenesisConfig::for_local_testing);
+
+        // Tokens staked at genesis add to the stake of their validator, in the Sui committee
+        // as well as in the consensus committee.
+        if let Some(schedule) = &initial_accounts_config.token_distribution_schedule {
+            let addresses = validators
+                .iter()
+                .map(|validator| SuiAddress::from(validator.key_pair.public_key_bytes()))
+                .collect::<Vec<_>>();
+            let stakes = schedule.validator_stakes(&addresses).unwrap();
+            for (validator, address) in validators.iter_mut().zip(addresses) {
+                validator.stake += stakes.get(&address).copied().unwrap_or_default();
+            }
+        }
+
         let validator_set = validators
             .iter()
             .map(|validator| {
-                let public_key = *validator.key_pair.public_key_bytes();
-                let stake = validator.stake;
-                let network_address = validator.network_address.clone();
-
-                ValidatorInfo {
-                    public_key,
-                    stake,
-                    network_address,
-                }
+                ValidatorInfo::new(
+                    &validator.key_pair,
+                    validator.stake,
+                    validator.network_address.clone(),
+                )
             })
             .collect::<Vec<_>>();
 
-        let initial_accounts_config = self
-            .initial_accounts_config
-            .unwrap_or_else(GenesisConfig::for_local_testing);
         let (account_keys, objects) = initial_accounts_config
             .generate_accounts(&mut self.rng)
             .unwrap();
@@ -127,6 +143,8 @@ impl<R: ::rand::RngCore + ::rand::CryptoRng> ConfigBuilder<R> {
             builder.build()
         };
 
+        let epoch_duration_ms = initial_accounts_config.epoch_duration_ms;
+        let committee = genesis.committee();
         let narwhal_committee = validators
             .iter()
             .map(|validator| {
@@ -150,7 +168,8 @@ impl<R: ::rand::RngCore + ::rand::CryptoRng> ConfigBuilder<R> {
                 .into_iter()
                 .collect();
                 let authority = Authority {
-                    stake: validator.stake as Stake, //TODO this should at least be the same size integer
+                    stake: committee.consensus_weight(validator.key_pair.public_key_bytes())
+                        as Stake,
                     primary,
                     workers,
                 };
@@ -185,12 +204,27 @@ impl<R: ::rand::RngCore + ::rand::CryptoRng> ConfigBuilder<R> {
 
                 NodeConfig {
                     key_pair: Arc::new(validator.key_pair),
+                    next_key_pair: None,
                     db_path,
                     network_address,
                     metrics_address: utils::available_local_socket_address(),
                     json_rpc_address: utils::available_local_socket_address(),
                     consensus_config: Some(consensus_config),
                     enable_event_processing: false,
+                    log_level: None,
+                    fork_detection: ForkDetection::Off,
+                    metrics_push: None,
+                    anomaly_webhook_url: None,
+                    validator_server_limits: Default::default(),
+                    json_rpc_limits: Default::default(),
+                    json_rpc_server: Default::default(),
+                    graphql_address: None,
+                    deny_list: Default::default(),
+                    archive_path: None,
+                    archive_url: None,
+                    checkpoints_retained: None,
+                    snapshot_path: None,
+                    epoch_duration_ms,
                     genesis: crate::node::Genesis::new(genesis.clone()),
                 }
             })
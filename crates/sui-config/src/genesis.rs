@@ -2,18 +2,28 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::ValidatorInfo;
-use anyhow::Context;
+use anyhow::{anyhow, Context};
 use base64ct::Encoding;
 use move_binary_format::CompiledModule;
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
-use std::{fs, path::Path};
+use std::collections::BTreeMap;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
 use sui_types::{
-    base_types::TxContext,
+    base_types::{SuiAddress, TxContext},
     committee::{Committee, EpochId},
+    crypto::{sha3_hash, AuthoritySignature, KeyPair, PublicKeyBytes, Signable},
+    intent::{IntentScope, IntentScoped},
     object::Object,
 };
 use tracing::{info, trace};
 
+const GENESIS_BUILDER_COMMITTEE_DIR: &str = "committee";
+const GENESIS_BUILDER_OBJECTS_DIR: &str = "objects";
+const GENESIS_BUILDER_SIGNATURES_DIR: &str = "signatures";
+
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct Genesis {
     modules: Vec<Vec<CompiledModule>>,
@@ -49,7 +59,12 @@ impl Genesis {
             .iter()
             .map(|validator| (validator.public_key(), validator.stake()))
             .collect();
-        Committee::new(self.epoch(), voting_rights)
+        let protocol_keys = self
+            .validator_set()
+            .iter()
+            .map(|validator| (validator.public_key(), validator.protocol_key()))
+            .collect();
+        Committee::new(self.epoch(), voting_rights, protocol_keys)
     }
 
     pub fn get_default_genesis() -> Self {
@@ -72,6 +87,49 @@ impl Genesis {
             .with_context(|| format!("Unable to save Genesis to {}", path.display()))?;
         Ok(())
     }
+
+    /// The digest of the genesis, for the operators of a genesis ceremony to check that they
+    /// built the same genesis.
+    pub fn sha3(&self) -> [u8; 32] {
+        sha3_hash(self)
+    }
+
+    /// Check that every validator of the genesis committee holds its protocol key, and signed
+    /// the genesis with it.
+    pub fn verify_signatures(
+        &self,
+        signatures: &BTreeMap<PublicKeyBytes, AuthoritySignature>,
+    ) -> Result<(), anyhow::Error> {
+        for validator in &self.validator_set {
+            let address = validator.sui_address();
+            validator
+                .protocol_key_proof
+                .verify(&validator.protocol_key())
+                .with_context(|| format!("Invalid protocol key proof of validator {address}"))?;
+            let signature = signatures
+                .get(&validator.public_key())
+                .ok_or_else(|| anyhow!("Validator {address} has not signed the genesis"))?;
+            let key = validator.protocol_key().to_public_key()?;
+            signature
+                .verify(self, &key)
+                .with_context(|| format!("Invalid genesis signature of validator {address}"))?;
+        }
+        Ok(())
+    }
+}
+
+impl<W> Signable<W> for Genesis
+where
+    W: std::io::Write,
+{
+    fn write(&self, writer: &mut W) {
+        write!(writer, "Genesis::").expect("Hasher should not fail");
+        bcs::serialize_into(writer, &self).expect("Message serialization should not fail");
+    }
+}
+
+impl IntentScoped for Genesis {
+    const INTENT_SCOPE: IntentScope = IntentScope::Genesis;
 }
 
 impl Serialize for Genesis {
@@ -166,6 +224,7 @@ impl<'de> Deserialize<'de> for Genesis {
     }
 }
 
+#[derive(Clone)]
 pub struct Builder {
     sui_framework: Option<Vec<CompiledModule>>,
     move_framework: Option<Vec<CompiledModule>>,
@@ -173,6 +232,7 @@ pub struct Builder {
     objects: Vec<Object>,
     genesis_ctx: TxContext,
     validators: Vec<ValidatorInfo>,
+    signatures: BTreeMap<PublicKeyBytes, AuthoritySignature>,
 }
 
 impl Builder {
@@ -184,6 +244,7 @@ impl Builder {
             objects: vec![],
             genesis_ctx,
             validators: vec![],
+            signatures: BTreeMap::new(),
         }
     }
 
@@ -222,6 +283,87 @@ impl Builder {
         self
     }
 
+    /// Sign the genesis of this builder with the key of a validator.
+    pub fn add_validator_signature(mut self, key_pair: &KeyPair) -> Self {
+        let genesis = self.clone().build();
+        let signature = AuthoritySignature::new(&genesis, key_pair);
+        self.signatures
+            .insert(*key_pair.public_key_bytes(), signature);
+        self
+    }
+
+    pub fn validators(&self) -> &[ValidatorInfo] {
+        &self.validators
+    }
+
+    pub fn objects(&self) -> &[Object] {
+        &self.objects
+    }
+
+    pub fn signatures(&self) -> &BTreeMap<PublicKeyBytes, AuthoritySignature> {
+        &self.signatures
+    }
+
+    /// Load the validators, objects and signatures of a genesis ceremony from the directory
+    /// `path`, in the order of their file names. The genesis is built with the frameworks of
+    /// this build.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, anyhow::Error> {
+        let path = path.as_ref();
+        trace!("Reading Genesis Builder from {}", path.display());
+        let mut builder = Self::new(sui_adapter::genesis::get_genesis_context());
+
+        for file in sorted_files(&path.join(GENESIS_BUILDER_COMMITTEE_DIR))? {
+            let validator: ValidatorInfo = serde_yaml::from_slice(&fs::read(&file)?)
+                .with_context(|| format!("Invalid validator info {}", file.display()))?;
+            builder = builder.add_validator(validator);
+        }
+        for file in sorted_files(&path.join(GENESIS_BUILDER_OBJECTS_DIR))? {
+            let object: Object = bcs::from_bytes(&fs::read(&file)?)
+                .with_context(|| format!("Invalid object {}", file.display()))?;
+            builder = builder.add_object(object);
+        }
+        for file in sorted_files(&path.join(GENESIS_BUILDER_SIGNATURES_DIR))? {
+            let address: SuiAddress = file_name(&file)?.parse()?;
+            let validator = builder
+                .validators
+                .iter()
+                .find(|validator| validator.sui_address() == address)
+                .ok_or_else(|| anyhow!("Signature of {address}, which is not a validator"))?;
+            let signature = bcs::from_bytes(&fs::read(&file)?)
+                .with_context(|| format!("Invalid signature {}", file.display()))?;
+            builder.signatures.insert(validator.public_key(), signature);
+        }
+        Ok(builder)
+    }
+
+    /// Save the validators, objects and signatures of a genesis ceremony to the directory
+    /// `path`, one file each.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), anyhow::Error> {
+        let path = path.as_ref();
+        trace!("Writing Genesis Builder to {}", path.display());
+        let committee_dir = path.join(GENESIS_BUILDER_COMMITTEE_DIR);
+        let objects_dir = path.join(GENESIS_BUILDER_OBJECTS_DIR);
+        let signatures_dir = path.join(GENESIS_BUILDER_SIGNATURES_DIR);
+        for dir in [&committee_dir, &objects_dir, &signatures_dir] {
+            fs::create_dir_all(dir)
+                .with_context(|| format!("Unable to create {}", dir.display()))?;
+        }
+
+        for validator in &self.validators {
+            let file = committee_dir.join(validator.sui_address().to_string());
+            fs::write(file, serde_yaml::to_vec(validator)?)?;
+        }
+        for object in &self.objects {
+            let file = objects_dir.join(object.id().to_string());
+            fs::write(file, bcs::to_bytes(object)?)?;
+        }
+        for (public_key, signature) in &self.signatures {
+            let file = signatures_dir.join(SuiAddress::from(public_key).to_string());
+            fs::write(file, bcs::to_bytes(signature)?)?;
+        }
+        Ok(())
+    }
+
     pub fn build(self) -> Genesis {
         let mut modules = Vec::new();
         let objects = self.objects;
@@ -257,9 +399,32 @@ impl Builder {
     }
 }
 
+/// The files of `dir` sorted by name, or none if `dir` doesn't exist.
+fn sorted_files(dir: &Path) -> Result<Vec<PathBuf>, anyhow::Error> {
+    if !dir.exists() {
+        return Ok(vec![]);
+    }
+    let mut files = fs::read_dir(dir)
+        .with_context(|| format!("Unable to read {}", dir.display()))?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<Result<Vec<_>, _>>()?;
+    files.sort();
+    Ok(files)
+}
+
+fn file_name(file: &Path) -> Result<&str, anyhow::Error> {
+    file.file_name()
+        .and_then(|name| name.to_str())
+        .ok_or_else(|| anyhow!("Invalid file name {}", file.display()))
+}
+
 #[cfg(test)]
 mod test {
-    use super::Genesis;
+    use super::{Builder, Genesis};
+    use crate::ValidatorInfo;
+    use sui_types::base_types::{ObjectID, SuiAddress};
+    use sui_types::crypto::get_key_pair;
+    use sui_types::object::Object;
 
     #[test]
     fn roundtrip() {
@@ -276,4 +441,48 @@ mod test {
         let from_s = serde_yaml::from_str(&s).unwrap();
         assert_eq!(genesis, from_s);
     }
+    #[test]
+    fn ceremony() {
+        let dir = tempfile::tempdir().unwrap();
+        let keys = [get_key_pair().1, get_key_pair().1];
+        let mut builder = Builder::new(sui_adapter::genesis::get_genesis_context());
+        for key in &keys {
+            builder = builder.add_validator(ValidatorInfo::new(
+                key,
+                1,
+                crate::utils::new_network_address(),
+            ));
+        }
+        builder = builder.add_object(Object::with_id_owner_gas_for_testing(
+            ObjectID::random(),
+            SuiAddress::random_for_testing_only(),
+            1000,
+        ));
+        builder.save(dir.path()).unwrap();
+
+        // Each operator signs the genesis built from the ceremony directory.
+        let genesis = Builder::load(dir.path()).unwrap().build();
+        for key in &keys {
+            let builder = Builder::load(dir.path()).unwrap();
+            assert_eq!(builder.clone().build(), genesis);
+            builder
+                .add_validator_signature(key)
+                .save(dir.path())
+                .unwrap();
+        }
+        let builder = Builder::load(dir.path()).unwrap();
+        assert_eq!(builder.signatures().len(), 2);
+        genesis.verify_signatures(builder.signatures()).unwrap();
+
+        // A genesis with another object is not the genesis which was signed.
+        let other = builder
+            .clone()
+            .add_object(Object::with_id_owner_gas_for_testing(
+                ObjectID::random(),
+                SuiAddress::random_for_testing_only(),
+                1000,
+            ))
+            .build();
+        assert!(other.verify_signatures(builder.signatures()).is_err());
+    }
 }
@@ -1,15 +1,16 @@
 // Copyright (c) 2022, Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use anyhow::Result;
+use anyhow::{bail, Result};
 use move_binary_format::CompiledModule;
 use multiaddr::Multiaddr;
 use serde::{Deserialize, Serialize};
 use std::collections::{BTreeMap, BTreeSet};
 use std::path::PathBuf;
 use sui_types::base_types::{ObjectID, SuiAddress, TxContext};
-use sui_types::committee::StakeUnit;
+use sui_types::committee::{EpochId, StakeUnit};
 use sui_types::crypto::{get_key_pair_from_rng, KeyPair};
+use sui_types::locked_coin::LockedCoin;
 use sui_types::object::Object;
 use tracing::info;
 
@@ -23,6 +24,14 @@ pub struct GenesisConfig {
     pub move_packages: Vec<PathBuf>,
     pub sui_framework_lib_path: Option<PathBuf>,
     pub move_framework_lib_path: Option<PathBuf>,
+    /// How the tokens of the network are allocated at genesis, in addition to the gas objects
+    /// of `accounts`.
+    #[serde(default)]
+    pub token_distribution_schedule: Option<TokenDistributionSchedule>,
+    /// Local and testing networks only: the duration of the epochs of the network, see
+    /// `epoch-duration-ms` in the configs of its validators.
+    #[serde(default)]
+    pub epoch_duration_ms: Option<u64>,
 }
 
 impl Config for GenesisConfig {}
@@ -80,6 +89,11 @@ impl GenesisConfig {
             }
         }
 
+        if let Some(schedule) = &self.token_distribution_schedule {
+            schedule.validate_vesting()?;
+            preload_objects.extend(schedule.generate_gas_objects());
+        }
+
         Ok((keys, preload_objects))
     }
 
@@ -138,6 +152,128 @@ pub struct AccountConfig {
     pub gas_object_ranges: Option<Vec<ObjectConfigRange>>,
 }
 
+/// A schedule of the allocations of tokens at genesis, such as:
+///
+/// ```yaml
+/// allocations:
+///   - recipient_address: "0x09818aac3edf9cf9b006b70c36e7241768b26386"
+///     amount: 1000000
+///   - recipient_address: "0x4e45306e0c17bea691439e71f3f0bfc17181d63b"
+///     amount: 5000000
+///     staked_with_validator: "0x4e45306e0c17bea691439e71f3f0bfc17181d63b"
+///   - recipient_address: "0x09818aac3edf9cf9b006b70c36e7241768b26386"
+///     amount: 2000000
+///     vesting:
+///       start_epoch: 10
+///       end_epoch: 100
+/// ```
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct TokenDistributionSchedule {
+    pub allocations: Vec<TokenAllocation>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct TokenAllocation {
+    pub recipient_address: SuiAddress,
+    pub amount: u64,
+    /// The validator the amount is staked with. Delegations only start with the first epoch,
+    /// so a staked allocation adds to the genesis stake of the validator, and its recipient
+    /// must be the validator itself.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub staked_with_validator: Option<SuiAddress>,
+    /// The schedule the amount vests under, locked in a `LockedCoin<SUI>` until then.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub vesting: Option<VestingSchedule>,
+}
+
+/// Nothing vests before `start_epoch`, everything at `end_epoch`, and the amount vests linearly
+/// in between. A schedule starting and ending at the same epoch is a timelock.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct VestingSchedule {
+    pub start_epoch: EpochId,
+    pub end_epoch: EpochId,
+}
+
+impl TokenDistributionSchedule {
+    /// One object owned by the recipient of each allocation which is not staked: a gas object,
+    /// or a locked coin for the allocations which vest.
+    pub fn generate_gas_objects(&self) -> Vec<Object> {
+        self.allocations
+            .iter()
+            .filter(|allocation| allocation.staked_with_validator.is_none())
+            .map(|allocation| match allocation.vesting {
+                Some(vesting) => LockedCoin::new_sui_object(
+                    ObjectID::random(),
+                    allocation.recipient_address,
+                    allocation.amount,
+                    vesting.start_epoch,
+                    vesting.end_epoch,
+                ),
+                None => Object::with_id_owner_gas_for_testing(
+                    ObjectID::random(),
+                    allocation.recipient_address,
+                    allocation.amount,
+                ),
+            })
+            .collect()
+    }
+
+    /// Check that the vesting schedules end no earlier than they start, and that staked
+    /// allocations don't vest.
+    pub fn validate_vesting(&self) -> Result<()> {
+        for allocation in &self.allocations {
+            let vesting = match allocation.vesting {
+                Some(vesting) => vesting,
+                None => continue,
+            };
+            if vesting.end_epoch < vesting.start_epoch {
+                bail!(
+                    "The tokens of {} vest until epoch {}, before they start vesting at epoch {}",
+                    allocation.recipient_address,
+                    vesting.end_epoch,
+                    vesting.start_epoch
+                );
+            }
+            if allocation.staked_with_validator.is_some() {
+                bail!(
+                    "The tokens of {} are staked, and staked tokens cannot vest",
+                    allocation.recipient_address
+                );
+            }
+        }
+        Ok(())
+    }
+
+    /// The amount staked with each validator, checking that every staked allocation is staked
+    /// by one of `validators` with itself.
+    pub fn validator_stakes(
+        &self,
+        validators: &[SuiAddress],
+    ) -> Result<BTreeMap<SuiAddress, StakeUnit>> {
+        let mut stakes = BTreeMap::new();
+        for allocation in &self.allocations {
+            let validator = match allocation.staked_with_validator {
+                Some(validator) => validator,
+                None => continue,
+            };
+            if !validators.contains(&validator) {
+                bail!("Tokens are staked with {validator}, which is not a genesis validator");
+            }
+            if allocation.recipient_address != validator {
+                bail!(
+                    "Tokens of {} are staked with validator {validator}, but only validators can stake at genesis",
+                    allocation.recipient_address
+                );
+            }
+            let stake: &mut StakeUnit = stakes.entry(validator).or_default();
+            *stake = stake
+                .checked_add(allocation.amount)
+                .ok_or_else(|| anyhow::anyhow!("The stake of validator {validator} overflows"))?;
+        }
+        Ok(stakes)
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct ObjectConfigRange {
     /// Starting object id
@@ -216,6 +352,97 @@ impl Default for GenesisConfig {
             move_packages: vec![],
             sui_framework_lib_path: None,
             move_framework_lib_path: None,
+            token_distribution_schedule: None,
+            epoch_duration_ms: None,
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sui_types::gas_coin::GAS;
+
+    #[test]
+    fn token_distribution_schedule() {
+        let validator = SuiAddress::random_for_testing_only();
+        let holder = SuiAddress::random_for_testing_only();
+        let schedule: TokenDistributionSchedule = serde_yaml::from_str(&format!(
+            "---
+allocations:
+  - recipient_address: \"{holder}\"
+    amount: 1000
+  - recipient_address: \"{validator}\"
+    amount: 20
+    staked_with_validator: \"{validator}\"
+  - recipient_address: \"{validator}\"
+    amount: 30
+    staked_with_validator: \"{validator}\"
+"
+        ))
+        .unwrap();
+
+        let objects = schedule.generate_gas_objects();
+        assert_eq!(objects.len(), 1);
+        assert_eq!(objects[0].get_single_owner(), Some(holder));
+
+        let stakes = schedule.validator_stakes(&[validator]).unwrap();
+        assert_eq!(
+            stakes.into_iter().collect::<Vec<_>>(),
+            vec![(validator, 50)]
+        );
+        assert!(schedule.validator_stakes(&[holder]).is_err());
+    }
+
+    #[test]
+    fn tokens_are_only_staked_by_validators() {
+        let validator = SuiAddress::random_for_testing_only();
+        let schedule = TokenDistributionSchedule {
+            allocations: vec![TokenAllocation {
+                recipient_address: SuiAddress::random_for_testing_only(),
+                amount: 1000,
+                staked_with_validator: Some(validator),
+                vesting: None,
+            }],
+        };
+        assert!(schedule.validator_stakes(&[validator]).is_err());
+    }
+
+    #[test]
+    fn vesting_allocations_are_locked() {
+        let holder = SuiAddress::random_for_testing_only();
+        let vesting = VestingSchedule {
+            start_epoch: 10,
+            end_epoch: 100,
+        };
+        let mut schedule = TokenDistributionSchedule {
+            allocations: vec![TokenAllocation {
+                recipient_address: holder,
+                amount: 1000,
+                staked_with_validator: None,
+                vesting: Some(vesting),
+            }],
+        };
+        schedule.validate_vesting().unwrap();
+
+        let objects = schedule.generate_gas_objects();
+        assert_eq!(objects.len(), 1);
+        assert_eq!(objects[0].get_single_owner(), Some(holder));
+        let object = objects[0].data.try_as_move().unwrap();
+        assert_eq!(object.type_, LockedCoin::type_(GAS::type_()));
+        let locked: LockedCoin = bcs::from_bytes(object.contents()).unwrap();
+        assert_eq!(
+            (locked.value(), locked.start_epoch, locked.end_epoch),
+            (1000, 10, 100)
+        );
+
+        schedule.allocations[0].vesting = Some(VestingSchedule {
+            start_epoch: 100,
+            end_epoch: 10,
+        });
+        assert!(schedule.validate_vesting().is_err());
+        schedule.allocations[0].vesting = Some(vesting);
+        schedule.allocations[0].staked_with_validator = Some(holder);
+        assert!(schedule.validate_vesting().is_err());
+    }
+}
@@ -17,7 +17,10 @@ pub mod node;
 mod swarm;
 pub mod utils;
 
-pub use node::{ConsensusConfig, NodeConfig, ValidatorInfo};
+pub use node::{
+    ConsensusConfig, DenyList, ForkDetection, JsonRpcLimits, JsonRpcServerConfig,
+    MetricsPushConfig, NodeConfig, TlsConfig, ValidatorInfo, ValidatorServerLimits,
+};
 pub use swarm::NetworkConfig;
 
 const SUI_DIR: &str = ".sui";
@@ -10,11 +10,13 @@ use narwhal_config::Committee as ConsensusCommittee;
 use narwhal_config::Parameters as ConsensusParameters;
 use narwhal_crypto::ed25519::Ed25519PublicKey;
 use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
 use std::net::SocketAddr;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
-use sui_types::base_types::SuiAddress;
-use sui_types::committee::StakeUnit;
+use sui_types::base_types::{ObjectID, SuiAddress, TransactionDigest};
+use sui_types::bls::{ProtocolKeyProof, ProtocolPublicKeyBytes};
+use sui_types::committee::{Committee, StakeUnit};
 use sui_types::crypto::{KeyPair, PublicKeyBytes};
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
@@ -22,6 +24,10 @@ use sui_types::crypto::{KeyPair, PublicKeyBytes};
 pub struct NodeConfig {
     #[serde(default = "default_key_pair")]
     pub key_pair: Arc<KeyPair>,
+    /// The key pair a validator rotates to with `SuiSystem::request_rotate_validator_key`. The
+    /// node signs with it instead of `key-pair` from the epoch the rotation takes effect in.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub next_key_pair: Option<Arc<KeyPair>>,
     pub db_path: PathBuf,
     #[serde(default = "default_grpc_address")]
     pub network_address: Multiaddr,
@@ -36,6 +42,75 @@ pub struct NodeConfig {
     #[serde(default)]
     pub enable_event_processing: bool,
 
+    /// The log level of the node, as directives in the syntax of the `RUST_LOG` environment
+    /// variable, such as `info,sui_core=debug`. `RUST_LOG` sets it when this is unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub log_level: Option<String>,
+
+    /// What a full node does when the effects it computes for a transaction differ from the
+    /// effects signed by a validity threshold of the validators.
+    #[serde(default)]
+    pub fork_detection: ForkDetection,
+
+    /// Where the node pushes its metrics, for nodes which cannot be scraped on
+    /// `metrics_address`, such as validators behind a NAT.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metrics_push: Option<MetricsPushConfig>,
+
+    /// Where the node posts, as JSON, the anomalies threatening the safety of the network it
+    /// detects, such as forks and equivocations, for operators to be paged on them.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub anomaly_webhook_url: Option<String>,
+
+    /// Validators only: the requests of each kind the validator serves concurrently.
+    #[serde(default)]
+    pub validator_server_limits: ValidatorServerLimits,
+
+    #[serde(default)]
+    pub json_rpc_limits: JsonRpcLimits,
+
+    /// How the JSON-RPC server is exposed: to which origins, over TLS or not, and who may call
+    /// the methods which write.
+    #[serde(default)]
+    pub json_rpc_server: JsonRpcServerConfig,
+
+    /// Full nodes only: the address the node serves the GraphQL read API on. Not served by
+    /// default.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub graphql_address: Option<SocketAddr>,
+
+    /// What the node refuses to sign or execute, to stop the damage of an incident until a fix
+    /// is released.
+    #[serde(default, skip_serializing_if = "DenyList::is_empty")]
+    pub deny_list: DenyList,
+
+    /// The directory of the archive of the history of the network, laid out as an object
+    /// storage bucket, which it can be mounted from or synced to. Validators upload their final
+    /// checkpoints to it, and full nodes read the transactions they don't store from it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub archive_path: Option<PathBuf>,
+
+    /// Where clients can read the history of the network this node pruned, such as the URL of
+    /// a public archive bucket. Reported to the clients asking for pruned data.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub archive_url: Option<String>,
+
+    /// Full nodes only: the number of the latest checkpoints they keep, with their contents,
+    /// the older ones being pruned. All of them are kept by default.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub checkpoints_retained: Option<u64>,
+
+    /// The formal snapshot, exported by `sui-tool snapshot export`, which a node with an empty
+    /// store starts from instead of genesis.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub snapshot_path: Option<PathBuf>,
+
+    /// Local and testing networks only: a validator changes epoch after this duration, without
+    /// waiting for the last checkpoint of the epoch, and serves `sui_advanceEpoch` on its JSON-RPC
+    /// address to change it right away.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub epoch_duration_ms: Option<u64>,
+
     pub genesis: Genesis,
 }
 
@@ -58,6 +133,193 @@ pub fn default_json_rpc_address() -> SocketAddr {
     SocketAddr::new(IpAddr::V4(Ipv4Addr::new(0, 0, 0, 0)), 9000)
 }
 
+/// Executions which are not deterministic fork the state of a node from the state of the
+/// validators, and every transaction the node executes afterwards builds on the fork.
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum ForkDetection {
+    /// The effects are not compared.
+    Off,
+    /// A divergence is logged and counted in the `fork_detected_count` metric.
+    Alert,
+    /// A divergence is alerted on, and the node stops syncing transactions, so that its state
+    /// stays at the fork to be inspected.
+    Halt,
+}
+
+impl Default for ForkDetection {
+    fn default() -> Self {
+        Self::Off
+    }
+}
+
+pub const DEFAULT_MAX_INFLIGHT_TRANSACTIONS: usize = 5_000;
+pub const DEFAULT_MAX_INFLIGHT_DRY_RUNS: usize = 500;
+pub const DEFAULT_MAX_INFLIGHT_CERTIFICATES: usize = 5_000;
+pub const DEFAULT_MAX_INFLIGHT_QUERIES: usize = 10_000;
+
+/// Caps on the requests a validator serves concurrently, by kind, so that a flood of one kind
+/// cannot starve the others: cheap queries cannot hold back the execution of certificates, nor
+/// certificates the queries. The requests past a cap are refused until others complete.
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub struct ValidatorServerLimits {
+    /// The transactions to sign.
+    #[serde(default = "default_max_inflight_transactions")]
+    pub max_inflight_transactions: usize,
+    /// The transactions to dry run, which are executed, and so cost more than signing.
+    #[serde(default = "default_max_inflight_dry_runs")]
+    pub max_inflight_dry_runs: usize,
+    /// The certificates to execute, directly or through consensus.
+    #[serde(default = "default_max_inflight_certificates")]
+    pub max_inflight_certificates: usize,
+    /// The queries of accounts, objects, transactions and checkpoints.
+    #[serde(default = "default_max_inflight_queries")]
+    pub max_inflight_queries: usize,
+}
+
+fn default_max_inflight_transactions() -> usize {
+    DEFAULT_MAX_INFLIGHT_TRANSACTIONS
+}
+
+fn default_max_inflight_dry_runs() -> usize {
+    DEFAULT_MAX_INFLIGHT_DRY_RUNS
+}
+
+fn default_max_inflight_certificates() -> usize {
+    DEFAULT_MAX_INFLIGHT_CERTIFICATES
+}
+
+fn default_max_inflight_queries() -> usize {
+    DEFAULT_MAX_INFLIGHT_QUERIES
+}
+
+impl Default for ValidatorServerLimits {
+    fn default() -> Self {
+        Self {
+            max_inflight_transactions: DEFAULT_MAX_INFLIGHT_TRANSACTIONS,
+            max_inflight_dry_runs: DEFAULT_MAX_INFLIGHT_DRY_RUNS,
+            max_inflight_certificates: DEFAULT_MAX_INFLIGHT_CERTIFICATES,
+            max_inflight_queries: DEFAULT_MAX_INFLIGHT_QUERIES,
+        }
+    }
+}
+
+pub const DEFAULT_MAX_MULTI_GET_SIZE: usize = 50;
+
+/// Limits on the requests the JSON-RPC server of a full node serves.
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub struct JsonRpcLimits {
+    /// The largest number of objects or transactions one `multiGet*` request reads.
+    #[serde(default = "default_max_multi_get_size")]
+    pub max_multi_get_size: usize,
+}
+
+fn default_max_multi_get_size() -> usize {
+    DEFAULT_MAX_MULTI_GET_SIZE
+}
+
+impl Default for JsonRpcLimits {
+    fn default() -> Self {
+        Self {
+            max_multi_get_size: DEFAULT_MAX_MULTI_GET_SIZE,
+        }
+    }
+}
+
+pub const DEFAULT_METRICS_PUSH_INTERVAL_MS: u64 = 60_000;
+
+/// A Prometheus push gateway the node pushes its metrics to. The metrics are grouped under the
+/// `sui-node` job, by the public key of the node, its role, `validator` or `fullnode`, and the
+/// labels of the config.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub struct MetricsPushConfig {
+    /// The URL of the push gateway, such as `https://pushgateway.example.com`.
+    pub url: String,
+    /// How often the metrics are pushed.
+    #[serde(default = "default_metrics_push_interval_ms")]
+    pub interval_ms: u64,
+    /// The bearer token the push gateway requires, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub auth_token: Option<String>,
+    /// Labels telling the node apart, added to those the node sets, such as the name of its
+    /// operator.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub labels: BTreeMap<String, String>,
+}
+
+fn default_metrics_push_interval_ms() -> u64 {
+    DEFAULT_METRICS_PUSH_INTERVAL_MS
+}
+
+pub const DEFAULT_MAX_REQUEST_BODY_SIZE: u32 = 10 * 1024 * 1024;
+
+/// How the JSON-RPC server of a node is exposed, for operators to serve the public without a
+/// proxy in front of the node.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub struct JsonRpcServerConfig {
+    /// The origins browsers may call the server from. Any origin may by default, unless the
+    /// `ACCESS_CONTROL_ALLOW_ORIGIN` environment variable lists them, separated by commas.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub allowed_origins: Option<Vec<String>>,
+    /// The largest request body the server reads, in bytes.
+    #[serde(default = "default_max_request_body_size")]
+    pub max_request_body_size: u32,
+    /// The certificate and key the server terminates TLS with, to serve HTTPS rather than HTTP.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tls: Option<TlsConfig>,
+    /// The bearer token the methods which write, such as `sui_submitTransaction`, require in
+    /// the `Authorization` header of their requests. Anyone may call them by default.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub write_auth_token: Option<String>,
+}
+
+fn default_max_request_body_size() -> u32 {
+    DEFAULT_MAX_REQUEST_BODY_SIZE
+}
+
+impl Default for JsonRpcServerConfig {
+    fn default() -> Self {
+        Self {
+            allowed_origins: None,
+            max_request_body_size: DEFAULT_MAX_REQUEST_BODY_SIZE,
+            tls: None,
+            write_auth_token: None,
+        }
+    }
+}
+
+/// PEM files of a TLS certificate chain and of its private key.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub struct TlsConfig {
+    pub cert_path: PathBuf,
+    pub key_path: PathBuf,
+}
+
+/// Transactions an authority refuses to sign or execute. A transaction is denied if its digest
+/// is denied, or if one of its inputs is a denied object or a denied package, such as the
+/// package of a function it calls.
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub struct DenyList {
+    #[serde(default, skip_serializing_if = "BTreeSet::is_empty")]
+    pub transactions: BTreeSet<TransactionDigest>,
+    #[serde(default, skip_serializing_if = "BTreeSet::is_empty")]
+    pub objects: BTreeSet<ObjectID>,
+    #[serde(default, skip_serializing_if = "BTreeSet::is_empty")]
+    pub packages: BTreeSet<ObjectID>,
+}
+
+impl DenyList {
+    pub fn is_empty(&self) -> bool {
+        self.transactions.is_empty() && self.objects.is_empty() && self.packages.is_empty()
+    }
+}
+
 impl Config for NodeConfig {}
 
 impl NodeConfig {
@@ -69,6 +331,22 @@ impl NodeConfig {
         *self.key_pair.public_key_bytes()
     }
 
+    /// Switch to the next key pair if the key rotation of the validator took effect, that is if
+    /// `committee` has the public key of the next key pair but not the one of the current key
+    /// pair. Returns whether the key pair changed.
+    pub fn rotate_key_pair(&mut self, committee: &Committee) -> bool {
+        let rotated = matches!(
+            &self.next_key_pair,
+            Some(next_key_pair) if committee.weight(&self.public_key()) == 0
+                && committee.weight(next_key_pair.public_key_bytes()) > 0
+        );
+        if rotated {
+            // Safe to unwrap since the match above checked the next key pair is set.
+            self.key_pair = self.next_key_pair.take().unwrap();
+        }
+        rotated
+    }
+
     pub fn sui_address(&self) -> SuiAddress {
         SuiAddress::from(self.public_key())
     }
@@ -128,11 +406,26 @@ impl ConsensusConfig {
 #[serde(rename_all = "kebab-case")]
 pub struct ValidatorInfo {
     pub public_key: PublicKeyBytes,
+    /// The key with which the validator signs protocol messages, and the proof that it holds
+    /// its secret.
+    pub protocol_key: ProtocolPublicKeyBytes,
+    pub protocol_key_proof: ProtocolKeyProof,
     pub stake: StakeUnit,
     pub network_address: Multiaddr,
 }
 
 impl ValidatorInfo {
+    /// The info of the validator of `key_pair`, with the protocol key derived from it.
+    pub fn new(key_pair: &KeyPair, stake: StakeUnit, network_address: Multiaddr) -> Self {
+        Self {
+            public_key: *key_pair.public_key_bytes(),
+            protocol_key: key_pair.protocol_public_key(),
+            protocol_key_proof: key_pair.protocol_key_proof(),
+            stake,
+            network_address,
+        }
+    }
+
     pub fn sui_address(&self) -> SuiAddress {
         SuiAddress::from(self.public_key())
     }
@@ -141,6 +434,10 @@ impl ValidatorInfo {
         self.public_key
     }
 
+    pub fn protocol_key(&self) -> ProtocolPublicKeyBytes {
+        self.protocol_key
+    }
+
     pub fn stake(&self) -> StakeUnit {
         self.stake
     }
@@ -204,6 +501,10 @@ enum GenesisLocation {
 mod tests {
     use super::Genesis;
     use crate::{genesis, NodeConfig};
+    use std::collections::BTreeMap;
+    use std::sync::Arc;
+    use sui_types::committee::Committee;
+    use sui_types::crypto::{get_key_pair, KeyPair};
 
     #[test]
     fn serialize_genesis_config_from_file() {
@@ -247,4 +548,29 @@ mod tests {
 
         let _template: NodeConfig = serde_yaml::from_str(TEMPLATE).unwrap();
     }
+
+    #[test]
+    fn rotate_key_pair() {
+        const TEMPLATE: &str = include_str!("../data/fullnode-template.yaml");
+        let committee = |key_pair: &KeyPair| {
+            let name = *key_pair.public_key_bytes();
+            Committee::new(
+                1,
+                BTreeMap::from([(name, 1)]),
+                BTreeMap::from([(name, key_pair.protocol_public_key())]),
+            )
+        };
+
+        let mut config: NodeConfig = serde_yaml::from_str(TEMPLATE).unwrap();
+        let next_key_pair = Arc::new(get_key_pair().1);
+        config.next_key_pair = Some(next_key_pair.clone());
+
+        // The next key pair is only used once the committee has its public key instead of the
+        // public key of the current key pair.
+        let current_committee = committee(config.key_pair());
+        assert!(!config.rotate_key_pair(&current_committee));
+        assert!(config.rotate_key_pair(&committee(&next_key_pair)));
+        assert_eq!(config.public_key(), *next_key_pair.public_key_bytes());
+        assert!(config.next_key_pair.is_none());
+    }
 }
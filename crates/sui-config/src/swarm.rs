@@ -1,7 +1,9 @@
 // Copyright (c) 2022, Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::{builder, genesis, utils, Config, NodeConfig, ValidatorInfo, FULL_NODE_DB_PATH};
+use crate::{
+    builder, genesis, utils, Config, ForkDetection, NodeConfig, ValidatorInfo, FULL_NODE_DB_PATH,
+};
 use rand::rngs::OsRng;
 use serde::{Deserialize, Serialize};
 use std::num::NonZeroUsize;
@@ -64,12 +66,27 @@ impl NetworkConfig {
 
         NodeConfig {
             key_pair,
+            next_key_pair: None,
             db_path: db_path.join(FULL_NODE_DB_PATH),
             network_address: utils::new_network_address(),
             metrics_address: utils::available_local_socket_address(),
             json_rpc_address: utils::available_local_socket_address(),
             consensus_config: None,
             enable_event_processing: true,
+            log_level: None,
+            fork_detection: ForkDetection::Alert,
+            metrics_push: None,
+            anomaly_webhook_url: None,
+            validator_server_limits: Default::default(),
+            json_rpc_limits: Default::default(),
+            json_rpc_server: Default::default(),
+            graphql_address: None,
+            deny_list: Default::default(),
+            archive_path: None,
+            archive_url: None,
+            checkpoints_retained: None,
+            snapshot_path: None,
+            epoch_duration_ms: None,
             genesis: validator_config.genesis.clone(),
         }
     }
@@ -0,0 +1,128 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! The conditions which threaten the safety of the network, reported apart from the other
+//! errors so that operators are paged on them: each one is logged, counted in
+//! `anomalies_detected_count` by kind, and posted to the webhook of the node if it has one.
+
+use std::time::Duration;
+
+use once_cell::sync::{Lazy, OnceCell};
+use prometheus_exporter::prometheus::{register_int_counter_vec, IntCounterVec};
+use serde::Serialize;
+use sui_types::base_types::{AuthorityName, TransactionDigest, TransactionEffectsDigest};
+use sui_types::error::SuiError;
+use sui_types::messages_checkpoint::CheckpointSequenceNumber;
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+use tracing::{error, warn};
+
+/// The anomalies not posted yet past which new ones are dropped, when the webhook is slow.
+const ANOMALY_QUEUE_SIZE: usize = 1000;
+
+const ANOMALY_WEBHOOK_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Anomaly {
+    /// A client signed two transactions on the same owned object version.
+    Equivocation {
+        transaction: TransactionDigest,
+        conflicting_transaction: TransactionDigest,
+    },
+    /// The effects of a transaction computed locally differ from those signed by `authority`,
+    /// and by a validity threshold of the validators.
+    ForkDetected {
+        digest: TransactionDigest,
+        local_effects: TransactionEffectsDigest,
+        peer_effects: TransactionEffectsDigest,
+        authority: AuthorityName,
+    },
+    /// A checkpoint failed the check of its signatures or of its contents.
+    CheckpointSignatureMismatch {
+        sequence_number: CheckpointSequenceNumber,
+        error: String,
+    },
+    /// `authority` answered a request with data which failed its checks.
+    InvalidPeerData {
+        authority: AuthorityName,
+        error: String,
+    },
+}
+
+impl Anomaly {
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Anomaly::Equivocation { .. } => "equivocation",
+            Anomaly::ForkDetected { .. } => "fork_detected",
+            Anomaly::CheckpointSignatureMismatch { .. } => "checkpoint_signature_mismatch",
+            Anomaly::InvalidPeerData { .. } => "invalid_peer_data",
+        }
+    }
+
+    pub fn invalid_peer_data(authority: AuthorityName, error: &SuiError) -> Self {
+        Anomaly::InvalidPeerData {
+            authority,
+            error: error.to_string(),
+        }
+    }
+
+    pub fn checkpoint_signature_mismatch(
+        sequence_number: CheckpointSequenceNumber,
+        error: &SuiError,
+    ) -> Self {
+        Anomaly::CheckpointSignatureMismatch {
+            sequence_number,
+            error: error.to_string(),
+        }
+    }
+}
+
+// Registered once for all the authorities of the process, as the other metrics.
+static ANOMALIES_DETECTED: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "anomalies_detected_count",
+        "Number of anomalies threatening the safety of the network detected, by kind",
+        &["kind"]
+    )
+    .unwrap()
+});
+
+static ANOMALY_SENDER: OnceCell<mpsc::Sender<Anomaly>> = OnceCell::new();
+
+/// Report `anomaly`: log it, count it and post it to the webhook if one is spawned.
+pub fn report(anomaly: Anomaly) {
+    error!(kind = anomaly.kind(), ?anomaly, "Anomaly detected");
+    ANOMALIES_DETECTED
+        .with_label_values(&[anomaly.kind()])
+        .inc();
+    if let Some(sender) = ANOMALY_SENDER.get() {
+        if sender.try_send(anomaly).is_err() {
+            warn!("The anomaly webhook is behind, dropping the anomaly");
+        }
+    }
+}
+
+/// Post each anomaly reported from now on to `url`, as JSON. Only the first webhook spawned in
+/// a process receives the anomalies.
+pub fn spawn_anomaly_webhook(url: String) -> JoinHandle<()> {
+    let (sender, mut receiver) = mpsc::channel(ANOMALY_QUEUE_SIZE);
+    if ANOMALY_SENDER.set(sender).is_err() {
+        warn!("An anomaly webhook is already spawned, not posting to {url}");
+    }
+    tokio::spawn(async move {
+        let client = reqwest::Client::new();
+        while let Some(anomaly) = receiver.recv().await {
+            let result = client
+                .post(&url)
+                .timeout(ANOMALY_WEBHOOK_TIMEOUT)
+                .json(&anomaly)
+                .send()
+                .await
+                .and_then(|response| response.error_for_status());
+            if let Err(error) = result {
+                warn!(?error, kind = anomaly.kind(), "Failed to post the anomaly");
+            }
+        }
+    })
+}
@@ -0,0 +1,256 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! The archive of the history of the network, in object storage. Validators upload each final
+//! checkpoint with the certificates and effects, and so the events, of its transactions, and full
+//! nodes read from the archive the transactions they don't store.
+//!
+//! The objects of the archive are keyed as in an S3 or GCS bucket:
+//! - `checkpoints/<sequence number padded to 20 digits>`: an `ArchivedCheckpoint`.
+//! - `transactions/<hex digest>`: an `ArchivedTransaction`.
+//! - `next-checkpoint`: the sequence number of the next checkpoint to archive.
+//!
+//! All of them are BCS encoded. A transaction is archived before its checkpoint, so readers
+//! finding a checkpoint find its transactions.
+
+use std::io;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use sui_types::base_types::TransactionDigest;
+use sui_types::error::{SuiError, SuiResult};
+use sui_types::messages::{CertifiedTransaction, TransactionEffects};
+use sui_types::messages_checkpoint::{
+    AuthenticatedCheckpoint, AuthorityCheckpointInfo, CheckpointContents, CheckpointResponse,
+    CheckpointSequenceNumber,
+};
+use sui_types::object_change::TransactionChanges;
+use tokio::task::JoinHandle;
+use tracing::{debug, warn};
+
+use crate::authority::AuthorityState;
+
+#[cfg(test)]
+#[path = "unit_tests/archive_tests.rs"]
+mod archive_tests;
+
+/// How often the writer looks for new final checkpoints.
+const ARCHIVE_INTERVAL: Duration = Duration::from_secs(10);
+
+const NEXT_CHECKPOINT_KEY: &str = "next-checkpoint";
+
+/// A store of objects by key, such as an S3 or GCS bucket.
+#[async_trait]
+pub trait ObjectStore: Send + Sync {
+    /// Write the object at `key`, replacing the one there if any.
+    async fn put(&self, key: &str, bytes: Vec<u8>) -> SuiResult;
+
+    async fn get(&self, key: &str) -> SuiResult<Option<Vec<u8>>>;
+}
+
+/// The objects in a directory, each in the file at its key. The directory can be the mount point
+/// of a bucket, or be synced to one.
+pub struct LocalObjectStore {
+    root: PathBuf,
+}
+
+impl LocalObjectStore {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+}
+
+#[async_trait]
+impl ObjectStore for LocalObjectStore {
+    async fn put(&self, key: &str, bytes: Vec<u8>) -> SuiResult {
+        let path = self.root.join(key);
+        if let Some(parent) = path.parent() {
+            tokio::fs::create_dir_all(parent)
+                .await
+                .map_err(archive_error)?;
+        }
+        // Readers never see a partially written object, as renames are atomic.
+        let partial = path.with_extension("partial");
+        tokio::fs::write(&partial, bytes)
+            .await
+            .map_err(archive_error)?;
+        tokio::fs::rename(&partial, &path)
+            .await
+            .map_err(archive_error)
+    }
+
+    async fn get(&self, key: &str) -> SuiResult<Option<Vec<u8>>> {
+        match tokio::fs::read(self.root.join(key)).await {
+            Ok(bytes) => Ok(Some(bytes)),
+            Err(error) if error.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(error) => Err(archive_error(error)),
+        }
+    }
+}
+
+/// A final checkpoint, with the digests of its transactions.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ArchivedCheckpoint {
+    pub checkpoint: AuthenticatedCheckpoint,
+    pub contents: CheckpointContents,
+}
+
+/// A transaction of a final checkpoint, with what its execution did.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ArchivedTransaction {
+    pub certificate: CertifiedTransaction,
+    pub effects: TransactionEffects,
+    pub changes: TransactionChanges,
+}
+
+fn checkpoint_key(checkpoint: CheckpointSequenceNumber) -> String {
+    format!("checkpoints/{checkpoint:020}")
+}
+
+fn transaction_key(digest: &TransactionDigest) -> String {
+    format!("transactions/{}", hex::encode(digest))
+}
+
+fn archive_error(error: impl ToString) -> SuiError {
+    SuiError::ArchiveError {
+        error: error.to_string(),
+    }
+}
+
+async fn put_bcs<T: Serialize>(store: &dyn ObjectStore, key: &str, value: &T) -> SuiResult {
+    store
+        .put(key, bcs::to_bytes(value).map_err(archive_error)?)
+        .await
+}
+
+async fn get_bcs<T: for<'de> Deserialize<'de>>(
+    store: &dyn ObjectStore,
+    key: &str,
+) -> SuiResult<Option<T>> {
+    store
+        .get(key)
+        .await?
+        .map(|bytes| bcs::from_bytes(&bytes).map_err(archive_error))
+        .transpose()
+}
+
+/// Uploads the final checkpoints of a validator, and their transactions, to the archive.
+pub struct ArchiveWriter {
+    state: Arc<AuthorityState>,
+    store: Arc<dyn ObjectStore>,
+}
+
+impl ArchiveWriter {
+    pub fn new(state: Arc<AuthorityState>, store: Arc<dyn ObjectStore>) -> Self {
+        Self { state, store }
+    }
+
+    pub fn spawn(self) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            loop {
+                match self.archive_final_checkpoints().await {
+                    Ok(next_checkpoint) => debug!(next_checkpoint, "Archived checkpoints"),
+                    Err(error) => warn!(?error, "Failed to archive checkpoints"),
+                }
+                tokio::time::sleep(ARCHIVE_INTERVAL).await;
+            }
+        })
+    }
+
+    /// Archive the final checkpoints all the transactions of which the validator executed, from
+    /// the next one the archive misses. Returns the next checkpoint to archive.
+    pub async fn archive_final_checkpoints(&self) -> SuiResult<CheckpointSequenceNumber> {
+        let checkpoints = self
+            .state
+            .checkpoints()
+            .ok_or(SuiError::UnsupportedFeatureError {
+                error: "Checkpoint not supported".to_owned(),
+            })?;
+        let mut next_checkpoint = get_bcs(&*self.store, NEXT_CHECKPOINT_KEY)
+            .await?
+            .unwrap_or(0);
+        loop {
+            let archived = {
+                let mut checkpoints = checkpoints.lock();
+                if next_checkpoint >= checkpoints.lowest_unprocessed_checkpoint() {
+                    return Ok(next_checkpoint);
+                }
+                match checkpoints.handle_past_checkpoint(true, next_checkpoint)? {
+                    CheckpointResponse {
+                        info: AuthorityCheckpointInfo::Past(checkpoint),
+                        detail: Some(contents),
+                    } => ArchivedCheckpoint {
+                        checkpoint,
+                        contents,
+                    },
+                    _ => {
+                        return Err(archive_error(format!(
+                            "Checkpoint {next_checkpoint} is not stored"
+                        )))
+                    }
+                }
+            };
+
+            for digests in &archived.contents.transactions {
+                let (certificate, effects) = self
+                    .state
+                    .get_certificate_and_effects(&digests.transaction)
+                    .await?;
+                let transaction = ArchivedTransaction {
+                    certificate,
+                    effects,
+                    changes: self
+                        .state
+                        .database
+                        .get_transaction_changes(&digests.transaction)?,
+                };
+                put_bcs(
+                    &*self.store,
+                    &transaction_key(&digests.transaction),
+                    &transaction,
+                )
+                .await?;
+            }
+            put_bcs(&*self.store, &checkpoint_key(next_checkpoint), &archived).await?;
+
+            next_checkpoint += 1;
+            put_bcs(&*self.store, NEXT_CHECKPOINT_KEY, &next_checkpoint).await?;
+        }
+    }
+}
+
+/// Reads the history of the network from the archive.
+#[derive(Clone)]
+pub struct ArchiveReader {
+    store: Arc<dyn ObjectStore>,
+}
+
+impl ArchiveReader {
+    pub fn new(store: Arc<dyn ObjectStore>) -> Self {
+        Self { store }
+    }
+
+    pub async fn get_checkpoint(
+        &self,
+        checkpoint: CheckpointSequenceNumber,
+    ) -> SuiResult<Option<ArchivedCheckpoint>> {
+        get_bcs(&*self.store, &checkpoint_key(checkpoint)).await
+    }
+
+    pub async fn get_transaction(
+        &self,
+        digest: &TransactionDigest,
+    ) -> SuiResult<Option<ArchivedTransaction>> {
+        get_bcs(&*self.store, &transaction_key(digest)).await
+    }
+
+    /// The first checkpoint not in the archive yet.
+    pub async fn next_checkpoint(&self) -> SuiResult<CheckpointSequenceNumber> {
+        Ok(get_bcs(&*self.store, NEXT_CHECKPOINT_KEY)
+            .await?
+            .unwrap_or(0))
+    }
+}
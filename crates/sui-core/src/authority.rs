@@ -4,24 +4,29 @@
 
 use crate::checkpoints::FragmentInternalError;
 use crate::{
+    anomaly::{self, Anomaly},
     authority_batch::{BroadcastReceiver, BroadcastSender},
     checkpoints::CheckpointStore,
     epoch::EpochInfoLocals,
     event_handler::EventHandler,
     execution_engine,
-    gateway_types::TransactionEffectsResponse,
+    gateway_types::{
+        CheckpointId, SuiCheckpoint, SuiFinalityProof, SuiStake, SuiTransactionResponseOptions,
+        SuiValidatorApys, SuiValidatorReport, TransactionEffectsResponse,
+    },
     query_helpers::QueryHelpers,
+    signature_verifier::{SignatureVerifier, DEFAULT_VERIFIED_CERTIFICATES_CACHE_SIZE},
+    transaction_builder::DataReader,
     transaction_input_checker,
 };
 use arc_swap::ArcSwap;
 use async_trait::async_trait;
 use itertools::Itertools;
 use move_binary_format::CompiledModule;
-use move_bytecode_utils::module_cache::SyncModuleCache;
 use move_core_types::{
     account_address::AccountAddress,
     ident_str,
-    language_storage::{ModuleId, StructTag},
+    language_storage::{ModuleId, StructTag, TypeTag},
     resolver::{ModuleResolver, ResourceResolver},
 };
 use move_vm_runtime::{move_vm::MoveVM, native_functions::NativeFunctionTable};
@@ -30,34 +35,39 @@ use narwhal_executor::{ExecutionIndices, ExecutionState};
 use once_cell::sync::Lazy;
 use parking_lot::Mutex;
 use prometheus_exporter::prometheus::{
-    register_histogram, register_int_counter, Histogram, IntCounter,
+    register_histogram, register_int_counter, register_int_counter_vec, register_int_gauge,
+    register_int_gauge_vec, Histogram, IntCounter, IntCounterVec, IntGauge, IntGaugeVec,
 };
 use std::ops::Deref;
 use std::{
-    collections::{BTreeMap, HashMap, HashSet, VecDeque},
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque},
     pin::Pin,
     sync::{
-        atomic::{AtomicBool, AtomicUsize, Ordering},
+        atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering},
         Arc,
     },
 };
 use sui_adapter::adapter;
-use sui_config::genesis::Genesis;
+use sui_config::{genesis::Genesis, DenyList};
 use sui_storage::IndexStore;
 use sui_types::{
     base_types::*,
     batch::{TxSequenceNumber, UpdateItem},
     committee::Committee,
-    crypto::AuthoritySignature,
+    crypto::{sha3_hash, AuthoritySignature, GenericSignature},
+    display::Display,
     error::{SuiError, SuiResult},
     fp_bail, fp_ensure,
     gas::SuiGasStatus,
     messages::*,
+    name_service::NameServiceRegistry,
     object::{Data, Object, ObjectFormatOptions, ObjectRead},
+    order_book::OrderBookEventEnvelope,
     storage::{BackingPackageStore, DeleteKind, Storage},
+    zk_login::ZkLoginEnv,
     MOVE_STDLIB_ADDRESS, SUI_FRAMEWORK_ADDRESS, SUI_SYSTEM_STATE_OBJECT_ID,
 };
-use tracing::{debug, error, instrument};
+use tracing::{debug, error, info, instrument, warn, Instrument};
 use typed_store::Map;
 
 #[cfg(test)]
@@ -76,24 +86,41 @@ pub mod move_integration_tests;
 #[path = "unit_tests/gas_tests.rs"]
 mod gas_tests;
 
+#[cfg(test)]
+#[path = "unit_tests/gas_snapshot_tests.rs"]
+mod gas_snapshot_tests;
+
 mod temporary_store;
 pub use temporary_store::AuthorityTemporaryStore;
 
 mod authority_store;
+mod epoch_tables;
+mod store_migrations;
 pub use authority_store::{
-    AuthorityStore, AuthorityStoreWrapper, GatewayStore, SuiDataStore, UpdateType,
+    AuthorityStore, AuthorityStoreWrapper, GatewayStore, SuiDataStore, UpdateType, STORE_TABLES,
 };
+pub use store_migrations::{SchemaVersion, STORE_SCHEMA_VERSION};
 use sui_types::messages_checkpoint::{
-    CheckpointRequest, CheckpointRequestType, CheckpointResponse,
+    CheckpointDigest, CheckpointRequest, CheckpointRequestType, CheckpointResponse,
+    CheckpointSequenceNumber,
 };
 use sui_types::object::Owner;
-use sui_types::sui_system_state::SuiSystemState;
+use sui_types::protocol_config::{ProtocolConfig, ProtocolVersion};
+use sui_types::sui_system_state::{Delegation, SuiSystemState};
 
 use self::authority_store::{
     generate_genesis_system_object, store_package_and_init_modules_for_genesis,
 };
 
 pub mod authority_notifier;
+pub mod object_cache;
+pub mod overload_monitor;
+pub mod package_cache;
+pub mod store_dump;
+pub mod vm_pool;
+
+use self::overload_monitor::{ExecutionLimiter, OverloadMonitorConfig};
+use self::vm_pool::{MoveVmPool, DEFAULT_MAX_IDLE_MOVE_VMS};
 
 pub const MAX_ITEMS_LIMIT: u64 = 100_000;
 const BROADCAST_CAPACITY: usize = 10_000;
@@ -110,11 +137,29 @@ pub struct AuthorityMetrics {
     num_input_objs: Histogram,
     num_shared_objects: Histogram,
     batch_size: Histogram,
+    pub(crate) verified_certificates_cache_hits: IntCounter,
+    pub(crate) certificate_verification_batch_size: Histogram,
+    pub(crate) certificate_verification_batch_failures: IntCounter,
 
     pub gossip_queued_count: IntCounter,
     pub gossip_sync_count: IntCounter,
     pub gossip_task_success_count: IntCounter,
     pub gossip_task_error_count: IntCounter,
+    pub fork_detected_count: IntCounter,
+
+    object_cache_hits: IntCounter,
+    object_cache_misses: IntCounter,
+    lock_cache_hits: IntCounter,
+    lock_cache_misses: IntCounter,
+    package_cache_hits: IntCounter,
+    package_cache_misses: IntCounter,
+
+    execution_concurrency: IntGauge,
+    execution_queue_depth: IntGauge,
+    overload_rejections: IntCounter,
+
+    pub(crate) server_requests_inflight: IntGaugeVec,
+    pub(crate) server_requests_refused: IntCounterVec,
 }
 
 // Override default Prom buckets for positive numbers in 0-50k range
@@ -176,6 +221,22 @@ impl AuthorityMetrics {
                 POSITIVE_INT_BUCKETS.to_vec()
             )
             .unwrap(),
+            verified_certificates_cache_hits: register_int_counter!(
+                "verified_certificates_cache_hits",
+                "Number of certificates found verified already"
+            )
+            .unwrap(),
+            certificate_verification_batch_size: register_histogram!(
+                "certificate_verification_batch_size",
+                "Distribution of the number of certificates verified together",
+                POSITIVE_INT_BUCKETS.to_vec()
+            )
+            .unwrap(),
+            certificate_verification_batch_failures: register_int_counter!(
+                "certificate_verification_batch_failures",
+                "Number of batches of certificates verified one by one after failing together"
+            )
+            .unwrap(),
             gossip_queued_count: register_int_counter!(
                 "gossip_queued_count",
                 "Number of digests queued from gossip peers",
@@ -196,6 +257,68 @@ impl AuthorityMetrics {
                 "Number of gossip tasks that completed with errors"
             )
             .unwrap(),
+            fork_detected_count: register_int_counter!(
+                "fork_detected_count",
+                "Number of transactions whose local effects differ from the effects of the validators"
+            )
+            .unwrap(),
+            object_cache_hits: register_int_counter!(
+                "object_cache_hits",
+                "Number of object reads served by the object cache"
+            )
+            .unwrap(),
+            object_cache_misses: register_int_counter!(
+                "object_cache_misses",
+                "Number of object reads which missed the object cache"
+            )
+            .unwrap(),
+            lock_cache_hits: register_int_counter!(
+                "lock_cache_hits",
+                "Number of transaction lock reads served by the object cache"
+            )
+            .unwrap(),
+            lock_cache_misses: register_int_counter!(
+                "lock_cache_misses",
+                "Number of transaction lock reads which missed the object cache"
+            )
+            .unwrap(),
+            package_cache_hits: register_int_counter!(
+                "package_cache_hits",
+                "Number of package reads served by the package cache"
+            )
+            .unwrap(),
+            package_cache_misses: register_int_counter!(
+                "package_cache_misses",
+                "Number of package reads which missed the package cache"
+            )
+            .unwrap(),
+            execution_concurrency: register_int_gauge!(
+                "execution_concurrency",
+                "Number of certificates the authority currently lets execute concurrently"
+            )
+            .unwrap(),
+            execution_queue_depth: register_int_gauge!(
+                "execution_queue_depth",
+                "Number of certificates waiting to execute"
+            )
+            .unwrap(),
+            overload_rejections: register_int_counter!(
+                "overload_rejections",
+                "Number of transactions rejected because the authority was overloaded"
+            )
+            .unwrap(),
+            server_requests_inflight: register_int_gauge_vec!(
+                "server_requests_inflight",
+                "Number of requests the authority server is serving, by lane",
+                &["lane"]
+            )
+            .unwrap(),
+            server_requests_refused: register_int_counter_vec!(
+                "server_requests_refused",
+                "Number of requests refused because their lane was full, by lane",
+                &["lane"]
+            )
+            .unwrap(),
         }
     }
 }
@@ -228,21 +351,30 @@ pub struct AuthorityState {
 
     /// Committee of this Sui instance.
     pub committee: ArcSwap<Committee>,
+    /// The protocol version of the current epoch, as recorded in the system state. It selects
+    /// the gas schedule transactions are metered with.
+    protocol_version: AtomicU64,
+    /// Verifies the certificates handled by the authority, in batches, and remembers them.
+    pub(crate) signature_verifier: SignatureVerifier,
     /// A global lock to halt all transaction/cert processing.
     #[allow(dead_code)]
     pub(crate) halted: AtomicBool,
+    /// What the authority refuses to sign or execute. The node reloads it when its config
+    /// changes.
+    pub deny_list: Arc<ArcSwap<DenyList>>,
+    /// Set once the authority shuts down. Certificates execute under a read guard, so that
+    /// shutting down waits for the executions in flight.
+    shutting_down: tokio::sync::RwLock<bool>,
 
     /// Move native functions that are available to invoke
     pub(crate) _native_functions: NativeFunctionTable,
-    pub(crate) move_vm: Arc<MoveVM>,
+    pub(crate) move_vms: MoveVmPool,
 
     /// The database
     pub(crate) database: Arc<AuthorityStore>, // TODO: remove pub
 
     indexes: Option<Arc<IndexStore>>,
 
-    module_cache: SyncModuleCache<AuthorityStoreWrapper>, // TODO: use strategies (e.g. LRU?) to constraint memory usage
-
     event_handler: Option<Arc<EventHandler>>,
 
     /// The checkpoint store
@@ -260,6 +392,9 @@ pub struct AuthorityState {
     /// Ensures there can only be a single consensus client is updating the state.
     pub consensus_guardrail: AtomicUsize,
 
+    /// Limits the certificates executing concurrently, see [`Self::run_overload_monitor`].
+    execution_limiter: ExecutionLimiter,
+
     pub metrics: &'static AuthorityMetrics,
 }
 
@@ -293,20 +428,33 @@ impl AuthorityState {
             SuiError::InvalidSystemTransaction
         );
 
+        self.check_deny_list(&transaction_digest, &transaction.data)?;
+
         if self.halted.load(Ordering::SeqCst) {
             // TODO: Do we want to include the new validator set?
             return Err(SuiError::ValidatorHaltedAtEpochEnd);
         }
 
+        // Signing more transactions would only add to the certificates waiting to execute.
+        if self.execution_limiter.is_overloaded() {
+            self.metrics.overload_rejections.inc();
+            return Err(SuiError::ValidatorOverloaded {
+                queue_depth: self.execution_limiter.queue_depth(),
+            });
+        }
+
         let (_gas_status, all_objects) = transaction_input_checker::check_transaction_input(
             &self.database,
             &transaction,
+            self.protocol_config(),
             &self.metrics.shared_obj_tx,
         )
         .await?;
 
         let owned_objects = transaction_input_checker::filter_owned_objects(&all_objects);
 
+        self.check_zk_login_proof(&transaction).await?;
+
         let signed_transaction = SignedTransaction::new(
             self.committee.load().epoch,
             transaction,
@@ -318,13 +466,70 @@ impl AuthorityState {
         // The call to self.set_transaction_lock checks the lock is not conflicting,
         // and returns ConflictingTransaction error in case there is a lock on a different
         // existing transaction.
-        self.set_transaction_lock(&owned_objects, signed_transaction)
-            .await?;
+        if let Err(error) = self
+            .set_transaction_lock(&owned_objects, signed_transaction)
+            .await
+        {
+            if let SuiError::ConflictingTransaction {
+                pending_transaction,
+            } = &error
+            {
+                anomaly::report(Anomaly::Equivocation {
+                    transaction: transaction_digest,
+                    conflicting_transaction: *pending_transaction,
+                });
+            }
+            return Err(error);
+        }
 
         // Return the signed Transaction or maybe a cert.
         self.make_transaction_info(&transaction_digest).await
     }
 
+    /// Refuse a transaction the deny list has, by its digest or by one of its inputs. System
+    /// transactions are never denied, so that epochs still change during an incident.
+    fn check_deny_list(&self, digest: &TransactionDigest, data: &TransactionData) -> SuiResult {
+        let deny_list = self.deny_list.load();
+        if deny_list.is_empty() || data.kind.is_system_tx() {
+            return Ok(());
+        }
+        let denied = |reason: String| SuiError::TransactionDenied {
+            digest: *digest,
+            reason,
+        };
+        fp_ensure!(
+            !deny_list.transactions.contains(digest),
+            denied("the transaction is on the deny list".to_string())
+        );
+        for input in data.input_objects()? {
+            let id = input.object_id();
+            let denied_ids = match input {
+                InputObjectKind::MovePackage(_) => &deny_list.packages,
+                _ => &deny_list.objects,
+            };
+            fp_ensure!(
+                !denied_ids.contains(&id),
+                denied(format!("input {id} is on the deny list"))
+            );
+        }
+        Ok(())
+    }
+
+    /// Check the proof of a zkLogin sender against the JWKs and the verifying key of the
+    /// current epoch, which the signature checks done by everyone else don't cover. A
+    /// certificate of the transaction then carries the word of a quorum that the proof is valid.
+    async fn check_zk_login_proof(&self, transaction: &Transaction) -> SuiResult<()> {
+        if let GenericSignature::ZkLogin(authenticator) = &transaction.tx_signature {
+            let system_state = self.get_sui_system_state_object().await?;
+            let env = ZkLoginEnv::from_system_state(&system_state)?;
+            authenticator.verify_proof(&env).map_err(|e| {
+                self.metrics.signature_errors.inc();
+                e
+            })?;
+        }
+        Ok(())
+    }
+
     /// Initiate a new transaction.
     pub async fn handle_transaction(
         &self,
@@ -354,6 +559,60 @@ impl AuthorityState {
         }
     }
 
+    /// Execute `transaction_data` against the current state without signing or committing
+    /// anything, returning the effects it would have, and where its gas went if `profile_gas`.
+    /// Shared objects are read at their latest version, which consensus may have moved past by
+    /// the time the transaction is sent.
+    pub async fn dry_run_transaction(
+        &self,
+        transaction_data: TransactionData,
+        profile_gas: bool,
+    ) -> SuiResult<DryRunTransactionResponse> {
+        let transaction_digest = TransactionDigest::new(sha3_hash(&transaction_data));
+        let (mut gas_status, objects_by_kind) =
+            transaction_input_checker::check_transaction_data_input(
+                &self.database,
+                &transaction_data,
+                self.protocol_config(),
+                &self.metrics.shared_obj_tx,
+            )
+            .await?;
+        let shared_object_refs: Vec<_> = objects_by_kind
+            .iter()
+            .filter(|(kind, _)| kind.is_shared_object())
+            .map(|(_, obj)| obj.compute_object_reference())
+            .sorted()
+            .collect();
+        let transaction_dependencies = objects_by_kind
+            .iter()
+            .map(|(_, obj)| obj.previous_transaction)
+            .collect();
+        let mut temporary_store = AuthorityTemporaryStore::new(
+            self.database.clone(),
+            objects_by_kind,
+            transaction_digest,
+        );
+        if profile_gas {
+            gas_status.enable_profiling();
+        }
+        // The temporary store is dropped with the writes of the transaction.
+        let effects = execution_engine::execute_transaction_to_effects(
+            shared_object_refs,
+            &mut temporary_store,
+            transaction_data,
+            transaction_digest,
+            transaction_dependencies,
+            &self.move_vms.checkout(&self.database)?,
+            &self._native_functions,
+            &mut gas_status,
+            self.committee.load().epoch,
+        )?;
+        Ok(DryRunTransactionResponse {
+            effects,
+            gas_profile: gas_status.take_profile(),
+        })
+    }
+
     /// Confirm a transfer.
     pub async fn handle_confirmation_transaction(
         &self,
@@ -380,33 +639,76 @@ impl AuthorityState {
             return Err(SuiError::ValidatorHaltedAtEpochEnd);
         }
 
+        // The certificates of an epoch which ended are never executed, on every validator alike:
+        // the locks of their transactions were released when the epoch changed.
+        let certificate_epoch = confirmation_transaction.certificate.auth_sign_info.epoch;
+        if certificate_epoch < self.committee.load().epoch
+            && !confirmation_transaction
+                .certificate
+                .data
+                .kind
+                .is_system_tx()
+        {
+            return Err(SuiError::EpochEnded {
+                epoch: certificate_epoch,
+            });
+        }
+
         // Check the certificate and retrieve the transfer data.
-        tracing::trace_span!("cert_check_signature")
-            .in_scope(|| {
-                confirmation_transaction
-                    .certificate
-                    .verify(&self.committee.load())
-            })
+        self.verify_certificate(&confirmation_transaction.certificate)
+            .instrument(tracing::trace_span!("cert_check_signature"))
+            .await?;
+
+        self.execution_limiter
+            .execute(self.process_certificate(confirmation_transaction))
+            .await
+    }
+
+    /// Adjust the number of certificates executing concurrently to their latency and to the
+    /// certificates waiting, and mark the authority overloaded while too many wait. Runs until
+    /// the task is aborted.
+    pub async fn run_overload_monitor(&self) {
+        let mut interval = tokio::time::interval(self.execution_limiter.adjustment_interval());
+        loop {
+            interval.tick().await;
+            self.execution_limiter.adjust();
+            self.metrics
+                .execution_concurrency
+                .set(self.execution_limiter.concurrency() as i64);
+            self.metrics
+                .execution_queue_depth
+                .set(self.execution_limiter.queue_depth() as i64);
+        }
+    }
+
+    /// Check the signatures of `certificate` against the current committee, along with the
+    /// other certificates being checked, unless it was verified recently.
+    pub async fn verify_certificate(&self, certificate: &CertifiedTransaction) -> SuiResult {
+        self.signature_verifier
+            .verify(certificate, self.committee.load_full())
+            .await
             .map_err(|e| {
                 self.metrics.signature_errors.inc();
                 e
-            })?;
-
-        self.process_certificate(confirmation_transaction).await
+            })
     }
 
     #[instrument(level = "trace", skip_all)]
+    /// Check the shared objects of the transaction hold the locks of their version, and return
+    /// the references of `deleted_shared_objects` at the versions consensus assigned them, which
+    /// are at least the versions of their deletion.
     async fn check_shared_locks(
         &self,
         transaction_digest: &TransactionDigest,
         // inputs: &[(InputObjectKind, Object)],
         shared_object_refs: &[ObjectRef],
-    ) -> Result<(), SuiError> {
+        deleted_shared_objects: &[ObjectRef],
+    ) -> Result<Vec<ObjectRef>, SuiError> {
         debug!("Validating shared object sequence numbers from consensus...");
 
         // Internal consistency check
         debug_assert!(
-            !shared_object_refs.is_empty(),
+            !shared_object_refs.is_empty() || !deleted_shared_objects.is_empty(),
             "we just checked that there are share objects yet none found?"
         );
 
@@ -419,14 +721,19 @@ impl AuthorityState {
         // Check whether the shared objects have already been assigned a sequence number by
         // the consensus. Bail if the transaction contains even one shared object that either:
         // (i) was not assigned a sequence number, or
-        // (ii) has a different sequence number than the current one.
-
+        // (ii) has a different sequence number than the current one, or for a deleted shared
+        //      object, a lower one than the version of its deletion, since the transactions
+        //      sequenced after the one deleting it are assigned that version or a later one.
         let lock_errors: Vec<_> = shared_object_refs
             .iter()
-            .filter_map(|(object_id, version, _)| {
+            .chain(deleted_shared_objects)
+            .filter_map(|(object_id, version, digest)| {
+                let deleted = *digest == ObjectDigest::OBJECT_DIGEST_DELETED;
                 if !shared_locks.contains_key(object_id) {
                     Some(SuiError::SharedObjectLockNotSetObject)
-                } else if shared_locks[object_id] != *version {
+                } else if (deleted && shared_locks[object_id] < *version)
+                    || (!deleted && shared_locks[object_id] != *version)
+                {
                     Some(SuiError::UnexpectedSequenceNumber {
                         object_id: *object_id,
                         // This sequence number is the one attributed by consensus.
@@ -449,7 +756,10 @@ impl AuthorityState {
             }
         );
 
-        Ok(())
+        Ok(deleted_shared_objects
+            .iter()
+            .map(|(object_id, _, digest)| (*object_id, shared_locks[object_id], *digest))
+            .collect())
     }
 
     #[instrument(level = "debug", name = "process_cert_inner", skip_all)]
@@ -457,33 +767,51 @@ impl AuthorityState {
         &self,
         confirmation_transaction: ConfirmationTransaction,
     ) -> Result<TransactionInfoResponse, SuiError> {
+        let shutting_down = self.shutting_down.read().await;
+        fp_ensure!(!*shutting_down, SuiError::AuthorityShuttingDown);
+
         let certificate = confirmation_transaction.certificate;
         let transaction_digest = *certificate.digest();
 
-        let (gas_status, objects_by_kind) = transaction_input_checker::check_transaction_input(
-            &self.database,
-            &certificate,
-            &self.metrics.shared_obj_tx,
-        )
-        .await?;
+        self.check_deny_list(&transaction_digest, &certificate.data)?;
+
+        let (mut gas_status, objects_by_kind, mut deleted_shared_objects) =
+            transaction_input_checker::check_certificate_input(
+                &self.database,
+                &certificate,
+                self.protocol_config(),
+                &self.metrics.shared_obj_tx,
+            )
+            .await?;
 
         // At this point we need to check if any shared objects need locks,
         // and whether they have them.
-        let shared_object_refs: Vec<_> = objects_by_kind
+        let mut shared_object_refs: Vec<_> = objects_by_kind
             .iter()
-            .filter(|(kind, _)| matches!(kind, InputObjectKind::SharedMoveObject(_)))
+            .filter(|(kind, _)| kind.is_shared_object())
             .map(|(_, obj)| obj.compute_object_reference())
             .sorted()
             .collect();
-        if !shared_object_refs.is_empty() && !certificate.data.kind.is_system_tx() {
+        if certificate.contains_shared_object() && !certificate.data.kind.is_system_tx() {
             // If the transaction contains shared objects, we need to ensure they have been scheduled
             // for processing by the consensus protocol.
             // There is no need to go through consensus for system transactions that can
             // only be executed at a time when consensus is turned off.
             // TODO: Add some assert here to make sure consensus is indeed off with is_system_tx.
-            self.check_shared_locks(&transaction_digest, &shared_object_refs)
+            deleted_shared_objects = self
+                .check_shared_locks(
+                    &transaction_digest,
+                    &shared_object_refs,
+                    &deleted_shared_objects,
+                )
                 .await?;
         }
+        // The transaction executes as a no-op failing with `SharedObjectDeleted` if one of its
+        // shared objects was deleted, which the effects record with the digest of the deletion.
+        if !deleted_shared_objects.is_empty() {
+            shared_object_refs.extend(deleted_shared_objects);
+            shared_object_refs.sort();
+        }
 
         self.metrics
             .num_input_objs
@@ -514,9 +842,9 @@ impl AuthorityState {
             certificate.data.clone(),
             transaction_digest,
             transaction_dependencies,
-            &self.move_vm,
+            &self.move_vms.checkout(&self.database)?,
             &self._native_functions,
-            gas_status,
+            &mut gas_status,
             self.committee.load().epoch,
         )?;
 
@@ -533,6 +861,12 @@ impl AuthorityState {
         self.update_state(temporary_store, &certificate, &signed_effects)
             .await?;
 
+        // The VMs keep the modules they loaded, so the ones which loaded the framework before an
+        // epoch change upgraded it are not used again.
+        if certificate.data.kind.upgrades_framework() && signed_effects.effects.status.is_ok() {
+            self.move_vms.invalidate();
+        }
+
         // Each certificate only reaches here once
         if let Some(event_handler) = &self.event_handler {
             event_handler.process_events(&signed_effects.effects).await;
@@ -644,7 +978,7 @@ impl AuthorityState {
                                 .await?
                         };
                         let layout = match request_layout {
-                            Some(format) => object.get_layout(format, &self.module_cache)?,
+                            Some(format) => object.get_layout(format, &*self.database)?,
                             None => None,
                         };
 
@@ -769,6 +1103,9 @@ impl AuthorityState {
             CheckpointRequestType::SetFragment(fragment) => {
                 checkpoint_store.handle_receive_fragment(fragment, &self.committee.load())
             }
+            CheckpointRequestType::EpochHandoff(epoch) => {
+                checkpoint_store.handle_epoch_handoff(*epoch)
+            }
         }
     }
 
@@ -791,10 +1128,13 @@ impl AuthorityState {
         );
 
         // Only initialize an empty database.
-        if store
+        let database_is_empty = store
             .database_is_empty()
-            .expect("Database read should not fail.")
-        {
+            .expect("Database read should not fail.");
+        let clean_shutdown = store
+            .take_clean_shutdown_marker()
+            .expect("Database read should not fail.");
+        if database_is_empty {
             let mut genesis_ctx = genesis.genesis_ctx().to_owned();
             for genesis_modules in genesis.modules() {
                 store_package_and_init_modules_for_genesis(
@@ -834,14 +1174,18 @@ impl AuthorityState {
             name,
             secret,
             committee: ArcSwap::from(Arc::new(current_epoch_info.committee)),
+            protocol_version: AtomicU64::new(ProtocolVersion::MIN.as_u64()),
+            signature_verifier: SignatureVerifier::new(
+                DEFAULT_VERIFIED_CERTIFICATES_CACHE_SIZE,
+                &METRICS,
+            ),
             halted: AtomicBool::new(current_epoch_info.validator_halted),
+            deny_list: Arc::new(ArcSwap::from_pointee(DenyList::default())),
+            shutting_down: tokio::sync::RwLock::new(false),
+            move_vms: MoveVmPool::new(native_functions.clone(), DEFAULT_MAX_IDLE_MOVE_VMS),
             _native_functions: native_functions,
-            move_vm,
             database: store.clone(),
             indexes,
-            // `module_cache` uses a separate in-mem cache from `event_handler`
-            // this is because they largely deal with different types of MoveStructs
-            module_cache: SyncModuleCache::new(AuthorityStoreWrapper(store.clone())),
             event_handler,
             checkpoints,
             batch_channels: tx,
@@ -850,13 +1194,25 @@ impl AuthorityState {
                     .expect("Notifier cannot start."),
             ),
             consensus_guardrail: AtomicUsize::new(0),
+            execution_limiter: ExecutionLimiter::new(OverloadMonitorConfig::default()),
             metrics: &METRICS,
         };
 
+        state
+            .reload_protocol_config()
+            .await
+            .expect("This build must support the protocol version of the current epoch");
+
         state
             .init_batches_from_database()
             .expect("Init batches failed!");
 
+        // The store may hold certificates sequenced by consensus which did not finish executing
+        // before a crash.
+        if !database_is_empty && !clean_shutdown {
+            state.recover_certificates().await;
+        }
+
         // If a checkpoint store is present, ensure it is up-to-date with the latest
         // batches.
         if let Some(checkpoint) = &state.checkpoints {
@@ -892,6 +1248,51 @@ impl AuthorityState {
         state
     }
 
+    /// Execute the certificates of the store which have no effects. A certificate may only
+    /// execute once the certificates before it on its shared objects did, so the certificates
+    /// are executed in rounds until a round executes none of them.
+    async fn recover_certificates(&self) {
+        let mut pending = self
+            .database
+            .certificates_without_effects()
+            .expect("Database read should not fail.");
+        if pending.is_empty() {
+            return;
+        }
+        info!(
+            "Recovering {} certificates without effects after an unclean shutdown",
+            pending.len()
+        );
+        loop {
+            let count = pending.len();
+            for certificate in std::mem::take(&mut pending) {
+                let confirmation = ConfirmationTransaction::new(certificate.clone());
+                if let Err(error) = self.process_certificate(confirmation).await {
+                    debug!(digest =? certificate.digest(), "Cannot recover certificate yet: {error}");
+                    pending.push(certificate);
+                }
+            }
+            if pending.is_empty() || pending.len() == count {
+                break;
+            }
+        }
+        for certificate in &pending {
+            warn!(
+                digest =? certificate.digest(),
+                "Certificate without effects could not be recovered"
+            );
+        }
+    }
+
+    /// Stop executing certificates, wait for the executions in flight to finish, then flush
+    /// the store and mark the shutdown as clean, so that the next start does not need to
+    /// recover the store. Certificates received afterwards are rejected.
+    pub async fn shutdown(&self) -> SuiResult {
+        let mut shutting_down = self.shutting_down.write().await;
+        *shutting_down = true;
+        self.database.record_clean_shutdown()
+    }
+
     pub fn checkpoints(&self) -> Option<Arc<Mutex<CheckpointStore>>> {
         self.checkpoints.clone()
     }
@@ -931,6 +1332,25 @@ impl AuthorityState {
         self.database.clone()
     }
 
+    /// The protocol config of the current epoch.
+    pub fn protocol_config(&self) -> &'static ProtocolConfig {
+        ProtocolConfig::get_for_version(ProtocolVersion::new(
+            self.protocol_version.load(Ordering::Relaxed),
+        ))
+        .expect("Only supported protocol versions are stored")
+    }
+
+    /// Read the protocol version of the current epoch from the system state, failing if this
+    /// build does not support it.
+    pub(crate) async fn reload_protocol_config(&self) -> SuiResult {
+        let version =
+            ProtocolVersion::new(self.get_sui_system_state_object().await?.protocol_version);
+        ProtocolConfig::get_for_version(version)?;
+        self.protocol_version
+            .store(version.as_u64(), Ordering::Relaxed);
+        Ok(())
+    }
+
     pub fn clone_committee(&self) -> Committee {
         self.committee.load().clone().deref().clone()
     }
@@ -961,6 +1381,11 @@ impl AuthorityState {
         Ok(result)
     }
 
+    /// Returns the display used to render objects of type `type_`, if its publisher has set one.
+    pub fn get_display(&self, type_: &StructTag) -> SuiResult<Option<Display>> {
+        self.database.get_display(type_)
+    }
+
     pub async fn get_object_read(&self, object_id: &ObjectID) -> Result<ObjectRead, SuiError> {
         match self.database.get_latest_parent_entry(*object_id)? {
             None => Ok(ObjectRead::NotExists(*object_id)),
@@ -975,7 +1400,7 @@ impl AuthorityState {
                         }
                         Some(object) => {
                             let layout = object
-                                .get_layout(ObjectFormatOptions::default(), &self.module_cache)?;
+                                .get_layout(ObjectFormatOptions::default(), &*self.database)?;
                             Ok(ObjectRead::Exists(obj_ref, object, layout))
                         }
                     }
@@ -986,10 +1411,175 @@ impl AuthorityState {
         }
     }
 
+    /// Read the object as it was at the end of `checkpoint`: its latest version written by
+    /// genesis or by a transaction of a checkpoint up to `checkpoint`. Clients reading every
+    /// object at the same checkpoint get a consistent view of the state, unaffected by the
+    /// transactions executed between their reads.
+    pub async fn get_object_read_at_checkpoint(
+        &self,
+        object_id: &ObjectID,
+        checkpoint: CheckpointSequenceNumber,
+    ) -> SuiResult<ObjectRead> {
+        let checkpoint_store = self.checkpoints_for_read_at(checkpoint)?;
+        let checkpoint_store = checkpoint_store.lock();
+        self.object_read_at_checkpoint(&checkpoint_store, object_id, checkpoint)
+    }
+
+    /// The objects owned by `owner` at the end of `checkpoint`, see
+    /// `get_object_read_at_checkpoint`. The candidates are the objects `owner` owns now and,
+    /// on nodes indexing transactions, the objects sent to `owner` since genesis.
+    pub async fn get_owner_objects_at_checkpoint(
+        &self,
+        owner: SuiAddress,
+        checkpoint: CheckpointSequenceNumber,
+    ) -> SuiResult<Vec<ObjectInfo>> {
+        let checkpoint_store = self.checkpoints_for_read_at(checkpoint)?;
+        let checkpoint_store = checkpoint_store.lock();
+        let mut candidates: BTreeSet<_> = self
+            .database
+            .get_owner_objects(Owner::AddressOwner(owner))?
+            .into_iter()
+            .map(|info| info.object_id)
+            .collect();
+        if let Some(indexes) = &self.indexes {
+            for (_, digest) in indexes.get_transactions_to_addr(owner)? {
+                let effects = self.database.get_effects(&digest)?;
+                candidates.extend(
+                    effects
+                        .mutated_and_created()
+                        .chain(effects.unwrapped.iter())
+                        .filter(|(_, object_owner)| object_owner == &Owner::AddressOwner(owner))
+                        .map(|((object_id, _, _), _)| *object_id),
+                );
+            }
+        }
+        let mut objects = Vec::new();
+        for object_id in candidates {
+            if let ObjectRead::Exists(object_ref, object, _) =
+                self.object_read_at_checkpoint(&checkpoint_store, &object_id, checkpoint)?
+            {
+                if object.owner == Owner::AddressOwner(owner) {
+                    objects.push(ObjectInfo::new(&object_ref, &object));
+                }
+            }
+        }
+        Ok(objects)
+    }
+
+    /// The checkpoints of the node, for reads at the end of `checkpoint`, which must be final.
+    fn checkpoints_for_read_at(
+        &self,
+        checkpoint: CheckpointSequenceNumber,
+    ) -> SuiResult<Arc<Mutex<CheckpointStore>>> {
+        let checkpoint_store =
+            self.checkpoints
+                .clone()
+                .ok_or(SuiError::UnsupportedFeatureError {
+                    error: "Checkpoint not supported".to_owned(),
+                })?;
+        let next_checkpoint = checkpoint_store.lock().next_checkpoint();
+        fp_ensure!(
+            checkpoint < next_checkpoint,
+            SuiError::CheckpointingError {
+                error: format!(
+                    "Checkpoint {checkpoint} is not final, the next checkpoint is {next_checkpoint}"
+                ),
+            }
+        );
+        Ok(checkpoint_store)
+    }
+
+    fn object_read_at_checkpoint(
+        &self,
+        checkpoint_store: &CheckpointStore,
+        object_id: &ObjectID,
+        checkpoint: CheckpointSequenceNumber,
+    ) -> SuiResult<ObjectRead> {
+        // Versions of an object are written in causal order, as are checkpoints, so the version
+        // at the end of the checkpoint is the latest one written by a transaction it includes.
+        let entries: Vec<_> = self
+            .database
+            .get_parent_iterator(*object_id, None)?
+            .collect();
+        for (object_ref, digest) in entries.into_iter().rev() {
+            if digest != TransactionDigest::genesis() {
+                let effects = self.database.get_effects(&digest)?;
+                let included = checkpoint_store
+                    .transactions_to_checkpoint
+                    .get(&ExecutionDigests::new(digest, effects.digest()))?;
+                if !matches!(included, Some((sequence_number, _)) if sequence_number <= checkpoint)
+                {
+                    continue;
+                }
+            }
+            if !object_ref.2.is_alive() {
+                return Ok(ObjectRead::Deleted(object_ref));
+            }
+            let object = self
+                .database
+                .get_object_by_key(object_id, object_ref.1)?
+                .ok_or(SuiError::ObjectNotFound {
+                    object_id: *object_id,
+                })?;
+            let layout = object.get_layout(ObjectFormatOptions::default(), &*self.database)?;
+            return Ok(ObjectRead::Exists(object_ref, object, layout));
+        }
+        Ok(ObjectRead::NotExists(*object_id))
+    }
+
     pub fn get_owner_objects(&self, owner: Owner) -> SuiResult<Vec<ObjectInfo>> {
         self.database.get_owner_objects(owner)
     }
 
+    /// Return a page of the objects owned by the object `parent`, and the cursor of the next page
+    /// if there is one.
+    pub fn get_child_objects(
+        &self,
+        parent: ObjectID,
+        cursor: Option<ObjectID>,
+        limit: usize,
+    ) -> SuiResult<(Vec<ObjectInfo>, Option<ObjectID>)> {
+        self.get_owner_objects_page(Owner::ObjectOwner(parent.into()), cursor, limit)
+    }
+
+    /// Return a page of the objects owned by `owner`, from the object `cursor` on, and the cursor
+    /// of the next page if there is one.
+    pub fn get_owner_objects_page(
+        &self,
+        owner: Owner,
+        cursor: Option<ObjectID>,
+        limit: usize,
+    ) -> SuiResult<(Vec<ObjectInfo>, Option<ObjectID>)> {
+        self.database.get_owner_objects_page(owner, cursor, limit)
+    }
+
+    /// The total value of the coins owned by `owner`, and the number of those coins, by coin
+    /// type.
+    pub async fn get_balances(
+        &self,
+        owner: SuiAddress,
+    ) -> SuiResult<BTreeMap<TypeTag, (u128, usize)>> {
+        let object_ids: Vec<_> = self
+            .get_owner_objects(Owner::AddressOwner(owner))?
+            .into_iter()
+            .map(|info| info.object_id)
+            .collect();
+        let mut balances = BTreeMap::new();
+        for object in self
+            .database
+            .get_objects(&object_ids)?
+            .into_iter()
+            .flatten()
+        {
+            if let Some((coin_type, value)) = temporary_store::coin_type_and_value(&object) {
+                let (total, count) = balances.entry(coin_type.clone()).or_insert((0, 0));
+                *total += value as u128;
+                *count += 1;
+            }
+        }
+        Ok(balances)
+    }
+
     pub fn get_total_transaction_number(&self) -> Result<u64, anyhow::Error> {
         QueryHelpers::get_total_transaction_number(&self.database)
     }
@@ -1012,11 +1602,32 @@ impl AuthorityState {
     pub async fn get_transaction(
         &self,
         digest: TransactionDigest,
+        options: SuiTransactionResponseOptions,
     ) -> Result<TransactionEffectsResponse, anyhow::Error> {
-        QueryHelpers::get_transaction(&self.database, digest)
+        let response = QueryHelpers::get_transaction(&self.database, digest, &options);
+        if let Err(error) = &response {
+            if matches!(
+                error.downcast_ref(),
+                Some(SuiError::TransactionNotFound { .. })
+            ) {
+                // A transaction of a checkpoint this node pruned, which it does not store, had
+                // its data pruned with the checkpoint, or is older than the snapshot the node
+                // started from.
+                if let Some(indexes) = &self.indexes {
+                    if let Some((_, sequence_number)) =
+                        indexes.get_checkpoint_of_transaction(&digest)?
+                    {
+                        if sequence_number < indexes.lowest_checkpoint() {
+                            return Err(data_pruned(indexes).into());
+                        }
+                    }
+                }
+            }
+        }
+        response
     }
 
-    fn get_indexes(&self) -> SuiResult<Arc<IndexStore>> {
+    pub(crate) fn get_indexes(&self) -> SuiResult<Arc<IndexStore>> {
         match &self.indexes {
             Some(i) => Ok(i.clone()),
             None => Err(SuiError::UnsupportedFeatureError {
@@ -1057,6 +1668,185 @@ impl AuthorityState {
         Ok(self.get_indexes()?.get_transactions_to_addr(address)?)
     }
 
+    pub async fn get_order_book_events(
+        &self,
+        pool: ObjectID,
+    ) -> Result<Vec<OrderBookEventEnvelope>, anyhow::Error> {
+        Ok(self.get_indexes()?.get_order_book_events(pool)?)
+    }
+
+    /// The address `name` points at in the name service registry, in the current epoch.
+    pub async fn resolve_name_service_address(
+        &self,
+        name: &str,
+    ) -> Result<Option<SuiAddress>, anyhow::Error> {
+        Ok(self
+            .get_name_service_registry()?
+            .and_then(|registry| registry.resolve(name, self.committee.load().epoch())))
+    }
+
+    /// The names pointing at `address` in the name service registry, in the current epoch, its
+    /// reverse record first.
+    pub async fn resolve_name_service_names(
+        &self,
+        address: SuiAddress,
+    ) -> Result<Vec<String>, anyhow::Error> {
+        Ok(self
+            .get_name_service_registry()?
+            .map(|registry| registry.names_of(&address, self.committee.load().epoch()))
+            .unwrap_or_default())
+    }
+
+    /// The delegations owned by `address`, with the rewards each would be paid if it were
+    /// withdrawn in the current epoch.
+    pub async fn get_stakes(&self, address: SuiAddress) -> Result<Vec<SuiStake>, anyhow::Error> {
+        let delegation_type = Delegation::type_().to_string();
+        let system_state = self.get_sui_system_state_object().await?;
+        let mut stakes = Vec::new();
+        for info in self.get_owner_objects(Owner::AddressOwner(address))? {
+            if info.type_ != delegation_type {
+                continue;
+            }
+            let delegation = match self.database.get_object(&info.object_id)? {
+                Some(object) => Delegation::try_from_object(&object),
+                None => None,
+            };
+            if let Some(delegation) = delegation {
+                stakes.push(SuiStake::new(info.object_id, delegation, &system_state));
+            }
+        }
+        Ok(stakes)
+    }
+
+    /// The annual yield of the delegations to each validator of the committee.
+    pub async fn get_validators_apy(&self) -> Result<SuiValidatorApys, anyhow::Error> {
+        Ok((&self.get_sui_system_state_object().await?).into())
+    }
+
+    /// How the validator of the committee at `address` took part in the current epoch, in the
+    /// checkpoints this node followed.
+    pub async fn get_validator_report(
+        &self,
+        address: SuiAddress,
+    ) -> Result<SuiValidatorReport, anyhow::Error> {
+        let system_state = self.get_sui_system_state_object().await?;
+        let validator = system_state
+            .validators
+            .active_validators
+            .iter()
+            .find(|validator| {
+                SuiAddress::from(ObjectID::from(validator.metadata.sui_address)) == address
+            })
+            .ok_or_else(|| anyhow::anyhow!("{address} is not a validator of the committee"))?;
+        let participation: Vec<_> = self
+            .get_indexes()?
+            .get_checkpoint_participation(system_state.epoch)?
+            .into_iter()
+            .map(|(_, participation)| participation)
+            .collect();
+        Ok(SuiValidatorReport::new(
+            validator,
+            &system_state,
+            &participation,
+        ))
+    }
+
+    /// The checkpoint named by `id`, of those this node followed.
+    pub fn get_checkpoint(&self, id: CheckpointId) -> Result<SuiCheckpoint, anyhow::Error> {
+        let indexes = self.get_indexes()?;
+        let sequence_number = match id {
+            CheckpointId::SequenceNumber(sequence_number) => Some(sequence_number),
+            CheckpointId::Digest(digest) => {
+                let digest: CheckpointDigest = digest
+                    .to_vec()?
+                    .try_into()
+                    .map_err(|_| anyhow::anyhow!("A checkpoint digest is 32 bytes long"))?;
+                indexes.get_checkpoint_sequence_number(&digest)?
+            }
+        };
+        let checkpoint = match sequence_number {
+            Some(sequence_number) if sequence_number < indexes.lowest_checkpoint() => {
+                return Err(data_pruned(&indexes).into())
+            }
+            Some(sequence_number) => indexes
+                .get_checkpoint(sequence_number)?
+                .zip(indexes.get_checkpoint_contents(sequence_number)?),
+            None => None,
+        };
+        let (checkpoint, contents) =
+            checkpoint.ok_or_else(|| anyhow::anyhow!("The checkpoint was not found"))?;
+        Ok(SuiCheckpoint::new(&checkpoint, &contents))
+    }
+
+    /// A page of at most `limit` of the checkpoints this node followed, starting with `cursor`,
+    /// and the cursor of the next page, if there are more checkpoints.
+    pub fn get_checkpoints(
+        &self,
+        cursor: Option<CheckpointSequenceNumber>,
+        limit: usize,
+        descending: bool,
+    ) -> Result<(Vec<SuiCheckpoint>, Option<CheckpointSequenceNumber>), anyhow::Error> {
+        let indexes = self.get_indexes()?;
+        if let Some(cursor) = cursor {
+            if cursor < indexes.lowest_checkpoint() {
+                return Err(data_pruned(&indexes).into());
+            }
+        }
+        let (checkpoints, next_cursor) = indexes.get_checkpoints(cursor, limit, descending)?;
+        Ok((
+            checkpoints
+                .iter()
+                .map(|(checkpoint, contents)| SuiCheckpoint::new(checkpoint, contents))
+                .collect(),
+            next_cursor,
+        ))
+    }
+
+    /// The proof that the transaction `digest` is final: its effects, the certified checkpoint
+    /// this node followed they are in, the proof of their inclusion in it, and the committee of
+    /// the epoch of the checkpoint.
+    pub fn get_finality_proof(
+        &self,
+        digest: TransactionDigest,
+    ) -> Result<SuiFinalityProof, anyhow::Error> {
+        let indexes = self.get_indexes()?;
+        let (epoch, sequence_number) =
+            indexes
+                .get_checkpoint_of_transaction(&digest)?
+                .ok_or_else(|| {
+                    anyhow::anyhow!("The transaction is not in a checkpoint this node followed yet")
+                })?;
+        if sequence_number < indexes.lowest_checkpoint() {
+            return Err(data_pruned(&indexes).into());
+        }
+        let (checkpoint, contents) = indexes
+            .get_checkpoint(sequence_number)?
+            .zip(indexes.get_checkpoint_contents(sequence_number)?)
+            .ok_or_else(|| anyhow::anyhow!("The checkpoint was not found"))?;
+        let effects = self.database.get_effects(&digest)?;
+        let proof = contents
+            .inclusion_proof(&ExecutionDigests::new(digest, effects.digest()))
+            .ok_or_else(|| {
+                anyhow::anyhow!("The effects of the transaction are not those in the checkpoint")
+            })?;
+        let committee = indexes
+            .get_committee(epoch)?
+            .ok_or_else(|| anyhow::anyhow!("The committee of epoch {epoch} was not found"))?;
+        SuiFinalityProof::new(&effects, &checkpoint, proof, &committee)
+    }
+
+    fn get_name_service_registry(&self) -> SuiResult<Option<NameServiceRegistry>> {
+        let registry_id = match self.get_indexes()?.get_name_service_registry()? {
+            Some(registry_id) => registry_id,
+            None => return Ok(None),
+        };
+        Ok(self
+            .database
+            .get_object(&registry_id)?
+            .as_ref()
+            .and_then(NameServiceRegistry::try_from_object))
+    }
+
     pub async fn insert_genesis_object(&self, object: Object) {
         self.database
             .insert_genesis_object(object)
@@ -1159,6 +1949,7 @@ impl AuthorityState {
                 certificate.sender_address(),
                 &inputs,
                 &outputs,
+                &signed_effects.effects.events,
                 seq,
                 certificate.digest(),
             ) {
@@ -1197,6 +1988,31 @@ impl AuthorityState {
         self.database.read_certificate(digest)
     }
 
+    /// The certificate of an executed transaction, with the effects it was executed with.
+    pub async fn get_certificate_and_effects(
+        &self,
+        digest: &TransactionDigest,
+    ) -> SuiResult<(CertifiedTransaction, TransactionEffects)> {
+        let certificate = self
+            .database
+            .read_certificate(digest)?
+            .ok_or(SuiError::TransactionNotFound { digest: *digest })?;
+        Ok((certificate, self.database.get_effects(digest)?))
+    }
+
+    /// The object at `version`, or at its latest version if `version` is `None`, including
+    /// the versions which transactions have since mutated.
+    pub async fn get_object_at_version(
+        &self,
+        object_id: &ObjectID,
+        version: Option<SequenceNumber>,
+    ) -> SuiResult<Option<Object>> {
+        match version {
+            Some(version) => self.database.get_object_by_key(object_id, version),
+            None => self.database.get_object(object_id),
+        }
+    }
+
     pub async fn parent(&self, object_ref: &ObjectRef) -> Option<TransactionDigest> {
         self.database
             .parent(object_ref)
@@ -1230,6 +2046,30 @@ impl AuthorityState {
     }
 }
 
+/// The error for the data of the checkpoints `indexes` pruned, with the checkpoints it holds.
+/// The RPC servers point the clients at the archive, if they know of one.
+fn data_pruned(indexes: &IndexStore) -> SuiError {
+    SuiError::DataPruned {
+        lowest_checkpoint: indexes.lowest_checkpoint(),
+        next_checkpoint: indexes.next_checkpoint(),
+        archive: None,
+    }
+}
+
+#[async_trait]
+impl DataReader for AuthorityState {
+    async fn get_latest_object(&self, object_id: &ObjectID) -> Result<Object, anyhow::Error> {
+        Ok(self.get_object_read(object_id).await?.into_object()?)
+    }
+
+    async fn get_owned_objects(
+        &self,
+        address: SuiAddress,
+    ) -> Result<Vec<ObjectInfo>, anyhow::Error> {
+        Ok(self.get_owner_objects(Owner::AddressOwner(address))?)
+    }
+}
+
 #[async_trait]
 impl ExecutionState for AuthorityState {
     type Transaction = ConsensusTransaction;
@@ -1261,7 +2101,7 @@ impl ExecutionState for AuthorityState {
                 if !self.shared_locks_exist(&certificate).await? {
                     // Check the certificate. Remember that Byzantine authorities may input anything into
                     // consensus.
-                    certificate.verify(&self.committee.load())?;
+                    self.verify_certificate(&certificate).await?;
 
                     // Persist the certificate since we are about to lock one or more shared object.
                     // We thus need to make sure someone (if not the client) can continue the protocol.
@@ -1,13 +1,21 @@
 // Copyright (c) 2022, Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
+use super::epoch_tables::EpochTables;
+use super::object_cache::{ObjectCache, DEFAULT_OBJECT_CACHE_SIZE};
+use super::package_cache::{CachedPackage, PackageCache, DEFAULT_PACKAGE_CACHE_SIZE};
+use super::store_dump::{self, parse_object_version, DumpKey};
+use super::store_migrations::{migrations, SchemaVersion, STORE_SCHEMA_VERSION};
 use super::*;
 use crate::epoch::EpochInfoLocals;
 use crate::gateway_state::GatewayTxSeqNumber;
+use arc_swap::ArcSwap;
+use move_bytecode_utils::module_cache::GetModule;
+use move_core_types::language_storage::{StructTag, TypeTag};
 use narwhal_executor::ExecutionIndices;
 use rocksdb::Options;
 use serde::{Deserialize, Serialize};
 use serde_with::serde_as;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use sui_storage::{
     default_db_options,
     mutex_table::{LockGuard, MutexTable},
@@ -17,9 +25,12 @@ use sui_types::base_types::SequenceNumber;
 use sui_types::batch::{SignedBatch, TxSequenceNumber};
 use sui_types::committee::EpochId;
 use sui_types::crypto::{AuthoritySignInfo, EmptySignInfo};
+use sui_types::deny_list::DenyList;
+use sui_types::display::Display;
 use sui_types::object::{Owner, OBJECT_START_VERSION};
+use sui_types::object_change::TransactionChanges;
 use tracing::{debug, error, info, trace};
-use typed_store::rocks::{DBBatch, DBMap};
+use typed_store::rocks::{DBBatch, DBMap, TypedStoreError};
 use typed_store::{reopen, traits::Map};
 
 pub type AuthorityStore = SuiDataStore<false, AuthoritySignInfo>;
@@ -27,10 +38,31 @@ pub type GatewayStore = SuiDataStore<false, EmptySignInfo>;
 
 const NUM_SHARDS: usize = 4096;
 
+/// The tables of the store, as `sui-tool db-tool` names them.
+pub const STORE_TABLES: &[&str] = &[
+    "objects",
+    "all_object_versions",
+    "owner_index",
+    "certificates",
+    "parent_sync",
+    "effects",
+    "executed_sequence",
+    "batches",
+    "epochs",
+    "deny_lists",
+    "displays",
+    "transaction_changes",
+    "clean_shutdown",
+    "schema_version",
+];
+
 /// The key where the latest consensus index is stored in the database.
 // TODO: Make a single table (e.g., called `variables`) storing all our lonely variables in one place.
 const LAST_CONSENSUS_INDEX_ADDR: u64 = 0;
 
+/// The key where the clean shutdown marker is stored in the database.
+const CLEAN_SHUTDOWN_ADDR: u64 = 0;
+
 /// ALL_OBJ_VER determines whether we want to store all past
 /// versions of every object in the store. Authority doesn't store
 /// them, but other entities such as replicas will.
@@ -44,7 +76,7 @@ pub struct SuiDataStore<const ALL_OBJ_VER: bool, S> {
     ///
     /// Note that while this map can store all versions of an object, in practice it only stores
     /// the most recent version.
-    objects: DBMap<ObjectKey, Object>,
+    pub(super) objects: DBMap<ObjectKey, Object>,
 
     /// Stores all history versions of all objects.
     /// This is not needed by an authority, but is needed by a replica.
@@ -57,22 +89,23 @@ pub struct SuiDataStore<const ALL_OBJ_VER: bool, S> {
     /// Internal vector of locks to manage concurrent writes to the database
     mutex_table: MutexTable<ObjectDigest>,
 
+    /// The latest versions of the objects read recently, and their locks.
+    cache: ObjectCache,
+
+    /// The modules of the packages read recently, deserialized.
+    packages: PackageCache,
+
     /// This is a an index of object references to currently existing objects, indexed by the
     /// composite key of the SuiAddress of their owner and the object ID of the object.
     /// This composite index allows an efficient iterator to list all objected currently owned
     /// by a specific user, and their object reference.
     owner_index: DBMap<(Owner, ObjectID), ObjectInfo>,
 
-    /// This is map between the transaction digest and transactions found in the `transaction_lock`.
-    /// NOTE: after a lock is deleted (after a certificate is processed) the corresponding entry here
-    /// could be deleted, but right now this is only done on gateways, not done on authorities.
-    transactions: DBMap<TransactionDigest, TransactionEnvelope<S>>,
-
     /// This is a map between the transaction digest and the corresponding certificate for all
     /// certificates that have been successfully processed by this authority. This set of certificates
     /// along with the genesis allows the reconstruction of all other state, and a full sync to this
     /// authority.
-    certificates: DBMap<TransactionDigest, CertifiedTransaction>,
+    pub(super) certificates: DBMap<TransactionDigest, CertifiedTransaction>,
 
     /// The map between the object ref of objects processed at all versions and the transaction
     /// digest of the certificate that lead to the creation of this version of the object.
@@ -87,13 +120,6 @@ pub struct SuiDataStore<const ALL_OBJ_VER: bool, S> {
     /// the same response for any call after the first (ie. make certificate processing idempotent).
     effects: DBMap<TransactionDigest, TransactionEffectsEnvelope<S>>,
 
-    /// Hold the lock for shared objects. These locks are written by a single task: upon receiving a valid
-    /// certified transaction from consensus, the authority assigns a lock to each shared objects of the
-    /// transaction. Note that all authorities are guaranteed to assign the same lock to these objects.
-    /// TODO: These two maps should be merged into a single one (no reason to have two).
-    sequenced: DBMap<(TransactionDigest, ObjectID), SequenceNumber>,
-    schedule: DBMap<ObjectID, SequenceNumber>,
-
     // Tables used for authority batch structure
     /// A sequence on all executed certificates and effects.
     pub executed_sequence: DBMap<TxSequenceNumber, ExecutionDigests>,
@@ -101,22 +127,49 @@ pub struct SuiDataStore<const ALL_OBJ_VER: bool, S> {
     /// A sequence of batches indexing into the sequence of executed transactions.
     pub batches: DBMap<TxSequenceNumber, SignedBatch>,
 
-    /// The following table is used to store a single value (the corresponding key is a constant). The value
-    /// represents the index of the latest consensus message this authority processed. This field is written
-    /// by a single process acting as consensus (light) client. It is used to ensure the authority processes
-    /// every message output by consensus (and in the right order).
-    last_consensus_index: DBMap<u64, ExecutionIndices>,
-
     /// Map from each epoch ID to the epoch information.
     epochs: DBMap<EpochId, EpochInfoLocals>,
+
+    /// Map from a coin type `T` to the ID of the shared `DenyList<T>` object governing it.
+    /// Only the first deny list created for a coin type is recorded.
+    deny_lists: DBMap<TypeTag, ObjectID>,
+
+    /// Map from a struct type `T` to the ID of the most recently written `Display<T>` object,
+    /// used to render objects of type `T`.
+    displays: DBMap<StructTag, ObjectID>,
+
+    /// A map between the transaction digest of a certificate that was successfully processed
+    /// and the balance and object changes made by its execution.
+    transaction_changes: DBMap<TransactionDigest, TransactionChanges>,
+
+    /// The following table is used to store a single value (the corresponding key is a constant). It
+    /// is present when the authority shut down cleanly, after executing every certificate it started
+    /// executing, and is removed when the authority starts again. The next start recovers the store
+    /// if it is missing.
+    clean_shutdown: DBMap<u64, ()>,
+
+    /// The following table is used to store a single value (the corresponding key is a constant):
+    /// the version of the layout of the tables, which the migrations of `store_migrations` bring
+    /// up to date when the store is opened.
+    pub(super) schema_version: DBMap<u64, SchemaVersion>,
+
+    /// The tables holding the signed transactions and the shared object locks of the current
+    /// epoch, in a database of their own.
+    epoch_tables: ArcSwap<EpochTables<S>>,
+
+    /// The directory of the store and the options it was opened with, to open the tables of
+    /// the next epochs.
+    path: PathBuf,
+    db_options: Option<Options>,
 }
 
 impl<const ALL_OBJ_VER: bool, S: Eq + Serialize + for<'de> Deserialize<'de>>
     SuiDataStore<ALL_OBJ_VER, S>
 {
-    /// Open an authority store by directory path
+    /// Open an authority store by directory path, and migrate its tables to the schema version of
+    /// this release. Panics if the store has a newer schema version.
     pub fn open<P: AsRef<Path>>(path: P, db_options: Option<Options>) -> Self {
-        let (options, point_lookup) = default_db_options(db_options);
+        let (options, point_lookup) = default_db_options(db_options.clone());
 
         let db = {
             let path = &path;
@@ -124,17 +177,18 @@ impl<const ALL_OBJ_VER: bool, S: Eq + Serialize + for<'de> Deserialize<'de>>
             let opt_cfs: &[(&str, &rocksdb::Options)] = &[
                 ("objects", &point_lookup),
                 ("all_object_versions", &options),
-                ("transactions", &point_lookup),
                 ("owner_index", &options),
                 ("certificates", &point_lookup),
                 ("parent_sync", &options),
                 ("effects", &point_lookup),
-                ("sequenced", &options),
-                ("schedule", &options),
                 ("executed_sequence", &options),
                 ("batches", &options),
-                ("last_consensus_index", &options),
                 ("epochs", &options),
+                ("deny_lists", &point_lookup),
+                ("displays", &point_lookup),
+                ("transaction_changes", &point_lookup),
+                ("clean_shutdown", &options),
+                ("schema_version", &options),
             ];
             typed_store::rocks::open_cf_opts(path, db_options, opt_cfs)
         }
@@ -147,29 +201,31 @@ impl<const ALL_OBJ_VER: bool, S: Eq + Serialize + for<'de> Deserialize<'de>>
             objects,
             all_object_versions,
             owner_index,
-            transactions,
             certificates,
             parent_sync,
             effects,
-            sequenced,
-            schedule,
             batches,
-            last_consensus_index,
             epochs,
+            deny_lists,
+            displays,
+            transaction_changes,
+            clean_shutdown,
+            schema_version,
         ) = reopen! (
             &db,
             "objects";<ObjectKey, Object>,
             "all_object_versions";<ObjectKey, Object>,
             "owner_index";<(Owner, ObjectID), ObjectInfo>,
-            "transactions";<TransactionDigest, TransactionEnvelope<S>>,
             "certificates";<TransactionDigest, CertifiedTransaction>,
             "parent_sync";<ObjectRef, TransactionDigest>,
             "effects";<TransactionDigest, TransactionEffectsEnvelope<S>>,
-            "sequenced";<(TransactionDigest, ObjectID), SequenceNumber>,
-            "schedule";<ObjectID, SequenceNumber>,
             "batches";<TxSequenceNumber, SignedBatch>,
-            "last_consensus_index";<u64, ExecutionIndices>,
-            "epochs";<EpochId, EpochInfoLocals>
+            "epochs";<EpochId, EpochInfoLocals>,
+            "deny_lists";<TypeTag, ObjectID>,
+            "displays";<StructTag, ObjectID>,
+            "transaction_changes";<TransactionDigest, TransactionChanges>,
+            "clean_shutdown";<u64, ()>,
+            "schema_version";<u64, SchemaVersion>
         );
 
         // For now, create one LockService for each SuiDataStore, and we use a specific
@@ -178,23 +234,68 @@ impl<const ALL_OBJ_VER: bool, S: Eq + Serialize + for<'de> Deserialize<'de>>
         let lock_service =
             LockService::new(lockdb_path, None).expect("Could not initialize lockdb");
 
-        Self {
+        // The current epoch is the last one recorded, or the genesis epoch in a new store.
+        let epoch = epochs
+            .iter()
+            .skip_to_last()
+            .next()
+            .map(|(epoch, _)| epoch)
+            .unwrap_or_default();
+        let epoch_tables = EpochTables::open(path.as_ref(), epoch, db_options.clone());
+
+        let store = Self {
             objects,
             all_object_versions,
             lock_service,
             mutex_table: MutexTable::new(NUM_SHARDS),
+            cache: ObjectCache::new(DEFAULT_OBJECT_CACHE_SIZE, &METRICS),
+            packages: PackageCache::new(DEFAULT_PACKAGE_CACHE_SIZE, &METRICS),
             owner_index,
-            transactions,
             certificates,
             parent_sync,
             effects,
-            sequenced,
-            schedule,
             executed_sequence,
             batches,
-            last_consensus_index,
             epochs,
-        }
+            deny_lists,
+            displays,
+            transaction_changes,
+            clean_shutdown,
+            schema_version,
+            epoch_tables: ArcSwap::from_pointee(epoch_tables),
+            path: path.as_ref().to_path_buf(),
+            db_options,
+        };
+        store
+            .migrate(STORE_SCHEMA_VERSION, &migrations())
+            .unwrap_or_else(|err| panic!("Cannot open the store at {:?}: {err}", path.as_ref()));
+        store
+    }
+
+    /// The tables of the current epoch.
+    fn epoch_tables(&self) -> Arc<EpochTables<S>> {
+        self.epoch_tables.load_full()
+    }
+
+    /// Switch to the tables of `epoch`, which start empty. The tables of the previous epoch are
+    /// kept until the next switch, so that the requests reading them when the epoch changes
+    /// can finish, and the tables of the epochs before it are removed.
+    pub fn start_epoch_tables(&self, epoch: EpochId) -> SuiResult {
+        let previous_epoch = self.epoch_tables().epoch;
+        fp_ensure!(
+            previous_epoch < epoch,
+            SuiError::InconsistentEpochState {
+                error: format!(
+                    "Cannot start the tables of epoch {epoch} in epoch {previous_epoch}"
+                )
+            }
+        );
+        self.epoch_tables.store(Arc::new(EpochTables::open(
+            &self.path,
+            epoch,
+            self.db_options.clone(),
+        )));
+        EpochTables::<S>::remove_epochs_before(&self.path, previous_epoch)
     }
 
     /// Returns the TransactionEffects if we have an effects structure for this transaction digest
@@ -219,7 +320,8 @@ impl<const ALL_OBJ_VER: bool, S: Eq + Serialize + for<'de> Deserialize<'de>>
 
     /// Returns true if we have a transaction structure for this transaction digest
     pub fn transaction_exists(&self, transaction_digest: &TransactionDigest) -> SuiResult<bool> {
-        self.transactions
+        self.epoch_tables()
+            .transactions
             .contains_key(transaction_digest)
             .map_err(|e| e.into())
     }
@@ -234,6 +336,50 @@ impl<const ALL_OBJ_VER: bool, S: Eq + Serialize + for<'de> Deserialize<'de>>
             .is_none())
     }
 
+    /// Returns true if the authority shut down cleanly the last time it ran, and removes the
+    /// clean shutdown marker so that a crash of this run is detected by the next one.
+    pub fn take_clean_shutdown_marker(&self) -> SuiResult<bool> {
+        let clean = self.clean_shutdown.contains_key(&CLEAN_SHUTDOWN_ADDR)?;
+        self.clean_shutdown.remove(&CLEAN_SHUTDOWN_ADDR)?;
+        Ok(clean)
+    }
+
+    /// Flush the memtables of the store to disk and mark the shutdown as clean. Must only be
+    /// called once no certificate is being executed.
+    pub fn record_clean_shutdown(&self) -> SuiResult {
+        for rocksdb in [
+            &self.objects.rocksdb,
+            &self.epoch_tables().sequenced.rocksdb,
+        ] {
+            rocksdb
+                .flush()
+                .map_err(|e| TypedStoreError::RocksDBError(e.to_string()))?;
+        }
+        self.clean_shutdown.insert(&CLEAN_SHUTDOWN_ADDR, &())?;
+        Ok(())
+    }
+
+    /// The certificates sequenced by consensus which hold shared object locks but have no
+    /// effects, such as the ones that were executing when the authority crashed.
+    pub fn certificates_without_effects(&self) -> SuiResult<Vec<CertifiedTransaction>> {
+        let digests: HashSet<_> = self
+            .epoch_tables()
+            .sequenced
+            .iter()
+            .map(|((transaction_digest, _), _)| transaction_digest)
+            .collect();
+        let mut certificates = Vec::new();
+        for digest in digests {
+            if self.effects_exists(&digest)? {
+                continue;
+            }
+            if let Some(certificate) = self.certificates.get(&digest)? {
+                certificates.push(certificate);
+            }
+        }
+        Ok(certificates)
+    }
+
     pub fn next_sequence_number(&self) -> Result<TxSequenceNumber, SuiError> {
         Ok(self
             .executed_sequence
@@ -269,6 +415,31 @@ impl<const ALL_OBJ_VER: bool, S: Eq + Serialize + for<'de> Deserialize<'de>>
             .collect())
     }
 
+    /// Return at most `limit` objects of `owner` in the order of their IDs, starting with the
+    /// object `cursor` if given, and the ID of the object following them if there are more.
+    pub fn get_owner_objects_page(
+        &self,
+        owner: Owner,
+        cursor: Option<ObjectID>,
+        limit: usize,
+    ) -> Result<(Vec<ObjectInfo>, Option<ObjectID>), SuiError> {
+        debug!(?owner, ?cursor, limit, "get_owner_objects_page");
+        let mut objects: Vec<_> = self
+            .owner_index
+            .iter()
+            .skip_to(&(owner, cursor.unwrap_or(ObjectID::ZERO)))?
+            .take_while(|((object_owner, _), _)| (object_owner == &owner))
+            .take(limit + 1)
+            .map(|(_, object_info)| object_info)
+            .collect();
+        let next_cursor = if objects.len() > limit {
+            objects.pop().map(|object_info| object_info.object_id)
+        } else {
+            None
+        };
+        Ok((objects, next_cursor))
+    }
+
     pub fn get_object_by_key(
         &self,
         object_id: &ObjectID,
@@ -279,6 +450,12 @@ impl<const ALL_OBJ_VER: bool, S: Eq + Serialize + for<'de> Deserialize<'de>>
 
     /// Read an object and return it, or Err(ObjectNotFound) if the object was not found.
     pub fn get_object(&self, object_id: &ObjectID) -> Result<Option<Object>, SuiError> {
+        self.cache
+            .get_object(object_id, || self.read_latest_object(object_id))
+    }
+
+    /// Read the latest version of an object from the tables, bypassing the cache.
+    fn read_latest_object(&self, object_id: &ObjectID) -> Result<Option<Object>, SuiError> {
         let obj_entry = self
             .objects
             .iter()
@@ -315,6 +492,39 @@ impl<const ALL_OBJ_VER: bool, S: Eq + Serialize + for<'de> Deserialize<'de>>
         }
     }
 
+    /// Returns the deny list governing coins of type `coin_type`, if the issuer has created one.
+    pub fn get_deny_list(&self, coin_type: &TypeTag) -> SuiResult<Option<DenyList>> {
+        match self.deny_lists.get(coin_type)? {
+            Some(list_id) => Ok(self
+                .get_object(&list_id)?
+                .as_ref()
+                .and_then(DenyList::try_from_object)),
+            None => Ok(None),
+        }
+    }
+
+    /// Returns the display used to render objects of type `type_`, if its publisher has set one.
+    pub fn get_display(&self, type_: &StructTag) -> SuiResult<Option<Display>> {
+        match self.displays.get(type_)? {
+            Some(display_id) => Ok(self
+                .get_object(&display_id)?
+                .as_ref()
+                .and_then(Display::try_from_object)),
+            None => Ok(None),
+        }
+    }
+
+    /// Returns the balance and object changes made by the transaction `transaction_digest`.
+    pub fn get_transaction_changes(
+        &self,
+        transaction_digest: &TransactionDigest,
+    ) -> SuiResult<TransactionChanges> {
+        Ok(self
+            .transaction_changes
+            .get(transaction_digest)?
+            .unwrap_or_default())
+    }
+
     /// Get many objects
     pub fn get_objects(&self, objects: &[ObjectID]) -> Result<Vec<Option<Object>>, SuiError> {
         let mut result = Vec::new();
@@ -330,14 +540,14 @@ impl<const ALL_OBJ_VER: bool, S: Eq + Serialize + for<'de> Deserialize<'de>>
         object_ref: &ObjectRef,
     ) -> Result<Option<TransactionEnvelope<S>>, SuiError> {
         let transaction_option = self
-            .lock_service
-            .get_lock(*object_ref)
+            .cache
+            .get_lock(object_ref, self.lock_service.get_lock(*object_ref))
             .await?
             .ok_or(SuiError::TransactionLockDoesNotExist)?;
 
         match transaction_option {
             Some(tx_digest) => {
-                return Ok(self.transactions.get(&tx_digest)?);
+                return Ok(self.epoch_tables().transactions.get(&tx_digest)?);
                 // .expect("Stored a lock without storing transaction?"),
             }
             None => Ok(None),
@@ -400,7 +610,10 @@ impl<const ALL_OBJ_VER: bool, S: Eq + Serialize + for<'de> Deserialize<'de>>
     ) -> Result<Vec<Option<SequenceNumber>>, SuiError> {
         let keys = object_ids.map(|objid| (*transaction_digest, *objid));
 
-        self.sequenced.multi_get(keys).map_err(SuiError::from)
+        self.epoch_tables()
+            .sequenced
+            .multi_get(keys)
+            .map_err(SuiError::from)
     }
 
     /// Read a lock for a specific (transaction, shared object) pair.
@@ -409,6 +622,7 @@ impl<const ALL_OBJ_VER: bool, S: Eq + Serialize + for<'de> Deserialize<'de>>
         transaction_digest: &TransactionDigest,
     ) -> Result<Vec<(ObjectID, SequenceNumber)>, SuiError> {
         Ok(self
+            .epoch_tables()
             .sequenced
             .iter()
             .skip_to(&(*transaction_digest, ObjectID::ZERO))?
@@ -447,11 +661,27 @@ impl<const ALL_OBJ_VER: bool, S: Eq + Serialize + for<'de> Deserialize<'de>>
         self.parent_sync
             .insert(&object_ref, &object.previous_transaction)?;
 
-        self.lock_service
-            .initialize_locks(&[object_ref], false /* is_force_reset */)
-            .await?;
+        // Record the deny list if it is the first one for its coin type
+        if let Some(coin_type) = DenyList::coin_type_of(object) {
+            if !self.deny_lists.contains_key(coin_type)? {
+                self.deny_lists.insert(coin_type, &object_ref.0)?;
+            }
+        }
 
-        Ok(())
+        // Record the display for its type
+        if let Some(displayed_type) = Display::displayed_type_of(object) {
+            self.displays.insert(displayed_type, &object_ref.0)?;
+        }
+
+        self.cache.write_objects(std::iter::once(object));
+        self.packages.invalidate(std::iter::once(&object_ref.0));
+
+        let result = self
+            .lock_service
+            .initialize_locks(&[object_ref], false /* is_force_reset */)
+            .await;
+        self.cache.invalidate_locks(std::iter::once(&object_ref));
+        result
     }
 
     /// This function is used by the bench.rs script, and should not be used in other contexts
@@ -485,12 +715,17 @@ impl<const ALL_OBJ_VER: bool, S: Eq + Serialize + for<'de> Deserialize<'de>>
             )?
             .write()?;
 
+        self.cache.write_objects(objects.iter().copied());
+        self.packages
+            .invalidate(ref_and_objects.iter().map(|(oref, _)| &oref.0));
+
         let refs: Vec<_> = ref_and_objects.iter().map(|(oref, _)| *oref).collect();
-        self.lock_service
+        let result = self
+            .lock_service
             .initialize_locks(&refs, false /* is_force_reset */)
-            .await?;
-
-        Ok(())
+            .await;
+        self.cache.invalidate_locks(refs.iter());
+        result
     }
 
     /// Acquires the transaction lock for a specific transaction, writing the transaction
@@ -504,27 +739,65 @@ impl<const ALL_OBJ_VER: bool, S: Eq + Serialize + for<'de> Deserialize<'de>>
         let tx_digest = *transaction.digest();
 
         // Acquire the lock on input objects
-        self.lock_service
+        let result = self
+            .lock_service
             .acquire_locks(owned_input_objects.to_owned(), tx_digest)
-            .await?;
+            .await;
+        self.cache.invalidate_locks(owned_input_objects.iter());
+        result?;
 
         // TODO: we should have transaction insertion be atomic with lock acquisition, or retry.
         // For now write transactions after because if we write before, there is a chance the lock can fail
         // and this can cause invalid transactions to be inserted in the table.
         // https://github.com/MystenLabs/sui/issues/1990
-        self.transactions.insert(&tx_digest, &transaction)?;
+        self.epoch_tables()
+            .transactions
+            .insert(&tx_digest, &transaction)?;
 
         Ok(())
     }
 
+    /// Release the locks which the transactions signed in the current epoch, but not executed,
+    /// hold on their owned input objects, for the objects to be used in the next epoch: the
+    /// transactions can't be certified anymore. Returns the digests of the transactions.
+    pub async fn release_pending_transaction_locks(&self) -> SuiResult<Vec<TransactionDigest>> {
+        let mut released = Vec::new();
+        for (digest, transaction) in self.epoch_tables().transactions.iter() {
+            if self.effects.contains_key(&digest)? {
+                continue;
+            }
+            let mut locked = Vec::new();
+            for input in transaction.data.input_objects()? {
+                if let InputObjectKind::ImmOrOwnedMoveObject(object_ref) = input {
+                    // An object mutated since has no lock at this version anymore.
+                    if self.lock_service.get_lock(object_ref).await? == Some(Some(digest)) {
+                        locked.push(object_ref);
+                    }
+                }
+            }
+            if !locked.is_empty() {
+                let result = self
+                    .lock_service
+                    .initialize_locks(&locked, true /* is_force_reset */)
+                    .await;
+                self.cache.invalidate_locks(locked.iter());
+                result?;
+            }
+            released.push(digest);
+        }
+        Ok(released)
+    }
+
     /// This function should only be used by the gateway.
     /// It's called when we could not get a transaction to successfully execute,
     /// and have to roll back.
     pub async fn reset_transaction_lock(&self, owned_input_objects: &[ObjectRef]) -> SuiResult {
-        self.lock_service
+        let result = self
+            .lock_service
             .initialize_locks(owned_input_objects, true /* is_force_reset */)
-            .await?;
-        Ok(())
+            .await;
+        self.cache.invalidate_locks(owned_input_objects.iter());
+        result
     }
 
     /// Updates the state resulting from the execution of a certificate.
@@ -555,10 +828,6 @@ impl<const ALL_OBJ_VER: bool, S: Eq + Serialize + for<'de> Deserialize<'de>>
             std::iter::once((transaction_digest, effects)),
         )?;
 
-        // Cleanup the lock of the shared objects.
-        let write_batch =
-            self.remove_shared_objects_locks(write_batch, transaction_digest, certificate)?;
-
         // Safe to unwrap since the "true" flag ensures we get a sequence value back.
         self.batch_update_objects(
             write_batch,
@@ -566,7 +835,10 @@ impl<const ALL_OBJ_VER: bool, S: Eq + Serialize + for<'de> Deserialize<'de>>
             *transaction_digest,
             update_type,
         )
-        .await
+        .await?;
+
+        // Cleanup the lock of the shared objects, which are in the tables of the epoch.
+        self.remove_shared_objects_locks(transaction_digest, certificate)
     }
 
     /// Persist temporary storage to DB for genesis modules
@@ -609,6 +881,9 @@ impl<const ALL_OBJ_VER: bool, S: Eq + Serialize + for<'de> Deserialize<'de>>
         for obj_ref in &effects.effects.wrapped {
             temporary_store.delete_object(&obj_ref.0, obj_ref.1, DeleteKind::Wrap);
         }
+        for obj_ref in &effects.effects.unwrapped_then_deleted {
+            temporary_store.delete_object(&obj_ref.0, obj_ref.1, DeleteKind::UnwrapThenDelete);
+        }
 
         let mut write_batch = self.certificates.batch();
 
@@ -618,11 +893,6 @@ impl<const ALL_OBJ_VER: bool, S: Eq + Serialize + for<'de> Deserialize<'de>>
             std::iter::once((transaction_digest, &certificate)),
         )?;
 
-        // Once a transaction is done processing and effects committed, we no longer
-        // need it in the transactions table. This also allows us to track pending
-        // transactions.
-        write_batch =
-            write_batch.delete_batch(&self.transactions, std::iter::once(transaction_digest))?;
         self.batch_update_objects(
             write_batch,
             temporary_store,
@@ -631,6 +901,13 @@ impl<const ALL_OBJ_VER: bool, S: Eq + Serialize + for<'de> Deserialize<'de>>
         )
         .await?;
 
+        // Once a transaction is done processing and effects committed, we no longer
+        // need it in the transactions table. This also allows us to track pending
+        // transactions.
+        self.epoch_tables()
+            .transactions
+            .remove(transaction_digest)?;
+
         // Store the unsigned effects of the transaction. Must be done after batch_update_objects
         // returns, as we use digest-in-effects? as a proxy for cert-has-been-fully-processed
         // throughout the code.
@@ -647,6 +924,7 @@ impl<const ALL_OBJ_VER: bool, S: Eq + Serialize + for<'de> Deserialize<'de>>
         transaction_digest: TransactionDigest,
         update_type: UpdateType,
     ) -> Result<(), SuiError> {
+        let changes = temporary_store.changes();
         let (objects, active_inputs, written, deleted, _events) = temporary_store.into_inner();
         trace!(written =? written.values().map(|((obj_id, ver, _), _)| (obj_id, ver)).collect::<Vec<_>>(),
                "batch_update_objects: temp store written");
@@ -732,6 +1010,33 @@ impl<const ALL_OBJ_VER: bool, S: Eq + Serialize + for<'de> Deserialize<'de>>
                 }),
         )?;
 
+        // Record the first deny list created for each coin type
+        let mut new_deny_lists = Vec::new();
+        for (object_id, (_, new_object)) in written.iter() {
+            if let Some(coin_type) = DenyList::coin_type_of(new_object) {
+                if !self.deny_lists.contains_key(coin_type)? {
+                    new_deny_lists.push((coin_type.clone(), *object_id));
+                }
+            }
+        }
+        write_batch = write_batch.insert_batch(&self.deny_lists, new_deny_lists)?;
+
+        // Record the most recently written display for each type
+        write_batch = write_batch.insert_batch(
+            &self.displays,
+            written.iter().filter_map(|(object_id, (_, new_object))| {
+                Display::displayed_type_of(new_object).map(|t| (t, *object_id))
+            }),
+        )?;
+
+        // Record the balance and object changes of the transaction
+        if matches!(update_type, UpdateType::Transaction(..)) {
+            write_batch = write_batch.insert_batch(
+                &self.transaction_changes,
+                std::iter::once((transaction_digest, changes)),
+            )?;
+        }
+
         // Insert each output object into the stores
         write_batch = write_batch.insert_batch(
             &self.objects,
@@ -743,6 +1048,11 @@ impl<const ALL_OBJ_VER: bool, S: Eq + Serialize + for<'de> Deserialize<'de>>
         // Atomic write of all data other than locks
         write_batch.write()?;
         trace!("Finished writing batch");
+        self.cache
+            .write_objects(written.values().map(|(_, object)| object));
+        self.cache.remove_objects(deleted.keys());
+        self.packages
+            .invalidate(written.keys().chain(deleted.keys()));
 
         // Need to have a critical section for now because we need to prevent execution of older
         // certs which may overwrite newer objects with older ones.  This can be removed once we have
@@ -769,6 +1079,11 @@ impl<const ALL_OBJ_VER: bool, S: Eq + Serialize + for<'de> Deserialize<'de>>
                     }
                 })
                 .collect();
+            let changed_locks: Vec<_> = owned_inputs
+                .iter()
+                .chain(&new_locks_to_init)
+                .copied()
+                .collect();
 
             match update_type {
                 UpdateType::Transaction(seq, effects_digest) => {
@@ -785,7 +1100,9 @@ impl<const ALL_OBJ_VER: bool, S: Eq + Serialize + for<'de> Deserialize<'de>>
                             owned_inputs,
                             new_locks_to_init,
                         )
-                        .await?;
+                        .await;
+                    self.cache.invalidate_locks(changed_locks.iter());
+                    let assigned_seq = assigned_seq?;
 
                     // This write may be done repeatedly when retrying a tx. The
                     // sequence_transaction call above assigns a sequence number to the transaction
@@ -798,9 +1115,12 @@ impl<const ALL_OBJ_VER: bool, S: Eq + Serialize + for<'de> Deserialize<'de>>
                 }
                 UpdateType::Genesis => {
                     info!("Creating locks for genesis objects");
-                    self.lock_service
+                    let result = self
+                        .lock_service
                         .create_locks_for_genesis_objects(new_locks_to_init)
-                        .await?;
+                        .await;
+                    self.cache.invalidate_locks(changed_locks.iter());
+                    result?;
                 }
             }
 
@@ -840,13 +1160,38 @@ impl<const ALL_OBJ_VER: bool, S: Eq + Serialize + for<'de> Deserialize<'de>>
         }))
     }
 
+    /// The references of the objects live at the end of `epoch`, in the order of their IDs: the
+    /// latest version of each object written at genesis or by a certificate of an epoch up to
+    /// `epoch`, unless that version deleted or wrapped the object.
+    // TODO: Accumulate the digest of the live objects as certificates are executed, rather than
+    // reading every object at the end of each epoch.
+    pub fn live_object_refs_at_end_of_epoch(&self, epoch: EpochId) -> SuiResult<Vec<ObjectRef>> {
+        let mut object_refs = Vec::new();
+        let mut latest: Option<ObjectRef> = None;
+        for (object_ref, digest) in self.parent_sync.iter() {
+            if matches!(latest, Some(previous) if previous.0 != object_ref.0) {
+                object_refs.extend(latest.take().filter(|previous| previous.2.is_alive()));
+            }
+            // A version which no certificate wrote was written at genesis.
+            let written_in_epoch = self
+                .certificates
+                .get(&digest)?
+                .map_or(0, |certificate| certificate.auth_sign_info.epoch);
+            if written_in_epoch <= epoch {
+                latest = Some(object_ref);
+            }
+        }
+        object_refs.extend(latest.filter(|previous| previous.2.is_alive()));
+        Ok(object_refs)
+    }
+
     /// Remove the shared objects locks. This function is not safety-critical and is only need to cleanup the store.
     pub fn remove_shared_objects_locks(
         &self,
-        mut write_batch: DBBatch,
         transaction_digest: &TransactionDigest,
         transaction: &CertifiedTransaction,
-    ) -> SuiResult<DBBatch> {
+    ) -> SuiResult {
+        let epoch_tables = self.epoch_tables();
         let mut sequenced_to_delete = Vec::new();
         let mut schedule_to_delete = Vec::new();
         for object_id in transaction.shared_input_objects() {
@@ -855,9 +1200,13 @@ impl<const ALL_OBJ_VER: bool, S: Eq + Serialize + for<'de> Deserialize<'de>>
                 schedule_to_delete.push(*object_id);
             }
         }
-        write_batch = write_batch.delete_batch(&self.sequenced, sequenced_to_delete)?;
-        write_batch = write_batch.delete_batch(&self.schedule, schedule_to_delete)?;
-        Ok(write_batch)
+        epoch_tables
+            .sequenced
+            .batch()
+            .delete_batch(&epoch_tables.sequenced, sequenced_to_delete)?
+            .delete_batch(&epoch_tables.schedule, schedule_to_delete)?
+            .write()
+            .map_err(SuiError::from)
     }
 
     /// Lock a sequence number for the shared objects of the input transaction. Also update the
@@ -867,40 +1216,56 @@ impl<const ALL_OBJ_VER: bool, S: Eq + Serialize + for<'de> Deserialize<'de>>
         certificate: CertifiedTransaction,
         consensus_index: ExecutionIndices,
     ) -> Result<(), SuiError> {
-        // Make an iterator to save the certificate.
+        let epoch_tables = self.epoch_tables();
         let transaction_digest = *certificate.digest();
-        let certificate_to_write = std::iter::once((transaction_digest, &certificate));
 
         // Make an iterator to update the locks of the transaction's shared objects.
         let ids = certificate.shared_input_objects();
-        let versions = self.schedule.multi_get(ids)?;
-
-        let ids = certificate.shared_input_objects();
-        let (sequenced_to_write, schedule_to_write): (Vec<_>, Vec<_>) = ids
-            .zip(versions.iter())
-            .map(|(id, v)| {
-                // If it is the first time the shared object has been sequenced, assign it the default
-                // sequence number (`OBJECT_START_VERSION`). Otherwise use the `scheduled` map to
-                // to assign the next sequence number.
-                let version = v.unwrap_or_else(|| OBJECT_START_VERSION);
-                let next_version = version.increment();
-
-                let sequenced = ((transaction_digest, *id), version);
-                let scheduled = (id, next_version);
-
-                (sequenced, scheduled)
-            })
-            .unzip();
+        let versions = epoch_tables.schedule.multi_get(ids)?;
+
+        let read_only: HashSet<_> = certificate.read_only_shared_input_objects().collect();
+        let mut sequenced_to_write = Vec::new();
+        let mut schedule_to_write = Vec::new();
+        for (id, v) in certificate.shared_input_objects().zip(versions) {
+            // If it is the first time the shared object has been sequenced in this epoch, assign it
+            // its version in the store, the version of its deletion if it was deleted, or the
+            // default sequence number (`OBJECT_START_VERSION`) if it is not in the store yet.
+            // Otherwise use the `scheduled` map to assign the next sequence number.
+            let version = match v {
+                Some(version) => version,
+                None => match self.get_object(id)? {
+                    Some(object) => object.version(),
+                    None => self
+                        .get_latest_parent_entry(*id)?
+                        .map(|((_, version, _), _)| version)
+                        .unwrap_or(OBJECT_START_VERSION),
+                },
+            };
+            sequenced_to_write.push(((transaction_digest, *id), version));
+            // A transaction reading the object leaves it at its version, which the transactions
+            // sequenced after it are assigned too, so that they do not wait on each other.
+            let next_version = if read_only.contains(id) {
+                version
+            } else {
+                version.increment()
+            };
+            schedule_to_write.push((id, next_version));
+        }
 
         // Make an iterator to update the last consensus index.
         let index_to_write = std::iter::once((LAST_CONSENSUS_INDEX_ADDR, consensus_index));
 
-        // Atomically store all elements.
-        let mut write_batch = self.sequenced.batch();
-        write_batch = write_batch.insert_batch(&self.certificates, certificate_to_write)?;
-        write_batch = write_batch.insert_batch(&self.sequenced, sequenced_to_write)?;
-        write_batch = write_batch.insert_batch(&self.schedule, schedule_to_write)?;
-        write_batch = write_batch.insert_batch(&self.last_consensus_index, index_to_write)?;
+        // Store the certificate first: once the consensus index is updated, consensus does not
+        // send the certificate again.
+        self.certificates
+            .insert(&transaction_digest, &certificate)?;
+
+        // Atomically store all elements of the epoch tables.
+        let mut write_batch = epoch_tables.sequenced.batch();
+        write_batch = write_batch.insert_batch(&epoch_tables.sequenced, sequenced_to_write)?;
+        write_batch = write_batch.insert_batch(&epoch_tables.schedule, schedule_to_write)?;
+        write_batch =
+            write_batch.insert_batch(&epoch_tables.last_consensus_index, index_to_write)?;
         write_batch.write().map_err(SuiError::from)
     }
 
@@ -1022,7 +1387,8 @@ impl<const ALL_OBJ_VER: bool, S: Eq + Serialize + for<'de> Deserialize<'de>>
 
     /// Return the latest consensus index. It is used to bootstrap the consensus client.
     pub fn last_consensus_index(&self) -> SuiResult<ExecutionIndices> {
-        self.last_consensus_index
+        self.epoch_tables()
+            .last_consensus_index
             .get(&LAST_CONSENSUS_INDEX_ADDR)
             .map(|x| x.unwrap_or_default())
             .map_err(SuiError::from)
@@ -1032,7 +1398,7 @@ impl<const ALL_OBJ_VER: bool, S: Eq + Serialize + for<'de> Deserialize<'de>>
         &self,
         transaction_digest: &TransactionDigest,
     ) -> SuiResult<Option<TransactionEnvelope<S>>> {
-        let transaction = self.transactions.get(transaction_digest)?;
+        let transaction = self.epoch_tables().transactions.get(transaction_digest)?;
         Ok(transaction)
     }
 
@@ -1044,6 +1410,64 @@ impl<const ALL_OBJ_VER: bool, S: Eq + Serialize + for<'de> Deserialize<'de>>
         Ok(transaction)
     }
 
+    /// The entries of `table`, one of `STORE_TABLES`, as JSON, with keys from `from` to `to`
+    /// included, at most `limit` of them.
+    pub fn dump_table(
+        &self,
+        table: &str,
+        from: Option<&str>,
+        to: Option<&str>,
+        limit: usize,
+    ) -> anyhow::Result<Vec<(String, String)>> {
+        match table {
+            "objects" => store_dump::dump_entries(&self.objects, from, to, limit),
+            "all_object_versions" => {
+                store_dump::dump_entries(&self.all_object_versions, from, to, limit)
+            }
+            "owner_index" => store_dump::dump_entries(&self.owner_index, from, to, limit),
+            "certificates" => store_dump::dump_entries(&self.certificates, from, to, limit),
+            "parent_sync" => store_dump::dump_entries(&self.parent_sync, from, to, limit),
+            "effects" => store_dump::dump_entries(&self.effects, from, to, limit),
+            "executed_sequence" => {
+                store_dump::dump_entries(&self.executed_sequence, from, to, limit)
+            }
+            "batches" => store_dump::dump_entries(&self.batches, from, to, limit),
+            "epochs" => store_dump::dump_entries(&self.epochs, from, to, limit),
+            "deny_lists" => store_dump::dump_entries(&self.deny_lists, from, to, limit),
+            "displays" => store_dump::dump_entries(&self.displays, from, to, limit),
+            "transaction_changes" => {
+                store_dump::dump_entries(&self.transaction_changes, from, to, limit)
+            }
+            "clean_shutdown" => store_dump::dump_entries(&self.clean_shutdown, from, to, limit),
+            "schema_version" => store_dump::dump_entries(&self.schema_version, from, to, limit),
+            _ => Err(anyhow::anyhow!(
+                "Unknown table {table}, expected one of {}",
+                STORE_TABLES.join(", ")
+            )),
+        }
+    }
+
+    /// The number of entries of each table of `STORE_TABLES`, in order.
+    pub fn table_stats(&self) -> Vec<(&'static str, usize)> {
+        let counts = [
+            store_dump::count_entries(&self.objects),
+            store_dump::count_entries(&self.all_object_versions),
+            store_dump::count_entries(&self.owner_index),
+            store_dump::count_entries(&self.certificates),
+            store_dump::count_entries(&self.parent_sync),
+            store_dump::count_entries(&self.effects),
+            store_dump::count_entries(&self.executed_sequence),
+            store_dump::count_entries(&self.batches),
+            store_dump::count_entries(&self.epochs),
+            store_dump::count_entries(&self.deny_lists),
+            store_dump::count_entries(&self.displays),
+            store_dump::count_entries(&self.transaction_changes),
+            store_dump::count_entries(&self.clean_shutdown),
+            store_dump::count_entries(&self.schema_version),
+        ];
+        STORE_TABLES.iter().copied().zip(counts).collect()
+    }
+
     pub fn insert_new_epoch_info(&self, epoch_info: EpochInfoLocals) -> SuiResult {
         self.epochs
             .insert(&epoch_info.committee.epoch(), &epoch_info)?;
@@ -1058,7 +1482,16 @@ impl<const ALL_OBJ_VER: bool, S: Eq + Serialize + for<'de> Deserialize<'de>>
     #[cfg(test)]
     /// Provide read access to the `schedule` table (useful for testing).
     pub fn get_schedule(&self, object_id: &ObjectID) -> SuiResult<Option<SequenceNumber>> {
-        self.schedule.get(object_id).map_err(SuiError::from)
+        self.epoch_tables()
+            .schedule
+            .get(object_id)
+            .map_err(SuiError::from)
+    }
+
+    #[cfg(test)]
+    /// The directory of the tables of `epoch` (useful for testing).
+    pub fn epoch_tables_path(&self, epoch: EpochId) -> PathBuf {
+        EpochTables::<S>::path(&self.path, epoch)
     }
 }
 
@@ -1068,7 +1501,7 @@ impl<const A: bool> SuiDataStore<A, AuthoritySignInfo> {
         transaction_digest: &TransactionDigest,
     ) -> Result<TransactionInfoResponse, SuiError> {
         Ok(TransactionInfoResponse {
-            signed_transaction: self.transactions.get(transaction_digest)?,
+            signed_transaction: self.epoch_tables().transactions.get(transaction_digest)?,
             certified_transaction: self.certificates.get(transaction_digest)?,
             signed_effects: self.effects.get(transaction_digest)?,
         })
@@ -1076,8 +1509,8 @@ impl<const A: bool> SuiDataStore<A, AuthoritySignInfo> {
 }
 
 impl<const A: bool> SuiDataStore<A, EmptySignInfo> {
-    pub fn pending_transactions(&self) -> &DBMap<TransactionDigest, Transaction> {
-        &self.transactions
+    pub fn pending_transactions(&self) -> DBMap<TransactionDigest, Transaction> {
+        self.epoch_tables().transactions.clone()
     }
 }
 
@@ -1096,6 +1529,10 @@ impl<const A: bool, S: Eq + Serialize + for<'de> Deserialize<'de>> BackingPackag
         }
         Ok(package)
     }
+
+    fn get_object(&self, object_id: &ObjectID) -> SuiResult<Option<Object>> {
+        SuiDataStore::get_object(self, object_id)
+    }
 }
 
 impl<const A: bool, S: Eq + Serialize + for<'de> Deserialize<'de>> ModuleResolver
@@ -1122,6 +1559,30 @@ impl<const A: bool, S: Eq + Serialize + for<'de> Deserialize<'de>> ModuleResolve
     }
 }
 
+impl<const A: bool, S: Eq + Serialize + for<'de> Deserialize<'de>> SuiDataStore<A, S> {
+    /// The modules of the latest version of the package `package_id`, deserialized.
+    pub fn get_cached_package(
+        &self,
+        package_id: &ObjectID,
+    ) -> SuiResult<Option<Arc<CachedPackage>>> {
+        self.packages
+            .get_package(package_id, || self.get_package(package_id))
+    }
+}
+
+impl<const A: bool, S: Eq + Serialize + for<'de> Deserialize<'de>> GetModule
+    for SuiDataStore<A, S>
+{
+    type Error = SuiError;
+    type Item = Arc<CompiledModule>;
+
+    fn get_module_by_id(&self, id: &ModuleId) -> Result<Option<Self::Item>, Self::Error> {
+        Ok(self
+            .get_cached_package(&ObjectID::from(*id.address()))?
+            .and_then(|package| package.module(id.name().as_str()).cloned()))
+    }
+}
+
 /// A wrapper to make Orphan Rule happy
 pub struct AuthorityStoreWrapper(pub Arc<AuthorityStore>);
 
@@ -1137,6 +1598,13 @@ impl ModuleResolver for AuthorityStoreWrapper {
 #[derive(Eq, PartialEq, Clone, Copy, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 struct ObjectKey(pub ObjectID, pub VersionNumber);
 
+impl DumpKey for ObjectKey {
+    fn parse_key(key: &str) -> anyhow::Result<Self> {
+        let (id, version) = parse_object_version(key)?;
+        Ok(ObjectKey(id, version))
+    }
+}
+
 impl ObjectKey {
     pub const ZERO: ObjectKey = ObjectKey(ObjectID::ZERO, VersionNumber::MIN);
 
@@ -1236,9 +1704,14 @@ pub async fn generate_genesis_system_object<
     let genesis_digest = genesis_ctx.digest();
     let mut temporary_store = AuthorityTemporaryStore::new(store.clone(), vec![], genesis_digest);
     let pubkeys: Vec<Vec<u8>> = committee
-        .expanded_keys
-        .values()
-        .map(|pk| pk.to_bytes().to_vec())
+        .voting_rights
+        .keys()
+        .map(|name| name.as_ref().to_vec())
+        .collect();
+    let protocol_pubkeys: Vec<Vec<u8>> = committee
+        .voting_rights
+        .keys()
+        .map(|name| committee.protocol_keys[name].as_ref().to_vec())
         .collect();
     // TODO: May use separate sui address than derived from pubkey.
     let sui_addresses: Vec<AccountAddress> = committee
@@ -1264,6 +1737,7 @@ pub async fn generate_genesis_system_object<
         vec![],
         vec![
             CallArg::Pure(bcs::to_bytes(&pubkeys).unwrap()),
+            CallArg::Pure(bcs::to_bytes(&protocol_pubkeys).unwrap()),
             CallArg::Pure(bcs::to_bytes(&sui_addresses).unwrap()),
             CallArg::Pure(bcs::to_bytes(&names).unwrap()),
             // TODO: below is netaddress, for now just use names as we don't yet want to expose them.
@@ -0,0 +1,111 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! The tables of the authority store which only hold data of the current epoch. Each epoch keeps
+//! them in a database of its own, so that the data of a finished epoch is deleted by removing its
+//! directory, rather than key by key at reconfiguration time.
+
+use super::*;
+use narwhal_executor::ExecutionIndices;
+use rocksdb::Options;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use sui_storage::default_db_options;
+use sui_types::base_types::SequenceNumber;
+use sui_types::committee::EpochId;
+use tracing::info;
+use typed_store::reopen;
+use typed_store::rocks::DBMap;
+
+/// The directory of the epoch databases, under the directory of the store.
+const EPOCHS_DIR: &str = "epochs";
+
+pub struct EpochTables<S> {
+    pub epoch: EpochId,
+
+    /// This is map between the transaction digest and transactions found in the `transaction_lock`.
+    /// NOTE: after a lock is deleted (after a certificate is processed) the corresponding entry here
+    /// could be deleted, but right now this is only done on gateways, not done on authorities.
+    pub transactions: DBMap<TransactionDigest, TransactionEnvelope<S>>,
+
+    /// Hold the lock for shared objects. These locks are written by a single task: upon receiving a valid
+    /// certified transaction from consensus, the authority assigns a lock to each shared objects of the
+    /// transaction. Note that all authorities are guaranteed to assign the same lock to these objects.
+    /// TODO: These two maps should be merged into a single one (no reason to have two).
+    ///
+    /// The `schedule` of an epoch only holds the shared objects sequenced during the epoch. Every
+    /// transaction sequenced in an epoch is executed before the next one starts, so the next version
+    /// of a shared object missing from it is its version in the store.
+    pub sequenced: DBMap<(TransactionDigest, ObjectID), SequenceNumber>,
+    pub schedule: DBMap<ObjectID, SequenceNumber>,
+
+    /// The following table is used to store a single value (the corresponding key is a constant). The value
+    /// represents the index of the latest consensus message this authority processed. This field is written
+    /// by a single process acting as consensus (light) client. It is used to ensure the authority processes
+    /// every message output by consensus (and in the right order).
+    pub last_consensus_index: DBMap<u64, ExecutionIndices>,
+}
+
+impl<S: Eq + Serialize + for<'de> Deserialize<'de>> EpochTables<S> {
+    /// The directory of the database of `epoch`, for the store in `store_path`.
+    pub fn path(store_path: &Path, epoch: EpochId) -> PathBuf {
+        store_path.join(EPOCHS_DIR).join(format!("epoch_{epoch}"))
+    }
+
+    /// Open the tables of `epoch`, creating them if the epoch has just started.
+    pub fn open(store_path: &Path, epoch: EpochId, db_options: Option<Options>) -> Self {
+        let (options, point_lookup) = default_db_options(db_options);
+
+        let db = {
+            let path = Self::path(store_path, epoch);
+            let db_options = Some(options.clone());
+            let opt_cfs: &[(&str, &rocksdb::Options)] = &[
+                ("transactions", &point_lookup),
+                ("sequenced", &options),
+                ("schedule", &options),
+                ("last_consensus_index", &options),
+            ];
+            typed_store::rocks::open_cf_opts(path, db_options, opt_cfs)
+        }
+        .expect("Cannot open epoch DB.");
+
+        let (transactions, sequenced, schedule, last_consensus_index) = reopen! (
+            &db,
+            "transactions";<TransactionDigest, TransactionEnvelope<S>>,
+            "sequenced";<(TransactionDigest, ObjectID), SequenceNumber>,
+            "schedule";<ObjectID, SequenceNumber>,
+            "last_consensus_index";<u64, ExecutionIndices>
+        );
+
+        Self {
+            epoch,
+            transactions,
+            sequenced,
+            schedule,
+            last_consensus_index,
+        }
+    }
+
+    /// Remove the databases of the epochs before `epoch`, for the store in `store_path`.
+    pub fn remove_epochs_before(store_path: &Path, epoch: EpochId) -> SuiResult {
+        let epochs_dir = store_path.join(EPOCHS_DIR);
+        let entries = fs::read_dir(&epochs_dir).map_err(|e| SuiError::GenericAuthorityError {
+            error: format!("Cannot list {epochs_dir:?}: {e}"),
+        })?;
+        for entry in entries.flatten() {
+            let old_epoch = entry
+                .file_name()
+                .to_str()
+                .and_then(|name| name.strip_prefix("epoch_"))
+                .and_then(|old_epoch| old_epoch.parse::<EpochId>().ok());
+            if let Some(old_epoch) = old_epoch.filter(|old_epoch| *old_epoch < epoch) {
+                info!("Removing the database of epoch {old_epoch}");
+                fs::remove_dir_all(entry.path()).map_err(|e| SuiError::GenericAuthorityError {
+                    error: format!("Cannot remove {:?}: {e}", entry.path()),
+                })?;
+            }
+        }
+        Ok(())
+    }
+}
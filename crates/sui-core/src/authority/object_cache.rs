@@ -0,0 +1,140 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! A bounded cache of the latest versions of the objects read recently, and of their
+//! transaction locks, in front of the tables of the store. Hot objects, such as the system
+//! state or popular shared objects, are then deserialized once rather than on every read.
+//!
+//! The store keeps the cache up to date as it writes: the objects written by a certificate
+//! replace their older versions in the cache, while the objects deleted and the locks changed
+//! are dropped from it. A read missing the cache only fills it if nothing was written since the
+//! read began, so that a value read before a write never outlives the write in the cache.
+
+use super::AuthorityMetrics;
+use lru::LruCache;
+use parking_lot::Mutex;
+use sui_types::base_types::{ObjectID, ObjectRef, TransactionDigest};
+use sui_types::error::SuiResult;
+use sui_types::object::Object;
+
+#[cfg(test)]
+#[path = "../unit_tests/object_cache_tests.rs"]
+mod object_cache_tests;
+
+/// The number of objects, and of locks, the cache holds.
+pub const DEFAULT_OBJECT_CACHE_SIZE: usize = 10_000;
+
+/// The lock of an object reference, as the lock service returns it: None if the lock does not
+/// exist, and otherwise the transaction holding it, if any.
+pub type CachedLock = Option<Option<TransactionDigest>>;
+
+pub struct ObjectCache {
+    objects: Mutex<Generational<ObjectID, Object>>,
+    locks: Mutex<Generational<ObjectRef, CachedLock>>,
+    metrics: &'static AuthorityMetrics,
+}
+
+/// An LRU cache along with the number of writes made to it, which tells a read missing the
+/// cache whether a write happened meanwhile.
+pub(super) struct Generational<K, V> {
+    pub(super) entries: LruCache<K, V>,
+    pub(super) writes: u64,
+}
+
+impl<K: std::hash::Hash + Eq, V> Generational<K, V> {
+    pub(super) fn new(size: usize) -> Self {
+        Self {
+            entries: LruCache::new(size),
+            writes: 0,
+        }
+    }
+}
+
+impl ObjectCache {
+    pub fn new(size: usize, metrics: &'static AuthorityMetrics) -> Self {
+        Self {
+            objects: Mutex::new(Generational::new(size)),
+            locks: Mutex::new(Generational::new(size)),
+            metrics,
+        }
+    }
+
+    /// The latest version of the object `object_id`, from the cache or else from `read`.
+    pub fn get_object(
+        &self,
+        object_id: &ObjectID,
+        read: impl FnOnce() -> SuiResult<Option<Object>>,
+    ) -> SuiResult<Option<Object>> {
+        let writes = {
+            let mut objects = self.objects.lock();
+            if let Some(object) = objects.entries.get(object_id) {
+                self.metrics.object_cache_hits.inc();
+                return Ok(Some(object.clone()));
+            }
+            objects.writes
+        };
+        self.metrics.object_cache_misses.inc();
+        let object = read()?;
+        if let Some(object) = &object {
+            let mut objects = self.objects.lock();
+            if objects.writes == writes {
+                objects.entries.put(*object_id, object.clone());
+            }
+        }
+        Ok(object)
+    }
+
+    /// Replace the cached versions of the objects written with their new versions. An object is
+    /// only cached once read, and a version older than the one cached, such as one written again
+    /// by a certificate executed twice, is ignored.
+    pub fn write_objects<'a>(&self, written: impl Iterator<Item = &'a Object>) {
+        let mut objects = self.objects.lock();
+        objects.writes += 1;
+        for object in written {
+            if let Some(cached) = objects.entries.peek_mut(&object.id()) {
+                if cached.version() < object.version() {
+                    *cached = object.clone();
+                }
+            }
+        }
+    }
+
+    pub fn remove_objects<'a>(&self, deleted: impl Iterator<Item = &'a ObjectID>) {
+        let mut objects = self.objects.lock();
+        objects.writes += 1;
+        for object_id in deleted {
+            objects.entries.pop(object_id);
+        }
+    }
+
+    /// The lock of `object_ref`, from the cache or else from `read`.
+    pub async fn get_lock<F>(&self, object_ref: &ObjectRef, read: F) -> SuiResult<CachedLock>
+    where
+        F: std::future::Future<Output = SuiResult<CachedLock>>,
+    {
+        let writes = {
+            let mut locks = self.locks.lock();
+            if let Some(lock) = locks.entries.get(object_ref) {
+                self.metrics.lock_cache_hits.inc();
+                return Ok(*lock);
+            }
+            locks.writes
+        };
+        self.metrics.lock_cache_misses.inc();
+        let lock = read.await?;
+        let mut locks = self.locks.lock();
+        if locks.writes == writes {
+            locks.entries.put(*object_ref, lock);
+        }
+        Ok(lock)
+    }
+
+    /// Drop the locks of `object_refs`, once they changed in the lock service.
+    pub fn invalidate_locks<'a>(&self, object_refs: impl Iterator<Item = &'a ObjectRef>) {
+        let mut locks = self.locks.lock();
+        locks.writes += 1;
+        for object_ref in object_refs {
+            locks.entries.pop(object_ref);
+        }
+    }
+}
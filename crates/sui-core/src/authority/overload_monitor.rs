@@ -0,0 +1,191 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Adapts the number of certificates an authority executes concurrently to how the executions
+//! fare. Too few concurrent executions leave CPUs idle, while too many contend on the store and
+//! slow every one of them down.
+//!
+//! At every interval, the monitor halves the concurrency if the executions of the interval took
+//! longer than the target latency on average, and otherwise raises it by one if certificates
+//! waited for their turn. While too many certificates wait, the authority is overloaded, and
+//! stops signing new transactions so that it completes the ones it already signed.
+
+use parking_lot::Mutex;
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+use tokio::sync::{Semaphore, SemaphorePermit};
+
+#[cfg(test)]
+#[path = "../unit_tests/overload_monitor_tests.rs"]
+mod overload_monitor_tests;
+
+#[derive(Clone, Debug)]
+pub struct OverloadMonitorConfig {
+    pub min_concurrency: usize,
+    pub max_concurrency: usize,
+    pub initial_concurrency: usize,
+    /// The average execution latency above which the concurrency is lowered.
+    pub target_latency: Duration,
+    /// The number of certificates waiting to execute from which the authority is overloaded.
+    pub overload_queue_depth: usize,
+    pub adjustment_interval: Duration,
+}
+
+impl Default for OverloadMonitorConfig {
+    fn default() -> Self {
+        Self {
+            min_concurrency: 4,
+            max_concurrency: 256,
+            initial_concurrency: 32,
+            target_latency: Duration::from_millis(100),
+            overload_queue_depth: 1000,
+            adjustment_interval: Duration::from_secs(1),
+        }
+    }
+}
+
+/// Limits the number of certificates executing concurrently to the concurrency the monitor
+/// adjusts.
+pub struct ExecutionLimiter {
+    config: OverloadMonitorConfig,
+    permits: Semaphore,
+    concurrency: AtomicUsize,
+    /// The permits to forget as they are released, to lower the concurrency to its target.
+    excess_permits: AtomicUsize,
+    queue_depth: AtomicUsize,
+    latencies: Mutex<LatencyStats>,
+    overloaded: AtomicBool,
+}
+
+#[derive(Default)]
+struct LatencyStats {
+    count: u32,
+    total: Duration,
+}
+
+/// Counts a certificate as waiting to execute until dropped.
+struct Queued<'a>(&'a AtomicUsize);
+
+impl Drop for Queued<'_> {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+impl ExecutionLimiter {
+    pub fn new(config: OverloadMonitorConfig) -> Self {
+        let concurrency = config
+            .initial_concurrency
+            .clamp(config.min_concurrency, config.max_concurrency);
+        Self {
+            config,
+            permits: Semaphore::new(concurrency),
+            concurrency: AtomicUsize::new(concurrency),
+            excess_permits: AtomicUsize::new(0),
+            queue_depth: AtomicUsize::new(0),
+            latencies: Mutex::new(LatencyStats::default()),
+            overloaded: AtomicBool::new(false),
+        }
+    }
+
+    /// Run `execution` once fewer than the current concurrency of executions are running.
+    pub async fn execute<T>(&self, execution: impl Future<Output = T>) -> T {
+        let permit = {
+            self.queue_depth.fetch_add(1, Ordering::SeqCst);
+            let _queued = Queued(&self.queue_depth);
+            self.permits
+                .acquire()
+                .await
+                .expect("The semaphore is never closed")
+        };
+        let start = Instant::now();
+        let result = execution.await;
+        self.record_latency(start.elapsed());
+        self.release(permit);
+        result
+    }
+
+    /// The target number of concurrent executions.
+    pub fn concurrency(&self) -> usize {
+        self.concurrency.load(Ordering::SeqCst)
+    }
+
+    /// The number of certificates waiting to execute.
+    pub fn queue_depth(&self) -> usize {
+        self.queue_depth.load(Ordering::SeqCst)
+    }
+
+    pub fn is_overloaded(&self) -> bool {
+        self.overloaded.load(Ordering::SeqCst)
+    }
+
+    pub fn adjustment_interval(&self) -> Duration {
+        self.config.adjustment_interval
+    }
+
+    /// Run one step of the control loop, over the executions since the previous one.
+    pub fn adjust(&self) {
+        let latencies = std::mem::take(&mut *self.latencies.lock());
+        let queue_depth = self.queue_depth();
+        let current = self.concurrency();
+        let target = if latencies.count > 0
+            && latencies.total / latencies.count > self.config.target_latency
+        {
+            (current / 2).max(self.config.min_concurrency)
+        } else if queue_depth > 0 {
+            (current + 1).min(self.config.max_concurrency)
+        } else {
+            current
+        };
+
+        if target > current {
+            // Permits still to be forgotten need not be added back.
+            let mut increase = target - current;
+            let _ =
+                self.excess_permits
+                    .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |excess| {
+                        let cancelled = excess.min(target - current);
+                        increase = target - current - cancelled;
+                        Some(excess - cancelled)
+                    });
+            self.permits.add_permits(increase);
+        } else if target < current {
+            // The available permits are forgotten now, and the ones held as they are released.
+            let mut decrease = current - target;
+            while decrease > 0 {
+                match self.permits.try_acquire() {
+                    Ok(permit) => {
+                        permit.forget();
+                        decrease -= 1;
+                    }
+                    Err(_) => break,
+                }
+            }
+            self.excess_permits.fetch_add(decrease, Ordering::SeqCst);
+        }
+        self.concurrency.store(target, Ordering::SeqCst);
+        self.overloaded.store(
+            queue_depth >= self.config.overload_queue_depth,
+            Ordering::SeqCst,
+        );
+    }
+
+    fn record_latency(&self, latency: Duration) {
+        let mut latencies = self.latencies.lock();
+        latencies.count += 1;
+        latencies.total += latency;
+    }
+
+    fn release(&self, permit: SemaphorePermit<'_>) {
+        let forget = self
+            .excess_permits
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |excess| {
+                excess.checked_sub(1)
+            })
+            .is_ok();
+        if forget {
+            permit.forget();
+        }
+    }
+}
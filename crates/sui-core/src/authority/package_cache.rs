@@ -0,0 +1,121 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! A bounded cache of the modules of the packages read recently, deserialized, so that the
+//! layouts of the objects and events of a popular package are built without deserializing its
+//! bytecode again for every transaction. The Move VM keeps its own cache of the modules it
+//! verified and linked for execution.
+//!
+//! An entry records the version of the package it was deserialized from. The store drops the
+//! entry of a package as it writes or deletes the package, so an upgraded package is read and
+//! deserialized again, and a read which raced the write does not fill the cache with the
+//! version it replaced.
+
+use super::object_cache::Generational;
+use super::AuthorityMetrics;
+use move_binary_format::CompiledModule;
+use parking_lot::Mutex;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use sui_types::base_types::{ObjectID, SequenceNumber};
+use sui_types::error::{SuiError, SuiResult};
+use sui_types::object::{Data, Object};
+
+#[cfg(test)]
+#[path = "../unit_tests/package_cache_tests.rs"]
+mod package_cache_tests;
+
+/// The number of packages the cache holds.
+pub const DEFAULT_PACKAGE_CACHE_SIZE: usize = 1_000;
+
+/// The modules of a version of a package, deserialized.
+pub struct CachedPackage {
+    version: SequenceNumber,
+    modules: BTreeMap<String, Arc<CompiledModule>>,
+}
+
+impl CachedPackage {
+    pub fn new(package: &Object) -> SuiResult<Self> {
+        let modules = match &package.data {
+            Data::Package(package) => package.serialized_module_map(),
+            Data::Move(_) => {
+                return Err(SuiError::BadObjectType {
+                    error: format!("Package expected, Move object found: {}", package.id()),
+                })
+            }
+        };
+        let modules = modules
+            .iter()
+            .map(|(name, bytes)| {
+                let module = CompiledModule::deserialize(bytes).map_err(|error| {
+                    SuiError::ModuleDeserializationFailure {
+                        error: error.to_string(),
+                    }
+                })?;
+                Ok((name.clone(), Arc::new(module)))
+            })
+            .collect::<SuiResult<_>>()?;
+        Ok(Self {
+            version: package.version(),
+            modules,
+        })
+    }
+
+    pub fn version(&self) -> SequenceNumber {
+        self.version
+    }
+
+    pub fn module(&self, name: &str) -> Option<&Arc<CompiledModule>> {
+        self.modules.get(name)
+    }
+}
+
+pub struct PackageCache {
+    packages: Mutex<Generational<ObjectID, Arc<CachedPackage>>>,
+    metrics: &'static AuthorityMetrics,
+}
+
+impl PackageCache {
+    pub fn new(size: usize, metrics: &'static AuthorityMetrics) -> Self {
+        Self {
+            packages: Mutex::new(Generational::new(size)),
+            metrics,
+        }
+    }
+
+    /// The latest version of the package `package_id`, from the cache or else deserialized from
+    /// the package `read` returns.
+    pub fn get_package(
+        &self,
+        package_id: &ObjectID,
+        read: impl FnOnce() -> SuiResult<Option<Object>>,
+    ) -> SuiResult<Option<Arc<CachedPackage>>> {
+        let writes = {
+            let mut packages = self.packages.lock();
+            if let Some(package) = packages.entries.get(package_id) {
+                self.metrics.package_cache_hits.inc();
+                return Ok(Some(package.clone()));
+            }
+            packages.writes
+        };
+        self.metrics.package_cache_misses.inc();
+        let package = match read()? {
+            Some(package) => Arc::new(CachedPackage::new(&package)?),
+            None => return Ok(None),
+        };
+        let mut packages = self.packages.lock();
+        if packages.writes == writes {
+            packages.entries.put(*package_id, package.clone());
+        }
+        Ok(Some(package))
+    }
+
+    /// Drop the packages among the objects written or deleted.
+    pub fn invalidate<'a>(&self, object_ids: impl Iterator<Item = &'a ObjectID>) {
+        let mut packages = self.packages.lock();
+        packages.writes += 1;
+        for object_id in object_ids {
+            packages.entries.pop(object_id);
+        }
+    }
+}
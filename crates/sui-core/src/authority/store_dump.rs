@@ -0,0 +1,104 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Reads the tables of a store as JSON, for `sui-tool db-tool` to inspect the database of a
+//! stopped node.
+
+use anyhow::{anyhow, Result};
+use move_core_types::language_storage::{StructTag, TypeTag};
+use move_core_types::parser::parse_type_tag;
+use serde::{de::DeserializeOwned, Serialize};
+use sui_types::base_types::{
+    ObjectDigest, ObjectID, ObjectRef, SequenceNumber, SuiAddress, TransactionDigest,
+};
+use sui_types::object::Owner;
+use sui_types::sui_serde::{Base64, Encoding};
+use typed_store::rocks::DBMap;
+use typed_store::traits::Map;
+
+/// A key of a table, as given on the command line to start or end a dump at.
+pub trait DumpKey: Sized {
+    fn parse_key(key: &str) -> Result<Self>;
+}
+
+impl DumpKey for u64 {
+    fn parse_key(key: &str) -> Result<Self> {
+        Ok(key.parse()?)
+    }
+}
+
+impl DumpKey for TransactionDigest {
+    fn parse_key(key: &str) -> Result<Self> {
+        Ok(TransactionDigest::try_from(&Base64::decode(key)?[..])?)
+    }
+}
+
+/// An object ID, from its first version, or `<object ID>:<version>`.
+pub fn parse_object_version(key: &str) -> Result<(ObjectID, SequenceNumber)> {
+    match key.split_once(':') {
+        Some((id, version)) => Ok((id.parse()?, SequenceNumber::from(version.parse::<u64>()?))),
+        None => Ok((key.parse()?, SequenceNumber::MIN)),
+    }
+}
+
+impl DumpKey for ObjectRef {
+    fn parse_key(key: &str) -> Result<Self> {
+        let (id, version) = parse_object_version(key)?;
+        Ok((id, version, ObjectDigest::MIN))
+    }
+}
+
+/// The objects of an address, from the first one.
+impl DumpKey for (Owner, ObjectID) {
+    fn parse_key(key: &str) -> Result<Self> {
+        let owner: SuiAddress = key.parse()?;
+        Ok((Owner::AddressOwner(owner), ObjectID::ZERO))
+    }
+}
+
+impl DumpKey for TypeTag {
+    fn parse_key(key: &str) -> Result<Self> {
+        parse_type_tag(key)
+    }
+}
+
+impl DumpKey for StructTag {
+    fn parse_key(key: &str) -> Result<Self> {
+        match parse_type_tag(key)? {
+            TypeTag::Struct(tag) => Ok(tag),
+            _ => Err(anyhow!("{key} is not a struct type")),
+        }
+    }
+}
+
+/// The entries of `map`, as JSON, with keys from `from` to `to` included, at most `limit` of
+/// them.
+pub fn dump_entries<K, V>(
+    map: &DBMap<K, V>,
+    from: Option<&str>,
+    to: Option<&str>,
+    limit: usize,
+) -> Result<Vec<(String, String)>>
+where
+    K: DumpKey + Serialize + DeserializeOwned + Ord,
+    V: Serialize + DeserializeOwned,
+{
+    let to = to.map(K::parse_key).transpose()?;
+    let iter = match from {
+        Some(from) => map.iter().skip_to(&K::parse_key(from)?)?,
+        None => map.iter(),
+    };
+    iter.take_while(|(key, _)| to.as_ref().map_or(true, |to| key <= to))
+        .take(limit)
+        .map(|(key, value)| Ok((serde_json::to_string(&key)?, serde_json::to_string(&value)?)))
+        .collect()
+}
+
+/// The number of entries of a table.
+pub fn count_entries<K, V>(map: &DBMap<K, V>) -> usize
+where
+    K: Serialize + DeserializeOwned,
+    V: Serialize + DeserializeOwned,
+{
+    map.keys().count()
+}
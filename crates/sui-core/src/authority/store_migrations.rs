@@ -0,0 +1,111 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Versions of the layout of the tables of the store, and the migrations between them.
+//!
+//! The store records the version of the layout its tables have. Opening the store runs the
+//! migrations from that version up to `STORE_SCHEMA_VERSION`, in order, so that a release which
+//! changes the layout upgrades the stores of the earlier releases rather than having nodes resync
+//! from genesis. A store with a newer version than this release supports is refused, as this
+//! release would misread the tables.
+//!
+//! A change to the layout of the tables bumps `STORE_SCHEMA_VERSION`, and adds to `migrations`
+//! the migration from the version before. The version is recorded after each migration, so a
+//! migration interrupted by a crash runs again from its start when the store is next opened,
+//! and must be idempotent.
+
+use super::*;
+use serde::{Deserialize, Serialize};
+
+#[cfg(test)]
+#[path = "../unit_tests/store_migrations_tests.rs"]
+mod store_migrations_tests;
+
+/// The version of the layout of the tables of the store.
+pub type SchemaVersion = u64;
+
+/// The version of the stores of the releases which did not record it, whose layout is the first
+/// versioned one.
+pub const FIRST_SCHEMA_VERSION: SchemaVersion = 1;
+
+/// The version of the layout this release reads and writes.
+pub const STORE_SCHEMA_VERSION: SchemaVersion = 1;
+
+/// The key where the schema version is stored in the database.
+pub(super) const SCHEMA_VERSION_ADDR: u64 = 0;
+
+/// A migration of the tables of a store from version `from_version` to the next one.
+pub struct Migration<const ALL_OBJ_VER: bool, S> {
+    pub from_version: SchemaVersion,
+    /// What the migration changes, for the logs.
+    pub description: &'static str,
+    pub run: fn(&SuiDataStore<ALL_OBJ_VER, S>) -> SuiResult,
+}
+
+/// The migrations of this release, each from the version of the one before, up to
+/// `STORE_SCHEMA_VERSION`.
+pub fn migrations<const ALL_OBJ_VER: bool, S>() -> Vec<Migration<ALL_OBJ_VER, S>> {
+    Vec::new()
+}
+
+impl<const ALL_OBJ_VER: bool, S: Eq + Serialize + for<'de> Deserialize<'de>>
+    SuiDataStore<ALL_OBJ_VER, S>
+{
+    /// The schema version recorded by the store, if any.
+    pub fn schema_version(&self) -> SuiResult<Option<SchemaVersion>> {
+        Ok(self.schema_version.get(&SCHEMA_VERSION_ADDR)?)
+    }
+
+    /// Run the migrations of the store from its schema version up to `target_version`, and return
+    /// the version it was at. A new store is at `target_version` from the start, and a store
+    /// recording no version is at `FIRST_SCHEMA_VERSION`. Fails, leaving the store as is, if its
+    /// version is newer than `target_version`.
+    pub fn migrate(
+        &self,
+        target_version: SchemaVersion,
+        migrations: &[Migration<ALL_OBJ_VER, S>],
+    ) -> SuiResult<SchemaVersion> {
+        let initial_version = match self.schema_version()? {
+            Some(version) => version,
+            None if self.database_is_empty() => target_version,
+            None => FIRST_SCHEMA_VERSION,
+        };
+        fp_ensure!(
+            initial_version <= target_version,
+            SuiError::StoreSchemaTooNew {
+                version: initial_version,
+                supported_version: target_version,
+            }
+        );
+
+        let mut version = initial_version;
+        while version < target_version {
+            let migration = migrations
+                .iter()
+                .find(|migration| migration.from_version == version)
+                .ok_or_else(|| SuiError::StoreMigrationFailure {
+                    version,
+                    error: "no migration from this version".to_string(),
+                })?;
+            info!(
+                version,
+                description = migration.description,
+                "Migrating the store"
+            );
+            (migration.run)(self).map_err(|error| SuiError::StoreMigrationFailure {
+                version,
+                error: error.to_string(),
+            })?;
+            version += 1;
+            self.schema_version.insert(&SCHEMA_VERSION_ADDR, &version)?;
+        }
+        self.schema_version.insert(&SCHEMA_VERSION_ADDR, &version)?;
+        Ok(initial_version)
+    }
+
+    /// Whether the store holds no objects and no certificates, which is the case of a new store
+    /// only: every store holds at least the genesis objects.
+    fn database_is_empty(&self) -> bool {
+        self.objects.iter().next().is_none() && self.certificates.iter().next().is_none()
+    }
+}
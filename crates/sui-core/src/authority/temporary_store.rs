@@ -1,7 +1,15 @@
 // Copyright (c) 2022, Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
-use move_core_types::account_address::AccountAddress;
-use sui_types::{event::Event, gas::SuiGasStatus, object::Owner};
+use move_core_types::{account_address::AccountAddress, language_storage::TypeTag};
+use sui_types::{
+    coin::Coin,
+    deny_list::DenyList,
+    event::Event,
+    gas::SuiGasStatus,
+    object::Owner,
+    object_change::{BalanceChange, ObjectChange, OwnerChange, TransactionChanges},
+    storage::{ChildObjectResolver, WriteTotals},
+};
 
 use super::*;
 
@@ -51,6 +59,9 @@ impl<S> AuthorityTemporaryStore<S> {
                     }
                 }
                 InputObjectKind::SharedMoveObject(_) => Some(object.compute_object_reference()),
+                // A shared object read by the transaction keeps its version, which the other
+                // transactions reading it are assigned too.
+                InputObjectKind::ReadOnlySharedMoveObject(_) => None,
             })
             .collect();
         let objects = input_objects
@@ -82,6 +93,109 @@ impl<S> AuthorityTemporaryStore<S> {
         &self.deleted
     }
 
+    /// Summarize the coin balance changes and the object changes made by the transaction.
+    pub fn changes(&self) -> TransactionChanges {
+        TransactionChanges {
+            balance_changes: self.balance_changes(),
+            object_changes: self.object_changes(),
+        }
+    }
+
+    fn balance_changes(&self) -> Vec<BalanceChange> {
+        let mut balances: BTreeMap<(Owner, TypeTag), i128> = BTreeMap::new();
+        // Only inputs that were written or deleted can have changed value or owner.
+        let old_coins = self
+            .objects
+            .iter()
+            .filter(|(id, _)| self.written.contains_key(*id) || self.deleted.contains_key(*id))
+            .filter_map(|(_, object)| coin_type_and_value(object).map(|c| (object.owner, c)));
+        for (owner, (coin_type, value)) in old_coins {
+            *balances.entry((owner, coin_type.clone())).or_default() -= value as i128;
+        }
+        let new_coins = self
+            .written
+            .values()
+            .filter_map(|(_, object)| coin_type_and_value(object).map(|c| (object.owner, c)));
+        for (owner, (coin_type, value)) in new_coins {
+            *balances.entry((owner, coin_type.clone())).or_default() += value as i128;
+        }
+        balances
+            .into_iter()
+            .filter(|(_, amount)| *amount != 0)
+            .map(|((owner, coin_type), amount)| BalanceChange {
+                owner,
+                coin_type,
+                amount,
+            })
+            .collect()
+    }
+
+    fn object_changes(&self) -> Vec<ObjectChange> {
+        let mut changes = Vec::new();
+        for (id, ((_, version, digest), object)) in &self.written {
+            let (object_id, version, digest) = (*id, *version, *digest);
+            let object_type = match object.type_() {
+                Some(object_type) => object_type.clone(),
+                None => {
+                    changes.push(ObjectChange::Published {
+                        package_id: object_id,
+                        version,
+                        digest,
+                    });
+                    continue;
+                }
+            };
+            changes.push(match self.objects.get(id) {
+                None => ObjectChange::Created {
+                    object_id,
+                    object_type,
+                    owner: object.owner,
+                    version,
+                    digest,
+                },
+                Some(old_object) if old_object.owner != object.owner => ObjectChange::Transferred {
+                    object_id,
+                    object_type,
+                    previous_owner: old_object.owner,
+                    owner: object.owner,
+                    version,
+                    digest,
+                },
+                Some(old_object) => ObjectChange::Mutated {
+                    object_id,
+                    object_type,
+                    owner: object.owner,
+                    previous_version: old_object.version(),
+                    version,
+                    digest,
+                },
+            });
+        }
+        for (id, (version, kind)) in &self.deleted {
+            // Objects that were unwrapped and then deleted are not in the inputs. They were
+            // never visible outside of the object wrapping them, so we do not report them.
+            let object_type = match self.objects.get(id).and_then(Object::type_) {
+                Some(object_type) => object_type.clone(),
+                None => continue,
+            };
+            let (object_id, version) = (*id, *version);
+            changes.push(if kind == &DeleteKind::Wrap {
+                ObjectChange::Wrapped {
+                    object_id,
+                    object_type,
+                    version,
+                }
+            } else {
+                ObjectChange::Deleted {
+                    object_id,
+                    object_type,
+                    version,
+                }
+            });
+        }
+        changes
+    }
+
     /// Break up the structure and return its internal stores (objects, active_inputs, written, deleted)
     pub fn into_inner(self) -> InnerTemporaryStore {
         #[cfg(debug_assertions)]
@@ -116,6 +230,18 @@ impl<S> AuthorityTemporaryStore<S> {
         }
     }
 
+    /// Write `package`, the next version of a framework package among the inputs, as an epoch
+    /// change upgrades it. Packages are never written again once published otherwise.
+    pub fn upgrade_package(&mut self, mut package: Object) {
+        debug_assert!(self
+            .objects
+            .get(&package.id())
+            .map_or(false, Object::is_package));
+        package.previous_transaction = self.tx_digest;
+        self.written
+            .insert(package.id(), (package.compute_object_reference(), package));
+    }
+
     /// For every object changes, charge gas accordingly. Since by this point we haven't charged gas yet,
     /// the gas object hasn't been mutated yet. Passing in `gas_object_size` so that we can also charge
     /// for the gas object mutation in advance.
@@ -224,7 +350,7 @@ impl<S> AuthorityTemporaryStore<S> {
                 .deleted
                 .iter()
                 .filter_map(|(id, (version, kind))| {
-                    if kind != &DeleteKind::Wrap {
+                    if kind == &DeleteKind::Normal {
                         Some((*id, *version, ObjectDigest::OBJECT_DIGEST_DELETED))
                     } else {
                         None
@@ -242,6 +368,30 @@ impl<S> AuthorityTemporaryStore<S> {
                     }
                 })
                 .collect(),
+            unwrapped_then_deleted: self
+                .deleted
+                .iter()
+                .filter_map(|(id, (version, kind))| {
+                    if kind == &DeleteKind::UnwrapThenDelete {
+                        Some((*id, *version, ObjectDigest::OBJECT_DIGEST_DELETED))
+                    } else {
+                        None
+                    }
+                })
+                .collect(),
+            owner_changes: self
+                .written
+                .iter()
+                .filter_map(|(id, ((_, version, _), object))| {
+                    let old_object = self.objects.get(id)?;
+                    (old_object.owner != object.owner).then(|| OwnerChange {
+                        object_id: *id,
+                        version: *version,
+                        previous_owner: old_object.owner,
+                        owner: object.owner,
+                    })
+                })
+                .collect(),
             gas_object: updated_gas_object_info,
             events: self.events.clone(),
             dependencies: transaction_dependencies,
@@ -352,6 +502,43 @@ impl<S> Storage for AuthorityTemporaryStore<S> {
     fn log_event(&mut self, event: Event) {
         self.events.push(event)
     }
+
+    fn record_child_object(&mut self, object: Object) {
+        // A child written earlier in the transaction was not read from the store: it was
+        // created or unwrapped, or it is already recorded.
+        let id = object.id();
+        if self.objects.contains_key(&id) || self.written.contains_key(&id) {
+            return;
+        }
+        // The lock of the child is consumed like the lock of an owned input.
+        self.active_inputs.push(object.compute_object_reference());
+        self.objects.insert(id, object);
+    }
+
+    fn write_totals(&self) -> WriteTotals {
+        WriteTotals {
+            objects: self.written.len() as u64,
+            events: self.events.len() as u64,
+            bytes: self
+                .written
+                .values()
+                .map(|(_, object)| object.object_size_for_gas_metering() as u64)
+                .sum(),
+        }
+    }
+}
+
+impl<S: BackingPackageStore> ChildObjectResolver for AuthorityTemporaryStore<S> {
+    fn read_child_object(&self, parent: &ObjectID, child: &ObjectID) -> SuiResult<Option<Object>> {
+        if self.deleted.contains_key(child) {
+            return Ok(None);
+        }
+        let object = match self.read_object(child) {
+            Some(object) => Some(object.clone()),
+            None => self.package_store.get_object(child)?,
+        };
+        Ok(object.filter(|object| object.owner == Owner::ObjectOwner((*parent).into())))
+    }
 }
 
 impl<S: BackingPackageStore> ModuleResolver for AuthorityTemporaryStore<S> {
@@ -417,3 +604,10 @@ impl<S> ResourceResolver for AuthorityTemporaryStore<S> {
         }
     }
 }
+
+/// If `object` is a `Coin<T>`, returns `T` and the value of the coin.
+pub(crate) fn coin_type_and_value(object: &Object) -> Option<(&TypeTag, u64)> {
+    let coin_type = DenyList::coin_type_of_coin(object)?;
+    let coin: Coin = bcs::from_bytes(object.data.try_as_move()?.contents()).ok()?;
+    Some((coin_type, coin.value()))
+}
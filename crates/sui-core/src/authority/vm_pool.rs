@@ -0,0 +1,145 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! A pool of Move VMs, so that each certificate executing holds a VM of its own rather than
+//! all of them contending on the loader of a single VM, and no VM is constructed for a
+//! transaction. A VM is warmed as it is created, by loading the modules of the framework
+//! packages, which almost every transaction calls, and keeps the modules it loads as it goes
+//! back to the pool. The VMs are dropped once an epoch change upgrades the framework packages,
+//! as the modules they loaded are stale.
+
+use move_core_types::account_address::AccountAddress;
+use move_core_types::identifier::Identifier;
+use move_core_types::language_storage::ModuleId;
+use move_vm_runtime::{move_vm::MoveVM, native_functions::NativeFunctionTable};
+use parking_lot::Mutex;
+use std::ops::Deref;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use sui_adapter::adapter;
+use sui_types::base_types::{ObjectID, TransactionDigest};
+use sui_types::error::SuiResult;
+use sui_types::storage::BackingPackageStore;
+use sui_types::{MOVE_STDLIB_ADDRESS, SUI_FRAMEWORK_ADDRESS};
+use tracing::debug;
+
+use super::AuthorityTemporaryStore;
+
+#[cfg(test)]
+#[path = "../unit_tests/vm_pool_tests.rs"]
+mod vm_pool_tests;
+
+/// The number of idle VMs the pool keeps. The VMs created past it for a burst of executions are
+/// dropped as the burst ends.
+pub const DEFAULT_MAX_IDLE_MOVE_VMS: usize = 32;
+
+/// The packages whose modules a VM loads as it is created.
+const WARM_PACKAGES: [AccountAddress; 2] = [MOVE_STDLIB_ADDRESS, SUI_FRAMEWORK_ADDRESS];
+
+pub struct MoveVmPool {
+    natives: NativeFunctionTable,
+    idle: Mutex<Vec<Arc<MoveVM>>>,
+    max_idle: usize,
+    /// Incremented as the VMs are invalidated. A VM is only returned to the pool if it was
+    /// created since the last invalidation.
+    generation: AtomicU64,
+}
+
+/// A VM of the pool, returned to it when dropped.
+pub struct PooledMoveVm<'a> {
+    vm: Option<Arc<MoveVM>>,
+    generation: u64,
+    pool: &'a MoveVmPool,
+}
+
+impl Deref for PooledMoveVm<'_> {
+    type Target = Arc<MoveVM>;
+
+    fn deref(&self) -> &Arc<MoveVM> {
+        self.vm.as_ref().expect("The VM is only taken on drop")
+    }
+}
+
+impl Drop for PooledMoveVm<'_> {
+    fn drop(&mut self) {
+        if let Some(vm) = self.vm.take() {
+            let mut idle = self.pool.idle.lock();
+            if idle.len() < self.pool.max_idle
+                && self.generation == self.pool.generation.load(Ordering::SeqCst)
+            {
+                idle.push(vm);
+            }
+        }
+    }
+}
+
+impl MoveVmPool {
+    pub fn new(natives: NativeFunctionTable, max_idle: usize) -> Self {
+        Self {
+            natives,
+            idle: Mutex::new(Vec::new()),
+            max_idle,
+            generation: AtomicU64::new(0),
+        }
+    }
+
+    /// An idle VM, or a new one warmed with the framework packages of `store` if none is.
+    pub fn checkout<S: BackingPackageStore>(&self, store: &Arc<S>) -> SuiResult<PooledMoveVm<'_>> {
+        let (idle, generation) = {
+            let mut idle = self.idle.lock();
+            (idle.pop(), self.generation.load(Ordering::SeqCst))
+        };
+        let vm = match idle {
+            Some(vm) => vm,
+            None => {
+                let vm = Arc::new(adapter::new_move_vm(self.natives.clone())?);
+                warm(&vm, store);
+                vm
+            }
+        };
+        Ok(PooledMoveVm {
+            vm: Some(vm),
+            generation,
+            pool: self,
+        })
+    }
+
+    /// Drop the idle VMs, and the ones checked out as they are returned, for the VMs checked out
+    /// from now on to load the modules of the store anew.
+    pub fn invalidate(&self) {
+        let mut idle = self.idle.lock();
+        self.generation.fetch_add(1, Ordering::SeqCst);
+        idle.clear();
+    }
+
+    /// The number of idle VMs in the pool.
+    pub fn idle_count(&self) -> usize {
+        self.idle.lock().len()
+    }
+}
+
+/// Load the modules of the framework packages of `store` in `vm`. A module which fails to load
+/// is left for the transactions calling it to load, and to fail on.
+fn warm<S: BackingPackageStore>(vm: &MoveVM, store: &Arc<S>) {
+    let resolver =
+        AuthorityTemporaryStore::new(store.clone(), vec![], TransactionDigest::genesis());
+    for address in WARM_PACKAGES {
+        let package = match store.get_package(&ObjectID::from(address)) {
+            Ok(Some(package)) => package,
+            _ => continue,
+        };
+        let names = match package.data.try_as_package() {
+            Some(package) => package.serialized_module_map().keys().cloned().collect(),
+            None => Vec::new(),
+        };
+        for name in names {
+            let module_id = match Identifier::new(name) {
+                Ok(name) => ModuleId::new(address, name),
+                Err(_) => continue,
+            };
+            if let Err(error) = vm.load_module(&module_id, &resolver) {
+                debug!(?module_id, ?error, "Cannot warm the Move VM with a module");
+            }
+        }
+    }
+}
@@ -8,20 +8,25 @@ use std::{
     time::Duration,
 };
 
+use arc_swap::ArcSwap;
 use parking_lot::Mutex;
+use sui_storage::IndexStore;
 use sui_types::{
     base_types::{AuthorityName, ExecutionDigests, TransactionDigest},
-    error::SuiError,
+    committee::EpochId,
+    error::{SuiError, SuiResult},
     messages::{CertifiedTransaction, ConfirmationTransaction, TransactionInfoRequest},
     messages_checkpoint::{
-        AuthenticatedCheckpoint, AuthorityCheckpointInfo, CertifiedCheckpoint, CheckpointContents,
-        CheckpointDigest, CheckpointFragment, CheckpointRequest, CheckpointResponse,
-        CheckpointSequenceNumber, SignedCheckpoint, SignedCheckpointProposal,
+        AuthenticatedCheckpoint, AuthorityCheckpointInfo, CertifiedCheckpoint,
+        CertifiedEpochHandoff, CheckpointContents, CheckpointDigest, CheckpointFragment,
+        CheckpointParticipation, CheckpointRequest, CheckpointResponse, CheckpointSequenceNumber,
+        SignedCheckpoint, SignedCheckpointProposal, SignedEpochHandoff,
     },
 };
 use tokio::time::timeout;
 
 use crate::{
+    anomaly::{self, Anomaly},
     authority_aggregator::{AuthorityAggregator, ReduceOutput},
     authority_client::AuthorityAPI,
     checkpoints::{proposal::CheckpointProposal, CheckpointStore},
@@ -195,12 +200,9 @@ pub async fn checkpoint_process<A>(
         // (4) Check if we need to advance to the next checkpoint, in case >2/3
         // have a proposal out. If so we start creating and injecting fragments
         // into the consensus protocol to make the new checkpoint.
-        let weight: StakeUnit = proposals
-            .iter()
-            .map(|(auth, _)| committee.weight(auth))
-            .sum();
+        let weight = committee.weight_of(proposals.iter().map(|(auth, _)| auth));
 
-        let _start_checkpoint_making = weight > committee.quorum_threshold();
+        let _start_checkpoint_making = weight >= committee.quorum_threshold();
 
         let proposal = state_checkpoints.lock().new_proposal().clone();
         if let Ok(my_proposal) = proposal {
@@ -223,6 +225,199 @@ pub async fn checkpoint_process<A>(
     }
 }
 
+/// Follows the checkpoints of the committee on a node which takes no part in making them,
+/// such as a full node, recording each certified checkpoint in the indexes of the node with
+/// its contents and the authorities which took part in it. Each round reads the latest checkpoint and
+/// proposals of the authorities, records those proposing the next checkpoint, and downloads
+/// the certified checkpoints the node has not recorded yet. The number of checkpoints kept,
+/// if they are pruned, is read again each round, as the config of the node can change it.
+pub async fn follow_checkpoints<A>(
+    active_authority: &ActiveAuthority<A>,
+    timing: &CheckpointProcessControl,
+    pause_between_rounds: Duration,
+    checkpoints_retained: Arc<ArcSwap<Option<u64>>>,
+) where
+    A: AuthorityAPI + Send + Sync + 'static + Clone,
+{
+    let indexes = match active_authority.state.get_indexes() {
+        Ok(indexes) => indexes,
+        // Checkpoints are only recorded by nodes which index their state.
+        Err(_) => return,
+    };
+    info!("Start following checkpoints.");
+
+    // The authorities seen proposing each checkpoint not certified yet.
+    let mut proposers: BTreeMap<CheckpointSequenceNumber, BTreeSet<AuthorityName>> =
+        BTreeMap::new();
+    loop {
+        let net = active_authority.net.load().deref().clone();
+        let state_of_world = get_latest_proposal_and_checkpoint_from_all(
+            net.clone(),
+            timing.extra_time_after_quorum,
+            timing.timeout_until_quorum,
+        )
+        .await;
+        let (checkpoint, proposals) = match state_of_world {
+            Ok(state_of_world) => state_of_world,
+            Err(err) => {
+                warn!("Cannot get a quorum of checkpoint information: {:?}", err);
+                tokio::time::sleep(timing.delay_on_quorum_failure).await;
+                continue;
+            }
+        };
+
+        for (name, proposal) in proposals {
+            proposers
+                .entry(proposal.0.checkpoint.sequence_number)
+                .or_default()
+                .insert(name);
+        }
+        if let Some(checkpoint) = checkpoint {
+            if let Err(err) = record_checkpoints(&net, &indexes, checkpoint, &mut proposers).await {
+                warn!("Cannot follow checkpoints: {err:?}");
+                tokio::time::sleep(timing.delay_on_quorum_failure).await;
+                continue;
+            }
+        }
+        if let Some(checkpoints_retained) = **checkpoints_retained.load() {
+            // The last checkpoint recorded is always kept, to follow on from it.
+            let lowest_retained = indexes
+                .next_checkpoint()
+                .saturating_sub(checkpoints_retained.max(1));
+            if let Err(err) = indexes.prune_checkpoints(lowest_retained) {
+                warn!("Cannot prune checkpoints: {err:?}");
+            }
+        }
+        tokio::time::sleep(pause_between_rounds).await;
+    }
+}
+
+/// Records in `indexes` the certified checkpoints up to `latest` they are missing, with their
+/// contents, checking each one against the committee of `net` and against the digest of the
+/// one before it.
+async fn record_checkpoints<A>(
+    net: &Arc<AuthorityAggregator<A>>,
+    indexes: &IndexStore,
+    latest: CertifiedCheckpoint,
+    proposers: &mut BTreeMap<CheckpointSequenceNumber, BTreeSet<AuthorityName>>,
+) -> SuiResult
+where
+    A: AuthorityAPI + Send + Sync + 'static + Clone,
+{
+    let committee = &net.committee;
+    let latest_sequence_number = latest.checkpoint.sequence_number;
+    latest.verify(committee).map_err(|error| {
+        anomaly::report(Anomaly::checkpoint_signature_mismatch(
+            latest_sequence_number,
+            &error,
+        ));
+        error
+    })?;
+    let available_authorities: BTreeSet<_> = latest
+        .signatory_authorities(committee)?
+        .into_iter()
+        .cloned()
+        .collect();
+
+    let next_checkpoint = indexes.next_checkpoint();
+    let mut previous_digest = match next_checkpoint.checked_sub(1) {
+        Some(sequence_number) => indexes
+            .get_checkpoint(sequence_number)?
+            .map(|checkpoint| checkpoint.checkpoint.digest()),
+        None => None,
+    };
+    for sequence_number in next_checkpoint..=latest_sequence_number {
+        let (checkpoint, contents) = if sequence_number == latest_sequence_number {
+            let contents = get_contents_from_signers(net, &latest).await?;
+            (latest.clone(), Some(contents))
+        } else {
+            get_one_checkpoint(net.clone(), sequence_number, true, &available_authorities).await?
+        };
+        let contents = contents.ok_or_else(|| SuiError::GenericAuthorityError {
+            error: format!("No contents returned for checkpoint {sequence_number}"),
+        })?;
+        let verified = checkpoint
+            .verify_with_transactions(committee, &contents)
+            .and_then(|_| {
+                if previous_digest.is_some()
+                    && checkpoint.checkpoint.previous_digest != previous_digest
+                {
+                    return Err(SuiError::GenericAuthorityError {
+                        error: format!(
+                            "Checkpoint {sequence_number} does not follow the previous one"
+                        ),
+                    });
+                }
+                Ok(())
+            });
+        if let Err(error) = verified {
+            anomaly::report(Anomaly::checkpoint_signature_mismatch(
+                sequence_number,
+                &error,
+            ));
+            return Err(error);
+        }
+
+        let participation = CheckpointParticipation {
+            signers: checkpoint
+                .signatory_authorities(committee)?
+                .into_iter()
+                .cloned()
+                .collect(),
+            proposers: proposers
+                .remove(&sequence_number)
+                .unwrap_or_default()
+                .into_iter()
+                .collect(),
+        };
+        indexes.insert_checkpoint(committee, &checkpoint, &contents, &participation)?;
+        previous_digest = Some(checkpoint.checkpoint.digest());
+    }
+
+    // Proposals for checkpoints certified since are too late to go into them.
+    proposers.retain(|sequence_number, _| *sequence_number > latest_sequence_number);
+    Ok(())
+}
+
+/// Downloads the contents of `checkpoint` from one of the authorities which signed it. Unlike
+/// `get_one_checkpoint`, this does not need the authorities to have certified the checkpoint
+/// yet, only to have signed it.
+async fn get_contents_from_signers<A>(
+    net: &Arc<AuthorityAggregator<A>>,
+    checkpoint: &CertifiedCheckpoint,
+) -> SuiResult<CheckpointContents>
+where
+    A: AuthorityAPI + Send + Sync + 'static + Clone,
+{
+    for name in checkpoint.signatory_authorities(&net.committee)? {
+        match net
+            .clone_client(name)
+            .handle_checkpoint(CheckpointRequest::past(
+                checkpoint.checkpoint.sequence_number,
+                true,
+            ))
+            .await
+        {
+            Ok(CheckpointResponse {
+                detail: Some(contents),
+                ..
+            }) if contents.digest() == checkpoint.checkpoint.content_digest => {
+                return Ok(contents);
+            }
+            Ok(resp) => {
+                warn!("Sync Error: Unexpected answer: {resp:?}");
+            }
+            Err(err) => {
+                warn!("Sync Error: peer error: {err:?}");
+            }
+        }
+    }
+
+    Err(SuiError::GenericAuthorityError {
+        error: "Used all signers but did not get the contents of the checkpoint.".to_string(),
+    })
+}
+
 /// Reads the latest checkpoint / proposal info from all validators
 /// and extracts the latest checkpoint as well as the set of proposals
 pub async fn get_latest_proposal_and_checkpoint_from_all<A>(
@@ -362,10 +557,7 @@ where
     partial_checkpoints
         .iter()
         .for_each(|((_seq, _digest), signed)| {
-            let weight: StakeUnit = signed
-                .iter()
-                .map(|(auth, _)| net.committee.weight(auth))
-                .sum();
+            let weight = net.committee.weight_of(signed.iter().map(|(auth, _)| auth));
 
             // Reminder: a valid checkpoint only contains a validity threshold (1/3 N + 1) of signatures.
             //           The reason is that if >3/2 of node fragments are used to construct the checkpoint
@@ -407,6 +599,88 @@ where
     Ok((highest_certificate_cert, proposals))
 }
 
+/// Collects the handoff of `epoch` signed by the authorities of its committee, the committee of
+/// `net`, until a quorum of them signed the same next committee.
+pub async fn get_epoch_handoff_certificate<A>(
+    net: Arc<AuthorityAggregator<A>>,
+    epoch: EpochId,
+    timeout_until_quorum: Duration,
+) -> Result<CertifiedEpochHandoff, SuiError>
+where
+    A: AuthorityAPI + Send + Sync + 'static + Clone,
+{
+    #[derive(Default)]
+    struct EpochHandoffs {
+        bad_weight: StakeUnit,
+        // The signed handoffs, by the digest of the handoff.
+        signed: BTreeMap<[u8; 32], (StakeUnit, Vec<SignedEpochHandoff>)>,
+        certificate: Option<CertifiedEpochHandoff>,
+        errors: Vec<(AuthorityName, SuiError)>,
+    }
+    let committee = &net.committee;
+    let validity = committee.validity_threshold();
+    let final_state = net
+        .quorum_map_then_reduce_with_timeout(
+            EpochHandoffs::default(),
+            |_name, client| {
+                Box::pin(async move {
+                    client
+                        .handle_checkpoint(CheckpointRequest::epoch_handoff(epoch))
+                        .await
+                })
+            },
+            |mut state, name, weight, result| {
+                Box::pin(async move {
+                    let signed = match result {
+                        Ok(CheckpointResponse {
+                            info: AuthorityCheckpointInfo::EpochHandoff(Some(signed)),
+                            ..
+                        }) if signed.authority == name => signed.verify(committee).map(|_| signed),
+                        Ok(response) => Err(SuiError::GenericAuthorityError {
+                            error: format!("Unexpected message: {:?}", response),
+                        }),
+                        Err(err) => Err(err),
+                    };
+                    match signed {
+                        Ok(signed) => {
+                            let (signed_weight, handoffs) = state
+                                .signed
+                                .entry(signed.handoff.digest())
+                                .or_insert_with(|| (0, Vec::new()));
+                            *signed_weight += weight;
+                            handoffs.push(signed);
+                            if *signed_weight >= committee.quorum_threshold() {
+                                state.certificate = Some(CertifiedEpochHandoff::aggregate(
+                                    handoffs.clone(),
+                                    committee,
+                                )?);
+                                return Ok(ReduceOutput::End(state));
+                            }
+                        }
+                        Err(err) => {
+                            state.bad_weight += weight;
+                            state.errors.push((name, err));
+                            if state.bad_weight > validity {
+                                return Err(SuiError::TooManyIncorrectAuthorities {
+                                    errors: state.errors,
+                                });
+                            }
+                        }
+                    }
+                    Ok(ReduceOutput::Continue(state))
+                })
+            },
+            timeout_until_quorum,
+        )
+        .await?;
+
+    final_state
+        .certificate
+        .ok_or(SuiError::TooManyIncorrectAuthorities {
+            errors: final_state.errors,
+        })
+}
+
 /// Download all checkpoints that are not known to us
 pub async fn sync_to_checkpoint<A>(
     name: AuthorityName,
@@ -420,7 +694,8 @@ where
     // Get out last checkpoint
     let latest_checkpoint = checkpoint_db.lock().latest_stored_checkpoint()?;
     let available_authorities: BTreeSet<_> = latest_known_checkpoint
-        .signatory_authorities()
+        .signatory_authorities(&net.committee)?
+        .into_iter()
         .cloned()
         .collect();
 
@@ -527,8 +802,11 @@ pub async fn get_checkpoint_contents<A>(
 where
     A: AuthorityAPI + Send + Sync + 'static + Clone,
 {
-    let mut available_authorities: BTreeSet<_> =
-        checkpoint.signatory_authorities().cloned().collect();
+    let mut available_authorities: BTreeSet<_> = checkpoint
+        .signatory_authorities(&net.committee)?
+        .into_iter()
+        .cloned()
+        .collect();
     available_authorities.remove(&name);
 
     loop {
@@ -21,8 +21,9 @@ use sui_types::crypto::{get_key_pair, KeyPair, PublicKeyBytes};
 use sui_types::error::SuiError;
 use sui_types::messages::{
     AccountInfoRequest, AccountInfoResponse, BatchInfoRequest, BatchInfoResponseItem,
-    ConfirmationTransaction, ConsensusTransaction, ObjectInfoRequest, ObjectInfoResponse,
-    Transaction, TransactionInfoRequest, TransactionInfoResponse,
+    ConfirmationTransaction, ConsensusTransaction, DryRunTransactionRequest,
+    DryRunTransactionResponse, ObjectInfoRequest, ObjectInfoResponse, Transaction,
+    TransactionInfoRequest, TransactionInfoResponse,
 };
 use sui_types::messages_checkpoint::{CheckpointRequest, CheckpointResponse};
 use sui_types::object::Object;
@@ -203,6 +204,13 @@ impl AuthorityAPI for ConfigurableBatchActionClient {
     ) -> Result<CheckpointResponse, SuiError> {
         todo!();
     }
+
+    async fn handle_dry_run(
+        &self,
+        _request: DryRunTransactionRequest,
+    ) -> Result<DryRunTransactionResponse, SuiError> {
+        todo!();
+    }
 }
 
 #[cfg(test)]
@@ -224,13 +232,15 @@ pub async fn init_configurable_authorities(
     // Create committee.
     let mut key_pairs = Vec::new();
     let mut voting_rights = BTreeMap::new();
+    let mut protocol_keys = BTreeMap::new();
     for _ in 0..authority_count {
         let (_, key_pair) = get_key_pair();
         let authority_name = *key_pair.public_key_bytes();
         voting_rights.insert(authority_name, 1);
+        protocol_keys.insert(authority_name, key_pair.protocol_public_key());
         key_pairs.push((authority_name, key_pair));
     }
-    let committee = Committee::new(0, voting_rights);
+    let committee = Committee::new(0, voting_rights, protocol_keys);
 
     // Create Authority Clients and States.
     let mut clients = Vec::new();
@@ -260,13 +270,15 @@ pub async fn init_configurable_authorities(
             let transaction =
                 crate_object_move_transaction(addr1, &key1, addr1, 100, framework_obj_ref, gas_ref);
 
-            // TODO: `take` here only works when each validator has equal stake.
-            for tx_client in clients
-                .iter_mut()
-                .take(committee.quorum_threshold() as usize)
-            {
+            // Send the transaction to authorities with a quorum of stake.
+            let mut weight = 0;
+            for (tx_client, name) in clients.iter_mut().zip(&names) {
+                if weight >= committee.quorum_threshold() {
+                    break;
+                }
                 // Do transactions.
                 do_transaction(tx_client, &transaction).await;
+                weight += committee.weight(name);
             }
             // Add the digest and number to the internal actions.
             let t_b = TestBatch {
@@ -299,12 +311,13 @@ pub async fn init_configurable_authorities(
         let cert1 = extract_cert(authority_clients_slice, &committee, &digest).await;
 
         let mut effects_digest = TransactionEffectsDigest::random();
-        // Submit the cert to 2f+1 authorities.
-        for (_, cert_client) in authority_clients
-            .iter_mut()
-            // TODO: This only works when every validator has equal stake
-            .take(committee.quorum_threshold() as usize)
-        {
+        // Submit the cert to authorities with a quorum of stake.
+        let mut weight = 0;
+        for (name, cert_client) in authority_clients.iter_mut() {
+            if weight >= committee.quorum_threshold() {
+                break;
+            }
+            weight += committee.weight(name);
             let effects = do_cert(cert_client, &cert1).await;
             effects_digest = effects.digest();
 
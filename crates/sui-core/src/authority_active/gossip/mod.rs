@@ -2,26 +2,33 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
+    anomaly::{self, Anomaly},
     authority::AuthorityState,
-    authority_aggregator::{AuthorityAggregator, ConfirmationTransactionHandler},
+    authority_aggregator::{AuthorityAggregator, ConfirmationTransactionHandler, ReduceOutput},
     authority_client::AuthorityAPI,
     safe_client::SafeClient,
 };
+use arc_swap::ArcSwap;
 use async_trait::async_trait;
 use futures::stream::FuturesOrdered;
 use futures::{stream::FuturesUnordered, StreamExt};
 use std::future::Future;
 use std::ops::Deref;
-use std::{collections::HashSet, sync::Arc, time::Duration};
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+    time::Duration,
+};
+use sui_config::ForkDetection;
 use sui_storage::follower_store::FollowerStore;
 use sui_types::committee::StakeUnit;
 use sui_types::{
-    base_types::AuthorityName,
+    base_types::{AuthorityName, TransactionDigest, TransactionEffectsDigest},
     batch::{TxSequenceNumber, UpdateItem},
     error::{SuiError, SuiResult},
     messages::{
-        BatchInfoRequest, BatchInfoResponseItem, ConfirmationTransaction, TransactionInfoRequest,
-        TransactionInfoResponse,
+        BatchInfoRequest, BatchInfoResponseItem, ConfirmationTransaction, TransactionEffects,
+        TransactionInfoRequest, TransactionInfoResponse,
     },
 };
 use tracing::{debug, error, info};
@@ -39,11 +46,13 @@ struct PeerGossip<A> {
     follower_store: Arc<FollowerStore>,
     max_seq: Option<TxSequenceNumber>,
     aggregator: Arc<AuthorityAggregator<A>>,
+    fork_detection: Arc<ArcSwap<ForkDetection>>,
 }
 
 const EACH_ITEM_DELAY_MS: u64 = 1_000;
 const REQUEST_FOLLOW_NUM_DIGESTS: u64 = 100_000;
 const REFRESH_FOLLOWER_PERIOD_SECS: u64 = 60;
+const FORK_CONFIRMATION_TIMEOUT_SECS: u64 = 10;
 
 use super::ActiveAuthority;
 
@@ -51,13 +60,23 @@ pub async fn gossip_process<A>(active_authority: &ActiveAuthority<A>, degree: us
 where
     A: AuthorityAPI + Send + Sync + 'static + Clone,
 {
-    gossip_process_with_start_seq(active_authority, degree, None).await
+    gossip_process_with_start_seq(
+        active_authority,
+        degree,
+        None,
+        Arc::new(ArcSwap::from_pointee(ForkDetection::Off)),
+    )
+    .await
 }
 
+/// Gossip with the validators, comparing the effects of the transactions executed locally
+/// with the effects signed by the validators as set by `fork_detection`, which the config of
+/// the node can change while gossiping.
 pub async fn gossip_process_with_start_seq<A>(
     _active_authority: &ActiveAuthority<A>,
     degree: usize,
     start_seq: Option<TxSequenceNumber>,
+    fork_detection: Arc<ArcSwap<ForkDetection>>,
 ) where
     A: AuthorityAPI + Send + Sync + 'static + Clone,
 {
@@ -118,8 +137,10 @@ pub async fn gossip_process_with_start_seq<A>(
 
             peer_names.insert(name);
             let local_active_ref_copy = local_active.clone();
+            let fork_detection = fork_detection.clone();
             gossip_tasks.push(async move {
-                let peer_gossip = PeerGossip::new(name, &local_active_ref_copy, start_seq);
+                let peer_gossip =
+                    PeerGossip::new(name, &local_active_ref_copy, start_seq, fork_detection);
                 // Add more duration if we make more than 1 to ensure overlap
                 debug!(peer = ?name, "Starting gossip from peer");
                 peer_gossip
@@ -130,11 +151,8 @@ pub async fn gossip_process_with_start_seq<A>(
 
             // If we have already used all the good stake, then stop here and
             // wait for some node to become available.
-            let total_stake_used = peer_names
-                .iter()
-                .map(|name| committee.weight(name))
-                .sum::<StakeUnit>()
-                + committee.weight(&local_active.state.name);
+            let total_stake_used =
+                committee.weight_of(&peer_names) + committee.weight(&local_active.state.name);
             if total_stake_used >= committee.quorum_threshold() {
                 break;
             }
@@ -145,24 +163,36 @@ pub async fn gossip_process_with_start_seq<A>(
             continue;
         }
 
-        wait_for_one_gossip_task_to_finish(&local_active, &mut peer_names, &mut gossip_tasks).await;
+        if let Err(err) =
+            wait_for_one_gossip_task_to_finish(&local_active, &mut peer_names, &mut gossip_tasks)
+                .await
+        {
+            error!("Turning off gossip mechanism to halt at the fork: {err}");
+            return;
+        }
     }
 }
 
+/// Wait for a gossip task to finish, and fail if it has detected a fork to halt at.
 async fn wait_for_one_gossip_task_to_finish<A>(
     active_authority: &ActiveAuthority<A>,
     peer_names: &mut HashSet<AuthorityName>,
     gossip_tasks: &mut FuturesUnordered<
         impl Future<Output = (AuthorityName, Result<(), SuiError>)>,
     >,
-) where
+) -> SuiResult<()>
+where
     A: AuthorityAPI + Send + Sync + 'static + Clone,
 {
     let (finished_name, _result) = gossip_tasks.select_next_some().await;
+    peer_names.remove(&finished_name);
     if let Err(err) = _result {
         active_authority.set_failure_backoff(finished_name).await;
         active_authority.state.metrics.gossip_task_error_count.inc();
         error!(peer = ?finished_name, "Peer returned error: {:?}", err);
+        if matches!(err, SuiError::ForkDetected { .. }) {
+            return Err(err);
+        }
     } else {
         active_authority.set_success_backoff(finished_name).await;
         active_authority
@@ -172,7 +202,7 @@ async fn wait_for_one_gossip_task_to_finish<A>(
             .inc();
         debug!(peer = ?finished_name, "End gossip from peer");
     }
-    peer_names.remove(&finished_name);
+    Ok(())
 }
 
 struct LocalConfirmationTransactionHandler {
@@ -226,6 +256,7 @@ where
         peer_name: AuthorityName,
         active_authority: &ActiveAuthority<A>,
         start_seq: Option<TxSequenceNumber>,
+        fork_detection: Arc<ArcSwap<ForkDetection>>,
     ) -> PeerGossip<A> {
         // TODO: for validator gossip, we should always use None as the start_seq, but we should
         // consult the start_seq we retrieved from the db to make sure that the peer is giving
@@ -252,6 +283,7 @@ where
             follower_store: active_authority.follower_store.clone(),
             max_seq: start_seq,
             aggregator: active_authority.net.load().clone(),
+            fork_detection,
         }
     }
 
@@ -331,6 +363,7 @@ where
 
     async fn process_response(&self, response: TransactionInfoResponse) -> Result<(), SuiError> {
         if let Some(certificate) = response.certified_transaction {
+            let digest = *certificate.digest();
             // Process the certificate from one authority to ourselves
             self.aggregator
                 .sync_authority_source_to_destination(
@@ -342,7 +375,10 @@ where
                 )
                 .await?;
             self.state.metrics.gossip_sync_count.inc();
-            Ok(())
+            match response.signed_effects {
+                Some(signed_effects) => self.detect_fork(digest, &signed_effects.effects).await,
+                None => Ok(()),
+            }
         } else {
             // The authority did not return the certificate, despite returning info
             // But it should know the certificate!
@@ -351,4 +387,103 @@ where
             })
         }
     }
+    /// Compare the effects of the local execution of `digest` with the effects signed by the
+    /// peer, and alert on a divergence which the validators confirm. Fails to halt gossip if so
+    /// configured.
+    async fn detect_fork(
+        &self,
+        digest: TransactionDigest,
+        peer_effects: &TransactionEffects,
+    ) -> SuiResult<()> {
+        let fork_detection = **self.fork_detection.load();
+        if fork_detection == ForkDetection::Off {
+            return Ok(());
+        }
+        let local_effects = self.state.database.get_effects(&digest)?.digest();
+        if local_effects == peer_effects.digest() {
+            return Ok(());
+        }
+        // A faulty peer can sign any effects, so that the local effects have forked only if
+        // a validity threshold of the validators signed the same other effects.
+        let peer_effects = match self.confirmed_effects(digest, local_effects).await? {
+            Some(peer_effects) => peer_effects,
+            None => {
+                debug!(
+                    peer = ?self.peer_name,
+                    ?digest,
+                    "The validators don't confirm the effects signed by the peer"
+                );
+                return Ok(());
+            }
+        };
+        self.state.metrics.fork_detected_count.inc();
+        anomaly::report(Anomaly::ForkDetected {
+            digest,
+            local_effects,
+            peer_effects,
+            authority: self.peer_name,
+        });
+        let error = SuiError::ForkDetected {
+            digest,
+            local_effects,
+            peer_effects,
+            authority: self.peer_name,
+        };
+        match fork_detection {
+            ForkDetection::Halt => Err(error),
+            ForkDetection::Alert | ForkDetection::Off => Ok(()),
+        }
+    }
+
+    /// The effects of `digest` other than `local_effects` which validators with a validity
+    /// threshold of stake signed, if any. As one of them at least is honest, the effects are
+    /// the ones the validators certify.
+    async fn confirmed_effects(
+        &self,
+        digest: TransactionDigest,
+        local_effects: TransactionEffectsDigest,
+    ) -> SuiResult<Option<TransactionEffectsDigest>> {
+        let validity = self.aggregator.committee.validity_threshold();
+        let threshold = self.aggregator.committee.quorum_threshold();
+        let stakes = self
+            .aggregator
+            .quorum_map_then_reduce_with_timeout(
+                HashMap::<TransactionEffectsDigest, StakeUnit>::new(),
+                |_name, client| {
+                    Box::pin(async move {
+                        client
+                            .handle_transaction_info_request(TransactionInfoRequest::from(digest))
+                            .await
+                    })
+                },
+                |mut stakes, _name, weight, result| {
+                    Box::pin(async move {
+                        // The signatures of the effects are checked by the safe client.
+                        if let Ok(TransactionInfoResponse {
+                            signed_effects: Some(signed_effects),
+                            ..
+                        }) = result
+                        {
+                            let effects = signed_effects.effects.digest();
+                            let stake = stakes.entry(effects).or_default();
+                            *stake += weight;
+                            // Either the other effects are confirmed, or the validators which
+                            // haven't signed the local effects can't confirm any others.
+                            if (effects != local_effects && *stake >= validity)
+                                || (effects == local_effects && *stake >= threshold)
+                            {
+                                return Ok(ReduceOutput::End(stakes));
+                            }
+                        }
+                        Ok(ReduceOutput::Continue(stakes))
+                    })
+                },
+                Duration::from_secs(FORK_CONFIRMATION_TIMEOUT_SECS),
+            )
+            .await?;
+        Ok(stakes
+            .into_iter()
+            .find(|(effects, stake)| *effects != local_effects && *stake >= validity)
+            .map(|(effects, _)| effects))
+    }
 }
@@ -274,9 +274,9 @@ where
         let mut candidate_source_authorties: HashSet<AuthorityName> = cert
             .certificate
             .auth_sign_info
-            .signatures
-            .iter()
-            .map(|(name, _)| *name)
+            .authorities(&self.committee)?
+            .into_iter()
+            .cloned()
             .collect();
 
         // Sample a `retries` number of distinct authorities by stake.
@@ -942,12 +942,14 @@ where
                                         .observe(state.signatures.len() as f64);
                                     self.metrics.num_good_stake.observe(state.good_stake as f64);
                                     self.metrics.num_bad_stake.observe(state.bad_stake as f64);
-                                    state.certificate =
-                                        Some(CertifiedTransaction::new_with_signatures(
-                                            self.committee.epoch(),
-                                            transaction_ref.clone(),
-                                            state.signatures.clone(),
-                                        ));
+                                    match CertifiedTransaction::new_with_signatures(
+                                        transaction_ref.clone(),
+                                        &state.signatures,
+                                        &self.committee,
+                                    ) {
+                                        Ok(certificate) => state.certificate = Some(certificate),
+                                        Err(err) => state.errors.push(err),
+                                    }
                                 }
                             }
                             // If we get back an error, then we aggregate and check
@@ -1225,6 +1227,41 @@ where
         Ok((new_certificate, response))
     }
 
+    /// Dry run `transaction_data` on an authority picked by stake, trying the others in turn
+    /// if it can't be reached, and profile its gas if `profile_gas`. No quorum is involved, as
+    /// nothing is signed or committed.
+    pub async fn dry_run_transaction(
+        &self,
+        transaction_data: TransactionData,
+        profile_gas: bool,
+    ) -> SuiResult<DryRunTransactionResponse> {
+        let sampled = *self.committee.sample();
+        let names = std::iter::once(sampled).chain(
+            self.authority_clients
+                .keys()
+                .copied()
+                .filter(move |name| *name != sampled),
+        );
+        let mut errors = vec![];
+        for name in names {
+            let request = DryRunTransactionRequest {
+                data: transaction_data.clone(),
+                profile_gas,
+            };
+            match timeout(
+                AUTHORITY_REQUEST_TIMEOUT,
+                self.authority_clients[&name].handle_dry_run(request),
+            )
+            .await
+            {
+                Ok(Ok(response)) => return Ok(response),
+                Ok(Err(err)) => errors.push((name, err)),
+                Err(_) => errors.push((name, SuiError::RpcError("Dry run timed out".to_string()))),
+            }
+        }
+        Err(SuiError::TooManyIncorrectAuthorities { errors })
+    }
+
     pub async fn get_object_info_execute(&self, object_id: ObjectID) -> SuiResult<ObjectRead> {
         let (object_map, cert_map) = self
             .get_object_by_id(object_id, AUTHORITY_REQUEST_TIMEOUT)
@@ -1234,10 +1271,9 @@ where
         while let Some(((obj_ref, tx_digest), (obj_option, layout_option, authorities))) =
             object_ref_stack.pop()
         {
-            let stake: StakeUnit = authorities
-                .iter()
-                .map(|(name, _)| self.committee.weight(name))
-                .sum();
+            let stake = self
+                .committee
+                .weight_of(authorities.iter().map(|(name, _)| name));
 
             let mut is_ok = false;
             if stake >= self.committee.validity_threshold() {
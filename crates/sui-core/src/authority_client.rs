@@ -6,9 +6,11 @@ use crate::authority::AuthorityState;
 use async_trait::async_trait;
 use futures::{stream::BoxStream, TryStreamExt};
 use multiaddr::Multiaddr;
+use serde::Serialize;
 use std::sync::Arc;
 
 use sui_network::{api::ValidatorClient, tonic};
+use sui_types::message_chunks::{MessageChunk, MAX_UNCHUNKED_MESSAGE_SIZE, MESSAGE_CHUNK_SIZE};
 use sui_types::{error::SuiError, messages::*};
 
 use sui_types::messages_checkpoint::{CheckpointRequest, CheckpointResponse};
@@ -67,6 +69,12 @@ pub trait AuthorityAPI {
         request: BatchInfoRequest,
     ) -> Result<BatchInfoResponseItemStream, SuiError>;
 
+    /// Execute a transaction without committing it.
+    async fn handle_dry_run(
+        &self,
+        request: DryRunTransactionRequest,
+    ) -> Result<DryRunTransactionResponse, SuiError>;
+
     async fn handle_checkpoint(
         &self,
         request: CheckpointRequest,
@@ -102,6 +110,15 @@ impl NetworkAuthorityClient {
     }
 }
 
+/// The chunks to send `message` in, if it is too large to send whole.
+fn chunks_of<T: Serialize>(message: &T) -> Result<Option<Vec<MessageChunk>>, SuiError> {
+    let size = bincode::serialized_size(message).map_err(|_| SuiError::InvalidDecoding)?;
+    if size as usize <= MAX_UNCHUNKED_MESSAGE_SIZE {
+        return Ok(None);
+    }
+    MessageChunk::split(message, MESSAGE_CHUNK_SIZE).map(Some)
+}
+
 #[async_trait]
 impl AuthorityAPI for NetworkAuthorityClient {
     /// Initiate a new transfer to a Sui or Primary account.
@@ -109,6 +126,14 @@ impl AuthorityAPI for NetworkAuthorityClient {
         &self,
         transaction: Transaction,
     ) -> Result<TransactionInfoResponse, SuiError> {
+        if let Some(chunks) = chunks_of(&transaction)? {
+            return self
+                .client()
+                .transaction_chunked(futures::stream::iter(chunks))
+                .await
+                .map(tonic::Response::into_inner)
+                .map_err(Into::into);
+        }
         self.client()
             .transaction(transaction)
             .await
@@ -121,6 +146,14 @@ impl AuthorityAPI for NetworkAuthorityClient {
         &self,
         transaction: ConfirmationTransaction,
     ) -> Result<TransactionInfoResponse, SuiError> {
+        if let Some(chunks) = chunks_of(&transaction.certificate)? {
+            return self
+                .client()
+                .confirmation_transaction_chunked(futures::stream::iter(chunks))
+                .await
+                .map(tonic::Response::into_inner)
+                .map_err(Into::into);
+        }
         self.client()
             .confirmation_transaction(transaction.certificate)
             .await
@@ -199,6 +232,17 @@ impl AuthorityAPI for NetworkAuthorityClient {
             .map(tonic::Response::into_inner)
             .map_err(Into::into)
     }
+
+    async fn handle_dry_run(
+        &self,
+        request: DryRunTransactionRequest,
+    ) -> Result<DryRunTransactionResponse, SuiError> {
+        self.client()
+            .dry_run(request)
+            .await
+            .map(tonic::Response::into_inner)
+            .map_err(Into::into)
+    }
 }
 
 #[derive(Clone, Copy, Default)]
@@ -314,6 +358,15 @@ impl AuthorityAPI for LocalAuthorityClient {
 
         state.handle_checkpoint_request(&request)
     }
+
+    async fn handle_dry_run(
+        &self,
+        request: DryRunTransactionRequest,
+    ) -> Result<DryRunTransactionResponse, SuiError> {
+        self.state
+            .dry_run_transaction(request.data, request.profile_gas)
+            .await
+    }
 }
 
 impl LocalAuthorityClient {
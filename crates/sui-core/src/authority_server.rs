@@ -3,7 +3,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    authority::AuthorityState,
+    authority::{AuthorityMetrics, AuthorityState},
     consensus_adapter::{
         CheckpointConsensusAdapter, CheckpointSender, ConsensusAdapter, ConsensusListener,
         ConsensusListenerMessage,
@@ -15,15 +15,20 @@ use async_trait::async_trait;
 use futures::{stream::BoxStream, TryStreamExt};
 use multiaddr::Multiaddr;
 use std::{io, sync::Arc, time::Duration};
-use sui_config::NodeConfig;
+use sui_config::{NodeConfig, ValidatorServerLimits};
 use sui_network::{
     api::{Validator, ValidatorServer},
     tonic,
 };
 
+use serde::de::DeserializeOwned;
+use sui_types::message_chunks::{MessageChunk, MessageChunkJoiner};
 use sui_types::{crypto::VerificationObligation, error::*, messages::*};
 use tokio::{
-    sync::mpsc::{channel, Sender},
+    sync::{
+        mpsc::{channel, Sender},
+        Semaphore, SemaphorePermit,
+    },
     task::JoinHandle,
 };
 
@@ -39,6 +44,99 @@ mod server_tests;
 const MIN_BATCH_SIZE: u64 = 1000;
 const MAX_DELAY_MILLIS: u64 = 5_000; // 5 sec
 
+/// The kinds of requests a validator serves concurrently up to a cap of their own, so that a
+/// flood of one kind cannot starve the others.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Lane {
+    Transaction,
+    /// Dry runs execute the transaction, so they have a lane of their own rather than sharing
+    /// the one of the transactions to sign.
+    DryRun,
+    Certificate,
+    Query,
+}
+
+impl Lane {
+    fn name(&self) -> &'static str {
+        match self {
+            Lane::Transaction => "transaction",
+            Lane::DryRun => "dry_run",
+            Lane::Certificate => "certificate",
+            Lane::Query => "query",
+        }
+    }
+}
+
+/// The requests in flight in each lane, refused past the cap of the lane.
+pub struct RequestLanes {
+    transactions: Semaphore,
+    dry_runs: Semaphore,
+    certificates: Semaphore,
+    queries: Semaphore,
+    metrics: &'static AuthorityMetrics,
+}
+
+/// A request in flight in a lane, until dropped.
+pub struct LanePermit<'a> {
+    _permit: SemaphorePermit<'a>,
+    lane: Lane,
+    metrics: &'static AuthorityMetrics,
+}
+
+impl Drop for LanePermit<'_> {
+    fn drop(&mut self) {
+        self.metrics
+            .server_requests_inflight
+            .with_label_values(&[self.lane.name()])
+            .dec();
+    }
+}
+
+impl RequestLanes {
+    pub fn new(limits: &ValidatorServerLimits, metrics: &'static AuthorityMetrics) -> Self {
+        Self {
+            transactions: Semaphore::new(limits.max_inflight_transactions),
+            dry_runs: Semaphore::new(limits.max_inflight_dry_runs),
+            certificates: Semaphore::new(limits.max_inflight_certificates),
+            queries: Semaphore::new(limits.max_inflight_queries),
+            metrics,
+        }
+    }
+
+    /// Serve a request in `lane`, or refuse it if the lane is full.
+    pub fn enter(&self, lane: Lane) -> Result<LanePermit<'_>, tonic::Status> {
+        let semaphore = match lane {
+            Lane::Transaction => &self.transactions,
+            Lane::DryRun => &self.dry_runs,
+            Lane::Certificate => &self.certificates,
+            Lane::Query => &self.queries,
+        };
+        match semaphore.try_acquire() {
+            Ok(permit) => {
+                self.metrics
+                    .server_requests_inflight
+                    .with_label_values(&[lane.name()])
+                    .inc();
+                Ok(LanePermit {
+                    _permit: permit,
+                    lane,
+                    metrics: self.metrics,
+                })
+            }
+            Err(_) => {
+                self.metrics
+                    .server_requests_refused
+                    .with_label_values(&[lane.name()])
+                    .inc();
+                Err(tonic::Status::resource_exhausted(format!(
+                    "Too many {} requests in flight, retry later",
+                    lane.name()
+                )))
+            }
+        }
+    }
+}
+
 pub struct AuthorityServerHandle {
     tx_cancellation: tokio::sync::oneshot::Sender<()>,
     local_addr: Multiaddr,
@@ -133,6 +231,7 @@ impl AuthorityServer {
         let mut server = mysten_network::config::Config::new()
             .server_builder()
             .add_service(ValidatorServer::new(ValidatorService {
+                lanes: RequestLanes::new(&ValidatorServerLimits::default(), self.state.metrics),
                 state: self.state,
                 consensus_adapter: self.consensus_adapter,
                 _checkpoint_consensus_handle: None,
@@ -153,6 +252,7 @@ impl AuthorityServer {
 
 pub struct ValidatorService {
     state: Arc<AuthorityState>,
+    lanes: RequestLanes,
     consensus_adapter: ConsensusAdapter,
     _checkpoint_consensus_handle: Option<JoinHandle<()>>,
 }
@@ -229,21 +329,17 @@ impl ValidatorService {
         };
 
         Ok(Self {
+            lanes: RequestLanes::new(&config.validator_server_limits, state.metrics),
             state,
             consensus_adapter,
             _checkpoint_consensus_handle: checkpoint_consensus_handle,
         })
     }
-}
 
-#[async_trait]
-impl Validator for ValidatorService {
-    async fn transaction(
+    async fn handle_transaction(
         &self,
-        request: tonic::Request<Transaction>,
+        mut transaction: Transaction,
     ) -> Result<tonic::Response<TransactionInfoResponse>, tonic::Status> {
-        let mut transaction = request.into_inner();
-
         let mut obligation = VerificationObligation::default();
         transaction
             .add_tx_sig_to_verification_obligation(&mut obligation)
@@ -273,18 +369,13 @@ impl Validator for ValidatorService {
         Ok(tonic::Response::new(info))
     }
 
-    async fn confirmation_transaction(
+    async fn handle_certificate(
         &self,
-        request: tonic::Request<CertifiedTransaction>,
+        mut transaction: CertifiedTransaction,
     ) -> Result<tonic::Response<TransactionInfoResponse>, tonic::Status> {
-        let mut transaction = request.into_inner();
-
-        let mut obligation = VerificationObligation::default();
-        transaction
-            .add_to_verification_obligation(&self.state.committee.load(), &mut obligation)
-            .map_err(|e| tonic::Status::invalid_argument(e.to_string()))?;
-        obligation
-            .verify_all()
+        self.state
+            .verify_certificate(&transaction)
+            .await
             .map_err(|e| tonic::Status::invalid_argument(e.to_string()))?;
         //TODO This is really really bad, we should have different types for signature verified transactions
         transaction.is_verified = true;
@@ -310,10 +401,75 @@ impl Validator for ValidatorService {
         Ok(tonic::Response::new(info))
     }
 
+    /// Join the chunks of a message as they are received, if the protocol version of the
+    /// validator accepts messages sent in chunks.
+    async fn join_chunks<T: DeserializeOwned>(
+        &self,
+        mut chunks: tonic::Streaming<MessageChunk>,
+    ) -> Result<T, tonic::Status> {
+        let protocol_config = self.state.protocol_config();
+        if !protocol_config.chunked_messages {
+            let error = SuiError::UnsupportedFeatureError {
+                error: format!(
+                    "chunked messages in protocol version {}",
+                    protocol_config.version
+                ),
+            };
+            return Err(tonic::Status::invalid_argument(error.to_string()));
+        }
+        let mut joiner = MessageChunkJoiner::new(protocol_config.max_chunked_message_size as usize);
+        while let Some(chunk) = chunks.message().await? {
+            joiner
+                .push(chunk)
+                .map_err(|e| tonic::Status::invalid_argument(e.to_string()))?;
+        }
+        joiner
+            .finish()
+            .map_err(|e| tonic::Status::invalid_argument(e.to_string()))
+    }
+}
+
+#[async_trait]
+impl Validator for ValidatorService {
+    async fn transaction(
+        &self,
+        request: tonic::Request<Transaction>,
+    ) -> Result<tonic::Response<TransactionInfoResponse>, tonic::Status> {
+        let _permit = self.lanes.enter(Lane::Transaction)?;
+        self.handle_transaction(request.into_inner()).await
+    }
+
+    async fn transaction_chunked(
+        &self,
+        request: tonic::Request<tonic::Streaming<MessageChunk>>,
+    ) -> Result<tonic::Response<TransactionInfoResponse>, tonic::Status> {
+        let _permit = self.lanes.enter(Lane::Transaction)?;
+        let transaction = self.join_chunks(request.into_inner()).await?;
+        self.handle_transaction(transaction).await
+    }
+
+    async fn confirmation_transaction(
+        &self,
+        request: tonic::Request<CertifiedTransaction>,
+    ) -> Result<tonic::Response<TransactionInfoResponse>, tonic::Status> {
+        let _permit = self.lanes.enter(Lane::Certificate)?;
+        self.handle_certificate(request.into_inner()).await
+    }
+
+    async fn confirmation_transaction_chunked(
+        &self,
+        request: tonic::Request<tonic::Streaming<MessageChunk>>,
+    ) -> Result<tonic::Response<TransactionInfoResponse>, tonic::Status> {
+        let _permit = self.lanes.enter(Lane::Certificate)?;
+        let certificate = self.join_chunks(request.into_inner()).await?;
+        self.handle_certificate(certificate).await
+    }
+
     async fn consensus_transaction(
         &self,
         request: tonic::Request<ConsensusTransaction>,
     ) -> Result<tonic::Response<TransactionInfoResponse>, tonic::Status> {
+        let _permit = self.lanes.enter(Lane::Certificate)?;
         let transaction = request.into_inner();
         let certificate = match transaction.clone() {
             ConsensusTransaction::UserTransaction(certificate) => certificate,
@@ -347,6 +503,7 @@ impl Validator for ValidatorService {
         &self,
         request: tonic::Request<AccountInfoRequest>,
     ) -> Result<tonic::Response<AccountInfoResponse>, tonic::Status> {
+        let _permit = self.lanes.enter(Lane::Query)?;
         let request = request.into_inner();
 
         let response = self
@@ -362,6 +519,7 @@ impl Validator for ValidatorService {
         &self,
         request: tonic::Request<ObjectInfoRequest>,
     ) -> Result<tonic::Response<ObjectInfoResponse>, tonic::Status> {
+        let _permit = self.lanes.enter(Lane::Query)?;
         let request = request.into_inner();
 
         let response = self
@@ -377,6 +535,7 @@ impl Validator for ValidatorService {
         &self,
         request: tonic::Request<TransactionInfoRequest>,
     ) -> Result<tonic::Response<TransactionInfoResponse>, tonic::Status> {
+        let _permit = self.lanes.enter(Lane::Query)?;
         let request = request.into_inner();
 
         let response = self
@@ -388,6 +547,22 @@ impl Validator for ValidatorService {
         Ok(tonic::Response::new(response))
     }
 
+    async fn dry_run(
+        &self,
+        request: tonic::Request<DryRunTransactionRequest>,
+    ) -> Result<tonic::Response<DryRunTransactionResponse>, tonic::Status> {
+        let _permit = self.lanes.enter(Lane::DryRun)?;
+        let request = request.into_inner();
+
+        let response = self
+            .state
+            .dry_run_transaction(request.data, request.profile_gas)
+            .await
+            .map_err(|e| tonic::Status::internal(e.to_string()))?;
+
+        Ok(tonic::Response::new(response))
+    }
+
     type BatchInfoStream = BoxStream<'static, Result<BatchInfoResponseItem, tonic::Status>>;
 
     async fn batch_info(
@@ -411,6 +586,7 @@ impl Validator for ValidatorService {
         &self,
         request: tonic::Request<CheckpointRequest>,
     ) -> Result<tonic::Response<CheckpointResponse>, tonic::Status> {
+        let _permit = self.lanes.enter(Lane::Query)?;
         let request = request.into_inner();
 
         let response = self
@@ -0,0 +1,146 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! The sidecar of a validator in the bridge of SUI to other chains. It signs the transfers from
+//! Sui, which the validator sees locked in the effects it executes, and the transfers to Sui,
+//! which it reads from the other chain, and publishes the signatures for relayers to aggregate
+//! into certificates: a certificate of a transfer from Sui releases the tokens on the other
+//! chain, and one of a transfer to Sui lets its recipient claim the SUI in `Sui::Bridge`.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use sui_types::batch::UpdateItem;
+use sui_types::bridge::{
+    BridgeChainId, BridgeTransfer, SignedBridgeTransfer, TokensLocked, SUI_CHAIN_ID,
+};
+use sui_types::error::SuiResult;
+use tokio::sync::broadcast::error::RecvError;
+use tokio::task::JoinHandle;
+use tracing::{debug, error, warn};
+
+use crate::authority::AuthorityState;
+
+/// How often the sidecar reads the new transfers of the other chain.
+const POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// The other chain of the bridge, as a validator sees it through the node it runs for it.
+#[async_trait]
+pub trait BridgeChain: Send + Sync {
+    /// The ID of the chain among the chains of the bridge.
+    fn chain_id(&self) -> BridgeChainId;
+
+    /// The transfers to Sui locked on the chain, from the one with nonce `from_nonce`, in the
+    /// order of their nonces. Only final transfers are returned.
+    async fn locked_transfers(&self, from_nonce: u64) -> SuiResult<Vec<BridgeTransfer>>;
+
+    /// Publish the signature of a transfer between Sui and the chain, in either direction.
+    async fn publish_signature(&self, signed: SignedBridgeTransfer) -> SuiResult;
+}
+
+pub struct BridgeSidecar<C> {
+    state: Arc<AuthorityState>,
+    chain: C,
+}
+
+impl<C: BridgeChain + 'static> BridgeSidecar<C> {
+    pub fn new(state: Arc<AuthorityState>, chain: C) -> Self {
+        Self { state, chain }
+    }
+
+    pub fn spawn(self) -> JoinHandle<()> {
+        let sidecar = Arc::new(self);
+        let outbound = sidecar.clone();
+        tokio::spawn(async move {
+            tokio::join!(outbound.sign_outbound(), sidecar.sign_inbound());
+        })
+    }
+
+    fn sign(&self, transfer: BridgeTransfer) -> SignedBridgeTransfer {
+        SignedBridgeTransfer::new(transfer, self.state.name, &*self.state.secret)
+    }
+
+    /// Sign the transfers to the chain locked by the transactions the validator executes.
+    async fn sign_outbound(&self) {
+        let mut subscriber = self.state.subscribe_batch();
+        loop {
+            let digests = match subscriber.recv().await {
+                Ok(UpdateItem::Transaction((_, digests))) => digests,
+                Ok(UpdateItem::Batch(_)) => continue,
+                Err(RecvError::Lagged(skipped)) => {
+                    // TODO: Catch up on the skipped transactions from the database.
+                    warn!(
+                        skipped,
+                        "Bridge sidecar lagged behind the executed transactions"
+                    );
+                    continue;
+                }
+                Err(RecvError::Closed) => return,
+            };
+            let effects = match self.state.database.get_effects(&digests.transaction) {
+                Ok(effects) => effects,
+                Err(err) => {
+                    error!(
+                        ?digests,
+                        ?err,
+                        "Failed to read the effects of a transaction"
+                    );
+                    continue;
+                }
+            };
+            for locked in effects.events.iter().filter_map(TokensLocked::from_event) {
+                if locked.target_chain != self.chain.chain_id() {
+                    continue;
+                }
+                let signed = self.sign(locked.into());
+                debug!(
+                    nonce = signed.transfer.nonce,
+                    "Signed a bridge transfer from Sui"
+                );
+                if let Err(err) = self.chain.publish_signature(signed).await {
+                    error!(?err, "Failed to publish the signature of a bridge transfer");
+                }
+            }
+        }
+    }
+
+    /// Sign the transfers to Sui locked on the chain.
+    async fn sign_inbound(&self) {
+        let mut next_nonce = 0;
+        let mut interval = tokio::time::interval(POLL_INTERVAL);
+        loop {
+            interval.tick().await;
+            let transfers = match self.chain.locked_transfers(next_nonce).await {
+                Ok(transfers) => transfers,
+                Err(err) => {
+                    warn!(
+                        ?err,
+                        "Failed to read the bridge transfers of the other chain"
+                    );
+                    continue;
+                }
+            };
+            for transfer in transfers {
+                if transfer.nonce < next_nonce {
+                    continue;
+                }
+                next_nonce = transfer.nonce + 1;
+                // Transfers to a third chain are for its validators to sign.
+                if transfer.source_chain != self.chain.chain_id()
+                    || transfer.target_chain != SUI_CHAIN_ID
+                {
+                    continue;
+                }
+                let signed = self.sign(transfer);
+                debug!(
+                    nonce = signed.transfer.nonce,
+                    "Signed a bridge transfer to Sui"
+                );
+                if let Err(err) = self.chain.publish_signature(signed).await {
+                    error!(?err, "Failed to publish the signature of a bridge transfer");
+                }
+            }
+        }
+    }
+}
@@ -16,15 +16,17 @@ use sui_storage::default_db_options;
 use sui_types::{
     base_types::{AuthorityName, ExecutionDigests},
     batch::TxSequenceNumber,
-    committee::Committee,
+    committee::{Committee, EpochId},
     error::SuiError,
     fp_ensure,
     messages::CertifiedTransaction,
     messages_checkpoint::{
         AuthenticatedCheckpoint, AuthorityCheckpointInfo, CertifiedCheckpoint, CheckpointContents,
         CheckpointDigest, CheckpointFragment, CheckpointRequest, CheckpointResponse,
-        CheckpointSequenceNumber, CheckpointSummary, SignedCheckpoint, SignedCheckpointProposal,
+        CheckpointSequenceNumber, CheckpointSummary, EpochHandoff, SignedCheckpoint,
+        SignedCheckpointProposal, SignedEpochHandoff,
     },
+    snapshot::LiveObjectsDigest,
 };
 use typed_store::{
     reopen,
@@ -110,6 +112,9 @@ pub struct CheckpointStore {
     /// The list of checkpoint, along with their authentication information
     pub checkpoints: DBMap<CheckpointSequenceNumber, AuthenticatedCheckpoint>,
 
+    /// The handoffs to the next committee signed by this authority, by the epoch which ended.
+    pub epoch_handoffs: DBMap<EpochId, SignedEpochHandoff>,
+
     // --- Logic related to fragments on the way to making checkpoints
 
     // A list of own fragments indexed by the other node that the fragment connects
@@ -246,6 +251,7 @@ impl CheckpointStore {
                 ("unprocessed_contents", &point_lookup),
                 ("extra_transactions", &point_lookup),
                 ("checkpoints", &point_lookup),
+                ("epoch_handoffs", &point_lookup),
                 ("local_fragments", &point_lookup),
                 ("fragments", &options),
                 ("locals", &point_lookup),
@@ -260,6 +266,7 @@ impl CheckpointStore {
             unprocessed_contents,
             extra_transactions,
             checkpoints,
+            epoch_handoffs,
             local_fragments,
             fragments,
             locals,
@@ -271,6 +278,7 @@ impl CheckpointStore {
             "unprocessed_contents";<ExecutionDigests, CertifiedTransaction>,
             "extra_transactions";<ExecutionDigests,TxSequenceNumber>,
             "checkpoints";<CheckpointSequenceNumber, AuthenticatedCheckpoint>,
+            "epoch_handoffs";<EpochId, SignedEpochHandoff>,
             "local_fragments";<AuthorityName, CheckpointFragment>,
             "fragments";<ExecutionIndices, CheckpointFragment>,
             "locals";<DBLabel, CheckpointLocals>
@@ -285,6 +293,7 @@ impl CheckpointStore {
             unprocessed_contents,
             extra_transactions,
             checkpoints,
+            epoch_handoffs,
             local_fragments,
             fragments,
             memory_locals: ArcSwapOption::from(None),
@@ -386,6 +395,39 @@ impl CheckpointStore {
         })
     }
 
+    pub fn handle_epoch_handoff(&self, epoch: EpochId) -> Result<CheckpointResponse, SuiError> {
+        Ok(CheckpointResponse {
+            info: AuthorityCheckpointInfo::EpochHandoff(self.epoch_handoffs.get(&epoch)?),
+            detail: None,
+        })
+    }
+
+    /// Call this function internally at the end of an epoch, once the committee of the next
+    /// epoch is known, to sign the handoff to it and to the objects live at the end of the
+    /// epoch. Only the first handoff of an epoch is signed, so that an authority never signs two
+    /// handoffs for the same epoch.
+    pub fn sign_epoch_handoff(
+        &mut self,
+        epoch: EpochId,
+        next_committee: &Committee,
+        live_objects_digest: LiveObjectsDigest,
+    ) -> Result<SignedEpochHandoff, SuiError> {
+        let handoff = EpochHandoff::new(epoch, next_committee.clone(), live_objects_digest);
+        if let Some(signed) = self.epoch_handoffs.get(&epoch)? {
+            fp_ensure!(
+                signed.handoff == handoff,
+                SuiError::InvalidEpochHandoff {
+                    epoch,
+                    error: "Another handoff was already signed for this epoch".to_string(),
+                }
+            );
+            return Ok(signed);
+        }
+        let signed = SignedEpochHandoff::new(handoff, self.name, &*self.secret);
+        self.epoch_handoffs.insert(&epoch, &signed)?;
+        Ok(signed)
+    }
+
     /// Call this function internally to update the latest checkpoint.
     /// Internally it is called with an unsigned checkpoint, and results
     /// in the checkpoint being signed, stored and the contents
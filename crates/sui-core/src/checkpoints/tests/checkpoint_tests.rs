@@ -290,7 +290,7 @@ fn make_diffs() {
 
 #[test]
 fn latest_proposal() {
-    let (_committee, _keys, mut stores) = random_ckpoint_store();
+    let (committee, _keys, mut stores) = random_ckpoint_store();
     let (_, mut cps1) = stores.pop().unwrap();
     let (_, mut cps2) = stores.pop().unwrap();
     let (_, mut cps3) = stores.pop().unwrap();
@@ -354,7 +354,10 @@ fn latest_proposal() {
         assert!(matches!(previous, AuthenticatedCheckpoint::None));
 
         let current_proposal = current.unwrap();
-        current_proposal.0.verify().expect("no signature error");
+        current_proposal
+            .0
+            .verify(&committee)
+            .expect("no signature error");
         assert_eq!(*current_proposal.0.checkpoint.sequence_number(), 0);
     }
 
@@ -375,7 +378,7 @@ fn latest_proposal() {
         let current_proposal = current.unwrap();
         current_proposal
             .0
-            .verify_with_transactions(response.detail.as_ref().unwrap())
+            .verify_with_transactions(&committee, response.detail.as_ref().unwrap())
             .expect("no signature error");
         assert_eq!(*current_proposal.0.checkpoint.sequence_number(), 0);
     }
@@ -466,7 +469,10 @@ fn latest_proposal() {
         assert!(matches!(previous, AuthenticatedCheckpoint::Signed { .. }));
 
         let current_proposal = current.unwrap();
-        current_proposal.0.verify().expect("no signature error");
+        current_proposal
+            .0
+            .verify(&committee)
+            .expect("no signature error");
         assert_eq!(*current_proposal.0.checkpoint.sequence_number(), 1);
     }
 }
@@ -550,7 +556,7 @@ fn set_get_checkpoint() {
     ));
     if let AuthorityCheckpointInfo::Past(AuthenticatedCheckpoint::Signed(signed)) = response.info {
         signed
-            .verify_with_transactions(&response.detail.unwrap())
+            .verify_with_transactions(&committee, &response.detail.unwrap())
             .unwrap();
     }
 
@@ -0,0 +1,205 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Cross-validator consistency checks, to find which validators are out of sync during an
+//! incident. Every validator is asked for the state of some objects and transactions, and their
+//! answers are grouped, each group weighed by the stake of the validators answering it.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::time::Duration;
+
+use futures::future::join_all;
+use sui_types::base_types::{
+    AuthorityName, ObjectDigest, ObjectID, SequenceNumber, TransactionDigest,
+    TransactionEffectsDigest,
+};
+use sui_types::committee::{Committee, StakeUnit};
+use sui_types::error::SuiError;
+use sui_types::messages::{ObjectInfoRequest, TransactionInfoRequest};
+use sui_types::object::Owner;
+
+use crate::authority_aggregator::AuthorityAggregator;
+use crate::authority_client::AuthorityAPI;
+use crate::safe_client::SafeClient;
+
+#[cfg(test)]
+#[path = "unit_tests/consistency_tests.rs"]
+mod consistency_tests;
+
+/// What the validators are asked about.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Subject {
+    Object(ObjectID),
+    Transaction(TransactionDigest),
+}
+
+impl fmt::Display for Subject {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Subject::Object(id) => write!(f, "object {id}"),
+            Subject::Transaction(digest) => write!(f, "transaction {digest:?}"),
+        }
+    }
+}
+
+/// The answer of a validator about a subject.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum View {
+    Object {
+        version: SequenceNumber,
+        digest: ObjectDigest,
+        owner: Owner,
+    },
+    /// The validator does not have the object, as it was deleted or never synced.
+    ObjectMissing,
+    Executed {
+        effects: TransactionEffectsDigest,
+    },
+    /// The validator signed or certified the transaction but did not execute it.
+    NotExecuted,
+    /// The validator does not know the transaction.
+    TransactionMissing,
+    Error(String),
+}
+
+impl fmt::Display for View {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            View::Object {
+                version,
+                digest,
+                owner,
+            } => write!(f, "version {version}, digest {digest:?}, owner {owner}"),
+            View::ObjectMissing => write!(f, "no object"),
+            View::Executed { effects } => write!(f, "effects {effects:?}"),
+            View::NotExecuted => write!(f, "signed, not executed"),
+            View::TransactionMissing => write!(f, "no transaction"),
+            View::Error(error) => write!(f, "error: {error}"),
+        }
+    }
+}
+
+/// The validators answering the same about a subject, and their stake.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ViewGroup {
+    pub view: View,
+    pub stake: StakeUnit,
+    pub authorities: Vec<AuthorityName>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ConsistencyReport {
+    pub subject: Subject,
+    pub total_stake: StakeUnit,
+    /// The answers of the validators, from the one with the most stake to the one with the
+    /// least.
+    pub groups: Vec<ViewGroup>,
+}
+
+impl ConsistencyReport {
+    /// Group the `answers` of the validators of `committee` about `subject`.
+    pub fn new(
+        subject: Subject,
+        committee: &Committee,
+        answers: Vec<(AuthorityName, View)>,
+    ) -> Self {
+        let mut groups: BTreeMap<View, ViewGroup> = BTreeMap::new();
+        for (authority, view) in answers {
+            let group = groups.entry(view.clone()).or_insert_with(|| ViewGroup {
+                view,
+                stake: 0,
+                authorities: Vec::new(),
+            });
+            group.stake += committee.weight(&authority);
+            group.authorities.push(authority);
+        }
+        let mut groups: Vec<_> = groups.into_values().collect();
+        groups.sort_by(|a, b| b.stake.cmp(&a.stake));
+        Self {
+            subject,
+            total_stake: committee.total_votes,
+            groups,
+        }
+    }
+
+    /// Whether all the validators answered the same.
+    pub fn is_consistent(&self) -> bool {
+        self.groups.len() <= 1
+    }
+
+    /// The validators which do not answer as the validators with the most stake do.
+    pub fn outliers(&self) -> impl Iterator<Item = &AuthorityName> {
+        self.groups
+            .iter()
+            .skip(1)
+            .flat_map(|group| group.authorities.iter())
+    }
+}
+
+/// Ask every validator of `net` about each of `subjects`, each within `timeout`.
+pub async fn check_consistency<A>(
+    net: &AuthorityAggregator<A>,
+    subjects: &[Subject],
+    timeout: Duration,
+) -> Vec<ConsistencyReport>
+where
+    A: AuthorityAPI + Send + Sync + 'static + Clone,
+{
+    let mut reports = Vec::new();
+    for subject in subjects {
+        let answers = join_all(
+            net.authority_clients
+                .iter()
+                .map(|(name, client)| async move {
+                    let view = match tokio::time::timeout(timeout, view_of(client, *subject)).await
+                    {
+                        Ok(Ok(view)) => view,
+                        Ok(Err(error)) => View::Error(error.to_string()),
+                        Err(_) => View::Error("timed out".to_string()),
+                    };
+                    (*name, view)
+                }),
+        )
+        .await;
+        reports.push(ConsistencyReport::new(*subject, &net.committee, answers));
+    }
+    reports
+}
+
+async fn view_of<A>(client: &SafeClient<A>, subject: Subject) -> Result<View, SuiError>
+where
+    A: AuthorityAPI + Send + Sync + 'static + Clone,
+{
+    match subject {
+        Subject::Object(id) => {
+            let response = client
+                .handle_object_info_request(ObjectInfoRequest::latest_object_info_request(id, None))
+                .await?;
+            Ok(match response.object() {
+                Some(object) => View::Object {
+                    version: object.version(),
+                    digest: object.digest(),
+                    owner: object.owner,
+                },
+                None => View::ObjectMissing,
+            })
+        }
+        Subject::Transaction(digest) => {
+            let response = client
+                .handle_transaction_info_request(TransactionInfoRequest::from(digest))
+                .await?;
+            Ok(match response.signed_effects {
+                Some(effects) => View::Executed {
+                    effects: effects.effects.digest(),
+                },
+                None if response.signed_transaction.is_some()
+                    || response.certified_transaction.is_some() =>
+                {
+                    View::NotExecuted
+                }
+                None => View::TransactionMissing,
+            })
+        }
+    }
+}
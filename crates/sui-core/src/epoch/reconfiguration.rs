@@ -4,14 +4,26 @@
 use crate::authority_active::ActiveAuthority;
 use crate::authority_aggregator::AuthorityAggregator;
 use crate::authority_client::AuthorityAPI;
+use std::collections::BTreeMap;
 use std::sync::atomic::Ordering;
 use std::sync::Arc;
 use std::time::Duration;
+use sui_adapter::adapter;
+use sui_types::bls::{ProtocolKeyProof, ProtocolPublicKeyBytes};
 use sui_types::committee::Committee;
 use sui_types::crypto::PublicKeyBytes;
-use sui_types::error::SuiResult;
-use sui_types::messages::{ConfirmationTransaction, SignedTransaction};
+use sui_types::error::{SuiError, SuiResult};
+use sui_types::messages::{
+    framework_digest, ConfirmationTransaction, SignedTransaction, SystemPackage,
+};
 use sui_types::messages_checkpoint::CheckpointSequenceNumber;
+use sui_types::protocol_config::{ProtocolConfig, ProtocolVersion};
+use sui_types::snapshot::live_objects_digest;
+use sui_types::storage::BackingPackageStore;
+use sui_types::sui_serde::{Encoding, Hex};
+use sui_types::sui_system_state::SuiSystemState;
+use tokio::sync::Notify;
+use tracing::{error, info, warn};
 use typed_store::Map;
 
 // TODO: Make last checkpoint number of each epoch more flexible.
@@ -44,10 +56,7 @@ where
             unreachable!();
         }
 
-        self.state.halted.store(true, Ordering::SeqCst);
-        while !self.state.batch_notifier.ticket_drained() {
-            tokio::time::sleep(Duration::from_millis(10)).await;
-        }
+        self.halt_validator().await;
         Ok(())
     }
 
@@ -79,20 +88,97 @@ where
             unreachable!();
         }
 
+        self.change_epoch().await
+    }
+
+    /// Change epoch right away, without waiting for the last checkpoint of the epoch. For local
+    /// and testing networks only, as nothing ensures that the transactions the validator executed
+    /// in the epoch are in its checkpoints. Each validator of the committee changes epoch on its
+    /// own, and the change completes once a quorum of them did.
+    pub async fn advance_epoch(&self) -> SuiResult {
+        self.halt_validator().await;
+        self.change_epoch().await
+    }
+
+    /// Advance the epoch every `epoch_duration`, and whenever `trigger` is notified.
+    pub async fn advance_epoch_process(&self, epoch_duration: Duration, trigger: Arc<Notify>) {
+        loop {
+            tokio::select! {
+                _ = tokio::time::sleep(epoch_duration) => {}
+                _ = trigger.notified() => {}
+            }
+            let epoch = self.state.committee.load().epoch;
+            info!("Advancing from epoch {epoch}");
+            if let Err(err) = self.advance_epoch().await {
+                error!("Cannot advance from epoch {epoch}: {err}");
+            }
+        }
+    }
+
+    /// Stop accepting transactions, and wait for the ones being executed.
+    async fn halt_validator(&self) {
+        self.state.halted.store(true, Ordering::SeqCst);
+        while !self.state.batch_notifier.ticket_drained() {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    }
+
+    /// Form the committee of the next epoch, execute the transaction which advances to it, and
+    /// resume the halted validator.
+    async fn change_epoch(&self) -> SuiResult {
         let sui_system_state = self.state.get_sui_system_state_object().await?;
+        let framework_packages = self.framework_upgrade(&sui_system_state)?;
         let next_epoch = sui_system_state.epoch + 1;
         let next_epoch_validators = &sui_system_state.validators.next_epoch_validators;
-        let votes = next_epoch_validators
-            .iter()
-            .map(|metadata| {
-                (
-                    PublicKeyBytes::try_from(metadata.pubkey_bytes.as_ref())
-                        .expect("Validity of public key bytes should be verified on-chain"),
-                    metadata.next_epoch_stake,
-                )
-            })
-            .collect();
-        let new_committee = Committee::new(next_epoch, votes);
+        let current_committee = self.state.committee.load();
+        let mut votes = BTreeMap::new();
+        let mut protocol_keys = BTreeMap::new();
+        for metadata in next_epoch_validators {
+            let name = PublicKeyBytes::try_from(metadata.pubkey_bytes.as_ref())
+                .expect("Validity of public key bytes should be verified on-chain");
+            let protocol_key =
+                ProtocolPublicKeyBytes::try_from(metadata.protocol_pubkey_bytes.as_ref())
+                    .expect("The length of protocol keys should be verified on-chain");
+            // The chain cannot check proofs of possession, so every validator checks them before
+            // admitting a protocol key to the committee: a key that no one holds would let its
+            // owner forge the aggregate signatures of the other validators. The keys of the
+            // current committee were checked when they were admitted.
+            if current_committee.protocol_keys.get(&name) != Some(&protocol_key) {
+                let proof = ProtocolKeyProof::try_from(metadata.protocol_key_proof.as_ref());
+                if let Err(error) = proof.and_then(|proof| proof.verify(&protocol_key)) {
+                    warn!(
+                        "Validator {:?} left out of the committee of epoch {next_epoch}: invalid proof of possession of its protocol key: {error}",
+                        name
+                    );
+                    continue;
+                }
+            }
+            votes.insert(name, metadata.next_epoch_stake);
+            protocol_keys.insert(name, protocol_key);
+        }
+        if votes.is_empty() {
+            return Err(SuiError::GenericAuthorityError {
+                error: format!("No validator of epoch {next_epoch} holds a valid protocol key"),
+            });
+        }
+        let new_committee = Committee::new(next_epoch, votes, protocol_keys);
+        // The authorities of the epoch which ends hand it off to the next committee, for light
+        // clients to follow the committees from one epoch to the next. The validator is halted
+        // and executed every transaction of the epoch, so the objects live now are the ones
+        // live at the end of the epoch.
+        if current_committee.weight(&self.state.name) > 0 {
+            if let Some(checkpoints) = &self.state.checkpoints {
+                let live_object_refs = self
+                    .state
+                    .db()
+                    .live_object_refs_at_end_of_epoch(sui_system_state.epoch)?;
+                checkpoints.lock().sign_epoch_handoff(
+                    sui_system_state.epoch,
+                    &new_committee,
+                    live_objects_digest(&live_object_refs),
+                )?;
+            }
+        }
         self.state.insert_new_epoch_info(&new_committee)?;
         let new_net = Arc::new(AuthorityAggregator::new(
             new_committee,
@@ -112,6 +198,7 @@ where
             next_epoch,
             0, // TODO: fill in storage_charge
             0, // TODO: fill in computation_charge
+            framework_packages,
             self.state.name,
             &*self.state.secret,
         );
@@ -140,11 +227,80 @@ where
             tokio::time::sleep(WAIT_BETWEEN_EPOCH_TX_QUERY_RETRY).await;
         }
 
+        // The new epoch may run another version of the protocol, which its transactions are
+        // metered with.
+        self.state.reload_protocol_config().await?;
+
+        // The transactions signed in the epoch but not executed can't be certified anymore, and
+        // their certificates are refused from now on, so their objects are unlocked for the
+        // transactions of the new epoch. Their senders submit them again to have them signed.
+        let released = self.state.db().release_pending_transaction_locks().await?;
+        if !released.is_empty() {
+            info!(
+                ?released,
+                "Released the locks of {} transactions of epoch {} which were not executed",
+                released.len(),
+                sui_system_state.epoch
+            );
+        }
+
+        // Every transaction of the epoch is executed, so the new epoch starts with tables of its
+        // own, and the ones of the epoch before this one are dropped.
+        self.state.db().start_epoch_tables(next_epoch)?;
+
         // Resume the validator to start accepting transactions for the new epoch.
         self.state.unhalt_validator()?;
         Ok(())
     }
 
+    /// The framework packages the epoch change upgrades to: the ones of this build, if the
+    /// validators voted for upgrading to them. Every validator builds the same transaction to
+    /// change epoch, so a validator whose build doesn't have the framework voted for, or doesn't
+    /// support the protocol version it runs under, can't change epoch until it runs a build
+    /// which does. The validators all leave the upgrade out if it is incompatible with the
+    /// framework on chain.
+    fn framework_upgrade(
+        &self,
+        sui_system_state: &SuiSystemState,
+    ) -> SuiResult<Vec<SystemPackage>> {
+        let upgrade = match sui_system_state.approved_framework_upgrade() {
+            Some(upgrade) => upgrade,
+            None => return Ok(Vec::new()),
+        };
+        let packages = sui_framework::get_system_packages();
+        if framework_digest(&packages)[..] != upgrade.digest[..] {
+            return Err(SuiError::UnknownFrameworkUpgrade {
+                digest: Hex::encode(&upgrade.digest),
+            });
+        }
+        ProtocolConfig::get_for_version(ProtocolVersion::new(upgrade.protocol_version))?;
+        for package in &packages {
+            let current = self.state.db().get_package(&package.id)?;
+            let result = match current
+                .as_ref()
+                .and_then(|object| object.data.try_as_package())
+            {
+                Some(current) => adapter::verify_framework_upgrade(current, &package.modules),
+                None => Err(SuiError::DependentPackageNotFound {
+                    package_id: package.id,
+                }),
+            };
+            if let Err(error) = result {
+                error!(
+                    "Leaving out the framework upgrade of epoch {}: {error}",
+                    sui_system_state.epoch
+                );
+                return Ok(Vec::new());
+            }
+        }
+        info!(
+            "Upgrading the framework to digest {} at the end of epoch {}",
+            Hex::encode(&upgrade.digest),
+            sui_system_state.epoch
+        );
+        Ok(packages)
+    }
+
     pub fn is_last_checkpoint_epoch(checkpoint: CheckpointSequenceNumber) -> bool {
         checkpoint > 0 && checkpoint % CHECKPOINT_COUNT_PER_EPOCH == 0
     }
@@ -15,16 +15,20 @@ use sui_types::{
     crypto::{get_key_pair, AuthoritySignature, Signature},
     error::SuiError,
     gas::SuiGasStatus,
+    light_client::verify_committee_chain,
     messages::{ConfirmationTransaction, SignatureAggregator, Transaction, TransactionData},
     object::Object,
     SUI_SYSTEM_STATE_OBJECT_ID,
 };
 
 use crate::{
-    authority::AuthorityTemporaryStore, authority_active::ActiveAuthority,
+    authority::AuthorityTemporaryStore,
+    authority_active::{checkpoint_driver::get_epoch_handoff_certificate, ActiveAuthority},
     authority_aggregator::authority_aggregator_tests::init_local_authorities,
-    checkpoints::CheckpointLocals, epoch::reconfiguration::CHECKPOINT_COUNT_PER_EPOCH,
+    checkpoints::CheckpointLocals,
+    epoch::reconfiguration::CHECKPOINT_COUNT_PER_EPOCH,
     execution_engine,
+    snapshot::export_snapshot,
 };
 
 #[tokio::test]
@@ -145,9 +149,9 @@ async fn test_start_epoch_change() {
         transaction.data.clone(),
         tx_digest,
         BTreeSet::new(),
-        &state.move_vm,
+        &state.move_vms.checkout(&state.database).unwrap(),
         &state._native_functions,
-        SuiGasStatus::new_with_budget(1000, 1, 1),
+        &mut SuiGasStatus::new_with_budget(1000, 1, 1),
         state.committee.load().epoch,
     )
     .unwrap();
@@ -220,6 +224,31 @@ async fn test_finish_epoch_change() {
         .collect();
     futures::future::join_all(results).await;
 
+    // The committee of the first epoch handed it off to the committee of the next one.
+    let genesis_committee = net.committee.clone();
+    let handoff = get_epoch_handoff_certificate(Arc::new(net), 0, Duration::from_secs(10))
+        .await
+        .unwrap();
+    assert_eq!(
+        verify_committee_chain(genesis_committee.clone(), [&handoff]).unwrap(),
+        **states[0].committee.load()
+    );
+    // The handoff is not one of the next committee.
+    let next_committee = (**states[0].committee.load()).clone();
+    assert!(verify_committee_chain(next_committee.clone(), [&handoff]).is_err());
+
+    // The handoff commits to the objects live at the end of the epoch, before the transaction
+    // which started the next one, so a snapshot of them verifies against it.
+    let snapshot = export_snapshot(&states[0].db(), vec![handoff]).unwrap();
+    assert!(snapshot
+        .objects
+        .iter()
+        .any(|object| object.id() == SUI_SYSTEM_STATE_OBJECT_ID));
+    assert_eq!(
+        snapshot.verify(genesis_committee.clone()).unwrap(),
+        next_committee
+    );
+
     // Verify that epoch changed in every authority state.
     for active in actives {
         assert_eq!(active.state.committee.load().epoch, 1);
@@ -254,3 +283,82 @@ async fn test_finish_epoch_change() {
         assert!(response.signed_effects.is_some());
     }
 }
+
+#[tokio::test]
+async fn test_epoch_change_releases_pending_transactions() {
+    // Create a sender, owning an object and a gas object.
+    let (sender, sender_key) = get_key_pair();
+    let object = Object::with_id_owner_for_testing(ObjectID::random(), sender);
+    let gas_object = Object::with_id_owner_for_testing(ObjectID::random(), sender);
+    let genesis_objects = vec![object.clone(), gas_object.clone()];
+    let (net, states) = init_local_authorities(vec![
+        genesis_objects.clone(),
+        genesis_objects.clone(),
+        genesis_objects.clone(),
+        genesis_objects.clone(),
+    ])
+    .await;
+
+    // Every validator signs a transaction of the first epoch, which is certified but never
+    // executed, so it holds the locks of its objects when the epoch changes.
+    let tx_data = TransactionData::new_transfer(
+        SuiAddress::default(),
+        object.compute_object_reference(),
+        sender,
+        gas_object.compute_object_reference(),
+        1000,
+    );
+    let signature = Signature::new(&tx_data, &sender_key);
+    let transaction = Transaction::new(tx_data, signature);
+    let mut sigs = SignatureAggregator::try_new(transaction.clone(), &net.committee).unwrap();
+    let mut cert = None;
+    for state in &states {
+        state.handle_transaction(transaction.clone()).await.unwrap();
+        cert = sigs
+            .append(
+                state.name,
+                AuthoritySignature::new(&transaction.data, &*state.secret),
+            )
+            .unwrap();
+    }
+    let certificate = cert.unwrap();
+
+    let actives: Vec<_> = states
+        .iter()
+        .map(|state| {
+            ActiveAuthority::new_with_ephemeral_follower_store(
+                state.clone(),
+                net.clone_inner_clients(),
+            )
+            .unwrap()
+        })
+        .collect();
+    futures::future::join_all(actives.iter().map(|active| active.advance_epoch()))
+        .await
+        .into_iter()
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+
+    for state in &states {
+        assert_eq!(state.committee.load().epoch, 1);
+        // The certificate of the first epoch is refused, with an error to retry on.
+        assert_eq!(
+            state
+                .handle_confirmation_transaction(ConfirmationTransaction {
+                    certificate: certificate.clone()
+                })
+                .await
+                .unwrap_err(),
+            SuiError::EpochEnded { epoch: 0 }
+        );
+        // The objects of the transaction are unlocked, and the transaction is signed again in
+        // the new epoch.
+        assert!(state
+            .get_transaction_lock(&object.compute_object_reference())
+            .await
+            .unwrap()
+            .is_none());
+        let info = state.handle_transaction(transaction.clone()).await.unwrap();
+        assert_eq!(info.signed_transaction.unwrap().auth_sign_info.epoch, 1);
+    }
+}
@@ -1,10 +1,9 @@
 // Copyright (c) 2022, Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::authority::{AuthorityStore, AuthorityStoreWrapper};
+use crate::authority::AuthorityStore;
 use crate::streamer::Streamer;
 use chrono::prelude::*;
-use move_bytecode_utils::module_cache::SyncModuleCache;
 use std::convert::TryFrom;
 use std::sync::Arc;
 use sui_types::{
@@ -23,7 +22,7 @@ pub fn get_unixtime_ms() -> u64 {
 }
 
 pub struct EventHandler {
-    module_cache: SyncModuleCache<AuthorityStoreWrapper>,
+    store: Arc<AuthorityStore>,
     streamer_queue: Sender<EventEnvelope>,
 }
 
@@ -32,7 +31,7 @@ impl EventHandler {
         let (tx, rx) = mpsc::channel::<EventEnvelope>(EVENT_DISPATCH_BUFFER_SIZE);
         Streamer::spawn(rx);
         Self {
-            module_cache: SyncModuleCache::new(AuthorityStoreWrapper(validator_store)),
+            store: validator_store,
             streamer_queue: tx,
         }
     }
@@ -50,7 +49,7 @@ impl EventHandler {
         let envolope = match event {
             Event::MoveEvent { .. } => {
                 debug!(event =? event, "Process MoveEvent.");
-                match event.extract_move_struct(&self.module_cache) {
+                match event.extract_move_struct(&*self.store) {
                     Ok(Some(move_struct)) => {
                         let json_value = serde_json::to_value(&move_struct).map_err(|e| {
                             SuiError::ObjectSerializationError {
@@ -9,10 +9,10 @@ use move_vm_runtime::{move_vm::MoveVM, native_functions::NativeFunctionTable};
 use sui_adapter::adapter;
 use sui_types::committee::EpochId;
 use sui_types::gas_coin::GasCoin;
-use sui_types::object::{MoveObject, Owner, OBJECT_START_VERSION};
+use sui_types::object::{Data, MoveObject, Owner, OBJECT_START_VERSION};
 use sui_types::{
-    base_types::{ObjectID, ObjectRef, SuiAddress, TransactionDigest, TxContext},
-    error::SuiResult,
+    base_types::{ObjectDigest, ObjectID, ObjectRef, SuiAddress, TransactionDigest, TxContext},
+    error::{SuiError, SuiResult},
     event::{Event, TransferType},
     gas::{self, SuiGasStatus},
     messages::{
@@ -35,15 +35,20 @@ pub fn execute_transaction_to_effects<S: BackingPackageStore>(
     mut transaction_dependencies: BTreeSet<TransactionDigest>,
     move_vm: &Arc<MoveVM>,
     native_functions: &NativeFunctionTable,
-    gas_status: SuiGasStatus,
+    gas_status: &mut SuiGasStatus,
     epoch: EpochId,
 ) -> SuiResult<TransactionEffects> {
     let mut tx_ctx = TxContext::new(&transaction_data.signer(), &transaction_digest, epoch);
 
     let gas_object_ref = *transaction_data.gas_payment_object_ref();
+    let deleted_shared_object = shared_object_refs
+        .iter()
+        .find(|(_, _, digest)| *digest == ObjectDigest::OBJECT_DIGEST_DELETED)
+        .map(|(object_id, _, _)| *object_id);
     let status = execute_transaction(
         temporary_store,
         transaction_data,
+        deleted_shared_object,
         gas_object_ref.0,
         &mut tx_ctx,
         move_vm,
@@ -59,6 +64,14 @@ pub fn execute_transaction_to_effects<S: BackingPackageStore>(
         status
     );
 
+    // The transaction also depends on the transactions which last wrote the child objects it
+    // loaded during execution, rather than received among its inputs.
+    transaction_dependencies.extend(
+        temporary_store
+            .objects()
+            .values()
+            .map(|object| object.previous_transaction),
+    );
     // Remove from dependencies the generic hash
     transaction_dependencies.remove(&TransactionDigest::genesis());
 
@@ -87,19 +100,25 @@ fn charge_gas_for_object_read<S>(
     gas_status.charge_storage_read(total_size)
 }
 
+/// Execute `transaction_data`, or only charge its gas and fail with `SharedObjectDeleted` if
+/// `deleted_shared_object`, one of its shared objects, was deleted before it.
 #[instrument(name = "tx_execute", level = "debug", skip_all)]
 fn execute_transaction<S: BackingPackageStore>(
     temporary_store: &mut AuthorityTemporaryStore<S>,
     transaction_data: TransactionData,
+    deleted_shared_object: Option<ObjectID>,
     gas_object_id: ObjectID,
     tx_ctx: &mut TxContext,
     move_vm: &Arc<MoveVM>,
     native_functions: &NativeFunctionTable,
-    mut gas_status: SuiGasStatus,
+    gas_status: &mut SuiGasStatus,
 ) -> ExecutionStatus {
     // We must charge object read gas inside here during transaction execution, because if this fails
     // we must still ensure an effect is committed and all objects versions incremented.
-    let mut result = charge_gas_for_object_read(temporary_store, &mut gas_status);
+    let mut result = charge_gas_for_object_read(temporary_store, gas_status);
+    if let (Ok(()), Some(object_id)) = (&result, deleted_shared_object) {
+        result = Err(SuiError::SharedObjectDeleted { object_id });
+    }
     if result.is_ok() {
         // TODO: Since we require all mutable objects to not show up more than
         // once across single tx, we should be able to run them in parallel.
@@ -140,7 +159,7 @@ fn execute_transaction<S: BackingPackageStore>(
                         &function,
                         type_arguments,
                         arguments,
-                        &mut gas_status,
+                        gas_status,
                         tx_ctx,
                     )
                 }
@@ -149,32 +168,18 @@ fn execute_transaction<S: BackingPackageStore>(
                     native_functions.clone(),
                     modules,
                     tx_ctx,
-                    &mut gas_status,
+                    gas_status,
                 ),
-                SingleTransactionKind::ChangeEpoch(ChangeEpoch {
-                    epoch,
-                    storage_charge,
-                    computation_charge,
-                }) => {
-                    let module_id =
-                        ModuleId::new(SUI_FRAMEWORK_ADDRESS, SUI_SYSTEM_MODULE_NAME.to_owned());
-                    let function = ADVANCE_EPOCH_FUNCTION_NAME.to_owned();
-                    adapter::execute(
-                        move_vm,
-                        temporary_store,
-                        module_id,
-                        &function,
-                        vec![],
-                        vec![
-                            CallArg::SharedObject(SUI_SYSTEM_STATE_OBJECT_ID),
-                            CallArg::Pure(bcs::to_bytes(&epoch).unwrap()),
-                            CallArg::Pure(bcs::to_bytes(&storage_charge).unwrap()),
-                            CallArg::Pure(bcs::to_bytes(&computation_charge).unwrap()),
-                        ],
-                        &mut gas_status,
-                        tx_ctx,
-                    )
+                SingleTransactionKind::ChangeEpoch(change_epoch) => {
+                    advance_epoch(temporary_store, change_epoch, move_vm, gas_status, tx_ctx)
                 }
+                SingleTransactionKind::Upgrade(upgrade) => adapter::upgrade(
+                    temporary_store,
+                    native_functions.clone(),
+                    upgrade,
+                    tx_ctx,
+                    gas_status,
+                ),
             };
             if result.is_err() {
                 break;
@@ -200,7 +205,7 @@ fn execute_transaction<S: BackingPackageStore>(
             .clone();
         trace!(?gas_object_id, "Obtained gas object");
         if let Err(err) =
-            temporary_store.charge_gas_for_storage_changes(&mut gas_status, &mut gas_object)
+            temporary_store.charge_gas_for_storage_changes(gas_status, &mut gas_object)
         {
             // If `result` is already `Err`, we basically have two errors at the same time.
             // Users should be generally more interested in the actual execution error, so we
@@ -234,6 +239,64 @@ fn execute_transaction<S: BackingPackageStore>(
     }
 }
 
+/// Advance the system state to the next epoch, and upgrade the framework packages to the ones
+/// `change_epoch` carries. The packages are only written once the system state advanced, for
+/// the transaction to run the framework of the epoch ending throughout.
+fn advance_epoch<S: BackingPackageStore>(
+    temporary_store: &mut AuthorityTemporaryStore<S>,
+    change_epoch: ChangeEpoch,
+    move_vm: &Arc<MoveVM>,
+    gas_status: &mut SuiGasStatus,
+    tx_ctx: &mut TxContext,
+) -> SuiResult {
+    let mut upgraded_packages = Vec::new();
+    for package in &change_epoch.framework_packages {
+        let object = temporary_store
+            .objects()
+            .get(&package.id)
+            .expect("The framework packages upgraded are among the inputs");
+        let current = object
+            .data
+            .try_as_package()
+            .ok_or(SuiError::MoveObjectAsPackage {
+                object_id: package.id,
+            })?;
+        let modules = adapter::verify_framework_upgrade(current, &package.modules)?;
+        let upgraded_package = current.new_version(modules);
+        // Only the packages whose bytecode changes get a new version.
+        if upgraded_package.serialized_module_map() == current.serialized_module_map() {
+            continue;
+        }
+        let mut upgraded = object.clone();
+        upgraded.data = Data::Package(upgraded_package);
+        upgraded_packages.push(upgraded);
+    }
+
+    let module_id = ModuleId::new(SUI_FRAMEWORK_ADDRESS, SUI_SYSTEM_MODULE_NAME.to_owned());
+    let function = ADVANCE_EPOCH_FUNCTION_NAME.to_owned();
+    adapter::execute(
+        move_vm,
+        temporary_store,
+        module_id,
+        &function,
+        vec![],
+        vec![
+            CallArg::SharedObject(SUI_SYSTEM_STATE_OBJECT_ID),
+            CallArg::Pure(bcs::to_bytes(&change_epoch.epoch).unwrap()),
+            CallArg::Pure(bcs::to_bytes(&change_epoch.storage_charge).unwrap()),
+            CallArg::Pure(bcs::to_bytes(&change_epoch.computation_charge).unwrap()),
+            CallArg::Pure(bcs::to_bytes(&change_epoch.framework_digest()).unwrap()),
+        ],
+        gas_status,
+        tx_ctx,
+    )?;
+
+    for package in upgraded_packages {
+        temporary_store.upgrade_package(package);
+    }
+    Ok(())
+}
+
 fn transfer_coin<S>(
     temporary_store: &mut AuthorityTemporaryStore<S>,
     mut object: Object,
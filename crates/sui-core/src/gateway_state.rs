@@ -8,11 +8,9 @@ use std::sync::atomic::AtomicU64;
 use std::sync::Arc;
 use std::time::Duration;
 
-use anyhow::anyhow;
 use async_trait::async_trait;
 use futures::future;
 use move_bytecode_utils::module_cache::ModuleCache;
-use move_core_types::identifier::Identifier;
 use move_core_types::language_storage::TypeTag;
 use once_cell::sync::Lazy;
 use prometheus_exporter::prometheus::{
@@ -20,8 +18,6 @@ use prometheus_exporter::prometheus::{
 };
 use tracing::{debug, error, Instrument};
 
-use sui_adapter::adapter::resolve_and_type_check;
-use sui_types::gas_coin::GasCoin;
 use sui_types::object::{ObjectFormatOptions, Owner};
 use sui_types::{
     base_types::*,
@@ -31,6 +27,7 @@ use sui_types::{
     fp_ensure,
     messages::*,
     object::{Object, ObjectRead},
+    protocol_config::ProtocolConfig,
     SUI_FRAMEWORK_ADDRESS,
 };
 
@@ -40,8 +37,10 @@ use crate::{
     authority_aggregator::AuthorityAggregator,
     authority_client::AuthorityAPI,
     query_helpers::QueryHelpers,
+    quorum_driver::{QuorumDriver, QuorumDriverConfig},
+    transaction_builder::{DataReader, TransactionBuilder},
 };
-use sui_json::{resolve_move_function_args, SuiJsonCallArg, SuiJsonValue};
+use sui_json::SuiJsonValue;
 
 use crate::gateway_types::*;
 
@@ -234,6 +233,19 @@ pub trait GatewayAPI {
         tx: Transaction,
     ) -> Result<TransactionResponse, anyhow::Error>;
 
+    /// Execute the transaction on a validator without signing or committing it, to see its
+    /// effects and the gas it uses.
+    async fn dry_run_transaction(
+        &self,
+        tx: TransactionData,
+    ) -> Result<SuiTransactionEffects, anyhow::Error>;
+
+    /// Dry run the transaction as `dry_run_transaction` does, and profile where its gas goes.
+    async fn profile_transaction_gas(
+        &self,
+        tx: TransactionData,
+    ) -> Result<GasProfileResponse, anyhow::Error>;
+
     /// Send coin object to a Sui address.
     async fn transfer_coin(
         &self,
@@ -244,6 +256,17 @@ pub trait GatewayAPI {
         recipient: SuiAddress,
     ) -> Result<TransactionData, anyhow::Error>;
 
+    /// Send `amount` SUI from the coin `sui_object_id` to a Sui address, or the whole coin if
+    /// `amount` is `None`. The coin also pays for the gas.
+    async fn transfer_sui(
+        &self,
+        signer: SuiAddress,
+        sui_object_id: ObjectID,
+        gas_budget: u64,
+        recipient: SuiAddress,
+        amount: Option<u64>,
+    ) -> Result<TransactionData, anyhow::Error>;
+
     /// Synchronise account state with a random authorities, updates all object_ids
     /// from account_addr, request only goes out to one authority.
     /// this method doesn't guarantee data correctness, caller will have to handle potential byzantine authority
@@ -307,6 +330,13 @@ pub trait GatewayAPI {
     async fn get_object(&self, object_id: ObjectID)
         -> Result<GetObjectDataResponse, anyhow::Error>;
 
+    /// Get the object information, including only the optional fields selected by `options`
+    async fn get_object_with_options(
+        &self,
+        object_id: ObjectID,
+        options: SuiObjectDataOptions,
+    ) -> Result<GetObjectDataResponse, anyhow::Error>;
+
     /// Get the object data
     async fn get_raw_object(
         &self,
@@ -341,10 +371,12 @@ pub trait GatewayAPI {
         count: u64,
     ) -> Result<Vec<(GatewayTxSeqNumber, TransactionDigest)>, anyhow::Error>;
 
-    /// return transaction details by digest
+    /// return transaction details by digest, including only the optional fields selected by
+    /// `options`
     async fn get_transaction(
         &self,
         digest: TransactionDigest,
+        options: SuiTransactionResponseOptions,
     ) -> Result<TransactionEffectsResponse, anyhow::Error>;
 }
 
@@ -453,9 +485,8 @@ where
             ?tx_digest,
             tx_kind = transaction.data.kind_as_str()
         );
-        let exec_result = self
-            .authorities
-            .execute_transaction(&transaction)
+        let exec_result = QuorumDriver::new(&self.authorities, QuorumDriverConfig::default())
+            .execute_transaction(transaction)
             .instrument(span)
             .await;
 
@@ -513,6 +544,7 @@ where
         let (_gas_status, all_objects) = transaction_input_checker::check_transaction_input(
             &self.store,
             &transaction,
+            ProtocolConfig::get_for_max_version(),
             &self.metrics.shared_obj_tx,
         )
         .await?;
@@ -771,47 +803,6 @@ where
         }
     }
 
-    async fn choose_gas_for_address(
-        &self,
-        address: SuiAddress,
-        budget: u64,
-        gas: Option<ObjectID>,
-        used_coins: Vec<ObjectID>,
-    ) -> Result<ObjectRef, anyhow::Error> {
-        if let Some(id) = gas {
-            Ok(self
-                .get_object_internal(&id)
-                .await?
-                .compute_object_reference())
-        } else {
-            let used_coins = used_coins.into_iter().collect::<BTreeSet<_>>();
-            for (id, balance) in self.get_owned_coins(address).await.unwrap() {
-                if balance >= budget && !used_coins.contains(&id.0) {
-                    return Ok(id);
-                }
-            }
-            return Err(anyhow!(
-                "No non-argument gas objects found with value >= budget {}",
-                budget
-            ));
-        }
-    }
-
-    async fn get_owned_coins(
-        &self,
-        address: SuiAddress,
-    ) -> Result<Vec<(ObjectRef, u64)>, anyhow::Error> {
-        let mut coins = Vec::new();
-        for info in self.store.get_owner_objects(Owner::AddressOwner(address))? {
-            if info.type_ == GasCoin::type_().to_string() {
-                let object = self.get_object_internal(&info.object_id).await?;
-                let gas_coin = GasCoin::try_from(object.data.try_as_move().unwrap())?;
-                coins.push((info.into(), gas_coin.value()));
-            }
-        }
-        Ok(coins)
-    }
-
     #[cfg(test)]
     pub fn highest_known_version(&self, object_id: &ObjectID) -> Result<SequenceNumber, SuiError> {
         self.latest_object_ref(object_id)
@@ -829,11 +820,51 @@ where
     }
 }
 
+#[async_trait]
+impl<A> DataReader for GatewayState<A>
+where
+    A: AuthorityAPI + Send + Sync + Clone + 'static,
+{
+    async fn get_latest_object(&self, object_id: &ObjectID) -> Result<Object, anyhow::Error> {
+        Ok(self.get_object_internal(object_id).await?)
+    }
+
+    async fn get_owned_objects(
+        &self,
+        address: SuiAddress,
+    ) -> Result<Vec<ObjectInfo>, anyhow::Error> {
+        Ok(self.store.get_owner_objects(Owner::AddressOwner(address))?)
+    }
+}
+
 #[async_trait]
 impl<A> GatewayAPI for GatewayState<A>
 where
     A: AuthorityAPI + Send + Sync + Clone + 'static,
 {
+    async fn dry_run_transaction(
+        &self,
+        tx: TransactionData,
+    ) -> Result<SuiTransactionEffects, anyhow::Error> {
+        Ok(self
+            .authorities
+            .dry_run_transaction(tx, false)
+            .await?
+            .effects
+            .into())
+    }
+
+    async fn profile_transaction_gas(
+        &self,
+        tx: TransactionData,
+    ) -> Result<GasProfileResponse, anyhow::Error> {
+        let response = self.authorities.dry_run_transaction(tx, true).await?;
+        Ok(GasProfileResponse {
+            effects: response.effects.into(),
+            gas_profile: response.gas_profile.ok_or(SuiError::UnexpectedMessage)?,
+        })
+    }
+
     async fn execute_transaction(
         &self,
         tx: Transaction,
@@ -914,11 +945,9 @@ where
                 _ => {}
             }
         }
+        let changes = self.store.get_transaction_changes(certificate.digest())?;
         return Ok(TransactionResponse::EffectResponse(
-            TransactionEffectsResponse {
-                certificate: certificate.try_into()?,
-                effects: effects.into(),
-            },
+            TransactionEffectsResponse::new(certificate.try_into()?, effects.into(), changes),
         ));
     }
 
@@ -930,14 +959,22 @@ where
         gas_budget: u64,
         recipient: SuiAddress,
     ) -> Result<TransactionData, anyhow::Error> {
-        let gas_payment = self
-            .choose_gas_for_address(signer, gas_budget, gas, vec![object_id])
-            .await?;
-        let object = self.get_object_internal(&object_id).await?;
-        let object_ref = object.compute_object_reference();
-        let data =
-            TransactionData::new_transfer(recipient, object_ref, signer, gas_payment, gas_budget);
-        Ok(data)
+        TransactionBuilder(self)
+            .transfer_coin(signer, object_id, gas, gas_budget, recipient)
+            .await
+    }
+
+    async fn transfer_sui(
+        &self,
+        signer: SuiAddress,
+        sui_object_id: ObjectID,
+        gas_budget: u64,
+        recipient: SuiAddress,
+        amount: Option<u64>,
+    ) -> Result<TransactionData, anyhow::Error> {
+        TransactionBuilder(self)
+            .transfer_sui(signer, sui_object_id, gas_budget, recipient, amount)
+            .await
     }
 
     // TODO: Get rid of the sync API.
@@ -975,71 +1012,18 @@ where
         gas: Option<ObjectID>,
         gas_budget: u64,
     ) -> Result<TransactionData, anyhow::Error> {
-        let module = Identifier::new(module)?;
-        let function = Identifier::new(function)?;
-        let package_obj = self.get_object_internal(&package_object_id).await?;
-        let package_obj_ref = package_obj.compute_object_reference();
-        let json_args = resolve_move_function_args(
-            package_obj.data.try_as_package().unwrap(),
-            module.clone(),
-            function.clone(),
-            arguments,
-        )?;
-
-        // Fetch all the objects needed for this call
-        let mut objects = BTreeMap::new();
-        let mut args = Vec::with_capacity(json_args.len());
-
-        for json_arg in json_args {
-            args.push(match json_arg {
-                SuiJsonCallArg::Object(id) => {
-                    let obj = self.get_object_internal(&id).await?;
-                    let arg = if obj.is_shared() {
-                        CallArg::SharedObject(id)
-                    } else {
-                        CallArg::ImmOrOwnedObject(obj.compute_object_reference())
-                    };
-                    objects.insert(id, obj);
-                    arg
-                }
-                SuiJsonCallArg::Pure(bytes) => CallArg::Pure(bytes),
-            })
-        }
-
-        let forbidden_gas_objects = objects.keys().copied().collect();
-        let gas = self
-            .choose_gas_for_address(signer, gas_budget, gas, forbidden_gas_objects)
-            .await?;
-
-        // Pass in the objects for a deeper check
-        let is_genesis = false;
-        let compiled_module = package_obj
-            .data
-            .try_as_package()
-            .ok_or_else(|| anyhow!("Cannot get package from object"))?
-            .deserialize_module(&module)?;
-        resolve_and_type_check(
-            &objects,
-            &compiled_module,
-            &function,
-            &type_arguments,
-            args.clone(),
-            is_genesis,
-        )?;
-
-        let data = TransactionData::new_move_call(
-            signer,
-            package_obj_ref,
-            module,
-            function,
-            type_arguments,
-            gas,
-            args,
-            gas_budget,
-        );
-
-        debug!(?data, "Created Move Call transaction data");
-        Ok(data)
+        TransactionBuilder(self)
+            .move_call(
+                signer,
+                package_object_id,
+                module,
+                function,
+                type_arguments,
+                arguments,
+                gas,
+                gas_budget,
+            )
+            .await
     }
 
     async fn publish(
@@ -1049,11 +1033,9 @@ where
         gas: Option<ObjectID>,
         gas_budget: u64,
     ) -> Result<TransactionData, anyhow::Error> {
-        let gas = self
-            .choose_gas_for_address(signer, gas_budget, gas, vec![])
-            .await?;
-        let data = TransactionData::new_module(signer, gas, package_bytes, gas_budget);
-        Ok(data)
+        TransactionBuilder(self)
+            .publish(signer, package_bytes, gas, gas_budget)
+            .await
     }
 
     async fn split_coin(
@@ -1064,27 +1046,9 @@ where
         gas: Option<ObjectID>,
         gas_budget: u64,
     ) -> Result<TransactionData, anyhow::Error> {
-        let gas = self
-            .choose_gas_for_address(signer, gas_budget, gas, vec![coin_object_id])
-            .await?;
-        let coin_object = self.get_object_internal(&coin_object_id).await?;
-        let coin_object_ref = coin_object.compute_object_reference();
-        let coin_type = coin_object.get_move_template_type()?;
-        let data = TransactionData::new_move_call(
-            signer,
-            self.get_framework_object_ref().await?,
-            coin::COIN_MODULE_NAME.to_owned(),
-            coin::COIN_SPLIT_VEC_FUNC_NAME.to_owned(),
-            vec![coin_type],
-            gas,
-            vec![
-                CallArg::ImmOrOwnedObject(coin_object_ref),
-                CallArg::Pure(bcs::to_bytes(&split_amounts)?),
-            ],
-            gas_budget,
-        );
-        debug!(?data, "Created Split Coin transaction data");
-        Ok(data)
+        TransactionBuilder(self)
+            .split_coin(signer, coin_object_id, split_amounts, gas, gas_budget)
+            .await
     }
 
     async fn merge_coins(
@@ -1095,37 +1059,33 @@ where
         gas: Option<ObjectID>,
         gas_budget: u64,
     ) -> Result<TransactionData, anyhow::Error> {
-        let gas = self
-            .choose_gas_for_address(signer, gas_budget, gas, vec![coin_to_merge, primary_coin])
-            .await?;
-        let primary_coin_ref = self.get_object_ref(&primary_coin).await?;
-        let coin_to_merge = self.get_object_internal(&coin_to_merge).await?;
-        let coin_to_merge_ref = coin_to_merge.compute_object_reference();
-
-        let coin_type = coin_to_merge.get_move_template_type()?;
-        let data = TransactionData::new_move_call(
-            signer,
-            self.get_framework_object_ref().await?,
-            coin::COIN_MODULE_NAME.to_owned(),
-            coin::COIN_JOIN_FUNC_NAME.to_owned(),
-            vec![coin_type],
-            gas,
-            vec![
-                CallArg::ImmOrOwnedObject(primary_coin_ref),
-                CallArg::ImmOrOwnedObject(coin_to_merge_ref),
-            ],
-            gas_budget,
-        );
-        debug!(?data, "Created Merge Coin transaction data");
-        Ok(data)
+        TransactionBuilder(self)
+            .merge_coins(signer, primary_coin, coin_to_merge, gas, gas_budget)
+            .await
     }
 
     async fn get_object(
         &self,
         object_id: ObjectID,
+    ) -> Result<GetObjectDataResponse, anyhow::Error> {
+        self.get_object_with_options(object_id, SuiObjectDataOptions::default())
+            .await
+    }
+
+    async fn get_object_with_options(
+        &self,
+        object_id: ObjectID,
+        options: SuiObjectDataOptions,
     ) -> Result<GetObjectDataResponse, anyhow::Error> {
         let result = self.download_object_from_authorities(object_id).await?;
-        Ok(result.try_into()?)
+        let display = match &result {
+            ObjectRead::Exists(_, object, _) if options.show_display => match object.type_() {
+                Some(type_) => self.store.get_display(type_)?,
+                None => None,
+            },
+            _ => None,
+        };
+        GetObjectDataResponse::try_from_with_options(result, display.as_ref(), &options)
     }
 
     async fn get_raw_object(
@@ -1184,7 +1144,8 @@ where
     async fn get_transaction(
         &self,
         digest: TransactionDigest,
+        options: SuiTransactionResponseOptions,
     ) -> Result<TransactionEffectsResponse, anyhow::Error> {
-        QueryHelpers::get_transaction(&self.store, digest)
+        QueryHelpers::get_transaction(&self.store, digest, &options)
     }
 }
@@ -15,6 +15,7 @@ use itertools::Itertools;
 use move_core_types::identifier::Identifier;
 use move_core_types::language_storage::StructTag;
 use move_core_types::value::{MoveStruct, MoveStructLayout, MoveValue};
+use multiaddr::Multiaddr;
 use schemars::JsonSchema;
 use serde::ser::Error;
 use serde::Deserialize;
@@ -22,32 +23,216 @@ use serde::Serialize;
 use serde_json::Value;
 
 use serde_with::serde_as;
+use sui_framework::abort_constant_name;
 use sui_json::SuiJsonValue;
 use sui_types::base_types::{
-    ObjectDigest, ObjectID, ObjectInfo, ObjectRef, SequenceNumber, SuiAddress, TransactionDigest,
+    AuthorityName, ObjectDigest, ObjectID, ObjectInfo, ObjectRef, SequenceNumber, SuiAddress,
+    TransactionDigest,
 };
-use sui_types::committee::EpochId;
-use sui_types::crypto::{AuthorityQuorumSignInfo, Signature};
-use sui_types::error::SuiError;
+use sui_types::bls::ProtocolPublicKeyBytes;
+use sui_types::committee::{Committee, EpochId, StakeUnit};
+use sui_types::crypto::{AuthorityQuorumSignInfo, GenericSignature, Signature};
+use sui_types::display::Display as ObjectDisplay;
+use sui_types::ecdsa::{Secp256k1Signature, Secp256r1Signature};
+use sui_types::error::{MoveLocation, SuiError};
 use sui_types::event::Event;
-use sui_types::gas::GasCostSummary;
+use sui_types::gas::{GasCostSummary, GasProfile};
 use sui_types::gas_coin::GasCoin;
+use sui_types::light_client::{self, InclusionProof};
 use sui_types::messages::{
     CallArg, CertifiedTransaction, ExecutionStatus, InputObjectKind, MoveModulePublish,
     SingleTransactionKind, TransactionData, TransactionEffects, TransactionKind,
 };
+use sui_types::messages_checkpoint::{
+    CertifiedCheckpoint, CheckpointContents, CheckpointParticipation, CheckpointSequenceNumber,
+};
 use sui_types::move_package::disassemble_modules;
+use sui_types::multisig::MultiSig;
 use sui_types::object::{Data, MoveObject, Object, ObjectRead, Owner};
+use sui_types::object_change::{BalanceChange, ObjectChange, OwnerChange, TransactionChanges};
 use sui_types::sui_serde::{Base64, Encoding};
+use sui_types::sui_system_state::{Delegation, SuiSystemState, Validator, ValidatorMetadata};
+use sui_types::zk_login::ZkLoginAuthenticator;
 
 #[cfg(test)]
 #[path = "unit_tests/gateway_types_tests.rs"]
 mod gateway_types_tests;
 
 #[derive(Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(rename_all = "camelCase")]
 pub struct TransactionEffectsResponse {
-    pub certificate: SuiCertifiedTransaction,
-    pub effects: SuiTransactionEffects,
+    /// The certificate of the transaction, left out when `showInput` is off.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub certificate: Option<SuiCertifiedTransaction>,
+    /// The effects of the transaction, left out when `showEffects` is off.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub effects: Option<SuiTransactionEffects>,
+    /// The changes in the coin balances of each owner, by coin type.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub balance_changes: Vec<SuiBalanceChange>,
+    /// The objects created, mutated, transferred, deleted or wrapped by the transaction.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub object_changes: Vec<SuiObjectChange>,
+    /// The transaction data signed by the sender, in the format accepted by `executeTransaction`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub raw_transaction: Option<Base64>,
+}
+
+impl TransactionEffectsResponse {
+    pub fn new(
+        certificate: SuiCertifiedTransaction,
+        effects: SuiTransactionEffects,
+        changes: TransactionChanges,
+    ) -> Self {
+        Self {
+            certificate: Some(certificate),
+            effects: Some(effects),
+            balance_changes: changes
+                .balance_changes
+                .into_iter()
+                .map(SuiBalanceChange::from)
+                .collect(),
+            object_changes: changes
+                .object_changes
+                .into_iter()
+                .map(SuiObjectChange::from)
+                .collect(),
+            raw_transaction: None,
+        }
+    }
+
+    /// The response for `certificate`, with the fields selected by `options`.
+    pub fn from_certificate(
+        certificate: CertifiedTransaction,
+        effects: TransactionEffects,
+        changes: TransactionChanges,
+        options: &SuiTransactionResponseOptions,
+    ) -> Result<Self, anyhow::Error> {
+        let raw_transaction = options
+            .show_raw_input
+            .then(|| Base64::from_bytes(&certificate.data.to_bytes()));
+        let mut response = Self::new(certificate.try_into()?, effects.into(), changes);
+        response.raw_transaction = raw_transaction;
+        response.apply_options(options);
+        Ok(response)
+    }
+
+    /// Drop the fields of the response that were not selected by `options`.
+    pub fn apply_options(&mut self, options: &SuiTransactionResponseOptions) {
+        if !options.show_input {
+            self.certificate = None;
+        }
+        if !options.show_effects {
+            self.effects = None;
+        }
+        if !options.show_events {
+            if let Some(effects) = &mut self.effects {
+                effects.events.clear();
+            }
+        }
+        if !options.show_balance_changes {
+            self.balance_changes.clear();
+        }
+        if !options.show_object_changes {
+            self.object_changes.clear();
+        }
+    }
+
+    /// The certificate and effects of the transaction, which are only left out of the
+    /// responses to `getTransaction` requests that did not select them.
+    pub fn into_certificate_and_effects(
+        self,
+    ) -> Result<(SuiCertifiedTransaction, SuiTransactionEffects), SuiError> {
+        self.certificate
+            .zip(self.effects)
+            .ok_or(SuiError::UnexpectedMessage)
+    }
+}
+
+/// The result of one of the lookups of a `multiGet*` request.
+#[derive(Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum MultiGetResult<T> {
+    /// The lookup succeeded.
+    Ok(T),
+    /// The lookup failed with this error.
+    Error(String),
+}
+
+impl<T, E: Display> From<Result<T, E>> for MultiGetResult<T> {
+    fn from(result: Result<T, E>) -> Self {
+        match result {
+            Ok(value) => Self::Ok(value),
+            Err(e) => Self::Error(e.to_string()),
+        }
+    }
+}
+
+/// Selects the optional fields returned by `getTransaction`.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, JsonSchema, Eq, PartialEq)]
+#[serde(
+    rename_all = "camelCase",
+    default,
+    rename = "TransactionResponseOptions"
+)]
+pub struct SuiTransactionResponseOptions {
+    /// Whether to include the certificate of the transaction. Defaults to true.
+    pub show_input: bool,
+    /// Whether to include the effects of the transaction. Defaults to true.
+    pub show_effects: bool,
+    /// Whether to include the events emitted by the transaction. Defaults to true.
+    pub show_events: bool,
+    /// Whether to include the balance changes of the transaction. Defaults to true.
+    pub show_balance_changes: bool,
+    /// Whether to include the object changes of the transaction. Defaults to true.
+    pub show_object_changes: bool,
+    /// Whether to include the BCS encoded transaction data. Defaults to false.
+    pub show_raw_input: bool,
+}
+
+impl Default for SuiTransactionResponseOptions {
+    fn default() -> Self {
+        Self {
+            show_input: true,
+            show_effects: true,
+            show_events: true,
+            show_balance_changes: true,
+            show_object_changes: true,
+            show_raw_input: false,
+        }
+    }
+}
+
+/// Selects the optional fields returned by `getObject`.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, JsonSchema, Eq, PartialEq)]
+#[serde(rename_all = "camelCase", default, rename = "ObjectDataOptions")]
+pub struct SuiObjectDataOptions {
+    /// Whether to include the fields of the Move object, parsed to JSON. Defaults to true.
+    pub show_content: bool,
+    /// Whether to include the fields rendered from the `Display` of the object's type.
+    /// Defaults to true.
+    pub show_display: bool,
+    /// Whether to include the digest of the transaction that last mutated the object.
+    /// Defaults to true.
+    pub show_previous_transaction: bool,
+    /// Whether to include the BCS encoded contents of the object. Defaults to false.
+    pub show_bcs: bool,
+    /// Read the object as it was at the end of this checkpoint rather than at its latest
+    /// version, for reads across requests to see the same state. Only served by nodes which
+    /// keep checkpoints.
+    pub at_checkpoint: Option<CheckpointSequenceNumber>,
+}
+
+impl Default for SuiObjectDataOptions {
+    fn default() -> Self {
+        Self {
+            show_content: true,
+            show_display: true,
+            show_previous_transaction: true,
+            show_bcs: false,
+            at_checkpoint: None,
+        }
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug, JsonSchema)]
@@ -88,6 +273,42 @@ impl TransactionResponse {
     }
 }
 
+/// How far a full node takes a transaction it submits before answering.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, JsonSchema)]
+pub enum ExecuteTransactionRequestType {
+    /// Answer once a quorum of validators has certified the transaction. The certificate is
+    /// executed after the answer.
+    WaitForTxCert,
+    /// Answer once a quorum of validators has executed the certificate to the same effects.
+    WaitForEffectsCert,
+    /// Answer once the full node has also executed the certificate, so that its reads see the
+    /// effects of the transaction.
+    WaitForLocalExecution,
+}
+
+#[derive(Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(rename = "ExecuteTransactionResponse")]
+pub enum SuiExecuteTransactionResponse {
+    TxCert {
+        certificate: SuiCertifiedTransaction,
+    },
+    #[serde(rename_all = "camelCase")]
+    EffectsCert {
+        certificate: SuiCertifiedTransaction,
+        effects: SuiTransactionEffects,
+        /// Whether the full node executed the certificate before answering.
+        confirmed_local_execution: bool,
+    },
+}
+
+/// The effects a dry run of a transaction found it would have, with where its gas went.
+#[derive(Serialize, Deserialize, Clone, Debug, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct GasProfileResponse {
+    pub effects: SuiTransactionEffects,
+    pub gas_profile: GasProfile,
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct SplitCoinResponse {
@@ -163,12 +384,16 @@ pub struct SuiObject<T: SuiMoveObject> {
     /// The owner that unlocks this object
     pub owner: Owner,
     /// The digest of the transaction that created or last mutated this object
-    pub previous_transaction: TransactionDigest,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub previous_transaction: Option<TransactionDigest>,
     /// The amount of SUI we would rebate if this object gets deleted.
     /// This number is re-calculated each time the object is mutated based on
     /// the present storage gas price.
     pub storage_rebate: u64,
     pub reference: SuiObjectRef,
+    /// The BCS encoded contents of the Move object, if requested.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bcs: Option<Base64>,
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, Eq, PartialEq, Ord, PartialOrd)]
@@ -229,12 +454,14 @@ impl Display for SuiParsedObject {
             "Storage Rebate".bold().bright_black(),
             self.storage_rebate
         )?;
-        writeln!(
-            writer,
-            "{}: {:?}",
-            "Previous Transaction".bold().bright_black(),
-            self.previous_transaction
-        )?;
+        if let Some(previous_transaction) = &self.previous_transaction {
+            writeln!(
+                writer,
+                "{}: {:?}",
+                "Previous Transaction".bold().bright_black(),
+                previous_transaction
+            )?;
+        }
         writeln!(writer, "{}", "----- Data -----".bold())?;
         write!(writer, "{}", &self.data)?;
         write!(f, "{}", writer)
@@ -260,9 +487,10 @@ impl<T: SuiMoveObject> SuiObject<T> {
         Ok(Self {
             data,
             owner: o.owner,
-            previous_transaction: o.previous_transaction,
+            previous_transaction: Some(o.previous_transaction),
             storage_rebate: o.storage_rebate,
             reference: oref.into(),
+            bcs: None,
         })
     }
 }
@@ -281,7 +509,9 @@ impl Display for SuiData<SuiParsedMoveObject> {
         match self {
             SuiData::MoveObject(o) => {
                 writeln!(writer, "{}: {}", "type".bold().bright_black(), o.type_)?;
-                write!(writer, "{}", &o.fields)?;
+                if let Some(fields) = &o.fields {
+                    write!(writer, "{}", fields)?;
+                }
             }
             SuiData::Package(p) => {
                 write!(
@@ -317,7 +547,47 @@ pub trait SuiMoveObject: Sized {
 pub struct SuiParsedMoveObject {
     #[serde(rename = "type")]
     pub type_: String,
-    pub fields: SuiMoveStruct,
+    /// The fields of the object, left out when `showContent` is off.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fields: Option<SuiMoveStruct>,
+    /// Fields rendered from the `Display` set by the publisher of this object's type, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub display: Option<BTreeMap<String, String>>,
+}
+
+impl SuiParsedMoveObject {
+    /// Render each template of `display` against the fields of this object.
+    /// `{path}` is replaced with the value of the (possibly nested, dot separated) field `path`,
+    /// or with an empty string if there is no such field.
+    pub fn render_display(&self, display: &ObjectDisplay) -> BTreeMap<String, String> {
+        display
+            .fields
+            .iter()
+            .map(|field| (field.name.clone(), self.render_template(&field.template)))
+            .collect()
+    }
+
+    fn render_template(&self, template: &str) -> String {
+        let mut rendered = String::new();
+        let mut rest = template;
+        while let Some(start) = rest.find('{') {
+            let end = match rest[start..].find('}') {
+                Some(end) => start + end,
+                None => break,
+            };
+            rendered.push_str(&rest[..start]);
+            if let Some(value) = self
+                .fields
+                .as_ref()
+                .and_then(|fields| fields.field_by_path(&rest[start + 1..end]))
+            {
+                rendered.push_str(&value.to_string());
+            }
+            rest = &rest[end + 1..];
+        }
+        rendered.push_str(rest);
+        rendered
+    }
 }
 
 impl SuiMoveObject for SuiParsedMoveObject {
@@ -335,12 +605,14 @@ impl SuiMoveObject for SuiParsedMoveObject {
             if let SuiMoveStruct::WithTypes { type_, fields } = move_struct {
                 SuiParsedMoveObject {
                     type_,
-                    fields: SuiMoveStruct::WithFields(fields),
+                    fields: Some(SuiMoveStruct::WithFields(fields)),
+                    display: None,
                 }
             } else {
                 SuiParsedMoveObject {
                     type_: object.type_.to_string(),
-                    fields: move_struct,
+                    fields: Some(move_struct),
+                    display: None,
                 }
             },
         )
@@ -384,7 +656,9 @@ impl TryFrom<&SuiParsedObject> for GasCoin {
         match &object.data {
             SuiData::MoveObject(o) => {
                 if GasCoin::type_().to_string() == o.type_ {
-                    return GasCoin::try_from(&o.fields);
+                    if let Some(fields) = &o.fields {
+                        return GasCoin::try_from(fields);
+                    }
                 }
             }
             SuiData::Package(_) => {}
@@ -515,6 +789,51 @@ impl<T: SuiMoveObject> SuiObjectRead<T> {
     }
 }
 
+impl SuiObjectRead<SuiParsedMoveObject> {
+    /// Render `display` against the object, if it exists and is a Move object.
+    pub fn set_display(&mut self, display: &ObjectDisplay) {
+        if let Self::Exists(SuiObject {
+            data: SuiData::MoveObject(object),
+            ..
+        }) = self
+        {
+            object.display = Some(object.render_display(display));
+        }
+    }
+
+    /// Convert `object_read`, including only the fields selected by `options`. `display` is
+    /// the `Display` of the object's type, if there is one.
+    pub fn try_from_with_options(
+        object_read: ObjectRead,
+        display: Option<&ObjectDisplay>,
+        options: &SuiObjectDataOptions,
+    ) -> Result<Self, anyhow::Error> {
+        let bcs = match &object_read {
+            ObjectRead::Exists(_, object, _) if options.show_bcs => object
+                .data
+                .try_as_move()
+                .map(|move_object| Base64::from_bytes(move_object.contents())),
+            _ => None,
+        };
+        let mut response: Self = object_read.try_into()?;
+        if let Some(display) = display.filter(|_| options.show_display) {
+            response.set_display(display);
+        }
+        if let Self::Exists(object) = &mut response {
+            if let SuiData::MoveObject(move_object) = &mut object.data {
+                if !options.show_content {
+                    move_object.fields = None;
+                }
+            }
+            object.bcs = bcs;
+            if !options.show_previous_transaction {
+                object.previous_transaction = None;
+            }
+        }
+        Ok(response)
+    }
+}
+
 impl<T: SuiMoveObject> TryFrom<ObjectRead> for SuiObjectRead<T> {
     type Error = anyhow::Error;
 
@@ -640,6 +959,31 @@ pub enum SuiMoveStruct {
     WithFields(BTreeMap<String, SuiMoveValue>),
 }
 
+impl SuiMoveStruct {
+    /// Look up a field by its name, or by a dot separated path of names for fields of
+    /// nested structs (e.g. `metadata.name`).
+    pub fn field_by_path(&self, path: &str) -> Option<&SuiMoveValue> {
+        let mut names = path.split('.');
+        let mut value = self.fields()?.get(names.next()?)?;
+        for name in names {
+            value = match value {
+                SuiMoveValue::Struct(s) => s.fields()?.get(name)?,
+                _ => return None,
+            };
+        }
+        Some(value)
+    }
+
+    fn fields(&self) -> Option<&BTreeMap<String, SuiMoveValue>> {
+        match self {
+            SuiMoveStruct::Runtime(_) => None,
+            SuiMoveStruct::WithTypes { fields, .. } | SuiMoveStruct::WithFields(fields) => {
+                Some(fields)
+            }
+        }
+    }
+}
+
 impl Display for SuiMoveStruct {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         let mut writer = String::new();
@@ -754,6 +1098,13 @@ pub struct SuiMovePackage {
     disassembled: BTreeMap<String, Value>,
 }
 
+impl SuiMovePackage {
+    /// The disassembled bytecode of each module, by module name.
+    pub fn disassembled(&self) -> &BTreeMap<String, Value> {
+        &self.disassembled
+    }
+}
+
 impl TryFrom<MoveModulePublish> for SuiMovePackage {
     type Error = anyhow::Error;
 
@@ -824,6 +1175,8 @@ pub enum SuiTransactionKind {
     TransferSui(SuiTransferSui),
     /// A system transaction that will update epoch information on-chain.
     ChangeEpoch(SuiChangeEpoch),
+    /// Publish a new version of a package, with the `UpgradeCap` of the package
+    Upgrade(SuiPackageUpgrade),
     // .. more transaction types go here
 }
 
@@ -854,6 +1207,11 @@ impl Display for SuiTransactionKind {
             Self::Publish(_p) => {
                 write!(writer, "Transaction Kind : Publish")?;
             }
+            Self::Upgrade(u) => {
+                writeln!(writer, "Transaction Kind : Upgrade")?;
+                writeln!(writer, "Package ID : {}", u.package)?;
+                write!(writer, "Upgrade Cap ID : {}", u.upgrade_cap.object_id)?;
+            }
             Self::Call(c) => {
                 writeln!(writer, "Transaction Kind : Call")?;
                 writeln!(
@@ -871,6 +1229,9 @@ impl Display for SuiTransactionKind {
                 writeln!(writer, "New epoch ID: {}", e.epoch)?;
                 writeln!(writer, "Storage gas reward: {}", e.storage_charge)?;
                 writeln!(writer, "Computation gas reward: {}", e.computation_charge)?;
+                for package in &e.framework_packages {
+                    writeln!(writer, "Framework package upgraded: {}", package)?;
+                }
             }
         }
         write!(f, "{}", writer)
@@ -904,7 +1265,7 @@ impl TryFrom<SingleTransactionKind> for SuiTransactionKind {
                         CallArg::ImmOrOwnedObject((id, _, _)) => {
                             SuiJsonValue::new(Value::String(id.to_hex_literal()))
                         }
-                        CallArg::SharedObject(id) => {
+                        CallArg::SharedObject(id) | CallArg::ReadOnlySharedObject(id) => {
                             SuiJsonValue::new(Value::String(id.to_hex_literal()))
                         }
                     })
@@ -914,6 +1275,16 @@ impl TryFrom<SingleTransactionKind> for SuiTransactionKind {
                 epoch: e.epoch,
                 storage_charge: e.storage_charge,
                 computation_charge: e.computation_charge,
+                framework_packages: e
+                    .framework_packages
+                    .iter()
+                    .map(|package| package.id)
+                    .collect(),
+            }),
+            SingleTransactionKind::Upgrade(u) => Self::Upgrade(SuiPackageUpgrade {
+                package: u.package,
+                upgrade_cap: u.upgrade_cap.into(),
+                modules: MoveModulePublish { modules: u.modules }.try_into()?,
             }),
         })
     }
@@ -936,6 +1307,19 @@ pub struct SuiChangeEpoch {
     pub epoch: EpochId,
     pub storage_charge: u64,
     pub computation_charge: u64,
+    /// The framework packages the epoch change upgrades, which are all of them if it does.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub framework_packages: Vec<ObjectID>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename = "PackageUpgrade", rename_all = "camelCase")]
+pub struct SuiPackageUpgrade {
+    /// The version of the package the upgrade replaces.
+    pub package: ObjectID,
+    pub upgrade_cap: SuiObjectRef,
+    /// The new version of the package.
+    pub modules: SuiMovePackage,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
@@ -946,7 +1330,7 @@ pub struct SuiCertifiedTransaction {
     pub transaction_digest: TransactionDigest,
     pub data: SuiTransactionData,
     /// tx_signature is signed by the transaction sender, applied on `data`.
-    pub tx_signature: Signature,
+    pub tx_signature: SuiGenericSignature,
     /// authority signature information, if available, is signed by an authority, applied on `data`.
     pub auth_sign_info: AuthorityQuorumSignInfo,
 }
@@ -958,12 +1342,8 @@ impl Display for SuiCertifiedTransaction {
         writeln!(writer, "Transaction Signature: {:?}", self.tx_signature)?;
         writeln!(
             writer,
-            "Signed Authorities : {:?}",
-            self.auth_sign_info
-                .signatures
-                .iter()
-                .map(|(name, _)| name)
-                .collect::<Vec<_>>()
+            "Signed Authorities Map : {}",
+            hex::encode(&self.auth_sign_info.signers_map)
         )?;
         write!(writer, "{}", &self.data)?;
         write!(f, "{}", writer)
@@ -977,12 +1357,36 @@ impl TryFrom<CertifiedTransaction> for SuiCertifiedTransaction {
         Ok(Self {
             transaction_digest: *cert.digest(),
             data: cert.data.try_into()?,
-            tx_signature: cert.tx_signature,
+            tx_signature: cert.tx_signature.into(),
             auth_sign_info: cert.auth_sign_info,
         })
     }
 }
 
+/// The signature of a transaction sender. A single key signature is serialized as it was
+/// before multisig senders existed, so existing clients keep reading it as a string.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Eq, PartialEq)]
+#[serde(untagged, rename = "GenericSignature")]
+pub enum SuiGenericSignature {
+    Signature(Signature),
+    Secp256k1(Secp256k1Signature),
+    Secp256r1(Secp256r1Signature),
+    MultiSig(MultiSig),
+    ZkLogin(ZkLoginAuthenticator),
+}
+
+impl From<GenericSignature> for SuiGenericSignature {
+    fn from(signature: GenericSignature) -> Self {
+        match signature {
+            GenericSignature::Signature(signature) => SuiGenericSignature::Signature(signature),
+            GenericSignature::Secp256k1(signature) => SuiGenericSignature::Secp256k1(signature),
+            GenericSignature::Secp256r1(signature) => SuiGenericSignature::Secp256r1(signature),
+            GenericSignature::MultiSig(multisig) => SuiGenericSignature::MultiSig(multisig),
+            GenericSignature::ZkLogin(authenticator) => SuiGenericSignature::ZkLogin(authenticator),
+        }
+    }
+}
+
 /// The response from processing a transaction or a certified transaction
 #[derive(Eq, PartialEq, Clone, Debug, Serialize, Deserialize, JsonSchema)]
 #[serde(rename = "TransactionEffects", rename_all = "camelCase")]
@@ -1011,6 +1415,14 @@ pub struct SuiTransactionEffects {
     // Object refs of objects now wrapped in other objects.
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub wrapped: Vec<SuiObjectRef>,
+    // Object refs of objects that were wrapped in other objects in the past, and got
+    // unwrapped and then deleted in this transaction. These are not included in `deleted`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub unwrapped_then_deleted: Vec<SuiObjectRef>,
+    /// The objects, among the mutated ones, whose owner changed, with their previous owner and
+    /// their new one.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub owner_changes: Vec<OwnerChange>,
     // The updated gas object reference. Have a dedicated field for convenient access.
     // It's also included in mutated.
     pub gas_object: OwnedObjectRef,
@@ -1075,6 +1487,28 @@ impl Display for SuiTransactionEffects {
                 )?;
             }
         }
+        if !self.unwrapped_then_deleted.is_empty() {
+            writeln!(writer, "Unwrapped Then Deleted Objects:")?;
+            for oref in &self.unwrapped_then_deleted {
+                writeln!(writer, "  - ID: {}", oref.object_id)?;
+            }
+        }
+        if !self.owner_changes.is_empty() {
+            writeln!(writer, "Owner Changes:")?;
+            for change in &self.owner_changes {
+                writeln!(
+                    writer,
+                    "  - ID: {} , Owner: {} -> {}",
+                    change.object_id, change.previous_owner, change.owner
+                )?;
+            }
+        }
+        if !self.dependencies.is_empty() {
+            writeln!(writer, "Dependencies:")?;
+            for digest in &self.dependencies {
+                writeln!(writer, "  - {:?}", digest)?;
+            }
+        }
         write!(f, "{}", writer)
     }
 }
@@ -1090,6 +1524,8 @@ impl From<TransactionEffects> for SuiTransactionEffects {
             unwrapped: to_owned_ref(effect.unwrapped),
             deleted: to_sui_object_ref(effect.deleted),
             wrapped: to_sui_object_ref(effect.wrapped),
+            unwrapped_then_deleted: to_sui_object_ref(effect.unwrapped_then_deleted),
+            owner_changes: effect.owner_changes,
             gas_object: OwnedObjectRef {
                 owner: effect.gas_object.1,
                 reference: effect.gas_object.0.into(),
@@ -1122,6 +1558,9 @@ pub enum SuiExecutionStatus {
     Failure {
         gas_cost: SuiGasCostSummary,
         error: String,
+        /// Where the transaction aborted, if a Move function aborted.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        move_abort: Option<SuiMoveAbort>,
     },
 }
 
@@ -1132,6 +1571,18 @@ impl SuiExecutionStatus {
     pub fn is_err(&self) -> bool {
         matches!(self, SuiExecutionStatus::Failure { .. })
     }
+
+    /// Why the transaction failed, naming the constant and the function of a Move abort.
+    pub fn error_message(&self) -> Option<String> {
+        match self {
+            SuiExecutionStatus::Success { .. } => None,
+            SuiExecutionStatus::Failure {
+                move_abort: Some(move_abort),
+                ..
+            } => Some(move_abort.to_string()),
+            SuiExecutionStatus::Failure { error, .. } => Some(error.clone()),
+        }
+    }
 }
 
 impl From<ExecutionStatus> for SuiExecutionStatus {
@@ -1143,11 +1594,58 @@ impl From<ExecutionStatus> for SuiExecutionStatus {
             ExecutionStatus::Failure { gas_cost, error } => Self::Failure {
                 gas_cost: gas_cost.into(),
                 error: error.to_string(),
+                move_abort: match *error {
+                    SuiError::MoveAbort { location, code } => {
+                        Some(SuiMoveAbort::new(location, code))
+                    }
+                    _ => None,
+                },
             },
         }
     }
 }
 
+/// A Move abort, with the name of the constant of the abort code when it is known.
+#[derive(Eq, PartialEq, Clone, Debug, Serialize, Deserialize, JsonSchema)]
+#[serde(rename = "MoveAbort", rename_all = "camelCase")]
+pub struct SuiMoveAbort {
+    /// The module, like `0x2::Coin`.
+    pub module: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub function: Option<String>,
+    pub instruction: u16,
+    pub code: u64,
+    /// The name of the error constant, as given by the source maps of the framework.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub constant: Option<String>,
+}
+
+impl SuiMoveAbort {
+    pub fn new(location: MoveLocation, code: u64) -> Self {
+        Self {
+            module: location.module.short_str_lossless(),
+            constant: abort_constant_name(&location, code),
+            function: location.function_name,
+            instruction: location.instruction,
+            code,
+        }
+    }
+}
+
+impl Display for SuiMoveAbort {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match &self.constant {
+            Some(constant) => write!(f, "{constant}")?,
+            None => write!(f, "Abort code {}", self.code)?,
+        }
+        write!(f, " in {}", self.module)?;
+        if let Some(function) = &self.function {
+            write!(f, "::{function}")?;
+        }
+        write!(f, " at instruction {}", self.instruction)
+    }
+}
+
 fn to_sui_object_ref(refs: Vec<ObjectRef>) -> Vec<SuiObjectRef> {
     refs.into_iter().map(SuiObjectRef::from).collect()
 }
@@ -1162,6 +1660,157 @@ fn to_owned_ref(owned_refs: Vec<(ObjectRef, Owner)>) -> Vec<OwnedObjectRef> {
         .collect()
 }
 
+#[derive(Eq, PartialEq, Clone, Debug, Serialize, Deserialize, JsonSchema)]
+#[serde(rename = "BalanceChange", rename_all = "camelCase")]
+pub struct SuiBalanceChange {
+    pub owner: Owner,
+    pub coin_type: String,
+    /// The change in the total value of the coins, negative if the balance decreased.
+    pub amount: i128,
+}
+
+impl From<BalanceChange> for SuiBalanceChange {
+    fn from(change: BalanceChange) -> Self {
+        Self {
+            owner: change.owner,
+            coin_type: change.coin_type.to_string(),
+            amount: change.amount,
+        }
+    }
+}
+
+#[derive(Eq, PartialEq, Clone, Debug, Serialize, Deserialize, JsonSchema)]
+#[serde(rename = "ObjectChange", rename_all = "camelCase", tag = "type")]
+pub enum SuiObjectChange {
+    /// A package was published.
+    #[serde(rename_all = "camelCase")]
+    Published {
+        package_id: ObjectID,
+        version: SequenceNumber,
+        digest: ObjectDigest,
+    },
+    /// An object was created, or unwrapped from another object.
+    #[serde(rename_all = "camelCase")]
+    Created {
+        object_id: ObjectID,
+        object_type: String,
+        owner: Owner,
+        version: SequenceNumber,
+        digest: ObjectDigest,
+    },
+    /// An object was mutated and kept its owner.
+    #[serde(rename_all = "camelCase")]
+    Mutated {
+        object_id: ObjectID,
+        object_type: String,
+        owner: Owner,
+        previous_version: SequenceNumber,
+        version: SequenceNumber,
+        digest: ObjectDigest,
+    },
+    /// An object changed owner.
+    #[serde(rename_all = "camelCase")]
+    Transferred {
+        object_id: ObjectID,
+        object_type: String,
+        previous_owner: Owner,
+        owner: Owner,
+        version: SequenceNumber,
+        digest: ObjectDigest,
+    },
+    /// An object was deleted.
+    #[serde(rename_all = "camelCase")]
+    Deleted {
+        object_id: ObjectID,
+        object_type: String,
+        version: SequenceNumber,
+    },
+    /// An object was wrapped into another object.
+    #[serde(rename_all = "camelCase")]
+    Wrapped {
+        object_id: ObjectID,
+        object_type: String,
+        version: SequenceNumber,
+    },
+}
+
+impl From<ObjectChange> for SuiObjectChange {
+    fn from(change: ObjectChange) -> Self {
+        match change {
+            ObjectChange::Published {
+                package_id,
+                version,
+                digest,
+            } => Self::Published {
+                package_id,
+                version,
+                digest,
+            },
+            ObjectChange::Created {
+                object_id,
+                object_type,
+                owner,
+                version,
+                digest,
+            } => Self::Created {
+                object_id,
+                object_type: object_type.to_string(),
+                owner,
+                version,
+                digest,
+            },
+            ObjectChange::Mutated {
+                object_id,
+                object_type,
+                owner,
+                previous_version,
+                version,
+                digest,
+            } => Self::Mutated {
+                object_id,
+                object_type: object_type.to_string(),
+                owner,
+                previous_version,
+                version,
+                digest,
+            },
+            ObjectChange::Transferred {
+                object_id,
+                object_type,
+                previous_owner,
+                owner,
+                version,
+                digest,
+            } => Self::Transferred {
+                object_id,
+                object_type: object_type.to_string(),
+                previous_owner,
+                owner,
+                version,
+                digest,
+            },
+            ObjectChange::Deleted {
+                object_id,
+                object_type,
+                version,
+            } => Self::Deleted {
+                object_id,
+                object_type: object_type.to_string(),
+                version,
+            },
+            ObjectChange::Wrapped {
+                object_id,
+                object_type,
+                version,
+            } => Self::Wrapped {
+                object_id,
+                object_type: object_type.to_string(),
+                version,
+            },
+        }
+    }
+}
+
 #[derive(Eq, PartialEq, Clone, Debug, Serialize, Deserialize, JsonSchema)]
 #[serde(rename = "GasCostSummary", rename_all = "camelCase")]
 pub struct SuiGasCostSummary {
@@ -1218,6 +1867,8 @@ pub enum SuiInputObjectKind {
     ImmOrOwnedMoveObject(SuiObjectRef),
     // A Move object that's shared and mutable.
     SharedMoveObject(ObjectID),
+    // A Move object that's shared, and only read by the transaction.
+    ReadOnlySharedMoveObject(ObjectID),
 }
 
 impl From<InputObjectKind> for SuiInputObjectKind {
@@ -1226,6 +1877,7 @@ impl From<InputObjectKind> for SuiInputObjectKind {
             InputObjectKind::MovePackage(id) => Self::MovePackage(id),
             InputObjectKind::ImmOrOwnedMoveObject(oref) => Self::ImmOrOwnedMoveObject(oref.into()),
             InputObjectKind::SharedMoveObject(id) => Self::SharedMoveObject(id),
+            InputObjectKind::ReadOnlySharedMoveObject(id) => Self::ReadOnlySharedMoveObject(id),
         }
     }
 }
@@ -1254,3 +1906,468 @@ impl From<ObjectInfo> for SuiObjectInfo {
         }
     }
 }
+
+/// The state of the Sui system, with the metadata explorers and delegators identify the
+/// validators by.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename = "SuiSystemState", rename_all = "camelCase")]
+pub struct SuiSystemStateSummary {
+    pub epoch: EpochId,
+    pub validator_stake: u64,
+    pub delegation_stake: u64,
+    pub quorum_stake_threshold: u64,
+    pub min_validator_stake: u64,
+    pub max_validator_candidate_count: u64,
+    pub storage_fund: u64,
+    pub active_validators: Vec<SuiValidatorSummary>,
+    /// The validators joining the committee in the next epoch.
+    pub pending_validators: Vec<SuiValidatorSummary>,
+    /// The metadata of the committee of the next epoch, with the pending key rotations and
+    /// metadata updates.
+    pub next_epoch_validators: Vec<SuiValidatorMetadata>,
+}
+
+impl From<SuiSystemState> for SuiSystemStateSummary {
+    fn from(state: SuiSystemState) -> Self {
+        let validators = state.validators;
+        Self {
+            epoch: state.epoch,
+            validator_stake: validators.validator_stake,
+            delegation_stake: validators.delegation_stake,
+            quorum_stake_threshold: validators.quorum_stake_threshold,
+            min_validator_stake: state.parameters.min_validator_stake,
+            max_validator_candidate_count: state.parameters.max_validator_candidate_count,
+            storage_fund: state.storage_fund.value(),
+            active_validators: validators
+                .active_validators
+                .into_iter()
+                .map(SuiValidatorSummary::from)
+                .collect(),
+            pending_validators: validators
+                .pending_validators
+                .into_iter()
+                .map(SuiValidatorSummary::from)
+                .collect(),
+            next_epoch_validators: validators
+                .next_epoch_validators
+                .into_iter()
+                .map(SuiValidatorMetadata::from)
+                .collect(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename = "ValidatorSummary", rename_all = "camelCase")]
+pub struct SuiValidatorSummary {
+    pub metadata: SuiValidatorMetadata,
+    pub stake: u64,
+    pub delegation: u64,
+    pub delegator_count: u64,
+}
+
+impl From<Validator> for SuiValidatorSummary {
+    fn from(validator: Validator) -> Self {
+        Self {
+            metadata: validator.metadata.into(),
+            stake: validator.stake.value(),
+            delegation: validator.delegation,
+            delegator_count: validator.delegator_count,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename = "ValidatorMetadata", rename_all = "camelCase")]
+pub struct SuiValidatorMetadata {
+    pub sui_address: SuiAddress,
+    pub pubkey_bytes: Base64,
+    pub protocol_pubkey_bytes: Base64,
+    pub name: String,
+    pub description: String,
+    pub image_url: String,
+    pub project_url: String,
+    pub net_address: String,
+    pub p2p_address: String,
+    pub consensus_address: String,
+    pub next_epoch_stake: u64,
+}
+
+impl From<ValidatorMetadata> for SuiValidatorMetadata {
+    fn from(metadata: ValidatorMetadata) -> Self {
+        Self {
+            sui_address: ObjectID::from(metadata.sui_address).into(),
+            pubkey_bytes: Base64::from_bytes(&metadata.pubkey_bytes),
+            protocol_pubkey_bytes: Base64::from_bytes(&metadata.protocol_pubkey_bytes),
+            name: String::from_utf8_lossy(&metadata.name).into_owned(),
+            description: String::from_utf8_lossy(&metadata.description).into_owned(),
+            image_url: String::from_utf8_lossy(&metadata.image_url).into_owned(),
+            project_url: String::from_utf8_lossy(&metadata.project_url).into_owned(),
+            net_address: network_address_to_string(metadata.net_address),
+            p2p_address: network_address_to_string(metadata.p2p_address),
+            consensus_address: network_address_to_string(metadata.consensus_address),
+            next_epoch_stake: metadata.next_epoch_stake,
+        }
+    }
+}
+
+/// Network addresses are stored as the bytes of a multiaddr, or as text.
+fn network_address_to_string(address: Vec<u8>) -> String {
+    match Multiaddr::try_from(address.clone()) {
+        Ok(address) => address.to_string(),
+        Err(_) => String::from_utf8_lossy(&address).into_owned(),
+    }
+}
+
+/// A delegation of stake to a validator, with the rewards it would be paid if it were withdrawn
+/// in the current epoch.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename = "Stake", rename_all = "camelCase")]
+pub struct SuiStake {
+    pub delegation_id: ObjectID,
+    pub validator_address: SuiAddress,
+    pub principal: u64,
+    /// The first epoch the delegation earns rewards in.
+    pub activation_epoch: EpochId,
+    pub estimated_reward: u64,
+}
+
+impl SuiStake {
+    pub fn new(delegation_id: ObjectID, delegation: Delegation, state: &SuiSystemState) -> Self {
+        let principal = delegation.principal.value();
+        let estimated_reward = state
+            .validators
+            .staking_pools
+            .iter()
+            .find(|pool| pool.validator_address == delegation.validator_address)
+            .map(|pool| pool.estimated_rewards(principal, delegation.activation_epoch, state.epoch))
+            .unwrap_or_default();
+        Self {
+            delegation_id,
+            validator_address: ObjectID::from(delegation.validator_address).into(),
+            principal,
+            activation_epoch: delegation.activation_epoch,
+            estimated_reward,
+        }
+    }
+}
+
+/// The annual yield of the delegations to each validator of the committee in `epoch`.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename = "ValidatorApys", rename_all = "camelCase")]
+pub struct SuiValidatorApys {
+    pub epoch: EpochId,
+    pub apys: Vec<SuiValidatorApy>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename = "ValidatorApy", rename_all = "camelCase")]
+pub struct SuiValidatorApy {
+    pub address: SuiAddress,
+    /// The yield of a year, as a fraction of the stake: `0.05` is 5%.
+    pub apy: f64,
+}
+
+impl From<&SuiSystemState> for SuiValidatorApys {
+    fn from(state: &SuiSystemState) -> Self {
+        let apys = state
+            .validators
+            .active_validators
+            .iter()
+            .map(|validator| SuiValidatorApy {
+                address: ObjectID::from(validator.metadata.sui_address).into(),
+                apy: validator_apy(validator, state),
+            })
+            .collect();
+        Self {
+            epoch: state.epoch,
+            apys,
+        }
+    }
+}
+
+fn validator_apy(validator: &Validator, state: &SuiSystemState) -> f64 {
+    state
+        .validators
+        .staking_pools
+        .iter()
+        .find(|pool| pool.validator_address == validator.metadata.sui_address)
+        .map(|pool| pool.apy(state.epoch))
+        .unwrap_or_default()
+}
+
+/// How a validator of the committee took part in `epoch` so far, for delegators to compare
+/// validators by. The checkpoints are those the full node serving the report followed.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename = "ValidatorReport", rename_all = "camelCase")]
+pub struct SuiValidatorReport {
+    pub sui_address: SuiAddress,
+    pub name: String,
+    pub epoch: EpochId,
+    pub apy: f64,
+    /// The checkpoints of the epoch followed.
+    pub checkpoint_count: u64,
+    /// The checkpoints followed whose certificate the validator signed.
+    pub signed_checkpoint_count: u64,
+    /// The fraction of the checkpoints followed the validator signed.
+    pub uptime: f64,
+    /// The checkpoints followed the validator was seen proposing, which its proposal went to
+    /// consensus for.
+    pub proposed_checkpoint_count: u64,
+    /// The fraction of the checkpoints followed the validator was seen proposing.
+    pub consensus_participation: f64,
+    /// The validators which reported this one in the epoch, as not taking its part.
+    pub reported_by: Vec<SuiAddress>,
+}
+
+impl SuiValidatorReport {
+    /// The report of `validator` in the epoch of `state`, given the authorities which took
+    /// part in the checkpoints of the epoch.
+    pub fn new(
+        validator: &Validator,
+        state: &SuiSystemState,
+        participation: &[CheckpointParticipation],
+    ) -> Self {
+        let metadata = &validator.metadata;
+        let name = AuthorityName::try_from(metadata.pubkey_bytes.as_ref()).ok();
+        // A validator whose key is not a valid authority name took part in no checkpoint.
+        let took_part = |names: &[AuthorityName]| match &name {
+            Some(name) => names.contains(name),
+            None => false,
+        };
+        let checkpoint_count = participation.len() as u64;
+        let signed_checkpoint_count = participation
+            .iter()
+            .filter(|participation| took_part(&participation.signers))
+            .count() as u64;
+        let proposed_checkpoint_count = participation
+            .iter()
+            .filter(|participation| took_part(&participation.proposers))
+            .count() as u64;
+        let fraction = |part: u64| {
+            if checkpoint_count == 0 {
+                0.0
+            } else {
+                part as f64 / checkpoint_count as f64
+            }
+        };
+        let reported_by = state
+            .validator_reports
+            .reports
+            .iter()
+            .find(|report| report.reported == metadata.sui_address)
+            .map(|report| {
+                report
+                    .reporters
+                    .iter()
+                    .map(|reporter| ObjectID::from(*reporter).into())
+                    .collect()
+            })
+            .unwrap_or_default();
+        Self {
+            sui_address: ObjectID::from(metadata.sui_address).into(),
+            name: String::from_utf8_lossy(&metadata.name).into_owned(),
+            epoch: state.epoch,
+            apy: validator_apy(validator, state),
+            checkpoint_count,
+            signed_checkpoint_count,
+            uptime: fraction(signed_checkpoint_count),
+            proposed_checkpoint_count,
+            consensus_participation: fraction(proposed_checkpoint_count),
+            reported_by,
+        }
+    }
+}
+
+/// A checkpoint, named by its sequence number or by the digest of its summary.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(untagged)]
+pub enum CheckpointId {
+    SequenceNumber(CheckpointSequenceNumber),
+    Digest(Base64),
+}
+
+/// The summary of a certified checkpoint, and the transactions in it.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename = "Checkpoint", rename_all = "camelCase")]
+pub struct SuiCheckpoint {
+    pub sequence_number: CheckpointSequenceNumber,
+    /// The digest of the summary of the checkpoint.
+    pub digest: Base64,
+    pub previous_digest: Option<Base64>,
+    pub content_digest: Base64,
+    /// The root of the Merkle tree of the transactions, which proofs of inclusion lead to.
+    pub transactions_root: Base64,
+    pub transactions: Vec<TransactionDigest>,
+}
+
+impl SuiCheckpoint {
+    pub fn new(checkpoint: &CertifiedCheckpoint, contents: &CheckpointContents) -> Self {
+        let summary = &checkpoint.checkpoint;
+        Self {
+            sequence_number: summary.sequence_number,
+            digest: Base64::from_bytes(&summary.digest()),
+            previous_digest: summary
+                .previous_digest
+                .map(|digest| Base64::from_bytes(&digest)),
+            content_digest: Base64::from_bytes(&summary.content_digest),
+            transactions_root: Base64::from_bytes(&summary.transactions_root),
+            transactions: contents
+                .transactions
+                .iter()
+                .map(|digests| digests.transaction)
+                .collect(),
+        }
+    }
+}
+
+/// The proof that a transaction is final: the effects of the transaction, the certified
+/// checkpoint they are in with the proof of their inclusion in it, and the committee which
+/// certified the checkpoint. The effects and the checkpoint are in BCS, for clients which trust
+/// the committee to check the proof offline with `SuiFinalityProof::verify`.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename = "FinalityProof", rename_all = "camelCase")]
+pub struct SuiFinalityProof {
+    pub transaction_digest: TransactionDigest,
+    /// The BCS bytes of the `TransactionEffects` of the transaction.
+    pub effects: Base64,
+    pub checkpoint_sequence_number: CheckpointSequenceNumber,
+    /// The BCS bytes of the `CertifiedCheckpoint` the effects are in.
+    pub certified_checkpoint: Base64,
+    pub inclusion_proof: SuiInclusionProof,
+    pub committee: SuiCommittee,
+}
+
+impl SuiFinalityProof {
+    pub fn new(
+        effects: &TransactionEffects,
+        checkpoint: &CertifiedCheckpoint,
+        proof: InclusionProof,
+        committee: &Committee,
+    ) -> Result<Self, anyhow::Error> {
+        Ok(Self {
+            transaction_digest: effects.transaction_digest,
+            effects: Base64::from_bytes(&bcs::to_bytes(effects)?),
+            checkpoint_sequence_number: checkpoint.checkpoint.sequence_number,
+            certified_checkpoint: Base64::from_bytes(&bcs::to_bytes(checkpoint)?),
+            inclusion_proof: proof.into(),
+            committee: committee.into(),
+        })
+    }
+
+    /// Check the proof against `committee`, which the caller trusts rather than the committee
+    /// of the proof, and return the effects of the transaction, which is final if the proof
+    /// holds.
+    pub fn verify(&self, committee: &Committee) -> Result<TransactionEffects, anyhow::Error> {
+        let effects: TransactionEffects = bcs::from_bytes(&self.effects.to_vec()?)?;
+        let checkpoint: CertifiedCheckpoint =
+            bcs::from_bytes(&self.certified_checkpoint.to_vec()?)?;
+        light_client::verify_finality(
+            committee,
+            &checkpoint,
+            &self.transaction_digest,
+            &effects,
+            &self.inclusion_proof.to_inclusion_proof()?,
+        )?;
+        Ok(effects)
+    }
+}
+
+/// The position of the execution digests of a transaction in the contents of a checkpoint, and
+/// the hashes of the siblings of their path to the root of the contents, from the leaf up.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename = "InclusionProof", rename_all = "camelCase")]
+pub struct SuiInclusionProof {
+    pub index: u64,
+    pub leaf_count: u64,
+    pub siblings: Vec<Base64>,
+}
+
+impl SuiInclusionProof {
+    pub fn to_inclusion_proof(&self) -> Result<InclusionProof, anyhow::Error> {
+        let siblings = self
+            .siblings
+            .iter()
+            .map(|sibling| {
+                sibling.to_vec()?.try_into().map_err(|_| {
+                    anyhow::anyhow!("The siblings of an inclusion proof are 32 bytes long")
+                })
+            })
+            .collect::<Result<_, anyhow::Error>>()?;
+        Ok(InclusionProof {
+            index: self.index,
+            leaf_count: self.leaf_count,
+            siblings,
+        })
+    }
+}
+
+impl From<InclusionProof> for SuiInclusionProof {
+    fn from(proof: InclusionProof) -> Self {
+        Self {
+            index: proof.index,
+            leaf_count: proof.leaf_count,
+            siblings: proof
+                .siblings
+                .iter()
+                .map(|sibling| Base64::from_bytes(sibling))
+                .collect(),
+        }
+    }
+}
+
+/// The authorities of an epoch, with their stakes and the keys they sign checkpoints with.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename = "Committee", rename_all = "camelCase")]
+pub struct SuiCommittee {
+    pub epoch: EpochId,
+    pub members: Vec<SuiCommitteeMember>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename = "CommitteeMember", rename_all = "camelCase")]
+pub struct SuiCommitteeMember {
+    pub name: AuthorityName,
+    pub stake: StakeUnit,
+    pub protocol_key: ProtocolPublicKeyBytes,
+}
+
+impl From<&Committee> for SuiCommittee {
+    fn from(committee: &Committee) -> Self {
+        Self {
+            epoch: committee.epoch(),
+            members: committee
+                .voting_rights
+                .iter()
+                .filter_map(|(name, stake)| {
+                    committee
+                        .protocol_keys
+                        .get(name)
+                        .map(|protocol_key| SuiCommitteeMember {
+                            name: *name,
+                            stake: *stake,
+                            protocol_key: *protocol_key,
+                        })
+                })
+                .collect(),
+        }
+    }
+}
+
+impl From<&SuiCommittee> for Committee {
+    fn from(committee: &SuiCommittee) -> Self {
+        Committee::new(
+            committee.epoch,
+            committee
+                .members
+                .iter()
+                .map(|member| (member.name, member.stake))
+                .collect(),
+            committee
+                .members
+                .iter()
+                .map(|member| (member.name, member.protocol_key))
+                .collect(),
+        )
+    }
+}
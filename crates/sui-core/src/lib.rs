@@ -1,21 +1,31 @@
 // Copyright (c) 2021, Facebook, Inc. and its affiliates
 // Copyright (c) 2022, Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
+pub mod anomaly;
+pub mod archive;
 pub mod authority;
 pub mod authority_active;
 pub mod authority_aggregator;
 pub mod authority_batch;
 pub mod authority_client;
 pub mod authority_server;
+pub mod bridge;
 pub mod checkpoints;
 pub mod consensus_adapter;
+pub mod consistency;
 pub mod epoch;
 pub mod event_handler;
 pub mod execution_engine;
 pub mod gateway_state;
 pub mod gateway_types;
+pub mod quorum_driver;
+pub mod replay;
 pub mod safe_client;
+pub mod signature_verifier;
+pub mod snapshot;
 pub mod streamer;
+pub mod transaction_builder;
 pub mod transaction_input_checker;
+pub mod transaction_orchestrator;
 
 mod query_helpers;
@@ -1,9 +1,13 @@
 // Copyright (c) 2022, Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::{authority::SuiDataStore, gateway_types::TransactionEffectsResponse};
+use crate::{
+    authority::SuiDataStore,
+    gateway_types::{SuiTransactionResponseOptions, TransactionEffectsResponse},
+};
 use anyhow::anyhow;
 use serde::{Deserialize, Serialize};
+use sui_types::object_change::TransactionChanges;
 use sui_types::{base_types::*, batch::TxSequenceNumber, error::SuiError, fp_ensure};
 use tracing::debug;
 
@@ -79,13 +83,23 @@ impl<const ALL_OBJ_VER: bool, S: Eq + Serialize + for<'de> Deserialize<'de>>
     pub fn get_transaction(
         database: &SuiDataStore<ALL_OBJ_VER, S>,
         digest: TransactionDigest,
+        options: &SuiTransactionResponseOptions,
     ) -> Result<TransactionEffectsResponse, anyhow::Error> {
         let opt = database.get_certified_transaction(&digest)?;
         match opt {
-            Some(certificate) => Ok(TransactionEffectsResponse {
-                certificate: certificate.try_into()?,
-                effects: database.get_effects(&digest)?.into(),
-            }),
+            Some(certificate) => {
+                let changes = if options.show_balance_changes || options.show_object_changes {
+                    database.get_transaction_changes(&digest)?
+                } else {
+                    TransactionChanges::default()
+                };
+                TransactionEffectsResponse::from_certificate(
+                    certificate,
+                    database.get_effects(&digest)?,
+                    changes,
+                    options,
+                )
+            }
             None => Err(anyhow!(SuiError::TransactionNotFound { digest })),
         }
     }
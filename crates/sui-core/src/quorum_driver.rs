@@ -0,0 +1,431 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! The quorum driver submits a transaction, and then its certificate, to the committee until a
+//! quorum of stake signs, and then executes, it. It keeps track of the validators which did, so
+//! that each retry only goes to the stake still missing, backing off exponentially between
+//! rounds.
+//!
+//! The errors of the validators are told apart: a retryable error, such as a timeout or an
+//! overloaded validator, leaves the validator to a later round; a validator holding a lock on a
+//! conflicting transaction, or rejecting the transaction outright, is not asked again. Once the
+//! stake left to ask cannot make up a quorum any more, the driver fails with
+//! `SuiError::QuorumFailed`, which aggregates the stake and the last error of every validator,
+//! along with the transactions conflicting with this one and the stake which locked the objects
+//! for each, so that a wallet can tell which transaction is already spending them.
+
+use crate::authority_aggregator::{AuthorityAggregator, DEFAULT_RETRIES};
+use crate::authority_client::AuthorityAPI;
+use crate::safe_client::SafeClient;
+use futures::stream::FuturesUnordered;
+use futures::StreamExt;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::future::Future;
+use std::time::Duration;
+use sui_types::base_types::{AuthorityName, TransactionDigest};
+use sui_types::committee::{Committee, StakeUnit};
+use sui_types::error::{SuiError, SuiResult};
+use sui_types::messages::{
+    CertifiedTransaction, ConfirmationTransaction, ConsensusTransaction, Transaction,
+    TransactionEffects, TransactionInfoResponse,
+};
+use tokio::time::timeout;
+use tracing::{debug, Instrument};
+
+#[cfg(test)]
+#[path = "unit_tests/quorum_driver_tests.rs"]
+mod quorum_driver_tests;
+
+#[derive(Clone)]
+pub struct QuorumDriverConfig {
+    /// The time to wait before the first retry, doubled on every following one.
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+    /// The number of rounds of requests to a validator before giving up on it.
+    pub max_rounds: usize,
+    /// The time a validator has to answer a request.
+    pub request_timeout: Duration,
+}
+
+impl Default for QuorumDriverConfig {
+    fn default() -> Self {
+        Self {
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_secs(5),
+            max_rounds: 5,
+            request_timeout: Duration::from_secs(10),
+        }
+    }
+}
+
+/// How the driver treats the error of a validator.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ErrorClass {
+    /// The validator may succeed if asked again later.
+    Retryable,
+    /// The validator rejects the transaction, and would reject it again.
+    NonRetryable,
+    /// The validator locked an input object for another transaction.
+    Conflicting,
+}
+
+pub fn classify_error(error: &SuiError) -> ErrorClass {
+    match error {
+        SuiError::ConflictingTransaction { .. } => ErrorClass::Conflicting,
+        SuiError::LockErrors { errors } => {
+            if errors
+                .iter()
+                .any(|error| classify_error(error) == ErrorClass::Conflicting)
+            {
+                ErrorClass::Conflicting
+            } else {
+                // The validator has not caught up with the input objects yet.
+                ErrorClass::Retryable
+            }
+        }
+        SuiError::RpcError(_)
+        | SuiError::GenericAuthorityError { .. }
+        | SuiError::ValidatorOverloaded { .. }
+        | SuiError::ValidatorHaltedAtEpochEnd
+        | SuiError::EpochEnded { .. }
+        | SuiError::AuthorityShuttingDown
+        | SuiError::ConsensusConnectionBroken(_)
+        | SuiError::FailedToHearBackFromConsensus(_)
+        | SuiError::ListenerCapacityExceeded
+        | SuiError::SharedObjectLockNotSetObject
+        | SuiError::ObjectNotFound { .. }
+        | SuiError::ParentNotfound { .. } => ErrorClass::Retryable,
+        _ => ErrorClass::NonRetryable,
+    }
+}
+
+/// The transactions which hold the locks `error` reports conflicts with.
+fn conflicting_transactions(error: &SuiError) -> BTreeSet<TransactionDigest> {
+    match error {
+        SuiError::ConflictingTransaction {
+            pending_transaction,
+        } => BTreeSet::from([*pending_transaction]),
+        SuiError::LockErrors { errors } => {
+            errors.iter().flat_map(conflicting_transactions).collect()
+        }
+        _ => BTreeSet::new(),
+    }
+}
+
+/// The validators asked so far, and how they answered.
+struct QuorumProgress<'a> {
+    committee: &'a Committee,
+    /// The validators to ask in the next round.
+    pending: BTreeSet<AuthorityName>,
+    /// The last error of every validator which has not succeeded.
+    errors: BTreeMap<AuthorityName, SuiError>,
+    conflicting_stake: StakeUnit,
+    non_retryable_stake: StakeUnit,
+    /// The stake behind each of the transactions conflicting with this one.
+    conflicting_transactions: BTreeMap<TransactionDigest, StakeUnit>,
+}
+
+impl<'a> QuorumProgress<'a> {
+    fn new(committee: &'a Committee, validators: impl Iterator<Item = AuthorityName>) -> Self {
+        Self {
+            committee,
+            pending: validators.collect(),
+            errors: BTreeMap::new(),
+            conflicting_stake: 0,
+            non_retryable_stake: 0,
+            conflicting_transactions: BTreeMap::new(),
+        }
+    }
+
+    fn record_success(&mut self, name: &AuthorityName) {
+        self.pending.remove(name);
+        self.errors.remove(name);
+    }
+
+    fn record_error(&mut self, name: AuthorityName, error: SuiError) {
+        match classify_error(&error) {
+            ErrorClass::Retryable => {}
+            ErrorClass::NonRetryable => {
+                self.pending.remove(&name);
+                self.non_retryable_stake += self.committee.weight(&name);
+            }
+            ErrorClass::Conflicting => {
+                self.pending.remove(&name);
+                let weight = self.committee.weight(&name);
+                self.conflicting_stake += weight;
+                for digest in conflicting_transactions(&error) {
+                    *self.conflicting_transactions.entry(digest).or_default() += weight;
+                }
+            }
+        }
+        self.errors.insert(name, error);
+    }
+
+    fn pending_stake(&self) -> StakeUnit {
+        self.committee.weight_of(self.pending.iter())
+    }
+
+    /// Whether `good_stake`, along with the stake still to ask, can make up a quorum.
+    fn is_possible(&self, good_stake: StakeUnit) -> bool {
+        good_stake + self.pending_stake() >= self.committee.quorum_threshold()
+    }
+
+    fn into_error(self, good_stake: StakeUnit) -> SuiError {
+        SuiError::QuorumFailed {
+            good_stake,
+            retryable_stake: self.pending_stake(),
+            conflicting_stake: self.conflicting_stake,
+            non_retryable_stake: self.non_retryable_stake,
+            conflicting_transactions: self.conflicting_transactions.into_iter().collect(),
+            errors: self.errors.into_iter().collect(),
+        }
+    }
+}
+
+pub struct QuorumDriver<'a, A> {
+    aggregator: &'a AuthorityAggregator<A>,
+    config: QuorumDriverConfig,
+}
+
+impl<'a, A> QuorumDriver<'a, A>
+where
+    A: AuthorityAPI + Send + Sync + 'static + Clone,
+{
+    pub fn new(aggregator: &'a AuthorityAggregator<A>, config: QuorumDriverConfig) -> Self {
+        Self { aggregator, config }
+    }
+
+    pub async fn execute_transaction(
+        &self,
+        transaction: Transaction,
+    ) -> SuiResult<(CertifiedTransaction, TransactionEffects)> {
+        let certificate = self
+            .process_transaction(transaction)
+            .instrument(tracing::debug_span!("process_tx"))
+            .await?;
+        self.aggregator.metrics.total_tx_certificates.inc();
+        let effects = self
+            .process_certificate(certificate.clone())
+            .instrument(tracing::debug_span!("process_cert"))
+            .await?;
+        Ok((certificate, effects))
+    }
+
+    /// Gather the signatures of a quorum of stake on `transaction`, or find a certificate for
+    /// it.
+    pub async fn process_transaction(
+        &self,
+        transaction: Transaction,
+    ) -> SuiResult<CertifiedTransaction> {
+        let committee = &self.aggregator.committee;
+        let required_ids: Vec<_> = transaction
+            .data
+            .input_objects()?
+            .iter()
+            .map(|kind| kind.object_id())
+            .collect();
+        self.aggregator
+            .sync_all_given_objects(&required_ids, self.config.request_timeout)
+            .await?;
+
+        let mut progress =
+            QuorumProgress::new(committee, self.aggregator.authority_clients.keys().copied());
+        let mut signatures = Vec::new();
+        let mut good_stake = 0;
+        let mut backoff = self.config.initial_backoff;
+        for round in 0..self.config.max_rounds {
+            let transaction = &transaction;
+            let mut responses = self.request_pending(&progress, |client| async move {
+                client.handle_transaction(transaction.clone()).await
+            });
+            while let Some((name, response)) = responses.next().await {
+                match response {
+                    Ok(TransactionInfoResponse {
+                        certified_transaction: Some(certificate),
+                        ..
+                    }) => return Ok(certificate),
+                    Ok(TransactionInfoResponse {
+                        signed_transaction: Some(signed),
+                        ..
+                    }) => {
+                        progress.record_success(&name);
+                        signatures.push((name, signed.auth_sign_info.signature));
+                        good_stake += committee.weight(&name);
+                        if good_stake >= committee.quorum_threshold() {
+                            return CertifiedTransaction::new_with_signatures(
+                                transaction.clone(),
+                                &signatures,
+                                committee,
+                            );
+                        }
+                    }
+                    Ok(response) => progress.record_error(
+                        name,
+                        SuiError::ErrorWhileProcessingTransactionTransaction {
+                            err: format!("Unexpected: {:?}", response),
+                        },
+                    ),
+                    Err(error) => progress.record_error(name, error),
+                }
+            }
+            if !self
+                .next_round(round, &progress, good_stake, &mut backoff)
+                .await
+            {
+                break;
+            }
+        }
+        Err(progress.into_error(good_stake))
+    }
+
+    /// Execute `certificate` on a quorum of stake agreeing on its effects.
+    pub async fn process_certificate(
+        &self,
+        certificate: CertifiedTransaction,
+    ) -> SuiResult<TransactionEffects> {
+        let committee = &self.aggregator.committee;
+        let mut progress =
+            QuorumProgress::new(committee, self.aggregator.authority_clients.keys().copied());
+        // The stake behind each of the distinct effects returned.
+        let mut effects_stake: HashMap<[u8; 32], (StakeUnit, TransactionEffects)> = HashMap::new();
+        let mut good_stake = 0;
+        let mut backoff = self.config.initial_backoff;
+        for round in 0..self.config.max_rounds {
+            let certificate = &certificate;
+            let mut responses = self.request_pending_named(&progress, |name, client| {
+                self.execute_certificate_on(name, client, certificate)
+            });
+            while let Some((name, response)) = responses.next().await {
+                match response {
+                    Ok(TransactionInfoResponse {
+                        signed_effects: Some(signed_effects),
+                        ..
+                    }) => {
+                        progress.record_success(&name);
+                        let entry = effects_stake
+                            .entry(signed_effects.digest())
+                            .or_insert((0, signed_effects.effects));
+                        entry.0 += committee.weight(&name);
+                        good_stake = good_stake.max(entry.0);
+                        if entry.0 >= committee.quorum_threshold() {
+                            return Ok(entry.1.clone());
+                        }
+                    }
+                    Ok(_) => progress.record_error(name, SuiError::ErrorWhileRequestingCertificate),
+                    Err(error) => progress.record_error(name, error),
+                }
+            }
+            if !self
+                .next_round(round, &progress, good_stake, &mut backoff)
+                .await
+            {
+                break;
+            }
+        }
+        Err(progress.into_error(good_stake))
+    }
+
+    /// Execute `certificate` on the validator `name`, bringing it up to date with the
+    /// certificates it depends on if it is missing some.
+    async fn execute_certificate_on(
+        &self,
+        name: AuthorityName,
+        client: &SafeClient<A>,
+        certificate: &CertifiedTransaction,
+    ) -> SuiResult<TransactionInfoResponse> {
+        let response = if certificate.contains_shared_object() {
+            client
+                .handle_consensus_transaction(ConsensusTransaction::UserTransaction(Box::new(
+                    certificate.clone(),
+                )))
+                .await
+        } else {
+            client
+                .handle_confirmation_transaction(ConfirmationTransaction::new(certificate.clone()))
+                .await
+        };
+        if !matches!(response, Err(SuiError::LockErrors { .. })) {
+            return response;
+        }
+        debug!(authority =? name, error =? response, "Authority out of date - syncing certificates");
+        self.aggregator
+            .sync_certificate_to_authority_with_timeout(
+                ConfirmationTransaction::new(certificate.clone()),
+                name,
+                self.config.request_timeout,
+                DEFAULT_RETRIES,
+            )
+            .await?;
+        client
+            .handle_confirmation_transaction(ConfirmationTransaction::new(certificate.clone()))
+            .await
+    }
+
+    fn request_pending<'s, F, Fut>(
+        &'s self,
+        progress: &QuorumProgress,
+        request: F,
+    ) -> FuturesUnordered<
+        impl Future<Output = (AuthorityName, SuiResult<TransactionInfoResponse>)> + 's,
+    >
+    where
+        F: Fn(&'s SafeClient<A>) -> Fut,
+        Fut: Future<Output = SuiResult<TransactionInfoResponse>> + 's,
+    {
+        self.request_pending_named(progress, |_, client| request(client))
+    }
+
+    /// Send `request` to every validator left to ask, each within the request timeout.
+    fn request_pending_named<'s, F, Fut>(
+        &'s self,
+        progress: &QuorumProgress,
+        request: F,
+    ) -> FuturesUnordered<
+        impl Future<Output = (AuthorityName, SuiResult<TransactionInfoResponse>)> + 's,
+    >
+    where
+        F: Fn(AuthorityName, &'s SafeClient<A>) -> Fut,
+        Fut: Future<Output = SuiResult<TransactionInfoResponse>> + 's,
+    {
+        let request_timeout = self.config.request_timeout;
+        progress
+            .pending
+            .iter()
+            .map(|name| {
+                let name = *name;
+                let response = request(name, &self.aggregator.authority_clients[&name]);
+                async move {
+                    let response = timeout(request_timeout, response)
+                        .await
+                        .unwrap_or_else(|_| Err(SuiError::RpcError("Request timed out".into())));
+                    (name, response)
+                }
+                .instrument(tracing::trace_span!("quorum_driver_request", authority =? name))
+            })
+            .collect()
+    }
+
+    /// Wait before the round after `round`, if a quorum is still within reach and rounds are
+    /// left.
+    async fn next_round(
+        &self,
+        round: usize,
+        progress: &QuorumProgress<'_>,
+        good_stake: StakeUnit,
+        backoff: &mut Duration,
+    ) -> bool {
+        if !progress.is_possible(good_stake) || round + 1 >= self.config.max_rounds {
+            return false;
+        }
+        debug!(
+            round,
+            good_stake,
+            pending_stake = progress.pending_stake(),
+            ?backoff,
+            "Retrying the validators missing from the quorum"
+        );
+        tokio::time::sleep(*backoff).await;
+        *backoff = (*backoff * 2).min(self.config.max_backoff);
+        true
+    }
+}
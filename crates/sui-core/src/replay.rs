@@ -0,0 +1,283 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Re-execution of a transaction of the past, to debug executions that validators disagree on.
+//! The transaction is executed again on the objects it read, at the versions it read them,
+//! and the effects of the replay are compared with the ones signed by the validators.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Debug;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use itertools::Itertools;
+use move_binary_format::access::ModuleAccess;
+use move_binary_format::CompiledModule;
+use parking_lot::Mutex;
+use sui_adapter::adapter;
+use sui_types::base_types::{ObjectID, SequenceNumber};
+use sui_types::error::{SuiError, SuiResult};
+use sui_types::gas::{self, SuiGasStatus};
+use sui_types::messages::{CertifiedTransaction, InputObjectKind, TransactionEffects};
+use sui_types::object::Object;
+use sui_types::protocol_config::ProtocolConfig;
+use sui_types::storage::BackingPackageStore;
+use sui_types::{MOVE_STDLIB_ADDRESS, SUI_FRAMEWORK_ADDRESS};
+
+use crate::authority::AuthorityTemporaryStore;
+use crate::execution_engine;
+
+#[cfg(test)]
+#[path = "unit_tests/replay_tests.rs"]
+mod replay_tests;
+
+/// Where the objects of the past are read from, such as the BCS API of a full node.
+#[async_trait]
+pub trait ObjectSource {
+    /// The object at `version`, or at its latest version if `version` is `None`.
+    async fn get_object(
+        &self,
+        object_id: ObjectID,
+        version: Option<SequenceNumber>,
+    ) -> SuiResult<Object>;
+}
+
+/// The packages which the replayed transaction loads modules from, and the child objects it
+/// loads during execution, at their versions before the transaction.
+struct ReplayPackageStore {
+    packages: BTreeMap<ObjectID, Object>,
+    child_objects: BTreeMap<ObjectID, Object>,
+    /// The objects the replay read which are not among `child_objects`, so that the replay
+    /// fails rather than differing from the original execution.
+    missing: Mutex<BTreeSet<ObjectID>>,
+}
+
+impl BackingPackageStore for ReplayPackageStore {
+    fn get_package(&self, package_id: &ObjectID) -> SuiResult<Option<Object>> {
+        Ok(self.packages.get(package_id).cloned())
+    }
+
+    fn get_object(&self, object_id: &ObjectID) -> SuiResult<Option<Object>> {
+        match self.child_objects.get(object_id) {
+            Some(object) => Ok(Some(object.clone())),
+            None => {
+                self.missing.lock().insert(*object_id);
+                Err(SuiError::ObjectNotFound {
+                    object_id: *object_id,
+                })
+            }
+        }
+    }
+}
+
+/// Execute `certificate` again on the objects it read when it had `effects`, as given by
+/// `source`, and return the effects of the replay. Gas is metered as in this build.
+pub async fn replay_transaction(
+    source: &(impl ObjectSource + Sync),
+    certificate: &CertifiedTransaction,
+    effects: &TransactionEffects,
+) -> SuiResult<TransactionEffects> {
+    let data = &certificate.data;
+    // Shared objects are not read at a version the sender chose, but at the one consensus
+    // assigned, which the effects record.
+    let shared_versions: BTreeMap<_, _> = effects
+        .shared_objects
+        .iter()
+        .map(|(id, version, _)| (*id, *version))
+        .collect();
+    let mut input_objects = Vec::new();
+    for kind in data.input_objects()? {
+        let version = match kind {
+            InputObjectKind::MovePackage(_) => None,
+            InputObjectKind::ImmOrOwnedMoveObject((_, version, _)) => Some(version),
+            InputObjectKind::SharedMoveObject(id)
+            | InputObjectKind::ReadOnlySharedMoveObject(id) => Some(
+                *shared_versions
+                    .get(&id)
+                    .ok_or_else(|| SuiError::GenericAuthorityError {
+                        error: format!("The effects have no version of the shared object {id}"),
+                    })?,
+            ),
+        };
+        let object = source.get_object(kind.object_id(), version).await?;
+        input_objects.push((kind, object));
+    }
+    let packages = fetch_packages(source, &input_objects).await?;
+    let child_objects = fetch_child_objects(source, effects, &input_objects).await;
+
+    // The gas status of check_transaction_input.
+    let mut gas_status = if data.kind.is_system_tx() {
+        SuiGasStatus::new_unmetered()
+    } else {
+        gas::start_gas_metering(ProtocolConfig::get_for_max_version(), data.gas_budget, 1, 1)?
+    };
+    if data.contains_shared_object() {
+        gas_status.charge_consensus()?;
+    }
+
+    let shared_object_refs: Vec<_> = input_objects
+        .iter()
+        .filter(|(kind, _)| kind.is_shared_object())
+        .map(|(_, object)| object.compute_object_reference())
+        .sorted()
+        .collect();
+    let transaction_dependencies = input_objects
+        .iter()
+        .map(|(_, object)| object.previous_transaction)
+        .collect();
+    let transaction_digest = *certificate.digest();
+    let store = Arc::new(ReplayPackageStore {
+        packages,
+        child_objects,
+        missing: Mutex::new(BTreeSet::new()),
+    });
+    let mut temporary_store =
+        AuthorityTemporaryStore::new(store.clone(), input_objects, transaction_digest);
+    let native_functions =
+        sui_framework::natives::all_natives(MOVE_STDLIB_ADDRESS, SUI_FRAMEWORK_ADDRESS);
+    let move_vm = Arc::new(adapter::new_move_vm(native_functions.clone())?);
+    let replayed = execution_engine::execute_transaction_to_effects(
+        shared_object_refs,
+        &mut temporary_store,
+        data.clone(),
+        transaction_digest,
+        transaction_dependencies,
+        &move_vm,
+        &native_functions,
+        &mut gas_status,
+        certificate.auth_sign_info.epoch,
+    )?;
+    let missing = store.missing.lock();
+    if !missing.is_empty() {
+        return Err(SuiError::GenericAuthorityError {
+            error: format!(
+                "The replay read the objects {missing:?}, whose versions before the \
+                 transaction its effects don't record",
+            ),
+        });
+    }
+    Ok(replayed)
+}
+
+/// The child objects the transaction loaded during execution, at their versions before it.
+/// They are not among its inputs, but as they can't be dropped, the effects record each of
+/// them as mutated, deleted or wrapped, one version after the one it was loaded at. The
+/// objects which were not standalone at that version, such as the ones unwrapped and
+/// wrapped again, are not child objects and are skipped.
+async fn fetch_child_objects(
+    source: &(impl ObjectSource + Sync),
+    effects: &TransactionEffects,
+    input_objects: &[(InputObjectKind, Object)],
+) -> BTreeMap<ObjectID, Object> {
+    let written = effects
+        .mutated
+        .iter()
+        .map(|(object_ref, _)| object_ref)
+        .chain(&effects.deleted)
+        .chain(&effects.wrapped);
+    let mut child_objects = BTreeMap::new();
+    for (id, version, _) in written {
+        if input_objects.iter().any(|(_, object)| object.id() == *id) {
+            continue;
+        }
+        let version = match version.decrement() {
+            Ok(version) => version,
+            Err(_) => continue,
+        };
+        if let Ok(object) = source.get_object(*id, Some(version)).await {
+            child_objects.insert(*id, object);
+        }
+    }
+    child_objects
+}
+
+/// The packages of the inputs, the framework and their dependencies, at their latest version
+/// as packages are immutable.
+async fn fetch_packages(
+    source: &(impl ObjectSource + Sync),
+    input_objects: &[(InputObjectKind, Object)],
+) -> SuiResult<BTreeMap<ObjectID, Object>> {
+    let mut pending: Vec<ObjectID> = input_objects
+        .iter()
+        .filter(|(_, object)| object.is_package())
+        .map(|(_, object)| object.id())
+        .collect();
+    pending.push(ObjectID::from(SUI_FRAMEWORK_ADDRESS));
+    pending.push(ObjectID::from(MOVE_STDLIB_ADDRESS));
+
+    let mut packages = BTreeMap::new();
+    while let Some(id) = pending.pop() {
+        if packages.contains_key(&id) {
+            continue;
+        }
+        let package = match input_objects.iter().find(|(_, object)| object.id() == id) {
+            Some((_, object)) => object.clone(),
+            None => source.get_object(id, None).await?,
+        };
+        if let Some(move_package) = package.data.try_as_package() {
+            for bytes in move_package.serialized_module_map().values() {
+                let module = CompiledModule::deserialize(bytes).map_err(|error| {
+                    SuiError::ModuleDeserializationFailure {
+                        error: error.to_string(),
+                    }
+                })?;
+                pending.extend(
+                    module
+                        .immediate_dependencies()
+                        .iter()
+                        .map(|dependency| ObjectID::from(*dependency.address())),
+                );
+            }
+        }
+        packages.insert(id, package);
+    }
+    Ok(packages)
+}
+
+/// How the effects of a replay differ from the effects signed by the validators, one entry
+/// for each field which differs.
+pub fn diff_effects(expected: &TransactionEffects, replayed: &TransactionEffects) -> Vec<String> {
+    let mut differences = Vec::new();
+    let mut compare = |field: &str, expected: &dyn Debug, replayed: &dyn Debug| {
+        let (expected, replayed) = (format!("{expected:?}"), format!("{replayed:?}"));
+        if expected != replayed {
+            differences.push(format!(
+                "{field}:\n  expected: {expected}\n  replayed: {replayed}"
+            ));
+        }
+    };
+    compare("status", &expected.status, &replayed.status);
+    compare(
+        "shared_objects",
+        &expected.shared_objects,
+        &replayed.shared_objects,
+    );
+    compare(
+        "transaction_digest",
+        &expected.transaction_digest,
+        &replayed.transaction_digest,
+    );
+    compare("created", &expected.created, &replayed.created);
+    compare("mutated", &expected.mutated, &replayed.mutated);
+    compare("unwrapped", &expected.unwrapped, &replayed.unwrapped);
+    compare("deleted", &expected.deleted, &replayed.deleted);
+    compare("wrapped", &expected.wrapped, &replayed.wrapped);
+    compare(
+        "unwrapped_then_deleted",
+        &expected.unwrapped_then_deleted,
+        &replayed.unwrapped_then_deleted,
+    );
+    compare(
+        "owner_changes",
+        &expected.owner_changes,
+        &replayed.owner_changes,
+    );
+    compare("gas_object", &expected.gas_object, &replayed.gas_object);
+    compare("events", &expected.events, &replayed.events);
+    compare(
+        "dependencies",
+        &expected.dependencies,
+        &replayed.dependencies,
+    );
+    differences
+}
@@ -2,6 +2,7 @@
 // Copyright (c) 2022, Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::anomaly::{self, Anomaly};
 use crate::authority_client::{AuthorityAPI, BatchInfoResponseItemStream};
 use futures::StreamExt;
 use sui_types::batch::{AuthorityBatch, SignedBatch, TxSequenceNumber, UpdateItem};
@@ -12,7 +13,6 @@ use sui_types::{
     error::{SuiError, SuiResult},
     messages::*,
 };
-use tracing::info;
 
 #[derive(Clone)]
 pub struct SafeClient<C> {
@@ -78,10 +78,11 @@ impl<C> SafeClient<C> {
 
         if let Some(signed_effects) = &response.signed_effects {
             // Check signature
+            let key = self.committee.protocol_key(&self.address)?;
             signed_effects
                 .auth_signature
                 .signature
-                .verify(&signed_effects.effects, self.address)?;
+                .verify(&signed_effects.effects, &key)?;
             // Checks it concerns the right tx
             fp_ensure!(
                 signed_effects.effects.transaction_digest == digest,
@@ -188,9 +189,8 @@ impl<C> SafeClient<C> {
         )>,
     ) -> SuiResult {
         // check the signature of the batch
-        signed_batch
-            .signature
-            .verify(&signed_batch.batch, signed_batch.authority)?;
+        let key = self.committee.protocol_key(&signed_batch.authority)?;
+        signed_batch.signature.verify(&signed_batch.batch, &key)?;
 
         // ensure transactions enclosed match requested range
 
@@ -234,7 +234,7 @@ impl<C> SafeClient<C> {
     /// This function is used by the higher level authority logic to report an
     /// error that could be due to this authority.
     pub fn report_client_error(&self, error: SuiError) {
-        info!(?error, authority =? self.address, "Client error");
+        anomaly::report(Anomaly::invalid_peer_data(self.address, &error));
     }
 }
 
@@ -375,6 +375,15 @@ where
         self.authority_client.handle_checkpoint(request).await
     }
 
+    /// The effects of a dry run aren't signed, so they can only be trusted as far as the
+    /// authority is.
+    pub async fn handle_dry_run(
+        &self,
+        request: DryRunTransactionRequest,
+    ) -> Result<DryRunTransactionResponse, SuiError> {
+        self.authority_client.handle_dry_run(request).await
+    }
+
     /// Handle Batch information requests for this authority.
     pub async fn handle_batch_stream(
         &self,
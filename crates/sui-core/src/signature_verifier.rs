@@ -0,0 +1,188 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Batched and cached verification of certificates. The certificates a validator receives at the
+//! same time have their signatures checked together, which takes far fewer pairings than
+//! checking them one by one. The certificates verified recently are remembered, so that a
+//! certificate received again, from a client retrying, from gossip or from consensus, is not
+//! verified twice.
+
+use crate::authority::AuthorityMetrics;
+use lru::LruCache;
+use parking_lot::Mutex;
+use std::sync::Arc;
+use sui_types::base_types::TransactionDigest;
+use sui_types::committee::{Committee, EpochId};
+use sui_types::crypto::VerificationObligation;
+use sui_types::error::{SuiError, SuiResult};
+use sui_types::messages::CertifiedTransaction;
+use tokio::sync::oneshot;
+
+#[cfg(test)]
+#[path = "unit_tests/signature_verifier_tests.rs"]
+mod signature_verifier_tests;
+
+/// The number of verified certificates remembered.
+pub const DEFAULT_VERIFIED_CERTIFICATES_CACHE_SIZE: usize = 100_000;
+
+/// The most certificates checked in one batch. A batch with an invalid certificate is checked
+/// again certificate by certificate, which bounds the work a single bad certificate causes.
+pub const MAX_VERIFICATION_BATCH_SIZE: usize = 64;
+
+/// What identifies a verified certificate: the epoch of the committee which verified it, its
+/// transaction, and the signatures vouching for the transaction. Another certificate of the same
+/// transaction, with other signatures, is verified on its own.
+#[derive(Clone, PartialEq, Eq, Hash)]
+struct CertificateKey {
+    epoch: EpochId,
+    digest: TransactionDigest,
+    signatures: Vec<u8>,
+}
+
+impl CertificateKey {
+    fn new(certificate: &CertifiedTransaction, committee: &Committee) -> Self {
+        Self {
+            epoch: committee.epoch(),
+            digest: *certificate.digest(),
+            signatures: bcs::to_bytes(&(&certificate.auth_sign_info, &certificate.tx_signature))
+                .expect("Serializing signatures cannot fail"),
+        }
+    }
+}
+
+struct PendingCertificate {
+    certificate: CertifiedTransaction,
+    committee: Arc<Committee>,
+    key: CertificateKey,
+    result: oneshot::Sender<SuiResult>,
+}
+
+/// Verifies the certificates handled by an authority. There is no task of its own: the first
+/// caller to find no batch in flight checks the certificates pending at the time, and keeps
+/// checking those which arrive meanwhile, so batches grow with the load and a lone certificate
+/// is checked right away.
+pub struct SignatureVerifier {
+    verified: Mutex<LruCache<CertificateKey, ()>>,
+    pending: Mutex<Vec<PendingCertificate>>,
+    /// Held by the caller checking batches.
+    verifying: Mutex<()>,
+    metrics: &'static AuthorityMetrics,
+}
+
+impl SignatureVerifier {
+    pub fn new(cache_size: usize, metrics: &'static AuthorityMetrics) -> Self {
+        Self {
+            verified: Mutex::new(LruCache::new(cache_size)),
+            pending: Mutex::new(Vec::new()),
+            verifying: Mutex::new(()),
+            metrics,
+        }
+    }
+
+    /// Check that `certificate` holds the signatures of a quorum of `committee`.
+    pub async fn verify(
+        &self,
+        certificate: &CertifiedTransaction,
+        committee: Arc<Committee>,
+    ) -> SuiResult {
+        if certificate.is_verified {
+            return Ok(());
+        }
+        let key = CertificateKey::new(certificate, &committee);
+        if self.verified.lock().get(&key).is_some() {
+            self.metrics.verified_certificates_cache_hits.inc();
+            return Ok(());
+        }
+
+        let (sender, receiver) = oneshot::channel();
+        self.pending.lock().push(PendingCertificate {
+            certificate: certificate.clone(),
+            committee,
+            key,
+            result: sender,
+        });
+        self.process_pending();
+        receiver.await.unwrap_or_else(|_| {
+            Err(SuiError::GenericAuthorityError {
+                error: "The verification of the certificate was dropped".to_string(),
+            })
+        })
+    }
+
+    /// Whether `certificate` was verified recently under `committee`.
+    pub fn is_cached(&self, certificate: &CertifiedTransaction, committee: &Committee) -> bool {
+        self.verified
+            .lock()
+            .contains(&CertificateKey::new(certificate, committee))
+    }
+
+    fn process_pending(&self) {
+        loop {
+            {
+                let _verifying = match self.verifying.try_lock() {
+                    Some(guard) => guard,
+                    // The caller holding the lock checks our certificate.
+                    None => return,
+                };
+                loop {
+                    let batch: Vec<_> = {
+                        let mut pending = self.pending.lock();
+                        let size = pending.len().min(MAX_VERIFICATION_BATCH_SIZE);
+                        pending.drain(..size).collect()
+                    };
+                    if batch.is_empty() {
+                        break;
+                    }
+                    self.verify_batch(batch);
+                }
+            }
+            // A certificate may have been added after the last batch was taken but before the
+            // lock was released, by a caller which then failed to take the lock.
+            if self.pending.lock().is_empty() {
+                return;
+            }
+        }
+    }
+
+    fn verify_batch(&self, batch: Vec<PendingCertificate>) {
+        self.metrics
+            .certificate_verification_batch_size
+            .observe(batch.len() as f64);
+
+        let mut obligation = VerificationObligation::default();
+        let mut batched = Vec::with_capacity(batch.len());
+        for pending in batch {
+            match pending
+                .certificate
+                .add_to_verification_obligation(&pending.committee, &mut obligation)
+            {
+                Ok(()) => batched.push(pending),
+                Err(error) => {
+                    let _ = pending.result.send(Err(error));
+                }
+            }
+        }
+        if batched.is_empty() {
+            return;
+        }
+
+        if obligation.verify_all().is_ok() {
+            let mut verified = self.verified.lock();
+            for pending in batched {
+                verified.put(pending.key, ());
+                let _ = pending.result.send(Ok(()));
+            }
+            return;
+        }
+
+        // Some certificate of the batch is invalid. Find out which.
+        self.metrics.certificate_verification_batch_failures.inc();
+        for pending in batched {
+            let result = pending.certificate.verify(&pending.committee);
+            if result.is_ok() {
+                self.verified.lock().put(pending.key, ());
+            }
+            let _ = pending.result.send(result);
+        }
+    }
+}
@@ -0,0 +1,92 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Export of the formal snapshot of an epoch from the store of a node, and import of one into
+//! the empty store of a new node, which then syncs the transactions of the epochs after it
+//! rather than the ones since genesis.
+
+use std::fs;
+use std::path::Path;
+
+use sui_types::committee::Committee;
+use sui_types::error::{SuiError, SuiResult};
+use sui_types::fp_ensure;
+use sui_types::messages_checkpoint::CertifiedEpochHandoff;
+use sui_types::snapshot::ObjectSnapshot;
+use tracing::info;
+
+use crate::authority::AuthorityStore;
+use crate::epoch::EpochInfoLocals;
+
+/// Read the BCS encoded snapshot in the file at `path`.
+pub fn read_snapshot(path: &Path) -> SuiResult<ObjectSnapshot> {
+    let bytes = fs::read(path).map_err(snapshot_error)?;
+    bcs::from_bytes(&bytes).map_err(snapshot_error)
+}
+
+/// Write `snapshot`, BCS encoded, to the file at `path`.
+pub fn write_snapshot(path: &Path, snapshot: &ObjectSnapshot) -> SuiResult {
+    let bytes = bcs::to_bytes(snapshot).map_err(snapshot_error)?;
+    fs::write(path, bytes).map_err(snapshot_error)
+}
+
+fn snapshot_error(error: impl ToString) -> SuiError {
+    SuiError::InvalidSnapshot {
+        error: error.to_string(),
+    }
+}
+
+/// The snapshot of the objects live at the end of the epoch of the last of `handoffs`, which
+/// hand off each epoch from the genesis one to it.
+pub fn export_snapshot(
+    store: &AuthorityStore,
+    handoffs: Vec<CertifiedEpochHandoff>,
+) -> SuiResult<ObjectSnapshot> {
+    let epoch = handoffs
+        .last()
+        .map(|handoff| handoff.handoff.epoch)
+        .ok_or_else(|| SuiError::InvalidSnapshot {
+            error: "No handoff to export the snapshot of".to_string(),
+        })?;
+    let objects = store
+        .live_object_refs_at_end_of_epoch(epoch)?
+        .into_iter()
+        .map(|(object_id, version, _)| {
+            store
+                .get_object_by_key(&object_id, version)?
+                .ok_or(SuiError::ObjectNotFound { object_id })
+        })
+        .collect::<SuiResult<_>>()?;
+    Ok(ObjectSnapshot { handoffs, objects })
+}
+
+/// Write the objects of `snapshot` into `store`, which must be empty, once the snapshot is
+/// verified from `genesis_committee`. The store then starts at the epoch after the one of the
+/// snapshot, whose committee is returned.
+pub async fn import_snapshot(
+    store: &AuthorityStore,
+    snapshot: &ObjectSnapshot,
+    genesis_committee: Committee,
+) -> SuiResult<Committee> {
+    fp_ensure!(
+        store.database_is_empty()?,
+        SuiError::InvalidSnapshot {
+            error: "Snapshots are only imported into an empty store".to_string(),
+        }
+    );
+    let committee = snapshot.verify(genesis_committee)?;
+    store
+        .bulk_object_insert(&snapshot.objects.iter().collect::<Vec<_>>())
+        .await?;
+    store.insert_new_epoch_info(EpochInfoLocals {
+        committee: committee.clone(),
+        validator_halted: false,
+    })?;
+    info!(
+        epoch = committee.epoch(),
+        objects = snapshot.objects.len(),
+        "Imported the snapshot of the end of epoch {}",
+        committee.epoch() - 1
+    );
+    Ok(committee)
+}
@@ -0,0 +1,282 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use anyhow::anyhow;
+use async_trait::async_trait;
+use move_core_types::identifier::Identifier;
+use move_core_types::language_storage::TypeTag;
+use tracing::debug;
+
+use sui_adapter::adapter::resolve_and_type_check;
+use sui_json::{resolve_move_function_args, SuiJsonCallArg, SuiJsonValue};
+use sui_types::base_types::{ObjectID, ObjectInfo, ObjectRef, SuiAddress};
+use sui_types::gas_coin::GasCoin;
+use sui_types::messages::{CallArg, TransactionData};
+use sui_types::object::Object;
+use sui_types::{coin, SUI_FRAMEWORK_ADDRESS};
+
+/// The object reads needed to build a transaction. Implemented by the gateway, which
+/// fetches the latest objects from the authorities, and by the authority itself, which
+/// serves them from its own store.
+#[async_trait]
+pub trait DataReader {
+    /// Return the latest version of the object.
+    async fn get_latest_object(&self, object_id: &ObjectID) -> Result<Object, anyhow::Error>;
+
+    /// Return the objects owned by `address`.
+    async fn get_owned_objects(
+        &self,
+        address: SuiAddress,
+    ) -> Result<Vec<ObjectInfo>, anyhow::Error>;
+}
+
+/// Builds ready-to-sign `TransactionData` for common operations from high level parameters,
+/// looking up object references with `R` and choosing a gas object for the signer when none
+/// is given.
+pub struct TransactionBuilder<'a, R: ?Sized>(pub &'a R);
+
+impl<'a, R> TransactionBuilder<'a, R>
+where
+    R: DataReader + Sync + ?Sized,
+{
+    async fn get_object_ref(&self, object_id: &ObjectID) -> Result<ObjectRef, anyhow::Error> {
+        Ok(self
+            .0
+            .get_latest_object(object_id)
+            .await?
+            .compute_object_reference())
+    }
+
+    async fn get_framework_object_ref(&self) -> Result<ObjectRef, anyhow::Error> {
+        self.get_object_ref(&ObjectID::from(SUI_FRAMEWORK_ADDRESS))
+            .await
+    }
+
+    /// Return the reference of `gas` if given, otherwise of the first coin owned by
+    /// `address` with a balance of at least `budget` which is not one of `used_coins`.
+    pub async fn choose_gas_for_address(
+        &self,
+        address: SuiAddress,
+        budget: u64,
+        gas: Option<ObjectID>,
+        used_coins: Vec<ObjectID>,
+    ) -> Result<ObjectRef, anyhow::Error> {
+        if let Some(id) = gas {
+            self.get_object_ref(&id).await
+        } else {
+            let used_coins = used_coins.into_iter().collect::<BTreeSet<_>>();
+            for (id, balance) in self.get_owned_coins(address).await? {
+                if balance >= budget && !used_coins.contains(&id.0) {
+                    return Ok(id);
+                }
+            }
+            Err(anyhow!(
+                "No non-argument gas objects found with value >= budget {}",
+                budget
+            ))
+        }
+    }
+
+    async fn get_owned_coins(
+        &self,
+        address: SuiAddress,
+    ) -> Result<Vec<(ObjectRef, u64)>, anyhow::Error> {
+        let mut coins = Vec::new();
+        for info in self.0.get_owned_objects(address).await? {
+            if info.type_ == GasCoin::type_().to_string() {
+                let object = self.0.get_latest_object(&info.object_id).await?;
+                let gas_coin = GasCoin::try_from(object.data.try_as_move().unwrap())?;
+                coins.push((object.compute_object_reference(), gas_coin.value()));
+            }
+        }
+        Ok(coins)
+    }
+
+    pub async fn transfer_coin(
+        &self,
+        signer: SuiAddress,
+        object_id: ObjectID,
+        gas: Option<ObjectID>,
+        gas_budget: u64,
+        recipient: SuiAddress,
+    ) -> Result<TransactionData, anyhow::Error> {
+        let gas_payment = self
+            .choose_gas_for_address(signer, gas_budget, gas, vec![object_id])
+            .await?;
+        let object_ref = self.get_object_ref(&object_id).await?;
+        let data =
+            TransactionData::new_transfer(recipient, object_ref, signer, gas_payment, gas_budget);
+        Ok(data)
+    }
+
+    /// Transfer `amount` SUI from `sui_object_id` to `recipient`, or the whole coin if
+    /// `amount` is `None`. The coin also pays for the gas.
+    pub async fn transfer_sui(
+        &self,
+        signer: SuiAddress,
+        sui_object_id: ObjectID,
+        gas_budget: u64,
+        recipient: SuiAddress,
+        amount: Option<u64>,
+    ) -> Result<TransactionData, anyhow::Error> {
+        let object_ref = self.get_object_ref(&sui_object_id).await?;
+        let data =
+            TransactionData::new_transfer_sui(recipient, signer, amount, object_ref, gas_budget);
+        Ok(data)
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub async fn move_call(
+        &self,
+        signer: SuiAddress,
+        package_object_id: ObjectID,
+        module: String,
+        function: String,
+        type_arguments: Vec<TypeTag>,
+        arguments: Vec<SuiJsonValue>,
+        gas: Option<ObjectID>,
+        gas_budget: u64,
+    ) -> Result<TransactionData, anyhow::Error> {
+        let module = Identifier::new(module)?;
+        let function = Identifier::new(function)?;
+        let package_obj = self.0.get_latest_object(&package_object_id).await?;
+        let package_obj_ref = package_obj.compute_object_reference();
+        let package = package_obj
+            .data
+            .try_as_package()
+            .ok_or_else(|| anyhow!("Cannot get package from object"))?;
+        let json_args =
+            resolve_move_function_args(package, module.clone(), function.clone(), arguments)?;
+
+        // Fetch all the objects needed for this call
+        let mut objects = BTreeMap::new();
+        let mut args = Vec::with_capacity(json_args.len());
+
+        for json_arg in json_args {
+            args.push(match json_arg {
+                SuiJsonCallArg::Object(id) => {
+                    let obj = self.0.get_latest_object(&id).await?;
+                    let arg = if obj.is_shared() {
+                        CallArg::SharedObject(id)
+                    } else {
+                        CallArg::ImmOrOwnedObject(obj.compute_object_reference())
+                    };
+                    objects.insert(id, obj);
+                    arg
+                }
+                SuiJsonCallArg::Pure(bytes) => CallArg::Pure(bytes),
+            })
+        }
+
+        let forbidden_gas_objects = objects.keys().copied().collect();
+        let gas = self
+            .choose_gas_for_address(signer, gas_budget, gas, forbidden_gas_objects)
+            .await?;
+
+        // Pass in the objects for a deeper check
+        let is_genesis = false;
+        let compiled_module = package.deserialize_module(&module)?;
+        resolve_and_type_check(
+            &objects,
+            &compiled_module,
+            &function,
+            &type_arguments,
+            args.clone(),
+            is_genesis,
+        )?;
+
+        let data = TransactionData::new_move_call(
+            signer,
+            package_obj_ref,
+            module,
+            function,
+            type_arguments,
+            gas,
+            args,
+            gas_budget,
+        );
+
+        debug!(?data, "Created Move Call transaction data");
+        Ok(data)
+    }
+
+    pub async fn publish(
+        &self,
+        signer: SuiAddress,
+        package_bytes: Vec<Vec<u8>>,
+        gas: Option<ObjectID>,
+        gas_budget: u64,
+    ) -> Result<TransactionData, anyhow::Error> {
+        let gas = self
+            .choose_gas_for_address(signer, gas_budget, gas, vec![])
+            .await?;
+        let data = TransactionData::new_module(signer, gas, package_bytes, gas_budget);
+        Ok(data)
+    }
+
+    pub async fn split_coin(
+        &self,
+        signer: SuiAddress,
+        coin_object_id: ObjectID,
+        split_amounts: Vec<u64>,
+        gas: Option<ObjectID>,
+        gas_budget: u64,
+    ) -> Result<TransactionData, anyhow::Error> {
+        let gas = self
+            .choose_gas_for_address(signer, gas_budget, gas, vec![coin_object_id])
+            .await?;
+        let coin_object = self.0.get_latest_object(&coin_object_id).await?;
+        let coin_object_ref = coin_object.compute_object_reference();
+        let coin_type = coin_object.get_move_template_type()?;
+        let data = TransactionData::new_move_call(
+            signer,
+            self.get_framework_object_ref().await?,
+            coin::COIN_MODULE_NAME.to_owned(),
+            coin::COIN_SPLIT_VEC_FUNC_NAME.to_owned(),
+            vec![coin_type],
+            gas,
+            vec![
+                CallArg::ImmOrOwnedObject(coin_object_ref),
+                CallArg::Pure(bcs::to_bytes(&split_amounts)?),
+            ],
+            gas_budget,
+        );
+        debug!(?data, "Created Split Coin transaction data");
+        Ok(data)
+    }
+
+    pub async fn merge_coins(
+        &self,
+        signer: SuiAddress,
+        primary_coin: ObjectID,
+        coin_to_merge: ObjectID,
+        gas: Option<ObjectID>,
+        gas_budget: u64,
+    ) -> Result<TransactionData, anyhow::Error> {
+        let gas = self
+            .choose_gas_for_address(signer, gas_budget, gas, vec![coin_to_merge, primary_coin])
+            .await?;
+        let primary_coin_ref = self.get_object_ref(&primary_coin).await?;
+        let coin_to_merge = self.0.get_latest_object(&coin_to_merge).await?;
+        let coin_to_merge_ref = coin_to_merge.compute_object_reference();
+
+        let coin_type = coin_to_merge.get_move_template_type()?;
+        let data = TransactionData::new_move_call(
+            signer,
+            self.get_framework_object_ref().await?,
+            coin::COIN_MODULE_NAME.to_owned(),
+            coin::COIN_JOIN_FUNC_NAME.to_owned(),
+            vec![coin_type],
+            gas,
+            vec![
+                CallArg::ImmOrOwnedObject(primary_coin_ref),
+                CallArg::ImmOrOwnedObject(coin_to_merge_ref),
+            ],
+            gas_budget,
+        );
+        debug!(?data, "Created Merge Coin transaction data");
+        Ok(data)
+    }
+}
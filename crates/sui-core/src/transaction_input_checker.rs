@@ -1,17 +1,22 @@
 // Copyright (c) 2022, Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 
 use prometheus_exporter::prometheus::IntCounter;
 use serde::{Deserialize, Serialize};
 use sui_types::{
-    base_types::{ObjectID, ObjectRef, SequenceNumber, SuiAddress},
+    base_types::{ObjectDigest, ObjectID, ObjectRef, SequenceNumber, SuiAddress},
+    deny_list::DenyList,
     error::{SuiError, SuiResult},
     fp_ensure,
     gas::{self, SuiGasStatus},
-    messages::{InputObjectKind, SingleTransactionKind, TransactionData, TransactionEnvelope},
+    messages::{
+        CertifiedTransaction, InputObjectKind, SingleTransactionKind, TransactionData,
+        TransactionEnvelope,
+    },
     object::{Object, Owner},
+    protocol_config::ProtocolConfig,
 };
 use tracing::{debug, instrument};
 
@@ -21,22 +26,133 @@ use crate::authority::SuiDataStore;
 pub async fn check_transaction_input<const A: bool, S, T>(
     store: &SuiDataStore<A, S>,
     transaction: &TransactionEnvelope<T>,
+    protocol_config: &'static ProtocolConfig,
     shared_obj_metric: &IntCounter,
 ) -> Result<(SuiGasStatus<'static>, Vec<(InputObjectKind, Object)>), SuiError>
 where
     S: Eq + Serialize + for<'de> Deserialize<'de>,
 {
+    check_transaction_data_input(store, &transaction.data, protocol_config, shared_obj_metric).await
+}
+
+/// The checks of `check_transaction_input` for a certificate to execute. A certificate sequenced
+/// after the transaction deleting one of its shared objects still executes, as a no-op failing
+/// with `SharedObjectDeleted`, so the shared objects which are deleted are returned apart from
+/// the input objects, at the version and digest of their deletion. The shared objects the
+/// certificate only reads are read at the versions consensus assigned them, which the
+/// certificates sequenced after it may have mutated since.
+#[instrument(level = "trace", skip_all)]
+pub async fn check_certificate_input<const A: bool, S>(
+    store: &SuiDataStore<A, S>,
+    certificate: &CertifiedTransaction,
+    protocol_config: &'static ProtocolConfig,
+    shared_obj_metric: &IntCounter,
+) -> Result<
+    (
+        SuiGasStatus<'static>,
+        Vec<(InputObjectKind, Object)>,
+        Vec<ObjectRef>,
+    ),
+    SuiError,
+>
+where
+    S: Eq + Serialize + for<'de> Deserialize<'de>,
+{
+    let read_only_versions = if certificate
+        .read_only_shared_input_objects()
+        .next()
+        .is_some()
+    {
+        store
+            .all_shared_locks(certificate.digest())?
+            .into_iter()
+            .collect()
+    } else {
+        HashMap::new()
+    };
+    check_input(
+        store,
+        &certificate.data,
+        protocol_config,
+        shared_obj_metric,
+        &read_only_versions,
+        true,
+    )
+    .await
+}
+
+/// The checks of `check_transaction_input` which don't need the signature, for running a
+/// transaction before it is signed. Unlike certificates, transactions to sign are refused when a
+/// deny list has their sender.
+#[instrument(level = "trace", skip_all)]
+pub async fn check_transaction_data_input<const A: bool, S>(
+    store: &SuiDataStore<A, S>,
+    data: &TransactionData,
+    protocol_config: &'static ProtocolConfig,
+    shared_obj_metric: &IntCounter,
+) -> Result<(SuiGasStatus<'static>, Vec<(InputObjectKind, Object)>), SuiError>
+where
+    S: Eq + Serialize + for<'de> Deserialize<'de>,
+{
+    let (gas_status, objects_by_kind, _) = check_input(
+        store,
+        data,
+        protocol_config,
+        shared_obj_metric,
+        &HashMap::new(),
+        false,
+    )
+    .await?;
+    check_deny_lists(store, &data.signer(), &objects_by_kind)?;
+    Ok((gas_status, objects_by_kind))
+}
+
+async fn check_input<const A: bool, S>(
+    store: &SuiDataStore<A, S>,
+    data: &TransactionData,
+    protocol_config: &'static ProtocolConfig,
+    shared_obj_metric: &IntCounter,
+    read_only_versions: &HashMap<ObjectID, SequenceNumber>,
+    allow_deleted_shared_objects: bool,
+) -> Result<
+    (
+        SuiGasStatus<'static>,
+        Vec<(InputObjectKind, Object)>,
+        Vec<ObjectRef>,
+    ),
+    SuiError,
+>
+where
+    S: Eq + Serialize + for<'de> Deserialize<'de>,
+{
+    fp_ensure!(
+        protocol_config.read_only_shared_objects
+            || data.read_only_shared_input_objects().next().is_none(),
+        SuiError::UnsupportedFeatureError {
+            error: format!(
+                "read-only shared objects in protocol version {}",
+                protocol_config.version
+            ),
+        }
+    );
     let mut gas_status = check_gas(
         store,
-        transaction.gas_payment_object_ref().0,
-        transaction.data.gas_budget,
-        transaction.data.kind.is_system_tx(),
+        protocol_config,
+        data.gas_payment_object_ref().0,
+        data.gas_budget,
+        data.kind.is_system_tx(),
     )
     .await?;
 
-    let objects_by_kind = check_locks(store, &transaction.data).await?;
+    let (objects_by_kind, deleted_shared_objects) = check_locks(
+        store,
+        data,
+        read_only_versions,
+        allow_deleted_shared_objects,
+    )
+    .await?;
 
-    if transaction.contains_shared_object() {
+    if data.contains_shared_object() {
         shared_obj_metric.inc();
 
         // It's important that we do this here to make sure there is enough
@@ -44,16 +160,18 @@ where
         gas_status.charge_consensus()?;
     }
 
-    Ok((gas_status, objects_by_kind))
+    Ok((gas_status, objects_by_kind, deleted_shared_objects))
 }
 
 /// Checking gas budget by fetching the gas object only from the store,
 /// and check whether the balance and budget satisfies the miminum requirement.
 /// Returns the gas object (to be able to reuse it latter) and a gas status
-/// that will be used in the entire lifecycle of the transaction execution.
+/// that will be used in the entire lifecycle of the transaction execution,
+/// metered with the gas schedule of `protocol_config`.
 #[instrument(level = "trace", skip_all)]
 async fn check_gas<const A: bool, S>(
     store: &SuiDataStore<A, S>,
+    protocol_config: &'static ProtocolConfig,
     gas_payment_id: ObjectID,
     gas_budget: u64,
     is_system_tx: bool,
@@ -68,38 +186,58 @@ where
         let gas_object = gas_object.ok_or(SuiError::ObjectNotFound {
             object_id: gas_payment_id,
         })?;
-        gas::check_gas_balance(&gas_object, gas_budget)?;
+        gas::check_gas_balance(&gas_object, gas_budget, protocol_config)?;
         // TODO: Pass in real computation gas unit price and storage gas unit price.
-        let gas_status = gas::start_gas_metering(gas_budget, 1, 1)?;
+        let gas_status = gas::start_gas_metering(protocol_config, gas_budget, 1, 1)?;
         Ok(gas_status)
     }
 }
 
+/// Fetch the latest version of each of `input_objects`, or for a shared object the transaction
+/// only reads, its version in `read_only_versions` if the store has it.
 #[instrument(level = "trace", skip_all, fields(num_objects = input_objects.len()))]
 async fn fetch_objects<const A: bool, S>(
     store: &SuiDataStore<A, S>,
     input_objects: &[InputObjectKind],
+    read_only_versions: &HashMap<ObjectID, SequenceNumber>,
 ) -> Result<Vec<Option<Object>>, SuiError>
 where
     S: Eq + Serialize + for<'de> Deserialize<'de>,
 {
     let ids: Vec<_> = input_objects.iter().map(|kind| kind.object_id()).collect();
-    store.get_objects(&ids[..])
+    let mut objects = store.get_objects(&ids[..])?;
+    for (kind, object) in input_objects.iter().zip(objects.iter_mut()) {
+        let version = match kind {
+            InputObjectKind::ReadOnlySharedMoveObject(object_id) => {
+                read_only_versions.get(object_id)
+            }
+            _ => None,
+        };
+        if let Some(version) = version {
+            if let Some(pinned) = store.get_object_by_key(&kind.object_id(), *version)? {
+                *object = Some(pinned);
+            }
+        }
+    }
+    Ok(objects)
 }
 
 /// Check all the objects used in the transaction against the database, and ensure
-/// that they are all the correct version and number.
+/// that they are all the correct version and number. The shared objects which were deleted are
+/// returned apart if `allow_deleted_shared_objects` is set, and are an error otherwise.
 #[instrument(level = "trace", skip_all)]
 async fn check_locks<const A: bool, S>(
     store: &SuiDataStore<A, S>,
     transaction: &TransactionData,
-) -> Result<Vec<(InputObjectKind, Object)>, SuiError>
+    read_only_versions: &HashMap<ObjectID, SequenceNumber>,
+    allow_deleted_shared_objects: bool,
+) -> Result<(Vec<(InputObjectKind, Object)>, Vec<ObjectRef>), SuiError>
 where
     S: Eq + Serialize + for<'de> Deserialize<'de>,
 {
     let input_objects = transaction.input_objects()?;
     // These IDs act as authenticators that can own other objects.
-    let objects = fetch_objects(store, &input_objects).await?;
+    let objects = fetch_objects(store, &input_objects, read_only_versions).await?;
 
     // Constructing the list of objects that could be used to authenticate other
     // objects. Any mutable object (either shared or owned) can be used to
@@ -125,8 +263,17 @@ where
         }
     }
 
+    // A shared object the transaction only reads can't authenticate the objects it owns, which
+    // the transactions reading it along with this one could otherwise all mutate.
+    for kind in &input_objects {
+        if let InputObjectKind::ReadOnlySharedMoveObject(object_id) = kind {
+            owned_object_authenticators.remove(&SuiAddress::from(*object_id));
+        }
+    }
+
     // Gather all objects and errors.
     let mut all_objects = Vec::with_capacity(input_objects.len());
+    let mut deleted_shared_objects = Vec::new();
     let mut errors = Vec::new();
     let transfer_object_ids: HashSet<_> = transaction
         .kind
@@ -144,7 +291,15 @@ where
         let object = match object {
             Some(object) => object,
             None => {
-                errors.push(object_kind.object_not_found_error());
+                match deleted_shared_object(store, object_kind)? {
+                    Some(deleted) if allow_deleted_shared_objects => {
+                        deleted_shared_objects.push(deleted)
+                    }
+                    Some((object_id, _, _)) => {
+                        errors.push(SuiError::SharedObjectDeleted { object_id })
+                    }
+                    None => errors.push(object_kind.object_not_found_error()),
+                }
                 continue;
             }
         };
@@ -172,7 +327,61 @@ where
     }
     fp_ensure!(!all_objects.is_empty(), SuiError::ObjectInputArityViolation);
 
-    Ok(all_objects)
+    Ok((all_objects, deleted_shared_objects))
+}
+
+/// The reference of the deletion of the input `object_kind`, if it is a shared object which was
+/// deleted.
+fn deleted_shared_object<const A: bool, S>(
+    store: &SuiDataStore<A, S>,
+    object_kind: InputObjectKind,
+) -> SuiResult<Option<ObjectRef>>
+where
+    S: Eq + Serialize + for<'de> Deserialize<'de>,
+{
+    let object_id = match object_kind {
+        InputObjectKind::SharedMoveObject(object_id)
+        | InputObjectKind::ReadOnlySharedMoveObject(object_id) => object_id,
+        _ => return Ok(None),
+    };
+    Ok(store
+        .get_latest_parent_entry(object_id)?
+        .map(|(object_ref, _)| object_ref)
+        .filter(|(_, _, digest)| *digest == ObjectDigest::OBJECT_DIGEST_DELETED))
+}
+
+/// Check that the sender is not denied from using any of the coins among the input objects,
+/// according to the deny list of each coin type (if its issuer has created one).
+///
+/// Only transactions to sign are checked. The deny lists are read as the local store holds them,
+/// rather than at versions consensus assigned, so checking certificates against them could make
+/// validators disagree on the effects of a certificate. A certificate signed before its sender
+/// was denied therefore still executes.
+#[instrument(level = "trace", skip_all)]
+fn check_deny_lists<const A: bool, S>(
+    store: &SuiDataStore<A, S>,
+    sender: &SuiAddress,
+    objects: &[(InputObjectKind, Object)],
+) -> SuiResult
+where
+    S: Eq + Serialize + for<'de> Deserialize<'de>,
+{
+    let coin_types: HashSet<_> = objects
+        .iter()
+        .filter_map(|(_, object)| DenyList::coin_type_of_coin(object))
+        .collect();
+    for coin_type in coin_types {
+        if let Some(deny_list) = store.get_deny_list(coin_type)? {
+            fp_ensure!(
+                !deny_list.contains(sender),
+                SuiError::AddressDeniedForCoin {
+                    address: *sender,
+                    coin_type: coin_type.to_string(),
+                }
+            );
+        }
+    }
+    Ok(())
 }
 
 pub fn filter_owned_objects(all_objects: &[(InputObjectKind, Object)]) -> Vec<ObjectRef> {
@@ -187,7 +396,9 @@ pub fn filter_owned_objects(all_objects: &[(InputObjectKind, Object)]) -> Vec<Ob
                     Some(*object_ref)
                 }
             }
-            InputObjectKind::SharedMoveObject(_) => None,
+            InputObjectKind::SharedMoveObject(_) | InputObjectKind::ReadOnlySharedMoveObject(_) => {
+                None
+            }
         })
         .collect();
 
@@ -270,16 +481,30 @@ fn check_one_lock(
                         }
                     );
                 }
-                Owner::Shared => {
-                    // This object is a mutable shared object. However the transaction
+                Owner::Shared | Owner::Party(_) => {
+                    // This object is a mutable shared or party object. However the transaction
                     // specifies it as an owned object. This is inconsistent.
                     return Err(SuiError::NotSharedObjectError);
                 }
             };
         }
-        InputObjectKind::SharedMoveObject(..) => {
+        InputObjectKind::SharedMoveObject(object_id)
+        | InputObjectKind::ReadOnlySharedMoveObject(object_id) => {
             // When someone locks an object as shared it must be shared already.
             fp_ensure!(object.is_shared(), SuiError::NotSharedObjectError);
+            // A party object can only be used by its party. Its owner never changes, so this
+            // holds as the transaction is signed just as it does as it is executed.
+            if let Owner::Party(party) = object.owner {
+                fp_ensure!(
+                    sender == &party,
+                    SuiError::IncorrectSigner {
+                        error: format!(
+                            "Object {:?} is owned by party {:?}, but signer address is {:?}",
+                            object_id, party, sender
+                        ),
+                    }
+                );
+            }
         }
     };
     Ok(())
@@ -0,0 +1,205 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! The transaction orchestrator of a full node submits the transactions of its clients to the
+//! committee with the quorum driver, and answers at the point the client chose with an
+//! `ExecuteTransactionRequestType`: once the transaction is certified, once a quorum of the
+//! committee has executed the certificate, or once the full node has executed it too, so that
+//! the reads the client makes of the node next see the effects of the transaction. The work
+//! left after the answer carries on in the background.
+//!
+//! Each request type is given its own timeout, covering the whole request.
+
+use crate::authority::AuthorityState;
+use crate::authority_aggregator::AuthorityAggregator;
+use crate::authority_client::AuthorityAPI;
+use crate::gateway_types::ExecuteTransactionRequestType;
+use crate::quorum_driver::{QuorumDriver, QuorumDriverConfig};
+use std::sync::Arc;
+use std::time::Duration;
+use sui_types::batch::UpdateItem;
+use sui_types::error::{SuiError, SuiResult};
+use sui_types::messages::{
+    CertifiedTransaction, ConfirmationTransaction, Transaction, TransactionEffects,
+};
+use tokio::sync::broadcast::error::RecvError;
+use tokio::time::timeout;
+use tracing::{debug, warn};
+
+#[cfg(test)]
+#[path = "unit_tests/transaction_orchestrator_tests.rs"]
+mod transaction_orchestrator_tests;
+
+#[derive(Clone)]
+pub struct TransactionOrchestratorConfig {
+    pub quorum_driver: QuorumDriverConfig,
+    /// The time a `WaitForTxCert` request waits for the certificate.
+    pub tx_cert_timeout: Duration,
+    /// The time a `WaitForEffectsCert` request waits for the effects.
+    pub effects_cert_timeout: Duration,
+    /// The time a `WaitForLocalExecution` request waits for the full node to execute the
+    /// certificate.
+    pub local_execution_timeout: Duration,
+}
+
+impl Default for TransactionOrchestratorConfig {
+    fn default() -> Self {
+        Self {
+            quorum_driver: QuorumDriverConfig::default(),
+            tx_cert_timeout: Duration::from_secs(30),
+            effects_cert_timeout: Duration::from_secs(60),
+            local_execution_timeout: Duration::from_secs(70),
+        }
+    }
+}
+
+pub enum ExecuteTransactionResponse {
+    TxCert(Box<CertifiedTransaction>),
+    /// The certificate, its effects, and whether the full node executed it before answering.
+    EffectsCert(Box<(CertifiedTransaction, TransactionEffects)>, bool),
+}
+
+pub struct TransactionOrchestrator<A> {
+    aggregator: Arc<AuthorityAggregator<A>>,
+    /// The state of the full node.
+    state: Arc<AuthorityState>,
+    config: TransactionOrchestratorConfig,
+}
+
+impl<A> TransactionOrchestrator<A>
+where
+    A: AuthorityAPI + Send + Sync + 'static + Clone,
+{
+    pub fn new(
+        aggregator: Arc<AuthorityAggregator<A>>,
+        state: Arc<AuthorityState>,
+        config: TransactionOrchestratorConfig,
+    ) -> Self {
+        Self {
+            aggregator,
+            state,
+            config,
+        }
+    }
+
+    pub async fn execute_transaction(
+        &self,
+        transaction: Transaction,
+        request_type: ExecuteTransactionRequestType,
+    ) -> SuiResult<ExecuteTransactionResponse> {
+        transaction.verify_signature()?;
+        let (stage, timeout_duration) = match request_type {
+            ExecuteTransactionRequestType::WaitForTxCert => {
+                ("certificate", self.config.tx_cert_timeout)
+            }
+            ExecuteTransactionRequestType::WaitForEffectsCert => {
+                ("effects", self.config.effects_cert_timeout)
+            }
+            ExecuteTransactionRequestType::WaitForLocalExecution => {
+                ("local execution", self.config.local_execution_timeout)
+            }
+        };
+        timeout(
+            timeout_duration,
+            self.execute_transaction_impl(transaction, request_type),
+        )
+        .await
+        .unwrap_or_else(|_| {
+            Err(SuiError::ExecutionTimedOut {
+                stage: stage.to_string(),
+                timeout_ms: timeout_duration.as_millis() as u64,
+            })
+        })
+    }
+
+    async fn execute_transaction_impl(
+        &self,
+        transaction: Transaction,
+        request_type: ExecuteTransactionRequestType,
+    ) -> SuiResult<ExecuteTransactionResponse> {
+        let driver = QuorumDriver::new(&self.aggregator, self.config.quorum_driver.clone());
+        let certificate = driver.process_transaction(transaction).await?;
+        self.aggregator.metrics.total_tx_certificates.inc();
+        if request_type == ExecuteTransactionRequestType::WaitForTxCert {
+            let aggregator = self.aggregator.clone();
+            let state = self.state.clone();
+            let config = self.config.clone();
+            let background_certificate = certificate.clone();
+            tokio::spawn(async move {
+                let certificate = background_certificate;
+                match QuorumDriver::new(&aggregator, config.quorum_driver)
+                    .process_certificate(certificate.clone())
+                    .await
+                {
+                    Ok(_) => {
+                        let _ = timeout(
+                            config.local_execution_timeout,
+                            execute_locally(&state, &certificate),
+                        )
+                        .await;
+                    }
+                    Err(error) => warn!(
+                        tx_digest =? certificate.digest(),
+                        "Failed to execute the certificate: {error}"
+                    ),
+                }
+            });
+            return Ok(ExecuteTransactionResponse::TxCert(Box::new(certificate)));
+        }
+
+        let effects = driver.process_certificate(certificate.clone()).await?;
+        if request_type == ExecuteTransactionRequestType::WaitForEffectsCert {
+            let state = self.state.clone();
+            let local_execution_timeout = self.config.local_execution_timeout;
+            let background_certificate = certificate.clone();
+            tokio::spawn(async move {
+                let _ = timeout(
+                    local_execution_timeout,
+                    execute_locally(&state, &background_certificate),
+                )
+                .await;
+            });
+            return Ok(ExecuteTransactionResponse::EffectsCert(
+                Box::new((certificate, effects)),
+                false,
+            ));
+        }
+
+        execute_locally(&self.state, &certificate).await;
+        Ok(ExecuteTransactionResponse::EffectsCert(
+            Box::new((certificate, effects)),
+            true,
+        ))
+    }
+}
+
+/// Execute `certificate` on the full node, or else wait for it to be executed as the full node
+/// follows the committee: a certificate on shared objects, as well as one whose inputs the full
+/// node has not caught up with, executes in the order the committee gives it.
+async fn execute_locally(state: &AuthorityState, certificate: &CertifiedTransaction) {
+    let digest = *certificate.digest();
+    // Subscribe before looking for the effects, not to miss them in between.
+    let mut updates = state.subscribe_batch();
+    if !certificate.contains_shared_object() {
+        match state
+            .handle_confirmation_transaction(ConfirmationTransaction::new(certificate.clone()))
+            .await
+        {
+            Ok(_) => return,
+            Err(error) => {
+                debug!(tx_digest =? digest, "Waiting for certificate to be synced: {error}")
+            }
+        }
+    }
+    loop {
+        if let Ok(true) = state.database.effects_exists(&digest) {
+            return;
+        }
+        match updates.recv().await {
+            Ok(UpdateItem::Transaction((_, digests))) if digests.transaction == digest => return,
+            Ok(_) | Err(RecvError::Lagged(_)) => {}
+            // The full node is shutting down.
+            Err(RecvError::Closed) => return,
+        }
+    }
+}
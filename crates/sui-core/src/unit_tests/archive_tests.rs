@@ -0,0 +1,78 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use super::*;
+
+use std::collections::BTreeSet;
+
+use crate::authority::authority_tests::{create_move_object, init_state_with_ids};
+use crate::checkpoints::CheckpointStore;
+use sui_types::base_types::{ExecutionDigests, ObjectID};
+use sui_types::crypto::{get_key_pair, KeyPair};
+use sui_types::messages_checkpoint::CheckpointSummary;
+
+#[tokio::test]
+async fn test_local_object_store() {
+    let store = LocalObjectStore::new(tempfile::tempdir().unwrap().into_path());
+    assert_eq!(store.get("checkpoints/0").await.unwrap(), None);
+
+    store.put("checkpoints/0", vec![1, 2]).await.unwrap();
+    store.put("checkpoints/0", vec![3]).await.unwrap();
+    assert_eq!(store.get("checkpoints/0").await.unwrap(), Some(vec![3]));
+}
+
+#[tokio::test]
+async fn test_archive_final_checkpoints() {
+    let (sender, sender_key) = get_key_pair();
+    let gas_object_id = ObjectID::random();
+    let mut state = init_state_with_ids(vec![(sender, gas_object_id)]).await;
+
+    let (_, checkpoint_key): (_, KeyPair) = get_key_pair();
+    let secret = Arc::pin(checkpoint_key);
+    let checkpoints = Arc::new(parking_lot::Mutex::new(
+        CheckpointStore::open(
+            tempfile::tempdir().unwrap().into_path(),
+            None,
+            *secret.public_key_bytes(),
+            secret,
+        )
+        .unwrap(),
+    ));
+    state.checkpoints = Some(checkpoints.clone());
+    let state = Arc::new(state);
+
+    let effects = create_move_object(&state, &gas_object_id, &sender, &sender_key)
+        .await
+        .unwrap();
+    let digest = effects.transaction_digest;
+    let digests = ExecutionDigests::new(digest, effects.digest());
+    {
+        let mut checkpoints = checkpoints.lock();
+        checkpoints
+            .handle_internal_batch(1, &[(0, digests)])
+            .unwrap();
+        let contents = CheckpointContents::new([digests].into_iter());
+        checkpoints
+            .handle_internal_set_checkpoint(CheckpointSummary::new(0, &contents, None), &contents)
+            .unwrap();
+    }
+
+    let store: Arc<dyn ObjectStore> = Arc::new(LocalObjectStore::new(
+        tempfile::tempdir().unwrap().into_path(),
+    ));
+    let writer = ArchiveWriter::new(state.clone(), store.clone());
+    let reader = ArchiveReader::new(store);
+    assert!(reader.get_transaction(&digest).await.unwrap().is_none());
+
+    assert_eq!(writer.archive_final_checkpoints().await.unwrap(), 1);
+    // The writer resumes from the next checkpoint the archive misses.
+    assert_eq!(writer.archive_final_checkpoints().await.unwrap(), 1);
+    assert_eq!(reader.next_checkpoint().await.unwrap(), 1);
+
+    let archived = reader.get_checkpoint(0).await.unwrap().unwrap();
+    assert_eq!(archived.contents.transactions, BTreeSet::from([digests]));
+    let archived = reader.get_transaction(&digest).await.unwrap().unwrap();
+    assert_eq!(archived.effects, effects);
+    assert_eq!(*archived.certificate.digest(), digest);
+    assert!(reader.get_checkpoint(1).await.unwrap().is_none());
+}
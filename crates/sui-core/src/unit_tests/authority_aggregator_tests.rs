@@ -50,13 +50,15 @@ pub async fn init_local_authorities_with_genesis(
     telemetry_subscribers::init_for_testing();
     let mut key_pairs = Vec::new();
     let mut voting_rights = BTreeMap::new();
+    let mut protocol_keys = BTreeMap::new();
     for _ in 0..genesis_objects.len() {
         let (_, key_pair) = get_key_pair();
         let authority_name = *key_pair.public_key_bytes();
         voting_rights.insert(authority_name, 1);
+        protocol_keys.insert(authority_name, key_pair.protocol_public_key());
         key_pairs.push((authority_name, key_pair));
     }
-    let committee = Committee::new(0, voting_rights);
+    let committee = Committee::new(0, voting_rights, protocol_keys);
 
     let mut clients = BTreeMap::new();
     let mut states = Vec::new();
@@ -263,10 +265,11 @@ where
     assert!(stake >= quorum_threshold);
 
     CertifiedTransaction::new_with_signatures(
-        committee.epoch(),
         transaction.unwrap().to_transaction(),
-        votes,
+        &votes,
+        committee,
     )
+    .unwrap()
 }
 
 pub async fn do_cert<A>(
@@ -727,7 +730,14 @@ async fn test_process_transaction1() {
     // Check which authorities has successfully processed the cert.
     // (NOTE: this method gets the TxInfoResponse from each authority, then reconstructs the cert)
     let cert2 = extract_cert(&authority_clients, &authorities.committee, create2.digest()).await;
-    assert_eq!(3, cert2.auth_sign_info.signatures.len());
+    assert_eq!(
+        3,
+        cert2
+            .auth_sign_info
+            .authorities(&authorities.committee)
+            .unwrap()
+            .len()
+    );
 }
 
 async fn get_owned_objects(
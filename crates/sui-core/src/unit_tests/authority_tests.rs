@@ -3,6 +3,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use super::*;
+use crate::gateway_types::{SuiObjectChange, SuiTransactionResponseOptions};
 use bcs;
 use move_binary_format::{
     file_format::{self, AddressIdentifierIndex, IdentifierIndex, ModuleHandle},
@@ -18,10 +19,13 @@ use sui_types::{
     base_types::dbg_addr,
     crypto::KeyPair,
     crypto::{get_key_pair, Signature},
-    messages::Transaction,
+    gas_coin::GasCoin,
+    messages::{framework_digest, SystemPackage, Transaction},
+    messages_checkpoint::{CheckpointContents, CheckpointSummary},
     object::{Owner, OBJECT_START_VERSION},
-    sui_system_state::SuiSystemState,
-    SUI_SYSTEM_STATE_OBJECT_ID,
+    object_change::OwnerChange,
+    sui_system_state::{FrameworkUpgrade, MoveOption, SuiSystemState},
+    MOVE_STDLIB_ADDRESS, SUI_FRAMEWORK_ADDRESS, SUI_SYSTEM_STATE_OBJECT_ID,
 };
 
 use std::fs;
@@ -58,7 +62,8 @@ const MAX_GAS: u64 = 10000;
 fn compare_certified_transactions(o1: &CertifiedTransaction, o2: &CertifiedTransaction) {
     assert_eq!(o1.digest(), o2.digest());
     // in this ser/de context it's relevant to compare signatures
-    assert_eq!(o1.auth_sign_info.signatures, o2.auth_sign_info.signatures);
+    assert_eq!(o1.auth_sign_info.signers_map, o2.auth_sign_info.signers_map);
+    assert_eq!(o1.auth_sign_info.signature, o2.auth_sign_info.signature);
 }
 
 // Only relevant in a ser/de context : the `CertifiedTransaction` for a transaction is not unique
@@ -72,8 +77,12 @@ fn compare_transaction_info_responses(o1: &TransactionInfoResponse, o2: &Transac
         (Some(cert1), Some(cert2)) => {
             assert_eq!(cert1.digest(), cert2.digest());
             assert_eq!(
-                cert1.auth_sign_info.signatures,
-                cert2.auth_sign_info.signatures
+                cert1.auth_sign_info.signers_map,
+                cert2.auth_sign_info.signers_map
+            );
+            assert_eq!(
+                cert1.auth_sign_info.signature,
+                cert2.auth_sign_info.signature
             );
         }
         (None, None) => (),
@@ -113,7 +122,7 @@ async fn test_handle_transfer_transaction_bad_signature() {
     let (_unknown_address, unknown_key) = get_key_pair();
     let mut bad_signature_transfer_transaction = transfer_transaction.clone();
     bad_signature_transfer_transaction.tx_signature =
-        Signature::new(&transfer_transaction.data, &unknown_key);
+        Signature::new(&transfer_transaction.data, &unknown_key).into();
     assert!(authority_state
         .handle_transaction(bad_signature_transfer_transaction)
         .await
@@ -306,6 +315,87 @@ async fn test_handle_transfer_transaction_ok() {
     );
 }
 
+#[tokio::test]
+async fn test_handle_transaction_denied() {
+    let (sender, sender_key) = get_key_pair();
+    let recipient = dbg_addr(2);
+    let object_id = ObjectID::random();
+    let gas_object_id = ObjectID::random();
+    let authority_state =
+        init_state_with_ids(vec![(sender, object_id), (sender, gas_object_id)]).await;
+    let object = authority_state
+        .get_object(&object_id)
+        .await
+        .unwrap()
+        .unwrap();
+    let gas_object = authority_state
+        .get_object(&gas_object_id)
+        .await
+        .unwrap()
+        .unwrap();
+    let transfer_transaction = init_transfer_transaction(
+        sender,
+        &sender_key,
+        recipient,
+        object.compute_object_reference(),
+        gas_object.compute_object_reference(),
+    );
+    let certificate = init_certified_transaction(transfer_transaction.clone(), &authority_state);
+
+    // Neither the transaction nor its certificate go through while an input is denied.
+    let mut deny_list = DenyList::default();
+    deny_list.objects.insert(object_id);
+    authority_state.deny_list.store(Arc::new(deny_list));
+    assert!(matches!(
+        authority_state
+            .handle_transaction(transfer_transaction.clone())
+            .await,
+        Err(SuiError::TransactionDenied { .. })
+    ));
+    assert!(matches!(
+        authority_state
+            .handle_confirmation_transaction(ConfirmationTransaction::new(certificate.clone()))
+            .await,
+        Err(SuiError::TransactionDenied { .. })
+    ));
+
+    let mut deny_list = DenyList::default();
+    deny_list
+        .transactions
+        .insert(*transfer_transaction.digest());
+    authority_state.deny_list.store(Arc::new(deny_list));
+    assert!(matches!(
+        authority_state
+            .handle_transaction(transfer_transaction.clone())
+            .await,
+        Err(SuiError::TransactionDenied { .. })
+    ));
+
+    // Calls to a denied package are denied.
+    let mut deny_list = DenyList::default();
+    deny_list
+        .packages
+        .insert(ObjectID::from(SUI_FRAMEWORK_ADDRESS));
+    authority_state.deny_list.store(Arc::new(deny_list));
+    assert!(matches!(
+        create_move_object(&authority_state, &gas_object_id, &sender, &sender_key).await,
+        Err(SuiError::TransactionDenied { .. })
+    ));
+    assert!(authority_state
+        .handle_transaction(transfer_transaction)
+        .await
+        .is_ok());
+
+    // The certificate executes once taken off the list.
+    authority_state
+        .deny_list
+        .store(Arc::new(DenyList::default()));
+    assert!(authority_state
+        .handle_confirmation_transaction(ConfirmationTransaction::new(certificate))
+        .await
+        .is_ok());
+}
+
 #[tokio::test]
 async fn test_transfer_package() {
     let (sender, sender_key) = get_key_pair();
@@ -537,6 +627,130 @@ async fn test_publish_non_existing_dependent_module() {
     );
 }
 
+#[tokio::test]
+async fn test_dry_run_transaction() {
+    let (sender, sender_key) = get_key_pair();
+    let recipient = dbg_addr(2);
+    let object_id = ObjectID::random();
+    let gas_object_id = ObjectID::random();
+    let authority_state =
+        init_state_with_ids(vec![(sender, object_id), (sender, gas_object_id)]).await;
+    let object = authority_state
+        .get_object(&object_id)
+        .await
+        .unwrap()
+        .unwrap();
+    let gas_object = authority_state
+        .get_object(&gas_object_id)
+        .await
+        .unwrap()
+        .unwrap();
+    let transaction = init_transfer_transaction(
+        sender,
+        &sender_key,
+        recipient,
+        object.compute_object_reference(),
+        gas_object.compute_object_reference(),
+    );
+
+    let response = authority_state
+        .dry_run_transaction(transaction.data.clone(), false)
+        .await
+        .unwrap();
+    assert!(response.gas_profile.is_none());
+    let effects = response.effects;
+    assert!(effects.status.is_ok());
+    assert!(effects
+        .mutated
+        .iter()
+        .any(|(object_ref, owner)| object_ref.0 == object_id
+            && *owner == Owner::AddressOwner(recipient)));
+
+    // Nothing was committed or locked, so the transaction can still be sent.
+    let object_after = authority_state
+        .get_object(&object_id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(object_after, object);
+    assert!(authority_state
+        .get_transaction_lock(&object.compute_object_reference())
+        .await
+        .unwrap()
+        .is_none());
+    authority_state
+        .handle_transaction(transaction)
+        .await
+        .unwrap();
+
+    // The inputs are checked as for a signed transaction.
+    let data = TransactionData::new_transfer(
+        recipient,
+        object.compute_object_reference(),
+        dbg_addr(3),
+        gas_object.compute_object_reference(),
+        10000,
+    );
+    assert!(authority_state
+        .dry_run_transaction(data, false)
+        .await
+        .is_err());
+}
+
+#[tokio::test]
+async fn test_dry_run_transaction_gas_profile() {
+    let (sender, _) = get_key_pair();
+    let gas_object_id = ObjectID::random();
+    let authority_state = init_state_with_ids(vec![(sender, gas_object_id)]).await;
+    let gas_object = authority_state
+        .get_object(&gas_object_id)
+        .await
+        .unwrap()
+        .unwrap();
+    let data = TransactionData::new_move_call(
+        sender,
+        authority_state.get_framework_object_ref().await.unwrap(),
+        Identifier::new("ObjectBasics").unwrap(),
+        Identifier::new("create").unwrap(),
+        vec![],
+        gas_object.compute_object_reference(),
+        vec![
+            CallArg::Pure(bcs::to_bytes(&16u64).unwrap()),
+            CallArg::Pure(bcs::to_bytes(&sender).unwrap()),
+        ],
+        MAX_GAS,
+    );
+
+    let response = authority_state
+        .dry_run_transaction(data, true)
+        .await
+        .unwrap();
+    assert!(response.effects.status.is_ok());
+    let profile = response.gas_profile.unwrap();
+    assert_eq!(profile.calls.len(), 1);
+    assert!(profile.calls[0].function.ends_with("ObjectBasics::create"));
+    // The gas of the call is at most the computation gas of the transaction, which also pays for
+    // reading and writing the objects.
+    assert!(profile.calls[0].gas_used > 0);
+    assert!(profile.calls[0].gas_used <= response.effects.gas_cost_summary().computation_cost);
+    let natives: Vec<_> = profile
+        .natives
+        .iter()
+        .map(|native| (native.function.as_str(), native.calls))
+        .collect();
+    assert_eq!(
+        natives,
+        vec![
+            ("Transfer::transfer_internal", 1),
+            ("TxContext::derive_id", 1)
+        ]
+    );
+    assert!(profile
+        .natives
+        .iter()
+        .all(|native| native.gas_used <= profile.calls[0].gas_used));
+}
+
 #[tokio::test]
 async fn test_handle_move_transaction() {
     let (sender, sender_key) = get_key_pair();
@@ -809,9 +1023,22 @@ async fn test_handle_confirmation_transaction_ok() {
         ))
         .await
         .unwrap();
-    info.signed_effects.unwrap().effects.status.unwrap();
+    let effects = info.signed_effects.unwrap().effects;
+    effects.status.clone().unwrap();
     // Key check: the ownership has changed
 
+    // The effects record the transfer, and not the mutation of the gas object, which kept its
+    // owner.
+    assert_eq!(
+        effects.owner_changes,
+        vec![OwnerChange {
+            object_id,
+            version: next_sequence_number,
+            previous_owner: Owner::AddressOwner(sender),
+            owner: Owner::AddressOwner(recipient),
+        }]
+    );
+
     let new_account = authority_state
         .get_object(&object_id)
         .await
@@ -856,6 +1083,219 @@ async fn test_handle_confirmation_transaction_ok() {
     );
 }
 
+#[tokio::test]
+async fn test_object_read_at_checkpoint() {
+    let (sender, sender_key) = get_key_pair();
+    let recipient = dbg_addr(2);
+    let object_id = ObjectID::random();
+    let gas_object_id = ObjectID::random();
+    let mut authority_state =
+        init_state_with_ids(vec![(sender, object_id), (sender, gas_object_id)]).await;
+
+    let path = env::temp_dir().join(format!("DB_{:?}", ObjectID::random()));
+    fs::create_dir(&path).unwrap();
+    let (_, checkpoint_key): (_, KeyPair) = get_key_pair();
+    let secret = Arc::pin(checkpoint_key);
+    let checkpoints = Arc::new(parking_lot::Mutex::new(
+        CheckpointStore::open(&path, None, *secret.public_key_bytes(), secret).unwrap(),
+    ));
+    authority_state.checkpoints = Some(checkpoints.clone());
+
+    // No checkpoint is final yet.
+    assert!(authority_state
+        .get_object_read_at_checkpoint(&object_id, 0)
+        .await
+        .is_err());
+
+    let object = authority_state
+        .get_object(&object_id)
+        .await
+        .unwrap()
+        .unwrap();
+    let gas_object = authority_state
+        .get_object(&gas_object_id)
+        .await
+        .unwrap()
+        .unwrap();
+    let certificate = init_certified_transfer_transaction(
+        sender,
+        &sender_key,
+        recipient,
+        object.compute_object_reference(),
+        gas_object.compute_object_reference(),
+        &authority_state,
+    );
+    let effects = authority_state
+        .handle_confirmation_transaction(ConfirmationTransaction::new(certificate.clone()))
+        .await
+        .unwrap()
+        .signed_effects
+        .unwrap()
+        .effects;
+
+    // Checkpoint 0 is before the transfer, checkpoint 1 includes it.
+    let mut previous_digest = None;
+    for (sequence_number, transactions) in [
+        vec![],
+        vec![ExecutionDigests::new(
+            *certificate.digest(),
+            effects.digest(),
+        )],
+    ]
+    .into_iter()
+    .enumerate()
+    {
+        let contents = CheckpointContents::new(transactions.into_iter());
+        let summary = CheckpointSummary::new(sequence_number as u64, &contents, previous_digest);
+        previous_digest = Some(summary.digest());
+        checkpoints
+            .lock()
+            .handle_internal_set_checkpoint(summary, &contents)
+            .unwrap();
+    }
+
+    let owner_at = |checkpoint| {
+        let authority_state = &authority_state;
+        async move {
+            match authority_state
+                .get_object_read_at_checkpoint(&object_id, checkpoint)
+                .await
+                .unwrap()
+            {
+                ObjectRead::Exists(_, object, _) => object.owner,
+                _ => panic!("The object should exist at checkpoint {checkpoint}"),
+            }
+        }
+    };
+    assert_eq!(owner_at(0).await, Owner::AddressOwner(sender));
+    assert_eq!(owner_at(1).await, Owner::AddressOwner(recipient));
+
+    let owned_at = |checkpoint| {
+        let authority_state = &authority_state;
+        async move {
+            authority_state
+                .get_owner_objects_at_checkpoint(recipient, checkpoint)
+                .await
+                .unwrap()
+                .into_iter()
+                .map(|info| info.object_id)
+                .collect::<Vec<_>>()
+        }
+    };
+    assert!(owned_at(0).await.is_empty());
+    assert_eq!(owned_at(1).await, vec![object_id]);
+
+    // Checkpoint 2 is not final.
+    assert!(authority_state
+        .get_object_read_at_checkpoint(&object_id, 2)
+        .await
+        .is_err());
+}
+
+#[tokio::test]
+async fn test_transaction_changes() {
+    let (sender, sender_key) = get_key_pair();
+    let recipient = dbg_addr(2);
+    let object_id = ObjectID::random();
+    let gas_object_id = ObjectID::random();
+    let authority_state =
+        init_state_with_ids(vec![(sender, object_id), (sender, gas_object_id)]).await;
+    let object = authority_state
+        .get_object(&object_id)
+        .await
+        .unwrap()
+        .unwrap();
+    let gas_object = authority_state
+        .get_object(&gas_object_id)
+        .await
+        .unwrap()
+        .unwrap();
+    let coin_value = GasCoin::try_from(&object).unwrap().value();
+    let gas_value = GasCoin::try_from(&gas_object).unwrap().value();
+
+    let certified_transfer_transaction = init_certified_transfer_transaction(
+        sender,
+        &sender_key,
+        recipient,
+        object.compute_object_reference(),
+        gas_object.compute_object_reference(),
+        &authority_state,
+    );
+    let digest = *certified_transfer_transaction.digest();
+    let transaction_data = certified_transfer_transaction.data.clone();
+    authority_state
+        .handle_confirmation_transaction(ConfirmationTransaction::new(
+            certified_transfer_transaction,
+        ))
+        .await
+        .unwrap();
+
+    let response = authority_state
+        .get_transaction(digest, SuiTransactionResponseOptions::default())
+        .await
+        .unwrap();
+    assert!(response.raw_transaction.is_none());
+    assert!(response.certificate.is_some());
+    assert!(response.effects.is_some());
+    let new_gas_object = authority_state
+        .get_object(&gas_object_id)
+        .await
+        .unwrap()
+        .unwrap();
+    let new_gas_value = GasCoin::try_from(&new_gas_object).unwrap().value();
+
+    // The sender paid for the transferred coin and for gas, and the recipient got the coin.
+    let balance_changes: BTreeMap<_, _> = response
+        .balance_changes
+        .iter()
+        .map(|change| (change.owner, change.amount))
+        .collect();
+    assert_eq!(balance_changes.len(), 2);
+    assert_eq!(
+        balance_changes[&Owner::AddressOwner(sender)],
+        new_gas_value as i128 - gas_value as i128 - coin_value as i128
+    );
+    assert_eq!(
+        balance_changes[&Owner::AddressOwner(recipient)],
+        coin_value as i128
+    );
+
+    assert_eq!(response.object_changes.len(), 2);
+    assert!(response.object_changes.iter().any(|change| matches!(
+        change,
+        SuiObjectChange::Transferred { object_id: id, previous_owner, owner, .. }
+            if *id == object_id
+                && *previous_owner == Owner::AddressOwner(sender)
+                && *owner == Owner::AddressOwner(recipient)
+    )));
+    assert!(response.object_changes.iter().any(|change| matches!(
+        change,
+        SuiObjectChange::Mutated { object_id: id, .. } if *id == gas_object_id
+    )));
+
+    // Only the selected fields are returned.
+    let options = SuiTransactionResponseOptions {
+        show_input: false,
+        show_effects: false,
+        show_balance_changes: false,
+        show_object_changes: false,
+        show_raw_input: true,
+        ..Default::default()
+    };
+    let response = authority_state
+        .get_transaction(digest, options)
+        .await
+        .unwrap();
+    assert!(response.certificate.is_none());
+    assert!(response.effects.is_none());
+    assert!(response.balance_changes.is_empty());
+    assert!(response.object_changes.is_empty());
+    assert_eq!(
+        response.raw_transaction.unwrap().to_vec().unwrap(),
+        transaction_data.to_bytes()
+    );
+}
+
 #[tokio::test]
 async fn test_handle_confirmation_transaction_idempotent() {
     let (sender, sender_key) = get_key_pair();
@@ -1255,13 +1695,17 @@ async fn test_genesis_sui_sysmtem_state_object() {
     assert_eq!(move_object.type_, SuiSystemState::type_());
 }
 
-#[tokio::test]
-async fn test_change_epoch_transaction() {
-    let authority_state = init_state().await;
+/// Certify and execute the transaction changing to epoch 1 and upgrading the framework to
+/// `framework_packages`, signed by the only validator of `authority_state`.
+async fn execute_change_epoch(
+    authority_state: &AuthorityState,
+    framework_packages: Vec<SystemPackage>,
+) -> TransactionEffects {
     let signed_tx = SignedTransaction::new_change_epoch(
         1,
         100,
         100,
+        framework_packages,
         authority_state.name,
         &*authority_state.secret,
     );
@@ -1283,17 +1727,120 @@ async fn test_change_epoch_transaction() {
         )
         .unwrap()
         .unwrap();
-    let result = authority_state
+    authority_state
         .handle_confirmation_transaction(ConfirmationTransaction::new(certificate))
         .await
+        .unwrap()
+        .signed_effects
+        .unwrap()
+        .effects
+}
+
+/// Record in the system state that validators with a quorum of stake voted for upgrading the
+/// framework to the bytecode of digest `digest`, running under `protocol_version`.
+async fn approve_framework_upgrade(
+    authority_state: &AuthorityState,
+    digest: Vec<u8>,
+    protocol_version: u64,
+) {
+    let mut sui_system_state = authority_state.get_sui_system_state_object().await.unwrap();
+    sui_system_state.framework_upgrade.approved = MoveOption {
+        vec: vec![FrameworkUpgrade {
+            digest,
+            protocol_version,
+        }],
+    };
+    let mut object = authority_state
+        .get_object(&SUI_SYSTEM_STATE_OBJECT_ID)
+        .await
+        .unwrap()
         .unwrap();
-    assert!(result.signed_effects.unwrap().effects.status.is_ok());
-    let sui_system_object = authority_state.get_sui_system_state_object().await.unwrap();
-    assert_eq!(sui_system_object.epoch, 1);
+    object
+        .data
+        .try_as_move_mut()
+        .unwrap()
+        .update_contents_without_version_change(bcs::to_bytes(&sui_system_state).unwrap());
+    object.previous_transaction = TransactionDigest::genesis();
+    authority_state.insert_genesis_object(object).await;
 }
 
 #[tokio::test]
-async fn test_transfer_sui_no_amount() {
+async fn test_change_epoch_transaction() {
+    let authority_state = init_state().await;
+    let effects = execute_change_epoch(&authority_state, vec![]).await;
+    assert!(effects.status.is_ok());
+    let sui_system_object = authority_state.get_sui_system_state_object().await.unwrap();
+    assert_eq!(sui_system_object.epoch, 1);
+}
+
+#[tokio::test]
+async fn test_change_epoch_transaction_upgrades_framework() {
+    let authority_state = init_state().await;
+    let framework_id = ObjectID::from(SUI_FRAMEWORK_ADDRESS);
+    let mut packages = sui_framework::get_system_packages();
+    // A compatible change to the bytecode of the framework: a constant no code uses.
+    let framework = packages
+        .iter_mut()
+        .find(|package| package.id == framework_id)
+        .unwrap();
+    let mut module = CompiledModule::deserialize(&framework.modules[0]).unwrap();
+    module.constant_pool.push(file_format::Constant {
+        type_: file_format::SignatureToken::U64,
+        data: bcs::to_bytes(&42u64).unwrap(),
+    });
+    let mut upgraded_module = Vec::new();
+    module.serialize(&mut upgraded_module).unwrap();
+    framework.modules[0] = upgraded_module.clone();
+    approve_framework_upgrade(&authority_state, framework_digest(&packages).to_vec(), 2).await;
+
+    let effects = execute_change_epoch(&authority_state, packages).await;
+    assert!(effects.status.is_ok());
+    let sui_system_state = authority_state.get_sui_system_state_object().await.unwrap();
+    assert_eq!(sui_system_state.epoch, 1);
+    assert_eq!(sui_system_state.protocol_version, 2);
+    assert!(sui_system_state.approved_framework_upgrade().is_none());
+
+    // Only the framework package changed, and gets a new version.
+    let framework = authority_state
+        .get_object(&framework_id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(framework.version(), OBJECT_START_VERSION.increment());
+    let module_name = module.self_id().name().to_string();
+    assert_eq!(
+        framework
+            .data
+            .try_as_package()
+            .unwrap()
+            .serialized_module_map()[&module_name],
+        upgraded_module
+    );
+    let stdlib = authority_state
+        .get_object(&ObjectID::from(MOVE_STDLIB_ADDRESS))
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(stdlib.version(), OBJECT_START_VERSION);
+    assert!(effects
+        .mutated
+        .iter()
+        .any(|(object_ref, _)| object_ref.0 == framework_id));
+}
+
+#[tokio::test]
+async fn test_change_epoch_transaction_cannot_upgrade_framework_not_voted_for() {
+    let authority_state = init_state().await;
+    let effects =
+        execute_change_epoch(&authority_state, sui_framework::get_system_packages()).await;
+    assert!(!effects.status.is_ok());
+    let sui_system_state = authority_state.get_sui_system_state_object().await.unwrap();
+    assert_eq!(sui_system_state.epoch, 0);
+    assert_eq!(sui_system_state.protocol_version, 1);
+}
+
+#[tokio::test]
+async fn test_transfer_sui_no_amount() {
     let (sender, sender_key) = get_key_pair();
     let recipient = dbg_addr(2);
     let gas_object_id = ObjectID::random();
@@ -1396,6 +1943,103 @@ async fn test_transfer_sui_with_amount() {
     );
 }
 
+/// A shared deny list of SUI coins, denying `denied`.
+fn sui_deny_list(denied: Vec<SuiAddress>) -> Object {
+    use sui_types::deny_list::DenyList;
+    use sui_types::gas_coin::GAS;
+    use sui_types::id::VersionedID;
+    use sui_types::object::MoveObject;
+
+    let content = DenyList {
+        id: VersionedID::new(ObjectID::random(), OBJECT_START_VERSION),
+        denied,
+    };
+    let obj = MoveObject::new(
+        DenyList::type_(GAS::type_tag()),
+        bcs::to_bytes(&content).unwrap(),
+    );
+    Object::new_move(obj, Owner::Shared, TransactionDigest::genesis())
+}
+
+#[tokio::test]
+async fn test_deny_list_blocks_denied_sender() {
+    use sui_types::gas_coin::GAS;
+
+    let (denied, denied_key) = get_key_pair();
+    let (allowed, allowed_key) = get_key_pair();
+    let recipient = dbg_addr(2);
+    let denied_gas = Object::with_id_owner_for_testing(ObjectID::random(), denied);
+    let allowed_gas = Object::with_id_owner_for_testing(ObjectID::random(), allowed);
+
+    let authority_state = init_state_with_objects(vec![
+        denied_gas.clone(),
+        allowed_gas.clone(),
+        sui_deny_list(vec![denied]),
+    ])
+    .await;
+
+    let tx_data = TransactionData::new_transfer_sui(
+        recipient,
+        denied,
+        None,
+        denied_gas.compute_object_reference(),
+        MAX_GAS,
+    );
+    let signature = Signature::new(&tx_data, &denied_key);
+    let result = authority_state
+        .handle_transaction(Transaction::new(tx_data, signature))
+        .await;
+    assert_eq!(
+        result.unwrap_err(),
+        SuiError::AddressDeniedForCoin {
+            address: denied,
+            coin_type: GAS::type_tag().to_string(),
+        }
+    );
+
+    let tx_data = TransactionData::new_transfer_sui(
+        recipient,
+        allowed,
+        None,
+        allowed_gas.compute_object_reference(),
+        MAX_GAS,
+    );
+    let signature = Signature::new(&tx_data, &allowed_key);
+    authority_state
+        .handle_transaction(Transaction::new(tx_data, signature))
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn test_deny_list_does_not_block_certificates() {
+    let (sender, sender_key) = get_key_pair();
+    let gas = Object::with_id_owner_for_testing(ObjectID::random(), sender);
+    let authority_state = init_state_with_objects(vec![gas.clone()]).await;
+
+    let tx_data = TransactionData::new_transfer_sui(
+        dbg_addr(2),
+        sender,
+        None,
+        gas.compute_object_reference(),
+        MAX_GAS,
+    );
+    let signature = Signature::new(&tx_data, &sender_key);
+    let certificate =
+        init_certified_transaction(Transaction::new(tx_data, signature), &authority_state);
+
+    // The sender is denied after the transaction was certified, which does not stop its
+    // execution: validators only check the deny lists when signing.
+    authority_state
+        .insert_genesis_object(sui_deny_list(vec![sender]))
+        .await;
+    let response = authority_state
+        .handle_confirmation_transaction(ConfirmationTransaction::new(certificate))
+        .await
+        .unwrap();
+    response.signed_effects.unwrap().effects.status.unwrap();
+}
+
 // helpers
 
 #[cfg(test)]
@@ -1406,7 +2050,11 @@ fn init_state_parameters() -> (Committee, SuiAddress, KeyPair, Arc<AuthorityStor
         /* address */ *authority_key.public_key_bytes(),
         /* voting right */ 1,
     );
-    let committee = Committee::new(0, authorities);
+    let protocol_keys = BTreeMap::from([(
+        *authority_key.public_key_bytes(),
+        authority_key.protocol_public_key(),
+    )]);
+    let committee = Committee::new(0, authorities, protocol_keys);
 
     // Create a random directory to store the DB
 
@@ -1596,7 +2244,6 @@ async fn shared_object() {
 
     let shared_object_id = ObjectID::random();
     let shared_object = {
-        use sui_types::gas_coin::GasCoin;
         use sui_types::object::MoveObject;
 
         let content = GasCoin::new(shared_object_id, OBJECT_START_VERSION, 10);
@@ -1686,3 +2333,511 @@ async fn shared_object() {
         .version();
     assert_eq!(shared_object_version, SequenceNumber::from(2));
 }
+
+/// A certificate of `ObjectBasics::<function>(args)`, sequenced by consensus.
+async fn sequence_framework_call(
+    authority: &AuthorityState,
+    sender: SuiAddress,
+    keypair: &KeyPair,
+    gas_object_ref: ObjectRef,
+    function: &str,
+    args: Vec<CallArg>,
+) -> CertifiedTransaction {
+    let package_object_ref = authority.get_framework_object_ref().await.unwrap();
+    let data = TransactionData::new_move_call(
+        sender,
+        package_object_ref,
+        ident_str!("ObjectBasics").to_owned(),
+        Identifier::new(function).unwrap(),
+        /* type_args */ vec![],
+        gas_object_ref,
+        args,
+        MAX_GAS,
+    );
+    let signature = Signature::new(&data, keypair);
+    let certificate = init_certified_transaction(Transaction::new(data, signature), authority);
+    authority
+        .handle_consensus_transaction(
+            /* last_consensus_index */ ExecutionIndices::default(),
+            ConsensusTransaction::UserTransaction(Box::new(certificate.clone())),
+        )
+        .await
+        .unwrap();
+    certificate
+}
+
+/// An `ObjectBasics::Object` of `owner`, which is laid out as a coin is: an ID and a `u64`.
+fn object_basics_object(object_id: ObjectID, owner: Owner) -> Object {
+    use sui_types::object::MoveObject;
+
+    let content = GasCoin::new(object_id, OBJECT_START_VERSION, 10);
+    let type_ = StructTag {
+        address: SUI_FRAMEWORK_ADDRESS,
+        module: ident_str!("ObjectBasics").to_owned(),
+        name: ident_str!("Object").to_owned(),
+        type_params: vec![],
+    };
+    let obj = MoveObject::new(type_, content.to_bcs_bytes());
+    Object::new_move(obj, owner, TransactionDigest::genesis())
+}
+
+#[tokio::test]
+async fn test_transactions_sequenced_after_shared_object_deletion_fail() {
+    let (sender, keypair) = get_key_pair();
+    let gas_objects: Vec<_> = (0..3)
+        .map(|_| Object::with_id_owner_for_testing(ObjectID::random(), sender))
+        .collect();
+    let shared_object_id = ObjectID::random();
+    let shared_object = object_basics_object(shared_object_id, Owner::Shared);
+    let authority =
+        init_state_with_objects(gas_objects.iter().cloned().chain([shared_object])).await;
+
+    // Both transactions are sequenced before the first one deletes the shared object.
+    let delete = sequence_framework_call(
+        &authority,
+        sender,
+        &keypair,
+        gas_objects[0].compute_object_reference(),
+        "delete",
+        vec![CallArg::SharedObject(shared_object_id)],
+    )
+    .await;
+    let set_value = sequence_framework_call(
+        &authority,
+        sender,
+        &keypair,
+        gas_objects[1].compute_object_reference(),
+        "set_value",
+        vec![
+            CallArg::SharedObject(shared_object_id),
+            CallArg::Pure(bcs::to_bytes(&5u64).unwrap()),
+        ],
+    )
+    .await;
+
+    let effects = authority
+        .handle_confirmation_transaction(ConfirmationTransaction::new(delete))
+        .await
+        .unwrap()
+        .signed_effects
+        .unwrap()
+        .effects;
+    assert!(effects.status.is_ok());
+    assert!(authority
+        .get_object(&shared_object_id)
+        .await
+        .unwrap()
+        .is_none());
+
+    // The transaction sequenced after the deletion executes as a no-op, which still pays for gas.
+    let effects = authority
+        .handle_confirmation_transaction(ConfirmationTransaction::new(set_value))
+        .await
+        .unwrap()
+        .signed_effects
+        .unwrap()
+        .effects;
+    match effects.status {
+        ExecutionStatus::Failure { error, .. } => assert!(matches!(
+            *error,
+            SuiError::SharedObjectDeleted { object_id } if object_id == shared_object_id
+        )),
+        status => panic!("Expected the transaction to fail, got {status:?}"),
+    }
+    // Only the gas object is mutated.
+    assert_eq!(effects.mutated.len(), 1);
+    assert_eq!(effects.gas_object.0 .0, gas_objects[1].id());
+    assert!(effects
+        .shared_objects
+        .iter()
+        .any(|(id, _, digest)| *id == shared_object_id
+            && *digest == ObjectDigest::OBJECT_DIGEST_DELETED));
+
+    // A transaction on the deleted shared object is not signed anymore.
+    let package_object_ref = authority.get_framework_object_ref().await.unwrap();
+    let data = TransactionData::new_move_call(
+        sender,
+        package_object_ref,
+        ident_str!("ObjectBasics").to_owned(),
+        ident_str!("delete").to_owned(),
+        /* type_args */ vec![],
+        gas_objects[2].compute_object_reference(),
+        vec![CallArg::SharedObject(shared_object_id)],
+        MAX_GAS,
+    );
+    let signature = Signature::new(&data, &keypair);
+    let result = authority
+        .handle_transaction(Transaction::new(data, signature))
+        .await;
+    match result {
+        Err(SuiError::LockErrors { errors }) => assert!(errors.iter().any(|error| matches!(
+            error,
+            SuiError::SharedObjectDeleted { object_id } if *object_id == shared_object_id
+        ))),
+        result => panic!("Expected the transaction to be refused, got {result:?}"),
+    }
+}
+
+#[tokio::test]
+async fn test_party_object_is_used_through_consensus_by_its_party_only() {
+    let (party, party_keypair) = get_key_pair();
+    let (outsider, outsider_keypair) = get_key_pair();
+    let party_gas = Object::with_id_owner_for_testing(ObjectID::random(), party);
+    let outsider_gas = Object::with_id_owner_for_testing(ObjectID::random(), outsider);
+    let party_object_id = ObjectID::random();
+    let party_object = object_basics_object(party_object_id, Owner::Party(party));
+    let authority =
+        init_state_with_objects([party_gas.clone(), outsider_gas.clone(), party_object]).await;
+    let set_value_args = vec![
+        CallArg::SharedObject(party_object_id),
+        CallArg::Pure(bcs::to_bytes(&5u64).unwrap()),
+    ];
+
+    // A transaction of someone outside the party is not signed.
+    let package_object_ref = authority.get_framework_object_ref().await.unwrap();
+    let data = TransactionData::new_move_call(
+        outsider,
+        package_object_ref,
+        ident_str!("ObjectBasics").to_owned(),
+        ident_str!("set_value").to_owned(),
+        /* type_args */ vec![],
+        outsider_gas.compute_object_reference(),
+        set_value_args.clone(),
+        MAX_GAS,
+    );
+    let signature = Signature::new(&data, &outsider_keypair);
+    let result = authority
+        .handle_transaction(Transaction::new(data, signature))
+        .await;
+    match result {
+        Err(SuiError::LockErrors { errors }) => assert!(errors
+            .iter()
+            .any(|error| matches!(error, SuiError::IncorrectSigner { .. }))),
+        result => panic!("Expected the transaction to be refused, got {result:?}"),
+    }
+
+    // The party uses the object through consensus, and keeps it.
+    let certificate = sequence_framework_call(
+        &authority,
+        party,
+        &party_keypair,
+        party_gas.compute_object_reference(),
+        "set_value",
+        set_value_args,
+    )
+    .await;
+    let effects = authority
+        .handle_confirmation_transaction(ConfirmationTransaction::new(certificate))
+        .await
+        .unwrap()
+        .signed_effects
+        .unwrap()
+        .effects;
+    assert!(effects.status.is_ok());
+    assert!(effects
+        .shared_objects
+        .iter()
+        .any(|(id, _, _)| *id == party_object_id));
+    let party_object = authority
+        .get_object(&party_object_id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(party_object.owner, Owner::Party(party));
+    assert!(party_object.version() > OBJECT_START_VERSION);
+}
+
+/// The value of the `ObjectBasics::Object` `object_id`.
+async fn object_basics_value(authority: &AuthorityState, object_id: &ObjectID) -> u64 {
+    let object = authority.get_object(object_id).await.unwrap().unwrap();
+    let contents = object.data.try_as_move().unwrap().contents();
+    bcs::from_bytes::<GasCoin>(contents).unwrap().value()
+}
+
+#[tokio::test]
+async fn test_read_only_shared_object_readers_share_its_version() {
+    let (sender, keypair) = get_key_pair();
+    let gas_objects: Vec<_> = (0..3)
+        .map(|_| Object::with_id_owner_for_testing(ObjectID::random(), sender))
+        .collect();
+    let readers: Vec<_> = (0..2)
+        .map(|_| object_basics_object(ObjectID::random(), Owner::AddressOwner(sender)))
+        .collect();
+    let shared_object_id = ObjectID::random();
+    let shared_object = object_basics_object(shared_object_id, Owner::Shared);
+    let authority = init_state_with_objects(
+        gas_objects
+            .iter()
+            .chain(&readers)
+            .cloned()
+            .chain([shared_object]),
+    )
+    .await;
+    let read = |reader: &Object| {
+        vec![
+            CallArg::ImmOrOwnedObject(reader.compute_object_reference()),
+            CallArg::ReadOnlySharedObject(shared_object_id),
+        ]
+    };
+
+    // The first reader and the writer sequenced after it are assigned the same version, and the
+    // second reader the version the writer leaves the object at.
+    let first_read = sequence_framework_call(
+        &authority,
+        sender,
+        &keypair,
+        gas_objects[0].compute_object_reference(),
+        "update",
+        read(&readers[0]),
+    )
+    .await;
+    assert_eq!(
+        authority.db().get_schedule(&shared_object_id).unwrap(),
+        Some(OBJECT_START_VERSION)
+    );
+    let write = sequence_framework_call(
+        &authority,
+        sender,
+        &keypair,
+        gas_objects[1].compute_object_reference(),
+        "set_value",
+        vec![
+            CallArg::SharedObject(shared_object_id),
+            CallArg::Pure(bcs::to_bytes(&5u64).unwrap()),
+        ],
+    )
+    .await;
+    let second_read = sequence_framework_call(
+        &authority,
+        sender,
+        &keypair,
+        gas_objects[2].compute_object_reference(),
+        "update",
+        read(&readers[1]),
+    )
+    .await;
+
+    // The writer executes first, and the first reader still reads the version it was assigned.
+    for certificate in [write, first_read, second_read] {
+        let effects = authority
+            .handle_confirmation_transaction(ConfirmationTransaction::new(certificate))
+            .await
+            .unwrap()
+            .signed_effects
+            .unwrap()
+            .effects;
+        assert!(effects.status.is_ok());
+    }
+    assert_eq!(object_basics_value(&authority, &readers[0].id()).await, 10);
+    assert_eq!(object_basics_value(&authority, &readers[1].id()).await, 5);
+    let shared_object = authority
+        .get_object(&shared_object_id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(shared_object.version(), OBJECT_START_VERSION.increment());
+}
+
+#[tokio::test]
+async fn test_read_only_shared_object_cannot_be_mutated() {
+    let (sender, keypair) = get_key_pair();
+    let gas_object = Object::with_id_owner_for_testing(ObjectID::random(), sender);
+    let shared_object_id = ObjectID::random();
+    let shared_object = object_basics_object(shared_object_id, Owner::Shared);
+    let authority = init_state_with_objects([gas_object.clone(), shared_object]).await;
+
+    let certificate = sequence_framework_call(
+        &authority,
+        sender,
+        &keypair,
+        gas_object.compute_object_reference(),
+        "set_value",
+        vec![
+            CallArg::ReadOnlySharedObject(shared_object_id),
+            CallArg::Pure(bcs::to_bytes(&5u64).unwrap()),
+        ],
+    )
+    .await;
+    let effects = authority
+        .handle_confirmation_transaction(ConfirmationTransaction::new(certificate))
+        .await
+        .unwrap()
+        .signed_effects
+        .unwrap()
+        .effects;
+    match effects.status {
+        ExecutionStatus::Failure { error, .. } => {
+            assert!(matches!(*error, SuiError::TypeError { .. }))
+        }
+        status => panic!("Expected the transaction to fail, got {status:?}"),
+    }
+    assert_eq!(object_basics_value(&authority, &shared_object_id).await, 10);
+}
+
+/// Start an authority again on the store of `authority`, as a node does when it restarts.
+async fn restart_authority(authority: &AuthorityState) -> AuthorityState {
+    AuthorityState::new(
+        (**authority.committee.load()).clone(),
+        authority.name,
+        authority.secret.clone(),
+        authority.database.clone(),
+        None,
+        None,
+        &sui_config::genesis::Genesis::get_default_genesis(),
+        false,
+    )
+    .await
+}
+
+#[tokio::test]
+async fn test_shutdown_rejects_certificates() {
+    let (sender, sender_key) = get_key_pair();
+    let object_id = ObjectID::random();
+    let gas_object_id = ObjectID::random();
+    let authority_state =
+        init_state_with_ids(vec![(sender, object_id), (sender, gas_object_id)]).await;
+    let object = authority_state
+        .get_object(&object_id)
+        .await
+        .unwrap()
+        .unwrap();
+    let gas_object = authority_state
+        .get_object(&gas_object_id)
+        .await
+        .unwrap()
+        .unwrap();
+    let certificate = init_certified_transfer_transaction(
+        sender,
+        &sender_key,
+        dbg_addr(2),
+        object.compute_object_reference(),
+        gas_object.compute_object_reference(),
+        &authority_state,
+    );
+
+    authority_state.shutdown().await.unwrap();
+    let result = authority_state
+        .handle_confirmation_transaction(ConfirmationTransaction::new(certificate))
+        .await;
+    assert!(matches!(result, Err(SuiError::AuthorityShuttingDown)));
+
+    // The shutdown is recorded as clean, so the next start does not recover the store.
+    assert!(authority_state
+        .database
+        .take_clean_shutdown_marker()
+        .unwrap());
+}
+
+#[tokio::test]
+async fn test_recover_certificates_without_effects() {
+    let (sender, keypair) = get_key_pair();
+    let gas_object = Object::with_id_owner_for_testing(ObjectID::random(), sender);
+    let gas_object_ref = gas_object.compute_object_reference();
+    let shared_object_id = ObjectID::random();
+    let shared_object = {
+        use sui_types::object::MoveObject;
+
+        let content = GasCoin::new(shared_object_id, OBJECT_START_VERSION, 10);
+        let obj = MoveObject::new(/* type */ GasCoin::type_(), content.to_bcs_bytes());
+        Object::new_move(obj, Owner::Shared, TransactionDigest::genesis())
+    };
+    let authority = init_state_with_objects(vec![gas_object, shared_object]).await;
+
+    let package_object_ref = authority.get_framework_object_ref().await.unwrap();
+    let data = TransactionData::new_move_call(
+        sender,
+        package_object_ref,
+        ident_str!("ObjectBasics").to_owned(),
+        ident_str!("create").to_owned(),
+        /* type_args */ vec![],
+        gas_object_ref,
+        /* args */
+        vec![
+            CallArg::SharedObject(shared_object_id),
+            CallArg::Pure(16u64.to_le_bytes().to_vec()),
+            CallArg::Pure(bcs::to_bytes(&AccountAddress::from(sender)).unwrap()),
+        ],
+        MAX_GAS,
+    );
+    let signature = Signature::new(&data, &keypair);
+    let transaction = Transaction::new(data, signature);
+    let transaction_digest = *transaction.digest();
+    let vote = authority
+        .handle_transaction(transaction.clone())
+        .await
+        .unwrap()
+        .signed_transaction
+        .unwrap();
+    let certificate = SignatureAggregator::try_new(transaction, &authority.committee.load())
+        .unwrap()
+        .append(vote.auth_sign_info.authority, vote.auth_sign_info.signature)
+        .unwrap()
+        .unwrap();
+
+    // The authority crashes after consensus sequenced the certificate, but before executing it.
+    authority
+        .handle_consensus_transaction(
+            /* last_consensus_index */ ExecutionIndices::default(),
+            ConsensusTransaction::UserTransaction(Box::new(certificate)),
+        )
+        .await
+        .unwrap();
+    assert!(!authority.db().effects_exists(&transaction_digest).unwrap());
+
+    // Starting again executes the certificate.
+    let authority = restart_authority(&authority).await;
+    assert!(authority.db().effects_exists(&transaction_digest).unwrap());
+    let shared_object_version = authority
+        .get_object(&shared_object_id)
+        .await
+        .unwrap()
+        .unwrap()
+        .version();
+    assert_eq!(shared_object_version, SequenceNumber::from(2));
+}
+
+#[tokio::test]
+async fn test_epoch_tables_are_dropped_after_the_next_epoch() {
+    let (sender, sender_key) = get_key_pair();
+    let object_id = ObjectID::random();
+    let gas_object_id = ObjectID::random();
+    let authority_state =
+        init_state_with_ids(vec![(sender, object_id), (sender, gas_object_id)]).await;
+    let object = authority_state
+        .get_object(&object_id)
+        .await
+        .unwrap()
+        .unwrap();
+    let gas_object = authority_state
+        .get_object(&gas_object_id)
+        .await
+        .unwrap()
+        .unwrap();
+    let transaction = init_transfer_transaction(
+        sender,
+        &sender_key,
+        dbg_addr(2),
+        object.compute_object_reference(),
+        gas_object.compute_object_reference(),
+    );
+    let transaction_digest = *transaction.digest();
+    authority_state
+        .handle_transaction(transaction)
+        .await
+        .unwrap();
+    let store = authority_state.db();
+    assert!(store.transaction_exists(&transaction_digest).unwrap());
+
+    // The signed transaction belongs to the epoch it was signed in.
+    store.start_epoch_tables(1).unwrap();
+    assert!(!store.transaction_exists(&transaction_digest).unwrap());
+    assert!(store.epoch_tables_path(0).exists());
+
+    // The tables of an epoch are removed once the epoch after it is over.
+    store.start_epoch_tables(2).unwrap();
+    assert!(!store.epoch_tables_path(0).exists());
+    assert!(store.epoch_tables_path(1).exists());
+
+    // Epochs only move forward.
+    assert!(store.start_epoch_tables(2).is_err());
+}
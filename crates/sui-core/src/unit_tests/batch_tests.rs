@@ -24,8 +24,9 @@ use std::fs;
 use std::sync::Arc;
 use sui_types::messages::{
     AccountInfoRequest, AccountInfoResponse, BatchInfoRequest, BatchInfoResponseItem,
-    ConfirmationTransaction, ConsensusTransaction, ObjectInfoRequest, ObjectInfoResponse,
-    Transaction, TransactionInfoRequest, TransactionInfoResponse,
+    ConfirmationTransaction, ConsensusTransaction, DryRunTransactionRequest,
+    DryRunTransactionResponse, ObjectInfoRequest, ObjectInfoResponse, Transaction,
+    TransactionInfoRequest, TransactionInfoResponse,
 };
 use sui_types::object::Object;
 
@@ -39,7 +40,11 @@ where
         /* address */ *authority_key.public_key_bytes(),
         /* voting right */ 1,
     );
-    let committee = Committee::new(0, authorities);
+    let protocol_keys = BTreeMap::from([(
+        *authority_key.public_key_bytes(),
+        authority_key.protocol_public_key(),
+    )]);
+    let committee = Committee::new(0, authorities, protocol_keys);
 
     (committee, authority_address, authority_key)
 }
@@ -566,6 +571,13 @@ impl AuthorityAPI for TrustworthyAuthorityClient {
         unimplemented!();
     }
 
+    async fn handle_dry_run(
+        &self,
+        _request: DryRunTransactionRequest,
+    ) -> Result<DryRunTransactionResponse, SuiError> {
+        unimplemented!();
+    }
+
     /// Handle Batch information requests for this authority.
     async fn handle_batch_stream(
         &self,
@@ -692,6 +704,13 @@ impl AuthorityAPI for ByzantineAuthorityClient {
         unimplemented!();
     }
 
+    async fn handle_dry_run(
+        &self,
+        _request: DryRunTransactionRequest,
+    ) -> Result<DryRunTransactionResponse, SuiError> {
+        unimplemented!();
+    }
+
     /// Handle Batch information requests for this authority.
     /// This function comes from a byzantine authority that has incorrect behavior.
     async fn handle_batch_stream(
@@ -760,7 +779,8 @@ async fn test_safe_batch_stream() {
     println!("init public key {:?}", public_key_bytes);
 
     authorities.insert(public_key_bytes, 1);
-    let committee = Committee::new(0, authorities);
+    let protocol_keys = BTreeMap::from([(public_key_bytes, authority_key.protocol_public_key())]);
+    let committee = Committee::new(0, authorities, protocol_keys);
     // Create an authority
     let store = Arc::new(AuthorityStore::open(&path, None));
     let state = AuthorityState::new(
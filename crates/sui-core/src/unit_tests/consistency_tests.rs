@@ -0,0 +1,73 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use super::*;
+
+use sui_types::base_types::SuiAddress;
+use sui_types::crypto::get_key_pair;
+
+fn committee(stakes: &[StakeUnit]) -> (Committee, Vec<AuthorityName>) {
+    let keys: Vec<_> = stakes.iter().map(|_| get_key_pair().1).collect();
+    let names: Vec<_> = keys.iter().map(|key| *key.public_key_bytes()).collect();
+    let voting_rights = names.iter().copied().zip(stakes.iter().copied()).collect();
+    let protocol_keys = keys
+        .iter()
+        .map(|key| (*key.public_key_bytes(), key.protocol_public_key()))
+        .collect();
+    (Committee::new(0, voting_rights, protocol_keys), names)
+}
+
+fn object_view(version: u64, owner: SuiAddress) -> View {
+    View::Object {
+        version: SequenceNumber::from(version),
+        digest: ObjectDigest::MIN,
+        owner: Owner::AddressOwner(owner),
+    }
+}
+
+#[test]
+fn test_report_groups_answers_by_stake() {
+    let (committee, names) = committee(&[1, 2, 3, 4]);
+    let owner = SuiAddress::random_for_testing_only();
+    let subject = Subject::Object(ObjectID::random());
+    let report = ConsistencyReport::new(
+        subject,
+        &committee,
+        vec![
+            (names[0], object_view(1, owner)),
+            (names[1], View::Error("timed out".to_string())),
+            (names[2], object_view(2, owner)),
+            (names[3], object_view(2, owner)),
+        ],
+    );
+
+    assert!(!report.is_consistent());
+    assert_eq!(report.total_stake, 10);
+    let stakes: Vec<_> = report.groups.iter().map(|group| group.stake).collect();
+    assert_eq!(stakes, vec![7, 2, 1]);
+    assert_eq!(report.groups[0].view, object_view(2, owner));
+    assert_eq!(report.groups[0].authorities, vec![names[2], names[3]]);
+    let mut outliers: Vec<_> = report.outliers().copied().collect();
+    outliers.sort();
+    let mut expected = vec![names[0], names[1]];
+    expected.sort();
+    assert_eq!(outliers, expected);
+}
+
+#[test]
+fn test_report_of_agreeing_validators_is_consistent() {
+    let (committee, names) = committee(&[1, 1, 1]);
+    let report = ConsistencyReport::new(
+        Subject::Transaction(TransactionDigest::random()),
+        &committee,
+        names
+            .iter()
+            .map(|name| (*name, View::TransactionMissing))
+            .collect(),
+    );
+
+    assert!(report.is_consistent());
+    assert_eq!(report.groups.len(), 1);
+    assert_eq!(report.groups[0].stake, 3);
+    assert_eq!(report.outliers().count(), 0);
+}
@@ -56,6 +56,8 @@ module ObjectOwner::ObjectOwner {
     // since all owners must be in the arguments for authentication.
     public(script) fun mutate_child(_child: &mut Child) {}
 
+    public(script) fun mutate_parent(_parent: &mut Parent) {}
+
     // This should always succeeds, even when child is not owned by parent.
     public(script) fun mutate_child_with_parent(_child: &mut Child, _parent: &mut Parent) {}
 
@@ -70,6 +72,28 @@ module ObjectOwner::ObjectOwner {
         Transfer::transfer_child_to_address(child, child_ref, TxContext::sender(ctx));
     }
 
+    // The child is loaded from the store, without being passed to the call.
+    public(script) fun take_and_return_child(parent: &mut Parent) {
+        let child_ref = Option::extract(&mut parent.child);
+        let child = Transfer::take_child_object(parent, &child_ref);
+        let child_ref = Transfer::transfer_child_to_object(child, child_ref, parent);
+        Option::fill(&mut parent.child, child_ref);
+    }
+
+    public(script) fun take_and_remove_child(parent: &mut Parent, ctx: &mut TxContext) {
+        let child_ref = Option::extract(&mut parent.child);
+        let child = Transfer::take_child_object(parent, &child_ref);
+        Transfer::transfer_child_to_address(child, child_ref, TxContext::sender(ctx));
+    }
+
+    // Taking a child which is also passed to the call aborts.
+    public(script) fun take_passed_child(parent: &mut Parent, _child: &mut Child) {
+        let child_ref = Option::extract(&mut parent.child);
+        let child = Transfer::take_child_object(parent, &child_ref);
+        let child_ref = Transfer::transfer_child_to_object(child, child_ref, parent);
+        Option::fill(&mut parent.child, child_ref);
+    }
+
     // Call to delete_child can fail if it's still owned by a parent.
     public(script) fun delete_child(child: Child, _parent: &mut Parent) {
         let Child { id } = child;
@@ -0,0 +1,212 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Executes a canonical suite of transactions and checks their gas costs against the snapshot
+//! in `data/gas_costs.yaml`, so that a change to the costs of common transactions is always
+//! deliberate. When a change is intended, update the snapshot with
+//!
+//! ```text
+//! UPDATE_GAS_SNAPSHOTS=1 cargo test -p sui-core gas_snapshot
+//! ```
+//!
+//! and check it in. The test fails when the snapshot is missing, rather than recording it, so
+//! that deleting the file doesn't pass the check.
+
+use super::*;
+
+use super::authority_tests::{
+    call_move, create_move_object, init_state_with_ids, send_and_confirm_transaction, TestCallArg,
+};
+use super::move_integration_tests::build_and_try_publish_test_package;
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::PathBuf;
+use sui_types::{
+    base_types::dbg_addr,
+    crypto::{get_key_pair, KeyPair, Signature},
+    gas::GasCostSummary,
+    gas_coin::GAS,
+    messages::Transaction,
+    object::GAS_VALUE_FOR_TESTING,
+};
+
+const GAS_SNAPSHOT_FILE: &str = "src/unit_tests/data/gas_costs.yaml";
+
+const UPDATE_GAS_SNAPSHOTS: &str = "UPDATE_GAS_SNAPSHOTS";
+
+#[tokio::test]
+async fn test_gas_snapshot() {
+    let costs = canonical_gas_costs().await;
+
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join(GAS_SNAPSHOT_FILE);
+    if std::env::var_os(UPDATE_GAS_SNAPSHOTS).is_some() {
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(&path, serde_yaml::to_string(&costs).unwrap()).unwrap();
+        return;
+    }
+
+    assert!(
+        path.exists(),
+        "The gas snapshot {} is missing: record it with `{UPDATE_GAS_SNAPSHOTS}=1 cargo test -p sui-core gas_snapshot`",
+        path.display()
+    );
+
+    let snapshot: BTreeMap<String, GasCostSummary> =
+        serde_yaml::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+    let changes = costs
+        .keys()
+        .chain(snapshot.keys())
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .filter(|name| costs.get(*name) != snapshot.get(*name))
+        .map(|name| {
+            format!(
+                "{name}: expected {:?}, got {:?}",
+                snapshot.get(name),
+                costs.get(name)
+            )
+        })
+        .collect::<Vec<_>>();
+    assert!(
+        changes.is_empty(),
+        "The gas costs of the canonical transactions changed:\n{}\n\
+         If the change is intended, update the snapshot with `{UPDATE_GAS_SNAPSHOTS}=1 cargo test -p sui-core gas_snapshot`",
+        changes.join("\n")
+    );
+}
+
+/// The gas costs of the canonical transactions, by name.
+async fn canonical_gas_costs() -> BTreeMap<String, GasCostSummary> {
+    let (sender, sender_key) = get_key_pair();
+    let recipient = dbg_addr(2);
+    let gas_object_id = ObjectID::random();
+    let object_id = ObjectID::random();
+    let coin_id = ObjectID::random();
+    let authority_state = init_state_with_ids(vec![
+        (sender, gas_object_id),
+        (sender, object_id),
+        (sender, coin_id),
+    ])
+    .await;
+    let mut costs = BTreeMap::new();
+    let mut record = |name: &str, effects: &TransactionEffects| {
+        assert!(
+            effects.status.is_ok(),
+            "{name} failed: {:?}",
+            effects.status
+        );
+        costs.insert(name.to_string(), effects.status.gas_cost_summary().clone());
+    };
+
+    // Native transfers.
+    let object = authority_state
+        .get_object(&object_id)
+        .await
+        .unwrap()
+        .unwrap();
+    let gas_object = authority_state
+        .get_object(&gas_object_id)
+        .await
+        .unwrap()
+        .unwrap();
+    let data = TransactionData::new_transfer(
+        recipient,
+        object.compute_object_reference(),
+        sender,
+        gas_object.compute_object_reference(),
+        GAS_VALUE_FOR_TESTING,
+    );
+    let effects = send_and_confirm(&authority_state, data, &sender_key).await;
+    record("transfer_object", &effects);
+
+    let coin = authority_state.get_object(&coin_id).await.unwrap().unwrap();
+    let data = TransactionData::new_transfer_sui(
+        recipient,
+        sender,
+        Some(100),
+        coin.compute_object_reference(),
+        GAS_VALUE_FOR_TESTING,
+    );
+    let effects = send_and_confirm(&authority_state, data, &sender_key).await;
+    record("transfer_sui", &effects);
+
+    // Publishing.
+    let response = build_and_try_publish_test_package(
+        &authority_state,
+        &sender,
+        &sender_key,
+        &gas_object_id,
+        "object_wrapping",
+        GAS_VALUE_FOR_TESTING,
+    )
+    .await;
+    record("publish_package", &response.signed_effects.unwrap().effects);
+
+    // Move calls.
+    let effects = create_move_object(&authority_state, &gas_object_id, &sender, &sender_key)
+        .await
+        .unwrap();
+    record("move_call_create_object", &effects);
+    let created_object_id = effects.created[0].0 .0;
+
+    let framework = authority_state.get_framework_object_ref().await.unwrap();
+    let effects = call_move(
+        &authority_state,
+        &gas_object_id,
+        &sender,
+        &sender_key,
+        &framework,
+        "ObjectBasics",
+        "set_value",
+        vec![],
+        vec![TestCallArg::Object(created_object_id), TestCallArg::U64(42)],
+    )
+    .await
+    .unwrap();
+    record("move_call_mutate_object", &effects);
+
+    let effects = call_move(
+        &authority_state,
+        &gas_object_id,
+        &sender,
+        &sender_key,
+        &framework,
+        "ObjectBasics",
+        "delete",
+        vec![],
+        vec![TestCallArg::Object(created_object_id)],
+    )
+    .await
+    .unwrap();
+    record("move_call_delete_object", &effects);
+
+    let effects = call_move(
+        &authority_state,
+        &gas_object_id,
+        &sender,
+        &sender_key,
+        &framework,
+        "Coin",
+        "split",
+        vec![GAS::type_tag()],
+        vec![TestCallArg::Object(coin_id), TestCallArg::U64(10)],
+    )
+    .await
+    .unwrap();
+    record("move_call_split_coin", &effects);
+
+    costs
+}
+
+async fn send_and_confirm(
+    authority_state: &AuthorityState,
+    data: TransactionData,
+    sender_key: &KeyPair,
+) -> TransactionEffects {
+    let signature = Signature::new(&data, sender_key);
+    send_and_confirm_transaction(authority_state, Transaction::new(data, signature))
+        .await
+        .unwrap()
+        .signed_effects
+        .unwrap()
+        .effects
+}
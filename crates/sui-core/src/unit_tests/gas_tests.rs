@@ -6,6 +6,7 @@ use super::*;
 use super::authority_tests::{init_state_with_ids, send_and_confirm_transaction};
 use super::move_integration_tests::build_and_try_publish_test_package;
 use move_core_types::account_address::AccountAddress;
+use move_core_types::gas_schedule::InternalGasUnits;
 use move_core_types::ident_str;
 use sui_adapter::genesis;
 use sui_types::gas_coin::GasCoin;
@@ -13,7 +14,7 @@ use sui_types::object::GAS_VALUE_FOR_TESTING;
 use sui_types::{
     base_types::dbg_addr,
     crypto::{get_key_pair, Signature},
-    gas::{MAX_GAS_BUDGET, MIN_GAS_BUDGET},
+    gas::{MAX_GAS_BUDGET, MAX_TX_COMPUTATION_UNITS, MIN_GAS_BUDGET},
     messages::Transaction,
 };
 
@@ -422,9 +423,12 @@ async fn test_move_call_gas() -> SuiResult {
     let effects = response.signed_effects.unwrap().effects;
     let (gas_cost, err) = effects.status.unwrap_err();
     // We will run out of gas during VM execution.
-    assert!(matches!(err, SuiError::AbortedExecution { .. }));
-    assert!(std::string::ToString::to_string(&err)
-        .contains("VMError with status OUT_OF_GAS at location Module ModuleId"));
+    assert_eq!(
+        err,
+        SuiError::InsufficientGas {
+            error: "Ran out of gas while executing Move code".to_owned()
+        }
+    );
     let gas_object = authority_state.get_object(&gas_object_id).await?.unwrap();
     let expected_gas_balance = expected_gas_balance - gas_cost.gas_used() + gas_cost.storage_rebate;
     assert_eq!(
@@ -451,6 +455,41 @@ async fn test_storage_gas_unit_price() -> SuiResult {
     Ok(())
 }
 
+#[test]
+fn test_move_call_exceeds_max_computation() {
+    // Mock a runaway Move call, which charges gas until the VM runs out of it.
+    let mut gas_status = SuiGasStatus::new_with_budget(MAX_TX_COMPUTATION_UNITS * 2, 1, 1);
+    gas_status.charge_min_tx_gas().unwrap();
+    gas_status.meter_move_call(|move_gas_status| {
+        while move_gas_status
+            .deduct_gas(InternalGasUnits::new(1_000))
+            .is_ok()
+        {}
+    });
+    // The call stops at the cap, and the gas above it is left of the budget.
+    assert_eq!(gas_status.computation_gas_used(), MAX_TX_COMPUTATION_UNITS);
+    assert_eq!(gas_status.remaining_gas(), MAX_TX_COMPUTATION_UNITS);
+    assert_eq!(
+        gas_status.out_of_gas_error(),
+        SuiError::ExceededMaxComputation {
+            max_computation: MAX_TX_COMPUTATION_UNITS
+        }
+    );
+
+    // Below the cap, running out of gas is running out of the budget.
+    let mut gas_status = SuiGasStatus::new_with_budget(*MIN_GAS_BUDGET * 2, 1, 1);
+    gas_status.meter_move_call(|move_gas_status| {
+        while move_gas_status
+            .deduct_gas(InternalGasUnits::new(1_000))
+            .is_ok()
+        {}
+    });
+    assert!(matches!(
+        gas_status.out_of_gas_error(),
+        SuiError::InsufficientGas { .. }
+    ));
+}
+
 struct TransferResult {
     pub authority_state: AuthorityState,
     pub object_id: ObjectID,
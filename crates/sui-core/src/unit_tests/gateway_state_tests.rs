@@ -58,7 +58,7 @@ async fn transfer_coin(
         )
         .await?;
 
-    let signature = key.sign(&data.to_bytes());
+    let signature = key.sign(&data.to_intent_bytes());
     let result = gateway
         .execute_transaction(Transaction::new(data, signature))
         .await?
@@ -88,7 +88,8 @@ async fn test_transfer_coin() {
     )
     .await
     .unwrap()
-    .effects;
+    .effects
+    .unwrap();
     assert_eq!(effects.mutated.len(), 2);
     assert_eq!(
         effects.mutated_excluding_gas().next().unwrap().owner,
@@ -120,7 +121,8 @@ async fn test_move_call() {
         .unwrap()
         .to_effect_response()
         .unwrap()
-        .effects;
+        .effects
+        .unwrap();
     assert!(effects.status.is_ok());
     assert_eq!(effects.mutated.len(), 1);
     assert_eq!(effects.created.len(), 1);
@@ -149,7 +151,7 @@ async fn test_publish() {
         .await
         .unwrap();
 
-    let signature = key1.sign(&data.to_bytes());
+    let signature = key1.sign(&data.to_intent_bytes());
     gateway
         .execute_transaction(Transaction::new(data, signature))
         .await
@@ -183,7 +185,7 @@ async fn test_coin_split() {
         .await
         .unwrap();
 
-    let signature = key1.sign(&data.to_bytes());
+    let signature = key1.sign(&data.to_intent_bytes());
     let response = gateway
         .execute_transaction(Transaction::new(data, signature))
         .await
@@ -236,7 +238,7 @@ async fn test_coin_split_insufficient_gas() {
         .await
         .unwrap();
 
-    let signature = key1.sign(&data.to_bytes());
+    let signature = key1.sign(&data.to_intent_bytes());
     let response = gateway
         .execute_transaction(Transaction::new(data, signature))
         .await;
@@ -282,7 +284,7 @@ async fn test_coin_merge() {
         .await
         .unwrap();
 
-    let signature = key1.sign(&data.to_bytes());
+    let signature = key1.sign(&data.to_intent_bytes());
     let response = gateway
         .execute_transaction(Transaction::new(data, signature))
         .await
@@ -330,7 +332,7 @@ async fn test_recent_transactions() -> Result<(), anyhow::Error> {
             .transfer_coin(addr1, obj_id, Some(gas_object.id()), 50000, addr2)
             .await
             .unwrap();
-        let signature = key1.sign(&data.to_bytes());
+        let signature = key1.sign(&data.to_intent_bytes());
         let response = gateway
             .execute_transaction(Transaction::new(data, signature))
             .await?;
@@ -339,6 +341,7 @@ async fn test_recent_transactions() -> Result<(), anyhow::Error> {
             response
                 .to_effect_response()?
                 .certificate
+                .unwrap()
                 .transaction_digest,
         ));
         cnt += 1;
@@ -383,7 +386,7 @@ async fn test_equivocation_resilient() {
             )
             .await
             .unwrap();
-        let signature = key1.sign(&data.to_bytes());
+        let signature = key1.sign(&data.to_intent_bytes());
         let handle = tokio::task::spawn({
             let gateway_copy = gateway.clone();
             async move {
@@ -468,7 +471,8 @@ async fn test_transfer_coin_with_retry() {
         .unwrap()
         .to_effect_response()
         .unwrap()
-        .effects;
+        .effects
+        .unwrap();
     let oref = effects.mutated_excluding_gas().next().unwrap();
     let updated_obj_ref = &oref.reference;
     let new_owner = &oref.owner;
@@ -542,7 +546,7 @@ async fn test_get_owner_object() {
         .await
         .unwrap();
 
-    let signature = key1.sign(&data.to_bytes());
+    let signature = key1.sign(&data.to_intent_bytes());
     let response = gateway
         .execute_transaction(Transaction::new(data, signature))
         .await
@@ -565,14 +569,16 @@ async fn test_get_owner_object() {
         )
         .await
         .unwrap();
-    let signature = key1.sign(&data.to_bytes());
-    let response = gateway
+    let signature = key1.sign(&data.to_intent_bytes());
+    let effects = gateway
         .execute_transaction(Transaction::new(data, signature))
         .await
         .unwrap()
         .to_effect_response()
+        .unwrap()
+        .effects
         .unwrap();
-    let parent = &response.effects.created.first().unwrap().reference;
+    let parent = &effects.created.first().unwrap().reference;
     let data = gateway
         .move_call(
             addr1,
@@ -586,14 +592,16 @@ async fn test_get_owner_object() {
         )
         .await
         .unwrap();
-    let signature = key1.sign(&data.to_bytes());
-    let response = gateway
+    let signature = key1.sign(&data.to_intent_bytes());
+    let effects = gateway
         .execute_transaction(Transaction::new(data, signature))
         .await
         .unwrap()
         .to_effect_response()
+        .unwrap()
+        .effects
         .unwrap();
-    let child = &response.effects.created.first().unwrap().reference;
+    let child = &effects.created.first().unwrap().reference;
 
     // Make parent owns child
     let data = gateway
@@ -612,7 +620,7 @@ async fn test_get_owner_object() {
         )
         .await
         .unwrap();
-    let signature = key1.sign(&data.to_bytes());
+    let signature = key1.sign(&data.to_intent_bytes());
     gateway
         .execute_transaction(Transaction::new(data, signature))
         .await
@@ -669,7 +677,7 @@ async fn test_multiple_gateways() {
     )
     .await
     .unwrap();
-    assert!(response.effects.status.is_ok());
+    assert!(response.effects.unwrap().status.is_ok());
 
     // gas_object on gateway2 should be out-of-dated.
     // Show that we can still handle the transaction successfully if we use it on gateway2.
@@ -683,7 +691,7 @@ async fn test_multiple_gateways() {
     )
     .await
     .unwrap();
-    assert!(response.effects.status.is_ok());
+    assert!(response.effects.unwrap().status.is_ok());
 
     // Now we try to use the same gas object on gateway1, and it will still work.
     let response = transfer_coin(
@@ -696,5 +704,5 @@ async fn test_multiple_gateways() {
     )
     .await
     .unwrap();
-    assert!(response.effects.status.is_ok());
+    assert!(response.effects.unwrap().status.is_ok());
 }
@@ -131,3 +131,45 @@ fn test_serde() {
         )
     }
 }
+
+#[test]
+fn test_render_display() {
+    use crate::gateway_types::SuiParsedMoveObject;
+    use std::collections::BTreeMap;
+    use sui_types::display::{Display, DisplayField};
+    use sui_types::id::VersionedID;
+
+    let metadata = BTreeMap::from([(
+        "name".to_string(),
+        SuiMoveValue::String("Excalibur".to_string()),
+    )]);
+    let fields = BTreeMap::from([
+        ("level".to_string(), SuiMoveValue::Number(7)),
+        (
+            "metadata".to_string(),
+            SuiMoveValue::Struct(SuiMoveStruct::WithFields(metadata)),
+        ),
+    ]);
+    let object = SuiParsedMoveObject {
+        type_: "0x1::M::Sword".to_string(),
+        fields: Some(SuiMoveStruct::WithFields(fields)),
+        display: None,
+    };
+    let display = Display {
+        id: VersionedID::new(ObjectID::random(), SequenceNumber::new()),
+        fields: vec![
+            DisplayField {
+                name: "name".to_string(),
+                template: "{metadata.name} (level {level})".to_string(),
+            },
+            DisplayField {
+                name: "description".to_string(),
+                template: "A sword{missing}".to_string(),
+            },
+        ],
+    };
+
+    let rendered = object.render_display(&display);
+    assert_eq!(rendered["name"], "Excalibur (level 7)");
+    assert_eq!(rendered["description"], "A sword");
+}
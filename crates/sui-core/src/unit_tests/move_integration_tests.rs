@@ -11,7 +11,9 @@ use move_package::BuildConfig;
 use sui_types::{
     crypto::KeyPair,
     crypto::{get_key_pair, Signature},
+    error::SuiError,
     event::{Event, EventType, TransferType},
+    gas_coin::GAS,
     messages::ExecutionStatus,
     object::OBJECT_START_VERSION,
 };
@@ -183,14 +185,17 @@ async fn test_object_wrapping_unwrapping() {
         "{:?}",
         effects.status
     );
-    assert_eq!(effects.deleted.len(), 2);
-    // Check that both objects are marked as wrapped in the authority.
+    assert_eq!(
+        (effects.deleted.len(), effects.unwrapped_then_deleted.len()),
+        (1, 1)
+    );
+    // Check that both objects are marked as deleted in the authority.
     let expected_child_object_ref = (
         child_object_ref.0,
         child_object_ref.1.increment(),
         ObjectDigest::OBJECT_DIGEST_DELETED,
     );
-    assert!(effects.deleted.contains(&expected_child_object_ref));
+    assert_eq!(effects.unwrapped_then_deleted[0], expected_child_object_ref);
     check_latest_object_ref(&authority, &expected_child_object_ref).await;
     let expected_parent_object_ref = (
         parent_object_ref.0,
@@ -383,6 +388,25 @@ async fn test_object_owning_another_object() {
     // Check that the child is now owned by the new parent.
     assert_eq!(child_effect.1, new_parent.0);
 
+    // Check that the children of the parents are listed accordingly, one page at a time.
+    let (children, next_cursor) = authority.get_child_objects(parent.0, None, 10).unwrap();
+    assert!(children.is_empty());
+    assert_eq!(next_cursor, None);
+    let (children, next_cursor) = authority.get_child_objects(new_parent.0, None, 0).unwrap();
+    assert!(children.is_empty());
+    assert_eq!(next_cursor, Some(child.0));
+    let (children, next_cursor) = authority
+        .get_child_objects(new_parent.0, next_cursor, 10)
+        .unwrap();
+    assert_eq!(
+        children
+            .iter()
+            .map(|info| info.object_id)
+            .collect::<Vec<_>>(),
+        vec![child.0]
+    );
+    assert_eq!(next_cursor, None);
+
     // Delete the child. This should fail because the child is still owned by a parent,
     // it cannot yet be deleted.
     let effects = call_move(
@@ -498,6 +522,159 @@ async fn test_object_owning_another_object() {
     assert_eq!(effects.deleted.len(), 2);
 }
 
+#[tokio::test]
+async fn test_take_child_object() {
+    let (sender, sender_key) = get_key_pair();
+    let gas = ObjectID::random();
+    let authority = init_state_with_ids(vec![(sender, gas)]).await;
+
+    let package =
+        build_and_publish_test_package(&authority, &sender, &sender_key, &gas, "object_owner")
+            .await;
+
+    let effects = call_move(
+        &authority,
+        &gas,
+        &sender,
+        &sender_key,
+        &package,
+        "ObjectOwner",
+        "create_parent_and_child",
+        vec![],
+        vec![],
+    )
+    .await
+    .unwrap();
+    assert!(effects.status.is_ok());
+    let (parent, child) = if effects.created[0].1 == sender {
+        (effects.created[0].0, effects.created[1].0)
+    } else {
+        (effects.created[1].0, effects.created[0].0)
+    };
+    let child_creation = effects.transaction_digest;
+
+    // The parent alone moves on, so that the child was last written by its creation.
+    let effects = call_move(
+        &authority,
+        &gas,
+        &sender,
+        &sender_key,
+        &package,
+        "ObjectOwner",
+        "mutate_parent",
+        vec![],
+        vec![TestCallArg::Object(parent.0)],
+    )
+    .await
+    .unwrap();
+    assert!(effects.status.is_ok());
+    let parent_mutation = effects.transaction_digest;
+
+    // The child is loaded from the store, with only the parent among the inputs.
+    let effects = call_move(
+        &authority,
+        &gas,
+        &sender,
+        &sender_key,
+        &package,
+        "ObjectOwner",
+        "take_and_return_child",
+        vec![],
+        vec![TestCallArg::Object(parent.0)],
+    )
+    .await
+    .unwrap();
+    assert!(effects.status.is_ok());
+    let child_effect = effects
+        .mutated
+        .iter()
+        .find(|((id, _, _), _)| id == &child.0)
+        .unwrap();
+    assert!(child_effect.0 .1 > child.1);
+    assert_eq!(child_effect.1, parent.0);
+    // The transaction depends on the last writes of both the parent and the child loaded.
+    assert!(effects.dependencies.contains(&parent_mutation));
+    assert!(effects.dependencies.contains(&child_creation));
+
+    // A child passed to the call cannot be taken again.
+    let effects = call_move(
+        &authority,
+        &gas,
+        &sender,
+        &sender_key,
+        &package,
+        "ObjectOwner",
+        "take_passed_child",
+        vec![],
+        vec![TestCallArg::Object(parent.0), TestCallArg::Object(child.0)],
+    )
+    .await
+    .unwrap();
+    let (location, code) = match effects.status.unwrap_err().1 {
+        SuiError::MoveAbort { location, code } => (location, code),
+        error => panic!("Unexpected error: {error}"),
+    };
+    assert_eq!(location.module.short_str_lossless(), "0x2::Transfer");
+    assert_eq!(code, 2);
+
+    // The child taken leaves its parent.
+    let effects = call_move(
+        &authority,
+        &gas,
+        &sender,
+        &sender_key,
+        &package,
+        "ObjectOwner",
+        "take_and_remove_child",
+        vec![],
+        vec![TestCallArg::Object(parent.0)],
+    )
+    .await
+    .unwrap();
+    assert!(effects.status.is_ok());
+    let child_effect = effects
+        .mutated
+        .iter()
+        .find(|((id, _, _), _)| id == &child.0)
+        .unwrap();
+    assert_eq!(child_effect.1, sender);
+}
+
+#[tokio::test]
+async fn test_move_abort_location() {
+    let (sender, sender_key) = get_key_pair();
+    let gas = ObjectID::random();
+    let coin = ObjectID::random();
+    let authority = init_state_with_ids(vec![(sender, gas), (sender, coin)]).await;
+    let package = authority.get_framework_object_ref().await.unwrap();
+
+    // Splitting more than the value of the coin aborts in Balance::split.
+    let effects = call_move(
+        &authority,
+        &gas,
+        &sender,
+        &sender_key,
+        &package,
+        "Coin",
+        "split",
+        vec![GAS::type_tag()],
+        vec![TestCallArg::Object(coin), TestCallArg::U64(u64::MAX)],
+    )
+    .await
+    .unwrap();
+    let (location, code) = match effects.status.unwrap_err().1 {
+        SuiError::MoveAbort { location, code } => (location, code),
+        error => panic!("Unexpected error: {error}"),
+    };
+    assert_eq!(location.module.short_str_lossless(), "0x2::Balance");
+    assert_eq!(location.function_name.as_deref(), Some("split"));
+    assert_eq!(code, 0);
+    assert_eq!(
+        sui_framework::abort_constant_name(&location, code).as_deref(),
+        Some("ENotEnough")
+    );
+}
+
 pub async fn build_and_try_publish_test_package(
     authority: &AuthorityState,
     sender: &SuiAddress,
@@ -532,7 +709,7 @@ pub async fn build_and_try_publish_test_package(
         .unwrap()
 }
 
-async fn build_and_publish_test_package(
+pub async fn build_and_publish_test_package(
     authority: &AuthorityState,
     sender: &SuiAddress,
     sender_key: &KeyPair,
@@ -0,0 +1,140 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use super::*;
+use crate::authority::METRICS;
+use sui_types::base_types::{dbg_addr, ObjectDigest, SequenceNumber};
+
+fn next_version(object: &Object) -> Object {
+    let mut object = object.clone();
+    object.data.try_as_move_mut().unwrap().increment_version();
+    object
+}
+
+async fn read_lock(lock: CachedLock) -> SuiResult<CachedLock> {
+    Ok(lock)
+}
+
+async fn unread_lock() -> SuiResult<CachedLock> {
+    panic!("The lock is cached")
+}
+
+#[test]
+fn test_reads_fill_the_cache() {
+    let cache = ObjectCache::new(2, &METRICS);
+    let object = Object::with_id_owner_for_testing(ObjectID::random(), dbg_addr(1));
+    let id = object.id();
+
+    assert_eq!(
+        cache.get_object(&id, || Ok(Some(object.clone()))).unwrap(),
+        Some(object.clone())
+    );
+    // The second read is served by the cache.
+    assert_eq!(
+        cache.get_object(&id, || unreachable!()).unwrap(),
+        Some(object.clone())
+    );
+
+    // A missing object is not cached.
+    let missing = ObjectID::random();
+    assert_eq!(cache.get_object(&missing, || Ok(None)).unwrap(), None);
+    assert_eq!(
+        cache
+            .get_object(&missing, || Ok(Some(object.clone())))
+            .unwrap(),
+        Some(object)
+    );
+}
+
+#[test]
+fn test_writes_update_the_cache() {
+    let cache = ObjectCache::new(2, &METRICS);
+    let object = Object::with_id_owner_for_testing(ObjectID::random(), dbg_addr(1));
+    let id = object.id();
+    cache.get_object(&id, || Ok(Some(object.clone()))).unwrap();
+
+    let newer = next_version(&object);
+    cache.write_objects(std::iter::once(&newer));
+    assert_eq!(
+        cache.get_object(&id, || unreachable!()).unwrap(),
+        Some(newer.clone())
+    );
+
+    // An older version written again does not replace the newer one.
+    cache.write_objects(std::iter::once(&object));
+    assert_eq!(
+        cache.get_object(&id, || unreachable!()).unwrap(),
+        Some(newer.clone())
+    );
+
+    cache.remove_objects(std::iter::once(&id));
+    assert_eq!(cache.get_object(&id, || Ok(None)).unwrap(), None);
+
+    // An object which was not read is not cached by a write.
+    let other = Object::with_id_owner_for_testing(ObjectID::random(), dbg_addr(1));
+    cache.write_objects(std::iter::once(&other));
+    let read_other = next_version(&other);
+    assert_eq!(
+        cache
+            .get_object(&other.id(), || Ok(Some(read_other.clone())))
+            .unwrap(),
+        Some(read_other)
+    );
+}
+
+#[test]
+fn test_reads_racing_writes_do_not_fill_the_cache() {
+    let cache = ObjectCache::new(2, &METRICS);
+    let object = Object::with_id_owner_for_testing(ObjectID::random(), dbg_addr(1));
+    let id = object.id();
+    let newer = next_version(&object);
+
+    // The object is written while it is read from the tables.
+    let read = cache
+        .get_object(&id, || {
+            cache.write_objects(std::iter::once(&newer));
+            Ok(Some(object.clone()))
+        })
+        .unwrap();
+    assert_eq!(read, Some(object));
+    assert_eq!(
+        cache.get_object(&id, || Ok(Some(newer.clone()))).unwrap(),
+        Some(newer.clone())
+    );
+    assert_eq!(
+        cache.get_object(&id, || unreachable!()).unwrap(),
+        Some(newer)
+    );
+}
+
+#[tokio::test]
+async fn test_lock_cache() {
+    let cache = ObjectCache::new(2, &METRICS);
+    let object_ref = (
+        ObjectID::random(),
+        SequenceNumber::from(1),
+        ObjectDigest::new([1; 32]),
+    );
+    let digest = TransactionDigest::random();
+
+    assert_eq!(
+        cache.get_lock(&object_ref, read_lock(Some(None))).await,
+        Ok(Some(None))
+    );
+    assert_eq!(
+        cache.get_lock(&object_ref, unread_lock()).await,
+        Ok(Some(None))
+    );
+
+    cache.invalidate_locks(std::iter::once(&object_ref));
+    assert_eq!(
+        cache
+            .get_lock(&object_ref, read_lock(Some(Some(digest))))
+            .await,
+        Ok(Some(Some(digest)))
+    );
+    assert_eq!(
+        cache.get_lock(&object_ref, unread_lock()).await,
+        Ok(Some(Some(digest)))
+    );
+}
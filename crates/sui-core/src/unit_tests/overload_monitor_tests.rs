@@ -0,0 +1,83 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use super::*;
+
+fn limiter() -> ExecutionLimiter {
+    ExecutionLimiter::new(OverloadMonitorConfig {
+        min_concurrency: 2,
+        max_concurrency: 10,
+        initial_concurrency: 8,
+        target_latency: Duration::from_millis(100),
+        overload_queue_depth: 3,
+        adjustment_interval: Duration::from_secs(1),
+    })
+}
+
+#[test]
+fn test_slow_executions_lower_concurrency() {
+    let limiter = limiter();
+    limiter.record_latency(Duration::from_millis(300));
+    limiter.record_latency(Duration::from_millis(100));
+    limiter.adjust();
+    assert_eq!(limiter.concurrency(), 4);
+    assert_eq!(limiter.permits.available_permits(), 4);
+
+    // The concurrency never falls below the minimum.
+    for _ in 0..3 {
+        limiter.record_latency(Duration::from_secs(1));
+        limiter.adjust();
+    }
+    assert_eq!(limiter.concurrency(), 2);
+    assert_eq!(limiter.permits.available_permits(), 2);
+
+    // Without executions or waiting certificates, the concurrency stays.
+    limiter.adjust();
+    assert_eq!(limiter.concurrency(), 2);
+}
+
+#[test]
+fn test_waiting_certificates_raise_concurrency() {
+    let limiter = limiter();
+    limiter.queue_depth.store(1, Ordering::SeqCst);
+    limiter.record_latency(Duration::from_millis(10));
+    limiter.adjust();
+    assert_eq!(limiter.concurrency(), 9);
+    assert_eq!(limiter.permits.available_permits(), 9);
+    assert!(!limiter.is_overloaded());
+
+    for _ in 0..5 {
+        limiter.adjust();
+    }
+    assert_eq!(limiter.concurrency(), 10);
+
+    limiter.queue_depth.store(3, Ordering::SeqCst);
+    limiter.adjust();
+    assert!(limiter.is_overloaded());
+    limiter.queue_depth.store(0, Ordering::SeqCst);
+    limiter.adjust();
+    assert!(!limiter.is_overloaded());
+}
+
+#[tokio::test]
+async fn test_held_permits_are_forgotten_on_release() {
+    let limiter = limiter();
+    let held = (0..8)
+        .map(|_| limiter.permits.try_acquire().unwrap())
+        .collect::<Vec<_>>();
+
+    limiter.record_latency(Duration::from_secs(1));
+    limiter.adjust();
+    assert_eq!(limiter.concurrency(), 4);
+    assert_eq!(limiter.excess_permits.load(Ordering::SeqCst), 4);
+
+    for permit in held {
+        limiter.release(permit);
+    }
+    assert_eq!(limiter.permits.available_permits(), 4);
+    assert_eq!(limiter.excess_permits.load(Ordering::SeqCst), 0);
+
+    assert_eq!(limiter.execute(async { 42 }).await, 42);
+    assert_eq!(limiter.queue_depth(), 0);
+    assert_eq!(limiter.permits.available_permits(), 4);
+}
@@ -0,0 +1,82 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use super::*;
+use crate::authority::METRICS;
+use sui_types::base_types::{dbg_addr, TransactionDigest};
+
+fn framework_package() -> Object {
+    Object::new_package(
+        sui_framework::get_sui_framework(),
+        TransactionDigest::genesis(),
+    )
+}
+
+#[test]
+fn test_packages_are_deserialized_once() {
+    let cache = PackageCache::new(2, &METRICS);
+    let package = framework_package();
+    let id = package.id();
+
+    let cached = cache
+        .get_package(&id, || Ok(Some(package.clone())))
+        .unwrap()
+        .unwrap();
+    assert_eq!(cached.version(), package.version());
+    assert_eq!(cached.module("Coin").unwrap().name().as_str(), "Coin");
+    assert!(cached.module("Missing").is_none());
+
+    // The second read is served by the cache.
+    let again = cache.get_package(&id, || unreachable!()).unwrap().unwrap();
+    assert!(Arc::ptr_eq(&cached, &again));
+
+    // A missing package is not cached.
+    let missing = ObjectID::random();
+    assert!(cache.get_package(&missing, || Ok(None)).unwrap().is_none());
+    assert!(cache
+        .get_package(&missing, || Ok(Some(package.clone())))
+        .unwrap()
+        .is_some());
+
+    // Neither is a Move object.
+    let object = Object::with_id_owner_for_testing(ObjectID::random(), dbg_addr(1));
+    assert!(matches!(
+        cache.get_package(&object.id(), || Ok(Some(object.clone()))),
+        Err(SuiError::BadObjectType { .. })
+    ));
+}
+
+#[test]
+fn test_writes_invalidate_packages() {
+    let cache = PackageCache::new(2, &METRICS);
+    let package = framework_package();
+    let id = package.id();
+    let cached = cache
+        .get_package(&id, || Ok(Some(package.clone())))
+        .unwrap()
+        .unwrap();
+
+    cache.invalidate(std::iter::once(&id));
+    let reread = cache
+        .get_package(&id, || Ok(Some(package.clone())))
+        .unwrap()
+        .unwrap();
+    assert!(!Arc::ptr_eq(&cached, &reread));
+
+    // A package written while it is read is not cached from the read.
+    cache.invalidate(std::iter::once(&id));
+    cache
+        .get_package(&id, || {
+            cache.invalidate(std::iter::once(&id));
+            Ok(Some(package.clone()))
+        })
+        .unwrap();
+    let mut read = false;
+    cache
+        .get_package(&id, || {
+            read = true;
+            Ok(Some(package.clone()))
+        })
+        .unwrap();
+    assert!(read);
+}
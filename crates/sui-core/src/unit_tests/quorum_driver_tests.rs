@@ -0,0 +1,198 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use super::*;
+use crate::authority_aggregator::authority_aggregator_tests::{
+    authority_genesis_objects, get_local_client, init_local_authorities, transfer_coin_transaction,
+};
+use crate::authority_client::LocalAuthorityClient;
+use sui_types::base_types::{ObjectID, SequenceNumber, SuiAddress, TransactionDigest};
+use sui_types::crypto::{get_key_pair, KeyPair};
+use sui_types::object::Object;
+
+fn test_config() -> QuorumDriverConfig {
+    QuorumDriverConfig {
+        initial_backoff: Duration::from_millis(10),
+        max_backoff: Duration::from_millis(40),
+        max_rounds: 3,
+        request_timeout: Duration::from_secs(5),
+    }
+}
+
+async fn init() -> (
+    AuthorityAggregator<LocalAuthorityClient>,
+    SuiAddress,
+    KeyPair,
+    Object,
+    Object,
+) {
+    let (sender, key) = get_key_pair();
+    let coin = Object::with_owner_for_testing(sender);
+    let gas = Object::with_owner_for_testing(sender);
+    let (authorities, _) = init_local_authorities(authority_genesis_objects(
+        4,
+        vec![coin.clone(), gas.clone()],
+    ))
+    .await;
+    (authorities, sender, key, coin, gas)
+}
+
+#[test]
+fn test_classify_error() {
+    let conflict = SuiError::ConflictingTransaction {
+        pending_transaction: TransactionDigest::random(),
+    };
+    assert_eq!(classify_error(&conflict), ErrorClass::Conflicting);
+    assert_eq!(
+        classify_error(&SuiError::LockErrors {
+            errors: vec![conflict]
+        }),
+        ErrorClass::Conflicting
+    );
+    assert_eq!(
+        classify_error(&SuiError::LockErrors {
+            errors: vec![SuiError::ObjectNotFound {
+                object_id: ObjectID::random()
+            }]
+        }),
+        ErrorClass::Retryable
+    );
+    assert_eq!(
+        classify_error(&SuiError::ValidatorOverloaded { queue_depth: 1 }),
+        ErrorClass::Retryable
+    );
+    assert_eq!(
+        classify_error(&SuiError::RpcError("Request timed out".into())),
+        ErrorClass::Retryable
+    );
+    assert_eq!(
+        classify_error(&SuiError::UnexpectedSequenceNumber {
+            object_id: ObjectID::random(),
+            expected_sequence: SequenceNumber::from(1),
+            given_sequence: SequenceNumber::from(2),
+        }),
+        ErrorClass::NonRetryable
+    );
+}
+
+#[tokio::test]
+async fn test_execute_transaction_with_a_faulty_validator() {
+    let (mut authorities, sender, key, coin, gas) = init().await;
+    get_local_client(&mut authorities, 0)
+        .fault_config
+        .fail_before_handle_transaction = true;
+    get_local_client(&mut authorities, 1)
+        .fault_config
+        .fail_after_handle_confirmation = true;
+
+    let transaction = transfer_coin_transaction(
+        sender,
+        &key,
+        SuiAddress::random_for_testing_only(),
+        coin.compute_object_reference(),
+        gas.compute_object_reference(),
+    );
+    let (certificate, effects) = QuorumDriver::new(&authorities, test_config())
+        .execute_transaction(transaction.clone())
+        .await
+        .unwrap();
+    assert_eq!(certificate.digest(), transaction.digest());
+    assert!(effects.status.is_ok());
+}
+
+#[tokio::test]
+async fn test_retries_run_out() {
+    let (mut authorities, sender, key, coin, gas) = init().await;
+    for index in 0..2 {
+        get_local_client(&mut authorities, index)
+            .fault_config
+            .fail_before_handle_transaction = true;
+    }
+
+    let transaction = transfer_coin_transaction(
+        sender,
+        &key,
+        SuiAddress::random_for_testing_only(),
+        coin.compute_object_reference(),
+        gas.compute_object_reference(),
+    );
+    let error = QuorumDriver::new(&authorities, test_config())
+        .process_transaction(transaction)
+        .await
+        .unwrap_err();
+    match error {
+        SuiError::QuorumFailed {
+            good_stake,
+            retryable_stake,
+            conflicting_stake,
+            non_retryable_stake,
+            conflicting_transactions,
+            errors,
+        } => {
+            assert_eq!(good_stake, 2);
+            assert_eq!(retryable_stake, 2);
+            assert_eq!(conflicting_stake, 0);
+            assert_eq!(non_retryable_stake, 0);
+            assert!(conflicting_transactions.is_empty());
+            assert_eq!(errors.len(), 2);
+        }
+        error => panic!("Unexpected error: {error}"),
+    }
+}
+
+#[tokio::test]
+async fn test_conflicting_transaction_fails_without_retries() {
+    let (authorities, sender, key, coin, gas) = init().await;
+    let conflicting = transfer_coin_transaction(
+        sender,
+        &key,
+        SuiAddress::random_for_testing_only(),
+        coin.compute_object_reference(),
+        gas.compute_object_reference(),
+    );
+    // Half the committee locks the objects for the conflicting transaction.
+    for client in authorities.authority_clients.values().take(2) {
+        client
+            .handle_transaction(conflicting.clone())
+            .await
+            .unwrap();
+    }
+
+    let transaction = transfer_coin_transaction(
+        sender,
+        &key,
+        SuiAddress::random_for_testing_only(),
+        coin.compute_object_reference(),
+        gas.compute_object_reference(),
+    );
+    let config = QuorumDriverConfig {
+        // A retry would fail the test by timing out.
+        initial_backoff: Duration::from_secs(600),
+        ..test_config()
+    };
+    let error = QuorumDriver::new(&authorities, config)
+        .process_transaction(transaction)
+        .await
+        .unwrap_err();
+    // The message names the transaction spending the objects.
+    assert!(error
+        .to_string()
+        .contains(&format!("{:?} (2 stake)", conflicting.digest())));
+    match error {
+        SuiError::QuorumFailed {
+            good_stake,
+            conflicting_stake,
+            conflicting_transactions,
+            errors,
+            ..
+        } => {
+            assert_eq!(good_stake, 2);
+            assert_eq!(conflicting_stake, 2);
+            assert_eq!(conflicting_transactions, vec![(*conflicting.digest(), 2)]);
+            assert!(errors
+                .iter()
+                .all(|(_, error)| classify_error(error) == ErrorClass::Conflicting));
+        }
+        error => panic!("Unexpected error: {error}"),
+    }
+}
@@ -0,0 +1,163 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use super::*;
+
+use crate::authority::authority_tests::{
+    call_move, create_move_object, init_state_with_ids, TestCallArg,
+};
+use crate::authority::move_integration_tests::build_and_publish_test_package;
+use crate::authority::AuthorityState;
+use sui_types::base_types::{SuiAddress, TransactionDigest};
+use sui_types::crypto::get_key_pair;
+use sui_types::object::Owner;
+use sui_types::object_change::OwnerChange;
+
+#[async_trait]
+impl ObjectSource for AuthorityState {
+    async fn get_object(
+        &self,
+        object_id: ObjectID,
+        version: Option<SequenceNumber>,
+    ) -> SuiResult<Object> {
+        self.get_object_at_version(&object_id, version)
+            .await?
+            .ok_or(SuiError::ObjectNotFound { object_id })
+    }
+}
+
+async fn replay(state: &AuthorityState, digest: TransactionDigest) -> Vec<String> {
+    let (certificate, effects) = state.get_certificate_and_effects(&digest).await.unwrap();
+    let replayed = replay_transaction(state, &certificate, &effects)
+        .await
+        .unwrap();
+    diff_effects(&effects, &replayed)
+}
+
+#[tokio::test]
+async fn test_replay_transaction() {
+    let (sender, sender_key) = get_key_pair();
+    let gas_object_id = ObjectID::random();
+    let state = init_state_with_ids(vec![(sender, gas_object_id)]).await;
+
+    let effects = create_move_object(&state, &gas_object_id, &sender, &sender_key)
+        .await
+        .unwrap();
+    assert!(effects.status.is_ok());
+    let created = effects.created[0].0 .0;
+
+    // The object and the gas have been mutated since, but the replay reads them at the
+    // versions the transaction read.
+    let framework = state.get_framework_object_ref().await.unwrap();
+    let transfer = call_move(
+        &state,
+        &gas_object_id,
+        &sender,
+        &sender_key,
+        &framework,
+        "ObjectBasics",
+        "transfer",
+        vec![],
+        vec![
+            TestCallArg::Object(created),
+            TestCallArg::Address(SuiAddress::random_for_testing_only()),
+        ],
+    )
+    .await
+    .unwrap();
+    assert!(transfer.status.is_ok());
+
+    assert!(replay(&state, effects.transaction_digest).await.is_empty());
+    assert!(replay(&state, transfer.transaction_digest).await.is_empty());
+}
+
+#[tokio::test]
+async fn test_replay_child_objects() {
+    let (sender, sender_key) = get_key_pair();
+    let gas_object_id = ObjectID::random();
+    let state = init_state_with_ids(vec![(sender, gas_object_id)]).await;
+    let package = build_and_publish_test_package(
+        &state,
+        &sender,
+        &sender_key,
+        &gas_object_id,
+        "object_owner",
+    )
+    .await;
+
+    let effects = call_move(
+        &state,
+        &gas_object_id,
+        &sender,
+        &sender_key,
+        &package,
+        "ObjectOwner",
+        "create_parent_and_child",
+        vec![],
+        vec![],
+    )
+    .await
+    .unwrap();
+    assert!(effects.status.is_ok());
+    let (parent, child) = if effects.created[0].1 == sender {
+        (effects.created[0].0, effects.created[1].0)
+    } else {
+        (effects.created[1].0, effects.created[0].0)
+    };
+
+    // The child is loaded during execution, with only the parent among the inputs.
+    let effects = call_move(
+        &state,
+        &gas_object_id,
+        &sender,
+        &sender_key,
+        &package,
+        "ObjectOwner",
+        "take_and_return_child",
+        vec![],
+        vec![TestCallArg::Object(parent.0)],
+    )
+    .await
+    .unwrap();
+    assert!(effects.status.is_ok());
+    assert!(replay(&state, effects.transaction_digest).await.is_empty());
+
+    // Without the child in the effects, its version before the transaction is unknown.
+    let (certificate, mut effects) = state
+        .get_certificate_and_effects(&effects.transaction_digest)
+        .await
+        .unwrap();
+    effects.mutated.retain(|((id, _, _), _)| id != &child.0);
+    assert!(replay_transaction(&state, &certificate, &effects)
+        .await
+        .is_err());
+}
+
+#[tokio::test]
+async fn test_diff_effects() {
+    let (sender, sender_key) = get_key_pair();
+    let gas_object_id = ObjectID::random();
+    let state = init_state_with_ids(vec![(sender, gas_object_id)]).await;
+    let expected = create_move_object(&state, &gas_object_id, &sender, &sender_key)
+        .await
+        .unwrap();
+
+    let mut replayed = expected.clone();
+    replayed.created.clear();
+    let differences = diff_effects(&expected, &replayed);
+    assert_eq!(differences.len(), 1);
+    assert!(differences[0].starts_with("created:"));
+
+    // A replay which only changes an owner differently is reported too.
+    let mut replayed = expected.clone();
+    let ((object_id, version, _), owner) = expected.created[0];
+    replayed.owner_changes.push(OwnerChange {
+        object_id,
+        version,
+        previous_owner: owner,
+        owner: Owner::Shared,
+    });
+    let differences = diff_effects(&expected, &replayed);
+    assert_eq!(differences.len(), 1);
+    assert!(differences[0].starts_with("owner_changes:"));
+}
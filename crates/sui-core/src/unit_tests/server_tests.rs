@@ -479,3 +479,31 @@ async fn test_subscription_safe_client() {
 
     server.state.batch_notifier.close();
 }
+
+#[test]
+fn test_request_lanes_are_capped_apart() {
+    let limits = ValidatorServerLimits {
+        max_inflight_transactions: 1,
+        max_inflight_dry_runs: 1,
+        max_inflight_certificates: 2,
+        max_inflight_queries: 1,
+    };
+    let lanes = RequestLanes::new(&limits, &crate::authority::METRICS);
+
+    let query = lanes.enter(Lane::Query).unwrap();
+    // A full lane refuses requests, and does not hold back the other lanes.
+    let refused = lanes.enter(Lane::Query).unwrap_err();
+    assert_eq!(refused.code(), tonic::Code::ResourceExhausted);
+    let _certificate = lanes.enter(Lane::Certificate).unwrap();
+    let _other_certificate = lanes.enter(Lane::Certificate).unwrap();
+    assert!(lanes.enter(Lane::Certificate).is_err());
+    let _transaction = lanes.enter(Lane::Transaction).unwrap();
+    // Dry runs cannot take the place of the transactions to sign, nor the other way round.
+    let _dry_run = lanes.enter(Lane::DryRun).unwrap();
+    assert!(lanes.enter(Lane::DryRun).is_err());
+    assert!(lanes.enter(Lane::Transaction).is_err());
+
+    // A completed request frees its place in the lane.
+    drop(query);
+    assert!(lanes.enter(Lane::Query).is_ok());
+}
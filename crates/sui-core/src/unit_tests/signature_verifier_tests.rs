@@ -0,0 +1,150 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use super::*;
+use crate::authority::METRICS;
+use std::collections::BTreeMap;
+use sui_types::base_types::{ObjectDigest, ObjectID, ObjectRef, SequenceNumber, SuiAddress};
+use sui_types::crypto::{get_key_pair, KeyPair, Signature};
+use sui_types::messages::{SignedTransaction, Transaction, TransactionData};
+
+fn test_committee(key_pairs: &[KeyPair]) -> Arc<Committee> {
+    Arc::new(Committee::new(
+        0,
+        key_pairs
+            .iter()
+            .map(|key_pair| (*key_pair.public_key_bytes(), 1))
+            .collect(),
+        key_pairs
+            .iter()
+            .map(|key_pair| (*key_pair.public_key_bytes(), key_pair.protocol_public_key()))
+            .collect::<BTreeMap<_, _>>(),
+    ))
+}
+
+fn random_object_ref() -> ObjectRef {
+    (
+        ObjectID::random(),
+        SequenceNumber::new(),
+        ObjectDigest::new([0; 32]),
+    )
+}
+
+/// A certificate of a new transfer, signed by all of `key_pairs`.
+fn make_certificate(key_pairs: &[KeyPair], committee: &Committee) -> CertifiedTransaction {
+    let (sender, sender_key) = get_key_pair();
+    let data = TransactionData::new_transfer(
+        SuiAddress::random_for_testing_only(),
+        random_object_ref(),
+        sender,
+        random_object_ref(),
+        10000,
+    );
+    let signature = Signature::new(&data, &sender_key);
+    let transaction = Transaction::new(data, signature);
+    let signatures: Vec<_> = key_pairs
+        .iter()
+        .map(|key_pair| {
+            let vote = SignedTransaction::new(
+                0,
+                transaction.clone(),
+                *key_pair.public_key_bytes(),
+                key_pair,
+            );
+            (vote.auth_sign_info.authority, vote.auth_sign_info.signature)
+        })
+        .collect();
+    CertifiedTransaction::new_with_signatures(transaction, &signatures, committee).unwrap()
+}
+
+fn pending(
+    certificate: &CertifiedTransaction,
+    committee: &Arc<Committee>,
+) -> (PendingCertificate, oneshot::Receiver<SuiResult>) {
+    let (sender, receiver) = oneshot::channel();
+    let pending = PendingCertificate {
+        certificate: certificate.clone(),
+        committee: committee.clone(),
+        key: CertificateKey::new(certificate, committee),
+        result: sender,
+    };
+    (pending, receiver)
+}
+
+#[tokio::test]
+async fn test_verified_certificates_are_cached() {
+    let key_pairs: Vec<_> = (0..4).map(|_| get_key_pair().1).collect();
+    let committee = test_committee(&key_pairs);
+    let verifier = SignatureVerifier::new(10, &METRICS);
+
+    let certificate = make_certificate(&key_pairs, &committee);
+    assert!(!verifier.is_cached(&certificate, &committee));
+    verifier
+        .verify(&certificate, committee.clone())
+        .await
+        .unwrap();
+    assert!(verifier.is_cached(&certificate, &committee));
+
+    // Another certificate of the same transaction is verified on its own.
+    let mut forged = certificate.clone();
+    forged.auth_sign_info.signature = make_certificate(&key_pairs, &committee)
+        .auth_sign_info
+        .signature;
+    assert!(!verifier.is_cached(&forged, &committee));
+    assert!(verifier.verify(&forged, committee.clone()).await.is_err());
+    assert!(!verifier.is_cached(&forged, &committee));
+}
+
+#[tokio::test]
+async fn test_invalid_certificate_does_not_fail_its_batch() {
+    let key_pairs: Vec<_> = (0..4).map(|_| get_key_pair().1).collect();
+    let committee = test_committee(&key_pairs);
+    let verifier = SignatureVerifier::new(10, &METRICS);
+
+    let certificates: Vec<_> = (0..3)
+        .map(|_| make_certificate(&key_pairs, &committee))
+        .collect();
+    let mut forged = make_certificate(&key_pairs, &committee);
+    forged.auth_sign_info.signature = certificates[0].auth_sign_info.signature;
+    // Signed by less than a quorum, which fails before the signatures are checked.
+    let minority = make_certificate(&key_pairs[..2], &committee);
+
+    let (batch, receivers): (Vec<_>, Vec<_>) = certificates
+        .iter()
+        .chain([&forged, &minority])
+        .map(|certificate| pending(certificate, &committee))
+        .unzip();
+    verifier.verify_batch(batch);
+
+    let mut results = Vec::new();
+    for receiver in receivers {
+        results.push(receiver.await.unwrap());
+    }
+    assert!(results[..3].iter().all(|result| result.is_ok()));
+    assert!(results[3].is_err());
+    assert_eq!(results[4], Err(SuiError::CertificateRequiresQuorum));
+    for certificate in &certificates {
+        assert!(verifier.is_cached(certificate, &committee));
+    }
+    assert!(!verifier.is_cached(&forged, &committee));
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn test_certificates_verified_concurrently() {
+    let key_pairs: Vec<_> = (0..4).map(|_| get_key_pair().1).collect();
+    let committee = test_committee(&key_pairs);
+    let verifier = Arc::new(SignatureVerifier::new(100, &METRICS));
+
+    let handles: Vec<_> = (0..20)
+        .map(|_| {
+            let certificate = make_certificate(&key_pairs, &committee);
+            let verifier = verifier.clone();
+            let committee = committee.clone();
+            tokio::spawn(async move { verifier.verify(&certificate, committee).await })
+        })
+        .collect();
+    for handle in handles {
+        handle.await.unwrap().unwrap();
+    }
+    assert!(verifier.pending.lock().is_empty());
+}
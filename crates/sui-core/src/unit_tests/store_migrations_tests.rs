@@ -0,0 +1,144 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use super::*;
+use std::path::Path;
+use sui_types::base_types::dbg_addr;
+use sui_types::crypto::AuthoritySignInfo;
+
+fn migrated_object(n: u8) -> Object {
+    Object::with_id_owner_for_testing(ObjectID::from_single_byte(n), dbg_addr(n))
+}
+
+/// Writes the object 1.
+fn migrate_from_1(store: &AuthorityStore) -> SuiResult {
+    let object = migrated_object(1);
+    store
+        .objects
+        .insert(&object.compute_object_reference().into(), &object)?;
+    Ok(())
+}
+
+/// Writes the object 2, and fails unless the migration from version 1 ran first.
+fn migrate_from_2(store: &AuthorityStore) -> SuiResult {
+    fp_ensure!(
+        store.get_object(&ObjectID::from_single_byte(1))?.is_some(),
+        SuiError::GenericAuthorityError {
+            error: "the migration from version 1 did not run".to_string(),
+        }
+    );
+    let object = migrated_object(2);
+    store
+        .objects
+        .insert(&object.compute_object_reference().into(), &object)?;
+    Ok(())
+}
+
+fn test_migrations() -> Vec<Migration<false, AuthoritySignInfo>> {
+    // Out of order, to check that the versions decide the order.
+    vec![
+        Migration {
+            from_version: 2,
+            description: "write the object 2",
+            run: migrate_from_2,
+        },
+        Migration {
+            from_version: 1,
+            description: "write the object 1",
+            run: migrate_from_1,
+        },
+    ]
+}
+
+/// A store which recorded no schema version, as opened by the releases before it was.
+async fn unversioned_store(path: &Path) -> AuthorityStore {
+    let store = AuthorityStore::open(path, None);
+    store
+        .insert_genesis_object(Object::with_id_owner_for_testing(
+            ObjectID::random(),
+            dbg_addr(3),
+        ))
+        .await
+        .unwrap();
+    store.schema_version.remove(&SCHEMA_VERSION_ADDR).unwrap();
+    store
+}
+
+#[test]
+fn test_new_store_records_schema_version() {
+    let dir = tempfile::tempdir().unwrap();
+    let store = AuthorityStore::open(dir.path(), None);
+    assert_eq!(store.schema_version().unwrap(), Some(STORE_SCHEMA_VERSION));
+
+    // Nothing to migrate when the store is opened again.
+    drop(store);
+    let store = AuthorityStore::open(dir.path(), None);
+    assert_eq!(store.schema_version().unwrap(), Some(STORE_SCHEMA_VERSION));
+}
+
+#[tokio::test]
+async fn test_migrations_run_in_order() {
+    let dir = tempfile::tempdir().unwrap();
+    let store = unversioned_store(dir.path()).await;
+
+    assert_eq!(
+        store.migrate(3, &test_migrations()).unwrap(),
+        FIRST_SCHEMA_VERSION
+    );
+    assert_eq!(store.schema_version().unwrap(), Some(3));
+    for n in [1, 2] {
+        assert!(store
+            .get_object(&ObjectID::from_single_byte(n))
+            .unwrap()
+            .is_some());
+    }
+
+    // The migrations done are not run again.
+    store
+        .objects
+        .remove(&migrated_object(1).compute_object_reference().into())
+        .unwrap();
+    assert_eq!(store.migrate(3, &test_migrations()).unwrap(), 3);
+    assert!(store
+        .get_object(&ObjectID::from_single_byte(1))
+        .unwrap()
+        .is_none());
+}
+
+#[tokio::test]
+async fn test_missing_migration_fails() {
+    let dir = tempfile::tempdir().unwrap();
+    let store = unversioned_store(dir.path()).await;
+
+    let mut migrations = test_migrations();
+    migrations.retain(|migration| migration.from_version != 2);
+    assert!(matches!(
+        store.migrate(3, &migrations),
+        Err(SuiError::StoreMigrationFailure { version: 2, .. })
+    ));
+    // The migration done is recorded, and the next open resumes from it.
+    assert_eq!(store.schema_version().unwrap(), Some(2));
+    assert_eq!(store.migrate(3, &test_migrations()).unwrap(), 2);
+    assert_eq!(store.schema_version().unwrap(), Some(3));
+}
+
+#[test]
+fn test_newer_schema_is_refused() {
+    let dir = tempfile::tempdir().unwrap();
+    let store = AuthorityStore::open(dir.path(), None);
+    let newer = STORE_SCHEMA_VERSION + 1;
+    store
+        .schema_version
+        .insert(&SCHEMA_VERSION_ADDR, &newer)
+        .unwrap();
+
+    assert!(matches!(
+        store.migrate(STORE_SCHEMA_VERSION, &migrations()),
+        Err(SuiError::StoreSchemaTooNew { version, supported_version })
+            if version == newer && supported_version == STORE_SCHEMA_VERSION
+    ));
+    assert_eq!(store.schema_version().unwrap(), Some(newer));
+
+    drop(store);
+    assert!(std::panic::catch_unwind(|| AuthorityStore::open(dir.path(), None)).is_err());
+}
@@ -0,0 +1,146 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use super::*;
+use crate::authority_aggregator::authority_aggregator_tests::{
+    authority_genesis_objects, get_local_client, init_local_authorities, transfer_coin_transaction,
+};
+use crate::authority_client::LocalAuthorityClient;
+use sui_types::base_types::SuiAddress;
+use sui_types::crypto::get_key_pair;
+use sui_types::object::Object;
+
+fn test_config() -> TransactionOrchestratorConfig {
+    TransactionOrchestratorConfig {
+        quorum_driver: QuorumDriverConfig {
+            initial_backoff: Duration::from_millis(10),
+            max_backoff: Duration::from_millis(40),
+            max_rounds: 3,
+            request_timeout: Duration::from_secs(5),
+        },
+        tx_cert_timeout: Duration::from_secs(10),
+        effects_cert_timeout: Duration::from_secs(10),
+        local_execution_timeout: Duration::from_secs(10),
+    }
+}
+
+/// A committee of four validators and a full node, which all hold the same objects, and a
+/// transaction transferring one of them.
+async fn init() -> (
+    AuthorityAggregator<LocalAuthorityClient>,
+    Arc<AuthorityState>,
+    Transaction,
+) {
+    let (sender, key) = get_key_pair();
+    let coin = Object::with_owner_for_testing(sender);
+    let gas = Object::with_owner_for_testing(sender);
+    let (authorities, _) = init_local_authorities(authority_genesis_objects(
+        4,
+        vec![coin.clone(), gas.clone()],
+    ))
+    .await;
+    let (_, full_node_key) = get_key_pair();
+    let full_node = LocalAuthorityClient::new_with_objects(
+        authorities.committee.clone(),
+        *full_node_key.public_key_bytes(),
+        full_node_key,
+        vec![coin.clone(), gas.clone()],
+        &sui_config::genesis::Genesis::get_default_genesis(),
+    )
+    .await;
+    let transaction = transfer_coin_transaction(
+        sender,
+        &key,
+        SuiAddress::random_for_testing_only(),
+        coin.compute_object_reference(),
+        gas.compute_object_reference(),
+    );
+    (authorities, full_node.state, transaction)
+}
+
+#[tokio::test]
+async fn test_wait_for_local_execution() {
+    let (authorities, full_node, transaction) = init().await;
+    let orchestrator =
+        TransactionOrchestrator::new(Arc::new(authorities), full_node.clone(), test_config());
+    let response = orchestrator
+        .execute_transaction(
+            transaction.clone(),
+            ExecuteTransactionRequestType::WaitForLocalExecution,
+        )
+        .await
+        .unwrap();
+    match response {
+        ExecuteTransactionResponse::EffectsCert(result, confirmed_local_execution) => {
+            let (certificate, effects) = *result;
+            assert_eq!(certificate.digest(), transaction.digest());
+            assert!(effects.status.is_ok());
+            assert!(confirmed_local_execution);
+        }
+        ExecuteTransactionResponse::TxCert(_) => panic!("Expected effects"),
+    }
+    assert!(full_node
+        .database
+        .effects_exists(transaction.digest())
+        .unwrap());
+}
+
+#[tokio::test]
+async fn test_wait_for_tx_cert_executes_in_the_background() {
+    let (authorities, full_node, transaction) = init().await;
+    let orchestrator =
+        TransactionOrchestrator::new(Arc::new(authorities), full_node.clone(), test_config());
+    let response = orchestrator
+        .execute_transaction(
+            transaction.clone(),
+            ExecuteTransactionRequestType::WaitForTxCert,
+        )
+        .await
+        .unwrap();
+    match response {
+        ExecuteTransactionResponse::TxCert(certificate) => {
+            assert_eq!(certificate.digest(), transaction.digest())
+        }
+        ExecuteTransactionResponse::EffectsCert(..) => panic!("Expected a certificate"),
+    }
+
+    // The certificate goes on to execute on the committee and the full node.
+    for _ in 0..100 {
+        if full_node
+            .database
+            .effects_exists(transaction.digest())
+            .unwrap()
+        {
+            return;
+        }
+        tokio::time::sleep(Duration::from_millis(50)).await;
+    }
+    panic!("The certificate was not executed");
+}
+
+#[tokio::test]
+async fn test_request_times_out() {
+    let (mut authorities, full_node, transaction) = init().await;
+    for index in 0..2 {
+        get_local_client(&mut authorities, index)
+            .fault_config
+            .fail_before_handle_transaction = true;
+    }
+    let mut config = test_config();
+    // The retry of the validators which failed outlasts the request.
+    config.quorum_driver.initial_backoff = Duration::from_secs(600);
+    config.tx_cert_timeout = Duration::from_millis(200);
+    let orchestrator = TransactionOrchestrator::new(Arc::new(authorities), full_node, config);
+    let error = orchestrator
+        .execute_transaction(transaction, ExecuteTransactionRequestType::WaitForTxCert)
+        .await
+        .err()
+        .unwrap();
+    assert!(matches!(
+        error,
+        SuiError::ExecutionTimedOut {
+            timeout_ms: 200,
+            ..
+        }
+    ));
+}
@@ -0,0 +1,53 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use super::*;
+use crate::authority::authority_tests::init_state;
+
+#[tokio::test]
+async fn test_vms_are_reused() {
+    let state = init_state().await;
+    let pool = MoveVmPool::new(state._native_functions.clone(), 1);
+
+    let first = pool.checkout(&state.database).unwrap();
+    let first_vm = Arc::clone(&first);
+    drop(first);
+    assert_eq!(pool.idle_count(), 1);
+
+    // A VM is handed back out once returned.
+    let again = pool.checkout(&state.database).unwrap();
+    assert!(Arc::ptr_eq(&first_vm, &again));
+    assert_eq!(pool.idle_count(), 0);
+
+    // The VMs checked out at once are distinct.
+    let other = pool.checkout(&state.database).unwrap();
+    assert!(!Arc::ptr_eq(&again, &other));
+
+    // Only `max_idle` of them are kept.
+    drop(again);
+    drop(other);
+    assert_eq!(pool.idle_count(), 1);
+}
+
+#[tokio::test]
+async fn test_invalidated_vms_are_not_reused() {
+    let state = init_state().await;
+    let pool = MoveVmPool::new(state._native_functions.clone(), 2);
+
+    let idle = pool.checkout(&state.database).unwrap();
+    let checked_out = pool.checkout(&state.database).unwrap();
+    let checked_out_vm = Arc::clone(&checked_out);
+    drop(idle);
+    assert_eq!(pool.idle_count(), 1);
+
+    // The idle VMs are dropped right away, and the ones checked out as they are returned.
+    pool.invalidate();
+    assert_eq!(pool.idle_count(), 0);
+    drop(checked_out);
+    assert_eq!(pool.idle_count(), 0);
+
+    let fresh = pool.checkout(&state.database).unwrap();
+    assert!(!Arc::ptr_eq(&checked_out_vm, &fresh));
+    drop(fresh);
+    assert_eq!(pool.idle_count(), 1);
+}
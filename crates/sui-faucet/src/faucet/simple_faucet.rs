@@ -120,7 +120,7 @@ impl SimpleFaucet {
                 budget,
             )
             .await?;
-        let signature = context.keystore.sign(&signer, &data.to_bytes())?;
+        let signature = context.keystore.sign(&signer, &data.to_intent_bytes())?;
         let response = context
             .gateway
             .execute_transaction(Transaction::new(data, signature))
@@ -144,13 +144,13 @@ impl SimpleFaucet {
             .gateway
             .transfer_coin(signer, coin_id, Some(gas_object_id), budget, recipient)
             .await?;
-        let signature = context.keystore.sign(&signer, &data.to_bytes())?;
-        let effects = context
+        let signature = context.keystore.sign(&signer, &data.to_intent_bytes())?;
+        let (_, effects) = context
             .gateway
             .execute_transaction(Transaction::new(data, signature))
             .await?
             .to_effect_response()?
-            .effects;
+            .into_certificate_and_effects()?;
         if matches!(effects.status, SuiExecutionStatus::Failure { .. }) {
             return Err(anyhow!("Error transferring object: {:#?}", effects.status));
         }
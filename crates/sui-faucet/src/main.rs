@@ -12,6 +12,7 @@ use clap::Parser;
 use std::{
     borrow::Cow,
     net::{IpAddr, Ipv4Addr, SocketAddr},
+    path::PathBuf,
     sync::Arc,
     time::Duration,
 };
@@ -48,6 +49,11 @@ struct FaucetConfig {
 
     #[clap(long, default_value_t = 120)]
     timeout_in_seconds: u64,
+
+    /// The config of the wallet whose active address funds the faucet, the one in the Sui config
+    /// directory by default.
+    #[clap(long)]
+    wallet_config: Option<PathBuf>,
 }
 
 struct AppState<F = SimpleFaucet> {
@@ -61,10 +67,10 @@ async fn main() -> Result<(), anyhow::Error> {
     // initialize tracing
     tracing_subscriber::fmt::init();
 
-    let context = create_wallet_context().await?;
-
     let config: FaucetConfig = FaucetConfig::parse();
 
+    let context = create_wallet_context(config.wallet_config.clone()).await?;
+
     let FaucetConfig {
         host_ip,
         port,
@@ -129,17 +135,19 @@ async fn request_gas(
     }
 }
 
-async fn create_wallet_context() -> Result<WalletContext, anyhow::Error> {
+async fn create_wallet_context(
+    wallet_conf: Option<PathBuf>,
+) -> Result<WalletContext, anyhow::Error> {
     // Create Wallet context.
-    let wallet_conf = sui_config_dir()?.join(SUI_WALLET_CONFIG);
+    let wallet_conf = match wallet_conf {
+        Some(path) => path,
+        None => sui_config_dir()?.join(SUI_WALLET_CONFIG),
+    };
     info!("Initialize wallet from config path: {:?}", wallet_conf);
     let mut context = WalletContext::new(&wallet_conf)?;
     let address = context
-        .config
-        .accounts
-        .first()
-        .cloned()
-        .ok_or_else(|| anyhow::anyhow!("Empty wallet context!"))?;
+        .active_address()
+        .map_err(|_| anyhow::anyhow!("Empty wallet context!"))?;
 
     // Sync client to retrieve objects from the network.
     WalletCommands::SyncClientState {
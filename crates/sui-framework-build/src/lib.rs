@@ -1,11 +1,14 @@
 // Copyright (c) 2022, Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use move_binary_format::CompiledModule;
+use move_binary_format::{access::ModuleAccess, CompiledModule};
 use move_compiler::compiled_unit::{CompiledUnit, NamedCompiledModule};
 use move_core_types::{account_address::AccountAddress, ident_str, language_storage::ModuleId};
 use move_package::BuildConfig;
-use std::{collections::HashSet, path::Path};
+use std::{
+    collections::{BTreeMap, HashSet},
+    path::Path,
+};
 use sui_types::error::{SuiError, SuiResult};
 use sui_verifier::verifier as sui_bytecode_verifier;
 
@@ -52,6 +55,41 @@ pub fn build_framework(framework_dir: &Path) -> SuiResult<Vec<CompiledModule>> {
     build_move_package(framework_dir, build_config, true)
 }
 
+/// The names of the constants of the modules of the package at `lib_dir` and of its
+/// dependencies, by their index in the constant pool. The bytecode only keeps the values of
+/// constants, so they are read from the source maps of the build.
+pub fn build_constant_names(
+    lib_dir: &Path,
+) -> SuiResult<BTreeMap<ModuleId, BTreeMap<u16, String>>> {
+    let build_config = BuildConfig {
+        dev_mode: false,
+        ..Default::default()
+    };
+    let package = build_config
+        .compile_package(lib_dir, &mut Vec::new())
+        .map_err(|error| SuiError::ModuleBuildFailure {
+            error: error.to_string(),
+        })?;
+    Ok(package
+        .root_compiled_units
+        .iter()
+        .chain(package.deps_compiled_units.iter().map(|(_, unit)| unit))
+        .filter_map(|unit| match &unit.unit {
+            CompiledUnit::Module(NamedCompiledModule {
+                module, source_map, ..
+            }) => {
+                let names = source_map
+                    .constant_map
+                    .iter()
+                    .map(|(name, index)| (*index, name.to_string()))
+                    .collect();
+                Some((module.self_id(), names))
+            }
+            _ => None,
+        })
+        .collect())
+}
+
 /// Given a `path` and a `build_config`, build the package in that path.
 /// If we are building the Sui framework, `is_framework` will be true;
 /// Otherwise `is_framework` should be false (e.g. calling from client).
@@ -60,63 +98,112 @@ pub fn build_move_package(
     build_config: BuildConfig,
     is_framework: bool,
 ) -> SuiResult<Vec<CompiledModule>> {
-    match build_config.compile_package(path, &mut Vec::new()) {
-        Err(error) => Err(SuiError::ModuleBuildFailure {
+    build_move_package_for_publish(path, build_config, is_framework, false)
+        .map(|package| package.modules)
+}
+
+/// The modules of a package to publish, and the on-chain modules they depend on.
+pub struct PackageForPublish {
+    /// The modules to publish, in their topological order.
+    pub modules: Vec<CompiledModule>,
+    /// The modules of each package the modules to publish depend on, by the address the package
+    /// was published at.
+    pub dependencies: BTreeMap<AccountAddress, Vec<CompiledModule>>,
+}
+
+/// Build the package in `path` for publishing, like `build_move_package`. Dependencies must have
+/// the on-chain addresses of their packages, unless `with_unpublished_dependencies` is set: the
+/// modules of the dependencies with no address are then published along with the package.
+pub fn build_move_package_for_publish(
+    path: &Path,
+    build_config: BuildConfig,
+    is_framework: bool,
+    with_unpublished_dependencies: bool,
+) -> SuiResult<PackageForPublish> {
+    let package = build_config
+        .compile_package(path, &mut Vec::new())
+        .map_err(|error| SuiError::ModuleBuildFailure {
             error: error.to_string(),
-        }),
-        Ok(package) => {
-            let compiled_modules = package.root_modules_map();
-            if !is_framework {
-                if let Some(m) = compiled_modules
-                    .iter_modules()
-                    .iter()
-                    .find(|m| m.self_id().address() != &AccountAddress::ZERO)
-                {
-                    return Err(SuiError::ModulePublishFailure {
-                        error: format!(
-                            "Modules must all have 0x0 as their addresses. Violated by module {:?}",
-                            m.self_id()
-                        ),
-                    });
-                }
-            }
-            // Collect all module IDs from the current package to be
-            // published (module names are not sufficient as we may
-            // have modules with the same names in user code and in
-            // Sui framework which would result in the latter being
-            // pulled into a set of modules to be published).
-            // For each transitive dependent module, if they are not to be published,
-            // they must have a non-zero address (meaning they are already published on-chain).
-            // TODO: Shall we also check if they are really on-chain in the future?
-            let self_modules: HashSet<ModuleId> = compiled_modules
-                .iter_modules()
-                .iter()
-                .map(|m| m.self_id())
-                .collect();
-            if let Some(m) =
-                package
-                    .deps_compiled_units
-                    .iter()
-                    .find_map(|(_, unit)| match &unit.unit {
-                        CompiledUnit::Module(NamedCompiledModule { module: m, .. })
-                            if !self_modules.contains(&m.self_id())
-                                && m.self_id().address() == &AccountAddress::ZERO =>
-                        {
-                            Some(m)
-                        }
-                        _ => None,
-                    })
-            {
-                return Err(SuiError::ModulePublishFailure { error: format!("Dependent modules must have been published on-chain with non-0 addresses, unlike module {:?}", m.self_id()) });
+        })?;
+    let compiled_modules = package.root_modules_map();
+    if !is_framework {
+        if let Some(m) = compiled_modules
+            .iter_modules()
+            .iter()
+            .find(|m| m.self_id().address() != &AccountAddress::ZERO)
+        {
+            return Err(SuiError::ModulePublishFailure {
+                error: format!(
+                    "Modules must all have 0x0 as their addresses. Violated by module {:?}",
+                    m.self_id()
+                ),
+            });
+        }
+    }
+    // Collect all module IDs from the current package to be
+    // published (module names are not sufficient as we may
+    // have modules with the same names in user code and in
+    // Sui framework which would result in the latter being
+    // pulled into a set of modules to be published).
+    // For each transitive dependent module, if they are not to be published,
+    // they must have a non-zero address (meaning they are already published on-chain).
+    let mut self_modules: HashSet<ModuleId> = compiled_modules
+        .iter_modules()
+        .iter()
+        .map(|m| m.self_id())
+        .collect();
+    let mut published_modules = BTreeMap::new();
+    for (_, unit) in &package.deps_compiled_units {
+        let m = match &unit.unit {
+            CompiledUnit::Module(NamedCompiledModule { module, .. }) => module,
+            _ => continue,
+        };
+        if self_modules.contains(&m.self_id()) {
+            continue;
+        }
+        if m.self_id().address() != &AccountAddress::ZERO {
+            published_modules.insert(m.self_id(), m);
+        } else if with_unpublished_dependencies {
+            self_modules.insert(m.self_id());
+        } else {
+            return Err(SuiError::ModulePublishFailure {
+                error: format!(
+                    "Dependent modules must have been published on-chain with non-0 addresses, unlike module {:?}. They can be published along with the package instead, with --with-unpublished-dependencies",
+                    m.self_id()
+                ),
+            });
+        }
+    }
+    let modules: Vec<CompiledModule> = package
+        .all_modules_map()
+        .compute_dependency_graph()
+        .compute_topological_order()
+        .unwrap()
+        .filter(|m| self_modules.contains(&m.self_id()))
+        .cloned()
+        .collect();
+
+    // Only the on-chain modules the modules to publish use, directly or not, are dependencies:
+    // a package may leave out modules of its own dependencies, as the standard library does.
+    let mut dependencies = BTreeMap::new();
+    let mut visited = HashSet::new();
+    let mut to_visit: Vec<ModuleId> = modules
+        .iter()
+        .flat_map(|m| m.immediate_dependencies())
+        .collect();
+    while let Some(id) = to_visit.pop() {
+        if let Some(m) = published_modules.get(&id) {
+            if visited.insert(id.clone()) {
+                to_visit.extend(m.immediate_dependencies());
+                dependencies
+                    .entry(*id.address())
+                    .or_insert_with(Vec::new)
+                    .push((*m).clone());
             }
-            Ok(package
-                .all_modules_map()
-                .compute_dependency_graph()
-                .compute_topological_order()
-                .unwrap()
-                .filter(|m| self_modules.contains(&m.self_id()))
-                .cloned()
-                .collect())
         }
     }
+    Ok(PackageForPublish {
+        modules,
+        dependencies,
+    })
 }
@@ -21,6 +21,13 @@ fn main() {
 
     serialize_modules_to_file(sui_framework, &out_dir.join("sui-framework")).unwrap();
     serialize_modules_to_file(move_stdlib, &out_dir.join("move-stdlib")).unwrap();
+    // The framework depends on the standard library, so this names the constants of both.
+    let constant_names = sui_framework_build::build_constant_names(sui_framwork_path).unwrap();
+    fs::write(
+        out_dir.join("constant-names"),
+        bcs::to_bytes(&constant_names).unwrap(),
+    )
+    .unwrap();
 
     println!("cargo:rerun-if-changed=build.rs");
     println!(
@@ -0,0 +1,148 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+/// Reads the BCS serialization of values field by field, for contracts
+/// getting the bytes of objects of types they don't know, such as generic
+/// marketplaces and registries, to peel the ID and the fields they need.
+/// Each read aborts past the end of the bytes, so no read costs more than
+/// the bytes it is given.
+module Sui::BCS {
+    use Std::Vector;
+
+    use Sui::ID::{Self, ID};
+
+    /// A read past the end of the bytes.
+    const EOutOfRange: u64 = 0;
+    /// A byte neither 0 nor 1 read as a `bool`.
+    const ENotBool: u64 = 1;
+    /// A length longer than the longest BCS sequence, or than the bytes left.
+    const ELenOutOfRange: u64 = 2;
+
+    /// The bytes of an address, and of an `ID`.
+    const ADDRESS_LENGTH: u64 = 20;
+    /// The length of the longest sequence BCS serializes.
+    const MAX_SEQUENCE_LENGTH: u64 = 2147483647;
+
+    /// The bytes read, and the offset of the next read.
+    struct BCS has store, copy, drop {
+        bytes: vector<u8>,
+        offset: u64,
+    }
+
+    public fun new(bytes: vector<u8>): BCS {
+        BCS { bytes, offset: 0 }
+    }
+
+    /// The number of bytes not read yet.
+    public fun remaining(bcs: &BCS): u64 {
+        Vector::length(&bcs.bytes) - bcs.offset
+    }
+
+    /// The bytes not read yet.
+    public fun into_remainder_bytes(bcs: BCS): vector<u8> {
+        let BCS { bytes, offset } = bcs;
+        let remainder = Vector::empty();
+        let length = Vector::length(&bytes);
+        while (offset < length) {
+            Vector::push_back(&mut remainder, *Vector::borrow(&bytes, offset));
+            offset = offset + 1;
+        };
+        remainder
+    }
+
+    public fun peel_u8(bcs: &mut BCS): u8 {
+        assert!(bcs.offset < Vector::length(&bcs.bytes), EOutOfRange);
+        let byte = *Vector::borrow(&bcs.bytes, bcs.offset);
+        bcs.offset = bcs.offset + 1;
+        byte
+    }
+
+    public fun peel_bool(bcs: &mut BCS): bool {
+        let byte = peel_u8(bcs);
+        assert!(byte <= 1, ENotBool);
+        byte == 1
+    }
+
+    public fun peel_u64(bcs: &mut BCS): u64 {
+        (peel_little_endian(bcs, 8) as u64)
+    }
+
+    public fun peel_u128(bcs: &mut BCS): u128 {
+        peel_little_endian(bcs, 16)
+    }
+
+    public fun peel_address(bcs: &mut BCS): address {
+        bytes_to_address(peel_bytes(bcs, ADDRESS_LENGTH))
+    }
+
+    /// The ID of an object is the first field of its serialization.
+    public fun peel_id(bcs: &mut BCS): ID {
+        ID::new(peel_address(bcs))
+    }
+
+    /// The ULEB128 length of a sequence, which is at most the number of bytes
+    /// left, as every element takes at least one.
+    public fun peel_vec_length(bcs: &mut BCS): u64 {
+        let length = 0;
+        let shift = 0;
+        loop {
+            assert!(shift <= 28, ELenOutOfRange);
+            let byte = (peel_u8(bcs) as u64);
+            length = length | ((byte & 0x7f) << shift);
+            if ((byte & 0x80) == 0) {
+                break
+            };
+            shift = shift + 7;
+        };
+        assert!(length <= MAX_SEQUENCE_LENGTH && length <= remaining(bcs), ELenOutOfRange);
+        length
+    }
+
+    public fun peel_vec_u8(bcs: &mut BCS): vector<u8> {
+        let length = peel_vec_length(bcs);
+        peel_bytes(bcs, length)
+    }
+
+    public fun peel_vec_u64(bcs: &mut BCS): vector<u64> {
+        let length = peel_vec_length(bcs);
+        let values = Vector::empty();
+        while (length > 0) {
+            Vector::push_back(&mut values, peel_u64(bcs));
+            length = length - 1;
+        };
+        values
+    }
+
+    public fun peel_vec_address(bcs: &mut BCS): vector<address> {
+        let length = peel_vec_length(bcs);
+        let values = Vector::empty();
+        while (length > 0) {
+            Vector::push_back(&mut values, peel_address(bcs));
+            length = length - 1;
+        };
+        values
+    }
+
+    fun peel_bytes(bcs: &mut BCS, length: u64): vector<u8> {
+        assert!(length <= remaining(bcs), EOutOfRange);
+        let bytes = Vector::empty();
+        while (length > 0) {
+            Vector::push_back(&mut bytes, peel_u8(bcs));
+            length = length - 1;
+        };
+        bytes
+    }
+
+    fun peel_little_endian(bcs: &mut BCS, length: u8): u128 {
+        assert!((length as u64) <= remaining(bcs), EOutOfRange);
+        let value = 0;
+        let shift = 0;
+        while (shift < 8 * length) {
+            value = value | ((peel_u8(bcs) as u128) << shift);
+            shift = shift + 8;
+        };
+        value
+    }
+
+    native fun bytes_to_address(bytes: vector<u8>): address;
+}
@@ -0,0 +1,38 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+/// Hashes and signature checks, for contracts verifying messages signed on
+/// other chains or by oracles.
+module Sui::Crypto {
+    /// The Keccak-256 hash of `data`, as Ethereum computes it.
+    public native fun keccak256(data: vector<u8>): vector<u8>;
+
+    /// The 32 bytes BLAKE2b hash of `data`.
+    public native fun blake2b256(data: vector<u8>): vector<u8>;
+
+    /// Whether `signature` is a valid ed25519 signature of `msg` by the
+    /// 32 bytes public key `public_key`.
+    public native fun ed25519_verify(
+        signature: vector<u8>,
+        public_key: vector<u8>,
+        msg: vector<u8>,
+    ): bool;
+
+    /// Whether `signature`, `r` followed by `s`, is a valid ECDSA signature
+    /// over secp256k1 of the SHA-256 hash of `msg` by the compressed public
+    /// key `public_key`.
+    public native fun secp256k1_verify(
+        signature: vector<u8>,
+        public_key: vector<u8>,
+        msg: vector<u8>,
+    ): bool;
+
+    /// Whether `signature`, a compressed point of G1, is a valid BLS
+    /// signature of `msg` over BLS12-381 by the compressed public key
+    /// `public_key`, a point of G2, in the basic scheme of the IETF draft.
+    public native fun bls12381_verify(
+        signature: vector<u8>,
+        public_key: vector<u8>,
+        msg: vector<u8>,
+    ): bool;
+}
@@ -0,0 +1,78 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+/// An optional deny list for regulated coins. The holder of the
+/// `TreasuryCap<T>` can create a shared `DenyList<T>` and manage the
+/// addresses in it. Validators refuse to sign transactions in which a denied
+/// address uses a `Coin<T>` as an input object (including as the gas payment).
+/// Transactions certified before the address was denied still execute.
+/// Only the first `DenyList<T>` created for a given `T` is honoured.
+module Sui::DenyList {
+    use Sui::Coin::TreasuryCap;
+    use Sui::ID::VersionedID;
+    use Sui::Transfer;
+    use Sui::TxContext::{Self, TxContext};
+    use Std::Vector;
+
+    /// The address is already in the deny list.
+    const EAlreadyDenied: u64 = 0;
+
+    /// The address is not in the deny list.
+    const ENotDenied: u64 = 1;
+
+    /// The set of addresses that are not allowed to use coins of type `T`
+    /// as transaction inputs. Always a shared object.
+    struct DenyList<phantom T> has key {
+        id: VersionedID,
+        denied: vector<address>,
+    }
+
+    /// Create an empty deny list for coins of type `T` and share it.
+    public fun create<T>(_cap: &TreasuryCap<T>, ctx: &mut TxContext) {
+        Transfer::share_object(DenyList<T> {
+            id: TxContext::new_id(ctx),
+            denied: Vector::empty(),
+        })
+    }
+
+    /// Return true if `addr` is in the deny list.
+    public fun contains<T>(self: &DenyList<T>, addr: address): bool {
+        Vector::contains(&self.denied, &addr)
+    }
+
+    /// Add `addr` to the deny list.
+    /// Aborts with `EAlreadyDenied` if `addr` is already denied.
+    public fun add<T>(self: &mut DenyList<T>, _cap: &TreasuryCap<T>, addr: address) {
+        assert!(!contains(self, addr), EAlreadyDenied);
+        Vector::push_back(&mut self.denied, addr)
+    }
+
+    /// Remove `addr` from the deny list.
+    /// Aborts with `ENotDenied` if `addr` is not denied.
+    public fun remove<T>(self: &mut DenyList<T>, _cap: &TreasuryCap<T>, addr: address) {
+        let (found, i) = Vector::index_of(&self.denied, &addr);
+        assert!(found, ENotDenied);
+        Vector::remove(&mut self.denied, i);
+    }
+
+    // === Entrypoints ===
+
+    /// Create an empty deny list for coins of type `T` and share it.
+    public(script) fun create_<T>(cap: &TreasuryCap<T>, ctx: &mut TxContext) {
+        create(cap, ctx)
+    }
+
+    /// Add `addr` to the deny list.
+    public(script) fun add_<T>(
+        self: &mut DenyList<T>, cap: &TreasuryCap<T>, addr: address, _ctx: &mut TxContext
+    ) {
+        add(self, cap, addr)
+    }
+
+    /// Remove `addr` from the deny list.
+    public(script) fun remove_<T>(
+        self: &mut DenyList<T>, cap: &TreasuryCap<T>, addr: address, _ctx: &mut TxContext
+    ) {
+        remove(self, cap, addr)
+    }
+}
@@ -0,0 +1,148 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+/// Rendering metadata for objects of type `T`. A `Display<T>` holds a list of
+/// named templates (e.g. "name", "image_url", "description") which reference
+/// the fields of a `T` object with `{field}` or `{field.subfield}`. Full nodes
+/// resolve the templates and return the result alongside the object.
+///
+/// `new<T>` takes the `Publisher` of the package that declares `T`, so only
+/// the publisher of a package can set the display for its types.
+module Sui::Display {
+    use Std::ASCII;
+    use Std::Vector;
+    use Sui::ID::{Self, VersionedID};
+    use Sui::Publisher::{Self, Publisher};
+    use Sui::Transfer;
+    use Sui::TxContext::{Self, TxContext};
+    use Sui::UTF8::{Self, String};
+
+    /// A field with the given name already exists.
+    const EFieldAlreadyExists: u64 = 0;
+
+    /// No field with the given name exists.
+    const EFieldDoesNotExist: u64 = 1;
+
+    /// The `Publisher` is not the one of the package that declares `T`.
+    const ENotPublisher: u64 = 2;
+
+    /// A single named template.
+    struct Field has store, copy, drop {
+        name: String,
+        template: String,
+    }
+
+    /// The rendering metadata for objects of type `T`.
+    struct Display<phantom T> has key {
+        id: VersionedID,
+        fields: vector<Field>,
+    }
+
+    /// Create an empty `Display` for objects of type `T`.
+    /// Aborts with `ENotPublisher` unless `publisher` is the one of the package that declares `T`.
+    public fun new<T>(publisher: &Publisher, ctx: &mut TxContext): Display<T> {
+        assert!(Publisher::is_package<T>(publisher), ENotPublisher);
+        Display { id: TxContext::new_id(ctx), fields: Vector::empty() }
+    }
+
+    /// Add a field called `name` rendered from `template`.
+    /// Aborts with `EFieldAlreadyExists` if there is already a field called `name`.
+    public fun add<T>(self: &mut Display<T>, name: String, template: String) {
+        let (found, _) = index_of(self, &name);
+        assert!(!found, EFieldAlreadyExists);
+        Vector::push_back(&mut self.fields, Field { name, template })
+    }
+
+    /// Replace the template of the field called `name`.
+    /// Aborts with `EFieldDoesNotExist` if there is no field called `name`.
+    public fun edit<T>(self: &mut Display<T>, name: String, template: String) {
+        let (found, i) = index_of(self, &name);
+        assert!(found, EFieldDoesNotExist);
+        let field = Vector::borrow_mut(&mut self.fields, i);
+        field.template = template
+    }
+
+    /// Remove the field called `name`.
+    /// Aborts with `EFieldDoesNotExist` if there is no field called `name`.
+    public fun remove<T>(self: &mut Display<T>, name: String) {
+        let (found, i) = index_of(self, &name);
+        assert!(found, EFieldDoesNotExist);
+        Vector::remove(&mut self.fields, i);
+    }
+
+    /// Return the template of the field called `name`.
+    /// Aborts with `EFieldDoesNotExist` if there is no field called `name`.
+    public fun template<T>(self: &Display<T>, name: &String): &String {
+        let (found, i) = index_of(self, name);
+        assert!(found, EFieldDoesNotExist);
+        &Vector::borrow(&self.fields, i).template
+    }
+
+    /// Return the number of fields.
+    public fun length<T>(self: &Display<T>): u64 {
+        Vector::length(&self.fields)
+    }
+
+    /// Send `display` to `recipient`.
+    public fun transfer<T>(display: Display<T>, recipient: address) {
+        Transfer::transfer(display, recipient)
+    }
+
+    /// Transfer `display` to the sender of the current transaction.
+    public fun keep<T>(display: Display<T>, ctx: &TxContext) {
+        transfer(display, TxContext::sender(ctx))
+    }
+
+    /// Destroy `display`.
+    public fun destroy<T>(display: Display<T>) {
+        let Display { id, fields: _ } = display;
+        ID::delete(id);
+    }
+
+    fun index_of<T>(self: &Display<T>, name: &String): (bool, u64) {
+        let i = 0;
+        let len = Vector::length(&self.fields);
+        while (i < len) {
+            if (&Vector::borrow(&self.fields, i).name == name) {
+                return (true, i)
+            };
+            i = i + 1
+        };
+        (false, 0)
+    }
+
+    // === Entrypoints ===
+
+    /// Create an empty `Display` for objects of type `T` and transfer it to the sender.
+    public(script) fun new_<T>(publisher: &Publisher, ctx: &mut TxContext) {
+        keep(new<T>(publisher, ctx), ctx)
+    }
+
+    /// Add a field called `name` rendered from `template`.
+    public(script) fun add_<T>(
+        self: &mut Display<T>, name: vector<u8>, template: vector<u8>, _ctx: &mut TxContext
+    ) {
+        add(self, ascii_to_string(name), ascii_to_string(template))
+    }
+
+    /// Replace the template of the field called `name`.
+    public(script) fun edit_<T>(
+        self: &mut Display<T>, name: vector<u8>, template: vector<u8>, _ctx: &mut TxContext
+    ) {
+        edit(self, ascii_to_string(name), ascii_to_string(template))
+    }
+
+    /// Remove the field called `name`.
+    public(script) fun remove_<T>(self: &mut Display<T>, name: vector<u8>, _ctx: &mut TxContext) {
+        remove(self, ascii_to_string(name))
+    }
+
+    /// Destroy `display`.
+    public(script) fun destroy_<T>(display: Display<T>, _ctx: &mut TxContext) {
+        destroy(display)
+    }
+
+    fun ascii_to_string(bytes: vector<u8>): String {
+        UTF8::from_ascii(ASCII::string(bytes))
+    }
+}
@@ -0,0 +1,164 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+/// A bridge of SUI to other chains, attested by the validators. SUI sent to
+/// another chain is locked in the bridge, and the validators sign the
+/// transfer for the other chain to mint its counterpart. Tokens sent back are
+/// burnt or locked there, and the validators sign the transfer for its
+/// recipient to claim the SUI here, once validators with a quorum of stake
+/// signed it. The bridge committee is the set of active validators, each
+/// signing with its protocol key and weighing its stake.
+module Sui::Bridge {
+    use Std::BCS;
+    use Std::Vector;
+
+    use Sui::Balance::{Self, Balance};
+    use Sui::Event;
+    use Sui::SUI::SUI;
+
+    friend Sui::SuiSystem;
+
+    #[test_only]
+    friend Sui::BridgeTests;
+
+    /// The ID of Sui among the chains of the bridge.
+    const SUI_CHAIN_ID: u8 = 0;
+
+    /// Transfers must be of a positive amount, to another chain.
+    const EInvalidTransfer: u64 = 0;
+    /// The transfer was already claimed.
+    const EAlreadyClaimed: u64 = 1;
+    /// The signers of the transfer don't have a quorum of stake, or their
+    /// aggregate signature of it is invalid.
+    const EInvalidAttestation: u64 = 2;
+
+    /// What identifies a transfer to Sui: its chain and its nonce there.
+    struct TransferKey has store, copy, drop {
+        source_chain: u8,
+        nonce: u64,
+    }
+
+    struct BridgeState has store {
+        /// The SUI sent to other chains, from which transfers to Sui are paid.
+        treasury: Balance<SUI>,
+        /// The nonce of the next transfer from Sui.
+        next_nonce: u64,
+        /// The transfers to Sui which were claimed.
+        // TODO: Use a table once dynamic fields are available.
+        claimed: vector<TransferKey>,
+    }
+
+    /// Emitted when SUI is locked for a transfer to another chain, for the
+    /// validators to sign the transfer.
+    struct TokensLocked has copy, drop {
+        nonce: u64,
+        target_chain: u8,
+        sender: address,
+        /// The address of the recipient on the target chain.
+        recipient: vector<u8>,
+        amount: u64,
+    }
+
+    /// Emitted when the recipient of a transfer to Sui claims it.
+    struct TokensClaimed has copy, drop {
+        source_chain: u8,
+        nonce: u64,
+        recipient: address,
+        amount: u64,
+    }
+
+    public(friend) fun new(): BridgeState {
+        BridgeState {
+            treasury: Balance::zero(),
+            next_nonce: 0,
+            claimed: Vector::empty(),
+        }
+    }
+
+    /// Lock `balance` for `recipient` on `target_chain`, returning the nonce
+    /// of the transfer.
+    public(friend) fun lock(
+        self: &mut BridgeState,
+        balance: Balance<SUI>,
+        target_chain: u8,
+        recipient: vector<u8>,
+        sender: address,
+    ): u64 {
+        let amount = Balance::value(&balance);
+        assert!(amount > 0 && target_chain != SUI_CHAIN_ID, EInvalidTransfer);
+        Balance::join(&mut self.treasury, balance);
+        let nonce = self.next_nonce;
+        self.next_nonce = nonce + 1;
+        Event::emit(TokensLocked { nonce, target_chain, sender, recipient, amount });
+        nonce
+    }
+
+    /// Release the SUI of the transfer of `amount` from `sender` on
+    /// `source_chain`, with `nonce` there, to `recipient`. `signature` is the
+    /// aggregate signature of the transfer by the owners of `public_keys`,
+    /// whose stake is `signers_stake`.
+    public(friend) fun claim(
+        self: &mut BridgeState,
+        source_chain: u8,
+        nonce: u64,
+        sender: vector<u8>,
+        recipient: address,
+        amount: u64,
+        signature: vector<u8>,
+        public_keys: vector<vector<u8>>,
+        signers_stake: u64,
+        quorum_stake_threshold: u64,
+    ): Balance<SUI> {
+        assert!(amount > 0 && source_chain != SUI_CHAIN_ID, EInvalidTransfer);
+        let key = TransferKey { source_chain, nonce };
+        assert!(!Vector::contains(&self.claimed, &key), EAlreadyClaimed);
+        assert!(
+            signers_stake >= quorum_stake_threshold
+                && verify_transfer(
+                    source_chain,
+                    SUI_CHAIN_ID,
+                    nonce,
+                    sender,
+                    BCS::to_bytes(&recipient),
+                    amount,
+                    signature,
+                    public_keys,
+                ),
+            EInvalidAttestation
+        );
+        Vector::push_back(&mut self.claimed, key);
+        Event::emit(TokensClaimed { source_chain, nonce, recipient, amount });
+        Balance::split(&mut self.treasury, amount)
+    }
+
+    public fun treasury_value(self: &BridgeState): u64 {
+        Balance::value(&self.treasury)
+    }
+
+    public fun next_nonce(self: &BridgeState): u64 {
+        self.next_nonce
+    }
+
+    public fun is_claimed(self: &BridgeState, source_chain: u8, nonce: u64): bool {
+        Vector::contains(&self.claimed, &TransferKey { source_chain, nonce })
+    }
+
+    /// Check that `signature` is the aggregate signature of the transfer by
+    /// the owners of the protocol keys `public_keys`.
+    native fun verify_transfer(
+        source_chain: u8,
+        target_chain: u8,
+        nonce: u64,
+        sender: vector<u8>,
+        recipient: vector<u8>,
+        amount: u64,
+        signature: vector<u8>,
+        public_keys: vector<vector<u8>>,
+    ): bool;
+
+    #[test_only]
+    public fun destroy_for_testing(self: BridgeState) {
+        let BridgeState { treasury, next_nonce: _, claimed: _ } = self;
+        Balance::destroy_for_testing(treasury);
+    }
+}
@@ -0,0 +1,193 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+/// Upgrades of the framework packages, which validators vote for by the
+/// digest of the bytecode of all the framework packages after the upgrade,
+/// and the protocol version that bytecode runs under. The upgrade voted for
+/// by validators with a quorum of stake is applied as the epoch ends: the
+/// validators carry the new bytecode in the transaction changing epoch, which
+/// replaces the framework packages if its digest is the one voted for.
+module Sui::FrameworkUpgrade {
+    use Std::Option::{Self, Option};
+    use Std::Vector;
+
+    friend Sui::SuiSystem;
+
+    #[test_only]
+    friend Sui::FrameworkUpgradeTests;
+
+    /// The digest can't be empty, nor the protocol version older than the
+    /// current one.
+    const EInvalidUpgrade: u64 = 0;
+
+    /// The epoch change applies an upgrade the validators did not vote for.
+    const EUpgradeNotApproved: u64 = 1;
+
+    struct Upgrade has store, copy, drop {
+        /// The digest of the bytecode of the framework packages.
+        digest: vector<u8>,
+        protocol_version: u64,
+    }
+
+    /// An upgrade some validators voted for, with the stake each voter had
+    /// at the time of its vote.
+    struct Proposal has store, drop {
+        upgrade: Upgrade,
+        voters: vector<address>,
+        voter_stakes: vector<u64>,
+        stake: u64,
+    }
+
+    struct FrameworkUpgradeState has store {
+        /// The upgrade voted for by validators with a quorum of stake, if any,
+        /// which the epoch change applies.
+        approved: Option<Upgrade>,
+        /// The proposals of the current epoch, of which each validator backs
+        /// at most one.
+        proposals: vector<Proposal>,
+    }
+
+    public(friend) fun new(): FrameworkUpgradeState {
+        FrameworkUpgradeState {
+            approved: Option::none(),
+            proposals: Vector::empty(),
+        }
+    }
+
+    /// Record the vote of `voter`, which has `stake`, for upgrading the
+    /// framework to the bytecode of digest `digest`, running under
+    /// `protocol_version`, taking back its previous vote if any. The upgrade
+    /// is approved while the voters for it have `quorum_stake_threshold`.
+    public(friend) fun vote(
+        self: &mut FrameworkUpgradeState,
+        digest: vector<u8>,
+        protocol_version: u64,
+        current_protocol_version: u64,
+        voter: address,
+        stake: u64,
+        quorum_stake_threshold: u64,
+    ) {
+        assert!(
+            !Vector::is_empty(&digest) && protocol_version >= current_protocol_version,
+            EInvalidUpgrade
+        );
+        withdraw_vote(self, voter);
+
+        let upgrade = Upgrade { digest, protocol_version };
+        let index = find_proposal(&self.proposals, &upgrade);
+        if (index == Vector::length(&self.proposals)) {
+            Vector::push_back(&mut self.proposals, Proposal {
+                upgrade,
+                voters: Vector::empty(),
+                voter_stakes: Vector::empty(),
+                stake: 0,
+            });
+        };
+        let proposal = Vector::borrow_mut(&mut self.proposals, index);
+        Vector::push_back(&mut proposal.voters, voter);
+        Vector::push_back(&mut proposal.voter_stakes, stake);
+        proposal.stake = proposal.stake + stake;
+
+        update_approved(self, quorum_stake_threshold);
+    }
+
+    /// Take back the vote of `voter` in the current epoch, if any.
+    public(friend) fun clear_vote(
+        self: &mut FrameworkUpgradeState,
+        voter: address,
+        quorum_stake_threshold: u64,
+    ) {
+        withdraw_vote(self, voter);
+        update_approved(self, quorum_stake_threshold);
+    }
+
+    /// Apply the upgrade carried by the epoch change, of which `digest` is the
+    /// digest, or empty if it carries none, and return the protocol version of
+    /// the next epoch. The validators leave out an approved upgrade that this
+    /// build of theirs finds incompatible with the framework on chain, which
+    /// keeps the protocol version. The votes don't carry over to the next
+    /// epoch, as the stake of voters changes with it.
+    public(friend) fun advance_epoch(
+        self: &mut FrameworkUpgradeState,
+        digest: vector<u8>,
+        protocol_version: u64,
+    ): u64 {
+        if (!Vector::is_empty(&digest)) {
+            assert!(Option::is_some(&self.approved), EUpgradeNotApproved);
+            let approved = Option::borrow(&self.approved);
+            assert!(approved.digest == digest, EUpgradeNotApproved);
+            protocol_version = approved.protocol_version;
+        };
+        self.approved = Option::none();
+        self.proposals = Vector::empty();
+        protocol_version
+    }
+
+    /// The upgrade the epoch change applies, if any.
+    public fun approved(self: &FrameworkUpgradeState): &Option<Upgrade> {
+        &self.approved
+    }
+
+    public fun digest(upgrade: &Upgrade): &vector<u8> {
+        &upgrade.digest
+    }
+
+    public fun protocol_version(upgrade: &Upgrade): u64 {
+        upgrade.protocol_version
+    }
+
+    /// Approve the proposal whose voters have `quorum_stake_threshold`, if any.
+    /// Each validator backs at most one proposal, so at most one has them.
+    fun update_approved(self: &mut FrameworkUpgradeState, quorum_stake_threshold: u64) {
+        self.approved = Option::none();
+        let length = Vector::length(&self.proposals);
+        let i = 0;
+        while (i < length) {
+            let proposal = Vector::borrow(&self.proposals, i);
+            if (proposal.stake >= quorum_stake_threshold) {
+                self.approved = Option::some(proposal.upgrade);
+                return
+            };
+            i = i + 1;
+        }
+    }
+
+    /// Remove `voter` from the proposal it voted for, and the proposal if no
+    /// one else voted for it.
+    fun withdraw_vote(self: &mut FrameworkUpgradeState, voter: address) {
+        let length = Vector::length(&self.proposals);
+        let i = 0;
+        while (i < length) {
+            let proposal = Vector::borrow_mut(&mut self.proposals, i);
+            let (found, index) = Vector::index_of(&proposal.voters, &voter);
+            if (found) {
+                Vector::remove(&mut proposal.voters, index);
+                proposal.stake = proposal.stake - Vector::remove(&mut proposal.voter_stakes, index);
+                if (Vector::is_empty(&proposal.voters)) {
+                    Vector::remove(&mut self.proposals, i);
+                };
+                return
+            };
+            i = i + 1;
+        }
+    }
+
+    /// The index of the proposal of `upgrade`, or the number of proposals if
+    /// there is none.
+    fun find_proposal(proposals: &vector<Proposal>, upgrade: &Upgrade): u64 {
+        let length = Vector::length(proposals);
+        let i = 0;
+        while (i < length) {
+            if (&Vector::borrow(proposals, i).upgrade == upgrade) {
+                return i
+            };
+            i = i + 1;
+        };
+        length
+    }
+
+    #[test_only]
+    public fun destroy_for_testing(self: FrameworkUpgradeState) {
+        let FrameworkUpgradeState { approved: _, proposals: _ } = self;
+    }
+}
@@ -25,6 +25,7 @@ module Sui::Genesis {
     /// all the information we need in the system.
     fun create(
         validator_pubkeys: vector<vector<u8>>,
+        validator_protocol_pubkeys: vector<vector<u8>>,
         validator_sui_addresses: vector<address>,
         validator_names: vector<vector<u8>>,
         validator_net_addresses: vector<vector<u8>>,
@@ -37,6 +38,7 @@ module Sui::Genesis {
         let count = Vector::length(&validator_pubkeys);
         assert!(
             Vector::length(&validator_sui_addresses) == count
+                && Vector::length(&validator_protocol_pubkeys) == count
                 && Vector::length(&validator_stakes) == count
                 && Vector::length(&validator_names) == count
                 && Vector::length(&validator_net_addresses) == count,
@@ -46,12 +48,16 @@ module Sui::Genesis {
         while (i < count) {
             let sui_address = *Vector::borrow(&validator_sui_addresses, i);
             let pubkey = *Vector::borrow(&validator_pubkeys, i);
+            let protocol_pubkey = *Vector::borrow(&validator_protocol_pubkeys, i);
             let name = *Vector::borrow(&validator_names, i);
             let net_address = *Vector::borrow(&validator_net_addresses, i);
             let stake = *Vector::borrow(&validator_stakes, i);
             Vector::push_back(&mut validators, Validator::new(
                 sui_address,
                 pubkey,
+                protocol_pubkey,
+                // The genesis ceremony checks the proofs of possession of the genesis validators.
+                vector[],
                 name,
                 net_address,
                 Coin::mint_balance(stake, &mut treasury_cap),
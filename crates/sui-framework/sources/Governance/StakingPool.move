@@ -0,0 +1,188 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+/// The staking pool of a validator, in which the delegations to the validator earn their
+/// rewards. The pool mints pool tokens for the delegated SUI as the delegations activate, and
+/// the rewards of each epoch add to the SUI of the pool without minting tokens, so that each
+/// token is worth more SUI as rewards accrue. A delegation is worth the tokens it was minted,
+/// at the exchange rate of the epoch it is withdrawn in.
+module Sui::StakingPool {
+    use Std::Vector;
+
+    use Sui::Balance::{Self, Balance};
+    use Sui::Math;
+    use Sui::SUI::SUI;
+
+    friend Sui::ValidatorSet;
+
+    #[test_only]
+    friend Sui::StakingPoolTests;
+
+    /// The SUI in a pool, and the pool tokens minted for it, at the start of `epoch`.
+    struct PoolTokenExchangeRate has store, copy, drop {
+        epoch: u64,
+        sui_amount: u64,
+        pool_token_amount: u64,
+    }
+
+    struct StakingPool has store {
+        /// The validator the delegations of the pool are delegated to.
+        validator_address: address,
+        /// The SUI of the active delegations, with the rewards they earned.
+        sui_balance: u64,
+        /// The pool tokens of the active delegations.
+        pool_token_balance: u64,
+        /// The rewards earned by the delegations, paid out as they are withdrawn. The principal
+        /// is held by the `Delegation` objects.
+        rewards_pool: Balance<SUI>,
+        /// The SUI of the delegations activating in the next epoch.
+        pending_delegation: u64,
+        /// The exchange rate at the start of each epoch the pool had active delegations in,
+        /// from the oldest epoch to the newest.
+        exchange_rates: vector<PoolTokenExchangeRate>,
+    }
+
+    public(friend) fun new(validator_address: address): StakingPool {
+        StakingPool {
+            validator_address,
+            sui_balance: 0,
+            pool_token_balance: 0,
+            rewards_pool: Balance::zero(),
+            pending_delegation: 0,
+            exchange_rates: Vector::empty(),
+        }
+    }
+
+    /// Add a delegation of `amount`, which activates in the next epoch.
+    public(friend) fun request_add_delegation(self: &mut StakingPool, amount: u64) {
+        self.pending_delegation = self.pending_delegation + amount;
+    }
+
+    /// Add the rewards of the active delegations in the epoch ending.
+    public(friend) fun deposit_rewards(self: &mut StakingPool, rewards: Balance<SUI>) {
+        self.sui_balance = self.sui_balance + Balance::value(&rewards);
+        Balance::join(&mut self.rewards_pool, rewards);
+    }
+
+    /// Activate the pending delegations at the start of `new_epoch`, minting their tokens at the
+    /// exchange rate after the rewards of the epoch ending, and record that rate.
+    public(friend) fun process_pending_delegations(self: &mut StakingPool, new_epoch: u64) {
+        let rate = exchange_rate(self);
+        self.pool_token_balance = self.pool_token_balance + sui_to_pool_tokens(&rate, self.pending_delegation);
+        self.sui_balance = self.sui_balance + self.pending_delegation;
+        self.pending_delegation = 0;
+        let length = Vector::length(&self.exchange_rates);
+        let unchanged = length > 0 && {
+            let last = Vector::borrow(&self.exchange_rates, length - 1);
+            last.sui_amount == self.sui_balance && last.pool_token_amount == self.pool_token_balance
+        };
+        // Record the rate only when it changes, not to grow the pool with each epoch.
+        if (self.sui_balance > 0 && !unchanged) {
+            Vector::push_back(&mut self.exchange_rates, PoolTokenExchangeRate {
+                epoch: new_epoch,
+                sui_amount: self.sui_balance,
+                pool_token_amount: self.pool_token_balance,
+            });
+        };
+    }
+
+    /// Withdraw the delegation of `principal` activated in `activation_epoch`, in
+    /// `current_epoch`, and return its rewards. A delegation which is not active yet earned
+    /// none.
+    public(friend) fun withdraw_rewards(
+        self: &mut StakingPool,
+        principal: u64,
+        activation_epoch: u64,
+        current_epoch: u64,
+    ): Balance<SUI> {
+        if (activation_epoch > current_epoch) {
+            self.pending_delegation = self.pending_delegation - principal;
+            return Balance::zero()
+        };
+        let pool_tokens = sui_to_pool_tokens(&exchange_rate_at(self, activation_epoch), principal);
+        let value = pool_tokens_to_sui(&exchange_rate(self), pool_tokens);
+        self.pool_token_balance = self.pool_token_balance - pool_tokens;
+        self.sui_balance = self.sui_balance - value;
+        let reward = if (value > principal) { value - principal } else { 0 };
+        // The pool tokens of each delegation are rounded down, which leaves the last ones
+        // withdrawn with a little more than the rewards left.
+        let reward = Math::min(reward, Balance::value(&self.rewards_pool));
+        Balance::split(&mut self.rewards_pool, reward)
+    }
+
+    /// The rewards the delegation of `principal` activated in `activation_epoch` would earn if
+    /// it were withdrawn in `current_epoch`.
+    public fun estimated_rewards(
+        self: &StakingPool,
+        principal: u64,
+        activation_epoch: u64,
+        current_epoch: u64,
+    ): u64 {
+        if (activation_epoch > current_epoch) return 0;
+        let pool_tokens = sui_to_pool_tokens(&exchange_rate_at(self, activation_epoch), principal);
+        let value = pool_tokens_to_sui(&exchange_rate(self), pool_tokens);
+        let reward = if (value > principal) { value - principal } else { 0 };
+        Math::min(reward, Balance::value(&self.rewards_pool))
+    }
+
+    public fun validator_address(self: &StakingPool): address {
+        self.validator_address
+    }
+
+    public fun sui_balance(self: &StakingPool): u64 {
+        self.sui_balance
+    }
+
+    public fun pool_token_balance(self: &StakingPool): u64 {
+        self.pool_token_balance
+    }
+
+    public fun pending_delegation(self: &StakingPool): u64 {
+        self.pending_delegation
+    }
+
+    /// The current exchange rate of the pool.
+    fun exchange_rate(self: &StakingPool): PoolTokenExchangeRate {
+        PoolTokenExchangeRate {
+            epoch: 0,
+            sui_amount: self.sui_balance,
+            pool_token_amount: self.pool_token_balance,
+        }
+    }
+
+    /// The exchange rate at the start of `epoch`: the last one recorded by then. A pool
+    /// without delegations mints one token per SUI.
+    fun exchange_rate_at(self: &StakingPool, epoch: u64): PoolTokenExchangeRate {
+        let i = Vector::length(&self.exchange_rates);
+        while (i > 0) {
+            let rate = Vector::borrow(&self.exchange_rates, i - 1);
+            if (rate.epoch <= epoch) return *rate;
+            i = i - 1;
+        };
+        PoolTokenExchangeRate { epoch, sui_amount: 0, pool_token_amount: 0 }
+    }
+
+    fun sui_to_pool_tokens(rate: &PoolTokenExchangeRate, sui_amount: u64): u64 {
+        if (rate.sui_amount == 0 || rate.pool_token_amount == 0) return sui_amount;
+        // Use u128 to avoid multiplication overflow.
+        (((sui_amount as u128) * (rate.pool_token_amount as u128) / (rate.sui_amount as u128)) as u64)
+    }
+
+    fun pool_tokens_to_sui(rate: &PoolTokenExchangeRate, pool_tokens: u64): u64 {
+        if (rate.sui_amount == 0 || rate.pool_token_amount == 0) return pool_tokens;
+        (((pool_tokens as u128) * (rate.sui_amount as u128) / (rate.pool_token_amount as u128)) as u64)
+    }
+
+    #[test_only]
+    public fun destroy_for_testing(self: StakingPool) {
+        let StakingPool {
+            validator_address: _,
+            sui_balance: _,
+            pool_token_balance: _,
+            rewards_pool,
+            pending_delegation: _,
+            exchange_rates: _,
+        } = self;
+        Balance::destroy_for_testing(rewards_pool);
+    }
+}
@@ -2,16 +2,21 @@
 // SPDX-License-Identifier: Apache-2.0
 
 module Sui::SuiSystem {
+    use Std::Vector;
+
     use Sui::Balance::{Self, Balance};
+    use Sui::Bridge::{Self, BridgeState};
     use Sui::Coin::{Self, Coin, TreasuryCap};
     use Sui::Delegation::{Self, Delegation};
-    use Sui::EpochRewardRecord::{Self, EpochRewardRecord};
+    use Sui::FrameworkUpgrade::{Self, FrameworkUpgradeState};
     use Sui::ID::{Self, VersionedID};
     use Sui::SUI::SUI;
     use Sui::Transfer;
     use Sui::TxContext::{Self, TxContext};
     use Sui::Validator::{Self, Validator};
+    use Sui::ValidatorReports::{Self, ValidatorReports};
     use Sui::ValidatorSet::{Self, ValidatorSet};
+    use Sui::ZkLogin::{Self, ZkLoginState};
 
     friend Sui::Genesis;
 
@@ -42,9 +47,18 @@ module Sui::SuiSystem {
         storage_fund: Balance<SUI>,
         /// A list of system config parameters.
         parameters: SystemParameters,
-        /// The delegation reward pool. All delegation reward goes into this.
-        /// Delegation reward claims withdraw from this.
-        delegation_reward: Balance<SUI>,
+        /// The JWKs and verifying key zkLogin signatures are checked against.
+        zk_login: ZkLoginState,
+        /// The reports of the validators about their peers in the current epoch.
+        validator_reports: ValidatorReports,
+        /// The SUI bridged to other chains, and the transfers claimed from them.
+        bridge: BridgeState,
+        /// The version of the protocol the validators run in the current epoch, which selects
+        /// the gas schedule and the limits of execution.
+        protocol_version: u64,
+        /// The votes of the validators for upgrading the framework packages at the end of
+        /// the epoch.
+        framework_upgrade: FrameworkUpgradeState,
     }
 
     // ==== functions that can only be called by Genesis ====
@@ -69,7 +83,11 @@ module Sui::SuiSystem {
                 min_validator_stake,
                 max_validator_candidate_count,
             },
-            delegation_reward: Balance::zero(),
+            zk_login: ZkLogin::new(),
+            validator_reports: ValidatorReports::new(),
+            bridge: Bridge::new(),
+            protocol_version: 1,
+            framework_upgrade: FrameworkUpgrade::new(),
         };
         Transfer::share_object(state);
     }
@@ -84,6 +102,8 @@ module Sui::SuiSystem {
     public(script) fun request_add_validator(
         self: &mut SuiSystemState,
         pubkey_bytes: vector<u8>,
+        protocol_pubkey_bytes: vector<u8>,
+        protocol_key_proof: vector<u8>,
         name: vector<u8>,
         net_address: vector<u8>,
         stake: Coin<SUI>,
@@ -98,9 +118,13 @@ module Sui::SuiSystem {
             stake_amount >= self.parameters.min_validator_stake,
             0
         );
+        // Only the validators of the genesis may go without a proof of possession.
+        assert!(!Vector::is_empty(&protocol_key_proof), 0);
         let validator = Validator::new(
             TxContext::sender(ctx),
             pubkey_bytes,
+            protocol_pubkey_bytes,
+            protocol_key_proof,
             name,
             net_address,
             Coin::into_balance(stake)
@@ -109,6 +133,31 @@ module Sui::SuiSystem {
         ValidatorSet::request_add_validator(&mut self.validators, validator);
     }
 
+    /// An active or pending validator can request to change its metadata, the way explorers and
+    /// delegators identify it, from the next epoch on.
+    public(script) fun request_update_validator_metadata(
+        self: &mut SuiSystemState,
+        name: vector<u8>,
+        description: vector<u8>,
+        image_url: vector<u8>,
+        project_url: vector<u8>,
+        net_address: vector<u8>,
+        p2p_address: vector<u8>,
+        consensus_address: vector<u8>,
+        ctx: &mut TxContext,
+    ) {
+        let update = Validator::new_metadata_update(
+            name,
+            description,
+            image_url,
+            project_url,
+            net_address,
+            p2p_address,
+            consensus_address,
+        );
+        ValidatorSet::request_update_validator_metadata(&mut self.validators, update, ctx)
+    }
+
     /// A validator can call this function to request a removal in the next epoch.
     /// We use the sender of `ctx` to look up the validator
     /// (i.e. sender must match the sui_address in the validator).
@@ -155,6 +204,26 @@ module Sui::SuiSystem {
         )
     }
 
+    /// An active validator can request to sign with the key of `new_pubkey_bytes`, and the
+    /// protocol key of `new_protocol_pubkey_bytes`, from the next epoch on, keeping its Sui
+    /// address, stake and delegations. The node of the validator loads the new key from the
+    /// `next-key-pair` of its config once the epoch changes.
+    public(script) fun request_rotate_validator_key(
+        self: &mut SuiSystemState,
+        new_pubkey_bytes: vector<u8>,
+        new_protocol_pubkey_bytes: vector<u8>,
+        new_protocol_key_proof: vector<u8>,
+        ctx: &mut TxContext,
+    ) {
+        ValidatorSet::request_rotate_validator_key(
+            &mut self.validators,
+            new_pubkey_bytes,
+            new_protocol_pubkey_bytes,
+            new_protocol_key_proof,
+            ctx,
+        )
+    }
+
     public(script) fun request_add_delegation(
         self: &mut SuiSystemState,
         delegate_stake: Coin<SUI>,
@@ -165,54 +234,193 @@ module Sui::SuiSystem {
         ValidatorSet::request_add_delegation(&mut self.validators, validator_address, amount);
 
         // Delegation starts from the next epoch.
-        let starting_epoch = self.epoch + 1;
-        Delegation::create(starting_epoch, validator_address, delegate_stake, ctx);
+        let activation_epoch = self.epoch + 1;
+        Delegation::create(activation_epoch, validator_address, delegate_stake, ctx);
     }
 
-    public(script) fun request_remove_delegation(
+    /// A delegator can withdraw its `delegation` at any time, and gets back the delegated stake
+    /// along with the rewards it earned in the staking pool of the validator until the current
+    /// epoch. A delegation withdrawn before it activates earned none. The stake of the validator
+    /// decreases at the end of the epoch.
+    public(script) fun withdraw_stake(
         self: &mut SuiSystemState,
-        delegation: &mut Delegation,
+        delegation: Delegation,
         ctx: &mut TxContext,
     ) {
-        ValidatorSet::request_remove_delegation(
+        let rewards = ValidatorSet::withdraw_delegation(
             &mut self.validators,
-            Delegation::validator(delegation),
-            Delegation::delegate_amount(delegation),
+            Delegation::validator(&delegation),
+            Delegation::delegate_amount(&delegation),
+            Delegation::activation_epoch(&delegation),
+            self.epoch,
+        );
+        let stake = Delegation::destroy(delegation);
+        Balance::join(&mut stake, rewards);
+        Transfer::transfer(Coin::from_balance(stake, ctx), TxContext::sender(ctx))
+    }
+
+    /// An active validator can call this function to vote for the JWKs of the
+    /// OpenID providers and the verifying key which zkLogin signatures are
+    /// checked against, given as the parts of each JWK. They replace the
+    /// current ones once validators with a quorum of stake voted for them.
+    public(script) fun vote_zk_login_config(
+        self: &mut SuiSystemState,
+        issuers: vector<vector<u8>>,
+        key_ids: vector<vector<u8>>,
+        moduli: vector<vector<u8>>,
+        verifying_key: vector<u8>,
+        ctx: &mut TxContext,
+    ) {
+        let voter = TxContext::sender(ctx);
+        assert!(ValidatorSet::is_active_validator(&self.validators, voter), 0);
+        ZkLogin::vote(
+            &mut self.zk_login,
+            ZkLogin::jwks_from_parts(issuers, key_ids, moduli),
+            verifying_key,
+            voter,
+            ValidatorSet::validator_total_stake(&self.validators, voter),
+            ValidatorSet::quorum_stake_threshold(&self.validators),
+        )
+    }
+
+    /// An active validator can call this function to report the active validator
+    /// `validator_address` as unresponsive or serving invalid data. A validator
+    /// reported by validators with a quorum of stake gets no reward for the epoch.
+    public(script) fun report_validator(
+        self: &mut SuiSystemState,
+        validator_address: address,
+        ctx: &mut TxContext,
+    ) {
+        let reporter = TxContext::sender(ctx);
+        assert!(
+            ValidatorSet::is_active_validator(&self.validators, reporter)
+                && ValidatorSet::is_active_validator(&self.validators, validator_address),
+            0
+        );
+        ValidatorReports::report(&mut self.validator_reports, reporter, validator_address)
+    }
+
+    /// An active validator can call this function to vote for upgrading the
+    /// framework packages at the end of the epoch to the bytecode of digest
+    /// `digest`, which runs under `protocol_version`. The digest is the one of
+    /// the framework packages of the build the validator runs. The upgrade is
+    /// applied if validators with a quorum of stake voted for it when the epoch
+    /// ends, and validators running a build without that bytecode can't change
+    /// epoch until they run one with it.
+    public(script) fun vote_framework_upgrade(
+        self: &mut SuiSystemState,
+        digest: vector<u8>,
+        protocol_version: u64,
+        ctx: &mut TxContext,
+    ) {
+        let voter = TxContext::sender(ctx);
+        assert!(ValidatorSet::is_active_validator(&self.validators, voter), 0);
+        FrameworkUpgrade::vote(
+            &mut self.framework_upgrade,
+            digest,
+            protocol_version,
+            self.protocol_version,
+            voter,
+            ValidatorSet::validator_total_stake(&self.validators, voter),
+            ValidatorSet::quorum_stake_threshold(&self.validators),
+        )
+    }
+
+    /// A validator can call this function to take back its vote for upgrading
+    /// the framework packages in the current epoch.
+    public(script) fun clear_framework_upgrade_vote(
+        self: &mut SuiSystemState,
+        ctx: &mut TxContext,
+    ) {
+        FrameworkUpgrade::clear_vote(
+            &mut self.framework_upgrade,
+            TxContext::sender(ctx),
+            ValidatorSet::quorum_stake_threshold(&self.validators),
+        )
+    }
+
+    /// A validator can call this function to take back its report about
+    /// `validator_address` in the current epoch.
+    public(script) fun clear_validator_report(
+        self: &mut SuiSystemState,
+        validator_address: address,
+        ctx: &mut TxContext,
+    ) {
+        ValidatorReports::clear_report(
+            &mut self.validator_reports,
+            TxContext::sender(ctx),
+            validator_address,
+        )
+    }
+
+    /// Send `coin` to `recipient` on the chain `target_chain` through the
+    /// bridge. The validators sign the transfer for the target chain once it
+    /// is executed.
+    public(script) fun bridge_lock(
+        self: &mut SuiSystemState,
+        coin: Coin<SUI>,
+        target_chain: u8,
+        recipient: vector<u8>,
+        ctx: &mut TxContext,
+    ) {
+        Bridge::lock(
+            &mut self.bridge,
+            Coin::into_balance(coin),
+            target_chain,
+            recipient,
+            TxContext::sender(ctx),
         );
-        Delegation::undelegate(delegation, self.epoch, ctx)
     }
 
-    // TODO: Once we support passing vector of object references as arguments,
-    // we should support passing a vector of &mut EpochRewardRecord,
-    // which will allow delegators to claim all their reward in one transaction.
-    public(script) fun claim_delegation_reward(
+    /// Claim the transfer of `amount` by `sender` on the chain `source_chain`,
+    /// with `nonce` there, to the caller. `signature` is the aggregate
+    /// signature of the transfer by the active validators `signers`, which
+    /// must have a quorum of stake.
+    public(script) fun claim_bridge_transfer(
         self: &mut SuiSystemState,
-        delegation: &mut Delegation,
-        epoch_reward_record: &mut EpochRewardRecord,
+        source_chain: u8,
+        nonce: u64,
+        sender: vector<u8>,
+        amount: u64,
+        signers: vector<address>,
+        signature: vector<u8>,
         ctx: &mut TxContext,
     ) {
-        let epoch = EpochRewardRecord::epoch(epoch_reward_record);
-        let validator = EpochRewardRecord::validator(epoch_reward_record);
-        assert!(Delegation::can_claim_reward(delegation, epoch, validator), 0);
-        let reward_amount = EpochRewardRecord::claim_reward(
-            epoch_reward_record,
-            Delegation::delegate_amount(delegation),
+        let (signers_stake, public_keys) =
+            ValidatorSet::signers_stake_and_protocol_keys(&self.validators, &signers);
+        let recipient = TxContext::sender(ctx);
+        let balance = Bridge::claim(
+            &mut self.bridge,
+            source_chain,
+            nonce,
+            sender,
+            recipient,
+            amount,
+            signature,
+            public_keys,
+            signers_stake,
+            ValidatorSet::quorum_stake_threshold(&self.validators),
         );
-        let reward = Balance::split(&mut self.delegation_reward, reward_amount);
-        Delegation::claim_reward(delegation, reward, ctx);
+        Transfer::transfer(Coin::from_balance(balance, ctx), recipient)
     }
 
     /// This function should be called at the end of an epoch, and advances the system to the next epoch.
     /// It does the following things:
     /// 1. Add storage charge to the storage fund.
-    /// 2. Distribute computation charge to validator stake and delegation stake.
-    /// 3. Create reward information records for each validator in this epoch.
-    /// 4. Update all validators.
+    /// 2. Distribute computation charge to validator stake and delegation stake,
+    ///    except to the validators reported by validators with a quorum of stake.
+    ///    The delegation reward goes to the staking pools of the validators, raising the
+    ///    exchange rates of their pool tokens; it is paid out as the delegations are withdrawn.
+    /// 3. Update all validators.
+    /// 4. Move to the protocol version of the framework upgrade the validators voted for, if the
+    ///    epoch change upgrades the framework packages to the bytecode of digest `framework_digest`.
+    ///    The digest is empty if it doesn't.
     public(script) fun advance_epoch(
         self: &mut SuiSystemState,
         new_epoch: u64,
         storage_charge: u64,
         computation_charge: u64,
+        framework_digest: vector<u8>,
         ctx: &mut TxContext,
     ) {
         // Validator will make a special system call with sender set as 0x0.
@@ -229,26 +437,39 @@ module Sui::SuiSystem {
         let delegator_reward_amount = delegation_stake * computation_charge / total_stake;
         let delegator_reward = Balance::split(&mut computation_reward, delegator_reward_amount);
         Balance::join(&mut self.storage_fund, storage_reward);
-        Balance::join(&mut self.delegation_reward, delegator_reward);
 
-        ValidatorSet::create_epoch_records(
-            &self.validators,
-            self.epoch,
-            computation_charge,
-            total_stake,
-            ctx,
-        );
+        let reported_validators = validators_reported_by_quorum(self);
 
         self.epoch = self.epoch + 1;
         // Sanity check to make sure we are advancing to the right epoch.
         assert!(new_epoch == self.epoch, 0);
+        // The delegation reward must be distributed before `ValidatorSet::advance_epoch`, with
+        // the delegated stake of the epoch ending.
+        ValidatorSet::distribute_delegation_rewards(
+            &mut self.validators,
+            &mut delegator_reward,
+            &reported_validators,
+            new_epoch,
+        );
+        // What is left of the delegation reward, such as the share of the reported validators,
+        // goes to the storage fund.
+        Balance::join(&mut self.storage_fund, delegator_reward);
         ValidatorSet::advance_epoch(
             &mut self.validators,
             &mut computation_reward,
+            &reported_validators,
             ctx,
         );
+        ZkLogin::advance_epoch(&mut self.zk_login);
+        ValidatorReports::advance_epoch(&mut self.validator_reports);
+        self.protocol_version = FrameworkUpgrade::advance_epoch(
+            &mut self.framework_upgrade,
+            framework_digest,
+            self.protocol_version,
+        );
         // Because of precision issues with integer divisions, we expect that there will be some
-        // remaining balance in `computation_reward`. All of these go to the storage fund.
+        // remaining balance in `computation_reward`. All of these go to the storage fund, along
+        // with the reward of the reported validators.
         Balance::join(&mut self.storage_fund, computation_reward)
     }
 
@@ -258,6 +479,38 @@ module Sui::SuiSystem {
         self.epoch
     }
 
+    /// Return the version of the protocol of the current epoch.
+    public fun protocol_version(self: &SuiSystemState): u64 {
+        self.protocol_version
+    }
+
+    /// The active validators whose reporters in the current epoch have a quorum of stake.
+    /// Every reporter was active when it reported, and validators only leave at the end
+    /// of the epoch, so its stake is known.
+    fun validators_reported_by_quorum(self: &SuiSystemState): vector<address> {
+        let validators = ValidatorSet::active_validator_addresses(&self.validators);
+        let quorum_stake_threshold = ValidatorSet::quorum_stake_threshold(&self.validators);
+        let result = Vector::empty();
+        let length = Vector::length(&validators);
+        let i = 0;
+        while (i < length) {
+            let validator = *Vector::borrow(&validators, i);
+            let reporters = ValidatorReports::reporters(&self.validator_reports, validator);
+            let stake = 0;
+            let j = 0;
+            while (j < Vector::length(&reporters)) {
+                let reporter = *Vector::borrow(&reporters, j);
+                stake = stake + ValidatorSet::validator_total_stake(&self.validators, reporter);
+                j = j + 1;
+            };
+            if (!Vector::is_empty(&reporters) && stake >= quorum_stake_threshold) {
+                Vector::push_back(&mut result, validator);
+            };
+            i = i + 1;
+        };
+        result
+    }
+
     #[test_only]
     public fun set_epoch_for_testing(self: &mut SuiSystemState, epoch_num: u64) {
         self.epoch = epoch_num
@@ -28,10 +28,27 @@ module Sui::Validator {
         /// The public key bytes corresponding to the private key that the validator
         /// holds to sign transactions. For now, this is the same as AuthorityName.
         pubkey_bytes: vector<u8>,
+        /// The BLS12-381 public key with which the validator signs protocol messages, such as its
+        /// votes on transactions and checkpoints.
+        protocol_pubkey_bytes: vector<u8>,
+        /// The signature of `protocol_pubkey_bytes` by its own key, proving that the validator
+        /// holds its secret. Validators check it before admitting the key to a committee. It is
+        /// empty for the validators of the genesis, whose keys the genesis ceremony checks.
+        protocol_key_proof: vector<u8>,
         /// A unique human-readable name of this validator.
         name: vector<u8>,
         /// The network address of the validator (could also contain extra info such as port, DNS and etc.).
         net_address: vector<u8>,
+        /// A UTF-8 description of the operator of this validator.
+        description: vector<u8>,
+        /// The URL of an image, such as a logo, identifying the operator.
+        image_url: vector<u8>,
+        /// The URL of the website of the operator.
+        project_url: vector<u8>,
+        /// The network address on which the validator talks to the other validators and full nodes.
+        p2p_address: vector<u8>,
+        /// The network address of the consensus of the validator.
+        consensus_address: vector<u8>,
         /// Total amount of validator stake that would be active in the next epoch.
         /// This only includes validator stake, and does not include delegation.
         next_epoch_stake: u64,
@@ -61,18 +78,58 @@ module Sui::Validator {
         pending_delegator_count: u64,
         /// Number of delegators that will withdraw stake at the end of the epoch.
         pending_delegator_withdraw_count: u64,
+        /// The public key bytes the validator rotates to in the next epoch, if any. The validator
+        /// keeps its Sui address, stake and delegations across the rotation.
+        next_epoch_pubkey_bytes: Option<vector<u8>>,
+        /// The protocol key the validator rotates to in the next epoch, along with its proof of
+        /// possession. It is set whenever `next_epoch_pubkey_bytes` is.
+        next_epoch_protocol_pubkey_bytes: Option<vector<u8>>,
+        next_epoch_protocol_key_proof: Option<vector<u8>>,
+        /// The metadata the validator goes by from the next epoch on, if it requested a change.
+        next_epoch_metadata_update: Option<ValidatorMetadataUpdate>,
     }
 
+    /// The metadata of a validator its operator can change, as a whole, from one epoch to the next.
+    struct ValidatorMetadataUpdate has store, drop, copy {
+        name: vector<u8>,
+        description: vector<u8>,
+        image_url: vector<u8>,
+        project_url: vector<u8>,
+        net_address: vector<u8>,
+        p2p_address: vector<u8>,
+        consensus_address: vector<u8>,
+    }
+
+    /// The length of a compressed BLS12-381 public key.
+    const PROTOCOL_PUBKEY_LENGTH: u64 = 96;
+
+    /// The length of a compressed BLS12-381 signature, such as a proof of possession.
+    const PROTOCOL_KEY_PROOF_LENGTH: u64 = 48;
+
+    // TODO: These constants are arbitrary, will adjust once we know more.
+    const MAX_NAME_LENGTH: u64 = 50;
+    const MAX_NET_ADDRESS_LENGTH: u64 = 100;
+    const MAX_DESCRIPTION_LENGTH: u64 = 500;
+    const MAX_URL_LENGTH: u64 = 200;
+
     public(friend) fun new(
         sui_address: address,
         pubkey_bytes: vector<u8>,
+        protocol_pubkey_bytes: vector<u8>,
+        protocol_key_proof: vector<u8>,
         name: vector<u8>,
         net_address: vector<u8>,
         stake: Balance<SUI>,
     ): Validator {
         assert!(
-            // TODO: These constants are arbitrary, will adjust once we know more.
-            Vector::length(&net_address) <= 100 && Vector::length(&name) <= 50 && Vector::length(&pubkey_bytes) <= 128,
+            Vector::length(&net_address) <= MAX_NET_ADDRESS_LENGTH
+                && Vector::length(&name) <= MAX_NAME_LENGTH
+                && Vector::length(&pubkey_bytes) <= 128,
+            0
+        );
+        assert!(
+            Vector::length(&protocol_pubkey_bytes) == PROTOCOL_PUBKEY_LENGTH
+                && Vector::length(&protocol_key_proof) <= PROTOCOL_KEY_PROOF_LENGTH,
             0
         );
         // Check that the name is human-readable.
@@ -81,8 +138,15 @@ module Sui::Validator {
             metadata: ValidatorMetadata {
                 sui_address,
                 pubkey_bytes,
+                protocol_pubkey_bytes,
+                protocol_key_proof,
                 name,
                 net_address,
+                description: vector[],
+                image_url: vector[],
+                project_url: vector[],
+                p2p_address: vector[],
+                consensus_address: vector[],
                 next_epoch_stake: Balance::value(&stake),
             },
             stake,
@@ -94,6 +158,10 @@ module Sui::Validator {
             delegator_count: 0,
             pending_delegator_count: 0,
             pending_delegator_withdraw_count: 0,
+            next_epoch_pubkey_bytes: Option::none(),
+            next_epoch_protocol_pubkey_bytes: Option::none(),
+            next_epoch_protocol_key_proof: Option::none(),
+            next_epoch_metadata_update: Option::none(),
         }
     }
 
@@ -109,6 +177,10 @@ module Sui::Validator {
             delegator_count: _,
             pending_delegator_count: _,
             pending_delegator_withdraw_count: _,
+            next_epoch_pubkey_bytes: _,
+            next_epoch_protocol_pubkey_bytes: _,
+            next_epoch_protocol_key_proof: _,
+            next_epoch_metadata_update: _,
         } = self;
 
         assert!(pending_withdraw == 0, 0);
@@ -186,14 +258,177 @@ module Sui::Validator {
         self.pending_delegator_withdraw_count = self.pending_delegator_withdraw_count + 1;
     }
 
+    /// Request to sign with the key of `new_pubkey_bytes`, and the protocol key of
+    /// `new_protocol_pubkey_bytes`, from the next epoch on. A later request in the same epoch
+    /// replaces this one.
+    public(friend) fun request_rotate_key(
+        self: &mut Validator,
+        new_pubkey_bytes: vector<u8>,
+        new_protocol_pubkey_bytes: vector<u8>,
+        new_protocol_key_proof: vector<u8>,
+    ) {
+        assert!(Vector::length(&new_pubkey_bytes) <= 128, 0);
+        assert!(new_pubkey_bytes != self.metadata.pubkey_bytes, 0);
+        assert!(
+            Vector::length(&new_protocol_pubkey_bytes) == PROTOCOL_PUBKEY_LENGTH
+                && Vector::length(&new_protocol_key_proof) == PROTOCOL_KEY_PROOF_LENGTH,
+            0
+        );
+        self.next_epoch_pubkey_bytes = Option::some(new_pubkey_bytes);
+        self.next_epoch_protocol_pubkey_bytes = Option::some(new_protocol_pubkey_bytes);
+        self.next_epoch_protocol_key_proof = Option::some(new_protocol_key_proof);
+    }
+
+    /// Process the pending key rotation, if any.
+    public(friend) fun rotate_key(self: &mut Validator) {
+        if (Option::is_some(&self.next_epoch_pubkey_bytes)) {
+            self.metadata.pubkey_bytes = Option::extract(&mut self.next_epoch_pubkey_bytes);
+            self.metadata.protocol_pubkey_bytes = Option::extract(&mut self.next_epoch_protocol_pubkey_bytes);
+            self.metadata.protocol_key_proof = Option::extract(&mut self.next_epoch_protocol_key_proof);
+        };
+    }
+
+    /// Check the metadata of an update: the name and the URLs must be ASCII. The description is
+    /// meant to be UTF-8, which is not checked.
+    public(friend) fun new_metadata_update(
+        name: vector<u8>,
+        description: vector<u8>,
+        image_url: vector<u8>,
+        project_url: vector<u8>,
+        net_address: vector<u8>,
+        p2p_address: vector<u8>,
+        consensus_address: vector<u8>,
+    ): ValidatorMetadataUpdate {
+        assert!(
+            Vector::length(&name) <= MAX_NAME_LENGTH
+                && Vector::length(&description) <= MAX_DESCRIPTION_LENGTH
+                && Vector::length(&image_url) <= MAX_URL_LENGTH
+                && Vector::length(&project_url) <= MAX_URL_LENGTH
+                && Vector::length(&net_address) <= MAX_NET_ADDRESS_LENGTH
+                && Vector::length(&p2p_address) <= MAX_NET_ADDRESS_LENGTH
+                && Vector::length(&consensus_address) <= MAX_NET_ADDRESS_LENGTH,
+            0
+        );
+        ASCII::string(copy name);
+        ASCII::string(copy image_url);
+        ASCII::string(copy project_url);
+        ValidatorMetadataUpdate {
+            name,
+            description,
+            image_url,
+            project_url,
+            net_address,
+            p2p_address,
+            consensus_address,
+        }
+    }
+
+    /// Request to go by the metadata of `update` from the next epoch on. A later request in the
+    /// same epoch replaces this one.
+    public(friend) fun request_update_metadata(self: &mut Validator, update: ValidatorMetadataUpdate) {
+        self.next_epoch_metadata_update = Option::some(update);
+    }
+
+    /// Process the pending metadata update, if any.
+    public(friend) fun update_metadata(self: &mut Validator) {
+        if (Option::is_some(&self.next_epoch_metadata_update)) {
+            let update = Option::extract(&mut self.next_epoch_metadata_update);
+            apply_metadata_update(&mut self.metadata, update);
+        };
+    }
+
+    fun apply_metadata_update(metadata: &mut ValidatorMetadata, update: ValidatorMetadataUpdate) {
+        let ValidatorMetadataUpdate {
+            name,
+            description,
+            image_url,
+            project_url,
+            net_address,
+            p2p_address,
+            consensus_address,
+        } = update;
+        metadata.name = name;
+        metadata.description = description;
+        metadata.image_url = image_url;
+        metadata.project_url = project_url;
+        metadata.net_address = net_address;
+        metadata.p2p_address = p2p_address;
+        metadata.consensus_address = consensus_address;
+    }
+
+    /// The metadata of the validator in the next epoch, with its pending key rotation and
+    /// metadata update.
+    public fun next_epoch_metadata(self: &Validator): ValidatorMetadata {
+        let metadata = self.metadata;
+        if (Option::is_some(&self.next_epoch_pubkey_bytes)) {
+            metadata.pubkey_bytes = *Option::borrow(&self.next_epoch_pubkey_bytes);
+            metadata.protocol_pubkey_bytes = *Option::borrow(&self.next_epoch_protocol_pubkey_bytes);
+            metadata.protocol_key_proof = *Option::borrow(&self.next_epoch_protocol_key_proof);
+        };
+        if (Option::is_some(&self.next_epoch_metadata_update)) {
+            apply_metadata_update(&mut metadata, *Option::borrow(&self.next_epoch_metadata_update));
+        };
+        metadata
+    }
+
     public fun metadata(self: &Validator): &ValidatorMetadata {
         &self.metadata
     }
 
+    public fun metadata_pubkey_bytes(metadata: &ValidatorMetadata): vector<u8> {
+        metadata.pubkey_bytes
+    }
+
+    public fun metadata_protocol_pubkey_bytes(metadata: &ValidatorMetadata): vector<u8> {
+        metadata.protocol_pubkey_bytes
+    }
+
+    public fun metadata_name(metadata: &ValidatorMetadata): &vector<u8> {
+        &metadata.name
+    }
+
+    public fun metadata_description(metadata: &ValidatorMetadata): &vector<u8> {
+        &metadata.description
+    }
+
+    public fun metadata_image_url(metadata: &ValidatorMetadata): &vector<u8> {
+        &metadata.image_url
+    }
+
+    public fun metadata_project_url(metadata: &ValidatorMetadata): &vector<u8> {
+        &metadata.project_url
+    }
+
+    public fun metadata_net_address(metadata: &ValidatorMetadata): &vector<u8> {
+        &metadata.net_address
+    }
+
+    public fun metadata_p2p_address(metadata: &ValidatorMetadata): &vector<u8> {
+        &metadata.p2p_address
+    }
+
+    public fun metadata_consensus_address(metadata: &ValidatorMetadata): &vector<u8> {
+        &metadata.consensus_address
+    }
+
     public fun sui_address(self: &Validator): address {
         self.metadata.sui_address
     }
 
+    public fun pubkey_bytes(self: &Validator): &vector<u8> {
+        &self.metadata.pubkey_bytes
+    }
+
+    public fun next_epoch_pubkey_bytes(self: &Validator): &Option<vector<u8>> {
+        &self.next_epoch_pubkey_bytes
+    }
+
+    /// Whether the validator signs or will sign with the key of `pubkey_bytes`.
+    public fun uses_pubkey(self: &Validator, pubkey_bytes: &vector<u8>): bool {
+        &self.metadata.pubkey_bytes == pubkey_bytes
+            || Option::contains(&self.next_epoch_pubkey_bytes, pubkey_bytes)
+    }
+
     public fun stake_amount(self: &Validator): u64 {
         Balance::value(&self.stake)
     }
@@ -223,4 +458,13 @@ module Sui::Validator {
             || self.metadata.name == other.metadata.name
             || self.metadata.net_address == other.metadata.net_address
     }
+
+    /// Whether the validator goes or will go by the name or the network address of `update`.
+    public fun conflicts_with_update(self: &Validator, update: &ValidatorMetadataUpdate): bool {
+        let metadata = next_epoch_metadata(self);
+        self.metadata.name == update.name
+            || self.metadata.net_address == update.net_address
+            || metadata.name == update.name
+            || metadata.net_address == update.net_address
+    }
 }
@@ -0,0 +1,94 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+/// The reports validators make about peers that are unresponsive or serve
+/// invalid data. A validator reported during an epoch by validators with a
+/// quorum of stake gets no reward for the epoch. Reports only hold for the
+/// epoch they are made in, and a validator can clear its own reports, for
+/// example once the peer it reported recovers.
+module Sui::ValidatorReports {
+    use Std::Vector;
+
+    friend Sui::SuiSystem;
+
+    #[test_only]
+    friend Sui::ValidatorReportsTests;
+
+    /// A validator can't report itself, nor clear a report it didn't make.
+    const EInvalidReport: u64 = 0;
+
+    /// The validators which reported `reported` in the current epoch.
+    struct Report has store, drop {
+        reported: address,
+        reporters: vector<address>,
+    }
+
+    struct ValidatorReports has store {
+        /// The reports of the current epoch, one for each reported validator.
+        reports: vector<Report>,
+    }
+
+    public(friend) fun new(): ValidatorReports {
+        ValidatorReports { reports: Vector::empty() }
+    }
+
+    /// Record that `reporter` reported `reported`. Reporting the same
+    /// validator twice in an epoch counts once.
+    public(friend) fun report(self: &mut ValidatorReports, reporter: address, reported: address) {
+        assert!(reporter != reported, EInvalidReport);
+        let index = find_report(&self.reports, reported);
+        if (index == Vector::length(&self.reports)) {
+            Vector::push_back(&mut self.reports, Report { reported, reporters: Vector::empty() });
+        };
+        let report = Vector::borrow_mut(&mut self.reports, index);
+        if (!Vector::contains(&report.reporters, &reporter)) {
+            Vector::push_back(&mut report.reporters, reporter);
+        }
+    }
+
+    /// Take back the report of `reporter` about `reported`.
+    public(friend) fun clear_report(self: &mut ValidatorReports, reporter: address, reported: address) {
+        let index = find_report(&self.reports, reported);
+        assert!(index < Vector::length(&self.reports), EInvalidReport);
+        let report = Vector::borrow_mut(&mut self.reports, index);
+        let (found, reporter_index) = Vector::index_of(&report.reporters, &reporter);
+        assert!(found, EInvalidReport);
+        Vector::remove(&mut report.reporters, reporter_index);
+        if (Vector::is_empty(&report.reporters)) {
+            Vector::remove(&mut self.reports, index);
+        }
+    }
+
+    /// Drop the reports of the epoch, once they have been tallied.
+    public(friend) fun advance_epoch(self: &mut ValidatorReports) {
+        self.reports = Vector::empty();
+    }
+
+    /// The validators which reported `reported` in the current epoch.
+    public fun reporters(self: &ValidatorReports, reported: address): vector<address> {
+        let index = find_report(&self.reports, reported);
+        if (index == Vector::length(&self.reports)) {
+            return Vector::empty()
+        };
+        Vector::borrow(&self.reports, index).reporters
+    }
+
+    /// The index of the report about `reported`, or the number of reports if
+    /// there is none.
+    fun find_report(reports: &vector<Report>, reported: address): u64 {
+        let length = Vector::length(reports);
+        let i = 0;
+        while (i < length) {
+            if (Vector::borrow(reports, i).reported == reported) {
+                return i
+            };
+            i = i + 1;
+        };
+        length
+    }
+
+    #[test_only]
+    public fun destroy_for_testing(self: ValidatorReports) {
+        let ValidatorReports { reports: _ } = self;
+    }
+}
@@ -6,10 +6,10 @@ module Sui::ValidatorSet {
     use Std::Vector;
 
     use Sui::Balance::{Self, Balance};
-    use Sui::EpochRewardRecord;
     use Sui::SUI::SUI;
+    use Sui::StakingPool::{Self, StakingPool};
     use Sui::TxContext::{Self, TxContext};
-    use Sui::Validator::{Self, Validator, ValidatorMetadata};
+    use Sui::Validator::{Self, Validator, ValidatorMetadata, ValidatorMetadataUpdate};
 
     friend Sui::SuiSystem;
 
@@ -43,6 +43,11 @@ module Sui::ValidatorSet {
         /// The metadata of the validator set for the next epoch. This is kept up-to-dated.
         /// Everytime a change request is received, this set is updated.
         next_epoch_validators: vector<ValidatorMetadata>,
+
+        /// The staking pool of each validator, active, pending or gone, in which the
+        /// delegations to it earn their rewards. The pools of the validators which left are
+        /// kept for their delegators to withdraw the rewards they earned.
+        staking_pools: vector<StakingPool>,
     }
 
     public(friend) fun new(init_active_validators: vector<Validator>): ValidatorSet {
@@ -55,6 +60,13 @@ module Sui::ValidatorSet {
             pending_validators: Vector::empty(),
             pending_removals: Vector::empty(),
             next_epoch_validators: Vector::empty(),
+            staking_pools: Vector::empty(),
+        };
+        let i = 0;
+        while (i < Vector::length(&validators.active_validators)) {
+            let validator_address = Validator::sui_address(Vector::borrow(&validators.active_validators, i));
+            add_staking_pool(&mut validators.staking_pools, validator_address);
+            i = i + 1;
         };
         validators.next_epoch_validators = derive_next_epoch_validators(&validators);
         validators
@@ -75,6 +87,7 @@ module Sui::ValidatorSet {
                 && !contains_duplicate_validator(&self.pending_validators, &validator),
             0
         );
+        add_staking_pool(&mut self.staking_pools, Validator::sui_address(&validator));
         Vector::push_back(&mut self.pending_validators, validator);
     }
 
@@ -125,6 +138,54 @@ module Sui::ValidatorSet {
         Validator::request_withdraw_stake(validator, withdraw_amount, min_validator_stake);
     }
 
+    /// Called by `SuiSystem`, to rotate the key of a validator in the next epoch.
+    /// No other validator may use the new key, now or in the next epoch.
+    public(friend) fun request_rotate_validator_key(
+        self: &mut ValidatorSet,
+        new_pubkey_bytes: vector<u8>,
+        new_protocol_pubkey_bytes: vector<u8>,
+        new_protocol_key_proof: vector<u8>,
+        ctx: &TxContext,
+    ) {
+        let validator_address = TxContext::sender(ctx);
+        assert!(
+            !contains_pubkey(&self.active_validators, &new_pubkey_bytes)
+                && !contains_pubkey(&self.pending_validators, &new_pubkey_bytes),
+            0
+        );
+        let validator = get_validator_mut(&mut self.active_validators, validator_address);
+        Validator::request_rotate_key(
+            validator,
+            new_pubkey_bytes,
+            new_protocol_pubkey_bytes,
+            new_protocol_key_proof,
+        );
+        self.next_epoch_validators = derive_next_epoch_validators(self);
+    }
+
+    /// Called by `SuiSystem`, to change the metadata of an active or pending validator from the
+    /// next epoch on. No other validator may go by the new name or network address, now or in
+    /// the next epoch.
+    public(friend) fun request_update_validator_metadata(
+        self: &mut ValidatorSet,
+        update: ValidatorMetadataUpdate,
+        ctx: &TxContext,
+    ) {
+        let validator_address = TxContext::sender(ctx);
+        assert!(
+            !conflicts_with_update(&self.active_validators, validator_address, &update)
+                && !conflicts_with_update(&self.pending_validators, validator_address, &update),
+            0
+        );
+        let validator = if (Option::is_some(&find_validator(&self.active_validators, validator_address))) {
+            get_validator_mut(&mut self.active_validators, validator_address)
+        } else {
+            get_validator_mut(&mut self.pending_validators, validator_address)
+        };
+        Validator::request_update_metadata(validator, update);
+        self.next_epoch_validators = derive_next_epoch_validators(self);
+    }
+
     public(friend) fun is_active_validator(
         self: &ValidatorSet,
         validator_address: address,
@@ -132,6 +193,52 @@ module Sui::ValidatorSet {
         Option::is_some(&find_validator(&self.active_validators, validator_address))
     }
 
+    /// The stake of the active validator `validator_address` and of its
+    /// delegators, at the beginning of the epoch.
+    public(friend) fun validator_total_stake(
+        self: &ValidatorSet,
+        validator_address: address,
+    ): u64 {
+        let index = find_validator(&self.active_validators, validator_address);
+        assert!(Option::is_some(&index), 0);
+        let validator = Vector::borrow(&self.active_validators, Option::extract(&mut index));
+        Validator::stake_amount(validator) + Validator::delegate_amount(validator)
+    }
+
+    public(friend) fun quorum_stake_threshold(self: &ValidatorSet): u64 {
+        self.quorum_stake_threshold
+    }
+
+    /// The total stake of the active validators `signers`, which must be
+    /// distinct, and their protocol keys, in the same order.
+    public(friend) fun signers_stake_and_protocol_keys(
+        self: &ValidatorSet,
+        signers: &vector<address>,
+    ): (u64, vector<vector<u8>>) {
+        let stake = 0;
+        let keys = Vector::empty();
+        let length = Vector::length(signers);
+        let i = 0;
+        while (i < length) {
+            let signer_address = *Vector::borrow(signers, i);
+            let index = find_validator(&self.active_validators, signer_address);
+            assert!(Option::is_some(&index), 0);
+            let j = i + 1;
+            while (j < length) {
+                assert!(*Vector::borrow(signers, j) != signer_address, 0);
+                j = j + 1;
+            };
+            let validator = Vector::borrow(&self.active_validators, Option::extract(&mut index));
+            stake = stake + Validator::stake_amount(validator) + Validator::delegate_amount(validator);
+            Vector::push_back(
+                &mut keys,
+                Validator::metadata_protocol_pubkey_bytes(Validator::metadata(validator)),
+            );
+            i = i + 1;
+        };
+        (stake, keys)
+    }
+
     public(friend) fun request_add_delegation(
         self: &mut ValidatorSet,
         validator_address: address,
@@ -139,9 +246,32 @@ module Sui::ValidatorSet {
     ) {
         let validator = get_validator_mut(&mut self.active_validators, validator_address);
         Validator::request_add_delegation(validator, delegate_amount);
+        StakingPool::request_add_delegation(
+            get_staking_pool_mut(&mut self.staking_pools, validator_address),
+            delegate_amount,
+        );
+    }
+
+    /// Withdraw the delegation of `delegate_amount` to `validator_address`, activated in
+    /// `activation_epoch`, from its staking pool, and return the rewards it earned until
+    /// `current_epoch`.
+    public(friend) fun withdraw_delegation(
+        self: &mut ValidatorSet,
+        validator_address: address,
+        delegate_amount: u64,
+        activation_epoch: u64,
+        current_epoch: u64,
+    ): Balance<SUI> {
+        request_remove_delegation(self, validator_address, delegate_amount);
+        StakingPool::withdraw_rewards(
+            get_staking_pool_mut(&mut self.staking_pools, validator_address),
+            delegate_amount,
+            activation_epoch,
+            current_epoch,
+        )
     }
 
-    public(friend) fun request_remove_delegation(
+    fun request_remove_delegation(
         self: &mut ValidatorSet,
         validator_address: address,
         delegate_amount: u64,
@@ -156,38 +286,53 @@ module Sui::ValidatorSet {
         }
     }
 
-    public(friend) fun create_epoch_records(
-        self: &ValidatorSet,
-        epoch: u64,
-        computation_charge: u64,
-        total_stake: u64,
-        ctx: &mut TxContext,
+    /// Distribute `reward`, the reward of the delegations in the epoch ending, to the staking
+    /// pools of the active validators in proportion to their delegated stake, except to the
+    /// `reported_validators`, then activate the pending delegations of every pool for
+    /// `new_epoch`. What is left of `reward` goes back to the caller.
+    public(friend) fun distribute_delegation_rewards(
+        self: &mut ValidatorSet,
+        reward: &mut Balance<SUI>,
+        reported_validators: &vector<address>,
+        new_epoch: u64,
     ) {
+        let total_reward = (Balance::value(reward) as u128);
+        let delegation_stake = (self.delegation_stake as u128);
         let length = Vector::length(&self.active_validators);
         let i = 0;
         while (i < length) {
-            let v = Vector::borrow(&self.active_validators, i);
-            EpochRewardRecord::create(
-                epoch,
-                computation_charge,
-                total_stake,
-                Validator::delegator_count(v),
-                Validator::sui_address(v),
-                ctx,
-            );
+            let validator = Vector::borrow(&self.active_validators, i);
+            let validator_address = Validator::sui_address(validator);
+            // Use u128 to avoid multiplication overflow.
+            let delegate_amount = (Validator::delegate_amount(validator) as u128);
+            let pool = get_staking_pool_mut(&mut self.staking_pools, validator_address);
+            // A pool whose delegations were all withdrawn has no one to earn the reward.
+            if (!Vector::contains(reported_validators, &validator_address)
+                && StakingPool::pool_token_balance(pool) > 0
+                && delegation_stake > 0) {
+                let reward_amount = delegate_amount * total_reward / delegation_stake;
+                StakingPool::deposit_rewards(pool, Balance::split(reward, (reward_amount as u64)));
+            };
+            i = i + 1;
+        };
+        let length = Vector::length(&self.staking_pools);
+        let i = 0;
+        while (i < length) {
+            StakingPool::process_pending_delegations(Vector::borrow_mut(&mut self.staking_pools, i), new_epoch);
             i = i + 1;
         }
     }
 
     /// Update the validator set at the end of epoch.
     /// It does the following things:
-    ///   1. Distribute stake award.
+    ///   1. Distribute stake award, except to the `reported_validators`.
     ///   2. Process pending stake deposits and withdraws for each validator (`adjust_stake`).
     ///   3. Process pending validator application and withdraws.
     ///   4. At the end, we calculate the total stake for the new epoch.
     public(friend) fun advance_epoch(
         self: &mut ValidatorSet,
         computation_reward: &mut Balance<SUI>,
+        reported_validators: &vector<address>,
         ctx: &mut TxContext,
     ) {
         // `compute_reward_distribution` must be called before `adjust_stake` to make sure we are using the current
@@ -196,16 +341,23 @@ module Sui::ValidatorSet {
             &self.active_validators,
             self.validator_stake,
             Balance::value(computation_reward),
+            reported_validators,
         );
 
         // `adjust_stake` must be called before `distribute_reward`, because reward distribution goes to
         // each validator's pending stake, and that shouldn't be available in the next epoch.
         adjust_stake(&mut self.active_validators, ctx);
 
+        rotate_keys(&mut self.active_validators);
+
         distribute_reward(&mut self.active_validators, &rewards, computation_reward);
 
         process_pending_validators(&mut self.active_validators, &mut self.pending_validators);
 
+        // After `process_pending_validators`, for the new validators to go by their updated
+        // metadata too.
+        update_metadata(&mut self.active_validators);
+
         process_pending_removals(&mut self.active_validators, &mut self.pending_removals, ctx);
 
         self.next_epoch_validators = derive_next_epoch_validators(self);
@@ -216,6 +368,22 @@ module Sui::ValidatorSet {
         self.quorum_stake_threshold = quorum_stake_threshold;
     }
 
+    public fun next_epoch_validators(self: &ValidatorSet): &vector<ValidatorMetadata> {
+        &self.next_epoch_validators
+    }
+
+    /// The addresses of the active validators.
+    public fun active_validator_addresses(self: &ValidatorSet): vector<address> {
+        let result = Vector::empty();
+        let length = Vector::length(&self.active_validators);
+        let i = 0;
+        while (i < length) {
+            Vector::push_back(&mut result, Validator::sui_address(Vector::borrow(&self.active_validators, i)));
+            i = i + 1;
+        };
+        result
+    }
+
     public fun validator_stake(self: &ValidatorSet): u64 {
         self.validator_stake
     }
@@ -224,6 +392,13 @@ module Sui::ValidatorSet {
         self.delegation_stake
     }
 
+    /// The staking pool of the delegations to `validator_address`.
+    public fun staking_pool(self: &ValidatorSet, validator_address: address): &StakingPool {
+        let i = find_staking_pool(&self.staking_pools, validator_address);
+        assert!(Option::is_some(&i), 0);
+        Vector::borrow(&self.staking_pools, Option::extract(&mut i))
+    }
+
     /// Checks whether a duplicate of `new_validator` is already in `validators`.
     /// Two validators duplicate if they share the same sui_address or same IP or same name.
     fun contains_duplicate_validator(validators: &vector<Validator>, new_validator: &Validator): bool {
@@ -239,6 +414,38 @@ module Sui::ValidatorSet {
         false
     }
 
+    /// Checks whether a validator in `validators`, other than the one of `validator_address`,
+    /// goes or will go by the name or the network address of `update`.
+    fun conflicts_with_update(
+        validators: &vector<Validator>,
+        validator_address: address,
+        update: &ValidatorMetadataUpdate,
+    ): bool {
+        let len = Vector::length(validators);
+        let i = 0;
+        while (i < len) {
+            let v = Vector::borrow(validators, i);
+            if (Validator::sui_address(v) != validator_address && Validator::conflicts_with_update(v, update)) {
+                return true
+            };
+            i = i + 1;
+        };
+        false
+    }
+
+    /// Checks whether a validator in `validators` uses or will use the key of `pubkey_bytes`.
+    fun contains_pubkey(validators: &vector<Validator>, pubkey_bytes: &vector<u8>): bool {
+        let len = Vector::length(validators);
+        let i = 0;
+        while (i < len) {
+            if (Validator::uses_pubkey(Vector::borrow(validators, i), pubkey_bytes)) {
+                return true
+            };
+            i = i + 1;
+        };
+        false
+    }
+
     /// Find validator by `validator_address`, in `validators`.
     /// Returns (true, index) if the validator is found, and the index is its index in the list.
     /// If not found, returns (false, 0).
@@ -255,6 +462,32 @@ module Sui::ValidatorSet {
         Option::none()
     }
 
+    fun find_staking_pool(pools: &vector<StakingPool>, validator_address: address): Option<u64> {
+        let length = Vector::length(pools);
+        let i = 0;
+        while (i < length) {
+            if (StakingPool::validator_address(Vector::borrow(pools, i)) == validator_address) {
+                return Option::some(i)
+            };
+            i = i + 1;
+        };
+        Option::none()
+    }
+
+    fun get_staking_pool_mut(pools: &mut vector<StakingPool>, validator_address: address): &mut StakingPool {
+        let i = find_staking_pool(pools, validator_address);
+        assert!(Option::is_some(&i), 0);
+        Vector::borrow_mut(pools, Option::extract(&mut i))
+    }
+
+    /// Add a staking pool for `validator_address`, unless it already has one from an earlier
+    /// time it was a validator.
+    fun add_staking_pool(pools: &mut vector<StakingPool>, validator_address: address) {
+        if (Option::is_none(&find_staking_pool(pools, validator_address))) {
+            Vector::push_back(pools, StakingPool::new(validator_address));
+        }
+    }
+
     fun get_validator_mut(
         validators: &mut vector<Validator>,
         validator_address: address,
@@ -332,6 +565,27 @@ module Sui::ValidatorSet {
         (threshold as u8)
     }
 
+    /// Process the pending key rotation of each validator.
+    fun rotate_keys(validators: &mut vector<Validator>) {
+        let length = Vector::length(validators);
+        let i = 0;
+        while (i < length) {
+            let validator = Vector::borrow_mut(validators, i);
+            Validator::rotate_key(validator);
+            i = i + 1;
+        }
+    }
+
+    fun update_metadata(validators: &mut vector<Validator>) {
+        let length = Vector::length(validators);
+        let i = 0;
+        while (i < length) {
+            let validator = Vector::borrow_mut(validators, i);
+            Validator::update_metadata(validator);
+            i = i + 1;
+        }
+    }
+
     /// Process the pending stake changes for each validator.
     fun adjust_stake(validators: &mut vector<Validator>, ctx: &mut TxContext) {
         let length = Vector::length(validators);
@@ -347,16 +601,23 @@ module Sui::ValidatorSet {
     /// calculate the amount of reward each validator should get.
     /// Returns the amount of reward for each validator, as well as a remaining reward
     /// due to integer division loss.
+    /// The `reported_validators` get no reward, and their share stays in the remaining reward.
     fun compute_reward_distribution(
         validators: &vector<Validator>,
         total_stake: u64,
         total_reward: u64,
+        reported_validators: &vector<address>,
     ): vector<u64> {
         let results = Vector::empty();
         let length = Vector::length(validators);
         let i = 0;
         while (i < length) {
             let validator = Vector::borrow(validators, i);
+            if (Vector::contains(reported_validators, &Validator::sui_address(validator))) {
+                Vector::push_back(&mut results, 0);
+                i = i + 1;
+                continue
+            };
             // Integer divisions will truncate the results. Because of this, we expect that at the end
             // there will be some reward remaining in `total_reward`.
             // Use u128 to avoid multiplication overflow.
@@ -398,10 +659,10 @@ module Sui::ValidatorSet {
                     continue
                 };
             };
-            let metadata = Validator::metadata(
+            let metadata = Validator::next_epoch_metadata(
                 Vector::borrow(&self.active_validators, active_count - 1),
             );
-            Vector::push_back(&mut result, *metadata);
+            Vector::push_back(&mut result, metadata);
             active_count = active_count - 1;
         };
         result
@@ -420,6 +681,7 @@ module Sui::ValidatorSet {
             pending_validators,
             pending_removals: _,
             next_epoch_validators: _,
+            staking_pools,
         } = self;
         while (!Vector::is_empty(&active_validators)) {
             let v = Vector::pop_back(&mut active_validators);
@@ -427,5 +689,9 @@ module Sui::ValidatorSet {
         };
         Vector::destroy_empty(active_validators);
         Vector::destroy_empty(pending_validators);
+        while (!Vector::is_empty(&staking_pools)) {
+            StakingPool::destroy_for_testing(Vector::pop_back(&mut staking_pools));
+        };
+        Vector::destroy_empty(staking_pools);
     }
 }
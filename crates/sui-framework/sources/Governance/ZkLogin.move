@@ -0,0 +1,182 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+/// The configuration validators check zkLogin signatures against: the JWKs
+/// which OpenID providers sign their JWTs with, and the Groth16 verifying key
+/// of the circuit proving that a JWT signed by one of them commits to an
+/// ephemeral key. Providers rotate their keys, so the configuration is
+/// replaced whenever validators with a quorum of stake vote for the same one.
+module Sui::ZkLogin {
+    use Std::Vector;
+
+    friend Sui::SuiSystem;
+
+    #[test_only]
+    friend Sui::ZkLoginTests;
+
+    /// The JWKs and the verifying key must be given for every provider, and
+    /// the verifying key can't be empty.
+    const EInvalidConfig: u64 = 0;
+
+    /// A JWK of an OpenID provider, the RSA key with which the provider
+    /// `iss` signs the JWTs whose header has the key ID `kid`.
+    struct Jwk has store, copy, drop {
+        iss: vector<u8>,
+        kid: vector<u8>,
+        /// The big endian modulus of the RSA key.
+        modulus: vector<u8>,
+    }
+
+    /// A configuration some validators voted for, with the stake each voter
+    /// had at the time of its vote.
+    struct Proposal has store, drop {
+        jwks: vector<Jwk>,
+        verifying_key: vector<u8>,
+        voters: vector<address>,
+        voter_stakes: vector<u64>,
+        stake: u64,
+    }
+
+    struct ZkLoginState has store {
+        /// The arkworks serialization of the verifying key, empty until the
+        /// validators first vote for one, which leaves zkLogin disabled.
+        verifying_key: vector<u8>,
+        jwks: vector<Jwk>,
+        /// The pending proposals, of which each validator backs at most one.
+        proposals: vector<Proposal>,
+    }
+
+    public(friend) fun new(): ZkLoginState {
+        ZkLoginState {
+            verifying_key: Vector::empty(),
+            jwks: Vector::empty(),
+            proposals: Vector::empty(),
+        }
+    }
+
+    public(friend) fun new_jwk(iss: vector<u8>, kid: vector<u8>, modulus: vector<u8>): Jwk {
+        Jwk { iss, kid, modulus }
+    }
+
+    /// Record the vote of `voter`, which has `stake`, for replacing the
+    /// configuration with `jwks` and `verifying_key`, taking back its previous
+    /// vote if any. The configuration is replaced once the voters for it have
+    /// `quorum_stake_threshold`, and the pending proposals are dropped.
+    public(friend) fun vote(
+        self: &mut ZkLoginState,
+        jwks: vector<Jwk>,
+        verifying_key: vector<u8>,
+        voter: address,
+        stake: u64,
+        quorum_stake_threshold: u64,
+    ) {
+        assert!(!Vector::is_empty(&verifying_key), EInvalidConfig);
+        withdraw_vote(self, voter);
+
+        let index = find_proposal(&self.proposals, &jwks, &verifying_key);
+        if (index == Vector::length(&self.proposals)) {
+            Vector::push_back(&mut self.proposals, Proposal {
+                jwks,
+                verifying_key,
+                voters: Vector::empty(),
+                voter_stakes: Vector::empty(),
+                stake: 0,
+            });
+        };
+        let proposal = Vector::borrow_mut(&mut self.proposals, index);
+        Vector::push_back(&mut proposal.voters, voter);
+        Vector::push_back(&mut proposal.voter_stakes, stake);
+        proposal.stake = proposal.stake + stake;
+
+        if (proposal.stake >= quorum_stake_threshold) {
+            let Proposal { jwks, verifying_key, voters: _, voter_stakes: _, stake: _ } =
+                Vector::remove(&mut self.proposals, index);
+            self.jwks = jwks;
+            self.verifying_key = verifying_key;
+            self.proposals = Vector::empty();
+        }
+    }
+
+    /// Drop the pending proposals, as the stake of their voters changes with
+    /// the epoch. The validators vote again for the configuration they want.
+    public(friend) fun advance_epoch(self: &mut ZkLoginState) {
+        self.proposals = Vector::empty();
+    }
+
+    /// Build the JWKs of the providers `issuers`, whose keys have the IDs
+    /// `key_ids` and the moduli `moduli`.
+    public(friend) fun jwks_from_parts(
+        issuers: vector<vector<u8>>,
+        key_ids: vector<vector<u8>>,
+        moduli: vector<vector<u8>>,
+    ): vector<Jwk> {
+        let length = Vector::length(&issuers);
+        assert!(
+            Vector::length(&key_ids) == length && Vector::length(&moduli) == length,
+            EInvalidConfig
+        );
+        let jwks = Vector::empty();
+        let i = 0;
+        while (i < length) {
+            Vector::push_back(&mut jwks, Jwk {
+                iss: *Vector::borrow(&issuers, i),
+                kid: *Vector::borrow(&key_ids, i),
+                modulus: *Vector::borrow(&moduli, i),
+            });
+            i = i + 1;
+        };
+        jwks
+    }
+
+    public fun verifying_key(self: &ZkLoginState): &vector<u8> {
+        &self.verifying_key
+    }
+
+    public fun jwks(self: &ZkLoginState): &vector<Jwk> {
+        &self.jwks
+    }
+
+    /// Remove `voter` from the proposal it voted for, and the proposal if no
+    /// one else voted for it.
+    fun withdraw_vote(self: &mut ZkLoginState, voter: address) {
+        let length = Vector::length(&self.proposals);
+        let i = 0;
+        while (i < length) {
+            let proposal = Vector::borrow_mut(&mut self.proposals, i);
+            let (found, index) = Vector::index_of(&proposal.voters, &voter);
+            if (found) {
+                Vector::remove(&mut proposal.voters, index);
+                proposal.stake = proposal.stake - Vector::remove(&mut proposal.voter_stakes, index);
+                if (Vector::is_empty(&proposal.voters)) {
+                    Vector::remove(&mut self.proposals, i);
+                };
+                return
+            };
+            i = i + 1;
+        }
+    }
+
+    /// The index of the proposal of `jwks` and `verifying_key`, or the number
+    /// of proposals if there is none.
+    fun find_proposal(
+        proposals: &vector<Proposal>,
+        jwks: &vector<Jwk>,
+        verifying_key: &vector<u8>,
+    ): u64 {
+        let length = Vector::length(proposals);
+        let i = 0;
+        while (i < length) {
+            let proposal = Vector::borrow(proposals, i);
+            if (&proposal.jwks == jwks && &proposal.verifying_key == verifying_key) {
+                return i
+            };
+            i = i + 1;
+        };
+        length
+    }
+
+    #[test_only]
+    public fun destroy_for_testing(self: ZkLoginState) {
+        let ZkLoginState { verifying_key: _, jwks: _, proposals: _ } = self;
+    }
+}
@@ -0,0 +1,97 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+/// Verification of Groth16 zk-SNARK proofs over BN254 or BLS12-381, with
+/// keys and proofs in their compressed arkworks serialization. A verifying
+/// key is prepared once, by an application storing it in an object, and
+/// proofs are checked against the prepared key.
+module Sui::Groth16 {
+    /// The curve is not one of `Curve`.
+    const EInvalidCurve: u64 = 0;
+    /// The bytes are not the serialization of a verifying key on the curve.
+    const EInvalidVerifyingKey: u64 = 1;
+    /// There are more than 8 public inputs, or they are not scalars of the
+    /// curve.
+    const EInvalidPublicInputs: u64 = 2;
+
+    /// A curve the proofs are over.
+    struct Curve has store, copy, drop {
+        id: u8,
+    }
+
+    /// A verifying key with the pairing of its `alpha` and `beta` computed,
+    /// which needs not be computed again for each proof.
+    struct PreparedVerifyingKey has store, copy, drop {
+        curve: Curve,
+        vk_gamma_abc_g1: vector<u8>,
+        alpha_g1_beta_g2: vector<u8>,
+        gamma_g2_neg_pc: vector<u8>,
+        delta_g2_neg_pc: vector<u8>,
+    }
+
+    public fun bn254(): Curve {
+        Curve { id: 0 }
+    }
+
+    public fun bls12381(): Curve {
+        Curve { id: 1 }
+    }
+
+    /// Prepare the verifying key `verifying_key`, on `curve`. Aborts if it is
+    /// not a valid key.
+    public fun prepare_verifying_key(curve: &Curve, verifying_key: &vector<u8>): PreparedVerifyingKey {
+        let (vk_gamma_abc_g1, alpha_g1_beta_g2, gamma_g2_neg_pc, delta_g2_neg_pc) =
+            prepare_verifying_key_internal(curve.id, *verifying_key);
+        PreparedVerifyingKey {
+            curve: *curve,
+            vk_gamma_abc_g1,
+            alpha_g1_beta_g2,
+            gamma_g2_neg_pc,
+            delta_g2_neg_pc,
+        }
+    }
+
+    public fun curve(key: &PreparedVerifyingKey): Curve {
+        key.curve
+    }
+
+    /// Whether `proof` is a valid proof for the `public_inputs`, the
+    /// concatenation of the 32 bytes little endian serialization of each
+    /// scalar, against the prepared verifying key `key`. Malformed proofs are
+    /// never valid.
+    public fun verify_groth16_proof(
+        key: &PreparedVerifyingKey,
+        public_inputs: &vector<u8>,
+        proof: &vector<u8>,
+    ): bool {
+        verify_groth16_proof_internal(
+            key.curve.id,
+            key.vk_gamma_abc_g1,
+            key.alpha_g1_beta_g2,
+            key.gamma_g2_neg_pc,
+            key.delta_g2_neg_pc,
+            *public_inputs,
+            *proof,
+        )
+    }
+
+    native fun prepare_verifying_key_internal(
+        curve: u8,
+        verifying_key: vector<u8>,
+    ): (vector<u8>, vector<u8>, vector<u8>, vector<u8>);
+
+    native fun verify_groth16_proof_internal(
+        curve: u8,
+        vk_gamma_abc_g1: vector<u8>,
+        alpha_g1_beta_g2: vector<u8>,
+        gamma_g2_neg_pc: vector<u8>,
+        delta_g2_neg_pc: vector<u8>,
+        public_inputs: vector<u8>,
+        proof: vector<u8>,
+    ): bool;
+
+    #[test_only]
+    public fun curve_for_testing(id: u8): Curve {
+        Curve { id }
+    }
+}
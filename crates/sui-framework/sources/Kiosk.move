@@ -0,0 +1,232 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+/// A marketplace-neutral storefront. The owner of a `Kiosk` places objects in
+/// it and lists them for a price; anyone can then purchase a listed object.
+/// Every purchase produces a `TransferRequest` which must be confirmed against
+/// the creator's `TransferPolicy` for the object type, so creator-defined rules
+/// (e.g. royalties) are enforced regardless of which marketplace is used.
+///
+/// Kiosks are shared objects and the objects placed in them are owned by the
+/// kiosk. Objects owned by a shared object can only be used from the module
+/// that declares them or from the module that declares the shared object, so
+/// objects placed in a kiosk can only leave it through this module.
+module Sui::Kiosk {
+    use Std::Option::{Self, Option};
+    use Std::Vector;
+    use Sui::Balance::{Self, Balance};
+    use Sui::Coin::{Self, Coin};
+    use Sui::ID::{Self, ID, VersionedID};
+    use Sui::SUI::SUI;
+    use Sui::Transfer;
+    use Sui::TransferPolicy::{Self, TransferPolicy, TransferRequest};
+    use Sui::TxContext::{Self, TxContext};
+
+    /// The `KioskOwnerCap` does not match the `Kiosk`.
+    const ENotOwner: u64 = 0;
+
+    /// The object is not in the kiosk.
+    const EItemNotFound: u64 = 1;
+
+    /// The object is not listed for sale.
+    const ENotListed: u64 = 2;
+
+    /// The payment does not match the price of the listing.
+    const EIncorrectAmount: u64 = 3;
+
+    /// A listing of the object `item` for `price` SUI.
+    struct Listing has store, copy, drop {
+        item: ID,
+        price: u64,
+    }
+
+    /// A storefront holding objects of arbitrary types. Always a shared object.
+    struct Kiosk has key {
+        id: VersionedID,
+        /// Proceeds of the purchases so far.
+        profits: Balance<SUI>,
+        /// The objects placed in the kiosk.
+        items: vector<ID>,
+        /// The objects listed for sale.
+        listings: vector<Listing>,
+    }
+
+    /// Capability allowing the bearer to manage the `Kiosk`.
+    struct KioskOwnerCap has key, store {
+        id: VersionedID,
+        kiosk_id: ID,
+    }
+
+    /// Create and share a new `Kiosk` and return the capability to manage it.
+    public fun new(ctx: &mut TxContext): KioskOwnerCap {
+        let id = TxContext::new_id(ctx);
+        let kiosk_id = *ID::inner(&id);
+        Transfer::share_object(Kiosk {
+            id,
+            profits: Balance::zero(),
+            items: Vector::empty(),
+            listings: Vector::empty(),
+        });
+        KioskOwnerCap { id: TxContext::new_id(ctx), kiosk_id }
+    }
+
+    /// Place `item` in the kiosk.
+    public fun place<T: key + store>(self: &mut Kiosk, cap: &KioskOwnerCap, item: T) {
+        assert!(ID::id(self) == &cap.kiosk_id, ENotOwner);
+        Vector::push_back(&mut self.items, *ID::id(&item));
+        Transfer::transfer_to_object_unsafe(item, Option::none(), self)
+    }
+
+    /// Take `item` out of the kiosk, removing its listing if any.
+    public fun take<T: key + store>(self: &mut Kiosk, cap: &KioskOwnerCap, item: T): T {
+        assert!(ID::id(self) == &cap.kiosk_id, ENotOwner);
+        remove_item(self, ID::id(&item));
+        item
+    }
+
+    /// List the object `item_id` for `price` SUI, replacing its previous listing if any.
+    public fun list(self: &mut Kiosk, cap: &KioskOwnerCap, item_id: ID, price: u64) {
+        assert!(ID::id(self) == &cap.kiosk_id, ENotOwner);
+        assert!(Vector::contains(&self.items, &item_id), EItemNotFound);
+        let existing = find_listing(self, &item_id);
+        if (Option::is_some(&existing)) {
+            Vector::remove(&mut self.listings, Option::destroy_some(existing));
+        };
+        Vector::push_back(&mut self.listings, Listing { item: item_id, price })
+    }
+
+    /// Remove the listing of the object `item_id`.
+    public fun delist(self: &mut Kiosk, cap: &KioskOwnerCap, item_id: ID) {
+        assert!(ID::id(self) == &cap.kiosk_id, ENotOwner);
+        let existing = find_listing(self, &item_id);
+        assert!(Option::is_some(&existing), ENotListed);
+        Vector::remove(&mut self.listings, Option::destroy_some(existing));
+    }
+
+    /// Return the price of the object `item_id`, if it is listed.
+    public fun price(self: &Kiosk, item_id: &ID): Option<u64> {
+        let existing = find_listing(self, item_id);
+        if (Option::is_some(&existing)) {
+            Option::some(Vector::borrow(&self.listings, Option::destroy_some(existing)).price)
+        } else {
+            Option::none()
+        }
+    }
+
+    /// Return true if the object `item_id` is in the kiosk.
+    public fun contains(self: &Kiosk, item_id: &ID): bool {
+        Vector::contains(&self.items, item_id)
+    }
+
+    /// Buy the listed `item` with `payment`, which must match its price exactly.
+    /// Returns the item together with a `TransferRequest` which must be confirmed
+    /// against the `TransferPolicy<T>` in the same call.
+    public fun purchase<T: key + store>(
+        self: &mut Kiosk, item: T, payment: Coin<SUI>
+    ): (T, TransferRequest<T>) {
+        let item_id = *ID::id(&item);
+        let existing = find_listing(self, &item_id);
+        assert!(Option::is_some(&existing), ENotListed);
+        let Listing { item: _, price } = *Vector::borrow(&self.listings, Option::destroy_some(existing));
+        assert!(Coin::value(&payment) == price, EIncorrectAmount);
+        remove_item(self, &item_id);
+        Coin::deposit(&mut self.profits, payment);
+        (item, TransferPolicy::new_request(item_id, price))
+    }
+
+    /// Withdraw the proceeds of the purchases so far.
+    public fun withdraw(self: &mut Kiosk, cap: &KioskOwnerCap, ctx: &mut TxContext): Coin<SUI> {
+        assert!(ID::id(self) == &cap.kiosk_id, ENotOwner);
+        let value = Balance::value(&self.profits);
+        Coin::withdraw(&mut self.profits, value, ctx)
+    }
+
+    fun remove_item(self: &mut Kiosk, item_id: &ID) {
+        let (found, i) = Vector::index_of(&self.items, item_id);
+        assert!(found, EItemNotFound);
+        Vector::remove(&mut self.items, i);
+        let existing = find_listing(self, item_id);
+        if (Option::is_some(&existing)) {
+            Vector::remove(&mut self.listings, Option::destroy_some(existing));
+        };
+    }
+
+    fun find_listing(self: &Kiosk, item_id: &ID): Option<u64> {
+        let i = 0;
+        let len = Vector::length(&self.listings);
+        while (i < len) {
+            if (&Vector::borrow(&self.listings, i).item == item_id) {
+                return Option::some(i)
+            };
+            i = i + 1
+        };
+        Option::none()
+    }
+
+    // === Entrypoints ===
+
+    /// Create and share a new `Kiosk` and send its `KioskOwnerCap` to the sender.
+    public(script) fun create(ctx: &mut TxContext) {
+        Transfer::transfer(new(ctx), TxContext::sender(ctx))
+    }
+
+    /// Place `item` in the kiosk.
+    public(script) fun place_<T: key + store>(
+        self: &mut Kiosk, cap: &KioskOwnerCap, item: T, _ctx: &mut TxContext
+    ) {
+        place(self, cap, item)
+    }
+
+    /// Take `item` out of the kiosk and send it to the sender.
+    public(script) fun take_<T: key + store>(
+        self: &mut Kiosk, cap: &KioskOwnerCap, item: T, ctx: &mut TxContext
+    ) {
+        Transfer::transfer(take(self, cap, item), TxContext::sender(ctx))
+    }
+
+    /// Place `item` in the kiosk and list it for `price` SUI.
+    public(script) fun place_and_list<T: key + store>(
+        self: &mut Kiosk, cap: &KioskOwnerCap, item: T, price: u64, _ctx: &mut TxContext
+    ) {
+        let item_id = *ID::id(&item);
+        place(self, cap, item);
+        list(self, cap, item_id, price)
+    }
+
+    /// List the object `item` for `price` SUI.
+    public(script) fun list_<T: key + store>(
+        self: &mut Kiosk, cap: &KioskOwnerCap, item: &T, price: u64, _ctx: &mut TxContext
+    ) {
+        list(self, cap, *ID::id(item), price)
+    }
+
+    /// Remove the listing of the object `item`.
+    public(script) fun delist_<T: key + store>(
+        self: &mut Kiosk, cap: &KioskOwnerCap, item: &T, _ctx: &mut TxContext
+    ) {
+        delist(self, cap, *ID::id(item))
+    }
+
+    /// Buy the listed `item`, paying its price and the royalty required by
+    /// `policy` out of `payment`, and send it to the sender.
+    public(script) fun purchase_<T: key + store>(
+        self: &mut Kiosk,
+        policy: &mut TransferPolicy<T>,
+        item: T,
+        payment: &mut Coin<SUI>,
+        ctx: &mut TxContext,
+    ) {
+        let price = Option::destroy_some(price(self, ID::id(&item)));
+        let royalty = TransferPolicy::royalty_amount(policy, price);
+        let price_coin = Coin::withdraw(Coin::balance_mut(payment), price, ctx);
+        let royalty_coin = Coin::withdraw(Coin::balance_mut(payment), royalty, ctx);
+        let (item, request) = purchase(self, item, price_coin);
+        TransferPolicy::confirm_request(policy, request, royalty_coin);
+        Transfer::transfer(item, TxContext::sender(ctx))
+    }
+
+    /// Withdraw the proceeds of the purchases so far to the sender.
+    public(script) fun withdraw_(self: &mut Kiosk, cap: &KioskOwnerCap, ctx: &mut TxContext) {
+        Coin::keep(withdraw(self, cap, ctx), ctx)
+    }
+}
@@ -0,0 +1,126 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+/// Coins locked under a vesting schedule, for allocations which are only
+/// released over time. A `LockedCoin<T>` releases nothing before its start
+/// epoch, all of its value at its end epoch, and vests linearly in between.
+/// A schedule starting and ending at the same epoch is a plain timelock.
+/// The owner of the locked coin withdraws what vested as a `Coin<T>`.
+module Sui::LockedCoin {
+    use Sui::Balance::{Self, Balance};
+    use Sui::Coin::{Self, Coin};
+    use Sui::ID::{Self, VersionedID};
+    use Sui::Transfer;
+    use Sui::TxContext::{Self, TxContext};
+
+    /// The end epoch of the schedule is before its start epoch.
+    const EInvalidSchedule: u64 = 0;
+
+    /// The locked coin still holds some value.
+    const ENotEmpty: u64 = 1;
+
+    /// A balance of coins of type `T` vesting from `start_epoch` to `end_epoch`.
+    struct LockedCoin<phantom T> has key {
+        id: VersionedID,
+        /// The value not withdrawn yet, vested or not.
+        balance: Balance<T>,
+        /// The value withdrawn so far.
+        withdrawn: u64,
+        start_epoch: u64,
+        end_epoch: u64,
+    }
+
+    /// Lock `balance` under a schedule vesting from `start_epoch` to
+    /// `end_epoch`, and transfer it to `recipient`.
+    /// Aborts with `EInvalidSchedule` if `end_epoch` is before `start_epoch`.
+    public fun lock<T>(
+        balance: Balance<T>,
+        start_epoch: u64,
+        end_epoch: u64,
+        recipient: address,
+        ctx: &mut TxContext,
+    ) {
+        assert!(start_epoch <= end_epoch, EInvalidSchedule);
+        Transfer::transfer(LockedCoin {
+            id: TxContext::new_id(ctx),
+            balance,
+            withdrawn: 0,
+            start_epoch,
+            end_epoch,
+        }, recipient)
+    }
+
+    /// The value not withdrawn yet, vested or not.
+    public fun value<T>(self: &LockedCoin<T>): u64 {
+        Balance::value(&self.balance)
+    }
+
+    public fun start_epoch<T>(self: &LockedCoin<T>): u64 {
+        self.start_epoch
+    }
+
+    public fun end_epoch<T>(self: &LockedCoin<T>): u64 {
+        self.end_epoch
+    }
+
+    /// The value vested at `epoch`, withdrawn or not.
+    public fun vested_value<T>(self: &LockedCoin<T>, epoch: u64): u64 {
+        let total = Balance::value(&self.balance) + self.withdrawn;
+        if (epoch >= self.end_epoch) {
+            total
+        } else if (epoch < self.start_epoch) {
+            0
+        } else {
+            // The product is computed in 128 bits so that it cannot overflow.
+            let elapsed = ((epoch - self.start_epoch) as u128);
+            let duration = ((self.end_epoch - self.start_epoch) as u128);
+            (((total as u128) * elapsed / duration) as u64)
+        }
+    }
+
+    /// The value which vested in the current epoch and is not withdrawn yet.
+    public fun withdrawable_value<T>(self: &LockedCoin<T>, ctx: &TxContext): u64 {
+        vested_value(self, TxContext::epoch(ctx)) - self.withdrawn
+    }
+
+    /// Withdraw all the value which vested in the current epoch.
+    public fun withdraw<T>(self: &mut LockedCoin<T>, ctx: &mut TxContext): Coin<T> {
+        let value = withdrawable_value(self, ctx);
+        self.withdrawn = self.withdrawn + value;
+        Coin::withdraw(&mut self.balance, value, ctx)
+    }
+
+    /// Destroy a locked coin which holds no value anymore.
+    /// Aborts with `ENotEmpty` if some value is left.
+    public fun destroy_empty<T>(self: LockedCoin<T>) {
+        let LockedCoin { id, balance, withdrawn: _, start_epoch: _, end_epoch: _ } = self;
+        assert!(Balance::value(&balance) == 0, ENotEmpty);
+        Balance::destroy_zero(balance);
+        ID::delete(id);
+    }
+
+    // === Entrypoints ===
+
+    /// Lock `coin` under a schedule vesting from `start_epoch` to `end_epoch`,
+    /// and transfer it to `recipient`.
+    public(script) fun lock_coin<T>(
+        coin: Coin<T>,
+        recipient: address,
+        start_epoch: u64,
+        end_epoch: u64,
+        ctx: &mut TxContext,
+    ) {
+        lock(Coin::into_balance(coin), start_epoch, end_epoch, recipient, ctx)
+    }
+
+    /// Withdraw all the value which vested in the current epoch, and keep it.
+    public(script) fun withdraw_<T>(self: &mut LockedCoin<T>, ctx: &mut TxContext) {
+        let coin = withdraw(self, ctx);
+        Coin::keep(coin, ctx)
+    }
+
+    /// Destroy a locked coin which holds no value anymore.
+    public(script) fun destroy_empty_<T>(self: LockedCoin<T>, _ctx: &mut TxContext) {
+        destroy_empty(self)
+    }
+}
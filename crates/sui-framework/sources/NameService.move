@@ -0,0 +1,298 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+/// A name service: names registered in a shared `Registry` point at
+/// addresses for a number of epochs, after which they expire and anyone can
+/// register them again. An address can pick one of the names pointing at it
+/// as its reverse record, the name it goes by.
+/// Names are the labels of the `.sui` domain, 1 to 63 lowercase letters,
+/// digits and hyphens, not starting or ending with a hyphen.
+/// Full nodes resolve names with the first `Registry` created.
+module Sui::NameService {
+    use Std::Option::{Self, Option};
+    use Std::Vector;
+    use Sui::Event;
+    use Sui::ID::{Self, ID, VersionedID};
+    use Sui::Transfer;
+    use Sui::TxContext::{Self, TxContext};
+
+    /// The name is not a valid label.
+    const EInvalidName: u64 = 0;
+
+    /// The name is registered and has not expired.
+    const ENameTaken: u64 = 1;
+
+    /// The name is not registered, or has expired.
+    const ENameNotFound: u64 = 2;
+
+    /// The sender does not own the name.
+    const ENotOwner: u64 = 3;
+
+    /// The registration would be empty, or last longer than `MAX_REGISTRATION_EPOCHS`.
+    const EInvalidDuration: u64 = 4;
+
+    /// The name does not point at the sender.
+    const ENotTarget: u64 = 5;
+
+    const MAX_NAME_LENGTH: u64 = 63;
+
+    /// The number of epochs ahead a name can be registered for.
+    const MAX_REGISTRATION_EPOCHS: u64 = 365;
+
+    const HYPHEN: u8 = 45;
+
+    struct Registry has key {
+        id: VersionedID,
+        records: vector<NameRecord>,
+        reverse_records: vector<ReverseRecord>,
+    }
+
+    struct NameRecord has store, drop {
+        name: vector<u8>,
+        owner: address,
+        target: address,
+        /// The first epoch in which the name no longer resolves.
+        expiration_epoch: u64,
+    }
+
+    struct ReverseRecord has store, drop {
+        addr: address,
+        name: vector<u8>,
+    }
+
+    /// Emitted as a registry is created, for full nodes to find the first one.
+    struct RegistryCreated has copy, drop {
+        registry_id: ID,
+    }
+
+    /// Create an empty registry and share it.
+    public fun create(ctx: &mut TxContext) {
+        Transfer::share_object(new(ctx))
+    }
+
+    fun new(ctx: &mut TxContext): Registry {
+        let id = TxContext::new_id(ctx);
+        Event::emit(RegistryCreated { registry_id: *ID::inner(&id) });
+        Registry { id, records: Vector::empty(), reverse_records: Vector::empty() }
+    }
+
+    /// Register `name`, owned by the sender, to point at `target` for
+    /// `duration_epochs` epochs.
+    /// Aborts with `ENameTaken` if `name` is registered and has not expired.
+    public fun register(
+        self: &mut Registry,
+        name: vector<u8>,
+        target: address,
+        duration_epochs: u64,
+        ctx: &mut TxContext,
+    ) {
+        assert!(is_valid_name(&name), EInvalidName);
+        assert!(duration_epochs > 0 && duration_epochs <= MAX_REGISTRATION_EPOCHS, EInvalidDuration);
+        let epoch = TxContext::epoch(ctx);
+        let (found, i) = find_record(self, &name);
+        if (found) {
+            assert!(Vector::borrow(&self.records, i).expiration_epoch <= epoch, ENameTaken);
+            Vector::remove(&mut self.records, i);
+            remove_reverse_records_of(self, &name);
+        };
+        Vector::push_back(&mut self.records, NameRecord {
+            name,
+            owner: TxContext::sender(ctx),
+            target,
+            expiration_epoch: epoch + duration_epochs,
+        })
+    }
+
+    /// Extend the registration of `name` by `duration_epochs` epochs.
+    public fun renew(self: &mut Registry, name: vector<u8>, duration_epochs: u64, ctx: &mut TxContext) {
+        let epoch = TxContext::epoch(ctx);
+        let record = borrow_owned_mut(self, &name, ctx);
+        record.expiration_epoch = record.expiration_epoch + duration_epochs;
+        assert!(
+            duration_epochs > 0 && record.expiration_epoch <= epoch + MAX_REGISTRATION_EPOCHS,
+            EInvalidDuration
+        );
+    }
+
+    /// Point `name` at `target`. The reverse record of the previous target
+    /// for `name` is removed.
+    public fun set_target(self: &mut Registry, name: vector<u8>, target: address, ctx: &mut TxContext) {
+        borrow_owned_mut(self, &name, ctx).target = target;
+        remove_reverse_records_of(self, &name);
+    }
+
+    /// Give `name` to `recipient`, who owns it from then on.
+    public fun transfer_name(self: &mut Registry, name: vector<u8>, recipient: address, ctx: &mut TxContext) {
+        borrow_owned_mut(self, &name, ctx).owner = recipient;
+    }
+
+    /// Release `name` before it expires, for anyone to register it.
+    public fun release(self: &mut Registry, name: vector<u8>, ctx: &mut TxContext) {
+        borrow_owned_mut(self, &name, ctx);
+        let (_, i) = find_record(self, &name);
+        Vector::remove(&mut self.records, i);
+        remove_reverse_records_of(self, &name);
+    }
+
+    /// Make `name`, which must point at the sender, the reverse record of the
+    /// sender, in place of its previous one.
+    public fun set_reverse_record(self: &mut Registry, name: vector<u8>, ctx: &mut TxContext) {
+        let sender = TxContext::sender(ctx);
+        assert!(resolve(self, &name, ctx) == Option::some(sender), ENotTarget);
+        clear_reverse_record(self, ctx);
+        Vector::push_back(&mut self.reverse_records, ReverseRecord { addr: sender, name })
+    }
+
+    /// Remove the reverse record of the sender, if any.
+    public fun clear_reverse_record(self: &mut Registry, ctx: &mut TxContext) {
+        let sender = TxContext::sender(ctx);
+        let (found, i) = find_reverse_record(self, sender);
+        if (found) {
+            Vector::remove(&mut self.reverse_records, i);
+        }
+    }
+
+    /// The address `name` points at, unless it is not registered or has expired.
+    public fun resolve(self: &Registry, name: &vector<u8>, ctx: &TxContext): Option<address> {
+        let (found, i) = find_record(self, name);
+        if (!found) return Option::none();
+        let record = Vector::borrow(&self.records, i);
+        if (record.expiration_epoch <= TxContext::epoch(ctx)) return Option::none();
+        Option::some(record.target)
+    }
+
+    /// The reverse record of `addr`, if it still points at `addr`.
+    public fun reverse_lookup(self: &Registry, addr: address, ctx: &TxContext): Option<vector<u8>> {
+        let (found, i) = find_reverse_record(self, addr);
+        if (!found) return Option::none();
+        let name = &Vector::borrow(&self.reverse_records, i).name;
+        if (resolve(self, name, ctx) != Option::some(addr)) return Option::none();
+        Option::some(*name)
+    }
+
+    /// The owner of `name`, unless it is not registered or has expired.
+    public fun owner(self: &Registry, name: &vector<u8>, ctx: &TxContext): Option<address> {
+        let (found, i) = find_record(self, name);
+        if (!found) return Option::none();
+        let record = Vector::borrow(&self.records, i);
+        if (record.expiration_epoch <= TxContext::epoch(ctx)) return Option::none();
+        Option::some(record.owner)
+    }
+
+    fun is_valid_name(name: &vector<u8>): bool {
+        let length = Vector::length(name);
+        if (length == 0 || length > MAX_NAME_LENGTH) return false;
+        if (*Vector::borrow(name, 0) == HYPHEN || *Vector::borrow(name, length - 1) == HYPHEN) {
+            return false
+        };
+        let i = 0;
+        while (i < length) {
+            let c = *Vector::borrow(name, i);
+            // a-z, 0-9 and -
+            if (!((c >= 97 && c <= 122) || (c >= 48 && c <= 57) || c == HYPHEN)) return false;
+            i = i + 1;
+        };
+        true
+    }
+
+    fun find_record(self: &Registry, name: &vector<u8>): (bool, u64) {
+        let i = 0;
+        let length = Vector::length(&self.records);
+        while (i < length) {
+            if (&Vector::borrow(&self.records, i).name == name) return (true, i);
+            i = i + 1;
+        };
+        (false, 0)
+    }
+
+    fun find_reverse_record(self: &Registry, addr: address): (bool, u64) {
+        let i = 0;
+        let length = Vector::length(&self.reverse_records);
+        while (i < length) {
+            if (Vector::borrow(&self.reverse_records, i).addr == addr) return (true, i);
+            i = i + 1;
+        };
+        (false, 0)
+    }
+
+    /// The record of `name`, which the sender must own, and which must not
+    /// have expired.
+    fun borrow_owned_mut(self: &mut Registry, name: &vector<u8>, ctx: &TxContext): &mut NameRecord {
+        let (found, i) = find_record(self, name);
+        assert!(found, ENameNotFound);
+        let record = Vector::borrow_mut(&mut self.records, i);
+        assert!(record.expiration_epoch > TxContext::epoch(ctx), ENameNotFound);
+        assert!(record.owner == TxContext::sender(ctx), ENotOwner);
+        record
+    }
+
+    fun remove_reverse_records_of(self: &mut Registry, name: &vector<u8>) {
+        let i = 0;
+        while (i < Vector::length(&self.reverse_records)) {
+            if (&Vector::borrow(&self.reverse_records, i).name == name) {
+                Vector::remove(&mut self.reverse_records, i);
+            } else {
+                i = i + 1;
+            }
+        }
+    }
+
+    // === Entrypoints ===
+
+    /// Create an empty registry and share it.
+    public(script) fun create_(ctx: &mut TxContext) {
+        create(ctx)
+    }
+
+    /// Register `name` to point at `target` for `duration_epochs` epochs.
+    public(script) fun register_(
+        self: &mut Registry, name: vector<u8>, target: address, duration_epochs: u64, ctx: &mut TxContext
+    ) {
+        register(self, name, target, duration_epochs, ctx)
+    }
+
+    /// Extend the registration of `name` by `duration_epochs` epochs.
+    public(script) fun renew_(self: &mut Registry, name: vector<u8>, duration_epochs: u64, ctx: &mut TxContext) {
+        renew(self, name, duration_epochs, ctx)
+    }
+
+    /// Point `name` at `target`.
+    public(script) fun set_target_(self: &mut Registry, name: vector<u8>, target: address, ctx: &mut TxContext) {
+        set_target(self, name, target, ctx)
+    }
+
+    /// Give `name` to `recipient`.
+    public(script) fun transfer_name_(
+        self: &mut Registry, name: vector<u8>, recipient: address, ctx: &mut TxContext
+    ) {
+        transfer_name(self, name, recipient, ctx)
+    }
+
+    /// Release `name` before it expires.
+    public(script) fun release_(self: &mut Registry, name: vector<u8>, ctx: &mut TxContext) {
+        release(self, name, ctx)
+    }
+
+    /// Make `name` the reverse record of the sender.
+    public(script) fun set_reverse_record_(self: &mut Registry, name: vector<u8>, ctx: &mut TxContext) {
+        set_reverse_record(self, name, ctx)
+    }
+
+    /// Remove the reverse record of the sender.
+    public(script) fun clear_reverse_record_(self: &mut Registry, ctx: &mut TxContext) {
+        clear_reverse_record(self, ctx)
+    }
+
+    // === Testing ===
+
+    #[test_only]
+    public fun new_for_testing(ctx: &mut TxContext): Registry {
+        new(ctx)
+    }
+
+    #[test_only]
+    public fun destroy_for_testing(self: Registry) {
+        let Registry { id, records: _, reverse_records: _ } = self;
+        ID::delete(id)
+    }
+}
@@ -0,0 +1,519 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+/// A central limit order book trading a `Base` asset for a `Quote` asset.
+/// Makers rest limit orders in a shared `Pool`, with the asset they sell
+/// locked in it, and takers fill them at the price of the maker, best price
+/// first and, at a price, oldest order first. A taker pays a fee on the quote
+/// value of each fill, of which the maker gets a rebate and the owner of the
+/// pool the rest.
+///
+/// The pool emits an event for each order resting in the book, each fill and
+/// each cancellation, which full nodes index by pool for the order history
+/// and the trades of a market.
+module Sui::OrderBook {
+    use Std::Option::{Self, Option};
+    use Std::Vector;
+    use Sui::Balance::{Self, Balance};
+    use Sui::Coin::{Self, Coin};
+    use Sui::Event;
+    use Sui::ID::{Self, ID, VersionedID};
+    use Sui::Math;
+    use Sui::Transfer;
+    use Sui::TxContext::{Self, TxContext};
+
+    /// Fees are in basis points of the quote value of a fill.
+    const FEE_PRECISION: u64 = 10000;
+
+    /// Prices are of one unit of the base asset in units of the quote asset,
+    /// scaled by `PRICE_PRECISION`.
+    const PRICE_PRECISION: u64 = 1000000000;
+
+    /// The `PoolOwnerCap` does not match the `Pool`, or the order is not of
+    /// the sender.
+    const ENotOwner: u64 = 0;
+
+    /// The taker fee is above 100%, or the maker rebate above the taker fee.
+    const EInvalidFee: u64 = 1;
+
+    /// The price is zero or not a multiple of the tick size.
+    const EInvalidPrice: u64 = 2;
+
+    /// The quantity is zero or not a multiple of the lot size.
+    const EInvalidQuantity: u64 = 3;
+
+    /// The payment does not cover the order.
+    const EInsufficientFunds: u64 = 4;
+
+    /// The order is not in the book.
+    const EOrderNotFound: u64 = 5;
+
+    /// The prices and quantities of a batch of orders differ in number.
+    const ELengthMismatch: u64 = 6;
+
+    /// An order resting in the book.
+    struct Order has store, drop {
+        order_id: u64,
+        owner: address,
+        price: u64,
+        /// The quantity of the base asset left to fill.
+        quantity: u64,
+    }
+
+    /// An order book. Always a shared object.
+    struct Pool<phantom Base, phantom Quote> has key {
+        id: VersionedID,
+        /// The resting bids, from the lowest price to the highest and, at a
+        /// price, from the newest to the oldest: the best bid is the last.
+        // TODO: Use a tree once dynamic fields are available.
+        bids: vector<Order>,
+        /// The resting asks, from the highest price to the lowest and, at a
+        /// price, from the newest to the oldest: the best ask is the last.
+        asks: vector<Order>,
+        next_order_id: u64,
+        tick_size: u64,
+        lot_size: u64,
+        taker_fee_bps: u64,
+        maker_rebate_bps: u64,
+        /// The base asset sold by the resting asks.
+        base_custodian: Balance<Base>,
+        /// The quote asset paying for the resting bids.
+        quote_custodian: Balance<Quote>,
+        /// The taker fees collected, net of the maker rebates.
+        fees: Balance<Quote>,
+    }
+
+    /// Capability allowing the bearer to withdraw the fees of the `Pool`.
+    struct PoolOwnerCap has key, store {
+        id: VersionedID,
+        pool_id: ID,
+    }
+
+    // === Events ===
+
+    struct PoolCreated has copy, drop {
+        pool_id: ID,
+        tick_size: u64,
+        lot_size: u64,
+        taker_fee_bps: u64,
+        maker_rebate_bps: u64,
+    }
+
+    /// Emitted when the unfilled part of an order rests in the book.
+    struct OrderPlaced has copy, drop {
+        pool_id: ID,
+        order_id: u64,
+        owner: address,
+        is_bid: bool,
+        price: u64,
+        quantity: u64,
+    }
+
+    /// Emitted when a taker fills `quantity` of the resting order
+    /// `maker_order_id`, at its price.
+    struct OrderFilled has copy, drop {
+        pool_id: ID,
+        maker_order_id: u64,
+        taker_order_id: u64,
+        maker: address,
+        taker: address,
+        /// Whether the taker bought the base asset.
+        taker_is_bid: bool,
+        price: u64,
+        quantity: u64,
+        taker_fee: u64,
+        maker_rebate: u64,
+    }
+
+    /// Emitted when the owner of a resting order cancels the `quantity` left.
+    struct OrderCanceled has copy, drop {
+        pool_id: ID,
+        order_id: u64,
+        owner: address,
+        is_bid: bool,
+        price: u64,
+        quantity: u64,
+    }
+
+    /// Create and share a new `Pool` and return the capability to withdraw its
+    /// fees. Orders must be of a multiple of `tick_size` in price and of
+    /// `lot_size` in quantity.
+    public fun new<Base, Quote>(
+        tick_size: u64,
+        lot_size: u64,
+        taker_fee_bps: u64,
+        maker_rebate_bps: u64,
+        ctx: &mut TxContext,
+    ): PoolOwnerCap {
+        assert!(tick_size > 0, EInvalidPrice);
+        assert!(lot_size > 0, EInvalidQuantity);
+        assert!(taker_fee_bps <= FEE_PRECISION && maker_rebate_bps <= taker_fee_bps, EInvalidFee);
+        let id = TxContext::new_id(ctx);
+        let pool_id = *ID::inner(&id);
+        Event::emit(PoolCreated { pool_id, tick_size, lot_size, taker_fee_bps, maker_rebate_bps });
+        Transfer::share_object(Pool<Base, Quote> {
+            id,
+            bids: Vector::empty(),
+            asks: Vector::empty(),
+            next_order_id: 0,
+            tick_size,
+            lot_size,
+            taker_fee_bps,
+            maker_rebate_bps,
+            base_custodian: Balance::zero(),
+            quote_custodian: Balance::zero(),
+            fees: Balance::zero(),
+        });
+        PoolOwnerCap { id: TxContext::new_id(ctx), pool_id }
+    }
+
+    /// Buy `quantity` of the base asset at `price` or less, paying out of
+    /// `payment`. The part of the order left unfilled rests in the book, with
+    /// its quote value locked. Returns the base asset bought and the ID of the
+    /// order.
+    public fun place_bid<Base, Quote>(
+        self: &mut Pool<Base, Quote>,
+        payment: &mut Balance<Quote>,
+        price: u64,
+        quantity: u64,
+        ctx: &mut TxContext,
+    ): (Balance<Base>, u64) {
+        check_order(self, price, quantity);
+        let order_id = self.next_order_id;
+        self.next_order_id = order_id + 1;
+        let taker = TxContext::sender(ctx);
+        let bought = Balance::zero();
+        while (quantity > 0 && !Vector::is_empty(&self.asks)) {
+            let last = Vector::length(&self.asks) - 1;
+            let ask = Vector::borrow_mut(&mut self.asks, last);
+            if (ask.price > price) { break };
+            let fill = Math::min(quantity, ask.quantity);
+            ask.quantity = ask.quantity - fill;
+            let (maker_order_id, maker, fill_price) = (ask.order_id, ask.owner, ask.price);
+            if (ask.quantity == 0) {
+                Vector::pop_back(&mut self.asks);
+            };
+
+            let value = quote_value(fill, fill_price);
+            let taker_fee = fee(value, self.taker_fee_bps);
+            let maker_rebate = fee(value, self.maker_rebate_bps);
+            assert!(Balance::value(payment) >= value + taker_fee, EInsufficientFunds);
+            let proceeds = Balance::split(payment, value + maker_rebate);
+            Balance::join(&mut self.fees, Balance::split(payment, taker_fee - maker_rebate));
+            Transfer::transfer(Coin::from_balance(proceeds, ctx), maker);
+            Balance::join(&mut bought, Balance::split(&mut self.base_custodian, fill));
+
+            Event::emit(OrderFilled {
+                pool_id: *ID::id(self),
+                maker_order_id,
+                taker_order_id: order_id,
+                maker,
+                taker,
+                taker_is_bid: true,
+                price: fill_price,
+                quantity: fill,
+                taker_fee,
+                maker_rebate,
+            });
+            quantity = quantity - fill;
+        };
+        if (quantity > 0) {
+            let locked = quote_value(quantity, price);
+            assert!(Balance::value(payment) >= locked, EInsufficientFunds);
+            Balance::join(&mut self.quote_custodian, Balance::split(payment, locked));
+            insert_order(&mut self.bids, Order { order_id, owner: taker, price, quantity }, true);
+            Event::emit(OrderPlaced {
+                pool_id: *ID::id(self),
+                order_id,
+                owner: taker,
+                is_bid: true,
+                price,
+                quantity,
+            });
+        };
+        (bought, order_id)
+    }
+
+    /// Sell `quantity` of the base asset at `price` or more, out of `offer`.
+    /// The part of the order left unfilled rests in the book, with its base
+    /// asset locked. Returns the quote asset received, net of the taker fees,
+    /// and the ID of the order.
+    public fun place_ask<Base, Quote>(
+        self: &mut Pool<Base, Quote>,
+        offer: &mut Balance<Base>,
+        price: u64,
+        quantity: u64,
+        ctx: &mut TxContext,
+    ): (Balance<Quote>, u64) {
+        check_order(self, price, quantity);
+        assert!(Balance::value(offer) >= quantity, EInsufficientFunds);
+        let order_id = self.next_order_id;
+        self.next_order_id = order_id + 1;
+        let taker = TxContext::sender(ctx);
+        let received = Balance::zero();
+        while (quantity > 0 && !Vector::is_empty(&self.bids)) {
+            let last = Vector::length(&self.bids) - 1;
+            let bid = Vector::borrow_mut(&mut self.bids, last);
+            if (bid.price < price) { break };
+            let fill = Math::min(quantity, bid.quantity);
+            bid.quantity = bid.quantity - fill;
+            let (maker_order_id, maker, fill_price) = (bid.order_id, bid.owner, bid.price);
+            if (bid.quantity == 0) {
+                Vector::pop_back(&mut self.bids);
+            };
+
+            let value = quote_value(fill, fill_price);
+            let taker_fee = fee(value, self.taker_fee_bps);
+            let maker_rebate = fee(value, self.maker_rebate_bps);
+            let paid = Balance::split(&mut self.quote_custodian, value);
+            Balance::join(&mut self.fees, Balance::split(&mut paid, taker_fee - maker_rebate));
+            if (maker_rebate > 0) {
+                let rebate = Balance::split(&mut paid, maker_rebate);
+                Transfer::transfer(Coin::from_balance(rebate, ctx), maker);
+            };
+            Balance::join(&mut received, paid);
+            Transfer::transfer(Coin::from_balance(Balance::split(offer, fill), ctx), maker);
+
+            Event::emit(OrderFilled {
+                pool_id: *ID::id(self),
+                maker_order_id,
+                taker_order_id: order_id,
+                maker,
+                taker,
+                taker_is_bid: false,
+                price: fill_price,
+                quantity: fill,
+                taker_fee,
+                maker_rebate,
+            });
+            quantity = quantity - fill;
+        };
+        if (quantity > 0) {
+            Balance::join(&mut self.base_custodian, Balance::split(offer, quantity));
+            insert_order(&mut self.asks, Order { order_id, owner: taker, price, quantity }, false);
+            Event::emit(OrderPlaced {
+                pool_id: *ID::id(self),
+                order_id,
+                owner: taker,
+                is_bid: false,
+                price,
+                quantity,
+            });
+        };
+        (received, order_id)
+    }
+
+    /// Cancel the resting order `order_id` of the sender, returning the base
+    /// asset locked by an ask and the quote asset locked by a bid.
+    public fun cancel_order<Base, Quote>(
+        self: &mut Pool<Base, Quote>,
+        order_id: u64,
+        ctx: &TxContext,
+    ): (Balance<Base>, Balance<Quote>) {
+        let (is_bid, index) = (true, find_order(&self.bids, order_id));
+        if (Option::is_none(&index)) {
+            is_bid = false;
+            index = find_order(&self.asks, order_id);
+        };
+        assert!(Option::is_some(&index), EOrderNotFound);
+        let orders = if (is_bid) { &mut self.bids } else { &mut self.asks };
+        let Order { order_id, owner, price, quantity } =
+            Vector::remove(orders, Option::destroy_some(index));
+        assert!(owner == TxContext::sender(ctx), ENotOwner);
+        Event::emit(OrderCanceled { pool_id: *ID::id(self), order_id, owner, is_bid, price, quantity });
+        if (is_bid) {
+            let refund = Balance::split(&mut self.quote_custodian, quote_value(quantity, price));
+            (Balance::zero(), refund)
+        } else {
+            (Balance::split(&mut self.base_custodian, quantity), Balance::zero())
+        }
+    }
+
+    /// Withdraw the fees collected by the pool so far.
+    public fun withdraw_fees<Base, Quote>(
+        self: &mut Pool<Base, Quote>,
+        cap: &PoolOwnerCap,
+        ctx: &mut TxContext,
+    ): Coin<Quote> {
+        assert!(ID::id(self) == &cap.pool_id, ENotOwner);
+        let value = Balance::value(&self.fees);
+        Coin::withdraw(&mut self.fees, value, ctx)
+    }
+
+    /// Return the price of the best bid, if any.
+    public fun best_bid<Base, Quote>(self: &Pool<Base, Quote>): Option<u64> {
+        best_price(&self.bids)
+    }
+
+    /// Return the price of the best ask, if any.
+    public fun best_ask<Base, Quote>(self: &Pool<Base, Quote>): Option<u64> {
+        best_price(&self.asks)
+    }
+
+    /// Return the quantity left of the resting order `order_id`, or 0 if it is
+    /// not in the book.
+    public fun order_quantity<Base, Quote>(self: &Pool<Base, Quote>, order_id: u64): u64 {
+        let index = find_order(&self.bids, order_id);
+        if (Option::is_some(&index)) {
+            return Vector::borrow(&self.bids, Option::destroy_some(index)).quantity
+        };
+        let index = find_order(&self.asks, order_id);
+        if (Option::is_some(&index)) {
+            return Vector::borrow(&self.asks, Option::destroy_some(index)).quantity
+        };
+        0
+    }
+
+    public fun fees_value<Base, Quote>(self: &Pool<Base, Quote>): u64 {
+        Balance::value(&self.fees)
+    }
+
+    fun check_order<Base, Quote>(self: &Pool<Base, Quote>, price: u64, quantity: u64) {
+        assert!(price > 0 && price % self.tick_size == 0, EInvalidPrice);
+        assert!(quantity > 0 && quantity % self.lot_size == 0, EInvalidQuantity);
+    }
+
+    /// The quote value of `quantity` of the base asset at `price`, rounded
+    /// down.
+    fun quote_value(quantity: u64, price: u64): u64 {
+        (((quantity as u128) * (price as u128) / (PRICE_PRECISION as u128)) as u64)
+    }
+
+    fun fee(value: u64, fee_bps: u64): u64 {
+        (((value as u128) * (fee_bps as u128) / (FEE_PRECISION as u128)) as u64)
+    }
+
+    /// Insert `order` behind the orders of a better price or the same, so that
+    /// the best order is last.
+    fun insert_order(orders: &mut vector<Order>, order: Order, is_bid: bool) {
+        Vector::push_back(orders, order);
+        let i = Vector::length(orders) - 1;
+        while (i > 0) {
+            let price = Vector::borrow(orders, i).price;
+            let before = Vector::borrow(orders, i - 1).price;
+            let in_place = if (is_bid) { before < price } else { before > price };
+            if (in_place) { break };
+            Vector::swap(orders, i - 1, i);
+            i = i - 1;
+        }
+    }
+
+    fun find_order(orders: &vector<Order>, order_id: u64): Option<u64> {
+        let i = 0;
+        let len = Vector::length(orders);
+        while (i < len) {
+            if (Vector::borrow(orders, i).order_id == order_id) {
+                return Option::some(i)
+            };
+            i = i + 1
+        };
+        Option::none()
+    }
+
+    fun best_price(orders: &vector<Order>): Option<u64> {
+        let len = Vector::length(orders);
+        if (len == 0) {
+            Option::none()
+        } else {
+            Option::some(Vector::borrow(orders, len - 1).price)
+        }
+    }
+
+    // === Entrypoints ===
+
+    /// Create and share a new `Pool` and send its `PoolOwnerCap` to the sender.
+    public(script) fun create<Base, Quote>(
+        tick_size: u64,
+        lot_size: u64,
+        taker_fee_bps: u64,
+        maker_rebate_bps: u64,
+        ctx: &mut TxContext,
+    ) {
+        let cap = new<Base, Quote>(tick_size, lot_size, taker_fee_bps, maker_rebate_bps, ctx);
+        Transfer::transfer(cap, TxContext::sender(ctx))
+    }
+
+    /// Place a bid for each of `prices` and `quantities`, paying out of
+    /// `payment`, and send the base asset bought to the sender.
+    public(script) fun place_bids<Base, Quote>(
+        self: &mut Pool<Base, Quote>,
+        payment: &mut Coin<Quote>,
+        prices: vector<u64>,
+        quantities: vector<u64>,
+        ctx: &mut TxContext,
+    ) {
+        let len = Vector::length(&prices);
+        assert!(len == Vector::length(&quantities), ELengthMismatch);
+        let bought = Balance::zero<Base>();
+        let i = 0;
+        while (i < len) {
+            let price = *Vector::borrow(&prices, i);
+            let quantity = *Vector::borrow(&quantities, i);
+            let (filled, _) = place_bid(self, Coin::balance_mut(payment), price, quantity, ctx);
+            Balance::join(&mut bought, filled);
+            i = i + 1;
+        };
+        keep_balance(bought, ctx)
+    }
+
+    /// Place an ask for each of `prices` and `quantities`, out of `offer`, and
+    /// send the quote asset received to the sender.
+    public(script) fun place_asks<Base, Quote>(
+        self: &mut Pool<Base, Quote>,
+        offer: &mut Coin<Base>,
+        prices: vector<u64>,
+        quantities: vector<u64>,
+        ctx: &mut TxContext,
+    ) {
+        let len = Vector::length(&prices);
+        assert!(len == Vector::length(&quantities), ELengthMismatch);
+        let received = Balance::zero<Quote>();
+        let i = 0;
+        while (i < len) {
+            let price = *Vector::borrow(&prices, i);
+            let quantity = *Vector::borrow(&quantities, i);
+            let (filled, _) = place_ask(self, Coin::balance_mut(offer), price, quantity, ctx);
+            Balance::join(&mut received, filled);
+            i = i + 1;
+        };
+        keep_balance(received, ctx)
+    }
+
+    /// Cancel the resting orders `order_ids` of the sender and send the assets
+    /// they locked back to the sender.
+    public(script) fun cancel_orders<Base, Quote>(
+        self: &mut Pool<Base, Quote>,
+        order_ids: vector<u64>,
+        ctx: &mut TxContext,
+    ) {
+        let base = Balance::zero<Base>();
+        let quote = Balance::zero<Quote>();
+        let i = 0;
+        let len = Vector::length(&order_ids);
+        while (i < len) {
+            let (base_refund, quote_refund) = cancel_order(self, *Vector::borrow(&order_ids, i), ctx);
+            Balance::join(&mut base, base_refund);
+            Balance::join(&mut quote, quote_refund);
+            i = i + 1;
+        };
+        keep_balance(base, ctx);
+        keep_balance(quote, ctx)
+    }
+
+    /// Withdraw the fees collected by the pool so far to the sender.
+    public(script) fun withdraw_fees_<Base, Quote>(
+        self: &mut Pool<Base, Quote>,
+        cap: &PoolOwnerCap,
+        ctx: &mut TxContext,
+    ) {
+        Coin::keep(withdraw_fees(self, cap, ctx), ctx)
+    }
+
+    fun keep_balance<T>(balance: Balance<T>, ctx: &mut TxContext) {
+        if (Balance::value(&balance) == 0) {
+            Balance::destroy_zero(balance)
+        } else {
+            Coin::keep(Coin::from_balance(balance, ctx), ctx)
+        }
+    }
+}
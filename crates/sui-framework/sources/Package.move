@@ -0,0 +1,90 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+/// Upgrades of the packages published by users. A package is upgraded by
+/// publishing a new version of it at a new ID, which keeps every module of
+/// the version it replaces with the same struct layouts and public function
+/// signatures, so that the packages depending on it can move to the new
+/// version as they are rebuilt.
+///
+/// A package is only upgraded with its `UpgradeCap`, claimed with a witness
+/// type `T` by the `init` function of the module declaring `T`, as the package
+/// is published. Both are enforced by the Sui bytecode verifier. A package
+/// whose `UpgradeCap` is never claimed, or is destroyed, can't be upgraded.
+module Sui::Package {
+    use Sui::ID::{Self, VersionedID};
+    use Sui::Publisher;
+    use Sui::Transfer;
+    use Sui::TxContext::{Self, TxContext};
+
+    /// The capability to upgrade a package. Upgrades are executed by the
+    /// validators, which replace `package` with the ID of the new version,
+    /// and increment `version`.
+    struct UpgradeCap has key, store {
+        id: VersionedID,
+        /// The latest version of the package, which the next upgrade replaces.
+        package: address,
+        /// The number of versions of the package, 1 until it is first upgraded.
+        version: u64,
+    }
+
+    /// Claim the `UpgradeCap` of the package declaring the witness type `T`.
+    /// Can only be called from the `init` function of the module declaring `T`.
+    public fun claim_upgrade_cap<T: drop>(_witness: T, ctx: &mut TxContext): UpgradeCap {
+        UpgradeCap {
+            id: TxContext::new_id(ctx),
+            package: Publisher::package_of<T>(),
+            version: 1,
+        }
+    }
+
+    /// Return the address of the latest version of the package of `self`.
+    public fun package(self: &UpgradeCap): address {
+        self.package
+    }
+
+    /// Return the number of versions of the package of `self`.
+    public fun version(self: &UpgradeCap): u64 {
+        self.version
+    }
+
+    /// Send `cap` to `recipient`.
+    public fun transfer(cap: UpgradeCap, recipient: address) {
+        Transfer::transfer(cap, recipient)
+    }
+
+    /// Transfer `cap` to the sender of the current transaction.
+    public fun keep(cap: UpgradeCap, ctx: &TxContext) {
+        transfer(cap, TxContext::sender(ctx))
+    }
+
+    /// Destroy `cap`, so that its package is never upgraded again.
+    public fun make_immutable(cap: UpgradeCap) {
+        let UpgradeCap { id, package: _, version: _ } = cap;
+        ID::delete(id);
+    }
+
+    // === Entrypoints ===
+
+    /// Send `cap` to `recipient`.
+    public(script) fun transfer_(cap: UpgradeCap, recipient: address, _ctx: &mut TxContext) {
+        transfer(cap, recipient)
+    }
+
+    /// Destroy `cap`.
+    public(script) fun make_immutable_(cap: UpgradeCap, _ctx: &mut TxContext) {
+        make_immutable(cap)
+    }
+
+    // === Testing ===
+
+    #[test_only]
+    /// Create the `UpgradeCap` of the package declaring `T`, outside of `init`.
+    public fun new_for_testing<T>(ctx: &mut TxContext): UpgradeCap {
+        UpgradeCap {
+            id: TxContext::new_id(ctx),
+            package: Publisher::package_of<T>(),
+            version: 1,
+        }
+    }
+}
@@ -0,0 +1,80 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+/// Proof of publishing a package. Its holder acts for the types declared in
+/// the package: only with the `Publisher` of a package can the display
+/// (`Display::new`) or the transfer policy (`TransferPolicy::new`) of its
+/// types be set, so no third party can set them for types it does not own.
+///
+/// A `Publisher` is claimed with a witness type `T` by the `init` function of
+/// the module declaring `T`, as the package is published. Both are enforced by
+/// the Sui bytecode verifier.
+module Sui::Publisher {
+    use Sui::ID::{Self, VersionedID};
+    use Sui::Transfer;
+    use Sui::TxContext::{Self, TxContext};
+
+    friend Sui::Package;
+
+    /// The proof of publishing the package at `package`.
+    struct Publisher has key, store {
+        id: VersionedID,
+        package: address,
+    }
+
+    /// Claim the `Publisher` of the package declaring the witness type `T`.
+    /// Can only be called from the `init` function of the module declaring `T`.
+    public fun claim<T: drop>(_witness: T, ctx: &mut TxContext): Publisher {
+        Publisher { id: TxContext::new_id(ctx), package: package_of<T>() }
+    }
+
+    /// Return the address of the package `self` was claimed for.
+    public fun package(self: &Publisher): address {
+        self.package
+    }
+
+    /// Return whether `T` is declared in the package `self` was claimed for.
+    public fun is_package<T>(self: &Publisher): bool {
+        package_of<T>() == self.package
+    }
+
+    /// Send `publisher` to `recipient`.
+    public fun transfer(publisher: Publisher, recipient: address) {
+        Transfer::transfer(publisher, recipient)
+    }
+
+    /// Transfer `publisher` to the sender of the current transaction.
+    public fun keep(publisher: Publisher, ctx: &TxContext) {
+        transfer(publisher, TxContext::sender(ctx))
+    }
+
+    /// Destroy `publisher`, giving up the authority over the types of its package.
+    public fun burn(publisher: Publisher) {
+        let Publisher { id, package: _ } = publisher;
+        ID::delete(id);
+    }
+
+    /// Return the address of the package declaring `T`.
+    /// Aborts if `T` is not a struct, as no package declares it.
+    public(friend) native fun package_of<T>(): address;
+
+    // === Entrypoints ===
+
+    /// Send `publisher` to `recipient`.
+    public(script) fun transfer_(publisher: Publisher, recipient: address, _ctx: &mut TxContext) {
+        transfer(publisher, recipient)
+    }
+
+    /// Destroy `publisher`.
+    public(script) fun burn_(publisher: Publisher, _ctx: &mut TxContext) {
+        burn(publisher)
+    }
+
+    // === Testing ===
+
+    #[test_only]
+    /// Create the `Publisher` of the package declaring `T`, outside of `init`.
+    public fun new_for_testing<T>(ctx: &mut TxContext): Publisher {
+        Publisher { id: TxContext::new_id(ctx), package: package_of<T>() }
+    }
+}
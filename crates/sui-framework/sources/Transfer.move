@@ -9,6 +9,8 @@ module Sui::Transfer {
     friend Sui::Bag;
     // To allow access to is_child_unsafe.
     friend Sui::Collection;
+    // To allow access to transfer_to_object_unsafe.
+    friend Sui::Kiosk;
 
     // When transferring a child object, this error is thrown if the child object
     // doesn't match the ChildRef that represents the ownership.
@@ -121,6 +123,19 @@ module Sui::Transfer {
         transfer(child, recipient)
     }
 
+    /// Take the child object `child_ref` points at out of its parent `owner`, loading it from the
+    /// store rather than from the inputs of the transaction, so that a parent can hold more
+    /// children than a transaction could list. The child comes by value, as if passed by value in
+    /// the inputs, and `child_ref` is consumed with it as usual: by transferring the child back to
+    /// `owner` or elsewhere, or by deleting it.
+    /// Aborts if the child is not owned by `owner`, or is already given to the call.
+    public fun take_child_object<T: key, R: key>(owner: &mut R, child_ref: &ChildRef<T>): T {
+        take_child_object_internal<T>(
+            ID::id_address(ID::id(owner)),
+            ID::id_address(&child_ref.child_id),
+        )
+    }
+
     /// Delete `child_ref`, which must point at `child_id`.
     /// This is the second way to consume a `ChildRef`.
     /// Passing ownership of `child_id` to this function implies that the child object
@@ -133,11 +148,16 @@ module Sui::Transfer {
 
     /// Freeze `obj`. After freezing `obj` becomes immutable and can no
     /// longer be transferred or mutated.
+    /// The transaction fails if `obj` is owned by another object.
     public native fun freeze_object<T: key>(obj: T);
 
     /// Turn the given object into a mutable shared object that everyone
     /// can access and mutate. This is irreversible, i.e. once an object
-    /// is shared, it will stay shared forever.
+    /// is shared, it will stay shared until it is deleted: an entry function
+    /// can take a shared object by value to delete it, but not to transfer,
+    /// freeze or wrap it. The transactions on the object sequenced after the
+    /// one deleting it fail without executing.
+    /// The transaction fails unless `obj` was created in the same transaction.
     /// Shared mutable object is not yet fully supported in Sui, which is being
     /// actively worked on and should be supported very soon.
     /// https://github.com/MystenLabs/sui/issues/633
@@ -146,8 +166,19 @@ module Sui::Transfer {
     /// Move contracts that use shared objects.
     public native fun share_object<T: key>(obj: T);
 
+    /// Transfer `obj` to `party`, the address of a set of members owning it jointly, such as the
+    /// multi-signature address of their keys, so that any of them, or a threshold of them, can
+    /// use it. Party objects are used through consensus, as shared objects are, and as with
+    /// those, an entry function can take a party object by value to delete it, but not to
+    /// transfer, freeze or wrap it.
+    /// The transaction fails unless `obj` was created in the same transaction.
+    public native fun transfer_to_party<T: key>(obj: T, party: address);
+
     native fun transfer_internal<T: key>(obj: T, recipient: address, to_object: bool);
 
     // delete `child_id`, emit a system `DeleteChildObject(child)` event
     native fun delete_child_object_internal(child: address, child_id: VersionedID);
+
+    // read the object `child` owned by `parent` from the store
+    native fun take_child_object_internal<T: key>(parent: address, child: address): T;
 }
@@ -0,0 +1,142 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+/// Creator-defined rules that are enforced whenever an object of type `T`
+/// is purchased from a `Kiosk`. A purchase produces a `TransferRequest<T>`,
+/// which has no abilities: it cannot be stored, copied or dropped, so the
+/// transaction can only complete if the request is confirmed against the
+/// `TransferPolicy<T>` (paying the royalty) in the same call.
+///
+/// `new<T>` takes the `Publisher` of the package that declares `T`, so only
+/// the creator of a type can set its policy.
+module Sui::TransferPolicy {
+    use Sui::Balance::{Self, Balance};
+    use Sui::Coin::{Self, Coin};
+    use Sui::ID::{Self, ID, VersionedID};
+    use Sui::Publisher::{Self, Publisher};
+    use Sui::SUI::SUI;
+    use Sui::Transfer;
+    use Sui::TxContext::{Self, TxContext};
+
+    friend Sui::Kiosk;
+
+    /// The royalty is expressed in basis points and cannot exceed 100%.
+    const EInvalidRoyalty: u64 = 0;
+
+    /// The `TransferPolicyCap` does not match the `TransferPolicy`.
+    const ENotOwner: u64 = 1;
+
+    /// The royalty paid is lower than the one required by the policy.
+    const EInsufficientRoyalty: u64 = 2;
+
+    /// The `Publisher` is not the one of the package that declares `T`.
+    const ENotPublisher: u64 = 3;
+
+    /// 100% in basis points.
+    const MAX_BPS: u64 = 10000;
+
+    /// A request to transfer an object of type `T` bought for `paid` SUI.
+    /// Must be consumed by `confirm_request`.
+    struct TransferRequest<phantom T> {
+        item: ID,
+        paid: u64,
+    }
+
+    /// The rules for transferring objects of type `T`. Always a shared object.
+    struct TransferPolicy<phantom T> has key {
+        id: VersionedID,
+        /// Royalty taken on every purchase, in basis points of the price.
+        royalty_bps: u64,
+        /// Royalties collected so far.
+        balance: Balance<SUI>,
+    }
+
+    /// Capability allowing the bearer to manage the `TransferPolicy<T>`.
+    struct TransferPolicyCap<phantom T> has key, store {
+        id: VersionedID,
+        policy_id: ID,
+    }
+
+    /// Create and share a `TransferPolicy<T>` and return the capability to manage it.
+    /// Aborts with `ENotPublisher` unless `publisher` is the one of the package that declares `T`.
+    public fun new<T>(publisher: &Publisher, royalty_bps: u64, ctx: &mut TxContext): TransferPolicyCap<T> {
+        assert!(Publisher::is_package<T>(publisher), ENotPublisher);
+        assert!(royalty_bps <= MAX_BPS, EInvalidRoyalty);
+        let id = TxContext::new_id(ctx);
+        let policy_id = *ID::inner(&id);
+        Transfer::share_object(TransferPolicy<T> { id, royalty_bps, balance: Balance::zero() });
+        TransferPolicyCap { id: TxContext::new_id(ctx), policy_id }
+    }
+
+    /// Return the royalty in basis points.
+    public fun royalty_bps<T>(self: &TransferPolicy<T>): u64 {
+        self.royalty_bps
+    }
+
+    /// Return the royalty due on a purchase for `paid` SUI.
+    public fun royalty_amount<T>(self: &TransferPolicy<T>, paid: u64): u64 {
+        (((paid as u128) * (self.royalty_bps as u128) / (MAX_BPS as u128)) as u64)
+    }
+
+    /// Change the royalty to `royalty_bps`.
+    public fun set_royalty<T>(self: &mut TransferPolicy<T>, cap: &TransferPolicyCap<T>, royalty_bps: u64) {
+        assert!(ID::id(self) == &cap.policy_id, ENotOwner);
+        assert!(royalty_bps <= MAX_BPS, EInvalidRoyalty);
+        self.royalty_bps = royalty_bps
+    }
+
+    /// Withdraw the royalties collected so far.
+    public fun withdraw<T>(
+        self: &mut TransferPolicy<T>, cap: &TransferPolicyCap<T>, ctx: &mut TxContext
+    ): Coin<SUI> {
+        assert!(ID::id(self) == &cap.policy_id, ENotOwner);
+        let value = Balance::value(&self.balance);
+        Coin::withdraw(&mut self.balance, value, ctx)
+    }
+
+    /// Create a request for the purchase of `item` for `paid` SUI.
+    public(friend) fun new_request<T>(item: ID, paid: u64): TransferRequest<T> {
+        TransferRequest { item, paid }
+    }
+
+    /// Return the ID of the object being transferred.
+    public fun item<T>(request: &TransferRequest<T>): &ID {
+        &request.item
+    }
+
+    /// Return the price the object was bought for.
+    public fun paid<T>(request: &TransferRequest<T>): u64 {
+        request.paid
+    }
+
+    /// Consume `request` by paying the royalty required by the policy.
+    /// Aborts with `EInsufficientRoyalty` if `royalty` is lower than required.
+    public fun confirm_request<T>(
+        self: &mut TransferPolicy<T>, request: TransferRequest<T>, royalty: Coin<SUI>
+    ) {
+        let TransferRequest { item: _, paid } = request;
+        assert!(Coin::value(&royalty) >= royalty_amount(self, paid), EInsufficientRoyalty);
+        Coin::deposit(&mut self.balance, royalty)
+    }
+
+    // === Entrypoints ===
+
+    /// Create and share a `TransferPolicy<T>` and transfer the capability to manage it to the sender.
+    public(script) fun new_<T>(publisher: &Publisher, royalty_bps: u64, ctx: &mut TxContext) {
+        Transfer::transfer(new<T>(publisher, royalty_bps, ctx), TxContext::sender(ctx))
+    }
+
+    /// Change the royalty to `royalty_bps`.
+    public(script) fun set_royalty_<T>(
+        self: &mut TransferPolicy<T>, cap: &TransferPolicyCap<T>, royalty_bps: u64, _ctx: &mut TxContext
+    ) {
+        set_royalty(self, cap, royalty_bps)
+    }
+
+    /// Withdraw the royalties collected so far to the sender.
+    public(script) fun withdraw_<T>(
+        self: &mut TransferPolicy<T>, cap: &TransferPolicyCap<T>, ctx: &mut TxContext
+    ) {
+        Coin::keep(withdraw(self, cap, ctx), ctx)
+    }
+}
@@ -97,6 +97,12 @@ module Sui::TxContext {
         new(new_signer_from_address(a), dummy_tx_hash_with_hint(hint), 0)
     }
 
+    #[test_only]
+    /// Create a `TxContext` with sender `a` in epoch `epoch` for testing.
+    public fun new_from_address_with_epoch(a: address, hint: u8, epoch: u64): TxContext {
+        new_with_epoch(new_signer_from_address(a), dummy_tx_hash_with_hint(hint), epoch, 0)
+    }
+
     #[test_only]
     /// Create a dummy `TxContext` for testing
     public fun dummy(): TxContext {
@@ -0,0 +1,23 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+/// The names of types, for contracts keeping values of many types apart,
+/// such as a registry keyed by the type of the objects it lists.
+module Sui::Types {
+    use Std::ASCII::{Self, String};
+
+    /// The name of the type is longer than 4096 bytes.
+    const ETypeNameTooLong: u64 = 0;
+
+    /// The name of `T`, with its type arguments, such as
+    /// `0x2::Coin::Coin<0x2::SUI::SUI>`.
+    public fun type_name<T>(): String {
+        ASCII::string(type_name_internal<T>())
+    }
+
+    public fun is_same_type<T, U>(): bool {
+        type_name_internal<T>() == type_name_internal<U>()
+    }
+
+    native fun type_name_internal<T>(): vector<u8>;
+}
@@ -0,0 +1,31 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+/// Verification of Wesolowski verifiable delay functions, for contracts
+/// revealing a value only after a delay nobody can shorten, such as the
+/// winner of a lottery drawn from a seed fixed at its close, or the bids of
+/// an auction. Computing the output of an input takes `iterations`
+/// sequential squarings in the RSA group of the modulus, while checking it
+/// with its proof is fast.
+///
+/// The modulus must be one nobody knows the factorization of, such as the
+/// RSA-2048 challenge number, since knowing it shortcuts the squarings. The
+/// numbers are unsigned big endian, taken up to their sign in the group:
+/// the least of `v` and `modulus - v`.
+module Sui::VDF {
+    /// The modulus is longer than 4096 bits, or is even.
+    const EInvalidModulus: u64 = 0;
+
+    /// The input of the VDF for `message`, such as the seed of a lottery.
+    public native fun hash_to_input(modulus: vector<u8>, message: vector<u8>): vector<u8>;
+
+    /// Whether `proof` shows that `output` is the output of `input` after
+    /// `iterations` squarings modulo `modulus`.
+    public native fun verify_wesolowski(
+        modulus: vector<u8>,
+        input: vector<u8>,
+        output: vector<u8>,
+        proof: vector<u8>,
+        iterations: u64,
+    ): bool;
+}
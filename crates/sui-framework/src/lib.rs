@@ -1,19 +1,28 @@
 // Copyright (c) 2022, Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+use move_binary_format::access::ModuleAccess;
+use move_binary_format::file_format::{Bytecode, SignatureToken};
 use move_binary_format::CompiledModule;
+use move_core_types::language_storage::ModuleId;
 use move_package::BuildConfig;
 use move_unit_test::UnitTestingConfig;
 use num_enum::TryFromPrimitive;
 use once_cell::sync::Lazy;
+use std::collections::BTreeMap;
 use std::path::Path;
-use sui_types::error::{SuiError, SuiResult};
+use sui_types::base_types::ObjectID;
+use sui_types::error::{MoveLocation, SuiError, SuiResult};
+use sui_types::messages::SystemPackage;
 
 pub mod natives;
 
 pub use sui_framework_build::build_move_stdlib_modules as get_move_stdlib_modules;
 pub use sui_framework_build::build_sui_framework_modules as get_sui_framework_modules;
-pub use sui_framework_build::{build_framework, build_move_package, verify_modules};
+pub use sui_framework_build::{
+    build_framework, build_move_package, build_move_package_for_publish, verify_modules,
+    PackageForPublish,
+};
 
 // Move unit tests will halt after executing this many steps. This is a protection to avoid divergence
 const MAX_UNIT_TEST_INSTRUCTIONS: u64 = 100_000;
@@ -40,6 +49,13 @@ static MOVE_STDLIB: Lazy<Vec<CompiledModule>> = Lazy::new(|| {
         .collect()
 });
 
+/// The names of the constants of the framework and standard library modules, by index.
+static CONSTANT_NAMES: Lazy<BTreeMap<ModuleId, BTreeMap<u16, String>>> = Lazy::new(|| {
+    const CONSTANT_NAMES_BYTES: &[u8] = include_bytes!(concat!(env!("OUT_DIR"), "/constant-names"));
+
+    bcs::from_bytes(CONSTANT_NAMES_BYTES).unwrap()
+});
+
 pub fn get_sui_framework() -> Vec<CompiledModule> {
     Lazy::force(&SUI_FRAMEWORK).to_owned()
 }
@@ -48,8 +64,71 @@ pub fn get_move_stdlib() -> Vec<CompiledModule> {
     Lazy::force(&MOVE_STDLIB).to_owned()
 }
 
+/// The framework packages of this build, which an epoch change upgrades the ones on chain to
+/// once the validators voted for their digest.
+pub fn get_system_packages() -> Vec<SystemPackage> {
+    [get_move_stdlib(), get_sui_framework()]
+        .into_iter()
+        .map(|modules| SystemPackage {
+            id: ObjectID::from(*modules[0].self_id().address()),
+            modules: modules
+                .iter()
+                .map(|module| {
+                    let mut bytes = Vec::new();
+                    module.serialize(&mut bytes).unwrap();
+                    bytes
+                })
+                .collect(),
+        })
+        .collect()
+}
+
 pub const DEFAULT_FRAMEWORK_PATH: &str = env!("CARGO_MANIFEST_DIR");
 
+/// The name of the constant which a framework or standard library function aborted with at
+/// `location`, such as `ENotEnough`. This is the constant loaded right before the abort, as
+/// `assert!(value >= amount, ENotEnough)` does, or else the only error constant of the module
+/// with the value `code`, as error constants are named `E...` by convention.
+pub fn abort_constant_name(location: &MoveLocation, code: u64) -> Option<String> {
+    let names = CONSTANT_NAMES.get(&location.module)?;
+    let module = SUI_FRAMEWORK
+        .iter()
+        .chain(MOVE_STDLIB.iter())
+        .find(|module| module.self_id() == location.module)?;
+    let has_code = |index: u16| {
+        module
+            .constant_pool()
+            .get(index as usize)
+            .map_or(false, |constant| {
+                constant.type_ == SignatureToken::U64 && constant.data == code.to_le_bytes()
+            })
+    };
+
+    let loaded = module
+        .function_defs()
+        .get(location.function as usize)
+        .and_then(|definition| definition.code.as_ref())
+        .and_then(|unit| {
+            unit.code
+                .get((location.instruction as usize).checked_sub(1)?)
+        })
+        .and_then(|instruction| match instruction {
+            Bytecode::LdConst(index) => Some(index.0),
+            _ => None,
+        })
+        .filter(|index| has_code(*index));
+    if let Some(index) = loaded {
+        return names.get(&index).cloned();
+    }
+    let mut errors = names
+        .iter()
+        .filter(|(index, name)| name.starts_with('E') && has_code(**index));
+    match (errors.next(), errors.next()) {
+        (Some((_, name)), None) => Some(name.clone()),
+        _ => None,
+    }
+}
+
 #[derive(TryFromPrimitive, PartialEq, Eq)]
 #[repr(u8)]
 pub enum EventType {
@@ -70,6 +149,8 @@ pub enum EventType {
     DeleteChildObject,
     /// User-defined event
     User,
+    /// System event: transfer an object to a party, owning it jointly
+    TransferToParty,
 }
 
 /// Given a `path` and a `build_config`, build the package in that path and return the compiled modules as base64.
@@ -163,6 +244,43 @@ mod tests {
         run_move_unit_tests(Path::new(env!("CARGO_MANIFEST_DIR")), None).unwrap();
     }
 
+    #[test]
+    fn test_abort_constant_name() {
+        let balance = get_sui_framework()
+            .into_iter()
+            .find(|module| module.self_id().name().as_str() == "Balance")
+            .unwrap();
+        let (function, definition) = balance
+            .function_defs()
+            .iter()
+            .enumerate()
+            .find(|(_, definition)| {
+                let handle = balance.function_handle_at(definition.function);
+                balance.identifier_at(handle.name).as_str() == "split"
+            })
+            .unwrap();
+        let instruction = definition
+            .code
+            .as_ref()
+            .unwrap()
+            .code
+            .iter()
+            .position(|instruction| *instruction == Bytecode::Abort)
+            .unwrap();
+        let location = MoveLocation {
+            module: balance.self_id(),
+            function: function as u16,
+            function_name: Some("split".to_string()),
+            instruction: instruction as u16,
+        };
+        // ENonZero has the same value, but ENotEnough is the constant split aborts with.
+        assert_eq!(
+            abort_constant_name(&location, 0).as_deref(),
+            Some("ENotEnough")
+        );
+        assert_eq!(abort_constant_name(&location, 1), None);
+    }
+
     #[test]
     fn run_examples_move_unit_tests() {
         let examples = vec![
@@ -0,0 +1,62 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use move_binary_format::errors::PartialVMResult;
+use move_vm_runtime::native_functions::NativeContext;
+use move_vm_types::{
+    gas_schedule::NativeCostIndex,
+    loaded_data::runtime_types::Type,
+    natives::function::{native_gas, NativeResult},
+    pop_arg,
+    values::Value,
+};
+use smallvec::smallvec;
+use std::collections::VecDeque;
+use sui_types::bls::{AggregateAuthoritySignature, ProtocolPublicKeyBytes};
+use sui_types::bridge::BridgeTransfer;
+
+pub fn verify_transfer(
+    context: &mut NativeContext,
+    ty_args: Vec<Type>,
+    mut args: VecDeque<Value>,
+) -> PartialVMResult<NativeResult> {
+    debug_assert!(ty_args.is_empty());
+    debug_assert!(args.len() == 8);
+
+    let public_keys = pop_arg!(args, Vec<Value>);
+    let signature = pop_arg!(args, Vec<u8>);
+    let amount = pop_arg!(args, u64);
+    let recipient = pop_arg!(args, Vec<u8>);
+    let sender = pop_arg!(args, Vec<u8>);
+    let nonce = pop_arg!(args, u64);
+    let target_chain = pop_arg!(args, u8);
+    let source_chain = pop_arg!(args, u8);
+
+    let transfer = BridgeTransfer {
+        source_chain,
+        target_chain,
+        nonce,
+        sender,
+        recipient,
+        amount,
+    };
+    let keys = public_keys
+        .into_iter()
+        .map(|key| {
+            let key = key.value_as::<Vec<u8>>()?;
+            Ok(ProtocolPublicKeyBytes::try_from(key.as_slice()).ok())
+        })
+        .collect::<PartialVMResult<Option<Vec<_>>>>()?;
+    let valid = match (
+        keys,
+        AggregateAuthoritySignature::try_from(signature.as_slice()),
+    ) {
+        (Some(keys), Ok(signature)) => transfer.verify_aggregate(&signature, &keys).is_ok(),
+        _ => false,
+    };
+
+    // TODO: choose cost, which should grow with the number of keys.
+    let cost = native_gas(context.cost_table(), NativeCostIndex::EMIT_EVENT, 0);
+
+    Ok(NativeResult::ok(cost, smallvec![Value::bool(valid)]))
+}
@@ -0,0 +1,31 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! The costs of the Sui natives, read from the protocol config of the transaction calling them.
+//! Natives run on the thread of the VM session calling them, so the adapter sets the config of
+//! the thread driving the session. Outside of a session, as in Move unit tests, the natives are
+//! charged with the latest config.
+
+use move_core_types::gas_schedule::{GasAlgebra, InternalGasUnits};
+use std::cell::Cell;
+use sui_types::protocol_config::ProtocolConfig;
+
+thread_local! {
+    static PROTOCOL_CONFIG: Cell<Option<&'static ProtocolConfig>> = Cell::new(None);
+}
+
+/// Run `f`, charging the natives it calls on this thread with the costs of `config`.
+pub fn with_protocol_config<T>(config: &'static ProtocolConfig, f: impl FnOnce() -> T) -> T {
+    let previous = PROTOCOL_CONFIG.with(|current| current.replace(Some(config)));
+    let result = f();
+    PROTOCOL_CONFIG.with(|current| current.set(previous));
+    result
+}
+
+/// The cost of a call to `native` doing `units` of work, such as bytes hashed.
+pub(crate) fn native_cost(native: &str, units: u64) -> InternalGasUnits {
+    let config = PROTOCOL_CONFIG
+        .with(|current| current.get())
+        .unwrap_or_else(ProtocolConfig::get_for_max_version);
+    InternalGasUnits::new(config.native_cost(native).of(units))
+}
@@ -0,0 +1,133 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::natives::costs::native_cost;
+use blake2::digest::{Update, VariableOutput};
+use blake2::VarBlake2b;
+use blst::min_sig as bls;
+use blst::BLST_ERROR;
+use k256::ecdsa::signature::Verifier;
+use move_binary_format::errors::PartialVMResult;
+use move_vm_runtime::native_functions::NativeContext;
+use move_vm_types::{
+    loaded_data::runtime_types::Type, natives::function::NativeResult, pop_arg, values::Value,
+};
+use sha3::{Digest, Keccak256};
+use smallvec::smallvec;
+use std::collections::VecDeque;
+
+/// The domain of the BLS signatures checked by `Crypto::bls12381_verify`, the basic scheme of
+/// the IETF BLS signature draft with signatures in G1. Validators sign protocol messages in
+/// another domain, so their signatures never pass this check.
+const BLS_SIGNATURE_DST: &[u8] = b"BLS_SIG_BLS12381G1_XMD:SHA-256_SSWU_RO_NUL_";
+
+/// Implementation of Move native function `Crypto::keccak256(data: vector<u8>): vector<u8>`
+pub fn keccak256(
+    _context: &mut NativeContext,
+    ty_args: Vec<Type>,
+    mut args: VecDeque<Value>,
+) -> PartialVMResult<NativeResult> {
+    debug_assert!(ty_args.is_empty());
+    debug_assert!(args.len() == 1);
+
+    let data = pop_arg!(args, Vec<u8>);
+    let cost = native_cost("Crypto::keccak256", data.len() as u64);
+    let hash = Keccak256::digest(&data).to_vec();
+    Ok(NativeResult::ok(cost, smallvec![Value::vector_u8(hash)]))
+}
+
+/// Implementation of Move native function `Crypto::blake2b256(data: vector<u8>): vector<u8>`
+pub fn blake2b256(
+    _context: &mut NativeContext,
+    ty_args: Vec<Type>,
+    mut args: VecDeque<Value>,
+) -> PartialVMResult<NativeResult> {
+    debug_assert!(ty_args.is_empty());
+    debug_assert!(args.len() == 1);
+
+    let data = pop_arg!(args, Vec<u8>);
+    let cost = native_cost("Crypto::blake2b256", data.len() as u64);
+    let mut hasher = VarBlake2b::new(32).expect("32 bytes is a valid output size of blake2b");
+    hasher.update(&data);
+    let mut hash = Vec::new();
+    hasher.finalize_variable(|output| hash.extend_from_slice(output));
+    Ok(NativeResult::ok(cost, smallvec![Value::vector_u8(hash)]))
+}
+
+/// Implementation of Move native function
+/// `Crypto::ed25519_verify(signature: vector<u8>, public_key: vector<u8>, msg: vector<u8>): bool`
+pub fn ed25519_verify(
+    _context: &mut NativeContext,
+    ty_args: Vec<Type>,
+    mut args: VecDeque<Value>,
+) -> PartialVMResult<NativeResult> {
+    debug_assert!(ty_args.is_empty());
+    debug_assert!(args.len() == 3);
+
+    let msg = pop_arg!(args, Vec<u8>);
+    let public_key = pop_arg!(args, Vec<u8>);
+    let signature = pop_arg!(args, Vec<u8>);
+    let cost = native_cost("Crypto::ed25519_verify", msg.len() as u64);
+
+    let valid = match (
+        ed25519_dalek::PublicKey::from_bytes(&public_key),
+        ed25519_dalek::Signature::try_from(signature.as_slice()),
+    ) {
+        (Ok(public_key), Ok(signature)) => public_key.verify_strict(&msg, &signature).is_ok(),
+        _ => false,
+    };
+    Ok(NativeResult::ok(cost, smallvec![Value::bool(valid)]))
+}
+
+/// Implementation of Move native function
+/// `Crypto::secp256k1_verify(signature: vector<u8>, public_key: vector<u8>, msg: vector<u8>): bool`
+pub fn secp256k1_verify(
+    _context: &mut NativeContext,
+    ty_args: Vec<Type>,
+    mut args: VecDeque<Value>,
+) -> PartialVMResult<NativeResult> {
+    debug_assert!(ty_args.is_empty());
+    debug_assert!(args.len() == 3);
+
+    let msg = pop_arg!(args, Vec<u8>);
+    let public_key = pop_arg!(args, Vec<u8>);
+    let signature = pop_arg!(args, Vec<u8>);
+    let cost = native_cost("Crypto::secp256k1_verify", msg.len() as u64);
+
+    let valid = match (
+        k256::ecdsa::VerifyingKey::from_sec1_bytes(&public_key),
+        k256::ecdsa::Signature::try_from(signature.as_slice()),
+    ) {
+        (Ok(public_key), Ok(signature)) => public_key.verify(&msg, &signature).is_ok(),
+        _ => false,
+    };
+    Ok(NativeResult::ok(cost, smallvec![Value::bool(valid)]))
+}
+
+/// Implementation of Move native function
+/// `Crypto::bls12381_verify(signature: vector<u8>, public_key: vector<u8>, msg: vector<u8>): bool`
+pub fn bls12381_verify(
+    _context: &mut NativeContext,
+    ty_args: Vec<Type>,
+    mut args: VecDeque<Value>,
+) -> PartialVMResult<NativeResult> {
+    debug_assert!(ty_args.is_empty());
+    debug_assert!(args.len() == 3);
+
+    let msg = pop_arg!(args, Vec<u8>);
+    let public_key = pop_arg!(args, Vec<u8>);
+    let signature = pop_arg!(args, Vec<u8>);
+    let cost = native_cost("Crypto::bls12381_verify", msg.len() as u64);
+
+    let valid = match (
+        bls::PublicKey::from_bytes(&public_key),
+        bls::Signature::from_bytes(&signature),
+    ) {
+        (Ok(public_key), Ok(signature)) => {
+            signature.verify(true, &msg, BLS_SIGNATURE_DST, &[], &public_key, true)
+                == BLST_ERROR::BLST_SUCCESS
+        }
+        _ => false,
+    };
+    Ok(NativeResult::ok(cost, smallvec![Value::bool(valid)]))
+}
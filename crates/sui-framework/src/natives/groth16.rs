@@ -0,0 +1,179 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::natives::costs::native_cost;
+use ark_bls12_381::Bls12_381;
+use ark_bn254::Bn254;
+use ark_ec::PairingEngine;
+use ark_groth16::{PreparedVerifyingKey, Proof, VerifyingKey};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use move_binary_format::errors::PartialVMResult;
+use move_core_types::gas_schedule::{GasAlgebra, InternalGasUnits};
+use move_vm_runtime::native_functions::NativeContext;
+use move_vm_types::{
+    loaded_data::runtime_types::Type, natives::function::NativeResult, pop_arg, values::Value,
+};
+use smallvec::smallvec;
+use std::collections::VecDeque;
+
+/// The IDs of the curves in `Groth16::Curve`.
+const BN254: u8 = 0;
+const BLS12381: u8 = 1;
+
+/// The curve is not one of `Groth16::Curve`.
+const EINVALID_CURVE: u64 = 0;
+/// The verifying key is not the arkworks serialization of a verifying key on the curve.
+const EINVALID_VERIFYING_KEY: u64 = 1;
+/// There are more public inputs than `MAX_PUBLIC_INPUTS`, or they are not scalars of the curve.
+const EINVALID_PUBLIC_INPUTS: u64 = 2;
+
+/// The public inputs a proof is checked against at most, as each one costs a scalar
+/// multiplication.
+const MAX_PUBLIC_INPUTS: usize = 8;
+/// The length of the serialization of a scalar of either curve.
+const SCALAR_LENGTH: usize = 32;
+
+// The costs are dominated by the pairings computing them: one to prepare a verifying key, and
+// 3 to check a proof, to which each public input adds a scalar multiplication in G1. The
+// pairings of BLS12-381 take about twice as long as those of BN254, so each curve has costs of
+// its own in the protocol config.
+
+/// The parts of a prepared verifying key, serialized. Preparing a key computes the pairing of
+/// `alpha` and `beta`, which needs not be done again for each proof checked against it.
+struct PreparedParts {
+    vk_gamma_abc_g1: Vec<u8>,
+    alpha_g1_beta_g2: Vec<u8>,
+    gamma_g2_neg_pc: Vec<u8>,
+    delta_g2_neg_pc: Vec<u8>,
+}
+
+fn to_bytes<T: CanonicalSerialize>(value: &T) -> Option<Vec<u8>> {
+    let mut bytes = Vec::new();
+    value.serialize(&mut bytes).ok()?;
+    Some(bytes)
+}
+
+fn prepare<E: PairingEngine>(verifying_key: &[u8]) -> Option<PreparedParts> {
+    let vk = VerifyingKey::<E>::deserialize(verifying_key).ok()?;
+    Some(PreparedParts {
+        vk_gamma_abc_g1: to_bytes(&vk.gamma_abc_g1)?,
+        alpha_g1_beta_g2: to_bytes(&E::pairing(vk.alpha_g1, vk.beta_g2))?,
+        gamma_g2_neg_pc: to_bytes(&-vk.gamma_g2)?,
+        delta_g2_neg_pc: to_bytes(&-vk.delta_g2)?,
+    })
+}
+
+fn prepared_key<E: PairingEngine>(parts: &PreparedParts) -> Option<PreparedVerifyingKey<E>> {
+    let gamma_g2_neg = E::G2Affine::deserialize(&parts.gamma_g2_neg_pc[..]).ok()?;
+    let delta_g2_neg = E::G2Affine::deserialize(&parts.delta_g2_neg_pc[..]).ok()?;
+    Some(PreparedVerifyingKey {
+        vk: VerifyingKey {
+            gamma_abc_g1: Vec::deserialize(&parts.vk_gamma_abc_g1[..]).ok()?,
+            ..Default::default()
+        },
+        alpha_g1_beta_g2: E::Fqk::deserialize(&parts.alpha_g1_beta_g2[..]).ok()?,
+        gamma_g2_neg_pc: gamma_g2_neg.into(),
+        delta_g2_neg_pc: delta_g2_neg.into(),
+    })
+}
+
+/// Whether `proof` is valid for `public_inputs` against the prepared key `parts`, or `None` if
+/// the public inputs are not scalars of the curve. Malformed keys and proofs are never valid.
+fn verify<E: PairingEngine>(
+    parts: &PreparedParts,
+    public_inputs: &[u8],
+    proof: &[u8],
+) -> Option<bool> {
+    let inputs = public_inputs
+        .chunks(SCALAR_LENGTH)
+        .map(|input| E::Fr::deserialize(input).ok())
+        .collect::<Option<Vec<_>>>()?;
+    let proof = Proof::<E>::deserialize(proof).ok();
+    Some(match (prepared_key::<E>(parts), proof) {
+        // Fails if there are not as many inputs as the key expects.
+        (Some(pvk), Some(proof)) => {
+            ark_groth16::verify_proof(&pvk, &proof, &inputs).unwrap_or(false)
+        }
+        _ => false,
+    })
+}
+
+/// Implementation of Move native function
+/// `Groth16::prepare_verifying_key_internal(curve: u8, verifying_key: vector<u8>): (vector<u8>, vector<u8>, vector<u8>, vector<u8>)`
+pub fn prepare_verifying_key_internal(
+    _context: &mut NativeContext,
+    ty_args: Vec<Type>,
+    mut args: VecDeque<Value>,
+) -> PartialVMResult<NativeResult> {
+    debug_assert!(ty_args.is_empty());
+    debug_assert!(args.len() == 2);
+
+    let verifying_key = pop_arg!(args, Vec<u8>);
+    let curve = pop_arg!(args, u8);
+    let (cost, parts) = match curve {
+        BN254 => (
+            native_cost("Groth16::prepare_verifying_key_bn254", 0),
+            prepare::<Bn254>(&verifying_key),
+        ),
+        BLS12381 => (
+            native_cost("Groth16::prepare_verifying_key_bls12381", 0),
+            prepare::<Bls12_381>(&verifying_key),
+        ),
+        _ => return Ok(NativeResult::err(InternalGasUnits::new(0), EINVALID_CURVE)),
+    };
+    match parts {
+        Some(parts) => Ok(NativeResult::ok(
+            cost,
+            smallvec![
+                Value::vector_u8(parts.vk_gamma_abc_g1),
+                Value::vector_u8(parts.alpha_g1_beta_g2),
+                Value::vector_u8(parts.gamma_g2_neg_pc),
+                Value::vector_u8(parts.delta_g2_neg_pc),
+            ],
+        )),
+        None => Ok(NativeResult::err(cost, EINVALID_VERIFYING_KEY)),
+    }
+}
+
+/// Implementation of Move native function
+/// `Groth16::verify_groth16_proof_internal(curve: u8, vk_gamma_abc_g1: vector<u8>, alpha_g1_beta_g2: vector<u8>, gamma_g2_neg_pc: vector<u8>, delta_g2_neg_pc: vector<u8>, public_inputs: vector<u8>, proof: vector<u8>): bool`
+pub fn verify_groth16_proof_internal(
+    _context: &mut NativeContext,
+    ty_args: Vec<Type>,
+    mut args: VecDeque<Value>,
+) -> PartialVMResult<NativeResult> {
+    debug_assert!(ty_args.is_empty());
+    debug_assert!(args.len() == 7);
+
+    let proof = pop_arg!(args, Vec<u8>);
+    let public_inputs = pop_arg!(args, Vec<u8>);
+    let parts = PreparedParts {
+        delta_g2_neg_pc: pop_arg!(args, Vec<u8>),
+        gamma_g2_neg_pc: pop_arg!(args, Vec<u8>),
+        alpha_g1_beta_g2: pop_arg!(args, Vec<u8>),
+        vk_gamma_abc_g1: pop_arg!(args, Vec<u8>),
+    };
+    let curve = pop_arg!(args, u8);
+
+    let native = match curve {
+        BN254 => "Groth16::verify_groth16_proof_bn254",
+        BLS12381 => "Groth16::verify_groth16_proof_bls12381",
+        _ => return Ok(NativeResult::err(InternalGasUnits::new(0), EINVALID_CURVE)),
+    };
+    let input_count = public_inputs.len() / SCALAR_LENGTH;
+    if public_inputs.len() % SCALAR_LENGTH != 0 || input_count > MAX_PUBLIC_INPUTS {
+        return Ok(NativeResult::err(
+            InternalGasUnits::new(0),
+            EINVALID_PUBLIC_INPUTS,
+        ));
+    }
+    let cost = native_cost(native, input_count as u64);
+    let valid = match curve {
+        BN254 => verify::<Bn254>(&parts, &public_inputs, &proof),
+        _ => verify::<Bls12_381>(&parts, &public_inputs, &proof),
+    };
+    match valid {
+        Some(valid) => Ok(NativeResult::ok(cost, smallvec![Value::bool(valid)])),
+        None => Ok(NativeResult::err(cost, EINVALID_PUBLIC_INPUTS)),
+    }
+}
@@ -24,8 +24,8 @@ pub fn bytes_to_address(
     debug_assert!(args.len() == 1);
 
     let addr_bytes = pop_arg!(args, Vec<u8>);
-    // unwrap safe because this native function is only called from new_from_bytes,
-    // which already asserts the size of bytes to be equal of account address.
+    // unwrap safe because this native function is only called from new_from_bytes and
+    // BCS::peel_address, which already assert the size of bytes to be equal of account address.
     let addr = AccountAddress::from_bytes(addr_bytes).unwrap();
 
     // TODO: what should the cost of this be?
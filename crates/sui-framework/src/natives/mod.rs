@@ -1,71 +1,128 @@
 // Copyright (c) 2022, Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+mod bridge;
+pub mod costs;
+mod crypto;
 mod event;
+mod groth16;
 mod id;
+pub mod object_runtime;
+pub mod profile;
+mod publisher;
 mod test_scenario;
 mod transfer;
 mod tx_context;
+mod types;
+mod vdf;
 
 use move_binary_format::errors::PartialVMError;
 use move_core_types::{account_address::AccountAddress, identifier::Identifier};
 use move_vm_runtime::native_functions::{NativeFunction, NativeFunctionTable};
 use move_vm_types::values::{Struct, Value};
 
+/// The entry of the native `$native` in the table of natives, which records the gas of its calls
+/// while the calling thread profiles gas.
+macro_rules! profiled {
+    ($module:literal, $function:literal, $native:path) => {{
+        fn profiled_native(
+            context: &mut move_vm_runtime::native_functions::NativeContext,
+            ty_args: Vec<move_vm_types::loaded_data::runtime_types::Type>,
+            args: std::collections::VecDeque<Value>,
+        ) -> move_binary_format::errors::PartialVMResult<
+            move_vm_types::natives::function::NativeResult,
+        > {
+            let result = $native(context, ty_args, args);
+            if let Ok(native_result) = &result {
+                profile::record($module, $function, native_result.cost);
+            }
+            result
+        }
+        ($module, $function, profiled_native as NativeFunction)
+    }};
+}
+
 pub fn all_natives(
     move_stdlib_addr: AccountAddress,
     sui_framework_addr: AccountAddress,
 ) -> NativeFunctionTable {
     const SUI_NATIVES: &[(&str, &str, NativeFunction)] = &[
-        ("Event", "emit", event::emit),
-        ("ID", "bytes_to_address", id::bytes_to_address),
-        ("ID", "delete_id", id::delete_id),
-        ("ID", "get_versioned_id", id::get_versioned_id),
-        (
+        profiled!("BCS", "bytes_to_address", id::bytes_to_address),
+        profiled!("Bridge", "verify_transfer", bridge::verify_transfer),
+        profiled!("Crypto", "blake2b256", crypto::blake2b256),
+        profiled!("Crypto", "bls12381_verify", crypto::bls12381_verify),
+        profiled!("Crypto", "ed25519_verify", crypto::ed25519_verify),
+        profiled!("Crypto", "keccak256", crypto::keccak256),
+        profiled!("Crypto", "secp256k1_verify", crypto::secp256k1_verify),
+        profiled!("Event", "emit", event::emit),
+        profiled!(
+            "Groth16",
+            "prepare_verifying_key_internal",
+            groth16::prepare_verifying_key_internal
+        ),
+        profiled!(
+            "Groth16",
+            "verify_groth16_proof_internal",
+            groth16::verify_groth16_proof_internal
+        ),
+        profiled!("ID", "bytes_to_address", id::bytes_to_address),
+        profiled!("ID", "delete_id", id::delete_id),
+        profiled!("ID", "get_versioned_id", id::get_versioned_id),
+        profiled!("Publisher", "package_of", publisher::package_of),
+        profiled!(
             "TestScenario",
             "drop_object_for_testing",
-            test_scenario::drop_object_for_testing,
+            test_scenario::drop_object_for_testing
         ),
-        (
+        profiled!(
             "TestScenario",
             "emit_wrapped_object_events",
-            test_scenario::emit_wrapped_object_events,
+            test_scenario::emit_wrapped_object_events
         ),
-        (
+        profiled!(
             "TestScenario",
             "get_account_owned_inventory",
-            test_scenario::get_account_owned_inventory,
+            test_scenario::get_account_owned_inventory
         ),
-        (
+        profiled!(
             "TestScenario",
             "get_object_owned_inventory",
-            test_scenario::get_object_owned_inventory,
+            test_scenario::get_object_owned_inventory
         ),
-        (
+        profiled!(
             "TestScenario",
             "get_unowned_inventory",
-            test_scenario::get_unowned_inventory,
+            test_scenario::get_unowned_inventory
         ),
-        ("TestScenario", "num_events", test_scenario::num_events),
-        (
+        profiled!("TestScenario", "num_events", test_scenario::num_events),
+        profiled!(
             "TestScenario",
             "update_object",
-            test_scenario::update_object,
+            test_scenario::update_object
         ),
-        (
+        profiled!(
             "Transfer",
             "delete_child_object_internal",
-            transfer::delete_child_object_internal,
+            transfer::delete_child_object_internal
+        ),
+        profiled!("Transfer", "transfer_internal", transfer::transfer_internal),
+        profiled!("Transfer", "freeze_object", transfer::freeze_object),
+        profiled!("Transfer", "share_object", transfer::share_object),
+        profiled!("Transfer", "transfer_to_party", transfer::transfer_to_party),
+        profiled!(
+            "Transfer",
+            "take_child_object_internal",
+            object_runtime::take_child_object_internal
         ),
-        ("Transfer", "transfer_internal", transfer::transfer_internal),
-        ("Transfer", "freeze_object", transfer::freeze_object),
-        ("Transfer", "share_object", transfer::share_object),
-        ("TxContext", "derive_id", tx_context::derive_id),
-        (
+        profiled!("TxContext", "derive_id", tx_context::derive_id),
+        profiled!(
             "TxContext",
             "new_signer_from_address",
-            tx_context::new_signer_from_address,
+            tx_context::new_signer_from_address
         ),
+        profiled!("Types", "type_name_internal", types::type_name_internal),
+        profiled!("VDF", "hash_to_input", vdf::hash_to_input),
+        profiled!("VDF", "verify_wesolowski", vdf::verify_wesolowski),
     ];
     SUI_NATIVES
         .iter()
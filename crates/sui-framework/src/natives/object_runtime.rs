@@ -0,0 +1,120 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! The state a Move call keeps across the natives it calls, as an extension of the native
+//! context: the child objects loaded on first access through `Transfer::take_child_object`,
+//! rather than listed among the inputs of the transaction. The adapter hands the extension to
+//! the session of each Move call, and treats the children taken as if they had been passed by
+//! value once the call returns.
+
+use better_any::{Tid, TidAble};
+use move_binary_format::errors::{PartialVMError, PartialVMResult};
+use move_core_types::{
+    account_address::AccountAddress, language_storage::TypeTag, vm_status::StatusCode,
+};
+use move_vm_runtime::native_functions::NativeContext;
+use move_vm_types::{
+    gas_schedule::NativeCostIndex,
+    loaded_data::runtime_types::Type,
+    natives::function::{native_gas, NativeResult},
+    pop_arg,
+    values::Value,
+};
+use smallvec::smallvec;
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+use sui_types::{base_types::ObjectID, object::Object, storage::ChildObjectResolver};
+
+/// The child is not owned by the parent, or is not in the store.
+const ECHILD_NOT_FOUND: u64 = 1;
+/// The child is already given to the Move call, among its arguments or taken before.
+const ECHILD_ALREADY_LOADED: u64 = 2;
+/// The child is not of the type of its `ChildRef`.
+const ECHILD_TYPE_MISMATCH: u64 = 3;
+
+#[derive(Tid)]
+pub struct ObjectRuntime<'a> {
+    resolver: &'a dyn ChildObjectResolver,
+    /// The objects passed to the Move call as arguments.
+    arguments: BTreeSet<ObjectID>,
+    taken: BTreeMap<ObjectID, Object>,
+}
+
+impl<'a> ObjectRuntime<'a> {
+    pub fn new(resolver: &'a dyn ChildObjectResolver, arguments: BTreeSet<ObjectID>) -> Self {
+        Self {
+            resolver,
+            arguments,
+            taken: BTreeMap::new(),
+        }
+    }
+
+    /// The child objects taken during the Move call, as they were loaded.
+    pub fn into_taken(self) -> BTreeMap<ObjectID, Object> {
+        self.taken
+    }
+
+    /// Load the contents of the child `child` of `parent`, of type `type_`, or return the code
+    /// to abort with.
+    fn take(
+        &mut self,
+        parent: ObjectID,
+        child: ObjectID,
+        type_: &TypeTag,
+    ) -> PartialVMResult<Result<Vec<u8>, u64>> {
+        // Taking a child given to the call already would duplicate it.
+        if self.arguments.contains(&child) || self.taken.contains_key(&child) {
+            return Ok(Err(ECHILD_ALREADY_LOADED));
+        }
+        let object = match self.resolver.read_child_object(&parent, &child) {
+            Ok(Some(object)) => object,
+            Ok(None) => return Ok(Err(ECHILD_NOT_FOUND)),
+            Err(error) => {
+                return Err(PartialVMError::new(StatusCode::STORAGE_ERROR)
+                    .with_message(format!("Cannot read child object {child}: {error}")))
+            }
+        };
+        let contents = match object.data.try_as_move() {
+            Some(move_object) if TypeTag::Struct(move_object.type_.clone()) == *type_ => {
+                move_object.contents().to_vec()
+            }
+            _ => return Ok(Err(ECHILD_TYPE_MISMATCH)),
+        };
+        self.taken.insert(child, object);
+        Ok(Ok(contents))
+    }
+}
+
+/// Implementation of Move native function
+/// `take_child_object_internal<T: key>(parent: address, child: address): T`
+/// The child is read from the store, and handed to the Move call by value. The read is charged
+/// by the adapter, along with the reads of the inputs.
+pub fn take_child_object_internal(
+    context: &mut NativeContext,
+    mut ty_args: Vec<Type>,
+    mut args: VecDeque<Value>,
+) -> PartialVMResult<NativeResult> {
+    debug_assert!(ty_args.len() == 1);
+    debug_assert!(args.len() == 2);
+
+    let ty = ty_args.pop().unwrap();
+    let child = pop_arg!(args, AccountAddress);
+    let parent = pop_arg!(args, AccountAddress);
+    // TODO: adjust native_gas cost size base.
+    let cost = native_gas(context.cost_table(), NativeCostIndex::EMIT_EVENT, 1);
+
+    let type_tag = context.type_to_type_tag(&ty)?;
+    let layout = match context.type_to_type_layout(&ty)? {
+        Some(layout) => layout,
+        None => return Ok(NativeResult::err(cost, ECHILD_TYPE_MISMATCH)),
+    };
+    let runtime: &mut ObjectRuntime = context.extensions_mut().get_mut();
+    let contents = match runtime.take(parent.into(), child.into(), &type_tag)? {
+        Ok(contents) => contents,
+        Err(code) => return Ok(NativeResult::err(cost, code)),
+    };
+    let child = Value::simple_deserialize(&contents, &layout).ok_or_else(|| {
+        PartialVMError::new(StatusCode::FAILED_TO_DESERIALIZE_RESOURCE)
+            .with_message(format!("Cannot deserialize child object {child}"))
+    })?;
+    Ok(NativeResult::ok(cost, smallvec![child]))
+}
@@ -0,0 +1,51 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! The gas of the Sui natives called by the current thread, recorded while profiling. Natives run
+//! on the thread of the VM session calling them, so the profile of a call into Move is the one of
+//! the thread driving the session.
+
+use move_core_types::gas_schedule::{GasAlgebra, GasCarrier, InternalGasUnits};
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use sui_types::gas::{to_external, NativeGas};
+
+thread_local! {
+    /// The number of calls and the internal gas units of each native called so far.
+    static NATIVE_GAS: RefCell<Option<BTreeMap<(&'static str, &'static str), (u64, GasCarrier)>>> =
+        RefCell::new(None);
+}
+
+/// Record the gas of the natives the thread calls from now on.
+pub fn start_profiling() {
+    NATIVE_GAS.with(|gas| *gas.borrow_mut() = Some(BTreeMap::new()));
+}
+
+/// Stop recording the gas of natives, and return the gas of the natives called since
+/// `start_profiling`, in the order of the natives.
+pub fn finish_profiling() -> Vec<NativeGas> {
+    NATIVE_GAS
+        .with(|gas| gas.borrow_mut().take())
+        .unwrap_or_default()
+        .into_iter()
+        .map(|((module, function), (calls, cost))| NativeGas {
+            function: format!("{module}::{function}"),
+            calls,
+            gas_used: to_external(InternalGasUnits::new(cost)).get(),
+        })
+        .collect()
+}
+
+pub(crate) fn record(
+    module: &'static str,
+    function: &'static str,
+    cost: InternalGasUnits<GasCarrier>,
+) {
+    NATIVE_GAS.with(|gas| {
+        if let Some(gas) = gas.borrow_mut().as_mut() {
+            let (calls, total) = gas.entry((module, function)).or_default();
+            *calls += 1;
+            *total += cost.get();
+        }
+    });
+}
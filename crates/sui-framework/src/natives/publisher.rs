@@ -0,0 +1,39 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use move_binary_format::errors::PartialVMResult;
+use move_core_types::language_storage::TypeTag;
+use move_vm_runtime::native_functions::NativeContext;
+use move_vm_types::{
+    gas_schedule::NativeCostIndex,
+    loaded_data::runtime_types::Type,
+    natives::function::{native_gas, NativeResult},
+    values::Value,
+};
+use smallvec::smallvec;
+use std::collections::VecDeque;
+
+/// Implementation of Move native function `Publisher::package_of<T>(): address`
+/// Returns the address of the package declaring the struct `T`.
+pub fn package_of(
+    context: &mut NativeContext,
+    mut ty_args: Vec<Type>,
+    args: VecDeque<Value>,
+) -> PartialVMResult<NativeResult> {
+    debug_assert!(ty_args.len() == 1);
+    debug_assert!(args.is_empty());
+
+    let ty = ty_args.pop().unwrap();
+
+    // TODO: what should the cost of this be?
+    let cost = native_gas(context.cost_table(), NativeCostIndex::SIGNER_BORROW, 0);
+
+    match context.type_to_type_tag(&ty)? {
+        TypeTag::Struct(tag) => Ok(NativeResult::ok(
+            cost,
+            smallvec![Value::address(tag.address)],
+        )),
+        // Only structs are declared by packages.
+        _ => Ok(NativeResult::err(cost, 0)),
+    }
+}
@@ -118,7 +118,8 @@ fn get_global_inventory(events: &[Event]) -> Result<Inventory, u64> {
             EventType::TransferToAddress
             | EventType::TransferToObject
             | EventType::FreezeObject
-            | EventType::ShareObject => {
+            | EventType::ShareObject
+            | EventType::TransferToParty => {
                 let owner = get_new_owner(&event_type, recipient.clone());
                 let signer = if event_type == EventType::TransferToObject {
                     let parent_id = ObjectID::try_from(recipient.as_slice()).unwrap();
@@ -175,6 +176,7 @@ fn get_new_owner(event_type: &EventType, recipient: Vec<u8>) -> Owner {
             Owner::AddressOwner(SuiAddress::try_from(recipient).unwrap())
         }
         EventType::TransferToObject => Owner::ObjectOwner(SuiAddress::try_from(recipient).unwrap()),
+        EventType::TransferToParty => Owner::Party(SuiAddress::try_from(recipient).unwrap()),
         _ => panic!("Unrecognized event_type"),
     }
 }
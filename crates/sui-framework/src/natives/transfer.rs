@@ -93,6 +93,28 @@ pub fn share_object(
     }
 }
 
+/// Implementation of Move native function
+/// `transfer_to_party<T: key>(obj: T, party: address)`
+pub fn transfer_to_party(
+    context: &mut NativeContext,
+    mut ty_args: Vec<Type>,
+    mut args: VecDeque<Value>,
+) -> PartialVMResult<NativeResult> {
+    debug_assert!(ty_args.len() == 1);
+    debug_assert!(args.len() == 2);
+
+    let ty = ty_args.pop().unwrap();
+    let party = pop_arg!(args, AccountAddress);
+    let obj = args.pop_back().unwrap();
+    let event_type = EventType::TransferToParty;
+    let cost = native_gas(context.cost_table(), NativeCostIndex::EMIT_EVENT, 1);
+    if context.save_event(party.to_vec(), event_type as u64, ty, obj)? {
+        Ok(NativeResult::ok(cost, smallvec![]))
+    } else {
+        Ok(NativeResult::err(cost, 0))
+    }
+}
+
 /// Implementation of Move native function
 /// `delete_child_object_internal<T: key>(child: T)`
 pub fn delete_child_object_internal(
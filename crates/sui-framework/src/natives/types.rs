@@ -0,0 +1,40 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::natives::costs::native_cost;
+use move_binary_format::errors::PartialVMResult;
+use move_vm_runtime::native_functions::NativeContext;
+use move_vm_types::{
+    loaded_data::runtime_types::Type, natives::function::NativeResult, values::Value,
+};
+use smallvec::smallvec;
+use std::collections::VecDeque;
+
+/// The name of the type is longer than `MAX_TYPE_NAME_LENGTH`.
+const ETYPE_NAME_TOO_LONG: u64 = 0;
+
+/// The length of the longest type name, so that a name costs a bounded amount to compare and
+/// to store.
+const MAX_TYPE_NAME_LENGTH: usize = 4096;
+
+/// Implementation of Move native function `Types::type_name_internal<T>(): vector<u8>`
+pub fn type_name_internal(
+    context: &mut NativeContext,
+    mut ty_args: Vec<Type>,
+    args: VecDeque<Value>,
+) -> PartialVMResult<NativeResult> {
+    debug_assert!(ty_args.len() == 1);
+    debug_assert!(args.is_empty());
+
+    let ty = ty_args.pop().unwrap();
+    let name = context.type_to_type_tag(&ty)?.to_string();
+    let length = name.len().min(MAX_TYPE_NAME_LENGTH) as u64;
+    let cost = native_cost("Types::type_name", length);
+    if name.len() > MAX_TYPE_NAME_LENGTH {
+        return Ok(NativeResult::err(cost, ETYPE_NAME_TOO_LONG));
+    }
+    Ok(NativeResult::ok(
+        cost,
+        smallvec![Value::vector_u8(name.into_bytes())],
+    ))
+}
@@ -0,0 +1,201 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Verification of Wesolowski VDFs in an RSA group, whose modulus is given by the contract and
+//! must be one nobody knows the factorization of. The output `y` of `x` after `T` iterations is
+//! `x^(2^T)`, and the proof `π` is `x^(2^T / l)` for the prime `l` derived from the hash of
+//! the modulus, `x`, `y` and `T`, so that `π^l * x^(2^T mod l) = y`.
+//!
+//! The elements are taken up to their sign, as `-1` is of order 2 in the group and would
+//! otherwise let a prover flip the sign of an output: an element is represented by the least of
+//! `v` and `N - v`, and a check passes on either sign.
+
+use crate::natives::costs::native_cost;
+use move_binary_format::errors::PartialVMResult;
+use move_vm_runtime::native_functions::NativeContext;
+use move_vm_types::{
+    loaded_data::runtime_types::Type, natives::function::NativeResult, pop_arg, values::Value,
+};
+use num_bigint::BigUint;
+use sha3::{Digest, Sha3_256};
+use smallvec::smallvec;
+use std::collections::VecDeque;
+
+/// The modulus is more than `MAX_MODULUS_LENGTH` bytes long, or is even.
+const EINVALID_MODULUS: u64 = 0;
+
+/// The length of the longest modulus, of 4096 bits.
+const MAX_MODULUS_LENGTH: usize = 512;
+
+const CHALLENGE_DOMAIN: &[u8] = b"SUI_VDF_CHALLENGE";
+const INPUT_DOMAIN: &[u8] = b"SUI_VDF_INPUT";
+/// The bits of the challenge prime `l`.
+const CHALLENGE_BITS: usize = 128;
+/// The candidates for the challenge tried at most. About one in 44 odd candidates is prime.
+const CHALLENGE_TRIES: u32 = 10_000;
+/// The rounds of the Miller-Rabin test of a candidate, with bases derived from its hash, so
+/// that a prover can't pick candidates passing them.
+const MILLER_RABIN_ROUNDS: u32 = 32;
+
+// Verifying is two exponentiations by exponents of 128 bits, whose cost grows with the square of
+// the length of the modulus, and the search for the challenge, whose cost is fixed, so it is
+// charged per square byte of the modulus.
+
+fn canonical(value: BigUint, modulus: &BigUint) -> BigUint {
+    let negated = modulus - &value;
+    value.min(negated)
+}
+
+fn length_prefixed(hasher: &mut Sha3_256, value: &BigUint) {
+    let bytes = value.to_bytes_be();
+    hasher.update((bytes.len() as u32).to_le_bytes());
+    hasher.update(&bytes);
+}
+
+/// Miller-Rabin test of the odd candidate `n`, of `CHALLENGE_BITS` bits.
+fn is_probable_prime(n: &BigUint) -> bool {
+    let one = BigUint::from(1u32);
+    let two = BigUint::from(2u32);
+    let n_minus_one = n - &one;
+    let s = n_minus_one.trailing_zeros().unwrap_or(0);
+    let d = &n_minus_one >> s;
+    let n_bytes = n.to_bytes_be();
+    let bases_range = n - BigUint::from(3u32);
+    'rounds: for round in 0..MILLER_RABIN_ROUNDS {
+        let mut hasher = Sha3_256::default();
+        hasher.update(&n_bytes);
+        hasher.update(round.to_le_bytes());
+        let base = &two + BigUint::from_bytes_be(&hasher.finalize()) % &bases_range;
+        let mut x = base.modpow(&d, n);
+        if x == one || x == n_minus_one {
+            continue;
+        }
+        for _ in 1..s {
+            x = &x * &x % n;
+            if x == n_minus_one {
+                continue 'rounds;
+            }
+        }
+        return false;
+    }
+    true
+}
+
+/// The challenge prime `l` of the output `y` of `x` after `iterations`.
+fn challenge(modulus: &BigUint, x: &BigUint, y: &BigUint, iterations: u64) -> Option<BigUint> {
+    let mut hasher = Sha3_256::default();
+    hasher.update(CHALLENGE_DOMAIN);
+    length_prefixed(&mut hasher, modulus);
+    length_prefixed(&mut hasher, x);
+    length_prefixed(&mut hasher, y);
+    hasher.update(iterations.to_le_bytes());
+    (0..CHALLENGE_TRIES).find_map(|counter| {
+        let mut hasher = hasher.clone();
+        hasher.update(counter.to_le_bytes());
+        let mut candidate = BigUint::from_bytes_be(&hasher.finalize()[..CHALLENGE_BITS / 8]);
+        candidate.set_bit(CHALLENGE_BITS as u64 - 1, true);
+        candidate.set_bit(0, true);
+        is_probable_prime(&candidate).then(|| candidate)
+    })
+}
+
+/// Whether `proof` shows that `output` is the output of `input` after `iterations`.
+fn verify(
+    modulus: &BigUint,
+    input: BigUint,
+    output: BigUint,
+    proof: BigUint,
+    iterations: u64,
+) -> bool {
+    let half = modulus >> 1u32;
+    let one = BigUint::from(1u32);
+    // The elements must be canonical, and the input neither 0 nor 1, whose outputs are known.
+    if input <= one || input > half || output > half || proof > half || iterations == 0 {
+        return false;
+    }
+    let l = match challenge(modulus, &input, &output, iterations) {
+        Some(l) => l,
+        None => return false,
+    };
+    let r = BigUint::from(2u32).modpow(&BigUint::from(iterations), &l);
+    let result = proof.modpow(&l, modulus) * input.modpow(&r, modulus) % modulus;
+    canonical(result, modulus) == output
+}
+
+fn read_modulus(modulus: &[u8]) -> Option<BigUint> {
+    if modulus.len() > MAX_MODULUS_LENGTH {
+        return None;
+    }
+    let modulus = BigUint::from_bytes_be(modulus);
+    (modulus.bit(0) && modulus > BigUint::from(3u32)).then(|| modulus)
+}
+
+/// Implementation of Move native function
+/// `VDF::verify_wesolowski(modulus: vector<u8>, input: vector<u8>, output: vector<u8>, proof: vector<u8>, iterations: u64): bool`
+pub fn verify_wesolowski(
+    _context: &mut NativeContext,
+    ty_args: Vec<Type>,
+    mut args: VecDeque<Value>,
+) -> PartialVMResult<NativeResult> {
+    debug_assert!(ty_args.is_empty());
+    debug_assert!(args.len() == 5);
+
+    let iterations = pop_arg!(args, u64);
+    let proof = pop_arg!(args, Vec<u8>);
+    let output = pop_arg!(args, Vec<u8>);
+    let input = pop_arg!(args, Vec<u8>);
+    let modulus_bytes = pop_arg!(args, Vec<u8>);
+
+    let length = modulus_bytes.len().min(MAX_MODULUS_LENGTH) as u64;
+    let cost = native_cost("VDF::verify_wesolowski", length * length);
+    let modulus = match read_modulus(&modulus_bytes) {
+        Some(modulus) => modulus,
+        None => return Ok(NativeResult::err(cost, EINVALID_MODULUS)),
+    };
+    let valid = verify(
+        &modulus,
+        BigUint::from_bytes_be(&input),
+        BigUint::from_bytes_be(&output),
+        BigUint::from_bytes_be(&proof),
+        iterations,
+    );
+    Ok(NativeResult::ok(cost, smallvec![Value::bool(valid)]))
+}
+
+/// Implementation of Move native function
+/// `VDF::hash_to_input(modulus: vector<u8>, message: vector<u8>): vector<u8>`
+pub fn hash_to_input(
+    _context: &mut NativeContext,
+    ty_args: Vec<Type>,
+    mut args: VecDeque<Value>,
+) -> PartialVMResult<NativeResult> {
+    debug_assert!(ty_args.is_empty());
+    debug_assert!(args.len() == 2);
+
+    let message = pop_arg!(args, Vec<u8>);
+    let modulus_bytes = pop_arg!(args, Vec<u8>);
+
+    let length = modulus_bytes.len().min(MAX_MODULUS_LENGTH) + message.len();
+    let cost = native_cost("VDF::hash_to_input", length as u64);
+    let modulus = match read_modulus(&modulus_bytes) {
+        Some(modulus) => modulus,
+        None => return Ok(NativeResult::err(cost, EINVALID_MODULUS)),
+    };
+    // Hash to 16 bytes more than the modulus, so that the input is close to uniform.
+    let wanted = (modulus.bits() as usize + 7) / 8 + 16;
+    let mut bytes = Vec::with_capacity(wanted + 32);
+    let mut counter = 0u32;
+    while bytes.len() < wanted {
+        let mut hasher = Sha3_256::default();
+        hasher.update(INPUT_DOMAIN);
+        hasher.update(counter.to_le_bytes());
+        hasher.update(&message);
+        bytes.extend_from_slice(&hasher.finalize());
+        counter += 1;
+    }
+    let input = canonical(BigUint::from_bytes_be(&bytes) % &modulus, &modulus);
+    Ok(NativeResult::ok(
+        cost,
+        smallvec![Value::vector_u8(input.to_bytes_be())],
+    ))
+}
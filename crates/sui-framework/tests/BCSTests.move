@@ -0,0 +1,82 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+#[test_only]
+module Sui::BCSTests {
+    use Std::BCS as StdBCS;
+    use Std::Vector;
+
+    use Sui::BCS;
+    use Sui::ID;
+    use Sui::TxContext;
+
+    const EValueMismatch: u64 = 0;
+
+    struct Info has drop {
+        a: bool,
+        b: u8,
+        c: u64,
+        d: u128,
+        e: vector<u8>,
+        f: vector<address>,
+        g: vector<u64>,
+    }
+
+    #[test]
+    fun test_peel_struct() {
+        let info = Info {
+            a: true,
+            b: 100,
+            c: 9999,
+            d: 112333,
+            e: x"0102",
+            f: vector[@0x1, @0x2],
+            g: vector[1, 2, 3],
+        };
+        let bcs = BCS::new(StdBCS::to_bytes(&info));
+        assert!(BCS::peel_bool(&mut bcs), EValueMismatch);
+        assert!(BCS::peel_u8(&mut bcs) == 100, EValueMismatch);
+        assert!(BCS::peel_u64(&mut bcs) == 9999, EValueMismatch);
+        assert!(BCS::peel_u128(&mut bcs) == 112333, EValueMismatch);
+        assert!(BCS::peel_vec_u8(&mut bcs) == x"0102", EValueMismatch);
+        assert!(BCS::peel_vec_address(&mut bcs) == vector[@0x1, @0x2], EValueMismatch);
+        assert!(BCS::peel_vec_u64(&mut bcs) == vector[1, 2, 3], EValueMismatch);
+        assert!(BCS::remaining(&bcs) == 0, EValueMismatch);
+    }
+
+    #[test]
+    fun test_peel_id() {
+        let ctx = TxContext::dummy();
+        let id = TxContext::new_id(&mut ctx);
+        let bytes = StdBCS::to_bytes(&id);
+        Vector::push_back(&mut bytes, 7);
+        let bcs = BCS::new(bytes);
+        assert!(&BCS::peel_id(&mut bcs) == ID::inner(&id), EValueMismatch);
+        // The version of the ID follows.
+        assert!(BCS::peel_u64(&mut bcs) == 0, EValueMismatch);
+        assert!(BCS::into_remainder_bytes(bcs) == x"07", EValueMismatch);
+        ID::delete(id);
+    }
+
+    #[test]
+    #[expected_failure(abort_code = 0)]
+    fun test_peel_past_the_end() {
+        let bcs = BCS::new(x"01020304");
+        BCS::peel_u64(&mut bcs);
+    }
+
+    #[test]
+    #[expected_failure(abort_code = 1)]
+    fun test_peel_invalid_bool() {
+        let bcs = BCS::new(x"02");
+        BCS::peel_bool(&mut bcs);
+    }
+
+    #[test]
+    #[expected_failure(abort_code = 2)]
+    fun test_peel_length_longer_than_bytes() {
+        // A length of 128, followed by a single byte.
+        let bcs = BCS::new(x"800101");
+        BCS::peel_vec_u8(&mut bcs);
+    }
+}
@@ -0,0 +1,69 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+#[test_only]
+module Sui::BridgeTests {
+    use Sui::Balance;
+    use Sui::Bridge;
+    use Sui::SUI::SUI;
+
+    #[test]
+    fun test_lock() {
+        let bridge = Bridge::new();
+        let nonce = Bridge::lock(&mut bridge, Balance::create_for_testing<SUI>(100), 1, x"0102", @0x1);
+        assert!(nonce == 0, 0);
+        let nonce = Bridge::lock(&mut bridge, Balance::create_for_testing<SUI>(50), 2, x"0304", @0x2);
+        assert!(nonce == 1, 0);
+        assert!(Bridge::next_nonce(&bridge) == 2, 0);
+        assert!(Bridge::treasury_value(&bridge) == 150, 0);
+        Bridge::destroy_for_testing(bridge);
+    }
+
+    #[test]
+    #[expected_failure(abort_code = 0)]
+    fun test_lock_of_nothing_is_rejected() {
+        let bridge = Bridge::new();
+        Bridge::lock(&mut bridge, Balance::zero<SUI>(), 1, x"0102", @0x1);
+        Bridge::destroy_for_testing(bridge);
+    }
+
+    #[test]
+    #[expected_failure(abort_code = 0)]
+    fun test_lock_to_sui_is_rejected() {
+        let bridge = Bridge::new();
+        Bridge::lock(&mut bridge, Balance::create_for_testing<SUI>(100), 0, x"0102", @0x1);
+        Bridge::destroy_for_testing(bridge);
+    }
+
+    #[test]
+    #[expected_failure(abort_code = 2)]
+    fun test_claim_without_quorum_is_rejected() {
+        let bridge = Bridge::new();
+        Bridge::lock(&mut bridge, Balance::create_for_testing<SUI>(100), 1, x"0102", @0x1);
+        let balance = Bridge::claim(
+            &mut bridge,
+            1,
+            0,
+            x"0102",
+            @0x1,
+            100,
+            vector[],
+            vector[],
+            100 /* signers_stake */,
+            101 /* quorum_stake_threshold */,
+        );
+        Balance::destroy_for_testing(balance);
+        Bridge::destroy_for_testing(bridge);
+    }
+
+    #[test]
+    #[expected_failure(abort_code = 0)]
+    fun test_claim_from_sui_is_rejected() {
+        let bridge = Bridge::new();
+        let balance = Bridge::claim(
+            &mut bridge, 0, 0, x"0102", @0x1, 100, vector[], vector[], 100, 100,
+        );
+        Balance::destroy_for_testing(balance);
+        Bridge::destroy_for_testing(bridge);
+    }
+}
@@ -0,0 +1,50 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+#[test_only]
+module Sui::CryptoTests {
+    use Sui::Crypto;
+
+    const EHashMismatch: u64 = 0;
+    const EVerificationMismatch: u64 = 1;
+
+    #[test]
+    fun test_keccak256() {
+        assert!(
+            Crypto::keccak256(b"") == x"c5d2460186f7233c927e7db2dcc703c0e500b653ca82273b7bfad8045d85a470",
+            EHashMismatch
+        );
+        assert!(
+            Crypto::keccak256(b"abc") == x"4e03657aea45a94fc7d47ba826c8d667c0d1e6e33a64a036ec44f58fa12d6c45",
+            EHashMismatch
+        );
+    }
+
+    #[test]
+    fun test_blake2b256() {
+        assert!(
+            Crypto::blake2b256(b"") == x"0e5751c026e543b2e8ab2eb06099daa1d1e5df47778f7787faab45cdf12fe3a8",
+            EHashMismatch
+        );
+        assert!(
+            Crypto::blake2b256(b"abc") == x"bddd813c634239723171ef3fee98579b94964e3bb1cb3e427262c8c068d52319",
+            EHashMismatch
+        );
+    }
+
+    #[test]
+    fun test_ed25519_verify() {
+        // The first test vector of RFC 8032.
+        let public_key = x"d75a980182b10ab7d54bfed3c964073a0ee172f3daa62325af021a68f707511a";
+        let signature = x"e5564300c360ac729086e2cc806e828a84877f1eb8e5d974d873e065224901555fb8821590a33bacc61e39701cf9b46bd25bf5f0595bbe24655141438e7a100b";
+        assert!(Crypto::ed25519_verify(signature, public_key, b""), EVerificationMismatch);
+        assert!(!Crypto::ed25519_verify(signature, public_key, b"a"), EVerificationMismatch);
+        assert!(!Crypto::ed25519_verify(signature, x"00", b""), EVerificationMismatch);
+    }
+
+    #[test]
+    fun test_malformed_signatures_fail_verification() {
+        assert!(!Crypto::secp256k1_verify(x"00", x"00", b"msg"), EVerificationMismatch);
+        assert!(!Crypto::bls12381_verify(x"00", x"00", b"msg"), EVerificationMismatch);
+    }
+}
@@ -0,0 +1,66 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+#[test_only]
+module Sui::DenyListTests {
+    use Sui::Coin::{Self, TreasuryCap};
+    use Sui::DenyList::{Self, DenyList};
+    use Sui::TestScenario::{Self, ctx};
+
+    struct REGULATED has drop {}
+
+    const ISSUER: address = @0xA;
+    const USER: address = @0xB;
+
+    #[test]
+    fun add_and_remove() {
+        let scenario = &mut TestScenario::begin(&ISSUER);
+        let cap = Coin::create_currency(REGULATED {}, ctx(scenario));
+        DenyList::create(&cap, ctx(scenario));
+        TestScenario::next_tx(scenario, &ISSUER);
+        {
+            let list_wrapper = TestScenario::take_shared<DenyList<REGULATED>>(scenario);
+            let list = TestScenario::borrow_mut(&mut list_wrapper);
+            assert!(!DenyList::contains(list, USER), 0);
+            DenyList::add(list, &cap, USER);
+            assert!(DenyList::contains(list, USER), 1);
+            DenyList::remove(list, &cap, USER);
+            assert!(!DenyList::contains(list, USER), 2);
+            TestScenario::return_shared(scenario, list_wrapper);
+        };
+        Coin::transfer_cap(cap, ISSUER);
+    }
+
+    #[test]
+    #[expected_failure(abort_code = 0)]
+    fun add_twice() {
+        let scenario = &mut TestScenario::begin(&ISSUER);
+        let cap = Coin::create_currency(REGULATED {}, ctx(scenario));
+        DenyList::create(&cap, ctx(scenario));
+        TestScenario::next_tx(scenario, &ISSUER);
+        {
+            let list_wrapper = TestScenario::take_shared<DenyList<REGULATED>>(scenario);
+            let list = TestScenario::borrow_mut(&mut list_wrapper);
+            DenyList::add(list, &cap, USER);
+            DenyList::add(list, &cap, USER);
+            TestScenario::return_shared(scenario, list_wrapper);
+        };
+        Coin::transfer_cap(cap, ISSUER);
+    }
+
+    #[test]
+    #[expected_failure(abort_code = 1)]
+    fun remove_missing() {
+        let scenario = &mut TestScenario::begin(&ISSUER);
+        let cap = Coin::create_currency(REGULATED {}, ctx(scenario));
+        DenyList::create(&cap, ctx(scenario));
+        TestScenario::next_tx(scenario, &ISSUER);
+        {
+            let list_wrapper = TestScenario::take_shared<DenyList<REGULATED>>(scenario);
+            let list = TestScenario::borrow_mut(&mut list_wrapper);
+            DenyList::remove(list, &cap, USER);
+            TestScenario::return_shared(scenario, list_wrapper);
+        };
+        Coin::transfer_cap(cap, ISSUER);
+    }
+}
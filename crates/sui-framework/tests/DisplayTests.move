@@ -0,0 +1,70 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+#[test_only]
+module Sui::DisplayTests {
+    use Std::ASCII;
+    use Sui::Display;
+    use Sui::Publisher;
+    use Sui::TestScenario::{Self, ctx};
+    use Sui::UTF8::{Self, String};
+
+    struct Hero has drop {}
+
+    fun str(bytes: vector<u8>): String {
+        UTF8::from_ascii(ASCII::string(bytes))
+    }
+
+    #[test]
+    fun add_edit_remove() {
+        let scenario = &mut TestScenario::begin(&@0x1);
+        let publisher = Publisher::new_for_testing<Hero>(ctx(scenario));
+        let display = Display::new<Hero>(&publisher, ctx(scenario));
+        Display::add(&mut display, str(b"name"), str(b"{name}"));
+        Display::add(&mut display, str(b"image_url"), str(b"https://example.com/{id}"));
+        assert!(Display::length(&display) == 2, 0);
+        assert!(Display::template(&display, &str(b"name")) == &str(b"{name}"), 1);
+
+        Display::edit(&mut display, str(b"name"), str(b"Hero {name}"));
+        assert!(Display::template(&display, &str(b"name")) == &str(b"Hero {name}"), 2);
+
+        Display::remove(&mut display, str(b"image_url"));
+        assert!(Display::length(&display) == 1, 3);
+        Display::keep(display, ctx(scenario));
+        Publisher::burn(publisher);
+    }
+
+    #[test]
+    #[expected_failure(abort_code = 0)]
+    fun add_existing() {
+        let scenario = &mut TestScenario::begin(&@0x1);
+        let publisher = Publisher::new_for_testing<Hero>(ctx(scenario));
+        let display = Display::new<Hero>(&publisher, ctx(scenario));
+        Display::add(&mut display, str(b"name"), str(b"{name}"));
+        Display::add(&mut display, str(b"name"), str(b"{name}"));
+        Display::keep(display, ctx(scenario));
+        Publisher::burn(publisher);
+    }
+
+    #[test]
+    #[expected_failure(abort_code = 1)]
+    fun edit_missing() {
+        let scenario = &mut TestScenario::begin(&@0x1);
+        let publisher = Publisher::new_for_testing<Hero>(ctx(scenario));
+        let display = Display::new<Hero>(&publisher, ctx(scenario));
+        Display::edit(&mut display, str(b"name"), str(b"{name}"));
+        Display::keep(display, ctx(scenario));
+        Publisher::burn(publisher);
+    }
+
+    #[test]
+    #[expected_failure(abort_code = 2)]
+    fun new_with_other_publisher() {
+        let scenario = &mut TestScenario::begin(&@0x1);
+        // `Hero` is declared in the framework, but `ASCII::String` in the standard library.
+        let publisher = Publisher::new_for_testing<Hero>(ctx(scenario));
+        let display = Display::new<ASCII::String>(&publisher, ctx(scenario));
+        Display::keep(display, ctx(scenario));
+        Publisher::burn(publisher);
+    }
+}
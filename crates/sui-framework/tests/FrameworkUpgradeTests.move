@@ -0,0 +1,69 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+#[test_only]
+module Sui::FrameworkUpgradeTests {
+    use Std::Option;
+    use Sui::FrameworkUpgrade;
+
+    #[test]
+    fun test_upgrade_is_approved_at_quorum() {
+        let state = FrameworkUpgrade::new();
+
+        // 3 validators with stake 100 each, and a quorum threshold of 200.
+        FrameworkUpgrade::vote(&mut state, x"01", 2, 1, @0x1, 100, 200);
+        FrameworkUpgrade::vote(&mut state, x"02", 2, 1, @0x2, 100, 200);
+        assert!(Option::is_none(FrameworkUpgrade::approved(&state)), 0);
+
+        // The first validator changes its mind, which takes back its vote for the
+        // first upgrade, and the second one reaches a quorum.
+        FrameworkUpgrade::vote(&mut state, x"02", 2, 1, @0x1, 100, 200);
+        let approved = Option::borrow(FrameworkUpgrade::approved(&state));
+        assert!(FrameworkUpgrade::digest(approved) == &x"02", 0);
+        assert!(FrameworkUpgrade::protocol_version(approved) == 2, 0);
+
+        // The upgrade is no longer approved once a voter takes back its vote.
+        FrameworkUpgrade::clear_vote(&mut state, @0x2, 200);
+        assert!(Option::is_none(FrameworkUpgrade::approved(&state)), 0);
+        FrameworkUpgrade::vote(&mut state, x"02", 2, 1, @0x3, 100, 200);
+
+        // The epoch change applies the approved upgrade and its protocol version,
+        // and the votes don't carry over to the next epoch.
+        assert!(FrameworkUpgrade::advance_epoch(&mut state, x"02", 1) == 2, 0);
+        assert!(Option::is_none(FrameworkUpgrade::approved(&state)), 0);
+        FrameworkUpgrade::vote(&mut state, x"03", 3, 2, @0x1, 100, 200);
+        assert!(Option::is_none(FrameworkUpgrade::approved(&state)), 0);
+
+        FrameworkUpgrade::destroy_for_testing(state);
+    }
+
+    #[test]
+    fun test_epoch_change_can_leave_out_the_approved_upgrade() {
+        let state = FrameworkUpgrade::new();
+        FrameworkUpgrade::vote(&mut state, x"01", 2, 1, @0x1, 100, 200);
+        FrameworkUpgrade::vote(&mut state, x"01", 2, 1, @0x2, 100, 200);
+
+        // Without the upgrade, the protocol version stays the same.
+        assert!(FrameworkUpgrade::advance_epoch(&mut state, x"", 1) == 1, 0);
+        assert!(Option::is_none(FrameworkUpgrade::approved(&state)), 0);
+
+        FrameworkUpgrade::destroy_for_testing(state);
+    }
+
+    #[test]
+    #[expected_failure(abort_code = 1)]
+    fun test_epoch_change_cannot_apply_an_upgrade_not_voted_for() {
+        let state = FrameworkUpgrade::new();
+        FrameworkUpgrade::vote(&mut state, x"01", 2, 1, @0x1, 100, 200);
+        FrameworkUpgrade::advance_epoch(&mut state, x"01", 1);
+        FrameworkUpgrade::destroy_for_testing(state);
+    }
+
+    #[test]
+    #[expected_failure(abort_code = 0)]
+    fun test_older_protocol_version_is_rejected() {
+        let state = FrameworkUpgrade::new();
+        FrameworkUpgrade::vote(&mut state, x"01", 1, 2, @0x1, 100, 200);
+        FrameworkUpgrade::destroy_for_testing(state);
+    }
+}
@@ -0,0 +1,25 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+#[test_only]
+module Sui::Groth16Tests {
+    use Sui::Groth16;
+
+    #[test]
+    #[expected_failure(abort_code = 0)]
+    fun test_unknown_curve() {
+        Groth16::prepare_verifying_key(&Groth16::curve_for_testing(2), &x"");
+    }
+
+    #[test]
+    #[expected_failure(abort_code = 1)]
+    fun test_invalid_bn254_verifying_key() {
+        Groth16::prepare_verifying_key(&Groth16::bn254(), &x"0102");
+    }
+
+    #[test]
+    #[expected_failure(abort_code = 1)]
+    fun test_invalid_bls12381_verifying_key() {
+        Groth16::prepare_verifying_key(&Groth16::bls12381(), &x"0102");
+    }
+}
@@ -0,0 +1,101 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+#[test_only]
+module Sui::KioskTests {
+    use Std::Option;
+    use Sui::ID::{Self, VersionedID};
+    use Sui::Kiosk::{Self, Kiosk, KioskOwnerCap};
+    use Sui::Publisher;
+    use Sui::TestScenario::{Self, ctx};
+    use Sui::TransferPolicy::{Self, TransferPolicy, TransferPolicyCap};
+    use Sui::Transfer;
+    use Sui::TxContext;
+
+    struct Sword has key, store {
+        id: VersionedID,
+    }
+
+    const CREATOR: address = @0xA;
+    const SELLER: address = @0xB;
+
+    #[test]
+    fun policy_royalty() {
+        let scenario = &mut TestScenario::begin(&CREATOR);
+        let publisher = Publisher::new_for_testing<Sword>(ctx(scenario));
+        let cap = TransferPolicy::new<Sword>(&publisher, 250, ctx(scenario));
+        Publisher::burn(publisher);
+        TestScenario::next_tx(scenario, &CREATOR);
+        {
+            let policy_wrapper = TestScenario::take_shared<TransferPolicy<Sword>>(scenario);
+            let policy = TestScenario::borrow_mut(&mut policy_wrapper);
+            assert!(TransferPolicy::royalty_bps(policy) == 250, 0);
+            assert!(TransferPolicy::royalty_amount(policy, 1000) == 25, 1);
+            TransferPolicy::set_royalty(policy, &cap, 10000);
+            assert!(TransferPolicy::royalty_amount(policy, 1000) == 1000, 2);
+            TestScenario::return_shared(scenario, policy_wrapper);
+        };
+        Transfer::transfer(cap, CREATOR);
+    }
+
+    #[test]
+    #[expected_failure(abort_code = 0)]
+    fun policy_royalty_too_high() {
+        let scenario = &mut TestScenario::begin(&CREATOR);
+        let publisher = Publisher::new_for_testing<Sword>(ctx(scenario));
+        let cap: TransferPolicyCap<Sword> = TransferPolicy::new(&publisher, 10001, ctx(scenario));
+        Publisher::burn(publisher);
+        Transfer::transfer(cap, CREATOR);
+    }
+
+    #[test]
+    fun list_and_delist() {
+        let scenario = &mut TestScenario::begin(&SELLER);
+        let cap = Kiosk::new(ctx(scenario));
+        Transfer::transfer(cap, SELLER);
+        TestScenario::next_tx(scenario, &SELLER);
+        {
+            let kiosk_wrapper = TestScenario::take_shared<Kiosk>(scenario);
+            let kiosk = TestScenario::borrow_mut(&mut kiosk_wrapper);
+            let cap = TestScenario::take_owned<KioskOwnerCap>(scenario);
+            let sword = Sword { id: TxContext::new_id(ctx(scenario)) };
+            let sword_id = *ID::id(&sword);
+
+            Kiosk::place(kiosk, &cap, sword);
+            assert!(Kiosk::contains(kiosk, &sword_id), 0);
+            assert!(Option::is_none(&Kiosk::price(kiosk, &sword_id)), 1);
+
+            Kiosk::list(kiosk, &cap, sword_id, 100);
+            assert!(Kiosk::price(kiosk, &sword_id) == Option::some(100), 2);
+            Kiosk::list(kiosk, &cap, sword_id, 200);
+            assert!(Kiosk::price(kiosk, &sword_id) == Option::some(200), 3);
+
+            Kiosk::delist(kiosk, &cap, sword_id);
+            assert!(Option::is_none(&Kiosk::price(kiosk, &sword_id)), 4);
+
+            TestScenario::return_owned(scenario, cap);
+            TestScenario::return_shared(scenario, kiosk_wrapper);
+        }
+    }
+
+    #[test]
+    #[expected_failure(abort_code = 1)]
+    fun list_missing_item() {
+        let scenario = &mut TestScenario::begin(&SELLER);
+        let cap = Kiosk::new(ctx(scenario));
+        Transfer::transfer(cap, SELLER);
+        TestScenario::next_tx(scenario, &SELLER);
+        {
+            let kiosk_wrapper = TestScenario::take_shared<Kiosk>(scenario);
+            let kiosk = TestScenario::borrow_mut(&mut kiosk_wrapper);
+            let cap = TestScenario::take_owned<KioskOwnerCap>(scenario);
+            let sword = Sword { id: TxContext::new_id(ctx(scenario)) };
+
+            Kiosk::list(kiosk, &cap, *ID::id(&sword), 100);
+
+            Transfer::transfer(sword, SELLER);
+            TestScenario::return_owned(scenario, cap);
+            TestScenario::return_shared(scenario, kiosk_wrapper);
+        }
+    }
+}
@@ -0,0 +1,77 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+#[test_only]
+module Sui::LockedCoinTests {
+    use Sui::Balance;
+    use Sui::Coin::{Self, Coin};
+    use Sui::LockedCoin::{Self, LockedCoin};
+    use Sui::SUI::SUI;
+    use Sui::TestScenario::{Self, ctx};
+
+    const FOUNDATION: address = @0xA;
+    const RECIPIENT: address = @0xB;
+
+    #[test]
+    fun vesting_schedule() {
+        let scenario = &mut TestScenario::begin(&FOUNDATION);
+        LockedCoin::lock(Balance::create_for_testing<SUI>(1000), 2, 6, RECIPIENT, ctx(scenario));
+        TestScenario::next_tx(scenario, &RECIPIENT);
+        {
+            let locked = TestScenario::take_owned<LockedCoin<SUI>>(scenario);
+            assert!(LockedCoin::vested_value(&locked, 0) == 0, 0);
+            assert!(LockedCoin::vested_value(&locked, 2) == 0, 1);
+            assert!(LockedCoin::vested_value(&locked, 3) == 250, 2);
+            assert!(LockedCoin::vested_value(&locked, 5) == 750, 3);
+            assert!(LockedCoin::vested_value(&locked, 6) == 1000, 4);
+            assert!(LockedCoin::vested_value(&locked, 100) == 1000, 5);
+
+            // Nothing vested in the first epoch yet.
+            assert!(LockedCoin::withdrawable_value(&locked, ctx(scenario)) == 0, 6);
+            let coin = LockedCoin::withdraw(&mut locked, ctx(scenario));
+            assert!(Coin::value(&coin) == 0, 7);
+            Coin::destroy_zero(coin);
+            assert!(LockedCoin::value(&locked) == 1000, 8);
+            TestScenario::return_owned(scenario, locked);
+        };
+    }
+
+    #[test]
+    fun timelock() {
+        let scenario = &mut TestScenario::begin(&FOUNDATION);
+        LockedCoin::lock(Balance::create_for_testing<SUI>(1000), 0, 0, RECIPIENT, ctx(scenario));
+        TestScenario::next_tx(scenario, &RECIPIENT);
+        {
+            let locked = TestScenario::take_owned<LockedCoin<SUI>>(scenario);
+            LockedCoin::withdraw_(&mut locked, ctx(scenario));
+            assert!(LockedCoin::value(&locked) == 0, 0);
+            // What was withdrawn still counts as vested.
+            assert!(LockedCoin::vested_value(&locked, 0) == 1000, 1);
+            assert!(LockedCoin::withdrawable_value(&locked, ctx(scenario)) == 0, 2);
+            LockedCoin::destroy_empty(locked);
+        };
+        TestScenario::next_tx(scenario, &RECIPIENT);
+        {
+            let coin = TestScenario::take_owned<Coin<SUI>>(scenario);
+            assert!(Coin::value(&coin) == 1000, 3);
+            TestScenario::return_owned(scenario, coin);
+        };
+    }
+
+    #[test]
+    #[expected_failure(abort_code = 0)]
+    fun end_before_start() {
+        let scenario = &mut TestScenario::begin(&FOUNDATION);
+        LockedCoin::lock(Balance::create_for_testing<SUI>(1000), 2, 1, RECIPIENT, ctx(scenario));
+    }
+
+    #[test]
+    #[expected_failure(abort_code = 1)]
+    fun destroy_not_empty() {
+        let scenario = &mut TestScenario::begin(&FOUNDATION);
+        LockedCoin::lock(Balance::create_for_testing<SUI>(1000), 2, 6, RECIPIENT, ctx(scenario));
+        TestScenario::next_tx(scenario, &RECIPIENT);
+        let locked = TestScenario::take_owned<LockedCoin<SUI>>(scenario);
+        LockedCoin::destroy_empty(locked);
+    }
+}
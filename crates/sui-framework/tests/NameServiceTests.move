@@ -0,0 +1,111 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+#[test_only]
+module Sui::NameServiceTests {
+    use Std::Option;
+    use Sui::NameService::{Self, Registry};
+    use Sui::TxContext::{Self, TxContext};
+
+    const ALICE: address = @0xA;
+    const BOB: address = @0xB;
+
+    fun ctx_at(sender: address, epoch: u64): TxContext {
+        TxContext::new_from_address_with_epoch(sender, 0, epoch)
+    }
+
+    fun registry_with_alice(): Registry {
+        let ctx = ctx_at(ALICE, 0);
+        let registry = NameService::new_for_testing(&mut ctx);
+        NameService::register(&mut registry, b"alice", ALICE, 10, &mut ctx);
+        registry
+    }
+
+    #[test]
+    fun register_and_resolve() {
+        let registry = registry_with_alice();
+        let ctx = ctx_at(BOB, 0);
+        assert!(NameService::resolve(&registry, &b"alice", &ctx) == Option::some(ALICE), 0);
+        assert!(NameService::owner(&registry, &b"alice", &ctx) == Option::some(ALICE), 1);
+        assert!(Option::is_none(&NameService::resolve(&registry, &b"bob", &ctx)), 2);
+
+        // The name expires at the end of its registration, and anyone can take it then.
+        let ctx = ctx_at(BOB, 10);
+        assert!(Option::is_none(&NameService::resolve(&registry, &b"alice", &ctx)), 3);
+        NameService::register(&mut registry, b"alice", BOB, 1, &mut ctx);
+        assert!(NameService::resolve(&registry, &b"alice", &ctx) == Option::some(BOB), 4);
+        NameService::destroy_for_testing(registry);
+    }
+
+    #[test]
+    #[expected_failure(abort_code = 1)]
+    fun register_taken_name() {
+        let registry = registry_with_alice();
+        let ctx = ctx_at(BOB, 9);
+        NameService::register(&mut registry, b"alice", BOB, 1, &mut ctx);
+        NameService::destroy_for_testing(registry);
+    }
+
+    #[test]
+    #[expected_failure(abort_code = 0)]
+    fun register_invalid_name() {
+        let ctx = ctx_at(ALICE, 0);
+        let registry = NameService::new_for_testing(&mut ctx);
+        NameService::register(&mut registry, b"Alice", ALICE, 1, &mut ctx);
+        NameService::destroy_for_testing(registry);
+    }
+
+    #[test]
+    fun renew_and_transfer() {
+        let registry = registry_with_alice();
+        let ctx = ctx_at(ALICE, 5);
+        NameService::renew(&mut registry, b"alice", 10, &mut ctx);
+        NameService::transfer_name(&mut registry, b"alice", BOB, &mut ctx);
+
+        let ctx = ctx_at(BOB, 15);
+        assert!(NameService::owner(&registry, &b"alice", &ctx) == Option::some(BOB), 0);
+        NameService::set_target(&mut registry, b"alice", BOB, &mut ctx);
+        assert!(NameService::resolve(&registry, &b"alice", &ctx) == Option::some(BOB), 1);
+        NameService::release(&mut registry, b"alice", &mut ctx);
+        assert!(Option::is_none(&NameService::resolve(&registry, &b"alice", &ctx)), 2);
+        NameService::destroy_for_testing(registry);
+    }
+
+    #[test]
+    #[expected_failure(abort_code = 3)]
+    fun set_target_of_other_name() {
+        let registry = registry_with_alice();
+        let ctx = ctx_at(BOB, 0);
+        NameService::set_target(&mut registry, b"alice", BOB, &mut ctx);
+        NameService::destroy_for_testing(registry);
+    }
+
+    #[test]
+    fun reverse_records() {
+        let registry = registry_with_alice();
+        let ctx = ctx_at(ALICE, 0);
+        NameService::set_reverse_record(&mut registry, b"alice", &mut ctx);
+        assert!(NameService::reverse_lookup(&registry, ALICE, &ctx) == Option::some(b"alice"), 0);
+
+        // Pointing the name elsewhere removes the reverse record.
+        NameService::set_target(&mut registry, b"alice", BOB, &mut ctx);
+        assert!(Option::is_none(&NameService::reverse_lookup(&registry, ALICE, &ctx)), 1);
+
+        let ctx = ctx_at(BOB, 0);
+        NameService::set_reverse_record(&mut registry, b"alice", &mut ctx);
+        assert!(NameService::reverse_lookup(&registry, BOB, &ctx) == Option::some(b"alice"), 2);
+        // The reverse record lapses with the name.
+        let ctx = ctx_at(BOB, 10);
+        assert!(Option::is_none(&NameService::reverse_lookup(&registry, BOB, &ctx)), 3);
+        NameService::destroy_for_testing(registry);
+    }
+
+    #[test]
+    #[expected_failure(abort_code = 5)]
+    fun reverse_record_of_other_target() {
+        let registry = registry_with_alice();
+        let ctx = ctx_at(BOB, 0);
+        NameService::set_reverse_record(&mut registry, b"alice", &mut ctx);
+        NameService::destroy_for_testing(registry);
+    }
+}
@@ -0,0 +1,162 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+#[test_only]
+module Sui::OrderBookTests {
+    use Std::Option;
+    use Sui::Balance;
+    use Sui::Coin::{Self, Coin};
+    use Sui::OrderBook::{Self, Pool, PoolOwnerCap};
+    use Sui::TestScenario::{Self, Scenario, ctx};
+    use Sui::Transfer;
+
+    struct BASE has drop {}
+    struct QUOTE has drop {}
+
+    const OWNER: address = @0xA;
+    const SELLER: address = @0xB;
+    const BUYER: address = @0xC;
+
+    /// Two units of the quote asset for one of the base asset.
+    const PRICE: u64 = 2000000000;
+
+    fun create_pool(scenario: &mut Scenario) {
+        let cap = OrderBook::new<BASE, QUOTE>(1000000, 100, 10, 2, ctx(scenario));
+        Transfer::transfer(cap, OWNER);
+    }
+
+    fun place_ask(scenario: &mut Scenario, seller: address, price: u64, quantity: u64): u64 {
+        TestScenario::next_tx(scenario, &seller);
+        let pool_wrapper = TestScenario::take_shared<Pool<BASE, QUOTE>>(scenario);
+        let pool = TestScenario::borrow_mut(&mut pool_wrapper);
+        let offer = Balance::create_for_testing<BASE>(quantity);
+        let (received, order_id) = OrderBook::place_ask(pool, &mut offer, price, quantity, ctx(scenario));
+        Balance::destroy_zero(offer);
+        Balance::destroy_for_testing(received);
+        TestScenario::return_shared(scenario, pool_wrapper);
+        order_id
+    }
+
+    #[test]
+    fun match_resting_orders() {
+        let scenario = &mut TestScenario::begin(&OWNER);
+        create_pool(scenario);
+        let ask_id = place_ask(scenario, SELLER, PRICE, 100000);
+
+        TestScenario::next_tx(scenario, &BUYER);
+        {
+            let pool_wrapper = TestScenario::take_shared<Pool<BASE, QUOTE>>(scenario);
+            let pool = TestScenario::borrow_mut(&mut pool_wrapper);
+            assert!(OrderBook::best_ask(pool) == Option::some(PRICE), 0);
+
+            // A bid above the best ask fills at the price of the ask.
+            let payment = Balance::create_for_testing<QUOTE>(100000);
+            let (bought, _) = OrderBook::place_bid(pool, &mut payment, 2 * PRICE, 40000, ctx(scenario));
+            assert!(Balance::value(&bought) == 40000, 1);
+            // The quote value of the fill, 80000, and the taker fee of 10 bps.
+            assert!(Balance::value(&payment) == 100000 - 80000 - 80, 2);
+            assert!(OrderBook::order_quantity(pool, ask_id) == 60000, 3);
+            // The fee net of the maker rebate of 2 bps.
+            assert!(OrderBook::fees_value(pool) == 64, 4);
+
+            // A bid below the best ask rests in the book.
+            let (nothing, bid_id) = OrderBook::place_bid(pool, &mut payment, PRICE / 2, 10000, ctx(scenario));
+            Balance::destroy_zero(nothing);
+            assert!(OrderBook::best_bid(pool) == Option::some(PRICE / 2), 5);
+            assert!(Balance::value(&payment) == 19920 - 10000, 6);
+
+            let (base, quote) = OrderBook::cancel_order(pool, bid_id, ctx(scenario));
+            Balance::destroy_zero(base);
+            assert!(Balance::destroy_for_testing(quote) == 10000, 7);
+            assert!(Option::is_none(&OrderBook::best_bid(pool)), 8);
+
+            Balance::destroy_for_testing(bought);
+            Balance::destroy_for_testing(payment);
+            TestScenario::return_shared(scenario, pool_wrapper);
+        };
+
+        // The maker was paid the quote value of the fill and the rebate.
+        TestScenario::next_tx(scenario, &SELLER);
+        {
+            let proceeds = TestScenario::take_owned<Coin<QUOTE>>(scenario);
+            assert!(Coin::value(&proceeds) == 80016, 9);
+            TestScenario::return_owned(scenario, proceeds);
+        };
+
+        TestScenario::next_tx(scenario, &OWNER);
+        {
+            let pool_wrapper = TestScenario::take_shared<Pool<BASE, QUOTE>>(scenario);
+            let pool = TestScenario::borrow_mut(&mut pool_wrapper);
+            let cap = TestScenario::take_owned<PoolOwnerCap>(scenario);
+            let fees = OrderBook::withdraw_fees(pool, &cap, ctx(scenario));
+            assert!(Coin::value(&fees) == 64, 10);
+            Transfer::transfer(fees, OWNER);
+            TestScenario::return_owned(scenario, cap);
+            TestScenario::return_shared(scenario, pool_wrapper);
+        };
+    }
+
+    #[test]
+    fun fill_best_price_first_and_oldest_first() {
+        let scenario = &mut TestScenario::begin(&OWNER);
+        create_pool(scenario);
+        let first = place_ask(scenario, SELLER, PRICE, 1000);
+        let second = place_ask(scenario, SELLER, PRICE, 1000);
+        let cheapest = place_ask(scenario, SELLER, PRICE / 2, 1000);
+
+        TestScenario::next_tx(scenario, &BUYER);
+        {
+            let pool_wrapper = TestScenario::take_shared<Pool<BASE, QUOTE>>(scenario);
+            let pool = TestScenario::borrow_mut(&mut pool_wrapper);
+            let payment = Balance::create_for_testing<QUOTE>(100000);
+            let (bought, _) = OrderBook::place_bid(pool, &mut payment, PRICE, 1500, ctx(scenario));
+            assert!(Balance::value(&bought) == 1500, 0);
+            assert!(OrderBook::order_quantity(pool, cheapest) == 0, 1);
+            assert!(OrderBook::order_quantity(pool, first) == 500, 2);
+            assert!(OrderBook::order_quantity(pool, second) == 1000, 3);
+            Balance::destroy_for_testing(bought);
+            Balance::destroy_for_testing(payment);
+            TestScenario::return_shared(scenario, pool_wrapper);
+        };
+    }
+
+    #[test]
+    #[expected_failure(abort_code = 0)]
+    fun cancel_order_of_another_is_rejected() {
+        let scenario = &mut TestScenario::begin(&OWNER);
+        create_pool(scenario);
+        let ask_id = place_ask(scenario, SELLER, PRICE, 1000);
+
+        TestScenario::next_tx(scenario, &BUYER);
+        let pool_wrapper = TestScenario::take_shared<Pool<BASE, QUOTE>>(scenario);
+        let pool = TestScenario::borrow_mut(&mut pool_wrapper);
+        let (base, quote) = OrderBook::cancel_order(pool, ask_id, ctx(scenario));
+        Balance::destroy_for_testing(base);
+        Balance::destroy_for_testing(quote);
+        TestScenario::return_shared(scenario, pool_wrapper);
+    }
+
+    #[test]
+    #[expected_failure(abort_code = 2)]
+    fun price_off_tick_is_rejected() {
+        let scenario = &mut TestScenario::begin(&OWNER);
+        create_pool(scenario);
+        place_ask(scenario, SELLER, PRICE + 1, 1000);
+    }
+
+    #[test]
+    #[expected_failure(abort_code = 4)]
+    fun underpaid_bid_is_rejected() {
+        let scenario = &mut TestScenario::begin(&OWNER);
+        create_pool(scenario);
+
+        TestScenario::next_tx(scenario, &BUYER);
+        let pool_wrapper = TestScenario::take_shared<Pool<BASE, QUOTE>>(scenario);
+        let pool = TestScenario::borrow_mut(&mut pool_wrapper);
+        let payment = Balance::create_for_testing<QUOTE>(1000);
+        let (bought, _) = OrderBook::place_bid(pool, &mut payment, PRICE, 1000, ctx(scenario));
+        Balance::destroy_for_testing(bought);
+        Balance::destroy_for_testing(payment);
+        TestScenario::return_shared(scenario, pool_wrapper);
+    }
+}
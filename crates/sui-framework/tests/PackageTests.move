@@ -0,0 +1,26 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+#[test_only]
+module Sui::PackageTests {
+    use Sui::Package::{Self, UpgradeCap};
+    use Sui::TestScenario::{Self, ctx};
+
+    struct PACKAGETESTS has drop {}
+
+    const CREATOR: address = @0xA;
+
+    #[test]
+    fun claim_and_keep() {
+        let scenario = &mut TestScenario::begin(&CREATOR);
+        let cap = Package::claim_upgrade_cap(PACKAGETESTS {}, ctx(scenario));
+        assert!(Package::package(&cap) == @Sui, 0);
+        assert!(Package::version(&cap) == 1, 1);
+        Package::keep(cap, ctx(scenario));
+        TestScenario::next_tx(scenario, &CREATOR);
+        {
+            let cap = TestScenario::take_owned<UpgradeCap>(scenario);
+            Package::make_immutable(cap);
+        };
+    }
+}
@@ -0,0 +1,29 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+#[test_only]
+module Sui::PublisherTests {
+    use Std::ASCII;
+    use Sui::Publisher::{Self, Publisher};
+    use Sui::TestScenario::{Self, ctx};
+
+    struct PUBLISHERTESTS has drop {}
+
+    const CREATOR: address = @0xA;
+
+    #[test]
+    fun claim_and_keep() {
+        let scenario = &mut TestScenario::begin(&CREATOR);
+        let publisher = Publisher::claim(PUBLISHERTESTS {}, ctx(scenario));
+        assert!(Publisher::package(&publisher) == @Sui, 0);
+        Publisher::keep(publisher, ctx(scenario));
+        TestScenario::next_tx(scenario, &CREATOR);
+        {
+            let publisher = TestScenario::take_owned<Publisher>(scenario);
+            assert!(Publisher::is_package<PUBLISHERTESTS>(&publisher), 1);
+            assert!(Publisher::is_package<Publisher>(&publisher), 2);
+            assert!(!Publisher::is_package<ASCII::String>(&publisher), 3);
+            Publisher::burn(publisher);
+        };
+    }
+}
@@ -0,0 +1,54 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+#[test_only]
+module Sui::StakingPoolTests {
+    use Sui::Balance;
+    use Sui::SUI::SUI;
+    use Sui::StakingPool;
+
+    #[test]
+    fun test_rewards_raise_the_exchange_rate() {
+        let pool = StakingPool::new(@0x1);
+        // Two delegations of 100 and 300 activate in epoch 1.
+        StakingPool::request_add_delegation(&mut pool, 100);
+        StakingPool::request_add_delegation(&mut pool, 300);
+        StakingPool::process_pending_delegations(&mut pool, 1);
+        assert!(StakingPool::sui_balance(&pool) == 400, 0);
+        assert!(StakingPool::pool_token_balance(&pool) == 400, 0);
+
+        // They earn 200 in epoch 1, and a delegation of 600 activates in epoch 2, at the rate
+        // of 3 SUI for 2 tokens.
+        StakingPool::deposit_rewards(&mut pool, Balance::create_for_testing<SUI>(200));
+        StakingPool::request_add_delegation(&mut pool, 600);
+        StakingPool::process_pending_delegations(&mut pool, 2);
+        assert!(StakingPool::sui_balance(&pool) == 1200, 0);
+        assert!(StakingPool::pool_token_balance(&pool) == 800, 0);
+
+        assert!(StakingPool::estimated_rewards(&pool, 100, 1, 2) == 50, 0);
+        assert!(StakingPool::estimated_rewards(&pool, 600, 2, 2) == 0, 0);
+
+        // All of them earn 120 in epoch 2.
+        StakingPool::deposit_rewards(&mut pool, Balance::create_for_testing<SUI>(120));
+        StakingPool::process_pending_delegations(&mut pool, 3);
+        let rewards = StakingPool::withdraw_rewards(&mut pool, 300, 1, 3);
+        assert!(Balance::value(&rewards) == 150 + 45, 0);
+        Balance::destroy_for_testing(rewards);
+        let rewards = StakingPool::withdraw_rewards(&mut pool, 600, 2, 3);
+        assert!(Balance::value(&rewards) == 60, 0);
+        Balance::destroy_for_testing(rewards);
+        assert!(StakingPool::estimated_rewards(&pool, 100, 1, 3) == 50 + 15, 0);
+        StakingPool::destroy_for_testing(pool);
+    }
+
+    #[test]
+    fun test_withdraw_before_activation() {
+        let pool = StakingPool::new(@0x1);
+        StakingPool::request_add_delegation(&mut pool, 100);
+        let rewards = StakingPool::withdraw_rewards(&mut pool, 100, 1, 0);
+        assert!(Balance::value(&rewards) == 0, 0);
+        Balance::destroy_zero(rewards);
+        assert!(StakingPool::pending_delegation(&pool) == 0, 0);
+        StakingPool::destroy_for_testing(pool);
+    }
+}
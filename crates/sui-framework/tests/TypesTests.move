@@ -0,0 +1,26 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+#[test_only]
+module Sui::TypesTests {
+    use Std::ASCII;
+
+    use Sui::Coin::Coin;
+    use Sui::SUI::SUI;
+    use Sui::Types;
+    use Sui::UTF8;
+
+    const ETypeNameMismatch: u64 = 0;
+
+    #[test]
+    fun test_type_names() {
+        assert!(
+            Types::type_name<Coin<SUI>>() == ASCII::string(b"0x2::Coin::Coin<0x2::SUI::SUI>"),
+            ETypeNameMismatch
+        );
+        assert!(Types::type_name<u64>() == ASCII::string(b"u64"), ETypeNameMismatch);
+        assert!(Types::is_same_type<Coin<SUI>, Coin<SUI>>(), ETypeNameMismatch);
+        assert!(!Types::is_same_type<Coin<SUI>, UTF8::String>(), ETypeNameMismatch);
+        assert!(!Types::is_same_type<u64, u128>(), ETypeNameMismatch);
+    }
+}
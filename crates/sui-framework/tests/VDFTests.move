@@ -0,0 +1,37 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+#[test_only]
+module Sui::VDFTests {
+    use Sui::VDF;
+
+    const EInputMismatch: u64 = 0;
+    const EVerificationMismatch: u64 = 1;
+
+    // A modulus whose factorization is known, (2^127 - 1)(2^89 - 1), which is
+    // only fit for tests.
+    const MODULUS: vector<u8> = x"ffffffffffffffffffffff7ffffffffe0000000000000000000001";
+    const INPUT: vector<u8> = x"042a5b072f15cac622e78fa27c9b89a281550e6d08be3cfd6243ee";
+    // The output of `INPUT` after 1000 iterations, and its proof.
+    const OUTPUT: vector<u8> = x"77645f30f41196df12a8b33618e32c35f01a51a89bf7e6d44950ca";
+    const PROOF: vector<u8> = x"2f6534723d4e56fed9c3280a984b94bb12418b783b6985b2792371";
+
+    #[test]
+    fun test_hash_to_input() {
+        assert!(VDF::hash_to_input(MODULUS, b"lottery") == INPUT, EInputMismatch);
+    }
+
+    #[test]
+    fun test_verify_wesolowski() {
+        assert!(VDF::verify_wesolowski(MODULUS, INPUT, OUTPUT, PROOF, 1000), EVerificationMismatch);
+        assert!(!VDF::verify_wesolowski(MODULUS, INPUT, OUTPUT, PROOF, 999), EVerificationMismatch);
+        assert!(!VDF::verify_wesolowski(MODULUS, INPUT, PROOF, OUTPUT, 1000), EVerificationMismatch);
+        assert!(!VDF::verify_wesolowski(MODULUS, x"01", OUTPUT, PROOF, 1000), EVerificationMismatch);
+    }
+
+    #[test]
+    #[expected_failure(abort_code = 0)]
+    fun test_even_modulus() {
+        VDF::verify_wesolowski(x"ffffffffffffffffffffff7ffffffffe0000000000000000000002", INPUT, OUTPUT, PROOF, 1000);
+    }
+}
@@ -0,0 +1,44 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+#[test_only]
+module Sui::ValidatorReportsTests {
+    use Sui::ValidatorReports;
+
+    #[test]
+    fun test_report_and_clear() {
+        let reports = ValidatorReports::new();
+        ValidatorReports::report(&mut reports, @0x1, @0x3);
+        ValidatorReports::report(&mut reports, @0x2, @0x3);
+        // Reporting the same validator twice counts once.
+        ValidatorReports::report(&mut reports, @0x2, @0x3);
+        assert!(ValidatorReports::reporters(&reports, @0x3) == vector[@0x1, @0x2], 0);
+        assert!(ValidatorReports::reporters(&reports, @0x1) == vector[], 0);
+
+        ValidatorReports::clear_report(&mut reports, @0x1, @0x3);
+        assert!(ValidatorReports::reporters(&reports, @0x3) == vector[@0x2], 0);
+
+        // Reports don't carry over to the next epoch.
+        ValidatorReports::advance_epoch(&mut reports);
+        assert!(ValidatorReports::reporters(&reports, @0x3) == vector[], 0);
+
+        ValidatorReports::destroy_for_testing(reports);
+    }
+
+    #[test]
+    #[expected_failure(abort_code = 0)]
+    fun test_self_report_is_rejected() {
+        let reports = ValidatorReports::new();
+        ValidatorReports::report(&mut reports, @0x1, @0x1);
+        ValidatorReports::destroy_for_testing(reports);
+    }
+
+    #[test]
+    #[expected_failure(abort_code = 0)]
+    fun test_clearing_a_report_of_another_validator_is_rejected() {
+        let reports = ValidatorReports::new();
+        ValidatorReports::report(&mut reports, @0x1, @0x3);
+        ValidatorReports::clear_report(&mut reports, @0x2, @0x3);
+        ValidatorReports::destroy_for_testing(reports);
+    }
+}
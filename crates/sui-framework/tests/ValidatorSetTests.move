@@ -3,11 +3,14 @@
 
 #[test_only]
 module Sui::ValidatorSetTests {
+    use Std::Vector;
+
     use Sui::Balance;
     use Sui::Coin;
     use Sui::SUI::SUI;
+    use Sui::StakingPool;
     use Sui::TxContext::{Self, TxContext};
-    use Sui::Validator::{Self, Validator};
+    use Sui::Validator::{Self, Validator, ValidatorMetadataUpdate};
     use Sui::ValidatorSet;
 
     #[test]
@@ -59,7 +62,7 @@ module Sui::ValidatorSetTests {
         );
 
         let reward = Balance::zero<SUI>();
-        ValidatorSet::advance_epoch(&mut validator_set, &mut reward, &mut ctx1);
+        ValidatorSet::advance_epoch(&mut validator_set, &mut reward, &vector[], &mut ctx1);
         // The total stake and quorum should reflect 4 validators.
         assert!(ValidatorSet::total_validator_candidate_count(&validator_set) == 4, 0);
         assert!(ValidatorSet::validator_stake(&validator_set) == 1000, 0);
@@ -71,7 +74,7 @@ module Sui::ValidatorSetTests {
         // Total validator candidate count changes, but total stake remains during epoch.
         assert!(ValidatorSet::total_validator_candidate_count(&validator_set) == 3, 0);
         assert!(ValidatorSet::validator_stake(&validator_set) == 1000, 0);
-        ValidatorSet::advance_epoch(&mut validator_set, &mut reward, &mut ctx1);
+        ValidatorSet::advance_epoch(&mut validator_set, &mut reward, &vector[], &mut ctx1);
         // Validator1 is gone.
         assert!(ValidatorSet::validator_stake(&validator_set) == 900, 0);
 
@@ -79,6 +82,179 @@ module Sui::ValidatorSetTests {
         Balance::destroy_zero(reward);
     }
 
+    #[test]
+    public(script) fun test_validator_key_rotation() {
+        let (ctx1, validator1) = create_validator(@0x1, 1);
+        let (_ctx2, validator2) = create_validator(@0x2, 2);
+        let validator_set = ValidatorSet::new(vector[validator1, validator2]);
+
+        // The validator set of the next epoch announces the new key right away.
+        ValidatorSet::request_rotate_validator_key(
+            &mut validator_set,
+            vector[9],
+            protocol_key(9, 96),
+            protocol_key(9, 48),
+            &ctx1,
+        );
+        assert!(next_epoch_pubkeys(&validator_set) == vector[vector[2], vector[9]], 0);
+
+        let reward = Balance::zero<SUI>();
+        ValidatorSet::advance_epoch(&mut validator_set, &mut reward, &vector[], &mut ctx1);
+        // The validator keeps its stake under the new key.
+        assert!(next_epoch_pubkeys(&validator_set) == vector[vector[2], vector[9]], 0);
+        assert!(ValidatorSet::validator_stake(&validator_set) == 300, 0);
+        assert!(ValidatorSet::is_active_validator(&validator_set, @0x1), 0);
+
+        ValidatorSet::destroy_for_testing(validator_set, &mut ctx1);
+        Balance::destroy_zero(reward);
+    }
+
+    #[test]
+    #[expected_failure(abort_code = 0)]
+    public(script) fun test_validator_key_rotation_to_a_key_in_use() {
+        let (ctx1, validator1) = create_validator(@0x1, 1);
+        let (_ctx2, validator2) = create_validator(@0x2, 2);
+        let validator_set = ValidatorSet::new(vector[validator1, validator2]);
+
+        ValidatorSet::request_rotate_validator_key(
+            &mut validator_set,
+            vector[2],
+            protocol_key(9, 96),
+            protocol_key(9, 48),
+            &ctx1,
+        );
+        ValidatorSet::destroy_for_testing(validator_set, &mut ctx1);
+    }
+
+    #[test]
+    public(script) fun test_validator_metadata_update() {
+        let (ctx1, validator1) = create_validator(@0x1, 1);
+        let (_ctx2, validator2) = create_validator(@0x2, 2);
+        let (ctx3, validator3) = create_validator(@0x3, 3);
+        let validator_set = ValidatorSet::new(vector[validator1, validator2]);
+        ValidatorSet::request_add_validator(&mut validator_set, validator3);
+
+        // Both active and pending validators can update their metadata.
+        ValidatorSet::request_update_validator_metadata(
+            &mut validator_set,
+            metadata_update(b"Validator1", vector[9]),
+            &ctx1,
+        );
+        ValidatorSet::request_update_validator_metadata(
+            &mut validator_set,
+            metadata_update(b"Validator3", vector[8]),
+            &ctx3,
+        );
+        assert!(next_epoch_names(&validator_set) == vector[vector[2], b"Validator1"], 0);
+
+        let reward = Balance::zero<SUI>();
+        ValidatorSet::advance_epoch(&mut validator_set, &mut reward, &vector[], &mut ctx1);
+        assert!(next_epoch_names(&validator_set) == vector[b"Validator3", vector[2], b"Validator1"], 0);
+
+        ValidatorSet::destroy_for_testing(validator_set, &mut ctx1);
+        Balance::destroy_zero(reward);
+    }
+
+    #[test]
+    #[expected_failure(abort_code = 0)]
+    public(script) fun test_validator_metadata_update_to_a_name_in_use() {
+        let (ctx1, validator1) = create_validator(@0x1, 1);
+        let (ctx2, validator2) = create_validator(@0x2, 2);
+        let validator_set = ValidatorSet::new(vector[validator1, validator2]);
+
+        ValidatorSet::request_update_validator_metadata(
+            &mut validator_set,
+            metadata_update(b"Validator", vector[9]),
+            &ctx2,
+        );
+        // The name is taken from the next epoch on.
+        ValidatorSet::request_update_validator_metadata(
+            &mut validator_set,
+            metadata_update(b"Validator", vector[8]),
+            &ctx1,
+        );
+        ValidatorSet::destroy_for_testing(validator_set, &mut ctx1);
+    }
+
+    #[test]
+    public(script) fun test_delegation_rewards() {
+        let (ctx1, validator1) = create_validator(@0x1, 1);
+        let (_ctx2, validator2) = create_validator(@0x2, 2);
+        let validator_set = ValidatorSet::new(vector[validator1, validator2]);
+        ValidatorSet::request_add_delegation(&mut validator_set, @0x1, 100);
+        ValidatorSet::request_add_delegation(&mut validator_set, @0x2, 300);
+
+        // The delegations activate in epoch 1, and earn nothing in epoch 0.
+        let reward = Balance::zero<SUI>();
+        ValidatorSet::distribute_delegation_rewards(&mut validator_set, &mut reward, &vector[], 1);
+        ValidatorSet::advance_epoch(&mut validator_set, &mut reward, &vector[], &mut ctx1);
+        assert!(ValidatorSet::delegation_stake(&validator_set) == 400, 0);
+
+        // The delegation reward of epoch 1 goes to the pools in proportion to their stake,
+        // except to the reported validator.
+        let delegation_reward = Balance::create_for_testing<SUI>(80);
+        ValidatorSet::distribute_delegation_rewards(&mut validator_set, &mut delegation_reward, &vector[@0x2], 2);
+        assert!(Balance::value(&delegation_reward) == 60, 0);
+        let pool = ValidatorSet::staking_pool(&validator_set, @0x1);
+        assert!(StakingPool::estimated_rewards(pool, 100, 1, 2) == 20, 0);
+        assert!(StakingPool::estimated_rewards(ValidatorSet::staking_pool(&validator_set, @0x2), 300, 1, 2) == 0, 0);
+
+        let rewards = ValidatorSet::withdraw_delegation(&mut validator_set, @0x1, 100, 1, 2);
+        assert!(Balance::value(&rewards) == 20, 0);
+        ValidatorSet::advance_epoch(&mut validator_set, &mut reward, &vector[], &mut ctx1);
+        assert!(ValidatorSet::delegation_stake(&validator_set) == 300, 0);
+
+        ValidatorSet::destroy_for_testing(validator_set, &mut ctx1);
+        Balance::destroy_zero(reward);
+        Balance::destroy_for_testing(rewards);
+        Balance::destroy_for_testing(delegation_reward);
+    }
+
+    #[test]
+    public(script) fun test_reported_validator_gets_no_reward() {
+        let (ctx1, validator1) = create_validator(@0x1, 1);
+        let (_ctx2, validator2) = create_validator(@0x2, 2);
+        let validator_set = ValidatorSet::new(vector[validator1, validator2]);
+
+        let reward = Balance::create_for_testing<SUI>(300);
+        ValidatorSet::advance_epoch(&mut validator_set, &mut reward, &vector[@0x1], &mut ctx1);
+        // The second validator gets its share of the reward, and the share of the
+        // reported one is left over.
+        assert!(Balance::value(&reward) == 100, 0);
+        ValidatorSet::advance_epoch(&mut validator_set, &mut reward, &vector[], &mut ctx1);
+        assert!(ValidatorSet::validator_stake(&validator_set) == 500, 0);
+
+        ValidatorSet::destroy_for_testing(validator_set, &mut ctx1);
+        Balance::destroy_for_testing(reward);
+    }
+
+    fun next_epoch_pubkeys(validator_set: &ValidatorSet::ValidatorSet): vector<vector<u8>> {
+        let metadata = ValidatorSet::next_epoch_validators(validator_set);
+        let pubkeys = vector[];
+        let i = 0;
+        while (i < Vector::length(metadata)) {
+            let pubkey_bytes = Validator::metadata_pubkey_bytes(Vector::borrow(metadata, i));
+            Vector::push_back(&mut pubkeys, pubkey_bytes);
+            i = i + 1;
+        };
+        pubkeys
+    }
+
+    fun next_epoch_names(validator_set: &ValidatorSet::ValidatorSet): vector<vector<u8>> {
+        let metadata = ValidatorSet::next_epoch_validators(validator_set);
+        let names = vector[];
+        let i = 0;
+        while (i < Vector::length(metadata)) {
+            Vector::push_back(&mut names, *Validator::metadata_name(Vector::borrow(metadata, i)));
+            i = i + 1;
+        };
+        names
+    }
+
+    fun metadata_update(name: vector<u8>, net_address: vector<u8>): ValidatorMetadataUpdate {
+        Validator::new_metadata_update(name, vector[], vector[], vector[], net_address, vector[], vector[])
+    }
+
     fun create_validator(addr: address, hint: u8): (TxContext, Validator) {
         let stake_value = (hint as u64) * 100;
         let ctx = TxContext::new_from_address(addr, hint);
@@ -87,10 +263,21 @@ module Sui::ValidatorSetTests {
         let validator = Validator::new(
             addr,
             vector[hint],
+            protocol_key(hint, 96),
+            protocol_key(hint, 48),
             vector[hint],
             vector[hint],
             init_stake,
         );
         (ctx, validator)
     }
+
+    /// A protocol key or proof of `length` bytes. The Move side only checks their lengths.
+    fun protocol_key(hint: u8, length: u64): vector<u8> {
+        let bytes = vector[];
+        while (Vector::length(&bytes) < length) {
+            Vector::push_back(&mut bytes, hint);
+        };
+        bytes
+    }
 }
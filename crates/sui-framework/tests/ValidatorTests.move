@@ -3,6 +3,9 @@
 
 #[test_only]
 module Sui::ValidatorTests {
+    use Std::Option;
+    use Std::Vector;
+
     use Sui::Coin::{Self, Coin};
     use Sui::SUI::SUI;
     use Sui::TestScenario;
@@ -19,6 +22,8 @@ module Sui::ValidatorTests {
             let validator = Validator::new(
                 sender,
                 x"FF",
+                protocol_pubkey(0xFF),
+                protocol_key_proof(0xFF),
                 b"Validator1",
                 x"FFFF",
                 init_stake,
@@ -47,6 +52,8 @@ module Sui::ValidatorTests {
         let validator = Validator::new(
             sender,
             x"FF",
+            protocol_pubkey(0xFF),
+            protocol_key_proof(0xFF),
             b"Validator1",
             x"FFFF",
             init_stake,
@@ -79,4 +86,159 @@ module Sui::ValidatorTests {
 
         Validator::destroy(validator, TestScenario::ctx(scenario));
     }
+
+    #[test]
+    public(script) fun test_key_rotation_flow() {
+        let sender = @0x1;
+        let scenario = &mut TestScenario::begin(&sender);
+        let ctx = TestScenario::ctx(scenario);
+        let init_stake = Coin::into_balance(Coin::mint_for_testing(10, ctx));
+        let validator = Validator::new(
+            sender,
+            x"FF",
+            protocol_pubkey(0xFF),
+            protocol_key_proof(0xFF),
+            b"Validator1",
+            x"FFFF",
+            init_stake,
+        );
+
+        // The new key only shows in the metadata of the next epoch until the rotation.
+        Validator::request_rotate_key(
+            &mut validator,
+            x"EE",
+            protocol_pubkey(0xEE),
+            protocol_key_proof(0xEE),
+        );
+        assert!(Validator::pubkey_bytes(&validator) == &x"FF", 0);
+        assert!(Validator::uses_pubkey(&validator, &x"EE"), 0);
+        let next_epoch_metadata = Validator::next_epoch_metadata(&validator);
+        assert!(&next_epoch_metadata != Validator::metadata(&validator), 0);
+
+        Validator::rotate_key(&mut validator);
+        assert!(Validator::pubkey_bytes(&validator) == &x"EE", 0);
+        assert!(
+            Validator::metadata_protocol_pubkey_bytes(Validator::metadata(&validator)) == protocol_pubkey(0xEE),
+            0
+        );
+        assert!(Option::is_none(Validator::next_epoch_pubkey_bytes(&validator)), 0);
+        assert!(&next_epoch_metadata == Validator::metadata(&validator), 0);
+        assert!(Validator::sui_address(&validator) == sender, 0);
+        assert!(Validator::stake_amount(&validator) == 10, 0);
+
+        Validator::destroy(validator, TestScenario::ctx(scenario));
+    }
+
+    #[test]
+    #[expected_failure(abort_code = 0)]
+    public(script) fun test_key_rotation_to_the_same_key() {
+        let sender = @0x1;
+        let scenario = &mut TestScenario::begin(&sender);
+        let ctx = TestScenario::ctx(scenario);
+        let init_stake = Coin::into_balance(Coin::mint_for_testing(10, ctx));
+        let validator = Validator::new(
+            sender,
+            x"FF",
+            protocol_pubkey(0xFF),
+            protocol_key_proof(0xFF),
+            b"Validator1",
+            x"FFFF",
+            init_stake,
+        );
+
+        Validator::request_rotate_key(
+            &mut validator,
+            x"FF",
+            protocol_pubkey(0xEE),
+            protocol_key_proof(0xEE),
+        );
+        Validator::destroy(validator, TestScenario::ctx(scenario));
+    }
+
+    #[test]
+    #[expected_failure(abort_code = 0)]
+    public(script) fun test_key_rotation_without_protocol_key_proof() {
+        let sender = @0x1;
+        let scenario = &mut TestScenario::begin(&sender);
+        let ctx = TestScenario::ctx(scenario);
+        let init_stake = Coin::into_balance(Coin::mint_for_testing(10, ctx));
+        let validator = Validator::new(
+            sender,
+            x"FF",
+            protocol_pubkey(0xFF),
+            protocol_key_proof(0xFF),
+            b"Validator1",
+            x"FFFF",
+            init_stake,
+        );
+
+        Validator::request_rotate_key(&mut validator, x"EE", protocol_pubkey(0xEE), vector[]);
+        Validator::destroy(validator, TestScenario::ctx(scenario));
+    }
+
+    #[test]
+    public(script) fun test_metadata_update_in_the_next_epoch() {
+        let sender = @0x1;
+        let scenario = &mut TestScenario::begin(&sender);
+        let ctx = TestScenario::ctx(scenario);
+        let init_stake = Coin::into_balance(Coin::mint_for_testing(10, ctx));
+        let validator = Validator::new(
+            sender,
+            x"FF",
+            protocol_pubkey(0xFF),
+            protocol_key_proof(0xFF),
+            b"Validator1",
+            x"FFFF",
+            init_stake,
+        );
+
+        let update = Validator::new_metadata_update(
+            b"Validator2",
+            b"A validator",
+            b"https://validator.io/logo.png",
+            b"https://validator.io",
+            x"EEEE",
+            x"DDDD",
+            x"CCCC",
+        );
+        Validator::request_update_metadata(&mut validator, update);
+        // The validator goes by its current metadata until the end of the epoch.
+        assert!(*Validator::metadata_name(Validator::metadata(&validator)) == b"Validator1", 0);
+        let next_epoch_metadata = Validator::next_epoch_metadata(&validator);
+        assert!(*Validator::metadata_name(&next_epoch_metadata) == b"Validator2", 0);
+        assert!(*Validator::metadata_p2p_address(&next_epoch_metadata) == x"DDDD", 0);
+
+        Validator::update_metadata(&mut validator);
+        let metadata = Validator::metadata(&validator);
+        assert!(*Validator::metadata_name(metadata) == b"Validator2", 0);
+        assert!(*Validator::metadata_description(metadata) == b"A validator", 0);
+        assert!(*Validator::metadata_image_url(metadata) == b"https://validator.io/logo.png", 0);
+        assert!(*Validator::metadata_project_url(metadata) == b"https://validator.io", 0);
+        assert!(*Validator::metadata_net_address(metadata) == x"EEEE", 0);
+        assert!(*Validator::metadata_consensus_address(metadata) == x"CCCC", 0);
+        Validator::destroy(validator, TestScenario::ctx(scenario));
+    }
+
+    #[test]
+    #[expected_failure(abort_code = 0)]
+    public(script) fun test_metadata_update_with_a_non_ascii_name() {
+        Validator::new_metadata_update(x"FF", vector[], vector[], vector[], vector[], vector[], vector[]);
+    }
+
+    /// A protocol key of the right length. The Move side does not check it is a valid key.
+    fun protocol_pubkey(byte: u8): vector<u8> {
+        repeat(byte, 96)
+    }
+
+    fun protocol_key_proof(byte: u8): vector<u8> {
+        repeat(byte, 48)
+    }
+
+    fun repeat(byte: u8, length: u64): vector<u8> {
+        let bytes = vector[];
+        while (Vector::length(&bytes) < length) {
+            Vector::push_back(&mut bytes, byte);
+        };
+        bytes
+    }
 }
@@ -0,0 +1,52 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+#[test_only]
+module Sui::ZkLoginTests {
+    use Std::Vector;
+    use Sui::ZkLogin::{Self, Jwk};
+
+    fun jwks(kid: vector<u8>): vector<Jwk> {
+        vector[ZkLogin::new_jwk(b"https://accounts.google.com", kid, x"c0ffee")]
+    }
+
+    #[test]
+    fun test_config_is_replaced_at_quorum() {
+        let state = ZkLogin::new();
+        assert!(Vector::is_empty(ZkLogin::verifying_key(&state)), 0);
+
+        // 3 validators with stake 100 each, and a quorum threshold of 200.
+        ZkLogin::vote(&mut state, jwks(b"1"), x"01", @0x1, 100, 200);
+        ZkLogin::vote(&mut state, jwks(b"2"), x"01", @0x2, 100, 200);
+        assert!(Vector::is_empty(ZkLogin::jwks(&state)), 0);
+
+        // The first validator changes its mind, which takes back its vote for the
+        // first configuration, and the second one reaches a quorum.
+        ZkLogin::vote(&mut state, jwks(b"2"), x"01", @0x1, 100, 200);
+        assert!(ZkLogin::jwks(&state) == &jwks(b"2"), 0);
+        assert!(ZkLogin::verifying_key(&state) == &x"01", 0);
+
+        // A validator voting twice for the same configuration doesn't count twice.
+        ZkLogin::vote(&mut state, jwks(b"3"), x"02", @0x3, 100, 200);
+        ZkLogin::vote(&mut state, jwks(b"3"), x"02", @0x3, 100, 200);
+        assert!(ZkLogin::jwks(&state) == &jwks(b"2"), 0);
+
+        // Votes don't carry over to the next epoch.
+        ZkLogin::advance_epoch(&mut state);
+        ZkLogin::vote(&mut state, jwks(b"3"), x"02", @0x1, 100, 200);
+        assert!(ZkLogin::jwks(&state) == &jwks(b"2"), 0);
+        ZkLogin::vote(&mut state, jwks(b"3"), x"02", @0x2, 100, 200);
+        assert!(ZkLogin::jwks(&state) == &jwks(b"3"), 0);
+        assert!(ZkLogin::verifying_key(&state) == &x"02", 0);
+
+        ZkLogin::destroy_for_testing(state);
+    }
+
+    #[test]
+    #[expected_failure(abort_code = 0)]
+    fun test_empty_verifying_key_is_rejected() {
+        let state = ZkLogin::new();
+        ZkLogin::vote(&mut state, jwks(b"1"), x"", @0x1, 100, 200);
+        ZkLogin::destroy_for_testing(state);
+    }
+}
@@ -10,8 +10,11 @@ use serde_with::serde_as;
 
 use sui_core::gateway_state::GatewayTxSeqNumber;
 use sui_core::gateway_types::{
-    GetObjectDataResponse, GetRawObjectDataResponse, SuiInputObjectKind, SuiObjectInfo,
-    SuiObjectRef,
+    CheckpointId, ExecuteTransactionRequestType, GasProfileResponse, GetObjectDataResponse,
+    GetRawObjectDataResponse, MultiGetResult, SuiCheckpoint, SuiExecuteTransactionResponse,
+    SuiFinalityProof, SuiInputObjectKind, SuiObjectDataOptions, SuiObjectInfo, SuiObjectRef,
+    SuiStake, SuiSystemStateSummary, SuiTransactionEffects, SuiTransactionResponseOptions,
+    SuiValidatorApys, SuiValidatorReport,
 };
 use sui_core::gateway_types::{TransactionEffectsResponse, TransactionResponse};
 use sui_json::SuiJsonValue;
@@ -19,25 +22,79 @@ use sui_open_rpc::Module;
 use sui_open_rpc_macros::open_rpc;
 use sui_types::sui_serde::Base64;
 use sui_types::{
-    base_types::{ObjectID, SuiAddress, TransactionDigest},
-    crypto::SignableBytes,
+    base_types::{ObjectID, SequenceNumber, SuiAddress, TransactionDigest},
+    crypto::{GenericSignature, SignableBytes, SignatureScheme},
+    intent::PersonalMessage,
     messages::TransactionData,
+    messages_checkpoint::CheckpointSequenceNumber,
+    multisig::MultiSig,
+    order_book::OrderBookEventEnvelope,
+    zk_login::ZkLoginAuthenticator,
 };
 
 use crate::rpc_gateway::responses::SuiTypeTag;
 
+/// The maximum number of objects or transactions that can be requested in one `multiGet*` call
+/// to the gateway. The limit of full nodes is set by their config.
+pub const MAX_MULTI_GET_SIZE: usize = sui_config::node::DEFAULT_MAX_MULTI_GET_SIZE;
+
+/// The number of children `getChildObjects` returns when the request sets no limit.
+pub const DEFAULT_CHILD_OBJECTS_PAGE_SIZE: usize = 50;
+
+/// The largest number of children `getChildObjects` returns in one page.
+pub const MAX_CHILD_OBJECTS_PAGE_SIZE: usize = 1000;
+
+/// The number of checkpoints `getCheckpoints` returns when the request sets no limit.
+pub const DEFAULT_CHECKPOINTS_PAGE_SIZE: usize = 50;
+
+/// The largest number of checkpoints `getCheckpoints` returns in one page.
+pub const MAX_CHECKPOINTS_PAGE_SIZE: usize = 1000;
+
 #[open_rpc(namespace = "sui", tag = "Gateway API")]
 #[rpc(server, client, namespace = "sui")]
 pub trait RpcGatewayApi {
-    /// Execute the transaction using the transaction data, signature and public key.
+    /// Execute the transaction using the transaction data, signature and public key. The
+    /// signature scheme of the key defaults to ed25519.
     #[method(name = "executeTransaction")]
     async fn execute_transaction(
         &self,
         tx_bytes: Base64,
         signature: Base64,
         pub_key: Base64,
+        sig_scheme: Option<SignatureScheme>,
+    ) -> RpcResult<TransactionResponse>;
+
+    /// Execute the transaction using the transaction data and the combined signatures of the
+    /// keys of a multisig sender.
+    #[method(name = "executeMultiSigTransaction")]
+    #[since = "0.2.0"]
+    async fn execute_multisig_transaction(
+        &self,
+        tx_bytes: Base64,
+        multisig: MultiSig,
+    ) -> RpcResult<TransactionResponse>;
+
+    /// Execute the transaction using the transaction data and the zkLogin authenticator of a
+    /// sender who logged in with an OpenID provider.
+    #[method(name = "executeZkLoginTransaction")]
+    #[since = "0.2.0"]
+    async fn execute_zk_login_transaction(
+        &self,
+        tx_bytes: Base64,
+        authenticator: ZkLoginAuthenticator,
     ) -> RpcResult<TransactionResponse>;
 
+    /// Execute the transaction on a validator without signing or committing it, and return
+    /// the effects it would have, including the gas it would use.
+    #[method(name = "dryRunTransaction")]
+    #[since = "0.2.0"]
+    async fn dry_run_transaction(&self, tx_bytes: Base64) -> RpcResult<SuiTransactionEffects>;
+
+    /// Dry run the transaction as `dryRunTransaction` does, and return where its computation gas
+    /// went: the gas of each Move function it calls, and of the Sui natives they call.
+    #[method(name = "profileTransactionGas")]
+    async fn profile_transaction_gas(&self, tx_bytes: Base64) -> RpcResult<GasProfileResponse>;
+
     /// Synchronize client state with validators.
     #[method(name = "syncAccountState")]
     async fn sync_account_state(&self, address: SuiAddress) -> RpcResult<()>;
@@ -46,11 +103,13 @@ pub trait RpcGatewayApi {
 #[open_rpc(namespace = "sui", tag = "Read API")]
 #[rpc(server, client, namespace = "sui")]
 pub trait RpcReadApi {
-    /// Return the list of objects owned by an address.
+    /// Return the list of objects owned by an address, or the objects it owned at the end of
+    /// the checkpoint `at_checkpoint` if set, as read by `getObject` at that checkpoint.
     #[method(name = "getObjectsOwnedByAddress")]
     async fn get_objects_owned_by_address(
         &self,
         address: SuiAddress,
+        at_checkpoint: Option<CheckpointSequenceNumber>,
     ) -> RpcResult<Vec<SuiObjectInfo>>;
 
     #[method(name = "getObjectsOwnedByObject")]
@@ -75,15 +134,58 @@ pub trait RpcReadApi {
         count: u64,
     ) -> RpcResult<Vec<(GatewayTxSeqNumber, TransactionDigest)>>;
 
+    /// Return the transaction with the specified digest. `options` selects the optional fields
+    /// of the response; events, balance changes and object changes are included by default.
     #[method(name = "getTransaction")]
     async fn get_transaction(
         &self,
         digest: TransactionDigest,
+        options: Option<SuiTransactionResponseOptions>,
     ) -> RpcResult<TransactionEffectsResponse>;
 
-    /// Return the object information for a specified object
+    /// Return the object information for a specified object. `options` selects the optional
+    /// fields of the response; display and previous transaction are included by default. It may
+    /// also pin the read to the end of a checkpoint, for a consistent view across requests.
     #[method(name = "getObject")]
-    async fn get_object(&self, object_id: ObjectID) -> RpcResult<GetObjectDataResponse>;
+    async fn get_object(
+        &self,
+        object_id: ObjectID,
+        options: Option<SuiObjectDataOptions>,
+    ) -> RpcResult<GetObjectDataResponse>;
+
+    /// Return the object information for each of the specified objects, in order, as in
+    /// `getObject`. A lookup that fails is reported in place of its object.
+    #[since = "0.2.0"]
+    #[method(name = "multiGetObjects")]
+    async fn multi_get_objects(
+        &self,
+        object_ids: Vec<ObjectID>,
+        options: Option<SuiObjectDataOptions>,
+    ) -> RpcResult<Vec<MultiGetResult<GetObjectDataResponse>>>;
+
+    /// Return each of the specified transactions, in order, as in `getTransaction`. A lookup
+    /// that fails is reported in place of its transaction.
+    #[since = "0.2.0"]
+    #[method(name = "multiGetTransactions")]
+    async fn multi_get_transactions(
+        &self,
+        digests: Vec<TransactionDigest>,
+        options: Option<SuiTransactionResponseOptions>,
+    ) -> RpcResult<Vec<MultiGetResult<TransactionEffectsResponse>>>;
+
+    /// Return whether `signature` is a signature of the personal message `message` by
+    /// `address`, for an app to check off-chain that a user owns an address. The signature is
+    /// base64 encoded as printed by `sui keytool sign-personal-message`, the signature followed
+    /// by the public key and, for ECDSA keys, prefixed with the flag of the scheme. It must be
+    /// of the intent of personal messages followed by the message.
+    #[since = "0.2.0"]
+    #[method(name = "verifyPersonalMessage")]
+    async fn verify_personal_message(
+        &self,
+        address: SuiAddress,
+        message: Base64,
+        signature: Base64,
+    ) -> RpcResult<bool>;
 }
 
 #[open_rpc(namespace = "sui", tag = "Full Node API")]
@@ -112,6 +214,91 @@ pub trait RpcFullNodeReadApi {
         &self,
         addr: SuiAddress,
     ) -> RpcResult<Vec<(GatewayTxSeqNumber, TransactionDigest)>>;
+
+    /// Return the events of the order book `pool`, a `Sui::OrderBook::Pool`: its creation, and
+    /// the orders placed in it, filled and canceled, in the order they were emitted.
+    #[since = "0.2.0"]
+    #[method(name = "getOrderBookEvents")]
+    async fn get_order_book_events(&self, pool: ObjectID)
+        -> RpcResult<Vec<OrderBookEventEnvelope>>;
+
+    /// Return the objects owned by the object `parent_id`, in the order of their IDs, at most
+    /// `limit` of them (50 by default, 1000 at most) starting with `cursor`, the `nextCursor` of
+    /// the previous page.
+    #[since = "0.2.0"]
+    #[method(name = "getChildObjects")]
+    async fn get_child_objects(
+        &self,
+        parent_id: ObjectID,
+        cursor: Option<ObjectID>,
+        limit: Option<usize>,
+    ) -> RpcResult<ChildObjectsPage>;
+
+    /// Return the address the name points at in the name service, with or without the `.sui`
+    /// domain, unless it is not registered or has expired.
+    #[since = "0.2.0"]
+    #[method(name = "resolveNameServiceAddress")]
+    async fn resolve_name_service_address(&self, name: String) -> RpcResult<Option<SuiAddress>>;
+
+    /// Return the names pointing at the address in the name service, its reverse record first.
+    #[since = "0.2.0"]
+    #[method(name = "resolveNameServiceNames")]
+    async fn resolve_name_service_names(&self, address: SuiAddress) -> RpcResult<Vec<String>>;
+
+    /// Return the latest state of the Sui system object: the epoch, the stake and the validators
+    /// of the committee, with the metadata they go by in this epoch and the next.
+    #[since = "0.2.0"]
+    #[method(name = "getLatestSuiSystemState")]
+    async fn get_latest_sui_system_state(&self) -> RpcResult<SuiSystemStateSummary>;
+
+    /// Return the delegations owned by the address, with the rewards each would be paid if it
+    /// were withdrawn in the current epoch.
+    #[since = "0.2.0"]
+    #[method(name = "getStakes")]
+    async fn get_stakes(&self, owner: SuiAddress) -> RpcResult<Vec<SuiStake>>;
+
+    /// Return the annual yield of the delegations to each validator of the committee, from the
+    /// growth of the exchange rate of its staking pool over the last year.
+    #[since = "0.2.0"]
+    #[method(name = "getValidatorsApy")]
+    async fn get_validators_apy(&self) -> RpcResult<SuiValidatorApys>;
+
+    /// Return how the validator of the committee took part in the current epoch: the share of
+    /// the checkpoints followed by the full node it signed and proposed, and the validators
+    /// which reported it.
+    #[since = "0.2.0"]
+    #[method(name = "getValidatorReport")]
+    async fn get_validator_report(&self, address: SuiAddress) -> RpcResult<SuiValidatorReport>;
+
+    /// Return the certified checkpoint with the sequence number or summary digest, with the
+    /// digests of the transactions in it.
+    #[since = "0.2.0"]
+    #[method(name = "getCheckpoint")]
+    async fn get_checkpoint(&self, id: CheckpointId) -> RpcResult<SuiCheckpoint>;
+
+    /// Return the certified checkpoints followed by the full node, with the digests of the
+    /// transactions in them, at most `limit` of them (50 by default, 1000 at most) starting with
+    /// `cursor`, the `nextCursor` of the previous page. They are in the order of their sequence
+    /// numbers, from the first one, or from the last one if `descending_order` is set.
+    #[since = "0.2.0"]
+    #[method(name = "getCheckpoints")]
+    async fn get_checkpoints(
+        &self,
+        cursor: Option<CheckpointSequenceNumber>,
+        limit: Option<usize>,
+        descending_order: Option<bool>,
+    ) -> RpcResult<CheckpointsPage>;
+
+    /// Return the proof that the transaction is final: its effects, the certified checkpoint
+    /// they are in, the proof of their inclusion in the checkpoint, and the committee of the
+    /// epoch of the checkpoint, with which the proof can be checked offline rather than trusting
+    /// the full node. The effects and the checkpoint are in BCS.
+    #[since = "0.2.0"]
+    #[method(name = "getTransactionFinalityProof")]
+    async fn get_transaction_finality_proof(
+        &self,
+        digest: TransactionDigest,
+    ) -> RpcResult<SuiFinalityProof>;
 }
 
 #[open_rpc(namespace = "sui", tag = "Transaction Builder API")]
@@ -128,6 +315,19 @@ pub trait RpcTransactionBuilder {
         recipient: SuiAddress,
     ) -> RpcResult<TransactionBytes>;
 
+    /// Create a transaction to send `amount` SUI from a coin to another address, or the whole
+    /// coin if `amount` is omitted. The coin also pays for the gas.
+    #[method(name = "transferSui")]
+    #[since = "0.2.0"]
+    async fn transfer_sui(
+        &self,
+        signer: SuiAddress,
+        sui_object_id: ObjectID,
+        gas_budget: u64,
+        recipient: SuiAddress,
+        amount: Option<u64>,
+    ) -> RpcResult<TransactionBytes>;
+
     /// Execute a Move call transaction by calling the specified function in the module of a given package.
     #[method(name = "moveCall")]
     async fn move_call(
@@ -179,6 +379,56 @@ pub trait RpcBcsApi {
     /// Return the raw BCS serialised move object bytes for a specified object
     #[method(name = "getRawObject")]
     async fn get_raw_object(&self, object_id: ObjectID) -> RpcResult<GetRawObjectDataResponse>;
+
+    /// Return the BCS serialised object at `version`, or at its latest version if no version
+    /// is given. Past versions are kept by full nodes only.
+    #[since = "0.2.0"]
+    #[method(name = "getRawObjectAtVersion")]
+    async fn get_raw_object_at_version(
+        &self,
+        object_id: ObjectID,
+        version: Option<SequenceNumber>,
+    ) -> RpcResult<Base64>;
+
+    /// Return the BCS serialised certificate of an executed transaction and its effects, as
+    /// signed by the validators.
+    #[since = "0.2.0"]
+    #[method(name = "getRawTransaction")]
+    async fn get_raw_transaction(
+        &self,
+        digest: TransactionDigest,
+    ) -> RpcResult<RawTransactionResponse>;
+}
+
+/// Served by full nodes, which submit the transactions to the validators themselves.
+#[open_rpc(namespace = "sui", tag = "Transaction Execution API")]
+#[rpc(server, client, namespace = "sui")]
+pub trait RpcTransactionExecutionApi {
+    /// Submit the transaction, signed as for `executeTransaction`, and return once it reaches
+    /// the point `request_type` sets: its certificate, the effects a quorum of validators agreed
+    /// on, or the full node executing it too, so that its reads then see the effects. The full
+    /// node waits for the effects by default.
+    #[since = "0.2.0"]
+    #[method(name = "submitTransaction")]
+    async fn submit_transaction(
+        &self,
+        tx_bytes: Base64,
+        signature: Base64,
+        pub_key: Base64,
+        sig_scheme: Option<SignatureScheme>,
+        request_type: Option<ExecuteTransactionRequestType>,
+    ) -> RpcResult<SuiExecuteTransactionResponse>;
+}
+
+/// Served by the validators of local and testing networks only, which set `epoch-duration-ms`.
+#[open_rpc(namespace = "sui", tag = "Epoch Admin API")]
+#[rpc(server, client, namespace = "sui")]
+pub trait RpcEpochAdminApi {
+    /// Make the validator change epoch right away. The call returns once the change started, and
+    /// the change completes once a quorum of the validators changed epoch.
+    #[since = "0.2.0"]
+    #[method(name = "advanceEpoch")]
+    async fn advance_epoch(&self) -> RpcResult<()>;
 }
 
 #[serde_as]
@@ -208,6 +458,53 @@ impl TransactionBytes {
     }
 }
 
+#[derive(Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RawTransactionResponse {
+    pub certificate: Base64,
+    pub effects: Base64,
+}
+
+/// A page of the children of an object.
+#[derive(Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ChildObjectsPage {
+    pub data: Vec<SuiObjectInfo>,
+    /// The cursor of the next page, if there are more children.
+    pub next_cursor: Option<ObjectID>,
+}
+
+/// A page of the checkpoints followed by a full node.
+#[derive(Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CheckpointsPage {
+    pub data: Vec<SuiCheckpoint>,
+    /// The cursor of the next page, if there are more checkpoints.
+    pub next_cursor: Option<CheckpointSequenceNumber>,
+}
+
+/// Fail if a `multiGet*` call requests more than `max_size` items.
+pub fn check_multi_get_size(size: usize, max_size: usize) -> Result<(), anyhow::Error> {
+    anyhow::ensure!(
+        size <= max_size,
+        "Number of items requested must not exceed {max_size}, {size} requested"
+    );
+    Ok(())
+}
+
+/// Whether `signature` is a signature of the personal message `message` by `address`. Fails if
+/// the signature can't be decoded.
+pub fn verify_personal_message(
+    address: SuiAddress,
+    message: Base64,
+    signature: Base64,
+) -> Result<bool, anyhow::Error> {
+    let signature = GenericSignature::from_bytes(&signature.to_vec()?)?;
+    Ok(PersonalMessage::new(message.to_vec()?)
+        .verify(&signature, address)
+        .is_ok())
+}
+
 pub trait SuiRpcModule
 where
     Self: Sized,
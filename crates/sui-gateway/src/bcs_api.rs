@@ -12,8 +12,11 @@ use sui_core::authority::AuthorityState;
 use sui_core::gateway_state::GatewayClient;
 use sui_core::gateway_types::GetRawObjectDataResponse;
 use sui_open_rpc::Module;
-use sui_types::base_types::ObjectID;
+use sui_types::base_types::{ObjectID, SequenceNumber, TransactionDigest};
+use sui_types::error::SuiError;
+use sui_types::sui_serde::Base64;
 
+use crate::api::RawTransactionResponse;
 use crate::api::RpcBcsApiServer;
 use crate::api::SuiRpcModule;
 
@@ -54,6 +57,43 @@ impl ClientStateAdaptor {
                 .try_into(),
         }
     }
+
+    async fn get_raw_object_at_version(
+        &self,
+        object_id: ObjectID,
+        version: Option<SequenceNumber>,
+    ) -> Result<Base64, anyhow::Error> {
+        match self {
+            ClientStateAdaptor::Gateway(_) => Err(anyhow!(
+                "Past versions of objects are only served by full nodes"
+            )),
+            ClientStateAdaptor::FullNode(client) => {
+                let object = client
+                    .get_object_at_version(&object_id, version)
+                    .await?
+                    .ok_or(SuiError::ObjectNotFound { object_id })?;
+                Ok(Base64::from_bytes(&bcs::to_bytes(&object)?))
+            }
+        }
+    }
+
+    async fn get_raw_transaction(
+        &self,
+        digest: TransactionDigest,
+    ) -> Result<RawTransactionResponse, anyhow::Error> {
+        match self {
+            ClientStateAdaptor::Gateway(_) => {
+                Err(anyhow!("Raw transactions are only served by full nodes"))
+            }
+            ClientStateAdaptor::FullNode(client) => {
+                let (certificate, effects) = client.get_certificate_and_effects(&digest).await?;
+                Ok(RawTransactionResponse {
+                    certificate: Base64::from_bytes(&bcs::to_bytes(&certificate)?),
+                    effects: Base64::from_bytes(&bcs::to_bytes(&effects)?),
+                })
+            }
+        }
+    }
 }
 
 #[async_trait]
@@ -61,6 +101,24 @@ impl RpcBcsApiServer for BcsApiImpl {
     async fn get_raw_object(&self, object_id: ObjectID) -> RpcResult<GetRawObjectDataResponse> {
         Ok(self.client.get_raw_object(object_id).await?)
     }
+
+    async fn get_raw_object_at_version(
+        &self,
+        object_id: ObjectID,
+        version: Option<SequenceNumber>,
+    ) -> RpcResult<Base64> {
+        Ok(self
+            .client
+            .get_raw_object_at_version(object_id, version)
+            .await?)
+    }
+
+    async fn get_raw_transaction(
+        &self,
+        digest: TransactionDigest,
+    ) -> RpcResult<RawTransactionResponse> {
+        Ok(self.client.get_raw_transaction(digest).await?)
+    }
 }
 
 impl SuiRpcModule for BcsApiImpl {
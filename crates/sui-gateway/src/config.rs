@@ -92,7 +92,12 @@ impl GatewayConfig {
             .iter()
             .map(|validator| (validator.public_key(), validator.stake()))
             .collect();
-        Committee::new(self.epoch, voting_rights)
+        let protocol_keys = self
+            .validator_set
+            .iter()
+            .map(|validator| (validator.public_key(), validator.protocol_key()))
+            .collect();
+        Committee::new(self.epoch, voting_rights, protocol_keys)
     }
 
     pub fn make_authority_clients(&self) -> BTreeMap<AuthorityName, NetworkAuthorityClient> {
@@ -0,0 +1,43 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use jsonrpsee_core::server::rpc_module::RpcModule;
+use jsonrpsee_core::RpcResult;
+use tokio::sync::Notify;
+
+use sui_open_rpc::Module;
+
+use crate::api::RpcEpochAdminApiServer;
+use crate::api::SuiRpcModule;
+
+pub struct EpochAdminApi {
+    /// Notifies the process advancing the epoch of the validator.
+    advance_epoch: Arc<Notify>,
+}
+
+impl EpochAdminApi {
+    pub fn new(advance_epoch: Arc<Notify>) -> Self {
+        Self { advance_epoch }
+    }
+}
+
+#[async_trait]
+impl RpcEpochAdminApiServer for EpochAdminApi {
+    async fn advance_epoch(&self) -> RpcResult<()> {
+        self.advance_epoch.notify_one();
+        Ok(())
+    }
+}
+
+impl SuiRpcModule for EpochAdminApi {
+    fn rpc(self) -> RpcModule<Self> {
+        self.into_rpc()
+    }
+
+    fn rpc_doc_module() -> Module {
+        crate::api::RpcEpochAdminApiOpenRpc::module_doc()
+    }
+}
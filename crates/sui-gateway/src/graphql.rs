@@ -0,0 +1,761 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! The GraphQL read API of full nodes, served from the objects and transactions the node stores
+//! and from its indexes. It serves the objects, transactions, events, coin balances and Move
+//! packages the JSON-RPC read API does, but clients select the fields they need and follow the
+//! references between them in one query, such as from an address to the transactions it sent,
+//! to the objects those created and to their owners. Lists are paginated with cursors, as
+//! GraphQL connections, forwards from the `after` cursor.
+//!
+//! Queries are POSTed as JSON to the address the node serves the API on.
+
+use std::convert::Infallible;
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use anyhow::anyhow;
+use async_graphql::connection::{Connection, Edge};
+use async_graphql::{
+    Context, EmptyMutation, EmptySubscription, Enum, Object, Result, Schema, SimpleObject,
+};
+use hyper::header::CONTENT_TYPE;
+use hyper::service::{make_service_fn, service_fn};
+use hyper::{Body, Method, Request, Response, Server, StatusCode};
+use move_binary_format::file_format::Visibility;
+use move_binary_format::normalized;
+use move_core_types::language_storage::TypeTag;
+use move_core_types::parser::parse_type_tag;
+use sui_core::authority::AuthorityState;
+use sui_types::base_types::{ObjectID, ObjectRef, SuiAddress, TransactionDigest};
+use sui_types::batch::TxSequenceNumber;
+use sui_types::error::SuiError;
+use sui_types::event::Event;
+use sui_types::gas_coin::GAS;
+use sui_types::messages::{CertifiedTransaction, ExecutionStatus, TransactionEffects};
+use sui_types::move_package::MovePackage;
+use sui_types::object::{Data, Object as SuiObject, ObjectRead, Owner};
+use sui_types::sui_serde::Base64;
+use tokio::task::JoinHandle;
+use tracing::{debug, error, info};
+
+use crate::json_rpc_front::{error_response, read_body};
+
+pub type SuiGraphQLSchema = Schema<Query, EmptyMutation, EmptySubscription>;
+
+/// The number of items a page of a connection has when the query sets no `first`.
+pub const DEFAULT_PAGE_SIZE: usize = 50;
+
+/// The largest number of items a page of a connection has.
+pub const MAX_PAGE_SIZE: usize = 1000;
+
+/// The deepest queries are nested, so that a query can't follow references without end.
+const MAX_QUERY_DEPTH: usize = 16;
+
+/// The largest number of fields a query selects, counting each field once however many items
+/// of a list it is selected for.
+const MAX_QUERY_COMPLEXITY: usize = 1000;
+
+/// The schema of the API, resolving the queries from `state`.
+pub fn build_schema(state: Arc<AuthorityState>) -> SuiGraphQLSchema {
+    Schema::build(Query, EmptyMutation, EmptySubscription)
+        .data(state)
+        .limit_depth(MAX_QUERY_DEPTH)
+        .limit_complexity(MAX_QUERY_COMPLEXITY)
+        .finish()
+}
+
+/// Serve the API on `address`, resolving the queries from `state`. Returns the address listened
+/// on, and the task serving it.
+pub fn spawn_graphql_server(
+    state: Arc<AuthorityState>,
+    address: SocketAddr,
+    max_request_body_size: u32,
+) -> anyhow::Result<(SocketAddr, JoinHandle<()>)> {
+    let schema = build_schema(state);
+    let server = Server::try_bind(&address)?.serve(make_service_fn(move |_| {
+        let schema = schema.clone();
+        async move {
+            Ok::<_, Infallible>(service_fn(move |request| {
+                handle(schema.clone(), request, max_request_body_size)
+            }))
+        }
+    }));
+    let local_addr = server.local_addr();
+    info!("GraphQL server listening on {local_addr}");
+    let handle = tokio::spawn(async move {
+        if let Err(error) = server.await {
+            error!(?error, "GraphQL server failed");
+        }
+    });
+    Ok((local_addr, handle))
+}
+
+async fn handle(
+    schema: SuiGraphQLSchema,
+    request: Request<Body>,
+    max_request_body_size: u32,
+) -> Result<Response<Body>, hyper::Error> {
+    if request.method() != Method::POST {
+        return Ok(error_response(
+            StatusCode::METHOD_NOT_ALLOWED,
+            "Queries are POSTed as JSON",
+        ));
+    }
+    let body = match read_body(request.into_body(), max_request_body_size).await? {
+        Some(body) => body,
+        None => {
+            return Ok(error_response(
+                StatusCode::PAYLOAD_TOO_LARGE,
+                "The request body is too large",
+            ))
+        }
+    };
+    let request: async_graphql::Request = match serde_json::from_slice(&body) {
+        Ok(request) => request,
+        Err(error) => {
+            debug!(?error, "Malformed GraphQL request");
+            return Ok(error_response(
+                StatusCode::BAD_REQUEST,
+                "The request is not a GraphQL request",
+            ));
+        }
+    };
+    let response = schema.execute(request).await;
+    let mut response = match serde_json::to_vec(&response) {
+        Ok(body) => Response::new(Body::from(body)),
+        Err(error) => {
+            error!(?error, "Failed to serialize a GraphQL response");
+            return Ok(error_response(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "The response could not be serialized",
+            ));
+        }
+    };
+    response
+        .headers_mut()
+        .insert(CONTENT_TYPE, "application/json".parse().unwrap());
+    Ok(response)
+}
+
+fn state<'a>(ctx: &Context<'a>) -> Result<&'a Arc<AuthorityState>> {
+    ctx.data::<Arc<AuthorityState>>()
+}
+
+fn parse_object_id(id: &str) -> Result<ObjectID> {
+    id.parse()
+        .map_err(|_| anyhow!("Invalid object ID {id}").into())
+}
+
+fn parse_address(address: &str) -> Result<SuiAddress> {
+    address
+        .parse()
+        .map_err(|_| anyhow!("Invalid address {address}").into())
+}
+
+fn parse_digest(digest: &str) -> Result<TransactionDigest> {
+    let bytes = Base64::try_from(digest.to_string())
+        .and_then(|digest| digest.to_vec())
+        .map_err(|_| anyhow!("Invalid transaction digest {digest}"))?;
+    Ok(TransactionDigest::try_from(&bytes[..])?)
+}
+
+fn encode_digest(digest: &impl AsRef<[u8]>) -> String {
+    Base64::from_bytes(digest.as_ref()).encoded()
+}
+
+/// The size of a page, from the `first` of the query.
+fn page_size(first: Option<i32>) -> Result<usize> {
+    match first {
+        None => Ok(DEFAULT_PAGE_SIZE),
+        Some(first) if first >= 0 && first as usize <= MAX_PAGE_SIZE => Ok(first as usize),
+        Some(first) => {
+            Err(anyhow!("first must be between 0 and {MAX_PAGE_SIZE}, not {first}").into())
+        }
+    }
+}
+
+/// The page of the objects owned by `owner` following the `after` cursor.
+async fn objects_connection(
+    state: &AuthorityState,
+    owner: Owner,
+    first: Option<i32>,
+    after: Option<String>,
+) -> Result<Connection<String, ObjectNode>> {
+    let first = page_size(first)?;
+    let after = after.as_deref().map(parse_object_id).transpose()?;
+    // The page starts at the cursor, which the connection excludes.
+    let (mut infos, next_cursor) = state.get_owner_objects_page(owner, after, first + 1)?;
+    if after.is_some() && infos.first().map(|info| info.object_id) == after {
+        infos.remove(0);
+    }
+    let has_next_page = infos.len() > first || next_cursor.is_some();
+    infos.truncate(first);
+
+    let object_ids: Vec<_> = infos.iter().map(|info| info.object_id).collect();
+    let objects = state.get_objects(&object_ids).await?;
+    let mut connection = Connection::new(after.is_some(), has_next_page);
+    connection.edges.extend(
+        objects
+            .into_iter()
+            .flatten()
+            .map(|object| Edge::new(object.id().to_string(), ObjectNode(object))),
+    );
+    Ok(connection)
+}
+
+/// The page of the transactions of `transactions`, in the order of their sequence numbers,
+/// following the `after` cursor.
+async fn transactions_connection(
+    state: &AuthorityState,
+    transactions: Vec<(TxSequenceNumber, TransactionDigest)>,
+    first: Option<i32>,
+    after: Option<String>,
+) -> Result<Connection<String, TransactionNode>> {
+    let first = page_size(first)?;
+    let after = match after {
+        Some(after) => Some(
+            after
+                .parse::<TxSequenceNumber>()
+                .map_err(|_| anyhow!("Invalid transaction cursor {after}"))?,
+        ),
+        None => None,
+    };
+    let mut page: Vec<_> = transactions
+        .into_iter()
+        .filter(|(seq, _)| after.map_or(true, |after| *seq > after))
+        .take(first + 1)
+        .collect();
+    let has_next_page = page.len() > first;
+    page.truncate(first);
+
+    let mut connection = Connection::new(after.is_some(), has_next_page);
+    for (seq, digest) in page {
+        let (certificate, effects) = state.get_certificate_and_effects(&digest).await?;
+        connection.edges.push(Edge::new(
+            seq.to_string(),
+            TransactionNode {
+                certificate,
+                effects,
+            },
+        ));
+    }
+    Ok(connection)
+}
+
+pub struct Query;
+
+#[Object]
+impl Query {
+    /// The object with this ID, at its latest version, or null if there is none or it was
+    /// deleted.
+    async fn object(&self, ctx: &Context<'_>, id: String) -> Result<Option<ObjectNode>> {
+        let id = parse_object_id(&id)?;
+        Ok(match state(ctx)?.get_object_read(&id).await? {
+            ObjectRead::Exists(_, object, _) => Some(ObjectNode(object)),
+            ObjectRead::NotExists(_) | ObjectRead::Deleted(_) => None,
+        })
+    }
+
+    /// The Move package with this ID, or null if there is none.
+    async fn package(&self, ctx: &Context<'_>, id: String) -> Result<Option<PackageNode>> {
+        Ok(self
+            .object(ctx, id)
+            .await?
+            .and_then(|object| object.package()))
+    }
+
+    /// The executed transaction with this digest, or null if the node has not executed it.
+    async fn transaction(
+        &self,
+        ctx: &Context<'_>,
+        digest: String,
+    ) -> Result<Option<TransactionNode>> {
+        let digest = parse_digest(&digest)?;
+        match state(ctx)?.get_certificate_and_effects(&digest).await {
+            Ok((certificate, effects)) => Ok(Some(TransactionNode {
+                certificate,
+                effects,
+            })),
+            Err(SuiError::TransactionNotFound { .. }) => Ok(None),
+            Err(error) => Err(error.into()),
+        }
+    }
+
+    /// The transactions the node executed, in the order it executed them.
+    async fn transactions(
+        &self,
+        ctx: &Context<'_>,
+        first: Option<i32>,
+        after: Option<String>,
+    ) -> Result<Connection<String, TransactionNode>> {
+        let state = state(ctx)?;
+        let start = match &after {
+            Some(after) => after
+                .parse::<TxSequenceNumber>()
+                .map_err(|_| anyhow!("Invalid transaction cursor {after}"))?
+                .saturating_add(1),
+            None => 0,
+        };
+        let end = start.saturating_add(page_size(first)? as u64 + 1);
+        let transactions = state.get_transactions_in_range(start, end)?;
+        transactions_connection(state, transactions, first, after).await
+    }
+
+    /// The address, with the objects it owns and the transactions it sent and received.
+    async fn address(&self, address: String) -> Result<AddressNode> {
+        Ok(AddressNode(parse_address(&address)?))
+    }
+}
+
+pub struct AddressNode(SuiAddress);
+
+#[Object(name = "Address")]
+impl AddressNode {
+    async fn address(&self) -> String {
+        self.0.to_string()
+    }
+
+    /// The coins the address owns, totalled by coin type.
+    async fn balances(&self, ctx: &Context<'_>) -> Result<Vec<BalanceNode>> {
+        Ok(state(ctx)?
+            .get_balances(self.0)
+            .await?
+            .into_iter()
+            .map(|(coin_type, (total, count))| BalanceNode::new(coin_type, total, count))
+            .collect())
+    }
+
+    /// The coins of type `coinType`, SUI by default, the address owns, totalled.
+    async fn balance(&self, ctx: &Context<'_>, coin_type: Option<String>) -> Result<BalanceNode> {
+        let coin_type = match coin_type {
+            Some(coin_type) => {
+                parse_type_tag(&coin_type).map_err(|_| anyhow!("Invalid coin type {coin_type}"))?
+            }
+            None => GAS::type_tag(),
+        };
+        let (total, count) = state(ctx)?
+            .get_balances(self.0)
+            .await?
+            .remove(&coin_type)
+            .unwrap_or_default();
+        Ok(BalanceNode::new(coin_type, total, count))
+    }
+
+    /// The objects the address owns, in the order of their IDs.
+    async fn objects(
+        &self,
+        ctx: &Context<'_>,
+        first: Option<i32>,
+        after: Option<String>,
+    ) -> Result<Connection<String, ObjectNode>> {
+        objects_connection(state(ctx)?, Owner::AddressOwner(self.0), first, after).await
+    }
+
+    /// The transactions the address sent.
+    async fn transactions_sent(
+        &self,
+        ctx: &Context<'_>,
+        first: Option<i32>,
+        after: Option<String>,
+    ) -> Result<Connection<String, TransactionNode>> {
+        let state = state(ctx)?;
+        let transactions = state.get_transactions_from_addr(self.0).await?;
+        transactions_connection(state, transactions, first, after).await
+    }
+
+    /// The transactions which sent objects to the address, or mutated the objects it owns.
+    async fn transactions_received(
+        &self,
+        ctx: &Context<'_>,
+        first: Option<i32>,
+        after: Option<String>,
+    ) -> Result<Connection<String, TransactionNode>> {
+        let state = state(ctx)?;
+        let transactions = state.get_transactions_to_addr(self.0).await?;
+        transactions_connection(state, transactions, first, after).await
+    }
+}
+
+/// The total value of the coins of a type owned by an address.
+#[derive(SimpleObject)]
+#[graphql(name = "Balance")]
+pub struct BalanceNode {
+    coin_type: String,
+    /// The total value of the coins, as a decimal string as it may not fit in an `Int`.
+    total_balance: String,
+    coin_object_count: u64,
+}
+
+impl BalanceNode {
+    fn new(coin_type: TypeTag, total: u128, count: usize) -> Self {
+        Self {
+            coin_type: coin_type.to_string(),
+            total_balance: total.to_string(),
+            coin_object_count: count as u64,
+        }
+    }
+}
+
+pub struct ObjectNode(SuiObject);
+
+impl ObjectNode {
+    fn package(self) -> Option<PackageNode> {
+        match self.0.data {
+            Data::Package(package) => Some(PackageNode(package)),
+            Data::Move(_) => None,
+        }
+    }
+}
+
+#[Object(name = "Object")]
+impl ObjectNode {
+    async fn id(&self) -> String {
+        self.0.id().to_string()
+    }
+
+    async fn version(&self) -> u64 {
+        self.0.version().value()
+    }
+
+    async fn digest(&self) -> String {
+        encode_digest(&self.0.digest())
+    }
+
+    /// The Move type of the object, or null for packages.
+    #[graphql(name = "type")]
+    async fn type_(&self) -> Option<String> {
+        self.0.type_().map(|type_| type_.to_string())
+    }
+
+    async fn owner(&self) -> OwnerNode {
+        OwnerNode(self.0.owner)
+    }
+
+    /// The BCS encoded contents of the Move object, or null for packages.
+    async fn bcs(&self) -> Option<String> {
+        self.0
+            .data
+            .try_as_move()
+            .map(|object| Base64::from_bytes(object.contents()).encoded())
+    }
+
+    /// The transaction which wrote this version of the object.
+    async fn previous_transaction(&self, ctx: &Context<'_>) -> Result<Option<TransactionNode>> {
+        Query
+            .transaction(ctx, encode_digest(&self.0.previous_transaction))
+            .await
+    }
+
+    /// The Move package, if the object is one.
+    async fn as_package(&self) -> Option<PackageNode> {
+        ObjectNode(self.0.clone()).package()
+    }
+
+    /// The objects this object owns, in the order of their IDs.
+    async fn objects(
+        &self,
+        ctx: &Context<'_>,
+        first: Option<i32>,
+        after: Option<String>,
+    ) -> Result<Connection<String, ObjectNode>> {
+        let owner = Owner::ObjectOwner(self.0.id().into());
+        objects_connection(state(ctx)?, owner, first, after).await
+    }
+
+    /// The transactions which created or mutated the object.
+    async fn transactions(
+        &self,
+        ctx: &Context<'_>,
+        first: Option<i32>,
+        after: Option<String>,
+    ) -> Result<Connection<String, TransactionNode>> {
+        let state = state(ctx)?;
+        let transactions = state
+            .get_transactions_by_mutated_object(self.0.id())
+            .await?;
+        transactions_connection(state, transactions, first, after).await
+    }
+}
+
+#[derive(Enum, Copy, Clone, Eq, PartialEq)]
+pub enum OwnerKind {
+    Address,
+    Object,
+    Shared,
+    Immutable,
+    Party,
+}
+
+pub struct OwnerNode(Owner);
+
+#[Object(name = "Owner")]
+impl OwnerNode {
+    async fn kind(&self) -> OwnerKind {
+        match self.0 {
+            Owner::AddressOwner(_) => OwnerKind::Address,
+            Owner::ObjectOwner(_) => OwnerKind::Object,
+            Owner::Shared => OwnerKind::Shared,
+            Owner::Immutable => OwnerKind::Immutable,
+            Owner::Party(_) => OwnerKind::Party,
+        }
+    }
+
+    /// The address of the owner, or the ID of the owning object, or null for shared and
+    /// immutable objects.
+    async fn address(&self) -> Option<String> {
+        self.0
+            .get_owner_address()
+            .ok()
+            .map(|address| address.to_string())
+    }
+}
+
+pub struct PackageNode(MovePackage);
+
+#[Object(name = "Package")]
+impl PackageNode {
+    async fn id(&self) -> String {
+        self.0.id().to_string()
+    }
+
+    async fn version(&self) -> u64 {
+        self.0.version().value()
+    }
+
+    /// The modules of the package, in the order of their names.
+    async fn modules(&self) -> Result<Vec<MoveModuleNode>> {
+        self.0
+            .serialized_module_map()
+            .iter()
+            .map(|(name, bytes)| {
+                let module = move_binary_format::CompiledModule::deserialize(bytes)
+                    .map_err(|error| anyhow!("Invalid module {name}: {error}"))?;
+                Ok(MoveModuleNode {
+                    module: normalized::Module::new(&module),
+                    bytecode: Base64::from_bytes(bytes).encoded(),
+                })
+            })
+            .collect()
+    }
+}
+
+pub struct MoveModuleNode {
+    module: normalized::Module,
+    bytecode: String,
+}
+
+#[Object(name = "MoveModule")]
+impl MoveModuleNode {
+    async fn name(&self) -> String {
+        self.module.name.to_string()
+    }
+
+    /// The structs the module declares, by name.
+    async fn structs(&self) -> Vec<String> {
+        self.module
+            .structs
+            .keys()
+            .map(ToString::to_string)
+            .collect()
+    }
+
+    /// The functions of the module callable from other modules or from transactions.
+    async fn functions(&self) -> Vec<MoveFunctionNode> {
+        self.module
+            .exposed_functions
+            .iter()
+            .map(|(name, function)| MoveFunctionNode {
+                name: name.to_string(),
+                visibility: match function.visibility {
+                    Visibility::Private => "private",
+                    Visibility::Public => "public",
+                    Visibility::Script => "script",
+                    Visibility::Friend => "friend",
+                }
+                .to_string(),
+                type_parameter_count: function.type_parameters.len() as u64,
+                parameter_count: function.parameters.len() as u64,
+            })
+            .collect()
+    }
+
+    /// The bytecode of the module, base64 encoded.
+    async fn bytecode(&self) -> &str {
+        &self.bytecode
+    }
+}
+
+#[derive(SimpleObject)]
+#[graphql(name = "MoveFunction")]
+pub struct MoveFunctionNode {
+    name: String,
+    visibility: String,
+    type_parameter_count: u64,
+    parameter_count: u64,
+}
+
+pub struct TransactionNode {
+    certificate: CertifiedTransaction,
+    effects: TransactionEffects,
+}
+
+impl TransactionNode {
+    /// The objects written by the transaction, at the versions it wrote.
+    async fn objects_at_versions<'a>(
+        &self,
+        ctx: &Context<'_>,
+        objects: impl Iterator<Item = &'a (ObjectRef, Owner)>,
+    ) -> Result<Vec<ObjectNode>> {
+        let state = state(ctx)?;
+        let mut nodes = Vec::new();
+        for ((object_id, version, _), _) in objects {
+            if let Some(object) = state
+                .get_object_at_version(object_id, Some(*version))
+                .await?
+            {
+                nodes.push(ObjectNode(object));
+            }
+        }
+        Ok(nodes)
+    }
+}
+
+#[derive(SimpleObject)]
+#[graphql(name = "GasCost")]
+pub struct GasCostNode {
+    computation_cost: u64,
+    storage_cost: u64,
+    storage_rebate: u64,
+}
+
+#[Object(name = "Transaction")]
+impl TransactionNode {
+    async fn digest(&self) -> String {
+        encode_digest(self.certificate.digest())
+    }
+
+    async fn sender(&self) -> AddressNode {
+        AddressNode(self.certificate.data.signer())
+    }
+
+    async fn gas_budget(&self) -> u64 {
+        self.certificate.data.gas_budget
+    }
+
+    /// Whether the transaction executed successfully.
+    async fn success(&self) -> bool {
+        self.effects.status.is_ok()
+    }
+
+    /// The error the transaction failed with, or null if it succeeded.
+    async fn error(&self) -> Option<String> {
+        match &self.effects.status {
+            ExecutionStatus::Success { .. } => None,
+            ExecutionStatus::Failure { error, .. } => Some(error.to_string()),
+        }
+    }
+
+    async fn gas_cost(&self) -> GasCostNode {
+        let gas_cost = self.effects.status.gas_cost_summary();
+        GasCostNode {
+            computation_cost: gas_cost.computation_cost,
+            storage_cost: gas_cost.storage_cost,
+            storage_rebate: gas_cost.storage_rebate,
+        }
+    }
+
+    /// The events the transaction emitted, in the order it emitted them.
+    async fn events(&self) -> Vec<EventNode> {
+        self.effects.events.iter().cloned().map(EventNode).collect()
+    }
+
+    /// The objects the transaction created, at the versions it created them at.
+    async fn created(&self, ctx: &Context<'_>) -> Result<Vec<ObjectNode>> {
+        self.objects_at_versions(ctx, self.effects.created.iter())
+            .await
+    }
+
+    /// The objects the transaction mutated or unwrapped, at the versions it wrote, including
+    /// the gas coin.
+    async fn mutated(&self, ctx: &Context<'_>) -> Result<Vec<ObjectNode>> {
+        self.objects_at_versions(
+            ctx,
+            self.effects
+                .mutated
+                .iter()
+                .chain(self.effects.unwrapped.iter()),
+        )
+        .await
+    }
+
+    /// The IDs of the objects the transaction deleted or wrapped.
+    async fn removed_object_ids(&self) -> Vec<String> {
+        self.effects
+            .deleted
+            .iter()
+            .chain(&self.effects.wrapped)
+            .chain(&self.effects.unwrapped_then_deleted)
+            .map(|(object_id, _, _)| object_id.to_string())
+            .collect()
+    }
+}
+
+#[derive(Enum, Copy, Clone, Eq, PartialEq)]
+pub enum EventKind {
+    Move,
+    Publish,
+    TransferObject,
+    DeleteObject,
+    NewObject,
+    EpochChange,
+    Checkpoint,
+}
+
+pub struct EventNode(Event);
+
+#[Object(name = "Event")]
+impl EventNode {
+    async fn kind(&self) -> EventKind {
+        match self.0 {
+            Event::MoveEvent { .. } => EventKind::Move,
+            Event::Publish { .. } => EventKind::Publish,
+            Event::TransferObject { .. } => EventKind::TransferObject,
+            Event::DeleteObject(_) => EventKind::DeleteObject,
+            Event::NewObject(_) => EventKind::NewObject,
+            Event::EpochChange(_) => EventKind::EpochChange,
+            Event::Checkpoint(_) => EventKind::Checkpoint,
+        }
+    }
+
+    /// The Move type of the event, for Move events.
+    async fn move_type(&self) -> Option<String> {
+        match &self.0 {
+            Event::MoveEvent { type_, .. } => Some(type_.to_string()),
+            _ => None,
+        }
+    }
+
+    /// The BCS encoded contents of the event, for Move events.
+    async fn bcs(&self) -> Option<String> {
+        match &self.0 {
+            Event::MoveEvent { contents, .. } => Some(Base64::from_bytes(contents).encoded()),
+            _ => None,
+        }
+    }
+
+    /// The ID of the object transferred, deleted or created, or of the package published.
+    async fn object_id(&self) -> Option<String> {
+        self.0.object_id().map(|object_id| object_id.to_string())
+    }
+
+    /// The address an object was transferred to, for transfers.
+    async fn recipient(&self) -> Option<AddressNode> {
+        match &self.0 {
+            Event::TransferObject {
+                destination_addr, ..
+            } => Some(AddressNode(*destination_addr)),
+            _ => None,
+        }
+    }
+}
@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::api::SuiRpcModule;
+use crate::json_rpc_front::spawn_front;
 use anyhow::Result;
 use jsonrpsee::{
     http_server::{AccessControlBuilder, HttpServerBuilder, HttpServerHandle},
@@ -12,14 +13,47 @@ use once_cell::sync::Lazy;
 use prometheus_exporter::prometheus::{
     register_histogram_vec, register_int_counter_vec, HistogramVec, IntCounterVec,
 };
-use std::{env, net::SocketAddr, time::Instant};
+use std::{
+    collections::HashSet,
+    env,
+    net::{IpAddr, Ipv4Addr, SocketAddr},
+    sync::Arc,
+    time::Instant,
+};
+use sui_config::JsonRpcServerConfig;
 use sui_open_rpc::Project;
+use tokio::task::JoinHandle;
 use tracing::info;
 
 pub struct JsonRpcServerBuilder {
     module: RpcModule<()>,
-    server_builder: HttpServerBuilder<JsonRpcMetrics>,
+    server_builder: HttpServerBuilder,
     rpc_doc: Project,
+    config: JsonRpcServerConfig,
+}
+
+/// A running JSON-RPC server, with its front.
+pub struct JsonRpcServerHandle {
+    server: HttpServerHandle,
+    front: JoinHandle<()>,
+    local_addr: SocketAddr,
+}
+
+impl JsonRpcServerHandle {
+    /// The address the front listens on, which clients connect to.
+    pub fn local_addr(&self) -> SocketAddr {
+        self.local_addr
+    }
+
+    pub fn stop(self) -> Result<()> {
+        self.front.abort();
+        self.server.stop().map_err(Into::into)
+    }
+
+    /// Wait for the server to stop.
+    pub async fn stopped(self) {
+        self.server.await
+    }
 }
 
 pub fn sui_rpc_doc() -> Project {
@@ -36,9 +70,16 @@ pub fn sui_rpc_doc() -> Project {
 
 impl JsonRpcServerBuilder {
     pub fn new() -> Result<Self> {
+        Self::new_with_config(JsonRpcServerConfig::default())
+    }
+
+    pub fn new_with_config(config: JsonRpcServerConfig) -> Result<Self> {
         let mut ac_builder = AccessControlBuilder::default();
 
-        if let Ok(value) = env::var("ACCESS_CONTROL_ALLOW_ORIGIN") {
+        if let Some(origins) = &config.allowed_origins {
+            info!("Setting the allowed origins to : {:?}", origins);
+            ac_builder = ac_builder.set_allowed_origins(origins)?;
+        } else if let Ok(value) = env::var("ACCESS_CONTROL_ALLOW_ORIGIN") {
             let list = value.split(',').collect::<Vec<_>>();
             info!("Setting ACCESS_CONTROL_ALLOW_ORIGIN to : {:?}", list);
             ac_builder = ac_builder.set_allowed_origins(list)?;
@@ -48,8 +89,8 @@ impl JsonRpcServerBuilder {
         info!(?acl);
 
         let server_builder = HttpServerBuilder::default()
-            .set_access_control(acl)
-            .set_middleware(JsonRpcMetrics::new());
+            .max_request_body_size(config.max_request_body_size)
+            .set_access_control(acl);
 
         let module = RpcModule::new(());
 
@@ -57,6 +98,7 @@ impl JsonRpcServerBuilder {
             module,
             server_builder,
             rpc_doc: sui_rpc_doc(),
+            config,
         })
     }
 
@@ -65,11 +107,17 @@ impl JsonRpcServerBuilder {
         self.module.merge(module.rpc()).map_err(Into::into)
     }
 
-    pub async fn start(mut self, listen_address: SocketAddr) -> Result<HttpServerHandle> {
+    pub async fn start(mut self, listen_address: SocketAddr) -> Result<JsonRpcServerHandle> {
         self.module
             .register_method("rpc.discover", move |_, _| Ok(self.rpc_doc.clone()))?;
 
-        let server = self.server_builder.build(listen_address).await?;
+        // The front listens on the address of the server, and the server only on the loopback
+        // interface behind it.
+        let server = self
+            .server_builder
+            .set_middleware(JsonRpcMetrics::new(self.module.method_names()))
+            .build(SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), 0))
+            .await?;
 
         let addr = server.local_addr()?;
         info!(local_addr =? addr, "Sui JSON-RPC server listening on {addr}");
@@ -78,7 +126,12 @@ impl JsonRpcServerBuilder {
             self.module.method_names().collect::<Vec<_>>()
         );
 
-        server.start(self.module).map_err(Into::into)
+        let (local_addr, front) = spawn_front(listen_address, addr, &self.config).await?;
+        Ok(JsonRpcServerHandle {
+            server: server.start(self.module)?,
+            front,
+            local_addr,
+        })
     }
 }
 
@@ -90,10 +143,15 @@ struct JsonRpcMetrics {
     req_latency_by_route: HistogramVec,
     /// Failed requests by route
     errors_by_route: IntCounterVec,
+    /// The methods the server serves. Requests for any other method are counted under the
+    /// `unknown` route, so that clients can't create a timeseries per method name they send.
+    routes: Arc<HashSet<&'static str>>,
 }
 
 impl JsonRpcMetrics {
-    pub fn new() -> Self {
+    const UNKNOWN_ROUTE: &'static str = "unknown";
+
+    pub fn new(routes: impl Iterator<Item = &'static str>) -> Self {
         static METRICS: Lazy<JsonRpcMetrics> = Lazy::new(|| JsonRpcMetrics {
             requests_by_route: register_int_counter_vec!(
                 "rpc_requests_by_route",
@@ -113,9 +171,13 @@ impl JsonRpcMetrics {
                 &["route"]
             )
             .unwrap(),
+            routes: Default::default(),
         });
 
-        Lazy::force(&METRICS).clone()
+        Self {
+            routes: Arc::new(routes.collect()),
+            ..Lazy::force(&METRICS).clone()
+        }
     }
 }
 
@@ -127,6 +189,11 @@ impl Middleware for JsonRpcMetrics {
     }
 
     fn on_result(&self, name: &str, success: bool, started_at: Instant) {
+        let name = if self.routes.contains(name) {
+            name
+        } else {
+            Self::UNKNOWN_ROUTE
+        };
         self.requests_by_route.with_label_values(&[name]).inc();
         let req_latency_secs = (Instant::now() - started_at).as_secs_f64();
         self.req_latency_by_route
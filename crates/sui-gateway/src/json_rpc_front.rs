@@ -0,0 +1,354 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! The front of the JSON-RPC server, for what the jsonrpsee server does not do: terminating TLS,
+//! authorizing the methods which write, and tracing requests. The front listens on the address
+//! of the server, reads each request, checks its bearer token if it calls a method which writes,
+//! and forwards it to the jsonrpsee server listening on the loopback interface, which still
+//! checks the origins.
+//!
+//! Each request is traced with the ID the client sets in the `X-Request-Id` header, or else one
+//! the front draws. The ID is returned in the same header, and in the `data` of the errors of
+//! the response, and the span of the request records the digest of the transaction it submits,
+//! if any, which the logs of the validators name, so that support can follow a failed submission
+//! from the client to the validators.
+
+use std::fs::File;
+use std::io::BufReader;
+use std::net::SocketAddr;
+use std::path::Path;
+use std::sync::Arc;
+
+use anyhow::{anyhow, Result};
+use hyper::body::HttpBody;
+use hyper::client::HttpConnector;
+use hyper::header::{HeaderValue, AUTHORIZATION, CONTENT_LENGTH, CONTENT_TYPE};
+use hyper::server::conn::Http;
+use hyper::service::service_fn;
+use hyper::{Body, Client, Request, Response, StatusCode, Uri};
+use serde_json::{json, Value};
+use sui_config::{JsonRpcServerConfig, TlsConfig};
+use sui_types::base_types::TransactionDigest;
+use sui_types::crypto::sha3_hash;
+use sui_types::messages::TransactionData;
+use sui_types::sui_serde::Base64;
+use tokio::net::TcpListener;
+use tokio::task::JoinHandle;
+use tokio_rustls::rustls::{Certificate, PrivateKey, ServerConfig};
+use tokio_rustls::TlsAcceptor;
+use tracing::{debug, error, info, info_span, warn, Instrument};
+
+/// The header of the ID of a request, set by the client or else by the front.
+pub const REQUEST_ID_HEADER: &str = "x-request-id";
+
+/// The longest request ID accepted from a client; longer ones are replaced.
+const MAX_REQUEST_ID_LENGTH: usize = 128;
+
+/// The methods which write, which require the bearer token if the server has one.
+pub const WRITE_METHODS: &[&str] = &[
+    "sui_executeTransaction",
+    "sui_executeMultiSigTransaction",
+    "sui_executeZkLoginTransaction",
+    "sui_submitTransaction",
+    "sui_syncAccountState",
+    "sui_advanceEpoch",
+];
+
+struct JsonRpcFront {
+    tls: Option<TlsAcceptor>,
+    write_auth_token: Option<String>,
+    max_request_body_size: u32,
+    /// The address of the jsonrpsee server.
+    backend: SocketAddr,
+    client: Client<HttpConnector>,
+}
+
+/// Listen on `listen_address` and forward the requests allowed by `config` to the jsonrpsee
+/// server at `backend`. Returns the address listened on, and the task serving it.
+pub async fn spawn_front(
+    listen_address: SocketAddr,
+    backend: SocketAddr,
+    config: &JsonRpcServerConfig,
+) -> Result<(SocketAddr, JoinHandle<()>)> {
+    let front = Arc::new(JsonRpcFront {
+        tls: config.tls.as_ref().map(tls_acceptor).transpose()?,
+        write_auth_token: config.write_auth_token.clone(),
+        max_request_body_size: config.max_request_body_size,
+        backend,
+        client: Client::new(),
+    });
+    let listener = TcpListener::bind(listen_address).await?;
+    let local_addr = listener.local_addr()?;
+    info!(
+        tls = front.tls.is_some(),
+        write_auth = front.write_auth_token.is_some(),
+        "JSON-RPC front listening on {local_addr}"
+    );
+    let handle = tokio::spawn(async move {
+        loop {
+            let (stream, peer) = match listener.accept().await {
+                Ok(connection) => connection,
+                Err(error) => {
+                    warn!(?error, "Failed to accept a JSON-RPC connection");
+                    continue;
+                }
+            };
+            let front = front.clone();
+            tokio::spawn(async move {
+                let service = {
+                    let front = front.clone();
+                    service_fn(move |request| {
+                        let front = front.clone();
+                        async move { front.handle(request).await }
+                    })
+                };
+                let result = match &front.tls {
+                    Some(acceptor) => match acceptor.accept(stream).await {
+                        Ok(stream) => Http::new().serve_connection(stream, service).await,
+                        Err(error) => {
+                            debug!(?error, %peer, "TLS handshake failed");
+                            return;
+                        }
+                    },
+                    None => Http::new().serve_connection(stream, service).await,
+                };
+                if let Err(error) = result {
+                    debug!(?error, %peer, "JSON-RPC connection failed");
+                }
+            });
+        }
+    });
+    Ok((local_addr, handle))
+}
+
+impl JsonRpcFront {
+    async fn handle(&self, request: Request<Body>) -> Result<Response<Body>, hyper::Error> {
+        let request_id = request
+            .headers()
+            .get(REQUEST_ID_HEADER)
+            .and_then(|value| value.to_str().ok())
+            .filter(|id| !id.is_empty() && id.len() <= MAX_REQUEST_ID_LENGTH)
+            .map(str::to_string)
+            .unwrap_or_else(|| format!("{:016x}", rand::random::<u64>()));
+        let span = info_span!(
+            "json_rpc_request",
+            request_id = %request_id,
+            method = tracing::field::Empty,
+            tx_digest = tracing::field::Empty,
+        );
+        let mut response = self
+            .handle_traced(request, &request_id)
+            .instrument(span)
+            .await?;
+        if let Ok(value) = HeaderValue::from_str(&request_id) {
+            response.headers_mut().insert(REQUEST_ID_HEADER, value);
+        }
+        Ok(response)
+    }
+
+    async fn handle_traced(
+        &self,
+        request: Request<Body>,
+        request_id: &str,
+    ) -> Result<Response<Body>, hyper::Error> {
+        let (mut parts, body) = request.into_parts();
+        let body = match read_body(body, self.max_request_body_size).await? {
+            Some(body) => body,
+            None => {
+                return Ok(error_response(
+                    StatusCode::PAYLOAD_TOO_LARGE,
+                    "The request body is too large",
+                ))
+            }
+        };
+        let calls = parse_calls(&body);
+        if let [call] = calls.as_slice() {
+            let span = tracing::Span::current();
+            if let Some(method) = call.get("method").and_then(Value::as_str) {
+                span.record("method", &method);
+            }
+            if let Some(digest) = submitted_transaction(call) {
+                span.record("tx_digest", &tracing::field::debug(digest));
+            }
+        }
+        if let Some(token) = &self.write_auth_token {
+            let authorized = parts
+                .headers
+                .get(AUTHORIZATION)
+                .and_then(|value| value.to_str().ok())
+                .and_then(|value| value.strip_prefix("Bearer "))
+                .map_or(false, |presented| tokens_match(presented, token));
+            if !authorized && calls.iter().any(is_write_call) {
+                return Ok(error_response(
+                    StatusCode::UNAUTHORIZED,
+                    "The method requires a bearer token",
+                ));
+            }
+        }
+
+        let path = parts
+            .uri
+            .path_and_query()
+            .map_or("/", |path| path.as_str())
+            .to_string();
+        parts.uri = match Uri::builder()
+            .scheme("http")
+            .authority(self.backend.to_string())
+            .path_and_query(path)
+            .build()
+        {
+            Ok(uri) => uri,
+            Err(_) => return Ok(error_response(StatusCode::BAD_REQUEST, "Invalid URI")),
+        };
+        let response = match self
+            .client
+            .request(Request::from_parts(parts, Body::from(body)))
+            .await
+        {
+            Ok(response) => response,
+            Err(error) => {
+                error!(?error, "Failed to forward a JSON-RPC request");
+                return Ok(error_response(
+                    StatusCode::BAD_GATEWAY,
+                    "The JSON-RPC server is unavailable",
+                ));
+            }
+        };
+
+        // Name the request in the errors of the response, for the client to report it.
+        let (mut parts, body) = response.into_parts();
+        let mut body = hyper::body::to_bytes(body).await?.to_vec();
+        let mut response = match serde_json::from_slice::<Value>(&body) {
+            Ok(mut value) => {
+                let mut tagged = false;
+                for response in call_responses(&mut value) {
+                    if let Some(error) = response.get_mut("error") {
+                        warn!(?error, "JSON-RPC call failed");
+                        tag_error(error, request_id);
+                        tagged = true;
+                    }
+                }
+                if tagged {
+                    body = serde_json::to_vec(&value).unwrap_or(body);
+                    parts.headers.remove(CONTENT_LENGTH);
+                }
+                Response::from_parts(parts, Body::from(body))
+            }
+            Err(_) => Response::from_parts(parts, Body::from(body)),
+        };
+        if response.status().is_server_error() {
+            error!(status = %response.status(), "The JSON-RPC server failed");
+        }
+        Ok(response)
+    }
+}
+
+/// The body of a request, unless it is larger than `limit`.
+pub(crate) async fn read_body(mut body: Body, limit: u32) -> Result<Option<Vec<u8>>, hyper::Error> {
+    let mut bytes = Vec::new();
+    while let Some(chunk) = body.data().await {
+        let chunk = chunk?;
+        if bytes.len() + chunk.len() > limit as usize {
+            return Ok(None);
+        }
+        bytes.extend_from_slice(&chunk);
+    }
+    Ok(Some(bytes))
+}
+
+/// The calls of a request, or of a batch of requests. Malformed requests have none, and are left
+/// to the jsonrpsee server to reject.
+fn parse_calls(body: &[u8]) -> Vec<Value> {
+    match serde_json::from_slice(body) {
+        Ok(Value::Array(calls)) => calls,
+        Ok(call) => vec![call],
+        Err(_) => Vec::new(),
+    }
+}
+
+/// The responses to the calls of a response, or of a batch of responses.
+fn call_responses(value: &mut Value) -> Vec<&mut Value> {
+    match value {
+        Value::Array(responses) => responses.iter_mut().collect(),
+        response => vec![response],
+    }
+}
+
+fn is_write_call(call: &Value) -> bool {
+    call.get("method")
+        .and_then(Value::as_str)
+        .map_or(false, |method| WRITE_METHODS.contains(&method))
+}
+
+/// The digest of the transaction the call submits or dry runs, the data of which is the first
+/// parameter, `tx_bytes`, of the methods taking one.
+fn submitted_transaction(call: &Value) -> Option<TransactionDigest> {
+    let params = call.get("params")?;
+    let tx_bytes = params.get(0).or_else(|| params.get("tx_bytes"))?.as_str()?;
+    let bytes = Base64::try_from(tx_bytes.to_string()).ok()?.to_vec().ok()?;
+    let data = TransactionData::from_signable_bytes(&bytes).ok()?;
+    Some(TransactionDigest::new(sha3_hash(&data)))
+}
+
+/// Add the ID of the request to the `data` of `error`, unless its data is not an object.
+fn tag_error(error: &mut Value, request_id: &str) {
+    let error = match error {
+        Value::Object(error) => error,
+        _ => return,
+    };
+    match error.get_mut("data") {
+        Some(Value::Object(data)) => {
+            data.insert("requestId".to_string(), json!(request_id));
+        }
+        Some(Value::Null) | None => {
+            error.insert("data".to_string(), json!({ "requestId": request_id }));
+        }
+        Some(_) => {}
+    }
+}
+
+/// Compare the tokens in a time independent of where they differ.
+fn tokens_match(presented: &str, token: &str) -> bool {
+    presented.len() == token.len()
+        && presented
+            .bytes()
+            .zip(token.bytes())
+            .fold(0, |difference, (a, b)| difference | (a ^ b))
+            == 0
+}
+
+pub(crate) fn error_response(status: StatusCode, message: &str) -> Response<Body> {
+    let mut response = Response::new(Body::from(message.to_string()));
+    *response.status_mut() = status;
+    response
+        .headers_mut()
+        .insert(CONTENT_TYPE, "text/plain".parse().unwrap());
+    response
+}
+
+fn tls_acceptor(config: &TlsConfig) -> Result<TlsAcceptor> {
+    let certs = rustls_pemfile::certs(&mut open(&config.cert_path)?)?
+        .into_iter()
+        .map(Certificate)
+        .collect();
+    let key = rustls_pemfile::read_all(&mut open(&config.key_path)?)?
+        .into_iter()
+        .find_map(|item| match item {
+            rustls_pemfile::Item::PKCS8Key(key)
+            | rustls_pemfile::Item::RSAKey(key)
+            | rustls_pemfile::Item::ECKey(key) => Some(PrivateKey(key)),
+            _ => None,
+        })
+        .ok_or_else(|| anyhow!("No private key in {}", config.key_path.display()))?;
+    let config = ServerConfig::builder()
+        .with_safe_defaults()
+        .with_no_client_auth()
+        .with_single_cert(certs, key)?;
+    Ok(TlsAcceptor::from(Arc::new(config)))
+}
+
+fn open(path: &Path) -> Result<BufReader<File>> {
+    Ok(BufReader::new(File::open(path).map_err(|error| {
+        anyhow!("Cannot open {}: {error}", path.display())
+    })?))
+}
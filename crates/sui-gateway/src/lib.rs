@@ -4,7 +4,12 @@
 pub mod api;
 pub mod bcs_api;
 pub mod config;
+pub mod epoch_admin_api;
+pub mod graphql;
 pub mod json_rpc;
+pub mod json_rpc_front;
 pub mod read_api;
 pub mod rpc_gateway;
 pub mod rpc_gateway_client;
+pub mod transaction_builder_api;
+pub mod transaction_execution_api;
@@ -2,41 +2,113 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::api::RpcReadApiServer;
-use crate::api::{RpcFullNodeReadApiServer, SuiRpcModule};
+use crate::api::{
+    check_multi_get_size, verify_personal_message, CheckpointsPage, ChildObjectsPage,
+    RpcFullNodeReadApiServer, SuiRpcModule, DEFAULT_CHECKPOINTS_PAGE_SIZE,
+    DEFAULT_CHILD_OBJECTS_PAGE_SIZE, MAX_CHECKPOINTS_PAGE_SIZE, MAX_CHILD_OBJECTS_PAGE_SIZE,
+};
 use anyhow::anyhow;
+use arc_swap::ArcSwap;
 use async_trait::async_trait;
+use futures::future::join_all;
 use jsonrpsee::core::RpcResult;
 use jsonrpsee_core::server::rpc_module::RpcModule;
 use std::sync::Arc;
+use sui_config::JsonRpcLimits;
+use sui_core::archive::ArchiveReader;
 use sui_core::gateway_state::GatewayTxSeqNumber;
-use sui_core::gateway_types::SuiObjectInfo;
+use sui_core::gateway_types::{
+    CheckpointId, SuiCheckpoint, SuiFinalityProof, SuiObjectInfo, SuiStake, SuiSystemStateSummary,
+    SuiValidatorApys, SuiValidatorReport,
+};
 use sui_core::{
     authority::AuthorityState,
-    gateway_types::{GetObjectDataResponse, TransactionEffectsResponse},
+    gateway_types::{
+        GetObjectDataResponse, MultiGetResult, SuiObjectDataOptions, SuiTransactionResponseOptions,
+        TransactionEffectsResponse,
+    },
 };
 use sui_open_rpc::Module;
 use sui_types::base_types::{ObjectID, SuiAddress, TransactionDigest};
-use sui_types::object::Owner;
+use sui_types::error::SuiError;
+use sui_types::messages_checkpoint::CheckpointSequenceNumber;
+use sui_types::object::{ObjectRead, Owner};
+use sui_types::order_book::OrderBookEventEnvelope;
+use sui_types::sui_serde::Base64;
 
 // An implementation of the read portion of the Gateway JSON-RPC interface intended for use in
 // Fullnodes.
 pub struct ReadApi {
     pub state: Arc<AuthorityState>,
+    /// The limits of the node, which its config can change while it runs.
+    pub limits: Arc<ArcSwap<JsonRpcLimits>>,
+    /// Where the transactions the node doesn't store are read from.
+    pub archive: Option<ArchiveReader>,
+    /// Where the clients asking for data the node pruned are pointed at.
+    pub archive_url: Option<String>,
 }
 
 pub struct FullNodeApi {
     pub state: Arc<AuthorityState>,
+    /// Where the clients asking for data the node pruned are pointed at.
+    pub archive_url: Option<String>,
 }
 
 impl FullNodeApi {
     pub fn new(state: Arc<AuthorityState>) -> Self {
-        Self { state }
+        Self {
+            state,
+            archive_url: None,
+        }
+    }
+
+    pub fn with_archive_url(mut self, archive_url: Option<String>) -> Self {
+        self.archive_url = archive_url;
+        self
     }
 }
 
 impl ReadApi {
     pub fn new(state: Arc<AuthorityState>) -> Self {
-        Self { state }
+        Self {
+            state,
+            limits: Default::default(),
+            archive: None,
+            archive_url: None,
+        }
+    }
+
+    pub fn with_limits(mut self, limits: Arc<ArcSwap<JsonRpcLimits>>) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    pub fn with_archive(mut self, archive: ArchiveReader) -> Self {
+        self.archive = Some(archive);
+        self
+    }
+
+    pub fn with_archive_url(mut self, archive_url: Option<String>) -> Self {
+        self.archive_url = archive_url;
+        self
+    }
+}
+
+/// Point the clients asking for data the node pruned at `archive_url`, where they can read it.
+fn point_at_archive(error: anyhow::Error, archive_url: &Option<String>) -> anyhow::Error {
+    match error.downcast::<SuiError>() {
+        Ok(SuiError::DataPruned {
+            lowest_checkpoint,
+            next_checkpoint,
+            archive: None,
+        }) => SuiError::DataPruned {
+            lowest_checkpoint,
+            next_checkpoint,
+            archive: archive_url.clone(),
+        }
+        .into(),
+        Ok(error) => error.into(),
+        Err(error) => error,
     }
 }
 
@@ -45,10 +117,17 @@ impl RpcReadApiServer for ReadApi {
     async fn get_objects_owned_by_address(
         &self,
         address: SuiAddress,
+        at_checkpoint: Option<CheckpointSequenceNumber>,
     ) -> RpcResult<Vec<SuiObjectInfo>> {
-        Ok(self
-            .state
-            .get_owner_objects(Owner::AddressOwner(address))
+        let objects = match at_checkpoint {
+            Some(checkpoint) => {
+                self.state
+                    .get_owner_objects_at_checkpoint(address, checkpoint)
+                    .await
+            }
+            None => self.state.get_owner_objects(Owner::AddressOwner(address)),
+        };
+        Ok(objects
             .map_err(|e| anyhow!("{e}"))?
             .into_iter()
             .map(SuiObjectInfo::from)
@@ -68,13 +147,48 @@ impl RpcReadApiServer for ReadApi {
             .collect())
     }
 
-    async fn get_object(&self, object_id: ObjectID) -> RpcResult<GetObjectDataResponse> {
-        Ok(self
-            .state
-            .get_object_read(&object_id)
-            .await
-            .map_err(|e| anyhow!("{e}"))?
-            .try_into()?)
+    async fn get_object(
+        &self,
+        object_id: ObjectID,
+        options: Option<SuiObjectDataOptions>,
+    ) -> RpcResult<GetObjectDataResponse> {
+        let options = options.unwrap_or_default();
+        let object_read = match options.at_checkpoint {
+            Some(checkpoint) => {
+                self.state
+                    .get_object_read_at_checkpoint(&object_id, checkpoint)
+                    .await
+            }
+            None => self.state.get_object_read(&object_id).await,
+        }
+        .map_err(|e| anyhow!("{e}"))?;
+        let display = match &object_read {
+            ObjectRead::Exists(_, object, _) if options.show_display => match object.type_() {
+                Some(type_) => self.state.get_display(type_).map_err(|e| anyhow!("{e}"))?,
+                None => None,
+            },
+            _ => None,
+        };
+        Ok(GetObjectDataResponse::try_from_with_options(
+            object_read,
+            display.as_ref(),
+            &options,
+        )?)
+    }
+
+    async fn multi_get_objects(
+        &self,
+        object_ids: Vec<ObjectID>,
+        options: Option<SuiObjectDataOptions>,
+    ) -> RpcResult<Vec<MultiGetResult<GetObjectDataResponse>>> {
+        check_multi_get_size(object_ids.len(), self.limits.load().max_multi_get_size)?;
+        let results = join_all(
+            object_ids
+                .into_iter()
+                .map(|object_id| self.get_object(object_id, options)),
+        )
+        .await;
+        Ok(results.into_iter().map(MultiGetResult::from).collect())
     }
 
     async fn get_total_transaction_number(&self) -> RpcResult<u64> {
@@ -99,8 +213,62 @@ impl RpcReadApiServer for ReadApi {
     async fn get_transaction(
         &self,
         digest: TransactionDigest,
+        options: Option<SuiTransactionResponseOptions>,
     ) -> RpcResult<TransactionEffectsResponse> {
-        Ok(self.state.get_transaction(digest).await?)
+        let options = options.unwrap_or_default();
+        let response = self
+            .state
+            .get_transaction(digest, options)
+            .await
+            .map_err(|error| point_at_archive(error, &self.archive_url));
+        let archive = match (&self.archive, &response) {
+            (Some(archive), Err(error))
+                if matches!(
+                    error.downcast_ref(),
+                    Some(SuiError::TransactionNotFound { .. } | SuiError::DataPruned { .. })
+                ) =>
+            {
+                archive
+            }
+            _ => return Ok(response?),
+        };
+        match archive
+            .get_transaction(&digest)
+            .await
+            .map_err(|e| anyhow!("{e}"))?
+        {
+            Some(archived) => Ok(TransactionEffectsResponse::from_certificate(
+                archived.certificate,
+                archived.effects,
+                archived.changes,
+                &options,
+            )?),
+            None => Ok(response?),
+        }
+    }
+
+    async fn multi_get_transactions(
+        &self,
+        digests: Vec<TransactionDigest>,
+        options: Option<SuiTransactionResponseOptions>,
+    ) -> RpcResult<Vec<MultiGetResult<TransactionEffectsResponse>>> {
+        check_multi_get_size(digests.len(), self.limits.load().max_multi_get_size)?;
+        let results = join_all(
+            digests
+                .into_iter()
+                .map(|digest| self.get_transaction(digest, options)),
+        )
+        .await;
+        Ok(results.into_iter().map(MultiGetResult::from).collect())
+    }
+
+    async fn verify_personal_message(
+        &self,
+        address: SuiAddress,
+        message: Base64,
+        signature: Base64,
+    ) -> RpcResult<bool> {
+        Ok(verify_personal_message(address, message, signature)?)
     }
 }
 
@@ -146,6 +314,92 @@ impl RpcFullNodeReadApiServer for FullNodeApi {
     ) -> RpcResult<Vec<(GatewayTxSeqNumber, TransactionDigest)>> {
         Ok(self.state.get_transactions_to_addr(addr).await?)
     }
+
+    async fn get_order_book_events(
+        &self,
+        pool: ObjectID,
+    ) -> RpcResult<Vec<OrderBookEventEnvelope>> {
+        Ok(self.state.get_order_book_events(pool).await?)
+    }
+
+    async fn get_child_objects(
+        &self,
+        parent_id: ObjectID,
+        cursor: Option<ObjectID>,
+        limit: Option<usize>,
+    ) -> RpcResult<ChildObjectsPage> {
+        let limit = limit.unwrap_or(DEFAULT_CHILD_OBJECTS_PAGE_SIZE);
+        check_multi_get_size(limit, MAX_CHILD_OBJECTS_PAGE_SIZE)?;
+        let (children, next_cursor) = self
+            .state
+            .get_child_objects(parent_id, cursor, limit)
+            .map_err(|e| anyhow!("{e}"))?;
+        Ok(ChildObjectsPage {
+            data: children.into_iter().map(SuiObjectInfo::from).collect(),
+            next_cursor,
+        })
+    }
+
+    async fn resolve_name_service_address(&self, name: String) -> RpcResult<Option<SuiAddress>> {
+        Ok(self.state.resolve_name_service_address(&name).await?)
+    }
+
+    async fn resolve_name_service_names(&self, address: SuiAddress) -> RpcResult<Vec<String>> {
+        Ok(self.state.resolve_name_service_names(address).await?)
+    }
+
+    async fn get_latest_sui_system_state(&self) -> RpcResult<SuiSystemStateSummary> {
+        Ok(self
+            .state
+            .get_sui_system_state_object()
+            .await
+            .map_err(|e| anyhow!("{e}"))?
+            .into())
+    }
+
+    async fn get_stakes(&self, owner: SuiAddress) -> RpcResult<Vec<SuiStake>> {
+        Ok(self.state.get_stakes(owner).await?)
+    }
+
+    async fn get_validators_apy(&self) -> RpcResult<SuiValidatorApys> {
+        Ok(self.state.get_validators_apy().await?)
+    }
+
+    async fn get_validator_report(&self, address: SuiAddress) -> RpcResult<SuiValidatorReport> {
+        Ok(self.state.get_validator_report(address).await?)
+    }
+
+    async fn get_checkpoint(&self, id: CheckpointId) -> RpcResult<SuiCheckpoint> {
+        Ok(self
+            .state
+            .get_checkpoint(id)
+            .map_err(|error| point_at_archive(error, &self.archive_url))?)
+    }
+
+    async fn get_checkpoints(
+        &self,
+        cursor: Option<CheckpointSequenceNumber>,
+        limit: Option<usize>,
+        descending_order: Option<bool>,
+    ) -> RpcResult<CheckpointsPage> {
+        let limit = limit.unwrap_or(DEFAULT_CHECKPOINTS_PAGE_SIZE);
+        check_multi_get_size(limit, MAX_CHECKPOINTS_PAGE_SIZE)?;
+        let (data, next_cursor) = self
+            .state
+            .get_checkpoints(cursor, limit, descending_order.unwrap_or_default())
+            .map_err(|error| point_at_archive(error, &self.archive_url))?;
+        Ok(CheckpointsPage { data, next_cursor })
+    }
+
+    async fn get_transaction_finality_proof(
+        &self,
+        digest: TransactionDigest,
+    ) -> RpcResult<SuiFinalityProof> {
+        Ok(self
+            .state
+            .get_finality_proof(digest)
+            .map_err(|error| point_at_archive(error, &self.archive_url))?)
+    }
 }
 
 impl SuiRpcModule for FullNodeApi {
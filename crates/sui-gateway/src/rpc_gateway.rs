@@ -6,7 +6,7 @@ use std::sync::Arc;
 
 use anyhow::anyhow;
 use async_trait::async_trait;
-use ed25519_dalek::ed25519::signature::Signature;
+use futures::future::join_all;
 use jsonrpsee::core::RpcResult;
 use jsonrpsee_core::server::rpc_module::RpcModule;
 use tracing::debug;
@@ -14,24 +14,28 @@ use tracing::debug;
 use crate::rpc_gateway::responses::SuiTypeTag;
 use crate::{
     api::{
-        RpcGatewayApiServer, RpcReadApiServer, RpcTransactionBuilderServer, SuiRpcModule,
-        TransactionBytes,
+        check_multi_get_size, verify_personal_message, RpcGatewayApiServer, RpcReadApiServer,
+        RpcTransactionBuilderServer, SuiRpcModule, TransactionBytes, MAX_MULTI_GET_SIZE,
     },
     config::GatewayConfig,
 };
 use sui_config::PersistedConfig;
 use sui_core::gateway_state::{GatewayClient, GatewayState, GatewayTxSeqNumber};
 use sui_core::gateway_types::{
-    GetObjectDataResponse, SuiObjectInfo, TransactionEffectsResponse, TransactionResponse,
+    GasProfileResponse, GetObjectDataResponse, MultiGetResult, SuiObjectDataOptions, SuiObjectInfo,
+    SuiTransactionEffects, SuiTransactionResponseOptions, TransactionEffectsResponse,
+    TransactionResponse,
 };
 use sui_json::SuiJsonValue;
 use sui_open_rpc::Module;
 use sui_types::sui_serde::Base64;
 use sui_types::{
     base_types::{ObjectID, SuiAddress, TransactionDigest},
-    crypto,
-    crypto::SignableBytes,
+    crypto::{GenericSignature, SignableBytes, SignatureScheme},
     messages::{Transaction, TransactionData},
+    messages_checkpoint::CheckpointSequenceNumber,
+    multisig::MultiSig,
+    zk_login::ZkLoginAuthenticator,
 };
 
 pub mod responses;
@@ -64,6 +68,15 @@ impl TransactionBuilderImpl {
     }
 }
 
+/// Fail if a read is pinned to a checkpoint, which the gateway doesn't keep.
+fn check_no_checkpoint(at_checkpoint: Option<CheckpointSequenceNumber>) -> anyhow::Result<()> {
+    anyhow::ensure!(
+        at_checkpoint.is_none(),
+        "Reads at a checkpoint are only served by full nodes"
+    );
+    Ok(())
+}
+
 pub fn create_client(config_path: &Path) -> Result<GatewayClient, anyhow::Error> {
     let config: GatewayConfig = PersistedConfig::read(config_path).map_err(|e| {
         anyhow!(
@@ -88,11 +101,14 @@ impl RpcGatewayApiServer for RpcGatewayImpl {
         tx_bytes: Base64,
         signature: Base64,
         pub_key: Base64,
+        sig_scheme: Option<SignatureScheme>,
     ) -> RpcResult<TransactionResponse> {
         let data = TransactionData::from_signable_bytes(&tx_bytes.to_vec()?)?;
-        let signature =
-            crypto::Signature::from_bytes(&[&*signature.to_vec()?, &*pub_key.to_vec()?].concat())
-                .map_err(|e| anyhow!(e))?;
+        let signature = GenericSignature::from_parts(
+            sig_scheme.unwrap_or(SignatureScheme::ED25519),
+            &signature.to_vec()?,
+            &pub_key.to_vec()?,
+        )?;
         let result = self
             .client
             .execute_transaction(Transaction::new(data, signature))
@@ -100,6 +116,42 @@ impl RpcGatewayApiServer for RpcGatewayImpl {
         Ok(result?)
     }
 
+    async fn execute_multisig_transaction(
+        &self,
+        tx_bytes: Base64,
+        multisig: MultiSig,
+    ) -> RpcResult<TransactionResponse> {
+        let data = TransactionData::from_signable_bytes(&tx_bytes.to_vec()?)?;
+        let result = self
+            .client
+            .execute_transaction(Transaction::new(data, multisig))
+            .await;
+        Ok(result?)
+    }
+
+    async fn execute_zk_login_transaction(
+        &self,
+        tx_bytes: Base64,
+        authenticator: ZkLoginAuthenticator,
+    ) -> RpcResult<TransactionResponse> {
+        let data = TransactionData::from_signable_bytes(&tx_bytes.to_vec()?)?;
+        let result = self
+            .client
+            .execute_transaction(Transaction::new(data, authenticator))
+            .await;
+        Ok(result?)
+    }
+
+    async fn dry_run_transaction(&self, tx_bytes: Base64) -> RpcResult<SuiTransactionEffects> {
+        let data = TransactionData::from_signable_bytes(&tx_bytes.to_vec()?)?;
+        Ok(self.client.dry_run_transaction(data).await?)
+    }
+
+    async fn profile_transaction_gas(&self, tx_bytes: Base64) -> RpcResult<GasProfileResponse> {
+        let data = TransactionData::from_signable_bytes(&tx_bytes.to_vec()?)?;
+        Ok(self.client.profile_transaction_gas(data).await?)
+    }
+
     async fn sync_account_state(&self, address: SuiAddress) -> RpcResult<()> {
         debug!("sync_account_state : {}", address);
         self.client.sync_account_state(address).await?;
@@ -122,8 +174,10 @@ impl RpcReadApiServer for GatewayReadApiImpl {
     async fn get_objects_owned_by_address(
         &self,
         address: SuiAddress,
+        at_checkpoint: Option<CheckpointSequenceNumber>,
     ) -> RpcResult<Vec<SuiObjectInfo>> {
         debug!("get_objects_own_by_address : {}", address);
+        check_no_checkpoint(at_checkpoint)?;
         Ok(self.client.get_objects_owned_by_address(address).await?)
     }
 
@@ -135,8 +189,32 @@ impl RpcReadApiServer for GatewayReadApiImpl {
         Ok(self.client.get_objects_owned_by_object(object_id).await?)
     }
 
-    async fn get_object(&self, object_id: ObjectID) -> RpcResult<GetObjectDataResponse> {
-        Ok(self.client.get_object(object_id).await?)
+    async fn get_object(
+        &self,
+        object_id: ObjectID,
+        options: Option<SuiObjectDataOptions>,
+    ) -> RpcResult<GetObjectDataResponse> {
+        let options = options.unwrap_or_default();
+        check_no_checkpoint(options.at_checkpoint)?;
+        Ok(self
+            .client
+            .get_object_with_options(object_id, options)
+            .await?)
+    }
+
+    async fn multi_get_objects(
+        &self,
+        object_ids: Vec<ObjectID>,
+        options: Option<SuiObjectDataOptions>,
+    ) -> RpcResult<Vec<MultiGetResult<GetObjectDataResponse>>> {
+        check_multi_get_size(object_ids.len(), MAX_MULTI_GET_SIZE)?;
+        let results = join_all(
+            object_ids
+                .into_iter()
+                .map(|object_id| self.get_object(object_id, options)),
+        )
+        .await;
+        Ok(results.into_iter().map(MultiGetResult::from).collect())
     }
 
     async fn get_recent_transactions(
@@ -149,8 +227,27 @@ impl RpcReadApiServer for GatewayReadApiImpl {
     async fn get_transaction(
         &self,
         digest: TransactionDigest,
+        options: Option<SuiTransactionResponseOptions>,
     ) -> RpcResult<TransactionEffectsResponse> {
-        Ok(self.client.get_transaction(digest).await?)
+        Ok(self
+            .client
+            .get_transaction(digest, options.unwrap_or_default())
+            .await?)
+    }
+
+    async fn multi_get_transactions(
+        &self,
+        digests: Vec<TransactionDigest>,
+        options: Option<SuiTransactionResponseOptions>,
+    ) -> RpcResult<Vec<MultiGetResult<TransactionEffectsResponse>>> {
+        check_multi_get_size(digests.len(), MAX_MULTI_GET_SIZE)?;
+        let results = join_all(
+            digests
+                .into_iter()
+                .map(|digest| self.get_transaction(digest, options)),
+        )
+        .await;
+        Ok(results.into_iter().map(MultiGetResult::from).collect())
     }
 
     async fn get_total_transaction_number(&self) -> RpcResult<u64> {
@@ -164,6 +261,15 @@ impl RpcReadApiServer for GatewayReadApiImpl {
     ) -> RpcResult<Vec<(GatewayTxSeqNumber, TransactionDigest)>> {
         Ok(self.client.get_transactions_in_range(start, end)?)
     }
+
+    async fn verify_personal_message(
+        &self,
+        address: SuiAddress,
+        message: Base64,
+        signature: Base64,
+    ) -> RpcResult<bool> {
+        Ok(verify_personal_message(address, message, signature)?)
+    }
 }
 
 impl SuiRpcModule for GatewayReadApiImpl {
@@ -193,6 +299,21 @@ impl RpcTransactionBuilderServer for TransactionBuilderImpl {
         Ok(TransactionBytes::from_data(data)?)
     }
 
+    async fn transfer_sui(
+        &self,
+        signer: SuiAddress,
+        sui_object_id: ObjectID,
+        gas_budget: u64,
+        recipient: SuiAddress,
+        amount: Option<u64>,
+    ) -> RpcResult<TransactionBytes> {
+        let data = self
+            .client
+            .transfer_sui(signer, sui_object_id, gas_budget, recipient, amount)
+            .await?;
+        Ok(TransactionBytes::from_data(data)?)
+    }
+
     async fn publish(
         &self,
         sender: SuiAddress,
@@ -9,11 +9,13 @@ use tokio::runtime::Handle;
 
 use sui_core::gateway_state::{GatewayAPI, GatewayTxSeqNumber};
 use sui_core::gateway_types::{
-    GetObjectDataResponse, GetRawObjectDataResponse, SuiObjectInfo, TransactionEffectsResponse,
-    TransactionResponse,
+    GasProfileResponse, GetObjectDataResponse, GetRawObjectDataResponse, SuiObjectDataOptions,
+    SuiObjectInfo, SuiTransactionEffects, SuiTransactionResponseOptions,
+    TransactionEffectsResponse, TransactionResponse,
 };
 use sui_json::SuiJsonValue;
 use sui_types::base_types::{ObjectID, SuiAddress, TransactionDigest};
+use sui_types::crypto::{GenericSignature, SignatureScheme};
 use sui_types::messages::{Transaction, TransactionData};
 use sui_types::sui_serde::Base64;
 
@@ -36,15 +38,59 @@ impl RpcGatewayClient {
 #[async_trait]
 impl GatewayAPI for RpcGatewayClient {
     async fn execute_transaction(&self, tx: Transaction) -> Result<TransactionResponse, Error> {
-        let signature = tx.tx_signature;
         let tx_bytes = Base64::from_bytes(&tx.data.to_bytes());
-        let signature_bytes = Base64::from_bytes(signature.signature_bytes());
-        let pub_key = Base64::from_bytes(signature.public_key_bytes());
+        Ok(match tx.tx_signature {
+            // The scheme is left out for ed25519, the default, so that older gateways accept it.
+            GenericSignature::Signature(signature) => {
+                let signature_bytes = Base64::from_bytes(signature.signature_bytes());
+                let pub_key = Base64::from_bytes(signature.public_key_bytes());
+                self.client
+                    .execute_transaction(tx_bytes, signature_bytes, pub_key, None)
+                    .await?
+            }
+            GenericSignature::Secp256k1(signature) => {
+                let signature_bytes = Base64::from_bytes(signature.signature_bytes());
+                let pub_key = Base64::from_bytes(signature.public_key_bytes());
+                let scheme = Some(SignatureScheme::Secp256k1);
+                self.client
+                    .execute_transaction(tx_bytes, signature_bytes, pub_key, scheme)
+                    .await?
+            }
+            GenericSignature::Secp256r1(signature) => {
+                let signature_bytes = Base64::from_bytes(signature.signature_bytes());
+                let pub_key = Base64::from_bytes(signature.public_key_bytes());
+                let scheme = Some(SignatureScheme::Secp256r1);
+                self.client
+                    .execute_transaction(tx_bytes, signature_bytes, pub_key, scheme)
+                    .await?
+            }
+            GenericSignature::MultiSig(multisig) => {
+                self.client
+                    .execute_multisig_transaction(tx_bytes, multisig)
+                    .await?
+            }
+            GenericSignature::ZkLogin(authenticator) => {
+                self.client
+                    .execute_zk_login_transaction(tx_bytes, authenticator)
+                    .await?
+            }
+        })
+    }
 
-        Ok(self
-            .client
-            .execute_transaction(tx_bytes, signature_bytes, pub_key)
-            .await?)
+    async fn dry_run_transaction(
+        &self,
+        tx: TransactionData,
+    ) -> Result<SuiTransactionEffects, Error> {
+        let tx_bytes = Base64::from_bytes(&tx.to_bytes());
+        Ok(self.client.dry_run_transaction(tx_bytes).await?)
+    }
+
+    async fn profile_transaction_gas(
+        &self,
+        tx: TransactionData,
+    ) -> Result<GasProfileResponse, Error> {
+        let tx_bytes = Base64::from_bytes(&tx.to_bytes());
+        Ok(self.client.profile_transaction_gas(tx_bytes).await?)
     }
 
     async fn transfer_coin(
@@ -62,6 +108,21 @@ impl GatewayAPI for RpcGatewayClient {
         bytes.to_data()
     }
 
+    async fn transfer_sui(
+        &self,
+        signer: SuiAddress,
+        sui_object_id: ObjectID,
+        gas_budget: u64,
+        recipient: SuiAddress,
+        amount: Option<u64>,
+    ) -> Result<TransactionData, Error> {
+        let bytes: TransactionBytes = self
+            .client
+            .transfer_sui(signer, sui_object_id, gas_budget, recipient, amount)
+            .await?;
+        bytes.to_data()
+    }
+
     async fn sync_account_state(&self, account_addr: SuiAddress) -> Result<(), Error> {
         self.client.sync_account_state(account_addr).await?;
         Ok(())
@@ -146,7 +207,15 @@ impl GatewayAPI for RpcGatewayClient {
     }
 
     async fn get_object(&self, object_id: ObjectID) -> Result<GetObjectDataResponse, Error> {
-        Ok(self.client.get_object(object_id).await?)
+        Ok(self.client.get_object(object_id, None).await?)
+    }
+
+    async fn get_object_with_options(
+        &self,
+        object_id: ObjectID,
+        options: SuiObjectDataOptions,
+    ) -> Result<GetObjectDataResponse, Error> {
+        Ok(self.client.get_object(object_id, Some(options)).await?)
     }
 
     async fn get_raw_object(&self, object_id: ObjectID) -> Result<GetRawObjectDataResponse, Error> {
@@ -157,7 +226,10 @@ impl GatewayAPI for RpcGatewayClient {
         &self,
         address: SuiAddress,
     ) -> Result<Vec<SuiObjectInfo>, Error> {
-        Ok(self.client.get_objects_owned_by_address(address).await?)
+        Ok(self
+            .client
+            .get_objects_owned_by_address(address, None)
+            .await?)
     }
 
     async fn get_objects_owned_by_object(
@@ -201,7 +273,8 @@ impl GatewayAPI for RpcGatewayClient {
     async fn get_transaction(
         &self,
         digest: TransactionDigest,
+        options: SuiTransactionResponseOptions,
     ) -> Result<TransactionEffectsResponse, Error> {
-        Ok(self.client.get_transaction(digest).await?)
+        Ok(self.client.get_transaction(digest, Some(options)).await?)
     }
 }
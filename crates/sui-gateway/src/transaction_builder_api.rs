@@ -0,0 +1,159 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use jsonrpsee::core::RpcResult;
+use jsonrpsee_core::server::rpc_module::RpcModule;
+use move_core_types::language_storage::TypeTag;
+
+use sui_core::authority::AuthorityState;
+use sui_core::transaction_builder::TransactionBuilder;
+use sui_json::SuiJsonValue;
+use sui_open_rpc::Module;
+use sui_types::base_types::{ObjectID, SuiAddress};
+use sui_types::sui_serde::Base64;
+
+use crate::api::{RpcTransactionBuilderServer, SuiRpcModule, TransactionBytes};
+use crate::rpc_gateway::responses::SuiTypeTag;
+
+/// An implementation of the Transaction Builder API intended for use in Fullnodes. Object
+/// references and gas are resolved against the fullnode's own store, which may lag behind the
+/// validators, and no objects are locked, so the caller should be prepared for the transaction
+/// to be rejected if an input object has since changed.
+pub struct FullNodeTransactionBuilderApi {
+    state: Arc<AuthorityState>,
+}
+
+impl FullNodeTransactionBuilderApi {
+    pub fn new(state: Arc<AuthorityState>) -> Self {
+        Self { state }
+    }
+
+    fn builder(&self) -> TransactionBuilder<'_, AuthorityState> {
+        TransactionBuilder(&*self.state)
+    }
+}
+
+#[async_trait]
+impl RpcTransactionBuilderServer for FullNodeTransactionBuilderApi {
+    async fn transfer_coin(
+        &self,
+        signer: SuiAddress,
+        object_id: ObjectID,
+        gas: Option<ObjectID>,
+        gas_budget: u64,
+        recipient: SuiAddress,
+    ) -> RpcResult<TransactionBytes> {
+        let data = self
+            .builder()
+            .transfer_coin(signer, object_id, gas, gas_budget, recipient)
+            .await?;
+        Ok(TransactionBytes::from_data(data)?)
+    }
+
+    async fn transfer_sui(
+        &self,
+        signer: SuiAddress,
+        sui_object_id: ObjectID,
+        gas_budget: u64,
+        recipient: SuiAddress,
+        amount: Option<u64>,
+    ) -> RpcResult<TransactionBytes> {
+        let data = self
+            .builder()
+            .transfer_sui(signer, sui_object_id, gas_budget, recipient, amount)
+            .await?;
+        Ok(TransactionBytes::from_data(data)?)
+    }
+
+    async fn move_call(
+        &self,
+        signer: SuiAddress,
+        package_object_id: ObjectID,
+        module: String,
+        function: String,
+        type_arguments: Vec<SuiTypeTag>,
+        rpc_arguments: Vec<SuiJsonValue>,
+        gas: Option<ObjectID>,
+        gas_budget: u64,
+    ) -> RpcResult<TransactionBytes> {
+        let type_arguments = type_arguments
+            .into_iter()
+            .map(|tag| tag.try_into())
+            .collect::<Result<Vec<TypeTag>, _>>()?;
+        let data = self
+            .builder()
+            .move_call(
+                signer,
+                package_object_id,
+                module,
+                function,
+                type_arguments,
+                rpc_arguments,
+                gas,
+                gas_budget,
+            )
+            .await?;
+        Ok(TransactionBytes::from_data(data)?)
+    }
+
+    async fn publish(
+        &self,
+        sender: SuiAddress,
+        compiled_modules: Vec<Base64>,
+        gas: Option<ObjectID>,
+        gas_budget: u64,
+    ) -> RpcResult<TransactionBytes> {
+        let compiled_modules = compiled_modules
+            .into_iter()
+            .map(|data| data.to_vec())
+            .collect::<Result<Vec<_>, _>>()?;
+        let data = self
+            .builder()
+            .publish(sender, compiled_modules, gas, gas_budget)
+            .await?;
+        Ok(TransactionBytes::from_data(data)?)
+    }
+
+    async fn split_coin(
+        &self,
+        signer: SuiAddress,
+        coin_object_id: ObjectID,
+        split_amounts: Vec<u64>,
+        gas: Option<ObjectID>,
+        gas_budget: u64,
+    ) -> RpcResult<TransactionBytes> {
+        let data = self
+            .builder()
+            .split_coin(signer, coin_object_id, split_amounts, gas, gas_budget)
+            .await?;
+        Ok(TransactionBytes::from_data(data)?)
+    }
+
+    async fn merge_coin(
+        &self,
+        signer: SuiAddress,
+        primary_coin: ObjectID,
+        coin_to_merge: ObjectID,
+        gas: Option<ObjectID>,
+        gas_budget: u64,
+    ) -> RpcResult<TransactionBytes> {
+        let data = self
+            .builder()
+            .merge_coins(signer, primary_coin, coin_to_merge, gas, gas_budget)
+            .await?;
+        Ok(TransactionBytes::from_data(data)?)
+    }
+}
+
+impl SuiRpcModule for FullNodeTransactionBuilderApi {
+    fn rpc(self) -> RpcModule<Self> {
+        self.into_rpc()
+    }
+
+    fn rpc_doc_module() -> Module {
+        crate::api::RpcTransactionBuilderOpenRpc::module_doc()
+    }
+}
@@ -0,0 +1,79 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use jsonrpsee::core::RpcResult;
+use jsonrpsee_core::server::rpc_module::RpcModule;
+
+use sui_core::authority_client::NetworkAuthorityClient;
+use sui_core::gateway_types::{ExecuteTransactionRequestType, SuiExecuteTransactionResponse};
+use sui_core::transaction_orchestrator::{ExecuteTransactionResponse, TransactionOrchestrator};
+use sui_open_rpc::Module;
+use sui_types::crypto::{GenericSignature, SignableBytes, SignatureScheme};
+use sui_types::messages::{Transaction, TransactionData};
+use sui_types::sui_serde::Base64;
+
+use crate::api::{RpcTransactionExecutionApiServer, SuiRpcModule};
+
+pub struct FullNodeTransactionExecutionApi {
+    orchestrator: Arc<TransactionOrchestrator<NetworkAuthorityClient>>,
+}
+
+impl FullNodeTransactionExecutionApi {
+    pub fn new(orchestrator: Arc<TransactionOrchestrator<NetworkAuthorityClient>>) -> Self {
+        Self { orchestrator }
+    }
+}
+
+#[async_trait]
+impl RpcTransactionExecutionApiServer for FullNodeTransactionExecutionApi {
+    async fn submit_transaction(
+        &self,
+        tx_bytes: Base64,
+        signature: Base64,
+        pub_key: Base64,
+        sig_scheme: Option<SignatureScheme>,
+        request_type: Option<ExecuteTransactionRequestType>,
+    ) -> RpcResult<SuiExecuteTransactionResponse> {
+        let data = TransactionData::from_signable_bytes(&tx_bytes.to_vec()?)?;
+        let signature = GenericSignature::from_parts(
+            sig_scheme.unwrap_or(SignatureScheme::ED25519),
+            &signature.to_vec()?,
+            &pub_key.to_vec()?,
+        )?;
+        let response = self
+            .orchestrator
+            .execute_transaction(
+                Transaction::new(data, signature),
+                request_type.unwrap_or(ExecuteTransactionRequestType::WaitForEffectsCert),
+            )
+            .await?;
+        Ok(match response {
+            ExecuteTransactionResponse::TxCert(certificate) => {
+                SuiExecuteTransactionResponse::TxCert {
+                    certificate: (*certificate).try_into()?,
+                }
+            }
+            ExecuteTransactionResponse::EffectsCert(result, confirmed_local_execution) => {
+                let (certificate, effects) = *result;
+                SuiExecuteTransactionResponse::EffectsCert {
+                    certificate: certificate.try_into()?,
+                    effects: effects.into(),
+                    confirmed_local_execution,
+                }
+            }
+        })
+    }
+}
+
+impl SuiRpcModule for FullNodeTransactionExecutionApi {
+    fn rpc(self) -> RpcModule<Self> {
+        self.into_rpc()
+    }
+
+    fn rpc_doc_module() -> Module {
+        crate::api::RpcTransactionExecutionApiOpenRpc::module_doc()
+    }
+}
@@ -37,6 +37,26 @@ fn main() -> Result<()> {
                 .codec_path(codec_path)
                 .build(),
         )
+        .method(
+            Method::builder()
+                .name("transaction_chunked")
+                .route_name("TransactionChunked")
+                .input_type("sui_types::message_chunks::MessageChunk")
+                .output_type("sui_types::messages::TransactionInfoResponse")
+                .client_streaming()
+                .codec_path(codec_path)
+                .build(),
+        )
+        .method(
+            Method::builder()
+                .name("confirmation_transaction_chunked")
+                .route_name("ConfirmationTransactionChunked")
+                .input_type("sui_types::message_chunks::MessageChunk")
+                .output_type("sui_types::messages::TransactionInfoResponse")
+                .client_streaming()
+                .codec_path(codec_path)
+                .build(),
+        )
         .method(
             Method::builder()
                 .name("consensus_transaction")
@@ -73,6 +93,15 @@ fn main() -> Result<()> {
                 .codec_path(codec_path)
                 .build(),
         )
+        .method(
+            Method::builder()
+                .name("dry_run")
+                .route_name("DryRun")
+                .input_type("sui_types::messages::DryRunTransactionRequest")
+                .output_type("sui_types::messages::DryRunTransactionResponse")
+                .codec_path(codec_path)
+                .build(),
+        )
         .method(
             Method::builder()
                 .name("checkpoint")
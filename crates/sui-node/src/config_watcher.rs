@@ -0,0 +1,241 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Changes to the config file of a running node, applied without restarting it. Only parameters
+//! which consensus and the stored state don't depend on can be changed this way: the other
+//! parameters are read once when the node starts.
+
+use anyhow::Result;
+use arc_swap::ArcSwap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+use sui_config::{Config, DenyList, ForkDetection, JsonRpcLimits, MetricsPushConfig, NodeConfig};
+use tracing::{info, warn};
+use tracing_subscriber::{reload, EnvFilter, Registry};
+
+/// How often the config file is checked for changes.
+const CONFIG_POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// The handle through which the log level of the node is changed.
+pub type LogLevelHandle = reload::Handle<EnvFilter, Registry>;
+
+/// The log level `log_level` of the config of the node sets, or else the `RUST_LOG`
+/// environment variable.
+pub fn log_filter(log_level: Option<&str>) -> Result<EnvFilter> {
+    Ok(match log_level {
+        Some(log_level) => EnvFilter::try_new(log_level)?,
+        None => EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info")),
+    })
+}
+
+/// The parameters of a running node which changes to its config file apply to.
+#[derive(Clone)]
+pub struct ReloadableParameters {
+    pub fork_detection: Arc<ArcSwap<ForkDetection>>,
+    pub json_rpc_limits: Arc<ArcSwap<JsonRpcLimits>>,
+    /// The deny list of the authority state of the node.
+    pub deny_list: Arc<ArcSwap<DenyList>>,
+    pub metrics_push: Arc<ArcSwap<Option<MetricsPushConfig>>>,
+    pub checkpoints_retained: Arc<ArcSwap<Option<u64>>>,
+    /// Set once the node has installed the log subscriber, as nodes started in tests don't.
+    pub log_level: Option<LogLevelHandle>,
+}
+
+impl ReloadableParameters {
+    pub fn new(config: &NodeConfig, deny_list: Arc<ArcSwap<DenyList>>) -> Self {
+        let parameters = Self {
+            fork_detection: Arc::new(ArcSwap::from_pointee(config.fork_detection)),
+            json_rpc_limits: Arc::new(ArcSwap::from_pointee(config.json_rpc_limits)),
+            deny_list,
+            metrics_push: Arc::new(ArcSwap::from_pointee(config.metrics_push.clone())),
+            checkpoints_retained: Arc::new(ArcSwap::from_pointee(config.checkpoints_retained)),
+            log_level: None,
+        };
+        parameters.apply(config);
+        parameters
+    }
+
+    /// Reload the log level through `handle` too.
+    pub fn with_log_level(mut self, handle: LogLevelHandle) -> Self {
+        self.log_level = Some(handle);
+        self
+    }
+
+    fn apply(&self, config: &NodeConfig) {
+        self.fork_detection.store(Arc::new(config.fork_detection));
+        self.json_rpc_limits.store(Arc::new(config.json_rpc_limits));
+        self.deny_list.store(Arc::new(config.deny_list.clone()));
+        self.metrics_push
+            .store(Arc::new(config.metrics_push.clone()));
+        self.checkpoints_retained
+            .store(Arc::new(config.checkpoints_retained));
+    }
+
+    /// Set the log level of the node to the one of `config`, if it is valid.
+    fn apply_log_level(&self, config: &NodeConfig) {
+        let handle = match &self.log_level {
+            Some(handle) => handle,
+            None => return,
+        };
+        match log_filter(config.log_level.as_deref()) {
+            Ok(filter) => {
+                if let Err(error) = handle.reload(filter) {
+                    warn!("Cannot reload the log level: {error}");
+                }
+            }
+            Err(error) => warn!(
+                log_level = ?config.log_level,
+                "Invalid log level, keeping the current one: {error}"
+            ),
+        }
+    }
+}
+
+/// Check the config file at `path`, which `config` was loaded from, for changes, and apply
+/// them to `parameters`. Changes to other parameters are only warned about.
+pub async fn watch_config(path: PathBuf, mut config: NodeConfig, parameters: ReloadableParameters) {
+    let mut modified = modified_time(&path);
+    let mut interval = tokio::time::interval(CONFIG_POLL_INTERVAL);
+    loop {
+        interval.tick().await;
+        let last_modified = modified_time(&path);
+        if last_modified == modified {
+            continue;
+        }
+        modified = last_modified;
+
+        let new_config = match NodeConfig::load(&path) {
+            Ok(new_config) => new_config,
+            Err(error) => {
+                warn!(
+                    ?path,
+                    "Cannot reload the node config, keeping the current one: {error}"
+                );
+                continue;
+            }
+        };
+        if new_config.fork_detection != config.fork_detection {
+            info!(fork_detection = ?new_config.fork_detection, "Reloaded fork detection");
+        }
+        if new_config.json_rpc_limits != config.json_rpc_limits {
+            info!(limits = ?new_config.json_rpc_limits, "Reloaded JSON-RPC limits");
+        }
+        if new_config.deny_list != config.deny_list {
+            info!(deny_list = ?new_config.deny_list, "Reloaded the deny list");
+        }
+        if new_config.metrics_push != config.metrics_push {
+            info!(
+                url = ?new_config.metrics_push.as_ref().map(|push| &push.url),
+                "Reloaded the metrics push"
+            );
+        }
+        if new_config.checkpoints_retained != config.checkpoints_retained {
+            info!(
+                checkpoints_retained = ?new_config.checkpoints_retained,
+                "Reloaded the checkpoint pruning"
+            );
+        }
+        if new_config.log_level != config.log_level {
+            parameters.apply_log_level(&new_config);
+            info!(log_level = ?new_config.log_level, "Reloaded the log level");
+        }
+        parameters.apply(&new_config);
+        let restart_only_changes = restart_only_changes(&config, &new_config);
+        if !restart_only_changes.is_empty() {
+            warn!(
+                ?path,
+                fields = ?restart_only_changes,
+                "The node config changed parameters which only apply once the node restarts"
+            );
+        }
+        config = new_config;
+    }
+}
+
+fn modified_time(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+}
+
+/// The fields of the config which can't be reloaded, and which `new_config` changes.
+fn restart_only_changes(config: &NodeConfig, new_config: &NodeConfig) -> Vec<&'static str> {
+    // The narwhal parameters and committee of the consensus config have no equality, and are
+    // generated along with the genesis.
+    let consensus = |config: &NodeConfig| {
+        config
+            .consensus_config()
+            .map(|consensus| (consensus.address().clone(), consensus.db_path().to_owned()))
+    };
+    let next_public_key = |config: &NodeConfig| {
+        config
+            .next_key_pair
+            .as_ref()
+            .map(|key_pair| *key_pair.public_key_bytes())
+    };
+    let changes = [
+        ("key-pair", config.public_key() != new_config.public_key()),
+        (
+            "next-key-pair",
+            next_public_key(config) != next_public_key(new_config),
+        ),
+        ("db-path", config.db_path != new_config.db_path),
+        (
+            "network-address",
+            config.network_address != new_config.network_address,
+        ),
+        (
+            "metrics-address",
+            config.metrics_address != new_config.metrics_address,
+        ),
+        (
+            "json-rpc-address",
+            config.json_rpc_address != new_config.json_rpc_address,
+        ),
+        (
+            "consensus-config",
+            consensus(config) != consensus(new_config),
+        ),
+        (
+            "enable-event-processing",
+            config.enable_event_processing != new_config.enable_event_processing,
+        ),
+        (
+            "anomaly-webhook-url",
+            config.anomaly_webhook_url != new_config.anomaly_webhook_url,
+        ),
+        (
+            "validator-server-limits",
+            config.validator_server_limits != new_config.validator_server_limits,
+        ),
+        (
+            "json-rpc-server",
+            config.json_rpc_server != new_config.json_rpc_server,
+        ),
+        (
+            "graphql-address",
+            config.graphql_address != new_config.graphql_address,
+        ),
+        (
+            "archive-path",
+            config.archive_path != new_config.archive_path,
+        ),
+        ("archive-url", config.archive_url != new_config.archive_url),
+        (
+            "snapshot-path",
+            config.snapshot_path != new_config.snapshot_path,
+        ),
+        (
+            "epoch-duration-ms",
+            config.epoch_duration_ms != new_config.epoch_duration_ms,
+        ),
+        ("genesis", config.genesis != new_config.genesis),
+    ];
+    changes
+        .into_iter()
+        .filter(|(_, changed)| *changed)
+        .map(|(field, _)| field)
+        .collect()
+}
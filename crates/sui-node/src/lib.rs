@@ -8,36 +8,85 @@ use std::{collections::BTreeMap, sync::Arc, time::Duration};
 use sui_config::NodeConfig;
 use sui_core::authority_server::ValidatorService;
 use sui_core::{
+    archive::{ArchiveReader, ArchiveWriter, LocalObjectStore, ObjectStore},
     authority::{AuthorityState, AuthorityStore},
-    authority_active::{gossip::gossip_process_with_start_seq, ActiveAuthority},
+    authority_active::{
+        checkpoint_driver::{follow_checkpoints, CheckpointProcessControl},
+        gossip::gossip_process_with_start_seq,
+        ActiveAuthority,
+    },
+    authority_aggregator::AuthorityAggregator,
     authority_client::NetworkAuthorityClient,
     checkpoints::CheckpointStore,
+    snapshot::{import_snapshot, read_snapshot},
+    transaction_orchestrator::{TransactionOrchestrator, TransactionOrchestratorConfig},
 };
 use sui_gateway::bcs_api::BcsApiImpl;
-use sui_gateway::json_rpc::JsonRpcServerBuilder;
+use sui_gateway::epoch_admin_api::EpochAdminApi;
+use sui_gateway::graphql::spawn_graphql_server;
+use sui_gateway::json_rpc::{JsonRpcServerBuilder, JsonRpcServerHandle};
 use sui_gateway::read_api::{FullNodeApi, ReadApi};
+use sui_gateway::transaction_builder_api::FullNodeTransactionBuilderApi;
+use sui_gateway::transaction_execution_api::FullNodeTransactionExecutionApi;
 use sui_network::api::ValidatorServer;
 use sui_storage::{follower_store::FollowerStore, IndexStore};
+use tokio::sync::Notify;
 use tracing::info;
 
+pub mod config_watcher;
+pub mod metrics_push;
+
+use config_watcher::ReloadableParameters;
+
+/// The time full nodes wait between asking the validators for their latest checkpoint.
+const CHECKPOINT_FOLLOWER_PAUSE: Duration = Duration::from_secs(5);
+
 pub struct SuiNode {
     grpc_server: tokio::task::JoinHandle<Result<()>>,
-    _json_rpc_service: Option<jsonrpsee::http_server::HttpServerHandle>,
-    _batch_subsystem_handle: tokio::task::JoinHandle<Result<()>>,
-    _gossip_handle: Option<tokio::task::JoinHandle<()>>,
+    json_rpc_service: Option<JsonRpcServerHandle>,
+    graphql_handle: Option<tokio::task::JoinHandle<()>>,
+    batch_subsystem_handle: tokio::task::JoinHandle<Result<()>>,
+    gossip_handle: Option<tokio::task::JoinHandle<()>>,
+    checkpoint_follower_handle: Option<tokio::task::JoinHandle<()>>,
+    overload_monitor_handle: tokio::task::JoinHandle<()>,
+    epoch_handle: Option<tokio::task::JoinHandle<()>>,
+    archive_writer_handle: Option<tokio::task::JoinHandle<()>>,
     state: Arc<AuthorityState>,
+    reloadable_parameters: ReloadableParameters,
 }
 
 impl SuiNode {
     pub async fn start(config: &NodeConfig) -> Result<SuiNode> {
+        let genesis = config.genesis()?;
+        let store = Arc::new(AuthorityStore::open(config.db_path().join("store"), None));
+        if let Some(snapshot_path) = &config.snapshot_path {
+            if store.database_is_empty()? {
+                info!("Starting from the snapshot {}", snapshot_path.display());
+                let snapshot = read_snapshot(snapshot_path)?;
+                import_snapshot(&store, &snapshot, genesis.committee()).await?;
+            }
+        }
+
+        // A validator whose key rotation took effect signs with its next key from then on.
+        let committee = if store.database_is_empty()? {
+            genesis.committee()
+        } else {
+            store.get_last_epoch_info()?.committee
+        };
+        let mut config = config.clone();
+        if config.rotate_key_pair(&committee) {
+            info!(
+                "Key rotation took effect in epoch {}, signing with the next key pair",
+                committee.epoch()
+            );
+        }
+        let config = &config;
+
         info!(node =? config.public_key(),
             "Initializing sui-node listening on {}", config.network_address
         );
 
-        let genesis = config.genesis()?;
-
         let secret = Arc::pin(config.key_pair().copy());
-        let store = Arc::new(AuthorityStore::open(config.db_path().join("store"), None));
         let checkpoint_store = if config.consensus_config().is_some() {
             Some(Arc::new(Mutex::new(CheckpointStore::open(
                 config.db_path().join("checkpoints"),
@@ -74,9 +123,10 @@ impl SuiNode {
             .await,
         );
 
-        let gossip_handle = if config.consensus_config().is_some() {
-            None
-        } else {
+        let reloadable_parameters = ReloadableParameters::new(config, state.deny_list.clone());
+
+        // Clients to the validators of the genesis committee.
+        let authority_clients = || {
             let mut net_config = mysten_network::config::Config::new();
             net_config.connect_timeout = Some(Duration::from_secs(5));
             net_config.request_timeout = Some(Duration::from_secs(5));
@@ -89,11 +139,39 @@ impl SuiNode {
                 let client = NetworkAuthorityClient::new(channel);
                 authority_clients.insert(validator.public_key(), client);
             }
+            authority_clients
+        };
 
+        // Validators of local and testing networks change epoch on a timer, or on request.
+        let advance_epoch = Arc::new(Notify::new());
+        let epoch_handle = match config.epoch_duration_ms {
+            Some(epoch_duration_ms) if config.consensus_config().is_some() => {
+                let active_authority = ActiveAuthority::new(
+                    state.clone(),
+                    follower_store.clone(),
+                    authority_clients(),
+                )?;
+                let advance_epoch = advance_epoch.clone();
+                Some(tokio::task::spawn(async move {
+                    active_authority
+                        .advance_epoch_process(
+                            Duration::from_millis(epoch_duration_ms),
+                            advance_epoch,
+                        )
+                        .await;
+                }))
+            }
+            _ => None,
+        };
+
+        let gossip_handle = if config.consensus_config().is_some() {
+            None
+        } else {
             let active_authority =
-                ActiveAuthority::new(state.clone(), follower_store, authority_clients)?;
+                ActiveAuthority::new(state.clone(), follower_store.clone(), authority_clients())?;
 
             // Start following validators
+            let fork_detection = reloadable_parameters.fork_detection.clone();
             Some(tokio::task::spawn(async move {
                 gossip_process_with_start_seq(
                     &active_authority,
@@ -101,6 +179,26 @@ impl SuiNode {
                     active_authority.state.committee.load().voting_rights.len(),
                     // start receiving the earliest TXes the validator has.
                     Some(0),
+                    fork_detection,
+                )
+                .await;
+            }))
+        };
+
+        // Full nodes record the checkpoints of the committee, and the validators taking part in
+        // them, for their clients.
+        let checkpoint_follower_handle = if config.consensus_config().is_some() {
+            None
+        } else {
+            let active_authority =
+                ActiveAuthority::new(state.clone(), follower_store, authority_clients())?;
+            let checkpoints_retained = reloadable_parameters.checkpoints_retained.clone();
+            Some(tokio::task::spawn(async move {
+                follow_checkpoints(
+                    &active_authority,
+                    &CheckpointProcessControl::default(),
+                    CHECKPOINT_FOLLOWER_PAUSE,
+                    checkpoints_retained,
                 )
                 .await;
             }))
@@ -117,12 +215,28 @@ impl SuiNode {
             })
         };
 
+        let overload_monitor_handle = {
+            let monitor_state = state.clone();
+            tokio::task::spawn(async move { monitor_state.run_overload_monitor().await })
+        };
+
         let validator_service = if config.consensus_config().is_some() {
             Some(ValidatorService::new(config, state.clone()).await?)
         } else {
             None
         };
 
+        let archive_store = config
+            .archive_path
+            .as_ref()
+            .map(|path| Arc::new(LocalObjectStore::new(path)) as Arc<dyn ObjectStore>);
+        let archive_writer_handle = match &archive_store {
+            Some(store) if config.consensus_config().is_some() => {
+                Some(ArchiveWriter::new(state.clone(), store.clone()).spawn())
+            }
+            _ => None,
+        };
+
         let grpc_server = {
             let mut server_builder = mysten_network::config::Config::new().server_builder();
 
@@ -138,23 +252,66 @@ impl SuiNode {
         };
 
         let json_rpc_service = if config.consensus_config().is_some() {
-            None
+            if epoch_handle.is_some() {
+                let mut server =
+                    JsonRpcServerBuilder::new_with_config(config.json_rpc_server.clone())?;
+                server.register_module(EpochAdminApi::new(advance_epoch))?;
+                Some(server.start(config.json_rpc_address).await?)
+            } else {
+                None
+            }
         } else {
-            let mut server = JsonRpcServerBuilder::new()?;
-            server.register_module(ReadApi::new(state.clone()))?;
-            server.register_module(FullNodeApi::new(state.clone()))?;
+            let mut server = JsonRpcServerBuilder::new_with_config(config.json_rpc_server.clone())?;
+            let mut read_api = ReadApi::new(state.clone())
+                .with_limits(reloadable_parameters.json_rpc_limits.clone())
+                .with_archive_url(config.archive_url.clone());
+            if let Some(store) = archive_store {
+                read_api = read_api.with_archive(ArchiveReader::new(store));
+            }
+            server.register_module(read_api)?;
+            server.register_module(
+                FullNodeApi::new(state.clone()).with_archive_url(config.archive_url.clone()),
+            )?;
             server.register_module(BcsApiImpl::new(state.clone()))?;
+            server.register_module(FullNodeTransactionBuilderApi::new(state.clone()))?;
+            let orchestrator = TransactionOrchestrator::new(
+                Arc::new(AuthorityAggregator::new(
+                    genesis.committee(),
+                    authority_clients(),
+                )),
+                state.clone(),
+                TransactionOrchestratorConfig::default(),
+            );
+            server.register_module(FullNodeTransactionExecutionApi::new(Arc::new(orchestrator)))?;
 
             let server_handle = server.start(config.json_rpc_address).await?;
             Some(server_handle)
         };
 
+        let graphql_handle = match config.graphql_address {
+            Some(address) if config.consensus_config().is_none() => {
+                let (_, handle) = spawn_graphql_server(
+                    state.clone(),
+                    address,
+                    config.json_rpc_server.max_request_body_size,
+                )?;
+                Some(handle)
+            }
+            _ => None,
+        };
+
         let node = Self {
             grpc_server,
-            _json_rpc_service: json_rpc_service,
-            _gossip_handle: gossip_handle,
-            _batch_subsystem_handle: batch_subsystem_handle,
+            json_rpc_service,
+            graphql_handle,
+            gossip_handle,
+            checkpoint_follower_handle,
+            overload_monitor_handle,
+            epoch_handle,
+            archive_writer_handle,
+            batch_subsystem_handle,
             state,
+            reloadable_parameters,
         };
 
         info!("SuiNode started!");
@@ -166,10 +323,48 @@ impl SuiNode {
         self.state.clone()
     }
 
+    /// The parameters of the node which changes to its config apply to while it runs.
+    pub fn reloadable_parameters(&self) -> ReloadableParameters {
+        self.reloadable_parameters.clone()
+    }
+
     //TODO watch/wait on all the components
-    pub async fn wait(self) -> Result<()> {
-        self.grpc_server.await??;
+    pub async fn wait(&mut self) -> Result<()> {
+        (&mut self.grpc_server).await??;
+
+        Ok(())
+    }
+
+    /// Stop executing certificates, wait for the ones being executed and flush the store, then
+    /// stop the servers and tasks of the node. The next start of the node then need not recover
+    /// its store.
+    pub async fn shutdown(self) -> Result<()> {
+        info!("Shutting down sui-node");
+        self.state.shutdown().await?;
+
+        self.grpc_server.abort();
+        self.batch_subsystem_handle.abort();
+        self.overload_monitor_handle.abort();
+        if let Some(gossip_handle) = self.gossip_handle {
+            gossip_handle.abort();
+        }
+        if let Some(checkpoint_follower_handle) = self.checkpoint_follower_handle {
+            checkpoint_follower_handle.abort();
+        }
+        if let Some(epoch_handle) = self.epoch_handle {
+            epoch_handle.abort();
+        }
+        if let Some(archive_writer_handle) = self.archive_writer_handle {
+            archive_writer_handle.abort();
+        }
+        if let Some(json_rpc_service) = self.json_rpc_service {
+            json_rpc_service.stop()?;
+        }
+        if let Some(graphql_handle) = self.graphql_handle {
+            graphql_handle.abort();
+        }
 
+        info!("sui-node shut down");
         Ok(())
     }
 }
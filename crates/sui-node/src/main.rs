@@ -6,7 +6,10 @@ use clap::Parser;
 use multiaddr::Multiaddr;
 use std::path::PathBuf;
 use sui_config::{Config, NodeConfig};
+use sui_node::config_watcher;
+use tokio::signal::unix::{signal, SignalKind};
 use tracing::info;
+use tracing_subscriber::{fmt, prelude::*, reload};
 
 #[derive(Parser)]
 #[clap(rename_all = "kebab-case")]
@@ -20,14 +23,18 @@ struct Args {
 
 #[tokio::main]
 async fn main() -> Result<()> {
-    // Initialize logging
-    let _guard = telemetry_subscribers::TelemetryConfig::new(env!("CARGO_BIN_NAME"))
-        .with_env()
-        .init();
-
     let args = Args::parse();
 
     let mut config = NodeConfig::load(&args.config_path)?;
+    let file_config = config.clone();
+
+    // Initialize logging, through a filter which changes to the config of the node reload.
+    let (log_filter, log_level) =
+        reload::Layer::new(config_watcher::log_filter(config.log_level.as_deref())?);
+    tracing_subscriber::registry()
+        .with(log_filter)
+        .with(fmt::layer())
+        .init();
 
     // TODO: Switch from prometheus exporter. See https://github.com/MystenLabs/sui/issues/1907
     info!(
@@ -36,13 +43,40 @@ async fn main() -> Result<()> {
     );
     prometheus_exporter::start(config.metrics_address)
         .expect("Failed to start Prometheus exporter");
+    let _anomaly_webhook_handle = config
+        .anomaly_webhook_url
+        .clone()
+        .map(sui_core::anomaly::spawn_anomaly_webhook);
 
     if let Some(listen_address) = args.listen_address {
         config.network_address = listen_address;
     }
 
-    let node = sui_node::SuiNode::start(&config).await?;
-    node.wait().await?;
+    let mut node = sui_node::SuiNode::start(&config).await?;
+    let reloadable_parameters = node.reloadable_parameters().with_log_level(log_level);
+    let _metrics_push_handle = sui_node::metrics_push::spawn_metrics_push(
+        &config,
+        reloadable_parameters.metrics_push.clone(),
+    );
+    tokio::spawn(config_watcher::watch_config(
+        args.config_path,
+        file_config,
+        reloadable_parameters,
+    ));
+    tokio::select! {
+        result = node.wait() => result?,
+        _ = shutdown_signal() => node.shutdown().await?,
+    }
 
     Ok(())
 }
+
+/// Resolves once the node is asked to stop, by Ctrl-C or by a SIGTERM as sent by
+/// `docker stop` and Kubernetes.
+async fn shutdown_signal() {
+    let mut terminate = signal(SignalKind::terminate()).expect("Cannot listen to SIGTERM");
+    tokio::select! {
+        _ = tokio::signal::ctrl_c() => info!("Received Ctrl-C"),
+        _ = terminate.recv() => info!("Received SIGTERM"),
+    }
+}
@@ -0,0 +1,109 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Pushes the metrics of the node to a Prometheus push gateway, for the nodes which cannot be
+//! scraped, such as validators behind a NAT.
+
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::{anyhow, Result};
+use arc_swap::ArcSwap;
+use prometheus_exporter::prometheus::{self, Encoder, TextEncoder};
+use sui_config::{MetricsPushConfig, NodeConfig};
+use sui_types::base_types::encode_bytes_hex;
+use tokio::task::JoinHandle;
+use tracing::{debug, warn};
+
+const METRICS_PUSH_JOB: &str = "sui-node";
+
+const METRICS_PUSH_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// How often the config is checked for a push gateway while it sets none.
+const METRICS_PUSH_DISABLED_POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Push the metrics of the node as `push` sets, if it does. `push` is read again before each
+/// push, as the config of the node can change it.
+pub fn spawn_metrics_push(
+    config: &NodeConfig,
+    push: Arc<ArcSwap<Option<MetricsPushConfig>>>,
+) -> JoinHandle<()> {
+    let node_labels = BTreeMap::from([
+        ("node".to_string(), encode_bytes_hex(&config.public_key())),
+        (
+            "role".to_string(),
+            if config.consensus_config().is_some() {
+                "validator"
+            } else {
+                "fullnode"
+            }
+            .to_string(),
+        ),
+    ]);
+    tokio::spawn(async move {
+        let client = reqwest::Client::new();
+        loop {
+            let push = push.load_full();
+            let push = match push.as_ref() {
+                Some(push) => push,
+                None => {
+                    tokio::time::sleep(METRICS_PUSH_DISABLED_POLL_INTERVAL).await;
+                    continue;
+                }
+            };
+            let mut labels = node_labels.clone();
+            labels.extend(push.labels.clone());
+            let url = push_url(&push.url, &labels);
+            match push_metrics(&client, &url, push).await {
+                Ok(()) => debug!("Pushed the metrics"),
+                Err(error) => warn!(?error, "Failed to push the metrics"),
+            }
+            tokio::time::sleep(Duration::from_millis(push.interval_ms)).await;
+        }
+    })
+}
+
+/// The URL of the group of the metrics of the node on the push gateway at `base`. The label
+/// values are base64 encoded, as they can hold any character.
+fn push_url(base: &str, labels: &BTreeMap<String, String>) -> String {
+    let mut url = format!(
+        "{}/metrics/job/{METRICS_PUSH_JOB}",
+        base.trim_end_matches('/')
+    );
+    for (name, value) in labels {
+        url.push_str(&format!(
+            "/{name}@base64/{}",
+            base64::encode_config(value, base64::URL_SAFE)
+        ));
+    }
+    url
+}
+
+/// Replace the metrics of the group at `url` with the current ones.
+async fn push_metrics(
+    client: &reqwest::Client,
+    url: &str,
+    config: &MetricsPushConfig,
+) -> Result<()> {
+    let mut body = Vec::new();
+    let encoder = TextEncoder::new();
+    encoder.encode(&prometheus::gather(), &mut body)?;
+    let mut request = client
+        .put(url)
+        .header(reqwest::header::CONTENT_TYPE, encoder.format_type())
+        .timeout(METRICS_PUSH_TIMEOUT)
+        .body(body);
+    if let Some(token) = &config.auth_token {
+        request = request.bearer_auth(token);
+    }
+    let response = request.send().await?;
+    let status = response.status();
+    if !status.is_success() {
+        return Err(anyhow!(
+            "The push gateway answered {status}: {}",
+            response.text().await.unwrap_or_default()
+        ));
+    }
+    Ok(())
+}
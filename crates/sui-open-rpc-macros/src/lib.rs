@@ -42,6 +42,7 @@ pub fn open_rpc(attr: TokenStream, item: TokenStream) -> TokenStream {
     for method in rpc_definition.methods {
         let name = method.name;
         let doc = method.doc;
+        let since = method.since.to_quote();
         let mut inputs = Vec::new();
         for (name, ty) in method.params {
             let (ty, required) = extract_type_from_option(ty);
@@ -61,7 +62,7 @@ pub fn open_rpc(attr: TokenStream, item: TokenStream) -> TokenStream {
             let mut inputs: Vec<sui_open_rpc::ContentDescriptor> = Vec::new();
             #(#inputs)*
             let result = #returns_ty
-            builder.add_method(#namespace, #name, inputs, result, #doc, #tag);
+            builder.add_method(#namespace, #name, inputs, result, #doc, #tag, #since);
         })
     }
     let open_rpc_name = quote::format_ident!("{}OpenRpc", &rpc_definition.name);
@@ -118,6 +119,7 @@ struct Method {
     params: Vec<(String, Type)>,
     returns: Option<Type>,
     doc: String,
+    since: Option<LitStr>,
 }
 
 fn parse_rpc_method(trait_data: &mut syn::ItemTrait) -> Result<RpcDefinition, syn::Error> {
@@ -132,6 +134,7 @@ fn parse_rpc_method(trait_data: &mut syn::ItemTrait) -> Result<RpcDefinition, sy
             };
 
             let doc = extract_doc_comments(&method.attrs).to_string();
+            let since = extract_since(&mut method.attrs)?;
 
             let params: Vec<_> = method
                 .sig
@@ -164,6 +167,7 @@ fn parse_rpc_method(trait_data: &mut syn::ItemTrait) -> Result<RpcDefinition, sy
                 params,
                 returns,
                 doc,
+                since,
             });
         }
     }
@@ -221,6 +225,29 @@ fn get_type(pat_type: &mut PatType) -> Result<Type, syn::Error> {
     )
 }
 
+/// Remove the `#[since = "<version>"]` attribute of a method, if any, and return its version.
+/// jsonrpsee does not know this attribute, so it must not be left on the trait.
+fn extract_since(attrs: &mut Vec<Attribute>) -> Result<Option<LitStr>, syn::Error> {
+    let (pos, attr) = match attrs.iter().find_position(|a| a.path.is_ident("since")) {
+        Some(found) => found,
+        None => return Ok(None),
+    };
+    let version = match attr.parse_meta()? {
+        syn::Meta::NameValue(syn::MetaNameValue {
+            lit: syn::Lit::Str(version),
+            ..
+        }) => version,
+        meta => {
+            return Err(syn::Error::new(
+                meta.span(),
+                "Expected `#[since = \"<version>\"]`",
+            ))
+        }
+    };
+    attrs.remove(pos);
+    Ok(Some(version))
+}
+
 fn find_attr<'a>(attrs: &'a [Attribute], ident: &str) -> Option<&'a Attribute> {
     attrs.iter().find(|a| a.path.is_ident(ident))
 }
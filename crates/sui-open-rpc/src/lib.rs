@@ -103,6 +103,9 @@ struct Method {
     params: Vec<ContentDescriptor>,
     #[serde(skip_serializing_if = "Option::is_none")]
     result: Option<ContentDescriptor>,
+    /// The API version this method was added in, if it was added after the first version.
+    #[serde(rename = "x-since", skip_serializing_if = "Option::is_none")]
+    since: Option<String>,
 }
 
 #[derive(Serialize, Deserialize, Default, Clone)]
@@ -196,6 +199,7 @@ impl RpcModuleDocBuilder {
         result: Option<ContentDescriptor>,
         doc: &str,
         tag: Option<String>,
+        since: Option<String>,
     ) {
         let description = if doc.trim().is_empty() {
             None
@@ -218,6 +222,7 @@ impl RpcModuleDocBuilder {
                     })
                     .into_iter()
                     .collect(),
+                since,
             },
         );
     }
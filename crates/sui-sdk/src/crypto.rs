@@ -0,0 +1,457 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::anyhow;
+use argon2::{Algorithm, Argon2, Params, Version};
+use chacha20poly1305::aead::{Aead, NewAead, Payload};
+use chacha20poly1305::{Key, XChaCha20Poly1305, XNonce};
+use ed25519_dalek::ed25519;
+use ed25519_dalek::ed25519::signature;
+use rand::rngs::OsRng;
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use serde_with::serde_as;
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::fmt::{Display, Formatter};
+use std::fs;
+use std::fs::File;
+use std::io::BufReader;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
+
+use sui_types::base_types::SuiAddress;
+use sui_types::crypto::{GenericSignature, Signature, SignatureScheme, SuiKeyPair};
+use sui_types::intent::PersonalMessage;
+use sui_types::key_derivation::{
+    derive_key_pair_from_mnemonic, generate_mnemonic, parse_mnemonic, DerivationPath,
+};
+use sui_types::sui_serde::Base64;
+use zeroize::Zeroizing;
+
+/// The environment variable holding the passphrase of an encrypted keystore. When it is not
+/// set, the passphrase is prompted for every time the keystore is loaded.
+pub const KEYSTORE_PASSPHRASE_ENV: &str = "SUI_KEYSTORE_PASSPHRASE";
+
+#[derive(Serialize, Deserialize)]
+#[non_exhaustive]
+// This will work on user signatures, but not suitable for authority signatures.
+pub enum KeystoreType {
+    File(PathBuf),
+}
+
+pub trait Keystore: Send + Sync {
+    fn sign(&self, address: &SuiAddress, msg: &[u8]) -> Result<GenericSignature, signature::Error>;
+    fn add_random_key_with_scheme(
+        &mut self,
+        scheme: SignatureScheme,
+    ) -> Result<SuiAddress, anyhow::Error>;
+
+    fn add_random_key(&mut self) -> Result<SuiAddress, anyhow::Error> {
+        self.add_random_key_with_scheme(SignatureScheme::ED25519)
+    }
+
+    /// Sign `message` after the intent of personal messages, for an app to check that the
+    /// signer owns `address` with `verify_personal_message`.
+    fn sign_personal_message(
+        &self,
+        address: &SuiAddress,
+        message: &[u8],
+    ) -> Result<GenericSignature, signature::Error> {
+        self.sign(
+            address,
+            &PersonalMessage::new(message.to_vec()).to_intent_bytes(),
+        )
+    }
+}
+
+impl KeystoreType {
+    pub fn init(&self) -> Result<Box<dyn Keystore>, anyhow::Error> {
+        Ok(match self {
+            KeystoreType::File(path) => Box::new(SuiKeystore::load_or_create(path)?),
+        })
+    }
+}
+
+impl Display for KeystoreType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let mut writer = String::new();
+        match self {
+            KeystoreType::File(path) => {
+                writeln!(writer, "Keystore Type : File")?;
+                write!(writer, "Keystore Path : {:?}", path)?;
+                write!(f, "{}", writer)
+            }
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Default)]
+pub struct SuiKeystore {
+    keys: BTreeMap<SuiAddress, SuiKeyPair>,
+    path: Option<PathBuf>,
+    /// Set if the keystore file is encrypted, to encrypt it again when keys are added.
+    #[serde(skip)]
+    encryption: Option<KeystoreEncryption>,
+}
+
+impl Keystore for SuiKeystore {
+    fn sign(&self, address: &SuiAddress, msg: &[u8]) -> Result<GenericSignature, signature::Error> {
+        self.keys
+            .get(address)
+            .ok_or_else(|| {
+                signature::Error::from_source(format!("Cannot find key for address: [{address}]"))
+            })?
+            .sign(msg)
+    }
+
+    fn add_random_key_with_scheme(
+        &mut self,
+        scheme: SignatureScheme,
+    ) -> Result<SuiAddress, anyhow::Error> {
+        let keypair = SuiKeyPair::generate(scheme)?;
+        let address = keypair.address();
+        self.keys.insert(address, keypair);
+        self.save()?;
+        Ok(address)
+    }
+}
+
+impl SuiKeystore {
+    /// Load the keystore at `path`, or start an empty one which will be saved there. The
+    /// passphrase of an encrypted keystore is read from `KEYSTORE_PASSPHRASE_ENV`, or
+    /// prompted for. A new keystore is encrypted, with a new passphrase read the same way;
+    /// `decrypt` stores it in plaintext instead.
+    pub fn load_or_create(path: &Path) -> Result<Self, anyhow::Error> {
+        Self::load_or_create_with_passphrase(path, None)
+    }
+
+    /// Like `load_or_create`, with the passphrase to unlock the keystore if it is encrypted,
+    /// or to encrypt it with if it is new.
+    pub fn load_or_create_with_passphrase(
+        path: &Path,
+        passphrase: Option<&str>,
+    ) -> Result<Self, anyhow::Error> {
+        let (keys, encryption): (Vec<SuiKeyPair>, _) = if path.exists() {
+            let reader = BufReader::new(File::open(path)?);
+            let file: serde_json::Value = serde_json::from_reader(reader)?;
+            // Keystores which predate encryption are a plain array of keys.
+            if file.is_array() {
+                (serde_json::from_value(file)?, None)
+            } else {
+                let file: EncryptedKeystore = serde_json::from_value(file)?;
+                let passphrase = match passphrase {
+                    Some(passphrase) => Zeroizing::new(passphrase.to_string()),
+                    None => read_keystore_passphrase(&format!(
+                        "Enter the passphrase of keystore {path:?}: "
+                    ))?,
+                };
+                let (encryption, keys) = KeystoreEncryption::unlock(&file, &passphrase)?;
+                (serde_json::from_slice(&keys)?, Some(encryption))
+            }
+        } else {
+            let passphrase = match passphrase {
+                Some(passphrase) => Zeroizing::new(passphrase.to_string()),
+                None => read_new_keystore_passphrase()?,
+            };
+            (Vec::new(), Some(KeystoreEncryption::new(&passphrase)?))
+        };
+
+        let keys = keys.into_iter().map(|key| (key.address(), key)).collect();
+
+        Ok(Self {
+            keys,
+            path: Some(path.to_path_buf()),
+            encryption,
+        })
+    }
+
+    pub fn set_path(&mut self, path: &Path) {
+        self.path = Some(path.to_path_buf());
+    }
+
+    pub fn save(&self) -> Result<(), anyhow::Error> {
+        if let Some(path) = &self.path {
+            let keys = Zeroizing::new(serde_json::to_string_pretty(
+                &self.keys.values().collect::<Vec<_>>(),
+            )?);
+            let store = match &self.encryption {
+                Some(encryption) => {
+                    serde_json::to_string_pretty(&encryption.encrypt(keys.as_bytes())?)?
+                }
+                None => keys.to_string(),
+            };
+            fs::write(path, store)?
+        }
+        Ok(())
+    }
+
+    pub fn is_encrypted(&self) -> bool {
+        self.encryption.is_some()
+    }
+
+    /// Encrypt the keystore file with `passphrase` from now on. This migrates a plaintext
+    /// keystore, or changes the passphrase of an encrypted one.
+    pub fn encrypt(&mut self, passphrase: &str) -> Result<(), anyhow::Error> {
+        self.encryption = Some(KeystoreEncryption::new(passphrase)?);
+        self.save()
+    }
+
+    /// Store the keys in plaintext again, which is only fit for local test networks.
+    pub fn decrypt(&mut self) -> Result<(), anyhow::Error> {
+        self.encryption = None;
+        self.save()
+    }
+
+    pub fn add_key(
+        &mut self,
+        address: SuiAddress,
+        keypair: impl Into<SuiKeyPair>,
+    ) -> Result<(), anyhow::Error> {
+        self.keys.insert(address, keypair.into());
+        Ok(())
+    }
+
+    /// Derive the key of `scheme` at `derivation_path` from the mnemonic `phrase`, or at the
+    /// default path of the scheme, and add it to the keystore.
+    pub fn import_from_mnemonic(
+        &mut self,
+        phrase: &str,
+        scheme: SignatureScheme,
+        derivation_path: Option<DerivationPath>,
+    ) -> Result<SuiAddress, anyhow::Error> {
+        let mnemonic = parse_mnemonic(phrase)?;
+        let keypair = derive_key_pair_from_mnemonic(&mnemonic, scheme, derivation_path)?;
+        let address = keypair.address();
+        self.keys.insert(address, keypair);
+        self.save()?;
+        Ok(address)
+    }
+
+    /// Add a key derived from a new mnemonic, and return its address with the mnemonic, which
+    /// is not stored and must be written down to restore the key.
+    pub fn generate_new_key_with_mnemonic(
+        &mut self,
+        scheme: SignatureScheme,
+        derivation_path: Option<DerivationPath>,
+    ) -> Result<(SuiAddress, String), anyhow::Error> {
+        let mnemonic = generate_mnemonic();
+        let keypair = derive_key_pair_from_mnemonic(&mnemonic, scheme, derivation_path)?;
+        let address = keypair.address();
+        self.keys.insert(address, keypair);
+        self.save()?;
+        Ok((address, mnemonic.to_string()))
+    }
+
+    pub fn addresses(&self) -> Vec<SuiAddress> {
+        self.keys.keys().cloned().collect()
+    }
+
+    pub fn key_pairs(&self) -> Vec<&SuiKeyPair> {
+        self.keys.values().collect()
+    }
+}
+
+pub struct SuiKeystoreSigner {
+    keystore: Arc<RwLock<Box<dyn Keystore>>>,
+    address: SuiAddress,
+}
+
+impl SuiKeystoreSigner {
+    pub fn new(keystore: Arc<RwLock<Box<dyn Keystore>>>, account: SuiAddress) -> Self {
+        Self {
+            keystore,
+            address: account,
+        }
+    }
+}
+
+// Only signs with ed25519 keys, as `signature::Signer` is implemented for a single type.
+impl signature::Signer<Signature> for SuiKeystoreSigner {
+    fn try_sign(&self, msg: &[u8]) -> Result<Signature, ed25519::Error> {
+        self.keystore
+            .read()
+            .unwrap()
+            .sign(&self.address, msg)?
+            .try_into()
+            .map_err(ed25519::Error::from_source)
+    }
+}
+
+/// Check that `signature`, as returned by `Keystore::sign_personal_message`, is a signature
+/// of `message` by `address`.
+pub fn verify_personal_message(
+    address: SuiAddress,
+    message: &[u8],
+    signature: &GenericSignature,
+) -> Result<(), anyhow::Error> {
+    Ok(PersonalMessage::new(message.to_vec()).verify(signature, address)?)
+}
+
+/// Read the passphrase of a keystore from `KEYSTORE_PASSPHRASE_ENV`, or prompt for it on the
+/// terminal.
+pub fn read_keystore_passphrase(prompt: &str) -> Result<Zeroizing<String>, anyhow::Error> {
+    if let Ok(passphrase) = std::env::var(KEYSTORE_PASSPHRASE_ENV) {
+        return Ok(Zeroizing::new(passphrase));
+    }
+    Ok(Zeroizing::new(rpassword::prompt_password(prompt)?))
+}
+
+/// Read a new passphrase for a keystore from `KEYSTORE_PASSPHRASE_ENV`, or prompt for it twice
+/// on the terminal.
+pub fn read_new_keystore_passphrase() -> Result<Zeroizing<String>, anyhow::Error> {
+    let passphrase = read_keystore_passphrase("Enter a new passphrase for the keystore: ")?;
+    if read_keystore_passphrase("Enter it again: ")? != passphrase {
+        return Err(anyhow!("The passphrases do not match"));
+    }
+    Ok(passphrase)
+}
+
+const ENCRYPTED_KEYSTORE_VERSION: u8 = 1;
+const ENCRYPTION_KEY_LENGTH: usize = 32;
+const SALT_LENGTH: usize = 16;
+const NONCE_LENGTH: usize = 24;
+// The Argon2id parameters recommended by OWASP: 19 MiB of memory and two passes.
+const ARGON2_M_COST: u32 = 19 * 1024;
+const ARGON2_T_COST: u32 = 2;
+const ARGON2_P_COST: u32 = 1;
+
+/// An encrypted keystore file: the keys, serialized as in a plaintext keystore, encrypted with
+/// XChaCha20-Poly1305 under a key derived from the passphrase with Argon2id. The version and
+/// the KDF parameters are the associated data, so that they can't be changed in the file
+/// without failing the decryption.
+#[serde_as]
+#[derive(Serialize, Deserialize)]
+struct EncryptedKeystore {
+    version: u8,
+    kdf: Argon2idParams,
+    #[serde_as(as = "Base64")]
+    nonce: Vec<u8>,
+    #[serde_as(as = "Base64")]
+    ciphertext: Vec<u8>,
+}
+
+/// The parameters are stored with the salt so that they can be raised for new keystores
+/// without breaking existing ones.
+#[serde_as]
+#[derive(Serialize, Deserialize, Clone)]
+struct Argon2idParams {
+    #[serde_as(as = "Base64")]
+    salt: Vec<u8>,
+    m_cost: u32,
+    t_cost: u32,
+    p_cost: u32,
+}
+
+impl Argon2idParams {
+    fn derive_key(
+        &self,
+        passphrase: &str,
+    ) -> Result<Zeroizing<[u8; ENCRYPTION_KEY_LENGTH]>, anyhow::Error> {
+        let params = Params::new(
+            self.m_cost,
+            self.t_cost,
+            self.p_cost,
+            Some(ENCRYPTION_KEY_LENGTH),
+        )
+        .map_err(|err| anyhow!("Invalid keystore encryption parameters: {err}"))?;
+        let mut key = Zeroizing::new([0u8; ENCRYPTION_KEY_LENGTH]);
+        Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+            .hash_password_into(passphrase.as_bytes(), &self.salt, &mut key[..])
+            .map_err(|err| anyhow!("Cannot derive the keystore encryption key: {err}"))?;
+        Ok(key)
+    }
+
+    /// The associated data of the keystore encrypted with these parameters: the version of
+    /// the file, the costs and then the salt, which is the only field whose length varies.
+    fn associated_data(&self, version: u8) -> Vec<u8> {
+        let mut data = vec![version];
+        for cost in [self.m_cost, self.t_cost, self.p_cost] {
+            data.extend(cost.to_le_bytes());
+        }
+        data.extend(&self.salt);
+        data
+    }
+}
+
+/// The key the keystore file is encrypted with, kept while the keystore is loaded so that it
+/// can be saved again without asking for the passphrase.
+struct KeystoreEncryption {
+    kdf: Argon2idParams,
+    key: Zeroizing<[u8; ENCRYPTION_KEY_LENGTH]>,
+}
+
+impl KeystoreEncryption {
+    fn new(passphrase: &str) -> Result<Self, anyhow::Error> {
+        if passphrase.is_empty() {
+            return Err(anyhow!("The passphrase of the keystore can't be empty"));
+        }
+        let mut salt = vec![0u8; SALT_LENGTH];
+        OsRng.fill_bytes(&mut salt);
+        let kdf = Argon2idParams {
+            salt,
+            m_cost: ARGON2_M_COST,
+            t_cost: ARGON2_T_COST,
+            p_cost: ARGON2_P_COST,
+        };
+        let key = kdf.derive_key(passphrase)?;
+        Ok(Self { kdf, key })
+    }
+
+    /// Derive the key of `file` from `passphrase`, and decrypt the keys with it.
+    fn unlock(
+        file: &EncryptedKeystore,
+        passphrase: &str,
+    ) -> Result<(Self, Zeroizing<Vec<u8>>), anyhow::Error> {
+        if file.version != ENCRYPTED_KEYSTORE_VERSION {
+            return Err(anyhow!(
+                "Unsupported keystore version {}, expected {ENCRYPTED_KEYSTORE_VERSION}",
+                file.version
+            ));
+        }
+        if file.nonce.len() != NONCE_LENGTH {
+            return Err(anyhow!("Invalid keystore nonce"));
+        }
+        let encryption = Self {
+            kdf: file.kdf.clone(),
+            key: file.kdf.derive_key(passphrase)?,
+        };
+        let keys = encryption
+            .cipher()
+            .decrypt(
+                XNonce::from_slice(&file.nonce),
+                Payload {
+                    msg: &file.ciphertext,
+                    aad: &file.kdf.associated_data(file.version),
+                },
+            )
+            .map_err(|_| anyhow!("Cannot decrypt the keystore, is the passphrase correct?"))?;
+        Ok((encryption, Zeroizing::new(keys)))
+    }
+
+    /// Encrypt `keys` with a new nonce.
+    fn encrypt(&self, keys: &[u8]) -> Result<EncryptedKeystore, anyhow::Error> {
+        let mut nonce = vec![0u8; NONCE_LENGTH];
+        OsRng.fill_bytes(&mut nonce);
+        let ciphertext = self
+            .cipher()
+            .encrypt(
+                XNonce::from_slice(&nonce),
+                Payload {
+                    msg: keys,
+                    aad: &self.kdf.associated_data(ENCRYPTED_KEYSTORE_VERSION),
+                },
+            )
+            .map_err(|_| anyhow!("Cannot encrypt the keystore"))?;
+        Ok(EncryptedKeystore {
+            version: ENCRYPTED_KEYSTORE_VERSION,
+            kdf: self.kdf.clone(),
+            nonce,
+            ciphertext,
+        })
+    }
+
+    fn cipher(&self) -> XChaCha20Poly1305 {
+        XChaCha20Poly1305::new(Key::from_slice(&self.key[..]))
+    }
+}
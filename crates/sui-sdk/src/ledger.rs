@@ -0,0 +1,257 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! An external signer for the keys of a Ledger device running the Sui app. The device derives
+//! an ed25519 key along a derivation path and never reveals it: the keystore asks it for the
+//! public key, and sends it the bytes to sign. The device can't parse transactions, so it
+//! blind-signs them, showing the digest of the bytes for the user to check and approve.
+//!
+//! The app is spoken to with APDUs. Every command has the class `CLA`, and starts with the
+//! derivation path, serialized as the number of levels followed by their big endian indexes.
+//! Commands longer than an APDU are sent in chunks, which is flagged in `p1` and `p2`.
+
+use std::sync::Arc;
+
+use anyhow::anyhow;
+use ed25519_dalek::ed25519::signature;
+use ed25519_dalek::ed25519::signature::Signature as _;
+use ed25519_dalek::Verifier;
+
+use sui_types::base_types::SuiAddress;
+use sui_types::crypto::{GenericSignature, PublicKeyBytes, Signature, SignatureScheme};
+use sui_types::key_derivation::DerivationPath;
+
+use crate::crypto::Keystore;
+
+pub const CLA: u8 = 0x00;
+/// Return the public key at the path, after the user confirms its address on the device if
+/// `p1` is `P1_CONFIRM`.
+pub const INS_GET_PUBLIC_KEY: u8 = 0x02;
+/// Sign the bytes which follow the path with the key at the path, once the user approves.
+pub const INS_SIGN: u8 = 0x03;
+
+pub const P1_SILENT: u8 = 0x00;
+pub const P1_CONFIRM: u8 = 0x01;
+/// `p1` of the first chunk of a command, and of the other chunks.
+pub const P1_FIRST: u8 = 0x00;
+pub const P1_MORE: u8 = 0x80;
+/// `p2` of the last chunk of a command, and of the chunks before it.
+pub const P2_LAST: u8 = 0x00;
+pub const P2_MORE: u8 = 0x80;
+
+/// The most data an APDU carries.
+pub const MAX_APDU_DATA_LENGTH: usize = 255;
+
+const SW_OK: u16 = 0x9000;
+const SW_REJECTED: u16 = 0x6985;
+const SW_LOCKED: u16 = 0x5515;
+const SW_CLA_NOT_SUPPORTED: u16 = 0x6e00;
+const SW_INS_NOT_SUPPORTED: u16 = 0x6d00;
+
+pub struct Apdu {
+    pub ins: u8,
+    pub p1: u8,
+    pub p2: u8,
+    pub data: Vec<u8>,
+}
+
+pub struct ApduAnswer {
+    pub status: u16,
+    pub data: Vec<u8>,
+}
+
+/// The connection to a device, which sends it an APDU of class `CLA` and returns its answer.
+pub trait LedgerTransport: Send + Sync {
+    fn exchange(&self, apdu: &Apdu) -> Result<ApduAnswer, anyhow::Error>;
+}
+
+/// Signs with the Ledger key at `derivation_path` for its address, and with `fallback` for
+/// the other addresses, so that accounts of the device and of a file can be used together.
+pub struct LedgerKeystore {
+    transport: Arc<dyn LedgerTransport>,
+    derivation_path: DerivationPath,
+    public_key: PublicKeyBytes,
+    fallback: Box<dyn Keystore>,
+}
+
+impl LedgerKeystore {
+    /// Connect to the first Ledger device plugged in over USB, for the key at
+    /// `derivation_path`, or at `m/44'/784'/0'/0'/0'`.
+    pub fn connect(
+        derivation_path: Option<DerivationPath>,
+        fallback: Box<dyn Keystore>,
+    ) -> Result<Self, anyhow::Error> {
+        Self::new(hid::open_transport()?, derivation_path, fallback)
+    }
+
+    pub fn new(
+        transport: Arc<dyn LedgerTransport>,
+        derivation_path: Option<DerivationPath>,
+        fallback: Box<dyn Keystore>,
+    ) -> Result<Self, anyhow::Error> {
+        let derivation_path = match derivation_path {
+            Some(path) => path,
+            None => DerivationPath::default_for(SignatureScheme::ED25519)?,
+        };
+        // The Sui app only holds ed25519 keys.
+        derivation_path.validate(SignatureScheme::ED25519)?;
+        let public_key = get_public_key(&*transport, &derivation_path, P1_SILENT)?;
+        Ok(Self {
+            transport,
+            derivation_path,
+            public_key,
+            fallback,
+        })
+    }
+
+    pub fn address(&self) -> SuiAddress {
+        SuiAddress::from(&self.public_key)
+    }
+
+    pub fn derivation_path(&self) -> &DerivationPath {
+        &self.derivation_path
+    }
+
+    /// Show the address on the device, for the user to check that it's the one they expect.
+    pub fn confirm_address(&self) -> Result<(), anyhow::Error> {
+        let public_key = get_public_key(&*self.transport, &self.derivation_path, P1_CONFIRM)?;
+        if public_key != self.public_key {
+            return Err(anyhow!("The Ledger key changed, was the device swapped?"));
+        }
+        Ok(())
+    }
+
+    fn sign_on_device(&self, msg: &[u8]) -> Result<Signature, anyhow::Error> {
+        let mut chunks = vec![serialize_path(&self.derivation_path)];
+        chunks.extend(msg.chunks(MAX_APDU_DATA_LENGTH).map(|chunk| chunk.to_vec()));
+        let mut signature = Vec::new();
+        for (i, data) in chunks.iter().enumerate() {
+            let apdu = Apdu {
+                ins: INS_SIGN,
+                p1: if i == 0 { P1_FIRST } else { P1_MORE },
+                p2: if i + 1 == chunks.len() {
+                    P2_LAST
+                } else {
+                    P2_MORE
+                },
+                data: data.clone(),
+            };
+            signature = exchange(&*self.transport, &apdu)?;
+        }
+
+        // Check the signature here, as a bad one would only be reported by the validators.
+        let public_key = ed25519_dalek::PublicKey::from_bytes(self.public_key.as_ref())?;
+        let dalek_signature = ed25519_dalek::Signature::from_bytes(&signature)?;
+        public_key.verify(msg, &dalek_signature)?;
+        Ok(Signature::from_bytes(
+            &[&signature[..], self.public_key.as_ref()].concat(),
+        )?)
+    }
+}
+
+impl Keystore for LedgerKeystore {
+    fn sign(&self, address: &SuiAddress, msg: &[u8]) -> Result<GenericSignature, signature::Error> {
+        if *address != self.address() {
+            return self.fallback.sign(address, msg);
+        }
+        self.sign_on_device(msg)
+            .map(GenericSignature::from)
+            .map_err(|err| signature::Error::from_source(err.to_string()))
+    }
+
+    /// The device has a single key per derivation path, so this returns its address instead of
+    /// a new one. Another path gives another account.
+    fn add_random_key_with_scheme(
+        &mut self,
+        scheme: SignatureScheme,
+    ) -> Result<SuiAddress, anyhow::Error> {
+        if scheme != SignatureScheme::ED25519 {
+            return Err(anyhow!("The Ledger Sui app only holds ed25519 keys"));
+        }
+        self.confirm_address()?;
+        Ok(self.address())
+    }
+}
+
+fn serialize_path(path: &DerivationPath) -> Vec<u8> {
+    let mut bytes = vec![path.indexes().len() as u8];
+    for index in path.indexes() {
+        bytes.extend(index.to_be_bytes());
+    }
+    bytes
+}
+
+fn get_public_key(
+    transport: &dyn LedgerTransport,
+    path: &DerivationPath,
+    p1: u8,
+) -> Result<PublicKeyBytes, anyhow::Error> {
+    let apdu = Apdu {
+        ins: INS_GET_PUBLIC_KEY,
+        p1,
+        p2: P2_LAST,
+        data: serialize_path(path),
+    };
+    Ok(PublicKeyBytes::try_from(&exchange(transport, &apdu)?[..])?)
+}
+
+/// Send `apdu` and return the data of the answer, failing on any status other than success.
+fn exchange(transport: &dyn LedgerTransport, apdu: &Apdu) -> Result<Vec<u8>, anyhow::Error> {
+    let answer = transport.exchange(apdu)?;
+    match answer.status {
+        SW_OK => Ok(answer.data),
+        SW_REJECTED => Err(anyhow!("Rejected on the Ledger device")),
+        SW_LOCKED => Err(anyhow!("The Ledger device is locked")),
+        SW_CLA_NOT_SUPPORTED | SW_INS_NOT_SUPPORTED => {
+            Err(anyhow!("The Sui app is not open on the Ledger device"))
+        }
+        status => Err(anyhow!("Ledger error {status:#06x}")),
+    }
+}
+
+#[cfg(feature = "ledger")]
+mod hid {
+    use std::sync::Arc;
+
+    use ledger_apdu::APDUCommand;
+    use ledger_transport_hid::hidapi::HidApi;
+    use ledger_transport_hid::TransportNativeHID;
+
+    use super::{Apdu, ApduAnswer, LedgerTransport, CLA};
+
+    struct HidTransport(TransportNativeHID);
+
+    impl LedgerTransport for HidTransport {
+        fn exchange(&self, apdu: &Apdu) -> Result<ApduAnswer, anyhow::Error> {
+            let answer = self.0.exchange(&APDUCommand {
+                cla: CLA,
+                ins: apdu.ins,
+                p1: apdu.p1,
+                p2: apdu.p2,
+                data: apdu.data.clone(),
+            })?;
+            Ok(ApduAnswer {
+                status: answer.retcode(),
+                data: answer.data().to_vec(),
+            })
+        }
+    }
+
+    pub fn open_transport() -> Result<Arc<dyn LedgerTransport>, anyhow::Error> {
+        let transport = TransportNativeHID::new(&HidApi::new()?)?;
+        Ok(Arc::new(HidTransport(transport)))
+    }
+}
+
+#[cfg(not(feature = "ledger"))]
+mod hid {
+    use std::sync::Arc;
+
+    use super::LedgerTransport;
+
+    pub fn open_transport() -> Result<Arc<dyn LedgerTransport>, anyhow::Error> {
+        Err(anyhow::anyhow!(
+            "Ledger devices are not supported by this build, enable the `ledger` feature"
+        ))
+    }
+}
@@ -0,0 +1,111 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! A Rust client for Sui. `SuiClient` gives typed access to the Gateway API, either over
+//! JSON-RPC or with the gateway embedded in the process, and executes transactions signed
+//! locally with the keys in a `crypto::Keystore`. The client keeps track of the latest versions
+//! of the objects its transactions write, and updates the object references of the transactions
+//! it signs to them.
+
+use std::ops::Deref;
+use std::sync::Arc;
+
+use sui_core::gateway_state::{GatewayAPI, GatewayClient};
+use sui_core::gateway_types::TransactionResponse;
+use sui_gateway::config::GatewayType;
+use sui_gateway::rpc_gateway_client::RpcGatewayClient;
+use sui_types::base_types::ObjectID;
+use sui_types::messages::{Transaction, TransactionData};
+
+use crate::crypto::Keystore;
+use crate::object_versions::ObjectVersions;
+
+pub mod crypto;
+pub mod ledger;
+pub mod object_versions;
+
+/// A client for the Sui Gateway API. All the read and transaction building methods of
+/// `GatewayAPI` can be called directly on the client.
+pub struct SuiClient {
+    api: GatewayClient,
+    versions: ObjectVersions,
+}
+
+impl SuiClient {
+    pub fn new(api: GatewayClient) -> Self {
+        Self {
+            api,
+            versions: ObjectVersions::default(),
+        }
+    }
+
+    /// The latest versions known of the objects written by the transactions of the client.
+    pub fn object_versions(&self) -> &ObjectVersions {
+        &self.versions
+    }
+
+    /// Create a client for the JSON-RPC gateway server at `server_url`.
+    pub fn new_rpc_client(server_url: &str) -> Result<Self, anyhow::Error> {
+        Ok(Self::new(Arc::new(RpcGatewayClient::new(
+            server_url.to_string(),
+        )?)))
+    }
+
+    /// Create a client for the gateway described by `gateway`, which may be embedded.
+    pub fn from_gateway_type(gateway: &GatewayType) -> Result<Self, anyhow::Error> {
+        Ok(Self::new(gateway.init()?))
+    }
+
+    /// Sign `data` with the key of its sender in `keystore` and execute it. The gateway
+    /// submits the transaction to the authorities and returns once a quorum of them has
+    /// certified and executed it.
+    ///
+    /// The object references of `data` older than the versions the client knows of are replaced
+    /// before signing, and those the client knows nothing of are read from the gateway. The
+    /// versions of the objects of a transaction which fails are read again the next time.
+    pub async fn sign_and_execute(
+        &self,
+        mut data: TransactionData,
+        keystore: &dyn Keystore,
+    ) -> Result<TransactionResponse, anyhow::Error> {
+        let unknown = self.versions.refresh(&mut data);
+        if !unknown.is_empty() {
+            self.read_versions(&unknown).await?;
+            self.versions.refresh(&mut data);
+        }
+        let signature = keystore.sign(&data.signer(), &data.to_intent_bytes())?;
+        let objects: Vec<ObjectID> = data.object_refs_mut().iter().map(|r| r.0).collect();
+        match self
+            .api
+            .execute_transaction(Transaction::new(data, signature))
+            .await
+        {
+            Ok(response) => {
+                self.versions.record_response(&response);
+                Ok(response)
+            }
+            Err(error) => {
+                self.versions.forget(&objects);
+                Err(error)
+            }
+        }
+    }
+
+    async fn read_versions(&self, object_ids: &[ObjectID]) -> Result<(), anyhow::Error> {
+        for object_id in object_ids {
+            // An object the gateway does not know of is left for the authorities to reject.
+            if let Ok(object) = self.api.get_object(*object_id).await?.into_object() {
+                self.versions.record(object.reference.to_object_ref());
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Deref for SuiClient {
+    type Target = dyn GatewayAPI + Sync + Send;
+
+    fn deref(&self) -> &Self::Target {
+        self.api.as_ref()
+    }
+}
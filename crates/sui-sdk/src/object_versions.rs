@@ -0,0 +1,113 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! The latest versions of the objects a client knows of, learnt from the effects of the
+//! transactions it executes. A transaction built from an object reference read before the
+//! object last changed is brought up to date before it is signed, rather than being rejected by
+//! the authorities for an object version mismatch.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use sui_core::gateway_types::{SuiObjectRef, SuiTransactionEffects, TransactionResponse};
+use sui_types::base_types::{ObjectID, ObjectRef};
+use sui_types::messages::TransactionData;
+
+#[derive(Default)]
+pub struct ObjectVersions {
+    latest: Mutex<HashMap<ObjectID, ObjectRef>>,
+}
+
+impl ObjectVersions {
+    /// The latest reference known to `object_id`, unless the object is deleted or wrapped.
+    pub fn latest(&self, object_id: &ObjectID) -> Option<ObjectRef> {
+        self.latest.lock().unwrap().get(object_id).copied()
+    }
+
+    /// Record `object_ref`, unless a later version of the object is known already.
+    pub fn record(&self, object_ref: ObjectRef) {
+        let mut latest = self.latest.lock().unwrap();
+        match latest.get(&object_ref.0) {
+            Some((_, version, _)) if *version >= object_ref.1 => (),
+            _ => {
+                latest.insert(object_ref.0, object_ref);
+            }
+        }
+    }
+
+    /// Forget the versions known of `object_ids`, for them to be read again.
+    pub fn forget<'a>(&self, object_ids: impl IntoIterator<Item = &'a ObjectID>) {
+        let mut latest = self.latest.lock().unwrap();
+        for object_id in object_ids {
+            latest.remove(object_id);
+        }
+    }
+
+    /// Record the objects `effects` wrote, and forget those it deleted or wrapped.
+    pub fn record_effects(&self, effects: &SuiTransactionEffects) {
+        for object in effects
+            .created
+            .iter()
+            .chain(&effects.mutated)
+            .chain(&effects.unwrapped)
+        {
+            self.record(object.reference.to_object_ref());
+        }
+        self.forget(
+            effects
+                .deleted
+                .iter()
+                .chain(&effects.wrapped)
+                .chain(&effects.unwrapped_then_deleted)
+                .map(|object| &object.object_id),
+        );
+    }
+
+    /// Record the objects `response` returns.
+    pub fn record_response(&self, response: &TransactionResponse) {
+        let objects: Vec<&SuiObjectRef> = match response {
+            TransactionResponse::EffectResponse(response) => {
+                if let Some(effects) = &response.effects {
+                    self.record_effects(effects);
+                }
+                return;
+            }
+            TransactionResponse::PublishResponse(response) => response
+                .created_objects
+                .iter()
+                .chain([&response.updated_gas])
+                .map(|object| &object.reference)
+                .collect(),
+            TransactionResponse::MergeCoinResponse(response) => {
+                vec![
+                    &response.updated_coin.reference,
+                    &response.updated_gas.reference,
+                ]
+            }
+            TransactionResponse::SplitCoinResponse(response) => response
+                .new_coins
+                .iter()
+                .chain([&response.updated_coin, &response.updated_gas])
+                .map(|object| &object.reference)
+                .collect(),
+        };
+        for object in objects {
+            self.record(object.to_object_ref());
+        }
+    }
+
+    /// Replace the references `data` takes with the later versions known of their objects.
+    /// Returns the objects of which no version is known, and so are left as they are.
+    pub fn refresh(&self, data: &mut TransactionData) -> Vec<ObjectID> {
+        let latest = self.latest.lock().unwrap();
+        let mut unknown = vec![];
+        for object_ref in data.object_refs_mut() {
+            match latest.get(&object_ref.0) {
+                Some(known) if known.1 > object_ref.1 => *object_ref = *known,
+                Some(_) => (),
+                None => unknown.push(object_ref.0),
+            }
+        }
+        unknown
+    }
+}
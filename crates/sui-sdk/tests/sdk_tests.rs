@@ -0,0 +1,246 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::sync::{Arc, Mutex};
+
+use signature::Signer;
+use sui_sdk::crypto::{Keystore, SuiKeystore};
+use sui_sdk::ledger::{
+    Apdu, ApduAnswer, LedgerKeystore, LedgerTransport, INS_GET_PUBLIC_KEY, INS_SIGN,
+    MAX_APDU_DATA_LENGTH, P1_FIRST, P2_LAST,
+};
+use sui_sdk::SuiClient;
+use sui_types::base_types::{ObjectDigest, ObjectID, SequenceNumber, SuiAddress};
+use sui_types::crypto::{get_key_pair, KeyPair, Signature, SignatureScheme};
+use sui_types::messages::{Transaction, TransactionData};
+use test_utils::network::start_rpc_test_network;
+
+#[tokio::test]
+async fn test_sign_and_execute() -> Result<(), anyhow::Error> {
+    let test_network = start_rpc_test_network(None).await?;
+    let client = SuiClient::new_rpc_client(&test_network.rpc_url)?;
+    let address = *test_network.accounts.first().unwrap();
+    let recipient = *test_network.accounts.last().unwrap();
+    client.sync_account_state(address).await?;
+    let objects = client.get_objects_owned_by_address(address).await?;
+    let coin = objects.first().unwrap().object_id;
+
+    let data = client
+        .transfer_coin(
+            address,
+            coin,
+            Some(objects.last().unwrap().object_id),
+            1000,
+            recipient,
+        )
+        .await?;
+    let keystore = SuiKeystore::load_or_create(&test_network.network.dir().join("wallet.key"))?;
+    let effects = client
+        .sign_and_execute(data, &keystore)
+        .await?
+        .to_effect_response()?
+        .effects
+        .unwrap();
+    assert_eq!(2, effects.mutated.len());
+
+    let object = client.get_object(coin).await?.into_object()?;
+    assert_eq!(recipient, object.owner.get_owner_address()?);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_stale_references_are_refreshed() -> Result<(), anyhow::Error> {
+    let test_network = start_rpc_test_network(None).await?;
+    let client = SuiClient::new_rpc_client(&test_network.rpc_url)?;
+    let address = *test_network.accounts.first().unwrap();
+    let recipient = *test_network.accounts.last().unwrap();
+    client.sync_account_state(address).await?;
+    let objects = client.get_objects_owned_by_address(address).await?;
+    let keystore = SuiKeystore::load_or_create(&test_network.network.dir().join("wallet.key"))?;
+    let gas = objects.last().unwrap();
+    let stale_gas = (gas.object_id, gas.version, gas.digest);
+
+    let data = client
+        .transfer_coin(
+            address,
+            objects[0].object_id,
+            Some(gas.object_id),
+            1000,
+            recipient,
+        )
+        .await?;
+    client.sign_and_execute(data, &keystore).await?;
+    let latest_gas = client.object_versions().latest(&gas.object_id).unwrap();
+    assert!(latest_gas.1 > stale_gas.1);
+
+    // The second transfer is built from the version of the gas object the first one spent.
+    let coin = &objects[1];
+    let data = TransactionData::new_transfer(
+        recipient,
+        (coin.object_id, coin.version, coin.digest),
+        address,
+        stale_gas,
+        1000,
+    );
+    let effects = client
+        .sign_and_execute(data, &keystore)
+        .await?
+        .to_effect_response()?
+        .effects
+        .unwrap();
+    assert!(effects.status.is_ok());
+    assert_eq!(
+        recipient,
+        client
+            .get_object(coin.object_id)
+            .await?
+            .into_object()?
+            .owner
+            .get_owner_address()?
+    );
+    assert!(client.object_versions().latest(&gas.object_id).unwrap().1 > latest_gas.1);
+    Ok(())
+}
+
+#[test]
+fn test_encrypted_keystore() -> Result<(), anyhow::Error> {
+    let temp_dir = tempfile::tempdir()?;
+
+    // New keystores are encrypted.
+    let path = temp_dir.path().join("new.key");
+    let mut keystore = SuiKeystore::load_or_create_with_passphrase(&path, Some("passphrase"))?;
+    assert!(keystore.is_encrypted());
+    let address = keystore.add_random_key()?;
+    assert!(!std::fs::read_to_string(&path)?
+        .trim_start()
+        .starts_with('['));
+    let reloaded = SuiKeystore::load_or_create_with_passphrase(&path, Some("passphrase"))?;
+    assert_eq!(reloaded.addresses(), vec![address]);
+    assert!(SuiKeystore::load_or_create_with_passphrase(
+        &temp_dir.path().join("empty.key"),
+        Some("")
+    )
+    .is_err());
+
+    // The KDF parameters are authenticated with the keys.
+    let mut file: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(&path)?)?;
+    file["kdf"]["p_cost"] = 2.into();
+    std::fs::write(&path, file.to_string())?;
+    assert!(SuiKeystore::load_or_create_with_passphrase(&path, Some("passphrase")).is_err());
+
+    // A plaintext keystore, as written for the test networks.
+    let path = temp_dir.path().join("wallet.key");
+    let mut keystore = SuiKeystore::default();
+    keystore.set_path(&path);
+    keystore.add_random_key()?;
+    keystore.add_random_key_with_scheme(SignatureScheme::Secp256k1)?;
+    let plaintext_keys: Vec<String> = serde_json::from_str(&std::fs::read_to_string(&path)?)?;
+
+    // Migrate the plaintext keystore. The file no longer holds the keys in the clear.
+    keystore.encrypt("correct horse battery staple")?;
+    let file = std::fs::read_to_string(&path)?;
+    assert!(plaintext_keys.iter().all(|key| !file.contains(key)));
+
+    let mut keystore =
+        SuiKeystore::load_or_create_with_passphrase(&path, Some("correct horse battery staple"))?;
+    assert!(keystore.is_encrypted());
+    assert_eq!(keystore.addresses().len(), 2);
+    assert!(SuiKeystore::load_or_create_with_passphrase(&path, Some("wrong")).is_err());
+
+    // Keys added later are encrypted too.
+    let address = keystore.add_random_key()?;
+    let reloaded =
+        SuiKeystore::load_or_create_with_passphrase(&path, Some("correct horse battery staple"))?;
+    assert!(reloaded.addresses().contains(&address));
+
+    // Back to plaintext, which loads without a passphrase.
+    keystore.decrypt()?;
+    let reloaded = SuiKeystore::load_or_create_with_passphrase(&path, None)?;
+    assert!(!reloaded.is_encrypted());
+    assert_eq!(reloaded.addresses(), keystore.addresses());
+    Ok(())
+}
+
+/// A device running the Sui app, which holds a single key whatever the derivation path.
+struct MockLedger {
+    key: KeyPair,
+    message: Mutex<Vec<u8>>,
+    apdus: Mutex<usize>,
+}
+
+impl LedgerTransport for MockLedger {
+    fn exchange(&self, apdu: &Apdu) -> Result<ApduAnswer, anyhow::Error> {
+        assert!(apdu.data.len() <= MAX_APDU_DATA_LENGTH);
+        *self.apdus.lock().unwrap() += 1;
+        let data = match apdu.ins {
+            INS_GET_PUBLIC_KEY => self.key.public_key_bytes().as_ref().to_vec(),
+            INS_SIGN => {
+                let mut message = self.message.lock().unwrap();
+                // The first chunk is the derivation path.
+                if apdu.p1 == P1_FIRST {
+                    message.clear();
+                } else {
+                    message.extend(&apdu.data);
+                }
+                if apdu.p2 != P2_LAST {
+                    return Ok(ApduAnswer {
+                        status: 0x9000,
+                        data: vec![],
+                    });
+                }
+                let signature: Signature = self.key.sign(&message);
+                signature.signature_bytes().to_vec()
+            }
+            _ => {
+                return Ok(ApduAnswer {
+                    status: 0x6d00,
+                    data: vec![],
+                })
+            }
+        };
+        Ok(ApduAnswer {
+            status: 0x9000,
+            data,
+        })
+    }
+}
+
+#[test]
+fn test_ledger_keystore() -> Result<(), anyhow::Error> {
+    let (ledger_address, key) = get_key_pair();
+    let device = Arc::new(MockLedger {
+        key,
+        message: Mutex::new(vec![]),
+        apdus: Mutex::new(0),
+    });
+    let mut fallback = SuiKeystore::default();
+    let file_address = fallback.add_random_key()?;
+    let keystore = LedgerKeystore::new(device.clone(), None, Box::new(fallback))?;
+    assert_eq!(keystore.address(), ledger_address);
+
+    // The device signs for its address, in chunks if the transaction doesn't fit in an APDU.
+    let sign = |sender: SuiAddress| -> Result<Transaction, anyhow::Error> {
+        let object_ref = (
+            ObjectID::random(),
+            SequenceNumber::new(),
+            ObjectDigest::new([0; 32]),
+        );
+        let modules = vec![vec![7u8; 3 * MAX_APDU_DATA_LENGTH]];
+        let data = TransactionData::new_module(sender, object_ref, modules, 10000);
+        let signature = keystore.sign(&sender, &data.to_intent_bytes())?;
+        Ok(Transaction::new(data, signature))
+    };
+    *device.apdus.lock().unwrap() = 0;
+    assert!(sign(ledger_address)?.verify_signature().is_ok());
+    assert!(*device.apdus.lock().unwrap() > 4);
+
+    // The other addresses are signed for by the fallback keystore.
+    *device.apdus.lock().unwrap() = 0;
+    assert!(sign(file_address)?.verify_signature().is_ok());
+    assert_eq!(*device.apdus.lock().unwrap(), 0);
+
+    // Paths of other schemes are rejected, as the Sui app only holds ed25519 keys.
+    let path = "m/54'/784'/0'/0/0".parse()?;
+    assert!(LedgerKeystore::new(device, Some(path), Box::new(SuiKeystore::default())).is_err());
+    Ok(())
+}
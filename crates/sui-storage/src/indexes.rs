@@ -10,10 +10,18 @@ use serde::{de::DeserializeOwned, Serialize};
 use std::path::Path;
 use sui_types::base_types::{ObjectID, SuiAddress, TransactionDigest};
 use sui_types::batch::TxSequenceNumber;
+use sui_types::committee::{Committee, EpochId};
 
 use sui_types::error::SuiResult;
+use sui_types::event::Event;
+use sui_types::messages_checkpoint::{
+    CertifiedCheckpoint, CheckpointContents, CheckpointDigest, CheckpointParticipation,
+    CheckpointSequenceNumber,
+};
 
+use sui_types::name_service::NameServiceRegistry;
 use sui_types::object::Object;
+use sui_types::order_book::{OrderBookEvent, OrderBookEventEnvelope};
 
 use typed_store::rocks::DBMap;
 use typed_store::{reopen, traits::Map};
@@ -30,6 +38,36 @@ pub struct IndexStore {
 
     /// Index from object id to transactions that modified/created that object id.
     transactions_by_mutated_object_id: DBMap<(ObjectID, TxSequenceNumber), TransactionDigest>,
+
+    /// Index from order book pool id to the events of the pool, in the order of the
+    /// transactions and, within a transaction, of the events.
+    order_book_events:
+        DBMap<(ObjectID, TxSequenceNumber, u64), (TransactionDigest, OrderBookEvent)>,
+
+    /// The name service registries, in the order they were created. Names resolve with the
+    /// first one.
+    name_service_registries: DBMap<(TxSequenceNumber, u64), ObjectID>,
+
+    /// The certified checkpoints this node followed, from the first one it has not pruned on.
+    checkpoints: DBMap<CheckpointSequenceNumber, CertifiedCheckpoint>,
+
+    /// The contents of the checkpoints followed.
+    checkpoint_contents: DBMap<CheckpointSequenceNumber, CheckpointContents>,
+
+    /// Index from the digest of a checkpoint followed to its sequence number.
+    checkpoints_by_digest: DBMap<CheckpointDigest, CheckpointSequenceNumber>,
+
+    /// Index from the transactions of the checkpoints followed to the epoch and the sequence
+    /// number of their checkpoint. It is kept for the pruned checkpoints, to tell the
+    /// transactions pruned from the unknown ones.
+    checkpoints_by_transaction: DBMap<TransactionDigest, (EpochId, CheckpointSequenceNumber)>,
+
+    /// The committees which certified the checkpoints followed, by epoch.
+    committees: DBMap<EpochId, Committee>,
+
+    /// The authorities which took part in each checkpoint followed, by the epoch of the
+    /// committee which certified it.
+    checkpoint_participation: DBMap<(EpochId, CheckpointSequenceNumber), CheckpointParticipation>,
 }
 
 impl IndexStore {
@@ -51,6 +89,14 @@ impl IndexStore {
                 ("transactions_to_addr", &options),
                 ("transactions_by_input_object_id", &options),
                 ("transactions_by_mutated_object_id", &options),
+                ("order_book_events", &options),
+                ("name_service_registries", &options),
+                ("checkpoints", &options),
+                ("checkpoint_contents", &options),
+                ("checkpoints_by_digest", &options),
+                ("checkpoints_by_transaction", &options),
+                ("committees", &options),
+                ("checkpoint_participation", &options),
             ];
             typed_store::rocks::open_cf_opts(path, db_options, opt_cfs)
         }
@@ -61,12 +107,28 @@ impl IndexStore {
             transactions_to_addr,
             transactions_by_input_object_id,
             transactions_by_mutated_object_id,
+            order_book_events,
+            name_service_registries,
+            checkpoints,
+            checkpoint_contents,
+            checkpoints_by_digest,
+            checkpoints_by_transaction,
+            committees,
+            checkpoint_participation,
         ) = reopen!(
             &db,
             "transactions_from_addr"; <(SuiAddress, TxSequenceNumber), TransactionDigest>,
             "transactions_to_addr"; <(SuiAddress, TxSequenceNumber), TransactionDigest>,
             "transactions_by_input_object_id"; <(ObjectID, TxSequenceNumber), TransactionDigest>,
-            "transactions_by_mutated_object_id"; <(ObjectID, TxSequenceNumber), TransactionDigest>
+            "transactions_by_mutated_object_id"; <(ObjectID, TxSequenceNumber), TransactionDigest>,
+            "order_book_events"; <(ObjectID, TxSequenceNumber, u64), (TransactionDigest, OrderBookEvent)>,
+            "name_service_registries"; <(TxSequenceNumber, u64), ObjectID>,
+            "checkpoints"; <CheckpointSequenceNumber, CertifiedCheckpoint>,
+            "checkpoint_contents"; <CheckpointSequenceNumber, CheckpointContents>,
+            "checkpoints_by_digest"; <CheckpointDigest, CheckpointSequenceNumber>,
+            "checkpoints_by_transaction"; <TransactionDigest, (EpochId, CheckpointSequenceNumber)>,
+            "committees"; <EpochId, Committee>,
+            "checkpoint_participation"; <(EpochId, CheckpointSequenceNumber), CheckpointParticipation>
         );
 
         Self {
@@ -74,6 +136,14 @@ impl IndexStore {
             transactions_to_addr,
             transactions_by_input_object_id,
             transactions_by_mutated_object_id,
+            order_book_events,
+            name_service_registries,
+            checkpoints,
+            checkpoint_contents,
+            checkpoints_by_digest,
+            checkpoints_by_transaction,
+            committees,
+            checkpoint_participation,
         }
     }
 
@@ -82,6 +152,7 @@ impl IndexStore {
         sender: SuiAddress,
         active_inputs: &[&Object],
         mutated_objects: &[&Object],
+        events: &[Event],
         sequence: TxSequenceNumber,
         digest: &TransactionDigest,
     ) -> SuiResult {
@@ -115,6 +186,26 @@ impl IndexStore {
             }),
         )?;
 
+        let batch = batch.insert_batch(
+            &self.order_book_events,
+            events
+                .iter()
+                .filter_map(OrderBookEvent::from_event)
+                .enumerate()
+                .map(|(index, event)| {
+                    ((event.pool_id(), sequence, index as u64), (*digest, event))
+                }),
+        )?;
+
+        let batch = batch.insert_batch(
+            &self.name_service_registries,
+            events
+                .iter()
+                .filter_map(NameServiceRegistry::created_by_event)
+                .enumerate()
+                .map(|(index, registry_id)| ((sequence, index as u64), registry_id)),
+        )?;
+
         batch.write()?;
 
         Ok(())
@@ -161,4 +252,315 @@ impl IndexStore {
     ) -> SuiResult<Vec<(TxSequenceNumber, TransactionDigest)>> {
         Self::get_transactions_by_object(&self.transactions_to_addr, addr)
     }
+
+    pub fn get_order_book_events(&self, pool: ObjectID) -> SuiResult<Vec<OrderBookEventEnvelope>> {
+        Ok(self
+            .order_book_events
+            .iter()
+            .skip_to(&(pool, TxSequenceNumber::MIN, 0))?
+            .take_while(|((id, _, _), _)| *id == pool)
+            .map(
+                |((_, tx_sequence_number, _), (tx_digest, event))| OrderBookEventEnvelope {
+                    tx_sequence_number,
+                    tx_digest,
+                    event,
+                },
+            )
+            .collect())
+    }
+
+    /// The registry names resolve with, once one is created.
+    pub fn get_name_service_registry(&self) -> SuiResult<Option<ObjectID>> {
+        Ok(self
+            .name_service_registries
+            .iter()
+            .next()
+            .map(|(_, registry_id)| registry_id))
+    }
+
+    /// Record `checkpoint`, certified by `committee`, with its contents and the authorities
+    /// which took part in it.
+    pub fn insert_checkpoint(
+        &self,
+        committee: &Committee,
+        checkpoint: &CertifiedCheckpoint,
+        contents: &CheckpointContents,
+        participation: &CheckpointParticipation,
+    ) -> SuiResult {
+        let epoch = committee.epoch();
+        let sequence_number = checkpoint.checkpoint.sequence_number;
+        let batch = self
+            .checkpoints
+            .batch()
+            .insert_batch(
+                &self.checkpoints,
+                std::iter::once((sequence_number, checkpoint)),
+            )?
+            .insert_batch(
+                &self.checkpoint_contents,
+                std::iter::once((sequence_number, contents)),
+            )?
+            .insert_batch(
+                &self.checkpoints_by_digest,
+                std::iter::once((checkpoint.checkpoint.digest(), sequence_number)),
+            )?
+            .insert_batch(
+                &self.checkpoints_by_transaction,
+                contents
+                    .transactions
+                    .iter()
+                    .map(|digests| (digests.transaction, (epoch, sequence_number))),
+            )?
+            .insert_batch(&self.committees, std::iter::once((epoch, committee)))?
+            .insert_batch(
+                &self.checkpoint_participation,
+                std::iter::once(((epoch, sequence_number), participation)),
+            )?;
+        batch.write()?;
+        Ok(())
+    }
+
+    /// The sequence number of the checkpoint to follow after those recorded.
+    pub fn next_checkpoint(&self) -> CheckpointSequenceNumber {
+        self.checkpoints
+            .iter()
+            .skip_to_last()
+            .next()
+            .map(|(sequence_number, _)| sequence_number + 1)
+            .unwrap_or(0)
+    }
+
+    /// The sequence number of the first checkpoint recorded which has not been pruned.
+    pub fn lowest_checkpoint(&self) -> CheckpointSequenceNumber {
+        match self.checkpoints.iter().next() {
+            Some((sequence_number, _)) => sequence_number,
+            None => self.next_checkpoint(),
+        }
+    }
+
+    /// Prune the checkpoints before `sequence_number`, with their contents. Their committees,
+    /// the participation in them and the index of their transactions are kept.
+    pub fn prune_checkpoints(&self, sequence_number: CheckpointSequenceNumber) -> SuiResult {
+        let pruned: Vec<_> = self
+            .checkpoints
+            .iter()
+            .take_while(|(pruned, _)| *pruned < sequence_number)
+            .map(|(pruned, checkpoint)| (pruned, checkpoint.checkpoint.digest()))
+            .collect();
+        if pruned.is_empty() {
+            return Ok(());
+        }
+        let batch = self
+            .checkpoints
+            .batch()
+            .delete_batch(&self.checkpoints, pruned.iter().map(|(pruned, _)| *pruned))?
+            .delete_batch(
+                &self.checkpoint_contents,
+                pruned.iter().map(|(pruned, _)| *pruned),
+            )?
+            .delete_batch(
+                &self.checkpoints_by_digest,
+                pruned.iter().map(|(_, digest)| *digest),
+            )?;
+        batch.write()?;
+        Ok(())
+    }
+
+    pub fn get_checkpoint(
+        &self,
+        sequence_number: CheckpointSequenceNumber,
+    ) -> SuiResult<Option<CertifiedCheckpoint>> {
+        Ok(self.checkpoints.get(&sequence_number)?)
+    }
+
+    pub fn get_checkpoint_contents(
+        &self,
+        sequence_number: CheckpointSequenceNumber,
+    ) -> SuiResult<Option<CheckpointContents>> {
+        Ok(self.checkpoint_contents.get(&sequence_number)?)
+    }
+
+    pub fn get_checkpoint_sequence_number(
+        &self,
+        digest: &CheckpointDigest,
+    ) -> SuiResult<Option<CheckpointSequenceNumber>> {
+        Ok(self.checkpoints_by_digest.get(digest)?)
+    }
+
+    /// The epoch and the sequence number of the checkpoint followed which `transaction` is in.
+    pub fn get_checkpoint_of_transaction(
+        &self,
+        transaction: &TransactionDigest,
+    ) -> SuiResult<Option<(EpochId, CheckpointSequenceNumber)>> {
+        Ok(self.checkpoints_by_transaction.get(transaction)?)
+    }
+
+    /// The committee of `epoch`, if it certified checkpoints followed.
+    pub fn get_committee(&self, epoch: EpochId) -> SuiResult<Option<Committee>> {
+        Ok(self.committees.get(&epoch)?)
+    }
+
+    /// A page of at most `limit` of the checkpoints followed and not pruned, with their
+    /// contents, starting with `cursor`, or else with the first checkpoint, or the last one if
+    /// `descending`. Also returns the cursor of the next page, if there are more checkpoints.
+    pub fn get_checkpoints(
+        &self,
+        cursor: Option<CheckpointSequenceNumber>,
+        limit: usize,
+        descending: bool,
+    ) -> SuiResult<(
+        Vec<(CertifiedCheckpoint, CheckpointContents)>,
+        Option<CheckpointSequenceNumber>,
+    )> {
+        // The checkpoints held are those from the lowest one not pruned up to the last one
+        // recorded.
+        let lowest_checkpoint = self.lowest_checkpoint();
+        let next_checkpoint = self.next_checkpoint();
+        let limit = limit as u64;
+        let (range, next_cursor) = if descending {
+            let last_recorded = match next_checkpoint.checked_sub(1) {
+                Some(last_recorded) => last_recorded,
+                None => return Ok((Vec::new(), None)),
+            };
+            let first = cursor.unwrap_or(last_recorded).min(last_recorded);
+            if first < lowest_checkpoint {
+                return Ok((Vec::new(), None));
+            }
+            let last = (first + 1).saturating_sub(limit).max(lowest_checkpoint);
+            let next_cursor = if last > lowest_checkpoint {
+                Some(last - 1)
+            } else {
+                None
+            };
+            ((last..=first).rev().collect::<Vec<_>>(), next_cursor)
+        } else {
+            let first = cursor.unwrap_or(0).max(lowest_checkpoint);
+            let end = first.saturating_add(limit).min(next_checkpoint);
+            let next_cursor = if end < next_checkpoint {
+                Some(end)
+            } else {
+                None
+            };
+            ((first..end).collect(), next_cursor)
+        };
+
+        let mut checkpoints = Vec::new();
+        for sequence_number in range {
+            if let (Some(checkpoint), Some(contents)) = (
+                self.checkpoints.get(&sequence_number)?,
+                self.checkpoint_contents.get(&sequence_number)?,
+            ) {
+                checkpoints.push((checkpoint, contents));
+            }
+        }
+        Ok((checkpoints, next_cursor))
+    }
+
+    /// The authorities which took part in the checkpoints of `epoch` this node followed.
+    pub fn get_checkpoint_participation(
+        &self,
+        epoch: EpochId,
+    ) -> SuiResult<Vec<(CheckpointSequenceNumber, CheckpointParticipation)>> {
+        Ok(self
+            .checkpoint_participation
+            .iter()
+            .skip_to(&(epoch, CheckpointSequenceNumber::MIN))?
+            .take_while(|((checkpoint_epoch, _), _)| *checkpoint_epoch == epoch)
+            .map(|((_, sequence_number), participation)| (sequence_number, participation))
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::IndexStore;
+    use std::collections::BTreeMap;
+    use sui_types::base_types::ExecutionDigests;
+    use sui_types::committee::Committee;
+    use sui_types::crypto::get_key_pair;
+    use sui_types::messages_checkpoint::{
+        CertifiedCheckpoint, CheckpointContents, CheckpointParticipation, SignedCheckpoint,
+    };
+
+    #[test]
+    fn test_checkpoints_pages() {
+        let working_dir = tempfile::tempdir().unwrap();
+        let index_store = IndexStore::open(&working_dir, None);
+
+        let (_, key_pair) = get_key_pair();
+        let name = *key_pair.public_key_bytes();
+        let committee = Committee::new(
+            0,
+            BTreeMap::from([(name, 1)]),
+            BTreeMap::from([(name, key_pair.protocol_public_key())]),
+        );
+        let mut previous_digest = None;
+        for sequence_number in 0..5 {
+            let contents = CheckpointContents::new(std::iter::once(ExecutionDigests::random()));
+            let signed =
+                SignedCheckpoint::new(sequence_number, name, &key_pair, &contents, previous_digest);
+            let checkpoint = CertifiedCheckpoint::aggregate(vec![signed], &committee).unwrap();
+            let participation = CheckpointParticipation {
+                signers: vec![name],
+                proposers: vec![],
+            };
+            index_store
+                .insert_checkpoint(&committee, &checkpoint, &contents, &participation)
+                .unwrap();
+            let transaction = contents.transactions.iter().next().unwrap().transaction;
+            assert_eq!(
+                index_store
+                    .get_checkpoint_of_transaction(&transaction)
+                    .unwrap(),
+                Some((0, sequence_number))
+            );
+            previous_digest = Some(checkpoint.checkpoint.digest());
+        }
+        assert_eq!(index_store.next_checkpoint(), 5);
+        assert_eq!(
+            index_store
+                .get_checkpoint_sequence_number(&previous_digest.unwrap())
+                .unwrap(),
+            Some(4)
+        );
+
+        let sequence_numbers = |cursor, descending| {
+            let (checkpoints, next_cursor) =
+                index_store.get_checkpoints(cursor, 2, descending).unwrap();
+            let sequence_numbers: Vec<_> = checkpoints
+                .iter()
+                .map(|(checkpoint, _)| checkpoint.checkpoint.sequence_number)
+                .collect();
+            (sequence_numbers, next_cursor)
+        };
+        assert_eq!(sequence_numbers(None, false), (vec![0, 1], Some(2)));
+        assert_eq!(sequence_numbers(Some(2), false), (vec![2, 3], Some(4)));
+        assert_eq!(sequence_numbers(Some(4), false), (vec![4], None));
+        assert_eq!(sequence_numbers(None, true), (vec![4, 3], Some(2)));
+        assert_eq!(sequence_numbers(Some(1), true), (vec![1, 0], None));
+        assert_eq!(sequence_numbers(Some(9), true), (vec![4, 3], Some(2)));
+
+        let transaction = index_store
+            .get_checkpoint_contents(1)
+            .unwrap()
+            .unwrap()
+            .transactions
+            .iter()
+            .next()
+            .unwrap()
+            .transaction;
+        index_store.prune_checkpoints(2).unwrap();
+        assert_eq!(index_store.lowest_checkpoint(), 2);
+        assert_eq!(index_store.next_checkpoint(), 5);
+        assert!(index_store.get_checkpoint(1).unwrap().is_none());
+        assert_eq!(
+            index_store
+                .get_checkpoint_of_transaction(&transaction)
+                .unwrap(),
+            Some((0, 1))
+        );
+        assert_eq!(sequence_numbers(None, false), (vec![2, 3], Some(4)));
+        assert_eq!(sequence_numbers(Some(3), true), (vec![3, 2], None));
+        assert_eq!(sequence_numbers(Some(1), true), (vec![], None));
+    }
 }
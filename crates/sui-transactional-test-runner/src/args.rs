@@ -3,10 +3,12 @@
 
 use anyhow::{bail, ensure};
 use clap;
-use move_command_line_common::values::ParsableValue;
+use move_command_line_common::values::{ParsableValue, ParsedValue};
 use move_command_line_common::{parser::Parser as MoveCLParser, values::ValueToken};
 use move_compiler::shared::parse_u128;
 use move_core_types::identifier::Identifier;
+use move_core_types::language_storage::TypeTag;
+use move_core_types::parser::parse_type_tag;
 use move_core_types::value::{MoveStruct, MoveValue};
 use sui_types::messages::CallArg;
 
@@ -39,10 +41,55 @@ pub struct ViewObjectCommand {
     pub id: u64,
 }
 
+#[derive(Debug, clap::Parser)]
+pub struct AdvanceEpochCommand {
+    #[clap(long = "count", default_value = "1")]
+    pub count: u64,
+}
+
+#[derive(Debug, clap::Parser)]
+pub struct RunBatchCommand {
+    #[clap(long = "sender")]
+    pub sender: Option<String>,
+    #[clap(long = "gas-budget")]
+    pub gas_budget: Option<u64>,
+    #[clap(long = "view-events")]
+    pub view_events: bool,
+}
+
+/// A call of a `run-batch` task, one per line of the task, as
+/// `<address>::<module>::<function> --type-args ... --args ...`.
+#[derive(Debug, clap::Parser)]
+#[clap(no_binary_name = true)]
+pub struct BatchCall {
+    pub function: String,
+    #[clap(
+        long = "type-args",
+        parse(try_from_str = parse_type_tag),
+        takes_value(true),
+        multiple_values(true)
+    )]
+    pub type_args: Vec<TypeTag>,
+    #[clap(
+        long = "args",
+        parse(try_from_str = ParsedValue::parse),
+        takes_value(true),
+        multiple_values(true)
+    )]
+    pub args: Vec<ParsedValue<SuiExtraValueArgs>>,
+}
+
 #[derive(Debug, clap::Parser)]
 pub enum SuiSubcommand {
     #[clap(name = "view-object")]
     ViewObject(ViewObjectCommand),
+    /// Execute the transactions of the following tasks in a later epoch
+    #[clap(name = "advance-epoch")]
+    AdvanceEpoch(AdvanceEpochCommand),
+    /// Execute the calls on the lines of the task in a single batch transaction, where the
+    /// calls fail or succeed together
+    #[clap(name = "run-batch")]
+    RunBatch(RunBatchCommand),
 }
 
 #[derive(Debug)]
@@ -20,6 +20,10 @@ impl BackingPackageStore for InMemoryStorage {
     fn get_package(&self, package_id: &ObjectID) -> SuiResult<Option<Object>> {
         Ok(self.persistent.get(package_id).cloned())
     }
+
+    fn get_object(&self, object_id: &ObjectID) -> SuiResult<Option<Object>> {
+        Ok(self.persistent.get(object_id).cloned())
+    }
 }
 
 impl InMemoryStorage {
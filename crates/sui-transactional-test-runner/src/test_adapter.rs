@@ -4,8 +4,9 @@
 //! This module contains the transactional test runner instantiation for the Sui adapter
 
 use crate::{args::*, in_memory_storage::InMemoryStorage};
-use anyhow::{anyhow, bail};
+use anyhow::{anyhow, bail, Context};
 use bimap::btree::BiBTreeMap;
+use clap::Parser;
 use itertools::Itertools;
 use move_binary_format::{file_format::CompiledScript, CompiledModule};
 use move_bytecode_utils::module_cache::GetModule;
@@ -45,14 +46,17 @@ use sui_types::{
         ObjectDigest, ObjectID, ObjectRef, SequenceNumber, SuiAddress, TransactionDigest,
         SUI_ADDRESS_LENGTH,
     },
+    committee::EpochId,
     crypto::{get_key_pair_from_rng, KeyPair, Signature},
     error::SuiError,
     event::Event,
     gas,
     messages::{
-        ExecutionStatus, InputObjectKind, Transaction, TransactionData, TransactionEffects,
+        ExecutionStatus, MoveCall, SingleTransactionKind, Transaction, TransactionData,
+        TransactionEffects, TransactionKind,
     },
     object::{self, Object, ObjectFormatOptions, GAS_VALUE_FOR_TESTING},
+    protocol_config::ProtocolConfig,
     MOVE_STDLIB_ADDRESS, SUI_FRAMEWORK_ADDRESS,
 };
 
@@ -76,6 +80,8 @@ pub struct SuiTestAdapter<'a> {
     object_enumeration: BiBTreeMap<ObjectID, FakeID>,
     next_fake: FakeID,
     rng: StdRng,
+    /// The epoch the transactions execute in, advanced by `advance-epoch`.
+    epoch: EpochId,
 }
 
 struct TxnSummary {
@@ -172,6 +178,7 @@ impl<'a> MoveTestAdapter<'a> for SuiTestAdapter<'a> {
             object_enumeration: BiBTreeMap::new(),
             next_fake: INIT_NEXT_FAKE,
             rng,
+            epoch: 0,
         };
         let object_ids = test_adapter
             .storage
@@ -281,16 +288,7 @@ impl<'a> MoveTestAdapter<'a> for SuiTestAdapter<'a> {
             .into_iter()
             .map(|arg| arg.into_call_args(self))
             .collect::<anyhow::Result<_>>()?;
-        let package_id = ObjectID::from(*module_id.address());
-        let package_ref = match self.storage.get_object(&package_id) {
-            Some(obj) => obj.compute_object_reference(),
-            // object not found
-            None => (
-                package_id,
-                SequenceNumber::from(1),
-                ObjectDigest::new([0; 32]),
-            ),
-        };
+        let package_ref = self.package_ref(ObjectID::from(*module_id.address()));
 
         let gas_budget = gas_budget.unwrap_or(GAS_VALUE_FOR_TESTING);
         let data = |sender, gas_payment| {
@@ -348,9 +346,47 @@ impl<'a> MoveTestAdapter<'a> for SuiTestAdapter<'a> {
             start_line,
             command_lines_stop,
             stop_line: _,
-            data: _,
+            data,
         } = task;
         match command {
+            SuiSubcommand::AdvanceEpoch(AdvanceEpochCommand { count }) => {
+                self.epoch += count;
+                Ok(Some(format!("Epoch advanced: {}", self.epoch)))
+            }
+            SuiSubcommand::RunBatch(RunBatchCommand {
+                sender,
+                gas_budget,
+                view_events,
+            }) => {
+                let contents = match &data {
+                    Some(data) => std::fs::read_to_string(data.path())?,
+                    None => String::new(),
+                };
+                let calls = contents
+                    .lines()
+                    .map(str::trim)
+                    .filter(|line| !line.is_empty() && !line.starts_with("//"))
+                    .map(|line| self.batch_call(line))
+                    .collect::<anyhow::Result<Vec<_>>>()?;
+                if calls.is_empty() {
+                    panic!(
+                        "task {}, lines {}-{}. A batch needs at least one call",
+                        number, start_line, command_lines_stop
+                    )
+                }
+                let gas_budget = gas_budget.unwrap_or(GAS_VALUE_FOR_TESTING);
+                let data = |sender, gas_payment| {
+                    TransactionData::new(
+                        TransactionKind::Batch(calls),
+                        sender,
+                        gas_payment,
+                        gas_budget,
+                    )
+                };
+                let transaction = self.sign_txn(sender, data);
+                let summary = self.execute_txn(transaction, gas_budget)?;
+                Ok(self.object_summary_output(&summary, view_events))
+            }
             SuiSubcommand::ViewObject(ViewObjectCommand { id: fake_id }) => {
                 let id = match self.fake_to_real_object_id(fake_id) {
                     None => panic!(
@@ -430,7 +466,9 @@ impl<'a> SuiTestAdapter<'a> {
         transaction: Transaction,
         gas_budget: u64,
     ) -> anyhow::Result<TxnSummary> {
-        let gas_status = gas::start_gas_metering(gas_budget, 1, 1).unwrap();
+        let mut gas_status =
+            gas::start_gas_metering(ProtocolConfig::get_for_max_version(), gas_budget, 1, 1)
+                .unwrap();
         let transaction_digest = TransactionDigest::new(self.rng.gen());
         let objects_by_kind = transaction
             .data
@@ -450,7 +488,7 @@ impl<'a> SuiTestAdapter<'a> {
             .collect();
         let shared_object_refs: Vec<_> = objects_by_kind
             .iter()
-            .filter(|(kind, _)| matches!(kind, InputObjectKind::SharedMoveObject(_)))
+            .filter(|(kind, _)| kind.is_shared_object())
             .map(|(_, obj)| obj.compute_object_reference())
             .sorted()
             .collect();
@@ -476,9 +514,8 @@ impl<'a> SuiTestAdapter<'a> {
             transaction_dependencies,
             &self.vm,
             &self.native_functions,
-            gas_status,
-            // TODO: Support different epochs in transactional tests.
-            0,
+            &mut gas_status,
+            self.epoch,
         )?;
         let (_objects, _active_inputs, written, deleted, _events) = temporary_store.into_inner();
         let created_set: BTreeSet<_> = created.iter().map(|((id, _, _), _)| *id).collect();
@@ -518,6 +555,62 @@ impl<'a> SuiTestAdapter<'a> {
         }
     }
 
+    /// The reference of the package at `package_id`, or a made up one if there is none, for the
+    /// transaction to fail on.
+    fn package_ref(&self, package_id: ObjectID) -> ObjectRef {
+        match self.storage.get_object(&package_id) {
+            Some(obj) => obj.compute_object_reference(),
+            // object not found
+            None => (
+                package_id,
+                SequenceNumber::from(1),
+                ObjectDigest::new([0; 32]),
+            ),
+        }
+    }
+
+    /// The call of a line of a `run-batch` task.
+    fn batch_call(&self, line: &str) -> anyhow::Result<SingleTransactionKind> {
+        let BatchCall {
+            function,
+            type_args,
+            args,
+        } = BatchCall::try_parse_from(line.split_whitespace())
+            .with_context(|| format!("INVALID TEST. Cannot parse the call '{}'", line))?;
+        let (address, module, function) = match function.split("::").collect::<Vec<_>>()[..] {
+            [address, module, function] => (address, module, function),
+            _ => bail!(
+                "INVALID TEST. Expected <address>::<module>::<function>, found '{}'",
+                function
+            ),
+        };
+        let mapping = |name: &str| {
+            self.compiled_state
+                .named_address_mapping
+                .get(name)
+                .map(|addr| addr.into_inner())
+        };
+        let address = if address.starts_with("0x") {
+            AccountAddress::from_hex_literal(address)?
+        } else {
+            match mapping(address) {
+                Some(address) => address,
+                None => bail!("INVALID TEST. Unbound named address '{}'", address),
+            }
+        };
+        let arguments = args
+            .into_iter()
+            .map(|arg| arg.into_concrete_value(&mapping)?.into_call_args(self))
+            .collect::<anyhow::Result<_>>()?;
+        Ok(SingleTransactionKind::Call(MoveCall {
+            package: self.package_ref(ObjectID::from(address)),
+            module: Identifier::new(module)?,
+            function: Identifier::new(function)?,
+            type_arguments: type_args,
+            arguments,
+        }))
+    }
+
     // stable way of sorting objects by type. Does not however, produce a stable sorting
     // between objects of the same type
     fn get_object_sorting_key(&self, id: &ObjectID) -> String {
@@ -322,6 +322,10 @@ impl TxContext {
         }
     }
 
+    pub fn sender(&self) -> SuiAddress {
+        SuiAddress::from(ObjectID::from(self.sender))
+    }
+
     pub fn epoch(&self) -> EpochId {
         self.epoch
     }
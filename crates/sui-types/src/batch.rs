@@ -4,6 +4,7 @@
 use crate::base_types::{AuthorityName, ExecutionDigests};
 use crate::crypto::{sha3_hash, AuthoritySignature, BcsSignable};
 use crate::error::SuiError;
+use crate::intent::{IntentScope, IntentScoped};
 use serde::{Deserialize, Serialize};
 
 pub type TxSequenceNumber = u64;
@@ -42,6 +43,10 @@ pub struct AuthorityBatch {
 
 impl BcsSignable for AuthorityBatch {}
 
+impl IntentScoped for AuthorityBatch {
+    const INTENT_SCOPE: IntentScope = IntentScope::AuthorityBatch;
+}
+
 impl AuthorityBatch {
     pub fn digest(&self) -> BatchDigest {
         sha3_hash(self)
@@ -0,0 +1,385 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! BLS12-381 keys and signatures, with which validators sign protocol messages: their votes on
+//! transactions and checkpoints, their signed effects and batches. BLS signatures of the same
+//! message aggregate into one signature, checked against the sum of the public keys of its
+//! signers, so that a certificate holds a single signature and takes a single pairing check
+//! however large the committee is. Accounts keep their ed25519 and ECDSA keys.
+//!
+//! Signatures are points of G1 and public keys points of G2, since certificates carry
+//! signatures while keys are only held by the committee. Summing the keys of the signers is only
+//! sound if every key is known to be held by its owner, so each validator registers its key
+//! with a proof of possession: its signature of the key itself, under a separate domain.
+
+use base64ct::Encoding;
+use blst::min_sig as bls;
+use blst::BLST_ERROR;
+use rand::rngs::OsRng;
+use rand::RngCore;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_with::serde_as;
+use signature::Signer;
+
+use crate::crypto::{KeyPair, Signable};
+use crate::error::{SuiError, SuiResult};
+use crate::fp_ensure;
+use crate::intent::{intent_message, IntentScoped};
+use crate::sui_serde::{Base64, Readable};
+
+#[cfg(test)]
+#[path = "unit_tests/bls_tests.rs"]
+mod bls_tests;
+
+/// The length of a compressed protocol public key, a point of G2.
+pub const PROTOCOL_PUBLIC_KEY_LENGTH: usize = 96;
+/// The length of a compressed protocol signature, a point of G1.
+pub const PROTOCOL_SIGNATURE_LENGTH: usize = 48;
+
+/// The domain of the signatures of protocol messages, as in the proof of possession scheme of
+/// the IETF BLS signature draft.
+const SIGNATURE_DST: &[u8] = b"BLS_SIG_BLS12381G1_XMD:SHA-256_SSWU_RO_POP_";
+/// The domain of proofs of possession, so that a proof is never the signature of a message.
+const PROOF_OF_POSSESSION_DST: &[u8] = b"BLS_POP_BLS12381G1_XMD:SHA-256_SSWU_RO_POP_";
+/// The bits of the scalars weighing the signatures of a batch.
+const RANDOM_SCALAR_BITS: usize = 64;
+/// The key info of the derivation of protocol keys from the ed25519 keys of the validators.
+const PROTOCOL_KEY_INFO: &[u8] = b"sui-protocol-key";
+
+/// A validated protocol public key, as the committee caches them.
+pub type ProtocolPublicKey = bls::PublicKey;
+
+/// Derive the protocol key of a validator from its ed25519 secret key, so that a validator
+/// keeps a single secret, and rotates both keys at once.
+pub(crate) fn derive_protocol_secret_key(ed25519_secret: &[u8]) -> bls::SecretKey {
+    bls::SecretKey::key_gen(ed25519_secret, PROTOCOL_KEY_INFO)
+        .expect("ed25519 secret keys are long enough to derive BLS keys")
+}
+
+fn invalid_signature(error: BLST_ERROR) -> SuiError {
+    SuiError::InvalidSignature {
+        error: format!("{error:?}"),
+    }
+}
+
+fn check(result: BLST_ERROR) -> SuiResult {
+    match result {
+        BLST_ERROR::BLST_SUCCESS => Ok(()),
+        error => Err(invalid_signature(error)),
+    }
+}
+
+/// Read a compressed signature, checked to be a point of G1 other than the identity.
+fn read_signature(bytes: &[u8]) -> SuiResult<bls::Signature> {
+    bls::Signature::sig_validate(bytes, true).map_err(invalid_signature)
+}
+
+impl KeyPair {
+    /// The public key with which the protocol signatures of this key pair are checked.
+    pub fn protocol_public_key(&self) -> ProtocolPublicKeyBytes {
+        ProtocolPublicKeyBytes(self.protocol_secret_key().sk_to_pk().compress())
+    }
+
+    /// The proof that the owner of this key pair holds the secret of its protocol key.
+    pub fn protocol_key_proof(&self) -> ProtocolKeyProof {
+        let key = self.protocol_public_key();
+        let proof = self
+            .protocol_secret_key()
+            .sign(key.as_ref(), PROOF_OF_POSSESSION_DST, &[]);
+        ProtocolKeyProof(proof.compress())
+    }
+}
+
+impl Signer<AuthoritySignature> for KeyPair {
+    fn try_sign(&self, msg: &[u8]) -> Result<AuthoritySignature, signature::Error> {
+        let signature = self.protocol_secret_key().sign(msg, SIGNATURE_DST, &[]);
+        Ok(AuthoritySignature(signature.compress()))
+    }
+}
+
+#[serde_as]
+#[derive(Eq, PartialEq, Ord, PartialOrd, Copy, Clone, Hash, Serialize, Deserialize, JsonSchema)]
+pub struct ProtocolPublicKeyBytes(
+    #[schemars(with = "Base64")]
+    #[serde_as(as = "Readable<Base64, [_; PROTOCOL_PUBLIC_KEY_LENGTH]>")]
+    [u8; PROTOCOL_PUBLIC_KEY_LENGTH],
+);
+
+impl ProtocolPublicKeyBytes {
+    /// The key, checked to be a point of G2 other than the identity.
+    pub fn to_public_key(&self) -> SuiResult<ProtocolPublicKey> {
+        bls::PublicKey::key_validate(&self.0).map_err(|_| SuiError::InvalidAuthenticator)
+    }
+}
+
+impl AsRef<[u8]> for ProtocolPublicKeyBytes {
+    fn as_ref(&self) -> &[u8] {
+        self.0.as_ref()
+    }
+}
+
+impl TryFrom<&[u8]> for ProtocolPublicKeyBytes {
+    type Error = SuiError;
+
+    fn try_from(bytes: &[u8]) -> Result<Self, SuiError> {
+        let arr: [u8; PROTOCOL_PUBLIC_KEY_LENGTH] = bytes
+            .try_into()
+            .map_err(|_| SuiError::InvalidAuthenticator)?;
+        Ok(Self(arr))
+    }
+}
+
+impl std::fmt::Debug for ProtocolPublicKeyBytes {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "bls#{}", hex::encode(&self.0))
+    }
+}
+
+/// The proof that a validator holds the secret of its protocol key: its signature of the key.
+#[serde_as]
+#[derive(Eq, PartialEq, Copy, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ProtocolKeyProof(
+    #[schemars(with = "Base64")]
+    #[serde_as(as = "Readable<Base64, [_; PROTOCOL_SIGNATURE_LENGTH]>")]
+    [u8; PROTOCOL_SIGNATURE_LENGTH],
+);
+
+impl ProtocolKeyProof {
+    /// Check that this is the proof of possession of `key`.
+    pub fn verify(&self, key: &ProtocolPublicKeyBytes) -> SuiResult {
+        let public_key = key.to_public_key()?;
+        let proof = read_signature(&self.0)?;
+        check(proof.verify(
+            false,
+            key.as_ref(),
+            PROOF_OF_POSSESSION_DST,
+            &[],
+            &public_key,
+            false,
+        ))
+    }
+}
+
+impl AsRef<[u8]> for ProtocolKeyProof {
+    fn as_ref(&self) -> &[u8] {
+        self.0.as_ref()
+    }
+}
+
+impl TryFrom<&[u8]> for ProtocolKeyProof {
+    type Error = SuiError;
+
+    fn try_from(bytes: &[u8]) -> Result<Self, SuiError> {
+        let arr: [u8; PROTOCOL_SIGNATURE_LENGTH] =
+            bytes.try_into().map_err(|_| SuiError::InvalidSignature {
+                error: "Invalid length of a protocol key proof".to_string(),
+            })?;
+        Ok(Self(arr))
+    }
+}
+
+impl std::fmt::Debug for ProtocolKeyProof {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", base64ct::Base64::encode_string(&self.0))
+    }
+}
+
+/// A signature emitted by an authority. It's useful to decouple this from user signatures,
+/// as their set of supported schemes will probably diverge
+#[serde_as]
+#[derive(Eq, PartialEq, Copy, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct AuthoritySignature(
+    #[schemars(with = "Base64")]
+    #[serde_as(as = "Readable<Base64, [_; PROTOCOL_SIGNATURE_LENGTH]>")]
+    [u8; PROTOCOL_SIGNATURE_LENGTH],
+);
+
+impl AuthoritySignature {
+    /// Signs with the provided Signer
+    pub fn new<T>(value: &T, secret: &dyn signature::Signer<AuthoritySignature>) -> Self
+    where
+        T: Signable<Vec<u8>> + IntentScoped,
+    {
+        let message = intent_message(value);
+        secret.sign(&message)
+    }
+
+    /// Check the signature against the protocol key of its author.
+    pub fn verify<T>(&self, value: &T, key: &ProtocolPublicKey) -> SuiResult
+    where
+        T: Signable<Vec<u8>> + IntentScoped,
+    {
+        let signature = read_signature(&self.0)?;
+        let message = intent_message(value);
+        check(signature.verify(false, &message, SIGNATURE_DST, &[], key, false))
+    }
+}
+
+impl AsRef<[u8]> for AuthoritySignature {
+    fn as_ref(&self) -> &[u8] {
+        self.0.as_ref()
+    }
+}
+
+impl signature::Signature for AuthoritySignature {
+    fn from_bytes(bytes: &[u8]) -> Result<Self, signature::Error> {
+        let bytes: [u8; PROTOCOL_SIGNATURE_LENGTH] =
+            bytes.try_into().map_err(|_| signature::Error::new())?;
+        Ok(Self(bytes))
+    }
+}
+
+impl std::fmt::Debug for AuthoritySignature {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", base64ct::Base64::encode_string(&self.0))
+    }
+}
+
+/// The signatures of several authorities of the same value, aggregated into one signature of
+/// the same size.
+#[serde_as]
+#[derive(Eq, PartialEq, Copy, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct AggregateAuthoritySignature(
+    #[schemars(with = "Base64")]
+    #[serde_as(as = "Readable<Base64, [_; PROTOCOL_SIGNATURE_LENGTH]>")]
+    [u8; PROTOCOL_SIGNATURE_LENGTH],
+);
+
+impl AggregateAuthoritySignature {
+    /// Aggregate `signatures`, which must sign the same value for the aggregate to be valid.
+    pub fn aggregate<'a>(
+        signatures: impl IntoIterator<Item = &'a AuthoritySignature>,
+    ) -> SuiResult<Self> {
+        let signatures = signatures
+            .into_iter()
+            .map(|signature| read_signature(&signature.0))
+            .collect::<SuiResult<Vec<_>>>()?;
+        fp_ensure!(
+            !signatures.is_empty(),
+            SuiError::InvalidSignature {
+                error: "No signature to aggregate".to_string(),
+            }
+        );
+        let signatures: Vec<_> = signatures.iter().collect();
+        let aggregate =
+            bls::AggregateSignature::aggregate(&signatures, false).map_err(invalid_signature)?;
+        Ok(Self(aggregate.to_signature().compress()))
+    }
+
+    /// Check that this is the aggregate of the signatures of `value` by the owners of `keys`,
+    /// which must be distinct.
+    pub fn verify<T>(&self, value: &T, keys: &[&ProtocolPublicKey]) -> SuiResult
+    where
+        T: Signable<Vec<u8>> + IntentScoped,
+    {
+        fp_ensure!(
+            !keys.is_empty(),
+            SuiError::InvalidSignature {
+                error: "An aggregate signature needs at least one signer".to_string(),
+            }
+        );
+        let signature = read_signature(&self.0)?;
+        let message = intent_message(value);
+        check(signature.fast_aggregate_verify(false, &message, SIGNATURE_DST, keys))
+    }
+}
+
+/// Aggregate signatures to check together. Each holds against the sum of the keys of its
+/// signers, and the batch takes one pairing per signature and one more, rather than two per
+/// signature.
+#[derive(Default)]
+pub struct AggregateVerificationObligation {
+    messages: Vec<Vec<u8>>,
+    keys: Vec<bls::PublicKey>,
+    signatures: Vec<bls::Signature>,
+}
+
+impl AggregateVerificationObligation {
+    /// Add the check of `signature`, the aggregate of the signatures of `value` by the owners of
+    /// `keys`, which must be distinct.
+    pub fn add<T>(
+        &mut self,
+        value: &T,
+        signature: &AggregateAuthoritySignature,
+        keys: &[&ProtocolPublicKey],
+    ) -> SuiResult
+    where
+        T: Signable<Vec<u8>> + IntentScoped,
+    {
+        fp_ensure!(
+            !keys.is_empty(),
+            SuiError::InvalidSignature {
+                error: "An aggregate signature needs at least one signer".to_string(),
+            }
+        );
+        let key = bls::AggregatePublicKey::aggregate(keys, false).map_err(invalid_signature)?;
+        self.signatures.push(read_signature(&signature.0)?);
+        self.keys.push(key.to_public_key());
+        self.messages.push(intent_message(value));
+        Ok(())
+    }
+
+    /// Check every signature added. The batch is weighed by random scalars, so that invalid
+    /// signatures cannot make up for each other; it fails without telling which signature is
+    /// invalid.
+    pub fn verify_all(&self) -> SuiResult {
+        match self.signatures.len() {
+            0 => Ok(()),
+            1 => check(self.signatures[0].verify(
+                false,
+                &self.messages[0],
+                SIGNATURE_DST,
+                &[],
+                &self.keys[0],
+                false,
+            )),
+            count => {
+                let messages: Vec<_> = self.messages.iter().map(|m| &m[..]).collect();
+                let keys: Vec<_> = self.keys.iter().collect();
+                let signatures: Vec<_> = self.signatures.iter().collect();
+                let scalars: Vec<_> = (0..count)
+                    .map(|_| {
+                        let mut scalar = blst::blst_scalar::default();
+                        OsRng.fill_bytes(&mut scalar.b[..RANDOM_SCALAR_BITS / 8]);
+                        scalar
+                    })
+                    .collect();
+                check(bls::Signature::verify_multiple_aggregate_signatures(
+                    &messages,
+                    SIGNATURE_DST,
+                    &keys,
+                    false,
+                    &signatures,
+                    false,
+                    &scalars,
+                    RANDOM_SCALAR_BITS,
+                ))
+            }
+        }
+    }
+}
+
+impl AsRef<[u8]> for AggregateAuthoritySignature {
+    fn as_ref(&self) -> &[u8] {
+        self.0.as_ref()
+    }
+}
+
+impl TryFrom<&[u8]> for AggregateAuthoritySignature {
+    type Error = SuiError;
+
+    fn try_from(bytes: &[u8]) -> Result<Self, SuiError> {
+        let arr: [u8; PROTOCOL_SIGNATURE_LENGTH] =
+            bytes.try_into().map_err(|_| SuiError::InvalidSignature {
+                error: "Invalid length of an aggregate signature".to_string(),
+            })?;
+        Ok(Self(arr))
+    }
+}
+
+impl std::fmt::Debug for AggregateAuthoritySignature {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", base64ct::Base64::encode_string(&self.0))
+    }
+}
@@ -0,0 +1,188 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! The messages of the bridge of SUI to other chains. The validators sign each transfer between
+//! Sui and another chain with their protocol keys, for the chain receiving the tokens to release
+//! them once validators with a quorum of stake signed the transfer: the other chain checks the
+//! aggregate signature of a transfer from Sui, and the framework module `Sui::Bridge` the one of
+//! a transfer to Sui when its recipient claims it.
+
+use move_core_types::{ident_str, identifier::IdentStr, language_storage::StructTag};
+use serde::{Deserialize, Serialize};
+
+use crate::base_types::{AuthorityName, SuiAddress};
+use crate::bls::{AggregateAuthoritySignature, AuthoritySignature, ProtocolPublicKeyBytes};
+use crate::committee::Committee;
+use crate::crypto::{AuthorityQuorumSignInfo, BcsSignable};
+use crate::error::{SuiError, SuiResult};
+use crate::event::Event;
+use crate::intent::{IntentScope, IntentScoped};
+use crate::SUI_FRAMEWORK_ADDRESS;
+
+#[cfg(test)]
+#[path = "unit_tests/bridge_tests.rs"]
+mod bridge_tests;
+
+pub const BRIDGE_MODULE_NAME: &IdentStr = ident_str!("Bridge");
+const TOKENS_LOCKED_STRUCT_NAME: &IdentStr = ident_str!("TokensLocked");
+
+/// The ID of a chain of the bridge.
+pub type BridgeChainId = u8;
+
+/// The ID of Sui among the chains of the bridge.
+pub const SUI_CHAIN_ID: BridgeChainId = 0;
+
+/// A transfer of tokens from `source_chain` to `target_chain`, the `nonce`-th from the source
+/// chain. Addresses are given in the format of their chain.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct BridgeTransfer {
+    pub source_chain: BridgeChainId,
+    pub target_chain: BridgeChainId,
+    pub nonce: u64,
+    pub sender: Vec<u8>,
+    pub recipient: Vec<u8>,
+    pub amount: u64,
+}
+
+impl BcsSignable for BridgeTransfer {}
+
+impl IntentScoped for BridgeTransfer {
+    const INTENT_SCOPE: IntentScope = IntentScope::BridgeTransfer;
+}
+
+impl From<TokensLocked> for BridgeTransfer {
+    fn from(locked: TokensLocked) -> Self {
+        Self {
+            source_chain: SUI_CHAIN_ID,
+            target_chain: locked.target_chain,
+            nonce: locked.nonce,
+            sender: locked.sender.to_vec(),
+            recipient: locked.recipient,
+            amount: locked.amount,
+        }
+    }
+}
+
+impl BridgeTransfer {
+    /// Check that `signature` is the aggregate signature of the transfer by the owners of
+    /// `keys`, which must be distinct.
+    pub fn verify_aggregate(
+        &self,
+        signature: &AggregateAuthoritySignature,
+        keys: &[ProtocolPublicKeyBytes],
+    ) -> SuiResult {
+        let keys = keys
+            .iter()
+            .map(|key| key.to_public_key())
+            .collect::<SuiResult<Vec<_>>>()?;
+        signature.verify(self, &keys.iter().collect::<Vec<_>>())
+    }
+}
+
+/// Rust version of the Move Sui::Bridge::TokensLocked event, emitted when SUI is locked for a
+/// transfer to another chain.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TokensLocked {
+    pub nonce: u64,
+    pub target_chain: BridgeChainId,
+    pub sender: SuiAddress,
+    pub recipient: Vec<u8>,
+    pub amount: u64,
+}
+
+impl TokensLocked {
+    pub fn type_() -> StructTag {
+        StructTag {
+            address: SUI_FRAMEWORK_ADDRESS,
+            module: BRIDGE_MODULE_NAME.to_owned(),
+            name: TOKENS_LOCKED_STRUCT_NAME.to_owned(),
+            type_params: vec![],
+        }
+    }
+
+    /// The transfer locked by `event`, if it is a `TokensLocked` event.
+    pub fn from_event(event: &Event) -> Option<Self> {
+        match event {
+            Event::MoveEvent { type_, contents } if type_ == &Self::type_() => {
+                bcs::from_bytes(contents).ok()
+            }
+            _ => None,
+        }
+    }
+}
+
+/// A transfer signed by an authority.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SignedBridgeTransfer {
+    pub transfer: BridgeTransfer,
+    pub authority: AuthorityName,
+    signature: AuthoritySignature,
+}
+
+impl SignedBridgeTransfer {
+    pub fn new(
+        transfer: BridgeTransfer,
+        authority: AuthorityName,
+        signer: &dyn signature::Signer<AuthoritySignature>,
+    ) -> Self {
+        let signature = AuthoritySignature::new(&transfer, signer);
+        Self {
+            transfer,
+            authority,
+            signature,
+        }
+    }
+
+    /// Checks that the signature is correct, with the key of the authority in `committee`.
+    pub fn verify(&self, committee: &Committee) -> SuiResult {
+        let key = committee.protocol_key(&self.authority)?;
+        self.signature.verify(&self.transfer, &key)
+    }
+}
+
+/// A transfer signed by authorities with a quorum of stake, which the receiving chain releases
+/// the tokens of.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CertifiedBridgeTransfer {
+    pub transfer: BridgeTransfer,
+    pub auth_signature: AuthorityQuorumSignInfo,
+}
+
+impl CertifiedBridgeTransfer {
+    /// Aggregate the signatures of a transfer into a certificate.
+    pub fn aggregate(signed: Vec<SignedBridgeTransfer>, committee: &Committee) -> SuiResult<Self> {
+        fp_ensure!(
+            !signed.is_empty(),
+            SuiError::from("Need at least one signed transfer to aggregate")
+        );
+        let transfer = signed[0].transfer.clone();
+        fp_ensure!(
+            signed.iter().all(|signed| signed.transfer == transfer),
+            SuiError::from("The signed transfers differ")
+        );
+        let signatures: Vec<_> = signed
+            .iter()
+            .map(|signed| (signed.authority, signed.signature))
+            .collect();
+        let certificate = Self {
+            transfer,
+            auth_signature: AuthorityQuorumSignInfo::new(&signatures, committee)?,
+        };
+        certificate.verify(committee)?;
+        Ok(certificate)
+    }
+
+    /// Check that the transfer is signed by a quorum of `committee`.
+    pub fn verify(&self, committee: &Committee) -> SuiResult {
+        self.auth_signature
+            .verify(&self.transfer, committee, committee.quorum_threshold())
+    }
+
+    /// The authorities of `committee` which signed the transfer.
+    pub fn signatory_authorities<'a>(
+        &self,
+        committee: &'a Committee,
+    ) -> SuiResult<Vec<&'a AuthorityName>> {
+        self.auth_signature.authorities(committee)
+    }
+}
@@ -3,6 +3,8 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use super::base_types::*;
+use crate::bls::{ProtocolPublicKey, ProtocolPublicKeyBytes};
+use crate::error::{SuiError, SuiResult};
 use ed25519_dalek::PublicKey;
 use itertools::Itertools;
 use rand::distributions::{Distribution, Uniform};
@@ -11,32 +13,64 @@ use serde::{Deserialize, Serialize};
 use std::borrow::Borrow;
 use std::collections::{BTreeMap, HashMap};
 
+#[cfg(test)]
+#[path = "unit_tests/committee_tests.rs"]
+mod committee_tests;
+
 pub type EpochId = u64;
 
 pub type StakeUnit = u64;
 
+/// The largest total stake of a consensus committee. Consensus weighs votes with stakes of a
+/// smaller integer type, so the stakes of larger committees are scaled down to it.
+pub const MAX_CONSENSUS_TOTAL_STAKE: StakeUnit = 10_000;
+
+/// The authorities of an epoch. Each authority votes with its stake, and quorums are reached by
+/// stake rather than by the number of authorities, since authorities need not have equal stakes.
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Committee {
     pub epoch: EpochId,
+    /// The stake of each authority. An authority with no stake is not a member of the committee.
     pub voting_rights: BTreeMap<AuthorityName, StakeUnit>,
+    /// The BLS key with which each authority signs protocol messages.
+    pub protocol_keys: BTreeMap<AuthorityName, ProtocolPublicKeyBytes>,
     pub total_votes: StakeUnit,
-    // Note: this is a derived structure, no need to store.
+    // Note: these are derived structures, no need to store.
     #[serde(skip)]
     pub expanded_keys: HashMap<AuthorityName, PublicKey>,
+    #[serde(skip)]
+    pub expanded_protocol_keys: HashMap<AuthorityName, ProtocolPublicKey>,
 }
 
 impl Committee {
-    pub fn new(epoch: EpochId, voting_rights: BTreeMap<AuthorityName, StakeUnit>) -> Self {
+    pub fn new(
+        epoch: EpochId,
+        voting_rights: BTreeMap<AuthorityName, StakeUnit>,
+        protocol_keys: BTreeMap<AuthorityName, ProtocolPublicKeyBytes>,
+    ) -> Self {
         let total_votes = voting_rights.iter().map(|(_, votes)| votes).sum();
         let expanded_keys: HashMap<_, _> = voting_rights
             .iter()
             .map(|(addr, _)| (*addr, (*addr).try_into().expect("Invalid Authority Key")))
             .collect();
+        let expanded_protocol_keys: HashMap<_, _> = voting_rights
+            .iter()
+            .map(|(addr, _)| {
+                let key = protocol_keys
+                    .get(addr)
+                    .expect("Every authority has a protocol key")
+                    .to_public_key()
+                    .expect("Invalid Authority Protocol Key");
+                (*addr, key)
+            })
+            .collect();
         Committee {
             epoch,
             voting_rights,
+            protocol_keys,
             total_votes,
             expanded_keys,
+            expanded_protocol_keys,
         }
     }
 
@@ -63,16 +97,61 @@ impl Committee {
         *self.voting_rights.get(author).unwrap_or(&0)
     }
 
+    /// The position of `authority` among the authorities of the committee, in the order of their
+    /// names.
+    pub fn authority_index(&self, authority: &AuthorityName) -> Option<usize> {
+        self.voting_rights.keys().position(|name| name == authority)
+    }
+
+    /// The key with which `authority` signs protocol messages.
+    pub fn protocol_key(&self, authority: &AuthorityName) -> SuiResult<ProtocolPublicKey> {
+        match self.expanded_protocol_keys.get(authority) {
+            Some(key) => Ok(*key),
+            None => self
+                .protocol_keys
+                .get(authority)
+                .ok_or(SuiError::UnknownSigner)?
+                .to_public_key(),
+        }
+    }
+
+    /// The stake of `authorities`, which must be distinct. Authorities which are not members of
+    /// the committee count for nothing.
+    pub fn weight_of<'a>(
+        &self,
+        authorities: impl IntoIterator<Item = &'a AuthorityName>,
+    ) -> StakeUnit {
+        authorities
+            .into_iter()
+            .map(|authority| self.weight(authority))
+            .sum()
+    }
+
+    /// The stake of `author` in the consensus committee: its stake, scaled down for the total not
+    /// to exceed `MAX_CONSENSUS_TOTAL_STAKE` (up to rounding), while every member keeps a non-zero
+    /// stake.
+    pub fn consensus_weight(&self, author: &AuthorityName) -> StakeUnit {
+        let weight = self.weight(author);
+        if weight == 0 || self.total_votes <= MAX_CONSENSUS_TOTAL_STAKE {
+            return weight;
+        }
+        let scaled = weight as u128 * MAX_CONSENSUS_TOTAL_STAKE as u128 / self.total_votes as u128;
+        (scaled as StakeUnit).max(1)
+    }
+
+    // The total stake is counted in the smallest unit of SUI, so the thresholds are computed in
+    // u128 for 2 N not to overflow.
+
     pub fn quorum_threshold(&self) -> StakeUnit {
         // If N = 3f + 1 + k (0 <= k < 3)
         // then (2 N + 3) / 3 = 2f + 1 + (2k + 2)/3 = 2f + 1 + k = N - f
-        2 * self.total_votes / 3 + 1
+        (2 * self.total_votes as u128 / 3 + 1) as StakeUnit
     }
 
     pub fn validity_threshold(&self) -> StakeUnit {
         // If N = 3f + 1 + k (0 <= k < 3)
         // then (N + 2) / 3 = f + 1 + k/3 = f + 1
-        (self.total_votes + 2) / 3
+        ((self.total_votes as u128 + 2) / 3) as StakeUnit
     }
 
     /// Given a sequence of (AuthorityName, value) for values, provide the
@@ -117,6 +196,7 @@ impl PartialEq for Committee {
     fn eq(&self, other: &Self) -> bool {
         self.epoch == other.epoch
             && self.voting_rights == other.voting_rights
+            && self.protocol_keys == other.protocol_keys
             && self.total_votes == other.total_votes
     }
 }
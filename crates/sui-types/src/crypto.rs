@@ -1,10 +1,19 @@
 // Copyright (c) 2022, Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 use crate::base_types::{AuthorityName, SuiAddress};
-use crate::committee::EpochId;
+use crate::bls::derive_protocol_secret_key;
+pub use crate::bls::{
+    AggregateAuthoritySignature, AggregateVerificationObligation, AuthoritySignature,
+};
+use crate::committee::{Committee, EpochId, StakeUnit};
+use crate::ecdsa::{Secp256k1KeyPair, Secp256k1Signature, Secp256r1KeyPair, Secp256r1Signature};
 use crate::error::{SuiError, SuiResult};
+use crate::fp_ensure;
+use crate::intent::{intent_message, IntentScoped};
+use crate::multisig::MultiSig;
 use crate::sui_serde::Base64;
 use crate::sui_serde::Readable;
+use crate::zk_login::ZkLoginAuthenticator;
 use anyhow::anyhow;
 use anyhow::Error;
 use base64ct::Encoding;
@@ -19,7 +28,7 @@ use serde::{Deserialize, Serialize};
 use serde_with::serde_as;
 use serde_with::Bytes;
 use sha3::Sha3_256;
-use std::borrow::Borrow;
+use signature::Signer;
 use std::collections::HashMap;
 use std::hash::{Hash, Hasher};
 use std::str::FromStr;
@@ -29,6 +38,7 @@ use std::str::FromStr;
 pub struct KeyPair {
     key_pair: DalekKeypair,
     public_key_cell: OnceCell<PublicKeyBytes>,
+    protocol_key_cell: OnceCell<blst::min_sig::SecretKey>,
 }
 
 impl KeyPair {
@@ -39,6 +49,12 @@ impl KeyPair {
         })
     }
 
+    /// The secret key with which the validator of this key pair signs protocol messages.
+    pub(crate) fn protocol_secret_key(&self) -> &blst::min_sig::SecretKey {
+        self.protocol_key_cell
+            .get_or_init(|| derive_protocol_secret_key(self.key_pair.secret.as_bytes()))
+    }
+
     // TODO: eradicate unneeded uses (i.e. most)
     /// Avoid implementing `clone` on secret keys to prevent mistakes.
     #[must_use]
@@ -49,6 +65,7 @@ impl KeyPair {
                 public: dalek::PublicKey::from_bytes(self.public_key_bytes().as_ref()).unwrap(),
             },
             public_key_cell: OnceCell::new(),
+            protocol_key_cell: OnceCell::new(),
         }
     }
 
@@ -67,6 +84,7 @@ impl From<DalekKeypair> for KeyPair {
         Self {
             key_pair: dalek_keypair,
             public_key_cell: OnceCell::new(),
+            protocol_key_cell: OnceCell::new(),
         }
     }
 }
@@ -93,6 +111,7 @@ impl<'de> Deserialize<'de> for KeyPair {
         Ok(KeyPair {
             key_pair: key,
             public_key_cell: OnceCell::new(),
+            protocol_key_cell: OnceCell::new(),
         })
     }
 }
@@ -106,6 +125,7 @@ impl FromStr for KeyPair {
         Ok(KeyPair {
             key_pair: key,
             public_key_cell: OnceCell::new(),
+            protocol_key_cell: OnceCell::new(),
         })
     }
 }
@@ -121,13 +141,6 @@ impl signature::Signer<Signature> for KeyPair {
     }
 }
 
-impl signature::Signer<AuthoritySignature> for KeyPair {
-    fn try_sign(&self, msg: &[u8]) -> Result<AuthoritySignature, signature::Error> {
-        let sig = self.key_pair.try_sign(msg)?;
-        Ok(AuthoritySignature(sig))
-    }
-}
-
 #[serde_as]
 #[derive(
     Eq, Default, PartialEq, Ord, PartialOrd, Copy, Clone, Hash, Serialize, Deserialize, JsonSchema,
@@ -215,6 +228,7 @@ where
     let keypair = KeyPair {
         key_pair: kp,
         public_key_cell: OnceCell::new(),
+        protocol_key_cell: OnceCell::new(),
     };
     (SuiAddress::from(keypair.public_key_bytes()), keypair)
 }
@@ -224,10 +238,197 @@ pub fn get_key_pair_from_bytes(bytes: &[u8]) -> (SuiAddress, KeyPair) {
     let keypair = KeyPair {
         key_pair: DalekKeypair::from_bytes(bytes).unwrap(),
         public_key_cell: OnceCell::new(),
+        protocol_key_cell: OnceCell::new(),
     };
     (SuiAddress::from(keypair.public_key_bytes()), keypair)
 }
 
+/// The signature schemes of the keys of Sui accounts. The flag of a scheme is hashed with the
+/// public key into the address of the account, except for ed25519 whose addresses predate the
+/// other schemes, and prefixes its serialized signatures.
+#[derive(Eq, PartialEq, Copy, Clone, Debug, Serialize, Deserialize, JsonSchema)]
+pub enum SignatureScheme {
+    ED25519,
+    Secp256k1,
+    Secp256r1,
+    MultiSig,
+    ZkLogin,
+}
+
+impl SignatureScheme {
+    pub fn flag(&self) -> u8 {
+        match self {
+            SignatureScheme::ED25519 => 0x00,
+            SignatureScheme::Secp256k1 => 0x01,
+            SignatureScheme::Secp256r1 => 0x02,
+            SignatureScheme::MultiSig => 0x03,
+            SignatureScheme::ZkLogin => 0x04,
+        }
+    }
+
+    pub fn from_flag(flag: u8) -> SuiResult<SignatureScheme> {
+        match flag {
+            0x00 => Ok(SignatureScheme::ED25519),
+            0x01 => Ok(SignatureScheme::Secp256k1),
+            0x02 => Ok(SignatureScheme::Secp256r1),
+            0x03 => Ok(SignatureScheme::MultiSig),
+            0x04 => Ok(SignatureScheme::ZkLogin),
+            _ => Err(SuiError::InvalidSignature {
+                error: format!("Unknown signature scheme flag {flag}"),
+            }),
+        }
+    }
+}
+
+impl FromStr for SignatureScheme {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "ed25519" => Ok(SignatureScheme::ED25519),
+            "secp256k1" => Ok(SignatureScheme::Secp256k1),
+            "secp256r1" => Ok(SignatureScheme::Secp256r1),
+            _ => Err(anyhow!(
+                "Unsupported key scheme {s}, use ed25519, secp256k1 or secp256r1"
+            )),
+        }
+    }
+}
+
+/// A key pair of any of the signature schemes of single key accounts.
+#[derive(Debug)]
+pub enum SuiKeyPair {
+    Ed25519(KeyPair),
+    Secp256k1(Secp256k1KeyPair),
+    Secp256r1(Secp256r1KeyPair),
+}
+
+impl SuiKeyPair {
+    pub fn generate(scheme: SignatureScheme) -> Result<SuiKeyPair, anyhow::Error> {
+        Ok(match scheme {
+            SignatureScheme::ED25519 => SuiKeyPair::Ed25519(get_key_pair().1),
+            SignatureScheme::Secp256k1 => {
+                SuiKeyPair::Secp256k1(Secp256k1KeyPair::generate(&mut OsRng))
+            }
+            SignatureScheme::Secp256r1 => {
+                SuiKeyPair::Secp256r1(Secp256r1KeyPair::generate(&mut OsRng))
+            }
+            SignatureScheme::MultiSig => {
+                return Err(anyhow!("A multisig has no key pair of its own"));
+            }
+            SignatureScheme::ZkLogin => {
+                return Err(anyhow!(
+                    "A zkLogin account is controlled by an OpenID provider"
+                ));
+            }
+        })
+    }
+
+    pub fn scheme(&self) -> SignatureScheme {
+        match self {
+            SuiKeyPair::Ed25519(_) => SignatureScheme::ED25519,
+            SuiKeyPair::Secp256k1(_) => SignatureScheme::Secp256k1,
+            SuiKeyPair::Secp256r1(_) => SignatureScheme::Secp256r1,
+        }
+    }
+
+    pub fn public_key_bytes(&self) -> Vec<u8> {
+        match self {
+            SuiKeyPair::Ed25519(key) => key.public_key_bytes().to_vec(),
+            SuiKeyPair::Secp256k1(key) => key.public_key_bytes().to_vec(),
+            SuiKeyPair::Secp256r1(key) => key.public_key_bytes().to_vec(),
+        }
+    }
+
+    pub fn address(&self) -> SuiAddress {
+        match self {
+            SuiKeyPair::Ed25519(key) => SuiAddress::from(key.public_key_bytes()),
+            SuiKeyPair::Secp256k1(key) => key.address(),
+            SuiKeyPair::Secp256r1(key) => key.address(),
+        }
+    }
+
+    pub fn sign(&self, msg: &[u8]) -> Result<GenericSignature, signature::Error> {
+        Ok(match self {
+            SuiKeyPair::Ed25519(key) => GenericSignature::Signature(key.try_sign(msg)?),
+            SuiKeyPair::Secp256k1(key) => GenericSignature::Secp256k1(key.try_sign(msg)?),
+            SuiKeyPair::Secp256r1(key) => GenericSignature::Secp256r1(key.try_sign(msg)?),
+        })
+    }
+
+    /// Avoid implementing `clone` on secret keys to prevent mistakes.
+    #[must_use]
+    pub fn copy(&self) -> SuiKeyPair {
+        match self {
+            SuiKeyPair::Ed25519(key) => SuiKeyPair::Ed25519(key.copy()),
+            SuiKeyPair::Secp256k1(key) => SuiKeyPair::Secp256k1(key.copy()),
+            SuiKeyPair::Secp256r1(key) => SuiKeyPair::Secp256r1(key.copy()),
+        }
+    }
+}
+
+impl From<KeyPair> for SuiKeyPair {
+    fn from(key: KeyPair) -> Self {
+        SuiKeyPair::Ed25519(key)
+    }
+}
+
+// An ed25519 key pair is serialized as before other schemes existed, so that keystores stay
+// readable by older wallets. The secret key of the other schemes is prefixed with their flag.
+impl Serialize for SuiKeyPair {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::ser::Serializer,
+    {
+        let bytes = match self {
+            SuiKeyPair::Ed25519(key) => return key.serialize(serializer),
+            SuiKeyPair::Secp256k1(key) => [&[self.scheme().flag()][..], &key.to_bytes()].concat(),
+            SuiKeyPair::Secp256r1(key) => [&[self.scheme().flag()][..], &key.to_bytes()].concat(),
+        };
+        serializer.serialize_str(&base64ct::Base64::encode_string(&bytes))
+    }
+}
+
+impl<'de> Deserialize<'de> for SuiKeyPair {
+    fn deserialize<D>(deserializer: D) -> Result<SuiKeyPair, D::Error>
+    where
+        D: serde::de::Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        let value = base64ct::Base64::decode_vec(&s)
+            .map_err(|err| serde::de::Error::custom(err.to_string()))?;
+        if value.len() == dalek::KEYPAIR_LENGTH {
+            let key = DalekKeypair::from_bytes(&value)
+                .map_err(|err| serde::de::Error::custom(err.to_string()))?;
+            return Ok(SuiKeyPair::Ed25519(key.into()));
+        }
+        let (flag, secret) = value
+            .split_first()
+            .ok_or_else(|| serde::de::Error::custom("Empty key pair"))?;
+        let scheme = SignatureScheme::from_flag(*flag).map_err(serde::de::Error::custom)?;
+        let key = match scheme {
+            SignatureScheme::Secp256k1 => Secp256k1KeyPair::from_bytes(secret).map(Into::into),
+            SignatureScheme::Secp256r1 => Secp256r1KeyPair::from_bytes(secret).map(Into::into),
+            _ => Err(SuiError::SignatureKeyGenError(format!(
+                "Unexpected key pair of scheme {scheme:?}"
+            ))),
+        };
+        key.map_err(serde::de::Error::custom)
+    }
+}
+
+impl From<Secp256k1KeyPair> for SuiKeyPair {
+    fn from(key: Secp256k1KeyPair) -> Self {
+        SuiKeyPair::Secp256k1(key)
+    }
+}
+
+impl From<Secp256r1KeyPair> for SuiKeyPair {
+    fn from(key: Secp256r1KeyPair) -> Self {
+        SuiKeyPair::Secp256r1(key)
+    }
+}
+
 // TODO: replace this with a byte interpretation based on multicodec
 pub const SUI_SIGNATURE_LENGTH: usize =
     ed25519_dalek::PUBLIC_KEY_LENGTH + ed25519_dalek::SIGNATURE_LENGTH;
@@ -266,10 +467,9 @@ impl std::fmt::Debug for Signature {
 impl Signature {
     pub fn new<T>(value: &T, secret: &dyn signature::Signer<Signature>) -> Self
     where
-        T: Signable<Vec<u8>>,
+        T: Signable<Vec<u8>> + IntentScoped,
     {
-        let mut message = Vec::new();
-        value.write(&mut message);
+        let message = intent_message(value);
         secret.sign(&message)
     }
 
@@ -289,7 +489,7 @@ impl Signature {
     /// that the signature was performed with a PublicKey belonging to an expected author, indicated by its Sui Address
     pub fn verify<T>(&self, value: &T, author: SuiAddress) -> Result<(), SuiError>
     where
-        T: Signable<Vec<u8>>,
+        T: Signable<Vec<u8>> + IntentScoped,
     {
         let (message, signature, public_key_bytes) = self.get_verification_inputs(value, author)?;
 
@@ -316,7 +516,7 @@ impl Signature {
         author: SuiAddress,
     ) -> Result<(Vec<u8>, ed25519_dalek::Signature, PublicKeyBytes), SuiError>
     where
-        T: Signable<Vec<u8>>,
+        T: Signable<Vec<u8>> + IntentScoped,
     {
         // Is this signature emitted by the expected author?
         let public_key_bytes: [u8; ed25519_dalek::PUBLIC_KEY_LENGTH] = self
@@ -338,112 +538,202 @@ impl Signature {
                 }
             })?;
 
-        // serialize the message (see BCS serialization for determinism)
-        let mut message = Vec::new();
-        value.write(&mut message);
+        // serialize the message after its intent (see BCS serialization for determinism)
+        let message = intent_message(value);
 
         Ok((message, signature, PublicKeyBytes(public_key_bytes)))
     }
 }
 
-/// A signature emitted by an authority. It's useful to decouple this from user signatures,
-/// as their set of supported schemes will probably diverge
-#[serde_as]
-#[derive(Debug, Eq, PartialEq, Copy, Clone, Serialize, Deserialize, JsonSchema)]
-pub struct AuthoritySignature(
-    #[schemars(with = "Base64")]
-    #[serde_as(as = "Readable<Base64, _>")]
-    pub dalek::Signature,
-);
-impl AsRef<[u8]> for AuthoritySignature {
-    fn as_ref(&self) -> &[u8] {
-        self.0.as_ref()
+/// The authenticator of a transaction: a signature by the single key of the sender, a
+/// `MultiSig` by enough of the keys of a multisig sender, or a `ZkLoginAuthenticator` of a
+/// sender who logged in with an OpenID provider. The variants are in the order of the flags of
+/// their schemes.
+#[derive(Eq, PartialEq, Clone, Debug, Serialize, Deserialize, JsonSchema)]
+pub enum GenericSignature {
+    /// An ed25519 signature.
+    Signature(Signature),
+    Secp256k1(Secp256k1Signature),
+    Secp256r1(Secp256r1Signature),
+    MultiSig(MultiSig),
+    ZkLogin(ZkLoginAuthenticator),
+}
+
+impl GenericSignature {
+    pub fn scheme(&self) -> SignatureScheme {
+        match self {
+            GenericSignature::Signature(_) => SignatureScheme::ED25519,
+            GenericSignature::Secp256k1(_) => SignatureScheme::Secp256k1,
+            GenericSignature::Secp256r1(_) => SignatureScheme::Secp256r1,
+            GenericSignature::MultiSig(_) => SignatureScheme::MultiSig,
+            GenericSignature::ZkLogin(_) => SignatureScheme::ZkLogin,
+        }
     }
-}
 
-impl signature::Signature for AuthoritySignature {
-    fn from_bytes(bytes: &[u8]) -> Result<Self, signature::Error> {
-        let sig = dalek::Signature::from_bytes(bytes)?;
-        Ok(AuthoritySignature(sig))
+    /// The bytes of an ed25519 signature are the signature followed by the public key, as
+    /// before other schemes existed. The bytes of the other schemes start with their flag.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        match self {
+            GenericSignature::Signature(signature) => signature.as_ref().to_vec(),
+            GenericSignature::Secp256k1(signature) => signature.as_ref().to_vec(),
+            GenericSignature::Secp256r1(signature) => signature.as_ref().to_vec(),
+            GenericSignature::MultiSig(multisig) => {
+                let mut bytes = vec![SignatureScheme::MultiSig.flag()];
+                bytes.extend(bcs::to_bytes(multisig).expect("Serialization should not fail"));
+                bytes
+            }
+            GenericSignature::ZkLogin(authenticator) => authenticator.to_bytes(),
+        }
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> SuiResult<GenericSignature> {
+        let invalid = |error: String| SuiError::InvalidSignature { error };
+        if bytes.len() == SUI_SIGNATURE_LENGTH {
+            return Ok(<Signature as signature::Signature>::from_bytes(bytes)
+                .map_err(|err| invalid(err.to_string()))?
+                .into());
+        }
+        let flag = *bytes
+            .first()
+            .ok_or_else(|| invalid("Empty signature".to_string()))?;
+        Ok(match SignatureScheme::from_flag(flag)? {
+            SignatureScheme::Secp256k1 => {
+                <Secp256k1Signature as signature::Signature>::from_bytes(bytes)
+                    .map_err(|err| invalid(err.to_string()))?
+                    .into()
+            }
+            SignatureScheme::Secp256r1 => {
+                <Secp256r1Signature as signature::Signature>::from_bytes(bytes)
+                    .map_err(|err| invalid(err.to_string()))?
+                    .into()
+            }
+            SignatureScheme::MultiSig => bcs::from_bytes::<MultiSig>(&bytes[1..])
+                .map_err(|err| invalid(err.to_string()))?
+                .into(),
+            SignatureScheme::ZkLogin => bcs::from_bytes::<ZkLoginAuthenticator>(&bytes[1..])
+                .map_err(|err| invalid(err.to_string()))?
+                .into(),
+            SignatureScheme::ED25519 => {
+                return Err(invalid(format!(
+                    "An ed25519 signature must be {SUI_SIGNATURE_LENGTH} bytes, got {}",
+                    bytes.len()
+                )))
+            }
+        })
+    }
+
+    /// Build the signature of a single key account from its signature and public key, as
+    /// separately submitted to the `executeTransaction` JSON-RPC method.
+    pub fn from_parts(
+        scheme: SignatureScheme,
+        signature: &[u8],
+        public_key: &[u8],
+    ) -> SuiResult<GenericSignature> {
+        match scheme {
+            SignatureScheme::ED25519 => Self::from_bytes(&[signature, public_key].concat()),
+            SignatureScheme::Secp256k1 | SignatureScheme::Secp256r1 => {
+                Self::from_bytes(&[&[scheme.flag()][..], signature, public_key].concat())
+            }
+            SignatureScheme::MultiSig => Err(SuiError::InvalidSignature {
+                error: "A multisig is made of several signatures and public keys".to_string(),
+            }),
+            SignatureScheme::ZkLogin => Err(SuiError::InvalidSignature {
+                error: "A zkLogin signature is made of a proof and an ephemeral signature"
+                    .to_string(),
+            }),
+        }
     }
-}
 
-impl AuthoritySignature {
-    /// Signs with the provided Signer
-    ///
-    pub fn new<T>(value: &T, secret: &dyn signature::Signer<AuthoritySignature>) -> Self
+    /// Check that the signature is by `author`, and add it to `obligation` to be verified in
+    /// the batch.
+    pub fn add_to_verification_obligation<T>(
+        &self,
+        value: &T,
+        author: SuiAddress,
+        obligation: &mut VerificationObligation,
+    ) -> SuiResult<()>
     where
-        T: Signable<Vec<u8>>,
+        T: Signable<Vec<u8>> + IntentScoped,
     {
-        let mut message = Vec::new();
-        value.write(&mut message);
-        secret.sign(&message)
+        match self {
+            GenericSignature::Signature(signature) => {
+                let (message, signature, public_key) =
+                    signature.get_verification_inputs(value, author)?;
+                let idx = obligation.messages.len();
+                obligation.messages.push(message);
+                let key = obligation.lookup_public_key(&public_key)?;
+                obligation.public_keys.push(key);
+                obligation.signatures.push(signature);
+                obligation.message_index.push(idx);
+                Ok(())
+            }
+            // ECDSA signatures can't be batched with ed25519 ones, so they are checked now.
+            GenericSignature::Secp256k1(signature) => signature.verify(value, author),
+            GenericSignature::Secp256r1(signature) => signature.verify(value, author),
+            GenericSignature::MultiSig(multisig) => {
+                multisig.add_to_verification_obligation(value, author, obligation)
+            }
+            // The proof needs the JWKs of the current epoch, so only the ephemeral signature is
+            // checked here. Validators check the proof before signing the transaction.
+            GenericSignature::ZkLogin(authenticator) => {
+                authenticator.verify_ephemeral_signature(value, author)
+            }
+        }
     }
 
-    /// Signature verification for a single signature
-    pub fn verify<T>(&self, value: &T, author: PublicKeyBytes) -> Result<(), SuiError>
+    pub fn verify<T>(&self, value: &T, author: SuiAddress) -> SuiResult<()>
     where
-        T: Signable<Vec<u8>>,
+        T: Signable<Vec<u8>> + IntentScoped,
     {
-        // is this a cryptographically valid public Key?
-        let public_key: dalek::PublicKey = author.try_into()?;
+        let mut obligation = VerificationObligation::default();
+        self.add_to_verification_obligation(value, author, &mut obligation)?;
+        obligation.verify_all().map(|_| ())
+    }
+}
 
-        // access the signature
-        let signature = self.0;
+impl From<Signature> for GenericSignature {
+    fn from(signature: Signature) -> Self {
+        GenericSignature::Signature(signature)
+    }
+}
 
-        // serialize the message (see BCS serialization for determinism)
-        let mut message = Vec::new();
-        value.write(&mut message);
+impl From<MultiSig> for GenericSignature {
+    fn from(multisig: MultiSig) -> Self {
+        GenericSignature::MultiSig(multisig)
+    }
+}
 
-        // perform cryptographic signature check
-        public_key
-            .verify(&message, &signature)
-            .map_err(|error| SuiError::InvalidSignature {
-                error: error.to_string(),
-            })
+impl From<ZkLoginAuthenticator> for GenericSignature {
+    fn from(authenticator: ZkLoginAuthenticator) -> Self {
+        GenericSignature::ZkLogin(authenticator)
     }
+}
 
-    /// This performs signature verification for a batch of signatures, equipped with a trusted cache of deserialized Public Keys that
-    /// represent their provided authors.
-    ///
-    /// We check that the provided signatures are valid w.r.t the provided public keys, but do not match them to any Sui Addresses.
-    pub fn verify_batch<T, I, K>(
-        value: &T,
-        votes: I,
-        key_cache: &HashMap<PublicKeyBytes, dalek::PublicKey>,
-    ) -> Result<(), SuiError>
-    where
-        T: Signable<Vec<u8>>,
-        I: IntoIterator<Item = K>,
-        K: Borrow<(PublicKeyBytes, AuthoritySignature)>,
-    {
-        let mut msg = Vec::new();
-        value.write(&mut msg);
-        let mut messages: Vec<&[u8]> = Vec::new();
-        let mut signatures: Vec<dalek::Signature> = Vec::new();
-        let mut public_keys: Vec<dalek::PublicKey> = Vec::new();
-        for tuple in votes.into_iter() {
-            let (authority, signature) = tuple.borrow();
-            // do we know, or can we build a valid public key?
-            match key_cache.get(authority) {
-                Some(v) => public_keys.push(*v),
-                None => {
-                    let public_key = (*authority).try_into()?;
-                    public_keys.push(public_key);
-                }
-            }
+impl From<Secp256k1Signature> for GenericSignature {
+    fn from(signature: Secp256k1Signature) -> Self {
+        GenericSignature::Secp256k1(signature)
+    }
+}
 
-            // build a signature
-            signatures.push(signature.0);
+impl From<Secp256r1Signature> for GenericSignature {
+    fn from(signature: Secp256r1Signature) -> Self {
+        GenericSignature::Secp256r1(signature)
+    }
+}
 
-            // collect the message
-            messages.push(&msg);
+impl TryFrom<GenericSignature> for Signature {
+    type Error = SuiError;
+
+    fn try_from(signature: GenericSignature) -> Result<Self, SuiError> {
+        match signature {
+            GenericSignature::Signature(signature) => Ok(signature),
+            signature => Err(SuiError::InvalidSignature {
+                error: format!(
+                    "Expected an ed25519 signature, got {:?}",
+                    signature.scheme()
+                ),
+            }),
         }
-        dalek::verify_batch(&messages[..], &signatures[..], &public_keys[..]).map_err(|error| {
-            SuiError::InvalidSignature {
-                error: format!("{error}"),
-            }
-        })
     }
 }
 
@@ -481,11 +771,105 @@ impl PartialEq for AuthoritySignInfo {
     }
 }
 
-/// Represents at least a quorum (could be more) of authority signatures.
+/// Represents at least a quorum (could be more) of authority signatures, aggregated into a
+/// single signature.
 #[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
 pub struct AuthorityQuorumSignInfo {
     pub epoch: EpochId,
-    pub signatures: Vec<(AuthorityName, AuthoritySignature)>,
+    /// The signers, as a bitmap over the authorities of the committee of the epoch, in the
+    /// order of their names: bit `i % 8` of byte `i / 8` is set if the `i`-th authority signed.
+    pub signers_map: Vec<u8>,
+    pub signature: AggregateAuthoritySignature,
+}
+
+impl AuthorityQuorumSignInfo {
+    /// Aggregate the `signatures` of the same value by authorities of `committee`.
+    pub fn new(
+        signatures: &[(AuthorityName, AuthoritySignature)],
+        committee: &Committee,
+    ) -> SuiResult<Self> {
+        let mut signers_map = vec![0u8; (committee.voting_rights.len() + 7) / 8];
+        for (authority, _) in signatures {
+            let index = committee
+                .authority_index(authority)
+                .ok_or(SuiError::UnknownSigner)?;
+            // Check that each authority only appears once.
+            fp_ensure!(
+                signers_map[index / 8] & (1 << (index % 8)) == 0,
+                SuiError::CertificateAuthorityReuse
+            );
+            signers_map[index / 8] |= 1 << (index % 8);
+        }
+        let signature = AggregateAuthoritySignature::aggregate(
+            signatures.iter().map(|(_, signature)| signature),
+        )?;
+        Ok(Self {
+            epoch: committee.epoch(),
+            signers_map,
+            signature,
+        })
+    }
+
+    /// The authorities which signed, in the order of `committee`.
+    pub fn authorities<'a>(&self, committee: &'a Committee) -> SuiResult<Vec<&'a AuthorityName>> {
+        fp_ensure!(
+            self.signers_map.len() == (committee.voting_rights.len() + 7) / 8,
+            SuiError::InvalidSignature {
+                error: "The map of signers does not match the committee".to_string(),
+            }
+        );
+        let mut authorities = Vec::new();
+        let mut names = committee.voting_rights.keys();
+        for index in 0..self.signers_map.len() * 8 {
+            let name = names.next();
+            if self.signers_map[index / 8] & (1 << (index % 8)) == 0 {
+                continue;
+            }
+            authorities.push(name.ok_or(SuiError::UnknownSigner)?);
+        }
+        Ok(authorities)
+    }
+
+    /// Check that authorities with at least `threshold` stake in `committee` signed `value`.
+    pub fn verify<T>(&self, value: &T, committee: &Committee, threshold: StakeUnit) -> SuiResult
+    where
+        T: Signable<Vec<u8>> + IntentScoped,
+    {
+        let mut obligation = AggregateVerificationObligation::default();
+        self.add_to_verification_obligation(value, committee, threshold, &mut obligation)?;
+        obligation.verify_all()
+    }
+
+    /// Check that the signers hold at least `threshold` stake in `committee`, and add the check of
+    /// their aggregate signature of `value` to `obligation`.
+    pub fn add_to_verification_obligation<T>(
+        &self,
+        value: &T,
+        committee: &Committee,
+        threshold: StakeUnit,
+        obligation: &mut AggregateVerificationObligation,
+    ) -> SuiResult
+    where
+        T: Signable<Vec<u8>> + IntentScoped,
+    {
+        fp_ensure!(
+            self.epoch == committee.epoch(),
+            SuiError::WrongEpoch {
+                expected_epoch: committee.epoch()
+            }
+        );
+        let authorities = self.authorities(committee)?;
+        let mut keys = Vec::with_capacity(authorities.len());
+        for authority in &authorities {
+            fp_ensure!(committee.weight(authority) > 0, SuiError::UnknownSigner);
+            keys.push(committee.protocol_key(authority)?);
+        }
+        fp_ensure!(
+            committee.weight_of(authorities) >= threshold,
+            SuiError::CertificateRequiresQuorum
+        );
+        obligation.add(value, &self.signature, &keys.iter().collect::<Vec<_>>())
+    }
 }
 // Note: if you meet an error due to this line it may be because you need an Eq implementation for `CertifiedTransaction`,
 // or one of the structs that include it, i.e. `ConfirmationTransaction`, `TransactionInfoResponse` or `ObjectInfoResponse`.
@@ -508,7 +892,8 @@ mod private {
     impl SealedAuthoritySignInfoTrait for super::AuthorityQuorumSignInfo {}
 }
 
-/// Something that we know how to hash and sign.
+/// Something that we know how to hash and sign. Signatures are over the bytes written after
+/// the intent of the value, see `intent_message`, while digests are over the bytes alone.
 pub trait Signable<W> {
     fn write(&self, writer: &mut W);
 }
@@ -564,6 +949,8 @@ pub struct VerificationObligation {
     pub message_index: Vec<usize>,
     pub signatures: Vec<dalek::Signature>,
     pub public_keys: Vec<dalek::PublicKey>,
+    /// The aggregate signatures of authorities, which are checked in a batch of their own.
+    pub aggregates: AggregateVerificationObligation,
 }
 
 impl VerificationObligation {
@@ -589,19 +976,22 @@ impl VerificationObligation {
     }
 
     pub fn verify_all(self) -> SuiResult<PubKeyLookup> {
-        let messages_inner: Vec<_> = self
-            .message_index
-            .iter()
-            .map(|idx| &self.messages[*idx][..])
-            .collect();
-        dalek::verify_batch(
-            &messages_inner[..],
-            &self.signatures[..],
-            &self.public_keys[..],
-        )
-        .map_err(|error| SuiError::InvalidSignature {
-            error: format!("{error}"),
-        })?;
+        if !self.signatures.is_empty() {
+            let messages_inner: Vec<_> = self
+                .message_index
+                .iter()
+                .map(|idx| &self.messages[*idx][..])
+                .collect();
+            dalek::verify_batch(
+                &messages_inner[..],
+                &self.signatures[..],
+                &self.public_keys[..],
+            )
+            .map_err(|error| SuiError::InvalidSignature {
+                error: format!("{error}"),
+            })?;
+        }
+        self.aggregates.verify_all()?;
 
         Ok(self.lookup)
     }
@@ -0,0 +1,84 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use move_core_types::{
+    ident_str,
+    identifier::IdentStr,
+    language_storage::{StructTag, TypeTag},
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    base_types::{ObjectID, SuiAddress},
+    coin::{COIN_MODULE_NAME, COIN_STRUCT_NAME},
+    id::VersionedID,
+    object::Object,
+    SUI_FRAMEWORK_ADDRESS,
+};
+
+pub const DENY_LIST_MODULE_NAME: &IdentStr = ident_str!("DenyList");
+pub const DENY_LIST_STRUCT_NAME: &IdentStr = DENY_LIST_MODULE_NAME;
+
+/// Rust version of the Move Sui::DenyList::DenyList type
+#[derive(Debug, Serialize, Deserialize, Clone, Eq, PartialEq)]
+pub struct DenyList {
+    pub id: VersionedID,
+    pub denied: Vec<SuiAddress>,
+}
+
+impl DenyList {
+    pub fn type_(coin_type: TypeTag) -> StructTag {
+        StructTag {
+            address: SUI_FRAMEWORK_ADDRESS,
+            name: DENY_LIST_STRUCT_NAME.to_owned(),
+            module: DENY_LIST_MODULE_NAME.to_owned(),
+            type_params: vec![coin_type],
+        }
+    }
+
+    pub fn id(&self) -> &ObjectID {
+        self.id.object_id()
+    }
+
+    pub fn contains(&self, address: &SuiAddress) -> bool {
+        self.denied.contains(address)
+    }
+
+    /// If `object` is a `DenyList<T>`, returns `T`.
+    pub fn coin_type_of(object: &Object) -> Option<&TypeTag> {
+        object
+            .type_()
+            .and_then(|t| Self::type_param(t, DENY_LIST_MODULE_NAME, DENY_LIST_STRUCT_NAME))
+    }
+
+    /// If `object` is a `Coin<T>`, returns `T`.
+    pub fn coin_type_of_coin(object: &Object) -> Option<&TypeTag> {
+        object
+            .type_()
+            .and_then(|t| Self::type_param(t, COIN_MODULE_NAME, COIN_STRUCT_NAME))
+    }
+
+    fn type_param<'a>(
+        tag: &'a StructTag,
+        module: &IdentStr,
+        name: &IdentStr,
+    ) -> Option<&'a TypeTag> {
+        if tag.address == SUI_FRAMEWORK_ADDRESS
+            && tag.module.as_ident_str() == module
+            && tag.name.as_ident_str() == name
+            && tag.type_params.len() == 1
+        {
+            tag.type_params.first()
+        } else {
+            None
+        }
+    }
+
+    pub fn try_from_object(object: &Object) -> Option<Self> {
+        let move_object = object.data.try_as_move()?;
+        if Self::coin_type_of(object).is_none() {
+            return None;
+        }
+        bcs::from_bytes(move_object.contents()).ok()
+    }
+}
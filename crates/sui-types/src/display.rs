@@ -0,0 +1,64 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use move_core_types::{
+    ident_str,
+    identifier::IdentStr,
+    language_storage::{StructTag, TypeTag},
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{base_types::ObjectID, id::VersionedID, object::Object, SUI_FRAMEWORK_ADDRESS};
+
+pub const DISPLAY_MODULE_NAME: &IdentStr = ident_str!("Display");
+pub const DISPLAY_STRUCT_NAME: &IdentStr = DISPLAY_MODULE_NAME;
+
+/// Rust version of the Move Sui::Display::Field type
+#[derive(Debug, Serialize, Deserialize, Clone, Eq, PartialEq)]
+pub struct DisplayField {
+    pub name: String,
+    pub template: String,
+}
+
+/// Rust version of the Move Sui::Display::Display type
+#[derive(Debug, Serialize, Deserialize, Clone, Eq, PartialEq)]
+pub struct Display {
+    pub id: VersionedID,
+    pub fields: Vec<DisplayField>,
+}
+
+impl Display {
+    pub fn type_(displayed_type: StructTag) -> StructTag {
+        StructTag {
+            address: SUI_FRAMEWORK_ADDRESS,
+            name: DISPLAY_STRUCT_NAME.to_owned(),
+            module: DISPLAY_MODULE_NAME.to_owned(),
+            type_params: vec![TypeTag::Struct(displayed_type)],
+        }
+    }
+
+    pub fn id(&self) -> &ObjectID {
+        self.id.object_id()
+    }
+
+    /// If `object` is a `Display<T>`, returns `T`.
+    pub fn displayed_type_of(object: &Object) -> Option<&StructTag> {
+        let tag = object.type_()?;
+        if tag.address != SUI_FRAMEWORK_ADDRESS
+            || tag.module.as_ident_str() != DISPLAY_MODULE_NAME
+            || tag.name.as_ident_str() != DISPLAY_STRUCT_NAME
+        {
+            return None;
+        }
+        match tag.type_params.as_slice() {
+            [TypeTag::Struct(displayed_type)] => Some(displayed_type),
+            _ => None,
+        }
+    }
+
+    pub fn try_from_object(object: &Object) -> Option<Self> {
+        let move_object = object.data.try_as_move()?;
+        Self::displayed_type_of(object)?;
+        bcs::from_bytes(move_object.contents()).ok()
+    }
+}
@@ -0,0 +1,270 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! ECDSA keys and signatures over secp256k1 and secp256r1, for accounts whose keys are held by
+//! hardware wallets or HSMs which don't support ed25519. The messages are hashed with SHA-256
+//! before signing, as these devices expect. Unlike ed25519 signatures, these signatures are
+//! checked one at a time rather than in a batch.
+
+use base64ct::Encoding;
+use digest::Digest;
+use k256::elliptic_curve::ff::PrimeField;
+use k256::elliptic_curve::sec1::ToEncodedPoint;
+use rand::{CryptoRng, RngCore};
+use schemars::JsonSchema;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_with::{Bytes, DeserializeAs, SerializeAs};
+use sha3::Sha3_256;
+use signature::{Signer, Verifier};
+
+use crate::base_types::{SuiAddress, SUI_ADDRESS_LENGTH};
+use crate::crypto::{Signable, SignatureScheme};
+use crate::error::{SuiError, SuiResult};
+use crate::fp_ensure;
+use crate::intent::{intent_message, IntentScoped};
+use crate::sui_serde::{Base64, Readable};
+
+#[cfg(test)]
+#[path = "unit_tests/ecdsa_tests.rs"]
+mod ecdsa_tests;
+
+/// The length of a compact ECDSA signature, `r` followed by `s`.
+pub const ECDSA_SIGNATURE_LENGTH: usize = 64;
+/// The length of a compressed SEC1 public key.
+pub const ECDSA_PUBLIC_KEY_LENGTH: usize = 33;
+/// The length of a serialized ECDSA signature: the flag of the scheme, the signature and the
+/// public key of the signer.
+pub const ECDSA_SUI_SIGNATURE_LENGTH: usize = 1 + ECDSA_SIGNATURE_LENGTH + ECDSA_PUBLIC_KEY_LENGTH;
+
+const ECDSA_SECRET_KEY_LENGTH: usize = 32;
+
+/// The address of an ECDSA key is the hash of the flag of its scheme followed by the
+/// compressed key, so the same curve point can't be claimed by accounts of two schemes.
+fn address_from_public_key(scheme: SignatureScheme, public_key: &[u8]) -> SuiAddress {
+    let mut hasher = Sha3_256::default();
+    hasher.update([scheme.flag()]);
+    hasher.update(public_key);
+    let hash = hasher.finalize();
+    SuiAddress::try_from(&hash[..SUI_ADDRESS_LENGTH]).expect("byte lengths match")
+}
+
+macro_rules! ecdsa_scheme {
+    ($curve:ident, $scheme:expr, $key_pair:ident, $signature:ident) => {
+        pub struct $key_pair($curve::ecdsa::SigningKey);
+
+        impl $key_pair {
+            pub fn generate<R>(rng: &mut R) -> Self
+            where
+                R: CryptoRng + RngCore,
+            {
+                loop {
+                    let mut secret = [0u8; ECDSA_SECRET_KEY_LENGTH];
+                    rng.fill_bytes(&mut secret);
+                    // Only fails if the bytes are zero or above the order of the curve.
+                    if let Ok(key) = $curve::ecdsa::SigningKey::from_bytes(&secret) {
+                        return Self(key);
+                    }
+                }
+            }
+
+            pub fn from_bytes(secret: &[u8]) -> SuiResult<Self> {
+                $curve::ecdsa::SigningKey::from_bytes(secret)
+                    .map(Self)
+                    .map_err(|err| SuiError::SignatureKeyGenError(err.to_string()))
+            }
+
+            pub fn to_bytes(&self) -> [u8; ECDSA_SECRET_KEY_LENGTH] {
+                let mut bytes = [0u8; ECDSA_SECRET_KEY_LENGTH];
+                bytes.copy_from_slice(&self.0.to_bytes());
+                bytes
+            }
+
+            /// The compressed SEC1 encoding of the public key.
+            pub fn public_key_bytes(&self) -> [u8; ECDSA_PUBLIC_KEY_LENGTH] {
+                let mut bytes = [0u8; ECDSA_PUBLIC_KEY_LENGTH];
+                bytes.copy_from_slice(self.0.verifying_key().to_encoded_point(true).as_bytes());
+                bytes
+            }
+
+            pub fn address(&self) -> SuiAddress {
+                address_from_public_key($scheme, &self.public_key_bytes())
+            }
+
+            /// The key whose secret is this secret plus `tweak`, modulo the order of the curve,
+            /// as used to derive child keys. Rejects a tweak above the order, or a sum of zero.
+            pub(crate) fn add_tweak(
+                &self,
+                tweak: &[u8; ECDSA_SECRET_KEY_LENGTH],
+            ) -> SuiResult<Self> {
+                let scalar = |bytes: &[u8]| -> Option<$curve::Scalar> {
+                    $curve::Scalar::from_repr($curve::FieldBytes::clone_from_slice(bytes)).into()
+                };
+                let tweak = scalar(tweak).ok_or_else(|| {
+                    SuiError::SignatureKeyGenError(
+                        "The tweak is above the order of the curve".to_string(),
+                    )
+                })?;
+                let secret = scalar(&self.to_bytes()).expect("secret keys are valid scalars");
+                let sum = secret + tweak;
+                Self::from_bytes(&sum.to_repr())
+            }
+
+            /// Avoid implementing `clone` on secret keys to prevent mistakes.
+            #[must_use]
+            pub fn copy(&self) -> Self {
+                Self(self.0.clone())
+            }
+        }
+
+        impl std::fmt::Debug for $key_pair {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                write!(f, "{}({})", stringify!($key_pair), self.address())
+            }
+        }
+
+        impl signature::Signer<$signature> for $key_pair {
+            fn try_sign(&self, msg: &[u8]) -> Result<$signature, signature::Error> {
+                let signature: $curve::ecdsa::Signature = self.0.try_sign(msg)?;
+                let mut bytes = [0u8; ECDSA_SUI_SIGNATURE_LENGTH];
+                bytes[0] = $scheme.flag();
+                bytes[1..=ECDSA_SIGNATURE_LENGTH].copy_from_slice(signature.as_ref());
+                bytes[1 + ECDSA_SIGNATURE_LENGTH..].copy_from_slice(&self.public_key_bytes());
+                Ok($signature(bytes))
+            }
+        }
+
+        /// The flag of the scheme, followed by the signature and the public key of the signer.
+        #[derive(Eq, PartialEq, Copy, Clone, JsonSchema)]
+        pub struct $signature(#[schemars(with = "Base64")] [u8; ECDSA_SUI_SIGNATURE_LENGTH]);
+
+        impl $signature {
+            pub fn new<T>(value: &T, secret: &$key_pair) -> Self
+            where
+                T: Signable<Vec<u8>> + IntentScoped,
+            {
+                let message = intent_message(value);
+                secret.sign(&message)
+            }
+
+            pub fn signature_bytes(&self) -> &[u8] {
+                &self.0[1..=ECDSA_SIGNATURE_LENGTH]
+            }
+
+            pub fn public_key_bytes(&self) -> &[u8] {
+                &self.0[1 + ECDSA_SIGNATURE_LENGTH..]
+            }
+
+            /// Check the signature, and that it was made with a key which belongs to `author`.
+            pub fn verify<T>(&self, value: &T, author: SuiAddress) -> SuiResult<()>
+            where
+                T: Signable<Vec<u8>> + IntentScoped,
+            {
+                let received_addr = address_from_public_key($scheme, self.public_key_bytes());
+                fp_ensure!(
+                    received_addr == author,
+                    SuiError::IncorrectSigner {
+                        error: format!(
+                            "Signature verification failure. Author is {author}, received \
+                             address is {received_addr}"
+                        ),
+                    }
+                );
+                Self::verify_with_key(value, self.signature_bytes(), self.public_key_bytes())
+            }
+
+            /// Check a compact signature of `value` against a compressed public key, as held
+            /// by the members of a multisig.
+            pub(crate) fn verify_with_key<T>(
+                value: &T,
+                signature: &[u8],
+                public_key: &[u8],
+            ) -> SuiResult<()>
+            where
+                T: Signable<Vec<u8>> + IntentScoped,
+            {
+                let invalid = |err: signature::Error| SuiError::InvalidSignature {
+                    error: err.to_string(),
+                };
+                let public_key =
+                    $curve::ecdsa::VerifyingKey::from_sec1_bytes(public_key).map_err(invalid)?;
+                let signature = $curve::ecdsa::Signature::try_from(signature).map_err(invalid)?;
+
+                let message = intent_message(value);
+                public_key.verify(&message, &signature).map_err(invalid)
+            }
+
+            /// Check that `public_key` is the compressed encoding of a point of the curve.
+            pub(crate) fn check_public_key(public_key: &[u8]) -> SuiResult<()> {
+                $curve::ecdsa::VerifyingKey::from_sec1_bytes(public_key)
+                    .map(|_| ())
+                    .map_err(|err| SuiError::InvalidSignature {
+                        error: err.to_string(),
+                    })
+            }
+        }
+
+        impl AsRef<[u8]> for $signature {
+            fn as_ref(&self) -> &[u8] {
+                self.0.as_ref()
+            }
+        }
+
+        impl signature::Signature for $signature {
+            fn from_bytes(bytes: &[u8]) -> Result<Self, signature::Error> {
+                let bytes: [u8; ECDSA_SUI_SIGNATURE_LENGTH] =
+                    bytes.try_into().map_err(|_| signature::Error::new())?;
+                if bytes[0] != $scheme.flag() {
+                    return Err(signature::Error::new());
+                }
+                Ok(Self(bytes))
+            }
+        }
+
+        impl std::fmt::Debug for $signature {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                let s = base64ct::Base64::encode_string(self.signature_bytes());
+                let p = base64ct::Base64::encode_string(self.public_key_bytes());
+                write!(f, "{s}@{p}")
+            }
+        }
+
+        impl Serialize for $signature {
+            fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+            where
+                S: Serializer,
+            {
+                Readable::<Base64, Bytes>::serialize_as(&self.0, serializer)
+            }
+        }
+
+        // Checks the flag, so that a signature of one curve can't be read as one of the other.
+        impl<'de> Deserialize<'de> for $signature {
+            fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+            where
+                D: Deserializer<'de>,
+            {
+                let bytes: [u8; ECDSA_SUI_SIGNATURE_LENGTH] =
+                    Readable::<Base64, Bytes>::deserialize_as(deserializer)?;
+                <Self as signature::Signature>::from_bytes(&bytes).map_err(|_| {
+                    serde::de::Error::custom(format!(
+                        "expected a {} signature",
+                        stringify!($signature)
+                    ))
+                })
+            }
+        }
+    };
+}
+
+ecdsa_scheme!(
+    k256,
+    SignatureScheme::Secp256k1,
+    Secp256k1KeyPair,
+    Secp256k1Signature
+);
+ecdsa_scheme!(
+    p256,
+    SignatureScheme::Secp256r1,
+    Secp256r1KeyPair,
+    Secp256r1Signature
+);
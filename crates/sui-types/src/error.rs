@@ -2,11 +2,15 @@
 // Copyright (c) 2022, Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::{base_types::*, committee::EpochId};
+use crate::{
+    base_types::*,
+    committee::{EpochId, StakeUnit},
+};
 use move_binary_format::errors::{PartialVMError, VMError};
+use move_core_types::language_storage::ModuleId;
 use narwhal_executor::{ExecutionStateError, SubscriberError};
 use serde::{Deserialize, Serialize};
-use std::fmt::Debug;
+use std::fmt::{Debug, Display, Formatter};
 use thiserror::Error;
 use typed_store::rocks::TypedStoreError;
 
@@ -355,10 +359,149 @@ pub enum SuiError {
 
     #[error("Use of disabled feature: {:?}", error)]
     UnsupportedFeatureError { error: String },
+
+    // New variants are appended here, as the position of a variant is its discriminant in BCS.
+    #[error("Address {address} is denied from using coins of type {coin_type}")]
+    AddressDeniedForCoin {
+        address: SuiAddress,
+        coin_type: String,
+    },
+    #[error("Object {object_id} cannot be shared: only objects created in the same transaction can be shared")]
+    SharedObjectNotNew { object_id: ObjectID },
+    #[error("Object {object_id} is owned by another object and cannot be frozen or shared. It must be transferred to an account address first")]
+    FreezeOrShareObjectOwnedObject { object_id: ObjectID },
+    #[error("Move abort in {location} with code {code}.")]
+    MoveAbort { location: MoveLocation, code: u64 },
+    #[error(
+        "The effects {local_effects:?} of transaction {digest:?} differ from the effects {peer_effects:?} signed by a validity threshold of the validators, after authority {authority:?}."
+    )]
+    ForkDetected {
+        digest: TransactionDigest,
+        local_effects: TransactionEffectsDigest,
+        peer_effects: TransactionEffectsDigest,
+        authority: AuthorityName,
+    },
+    #[error("Authority is shutting down and no longer executes certificates")]
+    AuthorityShuttingDown,
+    #[error(
+        "Checkpoint {} does not extend the verified chain: {}",
+        sequence_number,
+        error
+    )]
+    InvalidCheckpointChain { sequence_number: u64, error: String },
+    #[error("Invalid proof of inclusion in checkpoint {}", sequence_number)]
+    InvalidInclusionProof { sequence_number: u64 },
+    #[error("Invalid handoff of epoch {}: {}", epoch, error)]
+    InvalidEpochHandoff { epoch: EpochId, error: String },
+    #[error("Object {child} is a child of the shared object {ancestor}, whose module '{ancestor_module}' does not authorize its use")]
+    UnauthorizedSharedChildUse {
+        child: ObjectID,
+        ancestor: ObjectID,
+        ancestor_module: String,
+    },
+    #[error("Archive error: {error}")]
+    ArchiveError { error: String },
+    #[error("Invalid snapshot: {}", error)]
+    InvalidSnapshot { error: String },
+    #[error("Transaction {:?} is denied by the authority: {}", digest, reason)]
+    TransactionDenied {
+        digest: TransactionDigest,
+        reason: String,
+    },
+    #[error("Execution exceeded the maximum computation of {max_computation} gas units.")]
+    ExceededMaxComputation { max_computation: u64 },
+    #[error("Validator is overloaded with {queue_depth} certificates waiting to execute")]
+    ValidatorOverloaded { queue_depth: usize },
+    #[error(
+        "Failed to reach a quorum: {good_stake} stake succeeded, {conflicting_stake} stake locked the objects for other transactions {:?}, {non_retryable_stake} stake rejected the transaction and {retryable_stake} stake still failed when retries ran out. Errors: {:?}",
+        conflicting_transactions.iter().map(|(digest, stake)| format!("{digest:?} ({stake} stake)")).collect::<Vec<_>>(),
+        errors
+    )]
+    QuorumFailed {
+        good_stake: StakeUnit,
+        retryable_stake: StakeUnit,
+        conflicting_stake: StakeUnit,
+        non_retryable_stake: StakeUnit,
+        /// The transactions for which validators locked the objects of the transaction, with the
+        /// stake behind each.
+        conflicting_transactions: Vec<(TransactionDigest, StakeUnit)>,
+        /// The last error of every validator which did not succeed.
+        errors: Vec<(AuthorityName, SuiError)>,
+    },
+    #[error("Timed out after {timeout_ms}ms waiting for the {stage} of the transaction")]
+    ExecutionTimedOut { stage: String, timeout_ms: u64 },
+    #[error(
+        "The data was pruned from this node, which holds checkpoints {} onwards, up to {} excluded.{}",
+        lowest_checkpoint,
+        next_checkpoint,
+        .archive.as_ref().map(|archive| format!(" It is available at the archive {archive}.")).unwrap_or_default()
+    )]
+    DataPruned {
+        lowest_checkpoint: u64,
+        next_checkpoint: u64,
+        /// Where the data can be read from instead.
+        archive: Option<String>,
+    },
+    #[error("Epoch {epoch} ended before the certificate was executed: submit the transaction again to have it signed in the current epoch")]
+    EpochEnded { epoch: EpochId },
+    #[error("Protocol version {version} is not supported, only versions {min_version} to {max_version} are")]
+    UnsupportedProtocolVersion {
+        version: u64,
+        min_version: u64,
+        max_version: u64,
+    },
+    #[error("Execution exceeded the limit of {max} {limit}, with {actual}.")]
+    ExceededExecutionLimit {
+        limit: String,
+        max: u64,
+        actual: u64,
+    },
+    #[error("Shared or party object {object_id} passed by value can only be deleted, not transferred, frozen or wrapped")]
+    SharedObjectNotDeleted { object_id: ObjectID },
+    #[error("Shared object {object_id} was deleted by a transaction sequenced before this one")]
+    SharedObjectDeleted { object_id: ObjectID },
+    #[error("The chunks of the message add up to more than {max_size} bytes.")]
+    OversizedChunkedMessage { max_size: u64 },
+    #[error("Cannot upgrade the framework package {package_id}: {error}")]
+    IncompatibleFrameworkUpgrade { package_id: ObjectID, error: String },
+    #[error("The validators voted to upgrade to a framework of digest {digest}, which this build does not have")]
+    UnknownFrameworkUpgrade { digest: String },
+    #[error("Failed to upgrade the package {package_id}: {error}")]
+    PackageUpgradeFailure { package_id: ObjectID, error: String },
+    #[error("The store has schema version {version}, newer than version {supported_version} this release supports. Run a newer release, or resync the store")]
+    StoreSchemaTooNew {
+        version: u64,
+        supported_version: u64,
+    },
+    #[error("Cannot migrate the store from schema version {version}: {error}")]
+    StoreMigrationFailure { version: u64, error: String },
 }
 
 pub type SuiResult<T = ()> = Result<T, SuiError>;
 
+/// The instruction at which a Move function aborted.
+#[derive(Eq, PartialEq, Clone, Debug, Serialize, Deserialize, Hash)]
+pub struct MoveLocation {
+    pub module: ModuleId,
+    /// The index of the function in the function definitions of the module.
+    pub function: u16,
+    /// The name of the function, if the module could be read when it aborted.
+    pub function_name: Option<String>,
+    /// The offset of the instruction in the code of the function.
+    pub instruction: u16,
+}
+
+impl Display for MoveLocation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}::", self.module.short_str_lossless())?;
+        match &self.function_name {
+            Some(name) => write!(f, "{name}")?,
+            None => write!(f, "<function {}>", self.function)?,
+        }
+        write!(f, " at instruction {}", self.instruction)
+    }
+}
+
 // TODO these are both horribly wrong, categorization needs to be considered
 impl std::convert::From<PartialVMError> for SuiError {
     fn from(error: PartialVMError) -> Self {
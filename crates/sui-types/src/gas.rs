@@ -6,11 +6,12 @@ use crate::{
     error::{SuiError, SuiResult},
     gas_coin::GasCoin,
     object::Object,
+    protocol_config::ProtocolConfig,
 };
 use move_core_types::gas_schedule::{
     AbstractMemorySize, GasAlgebra, GasCarrier, GasPrice, GasUnits, InternalGasUnits,
 };
-use move_vm_types::gas_schedule::{GasStatus, INITIAL_COST_SCHEDULE};
+use move_vm_types::gas_schedule::GasStatus;
 use once_cell::sync::Lazy;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
@@ -44,6 +45,36 @@ impl GasCostSummary {
     }
 }
 
+/// Where the computation gas of a transaction went, in gas units before the gas price applies.
+/// Only dry runs profile gas.
+#[derive(Eq, PartialEq, Clone, Debug, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct GasProfile {
+    /// The gas of each call into Move, in the order of the calls: the functions the transaction
+    /// calls, and the `init` functions of the modules it publishes. The gas of a call includes
+    /// the gas of the natives it calls.
+    pub calls: Vec<FunctionGas>,
+    /// The gas of the calls to each Sui framework native, sorted by native.
+    pub natives: Vec<NativeGas>,
+}
+
+#[derive(Eq, PartialEq, Clone, Debug, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct FunctionGas {
+    /// The function, as `<package>::<module>::<function>`.
+    pub function: String,
+    pub gas_used: u64,
+}
+
+#[derive(Eq, PartialEq, Clone, Debug, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct NativeGas {
+    /// The native, as `<module>::<function>`.
+    pub function: String,
+    pub calls: u64,
+    pub gas_used: u64,
+}
+
 /// ComputationCost is a newtype wrapper of InternalGasUnits
 /// to ensure a value of this type is used specifically for computation cost.
 /// Anything that does not change the amount of bytes stored in the authority data store
@@ -51,6 +82,10 @@ impl GasCostSummary {
 struct ComputationCost(InternalGasUnits<GasCarrier>);
 
 impl ComputationCost {
+    fn new(cost: u64) -> Self {
+        Self(InternalGasUnits::new(cost))
+    }
+
     /// Some computations are also linear to the size of data it operates on.
     pub fn with_size(&self, size: usize) -> Self {
         Self(self.0.mul(AbstractMemorySize::new(size as u64)))
@@ -64,67 +99,61 @@ impl ComputationCost {
 struct StorageCost(InternalGasUnits<GasCarrier>);
 
 impl StorageCost {
+    fn new(cost: u64) -> Self {
+        Self(InternalGasUnits::new(cost))
+    }
+
     pub fn with_size(&self, size: usize) -> Self {
         Self(self.0.mul(AbstractMemorySize::new(size as u64)))
     }
 }
 
-/// A list of constant costs of various operations in Sui.
-struct SuiCostTable {
-    /// A flat fee charged for every transaction. This is also the mimmum amount of
-    /// gas charged for a transaction.
-    pub min_transaction_cost: ComputationCost,
-    /// Computation cost per byte charged for package publish. This cost is primarily
-    /// determined by the cost to verify and link a package. Note that this does not
-    /// include the cost of writing the package to the store.
-    pub package_publish_per_byte_cost: ComputationCost,
-    /// Per byte cost to read objects from the store. This is computation cost instead of
-    /// storage cost because it does not change the amount of data stored on the db.
-    pub object_read_per_byte_cost: ComputationCost,
-    /// Per byte cost to write objects to the store. This is computation cost instead of
-    /// storage cost because it does not change the amount of data stored on the db.
-    pub object_mutation_per_byte_cost: ComputationCost,
-    /// Cost to use shared objects in a transaction, which requires full consensus.
-    pub consensus_cost: ComputationCost,
-
-    /// Unit cost of a byte in the storage. This will be used both for charging for
-    /// new storage as well as rebating for deleting storage. That is, we expect users to
-    /// get full refund on the object storage when it's deleted.
-    /// TODO: We should introduce a flat fee on storage that does not get refunded even
-    /// when objects are deleted. This cost covers the cost of storing transaction metadata
-    /// which will always be there even after the objects are deleted.
-    pub storage_per_byte_cost: StorageCost,
-}
+/// The largest gas budget, under the gas schedule of the latest protocol version.
+pub static MAX_GAS_BUDGET: Lazy<u64> =
+    Lazy::new(|| max_gas_budget(ProtocolConfig::get_for_max_version()));
+
+/// The smallest gas budget, under the gas schedule of the latest protocol version.
+pub static MIN_GAS_BUDGET: Lazy<u64> =
+    Lazy::new(|| min_gas_budget(ProtocolConfig::get_for_max_version()));
 
-// TODO: The following numbers are arbitrary at this point.
-static INIT_SUI_COST_TABLE: Lazy<SuiCostTable> = Lazy::new(|| SuiCostTable {
-    min_transaction_cost: ComputationCost(InternalGasUnits::new(10000)),
-    package_publish_per_byte_cost: ComputationCost(InternalGasUnits::new(80)),
-    object_read_per_byte_cost: ComputationCost(InternalGasUnits::new(15)),
-    object_mutation_per_byte_cost: ComputationCost(InternalGasUnits::new(40)),
-    consensus_cost: ComputationCost(InternalGasUnits::new(100000)),
+pub fn max_gas_budget(config: &ProtocolConfig) -> u64 {
+    to_external_for(config, InternalGasUnits::new(u64::MAX)).get()
+}
 
-    storage_per_byte_cost: StorageCost(InternalGasUnits::new(100)),
-});
+pub fn min_gas_budget(config: &ProtocolConfig) -> u64 {
+    to_external_for(config, InternalGasUnits::new(config.min_transaction_cost)).get()
+}
 
-pub static MAX_GAS_BUDGET: Lazy<u64> =
-    Lazy::new(|| to_external(InternalGasUnits::new(u64::MAX)).get());
+/// The most computation gas units the Move code of a transaction may use, whatever its budget.
+/// As the cap is metered rather than timed, every validator stops a runaway execution at the
+/// same instruction, and it fails with `ExceededMaxComputation` instead of stalling commits.
+pub const MAX_TX_COMPUTATION_UNITS: u64 = 1_000_000;
 
-pub static MIN_GAS_BUDGET: Lazy<u64> =
-    Lazy::new(|| to_external(INIT_SUI_COST_TABLE.min_transaction_cost.0).get());
+/// Internal gas units in gas units, under the gas schedule of the latest protocol version.
+pub fn to_external(internal_units: InternalGasUnits<GasCarrier>) -> GasUnits<GasCarrier> {
+    to_external_for(ProtocolConfig::get_for_max_version(), internal_units)
+}
 
-fn to_external(internal_units: InternalGasUnits<GasCarrier>) -> GasUnits<GasCarrier> {
-    let consts = &INITIAL_COST_SCHEDULE.gas_constants;
+fn to_external_for(
+    config: &ProtocolConfig,
+    internal_units: InternalGasUnits<GasCarrier>,
+) -> GasUnits<GasCarrier> {
+    let consts = &config.move_cost_table.gas_constants;
     consts.to_external_units(internal_units)
 }
 
-fn to_internal(external_units: GasUnits<GasCarrier>) -> InternalGasUnits<GasCarrier> {
-    let consts = &INITIAL_COST_SCHEDULE.gas_constants;
+fn to_internal_for(
+    config: &ProtocolConfig,
+    external_units: GasUnits<GasCarrier>,
+) -> InternalGasUnits<GasCarrier> {
+    let consts = &config.move_cost_table.gas_constants;
     consts.to_internal_units(external_units)
 }
 
 pub struct SuiGasStatus<'a> {
     gas_status: GasStatus<'a>,
+    /// The protocol config of the epoch, whose gas schedule the transaction is charged by.
+    config: &'static ProtocolConfig,
     init_budget: GasUnits<GasCarrier>,
     charge: bool,
     computation_gas_unit_price: GasPrice<GasCarrier>,
@@ -137,16 +166,34 @@ pub struct SuiGasStatus<'a> {
     /// was the storage cost paid when the object was last mutated. It is not affected
     /// by the current storage gas unit price.
     storage_rebate: GasCarrier,
+    /// Set when profiling, to the gas used so far.
+    profile: Option<GasProfile>,
 }
 
 impl<'a> SuiGasStatus<'a> {
+    /// A gas status charging by the gas schedule of the latest protocol version.
     pub fn new_with_budget(
         gas_budget: u64,
         computation_gas_unit_price: GasCarrier,
         storage_gas_unit_price: GasCarrier,
+    ) -> SuiGasStatus<'a> {
+        Self::new_with_config(
+            ProtocolConfig::get_for_max_version(),
+            gas_budget,
+            computation_gas_unit_price,
+            storage_gas_unit_price,
+        )
+    }
+
+    pub fn new_with_config(
+        config: &'static ProtocolConfig,
+        gas_budget: u64,
+        computation_gas_unit_price: GasCarrier,
+        storage_gas_unit_price: GasCarrier,
     ) -> SuiGasStatus<'a> {
         Self::new(
-            GasStatus::new(&INITIAL_COST_SCHEDULE, GasUnits::new(gas_budget)),
+            config,
+            GasStatus::new(&config.move_cost_table, GasUnits::new(gas_budget)),
             gas_budget,
             true,
             computation_gas_unit_price,
@@ -155,7 +202,18 @@ impl<'a> SuiGasStatus<'a> {
     }
 
     pub fn new_unmetered() -> SuiGasStatus<'a> {
-        Self::new(GasStatus::new_unmetered(), 0, false, 0, 0)
+        Self::new(
+            ProtocolConfig::get_for_max_version(),
+            GasStatus::new_unmetered(),
+            0,
+            false,
+            0,
+            0,
+        )
+    }
+
+    pub fn protocol_config(&self) -> &'static ProtocolConfig {
+        self.config
     }
 
     pub fn is_unmetered(&self) -> bool {
@@ -166,25 +224,110 @@ impl<'a> SuiGasStatus<'a> {
         &mut self.gas_status
     }
 
+    /// Profile where the gas goes from now on.
+    pub fn enable_profiling(&mut self) {
+        self.profile.get_or_insert_with(GasProfile::default);
+    }
+
+    pub fn is_profiling(&self) -> bool {
+        self.profile.is_some()
+    }
+
+    /// The gas units left of the budget.
+    pub fn remaining_gas(&self) -> u64 {
+        self.gas_status.remaining_gas().get()
+    }
+
+    /// The computation gas units used so far.
+    pub fn computation_gas_used(&self) -> u64 {
+        self.init_budget
+            .sub(self.gas_status.remaining_gas())
+            .sub(self.storage_cost)
+            .get()
+    }
+
+    /// Run `call`, a call into the Move VM, with the Move gas meter. The call may use the gas
+    /// left of the budget, up to `MAX_TX_COMPUTATION_UNITS` of computation in the transaction.
+    pub fn meter_move_call<T>(&mut self, call: impl FnOnce(&mut GasStatus<'a>) -> T) -> T {
+        let allowance = MAX_TX_COMPUTATION_UNITS.saturating_sub(self.computation_gas_used());
+        let remaining_gas = self.remaining_gas();
+        if self.is_unmetered() || remaining_gas <= allowance {
+            return call(&mut self.gas_status);
+        }
+        // Set the gas above the allowance aside while the call runs, so that the VM runs out of
+        // gas at the cap as it would at the end of the budget.
+        let reserve = remaining_gas - allowance;
+        self.gas_status
+            .deduct_gas(to_internal_for(self.config, GasUnits::new(reserve)))
+            .expect("The reserve is less than the gas left");
+        let result = call(&mut self.gas_status);
+        self.gas_status = GasStatus::new(
+            &self.config.move_cost_table,
+            GasUnits::new(self.remaining_gas() + reserve),
+        );
+        result
+    }
+
+    /// The error of a call into the Move VM which ran out of gas: `ExceededMaxComputation` if it
+    /// hit the computation cap with gas left of the budget, `InsufficientGas` otherwise.
+    pub fn out_of_gas_error(&self) -> SuiError {
+        if self.remaining_gas() > 0 && self.computation_gas_used() >= MAX_TX_COMPUTATION_UNITS {
+            SuiError::ExceededMaxComputation {
+                max_computation: MAX_TX_COMPUTATION_UNITS,
+            }
+        } else {
+            SuiError::InsufficientGas {
+                error: "Ran out of gas while executing Move code".to_owned(),
+            }
+        }
+    }
+
+    /// Attribute to `function` the gas used since `remaining_gas` were left, and add the gas of
+    /// the natives it called to the totals of the natives.
+    pub fn record_call(
+        &mut self,
+        function: String,
+        remaining_gas: u64,
+        natives: impl IntoIterator<Item = NativeGas>,
+    ) {
+        let gas_used = remaining_gas.saturating_sub(self.remaining_gas());
+        if let Some(profile) = &mut self.profile {
+            profile.calls.push(FunctionGas { function, gas_used });
+            for native in natives {
+                match profile
+                    .natives
+                    .binary_search_by(|recorded| recorded.function.cmp(&native.function))
+                {
+                    Ok(index) => {
+                        profile.natives[index].calls += native.calls;
+                        profile.natives[index].gas_used += native.gas_used;
+                    }
+                    Err(index) => profile.natives.insert(index, native),
+                }
+            }
+        }
+    }
+
+    pub fn take_profile(&mut self) -> Option<GasProfile> {
+        self.profile.take()
+    }
+
     pub fn charge_min_tx_gas(&mut self) -> SuiResult {
-        self.deduct_computation_cost(&INIT_SUI_COST_TABLE.min_transaction_cost)
+        self.deduct_computation_cost(&ComputationCost::new(self.config.min_transaction_cost))
     }
 
     pub fn charge_consensus(&mut self) -> SuiResult {
-        self.deduct_computation_cost(&INIT_SUI_COST_TABLE.consensus_cost)
+        self.deduct_computation_cost(&ComputationCost::new(self.config.consensus_cost))
     }
 
     pub fn charge_publish_package(&mut self, size: usize) -> SuiResult {
-        let computation_cost = INIT_SUI_COST_TABLE
-            .package_publish_per_byte_cost
-            .with_size(size);
+        let computation_cost =
+            ComputationCost::new(self.config.package_publish_per_byte_cost).with_size(size);
         self.deduct_computation_cost(&computation_cost)
     }
 
     pub fn charge_storage_read(&mut self, size: usize) -> SuiResult {
-        let cost = INIT_SUI_COST_TABLE
-            .object_read_per_byte_cost
-            .with_size(size);
+        let cost = ComputationCost::new(self.config.object_read_per_byte_cost).with_size(size);
         self.deduct_computation_cost(&cost)
     }
 
@@ -201,16 +344,13 @@ impl<'a> SuiGasStatus<'a> {
         // Computation cost of a mutation is charged based on the sum of the old and new size.
         // This is because to update an object in the store, we have to erase the old one and
         // write a new one.
-        let cost = INIT_SUI_COST_TABLE
-            .object_mutation_per_byte_cost
+        let cost = ComputationCost::new(self.config.object_mutation_per_byte_cost)
             .with_size(old_size + new_size);
         self.deduct_computation_cost(&cost)?;
 
         self.storage_rebate += storage_rebate;
 
-        let storage_cost = INIT_SUI_COST_TABLE
-            .storage_per_byte_cost
-            .with_size(new_size);
+        let storage_cost = StorageCost::new(self.config.storage_per_byte_cost).with_size(new_size);
         self.deduct_storage_cost(&storage_cost)
     }
 
@@ -247,6 +387,7 @@ impl<'a> SuiGasStatus<'a> {
     }
 
     fn new(
+        config: &'static ProtocolConfig,
         move_gas_status: GasStatus<'a>,
         gas_budget: u64,
         charge: bool,
@@ -255,12 +396,14 @@ impl<'a> SuiGasStatus<'a> {
     ) -> SuiGasStatus<'a> {
         SuiGasStatus {
             gas_status: move_gas_status,
+            config,
             init_budget: GasUnits::new(gas_budget),
             charge,
             computation_gas_unit_price: GasPrice::new(computation_gas_unit_price),
             storage_gas_unit_price: GasPrice::new(storage_gas_unit_price),
             storage_cost: GasUnits::new(0),
             storage_rebate: 0,
+            profile: None,
         }
     }
 
@@ -278,8 +421,8 @@ impl<'a> SuiGasStatus<'a> {
         if self.is_unmetered() {
             return Ok(0);
         }
-        let ext_cost = to_external(cost.0);
-        let charge_amount = to_internal(ext_cost);
+        let ext_cost = to_external_for(self.config, cost.0);
+        let charge_amount = to_internal_for(self.config, ext_cost);
         let remaining_gas = self.gas_status.remaining_gas();
         if self.gas_status.deduct_gas(charge_amount).is_err() {
             debug_assert_eq!(self.gas_status.remaining_gas().get(), 0);
@@ -302,20 +445,27 @@ impl<'a> SuiGasStatus<'a> {
 /// 2. If it's enough to pay the flat minimum transaction fee
 /// 3. If it's less than the max gas budget allowed
 /// 4. If the gas_object actually has enough balance to pay for the budget.
-pub fn check_gas_balance(gas_object: &Object, gas_budget: u64) -> SuiResult {
+/// The budgets are the ones of the gas schedule of `config`.
+pub fn check_gas_balance(
+    gas_object: &Object,
+    gas_budget: u64,
+    config: &ProtocolConfig,
+) -> SuiResult {
     ok_or_gas_error!(
         gas_object.is_owned(),
         "Gas object must be owned Move object".to_owned()
     )?;
+    let max_gas_budget = max_gas_budget(config);
     ok_or_gas_error!(
-        gas_budget <= *MAX_GAS_BUDGET,
-        format!("Gas budget set too high; maximum is {}", *MAX_GAS_BUDGET)
+        gas_budget <= max_gas_budget,
+        format!("Gas budget set too high; maximum is {}", max_gas_budget)
     )?;
+    let min_gas_budget = min_gas_budget(config);
     ok_or_gas_error!(
-        gas_budget >= *MIN_GAS_BUDGET,
+        gas_budget >= min_gas_budget,
         format!(
             "Gas budget is {}, smaller than minimum requirement {}",
-            gas_budget, *MIN_GAS_BUDGET
+            gas_budget, min_gas_budget
         )
     )?;
 
@@ -326,13 +476,16 @@ pub fn check_gas_balance(gas_object: &Object, gas_budget: u64) -> SuiResult {
     )
 }
 
-/// Create a new gas status with the given `gas_budget`, and charge the transaction flat fee.
+/// Create a new gas status with the given `gas_budget`, charging by the gas schedule of
+/// `config`, and charge the transaction flat fee.
 pub fn start_gas_metering(
+    config: &'static ProtocolConfig,
     gas_budget: u64,
     computation_gas_unit_price: u64,
     storage_gas_unit_price: u64,
 ) -> SuiResult<SuiGasStatus<'static>> {
-    let mut gas_status = SuiGasStatus::new_with_budget(
+    let mut gas_status = SuiGasStatus::new_with_config(
+        config,
         gas_budget,
         computation_gas_unit_price,
         storage_gas_unit_price,
@@ -0,0 +1,147 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! The intent of a signature, which every signed message starts with so that a signature of
+//! one kind of message can't be replayed as another. The intent is the scope of the message,
+//! the version of the intent and the application it is for, one byte each. It is only part of
+//! the signed bytes: digests are still computed over the message alone.
+
+use serde::{Deserialize, Serialize};
+
+use crate::base_types::SuiAddress;
+use crate::crypto::{BcsSignable, GenericSignature, Signable};
+use crate::error::{SuiError, SuiResult};
+
+#[cfg(test)]
+#[path = "unit_tests/intent_tests.rs"]
+mod intent_tests;
+
+/// The length of a serialized intent.
+pub const INTENT_LENGTH: usize = 3;
+
+/// The kind of message which is signed.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
+#[repr(u8)]
+pub enum IntentScope {
+    TransactionData = 0,
+    TransactionEffects = 1,
+    CheckpointSummary = 2,
+    PersonalMessage = 3,
+    AuthorityBatch = 4,
+    Genesis = 5,
+    EpochHandoff = 6,
+    BridgeTransfer = 7,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
+#[repr(u8)]
+pub enum IntentVersion {
+    V0 = 0,
+}
+
+/// The application a message is signed for, so that other chains and apps can reuse the
+/// intent without their signatures being valid on Sui.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
+#[repr(u8)]
+pub enum AppId {
+    Sui = 0,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub struct Intent {
+    pub scope: IntentScope,
+    pub version: IntentVersion,
+    pub app_id: AppId,
+}
+
+impl Intent {
+    /// The current intent of Sui messages of `scope`.
+    pub fn new(scope: IntentScope) -> Self {
+        Self {
+            scope,
+            version: IntentVersion::V0,
+            app_id: AppId::Sui,
+        }
+    }
+
+    pub fn to_bytes(&self) -> [u8; INTENT_LENGTH] {
+        [self.scope as u8, self.version as u8, self.app_id as u8]
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> SuiResult<Self> {
+        let invalid = || SuiError::InvalidSignature {
+            error: format!("Invalid intent {bytes:?}"),
+        };
+        let scope = match bytes.first() {
+            Some(0) => IntentScope::TransactionData,
+            Some(1) => IntentScope::TransactionEffects,
+            Some(2) => IntentScope::CheckpointSummary,
+            Some(3) => IntentScope::PersonalMessage,
+            Some(4) => IntentScope::AuthorityBatch,
+            Some(5) => IntentScope::Genesis,
+            Some(6) => IntentScope::EpochHandoff,
+            Some(7) => IntentScope::BridgeTransfer,
+            _ => return Err(invalid()),
+        };
+        match bytes[1..] {
+            [0, 0] => Ok(Self::new(scope)),
+            _ => Err(invalid()),
+        }
+    }
+
+    /// The bytes signed for `message` with this intent: the intent followed by the message.
+    pub fn message_bytes(&self, message: &[u8]) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(INTENT_LENGTH + message.len());
+        bytes.extend_from_slice(&self.to_bytes());
+        bytes.extend_from_slice(message);
+        bytes
+    }
+}
+
+/// A type of message which is signed, and the scope of the intent its signatures commit to.
+pub trait IntentScoped {
+    const INTENT_SCOPE: IntentScope;
+}
+
+/// The bytes signed for `value`: the intent of its scope followed by its signable bytes. This
+/// is what clients sign for a transaction, given its `to_bytes()` or the `tx_bytes` returned by
+/// the gateway.
+pub fn intent_message<T>(value: &T) -> Vec<u8>
+where
+    T: Signable<Vec<u8>> + IntentScoped,
+{
+    let mut message = Intent::new(T::INTENT_SCOPE).to_bytes().to_vec();
+    value.write(&mut message);
+    message
+}
+
+/// A message signed off-chain, for an app to check that the signer owns an address. Its intent
+/// keeps the signature from being valid for a transaction, even if the message is the bytes of
+/// one.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct PersonalMessage {
+    pub message: Vec<u8>,
+}
+
+impl BcsSignable for PersonalMessage {}
+
+impl IntentScoped for PersonalMessage {
+    const INTENT_SCOPE: IntentScope = IntentScope::PersonalMessage;
+}
+
+impl PersonalMessage {
+    pub fn new(message: Vec<u8>) -> Self {
+        Self { message }
+    }
+
+    /// The bytes the signer signs: the intent of personal messages followed by the message.
+    pub fn to_intent_bytes(&self) -> Vec<u8> {
+        intent_message(self)
+    }
+
+    /// Check that `signature` is a signature of the message by `author`, which may also be a
+    /// multisig address.
+    pub fn verify(&self, signature: &GenericSignature, author: SuiAddress) -> SuiResult<()> {
+        signature.verify(self, author)
+    }
+}
@@ -0,0 +1,287 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Keys derived from BIP39 mnemonics, so that an account can be restored in any wallet from
+//! the words written down when it was created. The seed of the mnemonic is turned into keys
+//! along a derivation path with SLIP-0010 for ed25519, which only supports hardened children,
+//! and with BIP32 for secp256k1 and secp256r1. Each scheme has its own purpose in the path,
+//! under the coin type of Sui.
+
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+use anyhow::anyhow;
+use bip39::Mnemonic;
+use ed25519_dalek as dalek;
+use hmac::{Hmac, Mac};
+use rand::rngs::OsRng;
+use rand::RngCore;
+use sha2::Sha512;
+
+use crate::crypto::{KeyPair, SignatureScheme, SuiKeyPair};
+use crate::ecdsa::{Secp256k1KeyPair, Secp256r1KeyPair};
+use crate::error::{SuiError, SuiResult};
+use crate::fp_ensure;
+
+#[cfg(test)]
+#[path = "unit_tests/key_derivation_tests.rs"]
+mod key_derivation_tests;
+
+/// The registered SLIP-0044 coin type of Sui.
+pub const SUI_COIN_TYPE: u32 = 784;
+pub const DERIVATION_PATH_PURPOSE_ED25519: u32 = 44;
+pub const DERIVATION_PATH_PURPOSE_SECP256K1: u32 = 54;
+pub const DERIVATION_PATH_PURPOSE_SECP256R1: u32 = 74;
+
+/// The number of words of the mnemonics generated for new keys.
+pub const MNEMONIC_WORD_COUNT: usize = 24;
+
+const HARDENED: u32 = 1 << 31;
+const ED25519_SEED_KEY: &[u8] = b"ed25519 seed";
+const SECP256K1_SEED_KEY: &[u8] = b"Bitcoin seed";
+const SECP256R1_SEED_KEY: &[u8] = b"Nist256p1 seed";
+
+/// A BIP32 path such as `m/44'/784'/0'/0'/0'`, where `'` marks a hardened child.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DerivationPath(Vec<u32>);
+
+impl DerivationPath {
+    /// The path of the first key of the first account of `scheme`:
+    /// `m/44'/784'/0'/0'/0'` for ed25519, `m/54'/784'/0'/0/0` for secp256k1 and
+    /// `m/74'/784'/0'/0/0` for secp256r1.
+    pub fn default_for(scheme: SignatureScheme) -> SuiResult<DerivationPath> {
+        let (purpose, hardened_tail) = Self::purpose_of(scheme)?;
+        let tail = if hardened_tail { HARDENED } else { 0 };
+        Ok(DerivationPath(vec![
+            purpose | HARDENED,
+            SUI_COIN_TYPE | HARDENED,
+            HARDENED,
+            tail,
+            tail,
+        ]))
+    }
+
+    /// The purpose of the paths of `scheme`, and whether their change and address index
+    /// must be hardened.
+    fn purpose_of(scheme: SignatureScheme) -> SuiResult<(u32, bool)> {
+        match scheme {
+            SignatureScheme::ED25519 => Ok((DERIVATION_PATH_PURPOSE_ED25519, true)),
+            SignatureScheme::Secp256k1 => Ok((DERIVATION_PATH_PURPOSE_SECP256K1, false)),
+            SignatureScheme::Secp256r1 => Ok((DERIVATION_PATH_PURPOSE_SECP256R1, false)),
+            SignatureScheme::MultiSig | SignatureScheme::ZkLogin => {
+                Err(SuiError::SignatureKeyGenError(format!(
+                    "{scheme:?} keys can't be derived from a mnemonic"
+                )))
+            }
+        }
+    }
+
+    /// The indexes of the levels of the path, with the top bit set for hardened children.
+    pub fn indexes(&self) -> &[u32] {
+        &self.0
+    }
+
+    /// Check that the path is `m/purpose'/784'/account'/change/index` with the purpose of
+    /// `scheme`, and for ed25519, that the change and the index are hardened too.
+    pub fn validate(&self, scheme: SignatureScheme) -> SuiResult<()> {
+        let (purpose, hardened_tail) = Self::purpose_of(scheme)?;
+        let invalid = |reason: String| {
+            SuiError::SignatureKeyGenError(format!("Invalid derivation path {self}: {reason}"))
+        };
+        fp_ensure!(
+            self.0.len() == 5,
+            invalid("expected m/purpose'/coin_type'/account'/change/address_index".to_string())
+        );
+        fp_ensure!(
+            self.0[0] == purpose | HARDENED,
+            invalid(format!("the purpose of {scheme:?} keys is {purpose}'"))
+        );
+        fp_ensure!(
+            self.0[1] == SUI_COIN_TYPE | HARDENED,
+            invalid(format!("the coin type of Sui is {SUI_COIN_TYPE}'"))
+        );
+        fp_ensure!(
+            self.0[2] & HARDENED != 0,
+            invalid("the account must be hardened".to_string())
+        );
+        fp_ensure!(
+            !hardened_tail || self.0[3..].iter().all(|index| index & HARDENED != 0),
+            invalid(format!(
+                "every level of the path of {scheme:?} keys must be hardened"
+            ))
+        );
+        Ok(())
+    }
+}
+
+impl FromStr for DerivationPath {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut levels = s.trim().split('/');
+        if levels.next() != Some("m") {
+            return Err(anyhow!("Derivation path {s} must start with m/"));
+        }
+        levels
+            .map(|level| {
+                let (index, hardened) = match level.strip_suffix('\'') {
+                    Some(index) => (index, HARDENED),
+                    None => (level, 0),
+                };
+                match index.parse::<u32>() {
+                    Ok(index) if index < HARDENED => Ok(index | hardened),
+                    _ => Err(anyhow!("Invalid level {level} in derivation path {s}")),
+                }
+            })
+            .collect::<Result<_, _>>()
+            .map(DerivationPath)
+    }
+}
+
+impl Display for DerivationPath {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "m")?;
+        for index in &self.0 {
+            if index & HARDENED != 0 {
+                write!(f, "/{}'", index & !HARDENED)?;
+            } else {
+                write!(f, "/{index}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Generate a new mnemonic of `MNEMONIC_WORD_COUNT` English words.
+pub fn generate_mnemonic() -> Mnemonic {
+    // Each word encodes 11 bits, of which the last few of the phrase are a checksum.
+    let mut entropy = [0u8; MNEMONIC_WORD_COUNT * 4 / 3];
+    OsRng.fill_bytes(&mut entropy);
+    Mnemonic::from_entropy(&entropy).expect("the entropy has a valid length")
+}
+
+/// Parse an English mnemonic, ignoring case and extra whitespace between the words.
+pub fn parse_mnemonic(phrase: &str) -> SuiResult<Mnemonic> {
+    let phrase = phrase
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase();
+    Mnemonic::parse_normalized(&phrase)
+        .map_err(|err| SuiError::SignatureKeyGenError(format!("Invalid mnemonic phrase: {err}")))
+}
+
+/// Derive the key of `scheme` at `path` from the seed of `mnemonic`, without a passphrase,
+/// or at the default path of the scheme if `path` is `None`.
+pub fn derive_key_pair_from_mnemonic(
+    mnemonic: &Mnemonic,
+    scheme: SignatureScheme,
+    path: Option<DerivationPath>,
+) -> SuiResult<SuiKeyPair> {
+    let path = match path {
+        Some(path) => path,
+        None => DerivationPath::default_for(scheme)?,
+    };
+    path.validate(scheme)?;
+    let seed = mnemonic.to_seed("");
+    Ok(match scheme {
+        SignatureScheme::ED25519 => SuiKeyPair::Ed25519(derive_ed25519(&seed, &path)?),
+        SignatureScheme::Secp256k1 => SuiKeyPair::Secp256k1(derive_bip32(&seed, &path)?),
+        SignatureScheme::Secp256r1 => SuiKeyPair::Secp256r1(derive_bip32(&seed, &path)?),
+        SignatureScheme::MultiSig | SignatureScheme::ZkLogin => {
+            unreachable!("the path of {scheme:?} keys is rejected")
+        }
+    })
+}
+
+/// The left and right halves of the HMAC-SHA512 of the concatenation of `data`.
+fn hmac_sha512(key: &[u8], data: &[&[u8]]) -> ([u8; 32], [u8; 32]) {
+    let mut mac = Hmac::<Sha512>::new_from_slice(key).expect("HMAC takes keys of any length");
+    for bytes in data {
+        mac.update(bytes);
+    }
+    let output = mac.finalize().into_bytes();
+    let (mut left, mut right) = ([0u8; 32], [0u8; 32]);
+    left.copy_from_slice(&output[..32]);
+    right.copy_from_slice(&output[32..]);
+    (left, right)
+}
+
+/// SLIP-0010 derivation for ed25519, where every child is hardened and the left half of the
+/// HMAC is the secret key of the child.
+fn derive_ed25519(seed: &[u8], path: &DerivationPath) -> SuiResult<KeyPair> {
+    let secret = derive_ed25519_secret(seed, path);
+    let secret = dalek::SecretKey::from_bytes(&secret)
+        .map_err(|err| SuiError::SignatureKeyGenError(err.to_string()))?;
+    let public = dalek::PublicKey::from(&secret);
+    Ok(dalek::Keypair { secret, public }.into())
+}
+
+fn derive_ed25519_secret(seed: &[u8], path: &DerivationPath) -> [u8; 32] {
+    let (mut secret, mut chain_code) = hmac_sha512(ED25519_SEED_KEY, &[seed]);
+    for index in &path.0 {
+        let (child_secret, child_chain_code) =
+            hmac_sha512(&chain_code, &[&[0], &secret, &index.to_be_bytes()]);
+        secret = child_secret;
+        chain_code = child_chain_code;
+    }
+    secret
+}
+
+/// The operations of BIP32 on the keys of a curve.
+trait Bip32KeyPair: Sized {
+    const SEED_KEY: &'static [u8];
+
+    fn from_secret(secret: &[u8]) -> SuiResult<Self>;
+    fn secret(&self) -> [u8; 32];
+    fn compressed_public_key(&self) -> Vec<u8>;
+    fn add_tweak(&self, tweak: &[u8; 32]) -> SuiResult<Self>;
+}
+
+macro_rules! bip32_key_pair {
+    ($key_pair:ident, $seed_key:expr) => {
+        impl Bip32KeyPair for $key_pair {
+            const SEED_KEY: &'static [u8] = $seed_key;
+
+            fn from_secret(secret: &[u8]) -> SuiResult<Self> {
+                $key_pair::from_bytes(secret)
+            }
+
+            fn secret(&self) -> [u8; 32] {
+                self.to_bytes()
+            }
+
+            fn compressed_public_key(&self) -> Vec<u8> {
+                self.public_key_bytes().to_vec()
+            }
+
+            fn add_tweak(&self, tweak: &[u8; 32]) -> SuiResult<Self> {
+                $key_pair::add_tweak(self, tweak)
+            }
+        }
+    };
+}
+
+bip32_key_pair!(Secp256k1KeyPair, SECP256K1_SEED_KEY);
+bip32_key_pair!(Secp256r1KeyPair, SECP256R1_SEED_KEY);
+
+/// BIP32 derivation, where the secret key of a child is the secret key of its parent plus
+/// the left half of the HMAC. The keys which BIP32 says to skip, whose odds are about 2^-127,
+/// are rejected instead.
+fn derive_bip32<K: Bip32KeyPair>(seed: &[u8], path: &DerivationPath) -> SuiResult<K> {
+    let (secret, mut chain_code) = hmac_sha512(K::SEED_KEY, &[seed]);
+    let mut key = K::from_secret(&secret)?;
+    for index in &path.0 {
+        let (tweak, child_chain_code) = if index & HARDENED != 0 {
+            hmac_sha512(&chain_code, &[&[0], &key.secret(), &index.to_be_bytes()])
+        } else {
+            hmac_sha512(
+                &chain_code,
+                &[&key.compressed_public_key(), &index.to_be_bytes()],
+            )
+        };
+        key = key.add_tweak(&tweak)?;
+        chain_code = child_chain_code;
+    }
+    Ok(key)
+}
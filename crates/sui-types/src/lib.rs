@@ -16,22 +16,40 @@ pub mod error;
 pub mod balance;
 pub mod base_types;
 pub mod batch;
+pub mod bls;
+pub mod bridge;
 pub mod coin;
 pub mod committee;
 pub mod crypto;
+pub mod deny_list;
+pub mod display;
+pub mod ecdsa;
 pub mod event;
 pub mod gas;
 pub mod gas_coin;
 pub mod id;
+pub mod intent;
+pub mod key_derivation;
+pub mod light_client;
+pub mod locked_coin;
+pub mod message_chunks;
 pub mod messages;
 pub mod messages_checkpoint;
 pub mod move_package;
+pub mod multisig;
+pub mod name_service;
 pub mod object;
+pub mod object_change;
+pub mod order_book;
+pub mod protocol_config;
 pub mod signature_seed;
+pub mod snapshot;
 pub mod storage;
 pub mod sui_serde;
 pub mod sui_system_state;
+pub mod upgrade_cap;
 pub mod waypoint;
+pub mod zk_login;
 
 #[path = "./unit_tests/utils.rs"]
 pub mod utils;
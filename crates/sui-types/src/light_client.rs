@@ -0,0 +1,325 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Verification of checkpoints for light clients, such as bridges and mobile wallets, which
+//! trust a committee but run no node. A light client follows the chain of certified checkpoints
+//! from a checkpoint it trusts, each one naming the digest of the one before it, and checks that
+//! a transaction is final by its proof of inclusion in a verified checkpoint. At the end of each
+//! epoch it moves on to the next committee, which the committee it trusts hands off to, so that
+//! a client starting from the genesis committee comes to trust the current one.
+//!
+//! The module does no I/O and reads no clock, and the checks use neither threads nor
+//! randomness, so that it can be built for targets without `std`: it only needs the `Vec` and
+//! `BTreeMap` of `alloc`, and the hashing and signature crates, which build without `std`.
+
+use crate::base_types::{ExecutionDigests, TransactionDigest};
+use crate::committee::Committee;
+use crate::error::{SuiError, SuiResult};
+use crate::messages::TransactionEffects;
+use crate::messages_checkpoint::{
+    CertifiedCheckpoint, CertifiedEpochHandoff, CheckpointDigest, CheckpointSequenceNumber,
+    CheckpointSummary,
+};
+use serde::{Deserialize, Serialize};
+use sha3::{Digest, Sha3_256};
+
+#[cfg(test)]
+#[path = "unit_tests/light_client_tests.rs"]
+mod light_client_tests;
+
+/// The prefixes of the hashes of leaves and inner nodes, so that no leaf is taken for a node.
+const LEAF_PREFIX: u8 = 0;
+const NODE_PREFIX: u8 = 1;
+
+fn leaf_hash(digests: &ExecutionDigests) -> CheckpointDigest {
+    let mut hasher = Sha3_256::default();
+    hasher.update([LEAF_PREFIX]);
+    hasher.update(digests.transaction.as_ref());
+    hasher.update(digests.effects.0);
+    hasher.finalize().into()
+}
+
+fn node_hash(left: &CheckpointDigest, right: &CheckpointDigest) -> CheckpointDigest {
+    let mut hasher = Sha3_256::default();
+    hasher.update([NODE_PREFIX]);
+    hasher.update(left);
+    hasher.update(right);
+    hasher.finalize().into()
+}
+
+/// The levels of the Merkle tree of `leaves`, from the leaves up to the root. The nodes of a
+/// level are hashed in pairs, and the last node of a level of odd width moves up unchanged.
+fn merkle_levels<'a>(
+    leaves: impl IntoIterator<Item = &'a ExecutionDigests>,
+) -> Vec<Vec<CheckpointDigest>> {
+    let mut levels = vec![leaves.into_iter().map(leaf_hash).collect::<Vec<_>>()];
+    while levels[levels.len() - 1].len() > 1 {
+        let next = levels[levels.len() - 1]
+            .chunks(2)
+            .map(|pair| match pair {
+                [left, right] => node_hash(left, right),
+                [last] => *last,
+                _ => unreachable!(),
+            })
+            .collect();
+        levels.push(next);
+    }
+    levels
+}
+
+/// The root of the Merkle tree of `leaves`, in their order. The tree of no leaves has the hash
+/// of nothing as its root.
+pub fn merkle_root<'a>(leaves: impl IntoIterator<Item = &'a ExecutionDigests>) -> CheckpointDigest {
+    match merkle_levels(leaves).pop().and_then(|mut root| root.pop()) {
+        Some(root) => root,
+        None => Sha3_256::default().finalize().into(),
+    }
+}
+
+/// A proof that the execution digests of a transaction are in a checkpoint: their position in
+/// the contents of the checkpoint, and the hashes of the siblings of their path to the root of
+/// the tree of the contents, from the leaf up.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InclusionProof {
+    pub index: u64,
+    pub leaf_count: u64,
+    pub siblings: Vec<CheckpointDigest>,
+}
+
+impl InclusionProof {
+    /// The proof that `leaves[index]` is in the tree of `leaves`.
+    pub fn new(leaves: &[ExecutionDigests], index: usize) -> Option<Self> {
+        if index >= leaves.len() {
+            return None;
+        }
+        let levels = merkle_levels(leaves);
+        let mut siblings = Vec::new();
+        let mut position = index;
+        for level in &levels[..levels.len() - 1] {
+            if let Some(sibling) = level.get(position ^ 1) {
+                siblings.push(*sibling);
+            }
+            position /= 2;
+        }
+        Some(Self {
+            index: index as u64,
+            leaf_count: leaves.len() as u64,
+            siblings,
+        })
+    }
+
+    /// Check that `digests` are in the tree whose root is `root`.
+    pub fn verify(&self, digests: &ExecutionDigests, root: &CheckpointDigest) -> bool {
+        if self.index >= self.leaf_count {
+            return false;
+        }
+        let mut hash = leaf_hash(digests);
+        let mut siblings = self.siblings.iter();
+        let mut position = self.index;
+        let mut width = self.leaf_count;
+        while width > 1 {
+            if position % 2 == 1 {
+                match siblings.next() {
+                    Some(sibling) => hash = node_hash(sibling, &hash),
+                    None => return false,
+                }
+            } else if position + 1 < width {
+                match siblings.next() {
+                    Some(sibling) => hash = node_hash(&hash, sibling),
+                    None => return false,
+                }
+            }
+            position /= 2;
+            width = (width + 1) / 2;
+        }
+        siblings.next().is_none() && &hash == root
+    }
+}
+
+/// Check that `digests` are in the contents of `checkpoint`, which the caller verified.
+pub fn verify_inclusion(
+    checkpoint: &CheckpointSummary,
+    digests: &ExecutionDigests,
+    proof: &InclusionProof,
+) -> SuiResult {
+    fp_ensure!(
+        proof.verify(digests, &checkpoint.transactions_root),
+        SuiError::InvalidInclusionProof {
+            sequence_number: checkpoint.sequence_number
+        }
+    );
+    Ok(())
+}
+
+/// Check that `effects` are the effects of `transaction` in the contents of `checkpoint`,
+/// which the caller verified.
+pub fn verify_effects_inclusion(
+    checkpoint: &CheckpointSummary,
+    transaction: &TransactionDigest,
+    effects: &TransactionEffects,
+    proof: &InclusionProof,
+) -> SuiResult {
+    fp_ensure!(
+        &effects.transaction_digest == transaction,
+        SuiError::InvalidInclusionProof {
+            sequence_number: checkpoint.sequence_number
+        }
+    );
+    verify_inclusion(
+        checkpoint,
+        &ExecutionDigests::new(*transaction, effects.digest()),
+        proof,
+    )
+}
+
+/// Check that `effects` are the effects of `transaction` in the contents of `certificate`,
+/// certified by `committee`. The transaction is final if they are. This is the check of a client
+/// which trusts `committee`, such as an exchange crediting a deposit, and does not follow the
+/// chain of checkpoints.
+pub fn verify_finality(
+    committee: &Committee,
+    certificate: &CertifiedCheckpoint,
+    transaction: &TransactionDigest,
+    effects: &TransactionEffects,
+    proof: &InclusionProof,
+) -> SuiResult {
+    certificate.verify(committee)?;
+    verify_effects_inclusion(&certificate.checkpoint, transaction, effects, proof)
+}
+
+/// The committee `handoff` hands `committee` off to, if a quorum of `committee` certified it.
+pub fn verify_handoff(
+    committee: &Committee,
+    handoff: &CertifiedEpochHandoff,
+) -> SuiResult<Committee> {
+    handoff.verify(committee)?;
+    let next_committee = &handoff.handoff.next_committee;
+    // The committee was built by the authorities which signed it, but its derived fields are
+    // not trusted to be consistent.
+    let total_votes: u128 = next_committee
+        .voting_rights
+        .values()
+        .map(|votes| *votes as u128)
+        .sum();
+    fp_ensure!(
+        next_committee.total_votes as u128 == total_votes
+            && next_committee.total_votes > 0
+            && next_committee
+                .voting_rights
+                .keys()
+                .all(|name| next_committee.protocol_keys.contains_key(name)),
+        SuiError::InvalidEpochHandoff {
+            epoch: committee.epoch(),
+            error: "Inconsistent next committee".to_string(),
+        }
+    );
+    Ok(next_committee.clone())
+}
+
+/// The committee of the epoch after the last of `handoffs`, which hand off each epoch to the
+/// next, from the committee of the first one, such as the genesis committee.
+pub fn verify_committee_chain<'a>(
+    mut committee: Committee,
+    handoffs: impl IntoIterator<Item = &'a CertifiedEpochHandoff>,
+) -> SuiResult<Committee> {
+    for handoff in handoffs {
+        committee = verify_handoff(&committee, handoff)?;
+    }
+    Ok(committee)
+}
+
+/// A light client of a committee it trusts, following the chain of checkpoints certified by
+/// the committee.
+pub struct LightClient {
+    committee: Committee,
+    /// The last checkpoint verified, or trusted to begin with.
+    latest: Option<CheckpointSummary>,
+}
+
+impl LightClient {
+    /// A client following the checkpoints of `committee` from the first one on.
+    pub fn new(committee: Committee) -> Self {
+        Self {
+            committee,
+            latest: None,
+        }
+    }
+
+    /// A client following the checkpoints of `committee` from `checkpoint`, which it trusts,
+    /// for example because it was verified earlier, along with the checkpoints before it.
+    pub fn from_checkpoint(committee: Committee, checkpoint: CheckpointSummary) -> Self {
+        Self {
+            committee,
+            latest: Some(checkpoint),
+        }
+    }
+
+    pub fn committee(&self) -> &Committee {
+        &self.committee
+    }
+
+    /// Move on to the committee of the next epoch, if `handoff` is certified by the committee.
+    /// The checkpoints which follow are certified by the next committee.
+    pub fn verify_handoff(&mut self, handoff: &CertifiedEpochHandoff) -> SuiResult {
+        self.committee = verify_handoff(&self.committee, handoff)?;
+        Ok(())
+    }
+
+    /// The last checkpoint verified, or the checkpoint the client was trusted with.
+    pub fn latest_checkpoint(&self) -> Option<&CheckpointSummary> {
+        self.latest.as_ref()
+    }
+
+    /// The sequence number of the next checkpoint of the chain.
+    pub fn next_sequence_number(&self) -> CheckpointSequenceNumber {
+        self.latest
+            .as_ref()
+            .map_or(0, |checkpoint| checkpoint.sequence_number + 1)
+    }
+
+    /// Check that `certificate` is the next checkpoint of the chain, certified by the committee,
+    /// and make it the latest checkpoint.
+    pub fn verify_checkpoint(&mut self, certificate: &CertifiedCheckpoint) -> SuiResult {
+        let checkpoint = &certificate.checkpoint;
+        let previous_digest = self.latest.as_ref().map(|latest| latest.digest());
+        let chain_error = |error: &str| SuiError::InvalidCheckpointChain {
+            sequence_number: checkpoint.sequence_number,
+            error: error.to_string(),
+        };
+        fp_ensure!(
+            checkpoint.sequence_number == self.next_sequence_number(),
+            chain_error("Unexpected sequence number")
+        );
+        fp_ensure!(
+            checkpoint.previous_digest == previous_digest,
+            chain_error("The previous digest is not the digest of the latest checkpoint")
+        );
+        certificate.verify(&self.committee)?;
+        self.latest = Some(checkpoint.clone());
+        Ok(())
+    }
+
+    /// Verify `certificates` in turn, up to the first invalid one.
+    pub fn verify_checkpoints<'a>(
+        &mut self,
+        certificates: impl IntoIterator<Item = &'a CertifiedCheckpoint>,
+    ) -> SuiResult {
+        for certificate in certificates {
+            self.verify_checkpoint(certificate)?;
+        }
+        Ok(())
+    }
+
+    /// Check that `digests` are in the contents of the latest checkpoint. The transaction is
+    /// final if they are.
+    pub fn verify_inclusion(
+        &self,
+        digests: &ExecutionDigests,
+        proof: &InclusionProof,
+    ) -> SuiResult {
+        match &self.latest {
+            Some(latest) => verify_inclusion(latest, digests, proof),
+            None => Err(SuiError::InvalidInclusionProof { sequence_number: 0 }),
+        }
+    }
+}
@@ -0,0 +1,91 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use move_core_types::{
+    ident_str,
+    identifier::IdentStr,
+    language_storage::{StructTag, TypeTag},
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    balance::Balance,
+    base_types::{ObjectID, SequenceNumber, SuiAddress, TransactionDigest},
+    committee::EpochId,
+    gas_coin::GAS,
+    id::VersionedID,
+    object::{Data, MoveObject, Object, Owner},
+    SUI_FRAMEWORK_ADDRESS,
+};
+
+pub const LOCKED_COIN_MODULE_NAME: &IdentStr = ident_str!("LockedCoin");
+pub const LOCKED_COIN_STRUCT_NAME: &IdentStr = LOCKED_COIN_MODULE_NAME;
+
+/// Rust version of the Move Sui::LockedCoin::LockedCoin type
+#[derive(Debug, Serialize, Deserialize, Clone, Eq, PartialEq)]
+pub struct LockedCoin {
+    pub id: VersionedID,
+    pub balance: Balance,
+    pub withdrawn: u64,
+    pub start_epoch: EpochId,
+    pub end_epoch: EpochId,
+}
+
+impl LockedCoin {
+    pub fn new(id: VersionedID, value: u64, start_epoch: EpochId, end_epoch: EpochId) -> Self {
+        Self {
+            id,
+            balance: Balance::new(value),
+            withdrawn: 0,
+            start_epoch,
+            end_epoch,
+        }
+    }
+
+    pub fn type_(type_param: StructTag) -> StructTag {
+        StructTag {
+            address: SUI_FRAMEWORK_ADDRESS,
+            name: LOCKED_COIN_STRUCT_NAME.to_owned(),
+            module: LOCKED_COIN_MODULE_NAME.to_owned(),
+            type_params: vec![TypeTag::Struct(type_param)],
+        }
+    }
+
+    pub fn id(&self) -> &ObjectID {
+        self.id.object_id()
+    }
+
+    pub fn value(&self) -> u64 {
+        self.balance.value()
+    }
+
+    pub fn to_bcs_bytes(&self) -> Vec<u8> {
+        bcs::to_bytes(&self).unwrap()
+    }
+
+    /// A genesis object of `value` SUI locked under a schedule vesting from `start_epoch` to
+    /// `end_epoch`, owned by `owner`.
+    pub fn new_sui_object(
+        id: ObjectID,
+        owner: SuiAddress,
+        value: u64,
+        start_epoch: EpochId,
+        end_epoch: EpochId,
+    ) -> Object {
+        let locked = Self::new(
+            VersionedID::new(id, SequenceNumber::new()),
+            value,
+            start_epoch,
+            end_epoch,
+        );
+        Object {
+            owner: Owner::AddressOwner(owner),
+            data: Data::Move(MoveObject::new(
+                Self::type_(GAS::type_()),
+                locked.to_bcs_bytes(),
+            )),
+            previous_transaction: TransactionDigest::genesis(),
+            storage_rebate: 0,
+        }
+    }
+}
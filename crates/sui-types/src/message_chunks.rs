@@ -0,0 +1,70 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Messages too large to send to a validator whole, such as a transaction publishing a large
+//! package, are sent as a stream of chunks of their serialized bytes instead, which the validator
+//! joins back up to the size its protocol version accepts.
+
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+use crate::error::{SuiError, SuiResult};
+
+#[cfg(test)]
+#[path = "unit_tests/message_chunks_tests.rs"]
+mod message_chunks_tests;
+
+/// The size of the chunks a message is sent in.
+pub const MESSAGE_CHUNK_SIZE: usize = 1024 * 1024;
+/// The largest message a client sends whole, well under the size validators decode in a single
+/// message. The messages larger than this are sent in chunks.
+pub const MAX_UNCHUNKED_MESSAGE_SIZE: usize = 2 * 1024 * 1024;
+
+/// A piece of the serialized bytes of a message, in the order they are sent.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MessageChunk {
+    pub bytes: Vec<u8>,
+}
+
+impl MessageChunk {
+    /// The chunks of `message`, of `chunk_size` bytes but for the last one.
+    pub fn split<T: Serialize>(message: &T, chunk_size: usize) -> SuiResult<Vec<Self>> {
+        let bytes = bincode::serialize(message).map_err(|_| SuiError::InvalidDecoding)?;
+        Ok(bytes
+            .chunks(chunk_size.max(1))
+            .map(|bytes| Self {
+                bytes: bytes.to_vec(),
+            })
+            .collect())
+    }
+}
+
+/// Joins the chunks of a message as they are received, refusing them as soon as they add up to
+/// more than `max_size` bytes.
+pub struct MessageChunkJoiner {
+    bytes: Vec<u8>,
+    max_size: usize,
+}
+
+impl MessageChunkJoiner {
+    pub fn new(max_size: usize) -> Self {
+        Self {
+            bytes: Vec::new(),
+            max_size,
+        }
+    }
+
+    pub fn push(&mut self, chunk: MessageChunk) -> SuiResult {
+        if self.bytes.len() + chunk.bytes.len() > self.max_size {
+            return Err(SuiError::OversizedChunkedMessage {
+                max_size: self.max_size as u64,
+            });
+        }
+        self.bytes.extend(chunk.bytes);
+        Ok(())
+    }
+
+    /// The message the chunks received make up.
+    pub fn finish<T: DeserializeOwned>(self) -> SuiResult<T> {
+        bincode::deserialize(&self.bytes).map_err(|_| SuiError::InvalidDecoding)
+    }
+}
@@ -6,13 +6,16 @@ use super::{base_types::*, batch::*, committee::Committee, error::*, event::Even
 use crate::committee::{EpochId, StakeUnit};
 use crate::crypto::{
     sha3_hash, AuthorityQuorumSignInfo, AuthoritySignInfo, AuthoritySignature, BcsSignable,
-    EmptySignInfo, Signable, Signature, VerificationObligation,
+    EmptySignInfo, GenericSignature, Signable, Signature, VerificationObligation,
 };
-use crate::gas::GasCostSummary;
+use crate::gas::{GasCostSummary, GasProfile};
+use crate::intent::{intent_message, IntentScope, IntentScoped};
 use crate::messages_checkpoint::CheckpointFragment;
 use crate::object::{Object, ObjectFormatOptions, Owner, OBJECT_START_VERSION};
+use crate::object_change::OwnerChange;
 use crate::SUI_SYSTEM_STATE_OBJECT_ID;
 use base64ct::Encoding;
+use digest::Digest;
 use itertools::Either;
 use move_binary_format::access::ModuleAccess;
 use move_binary_format::CompiledModule;
@@ -26,6 +29,7 @@ use serde::{Deserialize, Serialize};
 use serde_name::{DeserializeNameAdapter, SerializeNameAdapter};
 use serde_with::serde_as;
 use serde_with::Bytes;
+use sha3::Sha3_256;
 use std::fmt::Write;
 use std::fmt::{Display, Formatter};
 use std::{
@@ -36,6 +40,10 @@ use std::{
 #[path = "unit_tests/messages_tests.rs"]
 mod messages_tests;
 
+#[cfg(test)]
+#[path = "unit_tests/bcs_snapshot_tests.rs"]
+mod bcs_snapshot_tests;
+
 #[derive(Debug, PartialEq, Eq, Hash, Clone, Serialize, Deserialize)]
 pub enum CallArg {
     // contains no structs or objects
@@ -45,6 +53,10 @@ pub enum CallArg {
     ImmOrOwnedObject(ObjectRef),
     // A Move object that's shared and mutable.
     SharedObject(ObjectID),
+    // A shared Move object the call only reads, through an immutable reference. Transactions
+    // reading a shared object are assigned the same version of it, so they do not contend for it
+    // as the transactions mutating it do.
+    ReadOnlySharedObject(ObjectID),
 }
 
 #[derive(Debug, PartialEq, Eq, Hash, Clone, Serialize, Deserialize)]
@@ -74,6 +86,19 @@ pub struct MoveModulePublish {
     pub modules: Vec<Vec<u8>>,
 }
 
+/// A new version of a published package, made of `modules`, which the validators publish at a
+/// new ID if its modules are compatible with the ones of `package`.
+#[serde_as]
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Serialize, Deserialize)]
+pub struct MoveModuleUpgrade {
+    /// The latest version of the package, which the upgrade replaces.
+    pub package: ObjectID,
+    /// The `UpgradeCap` of the package, owned by the sender.
+    pub upgrade_cap: ObjectRef,
+    #[serde_as(as = "Vec<Bytes>")]
+    pub modules: Vec<Vec<u8>>,
+}
+
 #[derive(Debug, PartialEq, Eq, Hash, Clone, Serialize, Deserialize)]
 pub struct TransferSui {
     pub recipient: SuiAddress,
@@ -88,6 +113,37 @@ pub struct ChangeEpoch {
     pub storage_charge: u64,
     /// The total amount of gas charged for computation during the epoch.
     pub computation_charge: u64,
+    /// All the framework packages, if the epoch change upgrades them as the validators voted,
+    /// or none.
+    pub framework_packages: Vec<SystemPackage>,
+}
+
+impl ChangeEpoch {
+    /// The digest of the framework packages the epoch change upgrades to, or none.
+    pub fn framework_digest(&self) -> Vec<u8> {
+        if self.framework_packages.is_empty() {
+            Vec::new()
+        } else {
+            framework_digest(&self.framework_packages).to_vec()
+        }
+    }
+}
+
+/// The bytecode of a framework package, as an epoch change upgrades the package on chain to.
+#[serde_as]
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Serialize, Deserialize)]
+pub struct SystemPackage {
+    pub id: ObjectID,
+    #[serde_as(as = "Vec<Bytes>")]
+    pub modules: Vec<Vec<u8>>,
+}
+
+/// The digest validators vote for upgrading the framework with: of the bytecode of all the
+/// framework packages after the upgrade.
+pub fn framework_digest(packages: &[SystemPackage]) -> [u8; 32] {
+    let mut hasher = Sha3_256::default();
+    hasher.update(bcs::to_bytes(packages).expect("Serializing packages cannot fail"));
+    hasher.finalize().into()
 }
 
 #[derive(Debug, PartialEq, Eq, Hash, Clone, Serialize, Deserialize)]
@@ -109,6 +165,8 @@ pub enum SingleTransactionKind {
     /// A validator will not sign a transaction of this kind from outside. It only
     /// signs internally during epoch changes.
     ChangeEpoch(ChangeEpoch),
+    /// Publish a new version of a package, with the `UpgradeCap` of the package
+    Upgrade(MoveModuleUpgrade),
     // .. more transaction types go here
 }
 
@@ -122,7 +180,22 @@ impl SingleTransactionKind {
             Self::Call(MoveCall { arguments, .. }) => {
                 Either::Left(arguments.iter().filter_map(|arg| match arg {
                     CallArg::Pure(_) | CallArg::ImmOrOwnedObject(_) => None,
-                    CallArg::SharedObject(id) => Some(id),
+                    CallArg::SharedObject(id) | CallArg::ReadOnlySharedObject(id) => Some(id),
+                }))
+            }
+            _ => Either::Right(std::iter::empty()),
+        }
+    }
+
+    /// The shared objects among `shared_input_objects` which the transaction only reads.
+    pub fn read_only_shared_input_objects(&self) -> impl Iterator<Item = &ObjectID> {
+        match &self {
+            Self::Call(MoveCall { arguments, .. }) => {
+                Either::Left(arguments.iter().filter_map(|arg| match arg {
+                    CallArg::ReadOnlySharedObject(id) => Some(id),
+                    CallArg::Pure(_) | CallArg::ImmOrOwnedObject(_) | CallArg::SharedObject(_) => {
+                        None
+                    }
                 }))
             }
             _ => Either::Right(std::iter::empty()),
@@ -148,6 +221,9 @@ impl SingleTransactionKind {
                         Some(InputObjectKind::ImmOrOwnedMoveObject(*object_ref))
                     }
                     CallArg::SharedObject(id) => Some(InputObjectKind::SharedMoveObject(*id)),
+                    CallArg::ReadOnlySharedObject(id) => {
+                        Some(InputObjectKind::ReadOnlySharedMoveObject(*id))
+                    }
                 })
                 .chain([InputObjectKind::MovePackage(package.0)])
                 .collect(),
@@ -169,13 +245,38 @@ impl SingleTransactionKind {
                     .collect::<Vec<_>>();
                 Transaction::input_objects_in_compiled_modules(&compiled_modules)
             }
+            Self::Upgrade(MoveModuleUpgrade {
+                package,
+                upgrade_cap,
+                modules,
+            }) => {
+                // As for publishing, the dependencies of the new version are implicit inputs,
+                // along with the version it replaces, whose modules it is checked against.
+                let compiled_modules = modules
+                    .iter()
+                    .filter_map(|bytes| CompiledModule::deserialize(bytes).ok())
+                    .collect::<Vec<_>>();
+                let mut inputs = Transaction::input_objects_in_compiled_modules(&compiled_modules);
+                if !inputs.contains(&InputObjectKind::MovePackage(*package)) {
+                    inputs.push(InputObjectKind::MovePackage(*package));
+                }
+                inputs.push(InputObjectKind::ImmOrOwnedMoveObject(*upgrade_cap));
+                inputs
+            }
             Self::TransferSui(_) => {
                 vec![]
             }
-            Self::ChangeEpoch(_) => {
-                vec![InputObjectKind::SharedMoveObject(
+            Self::ChangeEpoch(change_epoch) => {
+                let packages = change_epoch
+                    .framework_packages
+                    .iter()
+                    .map(|package| InputObjectKind::MovePackage(package.id));
+                [InputObjectKind::SharedMoveObject(
                     SUI_SYSTEM_STATE_OBJECT_ID,
                 )]
+                .into_iter()
+                .chain(packages)
+                .collect()
             }
         };
         // Ensure that there are no duplicate inputs. This cannot be removed because:
@@ -217,6 +318,11 @@ impl Display for SingleTransactionKind {
             Self::Publish(_p) => {
                 writeln!(writer, "Transaction Kind : Publish")?;
             }
+            Self::Upgrade(u) => {
+                writeln!(writer, "Transaction Kind : Upgrade")?;
+                writeln!(writer, "Package ID : {}", u.package)?;
+                writeln!(writer, "Upgrade Cap ID : {}", u.upgrade_cap.0)?;
+            }
             Self::Call(c) => {
                 writeln!(writer, "Transaction Kind : Call")?;
                 writeln!(writer, "Package ID : {}", c.package.0.to_hex_literal())?;
@@ -230,6 +336,9 @@ impl Display for SingleTransactionKind {
                 writeln!(writer, "New epoch ID: {}", e.epoch)?;
                 writeln!(writer, "Storage gas reward: {}", e.storage_charge)?;
                 writeln!(writer, "Computation gas reward: {}", e.computation_charge)?;
+                for package in &e.framework_packages {
+                    writeln!(writer, "Framework package upgraded: {}", package.id)?;
+                }
             }
         }
         write!(f, "{}", writer)
@@ -275,6 +384,17 @@ impl TransactionKind {
             TransactionKind::Single(SingleTransactionKind::ChangeEpoch(_))
         )
     }
+
+    /// Whether the transaction is an epoch change upgrading the framework packages.
+    pub fn upgrades_framework(&self) -> bool {
+        matches!(
+            self,
+            TransactionKind::Single(SingleTransactionKind::ChangeEpoch(ChangeEpoch {
+                framework_packages,
+                ..
+            })) if !framework_packages.is_empty()
+        )
+    }
 }
 
 impl Display for TransactionKind {
@@ -382,6 +502,22 @@ where
         Self::new(kind, sender, gas_payment, gas_budget)
     }
 
+    pub fn new_upgrade(
+        sender: SuiAddress,
+        gas_payment: ObjectRef,
+        package: ObjectID,
+        upgrade_cap: ObjectRef,
+        modules: Vec<Vec<u8>>,
+        gas_budget: u64,
+    ) -> Self {
+        let kind = TransactionKind::Single(SingleTransactionKind::Upgrade(MoveModuleUpgrade {
+            package,
+            upgrade_cap,
+            modules,
+        }));
+        Self::new(kind, sender, gas_payment, gas_budget)
+    }
+
     /// Returns the transaction kind as a &str (variant name, no fields)
     pub fn kind_as_str(&self) -> &'static str {
         self.kind.variant_name()
@@ -401,6 +537,11 @@ where
         writer
     }
 
+    /// The bytes the sender signs: the intent of transactions followed by `to_bytes()`.
+    pub fn to_intent_bytes(&self) -> Vec<u8> {
+        intent_message(self)
+    }
+
     pub fn to_base64(&self) -> String {
         base64ct::Base64::encode_string(&self.to_bytes())
     }
@@ -409,6 +550,29 @@ where
         &self.gas_payment
     }
 
+    pub fn contains_shared_object(&self) -> bool {
+        self.shared_input_objects().next().is_some()
+    }
+
+    pub fn shared_input_objects(&self) -> impl Iterator<Item = &ObjectID> {
+        match &self.kind {
+            TransactionKind::Single(s) => Either::Left(s.shared_input_objects()),
+            TransactionKind::Batch(b) => {
+                Either::Right(b.iter().flat_map(|kind| kind.shared_input_objects()))
+            }
+        }
+    }
+
+    pub fn read_only_shared_input_objects(&self) -> impl Iterator<Item = &ObjectID> {
+        match &self.kind {
+            TransactionKind::Single(s) => Either::Left(s.read_only_shared_input_objects()),
+            TransactionKind::Batch(b) => Either::Right(
+                b.iter()
+                    .flat_map(|kind| kind.read_only_shared_input_objects()),
+            ),
+        }
+    }
+
     pub fn input_objects(&self) -> SuiResult<Vec<InputObjectKind>> {
         let mut inputs = match &self.kind {
             TransactionKind::Single(s) => s.input_objects()?,
@@ -422,6 +586,13 @@ where
                                 .to_owned(),
                         }
                     );
+                    fp_ensure!(
+                        !matches!(kind, &SingleTransactionKind::Upgrade(..)),
+                        SuiError::InvalidBatchTransaction {
+                            error: "Upgrade transaction is not allowed in Batch Transaction"
+                                .to_owned(),
+                        }
+                    );
                     let sub = kind.input_objects()?;
                     result.extend(sub);
                 }
@@ -435,6 +606,38 @@ where
         }
         Ok(inputs)
     }
+
+    /// The references to the owned and immutable objects the transaction takes, its gas payment
+    /// included, for a client to bring up to date before signing the transaction.
+    pub fn object_refs_mut(&mut self) -> Vec<&mut ObjectRef> {
+        let mut refs = vec![&mut self.gas_payment];
+        let kinds = match &mut self.kind {
+            TransactionKind::Single(kind) => Either::Left(std::iter::once(kind)),
+            TransactionKind::Batch(kinds) => Either::Right(kinds.iter_mut()),
+        };
+        for kind in kinds {
+            match kind {
+                SingleTransactionKind::TransferCoin(TransferCoin { object_ref, .. }) => {
+                    refs.push(object_ref)
+                }
+                SingleTransactionKind::Upgrade(MoveModuleUpgrade { upgrade_cap, .. }) => {
+                    refs.push(upgrade_cap)
+                }
+                SingleTransactionKind::Call(MoveCall { arguments, .. }) => {
+                    refs.extend(arguments.iter_mut().filter_map(|argument| match argument {
+                        CallArg::ImmOrOwnedObject(object_ref) => Some(object_ref),
+                        CallArg::Pure(_)
+                        | CallArg::SharedObject(_)
+                        | CallArg::ReadOnlySharedObject(_) => None,
+                    }))
+                }
+                SingleTransactionKind::Publish(_)
+                | SingleTransactionKind::TransferSui(_)
+                | SingleTransactionKind::ChangeEpoch(_) => (),
+            }
+        }
+        refs
+    }
 }
 
 /// A transaction signed by a client, optionally signed by an authority (depending on `S`).
@@ -456,7 +659,7 @@ pub struct TransactionEnvelope<S> {
 
     pub data: TransactionData,
     /// tx_signature is signed by the transaction sender, applied on `data`.
-    pub tx_signature: Signature,
+    pub tx_signature: GenericSignature,
     /// authority signature information, if available, is signed by an authority, applied on `data`.
     pub auth_sign_info: S,
     // Note: If any new field is added here, make sure the Hash and PartialEq
@@ -483,16 +686,8 @@ impl<S> TransactionEnvelope<S> {
             return Ok(());
         }
 
-        let (message, signature, public_key) = self
-            .tx_signature
-            .get_verification_inputs(&self.data, self.data.sender)?;
-        let idx = obligation.messages.len();
-        obligation.messages.push(message);
-        let key = obligation.lookup_public_key(&public_key)?;
-        obligation.public_keys.push(key);
-        obligation.signatures.push(signature);
-        obligation.message_index.push(idx);
-        Ok(())
+        self.tx_signature
+            .add_to_verification_obligation(&self.data, self.data.sender, obligation)
     }
 
     pub fn sender_address(&self) -> SuiAddress {
@@ -504,16 +699,15 @@ impl<S> TransactionEnvelope<S> {
     }
 
     pub fn contains_shared_object(&self) -> bool {
-        self.shared_input_objects().next().is_some()
+        self.data.contains_shared_object()
     }
 
     pub fn shared_input_objects(&self) -> impl Iterator<Item = &ObjectID> {
-        match &self.data.kind {
-            TransactionKind::Single(s) => Either::Left(s.shared_input_objects()),
-            TransactionKind::Batch(b) => {
-                Either::Right(b.iter().flat_map(|kind| kind.shared_input_objects()))
-            }
-        }
+        self.data.shared_input_objects()
+    }
+
+    pub fn read_only_shared_input_objects(&self) -> impl Iterator<Item = &ObjectID> {
+        self.data.read_only_shared_input_objects()
     }
 
     /// Get the transaction digest and write it to the cache
@@ -592,12 +786,12 @@ impl Transaction {
         Self::new(data, signature)
     }
 
-    pub fn new(data: TransactionData, signature: Signature) -> Self {
+    pub fn new(data: TransactionData, signature: impl Into<GenericSignature>) -> Self {
         Self {
             transaction_digest: OnceCell::new(),
             is_verified: false,
             data,
-            tx_signature: signature,
+            tx_signature: signature.into(),
             auth_sign_info: EmptySignInfo {},
         }
     }
@@ -645,6 +839,7 @@ impl SignedTransaction {
         next_epoch: EpochId,
         storage_charge: u64,
         computation_charge: u64,
+        framework_packages: Vec<SystemPackage>,
         authority: AuthorityName,
         secret: &dyn signature::Signer<AuthoritySignature>,
     ) -> Self {
@@ -652,6 +847,7 @@ impl SignedTransaction {
             epoch: next_epoch,
             storage_charge,
             computation_charge,
+            framework_packages,
         }));
         // For the ChangeEpoch transaction, we do not care about the sender and the gas.
         let data = TransactionData::new(
@@ -665,7 +861,7 @@ impl SignedTransaction {
             transaction_digest: OnceCell::new(),
             is_verified: false,
             data,
-            tx_signature: Signature::new_empty(),
+            tx_signature: Signature::new_empty().into(),
             auth_sign_info: AuthoritySignInfo {
                 epoch: next_epoch,
                 authority,
@@ -679,9 +875,8 @@ impl SignedTransaction {
         self.verify_signature()?;
         let weight = committee.weight(&self.auth_sign_info.authority);
         fp_ensure!(weight > 0, SuiError::UnknownSigner);
-        self.auth_sign_info
-            .signature
-            .verify(&self.data, self.auth_sign_info.authority)?;
+        let key = committee.protocol_key(&self.auth_sign_info.authority)?;
+        self.auth_sign_info.signature.verify(&self.data, &key)?;
         Ok(weight)
     }
 
@@ -837,6 +1032,23 @@ impl From<TransactionDigest> for TransactionInfoRequest {
     }
 }
 
+/// A request to execute a transaction without committing its effects, so that the sender can
+/// see them and the gas the transaction uses before signing it.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct DryRunTransactionRequest {
+    pub data: TransactionData,
+    /// Whether to profile where the gas of the transaction goes.
+    pub profile_gas: bool,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct DryRunTransactionResponse {
+    /// The effects the transaction would have on the current state of the authority.
+    pub effects: TransactionEffects,
+    /// Where the gas of the transaction went, if it was profiled.
+    pub gas_profile: Option<GasProfile>,
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct TransactionInfoResponse {
     // The signed transaction response to handle_transaction
@@ -954,12 +1166,20 @@ pub struct TransactionEffects {
     pub deleted: Vec<ObjectRef>,
     // Object refs of objects now wrapped in other objects.
     pub wrapped: Vec<ObjectRef>,
+    // Object refs of objects that were wrapped in other objects in the past, and got
+    // unwrapped and then deleted in this transaction. These are not included in `deleted`.
+    pub unwrapped_then_deleted: Vec<ObjectRef>,
+    /// The objects, among the mutated ones, whose owner changed, with their previous owner and
+    /// their new one.
+    pub owner_changes: Vec<OwnerChange>,
     // The updated gas object reference. Have a dedicated field for convenient access.
     // It's also included in mutated.
     pub gas_object: (ObjectRef, Owner),
     /// The events emitted during execution. Note that only successful transactions emit events
     pub events: Vec<Event>,
-    /// The set of transaction digests this transaction depends on.
+    /// The set of transaction digests this transaction depends on: the transactions which last
+    /// wrote the objects it read, among its inputs or loaded during execution. Executing the
+    /// dependencies first executes certificates in causal order.
     pub dependencies: Vec<TransactionDigest>,
 }
 
@@ -987,6 +1207,7 @@ impl TransactionEffects {
             && self
                 .deleted
                 .iter()
+                .chain(self.unwrapped_then_deleted.iter())
                 .any(|(id, seq, _)| *id == obj_ref.0 && seq.increment() == obj_ref.1)
         {
             return true;
@@ -1036,6 +1257,10 @@ impl TransactionEffects {
 
 impl BcsSignable for TransactionEffects {}
 
+impl IntentScoped for TransactionEffects {
+    const INTENT_SCOPE: IntentScope = IntentScope::TransactionEffects;
+}
+
 impl Display for TransactionEffects {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         let mut writer = String::new();
@@ -1070,6 +1295,18 @@ impl Display for TransactionEffects {
                 writeln!(writer, "  - ID: {} , Owner: {}", id, owner)?;
             }
         }
+        if !self.unwrapped_then_deleted.is_empty() {
+            writeln!(writer, "Unwrapped Then Deleted Objects:")?;
+            for (id, _, _) in &self.unwrapped_then_deleted {
+                writeln!(writer, "  - ID: {}", id)?;
+            }
+        }
+        if !self.dependencies.is_empty() {
+            writeln!(writer, "Dependencies:")?;
+            for digest in &self.dependencies {
+                writeln!(writer, "  - {:?}", digest)?;
+            }
+        }
         write!(f, "{}", writer)
     }
 }
@@ -1103,6 +1340,8 @@ pub enum InputObjectKind {
     ImmOrOwnedMoveObject(ObjectRef),
     // A Move object that's shared and mutable.
     SharedMoveObject(ObjectID),
+    // A Move object that's shared, and only read by the transaction.
+    ReadOnlySharedMoveObject(ObjectID),
 }
 
 impl InputObjectKind {
@@ -1110,7 +1349,7 @@ impl InputObjectKind {
         match self {
             Self::MovePackage(id) => *id,
             Self::ImmOrOwnedMoveObject((id, _, _)) => *id,
-            Self::SharedMoveObject(id) => *id,
+            Self::SharedMoveObject(id) | Self::ReadOnlySharedMoveObject(id) => *id,
         }
     }
 
@@ -1118,23 +1357,33 @@ impl InputObjectKind {
         match self {
             Self::MovePackage(..) => OBJECT_START_VERSION,
             Self::ImmOrOwnedMoveObject((_, version, _)) => *version,
-            Self::SharedMoveObject(..) => OBJECT_START_VERSION,
+            Self::SharedMoveObject(..) | Self::ReadOnlySharedMoveObject(..) => OBJECT_START_VERSION,
         }
     }
 
+    /// Whether the object is shared, and so is assigned its version by consensus.
+    pub fn is_shared_object(&self) -> bool {
+        matches!(
+            self,
+            Self::SharedMoveObject(_) | Self::ReadOnlySharedMoveObject(_)
+        )
+    }
+
     pub fn object_not_found_error(&self) -> SuiError {
         match *self {
             Self::MovePackage(package_id) => SuiError::DependentPackageNotFound { package_id },
             Self::ImmOrOwnedMoveObject((object_id, _, _)) => SuiError::ObjectNotFound { object_id },
-            Self::SharedMoveObject(object_id) => SuiError::ObjectNotFound { object_id },
+            Self::SharedMoveObject(object_id) | Self::ReadOnlySharedMoveObject(object_id) => {
+                SuiError::ObjectNotFound { object_id }
+            }
         }
     }
 }
 pub struct SignatureAggregator<'a> {
     committee: &'a Committee,
     weight: StakeUnit,
-    used_authorities: HashSet<AuthorityName>,
-    partial: CertifiedTransaction,
+    transaction: Transaction,
+    signatures: Vec<(AuthorityName, AuthoritySignature)>,
 }
 
 impl<'a> SignatureAggregator<'a> {
@@ -1149,12 +1398,13 @@ impl<'a> SignatureAggregator<'a> {
         Self {
             committee,
             weight: 0,
-            used_authorities: HashSet::new(),
-            partial: CertifiedTransaction::new(transaction),
+            transaction,
+            signatures: Vec::new(),
         }
     }
 
-    /// Try to append a signature to a (partial) certificate. Returns Some(certificate) if a quorum was reached.
+    /// Try to append a signature to a (partial) certificate. Returns Some(certificate), with the
+    /// signatures aggregated so far, if a quorum was reached.
     /// The resulting final certificate is guaranteed to be valid in the sense of `check` below.
     /// Returns an error if the signed value cannot be aggregated.
     pub fn append(
@@ -1162,25 +1412,26 @@ impl<'a> SignatureAggregator<'a> {
         authority: AuthorityName,
         signature: AuthoritySignature,
     ) -> Result<Option<CertifiedTransaction>, SuiError> {
-        signature.verify(&self.partial.data, authority)?;
+        let key = self.committee.protocol_key(&authority)?;
+        signature.verify(&self.transaction.data, &key)?;
         // Check that each authority only appears once.
         fp_ensure!(
-            !self.used_authorities.contains(&authority),
+            !self.signatures.iter().any(|(name, _)| *name == authority),
             SuiError::CertificateAuthorityReuse
         );
-        self.used_authorities.insert(authority);
         // Update weight.
         let voting_rights = self.committee.weight(&authority);
         fp_ensure!(voting_rights > 0, SuiError::UnknownSigner);
         self.weight += voting_rights;
-        // Update certificate.
-        self.partial
-            .auth_sign_info
-            .signatures
-            .push((authority, signature));
+        self.signatures.push((authority, signature));
 
         if self.weight >= self.committee.quorum_threshold() {
-            Ok(Some(self.partial.clone()))
+            CertifiedTransaction::new_with_signatures(
+                self.transaction.clone(),
+                &self.signatures,
+                self.committee,
+            )
+            .map(Some)
         } else {
             Ok(None)
         }
@@ -1188,31 +1439,20 @@ impl<'a> SignatureAggregator<'a> {
 }
 
 impl CertifiedTransaction {
-    pub fn new(transaction: Transaction) -> CertifiedTransaction {
-        CertifiedTransaction {
-            transaction_digest: transaction.transaction_digest,
-            is_verified: false,
-            data: transaction.data,
-            tx_signature: transaction.tx_signature,
-            auth_sign_info: AuthorityQuorumSignInfo {
-                epoch: 0,
-                signatures: Vec::new(),
-            },
-        }
-    }
-
+    /// The certificate of `transaction` by the authorities of `committee` which signed it, with
+    /// their signatures aggregated. The signatures are not checked.
     pub fn new_with_signatures(
-        epoch: EpochId,
         transaction: Transaction,
-        signatures: Vec<(AuthorityName, AuthoritySignature)>,
-    ) -> CertifiedTransaction {
-        CertifiedTransaction {
+        signatures: &[(AuthorityName, AuthoritySignature)],
+        committee: &Committee,
+    ) -> SuiResult<CertifiedTransaction> {
+        Ok(CertifiedTransaction {
             transaction_digest: transaction.transaction_digest,
             is_verified: false,
             data: transaction.data,
             tx_signature: transaction.tx_signature,
-            auth_sign_info: AuthorityQuorumSignInfo { epoch, signatures },
-        }
+            auth_sign_info: AuthorityQuorumSignInfo::new(signatures, committee)?,
+        })
     }
 
     pub fn to_transaction(self) -> Transaction {
@@ -1234,70 +1474,21 @@ impl CertifiedTransaction {
         obligation.verify_all().map(|_| ())
     }
 
+    /// Check that the signers of the certificate hold a quorum of `committee`, and add their
+    /// aggregate signature and the signature of the sender to `obligation`. Certificates added
+    /// to the same obligation are checked together.
     pub fn add_to_verification_obligation(
         &self,
         committee: &Committee,
         obligation: &mut VerificationObligation,
     ) -> SuiResult<()> {
-        // Check epoch
-        fp_ensure!(
-            self.auth_sign_info.epoch == committee.epoch(),
-            SuiError::WrongEpoch {
-                expected_epoch: committee.epoch()
-            }
-        );
-
-        // First check the quorum is sufficient
-
-        let mut weight = 0;
-        let mut used_authorities = HashSet::new();
-        for (authority, _) in self.auth_sign_info.signatures.iter() {
-            // Check that each authority only appears once.
-            fp_ensure!(
-                !used_authorities.contains(authority),
-                SuiError::CertificateAuthorityReuse
-            );
-            used_authorities.insert(*authority);
-            // Update weight.
-            let voting_rights = committee.weight(authority);
-            fp_ensure!(voting_rights > 0, SuiError::UnknownSigner);
-            weight += voting_rights;
-        }
-        fp_ensure!(
-            weight >= committee.quorum_threshold(),
-            SuiError::CertificateRequiresQuorum
-        );
-
-        // Add the obligation of the transaction
-        self.add_tx_sig_to_verification_obligation(obligation)?;
-
-        // Create obligations for the committee signatures
-
-        let mut message = Vec::new();
-        self.data.write(&mut message);
-
-        let idx = obligation.messages.len();
-        obligation.messages.push(message);
-
-        for tuple in self.auth_sign_info.signatures.iter() {
-            let (authority, signature) = tuple;
-            // do we know, or can we build a valid public key?
-            match committee.expanded_keys.get(authority) {
-                Some(v) => obligation.public_keys.push(*v),
-                None => {
-                    let public_key = (*authority).try_into()?;
-                    obligation.public_keys.push(public_key);
-                }
-            }
-
-            // build a signature
-            obligation.signatures.push(signature.0);
-
-            // collect the message
-            obligation.message_index.push(idx);
-        }
-
-        Ok(())
+        self.auth_sign_info.add_to_verification_obligation(
+            &self.data,
+            committee,
+            committee.quorum_threshold(),
+            &mut obligation.aggregates,
+        )?;
+        self.add_tx_sig_to_verification_obligation(obligation)
     }
 }
 
@@ -1307,12 +1498,8 @@ impl Display for CertifiedTransaction {
         writeln!(writer, "Transaction Hash: {:?}", self.digest())?;
         writeln!(
             writer,
-            "Signed Authorities : {:?}",
-            self.auth_sign_info
-                .signatures
-                .iter()
-                .map(|(name, _)| name)
-                .collect::<Vec<_>>()
+            "Signed Authorities Map : {}",
+            hex::encode(&self.auth_sign_info.signers_map)
         )?;
         write!(writer, "{}", &self.data.kind)?;
         write!(f, "{}", writer)
@@ -1327,6 +1514,10 @@ impl ConfirmationTransaction {
 
 impl BcsSignable for TransactionData {}
 
+impl IntentScoped for TransactionData {
+    const INTENT_SCOPE: IntentScope = IntentScope::TransactionData;
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct ConsensusOutput {
     #[serde(with = "serde_bytes")]
@@ -1,16 +1,18 @@
 // Copyright (c) 2022, Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::collections::{BTreeMap, BTreeSet};
 
 use crate::base_types::ExecutionDigests;
-use crate::crypto::Signable;
+use crate::intent::{IntentScope, IntentScoped};
+use crate::light_client::{self, InclusionProof};
 use crate::messages::CertifiedTransaction;
+use crate::snapshot::LiveObjectsDigest;
 use crate::waypoint::{Waypoint, WaypointDiff};
 use crate::{
     base_types::AuthorityName,
-    committee::Committee,
-    crypto::{sha3_hash, AuthoritySignature, BcsSignable, VerificationObligation},
+    committee::{Committee, EpochId},
+    crypto::{sha3_hash, AuthorityQuorumSignInfo, AuthoritySignature, BcsSignable},
     error::SuiError,
 };
 use serde::{Deserialize, Serialize};
@@ -113,6 +115,14 @@ impl CheckpointRequest {
             detail: false,
         }
     }
+
+    /// Create a request for the handoff of an epoch to the next committee
+    pub fn epoch_handoff(epoch: EpochId) -> CheckpointRequest {
+        CheckpointRequest {
+            request_type: CheckpointRequestType::EpochHandoff(epoch),
+            detail: false,
+        }
+    }
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -125,6 +135,8 @@ pub enum CheckpointRequestType {
     SetCertificate(CertifiedCheckpoint, Option<CheckpointContents>),
     // Submit a consensus fragment to a node
     SetFragment(Box<CheckpointFragment>),
+    // Requests the handoff of an epoch to the next committee
+    EpochHandoff(EpochId),
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -153,6 +165,9 @@ pub enum AuthorityCheckpointInfo {
     },
     // Returns the requested checkpoint.
     Past(AuthenticatedCheckpoint),
+    // Returns the handoff of the requested epoch signed by the authority,
+    // if the epoch ended and the authority was a member of its committee.
+    EpochHandoff(Option<SignedEpochHandoff>),
 }
 
 // TODO: Rename to AuthenticatedCheckpointSummary
@@ -185,7 +200,9 @@ pub struct CheckpointSummary {
     pub waypoint: Box<Waypoint>, // Bigger structure, can live on heap.
     pub content_digest: CheckpointDigest,
     pub previous_digest: Option<CheckpointDigest>,
-    // TODO: add digest of previous checkpoint summary
+    /// The root of the Merkle tree of the contents, against which light clients check that a
+    /// transaction is in the checkpoint.
+    pub transactions_root: CheckpointDigest,
 }
 
 impl CheckpointSummary {
@@ -200,12 +217,14 @@ impl CheckpointSummary {
         });
 
         let content_digest = transactions.digest();
+        let transactions_root = transactions.merkle_root();
 
         Self {
             sequence_number,
             waypoint,
             content_digest,
             previous_digest,
+            transactions_root,
         }
     }
 
@@ -220,6 +239,10 @@ impl CheckpointSummary {
 
 impl BcsSignable for CheckpointSummary {}
 
+impl IntentScoped for CheckpointSummary {
+    const INTENT_SCOPE: IntentScope = IntentScope::CheckpointSummary;
+}
+
 // TODO: Rename SignedCheckpoint to SignedCheckpointSummary
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct SignedCheckpoint {
@@ -255,15 +278,21 @@ impl SignedCheckpoint {
         }
     }
 
-    /// Checks that the signature on the digest is correct
-    pub fn verify(&self) -> Result<(), SuiError> {
-        self.signature.verify(&self.checkpoint, self.authority)?;
+    /// Checks that the signature on the digest is correct, with the key of the authority in
+    /// `committee`.
+    pub fn verify(&self, committee: &Committee) -> Result<(), SuiError> {
+        let key = committee.protocol_key(&self.authority)?;
+        self.signature.verify(&self.checkpoint, &key)?;
         Ok(())
     }
 
     // Check that the digest and transactions are correctly signed
-    pub fn verify_with_transactions(&self, contents: &CheckpointContents) -> Result<(), SuiError> {
-        self.verify()?;
+    pub fn verify_with_transactions(
+        &self,
+        committee: &Committee,
+        contents: &CheckpointContents,
+    ) -> Result<(), SuiError> {
+        self.verify(committee)?;
         let recomputed = CheckpointSummary::new(
             *self.checkpoint.sequence_number(),
             contents,
@@ -289,7 +318,7 @@ impl SignedCheckpoint {
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct CertifiedCheckpoint {
     pub checkpoint: CheckpointSummary,
-    signatures: Vec<(AuthorityName, AuthoritySignature)>,
+    auth_signature: AuthorityQuorumSignInfo,
 }
 
 impl CertifiedCheckpoint {
@@ -303,83 +332,37 @@ impl CertifiedCheckpoint {
             SuiError::from("Need at least one signed checkpoint to aggregate")
         );
 
+        let signatures: Vec<_> = signed_checkpoints
+            .iter()
+            .map(|v| (v.authority, v.signature))
+            .collect();
         let certified_checkpoint = CertifiedCheckpoint {
             checkpoint: signed_checkpoints[0].checkpoint.clone(),
-            signatures: signed_checkpoints
-                .into_iter()
-                .map(|v| (v.authority, v.signature))
-                .collect(),
+            auth_signature: AuthorityQuorumSignInfo::new(&signatures, committee)?,
         };
 
         certified_checkpoint.verify(committee)?;
         Ok(certified_checkpoint)
     }
 
-    pub fn signatory_authorities(&self) -> impl Iterator<Item = &AuthorityName> {
-        self.signatures.iter().map(|(name, _)| name)
+    /// The authorities of `committee` which signed the certificate.
+    pub fn signatory_authorities<'a>(
+        &self,
+        committee: &'a Committee,
+    ) -> Result<Vec<&'a AuthorityName>, SuiError> {
+        self.auth_signature.authorities(committee)
     }
 
     /// Check that a certificate is valid, and signed by a quorum of authorities
     pub fn verify(&self, committee: &Committee) -> Result<(), SuiError> {
-        // Note: this code is nearly the same as the code that checks
-        // transaction certificates. There is an opportunity to unify this
-        // logic.
-
-        let mut weight = 0;
-        let mut used_authorities = HashSet::new();
-        for (authority, _) in self.signatures.iter() {
-            // Check that each authority only appears once.
-            fp_ensure!(
-                !used_authorities.contains(authority),
-                SuiError::CertificateAuthorityReuse
-            );
-            used_authorities.insert(*authority);
-            // Update weight.
-            let voting_rights = committee.weight(authority);
-            fp_ensure!(voting_rights > 0, SuiError::UnknownSigner);
-            weight += voting_rights;
-        }
-        fp_ensure!(
-            // NOTE: here we only require f+1 weight to accept it, since
-            //       we only need to ensure one honest node signs it, and
-            //       do not require quorum intersection properties between
-            //       any two sets of signers. Further f+1 is the most honest
-            //       nodes we can be sure is in the set of 2f+1 that were
-            //       used to create the checkpoint from fragments.
-            weight >= committee.validity_threshold(),
-            SuiError::CertificateRequiresQuorum
-        );
-
-        let mut obligation = VerificationObligation::default();
-
-        // We verify the same message, so that ensures all signatures are
-        // one a single and same message.
-        let mut message = Vec::new();
-        self.checkpoint.write(&mut message);
-
-        let idx = obligation.messages.len();
-        obligation.messages.push(message);
-
-        for tuple in self.signatures.iter() {
-            let (authority, signature) = tuple;
-            // do we know, or can we build a valid public key?
-            match committee.expanded_keys.get(authority) {
-                Some(v) => obligation.public_keys.push(*v),
-                None => {
-                    let public_key = (*authority).try_into()?;
-                    obligation.public_keys.push(public_key);
-                }
-            }
-
-            // build a signature
-            obligation.signatures.push(signature.0);
-
-            // collect the message
-            obligation.message_index.push(idx);
-        }
-
-        obligation.verify_all().map(|_| ())?;
-        Ok(())
+        // NOTE: here we only require f+1 weight to accept it, since
+        //       we only need to ensure one honest node signs it, and
+        //       do not require quorum intersection properties between
+        //       any two sets of signers. Further f+1 is the most honest
+        //       nodes we can be sure is in the set of 2f+1 that were
+        //       used to create the checkpoint from fragments.
+        self.auth_signature
+            .verify(&self.checkpoint, committee, committee.validity_threshold())
     }
 
     /// Check the certificate and whether it matches with a set of transactions.
@@ -397,6 +380,153 @@ impl CertifiedCheckpoint {
     }
 }
 
+/// The authorities which took part in a checkpoint, as a node following the checkpoints saw
+/// them: those which signed its certificate, and those whose proposal for it the node saw
+/// before the checkpoint was certified, which went to consensus as fragments.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct CheckpointParticipation {
+    pub signers: Vec<AuthorityName>,
+    pub proposers: Vec<AuthorityName>,
+}
+
+/// The handoff of an epoch to the committee of the next one. Once the last checkpoint of an
+/// epoch is processed, each authority of the committee signs the next committee, and a quorum
+/// of their signatures lets anyone who trusts the committee of an epoch trust the next one,
+/// and so on from the genesis committee to the current one. The handoff also commits to the
+/// objects live at the end of the epoch, for nodes to start from a snapshot of them.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct EpochHandoff {
+    /// The epoch which ends.
+    pub epoch: EpochId,
+    pub next_committee: Committee,
+    pub live_objects_digest: LiveObjectsDigest,
+}
+
+impl EpochHandoff {
+    pub fn new(
+        epoch: EpochId,
+        next_committee: Committee,
+        live_objects_digest: LiveObjectsDigest,
+    ) -> Self {
+        Self {
+            epoch,
+            next_committee,
+            live_objects_digest,
+        }
+    }
+
+    pub fn digest(&self) -> [u8; 32] {
+        sha3_hash(self)
+    }
+}
+
+impl BcsSignable for EpochHandoff {}
+
+impl IntentScoped for EpochHandoff {
+    const INTENT_SCOPE: IntentScope = IntentScope::EpochHandoff;
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SignedEpochHandoff {
+    pub handoff: EpochHandoff,
+    pub authority: AuthorityName,
+    signature: AuthoritySignature,
+}
+
+impl SignedEpochHandoff {
+    pub fn new(
+        handoff: EpochHandoff,
+        authority: AuthorityName,
+        signer: &dyn signature::Signer<AuthoritySignature>,
+    ) -> Self {
+        let signature = AuthoritySignature::new(&handoff, signer);
+        Self {
+            handoff,
+            authority,
+            signature,
+        }
+    }
+
+    /// Checks that the signature is correct, with the key of the authority in `committee`, the
+    /// committee of the epoch which ends.
+    pub fn verify(&self, committee: &Committee) -> Result<(), SuiError> {
+        fp_ensure!(
+            self.handoff.epoch == committee.epoch(),
+            SuiError::WrongEpoch {
+                expected_epoch: committee.epoch()
+            }
+        );
+        let key = committee.protocol_key(&self.authority)?;
+        self.signature.verify(&self.handoff, &key)?;
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CertifiedEpochHandoff {
+    pub handoff: EpochHandoff,
+    auth_signature: AuthorityQuorumSignInfo,
+}
+
+impl CertifiedEpochHandoff {
+    /// Aggregate the signatures of a handoff into a certificate.
+    pub fn aggregate(
+        signed_handoffs: Vec<SignedEpochHandoff>,
+        committee: &Committee,
+    ) -> Result<Self, SuiError> {
+        fp_ensure!(
+            !signed_handoffs.is_empty(),
+            SuiError::from("Need at least one signed handoff to aggregate")
+        );
+        let handoff = signed_handoffs[0].handoff.clone();
+        fp_ensure!(
+            signed_handoffs
+                .iter()
+                .all(|signed| signed.handoff == handoff),
+            SuiError::from("The signed handoffs are of different committees")
+        );
+        let signatures: Vec<_> = signed_handoffs
+            .iter()
+            .map(|signed| (signed.authority, signed.signature))
+            .collect();
+        let certificate = Self {
+            handoff,
+            auth_signature: AuthorityQuorumSignInfo::new(&signatures, committee)?,
+        };
+        certificate.verify(committee)?;
+        Ok(certificate)
+    }
+
+    /// Check that the handoff is signed by a quorum of `committee`, the committee of the epoch
+    /// which ends. Unlike a checkpoint, a handoff needs a quorum: a committee is trusted from
+    /// then on, so the handoff must be one which no quorum of the committee can contradict.
+    pub fn verify(&self, committee: &Committee) -> Result<(), SuiError> {
+        fp_ensure!(
+            self.handoff.epoch == committee.epoch(),
+            SuiError::WrongEpoch {
+                expected_epoch: committee.epoch()
+            }
+        );
+        fp_ensure!(
+            self.handoff.next_committee.epoch() == committee.epoch() + 1,
+            SuiError::InvalidEpochHandoff {
+                epoch: self.handoff.epoch,
+                error: "The next committee is not of the next epoch".to_string(),
+            }
+        );
+        self.auth_signature
+            .verify(&self.handoff, committee, committee.quorum_threshold())
+    }
+
+    /// The authorities of `committee` which signed the certificate.
+    pub fn signatory_authorities<'a>(
+        &self,
+        committee: &'a Committee,
+    ) -> Result<Vec<&'a AuthorityName>, SuiError> {
+        self.auth_signature.authorities(committee)
+    }
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct CheckpointContents {
     pub transactions: BTreeSet<ExecutionDigests>,
@@ -417,6 +547,18 @@ impl CheckpointContents {
     pub fn digest(&self) -> [u8; 32] {
         sha3_hash(self)
     }
+
+    /// The root of the Merkle tree of the transactions, in their order.
+    pub fn merkle_root(&self) -> CheckpointDigest {
+        light_client::merkle_root(&self.transactions)
+    }
+
+    /// The proof that `digests` are in the contents, if they are.
+    pub fn inclusion_proof(&self, digests: &ExecutionDigests) -> Option<InclusionProof> {
+        let leaves: Vec<_> = self.transactions.iter().copied().collect();
+        let index = leaves.iter().position(|leaf| leaf == digests)?;
+        InclusionProof::new(&leaves, index)
+    }
 }
 
 // The construction of checkpoints is based on the aggregation of fragments.
@@ -429,15 +571,15 @@ pub struct CheckpointFragment {
 }
 
 impl CheckpointFragment {
-    pub fn verify(&self, _committee: &Committee) -> Result<(), SuiError> {
+    pub fn verify(&self, committee: &Committee) -> Result<(), SuiError> {
         // Check the signatures of proposer and other
-        self.proposer.0.verify()?;
-        self.other.0.verify()?;
+        self.proposer.0.verify(committee)?;
+        self.other.0.verify(committee)?;
 
         // Check the proposers are authorities
         fp_ensure!(
-            _committee.weight(&self.proposer.0.authority) > 0
-                && _committee.weight(&self.other.0.authority) > 0,
+            committee.weight(&self.proposer.0.authority) > 0
+                && committee.weight(&self.other.0.authority) > 0,
             SuiError::from("Authorities not in the committee")
         );
 
@@ -484,7 +626,7 @@ mod tests {
     #[test]
     fn test_signed_proposal() {
         let mut rng = StdRng::from_seed(RNG_SEED);
-        let (authority_key, _committee) = make_committee_key(&mut rng);
+        let (authority_key, committee) = make_committee_key(&mut rng);
         let name = authority_key[0].public_key_bytes();
 
         let set = [ExecutionDigests::random()];
@@ -493,18 +635,20 @@ mod tests {
         let mut proposal = SignedCheckpoint::new(1, *name, &authority_key[0], &set, None);
 
         // Signature is correct on proposal, and with same transactions
-        assert!(proposal.verify().is_ok());
-        assert!(proposal.verify_with_transactions(&set).is_ok());
+        assert!(proposal.verify(&committee).is_ok());
+        assert!(proposal.verify_with_transactions(&committee, &set).is_ok());
 
         // Error on different transactions
         let contents = CheckpointContents {
             transactions: [ExecutionDigests::random()].into_iter().collect(),
         };
-        assert!(proposal.verify_with_transactions(&contents).is_err());
+        assert!(proposal
+            .verify_with_transactions(&committee, &contents)
+            .is_err());
 
         // Modify the proposal, and observe the signature fail
         proposal.checkpoint.sequence_number = 2;
-        assert!(proposal.verify().is_err());
+        assert!(proposal.verify(&committee).is_err());
     }
 
     #[test]
@@ -531,6 +675,10 @@ mod tests {
         assert!(checkpoint_cert
             .verify_with_transactions(&committee, &set)
             .is_ok());
+        assert_eq!(
+            checkpoint_cert.signatory_authorities(&committee).unwrap(),
+            committee.voting_rights.keys().collect::<Vec<_>>()
+        );
 
         // Make a bad proposal
         let signed_checkpoints: Vec<_> = keys
@@ -2,8 +2,9 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    base_types::ObjectID,
+    base_types::{ObjectID, SequenceNumber},
     error::{SuiError, SuiResult},
+    object::OBJECT_START_VERSION,
 };
 use move_binary_format::access::ModuleAccess;
 use move_binary_format::binary_views::BinaryIndexedView;
@@ -27,6 +28,9 @@ use std::collections::BTreeMap;
 #[derive(Eq, PartialEq, Debug, Clone, Deserialize, Serialize, Hash)]
 pub struct MovePackage {
     id: ObjectID,
+    /// The version of the package, which only the framework packages go past the first one of,
+    /// as epoch changes upgrade them.
+    version: SequenceNumber,
     // TODO use session cache
     #[serde_as(as = "BTreeMap<_, Bytes>")]
     module_map: BTreeMap<String, Vec<u8>>,
@@ -36,14 +40,27 @@ impl MovePackage {
     pub fn new(id: ObjectID, module_map: &BTreeMap<String, Vec<u8>>) -> Self {
         Self {
             id,
+            version: OBJECT_START_VERSION,
             module_map: module_map.clone(),
         }
     }
 
+    /// The next version of the package, made of `modules`, which have the address of the package.
+    pub fn new_version(&self, modules: Vec<CompiledModule>) -> Self {
+        let mut package = Self::from_iter(modules);
+        debug_assert_eq!(package.id, self.id);
+        package.version = self.version.increment();
+        package
+    }
+
     pub fn id(&self) -> ObjectID {
         self.id
     }
 
+    pub fn version(&self) -> SequenceNumber {
+        self.version
+    }
+
     pub fn serialized_module_map(&self) -> &BTreeMap<String, Vec<u8>> {
         &self.module_map
     }
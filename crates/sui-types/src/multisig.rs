@@ -0,0 +1,421 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::collections::BTreeSet;
+use std::fmt;
+
+use digest::Digest;
+use ed25519_dalek as dalek;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_with::{serde_as, Bytes};
+use sha3::Sha3_256;
+use signature::Signature as _;
+
+use crate::base_types::{SuiAddress, SUI_ADDRESS_LENGTH};
+use crate::crypto::{
+    GenericSignature, PublicKeyBytes, Signable, SignatureScheme, VerificationObligation,
+};
+use crate::ecdsa::{
+    Secp256k1Signature, Secp256r1Signature, ECDSA_PUBLIC_KEY_LENGTH, ECDSA_SIGNATURE_LENGTH,
+};
+use crate::error::{SuiError, SuiResult};
+use crate::fp_ensure;
+use crate::intent::{intent_message, IntentScoped};
+use crate::sui_serde::{Base64, Readable};
+
+#[cfg(test)]
+#[path = "unit_tests/multisig_tests.rs"]
+mod multisig_tests;
+
+pub type WeightUnit = u8;
+pub type ThresholdUnit = u16;
+
+/// The maximum number of keys in a `MultiSigPublicKey`, bounded by the size of the bitmap.
+pub const MAX_SIGNER_IN_MULTISIG: usize = 16;
+
+/// The public key of a member of a multisig, in the scheme the member signs with. The variants
+/// are in the order of the flags of their schemes.
+#[serde_as]
+#[derive(Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash, Serialize, Deserialize, JsonSchema)]
+pub enum MultiSigMemberKey {
+    Ed25519(PublicKeyBytes),
+    Secp256k1(
+        #[schemars(with = "Base64")]
+        #[serde_as(as = "Readable<Base64, Bytes>")]
+        [u8; ECDSA_PUBLIC_KEY_LENGTH],
+    ),
+    Secp256r1(
+        #[schemars(with = "Base64")]
+        #[serde_as(as = "Readable<Base64, Bytes>")]
+        [u8; ECDSA_PUBLIC_KEY_LENGTH],
+    ),
+}
+
+impl MultiSigMemberKey {
+    pub fn scheme(&self) -> SignatureScheme {
+        match self {
+            MultiSigMemberKey::Ed25519(_) => SignatureScheme::ED25519,
+            MultiSigMemberKey::Secp256k1(_) => SignatureScheme::Secp256k1,
+            MultiSigMemberKey::Secp256r1(_) => SignatureScheme::Secp256r1,
+        }
+    }
+
+    /// Read a key as printed by the keystore: the 32 bytes of an ed25519 key, or the flag of
+    /// an ECDSA scheme followed by the compressed key. The ECDSA keys must be on their curve.
+    pub fn from_bytes(bytes: &[u8]) -> SuiResult<Self> {
+        if bytes.len() == dalek::PUBLIC_KEY_LENGTH {
+            return Ok(MultiSigMemberKey::Ed25519(PublicKeyBytes::try_from(bytes)?));
+        }
+        let invalid = || SuiError::InvalidSignature {
+            error: format!(
+                "A multisig member key must be an ed25519 key or a flag followed by a \
+                 compressed ECDSA key, got {} bytes",
+                bytes.len()
+            ),
+        };
+        let (flag, key) = bytes.split_first().ok_or_else(invalid)?;
+        let key: [u8; ECDSA_PUBLIC_KEY_LENGTH] = key.try_into().map_err(|_| invalid())?;
+        match SignatureScheme::from_flag(*flag)? {
+            SignatureScheme::Secp256k1 => {
+                Secp256k1Signature::check_public_key(&key)?;
+                Ok(MultiSigMemberKey::Secp256k1(key))
+            }
+            SignatureScheme::Secp256r1 => {
+                Secp256r1Signature::check_public_key(&key)?;
+                Ok(MultiSigMemberKey::Secp256r1(key))
+            }
+            _ => Err(invalid()),
+        }
+    }
+}
+
+impl AsRef<[u8]> for MultiSigMemberKey {
+    fn as_ref(&self) -> &[u8] {
+        match self {
+            MultiSigMemberKey::Ed25519(key) => key.as_ref(),
+            MultiSigMemberKey::Secp256k1(key) | MultiSigMemberKey::Secp256r1(key) => key,
+        }
+    }
+}
+
+impl From<PublicKeyBytes> for MultiSigMemberKey {
+    fn from(key: PublicKeyBytes) -> Self {
+        MultiSigMemberKey::Ed25519(key)
+    }
+}
+
+impl fmt::Debug for MultiSigMemberKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MultiSigMemberKey::Ed25519(key) => write!(f, "{key:?}"),
+            MultiSigMemberKey::Secp256k1(key) => write!(f, "k1#{}", hex::encode(key)),
+            MultiSigMemberKey::Secp256r1(key) => write!(f, "r1#{}", hex::encode(key)),
+        }
+    }
+}
+
+/// The signature of a member of a multisig, in the scheme of its key. The public key is not
+/// repeated, as the bitmap of the `MultiSig` tells which member made it.
+#[serde_as]
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub enum MultiSigMemberSignature {
+    Ed25519(
+        #[schemars(with = "Base64")]
+        #[serde_as(as = "Readable<Base64, _>")]
+        dalek::Signature,
+    ),
+    Secp256k1(
+        #[schemars(with = "Base64")]
+        #[serde_as(as = "Readable<Base64, Bytes>")]
+        [u8; ECDSA_SIGNATURE_LENGTH],
+    ),
+    Secp256r1(
+        #[schemars(with = "Base64")]
+        #[serde_as(as = "Readable<Base64, Bytes>")]
+        [u8; ECDSA_SIGNATURE_LENGTH],
+    ),
+}
+
+impl MultiSigMemberSignature {
+    pub fn scheme(&self) -> SignatureScheme {
+        match self {
+            MultiSigMemberSignature::Ed25519(_) => SignatureScheme::ED25519,
+            MultiSigMemberSignature::Secp256k1(_) => SignatureScheme::Secp256k1,
+            MultiSigMemberSignature::Secp256r1(_) => SignatureScheme::Secp256r1,
+        }
+    }
+
+    /// Split the signature of a single key into the key and the signature of a member.
+    fn from_signature(sig: GenericSignature) -> SuiResult<(MultiSigMemberKey, Self)> {
+        let ecdsa_parts = |signature: &[u8], public_key: &[u8]| {
+            let signature: [u8; ECDSA_SIGNATURE_LENGTH] =
+                signature.try_into().expect("byte lengths match");
+            let public_key: [u8; ECDSA_PUBLIC_KEY_LENGTH] =
+                public_key.try_into().expect("byte lengths match");
+            (signature, public_key)
+        };
+        match sig {
+            GenericSignature::Signature(sig) => {
+                let pk = PublicKeyBytes::try_from(sig.public_key_bytes())?;
+                let sig = dalek::Signature::from_bytes(sig.signature_bytes()).map_err(|err| {
+                    SuiError::InvalidSignature {
+                        error: err.to_string(),
+                    }
+                })?;
+                Ok((MultiSigMemberKey::Ed25519(pk), Self::Ed25519(sig)))
+            }
+            GenericSignature::Secp256k1(sig) => {
+                let (sig, pk) = ecdsa_parts(sig.signature_bytes(), sig.public_key_bytes());
+                Ok((MultiSigMemberKey::Secp256k1(pk), Self::Secp256k1(sig)))
+            }
+            GenericSignature::Secp256r1(sig) => {
+                let (sig, pk) = ecdsa_parts(sig.signature_bytes(), sig.public_key_bytes());
+                Ok((MultiSigMemberKey::Secp256r1(pk), Self::Secp256r1(sig)))
+            }
+            GenericSignature::MultiSig(_) | GenericSignature::ZkLogin(_) => {
+                Err(SuiError::InvalidSignature {
+                    error: format!(
+                        "A multisig can only combine signatures of single keys, got a {:?} \
+                         signature",
+                        sig.scheme()
+                    ),
+                })
+            }
+        }
+    }
+}
+
+/// A set of public keys with a weight each, and the threshold the total weight of the signers
+/// must reach for a `MultiSig` to be valid. The address of the accounts controlled by these
+/// keys is derived from all of it, so changing a key, a weight or the threshold gives a
+/// different address. The keys may be of different schemes.
+#[derive(Clone, Debug, Eq, PartialEq, Hash, Serialize, Deserialize, JsonSchema)]
+pub struct MultiSigPublicKey {
+    pk_map: Vec<(MultiSigMemberKey, WeightUnit)>,
+    threshold: ThresholdUnit,
+}
+
+impl MultiSigPublicKey {
+    pub fn new(
+        pks: Vec<MultiSigMemberKey>,
+        weights: Vec<WeightUnit>,
+        threshold: ThresholdUnit,
+    ) -> SuiResult<Self> {
+        fp_ensure!(
+            !pks.is_empty() && pks.len() <= MAX_SIGNER_IN_MULTISIG,
+            SuiError::InvalidSignature {
+                error: format!(
+                    "A multisig must have between 1 and {MAX_SIGNER_IN_MULTISIG} keys, got {}",
+                    pks.len()
+                ),
+            }
+        );
+        fp_ensure!(
+            pks.len() == weights.len(),
+            SuiError::InvalidSignature {
+                error: "Every multisig key must have exactly one weight".to_string(),
+            }
+        );
+        fp_ensure!(
+            pks.iter().collect::<BTreeSet<_>>().len() == pks.len(),
+            SuiError::InvalidSignature {
+                error: "Multisig keys must be unique".to_string(),
+            }
+        );
+        fp_ensure!(
+            weights.iter().all(|w| *w > 0) && threshold > 0,
+            SuiError::InvalidSignature {
+                error: "Multisig weights and threshold must be positive".to_string(),
+            }
+        );
+        let total_weight: ThresholdUnit = weights.iter().map(|w| *w as ThresholdUnit).sum();
+        fp_ensure!(
+            total_weight >= threshold,
+            SuiError::InvalidSignature {
+                error: format!(
+                    "Multisig threshold {threshold} is above the total weight {total_weight}"
+                ),
+            }
+        );
+        Ok(Self {
+            pk_map: pks.into_iter().zip(weights).collect(),
+            threshold,
+        })
+    }
+
+    pub fn pubkeys(&self) -> &[(MultiSigMemberKey, WeightUnit)] {
+        &self.pk_map
+    }
+
+    pub fn threshold(&self) -> ThresholdUnit {
+        self.threshold
+    }
+
+    /// The address is the hash of the multisig flag, the threshold and every key with the flag
+    /// of its scheme and its weight, in order. The multisig flag keeps it from colliding with
+    /// the address of a single key, and the flags of the keys keep a secp256k1 key and a
+    /// secp256r1 key with the same bytes apart.
+    pub fn address(&self) -> SuiAddress {
+        let mut hasher = Sha3_256::default();
+        hasher.update([SignatureScheme::MultiSig.flag()]);
+        hasher.update(self.threshold.to_le_bytes());
+        for (pk, weight) in &self.pk_map {
+            hasher.update([pk.scheme().flag()]);
+            hasher.update(pk.as_ref());
+            hasher.update([*weight]);
+        }
+        let hash = hasher.finalize();
+        SuiAddress::try_from(&hash[..SUI_ADDRESS_LENGTH]).expect("byte lengths match")
+    }
+
+    fn index_of(&self, pk: &MultiSigMemberKey) -> Option<usize> {
+        self.pk_map.iter().position(|(key, _)| key == pk)
+    }
+}
+
+/// Signatures by some of the keys of a `MultiSigPublicKey`. Bit `i` of `bitmap` is set if the
+/// key at index `i` signed, and `sigs` holds these signatures in the order of the keys, each in
+/// the scheme of its key.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct MultiSig {
+    sigs: Vec<MultiSigMemberSignature>,
+    bitmap: u16,
+    multisig_pk: MultiSigPublicKey,
+}
+
+impl MultiSig {
+    /// Combine the signatures of some of the keys of `multisig_pk` over the same message. Each
+    /// signature carries the public key it was made with, which must be one of the keys. The
+    /// signatures are not checked here, only when the `MultiSig` is verified.
+    pub fn combine(
+        full_sigs: Vec<GenericSignature>,
+        multisig_pk: MultiSigPublicKey,
+    ) -> SuiResult<Self> {
+        fp_ensure!(
+            multisig_pk.pk_map.len() <= MAX_SIGNER_IN_MULTISIG,
+            SuiError::InvalidSignature {
+                error: "MultiSig public key is malformed".to_string(),
+            }
+        );
+        let mut indexed = Vec::with_capacity(full_sigs.len());
+        let mut bitmap = 0u16;
+        for sig in full_sigs {
+            let (pk, sig) = MultiSigMemberSignature::from_signature(sig)?;
+            let index = multisig_pk
+                .index_of(&pk)
+                .ok_or_else(|| SuiError::IncorrectSigner {
+                    error: format!("Key {pk:?} is not part of the multisig"),
+                })?;
+            fp_ensure!(
+                bitmap & (1 << index) == 0,
+                SuiError::InvalidSignature {
+                    error: format!("Key {pk:?} signed more than once"),
+                }
+            );
+            bitmap |= 1 << index;
+            indexed.push((index, sig));
+        }
+        indexed.sort_by_key(|(index, _)| *index);
+        Ok(Self {
+            sigs: indexed.into_iter().map(|(_, sig)| sig).collect(),
+            bitmap,
+            multisig_pk,
+        })
+    }
+
+    pub fn multisig_pk(&self) -> &MultiSigPublicKey {
+        &self.multisig_pk
+    }
+
+    /// Check that `author` is the address of the multisig and that the signers reach its
+    /// threshold, and check the signature of every signer in the scheme of its key. The
+    /// ed25519 signatures are added to `obligation` to be checked in the batch, the ECDSA
+    /// ones are checked now.
+    pub fn add_to_verification_obligation<T>(
+        &self,
+        value: &T,
+        author: SuiAddress,
+        obligation: &mut VerificationObligation,
+    ) -> SuiResult<()>
+    where
+        T: Signable<Vec<u8>> + IntentScoped,
+    {
+        let received_addr = self.multisig_pk.address();
+        fp_ensure!(
+            received_addr == author,
+            SuiError::IncorrectSigner {
+                error: format!(
+                    "MultiSig verification failure. Author is {author}, received address is \
+                     {received_addr}"
+                ),
+            }
+        );
+        let pk_map = &self.multisig_pk.pk_map;
+        fp_ensure!(
+            pk_map.len() <= MAX_SIGNER_IN_MULTISIG && self.multisig_pk.threshold > 0,
+            SuiError::InvalidSignature {
+                error: "MultiSig public key is malformed".to_string(),
+            }
+        );
+        fp_ensure!(
+            (self.bitmap as u32) < (1u32 << pk_map.len())
+                && self.bitmap.count_ones() as usize == self.sigs.len(),
+            SuiError::InvalidSignature {
+                error: "MultiSig bitmap does not match its keys and signatures".to_string(),
+            }
+        );
+        let signers = (0..pk_map.len()).filter(|i| self.bitmap & (1 << i) != 0);
+        let weight: ThresholdUnit = signers.clone().map(|i| pk_map[i].1 as ThresholdUnit).sum();
+        fp_ensure!(
+            weight >= self.multisig_pk.threshold,
+            SuiError::InvalidSignature {
+                error: format!(
+                    "MultiSig signers have weight {weight}, below the threshold {}",
+                    self.multisig_pk.threshold
+                ),
+            }
+        );
+
+        let mut message_idx = None;
+        for (index, sig) in signers.zip(&self.sigs) {
+            match (&pk_map[index].0, sig) {
+                (MultiSigMemberKey::Ed25519(pk), MultiSigMemberSignature::Ed25519(sig)) => {
+                    let idx = *message_idx.get_or_insert_with(|| {
+                        obligation.messages.push(intent_message(value));
+                        obligation.messages.len() - 1
+                    });
+                    let key = obligation.lookup_public_key(pk)?;
+                    obligation.public_keys.push(key);
+                    obligation.signatures.push(*sig);
+                    obligation.message_index.push(idx);
+                }
+                (MultiSigMemberKey::Secp256k1(pk), MultiSigMemberSignature::Secp256k1(sig)) => {
+                    Secp256k1Signature::verify_with_key(value, sig, pk)?
+                }
+                (MultiSigMemberKey::Secp256r1(pk), MultiSigMemberSignature::Secp256r1(sig)) => {
+                    Secp256r1Signature::verify_with_key(value, sig, pk)?
+                }
+                (pk, sig) => {
+                    return Err(SuiError::InvalidSignature {
+                        error: format!(
+                            "MultiSig member {index} has a {:?} key but a {:?} signature",
+                            pk.scheme(),
+                            sig.scheme()
+                        ),
+                    })
+                }
+            }
+        }
+        Ok(())
+    }
+
+    pub fn verify<T>(&self, value: &T, author: SuiAddress) -> SuiResult<()>
+    where
+        T: Signable<Vec<u8>> + IntentScoped,
+    {
+        let mut obligation = VerificationObligation::default();
+        self.add_to_verification_obligation(value, author, &mut obligation)?;
+        obligation.verify_all().map(|_| ())
+    }
+}
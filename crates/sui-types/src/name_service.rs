@@ -0,0 +1,132 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use move_core_types::{ident_str, identifier::IdentStr, language_storage::StructTag};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    base_types::{ObjectID, SuiAddress},
+    committee::EpochId,
+    event::Event,
+    id::VersionedID,
+    object::Object,
+    SUI_FRAMEWORK_ADDRESS,
+};
+
+#[cfg(test)]
+#[path = "unit_tests/name_service_tests.rs"]
+mod name_service_tests;
+
+pub const NAME_SERVICE_MODULE_NAME: &IdentStr = ident_str!("NameService");
+pub const REGISTRY_STRUCT_NAME: &IdentStr = ident_str!("Registry");
+const REGISTRY_CREATED_STRUCT_NAME: &IdentStr = ident_str!("RegistryCreated");
+
+/// The domain of the names of a registry. Clients accept a name with or without it, and name
+/// addresses with it.
+pub const NAME_SERVICE_DOMAIN: &str = ".sui";
+
+/// Rust version of the Move Sui::NameService::NameRecord type
+#[derive(Debug, Serialize, Deserialize, Clone, Eq, PartialEq)]
+pub struct NameRecord {
+    pub name: Vec<u8>,
+    pub owner: SuiAddress,
+    pub target: SuiAddress,
+    /// The first epoch in which the name no longer resolves.
+    pub expiration_epoch: EpochId,
+}
+
+/// Rust version of the Move Sui::NameService::ReverseRecord type
+#[derive(Debug, Serialize, Deserialize, Clone, Eq, PartialEq)]
+pub struct ReverseRecord {
+    pub addr: SuiAddress,
+    pub name: Vec<u8>,
+}
+
+/// Rust version of the Move Sui::NameService::Registry type
+#[derive(Debug, Serialize, Deserialize, Clone, Eq, PartialEq)]
+pub struct NameServiceRegistry {
+    pub id: VersionedID,
+    pub records: Vec<NameRecord>,
+    pub reverse_records: Vec<ReverseRecord>,
+}
+
+/// Rust version of the Move Sui::NameService::RegistryCreated event
+#[derive(Debug, Serialize, Deserialize, Clone, Eq, PartialEq)]
+struct RegistryCreated {
+    registry_id: ObjectID,
+}
+
+impl NameServiceRegistry {
+    pub fn type_() -> StructTag {
+        StructTag {
+            address: SUI_FRAMEWORK_ADDRESS,
+            module: NAME_SERVICE_MODULE_NAME.to_owned(),
+            name: REGISTRY_STRUCT_NAME.to_owned(),
+            type_params: vec![],
+        }
+    }
+
+    pub fn id(&self) -> &ObjectID {
+        self.id.object_id()
+    }
+
+    pub fn try_from_object(object: &Object) -> Option<Self> {
+        let move_object = object.data.try_as_move()?;
+        if move_object.type_ != Self::type_() {
+            return None;
+        }
+        bcs::from_bytes(move_object.contents()).ok()
+    }
+
+    /// The ID of the registry `event` reports the creation of, if it is a
+    /// `Sui::NameService::RegistryCreated` event.
+    pub fn created_by_event(event: &Event) -> Option<ObjectID> {
+        match event {
+            Event::MoveEvent { type_, contents }
+                if type_.address == SUI_FRAMEWORK_ADDRESS
+                    && type_.module.as_ident_str() == NAME_SERVICE_MODULE_NAME
+                    && type_.name.as_ident_str() == REGISTRY_CREATED_STRUCT_NAME =>
+            {
+                bcs::from_bytes::<RegistryCreated>(contents)
+                    .ok()
+                    .map(|event| event.registry_id)
+            }
+            _ => None,
+        }
+    }
+
+    /// The address `name`, with or without the domain, points at in `epoch`.
+    pub fn resolve(&self, name: &str, epoch: EpochId) -> Option<SuiAddress> {
+        let name = name.strip_suffix(NAME_SERVICE_DOMAIN).unwrap_or(name);
+        self.records
+            .iter()
+            .find(|record| record.name == name.as_bytes() && record.expiration_epoch > epoch)
+            .map(|record| record.target)
+    }
+
+    /// The names, with the domain, pointing at `address` in `epoch`: its reverse record first if
+    /// it has one, and then the others in the order they were registered.
+    pub fn names_of(&self, address: &SuiAddress, epoch: EpochId) -> Vec<String> {
+        let reverse_record = self
+            .reverse_records
+            .iter()
+            .find(|record| record.addr == *address)
+            .map(|record| record.name.as_slice());
+        let mut names: Vec<&NameRecord> = self
+            .records
+            .iter()
+            .filter(|record| record.target == *address && record.expiration_epoch > epoch)
+            .collect();
+        // A stable sort keeps the order of registration among the others.
+        names.sort_by_key(|record| Some(record.name.as_slice()) != reverse_record);
+        names
+            .into_iter()
+            .map(|record| {
+                format!(
+                    "{}{NAME_SERVICE_DOMAIN}",
+                    String::from_utf8_lossy(&record.name)
+                )
+            })
+            .collect()
+    }
+}
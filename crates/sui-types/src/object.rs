@@ -239,12 +239,19 @@ pub enum Owner {
     Shared,
     /// Object is immutable, and hence ownership doesn't matter.
     Immutable,
+    /// Object is owned jointly by the members of a party, and is mutable. The address is
+    /// typically the multi-signature address of the members, so that any of them, or a threshold
+    /// of them, can sign for it. Its versions are assigned by consensus, as those of shared
+    /// objects are, so that members using it concurrently cannot equivocate.
+    Party(SuiAddress),
 }
 
 impl Owner {
     pub fn get_owner_address(&self) -> SuiResult<SuiAddress> {
         match self {
-            Self::AddressOwner(address) | Self::ObjectOwner(address) => Ok(*address),
+            Self::AddressOwner(address) | Self::ObjectOwner(address) | Self::Party(address) => {
+                Ok(*address)
+            }
             Self::Shared | Self::Immutable => Err(SuiError::UnexpectedOwnerType),
         }
     }
@@ -256,12 +263,13 @@ impl Owner {
     pub fn is_owned(&self) -> bool {
         match self {
             Owner::AddressOwner(_) | Owner::ObjectOwner(_) => true,
-            Owner::Shared | Owner::Immutable => false,
+            Owner::Shared | Owner::Immutable | Owner::Party(_) => false,
         }
     }
 
+    /// Whether the object is used through consensus, as shared and party objects are.
     pub fn is_shared(&self) -> bool {
-        matches!(self, Owner::Shared)
+        matches!(self, Owner::Shared | Owner::Party(_))
     }
 }
 
@@ -269,7 +277,7 @@ impl PartialEq<SuiAddress> for Owner {
     fn eq(&self, other: &SuiAddress) -> bool {
         match self {
             Self::AddressOwner(address) => address == other,
-            Self::ObjectOwner(_) | Self::Shared | Self::Immutable => false,
+            Self::ObjectOwner(_) | Self::Shared | Self::Immutable | Self::Party(_) => false,
         }
     }
 }
@@ -279,7 +287,7 @@ impl PartialEq<ObjectID> for Owner {
         let other_id: SuiAddress = (*other).into();
         match self {
             Self::ObjectOwner(id) => id == &other_id,
-            Self::AddressOwner(_) | Self::Shared | Self::Immutable => false,
+            Self::AddressOwner(_) | Self::Shared | Self::Immutable | Self::Party(_) => false,
         }
     }
 }
@@ -299,6 +307,9 @@ impl Display for Owner {
             Self::Shared => {
                 write!(f, "Shared")
             }
+            Self::Party(address) => {
+                write!(f, "Party ( {} )", address)
+            }
         }
     }
 }
@@ -388,7 +399,7 @@ impl Object {
 
         match &self.data {
             Move(v) => v.version(),
-            Package(_) => SequenceNumber::from(1), // modules are immutable, version is always 1
+            Package(p) => p.version(),
         }
     }
 
@@ -408,11 +419,13 @@ impl Object {
         let meta_data_size = size_of::<Owner>() + size_of::<TransactionDigest>() + size_of::<u64>();
         let data_size = match &self.data {
             Data::Move(m) => m.object_size_for_gas_metering(),
-            Data::Package(p) => p
-                .serialized_module_map()
-                .iter()
-                .map(|(name, module)| name.len() + module.len())
-                .sum(),
+            Data::Package(p) => {
+                size_of::<SequenceNumber>()
+                    + p.serialized_module_map()
+                        .iter()
+                        .map(|(name, module)| name.len() + module.len())
+                        .sum::<usize>()
+            }
         };
         meta_data_size + data_size
     }
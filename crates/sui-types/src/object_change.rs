@@ -0,0 +1,88 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use move_core_types::language_storage::{StructTag, TypeTag};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    base_types::{ObjectDigest, ObjectID, SequenceNumber},
+    object::Owner,
+};
+
+/// The change in the total value of the coins of type `Coin<coin_type>` owned by `owner`
+/// caused by a transaction.
+#[derive(Debug, Serialize, Deserialize, Clone, Eq, PartialEq)]
+pub struct BalanceChange {
+    pub owner: Owner,
+    pub coin_type: TypeTag,
+    pub amount: i128,
+}
+
+/// A change of the owner of an object by a transaction: a transfer to another address or object,
+/// or the object being shared or frozen. Recorded in the effects of the transaction.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Eq, PartialEq, Hash, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct OwnerChange {
+    pub object_id: ObjectID,
+    /// The version of the object written by the transaction.
+    pub version: SequenceNumber,
+    pub previous_owner: Owner,
+    pub owner: Owner,
+}
+
+/// A change made to an object by a transaction.
+#[derive(Debug, Serialize, Deserialize, Clone, Eq, PartialEq)]
+pub enum ObjectChange {
+    /// A package was published.
+    Published {
+        package_id: ObjectID,
+        version: SequenceNumber,
+        digest: ObjectDigest,
+    },
+    /// An object was created, or unwrapped from another object.
+    Created {
+        object_id: ObjectID,
+        object_type: StructTag,
+        owner: Owner,
+        version: SequenceNumber,
+        digest: ObjectDigest,
+    },
+    /// An object was mutated and kept its owner.
+    Mutated {
+        object_id: ObjectID,
+        object_type: StructTag,
+        owner: Owner,
+        previous_version: SequenceNumber,
+        version: SequenceNumber,
+        digest: ObjectDigest,
+    },
+    /// An object changed owner.
+    Transferred {
+        object_id: ObjectID,
+        object_type: StructTag,
+        previous_owner: Owner,
+        owner: Owner,
+        version: SequenceNumber,
+        digest: ObjectDigest,
+    },
+    /// An object was deleted.
+    Deleted {
+        object_id: ObjectID,
+        object_type: StructTag,
+        version: SequenceNumber,
+    },
+    /// An object was wrapped into another object.
+    Wrapped {
+        object_id: ObjectID,
+        object_type: StructTag,
+        version: SequenceNumber,
+    },
+}
+
+/// A summary of the changes made by a transaction, computed when it is executed.
+#[derive(Debug, Serialize, Deserialize, Clone, Default, Eq, PartialEq)]
+pub struct TransactionChanges {
+    pub balance_changes: Vec<BalanceChange>,
+    pub object_changes: Vec<ObjectChange>,
+}
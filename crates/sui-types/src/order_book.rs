@@ -0,0 +1,129 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use move_core_types::{ident_str, identifier::IdentStr};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    base_types::{ObjectID, SuiAddress, TransactionDigest},
+    batch::TxSequenceNumber,
+    event::Event,
+    SUI_FRAMEWORK_ADDRESS,
+};
+
+#[cfg(test)]
+#[path = "unit_tests/order_book_tests.rs"]
+mod order_book_tests;
+
+pub const ORDER_BOOK_MODULE_NAME: &IdentStr = ident_str!("OrderBook");
+const POOL_CREATED_STRUCT_NAME: &IdentStr = ident_str!("PoolCreated");
+const ORDER_PLACED_STRUCT_NAME: &IdentStr = ident_str!("OrderPlaced");
+const ORDER_FILLED_STRUCT_NAME: &IdentStr = ident_str!("OrderFilled");
+const ORDER_CANCELED_STRUCT_NAME: &IdentStr = ident_str!("OrderCanceled");
+
+/// Rust version of the Move Sui::OrderBook::PoolCreated event
+#[derive(Debug, Serialize, Deserialize, Clone, Eq, PartialEq, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PoolCreated {
+    pub pool_id: ObjectID,
+    pub tick_size: u64,
+    pub lot_size: u64,
+    pub taker_fee_bps: u64,
+    pub maker_rebate_bps: u64,
+}
+
+/// Rust version of the Move Sui::OrderBook::OrderPlaced event
+#[derive(Debug, Serialize, Deserialize, Clone, Eq, PartialEq, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct OrderPlaced {
+    pub pool_id: ObjectID,
+    pub order_id: u64,
+    pub owner: SuiAddress,
+    pub is_bid: bool,
+    pub price: u64,
+    pub quantity: u64,
+}
+
+/// Rust version of the Move Sui::OrderBook::OrderFilled event
+#[derive(Debug, Serialize, Deserialize, Clone, Eq, PartialEq, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct OrderFilled {
+    pub pool_id: ObjectID,
+    pub maker_order_id: u64,
+    pub taker_order_id: u64,
+    pub maker: SuiAddress,
+    pub taker: SuiAddress,
+    pub taker_is_bid: bool,
+    pub price: u64,
+    pub quantity: u64,
+    pub taker_fee: u64,
+    pub maker_rebate: u64,
+}
+
+/// Rust version of the Move Sui::OrderBook::OrderCanceled event
+#[derive(Debug, Serialize, Deserialize, Clone, Eq, PartialEq, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct OrderCanceled {
+    pub pool_id: ObjectID,
+    pub order_id: u64,
+    pub owner: SuiAddress,
+    pub is_bid: bool,
+    pub price: u64,
+    pub quantity: u64,
+}
+
+/// An event of a `Sui::OrderBook::Pool`, as indexed by full nodes.
+#[derive(Debug, Serialize, Deserialize, Clone, Eq, PartialEq, JsonSchema)]
+pub enum OrderBookEvent {
+    PoolCreated(PoolCreated),
+    OrderPlaced(OrderPlaced),
+    OrderFilled(OrderFilled),
+    OrderCanceled(OrderCanceled),
+}
+
+/// An order book event, with the transaction which emitted it.
+#[derive(Debug, Serialize, Deserialize, Clone, Eq, PartialEq, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct OrderBookEventEnvelope {
+    pub tx_sequence_number: TxSequenceNumber,
+    pub tx_digest: TransactionDigest,
+    pub event: OrderBookEvent,
+}
+
+impl OrderBookEvent {
+    /// The order book event `event` is, if it is one.
+    pub fn from_event(event: &Event) -> Option<Self> {
+        let (type_, contents) = match event {
+            Event::MoveEvent { type_, contents } => (type_, contents),
+            _ => return None,
+        };
+        if type_.address != SUI_FRAMEWORK_ADDRESS
+            || type_.module.as_ident_str() != ORDER_BOOK_MODULE_NAME
+        {
+            return None;
+        }
+        let name = type_.name.as_ident_str();
+        if name == POOL_CREATED_STRUCT_NAME {
+            bcs::from_bytes(contents).ok().map(Self::PoolCreated)
+        } else if name == ORDER_PLACED_STRUCT_NAME {
+            bcs::from_bytes(contents).ok().map(Self::OrderPlaced)
+        } else if name == ORDER_FILLED_STRUCT_NAME {
+            bcs::from_bytes(contents).ok().map(Self::OrderFilled)
+        } else if name == ORDER_CANCELED_STRUCT_NAME {
+            bcs::from_bytes(contents).ok().map(Self::OrderCanceled)
+        } else {
+            None
+        }
+    }
+
+    /// The ID of the pool of the event.
+    pub fn pool_id(&self) -> ObjectID {
+        match self {
+            Self::PoolCreated(event) => event.pool_id,
+            Self::OrderPlaced(event) => event.pool_id,
+            Self::OrderFilled(event) => event.pool_id,
+            Self::OrderCanceled(event) => event.pool_id,
+        }
+    }
+}
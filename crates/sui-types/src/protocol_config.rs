@@ -0,0 +1,227 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! The parameters of the protocol every validator must agree on, such as the gas schedule,
+//! keyed by protocol version. The version of an epoch is recorded in the system state and only
+//! changes at epoch boundaries, so a rebalance of the costs ships as the tables of a new version,
+//! which every validator switches to in the same epoch, rather than as a fork of the code.
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+use move_core_types::gas_schedule::CostTable;
+use move_vm_types::gas_schedule::INITIAL_COST_SCHEDULE;
+use once_cell::sync::Lazy;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::error::{SuiError, SuiResult};
+
+#[cfg(test)]
+#[path = "unit_tests/protocol_config_tests.rs"]
+mod protocol_config_tests;
+
+#[derive(
+    Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize, JsonSchema,
+)]
+pub struct ProtocolVersion(u64);
+
+impl ProtocolVersion {
+    /// The version of the first epoch.
+    pub const MIN: Self = Self(1);
+    /// The latest version this binary can run.
+    pub const MAX: Self = Self(2);
+
+    pub const fn new(version: u64) -> Self {
+        Self(version)
+    }
+
+    pub fn as_u64(&self) -> u64 {
+        self.0
+    }
+}
+
+impl fmt::Display for ProtocolVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// The cost of a call to a Sui framework native, in internal gas units: a cost per call, and a
+/// cost per unit of the work the native does, such as a byte hashed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct NativeCost {
+    pub base: u64,
+    pub per_unit: u64,
+}
+
+impl NativeCost {
+    pub const fn new(base: u64, per_unit: u64) -> Self {
+        Self { base, per_unit }
+    }
+
+    pub fn of(&self, units: u64) -> u64 {
+        self.base
+            .saturating_add(self.per_unit.saturating_mul(units))
+    }
+}
+
+/// The parameters of a protocol version. The costs are in internal gas units.
+#[derive(Clone, Debug)]
+pub struct ProtocolConfig {
+    pub version: ProtocolVersion,
+
+    /// A flat fee charged for every transaction. This is also the mimmum amount of
+    /// gas charged for a transaction.
+    pub min_transaction_cost: u64,
+    /// Computation cost per byte charged for package publish. This cost is primarily
+    /// determined by the cost to verify and link a package. Note that this does not
+    /// include the cost of writing the package to the store.
+    pub package_publish_per_byte_cost: u64,
+    /// Per byte cost to read objects from the store. This is computation cost instead of
+    /// storage cost because it does not change the amount of data stored on the db.
+    pub object_read_per_byte_cost: u64,
+    /// Per byte cost to write objects to the store. This is computation cost instead of
+    /// storage cost because it does not change the amount of data stored on the db.
+    pub object_mutation_per_byte_cost: u64,
+    /// Cost to use shared objects in a transaction, which requires full consensus.
+    pub consensus_cost: u64,
+    /// Unit cost of a byte in the storage. This will be used both for charging for
+    /// new storage as well as rebating for deleting storage. That is, we expect users to
+    /// get full refund on the object storage when it's deleted.
+    /// TODO: We should introduce a flat fee on storage that does not get refunded even
+    /// when objects are deleted. This cost covers the cost of storing transaction metadata
+    /// which will always be there even after the objects are deleted.
+    pub storage_per_byte_cost: u64,
+
+    /// The most objects a transaction can write, created, mutated, unwrapped or transferred.
+    pub max_written_objects: u64,
+    /// The most events a transaction can emit, including those of its transfers and deletions.
+    pub max_events: u64,
+    /// The most bytes a transaction can write, as the sum of the sizes of the objects it writes.
+    pub max_written_bytes: u64,
+
+    /// The most bytes the modules of a published package can add up to.
+    pub max_package_size: u64,
+    /// Whether validators accept transactions and certificates sent as a stream of chunks, for
+    /// those too large to send in a single message.
+    pub chunked_messages: bool,
+    /// The most bytes a transaction or certificate sent in chunks can add up to.
+    pub max_chunked_message_size: u64,
+
+    /// Whether a transaction can use a shared object read-only, to be assigned the same version
+    /// of it as the other transactions reading it rather than a version of its own.
+    pub read_only_shared_objects: bool,
+    /// Whether an object that already exists (i.e. was not created by the transaction) can be
+    /// shared.
+    pub allow_sharing_existing_objects: bool,
+
+    /// The cost of each Move instruction and of each Move standard library native, and the
+    /// scale of internal gas units to gas units.
+    pub move_cost_table: CostTable,
+    /// The cost of the Sui framework natives which are not priced by `move_cost_table`, by
+    /// `<module>::<function>`, or `<module>::<function>_<variant>` for the natives whose cost
+    /// depends on an argument, such as the curve of a proof.
+    pub native_costs: BTreeMap<&'static str, NativeCost>,
+}
+
+static PROTOCOL_CONFIGS: Lazy<Vec<ProtocolConfig>> = Lazy::new(|| {
+    (ProtocolVersion::MIN.0..=ProtocolVersion::MAX.0)
+        .map(|version| config_for_version(ProtocolVersion(version)))
+        .collect()
+});
+
+impl ProtocolConfig {
+    /// The config of `version`, or an error if this binary can't run it.
+    pub fn get_for_version(version: ProtocolVersion) -> SuiResult<&'static ProtocolConfig> {
+        if version < ProtocolVersion::MIN || version > ProtocolVersion::MAX {
+            return Err(SuiError::UnsupportedProtocolVersion {
+                version: version.0,
+                min_version: ProtocolVersion::MIN.0,
+                max_version: ProtocolVersion::MAX.0,
+            });
+        }
+        Ok(&PROTOCOL_CONFIGS[(version.0 - ProtocolVersion::MIN.0) as usize])
+    }
+
+    pub fn get_for_max_version() -> &'static ProtocolConfig {
+        PROTOCOL_CONFIGS
+            .last()
+            .expect("There is a config for every supported version")
+    }
+
+    /// The cost of `native`, which must be in `native_costs`.
+    pub fn native_cost(&self, native: &str) -> NativeCost {
+        *self.native_costs.get(native).unwrap_or_else(|| {
+            panic!(
+                "No cost for the native {native} in protocol version {}",
+                self.version
+            )
+        })
+    }
+}
+
+fn config_for_version(version: ProtocolVersion) -> ProtocolConfig {
+    // Each version after the first starts from the values of the version before it, and changes
+    // some of them, in a block `if version >= ProtocolVersion(n)` following the first version's.
+    // The values of a version never change once a network runs it.
+    let mut config = ProtocolConfig {
+        version,
+        // TODO: The following numbers are arbitrary at this point.
+        min_transaction_cost: 10000,
+        package_publish_per_byte_cost: 80,
+        object_read_per_byte_cost: 15,
+        object_mutation_per_byte_cost: 40,
+        consensus_cost: 100000,
+        storage_per_byte_cost: 100,
+        // A certificate writing up to the limits still executes in a fraction of a second, so
+        // that a single transaction can't stall the validators executing it.
+        max_written_objects: 2048,
+        max_events: 1024,
+        max_written_bytes: 5_000_000,
+        // A package publish transaction is sent in a single message.
+        max_package_size: 1_000_000,
+        chunked_messages: false,
+        max_chunked_message_size: 0,
+        read_only_shared_objects: true,
+        allow_sharing_existing_objects: false,
+        move_cost_table: INITIAL_COST_SCHEDULE.clone(),
+        // The costs of the signature checks weigh how much slower than a hash of a short message
+        // they are. Proofs and VDFs cost about as much as the pairings and exponentiations
+        // checking them: per public input and per square byte of the modulus.
+        native_costs: BTreeMap::from([
+            ("Crypto::blake2b256", NativeCost::new(64, 1)),
+            ("Crypto::bls12381_verify", NativeCost::new(50_000, 1)),
+            ("Crypto::ed25519_verify", NativeCost::new(3_000, 1)),
+            ("Crypto::keccak256", NativeCost::new(64, 1)),
+            ("Crypto::secp256k1_verify", NativeCost::new(4_000, 1)),
+            (
+                "Groth16::prepare_verifying_key_bls12381",
+                NativeCost::new(300_000, 0),
+            ),
+            (
+                "Groth16::prepare_verifying_key_bn254",
+                NativeCost::new(150_000, 0),
+            ),
+            (
+                "Groth16::verify_groth16_proof_bls12381",
+                NativeCost::new(800_000, 20_000),
+            ),
+            (
+                "Groth16::verify_groth16_proof_bn254",
+                NativeCost::new(400_000, 10_000),
+            ),
+            ("Types::type_name", NativeCost::new(50, 1)),
+            ("VDF::hash_to_input", NativeCost::new(100, 2)),
+            ("VDF::verify_wesolowski", NativeCost::new(50_000, 2)),
+        ]),
+    };
+    if version >= ProtocolVersion(2) {
+        // Large packages are published in transactions sent in chunks, and write as many bytes.
+        config.max_package_size = 8_000_000;
+        config.chunked_messages = true;
+        config.max_chunked_message_size = 10_000_000;
+        config.max_written_bytes = 10_000_000;
+    }
+    config
+}
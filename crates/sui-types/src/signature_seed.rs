@@ -6,6 +6,7 @@
 use crate::base_types::SuiAddress;
 use crate::crypto::{KeyPair, Signable, Signature};
 use crate::error::SuiError;
+use crate::intent::IntentScoped;
 use hkdf::Hkdf;
 use rand::{CryptoRng, RngCore};
 use sha3::Sha3_256;
@@ -171,6 +172,7 @@ impl SignatureSeed {
     /// ```
     /// use serde::{Deserialize, Serialize};
     /// use sui_types::crypto::BcsSignable;
+    /// use sui_types::intent::{IntentScope, IntentScoped};
     /// use sui_types::signature_seed::SignatureSeed;
     ///
     /// #[derive(Serialize, Deserialize)]
@@ -178,6 +180,10 @@ impl SignatureSeed {
     ///
     /// impl BcsSignable for Foo {}
     ///
+    /// impl IntentScoped for Foo {
+    ///     const INTENT_SCOPE: IntentScope = IntentScope::PersonalMessage;
+    /// }
+    ///
     /// # fn main() {
     ///     // In production this SHOULD be a secret seed value, here we pin it for demo purposes.
     ///     let seed = SignatureSeed::from_bytes(&[5u8; 32]).unwrap();
@@ -219,7 +225,7 @@ impl SignatureSeed {
         value: &T,
     ) -> Result<Signature, signature::Error>
     where
-        T: Signable<Vec<u8>>,
+        T: Signable<Vec<u8>> + IntentScoped,
     {
         let keypair = SignatureSeed::new_deterministic_keypair(self, id, domain)
             .map_err(|_| signature::Error::new())?;
@@ -0,0 +1,84 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Formal snapshots of the objects live at the end of an epoch. The handoff of each epoch to the
+//! next committee commits to the digest of the live objects, so that a node trusting the genesis
+//! committee can start from a snapshot once it verified the chain of handoffs up to its epoch,
+//! instead of executing every transaction since genesis.
+
+use serde::{Deserialize, Serialize};
+use sha3::{Digest, Sha3_256};
+
+use crate::base_types::ObjectRef;
+use crate::committee::{Committee, EpochId};
+use crate::error::{SuiError, SuiResult};
+use crate::light_client::verify_committee_chain;
+use crate::messages_checkpoint::CertifiedEpochHandoff;
+use crate::object::Object;
+
+#[cfg(test)]
+#[path = "unit_tests/snapshot_tests.rs"]
+mod snapshot_tests;
+
+pub type LiveObjectsDigest = [u8; 32];
+
+/// The digest of the live objects given by reference, in the order of their IDs.
+pub fn live_objects_digest<'a>(
+    object_refs: impl IntoIterator<Item = &'a ObjectRef>,
+) -> LiveObjectsDigest {
+    let mut hasher = Sha3_256::default();
+    for object_ref in object_refs {
+        hasher.update(bcs::to_bytes(object_ref).expect("Serializing a reference cannot fail"));
+    }
+    hasher.finalize().into()
+}
+
+/// The objects live at the end of an epoch, with the handoffs of every epoch up to it.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ObjectSnapshot {
+    /// The handoffs of the epochs from the genesis one to the epoch of the snapshot, in order.
+    pub handoffs: Vec<CertifiedEpochHandoff>,
+    /// The live objects, in the order of their IDs.
+    pub objects: Vec<Object>,
+}
+
+impl ObjectSnapshot {
+    /// The epoch at the end of which the objects were live.
+    pub fn epoch(&self) -> Option<EpochId> {
+        self.handoffs.last().map(|handoff| handoff.handoff.epoch)
+    }
+
+    /// Check that the objects are the ones the committees handed off from `genesis_committee`
+    /// committed to at the end of the epoch of the snapshot. Returns the committee of the epoch
+    /// after it, which the node starting from the snapshot follows.
+    pub fn verify(&self, genesis_committee: Committee) -> SuiResult<Committee> {
+        let handoff = self
+            .handoffs
+            .last()
+            .ok_or_else(|| SuiError::InvalidSnapshot {
+                error: "The snapshot has no handoff".to_string(),
+            })?;
+        let next_committee = verify_committee_chain(genesis_committee, &self.handoffs)?;
+        let object_refs: Vec<_> = self
+            .objects
+            .iter()
+            .map(|object| object.compute_object_reference())
+            .collect();
+        fp_ensure!(
+            object_refs.windows(2).all(|pair| pair[0].0 < pair[1].0),
+            SuiError::InvalidSnapshot {
+                error: "The objects are not in the order of their IDs".to_string(),
+            }
+        );
+        fp_ensure!(
+            live_objects_digest(&object_refs) == handoff.handoff.live_objects_digest,
+            SuiError::InvalidSnapshot {
+                error: format!(
+                    "The objects are not the ones live at the end of epoch {}",
+                    handoff.handoff.epoch
+                ),
+            }
+        );
+        Ok(next_committee)
+    }
+}
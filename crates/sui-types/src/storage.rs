@@ -21,6 +21,16 @@ pub enum DeleteKind {
     Wrap,
 }
 
+/// What a transaction wrote so far, checked against the execution limits of the protocol.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct WriteTotals {
+    /// The objects written, created, mutated or unwrapped. Deleted objects are not counted.
+    pub objects: u64,
+    pub events: u64,
+    /// The sum of the sizes of the objects written.
+    pub bytes: u64,
+}
+
 /// An abstraction of the (possibly distributed) store for objects, and (soon) events and transactions
 pub trait Storage {
     fn reset(&mut self);
@@ -37,8 +47,25 @@ pub trait Storage {
     fn log_event(&mut self, event: Event);
 
     fn delete_object(&mut self, id: &ObjectID, version: SequenceNumber, kind: DeleteKind);
+
+    /// Record `object`, a child object loaded during execution, as read by the transaction, as
+    /// if it were among its inputs.
+    fn record_child_object(&mut self, object: Object);
+
+    /// What the transaction wrote so far.
+    fn write_totals(&self) -> WriteTotals;
 }
 
 pub trait BackingPackageStore {
     fn get_package(&self, package_id: &ObjectID) -> SuiResult<Option<Object>>;
+
+    /// Read the latest version of an object, for the child objects loaded during execution.
+    fn get_object(&self, object_id: &ObjectID) -> SuiResult<Option<Object>>;
+}
+
+/// Reads the child objects that a Move call loads on first access, through
+/// `Transfer::take_child_object`, rather than from the inputs of its transaction.
+pub trait ChildObjectResolver {
+    /// The latest version of the object `child`, if it is owned by the object `parent`.
+    fn read_child_object(&self, parent: &ObjectID, child: &ObjectID) -> SuiResult<Option<Object>>;
 }
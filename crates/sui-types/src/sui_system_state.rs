@@ -6,11 +6,22 @@ use move_core_types::{
 };
 use serde::{Deserialize, Serialize};
 
-use crate::{balance::Balance, coin::TreasuryCap, id::VersionedID, SUI_FRAMEWORK_ADDRESS};
+use crate::{
+    balance::Balance, coin::TreasuryCap, id::VersionedID, object::Object, SUI_FRAMEWORK_ADDRESS,
+};
 
 const SUI_SYSTEM_STATE_STRUCT_NAME: &IdentStr = ident_str!("SuiSystemState");
 pub const SUI_SYSTEM_MODULE_NAME: &IdentStr = ident_str!("SuiSystem");
 pub const ADVANCE_EPOCH_FUNCTION_NAME: &IdentStr = ident_str!("advance_epoch");
+const DELEGATION_MODULE_NAME: &IdentStr = ident_str!("Delegation");
+const DELEGATION_STRUCT_NAME: &IdentStr = DELEGATION_MODULE_NAME;
+
+#[cfg(test)]
+#[path = "unit_tests/sui_system_state_tests.rs"]
+mod sui_system_state_tests;
+
+/// The number of epochs in a year, at one epoch a day.
+pub const EPOCHS_PER_YEAR: u64 = 365;
 
 /// Rust version of the Move Sui::SuiSystem::SystemParameters type
 #[derive(Debug, Serialize, Deserialize, Clone, Eq, PartialEq)]
@@ -30,11 +41,30 @@ pub struct MoveOption<T> {
 pub struct ValidatorMetadata {
     pub sui_address: AccountAddress,
     pub pubkey_bytes: Vec<u8>,
+    pub protocol_pubkey_bytes: Vec<u8>,
+    pub protocol_key_proof: Vec<u8>,
     pub name: Vec<u8>,
     pub net_address: Vec<u8>,
+    pub description: Vec<u8>,
+    pub image_url: Vec<u8>,
+    pub project_url: Vec<u8>,
+    pub p2p_address: Vec<u8>,
+    pub consensus_address: Vec<u8>,
     pub next_epoch_stake: u64,
 }
 
+/// Rust version of the Move Sui::Validator::ValidatorMetadataUpdate type
+#[derive(Debug, Serialize, Deserialize, Clone, Eq, PartialEq)]
+pub struct ValidatorMetadataUpdate {
+    pub name: Vec<u8>,
+    pub description: Vec<u8>,
+    pub image_url: Vec<u8>,
+    pub project_url: Vec<u8>,
+    pub net_address: Vec<u8>,
+    pub p2p_address: Vec<u8>,
+    pub consensus_address: Vec<u8>,
+}
+
 /// Rust version of the Move Sui::Validator::Validator type
 #[derive(Debug, Serialize, Deserialize, Clone, Eq, PartialEq)]
 pub struct Validator {
@@ -48,6 +78,10 @@ pub struct Validator {
     pub delegator_count: u64,
     pub pending_delegator_count: u64,
     pub pending_delegator_withdraw_count: u64,
+    pub next_epoch_pubkey_bytes: MoveOption<Vec<u8>>,
+    pub next_epoch_protocol_pubkey_bytes: MoveOption<Vec<u8>>,
+    pub next_epoch_protocol_key_proof: MoveOption<Vec<u8>>,
+    pub next_epoch_metadata_update: MoveOption<ValidatorMetadataUpdate>,
 }
 
 /// Rust version of the Move Sui::ValidatorSet::ValidatorSet type
@@ -60,6 +94,192 @@ pub struct ValidatorSet {
     pub pending_validators: Vec<Validator>,
     pub pending_removals: Vec<u64>,
     pub next_epoch_validators: Vec<ValidatorMetadata>,
+    pub staking_pools: Vec<StakingPool>,
+}
+
+/// Rust version of the Move Sui::StakingPool::PoolTokenExchangeRate type
+#[derive(Debug, Serialize, Deserialize, Clone, Eq, PartialEq)]
+pub struct PoolTokenExchangeRate {
+    pub epoch: u64,
+    pub sui_amount: u64,
+    pub pool_token_amount: u64,
+}
+
+impl PoolTokenExchangeRate {
+    fn sui_to_pool_tokens(&self, sui_amount: u64) -> u64 {
+        if self.sui_amount == 0 || self.pool_token_amount == 0 {
+            return sui_amount;
+        }
+        (sui_amount as u128 * self.pool_token_amount as u128 / self.sui_amount as u128) as u64
+    }
+
+    fn pool_tokens_to_sui(&self, pool_tokens: u64) -> u64 {
+        if self.sui_amount == 0 || self.pool_token_amount == 0 {
+            return pool_tokens;
+        }
+        (pool_tokens as u128 * self.sui_amount as u128 / self.pool_token_amount as u128) as u64
+    }
+
+    /// The SUI a pool token is worth, unless the pool has no tokens.
+    fn sui_per_pool_token(&self) -> Option<f64> {
+        if self.sui_amount == 0 || self.pool_token_amount == 0 {
+            return None;
+        }
+        Some(self.sui_amount as f64 / self.pool_token_amount as f64)
+    }
+}
+
+/// Rust version of the Move Sui::StakingPool::StakingPool type
+#[derive(Debug, Serialize, Deserialize, Clone, Eq, PartialEq)]
+pub struct StakingPool {
+    pub validator_address: AccountAddress,
+    pub sui_balance: u64,
+    pub pool_token_balance: u64,
+    pub rewards_pool: Balance,
+    pub pending_delegation: u64,
+    pub exchange_rates: Vec<PoolTokenExchangeRate>,
+}
+
+impl StakingPool {
+    /// The rewards the delegation of `principal` activated in `activation_epoch` earned, as
+    /// `Sui::StakingPool::estimated_rewards` computes them, if withdrawn in `current_epoch`.
+    pub fn estimated_rewards(
+        &self,
+        principal: u64,
+        activation_epoch: u64,
+        current_epoch: u64,
+    ) -> u64 {
+        if activation_epoch > current_epoch {
+            return 0;
+        }
+        let activation_rate = self
+            .exchange_rates
+            .iter()
+            .rev()
+            .find(|rate| rate.epoch <= activation_epoch)
+            .cloned()
+            .unwrap_or(PoolTokenExchangeRate {
+                epoch: activation_epoch,
+                sui_amount: 0,
+                pool_token_amount: 0,
+            });
+        let current_rate = PoolTokenExchangeRate {
+            epoch: current_epoch,
+            sui_amount: self.sui_balance,
+            pool_token_amount: self.pool_token_balance,
+        };
+        let value = current_rate.pool_tokens_to_sui(activation_rate.sui_to_pool_tokens(principal));
+        value
+            .saturating_sub(principal)
+            .min(self.rewards_pool.value())
+    }
+
+    /// The annual yield of the delegations of the pool in `current_epoch`: the growth of the
+    /// SUI a pool token is worth over the last `EPOCHS_PER_YEAR` epochs, or over the epochs
+    /// since the pool first had delegations if there are fewer, compounded over a year.
+    pub fn apy(&self, current_epoch: u64) -> f64 {
+        let start_epoch = current_epoch.saturating_sub(EPOCHS_PER_YEAR);
+        let start_rate = match self
+            .exchange_rates
+            .iter()
+            .rev()
+            .find(|rate| rate.epoch <= start_epoch)
+            .or_else(|| self.exchange_rates.first())
+        {
+            Some(rate) => rate,
+            None => return 0.0,
+        };
+        let current_rate = PoolTokenExchangeRate {
+            epoch: current_epoch,
+            sui_amount: self.sui_balance,
+            pool_token_amount: self.pool_token_balance,
+        };
+        let epochs = current_epoch.saturating_sub(start_rate.epoch);
+        match (
+            start_rate.sui_per_pool_token(),
+            current_rate.sui_per_pool_token(),
+        ) {
+            (Some(start), Some(current)) if epochs > 0 => {
+                (current / start).powf(EPOCHS_PER_YEAR as f64 / epochs as f64) - 1.0
+            }
+            _ => 0.0,
+        }
+    }
+}
+
+/// Rust version of the Move Sui::ZkLogin::Jwk type
+#[derive(Debug, Serialize, Deserialize, Clone, Eq, PartialEq)]
+pub struct ZkLoginJwk {
+    pub iss: Vec<u8>,
+    pub kid: Vec<u8>,
+    pub modulus: Vec<u8>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Eq, PartialEq)]
+pub struct ZkLoginProposal {
+    pub jwks: Vec<ZkLoginJwk>,
+    pub verifying_key: Vec<u8>,
+    pub voters: Vec<AccountAddress>,
+    pub voter_stakes: Vec<u64>,
+    pub stake: u64,
+}
+
+/// Rust version of the Move Sui::ZkLogin::ZkLoginState type
+#[derive(Debug, Serialize, Deserialize, Clone, Eq, PartialEq)]
+pub struct ZkLoginState {
+    pub verifying_key: Vec<u8>,
+    pub jwks: Vec<ZkLoginJwk>,
+    pub proposals: Vec<ZkLoginProposal>,
+}
+
+/// Rust version of the Move Sui::ValidatorReports::Report type
+#[derive(Debug, Serialize, Deserialize, Clone, Eq, PartialEq)]
+pub struct ValidatorReport {
+    pub reported: AccountAddress,
+    pub reporters: Vec<AccountAddress>,
+}
+
+/// Rust version of the Move Sui::ValidatorReports::ValidatorReports type
+#[derive(Debug, Serialize, Deserialize, Clone, Eq, PartialEq)]
+pub struct ValidatorReports {
+    pub reports: Vec<ValidatorReport>,
+}
+
+/// Rust version of the Move Sui::Bridge::TransferKey type
+#[derive(Debug, Serialize, Deserialize, Clone, Eq, PartialEq)]
+pub struct BridgeTransferKey {
+    pub source_chain: u8,
+    pub nonce: u64,
+}
+
+/// Rust version of the Move Sui::Bridge::BridgeState type
+#[derive(Debug, Serialize, Deserialize, Clone, Eq, PartialEq)]
+pub struct BridgeState {
+    pub treasury: Balance,
+    pub next_nonce: u64,
+    pub claimed: Vec<BridgeTransferKey>,
+}
+
+/// Rust version of the Move Sui::FrameworkUpgrade::Upgrade type
+#[derive(Debug, Serialize, Deserialize, Clone, Eq, PartialEq)]
+pub struct FrameworkUpgrade {
+    pub digest: Vec<u8>,
+    pub protocol_version: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Eq, PartialEq)]
+pub struct FrameworkUpgradeProposal {
+    pub upgrade: FrameworkUpgrade,
+    pub voters: Vec<AccountAddress>,
+    pub voter_stakes: Vec<u64>,
+    pub stake: u64,
+}
+
+/// Rust version of the Move Sui::FrameworkUpgrade::FrameworkUpgradeState type
+#[derive(Debug, Serialize, Deserialize, Clone, Eq, PartialEq)]
+pub struct FrameworkUpgradeState {
+    pub approved: MoveOption<FrameworkUpgrade>,
+    pub proposals: Vec<FrameworkUpgradeProposal>,
 }
 
 /// Rust version of the Move Sui::SuiSystem::SuiSystemState type
@@ -71,10 +291,42 @@ pub struct SuiSystemState {
     pub treasury_cap: TreasuryCap,
     pub storage_fund: Balance,
     pub parameters: SystemParameters,
-    pub delegation_reward: Balance,
+    pub zk_login: ZkLoginState,
+    pub validator_reports: ValidatorReports,
+    pub bridge: BridgeState,
+    pub protocol_version: u64,
+    pub framework_upgrade: FrameworkUpgradeState,
     // TODO: Use getters instead of all pub.
 }
 
+/// Rust version of the Move Sui::Delegation::Delegation type
+#[derive(Debug, Serialize, Deserialize, Clone, Eq, PartialEq)]
+pub struct Delegation {
+    pub id: VersionedID,
+    pub principal: Balance,
+    pub activation_epoch: u64,
+    pub validator_address: AccountAddress,
+}
+
+impl Delegation {
+    pub fn type_() -> StructTag {
+        StructTag {
+            address: SUI_FRAMEWORK_ADDRESS,
+            name: DELEGATION_STRUCT_NAME.to_owned(),
+            module: DELEGATION_MODULE_NAME.to_owned(),
+            type_params: vec![],
+        }
+    }
+
+    pub fn try_from_object(object: &Object) -> Option<Self> {
+        let move_object = object.data.try_as_move()?;
+        if move_object.type_ != Self::type_() {
+            return None;
+        }
+        bcs::from_bytes(move_object.contents()).ok()
+    }
+}
+
 impl SuiSystemState {
     pub fn type_() -> StructTag {
         StructTag {
@@ -84,4 +336,10 @@ impl SuiSystemState {
             type_params: vec![],
         }
     }
+
+    /// The framework upgrade validators with a quorum of stake voted for, which the epoch change
+    /// applies.
+    pub fn approved_framework_upgrade(&self) -> Option<&FrameworkUpgrade> {
+        self.framework_upgrade.approved.vec.first()
+    }
 }
@@ -13,6 +13,7 @@ use crate::crypto::{get_key_pair_from_bytes, AuthoritySignature, KeyPair};
 use crate::{
     crypto::{get_key_pair, BcsSignable, Signature},
     gas_coin::GasCoin,
+    intent::{IntentScope, IntentScoped},
     object::Object,
     SUI_FRAMEWORK_ADDRESS,
 };
@@ -24,11 +25,19 @@ struct Foo(String);
 
 impl BcsSignable for Foo {}
 
+impl IntentScoped for Foo {
+    const INTENT_SCOPE: IntentScope = IntentScope::PersonalMessage;
+}
+
 #[derive(Serialize, Deserialize)]
 struct Bar(String);
 
 impl BcsSignable for Bar {}
 
+impl IntentScoped for Bar {
+    const INTENT_SCOPE: IntentScope = IntentScope::PersonalMessage;
+}
+
 #[test]
 fn test_signatures() {
     let (addr1, sec1) = get_key_pair();
@@ -294,7 +303,7 @@ fn test_signature_serde_not_human_readable() {
     let bcs_serialized = bcs::to_bytes(&sig).unwrap();
 
     assert_eq!(serialized, bcs_serialized);
-    assert_eq!(sig.0.to_bytes(), serialized[..]);
+    assert_eq!(sig.as_ref(), &serialized[..]);
     let deserialized: AuthoritySignature = bincode::deserialize(&serialized).unwrap();
     assert_eq!(deserialized, sig);
 }
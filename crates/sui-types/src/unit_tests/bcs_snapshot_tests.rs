@@ -0,0 +1,281 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Serializes a sample of each type sent between clients, authorities and full nodes, and checks
+//! its BCS bytes against the snapshot in `data/bcs_snapshots.json`, so that a change to the
+//! layout of a wire type, which would split the network between the nodes running the old layout
+//! and the ones running the new one, is always deliberate. Where `tests/staged/sui.yaml` of
+//! sui-core traces the formats of the types, this checks the actual bytes. When a change is
+//! intended, update the snapshot with
+//!
+//! ```text
+//! UPDATE_BCS_SNAPSHOTS=1 cargo test -p sui-types bcs_snapshot
+//! ```
+//!
+//! and check it in. The test fails when the snapshot is missing, rather than recording it, so
+//! that deleting the file doesn't pass the check.
+
+use super::*;
+
+use crate::crypto::{get_key_pair_from_bytes, AuthoritySignature, KeyPair};
+use crate::event::TransferType;
+use crate::messages_checkpoint::{CheckpointContents, CheckpointSummary, SignedCheckpoint};
+use crate::move_package::MovePackage;
+use crate::object::Data;
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+const BCS_SNAPSHOT_FILE: &str = "src/unit_tests/data/bcs_snapshots.json";
+
+const UPDATE_BCS_SNAPSHOTS: &str = "UPDATE_BCS_SNAPSHOTS";
+
+#[test]
+fn test_bcs_snapshot() {
+    let samples = wire_samples();
+
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join(BCS_SNAPSHOT_FILE);
+    if std::env::var_os(UPDATE_BCS_SNAPSHOTS).is_some() {
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(
+            &path,
+            serde_json::to_string_pretty(&samples).unwrap() + "\n",
+        )
+        .unwrap();
+        return;
+    }
+
+    assert!(
+        path.exists(),
+        "The BCS snapshot {} is missing: record it with `{UPDATE_BCS_SNAPSHOTS}=1 cargo test -p sui-types bcs_snapshot`",
+        path.display()
+    );
+    let snapshot: BTreeMap<String, String> =
+        serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+    let changes = samples
+        .keys()
+        .chain(snapshot.keys())
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .filter(|name| samples.get(*name) != snapshot.get(*name))
+        .map(|name| {
+            format!(
+                "{name}: expected {:?}, got {:?}",
+                snapshot.get(name),
+                samples.get(name)
+            )
+        })
+        .collect::<Vec<_>>();
+    assert!(
+        changes.is_empty(),
+        "The serialization of the wire types changed:\n{}\n\
+         If the change is intended, and the network upgraded with it, update the snapshot with `{UPDATE_BCS_SNAPSHOTS}=1 cargo test -p sui-types bcs_snapshot`",
+        changes.join("\n")
+    );
+}
+
+fn object_ref(id: u8, version: u64) -> ObjectRef {
+    (
+        dbg_object_id(id),
+        SequenceNumber::from(version),
+        ObjectDigest::new([id; 32]),
+    )
+}
+
+fn execution_digests(n: u8) -> ExecutionDigests {
+    ExecutionDigests::new(
+        TransactionDigest::new([n; 32]),
+        TransactionEffectsDigest([n + 1; 32]),
+    )
+}
+
+/// A key pair which is the same on every run, for the signatures to be too.
+fn fixed_key_pair() -> (SuiAddress, KeyPair) {
+    let secret = ed25519_dalek::SecretKey::from_bytes(&[7; 32]).unwrap();
+    let public = ed25519_dalek::PublicKey::from(&secret);
+    get_key_pair_from_bytes(&[secret.to_bytes(), public.to_bytes()].concat())
+}
+
+/// Signs every message with the same signature, as the snapshot checks the layout of the
+/// signed checkpoint, not the signing of protocol messages.
+struct FixedSigner;
+
+impl signature::Signer<AuthoritySignature> for FixedSigner {
+    fn try_sign(&self, _message: &[u8]) -> Result<AuthoritySignature, signature::Error> {
+        <AuthoritySignature as signature::Signature>::from_bytes(&[6; 48])
+    }
+}
+
+fn transaction_samples(sender: SuiAddress) -> Vec<(&'static str, TransactionData)> {
+    let recipient = dbg_addr(2);
+    let gas = object_ref(1, 3);
+    let call = MoveCall {
+        package: object_ref(2, 1),
+        module: Identifier::new("Module").unwrap(),
+        function: Identifier::new("function").unwrap(),
+        type_arguments: vec![TypeTag::U64],
+        arguments: vec![
+            CallArg::Pure(vec![1, 2, 3]),
+            CallArg::ImmOrOwnedObject(object_ref(3, 2)),
+            CallArg::SharedObject(dbg_object_id(4)),
+        ],
+    };
+    vec![
+        (
+            "transfer_coin",
+            TransactionData::new_transfer(recipient, object_ref(5, 1), sender, gas, 1000),
+        ),
+        (
+            "transfer_sui",
+            TransactionData::new_transfer_sui(recipient, sender, Some(100), gas, 1000),
+        ),
+        (
+            "move_call",
+            TransactionData::new(
+                TransactionKind::Single(SingleTransactionKind::Call(call.clone())),
+                sender,
+                gas,
+                1000,
+            ),
+        ),
+        (
+            "publish",
+            TransactionData::new_module(sender, gas, vec![vec![0xa1, 0x1c, 0xeb, 0x0b]], 1000),
+        ),
+        (
+            "batch",
+            TransactionData::new(
+                TransactionKind::Batch(vec![
+                    SingleTransactionKind::Call(call),
+                    SingleTransactionKind::TransferCoin(TransferCoin {
+                        recipient,
+                        object_ref: object_ref(5, 1),
+                    }),
+                ]),
+                sender,
+                gas,
+                1000,
+            ),
+        ),
+    ]
+}
+
+fn effects_sample(status: ExecutionStatus) -> TransactionEffects {
+    let sender = dbg_addr(1);
+    TransactionEffects {
+        status,
+        shared_objects: vec![object_ref(4, 7)],
+        transaction_digest: TransactionDigest::new([9; 32]),
+        created: vec![(object_ref(10, 1), Owner::AddressOwner(sender))],
+        mutated: vec![
+            (object_ref(1, 4), Owner::AddressOwner(sender)),
+            (object_ref(4, 8), Owner::Shared),
+        ],
+        unwrapped: vec![(object_ref(11, 2), Owner::ObjectOwner(dbg_addr(12)))],
+        deleted: vec![object_ref(13, 5)],
+        wrapped: vec![object_ref(14, 3)],
+        unwrapped_then_deleted: vec![object_ref(15, 6)],
+        owner_changes: vec![OwnerChange {
+            object_id: dbg_object_id(1),
+            version: SequenceNumber::from(4),
+            previous_owner: Owner::AddressOwner(sender),
+            owner: Owner::AddressOwner(dbg_addr(2)),
+        }],
+        gas_object: (object_ref(1, 4), Owner::AddressOwner(sender)),
+        events: vec![
+            Event::Publish {
+                package_id: dbg_object_id(16),
+            },
+            Event::TransferObject {
+                object_id: dbg_object_id(1),
+                version: SequenceNumber::from(4),
+                destination_addr: dbg_addr(2),
+                type_: TransferType::ToAddress,
+            },
+            Event::NewObject(dbg_object_id(10)),
+            Event::DeleteObject(dbg_object_id(13)),
+        ],
+        dependencies: vec![TransactionDigest::new([8; 32])],
+    }
+}
+
+/// The hex of the BCS bytes of each sample, by name.
+fn wire_samples() -> BTreeMap<String, String> {
+    let (sender, key_pair) = fixed_key_pair();
+    let mut samples: Vec<(String, Vec<u8>)> = Vec::new();
+    let mut record = |name: &str, bytes: Vec<u8>| samples.push((name.to_string(), bytes));
+
+    for (name, data) in transaction_samples(sender) {
+        record(
+            &format!("transaction_data/{name}"),
+            bcs::to_bytes(&data).unwrap(),
+        );
+        let signature = Signature::new(&data, &key_pair);
+        record(
+            &format!("transaction/{name}"),
+            bcs::to_bytes(&Transaction::new(data, signature)).unwrap(),
+        );
+    }
+
+    let gas_cost = GasCostSummary {
+        computation_cost: 100,
+        storage_cost: 40,
+        storage_rebate: 10,
+    };
+    record(
+        "transaction_effects/success",
+        bcs::to_bytes(&effects_sample(ExecutionStatus::Success {
+            gas_cost: gas_cost.clone(),
+        }))
+        .unwrap(),
+    );
+    record(
+        "transaction_effects/failure",
+        bcs::to_bytes(&effects_sample(ExecutionStatus::new_failure(
+            gas_cost,
+            SuiError::ObjectNotFound {
+                object_id: dbg_object_id(5),
+            },
+        )))
+        .unwrap(),
+    );
+
+    record(
+        "object/move",
+        bcs::to_bytes(&Object::with_id_owner_gas_for_testing(
+            dbg_object_id(1),
+            sender,
+            1_000_000,
+        ))
+        .unwrap(),
+    );
+    let package = Object {
+        data: Data::Package(MovePackage::new(
+            dbg_object_id(2),
+            &BTreeMap::from([("Module".to_string(), vec![0xa1, 0x1c, 0xeb, 0x0b])]),
+        )),
+        owner: Owner::Immutable,
+        previous_transaction: TransactionDigest::new([3; 32]),
+        storage_rebate: 0,
+    };
+    record("object/package", bcs::to_bytes(&package).unwrap());
+
+    let contents =
+        CheckpointContents::new([execution_digests(1), execution_digests(3)].into_iter());
+    let summary = CheckpointSummary::new(4, &contents, Some([5; 32]));
+    record("checkpoint/contents", bcs::to_bytes(&contents).unwrap());
+    record("checkpoint/summary", bcs::to_bytes(&summary).unwrap());
+    record(
+        "checkpoint/signed",
+        bcs::to_bytes(&SignedCheckpoint::new_from_summary(
+            summary,
+            *key_pair.public_key_bytes(),
+            &FixedSigner,
+        ))
+        .unwrap(),
+    );
+
+    samples
+        .into_iter()
+        .map(|(name, bytes)| (name, hex::encode(bytes)))
+        .collect()
+}
@@ -0,0 +1,150 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use super::*;
+use crate::crypto::{get_key_pair, BcsSignable};
+use crate::intent::IntentScope;
+
+#[derive(Serialize, Deserialize)]
+struct Foo(String);
+
+impl BcsSignable for Foo {}
+
+impl IntentScoped for Foo {
+    const INTENT_SCOPE: IntentScope = IntentScope::PersonalMessage;
+}
+
+fn protocol_key(key_pair: &KeyPair) -> ProtocolPublicKey {
+    key_pair.protocol_public_key().to_public_key().unwrap()
+}
+
+#[test]
+fn test_signatures() {
+    let (_, sec1) = get_key_pair();
+    let (_, sec2) = get_key_pair();
+    let foo = Foo("hello".into());
+    let foox = Foo("hellox".into());
+
+    let s = AuthoritySignature::new(&foo, &sec1);
+    assert!(s.verify(&foo, &protocol_key(&sec1)).is_ok());
+    assert!(s.verify(&foo, &protocol_key(&sec2)).is_err());
+    assert!(s.verify(&foox, &protocol_key(&sec1)).is_err());
+}
+
+#[test]
+fn test_protocol_key_is_derived_from_the_key_pair() {
+    let (_, key_pair) = get_key_pair();
+    assert_eq!(
+        key_pair.protocol_public_key(),
+        key_pair.copy().protocol_public_key()
+    );
+    assert_ne!(
+        key_pair.protocol_public_key(),
+        get_key_pair().1.protocol_public_key()
+    );
+}
+
+#[test]
+fn test_aggregate_signatures() {
+    let key_pairs: Vec<_> = (0..3).map(|_| get_key_pair().1).collect();
+    let keys: Vec<_> = key_pairs.iter().map(protocol_key).collect();
+    let foo = Foo("hello".into());
+    let signatures: Vec<_> = key_pairs
+        .iter()
+        .map(|key_pair| AuthoritySignature::new(&foo, key_pair))
+        .collect();
+
+    let aggregate = AggregateAuthoritySignature::aggregate(&signatures).unwrap();
+    assert!(aggregate
+        .verify(&foo, &keys.iter().collect::<Vec<_>>())
+        .is_ok());
+    assert!(aggregate
+        .verify(&Foo("hellox".into()), &keys.iter().collect::<Vec<_>>())
+        .is_err());
+
+    // The aggregate only holds with the keys of all of its signers, and only theirs.
+    assert!(aggregate.verify(&foo, &[&keys[0], &keys[1]]).is_err());
+    let outsider = protocol_key(&get_key_pair().1);
+    assert!(aggregate
+        .verify(&foo, &[&keys[0], &keys[1], &outsider])
+        .is_err());
+    assert!(aggregate.verify(&foo, &[]).is_err());
+
+    assert!(AggregateAuthoritySignature::aggregate(&[]).is_err());
+}
+
+#[test]
+fn test_protocol_key_proofs() {
+    let (_, sec1) = get_key_pair();
+    let (_, sec2) = get_key_pair();
+
+    let proof = sec1.protocol_key_proof();
+    assert!(proof.verify(&sec1.protocol_public_key()).is_ok());
+    assert!(proof.verify(&sec2.protocol_public_key()).is_err());
+
+    // A signature of the key under the domain of protocol messages is no proof of possession.
+    let key = sec1.protocol_public_key();
+    let signature: AuthoritySignature = sec1.sign(key.as_ref());
+    let forged = ProtocolKeyProof::try_from(signature.as_ref()).unwrap();
+    assert!(forged.verify(&key).is_err());
+}
+
+#[test]
+fn test_signature_serde() {
+    let (_, key_pair) = get_key_pair();
+    let signature = AuthoritySignature::new(&Foo("some data".into()), &key_pair);
+
+    let serialized = bcs::to_bytes(&signature).unwrap();
+    assert_eq!(signature.as_ref(), &serialized[..]);
+    let deserialized: AuthoritySignature = bcs::from_bytes(&serialized).unwrap();
+    assert_eq!(deserialized, signature);
+
+    let serialized = serde_json::to_string(&key_pair.protocol_public_key()).unwrap();
+    let deserialized: ProtocolPublicKeyBytes = serde_json::from_str(&serialized).unwrap();
+    assert_eq!(deserialized, key_pair.protocol_public_key());
+}
+
+#[test]
+fn test_aggregate_verification_obligation() {
+    let key_pairs: Vec<_> = (0..3).map(|_| get_key_pair().1).collect();
+    let keys: Vec<_> = key_pairs.iter().map(protocol_key).collect();
+    let values: Vec<_> = (0..4).map(|i| Foo(format!("value {i}"))).collect();
+    let aggregates: Vec<_> = values
+        .iter()
+        .map(|value| {
+            let signatures: Vec<_> = key_pairs
+                .iter()
+                .map(|key_pair| AuthoritySignature::new(value, key_pair))
+                .collect();
+            AggregateAuthoritySignature::aggregate(&signatures).unwrap()
+        })
+        .collect();
+    let all_keys: Vec<_> = keys.iter().collect();
+
+    let mut obligation = AggregateVerificationObligation::default();
+    for (value, aggregate) in values.iter().zip(&aggregates) {
+        obligation.add(value, aggregate, &all_keys).unwrap();
+    }
+    assert!(obligation.verify_all().is_ok());
+
+    // Aggregates of other values fail the batch.
+    let mut obligation = AggregateVerificationObligation::default();
+    for (value, aggregate) in values.iter().zip(aggregates.iter().rev()) {
+        obligation.add(value, aggregate, &all_keys).unwrap();
+    }
+    assert!(obligation.verify_all().is_err());
+
+    // As does an aggregate missing the signature of one of its signers.
+    let mut obligation = AggregateVerificationObligation::default();
+    obligation
+        .add(&values[0], &aggregates[0], &all_keys)
+        .unwrap();
+    obligation
+        .add(&values[1], &aggregates[1], &all_keys[..2])
+        .unwrap();
+    assert!(obligation.verify_all().is_err());
+
+    assert!(AggregateVerificationObligation::default()
+        .verify_all()
+        .is_ok());
+}
@@ -0,0 +1,107 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use super::*;
+use crate::crypto::{get_key_pair, KeyPair};
+use crate::utils::make_committee_of_epoch;
+
+fn test_transfer() -> BridgeTransfer {
+    BridgeTransfer {
+        source_chain: 1,
+        target_chain: SUI_CHAIN_ID,
+        nonce: 7,
+        sender: vec![1, 2, 3],
+        recipient: SuiAddress::random_for_testing_only().to_vec(),
+        amount: 100,
+    }
+}
+
+fn sign(key_pairs: &[KeyPair], transfer: &BridgeTransfer) -> Vec<SignedBridgeTransfer> {
+    key_pairs
+        .iter()
+        .map(|key_pair| {
+            SignedBridgeTransfer::new(transfer.clone(), *key_pair.public_key_bytes(), key_pair)
+        })
+        .collect()
+}
+
+#[test]
+fn test_signed_transfer() {
+    let key_pairs: Vec<_> = (0..4).map(|_| get_key_pair().1).collect();
+    let committee = make_committee_of_epoch(0, &key_pairs);
+    let mut signed = sign(&key_pairs[..1], &test_transfer()).remove(0);
+    assert!(signed.verify(&committee).is_ok());
+
+    signed.transfer.amount += 1;
+    assert!(signed.verify(&committee).is_err());
+
+    // Only members of the committee sign transfers.
+    let (_, outsider) = get_key_pair();
+    let signed = sign(&[outsider], &test_transfer()).remove(0);
+    assert!(signed.verify(&committee).is_err());
+}
+
+#[test]
+fn test_certified_transfer() {
+    let key_pairs: Vec<_> = (0..4).map(|_| get_key_pair().1).collect();
+    let committee = make_committee_of_epoch(0, &key_pairs);
+    let transfer = test_transfer();
+
+    let certificate =
+        CertifiedBridgeTransfer::aggregate(sign(&key_pairs[..3], &transfer), &committee).unwrap();
+    assert!(certificate.verify(&committee).is_ok());
+    assert_eq!(
+        certificate.signatory_authorities(&committee).unwrap().len(),
+        3
+    );
+
+    // The receiving chain checks the aggregate with the keys of the signers alone.
+    let keys: Vec<_> = key_pairs[..3]
+        .iter()
+        .map(|key_pair| key_pair.protocol_public_key())
+        .collect();
+    let signature = &certificate.auth_signature.signature;
+    assert!(transfer.verify_aggregate(signature, &keys).is_ok());
+    assert!(transfer.verify_aggregate(signature, &keys[..2]).is_err());
+    let mut other = transfer.clone();
+    other.nonce += 1;
+    assert!(other.verify_aggregate(signature, &keys).is_err());
+
+    // Half of the stake is no quorum.
+    assert!(
+        CertifiedBridgeTransfer::aggregate(sign(&key_pairs[..2], &transfer), &committee).is_err()
+    );
+
+    // Nor are signatures of different transfers aggregated.
+    let mut signed = sign(&key_pairs[..3], &transfer);
+    signed.extend(sign(&key_pairs[3..], &other));
+    assert!(CertifiedBridgeTransfer::aggregate(signed, &committee).is_err());
+}
+
+#[test]
+fn test_transfer_of_tokens_locked_event() {
+    let locked = TokensLocked {
+        nonce: 3,
+        target_chain: 1,
+        sender: SuiAddress::random_for_testing_only(),
+        recipient: vec![4, 5, 6],
+        amount: 100,
+    };
+    let event = Event::MoveEvent {
+        type_: TokensLocked::type_(),
+        contents: bcs::to_bytes(&locked).unwrap(),
+    };
+    assert_eq!(TokensLocked::from_event(&event), Some(locked.clone()));
+
+    let transfer = BridgeTransfer::from(locked.clone());
+    assert_eq!(transfer.source_chain, SUI_CHAIN_ID);
+    assert_eq!(transfer.sender, locked.sender.to_vec());
+
+    let mut type_ = TokensLocked::type_();
+    type_.name = ident_str!("TokensClaimed").to_owned();
+    let event = Event::MoveEvent {
+        type_,
+        contents: bcs::to_bytes(&locked).unwrap(),
+    };
+    assert_eq!(TokensLocked::from_event(&event), None);
+}
@@ -0,0 +1,65 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use super::*;
+use crate::crypto::get_key_pair;
+
+fn committee_with_stakes(stakes: &[StakeUnit]) -> (Committee, Vec<AuthorityName>) {
+    let key_pairs: Vec<_> = stakes.iter().map(|_| get_key_pair().1).collect();
+    let names: Vec<_> = key_pairs
+        .iter()
+        .map(|key_pair| *key_pair.public_key_bytes())
+        .collect();
+    let voting_rights = names.iter().copied().zip(stakes.iter().copied()).collect();
+    let protocol_keys = key_pairs
+        .iter()
+        .map(|key_pair| (*key_pair.public_key_bytes(), key_pair.protocol_public_key()))
+        .collect();
+    (Committee::new(0, voting_rights, protocol_keys), names)
+}
+
+#[test]
+fn test_thresholds_are_weighted_by_stake() {
+    let (committee, names) = committee_with_stakes(&[70, 10, 10, 10]);
+    assert_eq!(committee.quorum_threshold(), 67);
+    assert_eq!(committee.validity_threshold(), 34);
+
+    // The largest authority alone holds a quorum, while the three others together don't
+    // even hold a validity threshold.
+    assert!(committee.weight_of(&names[..1]) >= committee.quorum_threshold());
+    assert!(committee.weight_of(&names[1..]) < committee.validity_threshold());
+
+    // Authorities outside of the committee count for nothing.
+    let outsider = *get_key_pair().1.public_key_bytes();
+    assert_eq!(committee.weight_of([&names[1], &outsider]), 10);
+}
+
+#[test]
+fn test_thresholds_of_large_stakes() {
+    let (committee, _) = committee_with_stakes(&[u64::MAX / 4, u64::MAX / 4, u64::MAX / 4]);
+    let total_votes = committee.total_votes as u128;
+    assert_eq!(
+        committee.quorum_threshold() as u128,
+        2 * total_votes / 3 + 1
+    );
+    assert_eq!(
+        committee.validity_threshold() as u128,
+        (total_votes + 2) / 3
+    );
+}
+
+#[test]
+fn test_consensus_weights_are_scaled_down() {
+    // Small committees keep their stakes.
+    let (committee, names) = committee_with_stakes(&[1, 2, 3]);
+    assert_eq!(committee.consensus_weight(&names[2]), 3);
+
+    let (committee, names) = committee_with_stakes(&[1, 1_000_000, 3_000_000]);
+    let weights: Vec<_> = names
+        .iter()
+        .map(|name| committee.consensus_weight(name))
+        .collect();
+    // The smallest authority keeps a vote, and the others keep their proportions.
+    assert_eq!(weights, vec![1, 2499, 7499]);
+    assert!(weights.iter().sum::<StakeUnit>() <= MAX_CONSENSUS_TOTAL_STAKE);
+}
@@ -0,0 +1,100 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use rand::rngs::OsRng;
+use signature::Signature as _;
+
+use crate::crypto::{get_key_pair, GenericSignature, SuiKeyPair, VerificationObligation};
+use crate::messages::Transaction;
+use crate::utils::transfer_from;
+
+use super::*;
+
+#[test]
+fn test_secp256k1_signature() {
+    let key = Secp256k1KeyPair::generate(&mut OsRng);
+    let data = transfer_from(key.address());
+    let signature = Secp256k1Signature::new(&data, &key);
+
+    assert!(signature.verify(&data, key.address()).is_ok());
+    // Wrong author, or other data.
+    assert!(signature.verify(&data, get_key_pair().0).is_err());
+    assert!(signature
+        .verify(&transfer_from(key.address()), key.address())
+        .is_err());
+}
+
+#[test]
+fn test_secp256r1_signature() {
+    let key = Secp256r1KeyPair::generate(&mut OsRng);
+    let data = transfer_from(key.address());
+    let signature = Secp256r1Signature::new(&data, &key);
+
+    assert!(signature.verify(&data, key.address()).is_ok());
+    assert!(signature.verify(&data, get_key_pair().0).is_err());
+    assert!(signature
+        .verify(&transfer_from(key.address()), key.address())
+        .is_err());
+}
+
+#[test]
+fn test_scheme_is_part_of_signature_and_address() {
+    let key = Secp256k1KeyPair::generate(&mut OsRng);
+    let signature = Secp256k1Signature::new(&transfer_from(key.address()), &key);
+
+    // The same bytes are not a signature of the other curve.
+    assert!(Secp256r1Signature::from_bytes(signature.as_ref()).is_err());
+    let json = serde_json::to_string(&signature).unwrap();
+    assert!(serde_json::from_str::<Secp256r1Signature>(&json).is_err());
+    assert_eq!(
+        serde_json::from_str::<Secp256k1Signature>(&json).unwrap(),
+        signature
+    );
+
+    // The same secret on the other curve gives another address.
+    let other = Secp256r1KeyPair::from_bytes(&key.to_bytes()).unwrap();
+    assert_ne!(key.address(), other.address());
+}
+
+#[test]
+fn test_generic_signature_bytes() {
+    for scheme in [
+        SignatureScheme::ED25519,
+        SignatureScheme::Secp256k1,
+        SignatureScheme::Secp256r1,
+    ] {
+        let key = SuiKeyPair::generate(scheme).unwrap();
+        let data = transfer_from(key.address());
+        let signature = key.sign(&data.to_intent_bytes()).unwrap();
+        assert_eq!(signature.scheme(), scheme);
+
+        let bytes = signature.to_bytes();
+        assert_eq!(GenericSignature::from_bytes(&bytes).unwrap(), signature);
+
+        let mut obligation = VerificationObligation::default();
+        signature
+            .add_to_verification_obligation(&data, key.address(), &mut obligation)
+            .unwrap();
+        assert!(obligation.verify_all().is_ok());
+        assert!(Transaction::new(data, signature).verify_signature().is_ok());
+    }
+}
+
+#[test]
+fn test_key_pair_serialization() {
+    for scheme in [SignatureScheme::Secp256k1, SignatureScheme::Secp256r1] {
+        let key = SuiKeyPair::generate(scheme).unwrap();
+        let json = serde_json::to_string(&key).unwrap();
+        let decoded: SuiKeyPair = serde_json::from_str(&json).unwrap();
+        assert_eq!(decoded.scheme(), scheme);
+        assert_eq!(decoded.address(), key.address());
+    }
+
+    // An ed25519 key pair keeps the encoding of `KeyPair`, which older keystores hold.
+    let (address, key) = get_key_pair();
+    let json = serde_json::to_string(&key).unwrap();
+    let decoded: SuiKeyPair = serde_json::from_str(&json).unwrap();
+    assert_eq!(decoded.scheme(), SignatureScheme::ED25519);
+    assert_eq!(decoded.address(), address);
+    assert_eq!(serde_json::to_string(&decoded).unwrap(), json);
+}
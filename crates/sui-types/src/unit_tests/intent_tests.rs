@@ -0,0 +1,112 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use signature::Signer;
+
+use crate::crypto::{
+    get_key_pair, AuthoritySignature, GenericSignature, Signature, SignatureScheme, SuiKeyPair,
+};
+use crate::utils::transfer_from;
+
+use super::*;
+
+#[test]
+fn test_intent_bytes() {
+    for scope in [
+        IntentScope::TransactionData,
+        IntentScope::TransactionEffects,
+        IntentScope::CheckpointSummary,
+        IntentScope::PersonalMessage,
+        IntentScope::AuthorityBatch,
+        IntentScope::Genesis,
+        IntentScope::EpochHandoff,
+        IntentScope::BridgeTransfer,
+    ] {
+        let intent = Intent::new(scope);
+        assert_eq!(Intent::from_bytes(&intent.to_bytes()).unwrap(), intent);
+    }
+    assert_eq!(
+        Intent::new(IntentScope::PersonalMessage).to_bytes(),
+        [3, 0, 0]
+    );
+
+    // An unknown scope, version or app, and bytes of the wrong length.
+    assert!(Intent::from_bytes(&[8, 0, 0]).is_err());
+    assert!(Intent::from_bytes(&[0, 1, 0]).is_err());
+    assert!(Intent::from_bytes(&[0, 0, 1]).is_err());
+    assert!(Intent::from_bytes(&[0, 0]).is_err());
+    assert!(Intent::from_bytes(&[0, 0, 0, 0]).is_err());
+}
+
+#[test]
+fn test_transaction_is_signed_with_its_intent() {
+    let (sender, key) = get_key_pair();
+    let data = transfer_from(sender);
+    assert_eq!(
+        data.to_intent_bytes(),
+        Intent::new(IntentScope::TransactionData).message_bytes(&data.to_bytes())
+    );
+
+    let signature: Signature = key.sign(&data.to_intent_bytes());
+    assert!(signature.verify(&data, sender).is_ok());
+    assert_eq!(Signature::new(&data, &key), signature);
+
+    // Without the intent, or with the intent of another kind of message, the same bytes are
+    // not a signature of the transaction.
+    let signature: Signature = key.sign(&data.to_bytes());
+    assert!(signature.verify(&data, sender).is_err());
+    let message = Intent::new(IntentScope::PersonalMessage).message_bytes(&data.to_bytes());
+    let signature: Signature = key.sign(&message);
+    assert!(signature.verify(&data, sender).is_err());
+}
+
+#[test]
+fn test_intent_of_every_scheme_is_enforced() {
+    for scheme in [
+        SignatureScheme::ED25519,
+        SignatureScheme::Secp256k1,
+        SignatureScheme::Secp256r1,
+    ] {
+        let key = SuiKeyPair::generate(scheme).unwrap();
+        let data = transfer_from(key.address());
+        let signature: GenericSignature = key.sign(&data.to_intent_bytes()).unwrap();
+        assert!(signature.verify(&data, key.address()).is_ok());
+
+        let signature = key.sign(&data.to_bytes()).unwrap();
+        assert!(signature.verify(&data, key.address()).is_err());
+    }
+}
+
+#[test]
+fn test_authority_signature_enforces_intent() {
+    let (_, key) = get_key_pair();
+    let data = transfer_from(get_key_pair().0);
+    let protocol_key = key.protocol_public_key().to_public_key().unwrap();
+    let signature = AuthoritySignature::new(&data, &key);
+    assert!(signature.verify(&data, &protocol_key).is_ok());
+
+    let signature: AuthoritySignature = key.sign(&data.to_bytes());
+    assert!(signature.verify(&data, &protocol_key).is_err());
+}
+
+#[test]
+fn test_personal_message_signature() {
+    let key = SuiKeyPair::generate(SignatureScheme::ED25519).unwrap();
+    let (other, _) = get_key_pair();
+    let message = PersonalMessage::new(b"Sign in to example.com".to_vec());
+    let signature = key.sign(&message.to_intent_bytes()).unwrap();
+    assert!(message.verify(&signature, key.address()).is_ok());
+    assert!(message.verify(&signature, other).is_err());
+    assert!(PersonalMessage::new(b"Sign in to example.org".to_vec())
+        .verify(&signature, key.address())
+        .is_err());
+
+    // A personal message of the bytes of a transaction is not a signature of the transaction,
+    // nor the other way around.
+    let data = transfer_from(key.address());
+    let message = PersonalMessage::new(data.to_bytes());
+    let signature = key.sign(&message.to_intent_bytes()).unwrap();
+    assert!(signature.verify(&data, key.address()).is_err());
+    let signature = key.sign(&data.to_intent_bytes()).unwrap();
+    assert!(message.verify(&signature, key.address()).is_err());
+}
@@ -0,0 +1,153 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use super::*;
+
+// The seed of the first test vectors of BIP32 and SLIP-0010.
+const TEST_SEED: &str = "000102030405060708090a0b0c0d0e0f";
+
+fn path(s: &str) -> DerivationPath {
+    DerivationPath::from_str(s).unwrap()
+}
+
+#[test]
+fn test_derivation_path_parsing() {
+    for s in [
+        "m/44'/784'/0'/0'/0'",
+        "m/54'/784'/0'/0/0",
+        "m/0'/1/2'/2/1000000000",
+        "m",
+    ] {
+        assert_eq!(path(s).to_string(), s);
+    }
+    assert_eq!(
+        DerivationPath::default_for(SignatureScheme::ED25519).unwrap(),
+        path("m/44'/784'/0'/0'/0'")
+    );
+    assert_eq!(
+        DerivationPath::default_for(SignatureScheme::Secp256k1).unwrap(),
+        path("m/54'/784'/0'/0/0")
+    );
+    assert_eq!(
+        DerivationPath::default_for(SignatureScheme::Secp256r1).unwrap(),
+        path("m/74'/784'/0'/0/0")
+    );
+
+    assert!(DerivationPath::from_str("44'/784'").is_err());
+    assert!(DerivationPath::from_str("m/44'/x").is_err());
+    assert!(DerivationPath::from_str("m/2147483648").is_err());
+}
+
+#[test]
+fn test_derivation_path_validation() {
+    assert!(path("m/44'/784'/3'/0'/7'")
+        .validate(SignatureScheme::ED25519)
+        .is_ok());
+    assert!(path("m/54'/784'/3'/1/7")
+        .validate(SignatureScheme::Secp256k1)
+        .is_ok());
+    // The purpose of another scheme, or another coin.
+    assert!(path("m/54'/784'/0'/0/0")
+        .validate(SignatureScheme::Secp256r1)
+        .is_err());
+    assert!(path("m/44'/60'/0'/0'/0'")
+        .validate(SignatureScheme::ED25519)
+        .is_err());
+    // Ed25519 only has hardened children.
+    assert!(path("m/44'/784'/0'/0/0")
+        .validate(SignatureScheme::ED25519)
+        .is_err());
+    assert!(path("m/44'/784'/0'")
+        .validate(SignatureScheme::ED25519)
+        .is_err());
+    assert!(DerivationPath::default_for(SignatureScheme::MultiSig).is_err());
+}
+
+#[test]
+fn test_slip10_ed25519_vectors() {
+    let seed = hex::decode(TEST_SEED).unwrap();
+    assert_eq!(
+        hex::encode(derive_ed25519_secret(&seed, &path("m"))),
+        "2b4be7f19ee27bbf30c667b642d5f4aa69fd169872f8fc3059c08ebae2eb19e7"
+    );
+    assert_eq!(
+        hex::encode(derive_ed25519_secret(&seed, &path("m/0'"))),
+        "68e0fe46dfb67e368c75379acec591dad19df3cde26e63b93a8e704f1dade7a3"
+    );
+    assert_eq!(
+        hex::encode(derive_ed25519_secret(
+            &seed,
+            &path("m/0'/1'/2'/2'/1000000000'")
+        )),
+        "8f94d394a8e8fd6b1bc2f3f49f5c47e385281d5c17e65324b0f62483e37e8793"
+    );
+}
+
+#[test]
+fn test_bip32_vectors() {
+    let seed = hex::decode(TEST_SEED).unwrap();
+    let secret = |s: &str| {
+        let key: Secp256k1KeyPair = derive_bip32(&seed, &path(s)).unwrap();
+        hex::encode(key.to_bytes())
+    };
+    assert_eq!(
+        secret("m"),
+        "e8f32e723decf4051aefac8e2c93c9c5b214313817cdb01a1494b917c8436b35"
+    );
+    assert_eq!(
+        secret("m/0'"),
+        "edb2e14f9ee77d26dd93b4ecede8d16ed408ce149b6cd80b0715a2d911a0afea"
+    );
+    assert_eq!(
+        secret("m/0'/1/2'/2/1000000000"),
+        "471b76e389e528d6de6d816857e012c5455051cad6660850e58372a6c3e6e7c8"
+    );
+
+    let key: Secp256r1KeyPair = derive_bip32(&seed, &path("m")).unwrap();
+    assert_eq!(
+        hex::encode(key.to_bytes()),
+        "612091aaa12e22dd2abef664f8a01a82cae99ad7441b7ef8110424915c268bc2"
+    );
+}
+
+#[test]
+fn test_mnemonic_roundtrip() {
+    let mnemonic = generate_mnemonic();
+    let phrase = mnemonic.to_string();
+    assert_eq!(phrase.split(' ').count(), MNEMONIC_WORD_COUNT);
+
+    // Typed in again with other case and spacing, the phrase gives the same keys.
+    let typed = format!("  {}  ", phrase.to_uppercase().replace(' ', "\n "));
+    let restored = parse_mnemonic(&typed).unwrap();
+    for scheme in [
+        SignatureScheme::ED25519,
+        SignatureScheme::Secp256k1,
+        SignatureScheme::Secp256r1,
+    ] {
+        let key = derive_key_pair_from_mnemonic(&mnemonic, scheme, None).unwrap();
+        let again = derive_key_pair_from_mnemonic(&restored, scheme, None).unwrap();
+        assert_eq!(key.scheme(), scheme);
+        assert_eq!(key.address(), again.address());
+    }
+
+    // Another account of the same mnemonic has another key.
+    let first = derive_key_pair_from_mnemonic(&mnemonic, SignatureScheme::ED25519, None).unwrap();
+    let second = derive_key_pair_from_mnemonic(
+        &mnemonic,
+        SignatureScheme::ED25519,
+        Some(path("m/44'/784'/1'/0'/0'")),
+    )
+    .unwrap();
+    assert_ne!(first.address(), second.address());
+}
+
+#[test]
+fn test_invalid_mnemonic() {
+    // The 12 word phrase of the first BIP39 test vector.
+    let phrase = format!("{} about", vec!["abandon"; 11].join(" "));
+    assert!(parse_mnemonic(&phrase).is_ok());
+    // A wrong checksum, a missing word and words which are not in the list.
+    assert!(parse_mnemonic(&vec!["abandon"; 12].join(" ")).is_err());
+    assert!(parse_mnemonic(&vec!["abandon"; 11].join(" ")).is_err());
+    assert!(parse_mnemonic("not a mnemonic").is_err());
+}
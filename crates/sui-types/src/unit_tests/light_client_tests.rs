@@ -0,0 +1,367 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use super::*;
+use crate::base_types::{ObjectDigest, ObjectID, SequenceNumber, SuiAddress};
+use crate::committee::EpochId;
+use crate::crypto::{get_key_pair, KeyPair};
+use crate::gas::GasCostSummary;
+use crate::messages::ExecutionStatus;
+use crate::messages_checkpoint::{
+    CheckpointContents, EpochHandoff, SignedCheckpoint, SignedEpochHandoff,
+};
+use crate::object::Owner;
+use crate::utils::make_committee_of_epoch;
+
+fn random_contents(size: usize) -> CheckpointContents {
+    CheckpointContents::new((0..size).map(|_| ExecutionDigests::random()))
+}
+
+fn certify(
+    key_pairs: &[KeyPair],
+    committee: &Committee,
+    sequence_number: CheckpointSequenceNumber,
+    contents: &CheckpointContents,
+    previous_digest: Option<CheckpointDigest>,
+) -> CertifiedCheckpoint {
+    let signed = key_pairs
+        .iter()
+        .map(|key_pair| {
+            SignedCheckpoint::new(
+                sequence_number,
+                *key_pair.public_key_bytes(),
+                key_pair,
+                contents,
+                previous_digest,
+            )
+        })
+        .collect();
+    CertifiedCheckpoint::aggregate(signed, committee).unwrap()
+}
+
+/// A chain of `length` checkpoints certified by all of `key_pairs`, with their contents.
+fn make_chain(
+    key_pairs: &[KeyPair],
+    committee: &Committee,
+    length: usize,
+) -> Vec<(CertifiedCheckpoint, CheckpointContents)> {
+    let mut chain: Vec<(CertifiedCheckpoint, CheckpointContents)> = Vec::new();
+    for sequence_number in 0..length {
+        let contents = random_contents(sequence_number + 1);
+        let previous_digest = chain.last().map(|(last, _)| last.checkpoint.digest());
+        let certificate = certify(
+            key_pairs,
+            committee,
+            sequence_number as u64,
+            &contents,
+            previous_digest,
+        );
+        chain.push((certificate, contents));
+    }
+    chain
+}
+
+#[test]
+fn test_inclusion_proofs() {
+    for size in 1..12 {
+        let contents = random_contents(size);
+        let root = contents.merkle_root();
+        for digests in &contents.transactions {
+            let proof = contents.inclusion_proof(digests).unwrap();
+            assert!(proof.verify(digests, &root));
+            assert!(!proof.verify(&ExecutionDigests::random(), &root));
+
+            let mut moved = proof.clone();
+            moved.index = (moved.index + 1) % moved.leaf_count;
+            assert!(size == 1 || !moved.verify(digests, &root));
+
+            let mut truncated = proof.clone();
+            truncated.siblings.pop();
+            assert!(size == 1 || !truncated.verify(digests, &root));
+
+            let mut extended = proof;
+            extended.siblings.push(root);
+            assert!(!extended.verify(digests, &root));
+        }
+        assert!(contents
+            .inclusion_proof(&ExecutionDigests::random())
+            .is_none());
+    }
+    assert_ne!(
+        random_contents(0).merkle_root(),
+        random_contents(1).merkle_root()
+    );
+}
+
+#[test]
+fn test_verify_chain() {
+    let key_pairs: Vec<_> = (0..4).map(|_| get_key_pair().1).collect();
+    let committee = make_committee_of_epoch(0, &key_pairs);
+    let chain = make_chain(&key_pairs, &committee, 4);
+
+    let mut client = LightClient::new(committee.clone());
+    client
+        .verify_checkpoints(chain.iter().map(|(certificate, _)| certificate))
+        .unwrap();
+    assert_eq!(client.next_sequence_number(), 4);
+
+    let (last, contents) = &chain[3];
+    assert_eq!(client.latest_checkpoint(), Some(&last.checkpoint));
+    for digests in &contents.transactions {
+        let proof = contents.inclusion_proof(digests).unwrap();
+        client.verify_inclusion(digests, &proof).unwrap();
+    }
+    // The digests of an earlier checkpoint are not in the latest one.
+    let (earlier, earlier_contents) = &chain[2];
+    let digests = earlier_contents.transactions.iter().next().unwrap();
+    let proof = earlier_contents.inclusion_proof(digests).unwrap();
+    verify_inclusion(&earlier.checkpoint, digests, &proof).unwrap();
+    assert_eq!(
+        client.verify_inclusion(digests, &proof),
+        Err(SuiError::InvalidInclusionProof { sequence_number: 3 })
+    );
+
+    // A client trusting a checkpoint follows the chain from there.
+    let mut client = LightClient::from_checkpoint(committee, chain[1].0.checkpoint.clone());
+    client
+        .verify_checkpoints(chain[2..].iter().map(|(certificate, _)| certificate))
+        .unwrap();
+    assert_eq!(client.next_sequence_number(), 4);
+}
+
+#[test]
+fn test_reject_invalid_chain() {
+    let key_pairs: Vec<_> = (0..4).map(|_| get_key_pair().1).collect();
+    let committee = make_committee_of_epoch(0, &key_pairs);
+    let chain = make_chain(&key_pairs, &committee, 3);
+    let mut client = LightClient::new(committee.clone());
+
+    // A gap in the chain.
+    assert!(matches!(
+        client.verify_checkpoint(&chain[1].0),
+        Err(SuiError::InvalidCheckpointChain { .. })
+    ));
+    client.verify_checkpoint(&chain[0].0).unwrap();
+
+    // A checkpoint with the next sequence number but another previous checkpoint.
+    let fork = certify(&key_pairs, &committee, 1, &random_contents(1), None);
+    assert!(matches!(
+        client.verify_checkpoint(&fork),
+        Err(SuiError::InvalidCheckpointChain { .. })
+    ));
+
+    // A checkpoint certified by another committee.
+    let others: Vec<_> = (0..4).map(|_| get_key_pair().1).collect();
+    let other_committee = make_committee_of_epoch(0, &others);
+    let forged = certify(
+        &others,
+        &other_committee,
+        1,
+        &chain[1].1,
+        Some(chain[0].0.checkpoint.digest()),
+    );
+    assert!(client.verify_checkpoint(&forged).is_err());
+
+    // None of which moved the client on.
+    assert_eq!(client.next_sequence_number(), 1);
+    client.verify_checkpoint(&chain[1].0).unwrap();
+}
+
+fn make_effects() -> TransactionEffects {
+    let gas_object = (
+        (
+            ObjectID::random(),
+            SequenceNumber::new(),
+            ObjectDigest::new([0; 32]),
+        ),
+        Owner::AddressOwner(SuiAddress::random_for_testing_only()),
+    );
+    TransactionEffects {
+        status: ExecutionStatus::Success {
+            gas_cost: GasCostSummary {
+                computation_cost: 1,
+                storage_cost: 1,
+                storage_rebate: 0,
+            },
+        },
+        shared_objects: vec![],
+        transaction_digest: TransactionDigest::random(),
+        created: vec![],
+        mutated: vec![gas_object],
+        unwrapped: vec![],
+        deleted: vec![],
+        wrapped: vec![],
+        unwrapped_then_deleted: vec![],
+        owner_changes: vec![],
+        gas_object,
+        events: vec![],
+        dependencies: vec![],
+    }
+}
+
+#[test]
+fn test_verify_effects_inclusion() {
+    let effects = make_effects();
+    let transaction = effects.transaction_digest;
+    let digests = ExecutionDigests::new(transaction, effects.digest());
+    let contents = CheckpointContents::new(
+        [digests]
+            .into_iter()
+            .chain((0..4).map(|_| ExecutionDigests::random())),
+    );
+    let checkpoint = CheckpointSummary::new(0, &contents, None);
+    let proof = contents.inclusion_proof(&digests).unwrap();
+
+    verify_effects_inclusion(&checkpoint, &transaction, &effects, &proof).unwrap();
+    assert!(
+        verify_effects_inclusion(&checkpoint, &TransactionDigest::random(), &effects, &proof)
+            .is_err()
+    );
+    let mut other_effects = effects.clone();
+    other_effects.dependencies.push(TransactionDigest::random());
+    assert!(verify_effects_inclusion(&checkpoint, &transaction, &other_effects, &proof).is_err());
+}
+
+#[test]
+fn test_verify_finality() {
+    let key_pairs: Vec<_> = (0..4).map(|_| get_key_pair().1).collect();
+    let committee = make_committee_of_epoch(0, &key_pairs);
+    let effects = make_effects();
+    let transaction = effects.transaction_digest;
+    let digests = ExecutionDigests::new(transaction, effects.digest());
+    let contents = CheckpointContents::new(
+        [digests]
+            .into_iter()
+            .chain((0..4).map(|_| ExecutionDigests::random())),
+    );
+    let certificate = certify(&key_pairs, &committee, 0, &contents, None);
+    let proof = contents.inclusion_proof(&digests).unwrap();
+
+    verify_finality(&committee, &certificate, &transaction, &effects, &proof).unwrap();
+    // A checkpoint certified by another committee proves nothing.
+    let other_key_pairs: Vec<_> = (0..4).map(|_| get_key_pair().1).collect();
+    let other_committee = make_committee_of_epoch(0, &other_key_pairs);
+    assert!(verify_finality(
+        &other_committee,
+        &certificate,
+        &transaction,
+        &effects,
+        &proof
+    )
+    .is_err());
+}
+
+fn hand_off(
+    key_pairs: &[KeyPair],
+    committee: &Committee,
+    next_committee: &Committee,
+) -> CertifiedEpochHandoff {
+    let handoff = EpochHandoff::new(committee.epoch(), next_committee.clone(), [0; 32]);
+    let signed = key_pairs
+        .iter()
+        .map(|key_pair| {
+            SignedEpochHandoff::new(handoff.clone(), *key_pair.public_key_bytes(), key_pair)
+        })
+        .collect();
+    CertifiedEpochHandoff::aggregate(signed, committee).unwrap()
+}
+
+#[test]
+fn test_follow_committees_across_epochs() {
+    let key_pairs: Vec<Vec<_>> = (0..3)
+        .map(|_| (0..4).map(|_| get_key_pair().1).collect())
+        .collect();
+    let committees: Vec<_> = key_pairs
+        .iter()
+        .enumerate()
+        .map(|(epoch, key_pairs)| make_committee_of_epoch(epoch as EpochId, key_pairs))
+        .collect();
+    let handoffs: Vec<_> = (0..2)
+        .map(|epoch| {
+            hand_off(
+                &key_pairs[epoch],
+                &committees[epoch],
+                &committees[epoch + 1],
+            )
+        })
+        .collect();
+
+    assert_eq!(
+        verify_committee_chain(committees[0].clone(), &handoffs).unwrap(),
+        committees[2]
+    );
+    // The handoffs must be followed in order, from the committee which signed the first.
+    assert!(verify_committee_chain(committees[0].clone(), handoffs.iter().rev()).is_err());
+    assert!(verify_committee_chain(committees[1].clone(), &handoffs).is_err());
+
+    // The chain of checkpoints goes on under the next committee.
+    let first = certify(&key_pairs[0], &committees[0], 0, &random_contents(1), None);
+    let second = certify(
+        &key_pairs[1],
+        &committees[1],
+        1,
+        &random_contents(1),
+        Some(first.checkpoint.digest()),
+    );
+    let mut client = LightClient::new(committees[0].clone());
+    client.verify_checkpoint(&first).unwrap();
+    assert!(client.verify_checkpoint(&second).is_err());
+    client.verify_handoff(&handoffs[0]).unwrap();
+    assert_eq!(client.committee(), &committees[1]);
+    client.verify_checkpoint(&second).unwrap();
+    assert!(client.verify_handoff(&handoffs[0]).is_err());
+}
+
+#[test]
+fn test_reject_invalid_handoff() {
+    let key_pairs: Vec<_> = (0..4).map(|_| get_key_pair().1).collect();
+    let committee = make_committee_of_epoch(0, &key_pairs);
+    let next_key_pairs: Vec<_> = (0..4).map(|_| get_key_pair().1).collect();
+    let next_committee = make_committee_of_epoch(1, &next_key_pairs);
+
+    // Signed by less than a quorum.
+    let handoff = EpochHandoff::new(0, next_committee.clone(), [0; 32]);
+    let signed: Vec<_> = key_pairs[..2]
+        .iter()
+        .map(|key_pair| {
+            SignedEpochHandoff::new(handoff.clone(), *key_pair.public_key_bytes(), key_pair)
+        })
+        .collect();
+    assert!(CertifiedEpochHandoff::aggregate(signed, &committee).is_err());
+
+    // Signed by the next committee rather than the one which ends.
+    let next_handoff = EpochHandoff::new(0, next_committee.clone(), [0; 32]);
+    let signed = next_key_pairs
+        .iter()
+        .map(|key_pair| {
+            SignedEpochHandoff::new(next_handoff.clone(), *key_pair.public_key_bytes(), key_pair)
+        })
+        .collect();
+    assert!(CertifiedEpochHandoff::aggregate(signed, &committee).is_err());
+
+    // A handoff to a committee which skips an epoch.
+    let skipping = make_committee_of_epoch(2, &next_key_pairs);
+    let signed = key_pairs
+        .iter()
+        .map(|key_pair| {
+            SignedEpochHandoff::new(
+                EpochHandoff::new(0, skipping.clone(), [0; 32]),
+                *key_pair.public_key_bytes(),
+                key_pair,
+            )
+        })
+        .collect();
+    assert!(matches!(
+        CertifiedEpochHandoff::aggregate(signed, &committee),
+        Err(SuiError::InvalidEpochHandoff { epoch: 0, .. })
+    ));
+
+    // A next committee whose total stake is not the sum of its stakes.
+    let mut inconsistent = next_committee;
+    inconsistent.total_votes += 1;
+    let handoff = hand_off(&key_pairs, &committee, &inconsistent);
+    assert!(matches!(
+        verify_handoff(&committee, &handoff),
+        Err(SuiError::InvalidEpochHandoff { epoch: 0, .. })
+    ));
+}
@@ -0,0 +1,45 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use super::*;
+
+#[test]
+fn test_chunks_join_back_into_the_message() {
+    let message: Vec<Vec<u8>> = vec![vec![7; 2500], vec![], vec![1, 2, 3]];
+    let chunks = MessageChunk::split(&message, 1000).unwrap();
+    assert_eq!(chunks.len(), 3);
+    assert!(chunks[..2].iter().all(|chunk| chunk.bytes.len() == 1000));
+
+    let mut joiner = MessageChunkJoiner::new(10_000);
+    for chunk in chunks {
+        joiner.push(chunk).unwrap();
+    }
+    assert_eq!(joiner.finish::<Vec<Vec<u8>>>().unwrap(), message);
+}
+
+#[test]
+fn test_oversized_chunked_message_is_refused() {
+    let message = vec![0u8; 5000];
+    let mut joiner = MessageChunkJoiner::new(4096);
+    let result = MessageChunk::split(&message, 1024)
+        .unwrap()
+        .into_iter()
+        .try_for_each(|chunk| joiner.push(chunk));
+    assert!(matches!(
+        result,
+        Err(SuiError::OversizedChunkedMessage { max_size: 4096 })
+    ));
+}
+
+#[test]
+fn test_truncated_chunked_message_is_refused() {
+    let chunks = MessageChunk::split(&vec![0u8; 5000], 1024).unwrap();
+    let mut joiner = MessageChunkJoiner::new(10_000);
+    for chunk in chunks.into_iter().take(4) {
+        joiner.push(chunk).unwrap();
+    }
+    assert!(matches!(
+        joiner.finish::<Vec<u8>>(),
+        Err(SuiError::InvalidDecoding)
+    ));
+}
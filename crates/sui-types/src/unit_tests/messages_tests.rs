@@ -16,6 +16,23 @@ fn random_object_ref() -> ObjectRef {
     )
 }
 
+fn protocol_keys(key_pairs: &[&KeyPair]) -> BTreeMap<AuthorityName, ProtocolPublicKeyBytes> {
+    key_pairs
+        .iter()
+        .map(|key_pair| (*key_pair.public_key_bytes(), key_pair.protocol_public_key()))
+        .collect()
+}
+
+/// Remove `authority` from the signers of `certificate`, leaving the aggregate signature as is.
+fn clear_signer(
+    certificate: &mut CertifiedTransaction,
+    committee: &Committee,
+    authority: &AuthorityName,
+) {
+    let index = committee.authority_index(authority).unwrap();
+    certificate.auth_sign_info.signers_map[index / 8] &= !(1 << (index % 8));
+}
+
 #[test]
 fn test_signed_values() {
     let mut authorities = BTreeMap::new();
@@ -32,7 +49,7 @@ fn test_signed_values() {
         /* address */ *sec2.public_key_bytes(),
         /* voting right */ 0,
     );
-    let committee = Committee::new(0, authorities);
+    let committee = Committee::new(0, authorities, protocol_keys(&[&sec1, &sec2]));
 
     let transaction = Transaction::from_data(
         TransactionData::new_transfer(a2, random_object_ref(), a1, random_object_ref(), 10000),
@@ -91,7 +108,7 @@ fn test_certificates() {
         /* address */ *sec2.public_key_bytes(),
         /* voting right */ 1,
     );
-    let committee = Committee::new(0, authorities);
+    let committee = Committee::new(0, authorities, protocol_keys(&[&sec1, &sec2]));
 
     let transaction = Transaction::from_data(
         TransactionData::new_transfer(a2, random_object_ref(), a1, random_object_ref(), 10000),
@@ -131,7 +148,7 @@ fn test_certificates() {
         .unwrap()
         .unwrap();
     assert!(c.verify(&committee).is_ok());
-    c.auth_sign_info.signatures.pop();
+    clear_signer(&mut c, &committee, &v2.auth_sign_info.authority);
     assert!(c.verify(&committee).is_err());
 
     let mut builder = SignatureAggregator::try_new(transaction, &committee).unwrap();
@@ -145,3 +162,52 @@ fn test_certificates() {
 
     assert!(SignatureAggregator::try_new(bad_transaction, &committee).is_err());
 }
+
+#[test]
+fn test_certificates_are_weighted_by_stake() {
+    let (a1, sec1) = get_key_pair();
+    let (a2, sec2) = get_key_pair();
+
+    // The first authority holds a quorum of stake on its own.
+    let mut authorities = BTreeMap::new();
+    authorities.insert(*sec1.public_key_bytes(), 3);
+    authorities.insert(*sec2.public_key_bytes(), 1);
+    let committee = Committee::new(0, authorities, protocol_keys(&[&sec1, &sec2]));
+
+    let transaction = Transaction::from_data(
+        TransactionData::new_transfer(a2, random_object_ref(), a1, random_object_ref(), 10000),
+        &sec1,
+    );
+    let v1 = SignedTransaction::new(
+        committee.epoch(),
+        transaction.clone(),
+        *sec1.public_key_bytes(),
+        &sec1,
+    );
+    let v2 = SignedTransaction::new(
+        committee.epoch(),
+        transaction.clone(),
+        *sec2.public_key_bytes(),
+        &sec2,
+    );
+
+    let mut builder = SignatureAggregator::try_new(transaction.clone(), &committee).unwrap();
+    assert!(builder
+        .append(v2.auth_sign_info.authority, v2.auth_sign_info.signature)
+        .unwrap()
+        .is_none());
+
+    let mut builder = SignatureAggregator::try_new(transaction, &committee).unwrap();
+    let mut c = builder
+        .append(v1.auth_sign_info.authority, v1.auth_sign_info.signature)
+        .unwrap()
+        .unwrap();
+    assert!(c.verify(&committee).is_ok());
+
+    // The aggregate signature does not hold the signature of an authority claimed to sign.
+    let index = committee
+        .authority_index(&v2.auth_sign_info.authority)
+        .unwrap();
+    c.auth_sign_info.signers_map[index / 8] |= 1 << (index % 8);
+    assert!(c.verify(&committee).is_err());
+}
@@ -0,0 +1,183 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use rand::rngs::OsRng;
+
+use crate::crypto::{get_key_pair, KeyPair, Signature};
+use crate::ecdsa::{Secp256k1KeyPair, Secp256r1KeyPair};
+use crate::messages::{Transaction, TransactionData};
+use crate::utils::transfer_from;
+
+use super::*;
+
+fn keys(n: usize) -> Vec<KeyPair> {
+    (0..n).map(|_| get_key_pair().1).collect()
+}
+
+fn multisig_pk(
+    keys: &[KeyPair],
+    weights: Vec<WeightUnit>,
+    threshold: ThresholdUnit,
+) -> MultiSigPublicKey {
+    let pks = keys
+        .iter()
+        .map(|key| (*key.public_key_bytes()).into())
+        .collect();
+    MultiSigPublicKey::new(pks, weights, threshold).unwrap()
+}
+
+fn sign(data: &TransactionData, key: &KeyPair) -> GenericSignature {
+    Signature::new(data, key).into()
+}
+
+#[test]
+fn test_multisig_public_key_validation() {
+    let keys = keys(3);
+    let pks: Vec<MultiSigMemberKey> = keys
+        .iter()
+        .map(|key| (*key.public_key_bytes()).into())
+        .collect();
+
+    assert!(MultiSigPublicKey::new(pks.clone(), vec![1, 1, 1], 3).is_ok());
+    // Threshold above the total weight.
+    assert!(MultiSigPublicKey::new(pks.clone(), vec![1, 1, 1], 4).is_err());
+    // Missing weight.
+    assert!(MultiSigPublicKey::new(pks.clone(), vec![1, 1], 1).is_err());
+    // Zero weight or threshold.
+    assert!(MultiSigPublicKey::new(pks.clone(), vec![1, 0, 1], 1).is_err());
+    assert!(MultiSigPublicKey::new(pks.clone(), vec![1, 1, 1], 0).is_err());
+    // Duplicate key.
+    let duplicated = vec![pks[0], pks[0], pks[1]];
+    assert!(MultiSigPublicKey::new(duplicated, vec![1, 1, 1], 1).is_err());
+    // No key, or more than fit in the bitmap.
+    assert!(MultiSigPublicKey::new(vec![], vec![], 1).is_err());
+    let many: Vec<MultiSigMemberKey> = self::keys(MAX_SIGNER_IN_MULTISIG + 1)
+        .iter()
+        .map(|key| (*key.public_key_bytes()).into())
+        .collect();
+    let weights = vec![1; many.len()];
+    assert!(MultiSigPublicKey::new(many, weights, 1).is_err());
+}
+
+#[test]
+fn test_multisig_address() {
+    let keys = keys(2);
+    let address = multisig_pk(&keys, vec![1, 1], 2).address();
+
+    assert_eq!(address, multisig_pk(&keys, vec![1, 1], 2).address());
+    assert_ne!(address, multisig_pk(&keys, vec![1, 1], 1).address());
+    assert_ne!(address, multisig_pk(&keys, vec![1, 2], 2).address());
+    assert_ne!(address, SuiAddress::from(keys[0].public_key_bytes()));
+}
+
+#[test]
+fn test_multisig_verify() {
+    let keys = keys(3);
+    let pk = multisig_pk(&keys, vec![1, 1, 2], 2);
+    let data = transfer_from(pk.address());
+
+    // The third key alone reaches the threshold, and so do the first two together.
+    let multisig = MultiSig::combine(vec![sign(&data, &keys[2])], pk.clone()).unwrap();
+    assert!(multisig.verify(&data, pk.address()).is_ok());
+    let sigs = vec![sign(&data, &keys[1]), sign(&data, &keys[0])];
+    let multisig = MultiSig::combine(sigs, pk.clone()).unwrap();
+    assert!(multisig.verify(&data, pk.address()).is_ok());
+
+    // The first key alone does not.
+    let multisig = MultiSig::combine(vec![sign(&data, &keys[0])], pk.clone()).unwrap();
+    assert!(multisig.verify(&data, pk.address()).is_err());
+
+    // A valid multisig for someone else, or for other data, is rejected.
+    let multisig = MultiSig::combine(vec![sign(&data, &keys[2])], pk.clone()).unwrap();
+    let other = get_key_pair().0;
+    assert!(multisig.verify(&data, other).is_err());
+    assert!(multisig
+        .verify(&transfer_from(pk.address()), pk.address())
+        .is_err());
+}
+
+#[test]
+fn test_multisig_combine() {
+    let keys = keys(2);
+    let pk = multisig_pk(&keys, vec![1, 1], 2);
+    let data = transfer_from(pk.address());
+
+    // A key which is not part of the multisig.
+    let (_, outsider) = get_key_pair();
+    assert!(MultiSig::combine(vec![sign(&data, &outsider)], pk.clone()).is_err());
+
+    // The same key twice.
+    let sigs = vec![sign(&data, &keys[0]), sign(&data, &keys[0])];
+    assert!(MultiSig::combine(sigs, pk).is_err());
+}
+
+#[test]
+fn test_multisig_transaction() {
+    let keys = keys(2);
+    let pk = multisig_pk(&keys, vec![1, 1], 2);
+    let data = transfer_from(pk.address());
+    let sigs = keys.iter().map(|key| sign(&data, key)).collect();
+    let multisig = MultiSig::combine(sigs, pk).unwrap();
+
+    let transaction = Transaction::new(data, multisig);
+    assert!(transaction.verify_signature().is_ok());
+
+    let bytes = bcs::to_bytes(&transaction.tx_signature).unwrap();
+    let signature: GenericSignature = bcs::from_bytes(&bytes).unwrap();
+    assert_eq!(signature, transaction.tx_signature);
+}
+
+#[test]
+fn test_multisig_mixed_schemes() {
+    let ed25519 = get_key_pair().1;
+    let k1 = Secp256k1KeyPair::generate(&mut OsRng);
+    let r1 = Secp256r1KeyPair::generate(&mut OsRng);
+    let member_key = |scheme: SignatureScheme, key: &[u8]| {
+        MultiSigMemberKey::from_bytes(&[&[scheme.flag()][..], key].concat())
+    };
+    let pks = vec![
+        (*ed25519.public_key_bytes()).into(),
+        member_key(SignatureScheme::Secp256k1, &k1.public_key_bytes()).unwrap(),
+        member_key(SignatureScheme::Secp256r1, &r1.public_key_bytes()).unwrap(),
+    ];
+    let pk = MultiSigPublicKey::new(pks, vec![1, 1, 1], 2).unwrap();
+    let data = transfer_from(pk.address());
+
+    let k1_sig: GenericSignature = Secp256k1Signature::new(&data, &k1).into();
+    let r1_sig: GenericSignature = Secp256r1Signature::new(&data, &r1).into();
+    for sigs in [
+        vec![sign(&data, &ed25519), k1_sig.clone()],
+        vec![r1_sig.clone(), k1_sig.clone()],
+    ] {
+        let multisig = MultiSig::combine(sigs, pk.clone()).unwrap();
+        assert!(multisig.verify(&data, pk.address()).is_ok());
+        let transaction = Transaction::new(data.clone(), multisig);
+        assert!(transaction.verify_signature().is_ok());
+    }
+
+    // An ECDSA signature over other data is rejected, like an ed25519 one.
+    let other = transfer_from(pk.address());
+    let sigs = vec![
+        sign(&data, &ed25519),
+        Secp256k1Signature::new(&other, &k1).into(),
+    ];
+    let multisig = MultiSig::combine(sigs, pk.clone()).unwrap();
+    assert!(multisig.verify(&data, pk.address()).is_err());
+
+    // A signature in the scheme of another member's key is rejected.
+    let sigs = vec![sign(&data, &ed25519), k1_sig];
+    let mut multisig = MultiSig::combine(sigs, pk.clone()).unwrap();
+    multisig.sigs.swap(0, 1);
+    assert!(multisig.verify(&data, pk.address()).is_err());
+
+    // Only the signatures of single keys can be combined.
+    let nested = MultiSig::combine(vec![r1_sig], pk.clone()).unwrap();
+    assert!(MultiSig::combine(vec![nested.into()], pk).is_err());
+
+    // Keys which are neither ed25519 keys nor ECDSA keys on their curve.
+    assert!(MultiSigMemberKey::from_bytes(&[0; 16]).is_err());
+    assert!(member_key(SignatureScheme::MultiSig, &k1.public_key_bytes()).is_err());
+    let mut off_curve = [0xff; ECDSA_PUBLIC_KEY_LENGTH];
+    off_curve[0] = 0x02;
+    assert!(member_key(SignatureScheme::Secp256r1, &off_curve).is_err());
+}
@@ -0,0 +1,68 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use super::*;
+use crate::base_types::SequenceNumber;
+
+fn record(name: &str, target: SuiAddress, expiration_epoch: EpochId) -> NameRecord {
+    NameRecord {
+        name: name.as_bytes().to_vec(),
+        owner: target,
+        target,
+        expiration_epoch,
+    }
+}
+
+#[test]
+fn test_resolve_names() {
+    let alice = SuiAddress::random_for_testing_only();
+    let bob = SuiAddress::random_for_testing_only();
+    let registry = NameServiceRegistry {
+        id: VersionedID::new(ObjectID::random(), SequenceNumber::new()),
+        records: vec![
+            record("alice", alice, 10),
+            record("bob", bob, 10),
+            record("ally", alice, 10),
+            record("old-alice", alice, 5),
+        ],
+        reverse_records: vec![ReverseRecord {
+            addr: alice,
+            name: b"ally".to_vec(),
+        }],
+    };
+
+    assert_eq!(registry.resolve("alice", 0), Some(alice));
+    assert_eq!(registry.resolve("alice.sui", 0), Some(alice));
+    assert_eq!(registry.resolve("alice", 10), None);
+    assert_eq!(registry.resolve("carol", 0), None);
+
+    // The reverse record comes first, and the expired names are left out.
+    assert_eq!(registry.names_of(&alice, 5), vec!["ally.sui", "alice.sui"]);
+    assert_eq!(registry.names_of(&bob, 0), vec!["bob.sui"]);
+    assert!(registry.names_of(&bob, 10).is_empty());
+}
+
+#[test]
+fn test_registry_created_event() {
+    let registry_id = ObjectID::random();
+    let mut type_ = StructTag {
+        address: SUI_FRAMEWORK_ADDRESS,
+        module: NAME_SERVICE_MODULE_NAME.to_owned(),
+        name: REGISTRY_CREATED_STRUCT_NAME.to_owned(),
+        type_params: vec![],
+    };
+    let contents = bcs::to_bytes(&RegistryCreated { registry_id }).unwrap();
+    let event = Event::MoveEvent {
+        type_: type_.clone(),
+        contents: contents.clone(),
+    };
+    assert_eq!(
+        NameServiceRegistry::created_by_event(&event),
+        Some(registry_id)
+    );
+
+    // Only the framework emits the event.
+    type_.address = ObjectID::random().into();
+    let event = Event::MoveEvent { type_, contents };
+    assert_eq!(NameServiceRegistry::created_by_event(&event), None);
+}
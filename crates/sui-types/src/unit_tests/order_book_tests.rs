@@ -0,0 +1,75 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use super::*;
+use move_core_types::language_storage::StructTag;
+
+fn move_event(name: &IdentStr, contents: Vec<u8>) -> Event {
+    Event::MoveEvent {
+        type_: StructTag {
+            address: SUI_FRAMEWORK_ADDRESS,
+            module: ORDER_BOOK_MODULE_NAME.to_owned(),
+            name: name.to_owned(),
+            type_params: vec![],
+        },
+        contents,
+    }
+}
+
+#[test]
+fn test_order_book_events_from_move_events() {
+    let pool_id = ObjectID::random();
+    let filled = OrderFilled {
+        pool_id,
+        maker_order_id: 0,
+        taker_order_id: 1,
+        maker: SuiAddress::random_for_testing_only(),
+        taker: SuiAddress::random_for_testing_only(),
+        taker_is_bid: true,
+        price: 2_000_000_000,
+        quantity: 100,
+        taker_fee: 1,
+        maker_rebate: 0,
+    };
+    let event = move_event(ORDER_FILLED_STRUCT_NAME, bcs::to_bytes(&filled).unwrap());
+    let parsed = OrderBookEvent::from_event(&event).unwrap();
+    assert_eq!(parsed, OrderBookEvent::OrderFilled(filled));
+    assert_eq!(parsed.pool_id(), pool_id);
+
+    let placed = OrderPlaced {
+        pool_id,
+        order_id: 2,
+        owner: SuiAddress::random_for_testing_only(),
+        is_bid: false,
+        price: 1_000_000_000,
+        quantity: 100,
+    };
+    let event = move_event(ORDER_PLACED_STRUCT_NAME, bcs::to_bytes(&placed).unwrap());
+    assert_eq!(
+        OrderBookEvent::from_event(&event),
+        Some(OrderBookEvent::OrderPlaced(placed.clone()))
+    );
+
+    // Events of other modules, other events of the module and malformed events are not order
+    // book events.
+    let event = move_event(ident_str!("Pool"), bcs::to_bytes(&placed).unwrap());
+    assert_eq!(OrderBookEvent::from_event(&event), None);
+    let event = move_event(ORDER_FILLED_STRUCT_NAME, bcs::to_bytes(&placed).unwrap());
+    assert_eq!(OrderBookEvent::from_event(&event), None);
+    let event = Event::MoveEvent {
+        type_: StructTag {
+            address: SUI_FRAMEWORK_ADDRESS,
+            module: ident_str!("Kiosk").to_owned(),
+            name: ORDER_PLACED_STRUCT_NAME.to_owned(),
+            type_params: vec![],
+        },
+        contents: bcs::to_bytes(&placed).unwrap(),
+    };
+    assert_eq!(OrderBookEvent::from_event(&event), None);
+    assert_eq!(
+        OrderBookEvent::from_event(&Event::Publish {
+            package_id: pool_id
+        }),
+        None
+    );
+}
@@ -0,0 +1,48 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use super::*;
+
+#[test]
+fn test_every_supported_version_has_a_config() {
+    for version in ProtocolVersion::MIN.as_u64()..=ProtocolVersion::MAX.as_u64() {
+        let config = ProtocolConfig::get_for_version(ProtocolVersion::new(version)).unwrap();
+        assert_eq!(config.version, ProtocolVersion::new(version));
+    }
+    assert_eq!(
+        ProtocolConfig::get_for_max_version().version,
+        ProtocolVersion::MAX
+    );
+}
+
+#[test]
+fn test_unsupported_versions_are_refused() {
+    for version in [0, ProtocolVersion::MAX.as_u64() + 1] {
+        assert!(matches!(
+            ProtocolConfig::get_for_version(ProtocolVersion::new(version)),
+            Err(SuiError::UnsupportedProtocolVersion { version: v, .. }) if v == version
+        ));
+    }
+}
+
+#[test]
+fn test_native_costs_grow_with_units() {
+    let cost = ProtocolConfig::get_for_max_version().native_cost("Crypto::keccak256");
+    assert_eq!(cost.of(0), cost.base);
+    assert_eq!(cost.of(10), cost.base + 10 * cost.per_unit);
+    assert_eq!(NativeCost::new(1, u64::MAX).of(2), u64::MAX);
+}
+
+#[test]
+fn test_large_packages_are_sent_in_chunks_from_version_2() {
+    let v1 = ProtocolConfig::get_for_version(ProtocolVersion::new(1)).unwrap();
+    let v2 = ProtocolConfig::get_for_version(ProtocolVersion::new(2)).unwrap();
+    assert!(!v1.chunked_messages);
+    assert!(v2.chunked_messages);
+    assert!(v2.max_package_size > v1.max_package_size);
+    assert!(v2.max_chunked_message_size > v2.max_package_size);
+    assert!(v2.max_written_bytes >= v2.max_package_size);
+    // Everything else carries over from the version before.
+    assert_eq!(v1.min_transaction_cost, v2.min_transaction_cost);
+    assert_eq!(v1.native_costs, v2.native_costs);
+}
@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::crypto::BcsSignable;
+use crate::intent::{IntentScope, IntentScoped};
 use crate::signature_seed::SignatureSeed;
 use serde::{Deserialize, Serialize};
 
@@ -16,6 +17,10 @@ struct Foo(String);
 
 impl BcsSignable for Foo {}
 
+impl IntentScoped for Foo {
+    const INTENT_SCOPE: IntentScope = IntentScope::PersonalMessage;
+}
+
 #[test]
 fn test_deterministic_addresses_by_id() {
     let seed = SignatureSeed::default();
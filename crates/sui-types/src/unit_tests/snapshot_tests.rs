@@ -0,0 +1,113 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use super::*;
+use crate::base_types::{ObjectID, SuiAddress};
+use crate::crypto::{get_key_pair, KeyPair};
+use crate::messages_checkpoint::{EpochHandoff, SignedEpochHandoff};
+use std::collections::BTreeMap;
+
+fn committee_of_epoch(epoch: EpochId, key_pairs: &[KeyPair]) -> Committee {
+    Committee::new(
+        epoch,
+        key_pairs
+            .iter()
+            .map(|key_pair| (*key_pair.public_key_bytes(), 1))
+            .collect(),
+        key_pairs
+            .iter()
+            .map(|key_pair| (*key_pair.public_key_bytes(), key_pair.protocol_public_key()))
+            .collect::<BTreeMap<_, _>>(),
+    )
+}
+
+fn hand_off(
+    key_pairs: &[KeyPair],
+    committee: &Committee,
+    next_committee: &Committee,
+    live_objects_digest: LiveObjectsDigest,
+) -> CertifiedEpochHandoff {
+    let handoff = EpochHandoff::new(
+        committee.epoch(),
+        next_committee.clone(),
+        live_objects_digest,
+    );
+    let signed = key_pairs
+        .iter()
+        .map(|key_pair| {
+            SignedEpochHandoff::new(handoff.clone(), *key_pair.public_key_bytes(), key_pair)
+        })
+        .collect();
+    CertifiedEpochHandoff::aggregate(signed, committee).unwrap()
+}
+
+fn live_objects() -> Vec<Object> {
+    let owner = SuiAddress::random_for_testing_only();
+    let mut objects: Vec<_> = (0..3)
+        .map(|_| Object::with_id_owner_for_testing(ObjectID::random(), owner))
+        .collect();
+    objects.sort_by_key(|object| object.id());
+    objects
+}
+
+fn digest_of(objects: &[Object]) -> LiveObjectsDigest {
+    let object_refs: Vec<_> = objects
+        .iter()
+        .map(|object| object.compute_object_reference())
+        .collect();
+    live_objects_digest(&object_refs)
+}
+
+#[test]
+fn test_verify_snapshot() {
+    let key_pairs: Vec<Vec<_>> = (0..3)
+        .map(|_| (0..4).map(|_| get_key_pair().1).collect())
+        .collect();
+    let committees: Vec<_> = key_pairs
+        .iter()
+        .enumerate()
+        .map(|(epoch, key_pairs)| committee_of_epoch(epoch as EpochId, key_pairs))
+        .collect();
+    let objects = live_objects();
+
+    // A snapshot of the end of epoch 1, after a handoff of epoch 0 to another set of objects.
+    let snapshot = ObjectSnapshot {
+        handoffs: vec![
+            hand_off(&key_pairs[0], &committees[0], &committees[1], [0; 32]),
+            hand_off(
+                &key_pairs[1],
+                &committees[1],
+                &committees[2],
+                digest_of(&objects),
+            ),
+        ],
+        objects: objects.clone(),
+    };
+    assert_eq!(snapshot.epoch(), Some(1));
+    assert_eq!(
+        snapshot.verify(committees[0].clone()).unwrap(),
+        committees[2]
+    );
+
+    // The snapshot is verified from the genesis committee only.
+    assert!(snapshot.verify(committees[1].clone()).is_err());
+
+    // Objects missing or out of order are rejected.
+    let mut missing = snapshot.clone();
+    missing.objects.pop();
+    assert!(matches!(
+        missing.verify(committees[0].clone()),
+        Err(SuiError::InvalidSnapshot { .. })
+    ));
+    let mut reversed = snapshot.clone();
+    reversed.objects.reverse();
+    assert!(matches!(
+        reversed.verify(committees[0].clone()),
+        Err(SuiError::InvalidSnapshot { .. })
+    ));
+
+    // A snapshot of the end of epoch 0 has other objects.
+    let mut earlier = snapshot;
+    earlier.handoffs.pop();
+    assert!(earlier.verify(committees[0].clone()).is_err());
+}
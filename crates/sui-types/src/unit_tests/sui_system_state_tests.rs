@@ -0,0 +1,50 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use super::*;
+
+fn new_pool(sui_balance: u64, pool_token_balance: u64, rates: &[(u64, u64, u64)]) -> StakingPool {
+    StakingPool {
+        validator_address: AccountAddress::random(),
+        sui_balance,
+        pool_token_balance,
+        rewards_pool: Balance::new(sui_balance - pool_token_balance),
+        pending_delegation: 0,
+        exchange_rates: rates
+            .iter()
+            .map(
+                |(epoch, sui_amount, pool_token_amount)| PoolTokenExchangeRate {
+                    epoch: *epoch,
+                    sui_amount: *sui_amount,
+                    pool_token_amount: *pool_token_amount,
+                },
+            )
+            .collect(),
+    }
+}
+
+#[test]
+fn test_apy_of_a_year_of_rewards() {
+    let pool = new_pool(1100, 1000, &[(0, 1000, 1000), (100, 1050, 1000)]);
+    let apy = pool.apy(EPOCHS_PER_YEAR);
+    assert!((apy - 0.1).abs() < 1e-9, "{apy}");
+    // The rates of more than a year ago do not count.
+    let pool = new_pool(1210, 1000, &[(0, 1000, 1000), (1, 1100, 1000)]);
+    let apy = pool.apy(EPOCHS_PER_YEAR + 1);
+    assert!((apy - 0.1).abs() < 1e-9, "{apy}");
+}
+
+#[test]
+fn test_apy_compounds_a_shorter_history() {
+    // 1% over the 73 epochs since the first delegations, five times a year.
+    let pool = new_pool(1010, 1000, &[(10, 1000, 1000), (20, 1010, 1000)]);
+    let apy = pool.apy(83);
+    assert!((apy - (1.01f64.powi(5) - 1.0)).abs() < 1e-9, "{apy}");
+}
+
+#[test]
+fn test_apy_without_history() {
+    assert_eq!(new_pool(0, 0, &[]).apy(10), 0.0);
+    // Delegations activating in the current epoch earned nothing yet.
+    assert_eq!(new_pool(1000, 1000, &[(10, 1000, 1000)]).apy(10), 0.0);
+}
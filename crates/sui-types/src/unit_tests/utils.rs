@@ -2,11 +2,23 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    committee::Committee,
+    base_types::{ObjectDigest, ObjectID, SequenceNumber, SuiAddress},
+    committee::{Committee, EpochId},
     crypto::{get_key_pair_from_rng, KeyPair},
+    messages::TransactionData,
 };
 use std::collections::BTreeMap;
 
+/// A transfer of a made-up object from `sender` to itself, paying for gas with the same object.
+pub fn transfer_from(sender: SuiAddress) -> TransactionData {
+    let object_ref = (
+        ObjectID::random(),
+        SequenceNumber::new(),
+        ObjectDigest::new([0; 32]),
+    );
+    TransactionData::new_transfer(sender, object_ref, sender, object_ref, 10000)
+}
+
 pub fn make_committee_key<R>(rand: &mut R) -> (Vec<KeyPair>, Committee)
 where
     R: rand::CryptoRng + rand::RngCore,
@@ -18,18 +30,21 @@ pub fn make_committee_key_num<R>(num: usize, rand: &mut R) -> (Vec<KeyPair>, Com
 where
     R: rand::CryptoRng + rand::RngCore,
 {
-    let mut authorities = BTreeMap::new();
-    let mut keys = Vec::new();
+    let keys: Vec<KeyPair> = (0..num).map(|_| get_key_pair_from_rng(rand).1).collect();
+    let committee = make_committee_of_epoch(0, &keys);
+    (keys, committee)
+}
 
-    for _ in 0..num {
-        let (_, inner_authority_key) = get_key_pair_from_rng(rand);
+/// The committee of `epoch` in which each of `keys` has one vote, and signs with its protocol key.
+pub fn make_committee_of_epoch(epoch: EpochId, keys: &[KeyPair]) -> Committee {
+    let mut authorities = BTreeMap::new();
+    let mut protocol_keys = BTreeMap::new();
+    for key in keys {
         authorities.insert(
-            /* address */ *inner_authority_key.public_key_bytes(),
+            /* address */ *key.public_key_bytes(),
             /* voting right */ 1,
         );
-        keys.push(inner_authority_key);
+        protocol_keys.insert(*key.public_key_bytes(), key.protocol_public_key());
     }
-
-    let committee = Committee::new(0, authorities);
-    (keys, committee)
+    Committee::new(epoch, authorities, protocol_keys)
 }
@@ -0,0 +1,187 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use ark_groth16::{create_random_proof, generate_random_parameters, ProvingKey};
+use ark_relations::lc;
+use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystemRef, SynthesisError, Variable};
+use ark_serialize::CanonicalSerialize;
+use ark_std::rand::rngs::StdRng;
+use ark_std::rand::SeedableRng;
+
+use crate::crypto::{get_key_pair, GenericSignature, KeyPair};
+use crate::messages::TransactionData;
+use crate::utils::transfer_from;
+
+use super::*;
+
+const ISS: &str = "https://accounts.google.com";
+const KID: &str = "key-1";
+const MODULUS: &[u8] = &[0xc0, 0xff, 0xee];
+
+/// A stand-in for the zkLogin circuit, whose proofs only show knowledge of the public input.
+/// It is enough to check that the proof is bound to everything the public input commits to.
+struct TestCircuit {
+    public_inputs_hash: Option<Fr>,
+}
+
+impl ConstraintSynthesizer<Fr> for TestCircuit {
+    fn generate_constraints(self, cs: ConstraintSystemRef<Fr>) -> Result<(), SynthesisError> {
+        let value = || {
+            self.public_inputs_hash
+                .ok_or(SynthesisError::AssignmentMissing)
+        };
+        let input = cs.new_input_variable(value)?;
+        let witness = cs.new_witness_variable(value)?;
+        cs.enforce_constraint(lc!() + witness, lc!() + Variable::One, lc!() + input)
+    }
+}
+
+/// The parameters of the test circuit, of a setup ceremony whose randomness is `seed`.
+fn setup(seed: u64) -> ProvingKey<Bn254> {
+    let circuit = TestCircuit {
+        public_inputs_hash: None,
+    };
+    let mut rng = StdRng::seed_from_u64(seed);
+    generate_random_parameters::<Bn254, _, _>(circuit, &mut rng).unwrap()
+}
+
+fn env(proving_key: &ProvingKey<Bn254>, epoch: EpochId, modulus: &[u8]) -> ZkLoginEnv {
+    let mut verifying_key = Vec::new();
+    proving_key.vk.serialize(&mut verifying_key).unwrap();
+    let jwk = ZkLoginJwk {
+        iss: ISS.as_bytes().to_vec(),
+        kid: KID.as_bytes().to_vec(),
+        modulus: modulus.to_vec(),
+    };
+    ZkLoginEnv::new(epoch, &verifying_key, &[jwk]).unwrap()
+}
+
+/// The address of the account of the user, at which `data` is sent from.
+fn zk_login_sender() -> SuiAddress {
+    zk_login_address(ISS, &[7; ADDRESS_SEED_LENGTH])
+}
+
+/// Sign `data` with `ephemeral_key`, and prove that the JWT of the user authorizes the key
+/// until `max_epoch`.
+fn authenticate(
+    proving_key: &ProvingKey<Bn254>,
+    data: &TransactionData,
+    ephemeral_key: &KeyPair,
+    max_epoch: EpochId,
+) -> ZkLoginAuthenticator {
+    let mut authenticator = ZkLoginAuthenticator::new(
+        Vec::new(),
+        ISS.to_string(),
+        KID.to_string(),
+        vec![7; ADDRESS_SEED_LENGTH],
+        max_epoch,
+        Signature::new(data, ephemeral_key),
+    );
+    let circuit = TestCircuit {
+        public_inputs_hash: Some(authenticator.public_inputs_hash(MODULUS)),
+    };
+    let proof = create_random_proof(circuit, proving_key, &mut ark_std::test_rng()).unwrap();
+    proof.serialize(&mut authenticator.proof).unwrap();
+    authenticator
+}
+
+#[test]
+fn test_zk_login_signature() {
+    let proving_key = setup(0);
+    let (_, ephemeral_key) = get_key_pair();
+    let data = transfer_from(zk_login_sender());
+    let authenticator = authenticate(&proving_key, &data, &ephemeral_key, 10);
+    assert_eq!(authenticator.address(), zk_login_sender());
+    assert!(authenticator
+        .verify_proof(&env(&proving_key, 10, MODULUS))
+        .is_ok());
+
+    let signature = GenericSignature::from(authenticator);
+    assert_eq!(signature.scheme(), SignatureScheme::ZkLogin);
+    assert!(signature.verify(&data, zk_login_sender()).is_ok());
+    let decoded = GenericSignature::from_bytes(&signature.to_bytes()).unwrap();
+    assert_eq!(decoded, signature);
+
+    // The ephemeral key signs for the zkLogin address only, and for this transaction only.
+    let (other, _) = get_key_pair();
+    assert!(signature.verify(&data, other).is_err());
+    assert!(signature
+        .verify(&transfer_from(zk_login_sender()), zk_login_sender())
+        .is_err());
+}
+
+#[test]
+fn test_zk_login_address() {
+    let address = zk_login_sender();
+    assert_ne!(zk_login_address(ISS, &[8; ADDRESS_SEED_LENGTH]), address);
+    assert_ne!(
+        zk_login_address("https://www.facebook.com", &[7; ADDRESS_SEED_LENGTH]),
+        address
+    );
+
+    // A provider longer than 255 bytes is not confused with a prefix of the seed.
+    let long_iss = "a".repeat(256);
+    let seed = [7; ADDRESS_SEED_LENGTH];
+    assert_ne!(
+        zk_login_address(&long_iss, &seed),
+        zk_login_address("", &[long_iss.as_bytes(), &seed].concat())
+    );
+}
+
+#[test]
+fn test_zk_login_address_seed_length() {
+    let proving_key = setup(0);
+    let (_, ephemeral_key) = get_key_pair();
+    let data = transfer_from(zk_login_sender());
+    let mut authenticator = authenticate(&proving_key, &data, &ephemeral_key, 10);
+    authenticator.address_seed = vec![7; ADDRESS_SEED_LENGTH + 1];
+    let sender = authenticator.address();
+
+    let data = transfer_from(sender);
+    authenticator.ephemeral_signature = Signature::new(&data, &ephemeral_key);
+    assert!(authenticator
+        .verify_ephemeral_signature(&data, sender)
+        .is_err());
+    assert!(authenticator
+        .verify_proof(&env(&proving_key, 10, MODULUS))
+        .is_err());
+}
+
+#[test]
+fn test_zk_login_proof_is_checked_against_the_epoch() {
+    let proving_key = setup(0);
+    let (_, ephemeral_key) = get_key_pair();
+    let data = transfer_from(zk_login_sender());
+    let authenticator = authenticate(&proving_key, &data, &ephemeral_key, 10);
+
+    // The ephemeral key expired.
+    assert!(authenticator
+        .verify_proof(&env(&proving_key, 11, MODULUS))
+        .is_err());
+    // The provider rotated its key.
+    assert!(authenticator
+        .verify_proof(&env(&proving_key, 10, &[0xde, 0xad]))
+        .is_err());
+    // The proof is for the parameters of another setup.
+    assert!(authenticator
+        .verify_proof(&env(&setup(1), 10, MODULUS))
+        .is_err());
+
+    // Extending the expiry or swapping the ephemeral key invalidates the proof.
+    let mut extended = authenticator.clone();
+    extended.max_epoch = 20;
+    assert!(extended
+        .verify_proof(&env(&proving_key, 10, MODULUS))
+        .is_err());
+    let mut swapped = authenticator;
+    swapped.ephemeral_signature = Signature::new(&data, &get_key_pair().1);
+    assert!(swapped
+        .verify_proof(&env(&proving_key, 10, MODULUS))
+        .is_err());
+}
+
+#[test]
+fn test_zk_login_is_disabled_without_a_verifying_key() {
+    assert!(ZkLoginEnv::new(0, &[], &[]).is_err());
+    assert!(ZkLoginEnv::new(0, &[1, 2, 3], &[]).is_err());
+}
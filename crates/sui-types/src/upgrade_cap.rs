@@ -0,0 +1,74 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use move_core_types::{ident_str, identifier::IdentStr, language_storage::StructTag};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    base_types::ObjectID,
+    error::{SuiError, SuiResult},
+    id::VersionedID,
+    object::{Data, Object},
+    SUI_FRAMEWORK_ADDRESS,
+};
+
+pub const PACKAGE_MODULE_NAME: &IdentStr = ident_str!("Package");
+pub const UPGRADE_CAP_STRUCT_NAME: &IdentStr = ident_str!("UpgradeCap");
+
+/// Rust version of the Move Sui::Package::UpgradeCap type
+#[derive(Debug, Serialize, Deserialize, Clone, Eq, PartialEq)]
+pub struct UpgradeCap {
+    pub id: VersionedID,
+    /// The latest version of the package, which the next upgrade replaces.
+    pub package: ObjectID,
+    /// The number of versions of the package.
+    pub version: u64,
+}
+
+impl UpgradeCap {
+    pub fn type_() -> StructTag {
+        StructTag {
+            address: SUI_FRAMEWORK_ADDRESS,
+            name: UPGRADE_CAP_STRUCT_NAME.to_owned(),
+            module: PACKAGE_MODULE_NAME.to_owned(),
+            type_params: Vec::new(),
+        }
+    }
+
+    pub fn id(&self) -> &ObjectID {
+        self.id.object_id()
+    }
+
+    /// The capability after upgrading its package to `package`.
+    pub fn upgraded(&self, package: ObjectID) -> Self {
+        Self {
+            id: self.id.clone(),
+            package,
+            version: self.version + 1,
+        }
+    }
+
+    pub fn to_bcs_bytes(&self) -> Vec<u8> {
+        bcs::to_bytes(&self).unwrap()
+    }
+}
+
+impl TryFrom<&Object> for UpgradeCap {
+    type Error = SuiError;
+
+    fn try_from(object: &Object) -> SuiResult<Self> {
+        match &object.data {
+            Data::Move(o) if o.type_ == Self::type_() => {
+                bcs::from_bytes(o.contents()).map_err(|err| SuiError::TypeError {
+                    error: format!(
+                        "Unable to deserialize the upgrade cap {}: {err}",
+                        object.id()
+                    ),
+                })
+            }
+            _ => Err(SuiError::TypeError {
+                error: format!("Object {} is not an upgrade cap", object.id()),
+            }),
+        }
+    }
+}
@@ -10,7 +10,6 @@ use std::collections::{BTreeMap, BTreeSet};
 
 use curve25519_dalek::ristretto::RistrettoPoint;
 
-use crate::committee::StakeUnit;
 use crate::{base_types::AuthorityName, committee::Committee};
 
 #[cfg(test)]
@@ -261,12 +260,7 @@ where
     /// In case keys are authority names we can check if the set of
     /// authorities represented in this checkpoint represent a quorum
     pub fn has_quorum(&self, committee: &Committee) -> bool {
-        let authority_weights: StakeUnit = self
-            .authority_waypoints
-            .keys()
-            .map(|name| committee.weight(name))
-            .sum();
-        authority_weights >= committee.quorum_threshold()
+        committee.weight_of(self.authority_waypoints.keys()) >= committee.quorum_threshold()
     }
 }
 
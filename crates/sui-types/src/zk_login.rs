@@ -0,0 +1,241 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! zkLogin authenticators, which let the holder of a JWT of an OpenID provider sign for an
+//! address without a wallet. The user signs the transaction with an ephemeral ed25519 key,
+//! and a Groth16 proof shows that a JWT signed by the provider commits to that key until
+//! `max_epoch`, and that the subject of the JWT hashes to `address_seed`. The address is
+//! derived from the provider and the seed, so it stays the same across ephemeral keys.
+//!
+//! The ephemeral signature and the address can be checked by anyone. The proof is checked by
+//! the validators before they sign a transaction, against the JWKs of the providers and the
+//! verifying key of the circuit held by the system object, which the validators vote on.
+
+use std::collections::HashMap;
+
+use ark_bn254::{Bn254, Fr};
+use ark_ff::PrimeField;
+use ark_groth16::{PreparedVerifyingKey, Proof, VerifyingKey};
+use ark_serialize::CanonicalDeserialize;
+use digest::Digest;
+use ed25519_dalek as dalek;
+use ed25519_dalek::Verifier;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_with::serde_as;
+use sha3::Sha3_256;
+use signature::Signature as _;
+
+use crate::base_types::{SuiAddress, SUI_ADDRESS_LENGTH};
+use crate::committee::EpochId;
+use crate::crypto::{Signable, Signature, SignatureScheme};
+use crate::error::{SuiError, SuiResult};
+use crate::fp_ensure;
+use crate::intent::{intent_message, IntentScoped};
+use crate::sui_serde::{Base64, Readable};
+use crate::sui_system_state::{SuiSystemState, ZkLoginJwk};
+
+#[cfg(test)]
+#[path = "unit_tests/zk_login_tests.rs"]
+mod zk_login_tests;
+
+/// The length of the hash of the subject of a JWT, and of the audience it was issued for.
+pub const ADDRESS_SEED_LENGTH: usize = 32;
+
+/// A signature by an ephemeral key, with the proof that a JWT of the provider `iss`, signed
+/// with its key `kid`, authorizes that key to sign for the address of `iss` and `address_seed`
+/// until the end of `max_epoch`.
+#[serde_as]
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct ZkLoginAuthenticator {
+    /// The compressed arkworks serialization of the Groth16 proof.
+    #[schemars(with = "Base64")]
+    #[serde_as(as = "Readable<Base64, _>")]
+    proof: Vec<u8>,
+    iss: String,
+    kid: String,
+    #[schemars(with = "Base64")]
+    #[serde_as(as = "Readable<Base64, _>")]
+    address_seed: Vec<u8>,
+    max_epoch: EpochId,
+    ephemeral_signature: Signature,
+}
+
+impl ZkLoginAuthenticator {
+    pub fn new(
+        proof: Vec<u8>,
+        iss: String,
+        kid: String,
+        address_seed: Vec<u8>,
+        max_epoch: EpochId,
+        ephemeral_signature: Signature,
+    ) -> Self {
+        Self {
+            proof,
+            iss,
+            kid,
+            address_seed,
+            max_epoch,
+            ephemeral_signature,
+        }
+    }
+
+    pub fn iss(&self) -> &str {
+        &self.iss
+    }
+
+    pub fn max_epoch(&self) -> EpochId {
+        self.max_epoch
+    }
+
+    /// The address of the zkLogin account of `address_seed` at the provider `iss`.
+    pub fn address(&self) -> SuiAddress {
+        zk_login_address(&self.iss, &self.address_seed)
+    }
+
+    /// Check that `author` is the address of the authenticator and that the ephemeral key
+    /// signed `value`. This doesn't check the proof, see `verify_proof`.
+    pub fn verify_ephemeral_signature<T>(&self, value: &T, author: SuiAddress) -> SuiResult<()>
+    where
+        T: Signable<Vec<u8>> + IntentScoped,
+    {
+        self.check_address_seed()?;
+        let received_addr = self.address();
+        fp_ensure!(
+            received_addr == author,
+            SuiError::IncorrectSigner {
+                error: format!(
+                    "zkLogin verification failure. Author is {author}, received address is \
+                     {received_addr}"
+                ),
+            }
+        );
+        let invalid = |error: String| SuiError::InvalidSignature { error };
+        let public_key = dalek::PublicKey::from_bytes(self.ephemeral_signature.public_key_bytes())
+            .map_err(|err| invalid(err.to_string()))?;
+        let signature = dalek::Signature::from_bytes(self.ephemeral_signature.signature_bytes())
+            .map_err(|err| invalid(err.to_string()))?;
+        public_key
+            .verify(&intent_message(value), &signature)
+            .map_err(|err| invalid(err.to_string()))
+    }
+
+    /// Check the proof against the JWKs and the verifying key of `env`, and that the ephemeral
+    /// key has not expired.
+    pub fn verify_proof(&self, env: &ZkLoginEnv) -> SuiResult<()> {
+        self.check_address_seed()?;
+        let invalid = |error: String| SuiError::InvalidSignature { error };
+        fp_ensure!(
+            self.max_epoch >= env.epoch,
+            invalid(format!(
+                "The zkLogin ephemeral key expired at the end of epoch {}",
+                self.max_epoch
+            ))
+        );
+        let modulus = env
+            .jwks
+            .get(&(self.iss.as_bytes().to_vec(), self.kid.as_bytes().to_vec()))
+            .ok_or_else(|| {
+                invalid(format!(
+                    "Unknown JWK {} of the OpenID provider {}",
+                    self.kid, self.iss
+                ))
+            })?;
+        let proof = Proof::<Bn254>::deserialize(&self.proof[..])
+            .map_err(|err| invalid(format!("Invalid zkLogin proof: {err}")))?;
+        let public_inputs = [self.public_inputs_hash(modulus)];
+        let valid = ark_groth16::verify_proof(&env.verifying_key, &proof, &public_inputs)
+            .map_err(|err| invalid(format!("Invalid zkLogin proof: {err}")))?;
+        fp_ensure!(valid, invalid("Invalid zkLogin proof".to_string()));
+        Ok(())
+    }
+
+    /// The single public input of the circuit, which commits to everything the proof is
+    /// about: the ephemeral key, its expiry, the address seed and the JWK of the provider.
+    pub fn public_inputs_hash(&self, modulus: &[u8]) -> Fr {
+        let mut hasher = Sha3_256::default();
+        hasher.update(self.ephemeral_signature.public_key_bytes());
+        hasher.update(self.max_epoch.to_le_bytes());
+        hasher.update(&self.address_seed);
+        for part in [self.iss.as_bytes(), self.kid.as_bytes(), modulus] {
+            hasher.update((part.len() as u32).to_le_bytes());
+            hasher.update(part);
+        }
+        Fr::from_le_bytes_mod_order(&hasher.finalize())
+    }
+
+    /// The seed is a hash, so a seed of another length can't be the hash of a subject.
+    fn check_address_seed(&self) -> SuiResult<()> {
+        fp_ensure!(
+            self.address_seed.len() == ADDRESS_SEED_LENGTH,
+            SuiError::InvalidSignature {
+                error: format!(
+                    "A zkLogin address seed must be {ADDRESS_SEED_LENGTH} bytes, got {}",
+                    self.address_seed.len()
+                ),
+            }
+        );
+        Ok(())
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = vec![SignatureScheme::ZkLogin.flag()];
+        bytes.extend(bcs::to_bytes(self).expect("Serialization should not fail"));
+        bytes
+    }
+}
+
+/// The address is the hash of the zkLogin flag, the provider prefixed with its length as a
+/// u32, and the address seed. The flag keeps it from colliding with the address of a key, and
+/// the length keeps the provider and the seed apart, whatever the length of the provider.
+pub fn zk_login_address(iss: &str, address_seed: &[u8]) -> SuiAddress {
+    let mut hasher = Sha3_256::default();
+    hasher.update([SignatureScheme::ZkLogin.flag()]);
+    hasher.update((iss.len() as u32).to_le_bytes());
+    hasher.update(iss.as_bytes());
+    hasher.update(address_seed);
+    let hash = hasher.finalize();
+    SuiAddress::try_from(&hash[..SUI_ADDRESS_LENGTH]).expect("byte lengths match")
+}
+
+/// What the proofs of zkLogin authenticators are checked against in an epoch.
+pub struct ZkLoginEnv {
+    epoch: EpochId,
+    verifying_key: PreparedVerifyingKey<Bn254>,
+    /// The moduli of the JWKs, by provider and key ID.
+    jwks: HashMap<(Vec<u8>, Vec<u8>), Vec<u8>>,
+}
+
+impl ZkLoginEnv {
+    /// The environment of `epoch`, from the arkworks serialization of the verifying key.
+    pub fn new(epoch: EpochId, verifying_key: &[u8], jwks: &[ZkLoginJwk]) -> SuiResult<Self> {
+        fp_ensure!(
+            !verifying_key.is_empty(),
+            SuiError::InvalidSignature {
+                error: "zkLogin is disabled until the validators vote for a verifying key"
+                    .to_string(),
+            }
+        );
+        let verifying_key = VerifyingKey::<Bn254>::deserialize(verifying_key).map_err(|err| {
+            SuiError::InvalidSignature {
+                error: format!("Invalid zkLogin verifying key: {err}"),
+            }
+        })?;
+        Ok(Self {
+            epoch,
+            verifying_key: ark_groth16::prepare_verifying_key(&verifying_key),
+            jwks: jwks
+                .iter()
+                .map(|jwk| ((jwk.iss.clone(), jwk.kid.clone()), jwk.modulus.clone()))
+                .collect(),
+        })
+    }
+
+    pub fn from_system_state(system_state: &SuiSystemState) -> SuiResult<Self> {
+        Self::new(
+            system_state.epoch,
+            &system_state.zk_login.verifying_key,
+            &system_state.zk_login.jwks,
+        )
+    }
+}
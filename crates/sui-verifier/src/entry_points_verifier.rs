@@ -4,7 +4,9 @@
 use move_binary_format::{
     access::ModuleAccess,
     binary_views::BinaryIndexedView,
-    file_format::{AbilitySet, Bytecode, FunctionDefinition, SignatureToken, Visibility},
+    file_format::{
+        AbilitySet, Bytecode, FunctionDefinition, SignatureToken, TypeParameterIndex, Visibility,
+    },
     CompiledModule,
 };
 use move_core_types::{account_address::AccountAddress, ident_str, identifier::IdentStr};
@@ -21,6 +23,7 @@ use sui_types::{
 use crate::{format_signature_token, resolve_struct};
 
 pub const INIT_FN_NAME: &IdentStr = ident_str!("init");
+pub const AUTHORIZE_CHILD_FN_NAME: &IdentStr = ident_str!("authorize_child");
 
 /// Checks valid rules rules for entry points, both for module initialization and transactions
 ///
@@ -31,6 +34,15 @@ pub const INIT_FN_NAME: &IdentStr = ident_str!("init");
 /// - The function can have a single parameter: &mut TxContext (see `is_tx_context`)
 /// - Alternatively, the function can have zero parameters
 ///
+/// For the authorization of the use of children of shared objects
+/// - The existence of the function is optional
+/// - The function must have the name specified by `AUTHORIZE_CHILD_FN_NAME`
+/// - The function must have `Visibility::Private`
+/// - The function must have three parameters: a reference to a struct with key defined in the
+///   module (instantiated with the type parameters of the function, in order), an `ID` and an
+///   `address`
+/// - The function must return a single `bool`
+///
 /// For transaction entry points
 /// - The function must have `Visibility::Script`
 /// - The function must have at least one parameter: &mut TxContext (see `is_tx_context`)
@@ -48,6 +60,11 @@ pub fn verify_module(module: &CompiledModule) -> SuiResult {
                 .map_err(|error| SuiError::ModuleVerificationFailure { error })?;
             continue;
         }
+        if name == AUTHORIZE_CHILD_FN_NAME {
+            verify_authorize_child_function(module, func_def)
+                .map_err(|error| SuiError::ModuleVerificationFailure { error })?;
+            continue;
+        }
 
         // find candidate entry functions and checke their parameters
         // (ignore other functions)
@@ -153,6 +170,75 @@ fn verify_init_function(module: &CompiledModule, fdef: &FunctionDefinition) -> R
     }
 }
 
+/// Checks if this module has a conformant `authorize_child`
+fn verify_authorize_child_function(
+    module: &CompiledModule,
+    fdef: &FunctionDefinition,
+) -> Result<(), String> {
+    let view = &BinaryIndexedView::Module(module);
+
+    if fdef.visibility != Visibility::Private {
+        return Err(format!(
+            "{}. '{}' function must be private",
+            module.self_id(),
+            AUTHORIZE_CHILD_FN_NAME
+        ));
+    }
+
+    let fhandle = module.function_handle_at(fdef.function);
+    if view.signature_at(fhandle.return_).0 != [SignatureToken::Bool] {
+        return Err(format!(
+            "{}. '{}' function must return a single bool",
+            module.self_id(),
+            AUTHORIZE_CHILD_FN_NAME
+        ));
+    }
+
+    let parameters = &view.signature_at(fhandle.parameters).0;
+    let signature_error = || {
+        format!(
+            "Expected the parameters of {}::{} to be a reference to a struct with key defined \
+            in the module, {}::{}::{} and address, but found ({})",
+            module.self_id(),
+            AUTHORIZE_CHILD_FN_NAME,
+            SUI_FRAMEWORK_ADDRESS,
+            ID_MODULE_NAME,
+            ID_STRUCT_NAME,
+            parameters
+                .iter()
+                .map(|t| format_signature_token(view, t))
+                .collect::<Vec<_>>()
+                .join(", "),
+        )
+    };
+    let (parent, id, sender) = match parameters.as_slice() {
+        [SignatureToken::Reference(parent), id, sender] => (&**parent, id, sender),
+        _ => return Err(signature_error()),
+    };
+    let (struct_handle_idx, type_args) = match parent {
+        SignatureToken::Struct(idx) => (*idx, &[][..]),
+        SignatureToken::StructInstantiation(idx, type_args) => (*idx, type_args.as_slice()),
+        _ => return Err(signature_error()),
+    };
+    let is_own_struct = view.struct_handle_at(struct_handle_idx).module == module.self_handle_idx();
+    let is_instantiated_with_type_parameters = type_args.len() == fhandle.type_parameters.len()
+        && type_args
+            .iter()
+            .enumerate()
+            .all(|(idx, t)| t == &SignatureToken::TypeParameter(idx as TypeParameterIndex));
+    let is_id =
+        matches!(id, SignatureToken::Struct(idx) if resolve_struct(view, *idx) == RESOLVED_SUI_ID);
+    if !is_own_struct
+        || !is_instantiated_with_type_parameters
+        || !is_object(view, &fhandle.type_parameters, parent)?
+        || !is_id
+        || sender != &SignatureToken::Address
+    {
+        return Err(signature_error());
+    }
+    Ok(())
+}
+
 fn verify_entry_function_impl(
     module: &CompiledModule,
     func_def: &FunctionDefinition,
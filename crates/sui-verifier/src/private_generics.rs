@@ -0,0 +1,143 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use move_binary_format::{
+    access::ModuleAccess,
+    binary_views::BinaryIndexedView,
+    file_format::{
+        Bytecode, FunctionDefinition, FunctionInstantiation, ModuleHandle, SignatureToken,
+    },
+    CompiledModule,
+};
+use sui_types::{
+    error::{SuiError, SuiResult},
+    SUI_FRAMEWORK_ADDRESS,
+};
+
+use crate::{entry_points_verifier::INIT_FN_NAME, format_signature_token};
+
+/// Framework functions that are "private" in their type arguments: every type argument must be
+/// a type declared in the calling module. This way only the package declaring `T` can claim the
+/// `Publisher` or the `UpgradeCap` of its package with a `T` witness.
+pub const PRIVATE_GENERIC_FUNCTIONS: &[(&str, &str)] =
+    &[("Publisher", "claim"), ("Package", "claim_upgrade_cap")];
+
+/// Framework functions which can only be called from the `init` function of a module, that is
+/// as its package is published.
+pub const INIT_ONLY_FUNCTIONS: &[(&str, &str)] =
+    &[("Publisher", "claim"), ("Package", "claim_upgrade_cap")];
+
+pub fn verify_module(module: &CompiledModule) -> SuiResult {
+    let view = &BinaryIndexedView::Module(module);
+    // do not need to check the calls of a module to itself
+    if private_module_name(view, module.self_handle()).is_some() {
+        return Ok(());
+    }
+    for func_def in &module.function_defs {
+        let name = module.identifier_at(module.function_handle_at(func_def.function).name);
+        verify_function(view, func_def, name == INIT_FN_NAME).map_err(|error| {
+            SuiError::ModuleVerificationFailure {
+                error: format!("{}::{}. {}", module.self_id(), name, error),
+            }
+        })?;
+    }
+    Ok(())
+}
+
+fn verify_function(
+    view: &BinaryIndexedView,
+    fdef: &FunctionDefinition,
+    is_init: bool,
+) -> Result<(), String> {
+    let code = match &fdef.code {
+        None => return Ok(()),
+        Some(code) => code,
+    };
+    for instr in &code.code {
+        if !is_init {
+            if let Some((mident, fident)) = called_framework_function(view, instr) {
+                if INIT_ONLY_FUNCTIONS.contains(&(mident, fident)) {
+                    return Err(format!(
+                        "Invalid call to '{}::{}::{}'. It can only be called from '{}'",
+                        SUI_FRAMEWORK_ADDRESS, mident, fident, INIT_FN_NAME,
+                    ));
+                }
+            }
+        }
+        if let Bytecode::CallGeneric(finst_idx) = instr {
+            let FunctionInstantiation {
+                handle,
+                type_parameters,
+            } = view.function_instantiation_at(*finst_idx);
+
+            let fhandle = view.function_handle_at(*handle);
+            let mhandle = view.module_handle_at(fhandle.module);
+            let mident = match private_module_name(view, mhandle) {
+                Some(mident) => mident,
+                None => continue,
+            };
+            let fident = view.identifier_at(fhandle.name).as_str();
+            if !PRIVATE_GENERIC_FUNCTIONS.contains(&(mident, fident)) {
+                continue;
+            }
+            let type_arguments = &view.signature_at(*type_parameters).0;
+            for type_arg in type_arguments {
+                let is_defined_in_current_module = match type_arg {
+                    SignatureToken::Struct(idx) | SignatureToken::StructInstantiation(idx, _) => {
+                        let shandle = view.struct_handle_at(*idx);
+                        view.self_handle_idx() == Some(shandle.module)
+                    }
+                    _ => false,
+                };
+                if !is_defined_in_current_module {
+                    return Err(format!(
+                        "Invalid call to '{}::{}::{}' with type '{}'. \
+                        The type argument must be defined in the current module",
+                        SUI_FRAMEWORK_ADDRESS,
+                        mident,
+                        fident,
+                        format_signature_token(view, type_arg),
+                    ));
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Returns the module and function names of the framework function `instr` calls, if any.
+fn called_framework_function<'a>(
+    view: &'a BinaryIndexedView,
+    instr: &Bytecode,
+) -> Option<(&'a str, &'a str)> {
+    let fhandle = match instr {
+        Bytecode::Call(idx) => view.function_handle_at(*idx),
+        Bytecode::CallGeneric(idx) => {
+            view.function_handle_at(view.function_instantiation_at(*idx).handle)
+        }
+        _ => return None,
+    };
+    let mhandle = view.module_handle_at(fhandle.module);
+    if view.address_identifier_at(mhandle.address) != &SUI_FRAMEWORK_ADDRESS {
+        return None;
+    }
+    Some((
+        view.identifier_at(mhandle.name).as_str(),
+        view.identifier_at(fhandle.name).as_str(),
+    ))
+}
+
+/// Returns the name of the module if it is a framework module with private generic functions.
+fn private_module_name<'a>(view: &'a BinaryIndexedView, mhandle: &ModuleHandle) -> Option<&'a str> {
+    let maddr = view.address_identifier_at(mhandle.address);
+    let mident = view.identifier_at(mhandle.name).as_str();
+    if maddr == &SUI_FRAMEWORK_ADDRESS
+        && PRIVATE_GENERIC_FUNCTIONS
+            .iter()
+            .any(|(module, _)| *module == mident)
+    {
+        Some(mident)
+    } else {
+        None
+    }
+}
@@ -55,22 +55,21 @@ fn create_gas_object(object_id: ObjectID, owner: SuiAddress) -> Object {
 
 /// This builds, signs a cert
 fn make_cert(network_config: &NetworkConfig, tx: &Transaction) -> CertifiedTransaction {
-    // Make certificate
-    let mut certificate = CertifiedTransaction::new(tx.clone());
     let committee = network_config.committee();
-    certificate.auth_sign_info.epoch = committee.epoch();
-    // TODO: Why iterating from 0 to quorum_threshold??
-    for i in 0..committee.quorum_threshold() {
-        let secx = network_config
-            .validator_configs()
-            .get(i as usize)
-            .unwrap()
-            .key_pair();
+    // Sign with validators until their stake reaches a quorum.
+    let mut signatures = Vec::new();
+    let mut weight = 0;
+    for validator in network_config.validator_configs() {
+        if weight >= committee.quorum_threshold() {
+            break;
+        }
+        let secx = validator.key_pair();
         let pubx = secx.public_key_bytes();
-        let sig = AuthoritySignature::new(&certificate.data, secx);
-        certificate.auth_sign_info.signatures.push((*pubx, sig));
+        let sig = AuthoritySignature::new(&tx.data, secx);
+        signatures.push((*pubx, sig));
+        weight += committee.weight(pubx);
     }
-    certificate
+    CertifiedTransaction::new_with_signatures(tx.clone(), &signatures, &committee).unwrap()
 }
 
 fn make_transactions(
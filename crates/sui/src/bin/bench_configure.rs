@@ -64,6 +64,8 @@ fn main() {
         move_packages: vec![],
         sui_framework_lib_path: None,
         move_framework_lib_path: None,
+        token_distribution_schedule: None,
+        epoch_duration_ms: None,
     };
 
     let path_str = "distributed_bench_genesis.conf";
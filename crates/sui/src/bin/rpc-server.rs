@@ -65,6 +65,6 @@ async fn main() -> anyhow::Result<()> {
 
     let server_handle = server.start(address).await?;
 
-    server_handle.await;
+    server_handle.stopped().await;
     Ok(())
 }
@@ -0,0 +1,417 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::anyhow;
+use async_trait::async_trait;
+use clap::Parser;
+use jsonrpsee::http_client::{HttpClient, HttpClientBuilder};
+use multiaddr::Multiaddr;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+use sui_config::genesis::Genesis;
+use sui_core::authority::{AuthorityStore, STORE_TABLES};
+use sui_core::authority_active::checkpoint_driver::get_epoch_handoff_certificate;
+use sui_core::authority_aggregator::AuthorityAggregator;
+use sui_core::authority_client::NetworkAuthorityClient;
+use sui_core::consistency::{check_consistency, Subject};
+use sui_core::replay::{diff_effects, replay_transaction, ObjectSource};
+use sui_core::snapshot::{export_snapshot, write_snapshot};
+use sui_gateway::api::RpcBcsApiClient;
+use sui_types::base_types::{
+    encode_bytes_hex, AuthorityName, ObjectID, SequenceNumber, TransactionDigest,
+};
+use sui_types::committee::{Committee, EpochId};
+use sui_types::error::{SuiError, SuiResult};
+use sui_types::light_client::verify_handoff;
+use sui_types::messages::{CertifiedTransaction, TransactionEffects};
+use sui_types::messages_checkpoint::CertifiedEpochHandoff;
+use sui_types::object::Object;
+use sui_types::sui_serde::{Base64, Encoding};
+use sui_types::sui_system_state::SuiSystemState;
+use sui_types::SUI_SYSTEM_STATE_OBJECT_ID;
+
+#[derive(Parser)]
+#[clap(
+    name = "Sui Tool",
+    about = "Utilities for debugging Sui networks",
+    rename_all = "kebab-case"
+)]
+enum ToolOpt {
+    /// Re-execute a transaction on the objects it read, as given by a full node, and compare
+    /// the effects of the replay with the effects signed by the validators
+    Replay {
+        /// The base64 digest of the transaction
+        #[clap(long, parse(try_from_str = parse_digest))]
+        digest: TransactionDigest,
+        /// The JSON-RPC address of the full node
+        #[clap(long, default_value = "http://127.0.0.1:9000")]
+        rpc: String,
+    },
+    /// Formal snapshots of the objects live at the end of an epoch
+    #[clap(subcommand)]
+    Snapshot(SnapshotOpt),
+    /// Ask every validator of the current committee for the state of objects and transactions,
+    /// and report the validators which disagree, with the stake behind each answer
+    CheckConsistency {
+        /// The genesis of the network, which the committees since are checked from
+        #[clap(long)]
+        genesis: PathBuf,
+        /// The JSON-RPC address of a full node, to read the addresses of the validators from
+        #[clap(long, default_value = "http://127.0.0.1:9000")]
+        rpc: String,
+        /// The objects to check
+        #[clap(long = "object-id")]
+        object_ids: Vec<ObjectID>,
+        /// The base64 digests of the transactions to check
+        #[clap(long = "digest", parse(try_from_str = parse_digest))]
+        digests: Vec<TransactionDigest>,
+    },
+    /// Inspect the store of a stopped node, or a copy of it
+    #[clap(subcommand)]
+    DbTool(DbToolOpt),
+}
+
+#[derive(Parser)]
+#[clap(rename_all = "kebab-case")]
+enum DbToolOpt {
+    /// List the tables of the store
+    ListTables,
+    /// Print the entries of a table as JSON, from the first one or from a key
+    Dump {
+        /// The database path of the node
+        #[clap(long)]
+        db_path: PathBuf,
+        /// The table to print
+        #[clap(long)]
+        table: String,
+        /// The first key to print: an object ID, optionally followed by `:<version>`, a base64
+        /// transaction digest, an address, a type or a number, as the table is keyed by
+        #[clap(long)]
+        from: Option<String>,
+        /// The last key to print
+        #[clap(long)]
+        to: Option<String>,
+        /// The maximum number of entries to print
+        #[clap(long, default_value = "100")]
+        limit: usize,
+    },
+    /// Print the latest version of an object and the transaction which wrote it
+    Object {
+        /// The database path of the node
+        #[clap(long)]
+        db_path: PathBuf,
+        #[clap(long)]
+        id: ObjectID,
+    },
+    /// Print the certificate and the effects of a transaction
+    Transaction {
+        /// The database path of the node
+        #[clap(long)]
+        db_path: PathBuf,
+        /// The base64 digest of the transaction
+        #[clap(long, parse(try_from_str = parse_digest))]
+        digest: TransactionDigest,
+    },
+    /// Print the number of entries of each table
+    Stats {
+        /// The database path of the node
+        #[clap(long)]
+        db_path: PathBuf,
+    },
+}
+
+#[derive(Parser)]
+#[clap(rename_all = "kebab-case")]
+enum SnapshotOpt {
+    /// Write the objects live at the end of an epoch, read from the store of a stopped node,
+    /// with the handoffs of the epochs up to it collected from the validators, for new nodes to
+    /// start from with `snapshot-path`
+    Export {
+        /// The epoch at the end of which the objects were live
+        #[clap(long)]
+        epoch: EpochId,
+        /// The database path of the node
+        #[clap(long)]
+        db_path: PathBuf,
+        /// The genesis of the network
+        #[clap(long)]
+        genesis: PathBuf,
+        /// The file to write the snapshot to
+        #[clap(long)]
+        output: PathBuf,
+    },
+}
+
+/// The store of the node at `db_path`, as the node opens it. The store is opened for writing, so
+/// it should be a copy or the store of a stopped node.
+fn open_store(db_path: &Path) -> AuthorityStore {
+    AuthorityStore::open(db_path.join("store"), None)
+}
+
+fn db_tool(opt: DbToolOpt) -> anyhow::Result<()> {
+    match opt {
+        DbToolOpt::ListTables => {
+            for table in STORE_TABLES {
+                println!("{table}");
+            }
+        }
+        DbToolOpt::Dump {
+            db_path,
+            table,
+            from,
+            to,
+            limit,
+        } => {
+            let store = open_store(&db_path);
+            for (key, value) in store.dump_table(&table, from.as_deref(), to.as_deref(), limit)? {
+                println!("{key} => {value}");
+            }
+        }
+        DbToolOpt::Object { db_path, id } => {
+            let store = open_store(&db_path);
+            match store.get_object(&id)? {
+                Some(object) => println!("{}", serde_json::to_string_pretty(&object)?),
+                None => println!("The object {id} is not live"),
+            }
+            if let Some((object_ref, digest)) = store.get_latest_parent_entry(id)? {
+                println!("Version {} written by transaction {digest:?}", object_ref.1);
+            }
+        }
+        DbToolOpt::Transaction { db_path, digest } => {
+            let store = open_store(&db_path);
+            let certificate = store
+                .get_certified_transaction(&digest)?
+                .ok_or_else(|| anyhow!("No certificate for transaction {digest:?}"))?;
+            println!("{}", serde_json::to_string_pretty(&certificate)?);
+            println!(
+                "{}",
+                serde_json::to_string_pretty(&store.get_effects(&digest)?)?
+            );
+        }
+        DbToolOpt::Stats { db_path } => {
+            let store = open_store(&db_path);
+            for (table, entries) in store.table_stats() {
+                println!("{table}: {entries} entries");
+            }
+        }
+    }
+    Ok(())
+}
+
+/// How long each validator has to answer about each object or transaction.
+const CONSISTENCY_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// The system state held in `object`.
+fn system_state(object: &Object) -> anyhow::Result<SuiSystemState> {
+    let move_object = object
+        .data
+        .try_as_move()
+        .ok_or_else(|| anyhow!("The system state is not a Move object"))?;
+    Ok(bcs::from_bytes(move_object.contents())?)
+}
+
+/// The network addresses of the validators, as `system_state` records them, or as `genesis`
+/// does for the validators which have left the network since.
+fn validator_addresses(
+    genesis: &Genesis,
+    system_state: &SuiSystemState,
+) -> anyhow::Result<BTreeMap<AuthorityName, Multiaddr>> {
+    let mut addresses: BTreeMap<_, _> = genesis
+        .validator_set()
+        .iter()
+        .map(|validator| (validator.public_key(), validator.network_address().clone()))
+        .collect();
+    for validator in &system_state.validators.active_validators {
+        let name = AuthorityName::try_from(validator.metadata.pubkey_bytes.as_ref())?;
+        let address = Multiaddr::try_from(validator.metadata.net_address.clone())?;
+        addresses.insert(name, address);
+    }
+    Ok(addresses)
+}
+
+/// The clients of the validators of `committee`, at `addresses`.
+fn validator_clients(
+    committee: &Committee,
+    addresses: &BTreeMap<AuthorityName, Multiaddr>,
+) -> anyhow::Result<BTreeMap<AuthorityName, NetworkAuthorityClient>> {
+    let mut clients = BTreeMap::new();
+    for name in committee.voting_rights.keys() {
+        let address = addresses
+            .get(name)
+            .ok_or_else(|| anyhow!("No address for validator {}", encode_bytes_hex(name)))?;
+        clients.insert(*name, NetworkAuthorityClient::connect_lazy(address)?);
+    }
+    Ok(clients)
+}
+
+async fn check_validators_consistency(
+    genesis: &Genesis,
+    system_state: &SuiSystemState,
+    subjects: Vec<Subject>,
+) -> anyhow::Result<()> {
+    let addresses = validator_addresses(genesis, system_state)?;
+    // The committee of the current epoch is the one the handoff of the epoch before certifies.
+    let committee = match system_state.epoch.checked_sub(1) {
+        Some(last_epoch) => collect_handoffs(genesis, &addresses, last_epoch).await?.1,
+        None => genesis.committee(),
+    };
+    let net = AuthorityAggregator::new(
+        committee.clone(),
+        validator_clients(&committee, &addresses)?,
+    );
+    let reports = check_consistency(&net, &subjects, CONSISTENCY_TIMEOUT).await;
+    let mut inconsistent = 0;
+    for report in &reports {
+        if report.is_consistent() {
+            println!("{}: consistent", report.subject);
+        } else {
+            inconsistent += 1;
+            println!("{}: DIVERGENT", report.subject);
+        }
+        for group in &report.groups {
+            println!(
+                "  {}/{} stake: {}",
+                group.stake, report.total_stake, group.view
+            );
+            for authority in &group.authorities {
+                println!("    {}", encode_bytes_hex(authority));
+            }
+        }
+    }
+    if inconsistent > 0 {
+        return Err(anyhow!(
+            "The validators disagree on {inconsistent} of {} subjects",
+            reports.len()
+        ));
+    }
+    Ok(())
+}
+
+/// How long the validators have to sign the handoff of an epoch.
+const HANDOFF_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// The certified handoffs of the epochs from the genesis one to `epoch`, from the validators of
+/// each committee at `addresses`, and the committee the last one hands off to.
+async fn collect_handoffs(
+    genesis: &Genesis,
+    addresses: &BTreeMap<AuthorityName, Multiaddr>,
+    epoch: EpochId,
+) -> anyhow::Result<(Vec<CertifiedEpochHandoff>, Committee)> {
+    let mut committee = genesis.committee();
+    let mut handoffs = Vec::new();
+    for handoff_epoch in 0..=epoch {
+        let clients = validator_clients(&committee, addresses)?;
+        let net = Arc::new(AuthorityAggregator::new(committee.clone(), clients));
+        let handoff = get_epoch_handoff_certificate(net, handoff_epoch, HANDOFF_TIMEOUT).await?;
+        committee = verify_handoff(&committee, &handoff)?;
+        handoffs.push(handoff);
+    }
+    Ok((handoffs, committee))
+}
+
+fn parse_digest(digest: &str) -> Result<TransactionDigest, anyhow::Error> {
+    Ok(TransactionDigest::try_from(&Base64::decode(digest)?[..])?)
+}
+
+/// The objects of the past, as kept by a full node.
+struct FullNodeObjectSource {
+    client: HttpClient,
+}
+
+#[async_trait]
+impl ObjectSource for FullNodeObjectSource {
+    async fn get_object(
+        &self,
+        object_id: ObjectID,
+        version: Option<SequenceNumber>,
+    ) -> SuiResult<Object> {
+        let fetch = async {
+            let bytes = self
+                .client
+                .get_raw_object_at_version(object_id, version)
+                .await?;
+            Ok::<_, anyhow::Error>(bcs::from_bytes(&bytes.to_vec()?)?)
+        };
+        fetch
+            .await
+            .map_err(|error| SuiError::GenericAuthorityError {
+                error: format!(
+                    "Cannot fetch the object {object_id} at version {version:?}: {error}"
+                ),
+            })
+    }
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    match ToolOpt::parse() {
+        ToolOpt::Replay { digest, rpc } => {
+            let client = HttpClientBuilder::default().build(&rpc)?;
+            let transaction = client.get_raw_transaction(digest).await?;
+            let certificate: CertifiedTransaction =
+                bcs::from_bytes(&transaction.certificate.to_vec()?)?;
+            let effects: TransactionEffects = bcs::from_bytes(&transaction.effects.to_vec()?)?;
+
+            let source = FullNodeObjectSource { client };
+            let replayed = replay_transaction(&source, &certificate, &effects).await?;
+            let differences = diff_effects(&effects, &replayed);
+            if differences.is_empty() {
+                println!("The effects of the replay match the effects of the validators");
+                return Ok(());
+            }
+            for difference in &differences {
+                println!("{difference}");
+            }
+            Err(anyhow!(
+                "The effects of the replay differ from the effects of the validators in {} fields",
+                differences.len()
+            ))
+        }
+        ToolOpt::Snapshot(SnapshotOpt::Export {
+            epoch,
+            db_path,
+            genesis,
+            output,
+        }) => {
+            let genesis = Genesis::load(genesis)?;
+            let store = AuthorityStore::open(db_path.join("store"), None);
+            let system_state_object = store
+                .get_object(&SUI_SYSTEM_STATE_OBJECT_ID)?
+                .ok_or_else(|| anyhow!("The store has no system state"))?;
+            let addresses = validator_addresses(&genesis, &system_state(&system_state_object)?)?;
+            let (handoffs, _) = collect_handoffs(&genesis, &addresses, epoch).await?;
+            let snapshot = export_snapshot(&store, handoffs)?;
+            // The snapshot is checked as the nodes starting from it will check it.
+            snapshot.verify(genesis.committee())?;
+            write_snapshot(&output, &snapshot)?;
+            println!(
+                "Wrote the {} objects live at the end of epoch {epoch} to {}",
+                snapshot.objects.len(),
+                output.display()
+            );
+            Ok(())
+        }
+        ToolOpt::CheckConsistency {
+            genesis,
+            rpc,
+            object_ids,
+            digests,
+        } => {
+            let genesis = Genesis::load(genesis)?;
+            let source = FullNodeObjectSource {
+                client: HttpClientBuilder::default().build(&rpc)?,
+            };
+            let system_state =
+                system_state(&source.get_object(SUI_SYSTEM_STATE_OBJECT_ID, None).await?)?;
+            let subjects = object_ids
+                .into_iter()
+                .map(Subject::Object)
+                .chain(digests.into_iter().map(Subject::Transaction))
+                .collect();
+            check_validators_consistency(&genesis, &system_state, subjects).await
+        }
+        ToolOpt::DbTool(opt) => db_tool(opt),
+    }
+}
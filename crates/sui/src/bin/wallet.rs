@@ -15,8 +15,8 @@ use tracing::debug;
 
 use colored::Colorize;
 use sui::{
-    config::{GatewayType, WalletConfig},
-    keystore::KeystoreType,
+    config::{GatewayType, WalletConfig, WalletEnv},
+    keystore::{Keystore, KeystoreType, SuiKeystore},
     shell::{
         install_shell_plugins, AsyncHandler, CacheKey, CommandStructure, CompletionCache, Shell,
     },
@@ -24,6 +24,7 @@ use sui::{
 };
 use sui_config::{sui_config_dir, Config, SUI_DEV_NET_URL, SUI_WALLET_CONFIG};
 use sui_types::exit_main;
+use sui_types::key_derivation::DerivationPath;
 
 const SUI: &str = "   _____       _    _       __      ____     __
   / ___/__  __(_)  | |     / /___ _/ / /__  / /_
@@ -50,6 +51,15 @@ struct ClientOpt {
     /// Return command outputs in json format.
     #[clap(long, global = true)]
     json: bool,
+    /// Sign for the address of a Ledger device with the device.
+    #[clap(long, global = true)]
+    ledger: bool,
+    /// The derivation path of the Ledger key, m/44'/784'/0'/0'/0' by default.
+    #[clap(long, global = true, requires = "ledger")]
+    ledger_derivation_path: Option<DerivationPath>,
+    /// Send transactions after their dry run without asking for confirmation.
+    #[clap(short = 'y', long, global = true)]
+    yes: bool,
 }
 
 async fn try_main() -> Result<(), anyhow::Error> {
@@ -92,12 +102,28 @@ async fn try_main() -> Result<(), anyhow::Error> {
                 .parent()
                 .unwrap_or(&sui_config_dir()?)
                 .join("wallet.key");
-            let keystore = KeystoreType::File(keystore_path);
-            let new_address = keystore.init()?.add_random_key()?;
+            // A new keystore asks for the passphrase it is encrypted with.
+            let mut keystore = SuiKeystore::load_or_create(&keystore_path)?;
+            let new_address = keystore.add_random_key()?;
+            // DevNet is always one switch --env away, whichever gateway was chosen.
+            let mut envs = vec![WalletEnv {
+                alias: "devnet".to_string(),
+                rpc: SUI_DEV_NET_URL.to_string(),
+            }];
+            if url != SUI_DEV_NET_URL {
+                envs.push(WalletEnv {
+                    alias: "custom".to_string(),
+                    rpc: url.to_string(),
+                });
+            }
+            let active_env = envs.last().map(|env| env.alias.clone());
             WalletConfig {
                 accounts: vec![new_address],
-                keystore,
+                aliases: Default::default(),
+                keystore: KeystoreType::File(keystore_path),
                 gateway: GatewayType::RPC(url.to_string()),
+                envs,
+                active_env,
                 active_address: Some(new_address),
             }
             .persisted(&wallet_conf_path)
@@ -106,6 +132,12 @@ async fn try_main() -> Result<(), anyhow::Error> {
     }
 
     let mut context = WalletContext::new(&wallet_conf_path)?;
+    // The JSON output is read by programs, which can't answer.
+    context.confirm_before_sending = !options.yes && !options.json;
+    if options.ledger {
+        let address = context.use_ledger(options.ledger_derivation_path.clone())?;
+        debug!("Signing for Ledger address {address}");
+    }
 
     // Sync all accounts on start up.
     // Do not sync if command is a gateway switch, as the current gateway might be unreachable and causes sync to panic.
@@ -114,7 +146,9 @@ async fn try_main() -> Result<(), anyhow::Error> {
         Some(WalletCommands::Switch {
             gateway: Some(_),
             ..
-        })
+        }) | Some(WalletCommands::Switch { env: Some(_), .. })
+            | Some(WalletCommands::NewEnv { .. })
+            | Some(WalletCommands::Envs)
     ) {
         for address in context.config.accounts.clone() {
             WalletCommands::SyncClientState {
@@ -211,10 +245,12 @@ async fn handle_command(
     // TODO: Completion data are keyed by strings, are there ways to make it more error proof?
     if let Ok(mut cache) = completion_cache.write() {
         match result {
-            WalletCommandResult::Addresses(ref addresses) => {
-                let addresses = addresses
+            WalletCommandResult::Addresses(ref response) => {
+                let addresses = response
+                    .addresses
                     .iter()
                     .map(|addr| format!("{addr}"))
+                    .chain(response.aliases.keys().cloned())
                     .collect::<Vec<_>>();
                 cache.insert(CacheKey::flag("--address"), addresses.clone());
                 cache.insert(CacheKey::flag("--to"), addresses);
@@ -239,7 +275,7 @@ async fn handle_command(
         WalletCommandResult::Switch(SwitchResponse {
             gateway: Some(_),
             ..
-        })
+        }) | WalletCommandResult::Switch(SwitchResponse { env: Some(_), .. })
     ) {
         println!("Gateway switch completed, please restart wallet.");
         return Ok(true);
@@ -3,10 +3,14 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::keystore::KeystoreType;
+use anyhow::anyhow;
 use serde::{Deserialize, Serialize};
 use serde_with::{hex::Hex, serde_as};
+use std::collections::BTreeMap;
 use std::fmt::{Display, Formatter, Write};
+use std::str::FromStr;
 use sui_types::base_types::*;
+use sui_types::name_service::NAME_SERVICE_DOMAIN;
 
 pub use sui_config::Config;
 pub use sui_config::PersistedConfig;
@@ -20,13 +24,110 @@ pub use sui_gateway::config::{GatewayConfig, GatewayType};
 pub struct WalletConfig {
     #[serde_as(as = "Vec<Hex>")]
     pub accounts: Vec<SuiAddress>,
+    /// Names of some of the accounts, which commands accept in place of their address.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub aliases: BTreeMap<String, SuiAddress>,
     pub keystore: KeystoreType,
     pub gateway: GatewayType,
+    /// The networks which `switch --env` can connect to.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub envs: Vec<WalletEnv>,
+    /// The alias of the env of `gateway`, unless it was set with `switch --gateway`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub active_env: Option<String>,
     pub active_address: Option<SuiAddress>,
 }
 
 impl Config for WalletConfig {}
 
+impl WalletConfig {
+    /// The managed address given by `address`, as hex or as its alias. Names of the name service
+    /// are resolved by the network, with `WalletContext::resolve_address`.
+    pub fn resolve_address(&self, address: &AddressOrAlias) -> Result<SuiAddress, anyhow::Error> {
+        match address {
+            AddressOrAlias::Address(address) => Ok(*address),
+            AddressOrAlias::Alias(alias) => self
+                .aliases
+                .get(alias)
+                .copied()
+                .ok_or_else(|| anyhow!("No address has the alias {alias}")),
+            AddressOrAlias::Name(name) => {
+                Err(anyhow!("Name {name} can only be resolved by the network"))
+            }
+        }
+    }
+
+    /// Name the managed `address`, replacing its previous alias if any.
+    pub fn set_alias(&mut self, address: SuiAddress, alias: String) -> Result<(), anyhow::Error> {
+        if !self.accounts.contains(&address) {
+            return Err(anyhow!("Address {address} not managed by wallet"));
+        }
+        if SuiAddress::from_str(&alias).is_ok() {
+            return Err(anyhow!("Alias {alias} can't be an address"));
+        }
+        if alias.ends_with(NAME_SERVICE_DOMAIN) {
+            return Err(anyhow!(
+                "Alias {alias} can't end with {NAME_SERVICE_DOMAIN}, as names of the name service do"
+            ));
+        }
+        match self.aliases.get(&alias) {
+            Some(other) if *other != address => {
+                return Err(anyhow!("Alias {alias} already names address {other}"));
+            }
+            _ => {}
+        }
+        self.aliases.retain(|_, named| *named != address);
+        self.aliases.insert(alias, address);
+        Ok(())
+    }
+
+    pub fn add_env(&mut self, env: WalletEnv) -> Result<(), anyhow::Error> {
+        if self.get_env(&env.alias).is_some() {
+            return Err(anyhow!("Env {} already exists", env.alias));
+        }
+        self.envs.push(env);
+        Ok(())
+    }
+
+    pub fn get_env(&self, alias: &str) -> Option<&WalletEnv> {
+        self.envs.iter().find(|env| env.alias == alias)
+    }
+}
+
+/// A network the wallet can connect to, by the URL of its RPC gateway.
+#[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq)]
+pub struct WalletEnv {
+    pub alias: String,
+    pub rpc: String,
+}
+
+/// An address given on the command line, as hex, as the alias of a managed address, or as a
+/// name of the name service, ending with `.sui`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum AddressOrAlias {
+    Address(SuiAddress),
+    Alias(String),
+    Name(String),
+}
+
+impl From<SuiAddress> for AddressOrAlias {
+    fn from(address: SuiAddress) -> Self {
+        AddressOrAlias::Address(address)
+    }
+}
+
+impl FromStr for AddressOrAlias {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match SuiAddress::from_str(s) {
+            Ok(address) => AddressOrAlias::Address(address),
+            Err(_) if s.ends_with(NAME_SERVICE_DOMAIN) => AddressOrAlias::Name(s.to_string()),
+            Err(_) => AddressOrAlias::Alias(s.to_string()),
+        })
+    }
+}
+
 impl Display for WalletConfig {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         let mut writer = String::new();
@@ -39,6 +140,9 @@ impl Display for WalletConfig {
         };
         writeln!(writer, "{}", self.keystore)?;
         write!(writer, "{}", self.gateway)?;
+        if let Some(env) = &self.active_env {
+            writeln!(writer, "Active env : {}", env)?;
+        }
 
         write!(f, "{}", writer)
     }
@@ -0,0 +1,238 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! The configs of a network whose nodes run on separate hosts, such as the containers of a
+//! docker-compose or Kubernetes deployment. Every node listens on ports of its own, so that the
+//! nodes can also share a host, and finds the other nodes by the names of their hosts.
+
+use crate::keystore::{Keystore, SuiKeystore};
+use anyhow::bail;
+use std::collections::BTreeSet;
+use std::fmt::Write;
+use std::fs;
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::path::{Path, PathBuf};
+use sui_config::builder::ConfigBuilder;
+use sui_config::genesis_config::{GenesisConfig, ValidatorGenesisInfo};
+use sui_config::{Config, SUI_FULLNODE_CONFIG, SUI_GENESIS_FILENAME};
+use sui_types::base_types::SuiAddress;
+use sui_types::crypto::get_key_pair;
+use tracing::info;
+
+#[cfg(test)]
+#[path = "unit_tests/deployment_tests.rs"]
+mod deployment_tests;
+
+/// The host of the full node of the deployment.
+pub const FULLNODE_HOST: &str = "fullnode";
+
+/// The ports each node listens on, from the base port of the node up.
+const PORTS_PER_NODE: u16 = 10;
+
+pub struct NodeDeployment {
+    /// The hosts of the validators, one validator on each.
+    pub validator_hosts: Vec<String>,
+    /// The first port of the first validator. The ports of each node follow the ports of the
+    /// node before it.
+    pub base_port: u16,
+    /// The directory holding the configs and the databases of the nodes on their hosts.
+    pub node_root: PathBuf,
+}
+
+impl NodeDeployment {
+    /// The deployment of `genesis_conf`: its validators, or `committee_size` validators, on
+    /// `validator-0`, `validator-1`, and so on.
+    pub fn with_default_hosts(
+        genesis_conf: &GenesisConfig,
+        base_port: u16,
+        node_root: PathBuf,
+    ) -> Self {
+        let committee_size = match &genesis_conf.validator_genesis_info {
+            Some(validators) => validators.len(),
+            None => genesis_conf.committee_size,
+        };
+        Self {
+            validator_hosts: (0..committee_size)
+                .map(|i| format!("validator-{i}"))
+                .collect(),
+            base_port,
+            node_root,
+        }
+    }
+
+    /// Build the genesis of `genesis_conf` for the nodes of the deployment, and write it to
+    /// `dir` along with a config for each validator, a full node config, the keystore of the
+    /// genesis accounts, and a docker-compose file running the nodes.
+    pub fn write_node_configs(
+        &self,
+        mut genesis_conf: GenesisConfig,
+        dir: &Path,
+    ) -> Result<(), anyhow::Error> {
+        if self.validator_hosts.is_empty() {
+            bail!("A deployment needs at least one validator host");
+        }
+        let unique_hosts: BTreeSet<_> = self.validator_hosts.iter().collect();
+        if unique_hosts.len() != self.validator_hosts.len()
+            || self
+                .validator_hosts
+                .iter()
+                .any(|host| host == FULLNODE_HOST)
+        {
+            bail!("The hosts of the validators and of the full node must be distinct");
+        }
+        if self
+            .port(self.validator_hosts.len(), PORTS_PER_NODE - 1)
+            .is_none()
+        {
+            bail!("The ports of the deployment exceed the port range");
+        }
+
+        // The key pairs and stakes of the configured validators, if any, on the hosts of the
+        // deployment.
+        let key_pairs_and_stakes: Vec<_> = match genesis_conf.validator_genesis_info.take() {
+            Some(validators) => {
+                if validators.len() != self.validator_hosts.len() {
+                    bail!(
+                        "The genesis config has {} validators but the deployment {} hosts",
+                        validators.len(),
+                        self.validator_hosts.len()
+                    );
+                }
+                validators
+                    .into_iter()
+                    .map(|validator| (validator.key_pair, validator.stake))
+                    .collect()
+            }
+            None => self
+                .validator_hosts
+                .iter()
+                .map(|_| (get_key_pair().1, 1))
+                .collect(),
+        };
+        let validators = key_pairs_and_stakes
+            .into_iter()
+            .zip(&self.validator_hosts)
+            .enumerate()
+            .map(|(i, ((key_pair, stake), host))| ValidatorGenesisInfo {
+                key_pair,
+                network_address: self.network_address(host, i, 0),
+                stake,
+                narwhal_primary_to_primary: self.network_address(host, i, 1),
+                narwhal_worker_to_primary: self.network_address(host, i, 2),
+                narwhal_primary_to_worker: self.network_address(host, i, 3),
+                narwhal_worker_to_worker: self.network_address(host, i, 4),
+                narwhal_consensus_address: self.network_address(host, i, 5),
+            })
+            .collect();
+
+        let db_dir = self.node_root.join("db");
+        let genesis_path = self.node_root.join("config").join(SUI_GENESIS_FILENAME);
+        let network_config = ConfigBuilder::new(&db_dir)
+            .initial_accounts_config(genesis_conf)
+            .build_with_validators(validators);
+
+        fs::create_dir_all(dir)?;
+        network_config
+            .genesis
+            .save(dir.join(SUI_GENESIS_FILENAME))?;
+
+        let mut keystore = SuiKeystore::default();
+        for key in &network_config.account_keys {
+            keystore.add_key(SuiAddress::from(key.public_key_bytes()), key.copy())?;
+        }
+        keystore.set_path(&dir.join("wallet.key"));
+        keystore.save()?;
+
+        let mut fullnode_config = network_config.generate_fullnode_config();
+        let fullnode = self.validator_hosts.len();
+        fullnode_config.db_path = db_dir.join(sui_config::FULL_NODE_DB_PATH);
+        fullnode_config.network_address = self.network_address(FULLNODE_HOST, fullnode, 0);
+        fullnode_config.metrics_address = self.socket_address(fullnode, 6);
+        fullnode_config.json_rpc_address = self.socket_address(fullnode, 7);
+        fullnode_config.genesis = sui_config::node::Genesis::new_from_file(&genesis_path);
+        fullnode_config.save(dir.join(SUI_FULLNODE_CONFIG))?;
+
+        let mut validator_config_files = Vec::new();
+        for (i, mut validator) in network_config
+            .into_validator_configs()
+            .into_iter()
+            .enumerate()
+        {
+            validator.metrics_address = self.socket_address(i, 6);
+            validator.json_rpc_address = self.socket_address(i, 7);
+            validator.genesis = sui_config::node::Genesis::new_from_file(&genesis_path);
+            let file = format!("validator-config-{i}.yaml");
+            validator.save(dir.join(&file))?;
+            validator_config_files.push(file);
+        }
+
+        let services = self
+            .validator_hosts
+            .iter()
+            .zip(&validator_config_files)
+            .enumerate()
+            .map(|(i, (host, file))| (host.as_str(), file.as_str(), i))
+            .chain([(FULLNODE_HOST, SUI_FULLNODE_CONFIG, fullnode)]);
+        fs::write(
+            dir.join("docker-compose.yaml"),
+            self.docker_compose(services),
+        )?;
+
+        info!(
+            "Configs of {} validators and a full node written to {dir:?}",
+            validator_config_files.len()
+        );
+        Ok(())
+    }
+
+    /// The `offset`-th port of the `node`-th node.
+    fn port(&self, node: usize, offset: u16) -> Option<u16> {
+        let node = u16::try_from(node).ok()?;
+        self.base_port
+            .checked_add(node.checked_mul(PORTS_PER_NODE)?)?
+            .checked_add(offset)
+    }
+
+    fn network_address(&self, host: &str, node: usize, offset: u16) -> multiaddr::Multiaddr {
+        format!("/dns/{host}/tcp/{}/http", self.port(node, offset).unwrap())
+            .parse()
+            .unwrap()
+    }
+
+    fn socket_address(&self, node: usize, offset: u16) -> SocketAddr {
+        SocketAddr::new(
+            IpAddr::V4(Ipv4Addr::UNSPECIFIED),
+            self.port(node, offset).unwrap(),
+        )
+    }
+
+    /// A docker-compose file running the `sui-node` image of each (host, config file, node),
+    /// with the directory of the configs mounted in the containers.
+    fn docker_compose<'a>(
+        &self,
+        services: impl Iterator<Item = (&'a str, &'a str, usize)>,
+    ) -> String {
+        let config_dir = self.node_root.join("config");
+        let mut compose = String::from("version: \"3.9\"\nservices:\n");
+        for (host, file, node) in services {
+            let first_port = self.port(node, 0).unwrap();
+            let last_port = self.port(node, PORTS_PER_NODE - 1).unwrap();
+            // Writing to a String cannot fail.
+            let _ = write!(
+                compose,
+                "  {host}:
+    image: sui-node
+    hostname: {host}
+    command: [\"sui-node\", \"--config-path\", \"{}\"]
+    volumes:
+      - ./:{}:ro
+    ports:
+      - \"{first_port}-{last_port}:{first_port}-{last_port}\"
+",
+                config_dir.join(file).display(),
+                config_dir.display(),
+            );
+        }
+        compose
+    }
+}
@@ -0,0 +1,204 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! A genesis ceremony, in which the operators of the validators of a network build its genesis
+//! on their own machines. The ceremony is a directory shared by the operators, for example as a
+//! git repository, to which each of them adds their validator and, once the genesis is built,
+//! their signature of it. Private keys never leave the machines of the operators.
+
+use anyhow::{bail, Context};
+use clap::*;
+use multiaddr::Multiaddr;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use sui_config::genesis::{Builder, Genesis};
+use sui_config::{ValidatorInfo, SUI_GENESIS_FILENAME};
+use sui_types::base_types::{decode_bytes_hex, encode_bytes_hex, ObjectID, SuiAddress};
+use sui_types::committee::StakeUnit;
+use sui_types::crypto::KeyPair;
+use sui_types::object::Object;
+use tracing::info;
+
+#[cfg(test)]
+#[path = "unit_tests/genesis_ceremony_tests.rs"]
+mod genesis_ceremony_tests;
+
+/// The genesis built by `build`, which the validators sign.
+const UNSIGNED_GENESIS_FILENAME: &str = "unsigned-genesis.blob";
+
+#[derive(Parser)]
+pub struct Ceremony {
+    /// The directory of the ceremony, the current directory by default.
+    #[clap(long)]
+    path: Option<PathBuf>,
+
+    #[clap(subcommand)]
+    command: CeremonyCommand,
+}
+
+#[derive(Parser)]
+#[clap(rename_all = "kebab-case")]
+pub enum CeremonyCommand {
+    /// Start a ceremony in an empty directory.
+    Init,
+    /// Add a validator to the genesis committee. The key file holds the base64 key pair of the
+    /// validator, as in the `key-pair` field of its config, and is only read locally.
+    AddValidator {
+        #[clap(long)]
+        key_file: PathBuf,
+        #[clap(long)]
+        network_address: Multiaddr,
+        #[clap(long, default_value = "1")]
+        stake: StakeUnit,
+    },
+    /// Add a gas object owned by `address` to the genesis.
+    AddGasObject {
+        #[clap(long, parse(try_from_str = decode_bytes_hex))]
+        address: SuiAddress,
+        /// The ID of the object, random by default.
+        #[clap(long)]
+        object_id: Option<ObjectID>,
+        #[clap(long)]
+        value: u64,
+    },
+    /// Build the genesis of the validators and objects added so far. No validator or object
+    /// can be added afterwards.
+    Build,
+    /// Check that the built genesis is the genesis of the validators and objects of the
+    /// ceremony, and sign it with the key of a validator.
+    VerifyAndSign {
+        #[clap(long)]
+        key_file: PathBuf,
+    },
+    /// Check that every validator signed the built genesis, and write it to `genesis.blob`.
+    Finalize,
+}
+
+impl Ceremony {
+    pub fn run(&self) -> Result<(), anyhow::Error> {
+        let dir = match &self.path {
+            Some(path) => path.clone(),
+            None => std::env::current_dir()?,
+        };
+        let unsigned_genesis_path = dir.join(UNSIGNED_GENESIS_FILENAME);
+
+        match &self.command {
+            CeremonyCommand::Init => {
+                fs::create_dir_all(&dir)?;
+                if dir.read_dir()?.next().is_some() {
+                    bail!("Cannot start a genesis ceremony in non-empty directory {dir:?}");
+                }
+                Builder::new(sui_adapter::genesis::get_genesis_context()).save(&dir)?;
+                info!("Genesis ceremony started in {dir:?}");
+            }
+            CeremonyCommand::AddValidator {
+                key_file,
+                network_address,
+                stake,
+            } => {
+                check_not_built(&unsigned_genesis_path)?;
+                let key_pair = read_key_pair(key_file)?;
+                let validator = ValidatorInfo::new(&key_pair, *stake, network_address.clone());
+                let builder = Builder::load(&dir)?;
+                let address = validator.sui_address();
+                if builder
+                    .validators()
+                    .iter()
+                    .any(|validator| validator.sui_address() == address)
+                {
+                    bail!("Validator {address} is already in the genesis committee");
+                }
+                builder.add_validator(validator).save(&dir)?;
+                info!("Added validator {address}");
+            }
+            CeremonyCommand::AddGasObject {
+                address,
+                object_id,
+                value,
+            } => {
+                check_not_built(&unsigned_genesis_path)?;
+                let object_id = object_id.unwrap_or_else(ObjectID::random);
+                let builder = Builder::load(&dir)?;
+                if builder
+                    .objects()
+                    .iter()
+                    .any(|object| object.id() == object_id)
+                {
+                    bail!("Object {object_id} is already in the genesis");
+                }
+                let object = Object::with_id_owner_gas_for_testing(object_id, *address, *value);
+                builder.add_object(object).save(&dir)?;
+                info!("Added gas object {object_id} owned by {address}");
+            }
+            CeremonyCommand::Build => {
+                check_not_built(&unsigned_genesis_path)?;
+                let builder = Builder::load(&dir)?;
+                if builder.validators().is_empty() {
+                    bail!("Cannot build a genesis without validators");
+                }
+                let genesis = builder.build();
+                genesis.save(&unsigned_genesis_path)?;
+                println!("Built genesis {}", encode_bytes_hex(&genesis.sha3()));
+            }
+            CeremonyCommand::VerifyAndSign { key_file } => {
+                let key_pair = read_key_pair(key_file)?;
+                let builder = Builder::load(&dir)?;
+                let genesis = load_verified_genesis(&builder, &unsigned_genesis_path)?;
+                let address = SuiAddress::from(key_pair.public_key_bytes());
+                if !genesis
+                    .validator_set()
+                    .iter()
+                    .any(|validator| validator.public_key() == *key_pair.public_key_bytes())
+                {
+                    bail!("Validator {address} is not in the genesis committee");
+                }
+                builder.add_validator_signature(&key_pair).save(&dir)?;
+                println!(
+                    "Validator {address} signed genesis {}",
+                    encode_bytes_hex(&genesis.sha3())
+                );
+            }
+            CeremonyCommand::Finalize => {
+                let builder = Builder::load(&dir)?;
+                let genesis = load_verified_genesis(&builder, &unsigned_genesis_path)?;
+                genesis.verify_signatures(builder.signatures())?;
+                let genesis_path = dir.join(SUI_GENESIS_FILENAME);
+                genesis.save(&genesis_path)?;
+                println!(
+                    "Genesis {} written to {genesis_path:?}",
+                    encode_bytes_hex(&genesis.sha3())
+                );
+            }
+        }
+        Ok(())
+    }
+}
+
+fn check_not_built(unsigned_genesis_path: &Path) -> Result<(), anyhow::Error> {
+    if unsigned_genesis_path.exists() {
+        bail!("The genesis has already been built, and can't be changed");
+    }
+    Ok(())
+}
+
+fn read_key_pair(key_file: &Path) -> Result<KeyPair, anyhow::Error> {
+    let key = fs::read_to_string(key_file)
+        .with_context(|| format!("Unable to read key file {key_file:?}"))?;
+    KeyPair::from_str(key.trim()).with_context(|| format!("Invalid key file {key_file:?}"))
+}
+
+/// The built genesis, checked to be the genesis of the validators and objects of `builder`.
+fn load_verified_genesis(
+    builder: &Builder,
+    unsigned_genesis_path: &Path,
+) -> Result<Genesis, anyhow::Error> {
+    if !unsigned_genesis_path.exists() {
+        bail!("The genesis has not been built yet");
+    }
+    let genesis = Genesis::load(unsigned_genesis_path)?;
+    if builder.clone().build() != genesis {
+        bail!("The built genesis is not the genesis of the validators and objects of the ceremony");
+    }
+    Ok(genesis)
+}
@@ -0,0 +1,148 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+use crate::keystore::{
+    read_new_keystore_passphrase, verify_personal_message, Keystore, SuiKeystore,
+};
+use anyhow::anyhow;
+use base64ct::{Base64, Encoding};
+use clap::*;
+use sui_types::base_types::{decode_bytes_hex, SuiAddress};
+use sui_types::crypto::{GenericSignature, SignatureScheme};
+use sui_types::key_derivation::DerivationPath;
+
+#[derive(Subcommand)]
+#[clap(rename_all = "kebab-case")]
+pub enum KeyToolCommand {
+    /// Generate a new key from a new mnemonic, and print the mnemonic to restore it with.
+    Generate {
+        #[clap(long, default_value = "ed25519")]
+        key_scheme: SignatureScheme,
+        /// Defaults to m/44'/784'/0'/0'/0' for ed25519, m/54'/784'/0'/0/0 for secp256k1 and
+        /// m/74'/784'/0'/0/0 for secp256r1.
+        #[clap(long)]
+        derivation_path: Option<DerivationPath>,
+    },
+    /// Restore the key derived from a mnemonic created by this or any other wallet.
+    Import {
+        mnemonic_phrase: String,
+        #[clap(long, default_value = "ed25519")]
+        key_scheme: SignatureScheme,
+        /// Defaults to m/44'/784'/0'/0'/0' for ed25519, m/54'/784'/0'/0/0 for secp256k1 and
+        /// m/74'/784'/0'/0/0 for secp256r1.
+        #[clap(long)]
+        derivation_path: Option<DerivationPath>,
+    },
+    /// List the addresses of the keys in the keystore, with their scheme and public key.
+    List,
+    /// Encrypt the keystore with a new passphrase, read from SUI_KEYSTORE_PASSPHRASE if set.
+    /// This migrates a plaintext keystore, or changes the passphrase of an encrypted one.
+    Encrypt,
+    /// Store the keys in plaintext again, which is only fit for local test networks.
+    Decrypt,
+    /// Sign a message for an app to check that you own the address. The message is signed
+    /// after the intent of personal messages, so the signature can't be used for a transaction.
+    SignPersonalMessage {
+        #[clap(long, parse(try_from_str = decode_bytes_hex))]
+        address: SuiAddress,
+        /// The message as text, or base64 encoded with --base64.
+        message: String,
+        #[clap(long)]
+        base64: bool,
+    },
+    /// Check a signature printed by sign-personal-message.
+    VerifyPersonalMessage {
+        #[clap(long, parse(try_from_str = decode_bytes_hex))]
+        address: SuiAddress,
+        /// The message as text, or base64 encoded with --base64.
+        message: String,
+        /// The base64 encoded signature, followed by the public key.
+        #[clap(long)]
+        signature: String,
+        #[clap(long)]
+        base64: bool,
+    },
+}
+
+impl KeyToolCommand {
+    pub fn execute(&self, keystore: &mut SuiKeystore) -> Result<(), anyhow::Error> {
+        match self {
+            KeyToolCommand::Generate {
+                key_scheme,
+                derivation_path,
+            } => {
+                let (address, phrase) = keystore
+                    .generate_new_key_with_mnemonic(*key_scheme, derivation_path.clone())?;
+                println!("Created new keypair for address : {address}");
+                println!("Secret Recovery Phrase : [{phrase}]");
+            }
+            KeyToolCommand::Import {
+                mnemonic_phrase,
+                key_scheme,
+                derivation_path,
+            } => {
+                let address = keystore.import_from_mnemonic(
+                    mnemonic_phrase,
+                    *key_scheme,
+                    derivation_path.clone(),
+                )?;
+                println!("Key imported for address : {address}");
+            }
+            KeyToolCommand::List => {
+                println!(
+                    " {0: ^42} | {1: ^10} | {2: ^44}",
+                    "Sui Address", "Scheme", "Public Key (Base64)"
+                );
+                println!("{}", ["-"; 104].join(""));
+                for keypair in keystore.key_pairs() {
+                    println!(
+                        " {0: ^42} | {1: ^10} | {2: ^44}",
+                        keypair.address(),
+                        format!("{:?}", keypair.scheme()),
+                        Base64::encode_string(&keypair.public_key_bytes()),
+                    );
+                }
+            }
+            KeyToolCommand::Encrypt => {
+                keystore.encrypt(&read_new_keystore_passphrase()?)?;
+                println!("Keystore encrypted");
+            }
+            KeyToolCommand::Decrypt => {
+                keystore.decrypt()?;
+                println!("Keystore stored in plaintext");
+            }
+            KeyToolCommand::SignPersonalMessage {
+                address,
+                message,
+                base64,
+            } => {
+                let message = message_bytes(message, *base64)?;
+                let signature = keystore.sign_personal_message(address, &message)?;
+                println!(
+                    "Signature with Public Key : {}",
+                    Base64::encode_string(&signature.to_bytes())
+                );
+            }
+            KeyToolCommand::VerifyPersonalMessage {
+                address,
+                message,
+                signature,
+                base64,
+            } => {
+                let message = message_bytes(message, *base64)?;
+                let signature = Base64::decode_vec(signature).map_err(|e| anyhow!(e))?;
+                let signature = GenericSignature::from_bytes(&signature)?;
+                verify_personal_message(*address, &message, &signature)?;
+                println!("The message was signed by {address}");
+            }
+        }
+        Ok(())
+    }
+}
+
+fn message_bytes(message: &str, base64: bool) -> Result<Vec<u8>, anyhow::Error> {
+    if base64 {
+        Base64::decode_vec(message).map_err(|e| anyhow!(e))
+    } else {
+        Ok(message.as_bytes().to_vec())
+    }
+}
@@ -4,7 +4,12 @@
 
 pub mod benchmark;
 pub mod config;
-pub mod keystore;
+pub mod deployment;
+pub mod genesis_ceremony;
+pub mod keytool;
+pub mod lockfile;
 pub mod shell;
 pub mod sui_commands;
 pub mod wallet_commands;
+
+pub use sui_sdk::crypto as keystore;
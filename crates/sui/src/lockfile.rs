@@ -0,0 +1,208 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! The `Move.lock` of a Move package, which records the ID the package was published at in each
+//! env of the wallet, so that its `Move.toml` doesn't need editing for every network.
+//!
+//! A package leaves the named addresses of its own modules unassigned in its `Move.toml`, as
+//! `"_"`. Publishing assigns them 0x0, and records the ID of the published package in the
+//! lockfile under the active env. Packages depending on it are then built with its addresses
+//! assigned the ID recorded for the env they are published to.
+//!
+//! A package claiming its `UpgradeCap` as it is published has the ID of the capability recorded
+//! too, for `upgrade` to find it. Upgrading the package publishes its new version at a new ID,
+//! recorded as the latest ID of the package, which the packages depending on it are built
+//! against from then on.
+
+use anyhow::{anyhow, Context};
+use move_core_types::account_address::AccountAddress;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use sui_types::base_types::ObjectID;
+
+#[cfg(test)]
+#[path = "unit_tests/lockfile_tests.rs"]
+mod lockfile_tests;
+
+pub const MOVE_LOCK_FILENAME: &str = "Move.lock";
+
+const MOVE_MANIFEST_FILENAME: &str = "Move.toml";
+
+const UNASSIGNED_ADDRESS: &str = "_";
+
+const MOVE_LOCK_HEADER: &str = "# @generated by the Sui wallet when publishing the package.\n";
+
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq, Eq)]
+pub struct MoveLock {
+    /// The package as published in each env, by the alias of the env.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub env: BTreeMap<String, PublishedPackage>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub struct PublishedPackage {
+    pub published_id: ObjectID,
+    /// The ID of the latest version of the package, if it was upgraded since it was published.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub latest_published_id: Option<ObjectID>,
+    /// The `UpgradeCap` of the package, if it claimed one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub upgrade_cap: Option<ObjectID>,
+    /// The version of the wallet which built the latest version of the package.
+    pub compiler_version: String,
+}
+
+impl PublishedPackage {
+    /// The ID of the latest version of the package.
+    pub fn latest_id(&self) -> ObjectID {
+        self.latest_published_id.unwrap_or(self.published_id)
+    }
+}
+
+impl MoveLock {
+    /// The lockfile of the package at `package_path`, empty if it has none yet.
+    pub fn read(package_path: &Path) -> Result<Self, anyhow::Error> {
+        let path = package_path.join(MOVE_LOCK_FILENAME);
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let contents = fs::read_to_string(&path)
+            .with_context(|| format!("Unable to read {}", path.display()))?;
+        toml::from_str(&contents).with_context(|| format!("Invalid lockfile {}", path.display()))
+    }
+
+    pub fn write(&self, package_path: &Path) -> Result<(), anyhow::Error> {
+        let path = package_path.join(MOVE_LOCK_FILENAME);
+        let contents = format!("{MOVE_LOCK_HEADER}\n{}", toml::to_string(self)?);
+        fs::write(&path, contents).with_context(|| format!("Unable to write {}", path.display()))
+    }
+
+    /// Record that the package at `package_path` was published in `env` as `package_id`, with
+    /// `upgrade_cap` as its `UpgradeCap` if it claimed one.
+    pub fn record_publish(
+        package_path: &Path,
+        env: &str,
+        package_id: ObjectID,
+        upgrade_cap: Option<ObjectID>,
+    ) -> Result<(), anyhow::Error> {
+        let mut lock = Self::read(package_path)?;
+        lock.env.insert(
+            env.to_string(),
+            PublishedPackage {
+                published_id: package_id,
+                latest_published_id: None,
+                upgrade_cap,
+                compiler_version: env!("CARGO_PKG_VERSION").to_string(),
+            },
+        );
+        lock.write(package_path)
+    }
+
+    /// Record that the package at `package_path` was upgraded in `env` to its new version at
+    /// `package_id`, with `upgrade_cap`.
+    pub fn record_upgrade(
+        package_path: &Path,
+        env: &str,
+        package_id: ObjectID,
+        upgrade_cap: ObjectID,
+    ) -> Result<(), anyhow::Error> {
+        let mut lock = Self::read(package_path)?;
+        let published = lock.env.get_mut(env).ok_or_else(|| {
+            anyhow!(
+                "Package at {} is not published in env {env}",
+                package_path.display()
+            )
+        })?;
+        published.latest_published_id = Some(package_id);
+        published.upgrade_cap = Some(upgrade_cap);
+        published.compiler_version = env!("CARGO_PKG_VERSION").to_string();
+        lock.write(package_path)
+    }
+}
+
+/// The values of the named addresses left unassigned by the package at `package_path` and by
+/// the local packages it depends on, to build it for publishing in `env`. The addresses of the
+/// package itself are 0x0, and the ones of a dependency are the latest ID recorded in its
+/// lockfile for `env`. Dependencies not published in `env` have 0x0 too if `with_unpublished_dependencies`
+/// is set, as they are then published along with the package, and are left unassigned otherwise.
+pub fn named_addresses(
+    package_path: &Path,
+    env: Option<&str>,
+    with_unpublished_dependencies: bool,
+) -> Result<BTreeMap<String, AccountAddress>, anyhow::Error> {
+    let manifest = read_manifest(package_path)?;
+    let mut addresses: BTreeMap<_, _> = unassigned_addresses(&manifest)
+        .into_iter()
+        .map(|name| (name, AccountAddress::ZERO))
+        .collect();
+
+    let mut visited = BTreeSet::new();
+    let mut to_visit = local_dependencies(package_path, &manifest);
+    while let Some(path) = to_visit.pop() {
+        let path = path
+            .canonicalize()
+            .with_context(|| format!("No dependency at {}", path.display()))?;
+        if !visited.insert(path.clone()) {
+            continue;
+        }
+        let manifest = read_manifest(&path)?;
+        let published_id = match env {
+            Some(env) => MoveLock::read(&path)?
+                .env
+                .get(env)
+                .map(|published| published.latest_id()),
+            None => None,
+        };
+        let address = match published_id {
+            Some(id) => Some(AccountAddress::from(id)),
+            None if with_unpublished_dependencies => Some(AccountAddress::ZERO),
+            None => None,
+        };
+        if let Some(address) = address {
+            for name in unassigned_addresses(&manifest) {
+                addresses.entry(name).or_insert(address);
+            }
+        }
+        to_visit.extend(local_dependencies(&path, &manifest));
+    }
+    Ok(addresses)
+}
+
+fn read_manifest(package_path: &Path) -> Result<toml::Value, anyhow::Error> {
+    let path = package_path.join(MOVE_MANIFEST_FILENAME);
+    let contents =
+        fs::read_to_string(&path).with_context(|| format!("Unable to read {}", path.display()))?;
+    toml::from_str(&contents).map_err(|err| anyhow!("Invalid manifest {}: {err}", path.display()))
+}
+
+fn unassigned_addresses(manifest: &toml::Value) -> Vec<String> {
+    manifest
+        .get("addresses")
+        .and_then(|addresses| addresses.as_table())
+        .map(|addresses| {
+            addresses
+                .iter()
+                .filter(|(_, value)| value.as_str() == Some(UNASSIGNED_ADDRESS))
+                .map(|(name, _)| name.clone())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// The paths of the packages the manifest of the package at `package_path` depends on locally.
+fn local_dependencies(package_path: &Path, manifest: &toml::Value) -> Vec<PathBuf> {
+    manifest
+        .get("dependencies")
+        .and_then(|dependencies| dependencies.as_table())
+        .map(|dependencies| {
+            dependencies
+                .values()
+                .filter_map(|dependency| dependency.get("local")?.as_str())
+                .map(|local| package_path.join(local))
+                .collect()
+        })
+        .unwrap_or_default()
+}
@@ -2,33 +2,49 @@
 // SPDX-License-Identifier: Apache-2.0
 use crate::{
     config::{GatewayConfig, GatewayType, WalletConfig},
+    deployment::NodeDeployment,
+    genesis_ceremony::Ceremony,
     keystore::{Keystore, KeystoreType, SuiKeystore},
+    keytool::KeyToolCommand,
 };
 use anyhow::{anyhow, bail};
 use base64ct::{Base64, Encoding};
 use clap::*;
 use std::fs;
 use std::num::NonZeroUsize;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use sui_config::{builder::ConfigBuilder, NetworkConfig};
 use sui_config::{genesis_config::GenesisConfig, SUI_GENESIS_FILENAME};
 use sui_config::{
     sui_config_dir, Config, PersistedConfig, SUI_FULLNODE_CONFIG, SUI_GATEWAY_CONFIG,
     SUI_NETWORK_CONFIG, SUI_WALLET_CONFIG,
 };
+use sui_node::SuiNode;
 use sui_swarm::memory::Swarm;
 use sui_types::base_types::decode_bytes_hex;
 use sui_types::base_types::SuiAddress;
+use sui_types::crypto::GenericSignature;
+use sui_types::intent::{Intent, IntentScope};
 use tracing::info;
 
 #[derive(Parser)]
 #[clap(rename_all = "kebab-case")]
 pub enum SuiCommand {
-    /// Start sui network.
+    /// Start sui network, with a fullnode serving JSON-RPC.
     #[clap(name = "start")]
     Start {
         #[clap(long)]
         config: Option<PathBuf>,
+        /// Start a new local network from a fresh genesis, whose configs are written to a
+        /// temporary directory and removed when the network stops. The existing configs are
+        /// left untouched.
+        #[clap(long)]
+        force_regenesis: bool,
+        /// Also start a faucet serving the gas of an account of the network.
+        #[clap(long)]
+        with_faucet: bool,
+        #[clap(long, default_value = "9123")]
+        faucet_port: u16,
     },
     #[clap(name = "network")]
     Network {
@@ -46,42 +62,122 @@ pub enum SuiCommand {
             help = "Build a genesis config, write it to the specified path, and exit"
         )]
         write_config: Option<PathBuf>,
+        /// Build the genesis of a network whose nodes run on separate hosts, such as a
+        /// docker-compose or Kubernetes deployment, write the configs of its nodes to the
+        /// specified directory, and exit.
+        #[clap(long)]
+        write_node_configs: Option<PathBuf>,
+        /// The hosts of the validators of the deployment, `validator-0`, `validator-1`, and so
+        /// on by default.
+        #[clap(long, multiple_values = true)]
+        validator_hosts: Vec<String>,
+        /// The first port of the nodes of the deployment, each node listening on the next ten
+        /// ports.
+        #[clap(long, default_value = "8080")]
+        base_port: u16,
+        /// The directory of the configs and databases on the hosts of the deployment, where the
+        /// configs are to be mounted.
+        #[clap(long, default_value = "/opt/sui")]
+        node_root: PathBuf,
         #[clap(long)]
         working_dir: Option<PathBuf>,
         #[clap(short, long, help = "Forces overwriting existing configuration")]
         force: bool,
     },
+    /// Build the genesis of a network with the operators of its validators, each on their own
+    /// machine.
+    #[clap(name = "genesis-ceremony")]
+    GenesisCeremony(Ceremony),
+    /// Sign transaction bytes, as printed by `serialize-tx`, after the intent of transactions.
     #[clap(name = "signtool")]
     SignTool {
         #[clap(long)]
         keystore_path: Option<PathBuf>,
         #[clap(long, parse(try_from_str = decode_bytes_hex))]
         address: SuiAddress,
+        /// The base64 encoded `TransactionData`.
         #[clap(long)]
         data: String,
     },
+    /// Generate, restore and list the keys of the keystore.
+    #[clap(name = "keytool")]
+    KeyTool {
+        #[clap(long)]
+        keystore_path: Option<PathBuf>,
+        #[clap(subcommand)]
+        cmd: KeyToolCommand,
+    },
 }
 
 impl SuiCommand {
     pub async fn execute(&self) -> Result<(), anyhow::Error> {
         match self {
-            SuiCommand::Start { config } => {
+            SuiCommand::Start {
+                config,
+                force_regenesis,
+                with_faucet,
+                faucet_port,
+            } => {
+                // The directory of a fresh genesis, removed when the network stops.
+                let mut regenesis_dir = None;
+                let (config_dir, network_config_path) = if *force_regenesis {
+                    let dir = tempfile::tempdir()?;
+                    write_genesis(dir.path(), GenesisConfig::for_local_testing())?;
+                    let config_dir = dir.path().to_path_buf();
+                    regenesis_dir = Some(dir);
+                    (config_dir.clone(), config_dir.join(SUI_NETWORK_CONFIG))
+                } else {
+                    let path = config
+                        .clone()
+                        .unwrap_or(sui_config_dir()?.join(SUI_NETWORK_CONFIG));
+                    let config_dir = match path.parent() {
+                        Some(dir) if !dir.as_os_str().is_empty() => dir.to_path_buf(),
+                        _ => PathBuf::from("."),
+                    };
+                    (config_dir, path)
+                };
+
                 // Load the config of the Sui authority.
-                let network_config_path = config
-                    .clone()
-                    .unwrap_or(sui_config_dir()?.join(SUI_NETWORK_CONFIG));
                 let network_config: NetworkConfig = PersistedConfig::read(&network_config_path)
                     .map_err(|err| {
                         err.context(format!(
-                            "Cannot open Sui network config file at {:?}",
+                            "Cannot open Sui network config file at {:?}, run `sui genesis` or use the --force-regenesis option to create one",
                             network_config_path
                         ))
                     })?;
 
+                let fullnode_config_path = config_dir.join(SUI_FULLNODE_CONFIG);
+                let fullnode_config = if fullnode_config_path.exists() {
+                    PersistedConfig::read(&fullnode_config_path)?
+                } else {
+                    let mut fullnode_config = network_config.generate_fullnode_config();
+                    fullnode_config.json_rpc_address = sui_config::node::default_json_rpc_address();
+                    fullnode_config
+                };
+
                 let mut swarm =
-                    Swarm::builder().from_network_config(sui_config_dir()?, network_config);
+                    Swarm::builder().from_network_config(config_dir.clone(), network_config);
                 swarm.launch().await?;
 
+                let _fullnode = SuiNode::start(&fullnode_config).await?;
+                info!(
+                    "Fullnode JSON-RPC listening on http://{}",
+                    fullnode_config.json_rpc_address
+                );
+
+                let _faucet = if *with_faucet {
+                    Some(start_faucet(&config_dir, *faucet_port)?)
+                } else {
+                    None
+                };
+
+                if regenesis_dir.is_some() {
+                    info!(
+                        "Wallet config file of the new network is stored in {:?}.",
+                        config_dir.join(SUI_WALLET_CONFIG)
+                    );
+                }
+
                 let mut interval = tokio::time::interval(std::time::Duration::from_secs(5));
                 loop {
                     for node in swarm.validators_mut() {
@@ -121,7 +217,35 @@ impl SuiCommand {
                 force,
                 from_config,
                 write_config,
+                write_node_configs,
+                validator_hosts,
+                base_port,
+                node_root,
             } => {
+                if let Some(dir) = write_node_configs {
+                    if dir.exists() && dir.read_dir()?.next().is_some() && !*force {
+                        bail!("Cannot write node configs to non-empty directory {dir:?}, please use --force/-f option to overwrite them");
+                    }
+                    let genesis_conf = match from_config {
+                        Some(q) => PersistedConfig::read(q)?,
+                        None => GenesisConfig::for_local_testing(),
+                    };
+                    let deployment = if validator_hosts.is_empty() {
+                        NodeDeployment::with_default_hosts(
+                            &genesis_conf,
+                            *base_port,
+                            node_root.clone(),
+                        )
+                    } else {
+                        NodeDeployment {
+                            validator_hosts: validator_hosts.clone(),
+                            base_port: *base_port,
+                            node_root: node_root.clone(),
+                        }
+                    };
+                    return deployment.write_node_configs(genesis_conf, dir);
+                }
+
                 let sui_config_dir = &match working_dir {
                     // if a directory is specified, it must exist (it
                     // will not be created)
@@ -167,15 +291,7 @@ impl SuiCommand {
                     }
                 }
 
-                let network_path = sui_config_dir.join(SUI_NETWORK_CONFIG);
-                let genesis_path = sui_config_dir.join(SUI_GENESIS_FILENAME);
-                let wallet_path = sui_config_dir.join(SUI_WALLET_CONFIG);
-                let gateway_path = sui_config_dir.join(SUI_GATEWAY_CONFIG);
-                let keystore_path = sui_config_dir.join("wallet.key");
-                let db_folder_path = sui_config_dir.join("client_db");
-                let gateway_db_folder_path = sui_config_dir.join("gateway_client_db");
-
-                let mut genesis_conf = match from_config {
+                let genesis_conf = match from_config {
                     Some(q) => PersistedConfig::read(q)?,
                     None => GenesisConfig::for_local_testing(),
                 };
@@ -186,84 +302,9 @@ impl SuiCommand {
                     return Ok(());
                 }
 
-                let validator_info = genesis_conf.validator_genesis_info.take();
-                let mut network_config = if let Some(validators) = validator_info {
-                    ConfigBuilder::new(sui_config_dir)
-                        .initial_accounts_config(genesis_conf)
-                        .build_with_validators(validators)
-                } else {
-                    ConfigBuilder::new(sui_config_dir)
-                        .committee_size(NonZeroUsize::new(genesis_conf.committee_size).unwrap())
-                        .initial_accounts_config(genesis_conf)
-                        .build()
-                };
-
-                let mut accounts = Vec::new();
-                let mut keystore = SuiKeystore::default();
-
-                for key in &network_config.account_keys {
-                    let address = SuiAddress::from(key.public_key_bytes());
-                    accounts.push(address);
-                    keystore.add_key(address, key.copy())?;
-                }
-
-                network_config.genesis.save(&genesis_path)?;
-                for validator in &mut network_config.validator_configs {
-                    validator.genesis = sui_config::node::Genesis::new_from_file(&genesis_path);
-                }
-
-                info!("Network genesis completed.");
-                network_config.save(&network_path)?;
-                info!("Network config file is stored in {:?}.", network_path);
-
-                keystore.set_path(&keystore_path);
-                keystore.save()?;
-                info!("Wallet keystore is stored in {:?}.", keystore_path);
-
-                // Use the first address if any
-                let active_address = accounts.get(0).copied();
-
-                let validator_set = network_config.validator_set();
-
-                GatewayConfig {
-                    db_folder_path: gateway_db_folder_path,
-                    validator_set: validator_set.to_owned(),
-                    ..Default::default()
-                }
-                .save(&gateway_path)?;
-                info!("Gateway config file is stored in {:?}.", gateway_path);
-
-                let wallet_gateway_config = GatewayConfig {
-                    db_folder_path,
-                    validator_set: validator_set.to_owned(),
-                    ..Default::default()
-                };
-
-                let wallet_config = WalletConfig {
-                    accounts,
-                    keystore: KeystoreType::File(keystore_path),
-                    gateway: GatewayType::Embedded(wallet_gateway_config),
-                    active_address,
-                };
-
-                wallet_config.save(&wallet_path)?;
-                info!("Wallet config file is stored in {:?}.", wallet_path);
-
-                let mut fullnode_config = network_config.generate_fullnode_config();
-                fullnode_config.json_rpc_address = sui_config::node::default_json_rpc_address();
-                fullnode_config.save(sui_config_dir.join(SUI_FULLNODE_CONFIG))?;
-
-                for (i, validator) in network_config
-                    .into_validator_configs()
-                    .into_iter()
-                    .enumerate()
-                {
-                    let path = sui_config_dir.join(format!("validator-config-{}.yaml", i));
-                    validator.save(path)?;
-                }
-
-                Ok(())
+                write_genesis(sui_config_dir, genesis_conf)
             }
+            SuiCommand::GenesisCeremony(ceremony) => ceremony.run(),
             SuiCommand::SignTool {
                 keystore_path,
                 address,
@@ -276,20 +317,180 @@ impl SuiCommand {
                 info!("Data to sign : {}", data);
                 info!("Address : {}", address);
                 let message = Base64::decode_vec(data).map_err(|e| anyhow!(e))?;
+                let message = Intent::new(IntentScope::TransactionData).message_bytes(&message);
                 let signature = keystore.sign(address, &message)?;
-                // Separate pub key and signature string, signature and pub key are concatenated with an '@' symbol.
-                let signature_string = format!("{:?}", signature);
-                let sig_split = signature_string.split('@').collect::<Vec<_>>();
-                let signature = sig_split
-                    .first()
-                    .ok_or_else(|| anyhow!("Error creating signature."))?;
-                let pub_key = sig_split
-                    .last()
-                    .ok_or_else(|| anyhow!("Error creating signature."))?;
-                info!("Public Key Base64: {}", pub_key);
-                info!("Signature : {}", signature);
+                // In the encoding expected by `execute-signed-tx`, and for ECDSA keys, prefixed with
+                // the flag of their scheme.
+                info!(
+                    "Signature with Public Key : {}",
+                    Base64::encode_string(&signature.to_bytes())
+                );
+                // ECDSA public keys are also prefixed with the flag of their scheme, as expected
+                // by `multisig-address`.
+                let (signature_bytes, pub_key) = match &signature {
+                    GenericSignature::Signature(s) => {
+                        (s.signature_bytes(), s.public_key_bytes().to_vec())
+                    }
+                    GenericSignature::Secp256k1(s) => (
+                        s.signature_bytes(),
+                        [&[signature.scheme().flag()][..], s.public_key_bytes()].concat(),
+                    ),
+                    GenericSignature::Secp256r1(s) => (
+                        s.signature_bytes(),
+                        [&[signature.scheme().flag()][..], s.public_key_bytes()].concat(),
+                    ),
+                    GenericSignature::MultiSig(_) | GenericSignature::ZkLogin(_) => {
+                        bail!("Keystores only hold single keys")
+                    }
+                };
+                info!("Signature Scheme : {:?}", signature.scheme());
+                info!("Public Key Base64: {}", Base64::encode_string(&pub_key));
+                info!("Signature : {}", Base64::encode_string(signature_bytes));
                 Ok(())
             }
+            SuiCommand::KeyTool { keystore_path, cmd } => {
+                let keystore_path = keystore_path
+                    .clone()
+                    .unwrap_or(sui_config_dir()?.join("wallet.key"));
+                let mut keystore = SuiKeystore::load_or_create(&keystore_path)?;
+                cmd.execute(&mut keystore)
+            }
         }
     }
 }
+
+/// The config of the wallet of the faucet started along the network, in the config directory.
+const FAUCET_WALLET_CONFIG: &str = "faucet-wallet.yaml";
+
+/// Start the `sui-faucet` binary installed next to this one, paying gas from the last account of
+/// the wallet in `config_dir`. The faucet keeps a wallet config and a client database of its
+/// own, so that it never contends with the wallet of the user for either. The faucet is killed
+/// when the returned handle is dropped.
+fn start_faucet(config_dir: &Path, port: u16) -> Result<tokio::process::Child, anyhow::Error> {
+    let mut wallet_config: WalletConfig =
+        PersistedConfig::read(&config_dir.join(SUI_WALLET_CONFIG))?;
+    wallet_config.active_address = Some(
+        *wallet_config
+            .accounts
+            .last()
+            .ok_or_else(|| anyhow!("The wallet has no account to fund the faucet"))?,
+    );
+    if let GatewayType::Embedded(gateway) = &mut wallet_config.gateway {
+        gateway.db_folder_path = config_dir.join("faucet_client_db");
+    }
+    let faucet_wallet_path = config_dir.join(FAUCET_WALLET_CONFIG);
+    wallet_config.save(&faucet_wallet_path)?;
+
+    let faucet_binary = std::env::current_exe()?.with_file_name("sui-faucet");
+    let faucet_binary = if faucet_binary.exists() {
+        faucet_binary
+    } else {
+        PathBuf::from("sui-faucet")
+    };
+    let faucet = tokio::process::Command::new(&faucet_binary)
+        .arg("--wallet-config")
+        .arg(&faucet_wallet_path)
+        .arg("--port")
+        .arg(port.to_string())
+        .kill_on_drop(true)
+        .spawn()
+        .map_err(|err| anyhow!("Cannot start the faucet {faucet_binary:?}: {err}"))?;
+    info!("Faucet listening on http://127.0.0.1:{port}");
+    Ok(faucet)
+}
+
+/// Build the genesis of a local network from `genesis_conf`, and write the configs of its nodes,
+/// gateway and wallet to `sui_config_dir`.
+fn write_genesis(
+    sui_config_dir: &Path,
+    mut genesis_conf: GenesisConfig,
+) -> Result<(), anyhow::Error> {
+    let network_path = sui_config_dir.join(SUI_NETWORK_CONFIG);
+    let genesis_path = sui_config_dir.join(SUI_GENESIS_FILENAME);
+    let wallet_path = sui_config_dir.join(SUI_WALLET_CONFIG);
+    let gateway_path = sui_config_dir.join(SUI_GATEWAY_CONFIG);
+    let keystore_path = sui_config_dir.join("wallet.key");
+    let db_folder_path = sui_config_dir.join("client_db");
+    let gateway_db_folder_path = sui_config_dir.join("gateway_client_db");
+
+    let validator_info = genesis_conf.validator_genesis_info.take();
+    let mut network_config = if let Some(validators) = validator_info {
+        ConfigBuilder::new(sui_config_dir)
+            .initial_accounts_config(genesis_conf)
+            .build_with_validators(validators)
+    } else {
+        ConfigBuilder::new(sui_config_dir)
+            .committee_size(NonZeroUsize::new(genesis_conf.committee_size).unwrap())
+            .initial_accounts_config(genesis_conf)
+            .build()
+    };
+
+    let mut accounts = Vec::new();
+    let mut keystore = SuiKeystore::default();
+
+    for key in &network_config.account_keys {
+        let address = SuiAddress::from(key.public_key_bytes());
+        accounts.push(address);
+        keystore.add_key(address, key.copy())?;
+    }
+
+    network_config.genesis.save(&genesis_path)?;
+    for validator in &mut network_config.validator_configs {
+        validator.genesis = sui_config::node::Genesis::new_from_file(&genesis_path);
+    }
+
+    info!("Network genesis completed.");
+    network_config.save(&network_path)?;
+    info!("Network config file is stored in {:?}.", network_path);
+
+    keystore.set_path(&keystore_path);
+    keystore.save()?;
+    info!("Wallet keystore is stored in {:?}.", keystore_path);
+
+    // Use the first address if any
+    let active_address = accounts.get(0).copied();
+
+    let validator_set = network_config.validator_set();
+
+    GatewayConfig {
+        db_folder_path: gateway_db_folder_path,
+        validator_set: validator_set.to_owned(),
+        ..Default::default()
+    }
+    .save(&gateway_path)?;
+    info!("Gateway config file is stored in {:?}.", gateway_path);
+
+    let wallet_gateway_config = GatewayConfig {
+        db_folder_path,
+        validator_set: validator_set.to_owned(),
+        ..Default::default()
+    };
+
+    let wallet_config = WalletConfig {
+        accounts,
+        aliases: Default::default(),
+        keystore: KeystoreType::File(keystore_path),
+        gateway: GatewayType::Embedded(wallet_gateway_config),
+        envs: vec![],
+        active_env: None,
+        active_address,
+    };
+
+    wallet_config.save(&wallet_path)?;
+    info!("Wallet config file is stored in {:?}.", wallet_path);
+
+    let mut fullnode_config = network_config.generate_fullnode_config();
+    fullnode_config.json_rpc_address = sui_config::node::default_json_rpc_address();
+    fullnode_config.save(sui_config_dir.join(SUI_FULLNODE_CONFIG))?;
+
+    for (i, validator) in network_config
+        .into_validator_configs()
+        .into_iter()
+        .enumerate()
+    {
+        let path = sui_config_dir.join(format!("validator-config-{}.yaml", i));
+        validator.save(path)?;
+    }
+
+    Ok(())
+}
@@ -8,22 +8,28 @@ use serde_json::{json, Value};
 
 use sui::wallet_commands::SwitchResponse;
 use sui::{
-    config::{GatewayConfig, GatewayType, WalletConfig},
-    keystore::KeystoreType,
+    config::{AddressOrAlias, GatewayConfig, GatewayType, WalletConfig},
+    keystore::{Keystore, KeystoreType, SuiKeystore},
+    keytool::KeyToolCommand,
     sui_commands::SuiCommand,
-    wallet_commands::{WalletCommandResult, WalletCommands, WalletContext},
+    wallet_commands::{TransactionCommand, WalletCommandResult, WalletCommands, WalletContext},
 };
 use sui_config::genesis_config::{AccountConfig, GenesisConfig, ObjectConfig};
 use sui_config::{
     Config, NetworkConfig, PersistedConfig, SUI_FULLNODE_CONFIG, SUI_GATEWAY_CONFIG,
     SUI_GENESIS_FILENAME, SUI_NETWORK_CONFIG, SUI_WALLET_CONFIG,
 };
-use sui_core::gateway_types::{GetObjectDataResponse, SuiParsedObject, SuiTransactionEffects};
+use sui_core::gateway_types::{
+    GetObjectDataResponse, SuiData, SuiObject, SuiParsedObject, SuiTransactionEffects,
+};
 use sui_json::SuiJsonValue;
 use sui_types::{
     base_types::{ObjectID, SuiAddress},
-    crypto::get_key_pair,
+    crypto::{get_key_pair, GenericSignature, SignatureScheme},
     gas_coin::GasCoin,
+    intent::{Intent, IntentScope},
+    object::Owner,
+    sui_serde::{Base64, Encoding},
 };
 
 use test_utils::network::{setup_network_and_wallet, start_test_network};
@@ -39,6 +45,9 @@ async fn test_genesis() -> Result<(), anyhow::Error> {
     // Start network without authorities
     let start = SuiCommand::Start {
         config: Some(config),
+        force_regenesis: false,
+        with_faucet: false,
+        faucet_port: 9123,
     }
     .execute()
     .await;
@@ -47,6 +56,10 @@ async fn test_genesis() -> Result<(), anyhow::Error> {
     SuiCommand::Genesis {
         working_dir: Some(working_dir.to_path_buf()),
         write_config: None,
+        write_node_configs: None,
+        validator_hosts: vec![],
+        base_port: 8080,
+        node_root: PathBuf::from("/opt/sui"),
         force: false,
         from_config: None,
     }
@@ -88,6 +101,10 @@ async fn test_genesis() -> Result<(), anyhow::Error> {
     let result = SuiCommand::Genesis {
         working_dir: Some(working_dir.to_path_buf()),
         write_config: None,
+        write_node_configs: None,
+        validator_hosts: vec![],
+        base_port: 8080,
+        node_root: PathBuf::from("/opt/sui"),
         force: false,
         from_config: None,
     }
@@ -106,11 +123,14 @@ async fn test_addresses_command() -> Result<(), anyhow::Error> {
 
     let wallet_config = WalletConfig {
         accounts: vec![],
+        aliases: Default::default(),
         keystore: KeystoreType::File(working_dir.join("wallet.key")),
         gateway: GatewayType::Embedded(GatewayConfig {
             db_folder_path: working_dir.join("client_db"),
             ..Default::default()
         }),
+        envs: vec![],
+        active_env: None,
         active_address: None,
     };
     let wallet_conf_path = working_dir.join(SUI_WALLET_CONFIG);
@@ -128,10 +148,17 @@ async fn test_addresses_command() -> Result<(), anyhow::Error> {
     let mut context = WalletContext::new(&wallet_conf_path)?;
 
     // Print all addresses
-    WalletCommands::Addresses
-        .execute(&mut context)
-        .await?
-        .print(true);
+    let resp = WalletCommands::Addresses.execute(&mut context).await?;
+    resp.print(true);
+
+    // The JSON output has the addresses, in the order they were added.
+    let json = resp.to_json()?;
+    let addresses = json["addresses"].as_array().unwrap();
+    assert_eq!(addresses.len(), 3);
+    assert_eq!(
+        addresses[0],
+        json!(context.config.accounts.first().cloned().unwrap())
+    );
 
     Ok(())
 }
@@ -142,7 +169,7 @@ async fn test_objects_command() -> Result<(), anyhow::Error> {
 
     // Print objects owned by `address`
     WalletCommands::Objects {
-        address: Some(address),
+        address: Some(address.into()),
     }
     .execute(&mut context)
     .await?
@@ -218,7 +245,7 @@ async fn test_custom_genesis() -> Result<(), anyhow::Error> {
 
     // Print objects owned by `address`
     WalletCommands::Objects {
-        address: Some(address),
+        address: Some(address.into()),
     }
     .execute(&mut context)
     .await?
@@ -292,7 +319,7 @@ async fn test_gas_command() -> Result<(), anyhow::Error> {
     let object_to_send = object_refs.get(1).unwrap().object_id;
 
     WalletCommands::Gas {
-        address: Some(address),
+        address: Some(address.into()),
     }
     .execute(&mut context)
     .await?
@@ -302,7 +329,7 @@ async fn test_gas_command() -> Result<(), anyhow::Error> {
 
     // Send an object
     WalletCommands::Transfer {
-        to: recipient,
+        to: recipient.into(),
         coin_object_id: object_to_send,
         gas: Some(object_id),
         gas_budget: 50000,
@@ -312,7 +339,7 @@ async fn test_gas_command() -> Result<(), anyhow::Error> {
 
     // Fetch gas again
     WalletCommands::Gas {
-        address: Some(address),
+        address: Some(address.into()),
     }
     .execute(&mut context)
     .await?
@@ -347,7 +374,7 @@ async fn get_move_objects(
 
     // Fetch objects owned by `address`
     let objects_result = WalletCommands::Objects {
-        address: Some(address),
+        address: Some(address.into()),
     }
     .execute(context)
     .await?;
@@ -401,7 +428,7 @@ async fn test_move_call_args_linter_command() -> Result<(), anyhow::Error> {
 
     // Print objects owned by `address1`
     WalletCommands::Objects {
-        address: Some(address1),
+        address: Some(address1.into()),
     }
     .execute(&mut context)
     .await?
@@ -429,6 +456,34 @@ async fn test_move_call_args_linter_command() -> Result<(), anyhow::Error> {
         args.push(SuiJsonValue::new(a.clone()).unwrap());
     }
 
+    // A dry run gives the effects of the call without creating the object.
+    let resp = WalletCommands::Call {
+        package: ObjectID::from_hex_literal("0x2").unwrap(),
+        module: "ObjectBasics".to_string(),
+        function: "create".to_string(),
+        type_args: vec![],
+        args: args.clone(),
+        gas: Some(gas),
+        gas_budget: 1000,
+        dry_run: true,
+    }
+    .execute(&mut context)
+    .await?;
+    let effects = if let WalletCommandResult::DryRun(effects) = resp {
+        effects
+    } else {
+        unreachable!("Invalid response");
+    };
+    assert!(effects.status.is_ok());
+    assert_eq!(effects.created.len(), 1);
+    assert!(matches!(
+        context
+            .gateway
+            .get_object(effects.created[0].reference.object_id)
+            .await?,
+        GetObjectDataResponse::NotExists(..)
+    ));
+
     // Test case with no gas specified
     let resp = WalletCommands::Call {
         package: ObjectID::from_hex_literal("0x2").unwrap(),
@@ -438,6 +493,7 @@ async fn test_move_call_args_linter_command() -> Result<(), anyhow::Error> {
         args,
         gas: None,
         gas_budget: 1000,
+        dry_run: false,
     }
     .execute(&mut context)
     .await?;
@@ -478,6 +534,7 @@ async fn test_move_call_args_linter_command() -> Result<(), anyhow::Error> {
         args: args.to_vec(),
         gas: Some(gas),
         gas_budget: 1000,
+        dry_run: false,
     }
     .execute(&mut context)
     .await;
@@ -506,6 +563,7 @@ async fn test_move_call_args_linter_command() -> Result<(), anyhow::Error> {
         args: args.to_vec(),
         gas: Some(gas),
         gas_budget: 1000,
+        dry_run: false,
     }
     .execute(&mut context)
     .await;
@@ -533,6 +591,7 @@ async fn test_move_call_args_linter_command() -> Result<(), anyhow::Error> {
         args: args.to_vec(),
         gas: Some(gas),
         gas_budget: 1000,
+        dry_run: false,
     }
     .execute(&mut context)
     .await?;
@@ -561,6 +620,9 @@ async fn test_package_publish_command() -> Result<(), anyhow::Error> {
         path,
         gas: Some(gas_obj_id),
         gas_budget: 1000,
+        dry_run: false,
+        with_unpublished_dependencies: false,
+        skip_dependency_verification: false,
     }
     .execute(&mut context)
     .await?;
@@ -601,6 +663,51 @@ async fn test_package_publish_command() -> Result<(), anyhow::Error> {
     Ok(())
 }
 
+#[tokio::test]
+async fn test_package_publish_with_unpublished_dependencies() -> Result<(), anyhow::Error> {
+    let (_network, mut context, _) = setup_network_and_wallet().await?;
+    let publish = |package: &str, with_unpublished_dependencies| WalletCommands::Publish {
+        path: format!("{TEST_DATA_DIR}{package}"),
+        gas: None,
+        gas_budget: 1000,
+        dry_run: false,
+        with_unpublished_dependencies,
+        skip_dependency_verification: false,
+    };
+
+    // A dependency which is not on-chain is only published along with the package if asked to.
+    let err = publish("depends_on_unpublished", false)
+        .execute(&mut context)
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("--with-unpublished-dependencies"));
+    let resp = publish("depends_on_unpublished", true)
+        .execute(&mut context)
+        .await?;
+    let package = if let WalletCommandResult::Publish(response) = resp {
+        response.package
+    } else {
+        unreachable!("Invalid response");
+    };
+    let modules = match context.gateway.get_object(package.object_id).await? {
+        GetObjectDataResponse::Exists(SuiObject {
+            data: SuiData::Package(package),
+            ..
+        }) => package.disassembled().keys().cloned().collect::<Vec<_>>(),
+        _ => panic!("The package is not published"),
+    };
+    assert_eq!(modules, vec!["Counter".to_string(), "Start".to_string()]);
+
+    // Dependencies with an address are checked to be on-chain.
+    let err = publish("depends_on_missing", false)
+        .execute(&mut context)
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("is not on-chain"));
+
+    Ok(())
+}
+
 #[allow(clippy::assertions_on_constants)]
 #[tokio::test]
 async fn test_native_transfer() -> Result<(), anyhow::Error> {
@@ -618,7 +725,7 @@ async fn test_native_transfer() -> Result<(), anyhow::Error> {
 
     let resp = WalletCommands::Transfer {
         gas: Some(gas_obj_id),
-        to: recipient,
+        to: recipient.into(),
         coin_object_id: obj_id,
         gas_budget: 50000,
     }
@@ -706,7 +813,7 @@ async fn test_native_transfer() -> Result<(), anyhow::Error> {
 
     let resp = WalletCommands::Transfer {
         gas: None,
-        to: recipient,
+        to: recipient.into(),
         coin_object_id: obj_id,
         gas_budget: 50000,
     }
@@ -785,8 +892,9 @@ async fn test_switch_command() -> Result<(), anyhow::Error> {
     // Switch the address
     let addr2 = context.config.accounts.get(1).cloned().unwrap();
     let resp = WalletCommands::Switch {
-        address: Some(addr2),
+        address: Some(addr2.into()),
         gateway: None,
+        env: None,
     }
     .execute(&mut context)
     .await?;
@@ -798,7 +906,8 @@ async fn test_switch_command() -> Result<(), anyhow::Error> {
             "{}",
             WalletCommandResult::Switch(SwitchResponse {
                 address: Some(addr2),
-                gateway: None
+                gateway: None,
+                env: None,
             })
         )
     );
@@ -808,7 +917,12 @@ async fn test_switch_command() -> Result<(), anyhow::Error> {
     context.config.active_address = None;
 
     // Create a new address
-    let os = WalletCommands::NewAddress {}.execute(&mut context).await?;
+    let os = WalletCommands::NewAddress {
+        key_scheme: SignatureScheme::ED25519,
+        alias: None,
+    }
+    .execute(&mut context)
+    .await?;
     let new_addr = if let WalletCommandResult::NewAddress(a) = os {
         a
     } else {
@@ -818,8 +932,9 @@ async fn test_switch_command() -> Result<(), anyhow::Error> {
     // Check that we can switch to this address
     // Switch the address
     let resp = WalletCommands::Switch {
-        address: Some(new_addr),
+        address: Some(new_addr.into()),
         gateway: None,
+        env: None,
     }
     .execute(&mut context)
     .await?;
@@ -830,7 +945,8 @@ async fn test_switch_command() -> Result<(), anyhow::Error> {
             "{}",
             WalletCommandResult::Switch(SwitchResponse {
                 address: Some(new_addr),
-                gateway: None
+                gateway: None,
+                env: None,
             })
         )
     );
@@ -862,6 +978,7 @@ async fn test_active_address_command() -> Result<(), anyhow::Error> {
         .execute(&mut context)
         .await?;
 
+    assert_eq!(os.to_json()?, json!({ "activeAddress": addr1 }));
     let a = if let WalletCommandResult::ActiveAddress(Some(v)) = os {
         v
     } else {
@@ -871,8 +988,9 @@ async fn test_active_address_command() -> Result<(), anyhow::Error> {
 
     let addr2 = context.config.accounts.get(1).cloned().unwrap();
     let resp = WalletCommands::Switch {
-        address: Some(addr2),
+        address: Some(addr2.into()),
         gateway: None,
+        env: None,
     }
     .execute(&mut context)
     .await?;
@@ -882,13 +1000,141 @@ async fn test_active_address_command() -> Result<(), anyhow::Error> {
             "{}",
             WalletCommandResult::Switch(SwitchResponse {
                 address: Some(addr2),
-                gateway: None
+                gateway: None,
+                env: None,
             })
         )
     );
     Ok(())
 }
 
+#[tokio::test]
+async fn test_alias_and_env_commands() -> Result<(), anyhow::Error> {
+    let temp_dir = tempfile::tempdir()?;
+    let working_dir = temp_dir.path();
+
+    let (addr1, _) = get_key_pair();
+    let (addr2, _) = get_key_pair();
+    let wallet_conf_path = working_dir.join(SUI_WALLET_CONFIG);
+    WalletConfig {
+        accounts: vec![addr1, addr2],
+        aliases: Default::default(),
+        keystore: KeystoreType::File(working_dir.join("wallet.key")),
+        gateway: GatewayType::Embedded(GatewayConfig {
+            db_folder_path: working_dir.join("client_db"),
+            ..Default::default()
+        }),
+        envs: vec![],
+        active_env: None,
+        active_address: Some(addr1),
+    }
+    .save(&wallet_conf_path)?;
+    let mut context = WalletContext::new(&wallet_conf_path)?;
+
+    // Name the second address, and switch to it by its alias.
+    WalletCommands::Alias {
+        address: addr2.into(),
+        alias: "alice".to_string(),
+    }
+    .execute(&mut context)
+    .await?;
+    WalletCommands::Switch {
+        address: Some("alice".parse()?),
+        gateway: None,
+        env: None,
+    }
+    .execute(&mut context)
+    .await?;
+    assert_eq!(addr2, context.active_address()?);
+
+    // Aliases can't be addresses, and only managed addresses can be named.
+    assert!(WalletCommands::Alias {
+        address: addr1.into(),
+        alias: addr2.to_string(),
+    }
+    .execute(&mut context)
+    .await
+    .is_err());
+    assert!(WalletCommands::Alias {
+        address: get_key_pair().0.into(),
+        alias: "bob".to_string(),
+    }
+    .execute(&mut context)
+    .await
+    .is_err());
+    assert!(WalletCommands::Switch {
+        address: Some("bob".parse()?),
+        gateway: None,
+        env: None,
+    }
+    .execute(&mut context)
+    .await
+    .is_err());
+
+    // Names of the name service can't be aliases, and the embedded gateway can't resolve them.
+    assert!(WalletCommands::Alias {
+        address: addr1.into(),
+        alias: "carol.sui".to_string(),
+    }
+    .execute(&mut context)
+    .await
+    .is_err());
+    assert_eq!(
+        AddressOrAlias::Name("carol.sui".to_string()),
+        "carol.sui".parse()?
+    );
+    assert!(context
+        .resolve_address(&"carol.sui".parse()?)
+        .await
+        .is_err());
+
+    // Add a network and switch to it.
+    WalletCommands::NewEnv {
+        alias: "localnet".to_string(),
+        rpc: "http://127.0.0.1:5001".to_string(),
+    }
+    .execute(&mut context)
+    .await?;
+    assert!(WalletCommands::NewEnv {
+        alias: "localnet".to_string(),
+        rpc: "http://127.0.0.1:5002".to_string(),
+    }
+    .execute(&mut context)
+    .await
+    .is_err());
+    WalletCommands::Switch {
+        address: None,
+        gateway: None,
+        env: Some("localnet".to_string()),
+    }
+    .execute(&mut context)
+    .await?;
+    assert!(WalletCommands::Switch {
+        address: None,
+        gateway: None,
+        env: Some("devnet".to_string()),
+    }
+    .execute(&mut context)
+    .await
+    .is_err());
+
+    let config = PersistedConfig::<WalletConfig>::read(&wallet_conf_path)?;
+    assert!(matches!(&config.gateway, GatewayType::RPC(url) if url == "http://127.0.0.1:5001"));
+    assert_eq!(config.active_env.as_deref(), Some("localnet"));
+    assert_eq!(config.aliases.get("alice"), Some(&addr2));
+
+    // Switching to a gateway by URL leaves the env.
+    WalletCommands::Switch {
+        address: None,
+        gateway: Some("http://127.0.0.1:5002".to_string()),
+        env: None,
+    }
+    .execute(&mut context)
+    .await?;
+    assert_eq!(context.config.active_env, None);
+    Ok(())
+}
+
 fn get_gas_value(o: &SuiParsedObject) -> u64 {
     GasCoin::try_from(o).unwrap().value()
 }
@@ -983,6 +1229,58 @@ async fn test_merge_coin() -> Result<(), anyhow::Error> {
     Ok(())
 }
 
+#[tokio::test]
+async fn test_migrate_objects() -> Result<(), anyhow::Error> {
+    let (_network, mut context, address) = setup_network_and_wallet().await?;
+    let objects = context
+        .gateway
+        .get_objects_owned_by_address(address)
+        .await?;
+    let (recipient, _) = get_key_pair();
+
+    let resp = WalletCommands::MigrateObjects {
+        from: None,
+        to: recipient.into(),
+        gas: None,
+        gas_budget: 1000,
+        batch_size: 2,
+    }
+    .execute(&mut context)
+    .await?;
+    let migration = if let WalletCommandResult::MigrateObjects(migration) = resp {
+        migration
+    } else {
+        panic!("Command failed")
+    };
+    // The coins other than gas go in pairs, then the gas object on its own.
+    let coins = objects.len() - 1;
+    assert_eq!(migration.migrated.len(), objects.len());
+    assert_eq!(migration.transactions.len(), (coins + 1) / 2 + 1);
+    assert!(migration.skipped.is_empty());
+    assert!(context
+        .gateway
+        .get_objects_owned_by_address(address)
+        .await?
+        .is_empty());
+    for object in objects {
+        let object = get_object(object.object_id, &mut context).await.unwrap();
+        assert_eq!(object.owner, Owner::AddressOwner(recipient));
+    }
+
+    // Nothing is left to migrate, not even gas.
+    assert!(WalletCommands::MigrateObjects {
+        from: None,
+        to: recipient.into(),
+        gas: None,
+        gas_budget: 1000,
+        batch_size: 2,
+    }
+    .execute(&mut context)
+    .await
+    .is_err());
+    Ok(())
+}
+
 #[allow(clippy::assertions_on_constants)]
 #[tokio::test]
 async fn test_split_coin() -> Result<(), anyhow::Error> {
@@ -1061,3 +1359,365 @@ async fn test_split_coin() -> Result<(), anyhow::Error> {
     assert!((get_gas_value(&g.new_coins[1]) == 1000) || (get_gas_value(&g.new_coins[1]) == 10));
     Ok(())
 }
+
+#[tokio::test]
+async fn test_serialize_and_execute_signed_tx() -> Result<(), anyhow::Error> {
+    let (_network, mut context, address) = setup_network_and_wallet().await?;
+    let recipient = context.config.accounts.get(1).cloned().unwrap();
+
+    let object_refs = context
+        .gateway
+        .get_objects_owned_by_address(address)
+        .await?;
+    let gas_obj_id = object_refs.first().unwrap().object_id;
+    let obj_id = object_refs.get(1).unwrap().object_id;
+
+    let resp = WalletCommands::SerializeTx {
+        tx: TransactionCommand::Transfer {
+            to: recipient.into(),
+            coin_object_id: obj_id,
+            gas: Some(gas_obj_id),
+            gas_budget: 50000,
+        },
+    }
+    .execute(&mut context)
+    .await?;
+    resp.print(true);
+    let tx_bytes = if let WalletCommandResult::SerializeTx(tx_bytes) = resp {
+        tx_bytes
+    } else {
+        panic!("WalletCommands::SerializeTx returns wrong type")
+    };
+
+    // Sign the bytes as an offline signer would.
+    let message = Intent::new(IntentScope::TransactionData).message_bytes(&tx_bytes.to_vec()?);
+    let signature = context.keystore.sign(&address, &message)?;
+
+    // A signature of other bytes is rejected before it is submitted.
+    let bad_signature = context.keystore.sign(&address, b"not the transaction")?;
+    assert!(WalletCommands::ExecuteSignedTx {
+        tx_bytes: tx_bytes.encoded(),
+        signature: Base64::encode(bad_signature.to_bytes()),
+    }
+    .execute(&mut context)
+    .await
+    .is_err());
+
+    let resp = WalletCommands::ExecuteSignedTx {
+        tx_bytes: tx_bytes.encoded(),
+        signature: Base64::encode(signature.to_bytes()),
+    }
+    .execute(&mut context)
+    .await?;
+    resp.print(true);
+    let effects = if let WalletCommandResult::ExecuteSignedTx(_, effects) = resp {
+        effects
+    } else {
+        panic!("WalletCommands::ExecuteSignedTx returns wrong type")
+    };
+    assert!(effects.status.is_ok());
+
+    let object = get_object(obj_id, &mut context).await.unwrap();
+    assert_eq!(object.owner.get_owner_address().unwrap(), recipient);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_ecdsa_account_transfer() -> Result<(), anyhow::Error> {
+    let (_network, mut context, address) = setup_network_and_wallet().await?;
+    let recipient = context.config.accounts.get(1).cloned().unwrap();
+    let object_refs = context
+        .gateway
+        .get_objects_owned_by_address(address)
+        .await?;
+    let gas_obj_id = object_refs.first().unwrap().object_id;
+
+    for (i, key_scheme) in [SignatureScheme::Secp256k1, SignatureScheme::Secp256r1]
+        .into_iter()
+        .enumerate()
+    {
+        let resp = WalletCommands::NewAddress {
+            key_scheme,
+            alias: None,
+        }
+        .execute(&mut context)
+        .await?;
+        let ecdsa_address = if let WalletCommandResult::NewAddress(address) = resp {
+            address
+        } else {
+            panic!("WalletCommands::NewAddress returns wrong type")
+        };
+
+        // Fund the new account with a coin to transfer and a coin to pay for gas.
+        let coins: Vec<_> = object_refs[1 + 2 * i..3 + 2 * i]
+            .iter()
+            .map(|o| o.object_id)
+            .collect();
+        for coin in &coins {
+            WalletCommands::Transfer {
+                to: ecdsa_address.into(),
+                coin_object_id: *coin,
+                gas: Some(gas_obj_id),
+                gas_budget: 50000,
+            }
+            .execute(&mut context)
+            .await?;
+        }
+
+        // The wallet signs with the ECDSA key of the account.
+        let resp = WalletCommands::Transfer {
+            to: recipient.into(),
+            coin_object_id: coins[0],
+            gas: Some(coins[1]),
+            gas_budget: 50000,
+        }
+        .execute(&mut context)
+        .await?;
+        let effects = if let WalletCommandResult::Transfer(_, _, effects) = resp {
+            effects
+        } else {
+            panic!("WalletCommands::Transfer returns wrong type")
+        };
+        assert!(effects.status.is_ok());
+
+        let object = get_object(coins[0], &mut context).await.unwrap();
+        assert_eq!(object.owner.get_owner_address().unwrap(), recipient);
+    }
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_multisig_transfer() -> Result<(), anyhow::Error> {
+    let (_network, mut context, address) = setup_network_and_wallet().await?;
+    let other = context
+        .keystore
+        .add_random_key_with_scheme(SignatureScheme::Secp256k1)?;
+    let recipient = context.config.accounts.get(2).cloned().unwrap();
+
+    // The public keys of an ed25519 and a secp256k1 account, as the members of a 2-of-2
+    // multisig, in the encoding printed by `sui signtool`.
+    let pks: Vec<_> = [address, other]
+        .iter()
+        .map(|signer| {
+            let signature = context.keystore.sign(signer, b"public key").unwrap();
+            let flag = signature.scheme().flag();
+            match signature {
+                GenericSignature::Signature(signature) => {
+                    Base64::encode(signature.public_key_bytes())
+                }
+                GenericSignature::Secp256k1(signature) => {
+                    Base64::encode([&[flag][..], signature.public_key_bytes()].concat())
+                }
+                _ => panic!("unexpected signature scheme"),
+            }
+        })
+        .collect();
+    let resp = WalletCommands::MultiSigAddress {
+        pks: pks.clone(),
+        weights: vec![1, 1],
+        threshold: 2,
+    }
+    .execute(&mut context)
+    .await?;
+    resp.print(true);
+    let multisig_address = if let WalletCommandResult::MultiSigAddress(address) = resp {
+        address
+    } else {
+        panic!("WalletCommands::MultiSigAddress returns wrong type")
+    };
+
+    // Fund the multisig account with a coin to transfer and a coin to pay for gas.
+    let object_refs = context
+        .gateway
+        .get_objects_owned_by_address(address)
+        .await?;
+    let gas_obj_id = object_refs.first().unwrap().object_id;
+    let coins: Vec<_> = object_refs[1..3].iter().map(|o| o.object_id).collect();
+    for coin in &coins {
+        WalletCommands::Transfer {
+            to: multisig_address.into(),
+            coin_object_id: *coin,
+            gas: Some(gas_obj_id),
+            gas_budget: 50000,
+        }
+        .execute(&mut context)
+        .await?;
+    }
+
+    let resp = WalletCommands::SerializeTx {
+        tx: TransactionCommand::Transfer {
+            to: recipient.into(),
+            coin_object_id: coins[0],
+            gas: Some(coins[1]),
+            gas_budget: 50000,
+        },
+    }
+    .execute(&mut context)
+    .await?;
+    let tx_bytes = if let WalletCommandResult::SerializeTx(tx_bytes) = resp {
+        tx_bytes
+    } else {
+        panic!("WalletCommands::SerializeTx returns wrong type")
+    };
+    let sigs: Vec<_> = [address, other]
+        .iter()
+        .map(|signer| {
+            let message = Intent::new(IntentScope::TransactionData)
+                .message_bytes(&tx_bytes.to_vec().unwrap());
+            let signature = context.keystore.sign(signer, &message);
+            Base64::encode(signature.unwrap().to_bytes())
+        })
+        .collect();
+
+    // A single signature does not reach the threshold.
+    let resp = WalletCommands::MultiSigCombinePartialSig {
+        pks: pks.clone(),
+        weights: vec![1, 1],
+        threshold: 2,
+        sigs: sigs[..1].to_vec(),
+    }
+    .execute(&mut context)
+    .await?;
+    let partial = if let WalletCommandResult::MultiSigCombinePartialSig(multisig) = resp {
+        multisig
+    } else {
+        panic!("WalletCommands::MultiSigCombinePartialSig returns wrong type")
+    };
+    assert!(WalletCommands::ExecuteSignedTx {
+        tx_bytes: tx_bytes.encoded(),
+        signature: partial.encoded(),
+    }
+    .execute(&mut context)
+    .await
+    .is_err());
+
+    let resp = WalletCommands::MultiSigCombinePartialSig {
+        pks,
+        weights: vec![1, 1],
+        threshold: 2,
+        sigs,
+    }
+    .execute(&mut context)
+    .await?;
+    resp.print(true);
+    let multisig = if let WalletCommandResult::MultiSigCombinePartialSig(multisig) = resp {
+        multisig
+    } else {
+        panic!("WalletCommands::MultiSigCombinePartialSig returns wrong type")
+    };
+    let resp = WalletCommands::ExecuteSignedTx {
+        tx_bytes: tx_bytes.encoded(),
+        signature: multisig.encoded(),
+    }
+    .execute(&mut context)
+    .await?;
+    let effects = if let WalletCommandResult::ExecuteSignedTx(_, effects) = resp {
+        effects
+    } else {
+        panic!("WalletCommands::ExecuteSignedTx returns wrong type")
+    };
+    assert!(effects.status.is_ok());
+
+    let object = get_object(coins[0], &mut context).await.unwrap();
+    assert_eq!(object.owner.get_owner_address().unwrap(), recipient);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_keytool_generate_and_import() -> Result<(), anyhow::Error> {
+    let temp_dir = tempfile::tempdir()?;
+    let keystore_path = temp_dir.path().join("wallet.key");
+    // In plaintext, as the test networks write it, so that no passphrase is asked for.
+    let mut keystore = SuiKeystore::default();
+    keystore.set_path(&keystore_path);
+    keystore.save()?;
+    let keytool = |cmd| SuiCommand::KeyTool {
+        keystore_path: Some(keystore_path.clone()),
+        cmd,
+    };
+
+    keytool(KeyToolCommand::Generate {
+        key_scheme: SignatureScheme::Secp256k1,
+        derivation_path: None,
+    })
+    .execute()
+    .await?;
+    let keystore = SuiKeystore::load_or_create(&keystore_path)?;
+    assert_eq!(keystore.addresses().len(), 1);
+    assert_eq!(keystore.key_pairs()[0].scheme(), SignatureScheme::Secp256k1);
+
+    // The same mnemonic always restores the same keys, whose path depends on the scheme.
+    let phrase = format!("{} about", vec!["abandon"; 11].join(" "));
+    for key_scheme in [SignatureScheme::ED25519, SignatureScheme::Secp256r1] {
+        keytool(KeyToolCommand::Import {
+            mnemonic_phrase: phrase.clone(),
+            key_scheme,
+            derivation_path: None,
+        })
+        .execute()
+        .await?;
+    }
+    keytool(KeyToolCommand::Import {
+        mnemonic_phrase: phrase.to_uppercase(),
+        key_scheme: SignatureScheme::ED25519,
+        derivation_path: Some("m/44'/784'/0'/0'/0'".parse()?),
+    })
+    .execute()
+    .await?;
+    let keystore = SuiKeystore::load_or_create(&keystore_path)?;
+    assert_eq!(keystore.addresses().len(), 3);
+
+    // Paths which don't follow the scheme are rejected.
+    let result = keytool(KeyToolCommand::Import {
+        mnemonic_phrase: phrase,
+        key_scheme: SignatureScheme::ED25519,
+        derivation_path: Some("m/44'/784'/0'/0/0".parse()?),
+    })
+    .execute()
+    .await;
+    assert!(result.is_err());
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_keytool_personal_message() -> Result<(), anyhow::Error> {
+    let temp_dir = tempfile::tempdir()?;
+    let keystore_path = temp_dir.path().join("wallet.key");
+    let mut keystore = SuiKeystore::default();
+    keystore.set_path(&keystore_path);
+    let address = keystore.add_random_key()?;
+    let keytool = |cmd| SuiCommand::KeyTool {
+        keystore_path: Some(keystore_path.clone()),
+        cmd,
+    };
+
+    let message = "Sign in to example.com";
+    let signature = keystore.sign_personal_message(&address, message.as_bytes())?;
+    let verify = |message: &str, signature: &GenericSignature| {
+        let cmd = keytool(KeyToolCommand::VerifyPersonalMessage {
+            address,
+            message: message.to_string(),
+            signature: Base64::encode(signature.to_bytes()),
+            base64: false,
+        });
+        async move { cmd.execute().await }
+    };
+    verify(message, &signature).await?;
+    assert!(verify("Sign in to example.org", &signature).await.is_err());
+
+    // A signature of the same bytes as a transaction is not a signature of the message.
+    let signature = keystore.sign(
+        &address,
+        &Intent::new(IntentScope::TransactionData).message_bytes(message.as_bytes()),
+    )?;
+    assert!(verify(message, &signature).await.is_err());
+
+    keytool(KeyToolCommand::SignPersonalMessage {
+        address,
+        message: Base64::encode(message.as_bytes()),
+        base64: true,
+    })
+    .execute()
+    .await?;
+    Ok(())
+}
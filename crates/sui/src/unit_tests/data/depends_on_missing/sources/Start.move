@@ -0,0 +1,10 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+module DependsOnMissing::Start {
+    use MissingDependency::Counter;
+
+    public fun start(): u64 {
+        Counter::initial_value()
+    }
+}
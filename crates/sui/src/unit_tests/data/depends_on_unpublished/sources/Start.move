@@ -0,0 +1,10 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+module DependsOnUnpublished::Start {
+    use UnpublishedDependency::Counter;
+
+    public fun start(): u64 {
+        Counter::initial_value()
+    }
+}
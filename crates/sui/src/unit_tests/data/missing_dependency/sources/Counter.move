@@ -0,0 +1,9 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+/// A module of a package with an address no package is published at.
+module MissingDependency::Counter {
+    public fun initial_value(): u64 {
+        1
+    }
+}
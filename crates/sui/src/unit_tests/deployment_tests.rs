@@ -0,0 +1,72 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use super::*;
+use sui_config::NodeConfig;
+
+#[test]
+fn test_write_node_configs() -> Result<(), anyhow::Error> {
+    let dir = tempfile::tempdir()?;
+    let dir = dir.path();
+    let mut genesis_conf = GenesisConfig::custom_genesis(3, 1, 1);
+    genesis_conf.committee_size = 3;
+    let deployment = NodeDeployment::with_default_hosts(&genesis_conf, 8080, "/opt/sui".into());
+    deployment.write_node_configs(genesis_conf, dir)?;
+
+    let genesis = sui_config::genesis::Genesis::load(dir.join(SUI_GENESIS_FILENAME))?;
+    let validators = (0..3)
+        .map(|i| NodeConfig::load(dir.join(format!("validator-config-{i}.yaml"))))
+        .collect::<Result<Vec<_>, _>>()?;
+    let fullnode = NodeConfig::load(dir.join(SUI_FULLNODE_CONFIG))?;
+
+    // The peers of each node are the validators of the genesis, on their hosts.
+    for (i, validator) in genesis.validator_set().iter().enumerate() {
+        let host = format!("/dns/validator-{i}/tcp/{}/http", 8080 + 10 * i);
+        assert_eq!(validator.network_address().to_string(), host);
+        assert_eq!(validators[i].network_address(), validator.network_address());
+        assert_eq!(
+            validators[i]
+                .consensus_config()
+                .unwrap()
+                .narwhal_committee()
+                .authorities
+                .len(),
+            3
+        );
+    }
+    assert_eq!(
+        fullnode.network_address().to_string(),
+        "/dns/fullnode/tcp/8110/http"
+    );
+
+    // No two nodes listen on the same port, and they read the genesis and write their
+    // databases where the deployment mounts them.
+    let mut ports = BTreeSet::new();
+    for node in validators.iter().chain([&fullnode]) {
+        assert!(ports.insert(node.metrics_address.port()));
+        assert!(ports.insert(node.json_rpc_address.port()));
+        assert!(node.db_path().starts_with("/opt/sui/db"));
+        assert_eq!(
+            node.genesis,
+            sui_config::node::Genesis::new_from_file("/opt/sui/config/genesis.blob")
+        );
+    }
+
+    let compose = fs::read_to_string(dir.join("docker-compose.yaml"))?;
+    assert!(compose.contains("/opt/sui/config/validator-config-2.yaml"));
+    assert!(compose.contains("  fullnode:\n"));
+    Ok(())
+}
+
+#[test]
+fn test_validator_hosts_are_distinct() {
+    let dir = tempfile::tempdir().unwrap();
+    let deployment = NodeDeployment {
+        validator_hosts: vec!["validator".to_string(), "validator".to_string()],
+        base_port: 8080,
+        node_root: "/opt/sui".into(),
+    };
+    assert!(deployment
+        .write_node_configs(GenesisConfig::custom_genesis(2, 1, 1), dir.path())
+        .is_err());
+}
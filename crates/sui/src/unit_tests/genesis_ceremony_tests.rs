@@ -0,0 +1,115 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use super::*;
+use sui_config::utils;
+use sui_types::crypto::get_key_pair;
+
+/// Write the key file of a new validator, holding its base64 key pair.
+fn new_key_file(path: PathBuf) -> Result<PathBuf, anyhow::Error> {
+    let key_pair = serde_json::to_value(get_key_pair().1)?;
+    fs::write(&path, key_pair.as_str().unwrap())?;
+    Ok(path)
+}
+
+fn run(dir: &Path, command: CeremonyCommand) -> Result<(), anyhow::Error> {
+    Ceremony {
+        path: Some(dir.to_path_buf()),
+        command,
+    }
+    .run()
+}
+
+#[test]
+fn test_genesis_ceremony() -> Result<(), anyhow::Error> {
+    let dir = tempfile::tempdir()?;
+    let dir = dir.path();
+    run(dir, CeremonyCommand::Init)?;
+
+    // Each operator adds its validator from its own key file.
+    let mut key_files = Vec::new();
+    for i in 0..2 {
+        let key_file = new_key_file(dir.join(format!("validator-{i}.key")))?;
+        run(
+            dir,
+            CeremonyCommand::AddValidator {
+                key_file: key_file.clone(),
+                network_address: utils::new_network_address(),
+                stake: 1,
+            },
+        )?;
+        key_files.push(key_file);
+    }
+    let owner = SuiAddress::random_for_testing_only();
+    run(
+        dir,
+        CeremonyCommand::AddGasObject {
+            address: owner,
+            object_id: None,
+            value: 1000,
+        },
+    )?;
+    run(dir, CeremonyCommand::Build)?;
+
+    // Nothing can be added to a built genesis, and it is only final once every validator
+    // signed it.
+    assert!(run(
+        dir,
+        CeremonyCommand::AddGasObject {
+            address: owner,
+            object_id: None,
+            value: 1000,
+        },
+    )
+    .is_err());
+    run(
+        dir,
+        CeremonyCommand::VerifyAndSign {
+            key_file: key_files[0].clone(),
+        },
+    )?;
+    assert!(run(dir, CeremonyCommand::Finalize).is_err());
+    run(
+        dir,
+        CeremonyCommand::VerifyAndSign {
+            key_file: key_files[1].clone(),
+        },
+    )?;
+    run(dir, CeremonyCommand::Finalize)?;
+
+    let genesis = Genesis::load(dir.join(SUI_GENESIS_FILENAME))?;
+    assert_eq!(genesis.validator_set().len(), 2);
+    assert!(genesis
+        .objects()
+        .iter()
+        .any(|object| object.get_single_owner() == Some(owner)));
+    Ok(())
+}
+
+#[test]
+fn test_genesis_ceremony_rejects_a_tampered_genesis() -> Result<(), anyhow::Error> {
+    let dir = tempfile::tempdir()?;
+    let dir = dir.path();
+    run(dir, CeremonyCommand::Init)?;
+    let key_file = new_key_file(dir.join("validator.key"))?;
+    run(
+        dir,
+        CeremonyCommand::AddValidator {
+            key_file: key_file.clone(),
+            network_address: utils::new_network_address(),
+            stake: 1,
+        },
+    )?;
+    run(dir, CeremonyCommand::Build)?;
+
+    // An object added behind the back of the operators changes the genesis they would sign.
+    Builder::load(dir)?
+        .add_object(Object::with_id_owner_gas_for_testing(
+            ObjectID::random(),
+            SuiAddress::random_for_testing_only(),
+            1000,
+        ))
+        .save(dir)?;
+    assert!(run(dir, CeremonyCommand::VerifyAndSign { key_file }).is_err());
+    Ok(())
+}
@@ -0,0 +1,100 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use super::*;
+
+fn write_package(path: &Path, name: &str, dependencies: &str) {
+    fs::create_dir_all(path).unwrap();
+    fs::write(
+        path.join(MOVE_MANIFEST_FILENAME),
+        format!(
+            "[package]\nname = \"{name}\"\nversion = \"0.0.1\"\n\n[dependencies]\n{dependencies}\n[addresses]\n{name} = \"_\"\nStd = \"0x1\"\n"
+        ),
+    )
+    .unwrap();
+}
+
+#[test]
+fn test_named_addresses_from_lockfiles() -> Result<(), anyhow::Error> {
+    let dir = tempfile::tempdir()?;
+    let dependency = dir.path().join("dependency");
+    let package = dir.path().join("package");
+    write_package(&dependency, "Dependency", "");
+    write_package(
+        &package,
+        "Package",
+        "Dependency = { local = \"../dependency\" }\n",
+    );
+
+    let published_id = ObjectID::random();
+    MoveLock::record_publish(&dependency, "devnet", published_id, None)?;
+    let lock = MoveLock::read(&dependency)?;
+    assert_eq!(lock.env["devnet"].published_id, published_id);
+    assert_eq!(
+        lock.env["devnet"].compiler_version,
+        env!("CARGO_PKG_VERSION")
+    );
+    // A package never published has an empty lockfile.
+    assert_eq!(MoveLock::read(&package)?, MoveLock::default());
+
+    // The package is published at 0x0 against the ID of the dependency in the env.
+    assert_eq!(
+        named_addresses(&package, Some("devnet"), false)?,
+        BTreeMap::from([
+            ("Dependency".to_string(), AccountAddress::from(published_id)),
+            ("Package".to_string(), AccountAddress::ZERO),
+        ])
+    );
+
+    // The dependency is not published in another env.
+    assert_eq!(
+        named_addresses(&package, Some("localnet"), false)?,
+        BTreeMap::from([("Package".to_string(), AccountAddress::ZERO)])
+    );
+    assert_eq!(
+        named_addresses(&package, Some("localnet"), true)?,
+        BTreeMap::from([
+            ("Dependency".to_string(), AccountAddress::ZERO),
+            ("Package".to_string(), AccountAddress::ZERO),
+        ])
+    );
+    Ok(())
+}
+
+#[test]
+fn test_dependents_build_against_upgraded_package() -> Result<(), anyhow::Error> {
+    let dir = tempfile::tempdir()?;
+    let dependency = dir.path().join("dependency");
+    let package = dir.path().join("package");
+    write_package(&dependency, "Dependency", "");
+    write_package(
+        &package,
+        "Package",
+        "Dependency = { local = \"../dependency\" }\n",
+    );
+
+    let published_id = ObjectID::random();
+    let upgrade_cap = ObjectID::random();
+    MoveLock::record_publish(&dependency, "devnet", published_id, Some(upgrade_cap))?;
+    assert_eq!(
+        MoveLock::read(&dependency)?.env["devnet"].upgrade_cap,
+        Some(upgrade_cap)
+    );
+
+    // The package can only be upgraded in an env it was published in.
+    let upgraded_id = ObjectID::random();
+    assert!(MoveLock::record_upgrade(&dependency, "localnet", upgraded_id, upgrade_cap).is_err());
+    MoveLock::record_upgrade(&dependency, "devnet", upgraded_id, upgrade_cap)?;
+    let published = &MoveLock::read(&dependency)?.env["devnet"];
+    assert_eq!(published.published_id, published_id);
+    assert_eq!(published.latest_id(), upgraded_id);
+
+    assert_eq!(
+        named_addresses(&package, Some("devnet"), false)?,
+        BTreeMap::from([
+            ("Dependency".to_string(), AccountAddress::from(upgraded_id)),
+            ("Package".to_string(), AccountAddress::ZERO),
+        ])
+    );
+    Ok(())
+}
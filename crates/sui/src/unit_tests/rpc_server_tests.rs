@@ -1,20 +1,27 @@
 // Copyright (c) 2022, Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+use jsonrpsee::core::client::ClientT;
+use jsonrpsee::rpc_params;
+use prometheus_exporter::prometheus::{self, proto::MetricType};
 use std::{path::Path, str::FromStr};
 use sui::keystore::{Keystore, SuiKeystore};
 use sui_core::gateway_state::GatewayTxSeqNumber;
 use sui_core::gateway_types::{
-    GetObjectDataResponse, TransactionEffectsResponse, TransactionResponse,
+    GetObjectDataResponse, MultiGetResult, SuiData, SuiObjectDataOptions,
+    TransactionEffectsResponse, TransactionResponse,
 };
 use sui_framework::build_move_package_to_bytes;
 use sui_gateway::api::{
     RpcGatewayApiClient, RpcReadApiClient, RpcTransactionBuilderClient, TransactionBytes,
+    MAX_MULTI_GET_SIZE,
 };
 use sui_json::SuiJsonValue;
 use sui_types::sui_serde::Base64;
 use sui_types::{
-    base_types::{ObjectID, TransactionDigest},
+    base_types::{ObjectID, SuiAddress, TransactionDigest},
+    crypto::Signature,
+    intent::{Intent, IntentScope},
     SUI_FRAMEWORK_ADDRESS,
 };
 
@@ -27,7 +34,9 @@ async fn test_get_objects() -> Result<(), anyhow::Error> {
     let address = test_network.accounts.first().unwrap();
 
     http_client.sync_account_state(*address).await?;
-    let objects = http_client.get_objects_owned_by_address(*address).await?;
+    let objects = http_client
+        .get_objects_owned_by_address(*address, None)
+        .await?;
     assert_eq!(5, objects.len());
     Ok(())
 }
@@ -38,7 +47,9 @@ async fn test_transfer_coin() -> Result<(), anyhow::Error> {
     let http_client = test_network.http_client;
     let address = test_network.accounts.first().unwrap();
     http_client.sync_account_state(*address).await?;
-    let objects = http_client.get_objects_owned_by_address(*address).await?;
+    let objects = http_client
+        .get_objects_owned_by_address(*address, None)
+        .await?;
 
     let tx_data: TransactionBytes = http_client
         .transfer_coin(
@@ -52,17 +63,22 @@ async fn test_transfer_coin() -> Result<(), anyhow::Error> {
 
     let keystore = SuiKeystore::load_or_create(&test_network.network.dir().join("wallet.key"))?;
     let tx_bytes = tx_data.tx_bytes.to_vec()?;
-    let signature = keystore.sign(address, &tx_bytes)?;
+    let signature: Signature = keystore
+        .sign(
+            address,
+            &Intent::new(IntentScope::TransactionData).message_bytes(&tx_bytes),
+        )?
+        .try_into()?;
 
     let tx_bytes = Base64::from_bytes(&tx_bytes);
     let signature_bytes = Base64::from_bytes(signature.signature_bytes());
     let pub_key = Base64::from_bytes(signature.public_key_bytes());
 
     let tx_response: TransactionResponse = http_client
-        .execute_transaction(tx_bytes, signature_bytes, pub_key)
+        .execute_transaction(tx_bytes, signature_bytes, pub_key, None)
         .await?;
 
-    let effect = tx_response.to_effect_response()?.effects;
+    let effect = tx_response.to_effect_response()?.effects.unwrap();
     assert_eq!(2, effect.mutated.len());
 
     Ok(())
@@ -74,7 +90,9 @@ async fn test_publish() -> Result<(), anyhow::Error> {
     let http_client = test_network.http_client;
     let address = test_network.accounts.first().unwrap();
     http_client.sync_account_state(*address).await?;
-    let objects = http_client.get_objects_owned_by_address(*address).await?;
+    let objects = http_client
+        .get_objects_owned_by_address(*address, None)
+        .await?;
     let gas = objects.first().unwrap();
 
     let compiled_modules = build_move_package_to_bytes(
@@ -91,14 +109,19 @@ async fn test_publish() -> Result<(), anyhow::Error> {
 
     let keystore = SuiKeystore::load_or_create(&test_network.network.dir().join("wallet.key"))?;
     let tx_bytes = tx_data.tx_bytes.to_vec()?;
-    let signature = keystore.sign(address, &tx_bytes)?;
+    let signature: Signature = keystore
+        .sign(
+            address,
+            &Intent::new(IntentScope::TransactionData).message_bytes(&tx_bytes),
+        )?
+        .try_into()?;
 
     let tx_bytes = Base64::from_bytes(&tx_bytes);
     let signature_bytes = Base64::from_bytes(signature.signature_bytes());
     let pub_key = Base64::from_bytes(signature.public_key_bytes());
 
     let tx_response: TransactionResponse = http_client
-        .execute_transaction(tx_bytes, signature_bytes, pub_key)
+        .execute_transaction(tx_bytes, signature_bytes, pub_key, None)
         .await?;
 
     let response = tx_response.to_publish_response()?;
@@ -112,7 +135,9 @@ async fn test_move_call() -> Result<(), anyhow::Error> {
     let http_client = test_network.http_client;
     let address = test_network.accounts.first().unwrap();
     http_client.sync_account_state(*address).await?;
-    let objects = http_client.get_objects_owned_by_address(*address).await?;
+    let objects = http_client
+        .get_objects_owned_by_address(*address, None)
+        .await?;
     let gas = objects.first().unwrap();
 
     let package_id = ObjectID::new(SUI_FRAMEWORK_ADDRESS.into_bytes());
@@ -139,17 +164,22 @@ async fn test_move_call() -> Result<(), anyhow::Error> {
 
     let keystore = SuiKeystore::load_or_create(&test_network.network.dir().join("wallet.key"))?;
     let tx_bytes = tx_data.tx_bytes.to_vec()?;
-    let signature = keystore.sign(address, &tx_bytes)?;
+    let signature: Signature = keystore
+        .sign(
+            address,
+            &Intent::new(IntentScope::TransactionData).message_bytes(&tx_bytes),
+        )?
+        .try_into()?;
 
     let tx_bytes = Base64::from_bytes(&tx_bytes);
     let signature_bytes = Base64::from_bytes(signature.signature_bytes());
     let pub_key = Base64::from_bytes(signature.public_key_bytes());
 
     let tx_response: TransactionResponse = http_client
-        .execute_transaction(tx_bytes, signature_bytes, pub_key)
+        .execute_transaction(tx_bytes, signature_bytes, pub_key, None)
         .await?;
 
-    let effect = tx_response.to_effect_response()?.effects;
+    let effect = tx_response.to_effect_response()?.effects.unwrap();
     assert_eq!(1, effect.created.len());
     Ok(())
 }
@@ -160,13 +190,31 @@ async fn test_get_object_info() -> Result<(), anyhow::Error> {
     let http_client = test_network.http_client;
     let address = test_network.accounts.first().unwrap();
     http_client.sync_account_state(*address).await?;
-    let objects = http_client.get_objects_owned_by_address(*address).await?;
+    let objects = http_client
+        .get_objects_owned_by_address(*address, None)
+        .await?;
 
     for oref in objects {
-        let result: GetObjectDataResponse = http_client.get_object(oref.object_id).await?;
+        let result: GetObjectDataResponse = http_client.get_object(oref.object_id, None).await?;
         assert!(
             matches!(result, GetObjectDataResponse::Exists(object) if oref.object_id == object.id() && &object.owner.get_owner_address()? == address)
         );
+
+        let options = SuiObjectDataOptions {
+            show_content: false,
+            show_previous_transaction: false,
+            show_bcs: true,
+            ..Default::default()
+        };
+        let result = http_client
+            .get_object(oref.object_id, Some(options))
+            .await?
+            .into_object()?;
+        assert!(result.previous_transaction.is_none());
+        assert!(result.bcs.is_some());
+        if let SuiData::MoveObject(object) = result.data {
+            assert!(object.fields.is_none());
+        }
     }
     Ok(())
 }
@@ -179,7 +227,9 @@ async fn test_get_transaction() -> Result<(), anyhow::Error> {
 
     http_client.sync_account_state(*address).await?;
 
-    let objects = http_client.get_objects_owned_by_address(*address).await?;
+    let objects = http_client
+        .get_objects_owned_by_address(*address, None)
+        .await?;
     let gas_id = objects.last().unwrap().object_id;
 
     // Make some transactions
@@ -191,18 +241,23 @@ async fn test_get_transaction() -> Result<(), anyhow::Error> {
 
         let keystore = SuiKeystore::load_or_create(&test_network.network.dir().join("wallet.key"))?;
         let tx_bytes = tx_data.tx_bytes.to_vec()?;
-        let signature = keystore.sign(address, &tx_bytes)?;
+        let signature: Signature = keystore
+            .sign(
+                address,
+                &Intent::new(IntentScope::TransactionData).message_bytes(&tx_bytes),
+            )?
+            .try_into()?;
 
         let tx_bytes = Base64::from_bytes(&tx_bytes);
         let signature_bytes = Base64::from_bytes(signature.signature_bytes());
         let pub_key = Base64::from_bytes(signature.public_key_bytes());
 
         let response: TransactionResponse = http_client
-            .execute_transaction(tx_bytes, signature_bytes, pub_key)
+            .execute_transaction(tx_bytes, signature_bytes, pub_key, None)
             .await?;
 
-        if let TransactionResponse::EffectResponse(effects) = response {
-            tx_responses.push(effects);
+        if let TransactionResponse::EffectResponse(response) = response {
+            tx_responses.push(response.effects.unwrap());
         }
     }
     // test get_transactions_in_range
@@ -226,12 +281,169 @@ async fn test_get_transaction() -> Result<(), anyhow::Error> {
     assert_eq!(4, tx.len());
 
     // test get_transaction
-    for (_, tx_digest) in tx {
-        let response: TransactionEffectsResponse = http_client.get_transaction(tx_digest).await?;
-        assert!(tx_responses.iter().any(
-            |effects| effects.effects.transaction_digest == response.effects.transaction_digest
-        ))
+    for (_, tx_digest) in &tx {
+        let response: TransactionEffectsResponse =
+            http_client.get_transaction(*tx_digest, None).await?;
+        assert!(tx_responses.iter().any(|effects| effects.transaction_digest
+            == response.effects.as_ref().unwrap().transaction_digest))
+    }
+
+    // test multi_get_transactions, with a digest that does not exist
+    let mut digests: Vec<_> = tx.iter().map(|(_, digest)| *digest).collect();
+    digests.push(TransactionDigest::random());
+    let results = http_client.multi_get_transactions(digests, None).await?;
+    assert_eq!(5, results.len());
+    for (result, (_, tx_digest)) in results.iter().zip(&tx) {
+        assert!(
+            matches!(result, MultiGetResult::Ok(response) if response.certificate.as_ref().unwrap().transaction_digest == *tx_digest)
+        );
+    }
+    assert!(matches!(results.last(), Some(MultiGetResult::Error(_))));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_multi_get_objects() -> Result<(), anyhow::Error> {
+    let test_network = start_rpc_test_network(None).await?;
+    let http_client = test_network.http_client;
+    let address = test_network.accounts.first().unwrap();
+    http_client.sync_account_state(*address).await?;
+    let objects = http_client
+        .get_objects_owned_by_address(*address, None)
+        .await?;
+
+    let mut object_ids: Vec<_> = objects.iter().map(|oref| oref.object_id).collect();
+    let missing_id = ObjectID::random();
+    object_ids.push(missing_id);
+    let results = http_client.multi_get_objects(object_ids, None).await?;
+    assert_eq!(objects.len() + 1, results.len());
+    for (result, oref) in results.iter().zip(&objects) {
+        assert!(
+            matches!(result, MultiGetResult::Ok(GetObjectDataResponse::Exists(object)) if object.id() == oref.object_id)
+        );
     }
+    assert!(matches!(
+        results.last(),
+        Some(MultiGetResult::Ok(GetObjectDataResponse::NotExists(id))) if *id == missing_id
+    ));
+
+    // Requests for too many objects are rejected.
+    let object_ids = vec![missing_id; MAX_MULTI_GET_SIZE + 1];
+    assert!(http_client
+        .multi_get_objects(object_ids, None)
+        .await
+        .is_err());
+    Ok(())
+}
 
+#[tokio::test]
+async fn test_batch_request() -> Result<(), anyhow::Error> {
+    let test_network = start_rpc_test_network(None).await?;
+    let http_client = test_network.http_client;
+    let address = test_network.accounts.first().unwrap();
+    http_client.sync_account_state(*address).await?;
+    let objects = http_client
+        .get_objects_owned_by_address(*address, None)
+        .await?;
+
+    let batch = objects
+        .iter()
+        .map(|oref| ("sui_getObject", rpc_params![oref.object_id]))
+        .collect();
+    let responses: Vec<serde_json::Value> = http_client.batch_request(batch).await?;
+    assert_eq!(objects.len(), responses.len());
+    for (response, oref) in responses.into_iter().zip(&objects) {
+        let response: GetObjectDataResponse = serde_json::from_value(response)?;
+        assert_eq!(oref.object_id, response.object()?.id());
+    }
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_verify_personal_message() -> Result<(), anyhow::Error> {
+    let test_network = start_rpc_test_network(None).await?;
+    let http_client = test_network.http_client;
+    let address = test_network.accounts.first().unwrap();
+    let keystore = SuiKeystore::load_or_create(&test_network.network.dir().join("wallet.key"))?;
+
+    let message = b"Sign in to example.com";
+    let signature = keystore.sign_personal_message(address, message)?;
+    let signature = Base64::from_bytes(&signature.to_bytes());
+    for (message, address, valid) in [
+        (&message[..], *address, true),
+        (&b"Sign in to example.org"[..], *address, false),
+        (&message[..], SuiAddress::random_for_testing_only(), false),
+    ] {
+        let verified = http_client
+            .verify_personal_message(address, Base64::from_bytes(message), signature.clone())
+            .await?;
+        assert_eq!(verified, valid);
+    }
+
+    // A malformed signature is an error rather than a failed verification.
+    assert!(http_client
+        .verify_personal_message(
+            *address,
+            Base64::from_bytes(message),
+            Base64::from_bytes(b"not a signature"),
+        )
+        .await
+        .is_err());
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_rpc_discover_and_method_metrics() -> Result<(), anyhow::Error> {
+    let test_network = start_rpc_test_network(None).await?;
+    let http_client = test_network.http_client;
+    let address = test_network.accounts.first().unwrap();
+
+    let doc: serde_json::Value = http_client.request("rpc.discover", rpc_params![]).await?;
+    let methods = doc["methods"].as_array().unwrap();
+    let method = |name: &str| {
+        methods
+            .iter()
+            .find(|method| method["name"] == name)
+            .unwrap()
+    };
+    // The methods of the first API version have no version annotation.
+    assert!(method("sui_getObject").get("x-since").is_none());
+    assert_eq!(method("sui_executeMultiSigTransaction")["x-since"], "0.2.0");
+
+    http_client
+        .get_objects_owned_by_address(*address, None)
+        .await?;
+    assert!(http_client
+        .request::<serde_json::Value>("sui_getObject", rpc_params!["not an object id"])
+        .await
+        .is_err());
+    assert!(http_client
+        .request::<serde_json::Value>("sui_noSuchMethod", rpc_params![])
+        .await
+        .is_err());
+
+    // The metrics are shared by the servers of all the tests in this process, so only check
+    // that the requests above were counted.
+    let families = prometheus::gather();
+    let route_metric = |name: &str, route: &str| {
+        let family = families.iter().find(|family| family.get_name() == name)?;
+        let metric = family.get_metric().iter().find(|metric| {
+            metric
+                .get_label()
+                .iter()
+                .any(|label| label.get_value() == route)
+        })?;
+        Some(match family.get_field_type() {
+            MetricType::HISTOGRAM => metric.get_histogram().get_sample_count() as f64,
+            _ => metric.get_counter().get_value(),
+        })
+    };
+    let route = "sui_getObjectsOwnedByAddress";
+    assert!(route_metric("rpc_requests_by_route", route).unwrap() >= 1.0);
+    assert!(route_metric("req_latency_by_route", route).unwrap() >= 1.0);
+    assert!(route_metric("errors_by_route", "sui_getObject").unwrap() >= 1.0);
+    // Methods the server doesn't serve don't get a timeseries of their own.
+    assert!(route_metric("rpc_requests_by_route", "sui_noSuchMethod").is_none());
     Ok(())
 }
@@ -3,8 +3,9 @@
 
 use core::fmt;
 use std::{
-    collections::BTreeSet,
+    collections::{BTreeMap, BTreeSet},
     fmt::{Debug, Display, Formatter, Write},
+    io::{self, Write as _},
     path::Path,
     time::Instant,
 };
@@ -12,31 +13,48 @@ use std::{
 use anyhow::anyhow;
 use clap::*;
 use colored::Colorize;
+use jsonrpsee::http_client::HttpClientBuilder;
+use move_binary_format::CompiledModule;
 use move_core_types::{language_storage::TypeTag, parser::parse_type_tag};
+use move_package::BuildConfig;
 use serde::Serialize;
-use serde_json::json;
+use serde_json::{json, Value};
 use sui_core::gateway_types::{
-    GetObjectDataResponse, MergeCoinResponse, PublishResponse, SplitCoinResponse, SuiObjectInfo,
-    SuiParsedObject,
+    GetObjectDataResponse, MergeCoinResponse, PublishResponse, SplitCoinResponse, SuiData,
+    SuiObject, SuiObjectInfo, SuiParsedObject,
 };
-use tracing::info;
+use tracing::{info, warn};
 
+use sui_adapter::adapter;
 use sui_core::gateway_state::GatewayClient;
 use sui_core::gateway_types::{SuiCertifiedTransaction, SuiExecutionStatus, SuiTransactionEffects};
-use sui_framework::build_move_package_to_bytes;
+use sui_framework::build_move_package_for_publish;
+use sui_gateway::api::{RpcBcsApiClient, RpcFullNodeReadApiClient};
 use sui_json::SuiJsonValue;
-use sui_types::object::Owner;
+use sui_sdk::ledger::LedgerKeystore;
+use sui_types::move_package::disassemble_modules;
+use sui_types::object::{Object, Owner};
 use sui_types::sui_serde::{Base64, Encoding};
 use sui_types::{
-    base_types::{ObjectID, SuiAddress},
+    base_types::{ObjectID, ObjectRef, SuiAddress, TransactionDigest},
+    coin::{COIN_MODULE_NAME, COIN_STRUCT_NAME},
+    crypto::{GenericSignature, SignableBytes, SignatureScheme},
     gas_coin::GasCoin,
-    messages::Transaction,
-    SUI_FRAMEWORK_ADDRESS,
+    key_derivation::DerivationPath,
+    messages::{
+        framework_digest, SingleTransactionKind, Transaction, TransactionData, TransactionKind,
+        TransferCoin,
+    },
+    multisig::{MultiSig, MultiSigMemberKey, MultiSigPublicKey, ThresholdUnit, WeightUnit},
+    protocol_config::ProtocolVersion,
+    upgrade_cap::UpgradeCap,
+    SUI_FRAMEWORK_ADDRESS, SUI_SYSTEM_STATE_OBJECT_ID,
 };
 
 use crate::{
-    config::{Config, GatewayType, PersistedConfig, WalletConfig},
-    keystore::Keystore,
+    config::{AddressOrAlias, Config, GatewayType, PersistedConfig, WalletConfig, WalletEnv},
+    keystore::{Keystore, SuiKeystore},
+    lockfile::{self, MoveLock},
 };
 
 pub const EXAMPLE_NFT_NAME: &str = "Example NFT";
@@ -60,16 +78,45 @@ pub enum WalletCommands {
     /// Switch active address and network(e.g., devnet, local rpc server)
     #[clap(name = "switch")]
     Switch {
-        /// An Sui address to be used as the active address for subsequent
-        /// commands.
+        /// An Sui address, or the alias of one, to be used as the active address
+        /// for subsequent commands.
         #[clap(long)]
-        address: Option<SuiAddress>,
+        address: Option<AddressOrAlias>,
         /// The gateway URL (e.g., local rpc server, devnet rpc server, etc) to be
         /// used for subsequent commands.
-        #[clap(long, value_hint = ValueHint::Url)]
+        #[clap(long, value_hint = ValueHint::Url, conflicts_with = "env")]
         gateway: Option<String>,
+        /// The alias of an env added with new-env, whose gateway is used for
+        /// subsequent commands.
+        #[clap(long)]
+        env: Option<String>,
+    },
+
+    /// Name a managed address, so that commands accept the alias in place of the address.
+    #[clap(name = "alias")]
+    Alias {
+        #[clap(long)]
+        address: AddressOrAlias,
+        /// The new alias of the address, which replaces its previous one.
+        #[clap(long)]
+        alias: String,
+    },
+
+    /// Add a network the wallet can switch to with switch --env.
+    #[clap(name = "new-env")]
+    NewEnv {
+        /// The name of the network, e.g. localnet or devnet.
+        #[clap(long)]
+        alias: String,
+        /// The URL of the RPC gateway of the network.
+        #[clap(long, value_hint = ValueHint::Url)]
+        rpc: String,
     },
 
+    /// List the networks added with new-env, and which one is active.
+    #[clap(name = "envs")]
+    Envs,
+
     /// Default address used for commands when none specified
     #[clap(name = "active-address")]
     ActiveAddress {},
@@ -97,6 +144,50 @@ pub enum WalletCommands {
         /// Gas budget for running module initializers
         #[clap(long)]
         gas_budget: u64,
+
+        /// Only run the transaction against a validator and print its expected effects,
+        /// without signing or sending it
+        #[clap(long)]
+        dry_run: bool,
+
+        /// Publish the modules of the dependencies which are not on-chain yet, those with
+        /// 0x0 as their address, along with the package
+        #[clap(long)]
+        with_unpublished_dependencies: bool,
+
+        /// Publish without checking that the dependencies built locally are the packages
+        /// on-chain at their addresses
+        #[clap(long)]
+        skip_dependency_verification: bool,
+    },
+
+    /// Upgrade a package published in the active env to the version at `path`, with its
+    /// `UpgradeCap`. The new version is published at a new ID, recorded in the Move.lock of the
+    /// package for the packages depending on it to build against.
+    #[clap(name = "upgrade")]
+    Upgrade {
+        /// Path to directory containing the new version of a published Move package
+        #[clap(long)]
+        path: String,
+
+        /// ID of the `UpgradeCap` of the package. If not provided, the one recorded in the
+        /// Move.lock of the package, or one owned by the sender, is used
+        #[clap(long)]
+        upgrade_cap: Option<ObjectID>,
+
+        /// ID of the gas object for gas payment, in 20 bytes Hex string
+        /// If not provided, a gas object with at least gas_budget value will be selected
+        #[clap(long)]
+        gas: Option<ObjectID>,
+
+        /// Gas budget for the upgrade
+        #[clap(long)]
+        gas_budget: u64,
+
+        /// Upgrade without checking that the dependencies built locally are the packages
+        /// on-chain at their addresses
+        #[clap(long)]
+        skip_dependency_verification: bool,
     },
 
     /// Call Move function
@@ -131,14 +222,19 @@ pub enum WalletCommands {
         /// Gas budget for this call
         #[clap(long)]
         gas_budget: u64,
+
+        /// Only run the call against a validator and print its expected effects, without
+        /// signing or sending it
+        #[clap(long)]
+        dry_run: bool,
     },
 
     /// Transfer coin object
     #[clap(name = "transfer-coin")]
     Transfer {
-        /// Recipient address
+        /// Recipient address, or its alias
         #[clap(long)]
-        to: SuiAddress,
+        to: AddressOrAlias,
 
         /// Coin to transfer, in 20 bytes Hex string
         #[clap(long)]
@@ -153,6 +249,34 @@ pub enum WalletCommands {
         #[clap(long)]
         gas_budget: u64,
     },
+
+    /// Move every object an address owns to another address, e.g. when the key of the first
+    /// one is compromised. Coins are transferred in batches, and the gas object last. Objects
+    /// which only their module can transfer are listed for their modules to move. Running the
+    /// command again resumes a migration which stopped partway.
+    #[clap(name = "migrate-objects")]
+    MigrateObjects {
+        /// Address owning the objects, or its alias. Defaults to the active address
+        #[clap(long)]
+        from: Option<AddressOrAlias>,
+
+        /// The new address, or its alias
+        #[clap(long)]
+        to: AddressOrAlias,
+
+        /// ID of the gas object for gas payment, in 20 bytes Hex string
+        /// If not provided, the gas object with the largest value will be selected
+        #[clap(long)]
+        gas: Option<ObjectID>,
+
+        /// Gas budget for each transaction of the migration
+        #[clap(long, default_value = "10000")]
+        gas_budget: u64,
+
+        /// The number of coins each transaction transfers
+        #[clap(long, default_value = "50")]
+        batch_size: usize,
+    },
     /// Synchronize client state with authorities.
     #[clap(name = "sync")]
     SyncClientState {
@@ -166,22 +290,29 @@ pub enum WalletCommands {
 
     /// Generate new address and keypair.
     #[clap(name = "new-address")]
-    NewAddress,
+    NewAddress {
+        /// Signature scheme of the new key: ed25519, secp256k1 or secp256r1
+        #[clap(long, default_value = "ed25519")]
+        key_scheme: SignatureScheme,
+        /// A name for the new address, which commands accept in its place.
+        #[clap(long)]
+        alias: Option<String>,
+    },
 
     /// Obtain all objects owned by the address.
     #[clap(name = "objects")]
     Objects {
-        /// Address owning the objects
+        /// Address owning the objects, or its alias
         #[clap(long)]
-        address: Option<SuiAddress>,
+        address: Option<AddressOrAlias>,
     },
 
     /// Obtain all gas objects owned by the address.
     #[clap(name = "gas")]
     Gas {
-        /// Address owning the objects
+        /// Address owning the objects, or its alias
         #[clap(long)]
-        address: Option<SuiAddress>,
+        address: Option<AddressOrAlias>,
     },
 
     /// Split a coin object into multiple coins.
@@ -247,6 +378,347 @@ pub enum WalletCommands {
         #[clap(long)]
         gas_budget: Option<u64>,
     },
+
+    /// Report a validator as unresponsive or serving invalid data, with the active address as
+    /// the reporting validator. A validator reported by validators with a quorum of stake gets
+    /// no reward for the epoch. Reports only hold for the epoch they are made in.
+    #[clap(name = "report-validator")]
+    ReportValidator {
+        /// The reported validator, or its alias
+        #[clap(long)]
+        validator: AddressOrAlias,
+
+        /// Take back an earlier report about the validator instead
+        #[clap(long)]
+        clear: bool,
+
+        /// ID of the gas object for gas payment, in 20 bytes Hex string
+        /// If not provided, a gas object with at least gas_budget value will be selected
+        #[clap(long)]
+        gas: Option<ObjectID>,
+
+        /// Gas budget for this call
+        #[clap(long, default_value = "1000")]
+        gas_budget: u64,
+    },
+
+    /// Vote, with the active address as the voting validator, for upgrading the framework packages
+    /// at the end of the epoch to the ones this build of the CLI carries. The upgrade voted for by
+    /// validators with a quorum of stake is applied by the epoch change, if the validators find
+    /// it compatible with the framework on chain. Votes only hold for the epoch they are made in.
+    #[clap(name = "vote-framework-upgrade")]
+    VoteFrameworkUpgrade {
+        /// The protocol version the upgraded framework runs under, the latest one this build
+        /// supports if not provided
+        #[clap(long)]
+        protocol_version: Option<u64>,
+
+        /// Take back an earlier vote instead
+        #[clap(long)]
+        clear: bool,
+
+        /// ID of the gas object for gas payment, in 20 bytes Hex string
+        /// If not provided, a gas object with at least gas_budget value will be selected
+        #[clap(long)]
+        gas: Option<ObjectID>,
+
+        /// Gas budget for this call
+        #[clap(long, default_value = "1000")]
+        gas_budget: u64,
+    },
+
+    /// Build a transaction without signing or executing it, and print its base64 encoded
+    /// `TransactionData`. The bytes can be signed elsewhere, e.g. with `sui signtool` on an
+    /// air-gapped machine, and submitted with `execute-signed-tx`.
+    #[clap(name = "serialize-tx")]
+    SerializeTx {
+        #[clap(subcommand)]
+        tx: TransactionCommand,
+    },
+
+    /// Execute a transaction signed elsewhere
+    #[clap(name = "execute-signed-tx")]
+    ExecuteSignedTx {
+        /// Base64 encoded `TransactionData`, as printed by `serialize-tx`
+        #[clap(long)]
+        tx_bytes: String,
+
+        /// Base64 encoded signature of the transaction bytes, followed by the public key of
+        /// the signer, or a multisig as printed by `multisig-combine-partial-sig`
+        #[clap(long)]
+        signature: String,
+    },
+
+    /// Derive the address of a multisig account from its public keys, their weights and the
+    /// threshold the weights of the signers of a transaction must add up to.
+    #[clap(name = "multisig-address")]
+    MultiSigAddress {
+        /// Base64 encoded public keys of the members of the multisig, as printed by
+        /// `sui signtool`: ed25519 keys, or ECDSA keys prefixed with the flag of their scheme
+        #[clap(
+            long,
+            multiple_occurrences = false,
+            multiple_values = true,
+            required = true
+        )]
+        pks: Vec<String>,
+        /// Weight of each public key, in the same order
+        #[clap(
+            long,
+            multiple_occurrences = false,
+            multiple_values = true,
+            required = true
+        )]
+        weights: Vec<WeightUnit>,
+        #[clap(long)]
+        threshold: ThresholdUnit,
+    },
+
+    /// Combine the signatures of some of the members of a multisig over the same transaction
+    /// into a multisig accepted by `execute-signed-tx`.
+    #[clap(name = "multisig-combine-partial-sig")]
+    MultiSigCombinePartialSig {
+        /// Base64 encoded public keys of the members of the multisig, as printed by
+        /// `sui signtool`: ed25519 keys, or ECDSA keys prefixed with the flag of their scheme
+        #[clap(
+            long,
+            multiple_occurrences = false,
+            multiple_values = true,
+            required = true
+        )]
+        pks: Vec<String>,
+        /// Weight of each public key, in the same order
+        #[clap(
+            long,
+            multiple_occurrences = false,
+            multiple_values = true,
+            required = true
+        )]
+        weights: Vec<WeightUnit>,
+        #[clap(long)]
+        threshold: ThresholdUnit,
+        /// Base64 encoded signatures followed by public keys, as printed by `sui signtool`
+        #[clap(
+            long,
+            multiple_occurrences = false,
+            multiple_values = true,
+            required = true
+        )]
+        sigs: Vec<String>,
+    },
+}
+
+/// The transactions which can be built by `serialize-tx`. The arguments are the same as for
+/// the wallet commands of the same names.
+#[derive(Subcommand, Debug)]
+#[clap(rename_all = "kebab-case")]
+pub enum TransactionCommand {
+    /// Publish Move modules
+    #[clap(name = "publish")]
+    Publish {
+        /// Path to directory containing a Move package
+        #[clap(long)]
+        path: String,
+
+        /// ID of the gas object for gas payment, in 20 bytes Hex string
+        /// If not provided, a gas object with at least gas_budget value will be selected
+        #[clap(long)]
+        gas: Option<ObjectID>,
+
+        /// Gas budget for running module initializers
+        #[clap(long)]
+        gas_budget: u64,
+
+        /// Publish the modules of the dependencies which are not on-chain yet along with the
+        /// package
+        #[clap(long)]
+        with_unpublished_dependencies: bool,
+
+        /// Publish without checking that the dependencies are the packages on-chain
+        #[clap(long)]
+        skip_dependency_verification: bool,
+    },
+
+    /// Call Move function
+    #[clap(name = "call")]
+    Call {
+        /// Object ID of the package, which contains the module
+        #[clap(long)]
+        package: ObjectID,
+        /// The name of the module in the package
+        #[clap(long)]
+        module: String,
+        /// Function name in module
+        #[clap(long)]
+        function: String,
+        /// Type arguments of the function
+        #[clap(
+        long,
+        parse(try_from_str = parse_type_tag),
+        multiple_occurrences = false,
+        multiple_values = true
+        )]
+        type_args: Vec<TypeTag>,
+        /// Simplified ordered args like in the function syntax
+        /// ObjectIDs, Addresses must be hex strings
+        #[clap(long, multiple_occurrences = false, multiple_values = true)]
+        args: Vec<SuiJsonValue>,
+        /// ID of the gas object for gas payment, in 20 bytes Hex string
+        /// If not provided, a gas object with at least gas_budget value will be selected
+        #[clap(long)]
+        gas: Option<ObjectID>,
+        /// Gas budget for this call
+        #[clap(long)]
+        gas_budget: u64,
+    },
+
+    /// Transfer coin object
+    #[clap(name = "transfer-coin")]
+    Transfer {
+        /// Recipient address, or its alias
+        #[clap(long)]
+        to: AddressOrAlias,
+
+        /// Coin to transfer, in 20 bytes Hex string
+        #[clap(long)]
+        coin_object_id: ObjectID,
+
+        /// ID of the gas object for gas payment, in 20 bytes Hex string
+        /// If not provided, a gas object with at least gas_budget value will be selected
+        #[clap(long)]
+        gas: Option<ObjectID>,
+
+        /// Gas budget for this transfer
+        #[clap(long)]
+        gas_budget: u64,
+    },
+
+    /// Split a coin object into multiple coins.
+    SplitCoin {
+        /// Coin to Split, in 20 bytes Hex string
+        #[clap(long)]
+        coin_id: ObjectID,
+        /// Amount to split out from the coin
+        #[clap(
+            long,
+            multiple_occurrences = false,
+            multiple_values = true,
+            required = true
+        )]
+        amounts: Vec<u64>,
+        /// ID of the gas object for gas payment, in 20 bytes Hex string
+        /// If not provided, a gas object with at least gas_budget value will be selected
+        #[clap(long)]
+        gas: Option<ObjectID>,
+        /// Gas budget for this call
+        #[clap(long)]
+        gas_budget: u64,
+    },
+
+    /// Merge two coin objects into one coin
+    MergeCoin {
+        /// Coin to merge into, in 20 bytes Hex string
+        #[clap(long)]
+        primary_coin: ObjectID,
+        /// Coin to be merged, in 20 bytes Hex string
+        #[clap(long)]
+        coin_to_merge: ObjectID,
+        /// ID of the gas object for gas payment, in 20 bytes Hex string
+        /// If not provided, a gas object with at least gas_budget value will be selected
+        #[clap(long)]
+        gas: Option<ObjectID>,
+        /// Gas budget for this call
+        #[clap(long)]
+        gas_budget: u64,
+    },
+}
+
+impl TransactionCommand {
+    /// Build the unsigned transaction. The signer is the owner of the object being operated
+    /// on, or of the gas object, falling back to the active address.
+    pub async fn build(
+        self,
+        context: &mut WalletContext,
+    ) -> Result<TransactionData, anyhow::Error> {
+        Ok(match self {
+            TransactionCommand::Publish {
+                path,
+                gas,
+                gas_budget,
+                with_unpublished_dependencies,
+                skip_dependency_verification,
+            } => {
+                let sender = context.try_get_object_owner(&gas).await?;
+                let sender = sender.unwrap_or(context.active_address()?);
+                let compiled_modules = context
+                    .build_package_for_publish(
+                        Path::new(&path),
+                        with_unpublished_dependencies,
+                        skip_dependency_verification,
+                    )
+                    .await?;
+                context
+                    .gateway
+                    .publish(sender, compiled_modules, gas, gas_budget)
+                    .await?
+            }
+            TransactionCommand::Call {
+                package,
+                module,
+                function,
+                type_args,
+                args,
+                gas,
+                gas_budget,
+            } => {
+                let sender = context.try_get_object_owner(&gas).await?;
+                let sender = sender.unwrap_or(context.active_address()?);
+                context
+                    .gateway
+                    .move_call(
+                        sender, package, module, function, type_args, args, gas, gas_budget,
+                    )
+                    .await?
+            }
+            TransactionCommand::Transfer {
+                to,
+                coin_object_id,
+                gas,
+                gas_budget,
+            } => {
+                let to = context.resolve_address(&to).await?;
+                let from = context.get_object_owner(&coin_object_id).await?;
+                context
+                    .gateway
+                    .transfer_coin(from, coin_object_id, gas, gas_budget, to)
+                    .await?
+            }
+            TransactionCommand::SplitCoin {
+                coin_id,
+                amounts,
+                gas,
+                gas_budget,
+            } => {
+                let signer = context.get_object_owner(&coin_id).await?;
+                context
+                    .gateway
+                    .split_coin(signer, coin_id, amounts, gas, gas_budget)
+                    .await?
+            }
+            TransactionCommand::MergeCoin {
+                primary_coin,
+                coin_to_merge,
+                gas,
+                gas_budget,
+            } => {
+                let signer = context.get_object_owner(&primary_coin).await?;
+                context
+                    .gateway
+                    .merge_coins(signer, primary_coin, coin_to_merge, gas, gas_budget)
+                    .await?
+            }
+        })
+    }
 }
 
 impl WalletCommands {
@@ -259,25 +731,130 @@ impl WalletCommands {
                 path,
                 gas,
                 gas_budget,
+                dry_run,
+                with_unpublished_dependencies,
+                skip_dependency_verification,
             } => {
                 let sender = context.try_get_object_owner(&gas).await?;
                 let sender = sender.unwrap_or(context.active_address()?);
 
-                let compiled_modules = build_move_package_to_bytes(Path::new(&path), false)?;
+                let compiled_modules = context
+                    .build_package_for_publish(
+                        Path::new(&path),
+                        with_unpublished_dependencies,
+                        skip_dependency_verification,
+                    )
+                    .await?;
                 let data = context
                     .gateway
                     .publish(sender, compiled_modules, gas, gas_budget)
                     .await?;
-                let signature = context.keystore.sign(&sender, &data.to_bytes())?;
+                if dry_run {
+                    let effects = context.gateway.dry_run_transaction(data).await?;
+                    return Ok(WalletCommandResult::DryRun(effects));
+                }
+                context.dry_run_before_sending(&data).await?;
+                let signature = context.keystore.sign(&sender, &data.to_intent_bytes())?;
                 let response = context
                     .gateway
                     .execute_transaction(Transaction::new(data, signature))
                     .await?
                     .to_publish_response()?;
+                if let Some(env) = &context.config.active_env {
+                    let upgrade_cap_type = UpgradeCap::type_().to_string();
+                    let upgrade_cap = response
+                        .created_objects
+                        .iter()
+                        .find(|o| matches!(o.data.type_(), Some(t) if *t == upgrade_cap_type))
+                        .map(|o| o.id());
+                    MoveLock::record_publish(
+                        Path::new(&path),
+                        env,
+                        response.package.object_id,
+                        upgrade_cap,
+                    )?;
+                }
 
                 WalletCommandResult::Publish(response)
             }
 
+            WalletCommands::Upgrade {
+                path,
+                upgrade_cap,
+                gas,
+                gas_budget,
+                skip_dependency_verification,
+            } => {
+                let path = Path::new(&path);
+                let env = context.config.active_env.clone().ok_or_else(|| {
+                    anyhow!("Packages are upgraded in the active env, which is not set")
+                })?;
+                let published = MoveLock::read(path)?.env.remove(&env).ok_or_else(|| {
+                    anyhow!(
+                        "Package at {} is not published in env {env}",
+                        path.display()
+                    )
+                })?;
+                let package = published.latest_id();
+                let sender = context.active_address()?;
+                let (upgrade_cap, cap) = context
+                    .find_upgrade_cap(sender, package, upgrade_cap.or(published.upgrade_cap))
+                    .await?;
+
+                let modules = context
+                    .build_package_for_publish(path, false, skip_dependency_verification)
+                    .await?;
+                context
+                    .check_upgrade_compatibility(package, &modules)
+                    .await?;
+
+                let gas = match gas {
+                    Some(gas) => context.gateway.get_object(gas).await?.into_object()?,
+                    None => {
+                        context
+                            .gas_for_owner_budget(sender, gas_budget, BTreeSet::from([*cap.id()]))
+                            .await?
+                            .1
+                    }
+                };
+                let data = TransactionData::new_upgrade(
+                    sender,
+                    gas.reference.to_object_ref(),
+                    package,
+                    upgrade_cap,
+                    modules,
+                    gas_budget,
+                );
+                context.dry_run_before_sending(&data).await?;
+                let signature = context.keystore.sign(&sender, &data.to_intent_bytes())?;
+                let (certificate, effects) = context
+                    .gateway
+                    .execute_transaction(Transaction::new(data, signature))
+                    .await?
+                    .to_effect_response()?
+                    .into_certificate_and_effects()?;
+                if let SuiExecutionStatus::Failure { error, .. } = &effects.status {
+                    return Err(anyhow!("Upgrade of package {package} failed: {error}"));
+                }
+                let upgraded = effects
+                    .created
+                    .iter()
+                    .find(|o| o.owner == Owner::Immutable)
+                    .map(|o| o.reference.object_id)
+                    .ok_or_else(|| {
+                        anyhow!("The upgrade of package {package} created no package")
+                    })?;
+                MoveLock::record_upgrade(path, &env, upgraded, *cap.id())?;
+
+                WalletCommandResult::Upgrade(UpgradeResponse {
+                    previous_package: package,
+                    package: upgraded,
+                    upgrade_cap: *cap.id(),
+                    certificate,
+                    effects,
+                })
+            }
+
             WalletCommands::Object { id } => {
                 // Fetch the object ref
                 let object_read = context.gateway.get_object(id).await?;
@@ -291,7 +868,16 @@ impl WalletCommands {
                 gas,
                 gas_budget,
                 args,
+                dry_run,
             } => {
+                if dry_run {
+                    let (_, data) = move_call_data(
+                        package, &module, &function, type_args, gas, gas_budget, args, context,
+                    )
+                    .await?;
+                    let effects = context.gateway.dry_run_transaction(data).await?;
+                    return Ok(WalletCommandResult::DryRun(effects));
+                }
                 let (cert, effects) = call_move(
                     package, &module, &function, type_args, gas, gas_budget, args, context,
                 )
@@ -305,6 +891,7 @@ impl WalletCommands {
                 gas,
                 gas_budget,
             } => {
+                let to = context.resolve_address(&to).await?;
                 let from = context.get_object_owner(&object_id).await?;
                 let time_start = Instant::now();
 
@@ -312,14 +899,14 @@ impl WalletCommands {
                     .gateway
                     .transfer_coin(from, object_id, gas, gas_budget, to)
                     .await?;
-                let signature = context.keystore.sign(&from, &data.to_bytes())?;
-                let response = context
+                context.dry_run_before_sending(&data).await?;
+                let signature = context.keystore.sign(&from, &data.to_intent_bytes())?;
+                let (cert, effects) = context
                     .gateway
                     .execute_transaction(Transaction::new(data, signature))
                     .await?
-                    .to_effect_response()?;
-                let cert = response.certificate;
-                let effects = response.effects;
+                    .to_effect_response()?
+                    .into_certificate_and_effects()?;
 
                 let time_total = time_start.elapsed().as_micros();
                 if matches!(effects.status, SuiExecutionStatus::Failure { .. }) {
@@ -328,12 +915,93 @@ impl WalletCommands {
                 WalletCommandResult::Transfer(time_total, cert, effects)
             }
 
-            WalletCommands::Addresses => {
-                WalletCommandResult::Addresses(context.config.accounts.clone())
+            WalletCommands::MigrateObjects {
+                from,
+                to,
+                gas,
+                gas_budget,
+                batch_size,
+            } => {
+                if batch_size == 0 {
+                    return Err(anyhow!("The batch size must be at least 1"));
+                }
+                let from = context.resolve_or_active_address(from).await?;
+                let to = context.resolve_address(&to).await?;
+                if from == to {
+                    return Err(anyhow!("Objects of {from} can't be migrated to itself"));
+                }
+                let gas = match gas {
+                    Some(gas) => context.gateway.get_object(gas).await?.into_object()?,
+                    None => {
+                        context
+                            .gas_objects(from)
+                            .await?
+                            .into_iter()
+                            .max_by_key(|(value, _)| *value)
+                            .ok_or_else(|| anyhow!("No gas objects owned by {from}"))?
+                            .1
+                    }
+                };
+                if gas.owner != Owner::AddressOwner(from) {
+                    return Err(anyhow!("Gas object {} is not owned by {from}", gas.id()));
+                }
+
+                let (coins, skipped): (Vec<_>, Vec<_>) = context
+                    .gateway
+                    .get_objects_owned_by_address(from)
+                    .await?
+                    .into_iter()
+                    .filter(|object| object.object_id != gas.id())
+                    .partition(is_coin);
+                let mut migration = MigrateObjectsResponse {
+                    from,
+                    to,
+                    migrated: vec![],
+                    transactions: vec![],
+                    skipped,
+                };
+                let total = coins.len() + 1;
+                let mut gas_ref = gas.reference.to_object_ref();
+                for batch in coins.chunks(batch_size) {
+                    let transfers = batch
+                        .iter()
+                        .map(|coin| {
+                            SingleTransactionKind::TransferCoin(TransferCoin {
+                                recipient: to,
+                                object_ref: (coin.object_id, coin.version, coin.digest),
+                            })
+                        })
+                        .collect();
+                    let data = TransactionData::new(
+                        TransactionKind::Batch(transfers),
+                        from,
+                        gas_ref,
+                        gas_budget,
+                    );
+                    let effects = context.execute_migration(data, &migration, total).await?;
+                    gas_ref = effects.gas_object.reference.to_object_ref();
+                    migration.transactions.push(effects.transaction_digest);
+                    migration
+                        .migrated
+                        .extend(batch.iter().map(|coin| coin.object_id));
+                    eprintln!("Migrated {} of {total} objects", migration.migrated.len());
+                }
+
+                // The gas object pays for its own transfer.
+                let data = TransactionData::new_transfer_sui(to, from, None, gas_ref, gas_budget);
+                let effects = context.execute_migration(data, &migration, total).await?;
+                migration.transactions.push(effects.transaction_digest);
+                migration.migrated.push(gas_ref.0);
+                WalletCommandResult::MigrateObjects(migration)
             }
 
+            WalletCommands::Addresses => WalletCommandResult::Addresses(AddressesResponse {
+                addresses: context.config.accounts.clone(),
+                aliases: context.config.aliases.clone(),
+            }),
+
             WalletCommands::Objects { address } => {
-                let address = address.unwrap_or(context.active_address()?);
+                let address = context.resolve_or_active_address(address).await?;
                 let mut address_object = context
                     .gateway
                     .get_objects_owned_by_address(address)
@@ -352,14 +1020,20 @@ impl WalletCommands {
                 context.gateway.sync_account_state(address).await?;
                 WalletCommandResult::SyncClientState
             }
-            WalletCommands::NewAddress => {
-                let address = context.keystore.add_random_key()?;
-                context.config.accounts.push(address);
+            WalletCommands::NewAddress { key_scheme, alias } => {
+                let address = context.keystore.add_random_key_with_scheme(key_scheme)?;
+                // A Ledger gives the same address again.
+                if !context.config.accounts.contains(&address) {
+                    context.config.accounts.push(address);
+                }
+                if let Some(alias) = alias {
+                    context.config.set_alias(address, alias)?;
+                }
                 context.config.save()?;
                 WalletCommandResult::NewAddress(address)
             }
             WalletCommands::Gas { address } => {
-                let address = address.unwrap_or(context.active_address()?);
+                let address = context.resolve_or_active_address(address).await?;
                 let coins = context
                     .gas_objects(address)
                     .await?
@@ -380,7 +1054,8 @@ impl WalletCommands {
                     .gateway
                     .split_coin(signer, coin_id, amounts, gas, gas_budget)
                     .await?;
-                let signature = context.keystore.sign(&signer, &data.to_bytes())?;
+                context.dry_run_before_sending(&data).await?;
+                let signature = context.keystore.sign(&signer, &data.to_intent_bytes())?;
                 let response = context
                     .gateway
                     .execute_transaction(Transaction::new(data, signature))
@@ -399,7 +1074,8 @@ impl WalletCommands {
                     .gateway
                     .merge_coins(signer, primary_coin, coin_to_merge, gas, gas_budget)
                     .await?;
-                let signature = context.keystore.sign(&signer, &data.to_bytes())?;
+                context.dry_run_before_sending(&data).await?;
+                let signature = context.keystore.sign(&signer, &data.to_intent_bytes())?;
                 let response = context
                     .gateway
                     .execute_transaction(Transaction::new(data, signature))
@@ -408,29 +1084,75 @@ impl WalletCommands {
 
                 WalletCommandResult::MergeCoin(response)
             }
-            WalletCommands::Switch { address, gateway } => {
-                if let Some(addr) = address {
-                    if !context.config.accounts.contains(&addr) {
-                        return Err(anyhow!("Address {} not managed by wallet", addr));
-                    }
-                    context.config.active_address = Some(addr);
-                    context.config.save()?;
+            WalletCommands::Switch {
+                address,
+                gateway,
+                env,
+            } => {
+                if address.is_none() && gateway.is_none() && env.is_none() {
+                    return Err(anyhow!(
+                        "No address, gateway or env specified. Please Specify one."
+                    ));
                 }
 
+                let address = match address {
+                    Some(address) => {
+                        let addr = context.resolve_address(&address).await?;
+                        if !context.config.accounts.contains(&addr) {
+                            return Err(anyhow!("Address {} not managed by wallet", addr));
+                        }
+                        context.config.active_address = Some(addr);
+                        context.config.save()?;
+                        Some(addr)
+                    }
+                    None => None,
+                };
+
                 if let Some(gateway) = &gateway {
                     // TODO: handle embedded gateway
                     context.config.gateway = GatewayType::RPC(gateway.clone());
+                    context.config.active_env = None;
                     context.config.save()?;
                 }
 
-                if Option::is_none(&address) && Option::is_none(&gateway) {
-                    return Err(anyhow!(
-                        "No address or gateway specified. Please Specify one."
-                    ));
+                if let Some(alias) = &env {
+                    let rpc = context
+                        .config
+                        .get_env(alias)
+                        .ok_or_else(|| {
+                            anyhow!("Env {alias} doesn't exist, add it with the new-env command")
+                        })?
+                        .rpc
+                        .clone();
+                    context.config.gateway = GatewayType::RPC(rpc);
+                    context.config.active_env = Some(alias.clone());
+                    context.config.save()?;
                 }
 
-                WalletCommandResult::Switch(SwitchResponse { address, gateway })
+                WalletCommandResult::Switch(SwitchResponse {
+                    address,
+                    gateway,
+                    env,
+                })
             }
+            WalletCommands::Alias { address, alias } => {
+                let address = context.resolve_address(&address).await?;
+                context.config.set_alias(address, alias.clone())?;
+                context.config.save()?;
+                WalletCommandResult::Alias(address, alias)
+            }
+            WalletCommands::NewEnv { alias, rpc } => {
+                // Check url is valid
+                HttpClientBuilder::default().build(&rpc)?;
+                let env = WalletEnv { alias, rpc };
+                context.config.add_env(env.clone())?;
+                context.config.save()?;
+                WalletCommandResult::NewEnv(env)
+            }
+            WalletCommands::Envs => WalletCommandResult::Envs(
+                context.config.envs.clone(),
+                context.config.active_env.clone(),
+            ),
             WalletCommands::ActiveAddress {} => {
                 WalletCommandResult::ActiveAddress(context.active_address().ok())
             }
@@ -470,6 +1192,114 @@ impl WalletCommands {
                 let object_read = context.gateway.get_object(nft_id).await?;
                 WalletCommandResult::CreateExampleNFT(object_read)
             }
+            WalletCommands::ReportValidator {
+                validator,
+                clear,
+                gas,
+                gas_budget,
+            } => {
+                let validator = context.resolve_address(&validator).await?;
+                let function = if clear {
+                    "clear_validator_report"
+                } else {
+                    "report_validator"
+                };
+                let args = vec![
+                    SuiJsonValue::new(json!(SUI_SYSTEM_STATE_OBJECT_ID.to_string()))?,
+                    SuiJsonValue::new(json!(validator.to_string()))?,
+                ];
+                let (cert, effects) = call_move(
+                    ObjectID::from(SUI_FRAMEWORK_ADDRESS),
+                    "SuiSystem",
+                    function,
+                    vec![],
+                    gas,
+                    gas_budget,
+                    args,
+                    context,
+                )
+                .await?;
+                WalletCommandResult::Call(cert, effects)
+            }
+            WalletCommands::VoteFrameworkUpgrade {
+                protocol_version,
+                clear,
+                gas,
+                gas_budget,
+            } => {
+                let mut args = vec![SuiJsonValue::new(json!(
+                    SUI_SYSTEM_STATE_OBJECT_ID.to_string()
+                ))?];
+                let function = if clear {
+                    "clear_framework_upgrade_vote"
+                } else {
+                    let digest = framework_digest(&sui_framework::get_system_packages());
+                    let protocol_version =
+                        protocol_version.unwrap_or_else(|| ProtocolVersion::MAX.as_u64());
+                    args.push(SuiJsonValue::new(json!(format!(
+                        "0x{}",
+                        hex::encode(digest)
+                    )))?);
+                    args.push(SuiJsonValue::new(json!(protocol_version))?);
+                    "vote_framework_upgrade"
+                };
+                let (cert, effects) = call_move(
+                    ObjectID::from(SUI_FRAMEWORK_ADDRESS),
+                    "SuiSystem",
+                    function,
+                    vec![],
+                    gas,
+                    gas_budget,
+                    args,
+                    context,
+                )
+                .await?;
+                WalletCommandResult::Call(cert, effects)
+            }
+            WalletCommands::SerializeTx { tx } => {
+                let data = tx.build(context).await?;
+                WalletCommandResult::SerializeTx(Base64::from_bytes(&data.to_bytes()))
+            }
+            WalletCommands::ExecuteSignedTx {
+                tx_bytes,
+                signature,
+            } => {
+                let data = TransactionData::from_signable_bytes(&Base64::decode(&tx_bytes)?)?;
+                let signature = GenericSignature::from_bytes(&Base64::decode(&signature)?)?;
+                let transaction = Transaction::new(data, signature);
+                transaction.verify_signature()?;
+                let (certificate, effects) = context
+                    .gateway
+                    .execute_transaction(transaction)
+                    .await?
+                    .to_effect_response()?
+                    .into_certificate_and_effects()?;
+                WalletCommandResult::ExecuteSignedTx(certificate, effects)
+            }
+            WalletCommands::MultiSigAddress {
+                pks,
+                weights,
+                threshold,
+            } => {
+                let multisig_pk = parse_multisig_pk(pks, weights, threshold)?;
+                WalletCommandResult::MultiSigAddress(multisig_pk.address())
+            }
+            WalletCommands::MultiSigCombinePartialSig {
+                pks,
+                weights,
+                threshold,
+                sigs,
+            } => {
+                let multisig_pk = parse_multisig_pk(pks, weights, threshold)?;
+                let sigs = sigs
+                    .iter()
+                    .map(|sig| Ok(GenericSignature::from_bytes(&Base64::decode(sig)?)?))
+                    .collect::<Result<Vec<_>, anyhow::Error>>()?;
+                let multisig = GenericSignature::from(MultiSig::combine(sigs, multisig_pk)?);
+                WalletCommandResult::MultiSigCombinePartialSig(Base64::from_bytes(
+                    &multisig.to_bytes(),
+                ))
+            }
         });
         ret
     }
@@ -479,6 +1309,9 @@ pub struct WalletContext {
     pub config: PersistedConfig<WalletConfig>,
     pub keystore: Box<dyn Keystore>,
     pub gateway: GatewayClient,
+    /// Print the dry run of each transaction and ask whether to send it, as the wallet does on
+    /// a terminal. Off by default, for scripts and tests.
+    pub confirm_before_sending: bool,
 }
 
 impl WalletContext {
@@ -496,9 +1329,60 @@ impl WalletContext {
             config,
             keystore,
             gateway,
+            confirm_before_sending: false,
         };
         Ok(context)
     }
+
+    /// Sign for the address of the Ledger key at `derivation_path` with the device, and for
+    /// the other addresses with the keystore of the config. Returns the Ledger address.
+    pub fn use_ledger(
+        &mut self,
+        derivation_path: Option<DerivationPath>,
+    ) -> Result<SuiAddress, anyhow::Error> {
+        let keystore = std::mem::replace(&mut self.keystore, Box::new(SuiKeystore::default()));
+        let ledger = LedgerKeystore::connect(derivation_path, keystore)?;
+        let address = ledger.address();
+        self.keystore = Box::new(ledger);
+        Ok(address)
+    }
+
+    /// The address given by `address`, as hex, as its alias, or as a name the name service of
+    /// the gateway resolves, which must then be a full node.
+    pub async fn resolve_address(
+        &self,
+        address: &AddressOrAlias,
+    ) -> Result<SuiAddress, anyhow::Error> {
+        let name = match address {
+            AddressOrAlias::Name(name) => name,
+            _ => return self.config.resolve_address(address),
+        };
+        let url = match &self.config.gateway {
+            GatewayType::RPC(url) => url,
+            GatewayType::Embedded(_) => {
+                return Err(anyhow!(
+                    "Name {name} can only be resolved by the name service of a full node RPC"
+                ))
+            }
+        };
+        let client = HttpClientBuilder::default().build(url)?;
+        client
+            .resolve_name_service_address(name.clone())
+            .await?
+            .ok_or_else(|| anyhow!("Name {name} is not registered, or has expired"))
+    }
+
+    /// The address given by `address`, as for `resolve_address`, defaulting to the active one.
+    pub async fn resolve_or_active_address(
+        &mut self,
+        address: Option<AddressOrAlias>,
+    ) -> Result<SuiAddress, anyhow::Error> {
+        match address {
+            Some(address) => self.resolve_address(&address).await,
+            None => self.active_address(),
+        }
+    }
+
     pub fn active_address(&mut self) -> Result<SuiAddress, anyhow::Error> {
         if self.config.accounts.is_empty() {
             return Err(anyhow!(
@@ -558,6 +1442,235 @@ impl WalletContext {
         }
     }
 
+    /// Run `data` against a validator before it is signed and sent, so that a transaction
+    /// which would fail doesn't spend gas, and with `confirm_before_sending`, ask whether to
+    /// send it after printing its expected gas and effects. A gateway without dry runs only
+    /// gets a warning.
+    pub async fn dry_run_before_sending(
+        &self,
+        data: &TransactionData,
+    ) -> Result<(), anyhow::Error> {
+        match self.gateway.dry_run_transaction(data.clone()).await {
+            Ok(effects) => {
+                if let Some(error) = effects.status.error_message() {
+                    return Err(anyhow!("Dry run failed, nothing was sent: {error}"));
+                }
+                if self.confirm_before_sending {
+                    println!("{}", WalletCommandResult::DryRun(effects));
+                    print!("Send the transaction [Yn]? ");
+                    io::stdout().flush()?;
+                    let mut answer = String::new();
+                    io::stdin().read_line(&mut answer)?;
+                    if answer.trim().eq_ignore_ascii_case("n") {
+                        return Err(anyhow!("Transaction not sent"));
+                    }
+                }
+                Ok(())
+            }
+            Err(err) => {
+                warn!("Sending the transaction without a dry run: {err}");
+                Ok(())
+            }
+        }
+    }
+
+    /// Build the package at `path` for publishing in the active env, with the addresses of its
+    /// dependencies from their lockfiles, and check that the modules of each package it depends
+    /// on are the ones on-chain at the address of the package, unless
+    /// `skip_dependency_verification` is set.
+    pub async fn build_package_for_publish(
+        &self,
+        path: &Path,
+        with_unpublished_dependencies: bool,
+        skip_dependency_verification: bool,
+    ) -> Result<Vec<Vec<u8>>, anyhow::Error> {
+        let build_config = BuildConfig {
+            additional_named_addresses: lockfile::named_addresses(
+                path,
+                self.config.active_env.as_deref(),
+                with_unpublished_dependencies,
+            )?,
+            ..Default::default()
+        };
+        let package = build_move_package_for_publish(
+            path,
+            build_config,
+            false,
+            with_unpublished_dependencies,
+        )?;
+        if !skip_dependency_verification {
+            for (address, modules) in &package.dependencies {
+                self.verify_dependency(ObjectID::from(*address), modules)
+                    .await?;
+            }
+        }
+        Ok(package
+            .modules
+            .iter()
+            .map(|module| {
+                let mut bytes = Vec::new();
+                module.serialize(&mut bytes).unwrap();
+                bytes
+            })
+            .collect())
+    }
+
+    async fn verify_dependency(
+        &self,
+        package_id: ObjectID,
+        modules: &[CompiledModule],
+    ) -> Result<(), anyhow::Error> {
+        let on_chain = match self.gateway.get_object(package_id).await? {
+            GetObjectDataResponse::Exists(SuiObject {
+                data: SuiData::Package(package),
+                ..
+            }) => package,
+            GetObjectDataResponse::Exists(_) => {
+                return Err(anyhow!("Dependency {package_id} is not a package"))
+            }
+            _ => {
+                return Err(anyhow!(
+                    "Dependency {package_id} is not on-chain. Publish it first, or along with the package with --with-unpublished-dependencies"
+                ))
+            }
+        };
+        let bytes: Vec<Vec<u8>> = modules
+            .iter()
+            .map(|module| {
+                let mut bytes = Vec::new();
+                module.serialize(&mut bytes).unwrap();
+                bytes
+            })
+            .collect();
+        for (name, local) in disassemble_modules(bytes.iter())? {
+            if on_chain.disassembled().get(&name) != Some(&local) {
+                return Err(anyhow!(
+                    "Module {name} of dependency {package_id} does not match the bytecode on-chain. Check the version of the dependency, or pass --skip-dependency-verification"
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// The reference and contents of the `UpgradeCap` of `package`, `upgrade_cap` if given,
+    /// otherwise the first one owned by `owner` whose latest version of its package is `package`.
+    async fn find_upgrade_cap(
+        &self,
+        owner: SuiAddress,
+        package: ObjectID,
+        upgrade_cap: Option<ObjectID>,
+    ) -> Result<(ObjectRef, UpgradeCap), anyhow::Error> {
+        let upgrade_cap_type = UpgradeCap::type_().to_string();
+        let candidates = match upgrade_cap {
+            Some(id) => vec![id],
+            None => self
+                .gateway
+                .get_objects_owned_by_address(owner)
+                .await?
+                .into_iter()
+                .filter(|o| o.type_ == upgrade_cap_type)
+                .map(|o| o.object_id)
+                .collect(),
+        };
+        for id in candidates {
+            let object = self.gateway.get_raw_object(id).await?.into_object()?;
+            let cap: UpgradeCap = match &object.data {
+                SuiData::MoveObject(o) if o.type_ == upgrade_cap_type => {
+                    bcs::from_bytes(&o.bcs_bytes)?
+                }
+                _ => return Err(anyhow!("Object {id} is not an UpgradeCap")),
+            };
+            if cap.package == package {
+                return Ok((object.reference.to_object_ref(), cap));
+            }
+            if upgrade_cap.is_some() {
+                return Err(anyhow!(
+                    "UpgradeCap {id} is for package {}, not {package}",
+                    cap.package
+                ));
+            }
+        }
+        Err(anyhow!(
+            "No UpgradeCap of package {package} is owned by {owner}. Pass it with --upgrade-cap"
+        ))
+    }
+
+    /// Check that the modules of `package` on-chain are kept by the new version `modules`, as
+    /// the validators do, so that an incompatible upgrade fails before it is sent. The bytecode
+    /// of the package is only served by full nodes, without which the check is left to the
+    /// validators.
+    async fn check_upgrade_compatibility(
+        &self,
+        package: ObjectID,
+        modules: &[Vec<u8>],
+    ) -> Result<(), anyhow::Error> {
+        let url = match &self.config.gateway {
+            GatewayType::RPC(url) => url,
+            GatewayType::Embedded(_) => {
+                warn!("Upgrading without checking compatibility, which needs a full node RPC");
+                return Ok(());
+            }
+        };
+        let fetch = async {
+            let client = HttpClientBuilder::default().build(url)?;
+            let bytes = client.get_raw_object_at_version(package, None).await?;
+            Ok::<Object, anyhow::Error>(bcs::from_bytes(&bytes.to_vec()?)?)
+        };
+        let object = match fetch.await {
+            Ok(object) => object,
+            Err(err) => {
+                warn!("Upgrading without checking compatibility: {err}");
+                return Ok(());
+            }
+        };
+        let on_chain = object
+            .data
+            .try_as_package()
+            .ok_or_else(|| anyhow!("Object {package} is not a package"))?;
+        let modules = modules
+            .iter()
+            .map(|bytes| CompiledModule::deserialize(bytes))
+            .collect::<Result<Vec<_>, _>>()?;
+        adapter::check_upgrade_compatibility(on_chain, &modules)
+            .map_err(|error| anyhow!("Package at the path is not an upgrade of {package}: {error}"))
+    }
+
+    /// Sign and execute a transaction of the migration of the objects of the sender, whose
+    /// progress the error reports if it fails.
+    async fn execute_migration(
+        &self,
+        data: TransactionData,
+        migration: &MigrateObjectsResponse,
+        total: usize,
+    ) -> Result<SuiTransactionEffects, anyhow::Error> {
+        let progress = || {
+            format!(
+                "Migrated {} of {total} objects of {} to {}. Run the command again to migrate the rest",
+                migration.migrated.len(),
+                migration.from,
+                migration.to
+            )
+        };
+        let signature = self
+            .keystore
+            .sign(&migration.from, &data.to_intent_bytes())?;
+        let effects = self
+            .gateway
+            .execute_transaction(Transaction::new(data, signature))
+            .await
+            .and_then(|response| {
+                Ok(response
+                    .to_effect_response()?
+                    .into_certificate_and_effects()?
+                    .1)
+            })
+            .map_err(|error| error.context(progress()))?;
+        if let SuiExecutionStatus::Failure { error, .. } = &effects.status {
+            return Err(anyhow!("{}: {error}", progress()));
+        }
+        Ok(effects)
+    }
+
     /// Find a gas object which fits the budget
     pub async fn gas_for_owner_budget(
         &self,
@@ -584,6 +1697,9 @@ impl Display for WalletCommandResult {
             WalletCommandResult::Publish(response) => {
                 write!(writer, "{}", response)?;
             }
+            WalletCommandResult::Upgrade(response) => {
+                write!(writer, "{}", response)?;
+            }
             WalletCommandResult::Object(object_read) => {
                 let object = unwrap_err_to_string(|| Ok(object_read.object()?));
                 writeln!(writer, "{}", object)?;
@@ -595,11 +1711,11 @@ impl Display for WalletCommandResult {
                 writeln!(writer, "Transfer confirmed after {} us", time_elapsed)?;
                 write!(writer, "{}", write_cert_and_effects(cert, effects)?)?;
             }
-            WalletCommandResult::Addresses(addresses) => {
-                writeln!(writer, "Showing {} results.", addresses.len())?;
-                for address in addresses {
-                    writeln!(writer, "{}", address)?;
-                }
+            WalletCommandResult::MigrateObjects(response) => {
+                write!(writer, "{}", response)?;
+            }
+            WalletCommandResult::Addresses(response) => {
+                write!(writer, "{}", response)?;
             }
             WalletCommandResult::Objects(object_refs) => {
                 writeln!(
@@ -614,6 +1730,7 @@ impl Display for WalletCommandResult {
                         Owner::ObjectOwner(_) => "ObjectOwner",
                         Owner::Shared => "Shared",
                         Owner::Immutable => "Immutable",
+                        Owner::Party(_) => "Party",
                     };
                     writeln!(
                         writer,
@@ -663,6 +1780,36 @@ impl Display for WalletCommandResult {
             WalletCommandResult::Switch(response) => {
                 write!(writer, "{}", response)?;
             }
+            WalletCommandResult::Alias(address, alias) => {
+                writeln!(writer, "Address {} is now aliased {}", address, alias)?;
+            }
+            WalletCommandResult::NewEnv(env) => {
+                writeln!(
+                    writer,
+                    "Added new env [{}] with gateway {}",
+                    env.alias, env.rpc
+                )?;
+            }
+            WalletCommandResult::Envs(envs, active) => {
+                writeln!(
+                    writer,
+                    " {0: ^20} | {1: ^50} | {2: ^6}",
+                    "Env", "Gateway URL", "Active"
+                )?;
+                writeln!(writer, "{}", ["-"; 84].join(""))?;
+                for env in envs {
+                    let marker = if active.as_ref() == Some(&env.alias) {
+                        "*"
+                    } else {
+                        ""
+                    };
+                    writeln!(
+                        writer,
+                        " {0: ^20} | {1: ^50} | {2: ^6}",
+                        env.alias, env.rpc, marker
+                    )?;
+                }
+            }
             WalletCommandResult::ActiveAddress(response) => {
                 match response {
                     Some(r) => write!(writer, "{}", r)?,
@@ -675,12 +1822,54 @@ impl Display for WalletCommandResult {
                 writeln!(writer, "{}\n", "Successfully created an ExampleNFT:".bold())?;
                 writeln!(writer, "{}", object)?;
             }
+            WalletCommandResult::SerializeTx(tx_bytes) => {
+                writeln!(writer, "Raw tx_bytes to sign: {}", tx_bytes.encoded())?;
+            }
+            WalletCommandResult::ExecuteSignedTx(cert, effects) => {
+                write!(writer, "{}", write_cert_and_effects(cert, effects)?)?;
+            }
+            WalletCommandResult::DryRun(effects) => {
+                let gas_cost = match &effects.status {
+                    SuiExecutionStatus::Success { gas_cost }
+                    | SuiExecutionStatus::Failure { gas_cost, .. } => gas_cost,
+                };
+                writeln!(writer, "{}", "----- Dry Run, nothing was sent ----".bold())?;
+                writeln!(
+                    writer,
+                    "Expected gas : {} computation + {} storage - {} storage rebate",
+                    gas_cost.computation_cost, gas_cost.storage_cost, gas_cost.storage_rebate
+                )?;
+                if let Some(error) = effects.status.error_message() {
+                    writeln!(writer, "Expected failure : {}", error)?;
+                }
+                write!(writer, "{}", effects)?;
+            }
+            WalletCommandResult::MultiSigAddress(address) => {
+                writeln!(writer, "MultiSig address: {}", address)?;
+            }
+            WalletCommandResult::MultiSigCombinePartialSig(multisig) => {
+                writeln!(writer, "MultiSig to submit: {}", multisig.encoded())?;
+            }
         }
         write!(f, "{}", writer.trim_end_matches('\n'))
     }
 }
 
-pub async fn call_move(
+fn parse_multisig_pk(
+    pks: Vec<String>,
+    weights: Vec<WeightUnit>,
+    threshold: ThresholdUnit,
+) -> Result<MultiSigPublicKey, anyhow::Error> {
+    let pks = pks
+        .iter()
+        .map(|pk| Ok(MultiSigMemberKey::from_bytes(&Base64::decode(pk)?)?))
+        .collect::<Result<Vec<_>, anyhow::Error>>()?;
+    Ok(MultiSigPublicKey::new(pks, weights, threshold)?)
+}
+
+/// The sender and the unsigned transaction of a call, sent by the owner of the gas object or
+/// the active address.
+async fn move_call_data(
     package: ObjectID,
     module: &str,
     function: &str,
@@ -689,7 +1878,7 @@ pub async fn call_move(
     gas_budget: u64,
     args: Vec<SuiJsonValue>,
     context: &mut WalletContext,
-) -> Result<(SuiCertifiedTransaction, SuiTransactionEffects), anyhow::Error> {
+) -> Result<(SuiAddress, TransactionData), anyhow::Error> {
     let gas_owner = context.try_get_object_owner(&gas).await?;
     let sender = gas_owner.unwrap_or(context.active_address()?);
 
@@ -706,18 +1895,35 @@ pub async fn call_move(
             gas_budget,
         )
         .await?;
-    let signature = context.keystore.sign(&sender, &data.to_bytes())?;
+    Ok((sender, data))
+}
+
+pub async fn call_move(
+    package: ObjectID,
+    module: &str,
+    function: &str,
+    type_args: Vec<TypeTag>,
+    gas: Option<ObjectID>,
+    gas_budget: u64,
+    args: Vec<SuiJsonValue>,
+    context: &mut WalletContext,
+) -> Result<(SuiCertifiedTransaction, SuiTransactionEffects), anyhow::Error> {
+    let (sender, data) = move_call_data(
+        package, module, function, type_args, gas, gas_budget, args, context,
+    )
+    .await?;
+    context.dry_run_before_sending(&data).await?;
+    let signature = context.keystore.sign(&sender, &data.to_intent_bytes())?;
     let transaction = Transaction::new(data, signature);
-    let response = context
+    let (cert, effects) = context
         .gateway
         .execute_transaction(transaction)
         .await?
-        .to_effect_response()?;
-    let cert = response.certificate;
-    let effects = response.effects;
+        .to_effect_response()?
+        .into_certificate_and_effects()?;
 
-    if matches!(effects.status, SuiExecutionStatus::Failure { .. }) {
-        return Err(anyhow!("Error calling module: {:#?}", effects.status));
+    if let Some(error) = effects.status.error_message() {
+        return Err(anyhow!("Error calling module: {error}"));
     }
     Ok((cert, effects))
 }
@@ -729,6 +1935,16 @@ fn unwrap_or<'a>(val: &'a Option<String>, default: &'a str) -> &'a str {
     }
 }
 
+/// Whether `object` is a coin, which a transfer transaction can move.
+fn is_coin(object: &SuiObjectInfo) -> bool {
+    matches!(
+        parse_type_tag(&object.type_),
+        Ok(TypeTag::Struct(tag)) if tag.address == SUI_FRAMEWORK_ADDRESS
+            && tag.module.as_ident_str() == COIN_MODULE_NAME
+            && tag.name.as_ident_str() == COIN_STRUCT_NAME
+    )
+}
+
 fn write_cert_and_effects(
     cert: &SuiCertifiedTransaction,
     effects: &SuiTransactionEffects,
@@ -743,13 +1959,7 @@ fn write_cert_and_effects(
 
 impl Debug for WalletCommandResult {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        let s = unwrap_err_to_string(|| match self {
-            WalletCommandResult::Object(object_read) => {
-                let object = object_read.object()?;
-                Ok(serde_json::to_string_pretty(&object)?)
-            }
-            _ => Ok(serde_json::to_string_pretty(self)?),
-        });
+        let s = unwrap_err_to_string(|| Ok(serde_json::to_string_pretty(&self.to_json()?)?));
         write!(f, "{}", s)
     }
 }
@@ -762,11 +1972,66 @@ fn unwrap_err_to_string<T: Display, F: FnOnce() -> Result<T, anyhow::Error>>(fun
 }
 
 impl WalletCommandResult {
+    /// The output of `--json`. Every command gives a JSON object with camelCase fields (an
+    /// array for the commands listing objects), which only gains fields between releases,
+    /// unlike the text output.
+    pub fn to_json(&self) -> Result<Value, anyhow::Error> {
+        let transaction = |cert: &SuiCertifiedTransaction, effects: &SuiTransactionEffects| json!({ "certificate": cert, "effects": effects });
+        Ok(match self {
+            WalletCommandResult::Publish(response) => serde_json::to_value(response)?,
+            WalletCommandResult::Upgrade(response) => serde_json::to_value(response)?,
+            // The object itself, so that its fields can be read directly.
+            WalletCommandResult::Object(object_read)
+            | WalletCommandResult::CreateExampleNFT(object_read) => {
+                serde_json::to_value(object_read.object()?)?
+            }
+            WalletCommandResult::Call(cert, effects)
+            | WalletCommandResult::Transfer(_, cert, effects)
+            | WalletCommandResult::ExecuteSignedTx(cert, effects) => transaction(cert, effects),
+            WalletCommandResult::MigrateObjects(response) => serde_json::to_value(response)?,
+            WalletCommandResult::Addresses(response) => serde_json::to_value(response)?,
+            WalletCommandResult::Objects(object_refs) => serde_json::to_value(object_refs)?,
+            WalletCommandResult::SyncClientState => json!({}),
+            WalletCommandResult::NewAddress(address)
+            | WalletCommandResult::MultiSigAddress(address) => json!({ "address": address }),
+            WalletCommandResult::Gas(gases) => gases
+                .iter()
+                .map(|gas| {
+                    json!({
+                        "id": gas.id(),
+                        "version": gas.version(),
+                        "value": gas.value(),
+                    })
+                })
+                .collect(),
+            WalletCommandResult::SplitCoin(response) => serde_json::to_value(response)?,
+            WalletCommandResult::MergeCoin(response) => serde_json::to_value(response)?,
+            WalletCommandResult::Switch(response) => serde_json::to_value(response)?,
+            WalletCommandResult::Alias(address, alias) => {
+                json!({ "address": address, "alias": alias })
+            }
+            WalletCommandResult::NewEnv(env) => serde_json::to_value(env)?,
+            WalletCommandResult::Envs(envs, active) => {
+                json!({ "envs": envs, "activeEnv": active })
+            }
+            WalletCommandResult::ActiveAddress(address) => json!({ "activeAddress": address }),
+            WalletCommandResult::SerializeTx(tx_bytes) => json!({ "txBytes": tx_bytes }),
+            WalletCommandResult::DryRun(effects) => json!({ "effects": effects }),
+            WalletCommandResult::MultiSigCombinePartialSig(multisig) => {
+                json!({ "multiSig": multisig })
+            }
+        })
+    }
+
     pub fn print(&self, pretty: bool) {
         let line = if pretty {
             format!("{self}")
         } else {
-            format!("{:?}", self)
+            // Scripts read the error from the output like any other field.
+            let json = self
+                .to_json()
+                .unwrap_or_else(|err| json!({ "error": err.to_string() }));
+            serde_json::to_string_pretty(&json).expect("JSON values serialize")
         };
         // Log line by line
         for line in line.lines() {
@@ -777,19 +2042,19 @@ impl WalletCommandResult {
     }
 }
 
-#[derive(Serialize)]
-#[serde(untagged)]
 pub enum WalletCommandResult {
     Publish(PublishResponse),
+    Upgrade(UpgradeResponse),
     Object(GetObjectDataResponse),
     Call(SuiCertifiedTransaction, SuiTransactionEffects),
     Transfer(
-        // Skipping serialisation for elapsed time.
-        #[serde(skip)] u128,
+        // The elapsed time, which is left out of the JSON output.
+        u128,
         SuiCertifiedTransaction,
         SuiTransactionEffects,
     ),
-    Addresses(Vec<SuiAddress>),
+    MigrateObjects(MigrateObjectsResponse),
+    Addresses(AddressesResponse),
     Objects(Vec<SuiObjectInfo>),
     SyncClientState,
     NewAddress(SuiAddress),
@@ -797,15 +2062,26 @@ pub enum WalletCommandResult {
     SplitCoin(SplitCoinResponse),
     MergeCoin(MergeCoinResponse),
     Switch(SwitchResponse),
+    Alias(SuiAddress, String),
+    NewEnv(WalletEnv),
+    Envs(Vec<WalletEnv>, Option<String>),
     ActiveAddress(Option<SuiAddress>),
     CreateExampleNFT(GetObjectDataResponse),
+    SerializeTx(Base64),
+    ExecuteSignedTx(SuiCertifiedTransaction, SuiTransactionEffects),
+    /// The effects a transaction would have, which are not signed by the validator.
+    DryRun(SuiTransactionEffects),
+    MultiSigAddress(SuiAddress),
+    MultiSigCombinePartialSig(Base64),
 }
 
 #[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
 pub struct SwitchResponse {
     /// Active address
     pub address: Option<SuiAddress>,
     pub gateway: Option<String>,
+    pub env: Option<String>,
 }
 
 impl Display for SwitchResponse {
@@ -817,6 +2093,103 @@ impl Display for SwitchResponse {
         if let Some(gateway) = &self.gateway {
             writeln!(writer, "Active gateway switched to {}", gateway)?;
         }
+        if let Some(env) = &self.env {
+            writeln!(writer, "Active env switched to [{}]", env)?;
+        }
+        write!(f, "{}", writer)
+    }
+}
+
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct UpgradeResponse {
+    /// The version of the package the upgrade replaced.
+    pub previous_package: ObjectID,
+    /// The new version of the package.
+    pub package: ObjectID,
+    pub upgrade_cap: ObjectID,
+    pub certificate: SuiCertifiedTransaction,
+    pub effects: SuiTransactionEffects,
+}
+
+impl Display for UpgradeResponse {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let mut writer = String::new();
+        writeln!(
+            writer,
+            "Upgraded package {} to {}, with UpgradeCap {}",
+            self.previous_package, self.package, self.upgrade_cap
+        )?;
+        write!(
+            writer,
+            "{}",
+            write_cert_and_effects(&self.certificate, &self.effects)?
+        )?;
+        write!(f, "{}", writer)
+    }
+}
+
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct MigrateObjectsResponse {
+    pub from: SuiAddress,
+    pub to: SuiAddress,
+    /// The objects transferred to `to`, the gas object last.
+    pub migrated: Vec<ObjectID>,
+    pub transactions: Vec<TransactionDigest>,
+    /// The objects left with `from`, which only the functions of their modules can transfer.
+    pub skipped: Vec<SuiObjectInfo>,
+}
+
+impl Display for MigrateObjectsResponse {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let mut writer = String::new();
+        writeln!(
+            writer,
+            "Migrated {} objects from {} to {} in {} transactions",
+            self.migrated.len(),
+            self.from,
+            self.to,
+            self.transactions.len()
+        )?;
+        for digest in &self.transactions {
+            writeln!(writer, "  {digest:?}")?;
+        }
+        if !self.skipped.is_empty() {
+            writeln!(
+                writer,
+                "{} objects are left, for the functions of their modules to transfer:",
+                self.skipped.len()
+            )?;
+            for object in &self.skipped {
+                writeln!(writer, "  {} ({})", object.object_id, object.type_)?;
+            }
+        }
+        write!(f, "{}", writer)
+    }
+}
+
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct AddressesResponse {
+    pub addresses: Vec<SuiAddress>,
+    pub aliases: BTreeMap<String, SuiAddress>,
+}
+
+impl Display for AddressesResponse {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let mut writer = String::new();
+        writeln!(writer, " {0: ^42} | {1: ^20}", "Sui Address", "Alias")?;
+        writeln!(writer, "{}", ["-"; 66].join(""))?;
+        for address in &self.addresses {
+            let alias = self
+                .aliases
+                .iter()
+                .find(|(_, named)| *named == address)
+                .map_or("", |(alias, _)| alias.as_str());
+            writeln!(writer, " {0: ^42} | {1: ^20}", address, alias)?;
+        }
+        writeln!(writer, "Showing {} results.", self.addresses.len())?;
         write!(f, "{}", writer)
     }
 }
@@ -0,0 +1,20 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use test_utils::cluster::TestClusterBuilder;
+
+#[tokio::test]
+async fn test_advance_epoch() -> Result<(), anyhow::Error> {
+    // Long enough that only the calls to `advance_epoch` change epoch.
+    let cluster = TestClusterBuilder::new()
+        .with_epoch_duration_ms(3_600_000)
+        .build()
+        .await?;
+
+    cluster.advance_epoch().await?;
+    cluster.wait_for_epoch(1).await?;
+
+    cluster.advance_epoch().await?;
+    cluster.wait_for_epoch(2).await?;
+    Ok(())
+}
@@ -2,16 +2,24 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use futures::StreamExt;
+use std::collections::BTreeSet;
 use std::sync::Arc;
 use sui::wallet_commands::{WalletCommandResult, WalletCommands, WalletContext};
+use sui_config::{Config, ForkDetection, SUI_FULLNODE_CONFIG};
 use sui_core::authority::AuthorityState;
+use sui_core::transaction_builder::TransactionBuilder;
+use sui_gateway::graphql::build_schema;
+use sui_node::config_watcher::watch_config;
 use sui_node::SuiNode;
 
 use sui_types::{
     base_types::{ObjectID, SuiAddress, TransactionDigest},
     batch::UpdateItem,
-    messages::{BatchInfoRequest, BatchInfoResponseItem},
+    messages::{BatchInfoRequest, BatchInfoResponseItem, InputObjectKind, Transaction},
+    sui_serde::Base64,
+    SUI_FRAMEWORK_ADDRESS,
 };
+use test_utils::cluster::TestClusterBuilder;
 use test_utils::network::setup_network_and_wallet;
 use tokio::time::{sleep, Duration};
 use tracing::info;
@@ -32,7 +40,7 @@ async fn transfer_coin(
         object_to_send, sender, receiver
     );
     let res = WalletCommands::Transfer {
-        to: receiver,
+        to: receiver.into(),
         coin_object_id: object_to_send,
         gas: Some(gas_object),
         gas_budget: 50000,
@@ -106,16 +114,17 @@ async fn wait_for_tx(wait_digest: TransactionDigest, state: Arc<AuthorityState>)
 
 #[tokio::test]
 async fn test_full_node_follows_txes() -> Result<(), anyhow::Error> {
-    let (swarm, mut context, _) = setup_network_and_wallet().await?;
-
-    let config = swarm.config().generate_fullnode_config();
-    let node = SuiNode::start(&config).await?;
+    let mut cluster = TestClusterBuilder::new().build().await?;
 
-    let (transfered_object, _, receiver, digest) = transfer_coin(&mut context).await?;
-    wait_for_tx(digest, node.state().clone()).await;
+    let (transfered_object, _, receiver, digest) = transfer_coin(&mut cluster.wallet).await?;
+    cluster.wait_for_fullnode_transaction(digest).await?;
 
     // verify that the node has seen the transfer
-    let object_read = node.state().get_object_read(&transfered_object).await?;
+    let object_read = cluster
+        .fullnode
+        .state()
+        .get_object_read(&transfered_object)
+        .await?;
     let object = object_read.into_object()?;
 
     assert_eq!(object.owner.get_owner_address().unwrap(), receiver);
@@ -164,21 +173,61 @@ async fn test_full_node_indexes() -> Result<(), anyhow::Error> {
     Ok(())
 }
 
+#[tokio::test]
+async fn test_full_node_transaction_builder() -> Result<(), anyhow::Error> {
+    let (swarm, mut context, _) = setup_network_and_wallet().await?;
+
+    let config = swarm.config().generate_fullnode_config();
+    let node = SuiNode::start(&config).await?;
+
+    let (transfered_object, sender, receiver, digest) = transfer_coin(&mut context).await?;
+    wait_for_tx(digest, node.state().clone()).await;
+
+    // Send the object back, building the transaction from the fullnode's view of the objects
+    // and letting it choose one of the receiver's coins for gas.
+    let state = node.state();
+    let data = TransactionBuilder(&*state)
+        .transfer_coin(receiver, transfered_object, None, 50000, sender)
+        .await?;
+    assert_eq!(data.signer(), receiver);
+    assert_ne!(data.gas().0, transfered_object);
+    let object_ref = state
+        .get_object_read(&transfered_object)
+        .await?
+        .into_object()?
+        .compute_object_reference();
+    assert!(data
+        .input_objects()?
+        .contains(&InputObjectKind::ImmOrOwnedMoveObject(object_ref)));
+
+    let signature = context.keystore.sign(&receiver, &data.to_intent_bytes())?;
+    let effects = context
+        .gateway
+        .execute_transaction(Transaction::new(data, signature))
+        .await?
+        .to_effect_response()?
+        .effects
+        .unwrap();
+    assert!(effects.status.is_ok());
+
+    Ok(())
+}
+
 // Test for syncing a node to an authority that already has many txes.
 #[tokio::test]
 async fn test_full_node_cold_sync() -> Result<(), anyhow::Error> {
     telemetry_subscribers::init_for_testing();
 
-    let (swarm, mut context, _) = setup_network_and_wallet().await?;
-
-    let (_, _, _, _) = transfer_coin(&mut context).await?;
-    let (_, _, _, _) = transfer_coin(&mut context).await?;
-    let (_, _, _, _) = transfer_coin(&mut context).await?;
-    let (_transfered_object, sender, _receiver, digest) = transfer_coin(&mut context).await?;
+    let mut cluster = TestClusterBuilder::new().build().await?;
 
-    sleep(Duration::from_millis(1000)).await;
+    let (_, _, _, _) = transfer_coin(&mut cluster.wallet).await?;
+    let (_, _, _, _) = transfer_coin(&mut cluster.wallet).await?;
+    let (_, _, _, _) = transfer_coin(&mut cluster.wallet).await?;
+    let (_transfered_object, sender, _receiver, digest) =
+        transfer_coin(&mut cluster.wallet).await?;
 
-    let config = swarm.config().generate_fullnode_config();
+    // Start another fullnode once the validators executed the transfers.
+    let config = cluster.swarm.config().generate_fullnode_config();
     let node = SuiNode::start(&config).await?;
 
     wait_for_tx(digest, node.state().clone()).await;
@@ -188,3 +237,131 @@ async fn test_full_node_cold_sync() -> Result<(), anyhow::Error> {
 
     Ok(())
 }
+
+#[tokio::test]
+async fn test_full_node_reloads_config() -> Result<(), anyhow::Error> {
+    let (swarm, _, _) = setup_network_and_wallet().await?;
+
+    let dir = tempfile::tempdir()?;
+    let path = dir.path().join(SUI_FULLNODE_CONFIG);
+    let mut config = swarm.config().generate_fullnode_config();
+    config.save(&path)?;
+    let node = SuiNode::start(&config).await?;
+    let parameters = node.reloadable_parameters();
+    tokio::spawn(watch_config(
+        path.clone(),
+        config.clone(),
+        parameters.clone(),
+    ));
+
+    // Changes to the config file apply to the running node.
+    config.fork_detection = ForkDetection::Halt;
+    config.json_rpc_limits.max_multi_get_size = 1;
+    config.deny_list.objects.insert(ObjectID::random());
+    config.save(&path)?;
+    for _ in 0..30 {
+        if **parameters.json_rpc_limits.load() == config.json_rpc_limits {
+            break;
+        }
+        sleep(Duration::from_millis(500)).await;
+    }
+    assert_eq!(**parameters.json_rpc_limits.load(), config.json_rpc_limits);
+    assert_eq!(**parameters.fork_detection.load(), ForkDetection::Halt);
+    assert_eq!(**node.state().deny_list.load(), config.deny_list);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_full_node_graphql() -> Result<(), anyhow::Error> {
+    let (swarm, mut context, _) = setup_network_and_wallet().await?;
+
+    let config = swarm.config().generate_fullnode_config();
+    let node = SuiNode::start(&config).await?;
+
+    let (transfered_object, sender, receiver, digest) = transfer_coin(&mut context).await?;
+    wait_for_tx(digest, node.state().clone()).await;
+
+    let schema = build_schema(node.state().clone());
+    let query = |query: String| {
+        let schema = schema.clone();
+        async move {
+            let response = schema.execute(query.as_str()).await;
+            assert!(response.errors.is_empty(), "{:?}", response.errors);
+            response.data.into_json().unwrap()
+        }
+    };
+    let digest = Base64::from_bytes(digest.as_ref()).encoded();
+
+    // Follow the transfer from its sender to the objects it mutated and their owners.
+    let data = query(format!(
+        r#"{{
+            address(address: "{sender}") {{
+                balance {{ coinObjectCount }}
+                transactionsSent(first: 1) {{
+                    edges {{ node {{ digest success mutated {{ id owner {{ address }} }} }} }}
+                    pageInfo {{ hasNextPage }}
+                }}
+            }}
+            object(id: "{transfered_object}") {{
+                owner {{ kind address }}
+                previousTransaction {{ digest }}
+            }}
+        }}"#
+    ))
+    .await;
+    let sent = &data["address"]["transactionsSent"];
+    assert_eq!(sent["pageInfo"]["hasNextPage"], false);
+    let transaction = &sent["edges"][0]["node"];
+    assert_eq!(transaction["digest"], digest);
+    assert_eq!(transaction["success"], true);
+    assert!(transaction["mutated"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .any(|object| object["id"] == transfered_object.to_string()
+            && object["owner"]["address"] == receiver.to_string()));
+    assert_eq!(data["address"]["balance"]["coinObjectCount"], 4);
+    assert_eq!(data["object"]["owner"]["kind"], "ADDRESS");
+    assert_eq!(data["object"]["owner"]["address"], receiver.to_string());
+    assert_eq!(data["object"]["previousTransaction"]["digest"], digest);
+
+    // The objects of the sender, in pages of two.
+    let page = |after: Option<String>| {
+        let after = after.map_or_else(String::new, |after| format!(r#", after: "{after}""#));
+        query(format!(
+            r#"{{
+                address(address: "{sender}") {{
+                    objects(first: 2{after}) {{
+                        edges {{ node {{ id }} }}
+                        pageInfo {{ hasNextPage endCursor }}
+                    }}
+                }}
+            }}"#
+        ))
+    };
+    let first_page = page(None).await["address"]["objects"].clone();
+    assert_eq!(first_page["pageInfo"]["hasNextPage"], true);
+    let end_cursor = first_page["pageInfo"]["endCursor"].as_str().unwrap();
+    let second_page = page(Some(end_cursor.to_string())).await["address"]["objects"].clone();
+    assert_eq!(second_page["pageInfo"]["hasNextPage"], false);
+    let ids: BTreeSet<_> = [&first_page, &second_page]
+        .iter()
+        .flat_map(|page| page["edges"].as_array().unwrap())
+        .map(|edge| edge["node"]["id"].as_str().unwrap().to_string())
+        .collect();
+    assert_eq!(ids.len(), 4);
+    assert!(!ids.contains(&transfered_object.to_string()));
+
+    // The modules of the framework package.
+    let framework = ObjectID::from(SUI_FRAMEWORK_ADDRESS);
+    let data = query(format!(
+        r#"{{ package(id: "{framework}") {{ modules {{ name functions {{ name }} }} }} }}"#
+    ))
+    .await;
+    assert!(data["package"]["modules"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .any(|module| module["name"] == "Coin"));
+    Ok(())
+}
@@ -72,7 +72,11 @@ pub fn test_authority_aggregator(
         .iter()
         .map(|config| (config.public_key(), config.stake()))
         .collect();
-    let committee = Committee::new(0, voting_rights);
+    let protocol_keys: BTreeMap<_, _> = validators_info
+        .iter()
+        .map(|config| (config.public_key(), config.protocol_key()))
+        .collect();
+    let committee = Committee::new(0, voting_rights, protocol_keys);
     let clients: BTreeMap<_, _> = validators_info
         .iter()
         .map(|config| {
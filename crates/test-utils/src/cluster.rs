@@ -0,0 +1,252 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! A programmatic localnet for integration tests: a committee of validators and a fullnode
+//! serving JSON-RPC, with a wallet holding the gas of the genesis accounts. Tests wait on the
+//! state of the cluster through the `wait_for_*` helpers rather than sleeping.
+
+use jsonrpsee_http_client::{HttpClient, HttpClientBuilder};
+use std::time::Duration;
+use sui::wallet_commands::{WalletCommands, WalletContext};
+use sui_config::genesis_config::GenesisConfig;
+use sui_config::SUI_WALLET_CONFIG;
+use sui_core::authority_client::{AuthorityAPI, NetworkAuthorityClient};
+use sui_core::gateway_types::SuiTransactionResponseOptions;
+use sui_gateway::api::RpcEpochAdminApiClient;
+use sui_node::SuiNode;
+use sui_swarm::memory::Swarm;
+use sui_types::base_types::{SuiAddress, TransactionDigest};
+use sui_types::committee::EpochId;
+use sui_types::messages_checkpoint::{
+    AuthenticatedCheckpoint, AuthorityCheckpointInfo, CheckpointRequest, CheckpointSequenceNumber,
+};
+use tokio::time::sleep;
+
+use crate::network::start_test_network_with_committee_size;
+
+/// The default time the `wait_for_*` helpers of a [`TestCluster`] wait before failing.
+pub const DEFAULT_WAIT_TIMEOUT: Duration = Duration::from_secs(30);
+
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+pub struct TestClusterBuilder {
+    num_validators: usize,
+    genesis_config: Option<GenesisConfig>,
+    epoch_duration_ms: Option<u64>,
+    wait_timeout: Duration,
+}
+
+impl TestClusterBuilder {
+    pub fn new() -> Self {
+        Self {
+            num_validators: crate::TEST_COMMITTEE_SIZE,
+            genesis_config: None,
+            epoch_duration_ms: None,
+            wait_timeout: DEFAULT_WAIT_TIMEOUT,
+        }
+    }
+
+    pub fn with_num_validators(mut self, num_validators: usize) -> Self {
+        self.num_validators = num_validators;
+        self
+    }
+
+    /// The genesis config of the cluster, whose accounts the wallet holds.
+    pub fn set_genesis_config(mut self, genesis_config: GenesisConfig) -> Self {
+        self.genesis_config = Some(genesis_config);
+        self
+    }
+
+    /// Have the validators change epoch after `epoch_duration_ms`, and on
+    /// [`TestCluster::advance_epoch`].
+    pub fn with_epoch_duration_ms(mut self, epoch_duration_ms: u64) -> Self {
+        self.epoch_duration_ms = Some(epoch_duration_ms);
+        self
+    }
+
+    pub fn with_wait_timeout(mut self, wait_timeout: Duration) -> Self {
+        self.wait_timeout = wait_timeout;
+        self
+    }
+
+    /// Start the validators and the fullnode, and sync the wallet with the objects of the
+    /// genesis accounts.
+    pub async fn build(self) -> Result<TestCluster, anyhow::Error> {
+        let genesis_config = match self.epoch_duration_ms {
+            Some(epoch_duration_ms) => {
+                let mut genesis_config = self
+                    .genesis_config
+                    .unwrap_or_else(GenesisConfig::for_local_testing);
+                genesis_config.epoch_duration_ms = Some(epoch_duration_ms);
+                Some(genesis_config)
+            }
+            None => self.genesis_config,
+        };
+        let swarm =
+            start_test_network_with_committee_size(self.num_validators, genesis_config).await?;
+
+        let fullnode_config = swarm.config().generate_fullnode_config();
+        let fullnode = SuiNode::start(&fullnode_config).await?;
+        let rpc_url = format!("http://{}", fullnode_config.json_rpc_address);
+        let rpc_client = HttpClientBuilder::default().build(&rpc_url)?;
+
+        let mut wallet = WalletContext::new(&swarm.dir().join(SUI_WALLET_CONFIG))?;
+        let accounts = wallet.config.accounts.clone();
+        for address in &accounts {
+            WalletCommands::SyncClientState {
+                address: Some(*address),
+            }
+            .execute(&mut wallet)
+            .await?;
+        }
+
+        Ok(TestCluster {
+            swarm,
+            fullnode,
+            wallet,
+            accounts,
+            rpc_url,
+            rpc_client,
+            wait_timeout: self.wait_timeout,
+        })
+    }
+}
+
+impl Default for TestClusterBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+pub struct TestCluster {
+    pub swarm: Swarm,
+    pub fullnode: SuiNode,
+    /// A wallet holding the keys of the genesis accounts, synced with their gas.
+    pub wallet: WalletContext,
+    pub accounts: Vec<SuiAddress>,
+    /// The URL of the JSON-RPC served by the fullnode.
+    pub rpc_url: String,
+    pub rpc_client: HttpClient,
+    wait_timeout: Duration,
+}
+
+impl TestCluster {
+    pub fn get_address_0(&self) -> SuiAddress {
+        self.accounts[0]
+    }
+
+    pub fn get_address_1(&self) -> SuiAddress {
+        self.accounts[1]
+    }
+
+    /// Make every validator change epoch right away. The cluster must be built
+    /// [`TestClusterBuilder::with_epoch_duration_ms`]; see [`Self::wait_for_epoch`] to wait for
+    /// the change to complete.
+    pub async fn advance_epoch(&self) -> Result<(), anyhow::Error> {
+        for validator in self.swarm.config().validator_configs() {
+            let client = HttpClientBuilder::default()
+                .build(format!("http://{}", validator.json_rpc_address))?;
+            client.advance_epoch().await?;
+        }
+        Ok(())
+    }
+
+    /// Wait until every validator has started `epoch`, that is, has signed the handoff of the
+    /// epoch before it.
+    pub async fn wait_for_epoch(&self, epoch: EpochId) -> Result<(), anyhow::Error> {
+        if epoch == 0 {
+            return Ok(());
+        }
+        self.wait_for_validators(
+            &format!("epoch {epoch}"),
+            CheckpointRequest::epoch_handoff(epoch - 1),
+            |response| matches!(response, AuthorityCheckpointInfo::EpochHandoff(Some(_))),
+        )
+        .await
+    }
+
+    /// Wait until every validator has the checkpoint `sequence_number`.
+    pub async fn wait_for_checkpoint(
+        &self,
+        sequence_number: CheckpointSequenceNumber,
+    ) -> Result<(), anyhow::Error> {
+        self.wait_for_validators(
+            &format!("checkpoint {sequence_number}"),
+            CheckpointRequest::past(sequence_number, false),
+            |response| {
+                matches!(
+                    response,
+                    AuthorityCheckpointInfo::Past(
+                        AuthenticatedCheckpoint::Signed(_) | AuthenticatedCheckpoint::Certified(_)
+                    )
+                )
+            },
+        )
+        .await
+    }
+
+    /// Wait until the fullnode has executed the transaction `digest`.
+    pub async fn wait_for_fullnode_transaction(
+        &self,
+        digest: TransactionDigest,
+    ) -> Result<(), anyhow::Error> {
+        let state = self.fullnode.state();
+        self.poll(&format!("transaction {digest:?} on the fullnode"), || {
+            let state = state.clone();
+            async move {
+                state
+                    .get_transaction(digest, SuiTransactionResponseOptions::default())
+                    .await
+                    .is_ok()
+            }
+        })
+        .await
+    }
+
+    async fn wait_for_validators(
+        &self,
+        what: &str,
+        request: CheckpointRequest,
+        is_done: impl Fn(&AuthorityCheckpointInfo) -> bool,
+    ) -> Result<(), anyhow::Error> {
+        let clients = self
+            .swarm
+            .config()
+            .validator_set()
+            .iter()
+            .map(|validator| NetworkAuthorityClient::connect_lazy(validator.network_address()))
+            .collect::<Result<Vec<_>, _>>()?;
+        let is_done = &is_done;
+        let request = &request;
+        self.poll(what, || {
+            let clients = clients.clone();
+            async move {
+                for client in clients {
+                    match client.handle_checkpoint(request.clone()).await {
+                        Ok(response) if is_done(&response.info) => {}
+                        _ => return false,
+                    }
+                }
+                true
+            }
+        })
+        .await
+    }
+
+    async fn poll<F, Fut>(&self, what: &str, mut is_done: F) -> Result<(), anyhow::Error>
+    where
+        F: FnMut() -> Fut,
+        Fut: std::future::Future<Output = bool>,
+    {
+        let wait = async {
+            while !is_done().await {
+                sleep(POLL_INTERVAL).await;
+            }
+        };
+        tokio::time::timeout(self.wait_timeout, wait)
+            .await
+            .map_err(|_| {
+                anyhow::anyhow!("Timed out after {:?} waiting for {what}", self.wait_timeout)
+            })
+    }
+}
@@ -1,6 +1,7 @@
 // Copyright (c) 2022, Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 pub mod authority;
+pub mod cluster;
 pub mod messages;
 pub mod network;
 pub mod objects;
@@ -25,11 +26,14 @@ pub fn test_keys() -> Vec<(SuiAddress, KeyPair)> {
 
 /// Generate a test Sui committee with `TEST_COMMITTEE_SIZE` members.
 pub fn test_committee() -> Committee {
+    let keys = test_keys();
     Committee::new(
         0,
-        test_keys()
-            .into_iter()
+        keys.iter()
             .map(|(_, x)| (*x.public_key_bytes(), /* voting right */ 1))
             .collect(),
+        keys.iter()
+            .map(|(_, x)| (*x.public_key_bytes(), x.protocol_public_key()))
+            .collect(),
     )
 }
@@ -1,8 +1,8 @@
 // Copyright (c) 2022, Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+use anyhow::anyhow;
 use jsonrpsee_http_client::{HttpClient, HttpClientBuilder};
-use jsonrpsee_http_server::{HttpServerBuilder, HttpServerHandle, RpcModule};
 use std::net::SocketAddr;
 use std::num::NonZeroUsize;
 use std::path::Path;
@@ -14,9 +14,7 @@ use sui::{
 use sui_config::genesis_config::GenesisConfig;
 use sui_config::PersistedConfig;
 use sui_config::{Config, SUI_GATEWAY_CONFIG, SUI_NETWORK_CONFIG, SUI_WALLET_CONFIG};
-use sui_gateway::api::RpcGatewayApiServer;
-use sui_gateway::api::RpcReadApiServer;
-use sui_gateway::api::RpcTransactionBuilderServer;
+use sui_gateway::json_rpc::{JsonRpcServerBuilder, JsonRpcServerHandle};
 use sui_gateway::rpc_gateway::{
     create_client, GatewayReadApiImpl, RpcGatewayImpl, TransactionBuilderImpl,
 };
@@ -27,7 +25,16 @@ const NUM_VALIDAOTR: usize = 4;
 pub async fn start_test_network(
     genesis_config: Option<GenesisConfig>,
 ) -> Result<Swarm, anyhow::Error> {
-    let mut builder = Swarm::builder().committee_size(NonZeroUsize::new(NUM_VALIDAOTR).unwrap());
+    start_test_network_with_committee_size(NUM_VALIDAOTR, genesis_config).await
+}
+
+pub async fn start_test_network_with_committee_size(
+    committee_size: usize,
+    genesis_config: Option<GenesisConfig>,
+) -> Result<Swarm, anyhow::Error> {
+    let mut builder = Swarm::builder().committee_size(
+        NonZeroUsize::new(committee_size).ok_or_else(|| anyhow!("Empty committee"))?,
+    );
     if let Some(genesis_config) = genesis_config {
         builder = builder.initial_accounts_config(genesis_config);
     }
@@ -71,12 +78,15 @@ pub async fn start_test_network(
     // Create wallet config with stated authorities port
     WalletConfig {
         accounts,
+        aliases: Default::default(),
         keystore: KeystoreType::File(keystore_path),
         gateway: GatewayType::Embedded(GatewayConfig {
             db_folder_path,
             validator_set: validators,
             ..Default::default()
         }),
+        envs: vec![],
+        active_env: None,
         active_address,
     }
     .save(&wallet_path)?;
@@ -105,17 +115,15 @@ pub async fn setup_network_and_wallet() -> Result<(Swarm, WalletContext, SuiAddr
 
 async fn start_rpc_gateway(
     config_path: &Path,
-) -> Result<(SocketAddr, HttpServerHandle), anyhow::Error> {
-    let server = HttpServerBuilder::default().build("127.0.0.1:0").await?;
-    let addr = server.local_addr()?;
+) -> Result<(SocketAddr, JsonRpcServerHandle), anyhow::Error> {
     let client = create_client(config_path)?;
-    let mut module = RpcModule::new(());
-    module.merge(RpcGatewayImpl::new(client.clone()).into_rpc())?;
-    module.merge(GatewayReadApiImpl::new(client.clone()).into_rpc())?;
-    module.merge(TransactionBuilderImpl::new(client.clone()).into_rpc())?;
+    let mut server = JsonRpcServerBuilder::new()?;
+    server.register_module(RpcGatewayImpl::new(client.clone()))?;
+    server.register_module(GatewayReadApiImpl::new(client.clone()))?;
+    server.register_module(TransactionBuilderImpl::new(client))?;
 
-    let handle = server.start(module)?;
-    Ok((addr, handle))
+    let handle = server.start("127.0.0.1:0".parse()?).await?;
+    Ok((handle.local_addr(), handle))
 }
 
 pub async fn start_rpc_test_network(
@@ -146,7 +154,7 @@ pub async fn start_rpc_test_network(
 
 pub struct TestNetwork {
     pub network: Swarm,
-    _rpc_server: HttpServerHandle,
+    _rpc_server: JsonRpcServerHandle,
     pub accounts: Vec<SuiAddress>,
     pub http_client: HttpClient,
     pub rpc_url: String,
@@ -104,9 +104,9 @@ async function getRecentTransactions(
                     const [seq, digest] = transactions.filter(
                         (transactionId) =>
                             transactionId[1] ===
-                            getTransactionDigest(txEff.certificate)
+                            getTransactionDigest(txEff.certificate!)
                     )[0];
-                    const res: CertifiedTransaction = txEff.certificate;
+                    const res: CertifiedTransaction = txEff.certificate!;
                     // TODO: handle multiple transactions
                     const txns = getTransactions(res);
                     if (txns.length > 1) {
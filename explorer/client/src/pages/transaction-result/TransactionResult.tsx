@@ -112,8 +112,8 @@ const transformTransactionResponse = (
         txError: getExecutionStatusError(txObj) ?? '',
         txId: id,
         loadState: 'loaded',
-        mutated: getCreatedOrMutatedData(txObj.effects, 'mutated'),
-        created: getCreatedOrMutatedData(txObj.effects, 'created'),
+        mutated: getCreatedOrMutatedData(txObj.effects!, 'mutated'),
+        created: getCreatedOrMutatedData(txObj.effects!, 'created'),
     };
 };
 
@@ -0,0 +1,69 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+import { Base64DataBuffer } from '../serialization/base64';
+
+/**
+ * The kind of message which is signed. Every signed message starts with its
+ * intent, so that a signature of one kind of message can't be replayed as
+ * another.
+ */
+export enum IntentScope {
+  TransactionData = 0,
+  TransactionEffects = 1,
+  CheckpointSummary = 2,
+  PersonalMessage = 3,
+  AuthorityBatch = 4,
+  Genesis = 5,
+}
+
+export const INTENT_VERSION = 0;
+export const APP_ID = 0;
+
+/**
+ * The bytes to sign for a message of `scope`: the intent followed by the message
+ */
+export function messageWithIntent(
+  scope: IntentScope,
+  message: Base64DataBuffer
+): Base64DataBuffer {
+  const intent = [scope, INTENT_VERSION, APP_ID];
+  const bytes = new Uint8Array(intent.length + message.getLength());
+  bytes.set(intent);
+  bytes.set(message.getData(), intent.length);
+  return new Base64DataBuffer(bytes);
+}
+
+const PERSONAL_MESSAGE_NAME = 'PersonalMessage::';
+
+/**
+ * The bytes to sign for the personal message `message`: its intent followed by
+ * the name of the type and the BCS encoded message
+ */
+export function personalMessageWithIntent(
+  message: Uint8Array
+): Base64DataBuffer {
+  const name = new TextEncoder().encode(PERSONAL_MESSAGE_NAME);
+  const length = uleb128(message.length);
+  const bytes = new Uint8Array(name.length + length.length + message.length);
+  bytes.set(name);
+  bytes.set(length, name.length);
+  bytes.set(message, name.length + length.length);
+  return messageWithIntent(
+    IntentScope.PersonalMessage,
+    new Base64DataBuffer(bytes)
+  );
+}
+
+function uleb128(value: number): number[] {
+  const bytes = [];
+  do {
+    let byte = value & 0x7f;
+    value >>>= 7;
+    if (value !== 0) {
+      byte |= 0x80;
+    }
+    bytes.push(byte);
+  } while (value !== 0);
+  return bytes;
+}
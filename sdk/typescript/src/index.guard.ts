@@ -5,7 +5,7 @@
  * Generated type guards for "index.ts".
  * WARNING: Do not manually change this file.
  */
-import { Ed25519KeypairData, Keypair, PublicKeyInitData, PublicKeyData, TransferCoinTransaction, MergeCoinTransaction, SplitCoinTransaction, MoveCallTransaction, TxnDataSerializer, SignaturePubkeyPair, Signer, TransactionDigest, SuiAddress, ObjectOwner, SuiObjectRef, SuiObjectInfo, ObjectContentFields, MovePackageContent, SuiData, SuiMoveObject, SuiMovePackage, SuiObject, ObjectStatus, ObjectType, GetOwnedObjectsResponse, GetObjectDataResponse, ObjectDigest, ObjectId, SequenceNumber, TransferCoin, RawAuthoritySignInfo, TransactionKindName, SuiTransactionKind, TransactionData, EpochId, AuthorityQuorumSignInfo, CertifiedTransaction, GasCostSummary, ExecutionStatusType, ExecutionStatus, OwnedObjectRef, TransactionEffects, TransactionEffectsResponse, GatewayTxSeqNumber, GetTxnDigestsResponse, Event, MoveCall, SuiJsonValue, EmptySignInfo, AuthorityName, AuthoritySignature, TransactionBytes, MergeCoinResponse, SplitCoinResponse, TransactionResponse } from "./index";
+import { Ed25519KeypairData, Keypair, PublicKeyInitData, PublicKeyData, TransferCoinTransaction, MergeCoinTransaction, SplitCoinTransaction, MoveCallTransaction, TxnDataSerializer, SignaturePubkeyPair, Signer, TransactionDigest, SuiAddress, ObjectOwner, SuiObjectRef, SuiObjectInfo, ObjectContentFields, MovePackageContent, SuiData, SuiMoveObject, SuiMovePackage, SuiObject, ObjectStatus, ObjectType, GetOwnedObjectsResponse, GetObjectDataResponse, ObjectDigest, ObjectId, SequenceNumber, TransferCoin, RawAuthoritySignInfo, TransactionKindName, SuiTransactionKind, TransactionData, EpochId, AuthorityQuorumSignInfo, CertifiedTransaction, GasCostSummary, ExecutionStatusType, MoveAbort, ExecutionStatus, OwnedObjectRef, TransactionEffects, TransactionEffectsResponse, GatewayTxSeqNumber, GetTxnDigestsResponse, Event, MoveCall, SuiJsonValue, EmptySignInfo, AuthorityName, AuthoritySignature, TransactionBytes, MergeCoinResponse, SplitCoinResponse, TransactionResponse } from "./index";
 import { BN } from "bn.js";
 import { Base64DataBuffer } from "./serialization/base64";
 import { PublicKey } from "./cryptography/publickey";
@@ -172,7 +172,11 @@ export function isObjectOwner(obj: any, _argumentName?: string): obj is ObjectOw
                 typeof obj === "function") &&
             isTransactionDigest(obj.ObjectOwner) as boolean ||
             obj === "Shared" ||
-            obj === "Immutable")
+            obj === "Immutable" ||
+            (obj !== null &&
+                typeof obj === "object" ||
+                typeof obj === "function") &&
+            isTransactionDigest(obj.Party) as boolean)
     )
 }
 
@@ -241,7 +245,8 @@ export function isSuiMoveObject(obj: any, _argumentName?: string): obj is SuiMov
             typeof obj === "object" ||
             typeof obj === "function") &&
         isTransactionDigest(obj.type) as boolean &&
-        isObjectContentFields(obj.fields) as boolean
+        (typeof obj.fields === "undefined" ||
+            isObjectContentFields(obj.fields) as boolean)
     )
 }
 
@@ -428,6 +433,21 @@ export function isExecutionStatusType(obj: any, _argumentName?: string): obj is
     )
 }
 
+export function isMoveAbort(obj: any, _argumentName?: string): obj is MoveAbort {
+    return (
+        (obj !== null &&
+            typeof obj === "object" ||
+            typeof obj === "function") &&
+        isTransactionDigest(obj.module) as boolean &&
+        (typeof obj.function === "undefined" ||
+            isTransactionDigest(obj.function) as boolean) &&
+        isSequenceNumber(obj.instruction) as boolean &&
+        isSequenceNumber(obj.code) as boolean &&
+        (typeof obj.constant === "undefined" ||
+            isTransactionDigest(obj.constant) as boolean)
+    )
+}
+
 export function isExecutionStatus(obj: any, _argumentName?: string): obj is ExecutionStatus {
     return (
         (obj !== null &&
@@ -436,7 +456,9 @@ export function isExecutionStatus(obj: any, _argumentName?: string): obj is Exec
         isExecutionStatusType(obj.status) as boolean &&
         isGasCostSummary(obj.gas_cost) as boolean &&
         (typeof obj.error === "undefined" ||
-            isTransactionDigest(obj.error) as boolean)
+            isTransactionDigest(obj.error) as boolean) &&
+        (typeof obj.move_abort === "undefined" ||
+            isMoveAbort(obj.move_abort) as boolean)
     )
 }
 
@@ -506,8 +528,10 @@ export function isTransactionEffectsResponse(obj: any, _argumentName?: string):
         (obj !== null &&
             typeof obj === "object" ||
             typeof obj === "function") &&
-        isCertifiedTransaction(obj.certificate) as boolean &&
-        isTransactionEffects(obj.effects) as boolean
+        (typeof obj.certificate === "undefined" ||
+            isCertifiedTransaction(obj.certificate) as boolean) &&
+        (typeof obj.effects === "undefined" ||
+            isTransactionEffects(obj.effects) as boolean)
     )
 }
 
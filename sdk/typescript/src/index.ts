@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 export * from './cryptography/ed25519-keypair';
+export * from './cryptography/intent';
 export * from './cryptography/keypair';
 export * from './cryptography/publickey';
 
@@ -21,6 +21,7 @@ import {
 } from '../types';
 
 const isNumber = (val: any): val is number => typeof val === 'number';
+const isBoolean = (val: any): val is boolean => typeof val === 'boolean';
 
 export class JsonRpcProvider extends Provider {
   private client: JsonRpcClient;
@@ -146,6 +147,23 @@ export class JsonRpcProvider extends Provider {
     }
   }
 
+  async verifyPersonalMessage(
+    address: string,
+    message: string,
+    signature: string
+  ): Promise<boolean> {
+    try {
+      const resp = await this.client.requestWithType(
+        'sui_verifyPersonalMessage',
+        [address, message, signature],
+        isBoolean
+      );
+      return resp;
+    } catch (err) {
+      throw new Error(`Error verifying personal message: ${err}`);
+    }
+  }
+
   async getTotalTransactionNumber(): Promise<number> {
     try {
       const resp = await this.client.requestWithType(
@@ -55,5 +55,16 @@ export abstract class Provider {
     pubkey: string
   ): Promise<TransactionResponse>;
 
+  /**
+   * Whether `signature`, the base64 encoded signature followed by the public
+   * key, is a signature of the base64 encoded personal message `message` by
+   * `address`
+   */
+  abstract verifyPersonalMessage(
+    address: string,
+    message: string,
+    signature: string
+  ): Promise<boolean>;
+
   // TODO: add more interface methods
 }
@@ -37,6 +37,14 @@ export class VoidProvider extends Provider {
     throw this.newError('executeTransaction');
   }
 
+  async verifyPersonalMessage(
+    _address: string,
+    _message: string,
+    _signature: string
+  ): Promise<boolean> {
+    throw this.newError('verifyPersonalMessage');
+  }
+
   async getTotalTransactionNumber(): Promise<number> {
     throw this.newError('getTotalTransactionNumber');
   }
@@ -4,6 +4,11 @@
 import { JsonRpcProvider } from '../providers/json-rpc-provider';
 import { Provider } from '../providers/provider';
 import { VoidProvider } from '../providers/void-provider';
+import {
+  IntentScope,
+  messageWithIntent,
+  personalMessageWithIntent,
+} from '../cryptography/intent';
 import { Base64DataBuffer } from '../serialization/base64';
 import { SuiAddress, TransactionResponse } from '../types';
 import { SignaturePubkeyPair, Signer } from './signer';
@@ -57,7 +62,9 @@ export abstract class SignerWithProvider implements Signer {
   async signAndExecuteTransaction(
     txBytes: Base64DataBuffer
   ): Promise<TransactionResponse> {
-    const sig = await this.signData(txBytes);
+    const sig = await this.signData(
+      messageWithIntent(IntentScope.TransactionData, txBytes)
+    );
     return await this.provider.executeTransaction(
       txBytes.toString(),
       sig.signature.toString(),
@@ -65,6 +72,14 @@ export abstract class SignerWithProvider implements Signer {
     );
   }
 
+  /**
+   * Sign a personal message, for an app to check that the signer owns its
+   * address with `Provider.verifyPersonalMessage`
+   */
+  async signPersonalMessage(message: Uint8Array): Promise<SignaturePubkeyPair> {
+    return await this.signData(personalMessageWithIntent(message));
+  }
+
   /**
    * Serialize and Sign a `TransferCoin` transaction and submit to the Gateway for execution
    */
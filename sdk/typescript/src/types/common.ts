@@ -10,7 +10,8 @@ export type ObjectOwner =
   | { AddressOwner: SuiAddress }
   | { ObjectOwner: SuiAddress }
   | 'Shared'
-  | 'Immutable';
+  | 'Immutable'
+  | { Party: SuiAddress };
 
 
 // source of truth is
@@ -31,8 +31,11 @@ export type SuiData = { dataType: ObjectType } & (
 export type SuiMoveObject = {
   /** Move type (e.g., "0x2::Coin::Coin<0x2::SUI::SUI>") */
   type: string;
-  /** Fields and values stored inside the Move object */
-  fields: ObjectContentFields;
+  /**
+   * Fields and values stored inside the Move object, left out when
+   * `showContent` is off
+   */
+  fields?: ObjectContentFields;
 };
 
 export type SuiMovePackage = {
@@ -44,10 +44,20 @@ export type GasCostSummary = {
 };
 
 export type ExecutionStatusType = 'success' | 'failure';
+export type MoveAbort = {
+  /** The module, like `0x2::Coin` */
+  module: string;
+  function?: string;
+  instruction: number;
+  code: number;
+  /** The name of the error constant of the code, for the framework modules */
+  constant?: string;
+};
 export type ExecutionStatus = {
   status: ExecutionStatusType;
   gas_cost: GasCostSummary;
   error?: string;
+  move_abort?: MoveAbort;
 };
 
 // TODO: change the tuple to struct from the server end
@@ -89,8 +99,10 @@ export type TransactionEffects = {
 };
 
 export type TransactionEffectsResponse = {
-  certificate: CertifiedTransaction;
-  effects: TransactionEffects;
+  /** Left out when `showInput` is off */
+  certificate?: CertifiedTransaction;
+  /** Left out when `showEffects` is off */
+  effects?: TransactionEffects;
 };
 
 export type GatewayTxSeqNumber = number;
@@ -233,7 +245,9 @@ export function getExecutionStatusType(
 export function getExecutionStatus(
   data: TransactionEffectsResponse
 ): ExecutionStatus {
-  return data.effects.status;
+  // The effects are only left out of responses to requests that turned off
+  // `showEffects`
+  return data.effects!.status;
 }
 
 export function getExecutionStatusError(
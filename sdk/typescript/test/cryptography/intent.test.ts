@@ -0,0 +1,33 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+import {
+  Base64DataBuffer,
+  IntentScope,
+  messageWithIntent,
+  personalMessageWithIntent,
+} from '../../src';
+
+describe('Intent', () => {
+  it('prefixes the message', () => {
+    const message = new Base64DataBuffer(new Uint8Array([1, 2, 3]));
+    expect(
+      Array.from(
+        messageWithIntent(IntentScope.TransactionData, message).getData()
+      )
+    ).toEqual([0, 0, 0, 1, 2, 3]);
+  });
+
+  it('encodes personal messages', () => {
+    const bytes = personalMessageWithIntent(new Uint8Array([7, 8])).getData();
+    const name = Array.from(new TextEncoder().encode('PersonalMessage::'));
+    expect(Array.from(bytes)).toEqual([3, 0, 0, ...name, 2, 7, 8]);
+
+    const long = personalMessageWithIntent(new Uint8Array(200)).getData();
+    // The length of 200 is two bytes of ULEB128.
+    expect(Array.from(long.slice(3 + name.length, 5 + name.length))).toEqual([
+      0xc8,
+      0x01,
+    ]);
+  });
+});
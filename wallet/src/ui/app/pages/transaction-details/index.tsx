@@ -48,7 +48,7 @@ function TransactionDetailsPage() {
     const transferKind =
         txDetails &&
         getTransactionKindName(
-            getTransactions(txDetails.EffectResponse.certificate)[0]
+            getTransactions(txDetails.EffectResponse.certificate!)[0]
         );
     return (
         <div className={cl('container')}>
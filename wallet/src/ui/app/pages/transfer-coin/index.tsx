@@ -129,7 +129,7 @@ function TransferCoinPage() {
                     })
                 ).unwrap();
                 const txDigest =
-                    response.EffectResponse.certificate.transactionDigest;
+                    response.EffectResponse.certificate!.transactionDigest;
                 resetForm();
                 navigate(`/tx/${encodeURIComponent(txDigest)}`);
             } catch (e) {
@@ -62,7 +62,7 @@ export const sendTokens = createAsyncThunk<
 );
 
 const txAdapter = createEntityAdapter<TransactionResult>({
-    selectId: (tx) => tx.EffectResponse.certificate.transactionDigest,
+    selectId: (tx) => tx.EffectResponse.certificate!.transactionDigest,
 });
 
 export const txSelectors = txAdapter.getSelectors(